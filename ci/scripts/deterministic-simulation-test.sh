@@ -24,3 +24,6 @@ echo "--- Run unit tests in deterministic simulation mode"
     -p risingwave_source \
     -p risingwave_storage \
     -p risingwave_stream
+
+echo "--- Run recovery test in deterministic simulation mode"
+~/cargo-make/makers sim-recovery --count 5
@@ -136,6 +136,10 @@ def section_cluster_node(panels):
             panels.target(
                 "sum(rate(process_cpu_seconds_total[1m])) by (job,instance)", "{{job}} @ {{instance}}"
             )]),
+        panels.timeseries_count("Node Count", [
+            panels.target(
+                "sum(meta_worker_num) by (worker_type,state)", "{{worker_type}} ({{state}})"
+            )]),
     ]
 
 
@@ -328,6 +332,21 @@ def section_streaming(panels):
                 "rate(stream_source_output_rows_counts[15s])", "source_id = {{source_id}}"
             ),
         ]),
+        panels.timeseries_count("Scheduled Barriers", [
+            panels.target(
+                "meta_scheduled_barrier_num", "scheduled_barrier_num"
+            ),
+        ]),
+        panels.timeseries_count("Recovery", [
+            panels.target(
+                "increase(meta_recovery_count[1m])", "recovery_count"
+            ),
+        ]),
+        panels.timeseries_latency("Recovery Latency", [
+            panels.target(
+                "rate(meta_recovery_duration_seconds_sum[1m]) / rate(meta_recovery_duration_seconds_count[1m])", "recovery_latency_avg"
+            ),
+        ]),
     ]
 
 
@@ -0,0 +1,254 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use arrow::datatypes::SchemaRef;
+use arrow::record_batch::RecordBatch;
+use bytes::Bytes;
+use futures_async_stream::try_stream;
+use parquet::arrow::ArrowWriter;
+use risingwave_common::array::{ArrayBuilder, DataChunk, I64ArrayBuilder};
+use risingwave_common::catalog::{Field, Schema};
+use risingwave_common::error::ErrorCode::{InternalError, InvalidParameterValue};
+use risingwave_common::error::{Result, RwError};
+use risingwave_common::types::DataType;
+use risingwave_expr::arrow::{arrow_schema, to_record_batch};
+use risingwave_object_store::object::{parse_object_store, ObjectStore};
+use risingwave_pb::batch_plan::plan_node::NodeBody;
+
+use crate::executor::{
+    BoxedDataChunkStream, BoxedExecutor, BoxedExecutorBuilder, Executor, ExecutorBuilder,
+};
+use crate::task::{BatchTaskContext, TaskId};
+
+/// Rows written to a Parquet file before starting a new one. The rows of a file are buffered in
+/// memory until it's uploaded.
+const MAX_ROWS_PER_FILE: usize = 1 << 20;
+
+/// Splits the location of the files to export or import into the url of its object store and the
+/// prefix of the paths of the files in the store. Only S3 and local disks are supported.
+pub fn split_file_location(location: &str) -> Result<(String, String)> {
+    if let Some(path) = location.strip_prefix("s3://") {
+        let (bucket, prefix) = path.split_once('/').unwrap_or((path, ""));
+        if !bucket.is_empty() {
+            let prefix = prefix.trim_matches('/');
+            let prefix = match prefix {
+                "" => String::new(),
+                prefix => format!("{}/", prefix),
+            };
+            return Ok((format!("s3://{}", bucket), prefix));
+        }
+    } else if location.starts_with("disk://") {
+        return Ok((location.to_string(), String::new()));
+    }
+    Err(InvalidParameterValue(format!(
+        "unsupported file location {}, expect s3://<bucket>/<path> or disk://<path>",
+        location
+    ))
+    .into())
+}
+
+/// Encodes `batches` of `schema` as a Parquet file.
+fn encode_parquet(schema: SchemaRef, batches: &[RecordBatch]) -> Result<Bytes> {
+    let parquet_err = |e: parquet::errors::ParquetError| InternalError(e.to_string());
+    let mut buf = vec![];
+    let mut writer = ArrowWriter::try_new(&mut buf, schema, None).map_err(parquet_err)?;
+    for batch in batches {
+        writer.write(batch).map_err(parquet_err)?;
+    }
+    writer.close().map_err(parquet_err)?;
+    Ok(buf.into())
+}
+
+async fn upload_parquet(
+    object_store: &dyn ObjectStore,
+    path: &str,
+    schema: SchemaRef,
+    batches: &[RecordBatch],
+) -> Result<()> {
+    let file = encode_parquet(schema, batches)?;
+    object_store
+        .upload(path, file)
+        .await
+        .map_err(|e| InternalError(format!("failed to upload {}: {}", path, e)).into())
+}
+
+/// [`ExportExecutor`] writes the rows of its child executor to Parquet files at `location`, and
+/// outputs the number of rows written. Each task writes its own files, which are named after the
+/// id of the task.
+pub struct ExportExecutor {
+    location: String,
+    task_id: TaskId,
+    child: BoxedExecutor,
+    schema: Schema,
+    identity: String,
+}
+
+impl ExportExecutor {
+    pub fn new(location: String, task_id: TaskId, child: BoxedExecutor) -> Self {
+        Self {
+            location,
+            task_id,
+            child,
+            schema: Schema {
+                fields: vec![Field::unnamed(DataType::Int64)],
+            },
+            identity: "ExportExecutor".to_string(),
+        }
+    }
+}
+
+impl Executor for ExportExecutor {
+    fn schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    fn identity(&self) -> &str {
+        &self.identity
+    }
+
+    fn execute(self: Box<Self>) -> BoxedDataChunkStream {
+        self.do_execute()
+    }
+}
+
+impl ExportExecutor {
+    #[try_stream(boxed, ok = DataChunk, error = RwError)]
+    async fn do_execute(self: Box<Self>) {
+        let (url, prefix) = split_file_location(&self.location)?;
+        let object_store = parse_object_store(&url, false).await;
+        let schema = Arc::new(arrow_schema(self.child.schema().fields())?);
+        let path_prefix = format!(
+            "{}{}-{}-{}",
+            prefix, self.task_id.query_id, self.task_id.stage_id, self.task_id.task_id
+        );
+
+        let mut batches = vec![];
+        let mut rows_in_file = 0;
+        let mut files = 0;
+        let mut rows_exported = 0;
+        #[for_await]
+        for chunk in self.child.execute() {
+            let batch = to_record_batch(schema.clone(), chunk?)?;
+            rows_in_file += batch.num_rows();
+            rows_exported += batch.num_rows();
+            batches.push(batch);
+            if rows_in_file >= MAX_ROWS_PER_FILE {
+                let path = format!("{}-{}.parquet", path_prefix, files);
+                upload_parquet(&*object_store, &path, schema.clone(), &batches).await?;
+                batches.clear();
+                rows_in_file = 0;
+                files += 1;
+            }
+        }
+        if !batches.is_empty() {
+            let path = format!("{}-{}.parquet", path_prefix, files);
+            upload_parquet(&*object_store, &path, schema, &batches).await?;
+        }
+
+        let mut builder = I64ArrayBuilder::new(1)?;
+        builder.append(Some(rows_exported as i64))?;
+        yield DataChunk::new(vec![builder.finish()?.into()], 1);
+    }
+}
+
+#[async_trait::async_trait]
+impl BoxedExecutorBuilder for ExportExecutor {
+    async fn new_boxed_executor<C: BatchTaskContext>(
+        source: &ExecutorBuilder<C>,
+        mut inputs: Vec<BoxedExecutor>,
+    ) -> Result<BoxedExecutor> {
+        ensure!(inputs.len() == 1, "Export executor should have 1 child!");
+        let export_node = try_match_expand!(
+            source.plan_node().get_node_body().unwrap(),
+            NodeBody::Export
+        )?;
+
+        Ok(Box::new(Self::new(
+            export_node.location.clone(),
+            source.task_id.clone(),
+            inputs.remove(0),
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::StreamExt;
+    use risingwave_common::array::{Array, I32Array};
+    use risingwave_common::catalog::schema_test_utils;
+    use risingwave_common::column_nonnull;
+
+    use super::*;
+    use crate::executor::test_utils::MockExecutor;
+
+    #[test]
+    fn test_split_file_location() {
+        assert_eq!(
+            split_file_location("s3://bucket/path/").unwrap(),
+            ("s3://bucket".to_string(), "path/".to_string())
+        );
+        assert_eq!(
+            split_file_location("s3://bucket").unwrap(),
+            ("s3://bucket".to_string(), "".to_string())
+        );
+        assert_eq!(
+            split_file_location("disk:///tmp/export").unwrap(),
+            ("disk:///tmp/export".to_string(), "".to_string())
+        );
+        assert!(split_file_location("s3://").is_err());
+        assert!(split_file_location("hdfs://path").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_export_executor() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let mut child = MockExecutor::new(schema_test_utils::ii());
+        child.add(DataChunk::new(
+            vec![
+                column_nonnull!(I32Array, [1, 2, 3]),
+                column_nonnull!(I32Array, [4, 5, 6]),
+            ],
+            3,
+        ));
+        let task_id = TaskId {
+            task_id: 1,
+            stage_id: 2,
+            query_id: "q".to_string(),
+        };
+        let executor = Box::new(ExportExecutor::new(
+            format!("disk://{}", dir.path().to_str().unwrap()),
+            task_id,
+            Box::new(child),
+        ));
+
+        let mut stream = executor.execute();
+        let chunk = stream.next().await.unwrap().unwrap();
+        assert_eq!(
+            chunk
+                .column_at(0)
+                .array()
+                .as_int64()
+                .iter()
+                .collect::<Vec<_>>(),
+            vec![Some(3)]
+        );
+        assert!(stream.next().await.is_none());
+
+        // Parquet files start with the magic number.
+        let file = std::fs::read(dir.path().join("q-2-1-0.parquet")).unwrap();
+        assert_eq!(&file[..4], b"PAR1");
+    }
+}
@@ -0,0 +1,364 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io::Cursor;
+use std::sync::Arc;
+
+use arrow::csv::ReaderBuilder;
+use arrow::record_batch::RecordBatch;
+use bytes::Bytes;
+use futures_async_stream::try_stream;
+use itertools::Itertools;
+use parquet::arrow::{ArrowReader, ParquetFileArrowReader};
+use risingwave_common::array::column::Column;
+use risingwave_common::array::DataChunk;
+use risingwave_common::catalog::{ColumnDesc, Field, Schema};
+use risingwave_common::error::ErrorCode::InternalError;
+use risingwave_common::error::{Result, RwError};
+use risingwave_common::util::chunk_coalesce::DEFAULT_CHUNK_BUFFER_SIZE;
+use risingwave_expr::arrow::{arrow_schema, from_arrow_column};
+use risingwave_object_store::object::parse_object_store;
+use risingwave_pb::batch_plan::file_scan_node::FileFormat;
+use risingwave_pb::batch_plan::plan_node::NodeBody;
+
+use crate::executor::{
+    BoxedDataChunkStream, BoxedExecutor, BoxedExecutorBuilder, Executor, ExecutorBuilder,
+};
+use crate::task::BatchTaskContext;
+
+/// Decodes the record batches of a Parquet file.
+fn decode_parquet(file: Bytes) -> Result<Vec<RecordBatch>> {
+    let parquet_err = |e: parquet::errors::ParquetError| InternalError(e.to_string());
+    let mut reader = ParquetFileArrowReader::try_new(file).map_err(parquet_err)?;
+    let batches = reader
+        .get_record_reader(DEFAULT_CHUNK_BUFFER_SIZE)
+        .map_err(parquet_err)?
+        .try_collect()
+        .map_err(|e| InternalError(e.to_string()))?;
+    Ok(batches)
+}
+
+/// Decodes the record batches of a CSV file, whose columns are `fields`.
+fn decode_csv(
+    file: Bytes,
+    fields: &[Field],
+    has_header: bool,
+    delimiter: u8,
+) -> Result<Vec<RecordBatch>> {
+    let reader = ReaderBuilder::new()
+        .with_schema(Arc::new(arrow_schema(fields)?))
+        .has_header(has_header)
+        .with_delimiter(delimiter)
+        .with_batch_size(DEFAULT_CHUNK_BUFFER_SIZE)
+        .build(Cursor::new(file))
+        .map_err(|e| InternalError(e.to_string()))?;
+    let batches = reader
+        .try_collect()
+        .map_err(|e| InternalError(e.to_string()))?;
+    Ok(batches)
+}
+
+/// [`FileScanExecutor`] reads the rows of Parquet or CSV files in an object store, and converts
+/// them to the types of its schema. The columns of Parquet files are matched by name, while the
+/// columns of CSV files are matched by position. Columns absent from the files are nulls.
+pub struct FileScanExecutor {
+    store_url: String,
+    paths: Vec<String>,
+    format: FileFormat,
+    /// Indices of the columns in the schema present in the files.
+    file_column_indices: Vec<usize>,
+    csv_has_header: bool,
+    csv_delimiter: u8,
+    schema: Schema,
+    identity: String,
+}
+
+impl FileScanExecutor {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        store_url: String,
+        paths: Vec<String>,
+        format: FileFormat,
+        file_column_indices: Vec<usize>,
+        csv_has_header: bool,
+        csv_delimiter: u8,
+        schema: Schema,
+        identity: String,
+    ) -> Self {
+        Self {
+            store_url,
+            paths,
+            format,
+            file_column_indices,
+            csv_has_header,
+            csv_delimiter,
+            schema,
+            identity,
+        }
+    }
+
+    /// Converts a record batch decoded from a file to a chunk of the schema.
+    fn to_chunk(&self, batch: &RecordBatch) -> Result<DataChunk> {
+        let fields = self.schema.fields();
+        let mut file_columns = vec![None; fields.len()];
+        for (i, &index) in self.file_column_indices.iter().enumerate() {
+            let position = match self.format {
+                FileFormat::Csv => i,
+                FileFormat::Parquet => {
+                    batch.schema().index_of(&fields[index].name).map_err(|_| {
+                        InternalError(format!(
+                            "column {} is not found in the Parquet file",
+                            fields[index].name
+                        ))
+                    })?
+                }
+            };
+            file_columns[index] = Some(batch.column(position));
+        }
+
+        let columns = fields
+            .iter()
+            .zip_eq(file_columns)
+            .map(|(field, file_column)| {
+                let array = match file_column {
+                    Some(array) => from_arrow_column(&field.data_type, array)?,
+                    None => {
+                        let mut builder = field.data_type.create_array_builder(batch.num_rows())?;
+                        for _ in 0..batch.num_rows() {
+                            builder.append_datum(&None)?;
+                        }
+                        builder.finish()?
+                    }
+                };
+                Ok(Column::new(Arc::new(array)))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(DataChunk::new(columns, batch.num_rows()))
+    }
+}
+
+impl Executor for FileScanExecutor {
+    fn schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    fn identity(&self) -> &str {
+        &self.identity
+    }
+
+    fn execute(self: Box<Self>) -> BoxedDataChunkStream {
+        self.do_execute()
+    }
+}
+
+impl FileScanExecutor {
+    #[try_stream(boxed, ok = DataChunk, error = RwError)]
+    async fn do_execute(self: Box<Self>) {
+        let object_store = parse_object_store(&self.store_url, false).await;
+        let file_fields = self
+            .file_column_indices
+            .iter()
+            .map(|&index| self.schema[index].clone())
+            .collect_vec();
+
+        for path in &self.paths {
+            let file = object_store
+                .read(path, None)
+                .await
+                .map_err(|e| InternalError(format!("failed to read {}: {}", path, e)))?;
+            // The batches of a file are decoded at once, as the decoders are not `Send`.
+            let batches = match self.format {
+                FileFormat::Parquet => decode_parquet(file)?,
+                FileFormat::Csv => {
+                    decode_csv(file, &file_fields, self.csv_has_header, self.csv_delimiter)?
+                }
+            };
+            for batch in batches {
+                if batch.num_rows() > 0 {
+                    yield self.to_chunk(&batch)?;
+                }
+            }
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl BoxedExecutorBuilder for FileScanExecutor {
+    async fn new_boxed_executor<C: BatchTaskContext>(
+        source: &ExecutorBuilder<C>,
+        inputs: Vec<BoxedExecutor>,
+    ) -> Result<BoxedExecutor> {
+        ensure!(inputs.is_empty(), "FileScanExecutor should have no child!");
+        let file_scan_node = try_match_expand!(
+            source.plan_node().get_node_body().unwrap(),
+            NodeBody::FileScan
+        )?;
+
+        let fields = file_scan_node
+            .column_descs
+            .iter()
+            .map(|column_desc| Field::from(&ColumnDesc::from(column_desc.clone())))
+            .collect();
+        let csv_delimiter = match file_scan_node.csv_delimiter.as_bytes() {
+            [] => b',',
+            [delimiter] => *delimiter,
+            _ => {
+                return Err(InternalError(format!(
+                    "invalid CSV delimiter {}",
+                    file_scan_node.csv_delimiter
+                ))
+                .into())
+            }
+        };
+
+        Ok(Box::new(Self::new(
+            file_scan_node.store_url.clone(),
+            file_scan_node.paths.clone(),
+            file_scan_node.get_format()?,
+            file_scan_node
+                .file_column_indices
+                .iter()
+                .map(|&index| index as usize)
+                .collect(),
+            file_scan_node.csv_has_header,
+            csv_delimiter,
+            Schema { fields },
+            source.plan_node().get_identity().clone(),
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::StreamExt;
+    use risingwave_common::array::{I32Array, Utf8Array};
+    use risingwave_common::column_nonnull;
+    use risingwave_common::types::{DataType, ScalarImpl};
+    use risingwave_object_store::object::ObjectStore;
+
+    use super::*;
+    use crate::executor::test_utils::MockExecutor;
+    use crate::executor::ExportExecutor;
+    use crate::task::TaskId;
+
+    fn schema() -> Schema {
+        Schema::new(vec![
+            Field::with_name(DataType::Int64, "v1"),
+            Field::with_name(DataType::Varchar, "v2"),
+            Field::with_name(DataType::Int32, "v3"),
+        ])
+    }
+
+    async fn collect(executor: FileScanExecutor) -> Vec<Vec<Option<ScalarImpl>>> {
+        let mut stream = Box::new(executor).execute();
+        let mut rows = vec![];
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk.unwrap();
+            for row in chunk.rows() {
+                rows.push(row.to_owned_row().0);
+            }
+        }
+        rows
+    }
+
+    #[tokio::test]
+    async fn test_scan_csv() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let store_url = format!("disk://{}", dir.path().to_str().unwrap());
+        let object_store = parse_object_store(&store_url, false).await;
+        object_store
+            .upload("1.csv", Bytes::from("v2|v1\na|1\nb|\n"))
+            .await
+            .unwrap();
+
+        let executor = FileScanExecutor::new(
+            store_url,
+            vec!["1.csv".to_string()],
+            FileFormat::Csv,
+            vec![1, 0],
+            true,
+            b'|',
+            schema(),
+            "FileScanExecutor".to_string(),
+        );
+        assert_eq!(
+            collect(executor).await,
+            vec![
+                vec![
+                    Some(ScalarImpl::Int64(1)),
+                    Some(ScalarImpl::Utf8("a".into())),
+                    None
+                ],
+                vec![None, Some(ScalarImpl::Utf8("b".into())), None],
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_scan_exported_parquet() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let store_url = format!("disk://{}", dir.path().to_str().unwrap());
+
+        // Export the files to import first.
+        let mut child = MockExecutor::new(Schema::new(vec![
+            Field::with_name(DataType::Varchar, "v2"),
+            Field::with_name(DataType::Int32, "v1"),
+        ]));
+        child.add(DataChunk::new(
+            vec![
+                column_nonnull!(Utf8Array, ["a", "b"]),
+                column_nonnull!(I32Array, [1, 2]),
+            ],
+            2,
+        ));
+        let task_id = TaskId {
+            task_id: 0,
+            stage_id: 0,
+            query_id: "q".to_string(),
+        };
+        let mut stream = Box::new(ExportExecutor::new(
+            store_url.clone(),
+            task_id,
+            Box::new(child),
+        ))
+        .execute();
+        while stream.next().await.is_some() {}
+
+        let executor = FileScanExecutor::new(
+            store_url,
+            vec!["q-0-0-0.parquet".to_string()],
+            FileFormat::Parquet,
+            vec![0, 1],
+            false,
+            b',',
+            schema(),
+            "FileScanExecutor".to_string(),
+        );
+        assert_eq!(
+            collect(executor).await,
+            vec![
+                vec![
+                    Some(ScalarImpl::Int64(1)),
+                    Some(ScalarImpl::Utf8("a".into())),
+                    None
+                ],
+                vec![
+                    Some(ScalarImpl::Int64(2)),
+                    Some(ScalarImpl::Utf8("b".into())),
+                    None
+                ],
+            ]
+        );
+    }
+}
@@ -19,7 +19,7 @@ use risingwave_common::catalog::Schema;
 use risingwave_common::error::ErrorCode::InternalError;
 use risingwave_common::error::{Result, RwError};
 use risingwave_common::util::chunk_coalesce::{DataChunkBuilder, SlicedDataChunk};
-use risingwave_expr::expr::{build_from_prost, BoxedExpression};
+use risingwave_expr::expr::{build_from_prost, with_epoch, BoxedExpression};
 use risingwave_pb::batch_plan::plan_node::NodeBody;
 
 use crate::executor::{
@@ -108,7 +108,8 @@ impl BoxedExecutorBuilder for FilterExecutor {
         )?;
 
         let expr_node = filter_node.get_search_condition()?;
-        let expr = build_from_prost(expr_node)?;
+        // `NOW()` is evaluated at the epoch of the snapshot being read.
+        let expr = with_epoch(source.epoch(), || build_from_prost(expr_node))?;
         Ok(Box::new(Self {
             expr,
             child: inputs.remove(0),
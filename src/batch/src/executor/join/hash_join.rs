@@ -14,9 +14,11 @@
 
 use std::convert::TryInto;
 use std::marker::PhantomData;
+use std::sync::Arc;
 
 use futures::StreamExt;
 use futures_async_stream::try_stream;
+use itertools::Itertools;
 use risingwave_common::array::DataChunk;
 use risingwave_common::catalog::Schema;
 use risingwave_common::error::{Result, RwError};
@@ -27,11 +29,12 @@ use risingwave_expr::expr::{build_from_prost, BoxedExpression};
 use risingwave_pb::batch_plan::plan_node::NodeBody;
 
 use crate::executor::join::hash_join_state::{BuildTable, ProbeTable};
+use crate::executor::join::spill::{PartitionSpiller, SpillParams, MAX_SPILL_LEVEL};
 use crate::executor::join::JoinType;
 use crate::executor::{
     BoxedDataChunkStream, BoxedExecutor, BoxedExecutorBuilder, Executor, ExecutorBuilder,
 };
-use crate::task::{BatchTaskContext, TaskId};
+use crate::task::{BatchMemoryTracker, BatchMemoryTrackerRef, BatchTaskContext, TaskId};
 
 /// Parameters of equi-join.
 ///
@@ -72,6 +75,10 @@ pub(super) struct HashJoinExecutor<K> {
     params: EquiJoinParams,
     schema: Schema,
     identity: String,
+    /// Falls back to grace hash join if the build side exceeds the threshold. `None` means never
+    /// spill.
+    spill_params: Option<SpillParams>,
+    mem_tracker: BatchMemoryTrackerRef,
     _phantom: PhantomData<K>,
 }
 
@@ -134,99 +141,181 @@ impl<K: HashKey + Send + Sync> Executor for HashJoinExecutor<K> {
 impl<K: HashKey + Send + Sync> HashJoinExecutor<K> {
     #[try_stream(boxed, ok = DataChunk, error = RwError)]
     async fn do_execute(mut self: Box<Self>) {
-        let mut right_child_stream = self.right_child.take().unwrap().execute();
-        let mut build_table = BuildTable::with_params(self.params);
-
-        while let Some(chunk) = right_child_stream.next().await {
-            let chunk = chunk?;
-            build_table.append_build_chunk(chunk)?;
-        }
-        let mut probe_table: ProbeTable<K> = build_table.try_into()?;
+        let right_child_stream = self.right_child.take().unwrap().execute();
+        let left_child_stream = self.left_child.take().unwrap().execute();
+        let mut mem_reservation = self.mem_tracker.new_reservation();
+
+        // Each partition is a pair of build and probe streams, with the number of times it has
+        // been split. Without spilling, there is only one partition holding both inputs entirely.
+        let mut partitions: Vec<(BoxedDataChunkStream, BoxedDataChunkStream, usize)> =
+            vec![(right_child_stream, left_child_stream, 0)];
+        let mut params = self.params;
+        while let Some((mut right_child_stream, mut left_child_stream, level)) = partitions.pop() {
+            // Buffer the build side until it exceeds the spill threshold.
+            let mut build_chunks = vec![];
+            let mut need_spill = false;
+            while let Some(chunk) = right_child_stream.next().await {
+                let chunk = chunk?;
+                let chunk_size = chunk.estimated_heap_size();
+                build_chunks.push(chunk);
+                match &self.spill_params {
+                    // Spill if the build side exceeds either the threshold or the memory quota.
+                    Some(spill_params) if level <= MAX_SPILL_LEVEL => {
+                        if mem_reservation.try_grow(chunk_size).is_err()
+                            || mem_reservation.bytes() > spill_params.threshold_bytes
+                        {
+                            need_spill = true;
+                            break;
+                        }
+                    }
+                    // The partition has been split too many times to be split again.
+                    Some(_) => mem_reservation.grow(chunk_size),
+                    None => mem_reservation.try_grow(chunk_size)?,
+                }
+            }
 
-        let mut state = HashJoinState::Probe;
-        let mut left_child_stream = self.left_child.take().unwrap().execute();
+            if need_spill {
+                let spill_params = self.spill_params.as_ref().unwrap();
+                let seed = level as u64;
+                let mut build_spiller =
+                    PartitionSpiller::new(spill_params, params.build_key_columns(), seed).await?;
+                for chunk in build_chunks.drain(..) {
+                    build_spiller.spill(chunk).await?;
+                }
+                mem_reservation.release();
+                while let Some(chunk) = right_child_stream.next().await {
+                    build_spiller.spill(chunk?).await?;
+                }
 
-        // first probe
-        match left_child_stream.next().await {
-            Some(data_chunk) => {
-                let data_chunk = data_chunk?;
-                probe_table.set_probe_data(data_chunk)?;
-            }
-            None => {
-                state = HashJoinState::Done;
+                let mut probe_spiller =
+                    PartitionSpiller::new(spill_params, params.probe_key_columns(), seed).await?;
+                while let Some(chunk) = left_child_stream.next().await {
+                    probe_spiller.spill(chunk?).await?;
+                }
+                warn!(
+                    "{} spilled {} bytes of build side and {} bytes of probe side into {} partitions \
+                     at level {}",
+                    self.identity,
+                    build_spiller.spilled_bytes(),
+                    probe_spiller.spilled_bytes(),
+                    spill_params.partitions,
+                    level,
+                );
+                for (build_file, probe_file) in build_spiller
+                    .finish()
+                    .into_iter()
+                    .zip_eq(probe_spiller.finish())
+                {
+                    partitions.push((
+                        build_file.into_stream(),
+                        probe_file.into_stream(),
+                        level + 1,
+                    ));
+                }
+                continue;
             }
-        }
-        // probe
-        while state == HashJoinState::Probe {
-            if let Some(ret_data_chunk) = probe_table.join()? {
-                let data_chunk = if probe_table.has_non_equi_cond() {
-                    probe_table.process_non_equi_condition(ret_data_chunk)?
-                } else {
-                    Some(ret_data_chunk)
-                };
 
-                // TODO(yuhao): Current we handle cut null columns in semi/anti join just
-                // before returning chunks. We can furthur optimize this by cut columns earlier.
-                let output_data_chunk =
-                    data_chunk.map(|chunk| probe_table.remove_null_columns_for_semi_anti(chunk));
+            let mut build_table = BuildTable::with_params(params);
+            for chunk in build_chunks {
+                build_table.append_build_chunk(chunk)?;
+            }
+            let mut probe_table: ProbeTable<K> = build_table.try_into()?;
 
-                probe_table.reset_result_index();
+            let mut state = HashJoinState::Probe;
 
-                if let Some(data_chunk) = output_data_chunk && data_chunk.cardinality() > 0 {
-                    yield data_chunk;
+            // first probe
+            match left_child_stream.next().await {
+                Some(data_chunk) => {
+                    let data_chunk = data_chunk?;
+                    probe_table.set_probe_data(data_chunk)?;
                 }
-            } else {
-                match left_child_stream.next().await {
-                    Some(data_chunk) => {
-                        let data_chunk = data_chunk?;
-                        probe_table.set_probe_data(data_chunk)?;
-                    }
-                    None => {
-                        // Consume the rest when when probe side end.
-                        let ret_data_chunk = probe_table.consume_left()?;
-                        let data_chunk = if probe_table.has_non_equi_cond() {
-                            probe_table.process_non_equi_condition(ret_data_chunk)?
-                        } else {
-                            Some(ret_data_chunk)
-                        };
+                None => {
+                    // The probe side of a spilled partition can be empty while the build side is
+                    // not, in which case the unmatched build rows still need to be emitted.
+                    state = if probe_table.join_type().need_join_remaining() {
+                        HashJoinState::ProbeRemaining
+                    } else {
+                        HashJoinState::Done
+                    };
+                }
+            }
+            // probe
+            while state == HashJoinState::Probe {
+                if let Some(ret_data_chunk) = probe_table.join()? {
+                    let data_chunk = if probe_table.has_non_equi_cond() {
+                        probe_table.process_non_equi_condition(ret_data_chunk)?
+                    } else {
+                        Some(ret_data_chunk)
+                    };
 
-                        let output_data_chunk = data_chunk
-                            .map(|chunk| probe_table.remove_null_columns_for_semi_anti(chunk));
+                    // TODO(yuhao): Current we handle cut null columns in semi/anti join just
+                    // before returning chunks. We can furthur optimize this by cut columns earlier.
+                    let output_data_chunk = data_chunk
+                        .map(|chunk| probe_table.remove_null_columns_for_semi_anti(chunk));
 
-                        probe_table.reset_result_index();
+                    probe_table.reset_result_index();
 
-                        if probe_table.join_type().need_join_remaining() {
-                            state = HashJoinState::ProbeRemaining;
-                        } else {
-                            state = HashJoinState::Done;
+                    if let Some(data_chunk) = output_data_chunk && data_chunk.cardinality() > 0 {
+                        yield data_chunk;
+                    }
+                } else {
+                    match left_child_stream.next().await {
+                        Some(data_chunk) => {
+                            let data_chunk = data_chunk?;
+                            probe_table.set_probe_data(data_chunk)?;
                         }
-                        if let Some(data_chunk) = output_data_chunk && data_chunk.cardinality() > 0 {
-                            yield data_chunk;
+                        None => {
+                            // Consume the rest when when probe side end.
+                            let ret_data_chunk = probe_table.consume_left()?;
+                            let data_chunk = if probe_table.has_non_equi_cond() {
+                                probe_table.process_non_equi_condition(ret_data_chunk)?
+                            } else {
+                                Some(ret_data_chunk)
+                            };
+
+                            let output_data_chunk = data_chunk
+                                .map(|chunk| probe_table.remove_null_columns_for_semi_anti(chunk));
+
+                            probe_table.reset_result_index();
+
+                            if probe_table.join_type().need_join_remaining() {
+                                state = HashJoinState::ProbeRemaining;
+                            } else {
+                                state = HashJoinState::Done;
+                            }
+                            if let Some(data_chunk) = output_data_chunk && data_chunk.cardinality() > 0 {
+                                yield data_chunk;
+                            }
                         }
                     }
                 }
             }
-        }
-        // probe_remaining
-        if !probe_table.build_data_empty() {
-            while state == HashJoinState::ProbeRemaining {
-                let output_data_chunk =
-                    if let Some(ret_data_chunk) = probe_table.join_remaining()? {
-                        let output_data_chunk =
-                            probe_table.remove_null_columns_for_semi_anti(ret_data_chunk);
-
-                        probe_table.reset_result_index();
-                        output_data_chunk
-                    } else {
-                        let ret_data_chunk = probe_table.consume_left()?;
-                        let output_data_chunk =
-                            probe_table.remove_null_columns_for_semi_anti(ret_data_chunk);
+            // probe_remaining
+            if !probe_table.build_data_empty() {
+                while state == HashJoinState::ProbeRemaining {
+                    let output_data_chunk =
+                        if let Some(ret_data_chunk) = probe_table.join_remaining()? {
+                            let output_data_chunk =
+                                probe_table.remove_null_columns_for_semi_anti(ret_data_chunk);
+
+                            probe_table.reset_result_index();
+                            output_data_chunk
+                        } else {
+                            let ret_data_chunk = probe_table.consume_left()?;
+                            let output_data_chunk =
+                                probe_table.remove_null_columns_for_semi_anti(ret_data_chunk);
 
-                        state = HashJoinState::Done;
-                        output_data_chunk
-                    };
-                yield output_data_chunk
+                            state = HashJoinState::Done;
+                            output_data_chunk
+                        };
+                    if output_data_chunk.cardinality() > 0 {
+                        yield output_data_chunk
+                    }
+                }
             }
+
+            params = probe_table.into_params();
+            mem_reservation.release();
         }
     }
 }
@@ -252,9 +341,17 @@ impl<K> HashJoinExecutor<K> {
             params,
             schema,
             identity,
+            spill_params: None,
+            mem_tracker: Arc::new(BatchMemoryTracker::default()),
             _phantom: PhantomData,
         }
     }
+
+    fn with_spill(mut self, spill_params: SpillParams, mem_tracker: BatchMemoryTrackerRef) -> Self {
+        self.spill_params = Some(spill_params);
+        self.mem_tracker = mem_tracker;
+        self
+    }
 }
 
 pub struct HashJoinExecutorBuilder {
@@ -263,6 +360,8 @@ pub struct HashJoinExecutorBuilder {
     right_child: BoxedExecutor,
     schema: Schema,
     task_id: TaskId,
+    spill_params: SpillParams,
+    mem_tracker: BatchMemoryTrackerRef,
 }
 
 struct HashJoinExecutorBuilderDispatcher;
@@ -273,13 +372,16 @@ impl HashKeyDispatcher for HashJoinExecutorBuilderDispatcher {
     type Output = BoxedExecutor;
 
     fn dispatch<K: HashKey>(input: HashJoinExecutorBuilder) -> Self::Output {
-        Box::new(HashJoinExecutor::<K>::new(
-            input.left_child,
-            input.right_child,
-            input.params,
-            input.schema,
-            format!("HashJoinExecutor{:?}", input.task_id),
-        ))
+        Box::new(
+            HashJoinExecutor::<K>::new(
+                input.left_child,
+                input.right_child,
+                input.params,
+                input.schema,
+                format!("HashJoinExecutor{:?}", input.task_id),
+            )
+            .with_spill(input.spill_params, input.mem_tracker),
+        )
     }
 }
 
@@ -369,6 +471,8 @@ impl BoxedExecutorBuilder for HashJoinExecutorBuilder {
                 fields: schema_fields,
             },
             task_id: context.task_id.clone(),
+            spill_params: SpillParams::from_config(&context.context().batch_config()),
+            mem_tracker: context.context().mem_tracker(),
         };
 
         Ok(HashJoinExecutorBuilderDispatcher::dispatch_by_kind(
@@ -390,15 +494,17 @@ mod tests {
     use risingwave_common::error::Result;
     use risingwave_common::hash::Key32;
     use risingwave_common::test_prelude::DataChunkTestExt;
-    use risingwave_common::types::DataType;
+    use risingwave_common::types::{DataType, Datum};
     use risingwave_expr::expr::expr_binary_nonnull::new_binary_expr;
     use risingwave_expr::expr::{BoxedExpression, InputRefExpression};
     use risingwave_pb::expr::expr_node::Type;
 
     use crate::executor::join::hash_join::{EquiJoinParams, HashJoinExecutor};
+    use crate::executor::join::spill::SpillParams;
     use crate::executor::join::JoinType;
     use crate::executor::test_utils::MockExecutor;
     use crate::executor::BoxedExecutor;
+    use crate::task::BatchMemoryTracker;
     struct DataChunkMerger {
         data_types: Vec<DataType>,
         array_builders: Vec<ArrayBuilderImpl>,
@@ -585,6 +691,14 @@ mod tests {
         }
 
         fn create_join_executor(&self, has_non_equi_cond: bool) -> BoxedExecutor {
+            self.create_join_executor_with_spill(has_non_equi_cond, None)
+        }
+
+        fn create_join_executor_with_spill(
+            &self,
+            has_non_equi_cond: bool,
+            spill_params: Option<SpillParams>,
+        ) -> BoxedExecutor {
             let join_type = self.join_type;
 
             let left_child = self.create_left_executor();
@@ -632,13 +746,19 @@ mod tests {
                 fields: schema_fields,
             };
 
-            Box::new(HashJoinExecutor::<Key32>::new(
+            let executor = HashJoinExecutor::<Key32>::new(
                 left_child,
                 right_child,
                 params,
                 schema,
                 "HashJoinExecutor2".to_string(),
-            )) as BoxedExecutor
+            );
+            match spill_params {
+                Some(spill_params) => Box::new(
+                    executor.with_spill(spill_params, Arc::new(BatchMemoryTracker::default())),
+                ),
+                None => Box::new(executor),
+            }
         }
 
         fn select_from_chunk(&self, data_chunk: DataChunk) -> DataChunk {
@@ -1034,4 +1154,50 @@ mod tests {
 
         test_fixture.do_test(expected_chunk, true).await;
     }
+
+    async fn collect_sorted_rows(executor: BoxedExecutor) -> Vec<Vec<Datum>> {
+        let mut rows = vec![];
+        let mut stream = executor.execute();
+        while let Some(data_chunk) = stream.next().await {
+            let data_chunk = data_chunk.unwrap();
+            assert!(data_chunk.cardinality() > 0);
+            rows.extend(data_chunk.rows().map(|row| row.to_owned_row().0));
+        }
+        rows.sort();
+        rows
+    }
+
+    /// Spilling with a zero threshold always goes through the grace hash join, which must yield
+    /// the same rows as the in-memory join regardless of the output order.
+    #[tokio::test]
+    async fn test_join_with_spill() {
+        let join_types = [
+            JoinType::Inner,
+            JoinType::LeftOuter,
+            JoinType::LeftSemi,
+            JoinType::LeftAnti,
+            JoinType::RightOuter,
+            JoinType::RightSemi,
+            JoinType::RightAnti,
+            JoinType::FullOuter,
+        ];
+        for join_type in join_types {
+            let test_fixture = TestFixture::with_join_type(join_type);
+            for has_non_equi_cond in [false, true] {
+                let spill_params = SpillParams {
+                    threshold_bytes: 0,
+                    partitions: 3,
+                    directory: std::env::temp_dir(),
+                };
+                let expected =
+                    collect_sorted_rows(test_fixture.create_join_executor(has_non_equi_cond)).await;
+                let actual = collect_sorted_rows(
+                    test_fixture
+                        .create_join_executor_with_spill(has_non_equi_cond, Some(spill_params)),
+                )
+                .await;
+                assert_eq!(expected, actual, "{:?}", join_type);
+            }
+        }
+    }
 }
@@ -223,6 +223,11 @@ impl<K: HashKey> TryFrom<BuildTable> for ProbeTable<K> {
 }
 
 impl<K: HashKey> ProbeTable<K> {
+    /// Gives back the join parameters so that they can be reused to join another partition.
+    pub(super) fn into_params(self) -> EquiJoinParams {
+        self.params
+    }
+
     pub(super) fn build_data_empty(&self) -> bool {
         self.build_data.is_empty()
     }
@@ -0,0 +1,416 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use futures_async_stream::try_stream;
+use itertools::Itertools;
+use risingwave_common::array::{DataChunk, Row};
+use risingwave_common::catalog::{ColumnDesc, OrderedColumnDesc, Schema, TableId};
+use risingwave_common::error::{ErrorCode, Result, RwError};
+use risingwave_common::types::ScalarImpl;
+use risingwave_common::util::ordered::OrderedRowSerializer;
+use risingwave_expr::expr::{build_from_prost, BoxedExpression};
+use risingwave_pb::batch_plan::plan_node::NodeBody;
+use risingwave_storage::table::cell_based_table::CellBasedTable;
+use risingwave_storage::{dispatch_state_store, Keyspace, StateStore, StateStoreImpl};
+
+use crate::executor::join::JoinType;
+use crate::executor::{
+    BoxedDataChunkStream, BoxedExecutor, BoxedExecutorBuilder, Executor, ExecutorBuilder,
+};
+use crate::task::BatchTaskContext;
+
+/// Lookup join executor.
+///
+/// High Level Idea:
+/// 1. Iterate tuple from outer side.
+/// 2. Look up the rows of the inner table whose order key prefix equals the join key of the
+/// tuple, instead of scanning the whole inner table.
+/// 3. Eval join condition on the concatenated rows and output according to the join type.
+pub struct LookupJoinExecutor<S: StateStore> {
+    /// Executor should handle different join type. Only join types driven by the outer side are
+    /// supported.
+    join_type: JoinType,
+    /// Expression to eval join condition.
+    condition: Option<BoxedExpression>,
+    outer_side_input: Option<BoxedExecutor>,
+    /// Indices of the outer side columns to look up the inner table.
+    outer_side_key: Vec<usize>,
+    /// The inner table, whose pk serializer only serializes the lookup prefix.
+    inner_table: CellBasedTable<S>,
+    inner_side_len: usize,
+    epoch: u64,
+    chunk_size: usize,
+    schema: Schema,
+    identity: String,
+}
+
+impl<S: StateStore> LookupJoinExecutor<S> {
+    #[allow(clippy::too_many_arguments)]
+    pub(super) fn new(
+        join_type: JoinType,
+        condition: Option<BoxedExpression>,
+        outer_side_input: BoxedExecutor,
+        outer_side_key: Vec<usize>,
+        inner_table: CellBasedTable<S>,
+        epoch: u64,
+        chunk_size: usize,
+        identity: String,
+    ) -> Self {
+        let inner_side_len = inner_table.schema().len();
+        let schema = if join_type.keep_left() {
+            outer_side_input.schema().clone()
+        } else {
+            Schema {
+                fields: [
+                    outer_side_input.schema().fields.clone(),
+                    inner_table.schema().fields.clone(),
+                ]
+                .concat(),
+            }
+        };
+        Self {
+            join_type,
+            condition,
+            outer_side_input: Some(outer_side_input),
+            outer_side_key,
+            inner_table,
+            inner_side_len,
+            epoch,
+            chunk_size,
+            schema,
+            identity,
+        }
+    }
+
+    /// Returns the inner rows matching `outer_row`, concatenated after it.
+    async fn lookup(&self, outer_row: &Row) -> Result<Vec<Row>> {
+        let key = Row(self
+            .outer_side_key
+            .iter()
+            .map(|idx| outer_row.0[*idx].clone())
+            .collect());
+        // Null never equals to anything.
+        if key.0.iter().any(Option::is_none) {
+            return Ok(vec![]);
+        }
+        let inner_rows = self
+            .inner_table
+            .get_rows_by_pk_prefix(&key, self.epoch)
+            .await?;
+
+        let mut rows = vec![];
+        for inner_row in inner_rows {
+            let row = Row([outer_row.0.clone(), inner_row.0].concat());
+            if self.check_condition(&row)? {
+                rows.push(row);
+            }
+        }
+        Ok(rows)
+    }
+
+    fn check_condition(&self, row: &Row) -> Result<bool> {
+        match &self.condition {
+            Some(condition) => Ok(condition.eval_row(row)? == Some(ScalarImpl::Bool(true))),
+            None => Ok(true),
+        }
+    }
+}
+
+impl<S: StateStore> Executor for LookupJoinExecutor<S> {
+    fn schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    fn identity(&self) -> &str {
+        &self.identity
+    }
+
+    fn execute(self: Box<Self>) -> BoxedDataChunkStream {
+        self.do_execute()
+    }
+}
+
+impl<S: StateStore> LookupJoinExecutor<S> {
+    #[try_stream(boxed, ok = DataChunk, error = RwError)]
+    async fn do_execute(mut self: Box<Self>) {
+        let data_types = self.schema.data_types();
+        let mut output_rows = Vec::with_capacity(self.chunk_size);
+
+        #[for_await]
+        for chunk in self.outer_side_input.take().unwrap().execute() {
+            let chunk = chunk?;
+            for outer_row in chunk.rows() {
+                let outer_row = outer_row.to_owned_row();
+                let matched_rows = self.lookup(&outer_row).await?;
+                match self.join_type {
+                    JoinType::Inner => output_rows.extend(matched_rows),
+                    JoinType::LeftOuter if matched_rows.is_empty() => {
+                        let mut row = outer_row.0;
+                        row.resize(row.len() + self.inner_side_len, None);
+                        output_rows.push(Row(row));
+                    }
+                    JoinType::LeftOuter => output_rows.extend(matched_rows),
+                    JoinType::LeftSemi if !matched_rows.is_empty() => output_rows.push(outer_row),
+                    JoinType::LeftAnti if matched_rows.is_empty() => output_rows.push(outer_row),
+                    _ => {}
+                }
+
+                if output_rows.len() >= self.chunk_size {
+                    yield DataChunk::from_rows(&output_rows, &data_types)?;
+                    output_rows.clear();
+                }
+            }
+        }
+
+        if !output_rows.is_empty() {
+            yield DataChunk::from_rows(&output_rows, &data_types)?;
+        }
+    }
+}
+
+pub struct LookupJoinExecutorBuilder {}
+
+impl LookupJoinExecutorBuilder {
+    pub const DEFAULT_CHUNK_SIZE: usize = 1024;
+}
+
+#[async_trait::async_trait]
+impl BoxedExecutorBuilder for LookupJoinExecutorBuilder {
+    async fn new_boxed_executor<C: BatchTaskContext>(
+        source: &ExecutorBuilder<C>,
+        mut inputs: Vec<BoxedExecutor>,
+    ) -> Result<BoxedExecutor> {
+        ensure!(inputs.len() == 1, "LookupJoinExecutor should have 1 child!");
+        let outer_side_input = inputs.remove(0);
+
+        let lookup_join_node = try_match_expand!(
+            source.plan_node().get_node_body().unwrap(),
+            NodeBody::LookupJoin
+        )?;
+
+        let join_type = JoinType::from_prost(lookup_join_node.get_join_type()?);
+        if !matches!(
+            join_type,
+            JoinType::Inner | JoinType::LeftOuter | JoinType::LeftSemi | JoinType::LeftAnti
+        ) {
+            return Err(ErrorCode::NotImplemented(
+                format!(
+                    "Do not support {:?} join type in lookup join now.",
+                    join_type
+                ),
+                None.into(),
+            )
+            .into());
+        }
+        let condition = match lookup_join_node.get_condition() {
+            Ok(cond_prost) => Some(build_from_prost(cond_prost)?),
+            Err(_) => None,
+        };
+        let outer_side_key = lookup_join_node
+            .get_outer_side_key()
+            .iter()
+            .map(|idx| *idx as usize)
+            .collect_vec();
+
+        let table_desc = lookup_join_node.get_table_desc()?;
+        let table_id = TableId {
+            table_id: table_desc.table_id,
+        };
+        // Only the order key prefix matching the outer side key is serialized for lookup.
+        let order_types = table_desc
+            .pk
+            .iter()
+            .take(outer_side_key.len())
+            .map(|order| OrderedColumnDesc::from(order.clone()).order)
+            .collect_vec();
+        ensure!(
+            order_types.len() == outer_side_key.len(),
+            "Lookup join key is longer than the order key of the table!"
+        );
+        let column_descs = lookup_join_node
+            .inner_column_descs
+            .iter()
+            .map(|column_desc| ColumnDesc::from(column_desc.clone()))
+            .collect_vec();
+
+        let state_store = source.context().try_get_state_store()?;
+        // The rows expired by the retention of the table are not read.
+        if let Some(hummock) = state_store.hummock_storage() {
+            hummock.set_table_retention(table_id.table_id, table_desc.retention_seconds);
+        }
+        dispatch_state_store!(state_store, state_store, {
+            state_store.wait_epoch(source.epoch()).await?;
+            let keyspace = Keyspace::table_root(state_store.clone(), &table_id);
+            let inner_table = CellBasedTable::new(
+                keyspace,
+                column_descs,
+                Some(OrderedRowSerializer::new(order_types)),
+                state_store.stats(),
+                None,
+            );
+            Ok(Box::new(LookupJoinExecutor::new(
+                join_type,
+                condition,
+                outer_side_input,
+                outer_side_key,
+                inner_table,
+                source.epoch(),
+                LookupJoinExecutorBuilder::DEFAULT_CHUNK_SIZE,
+                source.plan_node().get_identity().clone(),
+            )))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::TryStreamExt;
+    use risingwave_common::array::{DataChunk, Row};
+    use risingwave_common::catalog::{ColumnDesc, ColumnId, Field, Schema, TableId};
+    use risingwave_common::test_prelude::DataChunkTestExt;
+    use risingwave_common::types::DataType;
+    use risingwave_common::util::sort_util::OrderType;
+    use risingwave_expr::expr::expr_binary_nonnull::new_binary_expr;
+    use risingwave_expr::expr::InputRefExpression;
+    use risingwave_pb::expr::expr_node::Type;
+    use risingwave_storage::memory::MemoryStateStore;
+    use risingwave_storage::table::cell_based_table::CellBasedTable;
+    use risingwave_storage::table::state_table::StateTable;
+    use risingwave_storage::Keyspace;
+
+    use super::LookupJoinExecutor;
+    use crate::executor::join::JoinType;
+    use crate::executor::test_utils::MockExecutor;
+    use crate::executor::{BoxedExecutor, Executor};
+
+    /// Creates an inner table of `(k int, v int)` keyed by `k` with rows `(1, 10)`, `(2, 20)` and
+    /// `(2, 21)`.
+    async fn create_inner_table() -> CellBasedTable<MemoryStateStore> {
+        let column_descs = vec![
+            ColumnDesc::unnamed(ColumnId::from(0), DataType::Int32),
+            ColumnDesc::unnamed(ColumnId::from(1), DataType::Int32),
+        ];
+        let order_types = vec![OrderType::Ascending, OrderType::Ascending];
+        let keyspace = Keyspace::table_root(MemoryStateStore::new(), &TableId::from(0x42));
+        let mut state = StateTable::new(
+            keyspace.clone(),
+            column_descs.clone(),
+            order_types,
+            None,
+            vec![0, 1],
+        );
+        for (k, v) in [(1, 10), (2, 20), (2, 21)] {
+            let row = Row(vec![Some(k.into()), Some(v.into())]);
+            state.insert(&row, row.clone()).unwrap();
+        }
+        state.commit(0).await.unwrap();
+
+        CellBasedTable::new_for_test(keyspace, column_descs, vec![OrderType::Ascending])
+    }
+
+    fn create_outer_input() -> BoxedExecutor {
+        let schema = Schema {
+            fields: vec![
+                Field::unnamed(DataType::Int32),
+                Field::unnamed(DataType::Int32),
+            ],
+        };
+        Box::new(MockExecutor::with_chunk(
+            DataChunk::from_pretty(
+                "i i
+                 1 100
+                 2 20
+                 3 300
+                 . 400",
+            ),
+            schema,
+        ))
+    }
+
+    async fn execute(join_type: JoinType, with_condition: bool) -> Vec<DataChunk> {
+        // `outer.v < inner.v`
+        let condition = with_condition.then(|| {
+            new_binary_expr(
+                Type::LessThan,
+                DataType::Boolean,
+                Box::new(InputRefExpression::new(DataType::Int32, 1)),
+                Box::new(InputRefExpression::new(DataType::Int32, 3)),
+            )
+        });
+        let executor = Box::new(LookupJoinExecutor::new(
+            join_type,
+            condition,
+            create_outer_input(),
+            vec![0],
+            create_inner_table().await,
+            u64::MAX,
+            1024,
+            "LookupJoinExecutor".to_string(),
+        ));
+
+        executor.execute().try_collect().await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_inner_join() {
+        let chunks = execute(JoinType::Inner, false).await;
+        assert_eq!(
+            chunks,
+            vec![DataChunk::from_pretty(
+                "i i   i i
+                 1 100 1 10
+                 2 20  2 20
+                 2 20  2 21"
+            )]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_left_outer_join_with_condition() {
+        let chunks = execute(JoinType::LeftOuter, true).await;
+        assert_eq!(
+            chunks,
+            vec![DataChunk::from_pretty(
+                "i i   i i
+                 1 100 . .
+                 2 20  2 21
+                 3 300 . .
+                 . 400 . ."
+            )]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_left_semi_and_anti_join() {
+        let chunks = execute(JoinType::LeftSemi, false).await;
+        assert_eq!(
+            chunks,
+            vec![DataChunk::from_pretty(
+                "i i
+                 1 100
+                 2 20"
+            )]
+        );
+
+        let chunks = execute(JoinType::LeftAnti, false).await;
+        assert_eq!(
+            chunks,
+            vec![DataChunk::from_pretty(
+                "i i
+                 3 300
+                 . 400"
+            )]
+        );
+    }
+}
@@ -15,12 +15,15 @@
 mod chunked_data;
 pub mod hash_join;
 mod hash_join_state;
+mod lookup_join;
 pub mod nested_loop_join;
 mod row_level_iter;
 mod sort_merge_join;
+mod spill;
 
 pub use chunked_data::*;
 pub use hash_join::*;
+pub use lookup_join::*;
 pub use nested_loop_join::*;
 use risingwave_pb::plan_common::JoinType as JoinTypeProst;
 pub use sort_merge_join::*;
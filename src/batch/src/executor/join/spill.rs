@@ -0,0 +1,283 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Partition spilling used by the grace hash join.
+//!
+//! Both inputs of the join are split by the hash of their join keys into the same number of
+//! partitions, so that rows which may match always land in partitions with the same index. Every
+//! partition is written into an anonymous temporary file as a sequence of length-prefixed
+//! protobuf-encoded data chunks. A partition that is still too large can be split again with
+//! another seed, up to [`MAX_SPILL_LEVEL`] times.
+
+use std::io::SeekFrom;
+use std::path::PathBuf;
+
+use futures_async_stream::try_stream;
+use prost::Message;
+use risingwave_common::array::DataChunk;
+use risingwave_common::buffer::Bitmap;
+use risingwave_common::config::BatchConfig;
+use risingwave_common::error::ErrorCode::InternalError;
+use risingwave_common::error::{Result, RwError};
+use risingwave_common::hash::HashCode;
+use risingwave_common::util::hash_util::CRC32FastBuilder;
+use risingwave_pb::data::DataChunk as ProstDataChunk;
+use tokio::fs::File;
+use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt, BufReader, BufWriter};
+
+use crate::executor::BoxedDataChunkStream;
+
+/// Maximum number of times a partition is split again. Rows sharing the same join key always land
+/// in the same partition, so a skewed key can't be split by any seed.
+pub(super) const MAX_SPILL_LEVEL: usize = 3;
+
+/// Parameters of spilling for the grace hash join.
+#[derive(Clone, Debug)]
+pub(super) struct SpillParams {
+    /// Size in bytes of the build side above which the join starts to spill.
+    pub threshold_bytes: usize,
+    /// Number of partitions to split both inputs into.
+    pub partitions: usize,
+    /// Directory to create spill files in.
+    pub directory: PathBuf,
+}
+
+impl SpillParams {
+    pub fn from_config(config: &BatchConfig) -> Self {
+        let directory = if config.spill_directory.is_empty() {
+            std::env::temp_dir()
+        } else {
+            PathBuf::from(&config.spill_directory)
+        };
+        Self {
+            threshold_bytes: config.hash_join_spill_threshold_mb << 20,
+            partitions: config.hash_join_spill_partitions.max(1),
+            directory,
+        }
+    }
+}
+
+fn io_error(e: std::io::Error) -> RwError {
+    InternalError(format!("spill io error: {}", e)).into()
+}
+
+/// Maps the hash of a row to a partition. The hash is mixed with the seed by the finalizer of
+/// MurmurHash3, so that rows of one partition are spread over all partitions with another seed.
+fn partition_of(hash: &HashCode, seed: u64, partitions: usize) -> usize {
+    let mut h = hash.0 ^ seed.wrapping_mul(0x9e37_79b9_7f4a_7c15);
+    h ^= h >> 33;
+    h = h.wrapping_mul(0xff51_afd7_ed55_8ccd);
+    h ^= h >> 33;
+    h = h.wrapping_mul(0xc4ce_b9fe_1a85_ec53);
+    h ^= h >> 33;
+    h as usize % partitions
+}
+
+/// A file holding a sequence of spilled data chunks.
+pub(super) struct SpillFile {
+    writer: BufWriter<File>,
+    chunks: usize,
+}
+
+impl SpillFile {
+    async fn create(params: &SpillParams) -> Result<Self> {
+        let directory = params.directory.clone();
+        let file = tokio::task::spawn_blocking(move || tempfile::tempfile_in(directory))
+            .await
+            .map_err(|e| InternalError(format!("failed to create spill file: {}", e)))?
+            .map_err(io_error)?;
+        Ok(Self {
+            writer: BufWriter::new(File::from_std(file)),
+            chunks: 0,
+        })
+    }
+
+    /// Appends a chunk to the file, returning the number of bytes written.
+    async fn write_chunk(&mut self, chunk: &DataChunk) -> Result<usize> {
+        let buf = chunk.to_protobuf().encode_to_vec();
+        self.writer
+            .write_u32_le(buf.len() as u32)
+            .await
+            .map_err(io_error)?;
+        self.writer.write_all(&buf).await.map_err(io_error)?;
+        self.chunks += 1;
+        Ok(buf.len() + 4)
+    }
+
+    /// Reads back the file as a chunk stream, in the order the chunks were written.
+    #[try_stream(boxed, ok = DataChunk, error = RwError)]
+    pub async fn into_stream(mut self) {
+        self.writer.flush().await.map_err(io_error)?;
+        let mut file = self.writer.into_inner();
+        file.seek(SeekFrom::Start(0)).await.map_err(io_error)?;
+        let mut reader = BufReader::new(file);
+        for _ in 0..self.chunks {
+            let len = reader.read_u32_le().await.map_err(io_error)? as usize;
+            let mut buf = vec![0; len];
+            reader.read_exact(&mut buf).await.map_err(io_error)?;
+            let prost = ProstDataChunk::decode(&buf[..])
+                .map_err(|e| InternalError(format!("failed to decode spilled chunk: {}", e)))?;
+            yield DataChunk::from_protobuf(&prost)?;
+        }
+    }
+}
+
+/// Splits the chunks of one join input into spill files by the hash of the join keys.
+pub(super) struct PartitionSpiller {
+    key_columns: Vec<usize>,
+    seed: u64,
+    files: Vec<SpillFile>,
+    spilled_bytes: usize,
+}
+
+impl PartitionSpiller {
+    /// Creates a spiller whose partitions are decided by `seed`. Both inputs of a join must be
+    /// spilled with the same seed.
+    pub async fn new(params: &SpillParams, key_columns: &[usize], seed: u64) -> Result<Self> {
+        let mut files = Vec::with_capacity(params.partitions);
+        for _ in 0..params.partitions {
+            files.push(SpillFile::create(params).await?);
+        }
+        Ok(Self {
+            key_columns: key_columns.to_vec(),
+            seed,
+            files,
+            spilled_bytes: 0,
+        })
+    }
+
+    pub async fn spill(&mut self, chunk: DataChunk) -> Result<()> {
+        let partitions = self.files.len();
+        let seed = self.seed;
+        let hash_values = chunk.get_hash_values(&self.key_columns, CRC32FastBuilder)?;
+        let visibility = chunk.visibility();
+        for (partition, file) in self.files.iter_mut().enumerate() {
+            let vis = hash_values
+                .iter()
+                .enumerate()
+                .map(|(row_idx, hash)| {
+                    partition_of(hash, seed, partitions) == partition
+                        && visibility.map_or(true, |vis| vis.is_set(row_idx).unwrap())
+                })
+                .collect::<Vec<_>>();
+            let part = chunk.with_visibility(Bitmap::try_from(vis)?).compact()?;
+            if part.cardinality() > 0 {
+                self.spilled_bytes += file.write_chunk(&part).await?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn spilled_bytes(&self) -> usize {
+        self.spilled_bytes
+    }
+
+    pub fn finish(self) -> Vec<SpillFile> {
+        self.files
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use futures::StreamExt;
+    use itertools::Itertools;
+    use risingwave_common::array::{DataChunk, DataChunkTestExt};
+    use risingwave_common::config::BatchConfig;
+    use risingwave_common::types::ScalarImpl;
+
+    use super::{PartitionSpiller, SpillFile, SpillParams};
+
+    async fn collect_keys(file: SpillFile) -> Vec<Option<ScalarImpl>> {
+        let mut keys = vec![];
+        let mut stream = file.into_stream();
+        while let Some(chunk) = stream.next().await {
+            for row in chunk.unwrap().rows() {
+                keys.push(row.to_owned_row().0[0].clone());
+            }
+        }
+        keys
+    }
+
+    #[tokio::test]
+    async fn test_spill_partitions() {
+        let params = SpillParams {
+            partitions: 3,
+            ..SpillParams::from_config(&BatchConfig::default())
+        };
+        let mut spiller = PartitionSpiller::new(&params, &[0], 0).await.unwrap();
+        let chunk = DataChunk::from_pretty(
+            "i I
+             1 10
+             2 20
+             . 30
+             1 40
+             3 50",
+        );
+        spiller.spill(chunk.clone()).await.unwrap();
+        spiller.spill(chunk).await.unwrap();
+        assert!(spiller.spilled_bytes() > 0);
+
+        let mut row_count = 0;
+        let mut seen_keys = HashSet::new();
+        for file in spiller.finish() {
+            let keys = collect_keys(file).await;
+            row_count += keys.len();
+            let keys: HashSet<_> = keys.into_iter().collect();
+            // Rows with the same key always land in the same partition.
+            assert!(seen_keys.is_disjoint(&keys));
+            seen_keys.extend(keys);
+        }
+        assert_eq!(row_count, 10);
+        assert_eq!(seen_keys.len(), 4);
+    }
+
+    /// Splitting a partition again with another seed spreads its rows over the new partitions.
+    #[tokio::test]
+    async fn test_spill_partitions_with_seed() {
+        let params = SpillParams {
+            partitions: 2,
+            ..SpillParams::from_config(&BatchConfig::default())
+        };
+        let chunk = DataChunk::from_pretty(&format!("i\n{}", (0..64).join("\n")));
+        let mut spiller = PartitionSpiller::new(&params, &[0], 0).await.unwrap();
+        spiller.spill(chunk).await.unwrap();
+        let partition = spiller
+            .finish()
+            .into_iter()
+            .next()
+            .unwrap()
+            .into_stream()
+            .next()
+            .await
+            .unwrap()
+            .unwrap();
+
+        let mut same_seed = PartitionSpiller::new(&params, &[0], 0).await.unwrap();
+        same_seed.spill(partition.clone()).await.unwrap();
+        let mut another_seed = PartitionSpiller::new(&params, &[0], 1).await.unwrap();
+        another_seed.spill(partition.clone()).await.unwrap();
+
+        let mut same_seed_sizes = vec![];
+        for file in same_seed.finish() {
+            same_seed_sizes.push(collect_keys(file).await.len());
+        }
+        assert_eq!(same_seed_sizes, vec![partition.cardinality(), 0]);
+        for file in another_seed.finish() {
+            let size = collect_keys(file).await.len();
+            assert!(size > 0 && size < partition.cardinality());
+        }
+    }
+}
@@ -13,6 +13,8 @@
 // limitations under the License.
 
 mod delete;
+mod export;
+mod file_scan;
 mod filter;
 mod generate_series;
 mod generic_exchange;
@@ -24,18 +26,23 @@ mod limit;
 mod merge_sort_exchange;
 pub mod monitor;
 mod order_by;
+mod profile;
 mod project;
 mod row_seq_scan;
 mod sort_agg;
+mod source_scan;
 #[cfg(test)]
 pub mod test_utils;
 mod top_n;
 mod trace;
+mod unnest;
 mod update;
 mod values;
 
 use async_recursion::async_recursion;
 pub use delete::*;
+pub use export::*;
+pub use file_scan::*;
 pub use filter::*;
 use futures::stream::BoxStream;
 pub use generate_series::*;
@@ -48,6 +55,7 @@ pub use limit::*;
 pub use merge_sort_exchange::*;
 pub use monitor::*;
 pub use order_by::*;
+pub use profile::*;
 pub use project::*;
 use risingwave_common::array::DataChunk;
 use risingwave_common::catalog::Schema;
@@ -57,8 +65,10 @@ use risingwave_pb::batch_plan::plan_node::NodeBody;
 use risingwave_pb::batch_plan::PlanNode;
 pub use row_seq_scan::*;
 pub use sort_agg::*;
+pub use source_scan::*;
 pub use top_n::*;
 pub use trace::*;
+pub use unnest::*;
 pub use update::*;
 pub use values::*;
 
@@ -103,6 +113,8 @@ pub struct ExecutorBuilder<'a, C> {
     pub task_id: &'a TaskId,
     context: C,
     epoch: u64,
+    /// Set if the runtime statistics of the executors are collected.
+    profile: Option<TaskProfile>,
 }
 
 macro_rules! build_executor {
@@ -124,12 +136,26 @@ impl<'a, C: Clone> ExecutorBuilder<'a, C> {
             task_id,
             context,
             epoch,
+            profile: None,
         }
     }
 
+    /// Records the runtime statistics of the executors into `profile`.
+    #[must_use]
+    pub fn with_profile(mut self, profile: TaskProfile) -> Self {
+        self.profile = Some(profile);
+        self
+    }
+
     #[must_use]
     pub fn clone_for_plan(&self, plan_node: &'a PlanNode) -> Self {
-        ExecutorBuilder::new(plan_node, self.task_id, self.context.clone(), self.epoch)
+        ExecutorBuilder {
+            plan_node,
+            task_id: self.task_id,
+            context: self.context.clone(),
+            epoch: self.epoch,
+            profile: self.profile.clone(),
+        }
     }
 
     pub fn plan_node(&self) -> &PlanNode {
@@ -181,14 +207,27 @@ impl<'a, C: BatchTaskContext> ExecutorBuilder<'a, C> {
             NodeBody::NestedLoopJoin => NestedLoopJoinExecutor,
             NodeBody::HashJoin => HashJoinExecutorBuilder,
             NodeBody::SortMergeJoin => SortMergeJoinExecutor,
+            NodeBody::LookupJoin => LookupJoinExecutorBuilder,
             NodeBody::HashAgg => HashAggExecutorBuilder,
             NodeBody::MergeSortExchange => MergeSortExchangeExecutorBuilder,
             NodeBody::GenerateSeries => GenerateSeriesExecutorBuilder,
             NodeBody::HopWindow => HopWindowExecutor,
+            NodeBody::Unnest => UnnestExecutorBuilder,
+            NodeBody::Export => ExportExecutor,
+            NodeBody::FileScan => FileScanExecutor,
+            NodeBody::SourceScan => SourceScanExecutor,
         }
         .await?;
         let input_desc = real_executor.identity().to_string();
-        Ok(Box::new(TraceExecutor::new(real_executor, input_desc)) as BoxedExecutor)
+        let executor = Box::new(TraceExecutor::new(real_executor, input_desc)) as BoxedExecutor;
+        Ok(match &self.profile {
+            Some(profile) => Box::new(ProfileExecutor::new(
+                executor,
+                self.plan_node.plan_node_id,
+                profile.clone(),
+            )),
+            None => executor,
+        })
     }
 }
 
@@ -13,11 +13,21 @@
 // limitations under the License.
 //
 use prometheus::{
-    exponential_buckets, histogram_opts, register_histogram_with_registry, Histogram, Registry,
+    exponential_buckets, histogram_opts, register_histogram_with_registry,
+    register_int_counter_with_registry, register_int_gauge_with_registry, Histogram, IntCounter,
+    IntGauge, Registry,
 };
 
 pub struct BatchMetrics {
     pub row_seq_scan_next_duration: Histogram,
+    /// Number of tasks queued because of insufficient resources.
+    pub task_queued_count: IntCounter,
+    /// Number of tasks rejected because of insufficient resources.
+    pub task_rejected_count: IntCounter,
+    /// Number of tasks waiting in the admission queue.
+    pub task_queueing: IntGauge,
+    /// Number of admitted tasks running on the node.
+    pub task_running: IntGauge,
 }
 
 impl BatchMetrics {
@@ -29,8 +39,37 @@ impl BatchMetrics {
         );
         let row_seq_scan_next_duration = register_histogram_with_registry!(opts, registry).unwrap();
 
+        let task_queued_count = register_int_counter_with_registry!(
+            "batch_task_queued_count",
+            "Total number of batch tasks queued because of insufficient resources",
+            registry
+        )
+        .unwrap();
+        let task_rejected_count = register_int_counter_with_registry!(
+            "batch_task_rejected_count",
+            "Total number of batch tasks rejected because of insufficient resources",
+            registry
+        )
+        .unwrap();
+        let task_queueing = register_int_gauge_with_registry!(
+            "batch_task_queueing",
+            "Number of batch tasks waiting for resources",
+            registry
+        )
+        .unwrap();
+        let task_running = register_int_gauge_with_registry!(
+            "batch_task_running",
+            "Number of admitted batch tasks running",
+            registry
+        )
+        .unwrap();
+
         Self {
             row_seq_scan_next_duration,
+            task_queued_count,
+            task_rejected_count,
+            task_queueing,
+            task_running,
         }
     }
 
@@ -0,0 +1,199 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use futures::stream::StreamExt;
+use futures_async_stream::try_stream;
+use parking_lot::Mutex;
+use prost::Message;
+use risingwave_common::array::DataChunk;
+use risingwave_common::catalog::Schema;
+use risingwave_common::error::RwError;
+use risingwave_pb::task_service::OperatorProfile as ProstOperatorProfile;
+
+use crate::executor::{BoxedDataChunkStream, BoxedExecutor, Executor};
+
+/// Runtime statistics of an operator, e.g. shown by `EXPLAIN ANALYZE`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct OperatorProfile {
+    /// Number of the rows produced.
+    pub rows: u64,
+    /// Encoded size of the chunks produced.
+    pub bytes: u64,
+    /// Time spent producing the chunks, including the time spent in the inputs.
+    pub elapsed: Duration,
+}
+
+impl OperatorProfile {
+    /// Adds up the statistics of the same operator in another task.
+    pub fn merge(&mut self, other: &Self) {
+        self.rows += other.rows;
+        self.bytes += other.bytes;
+        self.elapsed += other.elapsed;
+    }
+
+    pub fn from_prost(prost: &ProstOperatorProfile) -> Self {
+        Self {
+            rows: prost.rows,
+            bytes: prost.bytes,
+            elapsed: Duration::from_nanos(prost.elapsed_ns),
+        }
+    }
+
+    pub fn to_prost(&self, plan_node_id: i32) -> ProstOperatorProfile {
+        ProstOperatorProfile {
+            plan_node_id,
+            rows: self.rows,
+            bytes: self.bytes,
+            elapsed_ns: self.elapsed.as_nanos() as u64,
+        }
+    }
+}
+
+/// Collects the [`OperatorProfile`]s of the operators of a task by their plan node ids. Clones
+/// share the same profiles.
+#[derive(Clone, Debug, Default)]
+pub struct TaskProfile {
+    operators: Arc<Mutex<HashMap<i32, OperatorProfile>>>,
+}
+
+impl TaskProfile {
+    fn record(&self, plan_node_id: i32, chunk: Option<&DataChunk>, elapsed: Duration) {
+        let mut operators = self.operators.lock();
+        let profile = operators.entry(plan_node_id).or_default();
+        if let Some(chunk) = chunk {
+            profile.rows += chunk.cardinality() as u64;
+            profile.bytes += chunk.to_protobuf().encoded_len() as u64;
+        }
+        profile.elapsed += elapsed;
+    }
+
+    pub fn operators(&self) -> HashMap<i32, OperatorProfile> {
+        self.operators.lock().clone()
+    }
+
+    pub fn to_prost(&self) -> Vec<ProstOperatorProfile> {
+        self.operators
+            .lock()
+            .iter()
+            .map(|(plan_node_id, profile)| profile.to_prost(*plan_node_id))
+            .collect()
+    }
+}
+
+/// [`ProfileExecutor`] is built on top of the executor of each plan node if the task is profiled,
+/// recording the chunks produced by the executor and the time spent on them into the
+/// [`TaskProfile`].
+pub struct ProfileExecutor {
+    child: BoxedExecutor,
+    plan_node_id: i32,
+    profile: TaskProfile,
+}
+
+impl ProfileExecutor {
+    pub fn new(child: BoxedExecutor, plan_node_id: i32, profile: TaskProfile) -> Self {
+        Self {
+            child,
+            plan_node_id,
+            profile,
+        }
+    }
+}
+
+impl Executor for ProfileExecutor {
+    fn schema(&self) -> &Schema {
+        self.child.schema()
+    }
+
+    fn identity(&self) -> &str {
+        "ProfileExecutor"
+    }
+
+    fn execute(self: Box<Self>) -> BoxedDataChunkStream {
+        self.do_execute()
+    }
+}
+
+impl ProfileExecutor {
+    #[try_stream(boxed, ok = DataChunk, error = RwError)]
+    async fn do_execute(self: Box<Self>) {
+        let mut child_stream = self.child.execute();
+        loop {
+            let start_time = Instant::now();
+            let chunk = child_stream.next().await.transpose()?;
+            self.profile
+                .record(self.plan_node_id, chunk.as_ref(), start_time.elapsed());
+            match chunk {
+                Some(chunk) => yield chunk,
+                None => break,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::stream::StreamExt;
+    use risingwave_common::catalog::Field;
+    use risingwave_common::test_prelude::DataChunkTestExt;
+    use risingwave_common::types::DataType;
+
+    use super::*;
+    use crate::executor::test_utils::MockExecutor;
+
+    #[tokio::test]
+    async fn test_profile_executor() {
+        let schema = Schema::new(vec![Field::unnamed(DataType::Int32)]);
+        let mut mock_executor = MockExecutor::new(schema);
+        let chunks = [
+            DataChunk::from_pretty(
+                "i
+                 1
+                 2",
+            ),
+            DataChunk::from_pretty(
+                "i
+                 3",
+            ),
+        ];
+        for chunk in &chunks {
+            mock_executor.add(chunk.clone());
+        }
+        let profile = TaskProfile::default();
+        let executor = Box::new(ProfileExecutor::new(
+            Box::new(mock_executor),
+            7,
+            profile.clone(),
+        ));
+
+        let mut stream = executor.execute();
+        while let Some(chunk) = stream.next().await {
+            chunk.unwrap();
+        }
+        let operators = profile.operators();
+        assert_eq!(operators.len(), 1);
+        let bytes = chunks
+            .iter()
+            .map(|chunk| chunk.to_protobuf().encoded_len() as u64)
+            .sum();
+        assert_eq!((operators[&7].rows, operators[&7].bytes), (3, bytes));
+
+        let prost = profile.to_prost();
+        assert_eq!(prost[0].plan_node_id, 7);
+        assert_eq!(OperatorProfile::from_prost(&prost[0]), operators[&7]);
+    }
+}
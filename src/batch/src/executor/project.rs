@@ -17,7 +17,7 @@ use risingwave_common::array::column::Column;
 use risingwave_common::array::DataChunk;
 use risingwave_common::catalog::{Field, Schema};
 use risingwave_common::error::{Result, RwError};
-use risingwave_expr::expr::{build_from_prost, BoxedExpression};
+use risingwave_expr::expr::{build_from_prost, with_epoch, BoxedExpression};
 use risingwave_pb::batch_plan::plan_node::NodeBody;
 
 use crate::executor::{
@@ -80,11 +80,14 @@ impl BoxedExecutorBuilder for ProjectExecutor {
             NodeBody::Project
         )?;
 
-        let project_exprs = project_node
-            .get_select_list()
-            .iter()
-            .map(build_from_prost)
-            .collect::<Result<Vec<BoxedExpression>>>()?;
+        // `NOW()` is evaluated at the epoch of the snapshot being read.
+        let project_exprs = with_epoch(source.epoch(), || {
+            project_node
+                .get_select_list()
+                .iter()
+                .map(build_from_prost)
+                .collect::<Result<Vec<BoxedExpression>>>()
+        })?;
 
         let fields = project_exprs
             .iter()
@@ -58,8 +58,9 @@ impl<S: StateStore> RowSeqScanExecutor<S> {
     }
 
     // TODO: Remove this when we support real partition-scan.
-    // For shared storage like Hummock, we are using a fake partition-scan now. If `self.primary` is
-    // false, we'll ignore this scanning and yield no chunk.
+    // For shared storage like Hummock, we are using a fake partition-scan now, since the keys of a
+    // table are not prefixed by vnodes and a task can't scan only the key ranges of its vnodes. If
+    // `self.primary` is false, we'll ignore this scanning and yield no chunk.
     fn should_ignore(&self) -> bool {
         !self.primary
     }
@@ -87,15 +88,21 @@ impl BoxedExecutorBuilder for RowSeqScanExecutorBuilder {
             NodeBody::RowSeqScan
         )?;
 
+        let table_desc = seq_scan_node.get_table_desc()?;
         let table_id = TableId {
-            table_id: seq_scan_node.table_desc.as_ref().unwrap().table_id,
+            table_id: table_desc.table_id,
         };
         let column_descs = seq_scan_node
             .column_descs
             .iter()
             .map(|column_desc| ColumnDesc::from(column_desc.clone()))
             .collect_vec();
-        dispatch_state_store!(source.context().try_get_state_store()?, state_store, {
+        let state_store = source.context().try_get_state_store()?;
+        // The rows expired by the retention of the table are not read.
+        if let Some(hummock) = state_store.hummock_storage() {
+            hummock.set_table_retention(table_id.table_id, table_desc.retention_seconds);
+        }
+        dispatch_state_store!(state_store, state_store, {
             let keyspace = Keyspace::table_root(state_store.clone(), &table_id);
             let storage_stats = state_store.stats();
             let batch_stats = source.context().stats();
@@ -0,0 +1,230 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use futures_async_stream::try_stream;
+use itertools::Itertools;
+use risingwave_common::array::column::Column;
+use risingwave_common::array::{ArrayBuilder, DataChunk, I64ArrayBuilder};
+use risingwave_common::catalog::{ColumnId, Field, Schema};
+use risingwave_common::error::ErrorCode::{ConnectorError, InternalError};
+use risingwave_common::error::{Result, RwError};
+use risingwave_connector::monitor::SourceMetrics;
+use risingwave_connector::SplitEnumeratorImpl;
+use risingwave_pb::batch_plan::plan_node::NodeBody;
+use risingwave_source::{create_connector_source_desc, SourceDesc};
+
+use crate::executor::{
+    BoxedDataChunkStream, BoxedExecutor, BoxedExecutorBuilder, Executor, ExecutorBuilder,
+};
+use crate::task::BatchTaskContext;
+
+/// [`SourceScanExecutor`] reads the messages of a connector source from the earliest offsets to
+/// the latest ones when the scan starts, e.g. to explore a source before creating materialized
+/// views on it. The source is created from its definition, so that it can be read on any worker.
+pub struct SourceScanExecutor {
+    source_desc: SourceDesc,
+    column_ids: Vec<ColumnId>,
+    schema: Schema,
+    identity: String,
+}
+
+impl SourceScanExecutor {
+    pub fn new(
+        source_desc: SourceDesc,
+        column_ids: Vec<ColumnId>,
+        identity: String,
+    ) -> Result<Self> {
+        let fields: Vec<Field> = column_ids
+            .iter()
+            .map(|id| {
+                source_desc
+                    .columns
+                    .iter()
+                    .find(|c| c.column_id == *id)
+                    .map(|c| Field::with_name(c.data_type.clone(), c.name.clone()))
+                    .ok_or_else(|| InternalError(format!("column {} not found in source", id)))
+            })
+            .try_collect()?;
+        Ok(Self {
+            source_desc,
+            column_ids,
+            schema: Schema { fields },
+            identity,
+        })
+    }
+
+    /// Generates the ids of the rows, which are only unique within the scan.
+    fn gen_row_id_column(&self, len: usize) -> Result<Column> {
+        let mut builder = I64ArrayBuilder::new(len)?;
+        for row_id in self.source_desc.next_row_id_batch(len) {
+            builder.append(Some(row_id))?;
+        }
+        Ok(Column::from(builder.finish()?))
+    }
+}
+
+impl Executor for SourceScanExecutor {
+    fn schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    fn identity(&self) -> &str {
+        &self.identity
+    }
+
+    fn execute(self: Box<Self>) -> BoxedDataChunkStream {
+        self.do_execute()
+    }
+}
+
+impl SourceScanExecutor {
+    #[try_stream(boxed, ok = DataChunk, error = RwError)]
+    async fn do_execute(self: Box<Self>) {
+        let connector_error = |e: anyhow::Error| RwError::from(ConnectorError(e.to_string()));
+        let connector = self
+            .source_desc
+            .source
+            .as_connector()
+            .ok_or_else(|| InternalError("expect a connector source".to_string()))?;
+
+        // The splits are bounded by the latest offsets at this point.
+        let splits = SplitEnumeratorImpl::create(connector.config.clone())
+            .await
+            .map_err(connector_error)?
+            .list_bounded_splits()
+            .await
+            .map_err(connector_error)?;
+        let mut reader = connector
+            .batch_reader(splits, self.column_ids.clone())
+            .await?;
+
+        let row_id_column_id = self.source_desc.columns[self.source_desc.row_id_index].column_id;
+        let row_id_position = self
+            .column_ids
+            .iter()
+            .position(|column_id| *column_id == row_id_column_id);
+        while let Some(chunk) = reader.next_chunk().await? {
+            let (ops, mut columns, _) = chunk.chunk.into_inner();
+            if ops.is_empty() {
+                continue;
+            }
+            if let Some(position) = row_id_position {
+                columns[position] = self.gen_row_id_column(ops.len())?;
+            }
+            yield DataChunk::new(columns, ops.len());
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl BoxedExecutorBuilder for SourceScanExecutor {
+    async fn new_boxed_executor<C: BatchTaskContext>(
+        source: &ExecutorBuilder<C>,
+        inputs: Vec<BoxedExecutor>,
+    ) -> Result<BoxedExecutor> {
+        ensure!(
+            inputs.is_empty(),
+            "SourceScanExecutor should have no child!"
+        );
+        let source_scan_node = try_match_expand!(
+            source.plan_node().get_node_body().unwrap(),
+            NodeBody::SourceScan
+        )?;
+
+        let source_desc = create_connector_source_desc(
+            source_scan_node.get_info()?.clone(),
+            0,
+            Arc::new(SourceMetrics::unused()),
+        )
+        .await?;
+        let column_ids = source_scan_node
+            .column_ids
+            .iter()
+            .map(|id| ColumnId::new(*id))
+            .collect();
+
+        Ok(Box::new(Self::new(
+            source_desc,
+            column_ids,
+            source.plan_node().get_identity().clone(),
+        )?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::StreamExt;
+    use risingwave_common::catalog::ColumnDesc;
+    use risingwave_common::types::DataType;
+    use risingwave_pb::catalog::StreamSourceInfo;
+    use risingwave_pb::plan_common::{ColumnCatalog, RowFormatType};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_source_scan() {
+        let columns = vec![
+            ColumnDesc::new_atomic(DataType::Int64, "_row_id", 0),
+            ColumnDesc::new_atomic(DataType::Int64, "auction", 1),
+            ColumnDesc::new_atomic(DataType::Int64, "price", 2),
+        ];
+        let info = StreamSourceInfo {
+            properties: [
+                ("connector", "nexmark"),
+                ("nexmark.table.type", "Bid"),
+                ("nexmark.event.num", "100"),
+                ("nexmark.min.event.gap.in.ns", "0"),
+            ]
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect(),
+            row_format: RowFormatType::Json as i32,
+            row_id_index: 0,
+            columns: columns
+                .iter()
+                .map(|c| ColumnCatalog {
+                    column_desc: Some(c.to_protobuf()),
+                    is_hidden: c.name == "_row_id",
+                    generated_expr: None,
+                })
+                .collect(),
+            ..Default::default()
+        };
+        let source_desc = create_connector_source_desc(info, 0, Arc::new(SourceMetrics::unused()))
+            .await
+            .unwrap();
+        let executor = SourceScanExecutor::new(
+            source_desc,
+            vec![ColumnId::new(2), ColumnId::new(0)],
+            "SourceScanExecutor".to_string(),
+        )
+        .unwrap();
+        assert_eq!(executor.schema().names(), vec!["price", "_row_id"]);
+
+        let mut stream = Box::new(executor).execute();
+        let mut rows = vec![];
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk.unwrap();
+            for row in chunk.rows() {
+                rows.push(row.to_owned_row());
+            }
+        }
+        // The scan ends after the bids among the 100 events, with the row ids filled.
+        assert!(!rows.is_empty() && rows.len() < 100);
+        assert!(rows.iter().all(|row| row[0].is_some() && row[1].is_some()));
+        assert!(rows.iter().map(|row| row[1].clone()).all_unique());
+    }
+}
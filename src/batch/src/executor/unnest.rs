@@ -0,0 +1,160 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use futures_async_stream::try_stream;
+use risingwave_common::array::column::Column;
+use risingwave_common::array::DataChunk;
+use risingwave_common::catalog::{Field, Schema};
+use risingwave_common::error::{ErrorCode, Result, RwError};
+use risingwave_common::types::{DataType, Datum, ToOwnedDatum};
+use risingwave_common::util::chunk_coalesce::DEFAULT_CHUNK_BUFFER_SIZE;
+use risingwave_expr::expr::build_from_prost;
+use risingwave_pb::batch_plan::plan_node::NodeBody;
+
+use super::{BoxedExecutor, BoxedExecutorBuilder};
+use crate::executor::{BoxedDataChunkStream, Executor, ExecutorBuilder};
+use crate::task::BatchTaskContext;
+
+/// Expands a constant array to a set of rows, one for each element.
+pub struct UnnestExecutor {
+    values: Vec<Datum>,
+
+    schema: Schema,
+    identity: String,
+}
+
+impl UnnestExecutor {
+    pub fn new(values: Vec<Datum>, data_type: DataType, identity: String) -> Self {
+        Self {
+            values,
+            schema: Schema::new(vec![Field::unnamed(data_type)]),
+            identity,
+        }
+    }
+}
+
+impl Executor for UnnestExecutor {
+    fn schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    fn identity(&self) -> &str {
+        &self.identity
+    }
+
+    fn execute(self: Box<Self>) -> BoxedDataChunkStream {
+        self.do_execute()
+    }
+}
+
+impl UnnestExecutor {
+    #[try_stream(boxed, ok = DataChunk, error = RwError)]
+    async fn do_execute(self: Box<Self>) {
+        let data_type = &self.schema.fields[0].data_type;
+        for values in self.values.chunks(DEFAULT_CHUNK_BUFFER_SIZE) {
+            let mut builder = data_type.create_array_builder(values.len())?;
+            for value in values {
+                builder.append_datum(value)?;
+            }
+            let columns = vec![Column::new(Arc::new(builder.finish()?))];
+            yield DataChunk::new(columns, values.len());
+        }
+    }
+}
+
+pub struct UnnestExecutorBuilder {}
+
+#[async_trait::async_trait]
+impl BoxedExecutorBuilder for UnnestExecutorBuilder {
+    async fn new_boxed_executor<C: BatchTaskContext>(
+        source: &ExecutorBuilder<C>,
+        inputs: Vec<BoxedExecutor>,
+    ) -> Result<BoxedExecutor> {
+        ensure!(inputs.is_empty(), "UnnestExecutor should not have child!");
+        let node = try_match_expand!(
+            source.plan_node().get_node_body().unwrap(),
+            NodeBody::Unnest
+        )?;
+
+        let identity = source.plan_node().get_identity().clone();
+
+        let array_expr = build_from_prost(node.get_array()?)?;
+        let datatype = match array_expr.return_type() {
+            DataType::List { datatype } => *datatype,
+            _ => {
+                return Err(ErrorCode::InternalError(
+                    "the parameter of Unnest Function should be an array".to_string(),
+                )
+                .into())
+            }
+        };
+
+        let array = array_expr.eval(&DataChunk::new_dummy(1))?;
+        // Unnesting a null array results in no rows.
+        let values = match array.as_list().value_at(0) {
+            Some(list) => list
+                .values_ref()
+                .into_iter()
+                .map(|v| v.to_owned_datum())
+                .collect(),
+            None => vec![],
+        };
+
+        Ok(Box::new(UnnestExecutor::new(values, datatype, identity)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::StreamExt;
+    use risingwave_common::array::{Array, ArrayImpl};
+    use risingwave_common::try_match_expand;
+    use risingwave_common::types::ScalarImpl;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_unnest() {
+        let len = DEFAULT_CHUNK_BUFFER_SIZE + 3;
+        let values = (0..len)
+            .map(|i| match i % 3 {
+                0 => None,
+                _ => Some(ScalarImpl::Int32(i as i32)),
+            })
+            .collect();
+        let executor = Box::new(UnnestExecutor::new(
+            values,
+            DataType::Int32,
+            "UnnestExecutor".to_string(),
+        ));
+
+        let mut actual = vec![];
+        let mut stream = executor.execute();
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk.unwrap();
+            assert!(chunk.cardinality() <= DEFAULT_CHUNK_BUFFER_SIZE);
+            let arr = try_match_expand!(chunk.column_at(0).array_ref(), ArrayImpl::Int32).unwrap();
+            actual.extend(arr.iter());
+        }
+        let expected = (0..len)
+            .map(|i| match i % 3 {
+                0 => None,
+                _ => Some(i as i32),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(actual, expected);
+    }
+}
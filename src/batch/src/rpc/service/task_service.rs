@@ -18,9 +18,9 @@ use std::sync::Arc;
 use risingwave_pb::batch_plan::TaskOutputId;
 use risingwave_pb::task_service::task_service_server::TaskService;
 use risingwave_pb::task_service::{
-    AbortTaskRequest, AbortTaskResponse, CreateTaskRequest, CreateTaskResponse, ExecuteRequest,
-    GetDataResponse, GetTaskInfoRequest, GetTaskInfoResponse, RemoveTaskRequest,
-    RemoveTaskResponse,
+    AbortQueryRequest, AbortQueryResponse, AbortTaskRequest, AbortTaskResponse, CreateTaskRequest,
+    CreateTaskResponse, ExecuteRequest, GetDataResponse, GetTaskInfoRequest, GetTaskInfoResponse,
+    GetTaskProfileRequest, GetTaskProfileResponse, RemoveTaskRequest, RemoveTaskResponse,
 };
 use tokio_stream::wrappers::ReceiverStream;
 use tonic::{Request, Response, Status};
@@ -60,6 +60,7 @@ impl TaskService for BatchServiceImpl {
                 req.get_plan().expect("no plan found").clone(),
                 req.epoch,
                 ComputeNodeContext::new(self.env.clone()),
+                req.profile,
             )
             .await;
         match res {
@@ -97,6 +98,20 @@ impl TaskService for BatchServiceImpl {
         }
     }
 
+    #[cfg_attr(coverage, no_coverage)]
+    async fn abort_query(
+        &self,
+        req: Request<AbortQueryRequest>,
+    ) -> Result<Response<AbortQueryResponse>, Status> {
+        let req = req.into_inner();
+        let aborted_tasks = self.mgr.abort_query(&req.query_id);
+        debug!("aborted {} tasks of query {}", aborted_tasks, req.query_id);
+        Ok(Response::new(AbortQueryResponse {
+            status: None,
+            aborted_tasks: aborted_tasks as u32,
+        }))
+    }
+
     #[cfg_attr(coverage, no_coverage)]
     async fn remove_task(
         &self,
@@ -115,6 +130,21 @@ impl TaskService for BatchServiceImpl {
         }
     }
 
+    #[cfg_attr(coverage, no_coverage)]
+    async fn get_task_profile(
+        &self,
+        req: Request<GetTaskProfileRequest>,
+    ) -> Result<Response<GetTaskProfileResponse>, Status> {
+        let req = req.into_inner();
+        let profiles = self
+            .mgr
+            .get_task_profile(req.get_task_id().expect("no task id found"))?;
+        Ok(Response::new(GetTaskProfileResponse {
+            status: None,
+            profiles,
+        }))
+    }
+
     #[cfg_attr(coverage, no_coverage)]
     async fn execute(
         &self,
@@ -124,8 +154,15 @@ impl TaskService for BatchServiceImpl {
         let task_id = req.get_task_id().expect("no task id found");
         let plan = req.get_plan().expect("no plan found").clone();
         let epoch = req.epoch;
-        let context = ComputeNodeContext::new(self.env.clone());
-        let task = BatchTaskExecution::new(task_id, plan, context, epoch)?;
+        let admission = self
+            .env
+            .task_manager()
+            .admit_task(&task_id.query_id)
+            .await?;
+        let context =
+            ComputeNodeContext::new(self.env.clone()).with_mem_tracker(admission.mem_tracker());
+        let task =
+            BatchTaskExecution::new(task_id, plan, context, epoch)?.with_admission(admission);
         let task = Arc::new(task);
         if let Err(e) = task.clone().async_execute().await {
             error!(
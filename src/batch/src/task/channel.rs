@@ -24,6 +24,9 @@ use crate::task::fifo_channel::{new_fifo_channel, FifoReceiver, FifoSender};
 use crate::task::hash_shuffle_channel::{
     new_hash_shuffle_channel, HashShuffleReceiver, HashShuffleSender,
 };
+use crate::task::range_shuffle_channel::{
+    new_range_shuffle_channel, RangeShuffleReceiver, RangeShuffleSender,
+};
 
 pub trait ChanSender: Send {
     type SendFuture<'a>: Future<Output = Result<()>> + Send
@@ -37,6 +40,7 @@ pub trait ChanSender: Send {
 
 pub enum ChanSenderImpl {
     HashShuffle(HashShuffleSender),
+    RangeShuffle(RangeShuffleSender),
     Fifo(FifoSender),
     Broadcast(BroadcastSender),
 }
@@ -45,6 +49,7 @@ impl ChanSenderImpl {
     pub async fn send(&mut self, chunk: Option<DataChunk>) -> Result<()> {
         match self {
             Self::HashShuffle(sender) => sender.send(chunk).await,
+            Self::RangeShuffle(sender) => sender.send(chunk).await,
             Self::Fifo(sender) => sender.send(chunk).await,
             Self::Broadcast(sender) => sender.send(chunk).await,
         }
@@ -62,6 +67,7 @@ pub trait ChanReceiver: Send {
 
 pub enum ChanReceiverImpl {
     HashShuffle(HashShuffleReceiver),
+    RangeShuffle(RangeShuffleReceiver),
     Fifo(FifoReceiver),
     Broadcast(BroadcastReceiver),
 }
@@ -70,6 +76,7 @@ impl ChanReceiverImpl {
     pub async fn recv(&mut self) -> Result<Option<DataChunk>> {
         match self {
             Self::HashShuffle(receiver) => receiver.recv().await,
+            Self::RangeShuffle(receiver) => receiver.recv().await,
             Self::Broadcast(receiver) => receiver.recv().await,
             Self::Fifo(receiver) => receiver.recv().await,
         }
@@ -87,5 +94,6 @@ pub fn create_output_channel(
         ShuffleDistributionMode::Single => Ok(new_fifo_channel()),
         ShuffleDistributionMode::Hash => Ok(new_hash_shuffle_channel(shuffle)),
         ShuffleDistributionMode::Broadcast => Ok(new_broadcast_channel(shuffle)),
+        ShuffleDistributionMode::Range => new_range_shuffle_channel(shuffle),
     }
 }
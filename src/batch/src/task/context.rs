@@ -14,6 +14,7 @@
 
 use std::sync::Arc;
 
+use risingwave_common::config::BatchConfig;
 use risingwave_common::error::ErrorCode::InternalError;
 use risingwave_common::error::Result;
 use risingwave_common::util::addr::{is_local_address, HostAddr};
@@ -21,7 +22,7 @@ use risingwave_source::SourceManagerRef;
 use risingwave_storage::StateStoreImpl;
 
 use crate::executor::BatchMetrics;
-use crate::task::{BatchEnvironment, TaskOutput, TaskOutputId};
+use crate::task::{BatchEnvironment, BatchMemoryTrackerRef, TaskOutput, TaskOutputId};
 
 /// Context for batch task execution.
 ///
@@ -52,12 +53,20 @@ pub trait BatchTaskContext: Clone + Send + Sync + 'static {
     }
 
     fn stats(&self) -> Arc<BatchMetrics>;
+
+    /// Batch related configurations.
+    fn batch_config(&self) -> Arc<BatchConfig>;
+
+    /// Memory tracker accounting the executors of the task.
+    fn mem_tracker(&self) -> BatchMemoryTrackerRef;
 }
 
 /// Batch task context on compute node.
 #[derive(Clone)]
 pub struct ComputeNodeContext {
     env: BatchEnvironment,
+    /// Memory tracker of the query the task belongs to. The node-level tracker is used if not set.
+    mem_tracker: Option<BatchMemoryTrackerRef>,
 }
 
 impl BatchTaskContext for ComputeNodeContext {
@@ -82,17 +91,35 @@ impl BatchTaskContext for ComputeNodeContext {
     fn stats(&self) -> Arc<BatchMetrics> {
         self.env.stats()
     }
+
+    fn batch_config(&self) -> Arc<BatchConfig> {
+        self.env.config_ref()
+    }
+
+    fn mem_tracker(&self) -> BatchMemoryTrackerRef {
+        self.mem_tracker
+            .clone()
+            .unwrap_or_else(|| self.env.task_manager().mem_tracker())
+    }
 }
 
 impl ComputeNodeContext {
     #[cfg(test)]
     pub fn new_for_test() -> Self {
+        Self::new(BatchEnvironment::for_test())
+    }
+
+    pub fn new(env: BatchEnvironment) -> Self {
         Self {
-            env: BatchEnvironment::for_test(),
+            env,
+            mem_tracker: None,
         }
     }
 
-    pub fn new(env: BatchEnvironment) -> Self {
-        Self { env }
+    pub fn with_mem_tracker(self, mem_tracker: BatchMemoryTrackerRef) -> Self {
+        Self {
+            mem_tracker: Some(mem_tracker),
+            ..self
+        }
     }
 }
@@ -21,12 +21,16 @@ use tokio::sync::mpsc;
 
 use crate::task::channel::{ChanReceiver, ChanReceiverImpl, ChanSender, ChanSenderImpl};
 
+/// Maximum number of chunks buffered in the channel. The task is blocked when the consumer can't
+/// keep up, so that a large result set is not buffered in the task output as a whole.
+const FIFO_CHANNEL_CAPACITY: usize = 16;
+
 pub struct FifoSender {
-    sender: mpsc::UnboundedSender<Option<DataChunk>>,
+    sender: mpsc::Sender<Option<DataChunk>>,
 }
 
 pub struct FifoReceiver {
-    receiver: mpsc::UnboundedReceiver<Option<DataChunk>>,
+    receiver: mpsc::Receiver<Option<DataChunk>>,
 }
 
 impl ChanSender for FifoSender {
@@ -36,6 +40,7 @@ impl ChanSender for FifoSender {
         async move {
             self.sender
                 .send(chunk)
+                .await
                 .to_rw_result_with(|| "FifoSender::send".into())
         }
     }
@@ -56,7 +61,7 @@ impl ChanReceiver for FifoReceiver {
 }
 
 pub fn new_fifo_channel() -> (ChanSenderImpl, Vec<ChanReceiverImpl>) {
-    let (s, r) = mpsc::unbounded_channel();
+    let (s, r) = mpsc::channel(FIFO_CHANNEL_CAPACITY);
     (
         ChanSenderImpl::Fifo(FifoSender { sender: s }),
         vec![ChanReceiverImpl::Fifo(FifoReceiver { receiver: r })],
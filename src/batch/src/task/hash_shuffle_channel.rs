@@ -57,17 +57,17 @@ fn generate_hash_values(chunk: &DataChunk, hash_info: &HashInfo) -> Result<Vec<u
     Ok(hash_values)
 }
 
-/// The returned chunks must have cardinality > 0.
-fn generate_new_data_chunks(
+/// Splits `chunk` into `output_count` chunks by setting the visibility, where row `i` is sent to
+/// sink `partitions[i]`. The returned chunks may have cardinality 0.
+pub(super) fn generate_new_data_chunks(
     chunk: &DataChunk,
-    hash_info: &exchange_info::HashInfo,
-    hash_values: &[usize],
+    output_count: usize,
+    partitions: &[usize],
 ) -> Result<Vec<DataChunk>> {
-    let output_count = hash_info.output_count as usize;
     let mut vis_maps = vec![vec![]; output_count];
-    hash_values.iter().for_each(|hash| {
+    partitions.iter().for_each(|partition| {
         for (sink_id, vis_map) in vis_maps.iter_mut().enumerate() {
-            if *hash == sink_id {
+            if *partition == sink_id {
                 vis_map.push(true);
             } else {
                 vis_map.push(false);
@@ -109,7 +109,8 @@ impl ChanSender for HashShuffleSender {
 impl HashShuffleSender {
     async fn send_chunk(&mut self, chunk: DataChunk) -> Result<()> {
         let hash_values = generate_hash_values(&chunk, &self.hash_info)?;
-        let new_data_chunks = generate_new_data_chunks(&chunk, &self.hash_info, &hash_values)?;
+        let new_data_chunks =
+            generate_new_data_chunks(&chunk, self.hash_info.output_count as usize, &hash_values)?;
 
         for (sink_id, new_data_chunk) in new_data_chunks.into_iter().enumerate() {
             trace!(
@@ -0,0 +1,197 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use risingwave_common::error::ErrorCode::InternalError;
+use risingwave_common::error::Result;
+
+pub type BatchMemoryTrackerRef = Arc<BatchMemoryTracker>;
+
+/// Tracks the memory held by batch executors.
+///
+/// Trackers form a hierarchy: the node-level tracker owned by `BatchManager` is the parent of
+/// one tracker per query, and bytes reserved in a child are also accounted in its ancestors.
+///
+/// Executors don't update the tracker directly. Instead they hold a [`MemoryReservation`], which
+/// returns its bytes to the tracker when dropped.
+#[derive(Debug, Default)]
+pub struct BatchMemoryTracker {
+    bytes_used: AtomicUsize,
+    /// Limit of `bytes_used`. Zero means unlimited.
+    limit: usize,
+    parent: Option<BatchMemoryTrackerRef>,
+}
+
+impl BatchMemoryTracker {
+    pub fn with_limit(limit: usize) -> Self {
+        Self {
+            limit,
+            ..Default::default()
+        }
+    }
+
+    pub fn new_child(self: &Arc<Self>, limit: usize) -> BatchMemoryTrackerRef {
+        Arc::new(Self {
+            bytes_used: AtomicUsize::new(0),
+            limit,
+            parent: Some(self.clone()),
+        })
+    }
+
+    pub fn new_reservation(self: &Arc<Self>) -> MemoryReservation {
+        MemoryReservation {
+            tracker: self.clone(),
+            bytes: 0,
+        }
+    }
+
+    /// Total bytes reserved by all live reservations.
+    pub fn bytes_used(&self) -> usize {
+        self.bytes_used.load(Ordering::Relaxed)
+    }
+
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    /// Whether this tracker or any of its ancestors has reached its limit.
+    pub fn is_exhausted(&self) -> bool {
+        (self.limit > 0 && self.bytes_used() >= self.limit)
+            || self.parent.as_ref().map_or(false, |p| p.is_exhausted())
+    }
+
+    fn add(&self, bytes: usize) {
+        self.bytes_used.fetch_add(bytes, Ordering::Relaxed);
+        if let Some(parent) = &self.parent {
+            parent.add(bytes);
+        }
+    }
+
+    fn sub(&self, bytes: usize) {
+        self.bytes_used.fetch_sub(bytes, Ordering::Relaxed);
+        if let Some(parent) = &self.parent {
+            parent.sub(bytes);
+        }
+    }
+
+    /// Returns the first tracker in the chain whose limit is exceeded.
+    fn exceeded(&self) -> Option<&Self> {
+        if self.limit > 0 && self.bytes_used() > self.limit {
+            Some(self)
+        } else {
+            self.parent.as_ref().and_then(|p| p.exceeded())
+        }
+    }
+}
+
+/// A share of memory accounted in a [`BatchMemoryTracker`].
+#[derive(Debug)]
+pub struct MemoryReservation {
+    tracker: BatchMemoryTrackerRef,
+    bytes: usize,
+}
+
+impl MemoryReservation {
+    /// Grows the reservation regardless of the limits.
+    pub fn grow(&mut self, bytes: usize) {
+        self.tracker.add(bytes);
+        self.bytes += bytes;
+    }
+
+    /// Grows the reservation, or returns an error without growing if any limit is exceeded.
+    pub fn try_grow(&mut self, bytes: usize) -> Result<()> {
+        self.tracker.add(bytes);
+        if let Some(exceeded) = self.tracker.exceeded() {
+            let limit = exceeded.limit;
+            self.tracker.sub(bytes);
+            return Err(InternalError(format!(
+                "batch memory limit exceeded: failed to reserve {} bytes with limit {} bytes",
+                bytes, limit
+            ))
+            .into());
+        }
+        self.bytes += bytes;
+        Ok(())
+    }
+
+    /// Returns all reserved bytes to the tracker.
+    pub fn release(&mut self) {
+        self.tracker.sub(self.bytes);
+        self.bytes = 0;
+    }
+
+    pub fn bytes(&self) -> usize {
+        self.bytes
+    }
+}
+
+impl Drop for MemoryReservation {
+    fn drop(&mut self) {
+        self.release();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::BatchMemoryTracker;
+
+    #[test]
+    fn test_reservation_released_on_drop() {
+        let tracker = Arc::new(BatchMemoryTracker::default());
+        let mut r1 = tracker.new_reservation();
+        let mut r2 = tracker.new_reservation();
+        r1.grow(100);
+        r2.grow(20);
+        assert_eq!(tracker.bytes_used(), 120);
+
+        r1.release();
+        assert_eq!(r1.bytes(), 0);
+        assert_eq!(tracker.bytes_used(), 20);
+
+        drop(r2);
+        assert_eq!(tracker.bytes_used(), 0);
+    }
+
+    #[test]
+    fn test_limits_of_hierarchy() {
+        let node = Arc::new(BatchMemoryTracker::with_limit(100));
+        let query1 = node.new_child(60);
+        let query2 = node.new_child(0);
+
+        let mut r1 = query1.new_reservation();
+        r1.try_grow(50).unwrap();
+        // Exceeds the query limit.
+        assert!(r1.try_grow(20).is_err());
+        assert_eq!(r1.bytes(), 50);
+
+        let mut r2 = query2.new_reservation();
+        r2.try_grow(40).unwrap();
+        assert_eq!(node.bytes_used(), 90);
+        // Exceeds the node limit.
+        assert!(r2.try_grow(20).is_err());
+        assert!(!query2.is_exhausted());
+
+        r2.grow(10);
+        assert!(query2.is_exhausted());
+        assert!(query1.is_exhausted());
+
+        drop(r1);
+        assert_eq!(node.bytes_used(), 50);
+        assert!(!query2.is_exhausted());
+    }
+}
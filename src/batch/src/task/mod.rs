@@ -14,6 +14,8 @@
 
 pub use context::*;
 pub use env::*;
+pub use mem_tracker::*;
+pub use quota::*;
 pub use task_::*;
 pub use task_manager::*;
 
@@ -23,5 +25,8 @@ mod context;
 mod env;
 mod fifo_channel;
 mod hash_shuffle_channel;
+mod mem_tracker;
+mod quota;
+mod range_shuffle_channel;
 mod task_;
 mod task_manager;
@@ -0,0 +1,325 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use parking_lot::Mutex;
+use risingwave_common::config::BatchConfig;
+use risingwave_common::error::ErrorCode::InternalError;
+use risingwave_common::error::{Result, RwError};
+use tokio::sync::Notify;
+use tokio::time::Instant;
+
+use crate::executor::BatchMetrics;
+use crate::task::{BatchMemoryTracker, BatchMemoryTrackerRef};
+
+/// Interval to re-check the resources while queued, in case a release notification is missed.
+const ADMISSION_RECHECK_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Resource limits of batch tasks on a compute node. Zero means unlimited.
+#[derive(Clone, Debug, Default)]
+pub struct BatchQuota {
+    pub max_node_tasks: usize,
+    pub max_query_tasks: usize,
+    pub max_node_memory_bytes: usize,
+    pub max_query_memory_bytes: usize,
+    /// How long a task may wait for resources before being rejected.
+    pub admission_timeout: Duration,
+}
+
+impl BatchQuota {
+    pub fn from_config(config: &BatchConfig) -> Self {
+        Self {
+            max_node_tasks: config.max_node_tasks,
+            max_query_tasks: config.max_query_tasks,
+            max_node_memory_bytes: config.max_node_memory_mb << 20,
+            max_query_memory_bytes: config.max_query_memory_mb << 20,
+            admission_timeout: Duration::from_millis(config.task_admission_timeout_ms),
+        }
+    }
+}
+
+struct QueryResource {
+    running_tasks: usize,
+    mem_tracker: BatchMemoryTrackerRef,
+}
+
+#[derive(Default)]
+struct AdmissionState {
+    running_tasks: usize,
+    queries: HashMap<String, QueryResource>,
+}
+
+/// Decides whether a new batch task can run on the node according to the [`BatchQuota`].
+///
+/// Tasks exceeding the limits are queued until resources are released by other tasks, and
+/// rejected if still not admitted after the admission timeout.
+pub struct AdmissionController {
+    quota: BatchQuota,
+    state: Mutex<AdmissionState>,
+    released: Notify,
+    node_mem_tracker: BatchMemoryTrackerRef,
+    /// Set by the memory manager of the compute node when the node is short of memory.
+    memory_pressure: AtomicBool,
+    metrics: Arc<BatchMetrics>,
+}
+
+pub type AdmissionControllerRef = Arc<AdmissionController>;
+
+impl AdmissionController {
+    pub fn new(quota: BatchQuota, metrics: Arc<BatchMetrics>) -> Self {
+        let node_mem_tracker =
+            Arc::new(BatchMemoryTracker::with_limit(quota.max_node_memory_bytes));
+        Self {
+            quota,
+            state: Mutex::new(AdmissionState::default()),
+            released: Notify::new(),
+            node_mem_tracker,
+            memory_pressure: AtomicBool::new(false),
+            metrics,
+        }
+    }
+
+    pub fn node_mem_tracker(&self) -> BatchMemoryTrackerRef {
+        self.node_mem_tracker.clone()
+    }
+
+    /// Holds back new tasks while the whole node is short of memory, regardless of the memory
+    /// used by batch tasks.
+    pub fn set_memory_pressure(&self, memory_pressure: bool) {
+        let was = self
+            .memory_pressure
+            .swap(memory_pressure, Ordering::Relaxed);
+        if was && !memory_pressure {
+            self.released.notify_waiters();
+        }
+    }
+
+    /// Waits until a task of `query_id` can be admitted, or returns an error if the admission
+    /// times out.
+    pub async fn admit(self: &Arc<Self>, query_id: &str) -> Result<TaskAdmission> {
+        let deadline = Instant::now() + self.quota.admission_timeout;
+        let mut queued = false;
+        let res = loop {
+            let err = match self.try_admit(query_id) {
+                Ok(admission) => break Ok(admission),
+                Err(err) => err,
+            };
+            let now = Instant::now();
+            if now >= deadline {
+                self.metrics.task_rejected_count.inc();
+                break Err(err);
+            }
+            if !queued {
+                queued = true;
+                self.metrics.task_queued_count.inc();
+                self.metrics.task_queueing.inc();
+                debug!("task of query {} queued: {}", query_id, err);
+            }
+            let wait = (deadline - now).min(ADMISSION_RECHECK_INTERVAL);
+            let _ = tokio::time::timeout(wait, self.released.notified()).await;
+        };
+        if queued {
+            self.metrics.task_queueing.dec();
+        }
+        res
+    }
+
+    fn try_admit(self: &Arc<Self>, query_id: &str) -> Result<TaskAdmission> {
+        let mut state = self.state.lock();
+        let quota = &self.quota;
+        if quota.max_node_tasks > 0 && state.running_tasks >= quota.max_node_tasks {
+            return Err(exhausted(format!(
+                "{} tasks running on the node",
+                state.running_tasks
+            )));
+        }
+        if self.memory_pressure.load(Ordering::Relaxed) {
+            return Err(exhausted(
+                "memory of the node is nearly used up".to_string(),
+            ));
+        }
+        if self.node_mem_tracker.is_exhausted() {
+            return Err(exhausted(format!(
+                "{} bytes of memory used on the node",
+                self.node_mem_tracker.bytes_used()
+            )));
+        }
+        if let Some(query) = state.queries.get(query_id) {
+            if quota.max_query_tasks > 0 && query.running_tasks >= quota.max_query_tasks {
+                return Err(exhausted(format!(
+                    "{} tasks of query {} running on the node",
+                    query.running_tasks, query_id
+                )));
+            }
+            if query.mem_tracker.is_exhausted() {
+                return Err(exhausted(format!(
+                    "{} bytes of memory used by query {} on the node",
+                    query.mem_tracker.bytes_used(),
+                    query_id
+                )));
+            }
+        }
+
+        state.running_tasks += 1;
+        let node_mem_tracker = &self.node_mem_tracker;
+        let query = state
+            .queries
+            .entry(query_id.to_string())
+            .or_insert_with(|| QueryResource {
+                running_tasks: 0,
+                mem_tracker: node_mem_tracker.new_child(quota.max_query_memory_bytes),
+            });
+        query.running_tasks += 1;
+        self.metrics.task_running.inc();
+
+        Ok(TaskAdmission {
+            controller: self.clone(),
+            query_id: query_id.to_string(),
+            mem_tracker: query.mem_tracker.clone(),
+        })
+    }
+
+    fn release(&self, query_id: &str) {
+        {
+            let mut state = self.state.lock();
+            state.running_tasks -= 1;
+            if let Some(query) = state.queries.get_mut(query_id) {
+                query.running_tasks -= 1;
+                if query.running_tasks == 0 {
+                    state.queries.remove(query_id);
+                }
+            }
+        }
+        self.metrics.task_running.dec();
+        self.released.notify_waiters();
+    }
+}
+
+fn exhausted(reason: String) -> RwError {
+    InternalError(format!("batch resources exhausted: {}", reason)).into()
+}
+
+/// Resources granted to an admitted task, which are returned when dropped.
+pub struct TaskAdmission {
+    controller: AdmissionControllerRef,
+    query_id: String,
+    mem_tracker: BatchMemoryTrackerRef,
+}
+
+impl TaskAdmission {
+    /// Memory tracker of the query this task belongs to.
+    pub fn mem_tracker(&self) -> BatchMemoryTrackerRef {
+        self.mem_tracker.clone()
+    }
+}
+
+impl Drop for TaskAdmission {
+    fn drop(&mut self) {
+        self.controller.release(&self.query_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use super::{AdmissionController, BatchQuota};
+    use crate::executor::BatchMetrics;
+
+    fn new_controller(quota: BatchQuota) -> Arc<AdmissionController> {
+        Arc::new(AdmissionController::new(
+            quota,
+            Arc::new(BatchMetrics::unused()),
+        ))
+    }
+
+    #[tokio::test]
+    async fn test_reject_on_task_limit() {
+        let controller = new_controller(BatchQuota {
+            max_node_tasks: 2,
+            max_query_tasks: 1,
+            ..Default::default()
+        });
+        let _t1 = controller.admit("q1").await.unwrap();
+        // Exceeds the query limit.
+        assert!(controller.admit("q1").await.is_err());
+        let t2 = controller.admit("q2").await.unwrap();
+        // Exceeds the node limit.
+        assert!(controller.admit("q3").await.is_err());
+        assert_eq!(controller.metrics.task_rejected_count.get(), 2);
+
+        drop(t2);
+        controller.admit("q3").await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_queue_until_released() {
+        let controller = new_controller(BatchQuota {
+            max_node_tasks: 1,
+            admission_timeout: Duration::from_secs(10),
+            ..Default::default()
+        });
+        let t1 = controller.admit("q1").await.unwrap();
+        let controller2 = controller.clone();
+        let queued = tokio::spawn(async move { controller2.admit("q2").await.map(|_| ()) });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(controller.metrics.task_queueing.get(), 1);
+
+        drop(t1);
+        queued.await.unwrap().unwrap();
+        assert_eq!(controller.metrics.task_queued_count.get(), 1);
+        assert_eq!(controller.metrics.task_queueing.get(), 0);
+        assert_eq!(controller.metrics.task_rejected_count.get(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_reject_on_memory_limit() {
+        let controller = new_controller(BatchQuota {
+            max_query_memory_bytes: 100,
+            ..Default::default()
+        });
+        let t1 = controller.admit("q1").await.unwrap();
+        let mut reservation = t1.mem_tracker().new_reservation();
+        reservation.try_grow(100).unwrap();
+        assert!(reservation.try_grow(1).is_err());
+        // No more tasks of the query until memory is released.
+        assert!(controller.admit("q1").await.is_err());
+        controller.admit("q2").await.unwrap();
+
+        reservation.release();
+        controller.admit("q1").await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_queue_under_memory_pressure() {
+        let controller = new_controller(BatchQuota {
+            admission_timeout: Duration::from_secs(10),
+            ..Default::default()
+        });
+        controller.set_memory_pressure(true);
+        let controller2 = controller.clone();
+        let queued = tokio::spawn(async move { controller2.admit("q1").await.map(|_| ()) });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(controller.metrics.task_queueing.get(), 1);
+
+        controller.set_memory_pressure(false);
+        queued.await.unwrap().unwrap();
+        assert_eq!(controller.metrics.task_rejected_count.get(), 0);
+    }
+}
@@ -0,0 +1,272 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::future::Future;
+use std::option::Option;
+
+use itertools::Itertools;
+use risingwave_common::array::DataChunk;
+use risingwave_common::error::ErrorCode::InternalError;
+use risingwave_common::error::{Result, ToRwResult};
+use risingwave_common::util::ordered::OrderedArraysSerializer;
+use risingwave_common::util::sort_util::OrderPair;
+use risingwave_pb::batch_plan::exchange_info::RangeInfo;
+use risingwave_pb::batch_plan::*;
+use tokio::sync::mpsc;
+
+use crate::task::channel::{ChanReceiver, ChanReceiverImpl, ChanSender, ChanSenderImpl};
+use crate::task::hash_shuffle_channel::generate_new_data_chunks;
+
+/// Sends each row to the partition whose key range contains the row's key. Partition `i` holds the
+/// keys in `[bounds[i - 1], bounds[i])`, compared in the memcomparable format.
+pub struct RangeShuffleSender {
+    senders: Vec<mpsc::UnboundedSender<Option<DataChunk>>>,
+    serializer: OrderedArraysSerializer,
+    bounds: Vec<Vec<u8>>,
+}
+
+pub struct RangeShuffleReceiver {
+    receiver: mpsc::UnboundedReceiver<Option<DataChunk>>,
+}
+
+impl RangeShuffleSender {
+    fn generate_partitions(&self, chunk: &DataChunk) -> Vec<usize> {
+        let arrays = chunk
+            .columns()
+            .iter()
+            .map(|column| column.array_ref())
+            .collect_vec();
+        let mut keys = Vec::with_capacity(chunk.capacity());
+        self.serializer.serialize(&arrays, &mut keys);
+        keys.iter()
+            .map(|key| self.bounds.partition_point(|bound| bound <= key))
+            .collect()
+    }
+
+    async fn send_chunk(&mut self, chunk: DataChunk) -> Result<()> {
+        let partitions = self.generate_partitions(&chunk);
+        let new_data_chunks = generate_new_data_chunks(&chunk, self.senders.len(), &partitions)?;
+
+        for (sink_id, new_data_chunk) in new_data_chunks.into_iter().enumerate() {
+            trace!(
+                "send to sink:{}, cardinality:{}",
+                sink_id,
+                new_data_chunk.cardinality()
+            );
+            if new_data_chunk.cardinality() > 0 {
+                self.senders[sink_id]
+                    .send(Some(new_data_chunk))
+                    .to_rw_result_with(|| "RangeShuffleSender::send".into())?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn send_done(&mut self) -> Result<()> {
+        self.senders.iter_mut().try_for_each(|s| {
+            s.send(None)
+                .to_rw_result_with(|| "RangeShuffleSender::send".into())
+        })
+    }
+}
+
+impl ChanSender for RangeShuffleSender {
+    type SendFuture<'a> = impl Future<Output = Result<()>>;
+
+    fn send(&mut self, chunk: Option<DataChunk>) -> Self::SendFuture<'_> {
+        async move {
+            match chunk {
+                Some(c) => self.send_chunk(c).await,
+                None => self.send_done().await,
+            }
+        }
+    }
+}
+
+impl ChanReceiver for RangeShuffleReceiver {
+    type RecvFuture<'a> = impl Future<Output = Result<Option<DataChunk>>>;
+
+    fn recv(&mut self) -> Self::RecvFuture<'_> {
+        async move {
+            match self.receiver.recv().await {
+                Some(data_chunk) => Ok(data_chunk),
+                // Early close should be treated as error.
+                None => Err(InternalError("broken range_shuffle_channel".to_string()).into()),
+            }
+        }
+    }
+}
+
+pub fn new_range_shuffle_channel(
+    shuffle: &ExchangeInfo,
+) -> Result<(ChanSenderImpl, Vec<ChanReceiverImpl>)> {
+    let range_info = match shuffle.distribution {
+        Some(exchange_info::Distribution::RangeInfo(ref v)) => v.clone(),
+        _ => RangeInfo::default(),
+    };
+
+    let output_count = range_info.output_count as usize;
+    if output_count == 0 || range_info.bounds.len() + 1 != output_count {
+        return Err(InternalError(format!(
+            "range shuffle expects {} bounds for {} outputs, got {}",
+            output_count.saturating_sub(1),
+            output_count,
+            range_info.bounds.len()
+        ))
+        .into());
+    }
+    if !range_info
+        .bounds
+        .iter()
+        .tuple_windows()
+        .all(|(prev, next)| prev <= next)
+    {
+        return Err(InternalError("range shuffle bounds are not sorted".to_string()).into());
+    }
+
+    let mut senders = Vec::with_capacity(output_count);
+    let mut receivers = Vec::with_capacity(output_count);
+    for _ in 0..output_count {
+        let (s, r) = mpsc::unbounded_channel();
+        senders.push(s);
+        receivers.push(r);
+    }
+    let serializer = OrderedArraysSerializer::new(
+        range_info
+            .keys
+            .iter()
+            .map(OrderPair::from_prost)
+            .collect_vec(),
+    );
+    let channel_sender = ChanSenderImpl::RangeShuffle(RangeShuffleSender {
+        senders,
+        serializer,
+        bounds: range_info.bounds,
+    });
+    let channel_receivers = receivers
+        .into_iter()
+        .map(|receiver| ChanReceiverImpl::RangeShuffle(RangeShuffleReceiver { receiver }))
+        .collect::<Vec<_>>();
+    Ok((channel_sender, channel_receivers))
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_common::array::{Array, I64Array, Row};
+    use risingwave_common::test_prelude::DataChunkTestExt;
+    use risingwave_common::types::ScalarImpl;
+    use risingwave_common::util::ordered::OrderedRowSerializer;
+    use risingwave_common::util::sort_util::OrderType;
+    use risingwave_pb::expr::InputRefExpr;
+    use risingwave_pb::plan_common::ColumnOrder;
+
+    use super::*;
+
+    fn encode_bound(value: i64, order_type: OrderType) -> Vec<u8> {
+        let mut bound = vec![];
+        OrderedRowSerializer::new(vec![order_type])
+            .serialize(&Row(vec![Some(ScalarImpl::Int64(value))]), &mut bound);
+        bound
+    }
+
+    fn range_exchange_info(order_type: OrderType, bounds: &[i64]) -> ExchangeInfo {
+        ExchangeInfo {
+            mode: exchange_info::DistributionMode::Range as i32,
+            distribution: Some(exchange_info::Distribution::RangeInfo(RangeInfo {
+                output_count: bounds.len() as u32 + 1,
+                keys: vec![ColumnOrder {
+                    order_type: order_type.to_prost() as i32,
+                    input_ref: Some(InputRefExpr { column_idx: 1 }),
+                    return_type: None,
+                }],
+                bounds: bounds
+                    .iter()
+                    .map(|bound| encode_bound(*bound, order_type))
+                    .collect(),
+            })),
+        }
+    }
+
+    async fn collect_partitions(info: &ExchangeInfo, chunk: DataChunk) -> Vec<Vec<Option<i64>>> {
+        let (mut sender, receivers) = new_range_shuffle_channel(info).unwrap();
+        sender.send(Some(chunk)).await.unwrap();
+        sender.send(None).await.unwrap();
+
+        let mut partitions = vec![];
+        for mut receiver in receivers {
+            let mut values = vec![];
+            while let Some(chunk) = receiver.recv().await.unwrap() {
+                let chunk = chunk.compact().unwrap();
+                let array: &I64Array = chunk.column_at(0).array_ref().into();
+                values.extend(array.iter());
+            }
+            partitions.push(values);
+        }
+        partitions
+    }
+
+    #[tokio::test]
+    async fn test_range_shuffle() {
+        let chunk = DataChunk::from_pretty(
+            "I I
+             1 10
+             2 20
+             3 30
+             4 .
+             5 5
+             6 25 D
+             7 40",
+        );
+        let partitions =
+            collect_partitions(&range_exchange_info(OrderType::Ascending, &[10, 30]), chunk).await;
+        assert_eq!(
+            partitions,
+            vec![
+                vec![Some(4), Some(5)],
+                vec![Some(1), Some(2)],
+                vec![Some(3), Some(7)]
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_range_shuffle_descending() {
+        let chunk = DataChunk::from_pretty(
+            "I I
+             1 10
+             2 20
+             3 30
+             4 5",
+        );
+        let partitions =
+            collect_partitions(&range_exchange_info(OrderType::Descending, &[20]), chunk).await;
+        assert_eq!(
+            partitions,
+            vec![vec![Some(3)], vec![Some(1), Some(2), Some(4)]]
+        );
+    }
+
+    #[test]
+    fn test_range_shuffle_invalid_bounds() {
+        let mut info = range_exchange_info(OrderType::Ascending, &[10, 30]);
+        if let Some(exchange_info::Distribution::RangeInfo(ref mut range_info)) = info.distribution
+        {
+            range_info.output_count = 2;
+        }
+        assert!(new_range_shuffle_channel(&info).is_err());
+
+        let info = range_exchange_info(OrderType::Ascending, &[30, 10]);
+        assert!(new_range_shuffle_channel(&info).is_err());
+    }
+}
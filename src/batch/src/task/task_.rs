@@ -25,12 +25,13 @@ use risingwave_pb::batch_plan::{
 use risingwave_pb::task_service::task_info::TaskStatus;
 use risingwave_pb::task_service::GetDataResponse;
 use tokio::sync::oneshot::{Receiver, Sender};
+use tokio::sync::watch;
 use tracing_futures::Instrument;
 
-use crate::executor::{BoxedExecutor, ExecutorBuilder};
+use crate::executor::{BoxedExecutor, ExecutorBuilder, TaskProfile};
 use crate::rpc::service::exchange::ExchangeWriter;
 use crate::task::channel::{create_output_channel, ChanReceiverImpl, ChanSenderImpl};
-use crate::task::BatchTaskContext;
+use crate::task::{BatchTaskContext, TaskAdmission};
 
 #[derive(PartialEq, Eq, Hash, Clone, Debug, Default)]
 pub struct TaskId {
@@ -107,13 +108,38 @@ pub struct TaskOutput {
     receiver: ChanReceiverImpl,
     output_id: TaskOutputId,
     failure: Arc<Mutex<Option<RwError>>>,
+    /// Turns to `true` once the task is aborted.
+    cancelled: watch::Receiver<bool>,
 }
 
 impl TaskOutput {
+    /// Resolves once the producing task is aborted. Never resolves if the task finishes normally.
+    async fn wait_cancelled(cancelled: &mut watch::Receiver<bool>) {
+        while !*cancelled.borrow() {
+            if cancelled.changed().await.is_err() {
+                futures::future::pending::<()>().await;
+            }
+        }
+    }
+
+    fn cancelled_error(&self) -> RwError {
+        ErrorCode::QueryCancelled(format!("task {:?} aborted", self.output_id.task_id)).into()
+    }
+
+    /// Receives the next chunk, or returns an error if the task is aborted in the meantime.
+    async fn recv(&mut self) -> Result<Option<DataChunk>> {
+        tokio::select! {
+            biased;
+            _ = Self::wait_cancelled(&mut self.cancelled) => {}
+            res = self.receiver.recv() => return res,
+        }
+        Err(self.cancelled_error())
+    }
+
     /// Writes the data in serialized format to `ExchangeWriter`.
     pub async fn take_data(&mut self, writer: &mut dyn ExchangeWriter) -> Result<()> {
         loop {
-            match self.receiver.recv().await {
+            match self.recv().await {
                 // Received some data
                 Ok(Some(chunk)) => {
                     let chunk = chunk.compact()?;
@@ -151,7 +177,7 @@ impl TaskOutput {
 
     /// Directly takes data without serialization.
     pub async fn direct_take_data(&mut self) -> Result<Option<DataChunk>> {
-        self.receiver.recv().await
+        self.recv().await
     }
 
     pub fn id(&self) -> &TaskOutputId {
@@ -182,6 +208,16 @@ pub struct BatchTaskExecution<C> {
     /// Shutdown signal sender.
     shutdown_tx: Mutex<Option<Sender<u64>>>,
 
+    /// Notifies the task outputs that the task is aborted, so that in-flight exchanges are closed
+    /// with an error instead of a normal end of stream.
+    cancel_tx: watch::Sender<bool>,
+
+    /// Resources granted to the task by the admission control, released once the execution ends.
+    admission: Mutex<Option<TaskAdmission>>,
+
+    /// Set if the runtime statistics of the executors are collected.
+    profile: Option<TaskProfile>,
+
     epoch: u64,
 }
 
@@ -201,9 +237,28 @@ impl<C: BatchTaskContext> BatchTaskExecution<C> {
             failure: Arc::new(Mutex::new(None)),
             epoch,
             shutdown_tx: Mutex::new(None),
+            cancel_tx: watch::channel(false).0,
+            admission: Mutex::new(None),
+            profile: None,
         })
     }
 
+    pub fn with_admission(self, admission: TaskAdmission) -> Self {
+        *self.admission.lock() = Some(admission);
+        self
+    }
+
+    /// Collects the runtime statistics of the executors of the task.
+    pub fn with_profile(mut self) -> Self {
+        self.profile = Some(TaskProfile::default());
+        self
+    }
+
+    /// Returns the runtime statistics of the executors collected so far, if the task is profiled.
+    pub fn profile(&self) -> Option<&TaskProfile> {
+        self.profile.as_ref()
+    }
+
     pub fn get_task_id(&self) -> &TaskId {
         &self.task_id
     }
@@ -221,14 +276,16 @@ impl<C: BatchTaskContext> BatchTaskExecution<C> {
             serde_json::to_string_pretty(self.plan.get_root()?).unwrap()
         );
         *self.state.lock() = TaskStatus::Running;
-        let exec = ExecutorBuilder::new(
+        let mut builder = ExecutorBuilder::new(
             self.plan.root.as_ref().unwrap(),
-            &self.task_id.clone(),
+            &self.task_id,
             self.context.clone(),
             self.epoch,
-        )
-        .build()
-        .await?;
+        );
+        if let Some(profile) = &self.profile {
+            builder = builder.with_profile(profile.clone());
+        }
+        let exec = builder.build().await?;
 
         let (sender, receivers) = create_output_channel(self.plan.get_exchange_info()?)?;
         let (shutdown_tx, shutdown_rx) = tokio::sync::oneshot::channel::<u64>();
@@ -262,6 +319,7 @@ impl<C: BatchTaskContext> BatchTaskExecution<C> {
                     *failure.lock() = Some(e);
                     *self.state.lock() = TaskStatus::Failed;
                 }
+                self.admission.lock().take();
             });
 
             if let Err(join_error) = join_handle.await && join_error.is_panic() {
@@ -314,6 +372,7 @@ impl<C: BatchTaskContext> BatchTaskExecution<C> {
             ))
         })?;
         *self.state.lock() = TaskStatus::Aborting;
+        self.cancel_tx.send_replace(true);
         sender.send(0).map_err(|err| {
             ErrorCode::InternalError(format!(
                 "Task{:?};s shutdown channel send error:{:?}",
@@ -338,6 +397,7 @@ impl<C: BatchTaskContext> BatchTaskExecution<C> {
             receiver,
             output_id: output_id.try_into()?,
             failure: self.failure.clone(),
+            cancelled: self.cancel_tx.subscribe(),
         };
         Ok(task_output)
     }
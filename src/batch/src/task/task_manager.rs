@@ -14,6 +14,7 @@
 
 use std::collections::{hash_map, HashMap};
 use std::net::SocketAddr;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 
 use parking_lot::Mutex;
@@ -22,36 +23,87 @@ use risingwave_common::error::{Result, RwError};
 use risingwave_pb::batch_plan::{
     PlanFragment, TaskId as ProstTaskId, TaskOutputId as ProstTaskOutputId,
 };
-use risingwave_pb::task_service::GetDataResponse;
+use risingwave_pb::task_service::{GetDataResponse, OperatorProfile as ProstOperatorProfile};
 use tokio::sync::mpsc::Sender;
 use tonic::Status;
 
+use crate::executor::BatchMetrics;
 use crate::rpc::service::exchange::GrpcExchangeWriter;
-use crate::task::{BatchTaskExecution, ComputeNodeContext, TaskId, TaskOutput, TaskOutputId};
+use crate::task::{
+    AdmissionController, AdmissionControllerRef, BatchMemoryTrackerRef, BatchQuota,
+    BatchTaskExecution, ComputeNodeContext, TaskAdmission, TaskId, TaskOutput, TaskOutputId,
+};
 
 /// `BatchManager` is responsible for managing all batch tasks.
 #[derive(Clone)]
 pub struct BatchManager {
     /// Every task id has a corresponding task execution.
     tasks: Arc<Mutex<HashMap<TaskId, Arc<BatchTaskExecution<ComputeNodeContext>>>>>,
+
+    /// Limits the tasks and memory of batch queries on this node.
+    admission: AdmissionControllerRef,
+
+    /// Set when the node is shutting down, after which new tasks are rejected.
+    stopped: Arc<AtomicBool>,
 }
 
 impl BatchManager {
     pub fn new() -> Self {
+        Self::with_quota(BatchQuota::default(), Arc::new(BatchMetrics::unused()))
+    }
+
+    pub fn with_quota(quota: BatchQuota, metrics: Arc<BatchMetrics>) -> Self {
         BatchManager {
             tasks: Arc::new(Mutex::new(HashMap::new())),
+            admission: Arc::new(AdmissionController::new(quota, metrics)),
+            stopped: Arc::new(AtomicBool::new(false)),
         }
     }
 
+    /// Memory held by executors of all tasks on this node.
+    pub fn mem_tracker(&self) -> BatchMemoryTrackerRef {
+        self.admission.node_mem_tracker()
+    }
+
+    /// Waits for the resources to run a new task of `query_id` on this node. Returns an error if
+    /// the resources are not available before the admission timeout.
+    pub async fn admit_task(&self, query_id: &str) -> Result<TaskAdmission> {
+        if self.stopped.load(Ordering::Relaxed) {
+            return Err(ErrorCode::InternalError(
+                "compute node is shutting down, no new task is accepted".to_string(),
+            )
+            .into());
+        }
+        self.admission.admit(query_id).await
+    }
+
+    /// Queues new tasks until the memory pressure is relieved. See
+    /// [`AdmissionController::set_memory_pressure`].
+    pub fn set_memory_pressure(&self, memory_pressure: bool) {
+        self.admission.set_memory_pressure(memory_pressure);
+    }
+
+    /// Rejects new tasks, e.g. when the node is shutting down. Running tasks are not affected.
+    pub fn stop_accepting_tasks(&self) {
+        self.stopped.store(true, Ordering::Relaxed);
+    }
+
     pub async fn fire_task(
         &self,
         tid: &ProstTaskId,
         plan: PlanFragment,
         epoch: u64,
         context: ComputeNodeContext,
+        profile: bool,
     ) -> Result<()> {
         trace!("Received task id: {:?}, plan: {:?}", tid, plan);
-        let task = BatchTaskExecution::new(tid, plan, context, epoch)?;
+        let admission = self.admit_task(&tid.query_id).await?;
+        let context = context.with_mem_tracker(admission.mem_tracker());
+        let mut task =
+            BatchTaskExecution::new(tid, plan, context, epoch)?.with_admission(admission);
+        if profile {
+            task = task.with_profile();
+        }
         let task_id = task.get_task_id().clone();
         let task = Arc::new(task);
 
@@ -79,7 +131,16 @@ impl BatchManager {
         let mut task_output = self.take_output(pb_task_output_id)?;
         tokio::spawn(async move {
             let mut writer = GrpcExchangeWriter::new(tx.clone());
-            match task_output.take_data(&mut writer).await {
+            let res = tokio::select! {
+                // The consumer has gone, e.g. the downstream task is aborted. Stop forwarding so
+                // that the task output is released.
+                _ = tx.closed() => {
+                    tracing::debug!(from = ?task_id, "exchange consumer closed");
+                    return Ok(());
+                }
+                res = task_output.take_data(&mut writer) => res,
+            };
+            match res {
                 Ok(_) => {
                     tracing::debug!(
                         from = ?task_id,
@@ -112,6 +173,30 @@ impl BatchManager {
         }
     }
 
+    /// Aborts and removes all tasks of `query_id`. In-flight exchanges of these tasks are closed
+    /// with a cancellation error. Returns the number of aborted tasks.
+    pub fn abort_query(&self, query_id: &str) -> usize {
+        let tasks = {
+            let mut tasks = self.tasks.lock();
+            let task_ids = tasks
+                .keys()
+                .filter(|task_id| task_id.query_id == query_id)
+                .cloned()
+                .collect::<Vec<_>>();
+            task_ids
+                .into_iter()
+                .filter_map(|task_id| tasks.remove(&task_id))
+                .collect::<Vec<_>>()
+        };
+        for task in &tasks {
+            // The task may have finished or been aborted already, which is fine.
+            if let Err(e) = task.abort_task() {
+                debug!("skip aborting task {:?}: {}", task.get_task_id(), e);
+            }
+        }
+        tasks.len()
+    }
+
     pub fn remove_task(
         &self,
         sid: &ProstTaskId,
@@ -123,6 +208,18 @@ impl BatchManager {
         }
     }
 
+    /// Returns the runtime statistics of the executors of a profiled task, which are complete once
+    /// the output of the task is consumed.
+    pub fn get_task_profile(&self, sid: &ProstTaskId) -> Result<Vec<ProstOperatorProfile>> {
+        let task_id = TaskId::from(sid);
+        let tasks = self.tasks.lock();
+        let task = tasks.get(&task_id).ok_or(TaskNotFound)?;
+        let profile = task.profile().ok_or_else(|| {
+            ErrorCode::InternalError(format!("task {:?} is not profiled", task_id))
+        })?;
+        Ok(profile.to_prost())
+    }
+
     /// Returns error if task is not running.
     pub fn check_if_task_running(&self, task_id: &TaskId) -> Result<()> {
         match self.tasks.lock().get(task_id) {
@@ -222,6 +319,7 @@ mod tests {
             root: Some(PlanNode {
                 children: vec![],
                 identity: "".to_string(),
+                plan_node_id: 0,
                 node_body: Some(NodeBody::Values(ValuesNode {
                     tuples: vec![],
                     fields: vec![],
@@ -239,11 +337,11 @@ mod tests {
             task_id: 0,
         };
         manager
-            .fire_task(&task_id, plan.clone(), 0, context.clone())
+            .fire_task(&task_id, plan.clone(), 0, context.clone(), false)
             .await
             .unwrap();
         let err = manager
-            .fire_task(&task_id, plan, 0, context)
+            .fire_task(&task_id, plan, 0, context, false)
             .await
             .unwrap_err();
         assert!(err
@@ -251,6 +349,15 @@ mod tests {
             .contains("can not create duplicate task with the same id"));
     }
 
+    #[tokio::test]
+    async fn test_stop_accepting_tasks() {
+        let manager = BatchManager::new();
+        manager.admit_task("q1").await.unwrap();
+        manager.stop_accepting_tasks();
+        let err = manager.admit_task("q2").await.unwrap_err();
+        assert!(err.to_string().contains("shutting down"));
+    }
+
     #[tokio::test]
     async fn test_task_aborted() {
         let manager = BatchManager::new();
@@ -258,6 +365,7 @@ mod tests {
             root: Some(PlanNode {
                 children: vec![],
                 identity: "".to_string(),
+                plan_node_id: 0,
                 node_body: Some(NodeBody::GenerateSeries(GenerateSeriesNode {
                     start: Some(make_i32_literal(1)),
                     // This is a bit hacky as we want to make sure the task lasts long enough
@@ -278,7 +386,7 @@ mod tests {
             task_id: 0,
         };
         manager
-            .fire_task(&task_id, plan.clone(), 0, context.clone())
+            .fire_task(&task_id, plan.clone(), 0, context.clone(), false)
             .await
             .unwrap();
         manager.abort_task(&task_id).unwrap();
@@ -286,4 +394,120 @@ mod tests {
         let res = manager.wait_until_task_aborted(&task_id).await;
         assert_eq!(res, Ok(()));
     }
+
+    #[tokio::test]
+    async fn test_abort_query() {
+        let manager = BatchManager::new();
+        let plan = PlanFragment {
+            root: Some(PlanNode {
+                children: vec![],
+                identity: "".to_string(),
+                plan_node_id: 0,
+                node_body: Some(NodeBody::GenerateSeries(GenerateSeriesNode {
+                    start: Some(make_i32_literal(1)),
+                    stop: Some(make_i32_literal(i32::MAX)),
+                    step: Some(make_i32_literal(1)),
+                })),
+            }),
+            exchange_info: Some(ExchangeInfo {
+                mode: DistributionMode::Single as i32,
+                distribution: None,
+            }),
+        };
+        let context = ComputeNodeContext::new_for_test();
+        for (query_id, task_id) in [("q1", 0), ("q1", 1), ("q2", 0)] {
+            let task_id = ProstTaskId {
+                query_id: query_id.to_string(),
+                stage_id: 0,
+                task_id,
+            };
+            manager
+                .fire_task(&task_id, plan.clone(), 0, context.clone(), false)
+                .await
+                .unwrap();
+        }
+        let output_id = ProstTaskOutputId {
+            task_id: Some(ProstTaskId {
+                query_id: "q1".to_string(),
+                stage_id: 0,
+                task_id: 0,
+            }),
+            output_id: 0,
+        };
+        let mut output = manager.take_output(&output_id).unwrap();
+
+        assert_eq!(manager.abort_query("q1"), 2);
+        assert_eq!(manager.abort_query("q1"), 0);
+        manager
+            .check_if_task_running(&TaskId {
+                task_id: 0,
+                stage_id: 0,
+                query_id: "q2".to_string(),
+            })
+            .unwrap();
+
+        // The in-flight output ends with an error rather than a normal end of stream.
+        loop {
+            match output.direct_take_data().await {
+                Ok(Some(_)) => continue,
+                Ok(None) => panic!("aborted task output should not end normally"),
+                Err(e) => {
+                    assert!(e.to_string().contains("Query cancelled"));
+                    break;
+                }
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_task_profile() {
+        let manager = BatchManager::new();
+        let plan = PlanFragment {
+            root: Some(PlanNode {
+                children: vec![],
+                identity: "".to_string(),
+                plan_node_id: 1,
+                node_body: Some(NodeBody::GenerateSeries(GenerateSeriesNode {
+                    start: Some(make_i32_literal(1)),
+                    stop: Some(make_i32_literal(3)),
+                    step: Some(make_i32_literal(1)),
+                })),
+            }),
+            exchange_info: Some(ExchangeInfo {
+                mode: DistributionMode::Single as i32,
+                distribution: None,
+            }),
+        };
+        let context = ComputeNodeContext::new_for_test();
+        let task_id = ProstTaskId {
+            query_id: "q".to_string(),
+            stage_id: 0,
+            task_id: 0,
+        };
+        manager
+            .fire_task(&task_id, plan.clone(), 0, context.clone(), true)
+            .await
+            .unwrap();
+        let output_id = ProstTaskOutputId {
+            task_id: Some(task_id.clone()),
+            output_id: 0,
+        };
+        let mut output = manager.take_output(&output_id).unwrap();
+        while output.direct_take_data().await.unwrap().is_some() {}
+
+        let profiles = manager.get_task_profile(&task_id).unwrap();
+        assert_eq!(profiles.len(), 1);
+        assert_eq!((profiles[0].plan_node_id, profiles[0].rows), (1, 3));
+
+        // Tasks are not profiled unless asked to.
+        let task_id = ProstTaskId {
+            task_id: 1,
+            ..task_id
+        };
+        manager
+            .fire_task(&task_id, plan, 0, context, false)
+            .await
+            .unwrap();
+        assert!(manager.get_task_profile(&task_id).is_err());
+    }
 }
@@ -145,6 +145,7 @@ async fn main() {
     let config = Arc::new(StorageConfig {
         shared_buffer_capacity_mb: opts.shared_buffer_capacity_mb,
         bloom_false_positive: opts.bloom_false_positive,
+        value_separation_threshold_kb: 0,
         sstable_size_mb: opts.table_size_mb,
         block_size_kb: opts.block_size_kb,
         share_buffers_sync_parallelism: opts.share_buffers_sync_parallelism,
@@ -154,8 +155,10 @@ async fn main() {
         meta_cache_capacity_mb: opts.meta_cache_capacity_mb as usize,
         disable_remote_compactor: true,
         enable_local_spill: false,
+        enable_state_store_sanity_check: false,
         local_object_store: "memory".to_string(),
         share_buffer_compaction_worker_threads_number: 1,
+        wait_epoch_timeout_ms: 10_000,
     });
 
     let (_env, hummock_manager_ref, _cluster_manager_ref, worker_node) =
@@ -26,7 +26,7 @@ use std::future::Future;
 use std::pin::Pin;
 
 use clap::StructOpt;
-use risingwave_cmd_all::playground;
+use risingwave_cmd_all::{playground, PlaygroundOpts};
 
 type RwFns = HashMap<&'static str, Box<dyn Fn(Vec<String>) -> Box<dyn Future<Output = ()>>>>;
 
@@ -131,7 +131,12 @@ async fn main() {
     for fn_name in ["play", "playground"] {
         fns.insert(
             fn_name,
-            Box::new(|_: Vec<String>| Box::new(async move { playground().await.unwrap() })),
+            Box::new(|args: Vec<String>| {
+                Box::new(async move {
+                    let opts = PlaygroundOpts::parse_from(args);
+                    playground(opts).await.unwrap()
+                })
+            }),
         );
     }
 
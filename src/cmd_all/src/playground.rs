@@ -14,17 +14,30 @@
 
 use std::collections::HashMap;
 use std::ffi::OsString;
+use std::path::PathBuf;
 use std::process::Command;
+use std::time::Duration;
 
 use anyhow::{anyhow, Result};
-use clap::StructOpt;
+use clap::Parser;
 use risedev::{
     CompactorService, ComputeNodeService, ConfigExpander, FrontendService, MetaNodeService,
     ServiceConfig,
 };
 use tokio::fs::File;
 use tokio::io::AsyncReadExt;
+use tokio::net::TcpStream;
 use tokio::signal;
+use tokio::sync::oneshot::Sender;
+use tokio::task::JoinHandle;
+
+#[derive(Debug, Parser)]
+pub struct PlaygroundOpts {
+    /// The directory of the local object store, used when there's no `risedev.yml` in the
+    /// working directory. Defaults to `risingwave-playground` under the temp directory.
+    #[clap(long)]
+    data_directory: Option<PathBuf>,
+}
 
 async fn load_risedev_config(
     profile: &str,
@@ -50,7 +63,35 @@ pub enum RisingWaveService {
     Compactor(Vec<OsString>),
 }
 
-pub async fn playground() -> Result<()> {
+/// Waits until the service at `addr` accepts connections.
+async fn wait_for_listener(addr: &str) -> Result<()> {
+    for _ in 0..100 {
+        if TcpStream::connect(addr).await.is_ok() {
+            return Ok(());
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    Err(anyhow!("{} is not listening after 10s", addr))
+}
+
+/// Prepares an empty directory for the local object store. The files left by the previous run
+/// are removed, since the metadata is only kept in memory and lost on exit.
+async fn prepare_data_directory(opts: &PlaygroundOpts) -> Result<PathBuf> {
+    let data_directory = opts
+        .data_directory
+        .clone()
+        .unwrap_or_else(|| std::env::temp_dir().join("risingwave-playground"));
+    if tokio::fs::metadata(&data_directory).await.is_ok() {
+        tokio::fs::remove_dir_all(&data_directory).await?;
+    }
+    tokio::fs::create_dir_all(&data_directory).await?;
+    Ok(data_directory)
+}
+
+/// Runs meta, compute and frontend nodes in the current process. The components are started one
+/// after another once the previous one is ready, and the meta and compute nodes are shut down in
+/// order on ctrl-c.
+pub async fn playground(opts: PlaygroundOpts) -> Result<()> {
     eprintln!("launching playground");
 
     risingwave_logging::oneshot_common();
@@ -109,14 +150,21 @@ pub async fn playground() -> Result<()> {
         }
         Err(e) => {
             tracing::warn!("Failed to load risedev config. All components will be started using the default command line options.\n{}", e);
+            let data_directory = prepare_data_directory(&opts).await?;
+            tracing::info!("Using local object store at {}", data_directory.display());
             vec![
                 RisingWaveService::Meta(vec!["--backend".into(), "mem".into()]),
-                RisingWaveService::Compute(vec!["--state-store".into(), "hummock+memory".into()]),
+                RisingWaveService::Compute(vec![
+                    "--state-store".into(),
+                    format!("hummock+disk://{}", data_directory.display()).into(),
+                ]),
                 RisingWaveService::Frontend(vec![]),
             ]
         }
     };
 
+    // The handles of meta and compute nodes, in the order they are started.
+    let mut node_handles: Vec<(JoinHandle<()>, Sender<()>)> = vec![];
     for service in services {
         match service {
             RisingWaveService::Meta(mut opts) => {
@@ -124,17 +172,17 @@ pub async fn playground() -> Result<()> {
                 tracing::info!("starting meta-node thread with cli args: {:?}", opts);
                 let opts = risingwave_meta::MetaNodeOpts::parse_from(opts);
                 tracing::info!("opts: {:#?}", opts);
-                let _meta_handle = tokio::spawn(async move { risingwave_meta::start(opts).await });
-                // wait for the service to be ready
-                tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+                let meta_addr = opts.host.clone();
+                node_handles.push(risingwave_meta::serve(opts).await);
+                wait_for_listener(&meta_addr).await?;
             }
             RisingWaveService::Compute(mut opts) => {
                 opts.insert(0, "compute-node".into());
                 tracing::info!("starting compute-node thread with cli args: {:?}", opts);
                 let opts = risingwave_compute::ComputeNodeOpts::parse_from(opts);
                 tracing::info!("opts: {:#?}", opts);
-                let _compute_handle =
-                    tokio::spawn(async move { risingwave_compute::start(opts).await });
+                // Returns after the compute node is activated in meta.
+                node_handles.push(risingwave_compute::serve(opts).await);
             }
             RisingWaveService::Frontend(mut opts) => {
                 opts.insert(0, "frontend-node".into());
@@ -155,10 +203,15 @@ pub async fn playground() -> Result<()> {
         }
     }
 
-    // TODO: should we join all handles?
-    // Currently, not all services can be shutdown gracefully, just quit on Ctrl-C now.
-    signal::ctrl_c().await.unwrap();
+    signal::ctrl_c().await?;
     tracing::info!("Ctrl+C received, now exiting");
 
+    // Frontend and compactor can't be shut down gracefully yet, and are just dropped on exit. Meta
+    // is the last to stop, so that the compute nodes can still reach it while shutting down.
+    for (join_handle, shutdown_send) in node_handles.into_iter().rev() {
+        let _ = shutdown_send.send(());
+        join_handle.await?;
+    }
+
     Ok(())
 }
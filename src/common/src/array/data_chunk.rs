@@ -178,6 +178,17 @@ impl DataChunk {
         &self.columns
     }
 
+    /// Estimated size in bytes of the data the chunk holds on the heap, including the arrays that
+    /// may be shared with other chunks.
+    pub fn estimated_heap_size(&self) -> usize {
+        let visibility_size = self.visibility().map_or(0, |vis| vis.estimated_heap_size());
+        self.columns
+            .iter()
+            .map(|column| column.array_ref().estimated_heap_size())
+            .sum::<usize>()
+            + visibility_size
+    }
+
     pub fn to_protobuf(&self) -> ProstDataChunk {
         assert!(
             matches!(self.vis2, Vis::Compact(_)),
@@ -535,6 +546,7 @@ impl DataChunkTestExt for DataChunk {
 mod tests {
     use crate::array::column::Column;
     use crate::array::*;
+    use crate::buffer::Bitmap;
     use crate::{column, column_nonnull};
 
     #[test]
@@ -650,4 +662,20 @@ mod tests {
         assert_eq!(chunk_after_serde.rows().count(), 10);
         assert_eq!(chunk_after_serde.cardinality(), 10);
     }
+
+    #[test]
+    fn test_estimated_heap_size() {
+        let chunk = DataChunk::from_pretty(
+            "i T
+             1 abc
+             2 .
+             3 defgh",
+        );
+        // 3 i32 values, 3 + 1 offsets, 8 bytes of strings and 2 null bitmaps of 64 bytes.
+        let data_size = 3 * 4 + 4 * std::mem::size_of::<usize>() + 8;
+        assert!(chunk.estimated_heap_size() >= data_size + 2 * 64);
+
+        let filtered = chunk.with_visibility(Bitmap::try_from(vec![true, false, true]).unwrap());
+        assert!(filtered.estimated_heap_size() > chunk.estimated_heap_size());
+    }
 }
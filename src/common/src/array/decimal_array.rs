@@ -110,6 +110,10 @@ impl Array for DecimalArray {
         &self.bitmap
     }
 
+    fn estimated_heap_size(&self) -> usize {
+        self.bitmap.estimated_heap_size() + self.data.capacity() * size_of::<Decimal>()
+    }
+
     fn set_bitmap(&mut self, bitmap: Bitmap) {
         self.bitmap = bitmap;
     }
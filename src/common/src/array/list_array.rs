@@ -16,6 +16,7 @@ use core::fmt;
 use std::cmp::Ordering;
 use std::fmt::{Debug, Display};
 use std::hash::{Hash, Hasher};
+use std::mem::size_of;
 
 use itertools::EitherOrBoth::{Both, Left, Right};
 use itertools::Itertools;
@@ -190,6 +191,12 @@ impl Array for ListArray {
         &self.bitmap
     }
 
+    fn estimated_heap_size(&self) -> usize {
+        self.bitmap.estimated_heap_size()
+            + self.offsets.capacity() * size_of::<usize>()
+            + self.value.estimated_heap_size()
+    }
+
     fn set_bitmap(&mut self, bitmap: Bitmap) {
         self.bitmap = bitmap;
     }
@@ -173,6 +173,9 @@ pub trait Array: std::fmt::Debug + Send + Sync + Sized + 'static + Into<ArrayImp
     /// Get the null `Bitmap` from `Array`.
     fn null_bitmap(&self) -> &Bitmap;
 
+    /// Estimated size in bytes of the data the array holds on the heap.
+    fn estimated_heap_size(&self) -> usize;
+
     /// Check if an element is `null` or not.
     fn is_null(&self, idx: usize) -> bool {
         self.null_bitmap().is_set(idx).map(|v| !v).unwrap()
@@ -465,6 +468,13 @@ macro_rules! impl_array {
                 }
             }
 
+            /// Estimated size in bytes of the data the array holds on the heap.
+            pub fn estimated_heap_size(&self) -> usize {
+                match self {
+                    $( Self::$variant_name(inner) => inner.estimated_heap_size(), )*
+                }
+            }
+
             pub fn to_protobuf(&self) -> ProstArray {
                 match self {
                     $( Self::$variant_name(inner) => inner.to_protobuf(), )*
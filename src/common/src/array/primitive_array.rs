@@ -141,6 +141,16 @@ pub struct PrimitiveArray<T: PrimitiveArrayItemType> {
 }
 
 impl<T: PrimitiveArrayItemType> PrimitiveArray<T> {
+    pub fn new(bitmap: Bitmap, data: Vec<T>) -> Self {
+        assert_eq!(bitmap.len(), data.len());
+        Self { bitmap, data }
+    }
+
+    /// Returns the raw values of the array. Values at null positions are `T::default()`.
+    pub fn as_slice(&self) -> &[T] {
+        &self.data
+    }
+
     pub fn from_slice(data: &[Option<T>]) -> Result<Self> {
         let mut builder = <Self as Array>::Builder::new(data.len())?;
         for i in data {
@@ -209,6 +219,10 @@ impl<T: PrimitiveArrayItemType> Array for PrimitiveArray<T> {
         &self.bitmap
     }
 
+    fn estimated_heap_size(&self) -> usize {
+        self.bitmap.estimated_heap_size() + self.data.capacity() * size_of::<T>()
+    }
+
     fn set_bitmap(&mut self, bitmap: Bitmap) {
         self.bitmap = bitmap;
     }
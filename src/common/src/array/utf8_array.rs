@@ -113,6 +113,12 @@ impl Array for Utf8Array {
         &self.bitmap
     }
 
+    fn estimated_heap_size(&self) -> usize {
+        self.bitmap.estimated_heap_size()
+            + self.offset.capacity() * size_of::<usize>()
+            + self.data.capacity()
+    }
+
     fn set_bitmap(&mut self, bitmap: Bitmap) {
         self.bitmap = bitmap;
     }
@@ -149,6 +149,21 @@ impl Bitmap {
         }
     }
 
+    /// Packs a slice of bools into a bitmap, eight bits at a time. It's much faster than appending
+    /// to a [`BitmapBuilder`] bit by bit.
+    pub fn from_bool_slice(bools: &[bool]) -> Self {
+        let bits = bools
+            .chunks(8)
+            .map(|chunk| {
+                chunk
+                    .iter()
+                    .enumerate()
+                    .fold(0u8, |byte, (i, &bit)| byte | ((bit as u8) << i))
+            })
+            .collect();
+        Self::from_bytes_with_num_bits(bits, bools.len())
+    }
+
     #[cfg(test)]
     pub fn from_bytes(buf: Bytes) -> Self {
         let num_bits = buf.len() << 3;
@@ -195,6 +210,11 @@ impl Bitmap {
         self.bits.len()
     }
 
+    /// Estimated size in bytes of the bitmap on the heap.
+    pub fn estimated_heap_size(&self) -> usize {
+        self.bits.len()
+    }
+
     pub fn is_empty(&self) -> bool {
         self.bits.is_empty()
     }
@@ -487,6 +507,16 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_bitmap_from_bool_slice() {
+        let bools = (0..19).map(|i| i % 3 == 0).collect_vec();
+        let bitmap = Bitmap::from_bool_slice(&bools);
+        assert_eq!(bitmap.len(), bools.len());
+        assert_eq!(bitmap.num_high_bits(), 7);
+        assert_eq!(bitmap.iter().collect_vec(), bools);
+        assert_eq!(bitmap, Bitmap::try_from(bools).unwrap());
+    }
+
     #[test]
     fn test_bitmap_from_protobuf() {
         let bitmap_bytes = vec![3u8 /* len % 8 */, 0b0101_0010, 0b110];
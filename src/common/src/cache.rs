@@ -670,6 +670,22 @@ impl<K: LruKey, T: LruValue> LruCache<K, T> {
             .sum()
     }
 
+    /// Changes the capacity of the cache. Unused entries are evicted at once if the cache is
+    /// shrunk, and the entries in use are evicted when they are released.
+    pub fn set_capacity(&self, capacity: usize) {
+        let per_shard = capacity / self.shards.len();
+        for shard in &self.shards {
+            let mut to_delete = vec![];
+            {
+                let mut shard = shard.lock();
+                shard.capacity = per_shard;
+                unsafe { shard.evict_from_lru(0, &mut to_delete) };
+            }
+            // do not deallocate data with holding mutex.
+            drop(to_delete);
+        }
+    }
+
     fn shard(&self, hash: u64) -> usize {
         hash as usize % self.shards.len()
     }
@@ -809,6 +825,34 @@ mod tests {
         assert_eq!(256, cache.get_memory_usage());
     }
 
+    #[test]
+    fn test_set_capacity() {
+        let cache = Arc::new(LruCache::<String, String>::new(0, 4));
+        let entry = cache.insert("a".to_string(), 0, 1, "v".to_string());
+        for key in ["b", "c", "d"] {
+            cache.insert(key.to_string(), 0, 1, "v".to_string());
+        }
+        assert_eq!(4, cache.get_memory_usage());
+
+        // The least recently used entries are evicted.
+        cache.set_capacity(2);
+        assert_eq!(2, cache.get_memory_usage());
+        assert!(cache.lookup(0, &"b".to_string()).is_none());
+        assert!(cache.lookup(0, &"d".to_string()).is_some());
+
+        // The entry in use is evicted when released.
+        cache.set_capacity(0);
+        assert_eq!(1, cache.get_memory_usage());
+        drop(entry);
+        assert_eq!(0, cache.get_memory_usage());
+
+        cache.set_capacity(8);
+        for key in ["e", "f", "g"] {
+            cache.insert(key.to_string(), 0, 1, "v".to_string());
+        }
+        assert_eq!(3, cache.get_memory_usage());
+    }
+
     fn validate_lru_list(cache: &mut LruCacheShard<String, String>, keys: Vec<&str>) {
         unsafe {
             let mut lru: *mut LruHandle<String, String> = cache.lru.as_mut();
@@ -33,6 +33,8 @@ pub struct TableDesc {
     pub distribution_keys: Vec<usize>,
     /// Column indices for primary keys.
     pub pks: Vec<usize>,
+    /// Retention in seconds of the rows of the table, or 0 to keep the rows forever.
+    pub retention_seconds: u32,
 }
 
 impl TableDesc {
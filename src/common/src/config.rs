@@ -20,11 +20,18 @@ use serde::{Deserialize, Serialize};
 use crate::error::ErrorCode::InternalError;
 use crate::error::{Result, RwError};
 
+mod dynamic;
+pub use dynamic::*;
+
+/// The configuration shared by all node types, loaded from `risingwave.toml`. Each node only reads
+/// the sections it needs. Some knobs can be changed without restarting the nodes, see
+/// [`DynamicConfig`].
+///
 /// TODO(TaoWu): The configs here may be preferable to be managed under corresponding module
 /// separately.
-#[derive(Clone, Debug, Serialize, Deserialize, Default)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Default)]
 #[serde(deny_unknown_fields)]
-pub struct ComputeNodeConfig {
+pub struct RwConfig {
     // For connection
     #[serde(default)]
     pub server: ServerConfig,
@@ -40,29 +47,38 @@ pub struct ComputeNodeConfig {
     // Below for Hummock.
     #[serde(default)]
     pub storage: StorageConfig,
+
+    // Below for connectors of sources.
+    #[serde(default)]
+    pub connector: ConnectorConfig,
 }
 
-pub fn load_config(path: &str) -> ComputeNodeConfig {
+pub fn load_config(path: &str) -> RwConfig {
     if path.is_empty() {
         tracing::warn!("risingwave.toml not found, using default config.");
-        return ComputeNodeConfig::default();
+        return RwConfig::default();
     }
 
-    ComputeNodeConfig::init(path.to_owned().into()).unwrap()
+    RwConfig::init(path.to_owned().into()).unwrap()
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize, Default)]
-#[serde(deny_unknown_fields)]
-pub struct FrontendConfig {
-    // For connection
-    #[serde(default)]
-    pub server: ServerConfig,
-}
-
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct ServerConfig {
     #[serde(default = "default::heartbeat_interval_ms")]
     pub heartbeat_interval_ms: u32,
+
+    /// Interval of checking the config file for changes of the dynamic knobs.
+    #[serde(default = "default::config_reload_interval_ms")]
+    pub config_reload_interval_ms: u32,
+
+    /// Memory budget of the whole compute node, against which the memory manager evicts caches
+    /// and holds back new batch tasks. 0 disables the memory manager.
+    #[serde(default)]
+    pub total_memory_mb: usize,
+
+    /// Interval of the memory manager checking the memory usage of the compute node.
+    #[serde(default = "default::memory_control_interval_ms")]
+    pub memory_control_interval_ms: u32,
 }
 
 impl Default for ServerConfig {
@@ -71,11 +87,44 @@ impl Default for ServerConfig {
     }
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct BatchConfig {
     // #[serde(default = "default::chunk_size")]
     // pub chunk_size: u32,
+    /// Size threshold of the build side of a hash join. Once exceeded, the join falls back to a
+    /// grace hash join which spills partitions to local disk.
+    #[serde(default = "default::hash_join_spill_threshold_mb")]
+    pub hash_join_spill_threshold_mb: usize,
+
+    /// Number of partitions the inputs of a grace hash join are split into when spilling.
+    #[serde(default = "default::hash_join_spill_partitions")]
+    pub hash_join_spill_partitions: usize,
+
+    /// Local directory for spilled batch data. The system temporary directory is used if empty.
+    #[serde(default = "default::batch_spill_directory")]
+    pub spill_directory: String,
+
+    /// Maximum number of batch tasks running on the node. 0 means unlimited.
+    #[serde(default = "default::max_node_batch_tasks")]
+    pub max_node_tasks: usize,
+
+    /// Maximum number of tasks of a single query running on the node. 0 means unlimited.
+    #[serde(default = "default::max_query_batch_tasks")]
+    pub max_query_tasks: usize,
+
+    /// Memory limit of all batch executors on the node. 0 means unlimited.
+    #[serde(default = "default::max_node_batch_memory_mb")]
+    pub max_node_memory_mb: usize,
+
+    /// Memory limit of the executors of a single query on the node. 0 means unlimited.
+    #[serde(default = "default::max_query_batch_memory_mb")]
+    pub max_query_memory_mb: usize,
+
+    /// How long a new task may be queued waiting for resources before it's rejected. 0 means
+    /// rejecting immediately.
+    #[serde(default = "default::batch_task_admission_timeout_ms")]
+    pub task_admission_timeout_ms: u64,
 }
 
 impl Default for BatchConfig {
@@ -84,13 +133,52 @@ impl Default for BatchConfig {
     }
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct StreamingConfig {
     // #[serde(default = "default::chunk_size")]
     // pub chunk_size: u32,
     #[serde(default = "default::checkpoint_interval_ms")]
     pub checkpoint_interval_ms: u32,
+
+    /// Every `checkpoint_frequency`-th barrier is a checkpoint, which syncs the states buffered
+    /// since the last checkpoint to the object store and commits them. Other barriers only align
+    /// the actors and flush their outputs, and are lost on recovery.
+    #[serde(default = "default::checkpoint_frequency")]
+    pub checkpoint_frequency: usize,
+
+    /// Barriers taking longer than this to be collected are logged by meta, along with the actor
+    /// collecting them last. 0 disables the log.
+    #[serde(default)]
+    pub slow_barrier_threshold_ms: u64,
+
+    /// Number of recent epochs of changelog kept for each subscribed materialized view, so that
+    /// subscribers can resume after reconnecting.
+    #[serde(default = "default::changelog_retained_epochs")]
+    pub changelog_retained_epochs: usize,
+
+    /// Worker threads number of each dedicated tokio runtime for stream actors. 0 means actors
+    /// share the runtime of the compute node with batch tasks and RPC handlers.
+    #[serde(default = "default::actor_runtime_worker_threads_number")]
+    pub actor_runtime_worker_threads_number: usize,
+
+    /// Number of dedicated runtimes for stream actors, which are assigned to the runtimes by their
+    /// fragments, so that heavy fragments can't starve the actors on other runtimes. Only takes
+    /// effect if `actor_runtime_worker_threads_number` is not 0.
+    #[serde(default = "default::actor_runtime_shards")]
+    pub actor_runtime_shards: usize,
+
+    /// Pins the worker threads of each dedicated runtime for stream actors to a set of cores. The
+    /// runtimes are spread over the NUMA nodes of the machine, and get disjoint cores of their
+    /// nodes. Only takes effect on Linux, and if `actor_runtime_worker_threads_number` is not 0.
+    #[serde(default)]
+    pub actor_runtime_pin_cores: bool,
+
+    /// Resource groups that materialized views can be assigned to with `CREATE MATERIALIZED VIEW
+    /// .. WITH (resource_group = '<name>')`. Views without a resource group, or with one not
+    /// configured here, are in the default group.
+    #[serde(default)]
+    pub resource_groups: Vec<ResourceGroupConfig>,
 }
 
 impl Default for StreamingConfig {
@@ -99,8 +187,45 @@ impl Default for StreamingConfig {
     }
 }
 
+/// Scheduling priority and memory budget of the actors of the materialized views in a resource
+/// group.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ResourceGroupConfig {
+    pub name: String,
+
+    /// Worker threads number of the dedicated tokio runtime for the actors in the group, so that
+    /// they are not starved by the actors in other groups. 0 means the actors run with the
+    /// actors of the default group.
+    #[serde(default)]
+    pub worker_threads_number: usize,
+
+    /// Target number of entries kept in the cache of each hash aggregation and hash join executor
+    /// in the group.
+    #[serde(default = "default::executor_cache_capacity")]
+    pub executor_cache_capacity: usize,
+
+    /// Whether the blocks read by the actors in the group are filled into the block cache. Low
+    /// priority groups can disable it, so that e.g. a backfill doesn't evict the blocks of other
+    /// groups.
+    #[serde(default = "default::fill_block_cache")]
+    pub fill_block_cache: bool,
+}
+
+impl ResourceGroupConfig {
+    /// The group of the materialized views without a configured resource group.
+    pub fn default_group() -> Self {
+        Self {
+            name: String::new(),
+            worker_threads_number: 0,
+            executor_cache_capacity: default::executor_cache_capacity(),
+            fill_block_cache: default::fill_block_cache(),
+        }
+    }
+}
+
 /// Currently all configurations are server before they can be specified with DDL syntaxes.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct StorageConfig {
     /// Target size of the SSTable.
@@ -115,6 +240,11 @@ pub struct StorageConfig {
     #[serde(default = "default::bloom_false_positive")]
     pub bloom_false_positive: f64,
 
+    /// Values not smaller than this size are separated from SSTs into blob files, so that
+    /// compaction doesn't rewrite them. 0 disables the separation.
+    #[serde(default = "default::value_separation_threshold_kb")]
+    pub value_separation_threshold_kb: u32,
+
     /// parallelism while syncing share buffers into L0 SST. Should NOT be 0.
     #[serde(default = "default::share_buffers_sync_parallelism")]
     pub share_buffers_sync_parallelism: u32,
@@ -154,9 +284,18 @@ pub struct StorageConfig {
     #[serde(default = "default::enable_local_spill")]
     pub enable_local_spill: bool,
 
+    /// Whether to check every read of the state store against an in-memory shadow of the writes.
+    /// Only for debug builds in tests.
+    #[serde(default = "default::enable_state_store_sanity_check")]
+    pub enable_state_store_sanity_check: bool,
+
     /// Local object store root. We should call `get_local_object_store` to get the object store.
     #[serde(default = "default::local_object_store")]
     pub local_object_store: String,
+
+    /// Maximum time to wait for an epoch to be committed before reading it, e.g. in batch scans.
+    #[serde(default = "default::wait_epoch_timeout_ms")]
+    pub wait_epoch_timeout_ms: u64,
 }
 
 impl Default for StorageConfig {
@@ -165,23 +304,22 @@ impl Default for StorageConfig {
     }
 }
 
-impl ComputeNodeConfig {
-    pub fn init(path: PathBuf) -> Result<ComputeNodeConfig> {
-        let config_str = fs::read_to_string(path.clone()).map_err(|e| {
-            RwError::from(InternalError(format!(
-                "failed to open config file '{}': {}",
-                path.to_string_lossy(),
-                e
-            )))
-        })?;
-        let config: ComputeNodeConfig = toml::from_str(config_str.as_str())
-            .map_err(|e| RwError::from(InternalError(format!("parse error {}", e))))?;
-        Ok(config)
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ConnectorConfig {
+    /// Maximum number of rows each source actor reads per second. 0 means unlimited.
+    #[serde(default = "default::source_rate_limit_rows_per_sec")]
+    pub source_rate_limit_rows_per_sec: u64,
+}
+
+impl Default for ConnectorConfig {
+    fn default() -> Self {
+        toml::from_str("").unwrap()
     }
 }
 
-impl FrontendConfig {
-    pub fn init(path: PathBuf) -> Result<Self> {
+impl RwConfig {
+    pub fn init(path: PathBuf) -> Result<RwConfig> {
         let config_str = fs::read_to_string(path.clone()).map_err(|e| {
             RwError::from(InternalError(format!(
                 "failed to open config file '{}': {}",
@@ -189,7 +327,7 @@ impl FrontendConfig {
                 e
             )))
         })?;
-        let config: FrontendConfig = toml::from_str(config_str.as_str())
+        let config: RwConfig = toml::from_str(config_str.as_str())
             .map_err(|e| RwError::from(InternalError(format!("parse error {}", e))))?;
         Ok(config)
     }
@@ -201,6 +339,14 @@ mod default {
         1000
     }
 
+    pub fn config_reload_interval_ms() -> u32 {
+        10000
+    }
+
+    pub fn memory_control_interval_ms() -> u32 {
+        1000
+    }
+
     pub fn chunk_size() -> u32 {
         1024
     }
@@ -217,6 +363,10 @@ mod default {
         0.01
     }
 
+    pub fn value_separation_threshold_kb() -> u32 {
+        0
+    }
+
     pub fn share_buffers_sync_parallelism() -> u32 {
         2
     }
@@ -258,11 +408,79 @@ mod default {
         true
     }
 
+    pub fn enable_state_store_sanity_check() -> bool {
+        false
+    }
+
     pub fn local_object_store() -> String {
         "tempdisk".to_string()
     }
 
+    pub fn wait_epoch_timeout_ms() -> u64 {
+        30_000
+    }
+
     pub fn checkpoint_interval_ms() -> u32 {
         100
     }
+
+    pub fn checkpoint_frequency() -> usize {
+        1
+    }
+
+    pub fn changelog_retained_epochs() -> usize {
+        600
+    }
+
+    pub fn actor_runtime_worker_threads_number() -> usize {
+        0
+    }
+
+    pub fn actor_runtime_shards() -> usize {
+        1
+    }
+
+    pub fn executor_cache_capacity() -> usize {
+        1 << 16
+    }
+
+    pub fn fill_block_cache() -> bool {
+        true
+    }
+
+    pub fn hash_join_spill_threshold_mb() -> usize {
+        512
+    }
+
+    pub fn hash_join_spill_partitions() -> usize {
+        16
+    }
+
+    pub fn batch_spill_directory() -> String {
+        "".to_string()
+    }
+
+    pub fn max_node_batch_tasks() -> usize {
+        0
+    }
+
+    pub fn max_query_batch_tasks() -> usize {
+        0
+    }
+
+    pub fn max_node_batch_memory_mb() -> usize {
+        0
+    }
+
+    pub fn max_query_batch_memory_mb() -> usize {
+        0
+    }
+
+    pub fn batch_task_admission_timeout_ms() -> u64 {
+        10000
+    }
+
+    pub fn source_rate_limit_rows_per_sec() -> u64 {
+        0
+    }
 }
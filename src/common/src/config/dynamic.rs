@@ -0,0 +1,240 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Knobs of [`RwConfig`] that can be changed without restarting the nodes.
+//!
+//! [`ConfigReloader`] watches the config file, and publishes the new values of the dynamic knobs
+//! to the components using them through a [`DynamicConfigReceiver`].
+
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+use tokio::sync::oneshot::Sender;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+
+use super::RwConfig;
+use crate::error::ErrorCode::InternalError;
+use crate::error::{Result, RwError};
+
+/// The knobs that take effect without restarting the node when the config file is changed.
+/// Changes to other knobs are ignored until the node restarts.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DynamicConfig {
+    /// `streaming.checkpoint_interval_ms`, the interval of barriers injected by meta.
+    pub checkpoint_interval_ms: u32,
+    /// `streaming.checkpoint_frequency`, the number of barriers per checkpoint.
+    pub checkpoint_frequency: usize,
+    /// `streaming.slow_barrier_threshold_ms`
+    pub slow_barrier_threshold_ms: u64,
+    /// `storage.block_cache_capacity_mb`
+    pub block_cache_capacity_mb: usize,
+    /// `storage.meta_cache_capacity_mb`
+    pub meta_cache_capacity_mb: usize,
+    /// `connector.source_rate_limit_rows_per_sec`
+    pub source_rate_limit_rows_per_sec: u64,
+}
+
+impl DynamicConfig {
+    pub fn from_config(config: &RwConfig) -> Self {
+        Self {
+            checkpoint_interval_ms: config.streaming.checkpoint_interval_ms,
+            checkpoint_frequency: config.streaming.checkpoint_frequency,
+            slow_barrier_threshold_ms: config.streaming.slow_barrier_threshold_ms,
+            block_cache_capacity_mb: config.storage.block_cache_capacity_mb,
+            meta_cache_capacity_mb: config.storage.meta_cache_capacity_mb,
+            source_rate_limit_rows_per_sec: config.connector.source_rate_limit_rows_per_sec,
+        }
+    }
+
+    /// Overwrites the dynamic knobs of `config` with `self`.
+    fn apply_to(&self, config: &mut RwConfig) {
+        config.streaming.checkpoint_interval_ms = self.checkpoint_interval_ms;
+        config.streaming.checkpoint_frequency = self.checkpoint_frequency;
+        config.streaming.slow_barrier_threshold_ms = self.slow_barrier_threshold_ms;
+        config.storage.block_cache_capacity_mb = self.block_cache_capacity_mb;
+        config.storage.meta_cache_capacity_mb = self.meta_cache_capacity_mb;
+        config.connector.source_rate_limit_rows_per_sec = self.source_rate_limit_rows_per_sec;
+    }
+
+    /// Returns a receiver that always sees `self`, for nodes without a config file and tests.
+    pub fn fixed(self) -> DynamicConfigReceiver {
+        watch::channel(self).1
+    }
+}
+
+impl Default for DynamicConfig {
+    fn default() -> Self {
+        Self::from_config(&RwConfig::default())
+    }
+}
+
+/// Receives the latest dynamic knobs. Components either read it on each use with `borrow`, or
+/// wait for changes with `changed`, which fails once the config file is not watched any more.
+pub type DynamicConfigReceiver = watch::Receiver<DynamicConfig>;
+
+/// Reloads the config file when it's modified, and publishes the changes of the dynamic knobs.
+pub struct ConfigReloader {
+    path: PathBuf,
+    /// The config in effect, i.e. the config at startup with the latest dynamic knobs.
+    config: RwConfig,
+    /// Modification time of the config file when it's last loaded.
+    modified: Option<SystemTime>,
+    tx: watch::Sender<DynamicConfig>,
+    rx: DynamicConfigReceiver,
+}
+
+impl ConfigReloader {
+    /// Creates a reloader of the config file at `path`, which `config` is loaded from at startup.
+    pub fn new(path: impl Into<PathBuf>, config: RwConfig) -> Self {
+        let (tx, rx) = watch::channel(DynamicConfig::from_config(&config));
+        Self {
+            path: path.into(),
+            config,
+            modified: None,
+            tx,
+            rx,
+        }
+    }
+
+    pub fn subscribe(&self) -> DynamicConfigReceiver {
+        self.rx.clone()
+    }
+
+    /// Reloads the config file if it's modified since the last load. Returns whether any dynamic
+    /// knob is changed.
+    pub fn reload(&mut self) -> Result<bool> {
+        let modified = fs::metadata(&self.path)
+            .and_then(|metadata| metadata.modified())
+            .map_err(|e| {
+                RwError::from(InternalError(format!(
+                    "failed to stat config file '{}': {}",
+                    self.path.to_string_lossy(),
+                    e
+                )))
+            })?;
+        if self.modified == Some(modified) {
+            return Ok(false);
+        }
+        self.modified = Some(modified);
+
+        let new_config = RwConfig::init(self.path.clone())?;
+        let dynamic = DynamicConfig::from_config(&new_config);
+        let mut static_config = new_config;
+        DynamicConfig::from_config(&self.config).apply_to(&mut static_config);
+        if static_config != self.config {
+            tracing::warn!(
+                "config file '{}' is changed, but only the changes of the dynamic knobs take \
+                 effect before restart",
+                self.path.to_string_lossy()
+            );
+        }
+
+        if dynamic == *self.rx.borrow() {
+            return Ok(false);
+        }
+        tracing::info!("dynamic config is changed to {:?}", dynamic);
+        dynamic.apply_to(&mut self.config);
+        self.tx.send(dynamic).unwrap();
+        Ok(true)
+    }
+
+    /// Starts to check the config file for changes every `interval`.
+    pub fn start(mut self, interval: Duration) -> (JoinHandle<()>, Sender<()>) {
+        let (shutdown_tx, mut shutdown_rx) = tokio::sync::oneshot::channel();
+        let join_handle = tokio::spawn(async move {
+            let mut min_interval = tokio::time::interval(interval);
+            loop {
+                tokio::select! {
+                    _ = min_interval.tick() => {},
+                    _ = &mut shutdown_rx => {
+                        tracing::info!("Config reloader is stopped");
+                        return;
+                    }
+                }
+                if let Err(e) = self.reload() {
+                    tracing::warn!("failed to reload config: {}", e);
+                }
+            }
+        });
+        (join_handle, shutdown_tx)
+    }
+}
+
+/// Starts to watch the config file at `path` that `config` is loaded from, if any. Returns the
+/// receiver of the dynamic knobs, and the reloader task.
+pub fn start_config_reloader(
+    path: &str,
+    config: &RwConfig,
+) -> (DynamicConfigReceiver, Option<(JoinHandle<()>, Sender<()>)>) {
+    if path.is_empty() {
+        return (DynamicConfig::from_config(config).fixed(), None);
+    }
+    let reloader = ConfigReloader::new(path, config.clone());
+    let dynamic_config = reloader.subscribe();
+    let interval = Duration::from_millis(config.server.config_reload_interval_ms as u64);
+    (dynamic_config, Some(reloader.start(interval)))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use super::*;
+
+    fn write_config(file: &mut tempfile::NamedTempFile, content: &str) {
+        file.as_file().set_len(0).unwrap();
+        file.reopen()
+            .unwrap()
+            .write_all(content.as_bytes())
+            .unwrap();
+    }
+
+    #[test]
+    fn test_reload_config() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        write_config(&mut file, "[streaming]\ncheckpoint_interval_ms = 100\n");
+        let config = RwConfig::init(file.path().to_owned()).unwrap();
+        let mut reloader = ConfigReloader::new(file.path(), config);
+        let rx = reloader.subscribe();
+        assert!(!reloader.reload().unwrap());
+
+        // Only the dynamic knobs take effect.
+        write_config(
+            &mut file,
+            "[streaming]\ncheckpoint_interval_ms = 200\n\
+             [storage]\nblock_size_kb = 1\n\
+             [connector]\nsource_rate_limit_rows_per_sec = 1000\n",
+        );
+        reloader.modified = None;
+        assert!(reloader.reload().unwrap());
+        assert_eq!(rx.borrow().checkpoint_interval_ms, 200);
+        assert_eq!(rx.borrow().source_rate_limit_rows_per_sec, 1000);
+        assert_eq!(reloader.config.streaming.checkpoint_interval_ms, 200);
+        assert_eq!(
+            reloader.config.storage.block_size_kb,
+            RwConfig::default().storage.block_size_kb
+        );
+
+        // Invalid config files are not applied.
+        write_config(
+            &mut file,
+            "[streaming]\ncheckpoint_interval_ms = \"fast\"\n",
+        );
+        reloader.modified = None;
+        assert!(reloader.reload().is_err());
+        assert_eq!(rx.borrow().checkpoint_interval_ms, 200);
+    }
+}
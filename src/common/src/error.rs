@@ -142,6 +142,12 @@ pub enum ErrorCode {
     #[error("End of the stream")]
     Eof,
 
+    #[error("Query cancelled: {0}")]
+    QueryCancelled(String),
+
+    #[error("Permission denied: {0}")]
+    PermissionDenied(String),
+
     #[error("Unknown error: {0}")]
     UnknownError(String),
 }
@@ -312,6 +318,8 @@ impl ErrorCode {
             ErrorCode::UnknownWorker => 24,
             ErrorCode::ConnectorError(_) => 25,
             ErrorCode::InvalidParameterValue(_) => 26,
+            ErrorCode::QueryCancelled(_) => 27,
+            ErrorCode::PermissionDenied(_) => 28,
             ErrorCode::UnknownError(_) => 101,
         }
     }
@@ -18,7 +18,7 @@ use std::ops::{Add, Sub};
 
 use byteorder::{BigEndian, WriteBytesExt};
 use bytes::BytesMut;
-use num_traits::{CheckedAdd, CheckedSub};
+use num_traits::{CheckedAdd, CheckedSub, ToPrimitive};
 use risingwave_pb::data::IntervalUnit as IntervalUnitProto;
 use serde::{Deserialize, Serialize};
 use smallvec::SmallVec;
@@ -174,6 +174,44 @@ impl IntervalUnit {
         Some(IntervalUnit { months, days, ms })
     }
 
+    /// Multiple [`IntervalUnit`] by a float with overflow check. Like PostgreSQL, the fractional
+    /// part of months is carried over to days, assuming 30 days per month, and that of days is
+    /// carried over to milliseconds.
+    pub fn checked_mul_float<F>(&self, rhs: F) -> Option<Self>
+    where
+        F: TryInto<OrderedF64>,
+    {
+        let rhs = rhs.try_into().ok()?.0;
+        self.checked_scale(|v| v * rhs)
+    }
+
+    /// Divides [`IntervalUnit`] by a float with overflow check. Returns [`None`] when `rhs` is
+    /// zero. See [`IntervalUnit::checked_mul_float`] for how the fractional parts are handled.
+    pub fn checked_div_float<F>(&self, rhs: F) -> Option<Self>
+    where
+        F: TryInto<OrderedF64>,
+    {
+        let rhs = rhs.try_into().ok()?.0;
+        if rhs == 0.0 {
+            return None;
+        }
+        self.checked_scale(|v| v / rhs)
+    }
+
+    fn checked_scale(&self, scale: impl Fn(f64) -> f64) -> Option<Self> {
+        const DAYS_PER_MONTH: f64 = 30.0;
+        const MS_PER_DAY: f64 = 24.0 * 3600.0 * 1000.0;
+
+        let months = scale(self.months as f64);
+        let days = scale(self.days as f64) + months.fract() * DAYS_PER_MONTH;
+        let ms = scale(self.ms as f64) + days.fract() * MS_PER_DAY;
+        Some(IntervalUnit {
+            months: months.trunc().to_i32()?,
+            days: days.trunc().to_i32()?,
+            ms: ms.round().to_i64()?,
+        })
+    }
+
     /// Performs an exact division, returns [`None`] if for any unit, lhs % rhs != 0.
     pub fn exact_div(&self, rhs: &Self) -> Option<i64> {
         let mut res = None;
@@ -336,4 +374,27 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_mul_div_float() {
+        let interval = IntervalUnit::new(1, 1, 3600 * 1000);
+        assert_eq!(
+            interval.checked_mul_float(OrderedF64::from(2.0)),
+            Some(IntervalUnit::new(2, 2, 2 * 3600 * 1000))
+        );
+        // 1.5 months is 1 month and 15 days, and 1.5 days is 1 day and 12 hours.
+        assert_eq!(
+            interval.checked_mul_float(OrderedF64::from(1.5)),
+            Some(IntervalUnit::new(1, 16, 13 * 3600 * 1000 + 1800 * 1000))
+        );
+        assert_eq!(
+            interval.checked_div_float(OrderedF64::from(2.0)),
+            Some(IntervalUnit::new(0, 15, 12 * 3600 * 1000 + 1800 * 1000))
+        );
+        assert_eq!(interval.checked_div_float(OrderedF64::from(0.0)), None);
+        assert_eq!(
+            IntervalUnit::from_month(i32::MAX).checked_mul_float(OrderedF64::from(2.0)),
+            None
+        );
+    }
 }
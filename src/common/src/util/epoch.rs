@@ -55,6 +55,11 @@ impl Epoch {
         }
     }
 
+    /// Returns the first epoch of the physical time, in milliseconds since the singularity date.
+    pub fn from_physical_time(time: u64) -> Self {
+        Self(time << EPOCH_PHYSICAL_SHIFT_BITS)
+    }
+
     pub fn physical_time(&self) -> u64 {
         self.0 >> EPOCH_PHYSICAL_SHIFT_BITS
     }
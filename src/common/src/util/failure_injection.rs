@@ -0,0 +1,242 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Injects failures into RPCs and object store operations, so that recovery, barrier collection
+//! and retries can be tested against specific faults.
+//!
+//! Failures are configured by the `RW_FAILURE_INJECTION` environment variable, a comma-separated
+//! list of rules `<operation>:<action>:<probability>[:<delay_ms>]`:
+//! - `<operation>` is the name of an operation, e.g. `stream.inject_barrier` or
+//!   `object_store.upload`. A name ending with `*` matches all operations with the prefix.
+//! - `<action>` is `drop` to fail the operation without performing it, `delay` to wait `<delay_ms>`
+//!   milliseconds before performing it, or `duplicate` to perform it twice.
+//! - `<probability>` is the chance in `[0, 1]` that the rule is applied to an operation.
+//!
+//! For example,
+//! `RW_FAILURE_INJECTION=stream.inject_barrier:drop:0.01,object_store.*:delay:0.5:200`.
+
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use madsim::rand::{thread_rng, Rng};
+
+use crate::error::ErrorCode::InternalError;
+use crate::error::{Result, RwError};
+
+pub const FAILURE_INJECTION_ENV: &str = "RW_FAILURE_INJECTION";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FailureAction {
+    Drop,
+    Delay(Duration),
+    Duplicate,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FailureRule {
+    /// The name of the operations to inject failures into, or a prefix of them ending with `*`.
+    pub operation: String,
+    pub action: FailureAction,
+    pub probability: f64,
+}
+
+impl FailureRule {
+    fn matches(&self, operation: &str) -> bool {
+        match self.operation.strip_suffix('*') {
+            Some(prefix) => operation.starts_with(prefix),
+            None => operation == self.operation,
+        }
+    }
+}
+
+impl FromStr for FailureRule {
+    type Err = RwError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || RwError::from(InternalError(format!("invalid failure rule: {}", s)));
+        let parts = s.split(':').map(str::trim).collect::<Vec<_>>();
+        if parts.len() < 3 {
+            return Err(invalid());
+        }
+        let action = match (parts[1], parts.get(3)) {
+            ("drop", None) => FailureAction::Drop,
+            ("duplicate", None) => FailureAction::Duplicate,
+            ("delay", Some(delay_ms)) => FailureAction::Delay(Duration::from_millis(
+                delay_ms.parse().map_err(|_| invalid())?,
+            )),
+            _ => return Err(invalid()),
+        };
+        let probability: f64 = parts[2].parse().map_err(|_| invalid())?;
+        if parts.len() > 4 || !(0.0..=1.0).contains(&probability) {
+            return Err(invalid());
+        }
+        Ok(Self {
+            operation: parts[0].to_string(),
+            action,
+            probability,
+        })
+    }
+}
+
+/// The failures to inject into an operation.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Injection {
+    /// Fails the operation without performing it.
+    pub drop: bool,
+    /// Waits before performing the operation.
+    pub delay: Duration,
+    /// Performs the operation twice.
+    pub duplicate: bool,
+}
+
+impl Injection {
+    /// Waits for the injected delay.
+    pub async fn delay(&self) {
+        if !self.delay.is_zero() {
+            tokio::time::sleep(self.delay).await;
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct FailureInjector {
+    rules: Vec<FailureRule>,
+}
+
+pub type FailureInjectorRef = Arc<FailureInjector>;
+
+impl FailureInjector {
+    pub fn new(rules: Vec<FailureRule>) -> Self {
+        Self { rules }
+    }
+
+    /// Creates an injector from `RW_FAILURE_INJECTION`, or returns `None` if it's not set.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the rules are invalid, so that a misspelled rule won't silently disable a test.
+    pub fn from_env() -> Option<FailureInjectorRef> {
+        let spec = std::env::var(FAILURE_INJECTION_ENV).ok()?;
+        let injector: Self = spec
+            .parse()
+            .unwrap_or_else(|e| panic!("invalid {}: {}", FAILURE_INJECTION_ENV, e));
+        tracing::warn!("failure injection is enabled: {:?}", injector.rules);
+        Some(Arc::new(injector))
+    }
+
+    /// Decides the failures to inject into `operation`. Each rule matching the operation is
+    /// applied independently with its probability.
+    pub fn decide(&self, operation: &str) -> Injection {
+        let mut injection = Injection::default();
+        let mut rng = thread_rng();
+        for rule in self.rules.iter().filter(|rule| rule.matches(operation)) {
+            if !rng.gen_bool(rule.probability) {
+                continue;
+            }
+            match rule.action {
+                FailureAction::Drop => injection.drop = true,
+                FailureAction::Delay(delay) => injection.delay += delay,
+                FailureAction::Duplicate => injection.duplicate = true,
+            }
+        }
+        if injection != Injection::default() {
+            tracing::info!("inject failures into {}: {:?}", operation, injection);
+        }
+        injection
+    }
+}
+
+impl FromStr for FailureInjector {
+    type Err = RwError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let rules = s
+            .split(',')
+            .filter(|rule| !rule.trim().is_empty())
+            .map(str::parse)
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self::new(rules))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_failure_rules() {
+        let injector: FailureInjector =
+            "stream.inject_barrier:drop:0.1, object_store.*:delay:1:200,object_store.upload:duplicate:0"
+                .parse()
+                .unwrap();
+        assert_eq!(
+            injector.rules,
+            vec![
+                FailureRule {
+                    operation: "stream.inject_barrier".to_string(),
+                    action: FailureAction::Drop,
+                    probability: 0.1,
+                },
+                FailureRule {
+                    operation: "object_store.*".to_string(),
+                    action: FailureAction::Delay(Duration::from_millis(200)),
+                    probability: 1.0,
+                },
+                FailureRule {
+                    operation: "object_store.upload".to_string(),
+                    action: FailureAction::Duplicate,
+                    probability: 0.0,
+                },
+            ]
+        );
+
+        for invalid in [
+            "stream.inject_barrier:drop",
+            "stream.inject_barrier:drop:2",
+            "stream.inject_barrier:delay:0.5",
+            "stream.inject_barrier:drop:0.5:100",
+            "stream.inject_barrier:crash:0.5",
+        ] {
+            assert!(invalid.parse::<FailureInjector>().is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_decide_failures() {
+        let injector: FailureInjector =
+            "object_store.*:delay:1:200,object_store.upload:duplicate:1,stream.*:drop:0"
+                .parse()
+                .unwrap();
+        assert_eq!(
+            injector.decide("object_store.upload"),
+            Injection {
+                drop: false,
+                delay: Duration::from_millis(200),
+                duplicate: true,
+            }
+        );
+        assert_eq!(
+            injector.decide("object_store.read"),
+            Injection {
+                delay: Duration::from_millis(200),
+                ..Default::default()
+            }
+        );
+        assert_eq!(
+            injector.decide("stream.inject_barrier"),
+            Injection::default()
+        );
+    }
+}
@@ -25,6 +25,7 @@ pub mod chunk_coalesce;
 pub mod compress;
 pub mod encoding_for_comparison;
 pub mod env_var;
+pub mod failure_injection;
 pub mod hash_util;
 pub mod ordered;
 pub mod prost;
@@ -31,6 +31,8 @@
 #[macro_use]
 extern crate log;
 
+pub mod memory_management;
+pub mod observer;
 pub mod rpc;
 pub mod server;
 
@@ -70,17 +72,37 @@ pub struct ComputeNodeOpts {
     /// Enable reporting tracing information to jaeger
     #[clap(long)]
     pub enable_jaeger_tracing: bool,
+
+    /// Serve CPU and heap profiles of the compute node on demand, e.g. by `risectl compute
+    /// profile`
+    #[clap(long)]
+    pub enable_profiling: bool,
 }
 
 use std::future::Future;
 use std::pin::Pin;
 
+use tokio::sync::oneshot::Sender;
+use tokio::task::JoinHandle;
+
 use crate::server::compute_node_serve;
 
 /// Start compute node
 pub fn start(opts: ComputeNodeOpts) -> Pin<Box<dyn Future<Output = ()> + Send>> {
     // WARNING: don't change the function signature. Making it `async fn` will cause
     // slow compile in release mode.
+    Box::pin(async move {
+        let (join_handle, _shutdown_send) = serve(opts).await;
+        join_handle.await.unwrap();
+    })
+}
+
+/// Starts the compute node in the current process. Returns once the node is registered to and
+/// activated by meta, with the handle to wait for it and the sender to shut it down. Used to embed
+/// the compute node into another process, e.g. the playground.
+pub fn serve(
+    opts: ComputeNodeOpts,
+) -> Pin<Box<dyn Future<Output = (JoinHandle<()>, Sender<()>)> + Send>> {
     Box::pin(async move {
         tracing::info!("meta address: {}", opts.meta_address.clone());
 
@@ -95,8 +117,6 @@ pub fn start(opts: ComputeNodeOpts) -> Pin<Box<dyn Future<Output = ()> + Send>>
             .unwrap();
         tracing::info!("Client address is {}", client_address);
 
-        let (join_handle, _shutdown_send) =
-            compute_node_serve(listen_address, client_address, opts).await;
-        join_handle.await.unwrap();
+        compute_node_serve(listen_address, client_address, opts).await
     })
 }
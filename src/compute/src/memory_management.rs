@@ -0,0 +1,301 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Memory control of the whole compute node.
+//!
+//! Each component limits its own memory, e.g. the capacity of the block cache and the quota of
+//! batch tasks. On top of that, the [`MemoryManager`] watches the memory allocated by the process,
+//! and applies more and more aggressive [`MemoryControlPolicy`]s as it approaches the budget of
+//! the node.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use prometheus::core::{AtomicI64, GenericGauge, GenericGaugeVec};
+use prometheus::{
+    register_int_gauge_vec_with_registry, register_int_gauge_with_registry, Registry,
+};
+use risingwave_batch::task::BatchManager;
+use risingwave_common::config::DynamicConfigReceiver;
+use risingwave_storage::hummock::HummockStorage;
+use tokio::sync::oneshot::Sender;
+use tokio::task::JoinHandle;
+
+/// Fractions of the memory budget above which the policies are applied.
+const SHRINK_CACHE_WATERMARK: f64 = 0.7;
+const HOLD_BATCH_TASKS_WATERMARK: f64 = 0.85;
+const FLUSH_SHARED_BUFFER_WATERMARK: f64 = 0.95;
+
+/// The policies taking effect at a level of memory usage, in priority order. A policy also
+/// applies the ones before it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MemoryControlPolicy {
+    /// Memory is sufficient.
+    None = 0,
+    /// Shrinks the block cache and the meta cache to half of their configured capacities.
+    ShrinkCache = 1,
+    /// Queues new batch tasks until the memory usage drops.
+    HoldBatchTasks = 2,
+    /// Flushes the shared buffer of the state store to the object store.
+    FlushSharedBuffer = 3,
+}
+
+impl MemoryControlPolicy {
+    /// Decides the policy by the fraction of the memory budget in use.
+    pub fn from_usage(used_bytes: usize, total_bytes: usize) -> Self {
+        let ratio = used_bytes as f64 / total_bytes as f64;
+        if ratio >= FLUSH_SHARED_BUFFER_WATERMARK {
+            Self::FlushSharedBuffer
+        } else if ratio >= HOLD_BATCH_TASKS_WATERMARK {
+            Self::HoldBatchTasks
+        } else if ratio >= SHRINK_CACHE_WATERMARK {
+            Self::ShrinkCache
+        } else {
+            Self::None
+        }
+    }
+}
+
+pub struct MemoryControlMetrics {
+    /// Memory used by each component, and by the whole process as `total`.
+    pub memory_usage_bytes: GenericGaugeVec<AtomicI64>,
+    pub memory_control_policy: GenericGauge<AtomicI64>,
+}
+
+impl MemoryControlMetrics {
+    pub fn new(registry: Registry) -> Self {
+        let memory_usage_bytes = register_int_gauge_vec_with_registry!(
+            "compute_memory_usage_bytes",
+            "Memory used by each component of the compute node",
+            &["component"],
+            registry
+        )
+        .unwrap();
+
+        let memory_control_policy = register_int_gauge_with_registry!(
+            "compute_memory_control_policy",
+            "Memory control policy in effect on the compute node, higher is more aggressive",
+            registry
+        )
+        .unwrap();
+
+        Self {
+            memory_usage_bytes,
+            memory_control_policy,
+        }
+    }
+}
+
+/// Memory used by the components of the compute node.
+#[derive(Debug, Default)]
+struct MemoryUsage {
+    /// Memory allocated by the process, including the components below and the caches of the
+    /// stream executors.
+    total: usize,
+    block_cache: usize,
+    meta_cache: usize,
+    shared_buffer: usize,
+    batch: usize,
+}
+
+/// Keeps the memory used by the compute node under `total_memory_bytes`. See the module docs.
+pub struct MemoryManager {
+    total_memory_bytes: usize,
+    interval: Duration,
+    hummock: Option<HummockStorage>,
+    batch_mgr: Arc<BatchManager>,
+    /// The configured capacities of the caches, which are restored when memory is sufficient.
+    dynamic_config: DynamicConfigReceiver,
+    metrics: MemoryControlMetrics,
+    policy: MemoryControlPolicy,
+}
+
+impl MemoryManager {
+    pub fn new(
+        total_memory_bytes: usize,
+        interval: Duration,
+        hummock: Option<HummockStorage>,
+        batch_mgr: Arc<BatchManager>,
+        dynamic_config: DynamicConfigReceiver,
+        metrics: MemoryControlMetrics,
+    ) -> Self {
+        Self {
+            total_memory_bytes,
+            interval,
+            hummock,
+            batch_mgr,
+            dynamic_config,
+            metrics,
+            policy: MemoryControlPolicy::None,
+        }
+    }
+
+    /// Checks the memory usage every interval and applies the policies, until shut down.
+    pub fn start(mut self) -> (JoinHandle<()>, Sender<()>) {
+        let (shutdown_tx, mut shutdown_rx) = tokio::sync::oneshot::channel();
+        let join_handle = tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(self.interval);
+            loop {
+                tokio::select! {
+                    _ = ticker.tick() => {},
+                    _ = &mut shutdown_rx => {
+                        tracing::info!("Memory manager is shutting down");
+                        return;
+                    }
+                }
+                self.tick().await;
+            }
+        });
+        (join_handle, shutdown_tx)
+    }
+
+    async fn tick(&mut self) {
+        let usage = self.memory_usage();
+        for (component, bytes) in [
+            ("total", usage.total),
+            ("block_cache", usage.block_cache),
+            ("meta_cache", usage.meta_cache),
+            ("shared_buffer", usage.shared_buffer),
+            ("batch", usage.batch),
+        ] {
+            self.metrics
+                .memory_usage_bytes
+                .with_label_values(&[component])
+                .set(bytes as i64);
+        }
+
+        let policy = MemoryControlPolicy::from_usage(usage.total, self.total_memory_bytes);
+        if policy != self.policy {
+            tracing::info!(
+                "memory control policy changed from {:?} to {:?}, usage: {:?}",
+                self.policy,
+                policy,
+                usage
+            );
+            self.metrics.memory_control_policy.set(policy as i64);
+        }
+        self.apply(policy).await;
+        self.policy = policy;
+    }
+
+    fn memory_usage(&self) -> MemoryUsage {
+        let mut usage = MemoryUsage {
+            total: jemalloc_allocated_bytes().unwrap_or_default(),
+            batch: self.batch_mgr.mem_tracker().bytes_used(),
+            ..Default::default()
+        };
+        if let Some(hummock) = &self.hummock {
+            let sstable_store = hummock.sstable_store();
+            usage.block_cache = sstable_store.get_block_cache().size();
+            usage.meta_cache = sstable_store.get_meta_cache().get_memory_usage();
+            usage.shared_buffer = hummock.local_version_manager().get_shared_buffer_size();
+        }
+        // Without jemalloc stats, the usage of the tracked components is the best we know.
+        usage.total = usage
+            .total
+            .max(usage.block_cache + usage.meta_cache + usage.shared_buffer + usage.batch);
+        usage
+    }
+
+    async fn apply(&self, policy: MemoryControlPolicy) {
+        self.batch_mgr
+            .set_memory_pressure(policy >= MemoryControlPolicy::HoldBatchTasks);
+
+        let hummock = match &self.hummock {
+            Some(hummock) => hummock,
+            None => return,
+        };
+
+        let shrink = policy >= MemoryControlPolicy::ShrinkCache;
+        if shrink != (self.policy >= MemoryControlPolicy::ShrinkCache) {
+            let (block_cache_capacity_mb, meta_cache_capacity_mb) = {
+                let config = self.dynamic_config.borrow();
+                (
+                    config.block_cache_capacity_mb,
+                    config.meta_cache_capacity_mb,
+                )
+            };
+            let divisor = if shrink { 2 } else { 1 };
+            hummock.sstable_store().set_cache_capacity(
+                block_cache_capacity_mb * (1 << 20) / divisor,
+                meta_cache_capacity_mb * (1 << 20) / divisor,
+            );
+        }
+
+        if policy >= MemoryControlPolicy::FlushSharedBuffer {
+            if let Err(err) = hummock.local_version_manager().flush_shared_buffer().await {
+                tracing::warn!(
+                    "Failed to flush shared buffer under memory pressure: {:?}",
+                    err
+                );
+            }
+        }
+    }
+}
+
+/// Returns the bytes allocated by the process, if jemalloc is the global allocator.
+fn jemalloc_allocated_bytes() -> Option<usize> {
+    use std::ffi::c_void;
+    use std::ptr::null_mut;
+
+    use tikv_jemalloc_sys::mallctl;
+
+    // SAFETY: the names are nul-terminated, and the values have the types documented by jemalloc.
+    unsafe {
+        // The stats are cached by jemalloc, and refreshed by writing to `epoch`.
+        let mut epoch: u64 = 1;
+        mallctl(
+            b"epoch\0".as_ptr() as *const _,
+            null_mut(),
+            null_mut(),
+            &mut epoch as *mut u64 as *mut c_void,
+            std::mem::size_of::<u64>(),
+        );
+        let mut allocated: usize = 0;
+        let mut len = std::mem::size_of::<usize>();
+        let ret = mallctl(
+            b"stats.allocated\0".as_ptr() as *const _,
+            &mut allocated as *mut usize as *mut c_void,
+            &mut len,
+            null_mut(),
+            0,
+        );
+        (ret == 0).then(|| allocated)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MemoryControlPolicy;
+
+    #[test]
+    fn test_memory_control_policy() {
+        assert_eq!(
+            MemoryControlPolicy::from_usage(50, 100),
+            MemoryControlPolicy::None
+        );
+        assert_eq!(
+            MemoryControlPolicy::from_usage(70, 100),
+            MemoryControlPolicy::ShrinkCache
+        );
+        assert_eq!(
+            MemoryControlPolicy::from_usage(90, 100),
+            MemoryControlPolicy::HoldBatchTasks
+        );
+        assert_eq!(
+            MemoryControlPolicy::from_usage(120, 100),
+            MemoryControlPolicy::FlushSharedBuffer
+        );
+    }
+}
@@ -0,0 +1,147 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use risingwave_common::error::{ErrorCode, Result};
+use risingwave_common::util::addr::HostAddr;
+use risingwave_pb::meta::subscribe_response::Info;
+use risingwave_pb::meta::{MetaSnapshot, SubscribeResponse};
+use risingwave_rpc_client::{MetaClient, NotificationStream};
+use risingwave_storage::hummock::local_version_manager::LocalVersionManager;
+use tokio::task::JoinHandle;
+
+/// `ComputeObserverManager` is used to update the hummock version of the compute node based on
+/// notification from meta, which pins new versions for the node and pushes them.
+/// Call `start` to spawn a new asynchronous task which receives meta's notification.
+pub struct ComputeObserverManager {
+    rx: Box<dyn NotificationStream>,
+    meta_client: MetaClient,
+    addr: HostAddr,
+    local_version_manager: Arc<LocalVersionManager>,
+}
+
+const RE_SUBSCRIBE_RETRY_INTERVAL: Duration = Duration::from_millis(100);
+
+impl ComputeObserverManager {
+    pub async fn new(
+        meta_client: MetaClient,
+        addr: HostAddr,
+        local_version_manager: Arc<LocalVersionManager>,
+    ) -> Self {
+        let rx = meta_client
+            .subscribe_compute_node(&addr, local_version_manager.get_pinned_version().id())
+            .await
+            .unwrap();
+        Self {
+            rx,
+            meta_client,
+            addr,
+            local_version_manager,
+        }
+    }
+
+    fn handle_snapshot_notification(&self, resp: SubscribeResponse) -> Result<()> {
+        match resp.info {
+            Some(Info::Snapshot(MetaSnapshot {
+                hummock_version: Some(hummock_version),
+                ..
+            })) => {
+                // The version is not unpinned even if it's not newer than the local one, since
+                // it may be referenced by readers.
+                self.local_version_manager
+                    .try_update_pinned_version(hummock_version);
+                Ok(())
+            }
+            _ => Err(ErrorCode::InternalError(format!(
+                "the first notify should be compute snapshot, but get {:?}",
+                resp
+            ))
+            .into()),
+        }
+    }
+
+    fn handle_notification(&self, resp: SubscribeResponse) {
+        match resp.info {
+            Some(Info::HummockVersion(hummock_version)) => {
+                self.local_version_manager
+                    .try_update_pinned_version(hummock_version);
+            }
+            Some(Info::LogFilter(filter)) => {
+                if let Err(e) = risingwave_logging::set_log_filter(&filter.directives) {
+                    tracing::warn!("failed to change the log filter: {}", e);
+                }
+            }
+            Some(_) => panic!("receive an unsupported notify {:?}", resp),
+            None => {}
+        }
+    }
+
+    /// `start` is used to spawn a new asynchronous task which receives meta's notification and
+    /// updates the hummock version. The version is no longer polled from meta after that.
+    pub async fn start(mut self) -> Result<JoinHandle<()>> {
+        let first_resp = self.rx.next().await?.ok_or_else(|| {
+            ErrorCode::InternalError(
+                "ComputeObserverManager start failed, Stream of notification terminated at the \
+                 start."
+                    .to_string(),
+            )
+        })?;
+        self.handle_snapshot_notification(first_resp)?;
+        self.local_version_manager.stop_pin_worker();
+        let handle = tokio::spawn(async move {
+            loop {
+                match self.rx.next().await {
+                    Ok(Some(resp)) => self.handle_notification(resp),
+                    Ok(None) => {
+                        tracing::error!("Stream of notification terminated.");
+                        self.re_subscribe().await;
+                    }
+                    Err(err) => {
+                        tracing::error!("Failed to receive notification: {}", err);
+                        self.re_subscribe().await;
+                    }
+                }
+            }
+        });
+        Ok(handle)
+    }
+
+    /// `re_subscribe` is used to re-subscribe to the meta's notification. Meta sends the current
+    /// version as a snapshot again, and unpins the versions pushed but not received.
+    async fn re_subscribe(&mut self) {
+        loop {
+            let last_pinned = self.local_version_manager.get_pinned_version().id();
+            match self
+                .meta_client
+                .subscribe_compute_node(&self.addr, last_pinned)
+                .await
+            {
+                Ok(rx) => {
+                    tracing::debug!("re-subscribe success");
+                    self.rx = rx;
+                    if let Ok(Some(snapshot_resp)) = self.rx.next().await {
+                        self.handle_snapshot_notification(snapshot_resp)
+                            .expect("handle snapshot notification failed after re-subscribe");
+                        break;
+                    }
+                }
+                Err(_) => {
+                    tokio::time::sleep(RE_SUBSCRIBE_RETRY_INTERVAL).await;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,80 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use risingwave_common::catalog::TableId;
+use risingwave_pb::stream_service::changelog_service_server::ChangelogService;
+use risingwave_pb::stream_service::{ChangelogBatch, ChangelogFormat, SubscribeChangelogRequest};
+use risingwave_stream::task::{ChangelogFormatter, ChangelogManagerRef};
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_stream::StreamExt;
+use tonic::{Request, Response, Status};
+
+/// Serves the changelog of materialized views with actors on this compute node. A subscriber
+/// should subscribe to all the compute nodes with actors of the materialized view, and merge the
+/// batches of the same epoch.
+#[derive(Clone)]
+pub struct ChangelogServiceImpl {
+    changelog_manager: ChangelogManagerRef,
+}
+
+impl ChangelogServiceImpl {
+    pub fn new(changelog_manager: ChangelogManagerRef) -> Self {
+        Self { changelog_manager }
+    }
+}
+
+#[async_trait::async_trait]
+impl ChangelogService for ChangelogServiceImpl {
+    type SubscribeChangelogStream = std::pin::Pin<
+        Box<dyn futures::Stream<Item = std::result::Result<ChangelogBatch, Status>> + Send>,
+    >;
+
+    async fn subscribe_changelog(
+        &self,
+        request: Request<SubscribeChangelogRequest>,
+    ) -> std::result::Result<Response<Self::SubscribeChangelogStream>, Status> {
+        let req = request.into_inner();
+        let table_id = TableId::new(req.table_id);
+        if req.format() == ChangelogFormat::Retract && !req.dedup_by_pk {
+            let rx = self
+                .changelog_manager
+                .subscribe(table_id, req.start_after_epoch)?;
+            let stream = ReceiverStream::new(rx).map(|batch| Ok(batch.as_ref().clone()));
+            return Ok(Response::new(Box::pin(stream)));
+        }
+
+        // The schema is needed to convert the changes.
+        let schema = self.changelog_manager.schema(table_id).ok_or_else(|| {
+            Status::failed_precondition(format!(
+                "materialized view {} has no actors on this compute node",
+                table_id
+            ))
+        })?;
+        let formatter = ChangelogFormatter::new(table_id, req.format(), req.dedup_by_pk, schema);
+        let rx = self
+            .changelog_manager
+            .subscribe(table_id, req.start_after_epoch)?;
+        // The subscription ends after the first batch that can't be formatted.
+        let stream =
+            futures::StreamExt::scan(ReceiverStream::new(rx), false, move |failed, batch| {
+                let item = (!*failed).then(|| {
+                    let formatted = formatter.format(&batch).map_err(Status::from);
+                    *failed = formatted.is_err();
+                    formatted
+                });
+                futures::future::ready(item)
+            });
+        Ok(Response::new(Box::pin(stream)))
+    }
+}
@@ -112,6 +112,7 @@ impl ExchangeServiceImpl {
     ) -> Result<Response<<Self as ExchangeService>::GetStreamStream>> {
         let (tx, rx) = tokio::sync::mpsc::channel(EXCHANGE_BUFFER_SIZE);
         let metrics = self.metrics.clone();
+        let stats = self.stream_mgr.channel_stats(up_down_ids);
         tracing::trace!(target: "events::compute::exchange", peer_addr = %peer_addr, "serve stream exchange RPC");
         tokio::spawn(async move {
             let up_actor_id = up_down_ids.0.to_string();
@@ -122,6 +123,7 @@ impl ExchangeServiceImpl {
                     // the sender is closed, we close the receiver and stop forwarding message
                     None => break,
                     Some(msg) => {
+                        stats.on_recv();
                         let res = match msg.to_protobuf() {
                             Ok(stream_msg) => Ok(GetStreamResponse {
                                 message: Some(stream_msg),
@@ -0,0 +1,248 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::ffi::{c_void, CString};
+use std::os::raw::c_char;
+use std::ptr::null_mut;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use prost::Message;
+use risingwave_pb::monitor_service::monitor_service_server::MonitorService;
+use risingwave_pb::monitor_service::{
+    ProfileFormat, ProfileType, ProfilingRequest, ProfilingResponse,
+};
+use tikv_jemalloc_sys::mallctl;
+use tonic::{Request, Response, Status};
+
+/// Size of the parts a profile is streamed back in.
+const PROFILE_CHUNK_SIZE: usize = 1 << 20;
+
+/// Frequency of sampling the stacks of CPU profiles, in Hz.
+const CPU_PROFILE_FREQUENCY: i32 = 99;
+
+/// The longest duration of a profile, so that the overhead of profiling isn't kept for long by
+/// mistake.
+const MAX_PROFILE_DURATION: Duration = Duration::from_secs(600);
+
+/// Takes CPU and heap profiles of the compute node on demand. Profiling has overhead and exposes
+/// the internals of the node, so it's only served if the node is started with
+/// `--enable-profiling`.
+#[derive(Clone)]
+pub struct MonitorServiceImpl {
+    enable_profiling: bool,
+    /// Set while a profile is being taken. Only one profile is taken at a time.
+    profiling: Arc<AtomicBool>,
+}
+
+impl MonitorServiceImpl {
+    pub fn new(enable_profiling: bool) -> Self {
+        Self {
+            enable_profiling,
+            profiling: Arc::new(AtomicBool::new(false)),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl MonitorService for MonitorServiceImpl {
+    type ProfilingStream = std::pin::Pin<
+        Box<dyn futures::Stream<Item = std::result::Result<ProfilingResponse, Status>> + Send>,
+    >;
+
+    #[cfg_attr(coverage, no_coverage)]
+    async fn profiling(
+        &self,
+        request: Request<ProfilingRequest>,
+    ) -> std::result::Result<Response<Self::ProfilingStream>, Status> {
+        if !self.enable_profiling {
+            return Err(Status::permission_denied(
+                "profiling is disabled, start the compute node with --enable-profiling",
+            ));
+        }
+        let req = request.into_inner();
+        let duration = Duration::from_secs(req.duration_sec);
+        if duration.is_zero() || duration > MAX_PROFILE_DURATION {
+            return Err(Status::invalid_argument(format!(
+                "the duration of a profile should be within 1 to {} seconds",
+                MAX_PROFILE_DURATION.as_secs()
+            )));
+        }
+        let (profile_type, format) = (req.r#type(), req.format());
+        if profile_type == ProfileType::Heap && format != ProfileFormat::Pprof {
+            return Err(Status::invalid_argument(
+                "heap profiles are only available in the pprof format",
+            ));
+        }
+        if self.profiling.swap(true, Ordering::SeqCst) {
+            return Err(Status::failed_precondition(
+                "another profile is being taken on this compute node",
+            ));
+        }
+
+        tracing::info!("taking a {:?} profile for {:?}", profile_type, duration);
+        // The profile is taken on a blocking thread, which resets the flag even if the request is
+        // cancelled in the meantime.
+        let profiling = self.profiling.clone();
+        let profile = tokio::task::spawn_blocking(move || {
+            let profile = match profile_type {
+                ProfileType::Cpu => cpu_profile(duration, format),
+                ProfileType::Heap => heap_profile(duration),
+            };
+            profiling.store(false, Ordering::SeqCst);
+            profile
+        })
+        .await
+        .map_err(|e| Status::internal(e.to_string()))??;
+
+        let parts = profile
+            .chunks(PROFILE_CHUNK_SIZE)
+            .map(|data| {
+                Ok(ProfilingResponse {
+                    data: data.to_vec(),
+                })
+            })
+            .collect::<Vec<_>>();
+        Ok(Response::new(Box::pin(futures::stream::iter(parts))))
+    }
+}
+
+/// Samples the stacks of all threads for `duration`.
+fn cpu_profile(duration: Duration, format: ProfileFormat) -> Result<Vec<u8>, Status> {
+    let internal = |e: pprof::Error| Status::internal(format!("failed to profile CPU: {}", e));
+    let guard = pprof::ProfilerGuard::new(CPU_PROFILE_FREQUENCY).map_err(internal)?;
+    std::thread::sleep(duration);
+    let report = guard.report().build().map_err(internal)?;
+
+    let mut body = Vec::new();
+    match format {
+        ProfileFormat::Pprof => report
+            .pprof()
+            .map_err(internal)?
+            .encode(&mut body)
+            .map_err(|e| Status::internal(e.to_string()))?,
+        ProfileFormat::Flamegraph => report.flamegraph(&mut body).map_err(internal)?,
+    }
+    Ok(body)
+}
+
+/// Samples the allocations of jemalloc for `duration`, and dumps the ones still alive at the end.
+fn heap_profile(duration: Duration) -> Result<Vec<u8>, Status> {
+    if !jemalloc_read::<bool>(b"opt.prof\0").unwrap_or(false) {
+        return Err(Status::failed_precondition(
+            "heap profiling is not enabled by jemalloc, start the compute node with \
+             _RJEM_MALLOC_CONF=prof:true",
+        ));
+    }
+    let internal = |ret| Status::internal(format!("failed to profile heap: mallctl error {}", ret));
+    let was_active = jemalloc_read::<bool>(b"prof.active\0").map_err(internal)?;
+    jemalloc_write(b"prof.active\0", true).map_err(internal)?;
+    std::thread::sleep(duration);
+
+    let path = std::env::temp_dir().join(format!(
+        "risingwave-heap-{}-{}.prof",
+        std::process::id(),
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_millis()
+    ));
+    let path_c = CString::new(path.to_string_lossy().as_bytes()).unwrap();
+    let dumped = jemalloc_write(b"prof.dump\0", path_c.as_ptr());
+    jemalloc_write(b"prof.active\0", was_active).map_err(internal)?;
+    dumped.map_err(internal)?;
+
+    let profile = std::fs::read(&path).map_err(|e| Status::internal(e.to_string()))?;
+    if let Err(e) = std::fs::remove_file(&path) {
+        tracing::warn!(
+            "failed to remove the heap profile {}: {}",
+            path.display(),
+            e
+        );
+    }
+    Ok(profile)
+}
+
+/// Reads the jemalloc control `name`, returning the error code of `mallctl` on failure.
+fn jemalloc_read<T: Default>(name: &[u8]) -> Result<T, i32> {
+    let mut value = T::default();
+    let mut len = std::mem::size_of::<T>();
+    // SAFETY: `name` is nul-terminated, and `T` is the type of the control documented by jemalloc.
+    let ret = unsafe {
+        mallctl(
+            name.as_ptr() as *const c_char,
+            &mut value as *mut T as *mut c_void,
+            &mut len,
+            null_mut(),
+            0,
+        )
+    };
+    if ret == 0 {
+        Ok(value)
+    } else {
+        Err(ret)
+    }
+}
+
+/// Writes `value` to the jemalloc control `name`, returning the error code of `mallctl` on
+/// failure.
+fn jemalloc_write<T>(name: &[u8], mut value: T) -> Result<(), i32> {
+    // SAFETY: `name` is nul-terminated, and `T` is the type of the control documented by jemalloc.
+    let ret = unsafe {
+        mallctl(
+            name.as_ptr() as *const c_char,
+            null_mut(),
+            null_mut(),
+            &mut value as *mut T as *mut c_void,
+            std::mem::size_of::<T>(),
+        )
+    };
+    if ret == 0 {
+        Ok(())
+    } else {
+        Err(ret)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_profiling_rejected() {
+        let service = MonitorServiceImpl::new(false);
+        let err = service
+            .profiling(Request::new(ProfilingRequest {
+                duration_sec: 1,
+                ..Default::default()
+            }))
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(err.code(), tonic::Code::PermissionDenied);
+
+        let service = MonitorServiceImpl::new(true);
+        let err = service
+            .profiling(Request::new(ProfilingRequest {
+                r#type: ProfileType::Heap as i32,
+                format: ProfileFormat::Flamegraph as i32,
+                duration_sec: 1,
+            }))
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(err.code(), tonic::Code::InvalidArgument);
+    }
+}
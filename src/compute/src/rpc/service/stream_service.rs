@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use std::sync::Arc;
+use std::time::Duration;
 
 use itertools::Itertools;
 use risingwave_common::catalog::TableId;
@@ -20,6 +21,7 @@ use risingwave_common::error::{tonic_err, Result as RwResult};
 use risingwave_pb::catalog::Source;
 use risingwave_pb::stream_service::stream_service_server::StreamService;
 use risingwave_pb::stream_service::*;
+use risingwave_source::validate_stream_source;
 use risingwave_stream::executor::{Barrier, Epoch};
 use risingwave_stream::task::{LocalStreamManager, StreamEnvironment};
 use tonic::{Request, Response, Status};
@@ -116,10 +118,13 @@ impl StreamService for StreamServiceImpl {
         let req = request.into_inner();
         let epoch = req.epoch.unwrap();
         self.mgr
-            .stop_all_actors(Epoch {
-                curr: epoch.curr,
-                prev: epoch.prev,
-            })
+            .stop_all_actors(
+                Epoch {
+                    curr: epoch.curr,
+                    prev: epoch.prev,
+                },
+                req.committed_epoch,
+            )
             .await?;
         Ok(Response::new(ForceStopActorsResponse {
             request_id: req.request_id,
@@ -142,7 +147,7 @@ impl StreamService for StreamServiceImpl {
                 &barrier,
                 req.actor_ids_to_send,
                 req.actor_ids_to_collect,
-                true,
+                req.checkpoint,
             )
             .await?;
 
@@ -151,6 +156,8 @@ impl StreamService for StreamServiceImpl {
             status: None,
             create_mview_progress: collect_result.create_mview_progress,
             sycned_sstables: collect_result.synced_sstables,
+            source_rows: collect_result.source_rows,
+            last_collected_actor_id: collect_result.last_collected_actor_id.unwrap_or_default(),
         }))
     }
 
@@ -166,6 +173,28 @@ impl StreamService for StreamServiceImpl {
         Ok(Response::new(CreateSourceResponse { status: None }))
     }
 
+    #[cfg_attr(coverage, no_coverage)]
+    async fn validate_source(
+        &self,
+        request: Request<ValidateSourceRequest>,
+    ) -> Result<Response<ValidateSourceResponse>, Status> {
+        use risingwave_pb::catalog::source::Info;
+
+        let source = request.into_inner().source.unwrap();
+        let error = match source.get_info().map_err(tonic_err)? {
+            Info::StreamSource(info) => validate_stream_source(info).await.err(),
+            Info::TableSource(_) => None,
+        };
+        if let Some(error) = &error {
+            tracing::warn!(name = %source.name, ?error, "source validation failed");
+        }
+
+        Ok(Response::new(ValidateSourceResponse {
+            status: None,
+            error,
+        }))
+    }
+
     #[cfg_attr(coverage, no_coverage)]
     async fn sync_sources(
         &self,
@@ -200,6 +229,65 @@ impl StreamService for StreamServiceImpl {
 
         Ok(Response::new(DropSourceResponse { status: None }))
     }
+
+    #[cfg_attr(coverage, no_coverage)]
+    async fn add_source_columns(
+        &self,
+        request: Request<AddSourceColumnsRequest>,
+    ) -> Result<Response<AddSourceColumnsResponse>, Status> {
+        let req = request.into_inner();
+        let id = TableId::new(req.source_id); // TODO: use SourceId instead
+        let columns = req.columns.into_iter().map(Into::into).collect_vec();
+
+        self.env
+            .source_manager()
+            .add_columns(&id, columns)
+            .map_err(tonic_err)?;
+
+        tracing::debug!(id = %id, "add source columns");
+
+        Ok(Response::new(AddSourceColumnsResponse { status: None }))
+    }
+
+    #[cfg_attr(coverage, no_coverage)]
+    async fn get_backpressure(
+        &self,
+        request: Request<GetBackpressureRequest>,
+    ) -> Result<Response<GetBackpressureResponse>, Status> {
+        let limit = request.into_inner().limit as usize;
+        let mut all_stats = self.mgr.channel_stats_by_backpressure();
+        if limit > 0 {
+            all_stats.truncate(limit);
+        }
+        let channels = all_stats
+            .into_iter()
+            .map(
+                |((up_actor_id, down_actor_id), stats)| ChannelBackpressure {
+                    up_actor_id,
+                    down_actor_id,
+                    buffered_messages: stats.buffered_messages(),
+                    blocked_time_ns: stats.blocked_time().as_nanos() as u64,
+                },
+            )
+            .collect();
+        Ok(Response::new(GetBackpressureResponse { channels }))
+    }
+
+    #[cfg_attr(coverage, no_coverage)]
+    async fn profile_actors(
+        &self,
+        request: Request<ProfileActorsRequest>,
+    ) -> Result<Response<ProfileActorsResponse>, Status> {
+        let req = request.into_inner();
+        let actors = self
+            .mgr
+            .profile_actors(&req.actor_ids, Duration::from_millis(req.duration_ms))
+            .await
+            .into_iter()
+            .map(|(actor_id, stats)| stats.to_protobuf(actor_id))
+            .collect();
+        Ok(Response::new(ProfileActorsResponse { actors }))
+    }
 }
 
 impl StreamServiceImpl {
@@ -18,11 +18,14 @@ use std::time::Duration;
 
 use risingwave_batch::executor::monitor::BatchMetrics;
 use risingwave_batch::rpc::service::task_service::BatchServiceImpl;
-use risingwave_batch::task::{BatchEnvironment, BatchManager};
-use risingwave_common::config::ComputeNodeConfig;
+use risingwave_batch::task::{BatchEnvironment, BatchManager, BatchQuota};
+use risingwave_common::config::{start_config_reloader, DynamicConfigReceiver, RwConfig};
 use risingwave_common::service::MetricsManager;
 use risingwave_common::util::addr::HostAddr;
+use risingwave_connector::monitor::SourceMetrics;
 use risingwave_pb::common::WorkerType;
+use risingwave_pb::monitor_service::monitor_service_server::MonitorServiceServer;
+use risingwave_pb::stream_service::changelog_service_server::ChangelogServiceServer;
 use risingwave_pb::stream_service::stream_service_server::StreamServiceServer;
 use risingwave_pb::task_service::exchange_service_server::ExchangeServiceServer;
 use risingwave_pb::task_service::task_service_server::TaskServiceServer;
@@ -31,6 +34,7 @@ use risingwave_source::MemSourceManager;
 use risingwave_storage::hummock::compaction_executor::CompactionExecutor;
 use risingwave_storage::hummock::compactor::Compactor;
 use risingwave_storage::hummock::hummock_meta_client::MonitoredHummockMetaClient;
+use risingwave_storage::hummock::sstable_store::SstableStoreRef;
 use risingwave_storage::monitor::{
     monitor_cache, HummockMetrics, ObjectStoreMetrics, StateStoreMetrics,
 };
@@ -40,15 +44,77 @@ use risingwave_stream::task::{LocalStreamManager, StreamEnvironment};
 use tokio::sync::oneshot::Sender;
 use tokio::task::JoinHandle;
 
+use crate::memory_management::{MemoryControlMetrics, MemoryManager};
+use crate::observer::observer_manager::ComputeObserverManager;
+use crate::rpc::service::changelog_service::ChangelogServiceImpl;
 use crate::rpc::service::exchange_metrics::ExchangeServiceMetrics;
 use crate::rpc::service::exchange_service::ExchangeServiceImpl;
+use crate::rpc::service::monitor_service::MonitorServiceImpl;
 use crate::rpc::service::stream_service::StreamServiceImpl;
 use crate::ComputeNodeOpts;
 
-fn load_config(opts: &ComputeNodeOpts) -> ComputeNodeConfig {
+fn load_config(opts: &ComputeNodeOpts) -> RwConfig {
     risingwave_common::config::load_config(&opts.config_path)
 }
 
+/// Resizes the caches of the state store when their capacities in the config file are changed.
+fn start_cache_resizer(
+    sstable_store: SstableStoreRef,
+    mut dynamic_config: DynamicConfigReceiver,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        while dynamic_config.changed().await.is_ok() {
+            let (block_cache_capacity_mb, meta_cache_capacity_mb) = {
+                let config = dynamic_config.borrow();
+                (
+                    config.block_cache_capacity_mb,
+                    config.meta_cache_capacity_mb,
+                )
+            };
+            sstable_store.set_cache_capacity(
+                block_cache_capacity_mb * (1 << 20),
+                meta_cache_capacity_mb * (1 << 20),
+            );
+        }
+    })
+}
+
+/// Waits for ctrl-c, or SIGTERM on unix, which is sent by the orchestrator to stop the node.
+async fn wait_for_shutdown_signal() {
+    #[cfg(all(unix, not(madsim)))]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        let mut sigterm = signal(SignalKind::terminate()).unwrap();
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => {},
+            _ = sigterm.recv() => {},
+        }
+    }
+    #[cfg(not(all(unix, not(madsim))))]
+    {
+        let _ = tokio::signal::ctrl_c().await;
+    }
+}
+
+/// Leaves the cluster without losing data: new batch tasks are rejected, meta makes a final
+/// checkpoint with all sources paused, and the shared buffer is flushed to the object store.
+async fn graceful_shutdown(
+    batch_mgr: &BatchManager,
+    stream_mgr: &LocalStreamManager,
+    meta_client: &MetaClient,
+    client_addr: &HostAddr,
+) {
+    info!("shutting down compute node {}", client_addr);
+    batch_mgr.stop_accepting_tasks();
+    if let Err(err) = meta_client.drain_worker(client_addr).await {
+        tracing::warn!("Failed to drain worker: {:?}", err);
+    }
+    if let Err(err) = stream_mgr.sync_all().await {
+        tracing::warn!("Failed to flush shared buffer: {:?}", err);
+    }
+}
+
 fn get_compile_mode() -> &'static str {
     if cfg!(debug_assertions) {
         "debug"
@@ -84,12 +150,15 @@ pub async fn compute_node_serve(
         meta_client.clone(),
         Duration::from_millis(config.server.heartbeat_interval_ms as u64),
     )];
+    let (dynamic_config, config_reloader) = start_config_reloader(&opts.config_path, &config);
+    sub_tasks.extend(config_reloader);
     // Initialize the metrics subsystem.
     let registry = prometheus::Registry::new();
     let hummock_metrics = Arc::new(HummockMetrics::new(registry.clone()));
     let streaming_metrics = Arc::new(StreamingMetrics::new(registry.clone()));
     let batch_metrics = Arc::new(BatchMetrics::new(registry.clone()));
     let exchange_srv_metrics = Arc::new(ExchangeServiceMetrics::new(registry.clone()));
+    let source_metrics = Arc::new(SourceMetrics::new(registry.clone()));
 
     // Initialize state store.
     let storage_config = Arc::new(config.storage.clone());
@@ -108,7 +177,7 @@ pub async fn compute_node_serve(
     )
     .await
     .unwrap();
-    if let StateStoreImpl::HummockStateStore(storage) = &state_store {
+    if let Some(storage) = state_store.hummock_storage() {
         if opts.state_store.starts_with("hummock+memory")
             || opts.state_store.starts_with("hummock+disk")
             || storage_config.disable_remote_compactor
@@ -118,24 +187,50 @@ pub async fn compute_node_serve(
             let (handle, shutdown_sender) = Compactor::start_compactor(
                 storage_config,
                 hummock_meta_client,
-                storage.inner().sstable_store(),
+                storage.sstable_store(),
                 state_store_metrics.clone(),
                 Some(Arc::new(CompactionExecutor::new(Some(1)))),
             );
             sub_tasks.push((handle, shutdown_sender));
         }
-        monitor_cache(storage.inner().sstable_store(), &registry).unwrap();
+        monitor_cache(storage.sstable_store(), &registry).unwrap();
+        start_cache_resizer(storage.sstable_store(), dynamic_config.clone());
+
+        // New hummock versions are pushed by meta after subscription.
+        let observer_manager = ComputeObserverManager::new(
+            meta_client.clone(),
+            client_addr.clone(),
+            storage.local_version_manager().clone(),
+        )
+        .await;
+        observer_manager.start().await.unwrap();
     }
 
     // Initialize the managers.
-    let batch_mgr = Arc::new(BatchManager::new());
+    let batch_mgr = Arc::new(BatchManager::with_quota(
+        BatchQuota::from_config(&config.batch),
+        batch_metrics.clone(),
+    ));
     let stream_mgr = Arc::new(LocalStreamManager::new(
         client_addr.clone(),
         state_store.clone(),
         streaming_metrics.clone(),
         config.streaming.clone(),
     ));
-    let source_mgr = Arc::new(MemSourceManager::new(worker_id));
+    let source_mgr = Arc::new(MemSourceManager::new(worker_id).with_metrics(source_metrics));
+
+    // Start the memory manager if the memory budget of the node is configured.
+    if config.server.total_memory_mb > 0 {
+        let memory_mgr = MemoryManager::new(
+            config.server.total_memory_mb << 20,
+            Duration::from_millis(config.server.memory_control_interval_ms as u64),
+            state_store.hummock_storage().cloned(),
+            batch_mgr.clone(),
+            dynamic_config.clone(),
+            MemoryControlMetrics::new(registry.clone()),
+        );
+        sub_tasks.push(memory_mgr.start());
+    }
 
     // Initialize batch environment.
     let batch_config = Arc::new(config.batch.clone());
@@ -155,6 +250,7 @@ pub async fn compute_node_serve(
         source_mgr,
         client_addr.clone(),
         stream_config,
+        dynamic_config,
         worker_id,
         state_store,
     );
@@ -162,29 +258,42 @@ pub async fn compute_node_serve(
     // Boot the runtime gRPC services.
     let batch_srv = BatchServiceImpl::new(batch_mgr.clone(), batch_env);
     let exchange_srv =
-        ExchangeServiceImpl::new(batch_mgr, stream_mgr.clone(), exchange_srv_metrics);
-    let stream_srv = StreamServiceImpl::new(stream_mgr, stream_env.clone());
+        ExchangeServiceImpl::new(batch_mgr.clone(), stream_mgr.clone(), exchange_srv_metrics);
+    let changelog_srv = ChangelogServiceImpl::new(stream_env.changelog_manager().clone());
+    let stream_srv = StreamServiceImpl::new(stream_mgr.clone(), stream_env.clone());
+    let monitor_srv = MonitorServiceImpl::new(opts.enable_profiling);
 
+    let shutdown_meta_client = meta_client.clone();
+    let shutdown_addr = client_addr.clone();
     let (shutdown_send, mut shutdown_recv) = tokio::sync::oneshot::channel::<()>();
     let join_handle = tokio::spawn(async move {
         tonic::transport::Server::builder()
             .add_service(TaskServiceServer::new(batch_srv))
             .add_service(ExchangeServiceServer::new(exchange_srv))
             .add_service(StreamServiceServer::new(stream_srv))
+            .add_service(ChangelogServiceServer::new(changelog_srv))
+            .add_service(MonitorServiceServer::new(monitor_srv))
             .serve_with_shutdown(listen_addr, async move {
                 tokio::select! {
-                    _ = tokio::signal::ctrl_c() => {},
-                    _ = &mut shutdown_recv => {
-                        for (join_handle, shutdown_sender) in sub_tasks {
-                            if let Err(err) = shutdown_sender.send(()) {
-                                tracing::warn!("Failed to send shutdown: {:?}", err);
-                                continue;
-                            }
-                            if let Err(err) = join_handle.await {
-                                tracing::warn!("Failed to join shutdown: {:?}", err);
-                            }
-                        }
+                    _ = wait_for_shutdown_signal() => {
+                        graceful_shutdown(
+                            &batch_mgr,
+                            &stream_mgr,
+                            &shutdown_meta_client,
+                            &shutdown_addr,
+                        )
+                        .await;
                     },
+                    _ = &mut shutdown_recv => {},
+                }
+                for (join_handle, shutdown_sender) in sub_tasks {
+                    if let Err(err) = shutdown_sender.send(()) {
+                        tracing::warn!("Failed to send shutdown: {:?}", err);
+                        continue;
+                    }
+                    if let Err(err) = join_handle.await {
+                        tracing::warn!("Failed to join shutdown: {:?}", err);
+                    }
                 }
             })
             .await
@@ -0,0 +1,165 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use anyhow::{anyhow, Result};
+use aws_config::default_provider::credentials::DefaultCredentialsChain;
+use aws_config::default_provider::region::DefaultRegionChain;
+use aws_config::sts::AssumeRoleProvider;
+use aws_smithy_http::endpoint::Endpoint;
+use aws_types::credentials::SharedCredentialsProvider;
+use aws_types::region::Region;
+use http::Uri;
+
+/// The authentication and endpoint options of a connector accessing AWS services, e.g. the
+/// Kinesis and S3 sources. Each connector takes them from its `WITH` options under its own prefix.
+///
+/// The credentials are resolved in the following order:
+/// - Static credentials if `access_key` and `secret_key` are given.
+/// - The named `profile` in the shared config and credentials files.
+/// - The default chain, which covers the environment variables, the default profile, the web
+///   identity token (IRSA on EKS) and the ECS or EC2 instance role.
+///
+/// If `arn` is given, the resolved credentials are used to assume the role through STS, with the
+/// optional `external_id`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AwsAuthProps {
+    /// Falls back to the default region chain if not given.
+    pub region: Option<String>,
+    /// Overrides the endpoint of the service, e.g. for `LocalStack` or a VPC endpoint.
+    pub endpoint: Option<String>,
+    pub access_key: Option<String>,
+    pub secret_key: Option<String>,
+    pub session_token: Option<String>,
+    pub profile: Option<String>,
+    pub arn: Option<String>,
+    pub external_id: Option<String>,
+}
+
+impl AwsAuthProps {
+    /// Builds the shared config to create the clients of AWS services from.
+    pub async fn build_config(&self) -> Result<aws_types::SdkConfig> {
+        let region = self.build_region().await;
+        let mut credentials_provider = self.build_credentials_provider(region.clone()).await?;
+
+        if let Some(arn) = &self.arn {
+            let mut role = AssumeRoleProvider::builder(arn).session_name("RisingWave");
+            if let Some(region) = &region {
+                role = role.region(region.clone());
+            }
+            if let Some(external_id) = &self.external_id {
+                role = role.external_id(external_id);
+            }
+            credentials_provider = SharedCredentialsProvider::new(role.build(credentials_provider));
+        }
+
+        let mut config_loader = aws_config::from_env()
+            .region(region)
+            .credentials_provider(credentials_provider);
+        if let Some(endpoint) = &self.endpoint {
+            let uri = endpoint
+                .parse::<Uri>()
+                .map_err(|e| anyhow!("invalid endpoint {}: {}", endpoint, e))?;
+            config_loader = config_loader.endpoint_resolver(Endpoint::immutable(uri));
+        }
+        Ok(config_loader.load().await)
+    }
+
+    async fn build_region(&self) -> Option<Region> {
+        if let Some(region) = &self.region {
+            return Some(Region::new(region.clone()));
+        }
+        let mut region_chain = DefaultRegionChain::builder();
+        if let Some(profile) = &self.profile {
+            region_chain = region_chain.profile_name(profile);
+        }
+        region_chain.build().region().await
+    }
+
+    async fn build_credentials_provider(
+        &self,
+        region: Option<Region>,
+    ) -> Result<SharedCredentialsProvider> {
+        match (&self.access_key, &self.secret_key) {
+            (Some(access_key), Some(secret_key)) => Ok(SharedCredentialsProvider::new(
+                aws_types::Credentials::from_keys(
+                    access_key,
+                    secret_key,
+                    self.session_token.clone(),
+                ),
+            )),
+            (None, None) => {
+                let mut credentials_chain = DefaultCredentialsChain::builder().region(region);
+                if let Some(profile) = &self.profile {
+                    credentials_chain = credentials_chain.profile_name(profile);
+                }
+                Ok(SharedCredentialsProvider::new(
+                    credentials_chain.build().await,
+                ))
+            }
+            _ => Err(anyhow!(
+                "both access key and secret key should be provided or not provided at the same \
+                 time"
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use aws_types::credentials::ProvideCredentials;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_static_credentials() {
+        let props = AwsAuthProps {
+            region: Some("us-east-1".to_string()),
+            endpoint: Some("http://127.0.0.1:4566".to_string()),
+            access_key: Some("access".to_string()),
+            secret_key: Some("secret".to_string()),
+            session_token: Some("token".to_string()),
+            ..Default::default()
+        };
+        let config = props.build_config().await.unwrap();
+        assert_eq!(config.region(), Some(&Region::new("us-east-1")));
+        let credentials = config
+            .credentials_provider()
+            .unwrap()
+            .provide_credentials()
+            .await
+            .unwrap();
+        assert_eq!(credentials.access_key_id(), "access");
+        assert_eq!(credentials.secret_access_key(), "secret");
+        assert_eq!(credentials.session_token(), Some("token"));
+    }
+
+    #[tokio::test]
+    async fn test_invalid_props() {
+        let props = AwsAuthProps {
+            region: Some("us-east-1".to_string()),
+            access_key: Some("access".to_string()),
+            ..Default::default()
+        };
+        assert!(props.build_config().await.is_err());
+
+        let props = AwsAuthProps {
+            region: Some("us-east-1".to_string()),
+            endpoint: Some("not a uri".to_string()),
+            access_key: Some("access".to_string()),
+            secret_key: Some("secret".to_string()),
+            ..Default::default()
+        };
+        assert!(props.build_config().await.is_err());
+    }
+}
@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use std::collections::HashMap;
+use std::sync::Arc;
 
 use anyhow::{anyhow, Result};
 use async_trait::async_trait;
@@ -21,23 +22,14 @@ use enum_as_inner::EnumAsInner;
 use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 
-use crate::datagen::{
-    DatagenProperties, DatagenSplit, DatagenSplitEnumerator, DatagenSplitReader, DATAGEN_CONNECTOR,
-};
 use crate::dummy_connector::DummySplitReader;
 use crate::filesystem::s3::{S3Properties, S3_CONNECTOR};
-use crate::kafka::enumerator::KafkaSplitEnumerator;
-use crate::kafka::source::KafkaSplitReader;
-use crate::kafka::{KafkaProperties, KafkaSplit, KAFKA_CONNECTOR};
-use crate::kinesis::enumerator::client::KinesisSplitEnumerator;
-use crate::kinesis::source::reader::KinesisMultiSplitReader;
-use crate::kinesis::split::KinesisSplit;
-use crate::kinesis::{KinesisProperties, KINESIS_CONNECTOR};
-use crate::nexmark::source::reader::NexmarkSplitReader;
-use crate::nexmark::{NexmarkProperties, NexmarkSplit, NexmarkSplitEnumerator, NEXMARK_CONNECTOR};
-use crate::pulsar::source::reader::PulsarSplitReader;
-use crate::pulsar::{PulsarProperties, PulsarSplit, PulsarSplitEnumerator, PULSAR_CONNECTOR};
-use crate::{impl_connector_properties, impl_split, impl_split_enumerator, impl_split_reader};
+use crate::monitor::SourceMetrics;
+use crate::property::extract_properties;
+use crate::{
+    for_all_connectors, impl_connector_properties, impl_split, impl_split_enumerator,
+    impl_split_reader,
+};
 
 /// [`SplitEnumerator`] fetches the split metadata from the external source service.
 /// NOTE: It runs in the meta server, so probably it should be moved to the `meta` crate.
@@ -48,6 +40,21 @@ pub trait SplitEnumerator: Sized {
 
     async fn new(properties: Self::Properties) -> Result<Self>;
     async fn list_splits(&mut self) -> Result<Vec<Self::Split>>;
+
+    /// Checks that the upstream is reachable with the properties and has the splits to read, e.g.
+    /// the subscribed topic or stream exists.
+    async fn validate(&mut self) -> Result<()> {
+        if self.list_splits().await?.is_empty() {
+            return Err(anyhow!("no split found in the upstream"));
+        }
+        Ok(())
+    }
+
+    /// Lists the splits to read from the earliest offsets to the latest ones at the time of the
+    /// call, for a batch read of the source. The splits with nothing to read are omitted.
+    async fn list_bounded_splits(&mut self) -> Result<Vec<Self::Split>> {
+        Err(anyhow!("the connector doesn't support batch reads"))
+    }
 }
 
 /// [`SplitReader`] is an abstraction of the external connector read interface,
@@ -63,84 +70,18 @@ pub trait SplitReader: Sized {
         properties: Self::Properties,
         state: ConnectorState,
         columns: Option<Vec<Column>>,
+        metrics: Arc<SourceMetrics>,
     ) -> Result<Self>;
     async fn next(&mut self) -> Result<Option<Vec<SourceMessage>>>;
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, EnumAsInner, PartialEq, Hash)]
-pub enum SplitImpl {
-    Kafka(KafkaSplit),
-    Pulsar(PulsarSplit),
-    Kinesis(KinesisSplit),
-    Nexmark(NexmarkSplit),
-    Datagen(DatagenSplit),
-}
+for_all_connectors! { impl_connector_properties }
 
-pub enum SplitReaderImpl {
-    Kinesis(Box<KinesisMultiSplitReader>),
-    Kafka(Box<KafkaSplitReader>),
-    Dummy(Box<DummySplitReader>),
-    Nexmark(Box<NexmarkSplitReader>),
-    Pulsar(Box<PulsarSplitReader>),
-    Datagen(Box<DatagenSplitReader>),
-}
+for_all_connectors! { impl_split_enumerator }
 
-pub enum SplitEnumeratorImpl {
-    Kafka(KafkaSplitEnumerator),
-    Pulsar(PulsarSplitEnumerator),
-    Kinesis(KinesisSplitEnumerator),
-    Nexmark(NexmarkSplitEnumerator),
-    Datagen(DatagenSplitEnumerator),
-}
+for_all_connectors! { impl_split }
 
-#[derive(Clone, Debug, Deserialize)]
-pub enum ConnectorProperties {
-    Kafka(KafkaProperties),
-    Pulsar(PulsarProperties),
-    Kinesis(KinesisProperties),
-    Nexmark(Box<NexmarkProperties>),
-    Datagen(DatagenProperties),
-    S3(S3Properties),
-    Dummy(()),
-}
-
-impl_connector_properties! {
-    [ ] ,
-    { Kafka, KAFKA_CONNECTOR },
-    { Pulsar, PULSAR_CONNECTOR },
-    { Kinesis, KINESIS_CONNECTOR },
-    { Nexmark, NEXMARK_CONNECTOR },
-    { Datagen, DATAGEN_CONNECTOR },
-    { S3, S3_CONNECTOR }
-}
-
-impl_split_enumerator! {
-    [ ] ,
-    { Kafka, KafkaSplitEnumerator },
-    { Pulsar, PulsarSplitEnumerator },
-    { Kinesis, KinesisSplitEnumerator },
-    { Nexmark, NexmarkSplitEnumerator },
-    { Datagen, DatagenSplitEnumerator }
-}
-
-impl_split! {
-    [ ] ,
-    { Kafka, KAFKA_CONNECTOR, KafkaSplit },
-    { Pulsar, PULSAR_CONNECTOR, PulsarSplit },
-    { Kinesis, KINESIS_CONNECTOR, KinesisSplit },
-    { Nexmark, NEXMARK_CONNECTOR, NexmarkSplit },
-    { Datagen, DATAGEN_CONNECTOR, DatagenSplit }
-}
-
-impl_split_reader! {
-    [ ] ,
-    { Kafka, KafkaSplitReader },
-    { Pulsar, PulsarSplitReader },
-    { Kinesis, KinesisMultiSplitReader },
-    { Nexmark, NexmarkSplitReader },
-    { Datagen, DatagenSplitReader },
-    { Dummy, DummySplitReader }
-}
+for_all_connectors! { impl_split_reader }
 
 pub type DataType = risingwave_common::types::DataType;
 
@@ -157,6 +98,11 @@ pub struct SourceMessage {
     pub payload: Option<Bytes>,
     pub offset: String,
     pub split_id: String,
+    /// The key of the message, if the source has keys.
+    pub key: Option<Bytes>,
+    /// Milliseconds since the Unix epoch when the message was produced or appended to the source,
+    /// if known.
+    pub timestamp: Option<i64>,
 }
 
 /// The metadata of a split.
@@ -175,6 +121,7 @@ pub type ConnectorState = Option<Vec<SplitImpl>>;
 mod tests {
     #[allow(unused_imports)]
     use super::*;
+    use crate::kafka::KafkaSplit;
 
     #[test]
     fn test_split_impl_get_fn() -> Result<()> {
@@ -23,12 +23,14 @@ use serde::Deserialize;
 pub use source::*;
 pub use split::*;
 
+use crate::property::{PropertyDesc, PropertySchema, PropertyType};
+
 pub const DATAGEN_CONNECTOR: &str = "datagen";
 #[derive(Clone, Debug, Deserialize)]
 pub struct DatagenProperties {
     /// split_num means data source partition
     #[serde(rename = "datagen.split.num")]
-    pub split_num: Option<String>,
+    pub split_num: Option<i32>,
 
     /// default_rows_per_second =10
     /// when the split_num = 3 and default_rows_per_second =10
@@ -37,7 +39,7 @@ pub struct DatagenProperties {
         rename = "datagen.rows.per.second",
         default = "default_rows_per_second"
     )]
-    pub rows_per_second: String,
+    pub rows_per_second: u64,
 
     /// Some connector options of the datagen source's fields
     /// for example: create datagen source with column v1 int, v2 float
@@ -51,6 +53,13 @@ pub struct DatagenProperties {
     fields: HashMap<String, String>,
 }
 
-fn default_rows_per_second() -> String {
-    "10".to_string()
+fn default_rows_per_second() -> u64 {
+    10
+}
+
+impl PropertySchema for DatagenProperties {
+    const PROPERTIES: &'static [PropertyDesc] = &[
+        PropertyDesc::new("datagen.split.num", PropertyType::Int),
+        PropertyDesc::new("datagen.rows.per.second", PropertyType::Int),
+    ];
 }
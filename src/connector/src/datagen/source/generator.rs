@@ -73,6 +73,8 @@ impl DatagenEventGenerator {
                 payload: Some(Bytes::from(value.to_string())),
                 offset: (self.events_so_far + i).to_string(),
                 split_id: self.split_id.clone(),
+                key: None,
+                timestamp: None,
             };
             generated_count += 1;
             res.push(msg);
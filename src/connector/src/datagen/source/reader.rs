@@ -13,14 +13,16 @@
 // limitations under the License.
 
 use std::collections::HashMap;
+use std::sync::Arc;
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use async_trait::async_trait;
 
 use super::field_generator::FieldGeneratorImpl;
 use super::generator::DatagenEventGenerator;
 use crate::datagen::source::SEQUENCE_FIELD_KIND;
 use crate::datagen::{DatagenProperties, DatagenSplit};
+use crate::monitor::SourceMetrics;
 use crate::{Column, ConnectorState, DataType, SourceMessage, SplitImpl, SplitReader};
 
 const KAFKA_MAX_FETCH_MESSAGES: usize = 1024;
@@ -38,6 +40,7 @@ impl SplitReader for DatagenSplitReader {
         properties: DatagenProperties,
         state: ConnectorState,
         columns: Option<Vec<Column>>,
+        _metrics: Arc<SourceMetrics>,
     ) -> Result<Self>
     where
         Self: Sized,
@@ -63,14 +66,18 @@ impl SplitReader for DatagenSplitReader {
         let split_index = assigned_split.split_index as u64;
         let split_num = assigned_split.split_num as u64;
 
-        let rows_per_second = properties.rows_per_second.parse::<u64>()?;
+        let rows_per_second = properties.rows_per_second;
         let fields_option_map = properties.fields;
         let mut fields_map = HashMap::<String, FieldGeneratorImpl>::new();
 
         // check columns
-        assert!(columns.as_ref().is_some());
-        let columns = columns.unwrap();
-        assert!(columns.len() > 1);
+        let columns =
+            columns.ok_or_else(|| anyhow!("datagen requires the columns of the source"))?;
+        if columns.len() <= 1 {
+            return Err(anyhow!(
+                "datagen requires at least one column besides the row id"
+            ));
+        }
         let columns = &columns[1..];
 
         // parse field connector option to build FieldGeneratorImpl
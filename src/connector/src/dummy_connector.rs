@@ -12,10 +12,13 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::sync::Arc;
+
 use anyhow::Result;
 use async_trait::async_trait;
 use futures::future;
 
+use crate::monitor::SourceMetrics;
 use crate::{Column, ConnectorState, SourceMessage, SplitReader};
 
 /// [`DummySplitReader`] is a placeholder for source executor that is assigned no split. It will
@@ -31,6 +34,7 @@ impl SplitReader for DummySplitReader {
         _properties: Self::Properties,
         _state: ConnectorState,
         _columns: Option<Vec<Column>>,
+        _metrics: Arc<SourceMetrics>,
     ) -> Result<Self> {
         Ok(Self {})
     }
@@ -17,6 +17,9 @@ mod source;
 
 use serde::Deserialize;
 
+use crate::aws_auth::AwsAuthProps;
+use crate::property::{PropertyDesc, PropertySchema, PropertyType};
+
 pub const S3_CONNECTOR: &str = "s3";
 
 #[derive(Clone, Debug, Deserialize)]
@@ -33,4 +36,46 @@ pub struct S3Properties {
     pub access: String,
     #[serde(rename = "s3.credentials.secret", default)]
     pub secret: String,
+    #[serde(rename = "s3.credentials.session_token", default)]
+    pub session_token: Option<String>,
+    #[serde(rename = "s3.credentials.profile", default)]
+    pub profile: Option<String>,
+    #[serde(rename = "s3.assumerole.arn", default)]
+    pub assume_role_arn: Option<String>,
+    #[serde(rename = "s3.assumerole.external_id", default)]
+    pub assume_role_external_id: Option<String>,
+    #[serde(rename = "s3.endpoint_url", default)]
+    pub endpoint_url: Option<String>,
+}
+
+impl PropertySchema for S3Properties {
+    const PROPERTIES: &'static [PropertyDesc] = &[
+        PropertyDesc::new("s3.region_name", PropertyType::String).required(),
+        PropertyDesc::new("s3.bucket_name", PropertyType::String).required(),
+        PropertyDesc::new("sqs_queue_name", PropertyType::String).required(),
+        PropertyDesc::new("match_pattern", PropertyType::String),
+        PropertyDesc::new("s3.credentials.access", PropertyType::String),
+        PropertyDesc::new("s3.credentials.secret", PropertyType::String).secret(),
+        PropertyDesc::new("s3.credentials.session_token", PropertyType::String).secret(),
+        PropertyDesc::new("s3.credentials.profile", PropertyType::String),
+        PropertyDesc::new("s3.assumerole.arn", PropertyType::String),
+        PropertyDesc::new("s3.assumerole.external_id", PropertyType::String),
+        PropertyDesc::new("s3.endpoint_url", PropertyType::String),
+    ];
+}
+
+impl S3Properties {
+    pub fn aws_auth_props(&self) -> AwsAuthProps {
+        let non_empty = |s: &String| (!s.is_empty()).then(|| s.clone());
+        AwsAuthProps {
+            region: Some(self.region_name.clone()),
+            endpoint: self.endpoint_url.clone(),
+            access_key: non_empty(&self.access),
+            secret_key: non_empty(&self.secret),
+            session_token: self.session_token.clone(),
+            profile: self.profile.clone(),
+            arn: self.assume_role_arn.clone(),
+            external_id: self.assume_role_external_id.clone(),
+        }
+    }
 }
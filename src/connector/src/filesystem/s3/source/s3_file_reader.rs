@@ -32,7 +32,7 @@ use tokio_stream::wrappers::ReceiverStream;
 use tokio_util::io;
 use tokio_util::io::ReaderStream;
 
-use crate::aws_utils::{default_conn_config, s3_client, AwsConfigV2, AwsCredentialV2};
+use crate::aws_utils::{default_conn_config, s3_client};
 use crate::base::{SourceMessage, SplitReader};
 use crate::filesystem::file_common::{EntryStat, StatusWatch};
 use crate::filesystem::s3::s3_dir::FileSystemOptError::IllegalS3FilePath;
@@ -40,6 +40,7 @@ use crate::filesystem::s3::s3_dir::{
     AwsCustomConfig, S3SourceBasicConfig, S3SourceConfig, SqsReceiveMsgConfig,
 };
 use crate::filesystem::s3::S3Properties;
+use crate::monitor::SourceMetrics;
 use crate::{Column, ConnectorState, SplitMetaData};
 
 const MAX_CHANNEL_BUFFER_SIZE: usize = 2048;
@@ -302,35 +303,20 @@ impl SplitReader for S3FileReader {
     type Properties = S3Properties;
 
     /// 1. The config include all information about the connection to S3, for example:
-    /// `s3.region_name, s3.bucket_name, s3-dd-storage-notify-queue` and the credential's
-    /// `access_key` and secret. For now, only static credential is supported.
+    /// `s3.region_name, s3.bucket_name, s3-dd-storage-notify-queue` and the
+    /// credentials, see [`crate::aws_auth::AwsAuthProps`] for the supported ones.
     /// 2. The identifier of the State is the Path of S3 - <S3://bucket_name/object_key>
     async fn new(
         props: S3Properties,
         _state: ConnectorState,
         _columns: Option<Vec<Column>>,
+        _metrics: Arc<SourceMetrics>,
     ) -> Result<Self>
     where
         Self: Sized,
     {
+        let shared_config = props.aws_auth_props().build_config().await?;
         let s3_basic_config = S3SourceBasicConfig::from(props);
-        let credential = if s3_basic_config.secret.is_empty() || s3_basic_config.access.is_empty() {
-            AwsCredentialV2::None
-        } else {
-            AwsCredentialV2::Static {
-                access_key: s3_basic_config.clone().access,
-                secret_access: s3_basic_config.clone().secret,
-                session_token: None,
-            }
-        };
-        let aws_config = AwsConfigV2 {
-            region: None,
-            arn: None,
-            credential,
-            endpoint: None,
-        };
-        // TODO: should be use external_id parameter.
-        let shared_config = aws_config.load_config(None).await;
         let s3_source_config = S3SourceConfig {
             basic_config: s3_basic_config.clone(),
             shared_config,
@@ -364,6 +350,8 @@ impl SplitReader for S3FileReader {
                         payload: Some(msg.payload),
                         offset: new_offset.to_string(),
                         split_id: msg_id,
+                        key: None,
+                        timestamp: None,
                     }
                 })
                 .collect_vec(),
@@ -393,6 +381,11 @@ mod test {
             match_pattern: None,
             access: "".to_string(),
             secret: "".to_string(),
+            session_token: None,
+            profile: None,
+            assume_role_arn: None,
+            assume_role_external_id: None,
+            endpoint_url: None,
         }
     }
 
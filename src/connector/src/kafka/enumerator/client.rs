@@ -69,8 +69,7 @@ impl SplitEnumerator for KafkaSplitEnumerator {
             }
         };
 
-        if let Some(s) = properties.time_offset {
-            let time_offset = s.parse::<i64>().map_err(|e| anyhow!(e))?;
+        if let Some(time_offset) = properties.time_offset {
             scan_start_offset = KafkaEnumeratorOffset::Timestamp(time_offset)
         }
 
@@ -111,6 +110,26 @@ impl SplitEnumerator for KafkaSplitEnumerator {
 
         Ok(ret)
     }
+
+    async fn list_bounded_splits(&mut self) -> anyhow::Result<Vec<KafkaSplit>> {
+        let mut splits = vec![];
+        for partition in self.fetch_topic_partition()? {
+            let (low, high) = self.admin_client.fetch_watermarks(
+                self.topic.as_str(),
+                partition,
+                KAFKA_SYNC_CALL_TIMEOUT,
+            )?;
+            if low < high {
+                splits.push(KafkaSplit::new(
+                    partition,
+                    Some(low),
+                    Some(high),
+                    self.topic.clone(),
+                ));
+            }
+        }
+        Ok(splits)
+    }
 }
 
 impl KafkaSplitEnumerator {
@@ -16,6 +16,8 @@ use std::time::Duration;
 
 use serde::Deserialize;
 
+use crate::property::{PropertyDesc, PropertySchema, PropertyType};
+
 pub mod enumerator;
 pub mod source;
 pub mod split;
@@ -38,10 +40,23 @@ pub struct KafkaProperties {
     pub scan_startup_mode: Option<String>,
 
     #[serde(rename = "kafka.time.offset")]
-    pub time_offset: Option<String>,
+    pub time_offset: Option<i64>,
 
     #[serde(rename = "kafka.consumer.group")]
     pub consumer_group: Option<String>,
 }
 
+impl PropertySchema for KafkaProperties {
+    const PROPERTIES: &'static [PropertyDesc] = &[
+        PropertyDesc::new("kafka.brokers", PropertyType::String).required(),
+        PropertyDesc::new("kafka.topic", PropertyType::String).required(),
+        PropertyDesc::new(
+            "kafka.scan.startup.mode",
+            PropertyType::Enum(&["earliest", "latest"]),
+        ),
+        PropertyDesc::new("kafka.time.offset", PropertyType::Int),
+        PropertyDesc::new("kafka.consumer.group", PropertyType::String),
+    ];
+}
+
 const KAFKA_SYNC_CALL_TIMEOUT: Duration = Duration::from_secs(1);
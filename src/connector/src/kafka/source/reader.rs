@@ -21,11 +21,13 @@ use async_trait::async_trait;
 use futures::StreamExt;
 use rdkafka::config::RDKafkaLogLevel;
 use rdkafka::consumer::{Consumer, DefaultConsumerContext, StreamConsumer};
-use rdkafka::{ClientConfig, Offset, TopicPartitionList};
+use rdkafka::error::KafkaError;
+use rdkafka::{ClientConfig, Message, Offset, TopicPartitionList};
 
 use crate::base::{SourceMessage, SplitReader};
 use crate::kafka::split::KafkaSplit;
 use crate::kafka::KafkaProperties;
+use crate::monitor::SourceMetrics;
 use crate::{Column, ConnectorState, SplitImpl};
 
 const KAFKA_MAX_FETCH_MESSAGES: usize = 1024;
@@ -33,6 +35,10 @@ const KAFKA_MAX_FETCH_MESSAGES: usize = 1024;
 pub struct KafkaSplitReader {
     consumer: Arc<StreamConsumer<DefaultConsumerContext>>,
     assigned_splits: HashMap<String, Vec<KafkaSplit>>,
+    /// Whether the splits have stop offsets, i.e. the reader stops at the end of them.
+    bounded: bool,
+    /// The exclusive stop offsets of the partitions not finished yet, if the reader is bounded.
+    stop_offsets: HashMap<i32, i64>,
 }
 
 #[async_trait]
@@ -43,16 +49,28 @@ impl SplitReader for KafkaSplitReader {
         properties: KafkaProperties,
         state: ConnectorState,
         _columns: Option<Vec<Column>>,
+        _metrics: Arc<SourceMetrics>,
     ) -> Result<Self>
     where
         Self: Sized,
     {
         let bootstrap_servers = properties.brokers;
 
+        let stop_offsets: HashMap<i32, i64> = state
+            .iter()
+            .flatten()
+            .filter_map(|split| match split {
+                SplitImpl::Kafka(k) => k.stop_offset.map(|offset| (k.partition, offset)),
+                _ => None,
+            })
+            .collect();
+        let bounded = !stop_offsets.is_empty();
+
         let mut config = ClientConfig::new();
 
-        // disable partition eof
-        config.set("enable.partition.eof", "false");
+        // The end of a partition only matters to a bounded reader, which stops there even if the
+        // stop offset is never seen, e.g. it's a transaction marker.
+        config.set("enable.partition.eof", bounded.to_string());
         config.set("enable.auto.commit", "false");
         config.set("auto.offset.reset", "smallest");
         config.set("bootstrap.servers", bootstrap_servers);
@@ -100,10 +118,16 @@ impl SplitReader for KafkaSplitReader {
         Ok(Self {
             consumer: Arc::new(consumer),
             assigned_splits: HashMap::new(),
+            bounded,
+            stop_offsets,
         })
     }
 
     async fn next(&mut self) -> Result<Option<Vec<SourceMessage>>> {
+        if self.bounded && self.stop_offsets.is_empty() {
+            return Ok(None);
+        }
+
         let mut stream = self
             .consumer
             .stream()
@@ -114,10 +138,30 @@ impl SplitReader for KafkaSplitReader {
             Some(chunk) => chunk,
         };
 
-        chunk
-            .into_iter()
-            .map(|msg| msg.map_err(|e| anyhow!(e)).map(SourceMessage::from))
-            .collect::<Result<Vec<SourceMessage>>>()
-            .map(Some)
+        let mut messages = Vec::with_capacity(chunk.len());
+        for msg in chunk {
+            match msg {
+                Ok(msg) => {
+                    if self.bounded {
+                        let partition = msg.partition();
+                        match self.stop_offsets.get(&partition).copied() {
+                            Some(stop_offset) if msg.offset() < stop_offset => {
+                                if msg.offset() + 1 >= stop_offset {
+                                    self.stop_offsets.remove(&partition);
+                                }
+                            }
+                            // The partition is finished.
+                            _ => continue,
+                        }
+                    }
+                    messages.push(SourceMessage::from(msg));
+                }
+                Err(KafkaError::PartitionEOF(partition)) => {
+                    self.stop_offsets.remove(&partition);
+                }
+                Err(e) => return Err(anyhow!(e)),
+            }
+        }
+        Ok(Some(messages))
     }
 }
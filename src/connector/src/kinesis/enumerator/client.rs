@@ -12,6 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::time::{SystemTime, UNIX_EPOCH};
+
 use anyhow::Result;
 use async_trait::async_trait;
 use aws_sdk_kinesis::model::Shard;
@@ -26,8 +28,6 @@ pub struct KinesisSplitEnumerator {
     client: kinesis_client,
 }
 
-impl KinesisSplitEnumerator {}
-
 #[async_trait]
 impl SplitEnumerator for KinesisSplitEnumerator {
     type Properties = KinesisProperties;
@@ -43,6 +43,40 @@ impl SplitEnumerator for KinesisSplitEnumerator {
     }
 
     async fn list_splits(&mut self) -> Result<Vec<KinesisSplit>> {
+        Ok(self
+            .list_shard_ids()
+            .await?
+            .into_iter()
+            .map(|shard_id| KinesisSplit {
+                shard_id,
+                start_position: KinesisOffset::None,
+                end_position: KinesisOffset::None,
+            })
+            .collect())
+    }
+
+    /// Kinesis has no API to get the latest sequence numbers, so the shards are read until the
+    /// records arrived after the time of the call.
+    async fn list_bounded_splits(&mut self) -> Result<Vec<KinesisSplit>> {
+        let end_millis = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as i64;
+        Ok(self
+            .list_shard_ids()
+            .await?
+            .into_iter()
+            .map(|shard_id| KinesisSplit {
+                shard_id,
+                start_position: KinesisOffset::Earliest,
+                end_position: KinesisOffset::Timestamp(end_millis),
+            })
+            .collect())
+    }
+}
+
+impl KinesisSplitEnumerator {
+    async fn list_shard_ids(&self) -> Result<Vec<String>> {
         let mut next_token: Option<String> = None;
         let mut shard_collect: Vec<Shard> = Vec::new();
 
@@ -71,11 +105,7 @@ impl SplitEnumerator for KinesisSplitEnumerator {
         }
         Ok(shard_collect
             .into_iter()
-            .map(|x| KinesisSplit {
-                shard_id: x.shard_id().unwrap_or_default().to_string(),
-                start_position: KinesisOffset::None,
-                end_position: KinesisOffset::None,
-            })
+            .map(|x| x.shard_id().unwrap_or_default().to_string())
             .collect())
     }
 }
@@ -25,11 +25,20 @@ const KINESIS_ENDPOINT: &str = "kinesis.endpoint";
 const KINESIS_CREDENTIALS_ACCESS_KEY: &str = "kinesis.credentials.access";
 const KINESIS_CREDENTIALS_SECRET_ACCESS_KEY: &str = "kinesis.credentials.secret";
 const KINESIS_CREDENTIALS_SESSION_TOKEN: &str = "kinesis.credentials.session_token";
+const KINESIS_CREDENTIALS_PROFILE: &str = "kinesis.credentials.profile";
 const KINESIS_ASSUMEROLE_ARN: &str = "kinesis.assumerole.arn";
 const KINESIS_ASSUMEROLE_EXTERNAL_ID: &str = "kinesis.assumerole.external_id";
+const KINESIS_SCAN_STARTUP_MODE: &str = "kinesis.scan.startup.mode";
+const KINESIS_SCAN_STARTUP_TIMESTAMP_MILLIS: &str = "kinesis.scan.startup.timestamp_millis";
+const KINESIS_SCAN_STARTUP_SEQUENCE_NUMBER: &str = "kinesis.scan.startup.sequence_number";
 
+use anyhow::{anyhow, Result};
 use serde::Deserialize;
 
+use crate::aws_auth::AwsAuthProps;
+use crate::kinesis::split::KinesisOffset;
+use crate::property::{PropertyDesc, PropertySchema, PropertyType};
+
 pub const KINESIS_CONNECTOR: &str = "kinesis";
 
 #[derive(Clone, Debug, Deserialize)]
@@ -46,8 +55,189 @@ pub struct KinesisProperties {
     pub credentials_secret_access_key: Option<String>,
     #[serde(rename = "kinesis.credentials.session_token")]
     pub session_token: Option<String>,
+    #[serde(rename = "kinesis.credentials.profile")]
+    pub profile: Option<String>,
     #[serde(rename = "kinesis.assumerole.arn")]
     pub assume_role_arn: Option<String>,
     #[serde(rename = "kinesis.assumerole.external_id")]
     pub assume_role_external_id: Option<String>,
+
+    /// Where to start reading the shards without offsets restored from the source state, one of
+    /// `earliest`, `latest`, `timestamp` and `sequence_number`. Defaults to `earliest`.
+    #[serde(rename = "kinesis.scan.startup.mode")]
+    pub scan_startup_mode: Option<String>,
+    /// The timestamp in milliseconds to start reading at, required by the `timestamp` mode.
+    #[serde(rename = "kinesis.scan.startup.timestamp_millis")]
+    pub scan_startup_timestamp_millis: Option<i64>,
+    /// The sequence number to start reading at, required by the `sequence_number` mode.
+    #[serde(rename = "kinesis.scan.startup.sequence_number")]
+    pub scan_startup_sequence_number: Option<String>,
+}
+
+impl PropertySchema for KinesisProperties {
+    const PROPERTIES: &'static [PropertyDesc] = &[
+        PropertyDesc::new(KINESIS_STREAM_NAME, PropertyType::String).required(),
+        PropertyDesc::new(KINESIS_STREAM_REGION, PropertyType::String).required(),
+        PropertyDesc::new(KINESIS_ENDPOINT, PropertyType::String),
+        PropertyDesc::new(KINESIS_CREDENTIALS_ACCESS_KEY, PropertyType::String),
+        PropertyDesc::new(KINESIS_CREDENTIALS_SECRET_ACCESS_KEY, PropertyType::String).secret(),
+        PropertyDesc::new(KINESIS_CREDENTIALS_SESSION_TOKEN, PropertyType::String).secret(),
+        PropertyDesc::new(KINESIS_CREDENTIALS_PROFILE, PropertyType::String),
+        PropertyDesc::new(KINESIS_ASSUMEROLE_ARN, PropertyType::String),
+        PropertyDesc::new(KINESIS_ASSUMEROLE_EXTERNAL_ID, PropertyType::String),
+        PropertyDesc::new(
+            KINESIS_SCAN_STARTUP_MODE,
+            PropertyType::Enum(&["earliest", "latest", "timestamp", "sequence_number"]),
+        ),
+        PropertyDesc::new(KINESIS_SCAN_STARTUP_TIMESTAMP_MILLIS, PropertyType::Int),
+        PropertyDesc::new(KINESIS_SCAN_STARTUP_SEQUENCE_NUMBER, PropertyType::String),
+    ];
+}
+
+impl KinesisProperties {
+    pub fn aws_auth_props(&self) -> AwsAuthProps {
+        AwsAuthProps {
+            region: Some(self.stream_region.clone()),
+            endpoint: self.endpoint.clone(),
+            access_key: self.credentials_access_key.clone(),
+            secret_key: self.credentials_secret_access_key.clone(),
+            session_token: self.session_token.clone(),
+            profile: self.profile.clone(),
+            arn: self.assume_role_arn.clone(),
+            external_id: self.assume_role_external_id.clone(),
+        }
+    }
+
+    /// Returns the position to start reading a shard from if no offset of it is restored.
+    pub fn startup_position(&self) -> Result<KinesisOffset> {
+        match self
+            .scan_startup_mode
+            .as_ref()
+            .map(|s| s.to_lowercase())
+            .as_deref()
+        {
+            None | Some("earliest") => Ok(KinesisOffset::Earliest),
+            Some("latest") => Ok(KinesisOffset::Latest),
+            Some("timestamp") => {
+                let timestamp = self.scan_startup_timestamp_millis.ok_or_else(|| {
+                    anyhow!(
+                        "startup mode `timestamp` requires {}",
+                        KINESIS_SCAN_STARTUP_TIMESTAMP_MILLIS
+                    )
+                })?;
+                Ok(KinesisOffset::Timestamp(timestamp))
+            }
+            Some("sequence_number") => {
+                let sequence_number =
+                    self.scan_startup_sequence_number.clone().ok_or_else(|| {
+                        anyhow!(
+                            "startup mode `sequence_number` requires {}",
+                            KINESIS_SCAN_STARTUP_SEQUENCE_NUMBER
+                        )
+                    })?;
+                Ok(KinesisOffset::SequenceNumber(sequence_number))
+            }
+            Some(mode) => Err(anyhow!(
+                "unknown startup mode {}, expect earliest, latest, timestamp or sequence_number",
+                mode
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::property::extract_properties;
+
+    fn properties_with_startup(
+        mode: Option<&str>,
+        timestamp_millis: Option<i64>,
+        sequence_number: Option<&str>,
+    ) -> KinesisProperties {
+        KinesisProperties {
+            stream_name: "kinesis_debug".to_string(),
+            stream_region: "cn-northwest-1".to_string(),
+            endpoint: None,
+            credentials_access_key: None,
+            credentials_secret_access_key: None,
+            session_token: None,
+            profile: None,
+            assume_role_arn: None,
+            assume_role_external_id: None,
+            scan_startup_mode: mode.map(str::to_string),
+            scan_startup_timestamp_millis: timestamp_millis,
+            scan_startup_sequence_number: sequence_number.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_startup_position() {
+        let position = |mode, timestamp_millis, sequence_number| {
+            properties_with_startup(mode, timestamp_millis, sequence_number).startup_position()
+        };
+
+        assert_eq!(position(None, None, None).unwrap(), KinesisOffset::Earliest);
+        assert_eq!(
+            position(Some("EARLIEST"), None, None).unwrap(),
+            KinesisOffset::Earliest
+        );
+        assert_eq!(
+            position(Some("latest"), None, None).unwrap(),
+            KinesisOffset::Latest
+        );
+        assert_eq!(
+            position(Some("timestamp"), Some(1660000000000), None).unwrap(),
+            KinesisOffset::Timestamp(1660000000000)
+        );
+        assert_eq!(
+            position(Some("sequence_number"), None, Some("4962913981750490")).unwrap(),
+            KinesisOffset::SequenceNumber("4962913981750490".to_string())
+        );
+
+        assert!(position(Some("timestamp"), None, None).is_err());
+        assert!(position(Some("sequence_number"), None, None).is_err());
+        assert!(position(Some("trim_horizon"), None, None).is_err());
+    }
+
+    #[test]
+    fn test_extract_properties() {
+        let extract = |props: &[(&str, &str)]| {
+            let props = props
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect();
+            extract_properties::<KinesisProperties>(props)
+        };
+        let with_stream = |props: &[(&'static str, &'static str)]| {
+            let mut props = props.to_vec();
+            props.push((KINESIS_STREAM_NAME, "kinesis_debug"));
+            props.push((KINESIS_STREAM_REGION, "cn-northwest-1"));
+            extract(&props)
+        };
+
+        let properties = with_stream(&[
+            (KINESIS_SCAN_STARTUP_MODE, "Timestamp"),
+            (KINESIS_SCAN_STARTUP_TIMESTAMP_MILLIS, "1660000000000"),
+        ])
+        .unwrap();
+        assert_eq!(
+            properties.startup_position().unwrap(),
+            KinesisOffset::Timestamp(1660000000000)
+        );
+
+        assert_eq!(
+            extract(&[(KINESIS_STREAM_REGION, "cn-northwest-1")])
+                .unwrap_err()
+                .to_string(),
+            "missing kinesis.stream.name"
+        );
+        assert_eq!(
+            with_stream(&[(KINESIS_SCAN_STARTUP_TIMESTAMP_MILLIS, "yesterday")])
+                .unwrap_err()
+                .to_string(),
+            "invalid kinesis.scan.startup.timestamp_millis: expect an integer, got 'yesterday'"
+        );
+        assert!(with_stream(&[(KINESIS_SCAN_STARTUP_MODE, "trim_horizon")]).is_err());
+    }
 }
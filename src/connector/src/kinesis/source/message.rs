@@ -23,6 +23,8 @@ pub struct KinesisMessage {
     pub shard_id: String,
     pub sequence_number: String,
     pub partition_key: String,
+    /// Milliseconds since the Unix epoch when the record arrived at the stream.
+    pub approximate_arrival_timestamp: Option<i64>,
     pub payload: Option<Vec<u8>>,
 }
 
@@ -35,6 +37,8 @@ impl From<KinesisMessage> for SourceMessage {
                 .map(|payload| Bytes::copy_from_slice(payload)),
             offset: msg.sequence_number.clone(),
             split_id: msg.shard_id,
+            key: Some(Bytes::from(msg.partition_key)),
+            timestamp: msg.approximate_arrival_timestamp,
         }
     }
 }
@@ -45,6 +49,9 @@ impl KinesisMessage {
             shard_id,
             sequence_number: message.sequence_number.unwrap(),
             partition_key: message.partition_key.unwrap(),
+            approximate_arrival_timestamp: message
+                .approximate_arrival_timestamp
+                .map(|t| t.secs() * 1000 + t.subsec_nanos() as i64 / 1_000_000),
             payload: Some(message.data.unwrap().into_inner()),
         }
     }
@@ -14,6 +14,7 @@
 
 use core::result::Result::Ok;
 use std::sync::Arc;
+use std::time::Duration;
 
 use anyhow::{anyhow, Result};
 use async_trait::async_trait;
@@ -22,6 +23,7 @@ use aws_sdk_kinesis::model::ShardIteratorType;
 use aws_sdk_kinesis::output::GetRecordsOutput;
 use aws_sdk_kinesis::types::SdkError;
 use aws_sdk_kinesis::Client as KinesisClient;
+use aws_smithy_types::DateTime;
 use futures::future::join_all;
 use futures_async_stream::{for_await, try_stream};
 use futures_concurrency::prelude::*;
@@ -32,14 +34,84 @@ use crate::base::{SourceMessage, SplitReader};
 use crate::kinesis::source::message::KinesisMessage;
 use crate::kinesis::split::{KinesisOffset, KinesisSplit};
 use crate::kinesis::{build_client, KinesisProperties};
+use crate::monitor::SourceMetrics;
 use crate::{Column, ConnectorState, SplitImpl};
 
+/// Bounds of the number of records requested by a `GetRecords` call, which returns at most 10000
+/// records.
+const MIN_GET_RECORDS_LIMIT: i32 = 100;
+const MAX_GET_RECORDS_LIMIT: i32 = 10000;
+
+/// Bounds of the interval between the `GetRecords` calls of a shard. A shard serves at most 5 calls
+/// per second, shared by all the consumers of it.
+const MIN_POLL_INTERVAL: Duration = Duration::from_millis(200);
+const MAX_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Bounds of the backoff after a `GetRecords` call is throttled.
+const MIN_THROTTLE_BACKOFF: Duration = Duration::from_millis(500);
+const MAX_THROTTLE_BACKOFF: Duration = Duration::from_secs(10);
+
+/// Adapts the `GetRecords` calls of a shard to its backlog. While the shard has a backlog, the
+/// reader requests more records per call and polls more frequently to catch up. Once it's caught
+/// up with an idle shard, it polls less frequently to leave the read throughput to the other
+/// consumers. When throttled, it backs off exponentially until a call succeeds.
+#[derive(Debug, Clone)]
+struct AdaptivePolling {
+    limit: i32,
+    interval: Duration,
+    /// Zero if the last call is not throttled.
+    backoff: Duration,
+}
+
+impl AdaptivePolling {
+    fn new() -> Self {
+        Self {
+            limit: MIN_GET_RECORDS_LIMIT,
+            interval: MIN_POLL_INTERVAL,
+            backoff: Duration::ZERO,
+        }
+    }
+
+    /// The number of records to request by the next call.
+    fn limit(&self) -> i32 {
+        self.limit
+    }
+
+    /// How long to wait before the next call.
+    fn delay(&self) -> Duration {
+        if self.backoff.is_zero() {
+            self.interval
+        } else {
+            self.backoff
+        }
+    }
+
+    fn on_records(&mut self, num_records: usize, millis_behind_latest: i64) {
+        self.backoff = Duration::ZERO;
+        if millis_behind_latest > 0 || num_records >= self.limit as usize {
+            self.limit = (self.limit * 2).min(MAX_GET_RECORDS_LIMIT);
+            self.interval = (self.interval / 2).max(MIN_POLL_INTERVAL);
+        } else if num_records == 0 {
+            self.limit = (self.limit / 2).max(MIN_GET_RECORDS_LIMIT);
+            self.interval = (self.interval * 2).min(MAX_POLL_INTERVAL);
+        }
+    }
+
+    fn on_throttled(&mut self) {
+        self.backoff = (self.backoff * 2).clamp(MIN_THROTTLE_BACKOFF, MAX_THROTTLE_BACKOFF);
+        self.limit = (self.limit / 2).max(MIN_GET_RECORDS_LIMIT);
+    }
+}
+
 pub struct KinesisMultiSplitReader {
     /// splits are not allowed to be empty, otherwise connector source should create
     /// DummySplitReader which is always idling.
     splits: Vec<KinesisSplit>,
     properties: KinesisProperties,
+    metrics: Arc<SourceMetrics>,
     message_cache: Arc<Mutex<Vec<SourceMessage>>>,
+    /// Set once all the splits are read to their ends, or one of them fails.
+    finished: Arc<Mutex<Option<Result<()>>>>,
     consumer_handler: Option<JoinHandle<()>>,
 }
 
@@ -59,11 +131,28 @@ pub struct KinesisSplitReader {
     latest_offset: Option<String>,
     shard_iter: Option<String>,
     start_position: KinesisOffset,
+    /// Whether the reader starts at `start_position` rather than after it. A restored sequence
+    /// number is the last record read, while the startup one is the first record to read.
+    start_inclusive: bool,
+    /// Records arrived after `end_position` are not read, if it's a timestamp.
     end_position: KinesisOffset,
+    /// Whether `end_position` is reached.
+    finished: bool,
+    polling: AdaptivePolling,
+    metrics: Arc<SourceMetrics>,
 }
 
 impl KinesisSplitReader {
-    pub async fn new(properties: KinesisProperties, split: KinesisSplit) -> Result<Self> {
+    pub async fn new(
+        properties: KinesisProperties,
+        split: KinesisSplit,
+        metrics: Arc<SourceMetrics>,
+    ) -> Result<Self> {
+        // The startup position only applies if the split has no offset restored from the state.
+        let (start_position, start_inclusive) = match split.start_position {
+            KinesisOffset::None => (properties.startup_position()?, true),
+            start_position => (start_position, false),
+        };
         let stream_name = properties.stream_name.clone();
         let client = build_client(properties).await?;
         Ok(Self {
@@ -72,21 +161,30 @@ impl KinesisSplitReader {
             shard_id: split.shard_id,
             shard_iter: None,
             latest_offset: None,
-            start_position: split.start_position,
+            start_position,
+            start_inclusive,
             end_position: split.end_position,
+            finished: false,
+            polling: AdaptivePolling::new(),
+            metrics,
         })
     }
 
-    pub async fn next(&mut self) -> Result<Vec<SourceMessage>> {
+    /// Returns the next records of the shard, or `None` if the end position is reached.
+    pub async fn next(&mut self) -> Result<Option<Vec<SourceMessage>>> {
+        if self.finished {
+            return Ok(None);
+        }
         if self.shard_iter.is_none() {
             self.new_shard_iter().await?;
         }
         assert!(self.shard_iter.is_some());
         loop {
+            tokio::time::sleep(self.polling.delay()).await;
             match self.get_records().await {
                 Ok(resp) => {
                     self.shard_iter = resp.next_shard_iterator().map(String::from);
-                    let chunk = resp
+                    let mut chunk = resp
                         .records()
                         .unwrap()
                         .iter()
@@ -97,17 +195,49 @@ impl KinesisSplitReader {
                             ))
                         })
                         .collect::<Vec<SourceMessage>>();
+                    let millis_behind_latest = resp.millis_behind_latest().unwrap_or(0);
+                    self.polling.on_records(chunk.len(), millis_behind_latest);
+                    if let KinesisOffset::Timestamp(end_millis) = self.end_position {
+                        let len = chunk
+                            .iter()
+                            .position(|m| m.timestamp.map_or(false, |t| t > end_millis))
+                            .unwrap_or(chunk.len());
+                        // Caught up with the shard, so the records arrived before the end are
+                        // all read.
+                        self.finished = len < chunk.len()
+                            || millis_behind_latest == 0
+                            || self.shard_iter.is_none();
+                        chunk.truncate(len);
+                    }
                     if chunk.is_empty() {
-                        tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+                        if self.finished {
+                            return Ok(None);
+                        }
                         continue;
                     }
                     self.latest_offset = Some(chunk.last().unwrap().offset.clone());
-                    return Ok(chunk);
+                    return Ok(Some(chunk));
                 }
                 Err(e) => match e {
                     SdkError::ServiceError { err, .. } if err.is_expired_iterator_exception() => {
                         self.new_shard_iter().await?;
-                        tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+                        continue;
+                    }
+                    // Throttled by the read limits of the shard, which are shared by all its
+                    // consumers. Retry the same iterator after backing off.
+                    SdkError::ServiceError { err, .. }
+                        if err.is_provisioned_throughput_exceeded_exception() =>
+                    {
+                        self.polling.on_throttled();
+                        self.metrics
+                            .kinesis_throttled_count
+                            .with_label_values(&[&self.stream_name, &self.shard_id])
+                            .inc();
+                        log::warn!(
+                            "GetRecords of kinesis shard {} is throttled, back off for {:?}",
+                            self.shard_id,
+                            self.polling.delay()
+                        );
                         continue;
                     }
                     e => return Err(anyhow!(e)),
@@ -117,18 +247,27 @@ impl KinesisSplitReader {
     }
 
     async fn new_shard_iter(&mut self) -> Result<()> {
+        let mut timestamp = None;
         let (starting_seq_num, iter_type) = if self.latest_offset.is_some() {
             (
-                self.latest_offset.take(),
+                self.latest_offset.clone(),
                 ShardIteratorType::AfterSequenceNumber,
             )
         } else {
             match &self.start_position {
                 KinesisOffset::Earliest => (None, ShardIteratorType::TrimHorizon),
+                KinesisOffset::Latest => (None, ShardIteratorType::Latest),
+                KinesisOffset::SequenceNumber(seq) if self.start_inclusive => {
+                    (Some(seq.clone()), ShardIteratorType::AtSequenceNumber)
+                }
                 KinesisOffset::SequenceNumber(seq) => {
                     (Some(seq.clone()), ShardIteratorType::AfterSequenceNumber)
                 }
-                _ => unreachable!(),
+                KinesisOffset::Timestamp(millis) => {
+                    timestamp = Some(DateTime::from_millis(*millis));
+                    (None, ShardIteratorType::AtTimestamp)
+                }
+                KinesisOffset::None => unreachable!(),
             }
         };
 
@@ -139,6 +278,7 @@ impl KinesisSplitReader {
             .shard_id(self.shard_id.clone())
             .shard_iterator_type(iter_type)
             .set_starting_sequence_number(starting_seq_num)
+            .set_timestamp(timestamp)
             .send()
             .await?;
 
@@ -153,7 +293,8 @@ impl KinesisSplitReader {
         let resp = self
             .client
             .get_records()
-            .set_shard_iterator(self.shard_iter.take())
+            .set_shard_iterator(self.shard_iter.clone())
+            .limit(self.polling.limit())
             .send()
             .await;
         resp
@@ -164,7 +305,10 @@ impl KinesisSplitReader {
 async fn split_reader_into_stream(mut reader: KinesisSplitReader) {
     loop {
         match reader.next().await {
-            Ok(chunk) => yield chunk,
+            Ok(Some(chunk)) => yield chunk,
+            Ok(None) => break,
+            // A bounded read fails rather than returning part of the records.
+            Err(e) if reader.end_position != KinesisOffset::None => return Err(e),
             Err(e) => {
                 log::error!("hang up kinesis reader due to polling error: {}", e);
                 drop(reader);
@@ -182,6 +326,7 @@ impl SplitReader for KinesisMultiSplitReader {
         properties: KinesisProperties,
         state: ConnectorState,
         _columns: Option<Vec<Column>>,
+        metrics: Arc<SourceMetrics>,
     ) -> Result<Self>
     where
         Self: Sized,
@@ -196,7 +341,9 @@ impl SplitReader for KinesisMultiSplitReader {
                 })
                 .collect::<Result<Vec<KinesisSplit>>>()?,
             properties,
+            metrics,
             message_cache: Arc::new(Mutex::new(Vec::new())),
+            finished: Arc::new(Mutex::new(None)),
             consumer_handler: None,
         })
     }
@@ -207,14 +354,19 @@ impl SplitReader for KinesisMultiSplitReader {
                 self.splits
                     .iter()
                     .map(|split| async {
-                        KinesisSplitReader::new(self.properties.clone(), split.to_owned())
-                            .await
-                            .unwrap()
+                        KinesisSplitReader::new(
+                            self.properties.clone(),
+                            split.to_owned(),
+                            self.metrics.clone(),
+                        )
+                        .await
+                        .unwrap()
                     })
                     .collect::<Vec<_>>(),
             )
             .await;
             let cache = Arc::clone(&self.message_cache);
+            let finished = Arc::clone(&self.finished);
 
             self.consumer_handler = Some(tokio::spawn(async move {
                 let join_stream = split_readers
@@ -231,17 +383,28 @@ impl SplitReader for KinesisMultiSplitReader {
                         }
                         Err(e) => {
                             log::error!("split encountered error: {:?}, shutting down stream", e);
-                            break;
+                            *finished.lock().await = Some(Err(e));
+                            return;
                         }
                     }
                 }
+                *finished.lock().await = Some(Ok(()));
             }));
             log::info!("launch kinesis reader with splits: {:?}", self.splits);
         }
         loop {
+            // Checked before the cache, as the splits finish after their messages are cached.
+            let finished = match &*self.finished.lock().await {
+                Some(Ok(())) => Some(Ok(None)),
+                Some(Err(e)) => Some(Err(anyhow!(e.to_string()))),
+                None => None,
+            };
             let mut cache_lock = self.message_cache.lock().await;
             if cache_lock.is_empty() {
                 drop(cache_lock);
+                if let Some(result) = finished {
+                    return result;
+                }
                 tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
                 continue;
             }
@@ -264,6 +427,51 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn test_adaptive_polling() {
+        let mut polling = AdaptivePolling::new();
+        assert_eq!(polling.limit(), MIN_GET_RECORDS_LIMIT);
+        assert_eq!(polling.delay(), MIN_POLL_INTERVAL);
+
+        // Idle shard polls less frequently.
+        polling.on_records(0, 0);
+        assert_eq!(polling.limit(), MIN_GET_RECORDS_LIMIT);
+        assert_eq!(polling.delay(), MIN_POLL_INTERVAL * 2);
+        for _ in 0..10 {
+            polling.on_records(0, 0);
+        }
+        assert_eq!(polling.delay(), MAX_POLL_INTERVAL);
+
+        // Backlogged shard requests more records and polls more frequently.
+        polling.on_records(10, 1000);
+        assert_eq!(polling.limit(), MIN_GET_RECORDS_LIMIT * 2);
+        assert_eq!(polling.delay(), MAX_POLL_INTERVAL / 2);
+        for _ in 0..10 {
+            polling.on_records(polling.limit() as usize, 0);
+        }
+        assert_eq!(polling.limit(), MAX_GET_RECORDS_LIMIT);
+        assert_eq!(polling.delay(), MIN_POLL_INTERVAL);
+
+        // Caught up with a non-empty shard keeps the current pace.
+        polling.on_records(10, 0);
+        assert_eq!(polling.limit(), MAX_GET_RECORDS_LIMIT);
+        assert_eq!(polling.delay(), MIN_POLL_INTERVAL);
+
+        // Throttled calls back off exponentially until a call succeeds.
+        polling.on_throttled();
+        assert_eq!(polling.limit(), MAX_GET_RECORDS_LIMIT / 2);
+        assert_eq!(polling.delay(), MIN_THROTTLE_BACKOFF);
+        polling.on_throttled();
+        assert_eq!(polling.delay(), MIN_THROTTLE_BACKOFF * 2);
+        for _ in 0..10 {
+            polling.on_throttled();
+        }
+        assert_eq!(polling.delay(), MAX_THROTTLE_BACKOFF);
+        assert_eq!(polling.limit(), MIN_GET_RECORDS_LIMIT);
+        polling.on_records(10, 0);
+        assert_eq!(polling.delay(), MIN_POLL_INTERVAL);
+    }
+
     #[tokio::test]
     #[ignore]
     async fn test_single_thread_kinesis_reader() -> Result<()> {
@@ -275,7 +483,11 @@ mod tests {
             stream_region: "cn-northwest-1".to_string(),
             endpoint: None,
             session_token: None,
+            profile: None,
             assume_role_external_id: None,
+            scan_startup_mode: None,
+            scan_startup_timestamp_millis: None,
+            scan_startup_sequence_number: None,
         };
 
         let mut trim_horizen_reader = KinesisSplitReader::new(
@@ -285,6 +497,7 @@ mod tests {
                 start_position: KinesisOffset::Earliest,
                 end_position: KinesisOffset::None,
             },
+            Arc::new(SourceMetrics::unused()),
         )
         .await?;
         let stream_reader = trim_horizen_reader.clone();
@@ -299,6 +512,7 @@ mod tests {
                 ),
                 end_position: KinesisOffset::None,
             },
+            Arc::new(SourceMetrics::unused()),
         )
         .await?;
         println!("{:?}", offset_reader.next().await?);
@@ -326,7 +540,11 @@ mod tests {
             stream_region: "cn-northwest-1".to_string(),
             endpoint: None,
             session_token: None,
+            profile: None,
             assume_role_external_id: None,
+            scan_startup_mode: None,
+            scan_startup_timestamp_millis: None,
+            scan_startup_sequence_number: None,
         };
 
         let splits = vec!["shardId-000000000000", "shardId-000000000001"]
@@ -340,7 +558,13 @@ mod tests {
             })
             .collect::<Vec<_>>();
 
-        let mut reader = KinesisMultiSplitReader::new(properties, Some(splits), None).await?;
+        let mut reader = KinesisMultiSplitReader::new(
+            properties,
+            Some(splits),
+            None,
+            Arc::new(SourceMetrics::unused()),
+        )
+        .await?;
         tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
         println!("1: {:?}", reader.next().await);
         tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
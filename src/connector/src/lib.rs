@@ -43,9 +43,13 @@ mod pulsar;
 
 pub use base::*;
 
+pub mod aws_auth;
 pub mod aws_utils;
 pub mod dummy_connector;
 mod macros;
+pub mod monitor;
+pub mod property;
+pub mod registry;
 pub mod state;
 
 pub use base::ConnectorState;
@@ -14,12 +14,16 @@
 
 #[macro_export]
 macro_rules! impl_split_enumerator {
-    ([], $({ $variant_name:ident, $split_enumerator_name:ident} ),*) => {
+    ($({ $variant_name:ident, $connector_name:path, $split:ty, $split_enumerator:ty, $split_reader:ty }),*) => {
+        pub enum SplitEnumeratorImpl {
+            $( $variant_name($split_enumerator), )*
+        }
+
         impl SplitEnumeratorImpl {
 
              pub async fn create(properties: ConnectorProperties) -> Result<Self> {
                 match properties {
-                    $( ConnectorProperties::$variant_name(props) => $split_enumerator_name::new(props).await.map(Self::$variant_name), )*
+                    $( ConnectorProperties::$variant_name(props) => <$split_enumerator>::new(props).await.map(Self::$variant_name), )*
                     other => Err(anyhow!("split enumerator type for config {:?} is not supported", other)),
                 }
              }
@@ -29,13 +33,32 @@ macro_rules! impl_split_enumerator {
                     $( Self::$variant_name(inner) => inner.list_splits().await.map(|ss| ss.into_iter().map(SplitImpl::$variant_name).collect_vec()), )*
                 }
              }
+
+             pub async fn list_bounded_splits(&mut self) -> Result<Vec<SplitImpl>> {
+                match self {
+                    $( Self::$variant_name(inner) => inner.list_bounded_splits().await.map(|ss| ss.into_iter().map(SplitImpl::$variant_name).collect_vec()), )*
+                }
+             }
+
+             /// Validates the properties against the upstream, if the connector has a split enumerator.
+             pub async fn validate(properties: ConnectorProperties) -> Result<()> {
+                match properties {
+                    $( ConnectorProperties::$variant_name(props) => <$split_enumerator>::new(props).await?.validate().await, )*
+                    _ => Ok(()),
+                }
+             }
         }
     }
 }
 
 #[macro_export]
 macro_rules! impl_split {
-    ([], $({ $variant_name:ident, $connector_name:ident, $split:ty} ),*) => {
+    ($({ $variant_name:ident, $connector_name:path, $split:ty, $split_enumerator:ty, $split_reader:ty }),*) => {
+        #[derive(Debug, Clone, Serialize, Deserialize, EnumAsInner, PartialEq, Hash)]
+        pub enum SplitImpl {
+            $( $variant_name($split), )*
+        }
+
         impl SplitImpl {
             pub fn id(&self) -> String {
                 match self {
@@ -74,11 +97,17 @@ macro_rules! impl_split {
 
 #[macro_export]
 macro_rules! impl_split_reader {
-    ([], $({ $variant_name:ident, $split_reader_name:ident} ),*) => {
+    ($({ $variant_name:ident, $connector_name:path, $split:ty, $split_enumerator:ty, $split_reader:ty }),*) => {
+        pub enum SplitReaderImpl {
+            $( $variant_name(Box<$split_reader>), )*
+            Dummy(Box<DummySplitReader>),
+        }
+
         impl SplitReaderImpl {
             pub async fn next(&mut self) -> Result<Option<Vec<SourceMessage>>> {
                 match self {
                     $( Self::$variant_name(inner) => inner.next().await, )*
+                    Self::Dummy(inner) => inner.next().await,
                 }
             }
 
@@ -86,14 +115,15 @@ macro_rules! impl_split_reader {
                 config: ConnectorProperties,
                 state: ConnectorState,
                 columns: Option<Vec<Column>>,
+                metrics: Arc<SourceMetrics>,
             ) -> Result<Self> {
                 if state.is_none() {
                     return Ok(Self::Dummy(Box::new(DummySplitReader {})));
                 }
 
                 let connector = match config {
-                     $( ConnectorProperties::$variant_name(props) => Self::$variant_name(Box::new($split_reader_name::new(props, state, columns).await?)), )*
-                    _ => todo!()
+                     $( ConnectorProperties::$variant_name(props) => Self::$variant_name(Box::new(<$split_reader>::new(props, state, columns, metrics).await?)), )*
+                    other => return Err(anyhow!("split reader type for config {:?} is not supported", other)),
                 };
 
                 Ok(connector)
@@ -104,14 +134,21 @@ macro_rules! impl_split_reader {
 
 #[macro_export]
 macro_rules! impl_connector_properties {
-    ([], $({ $variant_name:ident, $connector_name:ident } ),*) => {
+    ($({ $variant_name:ident, $connector_name:path, $split:ty, $split_enumerator:ty, $split_reader:ty }),*) => {
+        #[derive(Clone, Debug, Deserialize)]
+        pub enum ConnectorProperties {
+            $( $variant_name(<$split_enumerator as SplitEnumerator>::Properties), )*
+            S3(S3Properties),
+            Dummy(()),
+        }
+
         impl ConnectorProperties {
             pub fn extract(mut props: HashMap<String, String>) -> Result<Self> {
                 const UPSTREAM_SOURCE_KEY: &str = "connector";
                 let connector = props.remove(UPSTREAM_SOURCE_KEY).ok_or_else(|| anyhow!("Must specify 'connector' in WITH clause"))?;
-                let json_value = serde_json::to_value(props).map_err(|e| anyhow!(e))?;
                 match connector.to_lowercase().as_str() {
-                    $( $connector_name => { serde_json::from_value(json_value).map_err(|e| anyhow!(e.to_string())).map(Self::$variant_name) } ,)*
+                    $( $connector_name => extract_properties(props).map(Self::$variant_name), )*
+                    S3_CONNECTOR => extract_properties(props).map(Self::S3),
                     _ => {
                         Err(anyhow!("connector '{}' is not supported", connector,))
                     }
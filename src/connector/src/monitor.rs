@@ -0,0 +1,57 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt::Debug;
+
+use prometheus::core::{AtomicU64, GenericCounterVec};
+use prometheus::{register_int_counter_vec_with_registry, Registry};
+
+/// Metrics of the connectors reading the upstream sources.
+pub struct SourceMetrics {
+    /// Number of `GetRecords` requests of each Kinesis shard rejected by throttling.
+    pub kinesis_throttled_count: GenericCounterVec<AtomicU64>,
+}
+
+impl SourceMetrics {
+    pub fn new(registry: Registry) -> Self {
+        let kinesis_throttled_count = register_int_counter_vec_with_registry!(
+            "source_kinesis_throttled_count",
+            "Total number of GetRecords requests of the Kinesis shard throttled",
+            &["stream", "shard"],
+            registry
+        )
+        .unwrap();
+
+        Self {
+            kinesis_throttled_count,
+        }
+    }
+
+    /// Creates a new `SourceMetrics` instance used in tests or other places.
+    pub fn unused() -> Self {
+        Self::new(Registry::new())
+    }
+}
+
+impl Default for SourceMetrics {
+    fn default() -> Self {
+        Self::unused()
+    }
+}
+
+impl Debug for SourceMetrics {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SourceMetrics").finish()
+    }
+}
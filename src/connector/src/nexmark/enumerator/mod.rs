@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use anyhow::anyhow;
 use async_trait::async_trait;
 
 use crate::base::SplitEnumerator;
@@ -20,6 +21,8 @@ use crate::nexmark::NexmarkProperties;
 
 pub struct NexmarkSplitEnumerator {
     split_num: i32,
+    /// The number of events to generate, unbounded if not positive.
+    event_num: i64,
 }
 
 impl NexmarkSplitEnumerator {}
@@ -31,7 +34,10 @@ impl SplitEnumerator for NexmarkSplitEnumerator {
 
     async fn new(properties: Box<NexmarkProperties>) -> anyhow::Result<NexmarkSplitEnumerator> {
         let split_num = properties.split_num.unwrap_or(1);
-        Ok(Self { split_num })
+        Ok(Self {
+            split_num,
+            event_num: properties.event_num,
+        })
     }
 
     async fn list_splits(&mut self) -> anyhow::Result<Vec<NexmarkSplit>> {
@@ -45,6 +51,13 @@ impl SplitEnumerator for NexmarkSplitEnumerator {
         }
         Ok(splits)
     }
+
+    async fn list_bounded_splits(&mut self) -> anyhow::Result<Vec<NexmarkSplit>> {
+        if self.event_num <= 0 {
+            return Err(anyhow!("nexmark.event.num is required for batch reads"));
+        }
+        self.list_splits().await
+    }
 }
 
 #[cfg(test)]
@@ -55,7 +68,10 @@ mod tests {
     use crate::SplitMetaData;
     #[tokio::test]
     async fn test_nexmark_split_enumerator() -> Result<()> {
-        let mut enumerator = NexmarkSplitEnumerator { split_num: 4 };
+        let mut enumerator = NexmarkSplitEnumerator {
+            split_num: 4,
+            event_num: -1,
+        };
         let list_splits_resp = enumerator.list_splits().await?;
         // println!("{:#?}", list_splits_resp);
         assert_eq!(list_splits_resp.len(), 4);
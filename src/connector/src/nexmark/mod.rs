@@ -26,6 +26,8 @@ const NEXMARK_BASE_TIME: usize = 1_436_918_400_000;
 
 use serde::Deserialize;
 
+use crate::property::{PropertyDesc, PropertySchema, PropertyType};
+
 pub const NEXMARK_CONNECTOR: &str = "nexmark";
 
 #[derive(Clone, Debug, Deserialize)]
@@ -122,6 +124,50 @@ pub struct NexmarkProperties {
     pub threads: Option<usize>,
 }
 
+impl PropertySchema for NexmarkProperties {
+    const PROPERTIES: &'static [PropertyDesc] = &[
+        PropertyDesc::new("nexmark.split.num", PropertyType::Int),
+        PropertyDesc::new("nexmark.event.num", PropertyType::Int),
+        PropertyDesc::new("nexmark.table.type", PropertyType::String),
+        PropertyDesc::new("nexmark.max.chunk.size", PropertyType::Int),
+        PropertyDesc::new("nexmark.use.real.time", PropertyType::Bool),
+        PropertyDesc::new("nexmark.min.event.gap.in.ns", PropertyType::Int),
+        PropertyDesc::new("nexmark.active.people", PropertyType::Int),
+        PropertyDesc::new("nexmark.in.flight.auctions", PropertyType::Int),
+        PropertyDesc::new("nexmark.out.of.order.group.size", PropertyType::Int),
+        PropertyDesc::new("nexmark.hot.seller.ratio", PropertyType::Int),
+        PropertyDesc::new("nexmark.hot.auction.ratio", PropertyType::Int),
+        PropertyDesc::new("nexmark.hot.bidder.ratio", PropertyType::Int),
+        PropertyDesc::new("nexmark.first.event.id", PropertyType::Int),
+        PropertyDesc::new("nexmark.first.event.number", PropertyType::Int),
+        PropertyDesc::new("nexmark.num.categories", PropertyType::Int),
+        PropertyDesc::new("nexmark.auction.id.lead", PropertyType::Int),
+        PropertyDesc::new("nexmark.hot.seller.ratio.2", PropertyType::Int),
+        PropertyDesc::new("nexmark.hot.auction.ratio.2", PropertyType::Int),
+        PropertyDesc::new("nexmark.hot.bidder.ratio.2", PropertyType::Int),
+        PropertyDesc::new("nexmark.person.proportion", PropertyType::Int),
+        PropertyDesc::new("nexmark.auction.proportion", PropertyType::Int),
+        PropertyDesc::new("nexmark.bid.proportion", PropertyType::Int),
+        PropertyDesc::new("nexmark.first.auction.id", PropertyType::Int),
+        PropertyDesc::new("nexmark.first.person.id", PropertyType::Int),
+        PropertyDesc::new("nexmark.first.category.id", PropertyType::Int),
+        PropertyDesc::new("nexmark.person.id.lead", PropertyType::Int),
+        PropertyDesc::new("nexmark.sine.approx.steps", PropertyType::Int),
+        PropertyDesc::new("nexmark.base.time", PropertyType::Int),
+        PropertyDesc::new("nexmark.us.states", PropertyType::String),
+        PropertyDesc::new("nexmark.us.cities", PropertyType::String),
+        PropertyDesc::new("nexmark.first.names", PropertyType::String),
+        PropertyDesc::new("nexmark.last.names", PropertyType::String),
+        PropertyDesc::new("nexmark.rate.shape", PropertyType::String),
+        PropertyDesc::new("nexmark.rate.period", PropertyType::Int),
+        PropertyDesc::new("nexmark.first.event.rate", PropertyType::Int),
+        PropertyDesc::new("nexmark.events.per.sec", PropertyType::Int),
+        PropertyDesc::new("nexmark.next.event.rate", PropertyType::Int),
+        PropertyDesc::new("nexmark.us.per.unit", PropertyType::Int),
+        PropertyDesc::new("nexmark.threads", PropertyType::Int),
+    ];
+}
+
 fn default_event_num() -> i64 {
     -1
 }
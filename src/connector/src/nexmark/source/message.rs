@@ -34,6 +34,8 @@ impl From<NexmarkMessage> for SourceMessage {
                 .map(|payload| Bytes::copy_from_slice(payload)),
             offset: msg.sequence_number.clone(),
             split_id: msg.shard_id,
+            key: None,
+            timestamp: None,
         }
     }
 }
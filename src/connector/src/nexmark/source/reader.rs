@@ -12,11 +12,13 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
 
 use anyhow::{anyhow, Result};
 use async_trait::async_trait;
 
+use crate::monitor::SourceMetrics;
 use crate::nexmark::config::NexmarkConfig;
 use crate::nexmark::source::event::EventType;
 use crate::nexmark::source::generator::NexmarkEventGenerator;
@@ -37,6 +39,7 @@ impl SplitReader for NexmarkSplitReader {
         properties: Box<NexmarkProperties>,
         state: ConnectorState,
         _columns: Option<Vec<Column>>,
+        _metrics: Arc<SourceMetrics>,
     ) -> Result<Self>
     where
         Self: Sized,
@@ -108,6 +111,10 @@ impl SplitReader for NexmarkSplitReader {
     }
 
     async fn next(&mut self) -> Result<Option<Vec<SourceMessage>>> {
+        let generator = &self.generator;
+        if generator.event_num > 0 && generator.events_so_far >= generator.event_num as u64 {
+            return Ok(None);
+        }
         let chunk = match self.generator.next().await {
             Err(e) => return Err(anyhow!(e)),
             Ok(chunk) => chunk,
@@ -145,7 +152,13 @@ mod tests {
             .collect();
 
         let state = Some(list_splits_resp);
-        let mut reader = NexmarkSplitReader::new(Box::new(props), state, None).await?;
+        let mut reader = NexmarkSplitReader::new(
+            Box::new(props),
+            state,
+            None,
+            Arc::new(SourceMetrics::unused()),
+        )
+        .await?;
         let chunk = reader.next().await?.unwrap();
         assert_eq!(chunk.len(), 5);
 
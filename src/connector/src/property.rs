@@ -0,0 +1,213 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Result};
+use itertools::Itertools;
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+
+/// The type of the value of a connector property.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PropertyType {
+    String,
+    Int,
+    Bool,
+    /// One of the listed values, case-insensitive.
+    Enum(&'static [&'static str]),
+}
+
+/// Describes a property in the `WITH` clause of a connector.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PropertyDesc {
+    pub name: &'static str,
+    pub ty: PropertyType,
+    pub required: bool,
+    pub default: Option<&'static str>,
+    /// Secrets, e.g. credentials, are never shown in the error messages.
+    pub secret: bool,
+}
+
+impl PropertyDesc {
+    /// An optional property without default value.
+    pub const fn new(name: &'static str, ty: PropertyType) -> Self {
+        Self {
+            name,
+            ty,
+            required: false,
+            default: None,
+            secret: false,
+        }
+    }
+
+    pub const fn required(mut self) -> Self {
+        self.required = true;
+        self
+    }
+
+    pub const fn with_default(mut self, default: &'static str) -> Self {
+        self.default = Some(default);
+        self
+    }
+
+    pub const fn secret(mut self) -> Self {
+        self.secret = true;
+        self
+    }
+
+    /// Parses the value of the property into the JSON value deserialized by the properties struct.
+    fn parse(&self, value: String) -> Result<Value, String> {
+        let invalid = |expect: String| {
+            if self.secret {
+                format!("invalid {}: expect {}", self.name, expect)
+            } else {
+                format!("invalid {}: expect {}, got '{}'", self.name, expect, value)
+            }
+        };
+        match self.ty {
+            PropertyType::String => Ok(Value::String(value)),
+            PropertyType::Int => value
+                .trim()
+                .parse::<i64>()
+                .map(Value::from)
+                .map_err(|_| invalid("an integer".to_string())),
+            PropertyType::Bool => value
+                .trim()
+                .to_lowercase()
+                .parse::<bool>()
+                .map(Value::from)
+                .map_err(|_| invalid("true or false".to_string())),
+            PropertyType::Enum(values) => {
+                let lowercase = value.trim().to_lowercase();
+                values
+                    .iter()
+                    .find(|v| **v == lowercase)
+                    .map(|v| Value::from(*v))
+                    .ok_or_else(|| invalid(format!("one of {}", values.iter().join(", "))))
+            }
+        }
+    }
+}
+
+/// The properties of a connector, described by [`PropertyDesc`]s.
+pub trait PropertySchema {
+    const PROPERTIES: &'static [PropertyDesc];
+}
+
+impl<T: PropertySchema> PropertySchema for Box<T> {
+    const PROPERTIES: &'static [PropertyDesc] = T::PROPERTIES;
+}
+
+/// Parses the properties by the schema into a JSON object to deserialize the properties struct
+/// from. Reports all the missing or invalid properties at once. The properties not in the schema
+/// are kept as strings, e.g. those flattened into a map by the properties struct.
+pub fn parse_properties(
+    schema: &[PropertyDesc],
+    mut props: HashMap<String, String>,
+) -> Result<Value> {
+    let mut object = Map::new();
+    let mut errors = vec![];
+    for desc in schema {
+        let value = props
+            .remove(desc.name)
+            .or_else(|| desc.default.map(str::to_string));
+        match value {
+            Some(value) => match desc.parse(value) {
+                Ok(value) => {
+                    object.insert(desc.name.to_string(), value);
+                }
+                Err(e) => errors.push(e),
+            },
+            None if desc.required => errors.push(format!("missing {}", desc.name)),
+            None => {}
+        }
+    }
+    if !errors.is_empty() {
+        return Err(anyhow!(errors.join("; ")));
+    }
+    object.extend(props.into_iter().map(|(k, v)| (k, Value::String(v))));
+    Ok(Value::Object(object))
+}
+
+/// Parses the properties by the schema of `T`, and deserializes `T` from them.
+pub fn extract_properties<T>(props: HashMap<String, String>) -> Result<T>
+where
+    T: PropertySchema + DeserializeOwned,
+{
+    let json_value = parse_properties(T::PROPERTIES, props)?;
+    serde_json::from_value(json_value).map_err(|e| anyhow!(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use maplit::hashmap;
+    use serde_json::json;
+
+    use super::*;
+
+    const SCHEMA: &[PropertyDesc] = &[
+        PropertyDesc::new("aws.region", PropertyType::String).required(),
+        PropertyDesc::new("split.num", PropertyType::Int).with_default("1"),
+        PropertyDesc::new("use.real.time", PropertyType::Bool),
+        PropertyDesc::new(
+            "scan.startup.mode",
+            PropertyType::Enum(&["earliest", "latest"]),
+        ),
+    ];
+
+    fn parse(props: HashMap<&str, &str>) -> Result<Value> {
+        let props = props
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        parse_properties(SCHEMA, props)
+    }
+
+    #[test]
+    fn test_parse_properties() {
+        assert_eq!(
+            parse(hashmap! {
+                "aws.region" => "us-east-1",
+                "use.real.time" => "TRUE",
+                "scan.startup.mode" => "Latest",
+                "fields.v1.kind" => "sequence",
+            })
+            .unwrap(),
+            json!({
+                "aws.region": "us-east-1",
+                "split.num": 1,
+                "use.real.time": true,
+                "scan.startup.mode": "latest",
+                "fields.v1.kind": "sequence",
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid_properties() {
+        assert_eq!(
+            parse(hashmap! { "split.num" => "two" })
+                .unwrap_err()
+                .to_string(),
+            "missing aws.region; invalid split.num: expect an integer, got 'two'"
+        );
+        assert_eq!(
+            parse(hashmap! { "aws.region" => "us-east-1", "scan.startup.mode" => "now" })
+                .unwrap_err()
+                .to_string(),
+            "invalid scan.startup.mode: expect one of earliest, latest, got 'now'"
+        );
+    }
+}
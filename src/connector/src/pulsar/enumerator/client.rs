@@ -64,8 +64,7 @@ impl SplitEnumerator for PulsarSplitEnumerator {
             }
         };
 
-        if let Some(s) = properties.time_offset {
-            let time_offset = s.parse::<i64>().map_err(|e| anyhow!(e))?;
+        if let Some(time_offset) = properties.time_offset {
             scan_start_offset = PulsarEnumeratorOffset::Timestamp(time_offset)
         }
 
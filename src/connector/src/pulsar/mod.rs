@@ -22,6 +22,8 @@ pub use enumerator::*;
 use serde::Deserialize;
 pub use split::*;
 
+use crate::property::{PropertyDesc, PropertySchema, PropertyType};
+
 pub const PULSAR_CONNECTOR: &str = "pulsar";
 
 #[derive(Clone, Debug, Deserialize)]
@@ -39,5 +41,18 @@ pub struct PulsarProperties {
     pub scan_startup_mode: Option<String>,
 
     #[serde(rename = "pulsar.time.offset")]
-    pub time_offset: Option<String>,
+    pub time_offset: Option<i64>,
+}
+
+impl PropertySchema for PulsarProperties {
+    const PROPERTIES: &'static [PropertyDesc] = &[
+        PropertyDesc::new("pulsar.topic", PropertyType::String).required(),
+        PropertyDesc::new("pulsar.admin.url", PropertyType::String).required(),
+        PropertyDesc::new("pulsar.service.url", PropertyType::String).required(),
+        PropertyDesc::new(
+            "pulsar.scan.startup.mode",
+            PropertyType::Enum(&["earliest", "latest"]),
+        ),
+        PropertyDesc::new("pulsar.time.offset", PropertyType::Int),
+    ];
 }
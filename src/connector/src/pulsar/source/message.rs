@@ -19,6 +19,7 @@ use crate::base::SourceMessage;
 impl From<Message<Vec<u8>>> for SourceMessage {
     fn from(msg: Message<Vec<u8>>) -> Self {
         let message_id = msg.message_id.id;
+        let metadata = msg.payload.metadata;
 
         SourceMessage {
             payload: Some(bytes::Bytes::from(msg.payload.data)),
@@ -30,6 +31,8 @@ impl From<Message<Vec<u8>>> for SourceMessage {
                 message_id.batch_index.unwrap_or(-1)
             ),
             split_id: msg.topic,
+            key: metadata.partition_key.map(bytes::Bytes::from),
+            timestamp: Some(metadata.publish_time as i64),
         }
     }
 }
@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use std::borrow::BorrowMut;
+use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
 
 use anyhow::{anyhow, Result};
@@ -28,6 +29,7 @@ use tokio::sync::{mpsc, oneshot};
 use tokio_stream::wrappers::UnboundedReceiverStream;
 
 use crate::base::{SourceMessage, SplitReader};
+use crate::monitor::SourceMetrics;
 use crate::pulsar::split::PulsarSplit;
 use crate::pulsar::{PulsarEnumeratorOffset, PulsarProperties};
 use crate::{Column, ConnectorState, SplitImpl};
@@ -172,6 +174,7 @@ impl SplitReader for PulsarSplitReader {
         props: PulsarProperties,
         state: ConnectorState,
         _columns: Option<Vec<Column>>,
+        _metrics: Arc<SourceMetrics>,
     ) -> Result<Self>
     where
         Self: Sized,
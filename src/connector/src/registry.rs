@@ -0,0 +1,66 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The registry of the connectors.
+//!
+//! To add a connector, implement [`crate::SplitEnumerator`], [`crate::SplitReader`] and
+//! [`crate::SplitMetaData`] in its own module, describe its properties with
+//! [`crate::property::PropertySchema`], and register it in [`crate::for_all_connectors`]. The
+//! properties, split, enumerator and reader enums are all generated from the registry, so that
+//! sources resolve the connector by the `connector` property without any change to the source
+//! managers.
+
+/// Registers all the connectors, each with
+/// - the variant name in the enums dispatching to the connectors,
+/// - the name of the connector, which is the value of the `connector` property,
+/// - the types of its split, split enumerator and split reader. The properties type is the
+///   `Properties` of the enumerator, which must be the same as the reader's.
+///
+/// `$macro` is called with the registered connectors, e.g. `for_all_connectors! { impl_split }`.
+#[macro_export]
+macro_rules! for_all_connectors {
+    ($macro:ident) => {
+        $macro! {
+            { Kafka, $crate::kafka::KAFKA_CONNECTOR, $crate::kafka::KafkaSplit, $crate::kafka::enumerator::KafkaSplitEnumerator, $crate::kafka::source::KafkaSplitReader },
+            { Pulsar, $crate::pulsar::PULSAR_CONNECTOR, $crate::pulsar::PulsarSplit, $crate::pulsar::PulsarSplitEnumerator, $crate::pulsar::source::reader::PulsarSplitReader },
+            { Kinesis, $crate::kinesis::KINESIS_CONNECTOR, $crate::kinesis::split::KinesisSplit, $crate::kinesis::enumerator::client::KinesisSplitEnumerator, $crate::kinesis::source::reader::KinesisMultiSplitReader },
+            { Nexmark, $crate::nexmark::NEXMARK_CONNECTOR, $crate::nexmark::NexmarkSplit, $crate::nexmark::NexmarkSplitEnumerator, $crate::nexmark::source::reader::NexmarkSplitReader },
+            { Datagen, $crate::datagen::DATAGEN_CONNECTOR, $crate::datagen::DatagenSplit, $crate::datagen::DatagenSplitEnumerator, $crate::datagen::DatagenSplitReader }
+        }
+    };
+}
+
+macro_rules! impl_connector_names {
+    ($({ $variant_name:ident, $connector_name:path, $split:ty, $split_enumerator:ty, $split_reader:ty }),*) => {
+        /// Names of all the registered connectors.
+        pub fn connector_names() -> Vec<&'static str> {
+            vec![$( $connector_name ),*]
+        }
+    };
+}
+
+for_all_connectors! { impl_connector_names }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_connector_names() {
+        assert_eq!(
+            connector_names(),
+            vec!["kafka", "pulsar", "kinesis", "nexmark", "datagen"]
+        );
+    }
+}
@@ -12,4 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub mod compute;
 pub mod hummock;
+pub mod meta;
+pub mod stream;
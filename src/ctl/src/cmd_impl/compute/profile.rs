@@ -0,0 +1,65 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::path::Path;
+
+use clap::ArgEnum;
+use risingwave_common::util::addr::HostAddr;
+use risingwave_pb::monitor_service::{ProfileFormat, ProfileType};
+use risingwave_rpc_client::ComputeClient;
+
+#[derive(Copy, Clone, Debug, ArgEnum)]
+pub enum ProfileTypeArg {
+    Cpu,
+    Heap,
+}
+
+#[derive(Copy, Clone, Debug, ArgEnum)]
+pub enum ProfileFormatArg {
+    Pprof,
+    Flamegraph,
+}
+
+/// Takes a profile of the compute node at `addr` for `duration_sec` seconds, and writes it to
+/// `output`.
+pub async fn profile(
+    addr: &str,
+    profile_type: ProfileTypeArg,
+    format: ProfileFormatArg,
+    duration_sec: u64,
+    output: &Path,
+) -> anyhow::Result<()> {
+    let profile_type = match profile_type {
+        ProfileTypeArg::Cpu => ProfileType::Cpu,
+        ProfileTypeArg::Heap => ProfileType::Heap,
+    };
+    let format = match format {
+        ProfileFormatArg::Pprof => ProfileFormat::Pprof,
+        ProfileFormatArg::Flamegraph => ProfileFormat::Flamegraph,
+    };
+    let addr = HostAddr::try_from(addr)?;
+    let client = ComputeClient::new(addr.clone()).await?;
+    println!(
+        "taking a {:?} profile of {} for {} seconds",
+        profile_type, addr, duration_sec
+    );
+    let mut stream = client.profiling(profile_type, format, duration_sec).await?;
+    let mut profile = Vec::new();
+    while let Some(part) = stream.message().await? {
+        profile.extend_from_slice(&part.data);
+    }
+    std::fs::write(output, &profile)?;
+    println!("wrote {} bytes to {}", profile.len(), output.display());
+    Ok(())
+}
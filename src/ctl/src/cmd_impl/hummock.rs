@@ -16,3 +16,9 @@ mod list_version;
 pub use list_version::*;
 mod list_kv;
 pub use list_kv::*;
+mod list_sst;
+pub use list_sst::*;
+mod sst_dump;
+pub use sst_dump::*;
+mod trigger_manual_compaction;
+pub use trigger_manual_compaction::*;
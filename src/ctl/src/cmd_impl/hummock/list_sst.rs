@@ -0,0 +1,62 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use risingwave_hummock_sdk::compaction_group::CompactionGroupId;
+use risingwave_rpc_client::HummockMetaClient;
+
+use crate::common::{print_json, MetaServiceOpts};
+
+pub async fn list_sst(json: bool) -> anyhow::Result<()> {
+    let meta_opts = MetaServiceOpts::from_env()?;
+    let meta_client = meta_opts.create_meta_client().await?;
+    let version = meta_client.pin_version(u64::MAX).await?;
+    if json {
+        print_json(&version.levels)?;
+        meta_client.unpin_version(&[version.id]).await?;
+        return Ok(());
+    }
+    println!(
+        "version: {}, max_committed_epoch: {}, safe_epoch: {}",
+        version.id, version.max_committed_epoch, version.safe_epoch
+    );
+    let mut compaction_group_ids = version.levels.keys().cloned().collect::<Vec<_>>();
+    compaction_group_ids.sort_unstable();
+    for compaction_group_id in compaction_group_ids {
+        println!(
+            "compaction group {:?}",
+            CompactionGroupId::from(compaction_group_id)
+        );
+        for level in &version.levels[&compaction_group_id].levels {
+            println!(
+                "  level {}: {} SSTs, {} bytes",
+                level.level_idx,
+                level.table_infos.len(),
+                level.total_file_size
+            );
+            for sst in &level.table_infos {
+                let key_range = sst.key_range.as_ref().unwrap();
+                println!(
+                    "    SST {}: {} bytes, key range {:?}..={:?}{}",
+                    sst.id,
+                    sst.file_size,
+                    key_range.left,
+                    key_range.right,
+                    if key_range.inf { " (inf)" } else { "" }
+                );
+            }
+        }
+    }
+    meta_client.unpin_version(&[version.id]).await?;
+    Ok(())
+}
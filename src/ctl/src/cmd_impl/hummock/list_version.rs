@@ -14,13 +14,17 @@
 
 use risingwave_rpc_client::HummockMetaClient;
 
-use crate::common::MetaServiceOpts;
+use crate::common::{print_json, MetaServiceOpts};
 
-pub async fn list_version() -> anyhow::Result<()> {
+pub async fn list_version(json: bool) -> anyhow::Result<()> {
     let meta_opts = MetaServiceOpts::from_env()?;
     let meta_client = meta_opts.create_meta_client().await?;
     let version = meta_client.pin_version(u64::MAX).await?;
-    println!("{:#?}", version);
+    if json {
+        print_json(&version)?;
+    } else {
+        println!("{:#?}", version);
+    }
     meta_client.unpin_version(&[version.id]).await?;
     Ok(())
 }
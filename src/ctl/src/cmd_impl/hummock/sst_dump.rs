@@ -0,0 +1,46 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use risingwave_storage::monitor::StoreLocalStatistic;
+
+use crate::common::HummockServiceOpts;
+
+pub async fn sst_dump(sst_id: u64) -> anyhow::Result<()> {
+    let hummock_opts = HummockServiceOpts::from_env()?;
+    let hummock = hummock_opts.create_hummock_store().await?;
+    let sstable_store = hummock.inner().sstable_store();
+    let sstable = sstable_store
+        .sstable(sst_id, &mut StoreLocalStatistic::default())
+        .await?;
+    sstable_store.load_filter(sstable.value()).await?;
+    let meta = &sstable.value().meta;
+    println!("SST {}", sst_id);
+    println!("  format version: {}", meta.version);
+    println!("  estimated size: {} bytes", meta.estimated_size);
+    println!("  key count: {}", meta.key_count);
+    println!(
+        "  bloom filter: {} bytes",
+        sstable.value().bloom_filter().len()
+    );
+    println!("  smallest key: {:?}", meta.smallest_key);
+    println!("  largest key: {:?}", meta.largest_key);
+    println!("  {} blocks", meta.block_metas.len());
+    for (idx, block_meta) in meta.block_metas.iter().enumerate() {
+        println!(
+            "    block {}: offset {}, len {}, smallest key {:?}",
+            idx, block_meta.offset, block_meta.len, block_meta.smallest_key
+        );
+    }
+    Ok(())
+}
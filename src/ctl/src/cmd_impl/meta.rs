@@ -0,0 +1,26 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod backup_meta;
+pub use backup_meta::*;
+mod list_workers;
+pub use list_workers::*;
+mod restore_meta;
+pub use restore_meta::*;
+mod set_log_filter;
+pub use set_log_filter::*;
+mod trigger_recovery;
+pub use trigger_recovery::*;
+mod unregister_worker;
+pub use unregister_worker::*;
@@ -0,0 +1,48 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use risingwave_pb::common::WorkerType;
+
+use crate::common::{print_json, MetaServiceOpts};
+
+pub async fn list_workers(json: bool) -> anyhow::Result<()> {
+    let meta_opts = MetaServiceOpts::from_env()?;
+    let meta_client = meta_opts.create_meta_client().await?;
+    let mut workers = vec![];
+    for worker_type in [
+        WorkerType::Frontend,
+        WorkerType::ComputeNode,
+        WorkerType::Compactor,
+        WorkerType::RiseCtl,
+    ] {
+        workers.extend(meta_client.list_all_nodes(worker_type, true).await?);
+    }
+    workers.sort_by_key(|worker| worker.id);
+    if json {
+        return print_json(&workers);
+    }
+    for worker in &workers {
+        let host = worker.host.as_ref().unwrap();
+        println!(
+            "worker {}: {:?} at {}:{}, {:?}, {} parallel units",
+            worker.id,
+            worker.r#type(),
+            host.host,
+            host.port,
+            worker.state(),
+            worker.parallel_units.len()
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,27 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::common::MetaServiceOpts;
+
+pub async fn set_log_filter(directives: &str) -> anyhow::Result<()> {
+    let meta_opts = MetaServiceOpts::from_env()?;
+    let meta_client = meta_opts.create_meta_client().await?;
+    meta_client.set_log_filter(directives.to_string()).await?;
+    if directives.is_empty() {
+        println!("restored the default log filter");
+    } else {
+        println!("changed the log filter to {}", directives);
+    }
+    Ok(())
+}
@@ -0,0 +1,26 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use risingwave_common::util::addr::HostAddr;
+
+use crate::common::MetaServiceOpts;
+
+pub async fn unregister_worker(addr: &str) -> anyhow::Result<()> {
+    let addr = HostAddr::try_from(addr)?;
+    let meta_opts = MetaServiceOpts::from_env()?;
+    let meta_client = meta_opts.create_meta_client().await?;
+    meta_client.unregister(addr.clone()).await?;
+    println!("unregistered worker {}", addr);
+    Ok(())
+}
@@ -0,0 +1,85 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use anyhow::anyhow;
+use risingwave_pb::stream_plan::StreamNode;
+
+use crate::common::{print_json, MetaServiceOpts};
+
+fn print_plan(node: &StreamNode, depth: usize) {
+    println!("{}{}", "  ".repeat(depth), node.identity);
+    for input in &node.input {
+        print_plan(input, depth + 1);
+    }
+}
+
+pub async fn dump_fragments(table_id: u32, json: bool) -> anyhow::Result<()> {
+    let meta_opts = MetaServiceOpts::from_env()?;
+    let meta_client = meta_opts.create_meta_client().await?;
+    let table_fragments = meta_client
+        .list_table_fragments(&[table_id])
+        .await?
+        .pop()
+        .ok_or_else(|| anyhow!("table {} not found", table_id))?;
+    if json {
+        return print_json(&table_fragments);
+    }
+    let mut fragment_ids = table_fragments
+        .fragments
+        .keys()
+        .cloned()
+        .collect::<Vec<_>>();
+    fragment_ids.sort_unstable();
+    for fragment_id in fragment_ids {
+        let fragment = &table_fragments.fragments[&fragment_id];
+        println!(
+            "fragment {}: {:?}, {:?} distribution",
+            fragment_id,
+            fragment.fragment_type(),
+            fragment.distribution_type()
+        );
+        for actor in &fragment.actors {
+            let location = match table_fragments.actor_status.get(&actor.actor_id) {
+                Some(status) => format!(
+                    "parallel unit {} ({:?})",
+                    status
+                        .parallel_unit
+                        .as_ref()
+                        .map(|parallel_unit| parallel_unit.id)
+                        .unwrap_or_default(),
+                    status.state()
+                ),
+                None => "unknown location".to_string(),
+            };
+            let downstream = actor
+                .dispatcher
+                .iter()
+                .flat_map(|dispatcher| dispatcher.downstream_actor_id.iter())
+                .collect::<Vec<_>>();
+            println!(
+                "  actor {} on {}, upstream {:?}, downstream {:?}",
+                actor.actor_id, location, actor.upstream_actor_id, downstream
+            );
+        }
+        // All actors of a fragment share the same plan.
+        if let Some(nodes) = fragment
+            .actors
+            .first()
+            .and_then(|actor| actor.nodes.as_ref())
+        {
+            print_plan(nodes, 2);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,57 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use risingwave_pb::meta::table_fragments::ActorState;
+use serde::Serialize;
+
+use crate::common::{print_json, MetaServiceOpts};
+
+#[derive(Serialize)]
+struct StreamingJob {
+    table_id: u32,
+    fragments: usize,
+    actors: usize,
+    running_actors: usize,
+}
+
+pub async fn list_jobs(json: bool) -> anyhow::Result<()> {
+    let meta_opts = MetaServiceOpts::from_env()?;
+    let meta_client = meta_opts.create_meta_client().await?;
+    let mut jobs = meta_client
+        .list_table_fragments(&[])
+        .await?
+        .into_iter()
+        .map(|table_fragments| StreamingJob {
+            table_id: table_fragments.table_id,
+            fragments: table_fragments.fragments.len(),
+            actors: table_fragments.actor_status.len(),
+            running_actors: table_fragments
+                .actor_status
+                .values()
+                .filter(|status| status.state() == ActorState::Running)
+                .count(),
+        })
+        .collect::<Vec<_>>();
+    jobs.sort_by_key(|job| job.table_id);
+    if json {
+        return print_json(&jobs);
+    }
+    for job in &jobs {
+        println!(
+            "table {}: {} fragments, {} actors ({} running)",
+            job.table_id, job.fragments, job.actors, job.running_actors
+        );
+    }
+    Ok(())
+}
@@ -12,7 +12,11 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::path::PathBuf;
+
 use clap::{Parser, Subcommand};
+use cmd_impl::compute::{ProfileFormatArg, ProfileTypeArg};
+use risingwave_meta::backup::RestoreOpts;
 mod cmd_impl;
 pub(crate) mod common;
 
@@ -21,6 +25,10 @@ pub(crate) mod common;
 #[clap(propagate_version = true)]
 #[clap(infer_subcommands = true)]
 pub struct CliOpts {
+    /// print the output as JSON, for commands that support it
+    #[clap(long, global = true)]
+    json: bool,
+
     #[clap(subcommand)]
     command: Commands,
 }
@@ -31,6 +39,15 @@ enum Commands {
     /// Commands for Hummock
     #[clap(subcommand)]
     Hummock(HummockCommands),
+    /// Commands for Meta
+    #[clap(subcommand)]
+    Meta(MetaCommands),
+    /// Commands for streaming jobs
+    #[clap(subcommand)]
+    Stream(StreamCommands),
+    /// Commands for compute nodes
+    #[clap(subcommand)]
+    Compute(ComputeCommands),
 }
 
 #[derive(Subcommand)]
@@ -45,15 +62,152 @@ enum HummockCommands {
         #[clap(short, long = "table-id", default_value_t = u32::MAX)]
         tableid: u32,
     },
+    /// list SSTs of each level in the latest Hummock version, with their sizes and key ranges
+    ListSst,
+    /// print the metadata of an SST, including its blocks
+    SstDump {
+        #[clap(short, long = "sst-id")]
+        sst_id: u64,
+    },
+    /// trigger a compaction of a level in a compaction group, optionally only the SSTs of a table
+    TriggerManualCompaction {
+        #[clap(short, long = "compaction-group-id")]
+        compaction_group_id: u64,
+
+        #[clap(short, long = "table-id")]
+        table_id: Option<u32>,
+
+        #[clap(short, long = "level", default_value_t = 0)]
+        level: u32,
+    },
+}
+
+#[derive(Subcommand)]
+enum MetaCommands {
+    /// take a backup of the meta store into the backup storage of meta node
+    Backup,
+    /// restore the meta store of a fresh cluster from a backup, before starting its meta node
+    Restore(RestoreOpts),
+    /// list all workers registered in the cluster
+    ListWorkers,
+    /// remove a worker from the cluster, e.g. a compute node that is gone for good
+    UnregisterWorker {
+        /// address of the worker, e.g. 127.0.0.1:5688
+        #[clap(short, long = "addr")]
+        addr: String,
+    },
+    /// rebuild all actors from the latest committed epoch
+    TriggerRecovery,
+    /// change the log filter of the meta node, frontends and compute nodes until they restart
+    SetLogFilter {
+        /// comma-separated `target=level`s overriding the default levels, e.g.
+        /// `events::stream::barrier=trace`, or empty to restore the default filter
+        #[clap(default_value = "")]
+        directives: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum StreamCommands {
+    /// list the streaming jobs, i.e. materialized views and sources, with their actor counts
+    ListJobs,
+    /// print the fragments of a streaming job, with their actors and plans
+    DumpFragments {
+        #[clap(short, long = "table-id")]
+        table_id: u32,
+    },
+    /// stop all sources from reading new data, until resumed
+    Pause,
+    /// resume the paused sources
+    Resume,
+    /// wait for the next barrier to be collected
+    Checkpoint,
+}
+
+#[derive(Subcommand)]
+enum ComputeCommands {
+    /// take a CPU or heap profile of a compute node started with `--enable-profiling`
+    Profile {
+        /// address of the compute node, e.g. 127.0.0.1:5688
+        #[clap(short, long = "addr")]
+        addr: String,
+
+        #[clap(long = "type", arg_enum, default_value_t = ProfileTypeArg::Cpu)]
+        profile_type: ProfileTypeArg,
+
+        /// flamegraph is only available for CPU profiles, and heap profiles are to be viewed by
+        /// `jeprof`
+        #[clap(long, arg_enum, default_value_t = ProfileFormatArg::Pprof)]
+        format: ProfileFormatArg,
+
+        /// how long to sample for, in seconds
+        #[clap(short, long, default_value_t = 10)]
+        duration: u64,
+
+        /// the file to write the profile to
+        #[clap(short, long)]
+        output: PathBuf,
+    },
 }
 
 pub async fn start(opts: CliOpts) {
     match &opts.command {
         Commands::Hummock(HummockCommands::ListVersion) => {
-            cmd_impl::hummock::list_version().await.unwrap()
+            cmd_impl::hummock::list_version(opts.json).await.unwrap()
         }
         Commands::Hummock(HummockCommands::ListKv { epoch, tableid }) => {
             cmd_impl::hummock::list_kv(*epoch, *tableid).await.unwrap()
         }
+        Commands::Hummock(HummockCommands::ListSst) => {
+            cmd_impl::hummock::list_sst(opts.json).await.unwrap()
+        }
+        Commands::Hummock(HummockCommands::SstDump { sst_id }) => {
+            cmd_impl::hummock::sst_dump(*sst_id).await.unwrap()
+        }
+        Commands::Hummock(HummockCommands::TriggerManualCompaction {
+            compaction_group_id,
+            table_id,
+            level,
+        }) => cmd_impl::hummock::trigger_manual_compaction(*compaction_group_id, *table_id, *level)
+            .await
+            .unwrap(),
+        Commands::Meta(MetaCommands::Backup) => cmd_impl::meta::backup_meta().await.unwrap(),
+        Commands::Meta(MetaCommands::Restore(opts)) => {
+            cmd_impl::meta::restore_meta(opts.clone()).await.unwrap()
+        }
+        Commands::Meta(MetaCommands::ListWorkers) => {
+            cmd_impl::meta::list_workers(opts.json).await.unwrap()
+        }
+        Commands::Meta(MetaCommands::UnregisterWorker { addr }) => {
+            cmd_impl::meta::unregister_worker(addr).await.unwrap()
+        }
+        Commands::Meta(MetaCommands::TriggerRecovery) => {
+            cmd_impl::meta::trigger_recovery().await.unwrap()
+        }
+        Commands::Meta(MetaCommands::SetLogFilter { directives }) => {
+            cmd_impl::meta::set_log_filter(directives).await.unwrap()
+        }
+        Commands::Stream(StreamCommands::ListJobs) => {
+            cmd_impl::stream::list_jobs(opts.json).await.unwrap()
+        }
+        Commands::Stream(StreamCommands::DumpFragments { table_id }) => {
+            cmd_impl::stream::dump_fragments(*table_id, opts.json)
+                .await
+                .unwrap()
+        }
+        Commands::Stream(StreamCommands::Pause) => cmd_impl::stream::pause().await.unwrap(),
+        Commands::Stream(StreamCommands::Resume) => cmd_impl::stream::resume().await.unwrap(),
+        Commands::Stream(StreamCommands::Checkpoint) => {
+            cmd_impl::stream::checkpoint().await.unwrap()
+        }
+        Commands::Compute(ComputeCommands::Profile {
+            addr,
+            profile_type,
+            format,
+            duration,
+            output,
+        }) => cmd_impl::compute::profile(addr, *profile_type, *format, *duration, output)
+            .await
+            .unwrap(),
     }
 }
@@ -0,0 +1,113 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use itertools::multizip;
+use risingwave_common::array::column::Column;
+use risingwave_common::array::{
+    Array, ArrayBuilder, ArrayImpl, BoolArray, DataChunk, I32Array, PrimitiveArray,
+};
+use risingwave_common::error::Result;
+use risingwave_common::types::{DataType, Scalar};
+use risingwave_expr::expr::expr_binary_nonnull::new_binary_expr;
+use risingwave_expr::expr::{BoxedExpression, InputRefExpression};
+use risingwave_expr::vector_op::arithmetic_op::general_add;
+use risingwave_expr::vector_op::cmp::general_lt;
+use risingwave_pb::expr::expr_node::Type;
+
+const CHUNK_SIZE: usize = 1024;
+
+/// Creates a chunk of two int32 columns, where one of `null_every` rows is null.
+fn make_chunk(null_every: Option<usize>) -> DataChunk {
+    let columns = (0..2)
+        .map(|col| {
+            let values = (0..CHUNK_SIZE)
+                .map(|i| match null_every {
+                    Some(n) if i % n == col => None,
+                    _ => Some((i * (col + 1)) as i32),
+                })
+                .collect::<Vec<_>>();
+            Column::new(Arc::new(I32Array::from_slice(&values).unwrap().into()))
+        })
+        .collect();
+    DataChunk::new(columns, CHUNK_SIZE)
+}
+
+fn make_expr(expr_type: Type, ret: DataType) -> BoxedExpression {
+    new_binary_expr(
+        expr_type,
+        ret,
+        Box::new(InputRefExpression::new(DataType::Int32, 0)),
+        Box::new(InputRefExpression::new(DataType::Int32, 1)),
+    )
+}
+
+/// Evaluates the binary function row by row, in the same way as the template expressions, as the
+/// baseline.
+fn eval_row_by_row<OA: Array>(
+    chunk: &DataChunk,
+    func: impl Fn(i32, i32) -> Result<OA::OwnedItem>,
+) -> Result<ArrayImpl>
+where
+    ArrayImpl: From<OA>,
+{
+    let l: &PrimitiveArray<i32> = chunk.column_at(0).array_ref().into();
+    let r: &PrimitiveArray<i32> = chunk.column_at(1).array_ref().into();
+    let mut builder = OA::Builder::new(chunk.capacity())?;
+    for (l, r) in multizip((l.iter(), r.iter())) {
+        match (l, r) {
+            (Some(l), Some(r)) => {
+                let ret = func(l, r)?;
+                builder.append(Some(ret.as_scalar_ref()))?;
+            }
+            _ => builder.append(None)?,
+        }
+    }
+    Ok(builder.finish()?.into())
+}
+
+fn bench_binary_expr(c: &mut Criterion) {
+    for (name, null_every) in [("no null", None), ("nullable", Some(4))] {
+        let chunk = make_chunk(null_every);
+
+        let add = make_expr(Type::Add, DataType::Int32);
+        c.bench_with_input(
+            BenchmarkId::new("i32 + i32 - vectorized", name),
+            &chunk,
+            |b, chunk| b.iter(|| add.eval(chunk).unwrap()),
+        );
+        c.bench_with_input(
+            BenchmarkId::new("i32 + i32 - row by row", name),
+            &chunk,
+            |b, chunk| b.iter(|| eval_row_by_row::<I32Array>(chunk, general_add).unwrap()),
+        );
+
+        let lt = make_expr(Type::LessThan, DataType::Boolean);
+        c.bench_with_input(
+            BenchmarkId::new("i32 < i32 - vectorized", name),
+            &chunk,
+            |b, chunk| b.iter(|| lt.eval(chunk).unwrap()),
+        );
+        c.bench_with_input(
+            BenchmarkId::new("i32 < i32 - row by row", name),
+            &chunk,
+            |b, chunk| b.iter(|| eval_row_by_row::<BoolArray>(chunk, general_lt).unwrap()),
+        );
+    }
+}
+
+criterion_group!(benches, bench_binary_expr);
+criterion_main!(benches);
@@ -0,0 +1,251 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Conversions between SQL values and Arrow arrays, used to exchange data with external UDF
+//! servers and Arrow Flight clients.
+
+use std::sync::Arc;
+
+use arrow::array::{
+    Array as ArrowArray, ArrayRef as ArrowArrayRef, BooleanArray, Float32Array, Float64Array,
+    Int16Array, Int32Array, Int64Array, StringArray,
+};
+use arrow::compute::cast;
+use arrow::datatypes::{DataType as ArrowDataType, Field as ArrowField, Schema, SchemaRef};
+use arrow::record_batch::RecordBatch;
+use itertools::Itertools;
+use risingwave_common::array::{ArrayImpl, DataChunk};
+use risingwave_common::catalog::Field;
+use risingwave_common::error::ErrorCode::{InternalError, NotImplemented};
+use risingwave_common::error::Result;
+use risingwave_common::types::{DataType, Datum, ScalarImpl};
+
+/// Maps a SQL type to the Arrow type representing it.
+pub fn arrow_type(data_type: &DataType) -> Result<ArrowDataType> {
+    match data_type {
+        DataType::Boolean => Ok(ArrowDataType::Boolean),
+        DataType::Int16 => Ok(ArrowDataType::Int16),
+        DataType::Int32 => Ok(ArrowDataType::Int32),
+        DataType::Int64 => Ok(ArrowDataType::Int64),
+        DataType::Float32 => Ok(ArrowDataType::Float32),
+        DataType::Float64 => Ok(ArrowDataType::Float64),
+        DataType::Varchar => Ok(ArrowDataType::Utf8),
+        _ => Err(NotImplemented(format!("type {:?} in Arrow", data_type), None.into()).into()),
+    }
+}
+
+/// Maps the fields of a SQL schema to an Arrow schema. All the fields are nullable.
+pub fn arrow_schema(fields: &[Field]) -> Result<Schema> {
+    let fields = fields
+        .iter()
+        .map(|field| {
+            Ok(ArrowField::new(
+                &field.name,
+                arrow_type(&field.data_type)?,
+                true,
+            ))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(Schema::new(fields))
+}
+
+pub fn to_arrow_array(data_type: &DataType, datums: Vec<Datum>) -> Result<ArrowArrayRef> {
+    macro_rules! build {
+        ($array:ty, $variant:ident, $value:ident => $convert:expr) => {{
+            let values = datums
+                .into_iter()
+                .map(|datum| match datum {
+                    Some(ScalarImpl::$variant($value)) => Ok(Some($convert)),
+                    None => Ok(None),
+                    Some(scalar) => Err(InternalError(format!(
+                        "unexpected value {:?} for type {:?}",
+                        scalar, data_type
+                    ))),
+                })
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+            Arc::new(<$array>::from(values)) as ArrowArrayRef
+        }};
+    }
+    let array = match data_type {
+        DataType::Boolean => build!(BooleanArray, Bool, v => v),
+        DataType::Int16 => build!(Int16Array, Int16, v => v),
+        DataType::Int32 => build!(Int32Array, Int32, v => v),
+        DataType::Int64 => build!(Int64Array, Int64, v => v),
+        DataType::Float32 => build!(Float32Array, Float32, v => v.0),
+        DataType::Float64 => build!(Float64Array, Float64, v => v.0),
+        DataType::Varchar => build!(StringArray, Utf8, v => v),
+        _ => return Err(arrow_type(data_type).unwrap_err()),
+    };
+    Ok(array)
+}
+
+pub fn from_arrow_array(data_type: &DataType, array: &ArrowArrayRef) -> Result<Vec<Datum>> {
+    macro_rules! convert {
+        ($array:ty, $variant:ident, $value:ident => $convert:expr) => {
+            array
+                .as_any()
+                .downcast_ref::<$array>()
+                .ok_or_else(|| {
+                    InternalError(format!(
+                        "expect Arrow array of {:?}, got {:?}",
+                        data_type,
+                        array.data_type()
+                    ))
+                })?
+                .iter()
+                .map(|v| v.map(|$value| ScalarImpl::$variant($convert)))
+                .collect()
+        };
+    }
+    let datums = match data_type {
+        DataType::Boolean => convert!(BooleanArray, Bool, v => v),
+        DataType::Int16 => convert!(Int16Array, Int16, v => v),
+        DataType::Int32 => convert!(Int32Array, Int32, v => v),
+        DataType::Int64 => convert!(Int64Array, Int64, v => v),
+        DataType::Float32 => convert!(Float32Array, Float32, v => v.into()),
+        DataType::Float64 => convert!(Float64Array, Float64, v => v.into()),
+        DataType::Varchar => convert!(StringArray, Utf8, v => v.to_string()),
+        _ => return Err(arrow_type(data_type).unwrap_err()),
+    };
+    Ok(datums)
+}
+
+/// Converts an Arrow array to an array of `data_type`, casting the values if the Arrow array is of
+/// another type. Values failing the cast become nulls.
+pub fn from_arrow_column(data_type: &DataType, array: &ArrowArrayRef) -> Result<ArrayImpl> {
+    let array = cast(array, &arrow_type(data_type)?).map_err(|e| InternalError(e.to_string()))?;
+    let datums = from_arrow_array(data_type, &array)?;
+    let mut builder = data_type.create_array_builder(datums.len())?;
+    for datum in &datums {
+        builder.append_datum(datum)?;
+    }
+    builder.finish()
+}
+
+/// Maps an Arrow type to the SQL type it represents, the reverse of [`arrow_type`].
+fn sql_type(data_type: &ArrowDataType) -> Result<DataType> {
+    match data_type {
+        ArrowDataType::Boolean => Ok(DataType::Boolean),
+        ArrowDataType::Int16 => Ok(DataType::Int16),
+        ArrowDataType::Int32 => Ok(DataType::Int32),
+        ArrowDataType::Int64 => Ok(DataType::Int64),
+        ArrowDataType::Float32 => Ok(DataType::Float32),
+        ArrowDataType::Float64 => Ok(DataType::Float64),
+        ArrowDataType::Utf8 => Ok(DataType::Varchar),
+        _ => Err(NotImplemented(format!("Arrow type {:?}", data_type), None.into()).into()),
+    }
+}
+
+/// Converts the visible rows of `chunk` to a record batch of `schema`, which is the
+/// [`arrow_schema`] of the fields of the chunk.
+pub fn to_record_batch(schema: SchemaRef, chunk: DataChunk) -> Result<RecordBatch> {
+    let chunk = chunk.compact()?;
+    let columns = chunk
+        .columns()
+        .iter()
+        .zip_eq(schema.fields())
+        .map(|(column, field)| {
+            let array = column.array_ref();
+            let datums = (0..chunk.cardinality())
+                .map(|i| array.datum_at(i))
+                .collect();
+            to_arrow_array(&sql_type(field.data_type())?, datums)
+        })
+        .collect::<Result<Vec<_>>>()?;
+    RecordBatch::try_new(schema, columns).map_err(|e| InternalError(e.to_string()).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_common::array::{I32Array, Utf8Array};
+    use risingwave_common::buffer::Bitmap;
+    use risingwave_common::column;
+
+    use super::*;
+
+    #[test]
+    fn test_arrow_conversion() {
+        let cases = vec![
+            (
+                DataType::Int32,
+                vec![Some(ScalarImpl::Int32(1)), None, Some(ScalarImpl::Int32(3))],
+            ),
+            (
+                DataType::Float64,
+                vec![None, Some(ScalarImpl::Float64(1.5.into()))],
+            ),
+            (
+                DataType::Varchar,
+                vec![Some(ScalarImpl::Utf8("abc".into())), None],
+            ),
+            (DataType::Boolean, vec![Some(ScalarImpl::Bool(true))]),
+        ];
+        for (data_type, datums) in cases {
+            let array = to_arrow_array(&data_type, datums.clone()).unwrap();
+            assert_eq!(array.data_type(), &arrow_type(&data_type).unwrap());
+            assert_eq!(from_arrow_array(&data_type, &array).unwrap(), datums);
+        }
+        assert!(from_arrow_array(
+            &DataType::Int64,
+            &to_arrow_array(&DataType::Int32, vec![None]).unwrap()
+        )
+        .is_err());
+        assert!(arrow_type(&DataType::Date).is_err());
+
+        // Values are casted to the SQL type.
+        let array = to_arrow_array(
+            &DataType::Varchar,
+            vec![
+                Some(ScalarImpl::Utf8("1".into())),
+                Some(ScalarImpl::Utf8("a".into())),
+            ],
+        )
+        .unwrap();
+        let array = from_arrow_column(&DataType::Int64, &array).unwrap();
+        assert_eq!(array.datum_at(0), Some(ScalarImpl::Int64(1)));
+        assert_eq!(array.datum_at(1), None);
+    }
+
+    #[test]
+    fn test_to_record_batch() {
+        let fields = vec![
+            Field::with_name(DataType::Int32, "v1"),
+            Field::with_name(DataType::Varchar, "v2"),
+        ];
+        let schema = Arc::new(arrow_schema(&fields).unwrap());
+        let chunk = DataChunk::new(
+            vec![
+                column!(I32Array, [Some(1), Some(2), None]),
+                column!(Utf8Array, [Some("a"), None, Some("c")]),
+            ],
+            Bitmap::try_from(vec![true, false, true]).unwrap(),
+        );
+
+        // Only the visible rows are converted.
+        let batch = to_record_batch(schema, chunk).unwrap();
+        assert_eq!(batch.num_rows(), 2);
+        assert_eq!(batch.schema().field(1).name(), "v2");
+        assert_eq!(
+            from_arrow_array(&DataType::Int32, batch.column(0)).unwrap(),
+            vec![Some(ScalarImpl::Int32(1)), None]
+        );
+        assert_eq!(
+            from_arrow_array(&DataType::Varchar, batch.column(1)).unwrap(),
+            vec![
+                Some(ScalarImpl::Utf8("a".into())),
+                Some(ScalarImpl::Utf8("c".into()))
+            ]
+        );
+    }
+}
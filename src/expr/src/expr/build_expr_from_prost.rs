@@ -12,7 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use risingwave_common::array::DataChunk;
+use risingwave_common::array::{DataChunk, DecimalArray, I32Array};
 use risingwave_common::ensure;
 use risingwave_common::error::{ErrorCode, Result, RwError};
 use risingwave_common::types::{DataType, ToOwnedDatum};
@@ -30,7 +30,9 @@ use crate::expr::expr_ternary_bytes::{
 use crate::expr::expr_unary::{
     new_length_default, new_ltrim_expr, new_rtrim_expr, new_trim_expr, new_unary_expr,
 };
+use crate::expr::template::TernaryExpression;
 use crate::expr::{build_from_prost as expr_build_from_prost, BoxedExpression};
+use crate::vector_op::cast::rescale_decimal;
 
 fn get_children_and_return_type(prost: &ExprNode) -> Result<(Vec<ExprNode>, DataType)> {
     let ret_type = DataType::from(prost.get_return_type()?);
@@ -125,6 +127,27 @@ pub fn build_replace_expr(prost: &ExprNode) -> Result<BoxedExpression> {
     Ok(new_replace_expr(s, from_str, to_str, ret_type))
 }
 
+pub fn build_decimal_rescale_expr(prost: &ExprNode) -> Result<BoxedExpression> {
+    let (children, ret_type) = get_children_and_return_type(prost)?;
+    ensure!(children.len() == 3);
+    let input = expr_build_from_prost(&children[0])?;
+    let precision = expr_build_from_prost(&children[1])?;
+    let scale = expr_build_from_prost(&children[2])?;
+    Ok(Box::new(TernaryExpression::<
+        DecimalArray,
+        I32Array,
+        I32Array,
+        DecimalArray,
+        _,
+    >::new(
+        input,
+        precision,
+        scale,
+        ret_type,
+        rescale_decimal,
+    )))
+}
+
 pub fn build_length_expr(prost: &ExprNode) -> Result<BoxedExpression> {
     let (children, ret_type) = get_children_and_return_type(prost)?;
     // TODO: add encoding length expr
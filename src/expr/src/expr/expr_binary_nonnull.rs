@@ -19,6 +19,7 @@ use risingwave_common::array::{
 use risingwave_common::types::*;
 use risingwave_pb::expr::expr_node::Type;
 
+use crate::expr::expr_binary_primitive::{PrimitiveAtmExpression, PrimitiveCmpExpression};
 use crate::expr::template::BinaryExpression;
 use crate::expr::BoxedExpression;
 use crate::for_all_cmp_variants;
@@ -135,6 +136,67 @@ macro_rules! gen_shift_impl {
     };
 }
 
+/// This macro helps create comparison expression between integers, which evaluates over the raw
+/// values of the arrays. Similar to `gen_cmp_impl`.
+macro_rules! gen_primitive_cmp_impl {
+    ([$l:expr, $r:expr, $ret:expr], $( { $i1:ident, $i2:ident, $cast:ident, $func:ident }, )*) => {
+        match ($l.return_type(), $r.return_type()) {
+            $(
+                ($i1! { type_match_pattern }, $i2! { type_match_pattern }) => {
+                    Box::new(
+                        PrimitiveCmpExpression::<
+                            <$i1! { type_array } as Array>::OwnedItem,
+                            <$i2! { type_array } as Array>::OwnedItem,
+                            _
+                        >::new(
+                            $l,
+                            $r,
+                            $ret,
+                            $func::<
+                                <$i1! { type_array } as Array>::OwnedItem,
+                                <$i2! { type_array } as Array>::OwnedItem,
+                                <$cast! { type_array } as Array>::OwnedItem
+                            >,
+                        )
+                    )
+                },
+            )*
+            _ => unreachable!(),
+        }
+    };
+}
+
+/// This macro helps create arithmetic expression between integers, which evaluates over the raw
+/// values of the arrays. Similar to `gen_atm_impl`.
+macro_rules! gen_primitive_atm_impl {
+    ([$l:expr, $r:expr, $ret:expr], $( { $i1:ident, $i2:ident, $rt:ident, $func:ident }, )*) => {
+        match ($l.return_type(), $r.return_type()) {
+            $(
+                ($i1! { type_match_pattern }, $i2! { type_match_pattern }) => {
+                    Box::new(
+                        PrimitiveAtmExpression::<
+                            <$i1! { type_array } as Array>::OwnedItem,
+                            <$i2! { type_array } as Array>::OwnedItem,
+                            <$rt! { type_array } as Array>::OwnedItem,
+                            _
+                        >::new(
+                            $l,
+                            $r,
+                            $ret,
+                            $func::<
+                                <$i1! { type_array } as Array>::OwnedItem,
+                                <$i2! { type_array } as Array>::OwnedItem,
+                                <$rt! { type_array } as Array>::OwnedItem
+                            >,
+                        )
+                    )
+                },
+            )*
+            _ => unreachable!(),
+        }
+    };
+}
+
 /// Based on the data type of `$l`, `$r`, `$ret`, return corresponding expression struct with scalar
 /// function inside.
 /// * `$l`: left expression
@@ -303,6 +365,34 @@ macro_rules! gen_binary_expr_shift {
     };
 }
 
+/// `gen_binary_expr_primitive` lists the integral type combinations, which are evaluated with the
+/// `Primitive*Expression`s instead of the row-by-row template expressions. Check
+/// `is_primitive_int` before using it.
+/// * `$general_f`: generic function over primitives that never fails
+macro_rules! gen_binary_expr_primitive {
+    ($macro:ident, $l:expr, $r:expr, $ret:expr, $general_f:ident $(,)?) => {
+        $macro! {
+            [$l, $r, $ret],
+            { int16, int16, int16, $general_f },
+            { int16, int32, int32, $general_f },
+            { int16, int64, int64, $general_f },
+            { int32, int16, int32, $general_f },
+            { int32, int32, int32, $general_f },
+            { int32, int64, int64, $general_f },
+            { int64, int16, int64, $general_f },
+            { int64, int32, int64, $general_f },
+            { int64, int64, int64, $general_f },
+        }
+    };
+}
+
+/// Whether both sides are integers, so that the expression can be evaluated over the raw values
+/// of the arrays with `gen_binary_expr_primitive`.
+fn is_primitive_int(l: &BoxedExpression, r: &BoxedExpression) -> bool {
+    let is_int = |ty: DataType| matches!(ty, DataType::Int16 | DataType::Int32 | DataType::Int64);
+    is_int(l.return_type()) && is_int(r.return_type())
+}
+
 fn build_extract_expr(ret: DataType, l: BoxedExpression, r: BoxedExpression) -> BoxedExpression {
     match r.return_type() {
         DataType::Date => Box::new(
@@ -333,24 +423,45 @@ pub fn new_binary_expr(
 ) -> BoxedExpression {
     use crate::expr::data_types::*;
     match expr_type {
+        Type::Equal if is_primitive_int(&l, &r) => {
+            gen_binary_expr_primitive! {gen_primitive_cmp_impl, l, r, ret, primitive_eq}
+        }
         Type::Equal => {
             gen_binary_expr_cmp! {gen_cmp_impl, general_eq, EQ, l, r, ret}
         }
+        Type::NotEqual if is_primitive_int(&l, &r) => {
+            gen_binary_expr_primitive! {gen_primitive_cmp_impl, l, r, ret, primitive_ne}
+        }
         Type::NotEqual => {
             gen_binary_expr_cmp! {gen_cmp_impl, general_ne, NE, l, r, ret}
         }
+        Type::LessThan if is_primitive_int(&l, &r) => {
+            gen_binary_expr_primitive! {gen_primitive_cmp_impl, l, r, ret, primitive_lt}
+        }
         Type::LessThan => {
             gen_binary_expr_cmp! {gen_cmp_impl, general_lt, LT, l, r, ret}
         }
+        Type::GreaterThan if is_primitive_int(&l, &r) => {
+            gen_binary_expr_primitive! {gen_primitive_cmp_impl, l, r, ret, primitive_gt}
+        }
         Type::GreaterThan => {
             gen_binary_expr_cmp! {gen_cmp_impl, general_gt, GT, l, r, ret}
         }
+        Type::GreaterThanOrEqual if is_primitive_int(&l, &r) => {
+            gen_binary_expr_primitive! {gen_primitive_cmp_impl, l, r, ret, primitive_ge}
+        }
         Type::GreaterThanOrEqual => {
             gen_binary_expr_cmp! {gen_cmp_impl, general_ge, GE, l, r, ret}
         }
+        Type::LessThanOrEqual if is_primitive_int(&l, &r) => {
+            gen_binary_expr_primitive! {gen_primitive_cmp_impl, l, r, ret, primitive_le}
+        }
         Type::LessThanOrEqual => {
             gen_binary_expr_cmp! {gen_cmp_impl, general_le, LE, l, r, ret}
         }
+        Type::Add if is_primitive_int(&l, &r) => {
+            gen_binary_expr_primitive! {gen_primitive_atm_impl, l, r, ret, primitive_add}
+        }
         Type::Add => {
             gen_binary_expr_atm! {
                 gen_atm_impl,
@@ -361,10 +472,21 @@ pub fn new_binary_expr(
                     { interval, timestamp, timestamp, interval_timestamp_add },
                     { interval, date, timestamp, interval_date_add },
                     { date, interval, timestamp, date_interval_add },
+                    { timestampz, interval, timestampz, timestampz_interval_add },
+                    { interval, timestampz, timestampz, interval_timestampz_add },
+                    { time, interval, time, time_interval_add },
+                    { interval, time, time, interval_time_add },
+                    { date, int32, date, date_int_add },
+                    { int32, date, date, int_date_add },
+                    { date, time, timestamp, date_time_add },
+                    { time, date, timestamp, time_date_add },
                     { interval, interval, interval, general_add },
                 },
             }
         }
+        Type::Subtract if is_primitive_int(&l, &r) => {
+            gen_binary_expr_primitive! {gen_primitive_atm_impl, l, r, ret, primitive_sub}
+        }
         Type::Subtract => {
             gen_binary_expr_atm! {
                 gen_atm_impl,
@@ -375,10 +497,18 @@ pub fn new_binary_expr(
                     { timestamp, interval, timestamp, timestamp_interval_sub },
                     { date, date, int32, date_date_sub },
                     { date, interval, timestamp, date_interval_sub },
+                    { timestampz, timestampz, interval, timestampz_timestampz_sub },
+                    { timestampz, interval, timestampz, timestampz_interval_sub },
+                    { time, time, interval, time_time_sub },
+                    { time, interval, time, time_interval_sub },
+                    { date, int32, date, date_int_sub },
                     { interval, interval, interval, general_sub },
                 },
             }
         }
+        Type::Multiply if is_primitive_int(&l, &r) => {
+            gen_binary_expr_primitive! {gen_primitive_atm_impl, l, r, ret, primitive_mul}
+        }
         Type::Multiply => {
             gen_binary_expr_atm! {
                 gen_atm_impl,
@@ -391,6 +521,12 @@ pub fn new_binary_expr(
                     { int16, interval, interval, int_interval_mul },
                     { int32, interval, interval, int_interval_mul },
                     { int64, interval, interval, int_interval_mul },
+                    { interval, float32, interval, interval_float_mul },
+                    { interval, float64, interval, interval_float_mul },
+                    { interval, decimal, interval, interval_float_mul },
+                    { float32, interval, interval, float_interval_mul },
+                    { float64, interval, interval, float_interval_mul },
+                    { decimal, interval, interval, float_interval_mul },
                 },
             }
         }
@@ -400,6 +536,12 @@ pub fn new_binary_expr(
                 l, r, ret,
                 general_div,
                 {
+                    { interval, int16, interval, interval_float_div },
+                    { interval, int32, interval, interval_float_div },
+                    { interval, int64, interval, interval_float_div },
+                    { interval, float32, interval, interval_float_div },
+                    { interval, float64, interval, interval_float_div },
+                    { interval, decimal, interval, interval_float_div },
                 },
             }
         }
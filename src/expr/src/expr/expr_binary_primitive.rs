@@ -0,0 +1,395 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Binary expressions over fixed-width primitive arrays.
+//!
+//! Unlike the expressions generated in [`super::template`], which call the scalar function row by
+//! row and append the result to an array builder, these expressions apply an infallible function
+//! to the raw value slices of the whole arrays and compute the null bitmap of the result with
+//! bitwise operations. The tight loops can be auto-vectorized into SIMD instructions by the
+//! compiler.
+
+use std::fmt;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use bytes::Bytes;
+use itertools::Itertools;
+use risingwave_common::array::{
+    Array, ArrayBuilder, ArrayRef, BoolArray, DataChunk, PrimitiveArray, PrimitiveArrayItemType,
+    Row,
+};
+use risingwave_common::buffer::Bitmap;
+use risingwave_common::error::ErrorCode::{InternalError, NumericValueOutOfRange};
+use risingwave_common::error::{Result, RwError};
+use risingwave_common::types::{DataType, Datum, Scalar, ScalarImpl};
+
+use crate::expr::{BoxedExpression, Expression};
+
+/// Evaluates `expr` and downcasts the result to a primitive array.
+fn eval_primitive<T: PrimitiveArrayItemType>(
+    expr: &BoxedExpression,
+    data_chunk: &DataChunk,
+) -> Result<ArrayRef> {
+    let array = expr.eval(data_chunk)?;
+    if T::try_into_array_ref(&array).is_none() {
+        return Err(InternalError(format!(
+            "expect {:?} array, but got {}",
+            T::array_type(),
+            array.get_ident()
+        ))
+        .into());
+    }
+    Ok(array)
+}
+
+/// Applies `func` to each pair of values. Iterating by index over slices of the same length lets
+/// the compiler eliminate bound checks and vectorize the loop.
+fn binary_map<T1: Copy, T2: Copy, O>(
+    l: &[T1],
+    r: &[T2],
+    mut func: impl FnMut(T1, T2) -> O,
+) -> Vec<O> {
+    assert_eq!(l.len(), r.len());
+    let r = &r[..l.len()];
+    (0..l.len()).map(|i| func(l[i], r[i])).collect()
+}
+
+/// Returns the null bitmap of the result, which is the intersection of the null bitmaps of both
+/// sides. If one side has no null, the bitmap of the other side is reused.
+fn and_null_bitmap(l: &Bitmap, r: &Bitmap, len: usize) -> Bitmap {
+    if l.len() == len && r.len() == len {
+        if r.num_high_bits() == len {
+            return l.clone();
+        }
+        if l.num_high_bits() == len {
+            return r.clone();
+        }
+    }
+    let num_bytes = (len + 7) / 8;
+    let bits: Bytes = l.bits[..num_bytes]
+        .iter()
+        .zip_eq(&r.bits[..num_bytes])
+        .map(|(a, b)| a & b)
+        .collect();
+    Bitmap::from_bytes_with_num_bits(bits, len)
+}
+
+/// Only keeps the visible rows of `array`, to conform with the expressions generated from the
+/// templates.
+fn compact<A: Array>(array: A, visibility: Option<&Bitmap>) -> Result<A> {
+    match visibility {
+        None => Ok(array),
+        Some(visibility) => {
+            let mut builder = A::Builder::new(visibility.num_high_bits())?;
+            for (value, visible) in array.iter().zip_eq(visibility.iter()) {
+                if visible {
+                    builder.append(value)?;
+                }
+            }
+            builder.finish()
+        }
+    }
+}
+
+/// Evaluates both sides of a binary expression for a single row, and converts the datums to the
+/// primitive types.
+fn eval_row_primitive<T1, T2>(
+    expr_ia1: &BoxedExpression,
+    expr_ia2: &BoxedExpression,
+    row: &Row,
+) -> Result<Option<(T1, T2)>>
+where
+    T1: PrimitiveArrayItemType + TryFrom<ScalarImpl, Error = RwError>,
+    T2: PrimitiveArrayItemType + TryFrom<ScalarImpl, Error = RwError>,
+{
+    match (expr_ia1.eval_row(row)?, expr_ia2.eval_row(row)?) {
+        (Some(l), Some(r)) => Ok(Some((T1::try_from(l)?, T2::try_from(r)?))),
+        _ => Ok(None),
+    }
+}
+
+/// Comparison between primitive arrays, whose output is a bool array.
+pub struct PrimitiveCmpExpression<T1, T2, F> {
+    expr_ia1: BoxedExpression,
+    expr_ia2: BoxedExpression,
+    return_type: DataType,
+    func: F,
+    _phantom: PhantomData<(T1, T2)>,
+}
+
+impl<T1, T2, F> fmt::Debug for PrimitiveCmpExpression<T1, T2, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PrimitiveCmpExpression")
+            .field("func", &std::any::type_name::<F>())
+            .field("expr_ia1", &self.expr_ia1)
+            .field("expr_ia2", &self.expr_ia2)
+            .field("return_type", &self.return_type)
+            .finish()
+    }
+}
+
+impl<T1, T2, F> PrimitiveCmpExpression<T1, T2, F> {
+    pub fn new(
+        expr_ia1: BoxedExpression,
+        expr_ia2: BoxedExpression,
+        return_type: DataType,
+        func: F,
+    ) -> Self {
+        Self {
+            expr_ia1,
+            expr_ia2,
+            return_type,
+            func,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<T1, T2, F> Expression for PrimitiveCmpExpression<T1, T2, F>
+where
+    T1: PrimitiveArrayItemType + TryFrom<ScalarImpl, Error = RwError>,
+    T2: PrimitiveArrayItemType + TryFrom<ScalarImpl, Error = RwError>,
+    F: Fn(T1, T2) -> bool + Sync + Send,
+{
+    fn return_type(&self) -> DataType {
+        self.return_type.clone()
+    }
+
+    fn eval(&self, data_chunk: &DataChunk) -> Result<ArrayRef> {
+        let ret_ia1 = eval_primitive::<T1>(&self.expr_ia1, data_chunk)?;
+        let ret_ia2 = eval_primitive::<T2>(&self.expr_ia2, data_chunk)?;
+        let arr_ia1 = T1::try_into_array_ref(&ret_ia1).unwrap();
+        let arr_ia2 = T2::try_into_array_ref(&ret_ia2).unwrap();
+
+        let values = binary_map(arr_ia1.as_slice(), arr_ia2.as_slice(), &self.func);
+        let null_bitmap =
+            and_null_bitmap(arr_ia1.null_bitmap(), arr_ia2.null_bitmap(), values.len());
+        let output_array = BoolArray::new(null_bitmap, Bitmap::from_bool_slice(&values));
+
+        Ok(Arc::new(
+            compact(output_array, data_chunk.get_visibility_ref())?.into(),
+        ))
+    }
+
+    fn eval_row(&self, row: &Row) -> Result<Datum> {
+        Ok(
+            eval_row_primitive::<T1, T2>(&self.expr_ia1, &self.expr_ia2, row)?
+                .map(|(l, r)| ScalarImpl::Bool((self.func)(l, r))),
+        )
+    }
+}
+
+/// Arithmetic between primitive arrays. The function returns the wrapped result and whether the
+/// operation overflows, and an error is raised if any non-null visible row overflows.
+pub struct PrimitiveAtmExpression<T1, T2, T3, F> {
+    expr_ia1: BoxedExpression,
+    expr_ia2: BoxedExpression,
+    return_type: DataType,
+    func: F,
+    _phantom: PhantomData<(T1, T2, T3)>,
+}
+
+impl<T1, T2, T3, F> fmt::Debug for PrimitiveAtmExpression<T1, T2, T3, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PrimitiveAtmExpression")
+            .field("func", &std::any::type_name::<F>())
+            .field("expr_ia1", &self.expr_ia1)
+            .field("expr_ia2", &self.expr_ia2)
+            .field("return_type", &self.return_type)
+            .finish()
+    }
+}
+
+impl<T1, T2, T3, F> PrimitiveAtmExpression<T1, T2, T3, F> {
+    pub fn new(
+        expr_ia1: BoxedExpression,
+        expr_ia2: BoxedExpression,
+        return_type: DataType,
+        func: F,
+    ) -> Self {
+        Self {
+            expr_ia1,
+            expr_ia2,
+            return_type,
+            func,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<T1, T2, T3, F> Expression for PrimitiveAtmExpression<T1, T2, T3, F>
+where
+    T1: PrimitiveArrayItemType + TryFrom<ScalarImpl, Error = RwError>,
+    T2: PrimitiveArrayItemType + TryFrom<ScalarImpl, Error = RwError>,
+    T3: PrimitiveArrayItemType,
+    F: Fn(T1, T2) -> (T3, bool) + Sync + Send,
+{
+    fn return_type(&self) -> DataType {
+        self.return_type.clone()
+    }
+
+    fn eval(&self, data_chunk: &DataChunk) -> Result<ArrayRef> {
+        let ret_ia1 = eval_primitive::<T1>(&self.expr_ia1, data_chunk)?;
+        let ret_ia2 = eval_primitive::<T2>(&self.expr_ia2, data_chunk)?;
+        let arr_ia1 = T1::try_into_array_ref(&ret_ia1).unwrap();
+        let arr_ia2 = T2::try_into_array_ref(&ret_ia2).unwrap();
+
+        let mut overflow = false;
+        let values = binary_map(arr_ia1.as_slice(), arr_ia2.as_slice(), |l, r| {
+            let (value, o) = (self.func)(l, r);
+            overflow |= o;
+            value
+        });
+        let null_bitmap =
+            and_null_bitmap(arr_ia1.null_bitmap(), arr_ia2.null_bitmap(), values.len());
+
+        // Overflow is rare, so only check whether it happens on a valid row when necessary.
+        // Invisible rows and null rows (whose raw values are arbitrary) are ignored.
+        if overflow {
+            let visibility = data_chunk.get_visibility_ref();
+            let overflowed = arr_ia1
+                .as_slice()
+                .iter()
+                .zip_eq(arr_ia2.as_slice())
+                .zip_eq(null_bitmap.iter())
+                .enumerate()
+                .any(|(idx, ((&l, &r), valid))| {
+                    valid
+                        && visibility.map_or(true, |vis| vis.is_set(idx).unwrap_or(false))
+                        && (self.func)(l, r).1
+                });
+            if overflowed {
+                return Err(NumericValueOutOfRange.into());
+            }
+        }
+
+        let output_array = PrimitiveArray::<T3>::new(null_bitmap, values);
+        Ok(Arc::new(T3::erase_array_type(compact(
+            output_array,
+            data_chunk.get_visibility_ref(),
+        )?)))
+    }
+
+    fn eval_row(&self, row: &Row) -> Result<Datum> {
+        match eval_row_primitive::<T1, T2>(&self.expr_ia1, &self.expr_ia2, row)? {
+            Some((l, r)) => match (self.func)(l, r) {
+                (_, true) => Err(NumericValueOutOfRange.into()),
+                (value, false) => Ok(Some(value.to_scalar_value())),
+            },
+            None => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_common::array::{DataChunk, DataChunkTestExt, Row};
+    use risingwave_common::types::{DataType, ScalarImpl};
+    use risingwave_pb::expr::expr_node::Type;
+
+    use super::*;
+    use crate::expr::expr_binary_nonnull::new_binary_expr;
+    use crate::expr::InputRefExpression;
+
+    fn make_expr(expr_type: Type, ret: DataType, l: DataType, r: DataType) -> BoxedExpression {
+        new_binary_expr(
+            expr_type,
+            ret,
+            Box::new(InputRefExpression::new(l, 0)),
+            Box::new(InputRefExpression::new(r, 1)),
+        )
+    }
+
+    #[test]
+    fn test_primitive_cmp() {
+        let chunk = DataChunk::from_pretty(
+            "i I
+             1 1
+             2 1
+             . 3
+             4 .
+             5 9",
+        );
+        let expr = make_expr(
+            Type::LessThan,
+            DataType::Boolean,
+            DataType::Int32,
+            DataType::Int64,
+        );
+        let result = expr.eval(&chunk).unwrap();
+        assert_eq!(
+            result.iter().collect_vec(),
+            [Some(false), Some(false), None, None, Some(true)]
+                .into_iter()
+                .map(|v| v.map(Into::into))
+                .collect_vec()
+        );
+
+        let row = Row(vec![Some(ScalarImpl::Int32(1)), Some(ScalarImpl::Int64(2))]);
+        assert_eq!(expr.eval_row(&row).unwrap(), Some(ScalarImpl::Bool(true)));
+    }
+
+    #[test]
+    fn test_primitive_atm() {
+        let chunk = DataChunk::from_pretty(
+            "i I
+             1 1
+             2 -1
+             . 3
+             4 .",
+        );
+        let expr = make_expr(Type::Add, DataType::Int64, DataType::Int32, DataType::Int64);
+        let result = expr.eval(&chunk).unwrap();
+        assert_eq!(
+            result.iter().collect_vec(),
+            [Some(2i64), Some(1), None, None]
+                .into_iter()
+                .map(|v| v.map(Into::into))
+                .collect_vec()
+        );
+    }
+
+    #[test]
+    fn test_primitive_atm_overflow() {
+        let expr = make_expr(
+            Type::Multiply,
+            DataType::Int32,
+            DataType::Int32,
+            DataType::Int32,
+        );
+
+        let chunk = DataChunk::from_pretty(
+            "i          i
+             1          1
+             2147483647 2",
+        );
+        assert!(expr.eval(&chunk).is_err());
+
+        // Overflow on invisible rows is ignored.
+        let chunk = DataChunk::from_pretty(
+            "i          i
+             1          1
+             2147483647 2 D",
+        );
+        let result = expr.eval(&chunk).unwrap();
+        assert_eq!(result.iter().collect_vec(), vec![Some(1i32.into())]);
+
+        let row = Row(vec![
+            Some(ScalarImpl::Int32(i32::MAX)),
+            Some(ScalarImpl::Int32(2)),
+        ]);
+        assert!(expr.eval_row(&row).is_err());
+    }
+}
@@ -0,0 +1,682 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! User-defined functions served by an external UDF server over Arrow Flight, e.g. Python
+//! functions.
+//!
+//! The protocol between the compute node and the UDF server is:
+//! - `GetFlightInfo` with the path `[name]` returns the signature of the function `name`, whose
+//!   schema has one field for each argument followed by one field for the result.
+//! - `DoExchange` calls the function. The first message of the request carries the descriptor
+//!   `[name]` and the schema of the arguments, and is followed by record batches of the arguments.
+//!   The response is the schema of the result followed by record batches with one result for each
+//!   row of the arguments, in the same order.
+//!
+//! Connections to the same server are shared by all the functions in the process. Each call is
+//! bounded by the timeout of the function. Only the calls that failed to reach the server are
+//! retried, since a timed out call may still be running on the server.
+//!
+//! Expressions are evaluated synchronously, so the executor thread is blocked while calling the
+//! server. This is only allowed on a multi-threaded runtime, where the other tasks on the thread
+//! are moved to the other workers in the meantime.
+
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use arrow::datatypes::{Field, Schema, SchemaRef};
+use arrow::ipc::writer::IpcWriteOptions;
+use arrow::record_batch::RecordBatch;
+use arrow_flight::flight_service_client::FlightServiceClient;
+use arrow_flight::utils::{flight_data_from_arrow_batch, flight_data_to_arrow_batch};
+use arrow_flight::{FlightData, FlightDescriptor, IpcMessage, SchemaAsIpc};
+use itertools::Itertools;
+use lazy_static::lazy_static;
+use risingwave_common::array::{ArrayBuilderImpl, ArrayRef, DataChunk, Row};
+use risingwave_common::error::ErrorCode::{InternalError, InvalidParameterValue, NotImplemented};
+use risingwave_common::error::Result;
+use risingwave_common::types::{DataType, Datum};
+use tokio::runtime::{Handle, RuntimeFlavor};
+use tonic::transport::{Channel, Endpoint};
+use tonic::{Code, Status};
+
+use crate::arrow::{arrow_type, from_arrow_array, to_arrow_array};
+use crate::expr::{BoxedExpression, Expression};
+
+/// The timeout of each call when the function does not specify one.
+pub const DEFAULT_TIMEOUT_MS: u64 = 10_000;
+/// The delay before the first retry of a failed call, which grows linearly with the attempts.
+const RETRY_BACKOFF: Duration = Duration::from_millis(100);
+
+lazy_static! {
+    /// The channels to the UDF servers, keyed by their addresses. A channel multiplexes the calls
+    /// over a pool of HTTP/2 connections, and reconnects on failures.
+    static ref CHANNELS: Mutex<HashMap<String, Channel>> = Mutex::new(HashMap::new());
+}
+
+fn get_channel(link: &str) -> Result<Channel> {
+    let mut channels = CHANNELS.lock().unwrap();
+    if let Some(channel) = channels.get(link) {
+        return Ok(channel.clone());
+    }
+    let channel = Endpoint::from_shared(link.to_string())
+        .map_err(|e| InvalidParameterValue(format!("invalid UDF server link {}: {}", link, e)))?
+        .connect_lazy();
+    channels.insert(link.to_string(), channel.clone());
+    Ok(channel)
+}
+
+/// The error of a single call to the UDF server.
+#[derive(Debug)]
+enum CallError {
+    Timeout,
+    Status(Status),
+    Other(String),
+}
+
+impl CallError {
+    /// Whether the call is safe to retry, i.e. it failed to reach the server. Timed out calls are
+    /// not retried, as they may have been run by the server.
+    fn is_retryable(&self) -> bool {
+        match self {
+            Self::Status(status) => status.code() == Code::Unavailable,
+            Self::Timeout | Self::Other(_) => false,
+        }
+    }
+}
+
+impl From<Status> for CallError {
+    fn from(status: Status) -> Self {
+        Self::Status(status)
+    }
+}
+
+impl From<arrow::error::ArrowError> for CallError {
+    fn from(e: arrow::error::ArrowError) -> Self {
+        Self::Other(e.to_string())
+    }
+}
+
+/// A function on an external UDF server.
+#[derive(Debug)]
+pub struct ExternalFunction {
+    name: String,
+    link: String,
+    arg_types: Vec<DataType>,
+    return_type: DataType,
+    timeout: Duration,
+    max_retries: u32,
+}
+
+impl ExternalFunction {
+    /// Creates a function `name` on the UDF server at `link`. A `timeout_ms` of 0 means
+    /// [`DEFAULT_TIMEOUT_MS`]. The connection is established lazily on the first call.
+    pub fn new(
+        name: &str,
+        link: &str,
+        arg_types: Vec<DataType>,
+        return_type: DataType,
+        timeout_ms: u64,
+        max_retries: u32,
+    ) -> Result<Self> {
+        if link.is_empty() {
+            return Err(InvalidParameterValue(format!(
+                "the link to the UDF server of function \"{}\" is not specified",
+                name
+            ))
+            .into());
+        }
+        for t in arg_types.iter().chain(std::iter::once(&return_type)) {
+            arrow_type(t)?;
+        }
+        let timeout_ms = match timeout_ms {
+            0 => DEFAULT_TIMEOUT_MS,
+            timeout_ms => timeout_ms,
+        };
+        Ok(Self {
+            name: name.to_string(),
+            link: link.to_string(),
+            arg_types,
+            return_type,
+            timeout: Duration::from_millis(timeout_ms),
+            max_retries,
+        })
+    }
+
+    /// Checks that the function exists on the UDF server with the expected signature.
+    pub async fn check(&self) -> Result<()> {
+        let info = self
+            .with_retry(|| async {
+                let mut client = self.client()?;
+                let info = client
+                    .get_flight_info(self.descriptor())
+                    .await?
+                    .into_inner();
+                Ok(info)
+            })
+            .await?;
+        let schema = Schema::try_from(IpcMessage(info.schema))
+            .map_err(|e| InternalError(format!("invalid schema from UDF server: {}", e)))?;
+        let actual = schema.fields().iter().map(Field::data_type).collect_vec();
+        let expected = self
+            .arg_types
+            .iter()
+            .chain(std::iter::once(&self.return_type))
+            .map(arrow_type)
+            .collect::<Result<Vec<_>>>()?;
+        if actual.into_iter().ne(expected.iter()) {
+            return Err(InvalidParameterValue(format!(
+                "the signature of function \"{}\" on the UDF server does not match ({:?}) -> {:?}",
+                self.name, self.arg_types, self.return_type
+            ))
+            .into());
+        }
+        Ok(())
+    }
+
+    /// Calls the function on the rows of `args`, and returns one result for each row.
+    fn call(&self, args: Vec<Vec<Datum>>, num_rows: usize) -> Result<Vec<Datum>> {
+        let fields = self
+            .arg_types
+            .iter()
+            .enumerate()
+            .map(|(i, t)| Ok(Field::new(&format!("arg{}", i), arrow_type(t)?, true)))
+            .collect::<Result<Vec<_>>>()?;
+        let columns = args
+            .into_iter()
+            .zip_eq(self.arg_types.iter())
+            .map(|(datums, t)| to_arrow_array(t, datums))
+            .collect::<Result<Vec<_>>>()?;
+        let input = RecordBatch::try_new(Arc::new(Schema::new(fields)), columns)
+            .map_err(|e| InternalError(e.to_string()))?;
+
+        let batches = block_on(self.with_retry(|| self.exchange(&input)))??;
+
+        let mut results = Vec::with_capacity(num_rows);
+        for batch in batches {
+            if batch.num_columns() != 1 {
+                return Err(InternalError(format!(
+                    "expect 1 column from UDF server, got {}",
+                    batch.num_columns()
+                ))
+                .into());
+            }
+            results.extend(from_arrow_array(&self.return_type, batch.column(0))?);
+        }
+        if results.len() != num_rows {
+            return Err(InternalError(format!(
+                "expect {} rows from UDF server, got {}",
+                num_rows,
+                results.len()
+            ))
+            .into());
+        }
+        Ok(results)
+    }
+
+    fn client(&self) -> std::result::Result<FlightServiceClient<Channel>, CallError> {
+        let channel = get_channel(&self.link).map_err(|e| CallError::Other(e.to_string()))?;
+        Ok(FlightServiceClient::new(channel))
+    }
+
+    fn descriptor(&self) -> FlightDescriptor {
+        FlightDescriptor::new_path(vec![self.name.clone()])
+    }
+
+    /// Sends `input` to the UDF server and collects the results.
+    async fn exchange(
+        &self,
+        input: &RecordBatch,
+    ) -> std::result::Result<Vec<RecordBatch>, CallError> {
+        let options = IpcWriteOptions::default();
+        let mut schema: FlightData = SchemaAsIpc::new(&input.schema(), &options).into();
+        schema.flight_descriptor = Some(self.descriptor());
+        let (_, batch) = flight_data_from_arrow_batch(input, &options);
+
+        let mut client = self.client()?;
+        let mut response = client
+            .do_exchange(tokio_stream::iter(vec![schema, batch]))
+            .await?
+            .into_inner();
+
+        let schema: SchemaRef = match response.message().await? {
+            Some(data) => Arc::new(Schema::try_from(&data)?),
+            None => return Err(CallError::Other("empty response from UDF server".into())),
+        };
+        let mut batches = vec![];
+        while let Some(data) = response.message().await? {
+            batches.push(flight_data_to_arrow_batch(
+                &data,
+                schema.clone(),
+                &HashMap::new(),
+            )?);
+        }
+        Ok(batches)
+    }
+
+    /// Runs `call` with the timeout of the function, and retries it up to `max_retries` times if it
+    /// failed to reach the server.
+    async fn with_retry<T, F, Fut>(&self, call: F) -> Result<T>
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = std::result::Result<T, CallError>>,
+    {
+        let mut attempt = 0;
+        loop {
+            let result = match tokio::time::timeout(self.timeout, call()).await {
+                Ok(result) => result,
+                Err(_) => Err(CallError::Timeout),
+            };
+            match result {
+                Ok(output) => return Ok(output),
+                Err(e) if e.is_retryable() && attempt < self.max_retries => {
+                    attempt += 1;
+                    tokio::time::sleep(RETRY_BACKOFF * attempt).await;
+                }
+                Err(e) => {
+                    return Err(InternalError(format!(
+                        "failed to call function \"{}\" on UDF server {}: {:?}",
+                        self.name, self.link, e
+                    ))
+                    .into())
+                }
+            }
+        }
+    }
+}
+
+/// Runs `future` to completion in the synchronous evaluation of expressions. Fails if not on a
+/// multi-threaded runtime, where blocking the thread would stall the other tasks or panic.
+fn block_on<F: Future>(future: F) -> Result<F::Output> {
+    let handle = Handle::try_current().map_err(|_| {
+        InternalError("external functions must be called in a tokio runtime".to_string())
+    })?;
+    if handle.runtime_flavor() != RuntimeFlavor::MultiThread {
+        return Err(NotImplemented(
+            "calling external functions in a single-threaded runtime".to_string(),
+            None.into(),
+        )
+        .into());
+    }
+    Ok(tokio::task::block_in_place(|| handle.block_on(future)))
+}
+
+/// Calls an external user-defined function on the results of `children`. The rows of a chunk
+/// are sent to the UDF server in one batch.
+#[derive(Debug)]
+pub struct ExternalUdfExpression {
+    children: Vec<BoxedExpression>,
+    function: Arc<ExternalFunction>,
+}
+
+impl ExternalUdfExpression {
+    pub fn new(children: Vec<BoxedExpression>, function: ExternalFunction) -> Self {
+        Self {
+            children,
+            function: Arc::new(function),
+        }
+    }
+}
+
+impl Expression for ExternalUdfExpression {
+    fn return_type(&self) -> DataType {
+        self.function.return_type.clone()
+    }
+
+    fn eval(&self, input: &DataChunk) -> Result<ArrayRef> {
+        let children = self
+            .children
+            .iter()
+            .map(|c| c.eval(input))
+            .collect::<Result<Vec<_>>>()?;
+        // Only the visible rows are sent to the UDF server.
+        let visible = match input.visibility() {
+            Some(visibility) => (0..input.capacity())
+                .map(|i| visibility.is_set(i))
+                .collect::<Result<Vec<_>>>()?,
+            None => vec![true; input.capacity()],
+        };
+        let num_rows = visible.iter().filter(|v| **v).count();
+        let args = children
+            .iter()
+            .map(|c| {
+                (0..input.capacity())
+                    .filter(|i| visible[*i])
+                    .map(|i| c.datum_at(i))
+                    .collect_vec()
+            })
+            .collect_vec();
+
+        let mut results = match num_rows {
+            0 => vec![],
+            _ => self.function.call(args, num_rows)?,
+        }
+        .into_iter();
+        let mut builder: ArrayBuilderImpl = self
+            .function
+            .return_type
+            .create_array_builder(input.capacity())?;
+        for visible in visible {
+            let datum = match visible {
+                true => results.next().unwrap(),
+                false => None,
+            };
+            builder.append_datum(&datum)?;
+        }
+        Ok(Arc::new(builder.finish()?))
+    }
+
+    fn eval_row(&self, input: &Row) -> Result<Datum> {
+        let args = self
+            .children
+            .iter()
+            .map(|c| c.eval_row(input).map(|datum| vec![datum]))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(self.function.call(args, 1)?.pop().unwrap())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::pin::Pin;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use arrow::array::Int32Array;
+    use arrow::datatypes::DataType as ArrowDataType;
+    use arrow::error::ArrowError;
+    use arrow_flight::flight_service_server::{FlightService, FlightServiceServer};
+    use arrow_flight::{
+        Action, ActionType, Criteria, Empty, FlightInfo, HandshakeRequest, HandshakeResponse,
+        PutResult, SchemaResult, Ticket,
+    };
+    use risingwave_common::types::ScalarImpl;
+    use tokio::net::TcpListener;
+    use tokio_stream::wrappers::TcpListenerStream;
+    use tokio_stream::Stream;
+    use tonic::{Request, Response, Streaming};
+
+    use super::*;
+
+    type MockStream<T> =
+        Pin<Box<dyn Stream<Item = std::result::Result<T, Status>> + Send + Sync + 'static>>;
+
+    /// A UDF server with the function `add_one(int) -> int`. It fails the first `failures` calls
+    /// with `Unavailable`, and waits `delay` before responding to the others.
+    #[derive(Default)]
+    struct MockUdfServer {
+        failures: usize,
+        delay: Duration,
+        calls: Arc<AtomicUsize>,
+    }
+
+    fn to_status(e: ArrowError) -> Status {
+        Status::internal(e.to_string())
+    }
+
+    #[async_trait::async_trait]
+    impl FlightService for MockUdfServer {
+        type DoActionStream = MockStream<arrow_flight::Result>;
+        type DoExchangeStream = MockStream<FlightData>;
+        type DoGetStream = MockStream<FlightData>;
+        type DoPutStream = MockStream<PutResult>;
+        type HandshakeStream = MockStream<HandshakeResponse>;
+        type ListActionsStream = MockStream<ActionType>;
+        type ListFlightsStream = MockStream<FlightInfo>;
+
+        async fn handshake(
+            &self,
+            _request: Request<Streaming<HandshakeRequest>>,
+        ) -> std::result::Result<Response<Self::HandshakeStream>, Status> {
+            Err(Status::unimplemented("handshake"))
+        }
+
+        async fn list_flights(
+            &self,
+            _request: Request<Criteria>,
+        ) -> std::result::Result<Response<Self::ListFlightsStream>, Status> {
+            Err(Status::unimplemented("list_flights"))
+        }
+
+        async fn get_flight_info(
+            &self,
+            request: Request<FlightDescriptor>,
+        ) -> std::result::Result<Response<FlightInfo>, Status> {
+            if request.into_inner().path != vec!["add_one".to_string()] {
+                return Err(Status::not_found("function not found"));
+            }
+            let schema = Schema::new(vec![
+                Field::new("arg0", ArrowDataType::Int32, true),
+                Field::new("result", ArrowDataType::Int32, true),
+            ]);
+            let IpcMessage(schema) = SchemaAsIpc::new(&schema, &IpcWriteOptions::default())
+                .try_into()
+                .map_err(to_status)?;
+            Ok(Response::new(FlightInfo {
+                schema,
+                ..Default::default()
+            }))
+        }
+
+        async fn get_schema(
+            &self,
+            _request: Request<FlightDescriptor>,
+        ) -> std::result::Result<Response<SchemaResult>, Status> {
+            Err(Status::unimplemented("get_schema"))
+        }
+
+        async fn do_get(
+            &self,
+            _request: Request<Ticket>,
+        ) -> std::result::Result<Response<Self::DoGetStream>, Status> {
+            Err(Status::unimplemented("do_get"))
+        }
+
+        async fn do_put(
+            &self,
+            _request: Request<Streaming<FlightData>>,
+        ) -> std::result::Result<Response<Self::DoPutStream>, Status> {
+            Err(Status::unimplemented("do_put"))
+        }
+
+        async fn do_exchange(
+            &self,
+            request: Request<Streaming<FlightData>>,
+        ) -> std::result::Result<Response<Self::DoExchangeStream>, Status> {
+            if self.calls.fetch_add(1, Ordering::SeqCst) < self.failures {
+                return Err(Status::unavailable("the server is not ready"));
+            }
+            tokio::time::sleep(self.delay).await;
+
+            let mut request = request.into_inner();
+            let data = request
+                .message()
+                .await?
+                .ok_or_else(|| Status::invalid_argument("missing schema"))?;
+            assert_eq!(
+                data.flight_descriptor.as_ref().unwrap().path,
+                vec!["add_one".to_string()]
+            );
+            let input_schema = Arc::new(Schema::try_from(&data).map_err(to_status)?);
+            let output_schema = Arc::new(Schema::new(vec![Field::new(
+                "result",
+                ArrowDataType::Int32,
+                true,
+            )]));
+
+            let options = IpcWriteOptions::default();
+            let mut response = vec![Ok(SchemaAsIpc::new(&output_schema, &options).into())];
+            while let Some(data) = request.message().await? {
+                let batch =
+                    flight_data_to_arrow_batch(&data, input_schema.clone(), &HashMap::new())
+                        .map_err(to_status)?;
+                let output: Int32Array = batch
+                    .column(0)
+                    .as_any()
+                    .downcast_ref::<Int32Array>()
+                    .unwrap()
+                    .iter()
+                    .map(|v| v.map(|v| v + 1))
+                    .collect();
+                let batch = RecordBatch::try_new(output_schema.clone(), vec![Arc::new(output)])
+                    .map_err(to_status)?;
+                response.push(Ok(flight_data_from_arrow_batch(&batch, &options).1));
+            }
+            Ok(Response::new(Box::pin(tokio_stream::iter(response))))
+        }
+
+        async fn do_action(
+            &self,
+            _request: Request<Action>,
+        ) -> std::result::Result<Response<Self::DoActionStream>, Status> {
+            Err(Status::unimplemented("do_action"))
+        }
+
+        async fn list_actions(
+            &self,
+            _request: Request<Empty>,
+        ) -> std::result::Result<Response<Self::ListActionsStream>, Status> {
+            Err(Status::unimplemented("list_actions"))
+        }
+    }
+
+    /// Serves `server` on a random port, and returns the link to it.
+    async fn serve(server: MockUdfServer) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let link = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(
+            tonic::transport::Server::builder()
+                .add_service(FlightServiceServer::new(server))
+                .serve_with_incoming(TcpListenerStream::new(listener)),
+        );
+        link
+    }
+
+    fn add_one(link: &str, timeout_ms: u64, max_retries: u32) -> ExternalFunction {
+        ExternalFunction::new(
+            "add_one",
+            link,
+            vec![DataType::Int32],
+            DataType::Int32,
+            timeout_ms,
+            max_retries,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_retryable_errors() {
+        assert!(CallError::Status(Status::unavailable("")).is_retryable());
+        assert!(!CallError::Timeout.is_retryable());
+        assert!(!CallError::Status(Status::deadline_exceeded("")).is_retryable());
+        assert!(!CallError::Status(Status::invalid_argument("")).is_retryable());
+        assert!(!CallError::Other(String::new()).is_retryable());
+    }
+
+    #[tokio::test]
+    async fn test_block_on_single_threaded_runtime() {
+        assert!(block_on(async { 1 }).is_err());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_block_on_multi_threaded_runtime() {
+        assert_eq!(block_on(async { 1 }).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_external_function_options() {
+        let function =
+            ExternalFunction::new("f", "http://localhost:8815", vec![], DataType::Int32, 0, 3)
+                .unwrap();
+        assert_eq!(function.timeout, Duration::from_millis(DEFAULT_TIMEOUT_MS));
+        assert!(ExternalFunction::new("f", "", vec![], DataType::Int32, 0, 0).is_err());
+        assert!(ExternalFunction::new(
+            "f",
+            "http://localhost:8815",
+            vec![DataType::Date],
+            DataType::Int32,
+            0,
+            0
+        )
+        .is_err());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_exchange() {
+        let link = serve(MockUdfServer::default()).await;
+        add_one(&link, 0, 0).check().await.unwrap();
+        assert!(ExternalFunction::new(
+            "add_one",
+            &link,
+            vec![DataType::Int64],
+            DataType::Int32,
+            0,
+            0
+        )
+        .unwrap()
+        .check()
+        .await
+        .is_err());
+
+        let results = add_one(&link, 0, 0)
+            .call(vec![vec![Some(ScalarImpl::Int32(1)), None]], 2)
+            .unwrap();
+        assert_eq!(results, vec![Some(ScalarImpl::Int32(2)), None]);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_timeout() {
+        let server = MockUdfServer {
+            delay: Duration::from_secs(1),
+            ..Default::default()
+        };
+        let calls = server.calls.clone();
+        let link = serve(server).await;
+
+        let err = add_one(&link, 100, 3)
+            .call(vec![vec![Some(ScalarImpl::Int32(1))]], 1)
+            .unwrap_err();
+        assert!(err.to_string().contains("Timeout"), "{}", err);
+        // A timed out call is not retried.
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_retry() {
+        let server = MockUdfServer {
+            failures: 2,
+            ..Default::default()
+        };
+        let calls = server.calls.clone();
+        let link = serve(server).await;
+        let results = add_one(&link, 0, 2)
+            .call(vec![vec![Some(ScalarImpl::Int32(1))]], 1)
+            .unwrap();
+        assert_eq!(results, vec![Some(ScalarImpl::Int32(2))]);
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+        let server = MockUdfServer {
+            failures: 2,
+            ..Default::default()
+        };
+        let calls = server.calls.clone();
+        let link = serve(server).await;
+        let err = add_one(&link, 0, 1)
+            .call(vec![vec![Some(ScalarImpl::Int32(1))]], 1)
+            .unwrap_err();
+        assert!(err.to_string().contains("Unavailable"), "{}", err);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+}
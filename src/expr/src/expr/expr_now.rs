@@ -0,0 +1,142 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! `NOW()` and `PROCTIME()`, which return the wall-clock time of the epoch being processed
+//! instead of the time of evaluation. Epochs are generated by meta from its clock, so the result
+//! is the same for all rows of an epoch on all nodes, and can be reproduced from the epoch alone.
+
+use std::cell::Cell;
+use std::convert::TryFrom;
+use std::time::UNIX_EPOCH;
+
+use risingwave_common::array::{ArrayRef, DataChunk, Row};
+use risingwave_common::ensure;
+use risingwave_common::error::{Result, RwError};
+use risingwave_common::types::{DataType, Datum, NaiveDateTimeWrapper, ScalarImpl};
+use risingwave_common::util::epoch::{Epoch, INVALID_EPOCH};
+use risingwave_pb::expr::expr_node::Type;
+use risingwave_pb::expr::ExprNode;
+
+use crate::expr::{Expression, LiteralExpression};
+
+thread_local! {
+    static CURRENT_EPOCH: Cell<Option<u64>> = Cell::new(None);
+}
+
+/// Runs `f` with `epoch` as the epoch that `NOW()` is evaluated at. `f` must not await, since
+/// the epoch is kept in a thread-local.
+pub fn with_epoch<R>(epoch: u64, f: impl FnOnce() -> R) -> R {
+    let prev = CURRENT_EPOCH.with(|current| current.replace(Some(epoch)));
+    let ret = f();
+    CURRENT_EPOCH.with(|current| current.set(prev));
+    ret
+}
+
+/// Returns the wall-clock time of `epoch` as a timestamp in UTC.
+pub fn epoch_to_timestamp(epoch: u64) -> NaiveDateTimeWrapper {
+    let since_unix_epoch = Epoch(epoch)
+        .as_system_time()
+        .duration_since(UNIX_EPOCH)
+        .unwrap();
+    NaiveDateTimeWrapper::new_with_secs_nsecs_value_encoding(
+        since_unix_epoch.as_secs() as i64,
+        since_unix_epoch.subsec_nanos(),
+    )
+    .unwrap()
+}
+
+/// Evaluates to the time of the epoch set by [`with_epoch`] when the expression is evaluated,
+/// falling back to the one when it's built, e.g. the snapshot epoch of a batch query. Expressions
+/// evaluated outside any epoch get the current time.
+#[derive(Debug)]
+pub struct NowExpression {
+    build_epoch: Option<u64>,
+}
+
+impl NowExpression {
+    pub fn new() -> Self {
+        Self {
+            build_epoch: CURRENT_EPOCH.with(|current| current.get()),
+        }
+    }
+
+    fn now(&self) -> Datum {
+        let epoch = CURRENT_EPOCH
+            .with(|current| current.get())
+            .or(self.build_epoch)
+            .filter(|epoch| *epoch != INVALID_EPOCH)
+            .unwrap_or_else(|| Epoch::now().0);
+        Some(ScalarImpl::NaiveDateTime(epoch_to_timestamp(epoch)))
+    }
+}
+
+impl Default for NowExpression {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Expression for NowExpression {
+    fn return_type(&self) -> DataType {
+        DataType::Timestamp
+    }
+
+    fn eval(&self, input: &DataChunk) -> Result<ArrayRef> {
+        LiteralExpression::new(DataType::Timestamp, self.now()).eval(input)
+    }
+
+    fn eval_row(&self, _input: &Row) -> Result<Datum> {
+        Ok(self.now())
+    }
+}
+
+impl<'a> TryFrom<&'a ExprNode> for NowExpression {
+    type Error = RwError;
+
+    fn try_from(prost: &'a ExprNode) -> Result<Self> {
+        ensure!(prost.get_expr_type()? == Type::Now);
+        Ok(Self::new())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_common::array::{Array, ArrayImpl};
+
+    use super::*;
+
+    #[test]
+    fn test_now_of_epoch() {
+        let epoch = Epoch::now().0;
+        let expected = Some(ScalarImpl::NaiveDateTime(epoch_to_timestamp(epoch)));
+
+        let row = Row::new(vec![]);
+        let built_in_epoch = with_epoch(epoch, NowExpression::new);
+        assert_eq!(built_in_epoch.eval_row(&row).unwrap(), expected);
+
+        let expr = NowExpression::new();
+        assert_eq!(with_epoch(epoch, || expr.eval_row(&row)).unwrap(), expected);
+        let chunk = DataChunk::new_dummy(3);
+        let array = with_epoch(epoch, || expr.eval(&chunk)).unwrap();
+        match array.as_ref() {
+            ArrayImpl::NaiveDateTime(array) => {
+                assert_eq!(array.len(), 3);
+                assert!(array
+                    .iter()
+                    .all(|value| value == Some(epoch_to_timestamp(epoch))));
+            }
+            _ => unreachable!(),
+        }
+    }
+}
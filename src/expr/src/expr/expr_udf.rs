@@ -0,0 +1,526 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! User-defined functions written in WebAssembly.
+//!
+//! The module of a function is instantiated without any imports, so it can not access anything
+//! outside its own linear memory. Each invocation is limited by [`FUEL_PER_INVOCATION`] and the
+//! memory of an instance by [`MAX_MEMORY_BYTES`], so a misbehaving function fails the query
+//! instead of hanging or exhausting the compute node.
+//!
+//! Compiled modules are cached by their bodies, so that the function is only compiled once when
+//! its expression is built for every task or actor. Each expression keeps its own instance of the
+//! module across the chunks it evaluates.
+
+use std::convert::TryFrom;
+use std::sync::{Arc, Mutex};
+
+use lazy_static::lazy_static;
+use lru::LruCache;
+use risingwave_common::array::{ArrayRef, DataChunk, Row};
+use risingwave_common::error::ErrorCode::{
+    InternalError, InvalidParameterValue, NotImplemented, NumericValueOutOfRange,
+};
+use risingwave_common::error::{Result, RwError};
+use risingwave_common::types::{DataType, Datum, ScalarImpl};
+use risingwave_common::{ensure, try_match_expand};
+use risingwave_pb::expr::expr_node::{RexNode, Type};
+use risingwave_pb::expr::{ExprNode, UserDefinedFunction};
+use wasmtime::{
+    Config, Engine, ExternType, Func, Instance, Module, Store, StoreLimits, StoreLimitsBuilder,
+    ValType,
+};
+
+#[cfg(not(madsim))]
+use crate::expr::expr_external_udf::{ExternalFunction, ExternalUdfExpression};
+use crate::expr::{build_from_prost as expr_build_from_prost, BoxedExpression, Expression};
+
+/// The fuel given to each invocation of a function, which is roughly the number of wasm
+/// instructions it can execute.
+pub const FUEL_PER_INVOCATION: u64 = 10_000_000;
+/// The maximum size of the linear memory of a function instance.
+pub const MAX_MEMORY_BYTES: usize = 16 << 20;
+/// The maximum number of compiled modules kept in [`MODULES`].
+const MODULE_CACHE_CAPACITY: usize = 64;
+
+lazy_static! {
+    /// The engine shared by all the modules, with fuel consumption enabled.
+    static ref ENGINE: Engine = {
+        let mut config = Config::new();
+        config.consume_fuel(true);
+        Engine::new(&config).expect("failed to create the wasm engine")
+    };
+    /// The compiled modules, keyed by their bodies.
+    static ref MODULES: Mutex<LruCache<Vec<u8>, Module>> =
+        Mutex::new(LruCache::new(MODULE_CACHE_CAPACITY));
+}
+
+/// Compiles the module `body`, or gets it from [`MODULES`] if it has been compiled before.
+fn get_module(body: &[u8]) -> Result<Module> {
+    let key = body.to_vec();
+    if let Some(module) = MODULES.lock().unwrap().get(&key) {
+        return Ok(module.clone());
+    }
+    // Compile without holding the lock, as it may take a while.
+    let module = Module::new(&ENGINE, body)
+        .map_err(|e| InvalidParameterValue(format!("invalid wasm module: {}", e)))?;
+    MODULES.lock().unwrap().put(key, module.clone());
+    Ok(module)
+}
+
+/// A compiled WebAssembly function whose signature has been checked against the SQL types.
+pub struct WasmFunction {
+    module: Module,
+    link_symbol: String,
+    arg_types: Vec<DataType>,
+    return_type: DataType,
+}
+
+impl std::fmt::Debug for WasmFunction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WasmFunction")
+            .field("link_symbol", &self.link_symbol)
+            .field("arg_types", &self.arg_types)
+            .field("return_type", &self.return_type)
+            .finish_non_exhaustive()
+    }
+}
+
+impl WasmFunction {
+    /// Compiles the module `body`, which can be either in the binary or the text format, and
+    /// checks that it exports a function `link_symbol` with the given signature.
+    pub fn new(
+        body: &[u8],
+        link_symbol: &str,
+        arg_types: Vec<DataType>,
+        return_type: DataType,
+    ) -> Result<Self> {
+        let module = get_module(body)?;
+
+        let expected_params = arg_types
+            .iter()
+            .map(wasm_type)
+            .collect::<Result<Vec<_>>>()?;
+        let expected_result = wasm_type(&return_type)?;
+        let signature = match module.get_export(link_symbol) {
+            Some(ExternType::Func(signature)) => signature,
+            _ => {
+                return Err(InvalidParameterValue(format!(
+                    "wasm module does not export function \"{}\"",
+                    link_symbol
+                ))
+                .into())
+            }
+        };
+        if !signature.params().eq(expected_params)
+            || !signature.results().eq(std::iter::once(expected_result))
+        {
+            return Err(InvalidParameterValue(format!(
+                "the signature of wasm function \"{}\" does not match ({:?}) -> {:?}",
+                link_symbol, arg_types, return_type
+            ))
+            .into());
+        }
+
+        Ok(Self {
+            module,
+            link_symbol: link_symbol.to_string(),
+            arg_types,
+            return_type,
+        })
+    }
+
+    /// Creates a new instance of the module, which can be used to call the function many times.
+    fn instantiate(&self) -> Result<WasmInstance> {
+        let limits = StoreLimitsBuilder::new()
+            .memory_size(MAX_MEMORY_BYTES)
+            .instances(1)
+            .build();
+        let mut store = Store::new(&ENGINE, limits);
+        store.limiter(|limits| limits);
+        let instance = Instance::new(&mut store, &self.module, &[]).map_err(wasm_error)?;
+        let func = instance
+            .get_func(&mut store, &self.link_symbol)
+            .ok_or_else(|| InternalError(format!("missing function {}", self.link_symbol)))?;
+        Ok(WasmInstance { store, func })
+    }
+
+    /// Calls the function on one row. Like PostgreSQL's `STRICT` functions, returns NULL without
+    /// calling the function if any argument is NULL.
+    fn call(&self, instance: &mut WasmInstance, args: &[Datum]) -> Result<Datum> {
+        let mut params = Vec::with_capacity(args.len());
+        for arg in args {
+            match arg {
+                Some(arg) => params.push(to_wasm_value(arg)?),
+                None => return Ok(None),
+            }
+        }
+
+        // Refill the fuel, so that each invocation has the same budget.
+        let remaining = instance.store.consume_fuel(0).map_err(wasm_error)?;
+        instance
+            .store
+            .add_fuel(FUEL_PER_INVOCATION - remaining)
+            .map_err(wasm_error)?;
+
+        let mut results = [wasmtime::Val::I32(0)];
+        instance
+            .func
+            .call(&mut instance.store, &params, &mut results)
+            .map_err(wasm_error)?;
+        from_wasm_value(&results[0], &self.return_type).map(Some)
+    }
+}
+
+struct WasmInstance {
+    store: Store<StoreLimits>,
+    func: Func,
+}
+
+/// Maps a SQL type to the type of the wasm value representing it.
+fn wasm_type(data_type: &DataType) -> Result<ValType> {
+    match data_type {
+        DataType::Boolean | DataType::Int16 | DataType::Int32 => Ok(ValType::I32),
+        DataType::Int64 => Ok(ValType::I64),
+        DataType::Float32 => Ok(ValType::F32),
+        DataType::Float64 => Ok(ValType::F64),
+        _ => Err(NotImplemented(
+            format!("type {:?} in wasm user-defined functions", data_type),
+            None.into(),
+        )
+        .into()),
+    }
+}
+
+fn to_wasm_value(scalar: &ScalarImpl) -> Result<wasmtime::Val> {
+    use wasmtime::Val;
+    match scalar {
+        ScalarImpl::Bool(v) => Ok(Val::I32(*v as i32)),
+        ScalarImpl::Int16(v) => Ok(Val::I32(*v as i32)),
+        ScalarImpl::Int32(v) => Ok(Val::I32(*v)),
+        ScalarImpl::Int64(v) => Ok(Val::I64(*v)),
+        ScalarImpl::Float32(v) => Ok(Val::F32(v.0.to_bits())),
+        ScalarImpl::Float64(v) => Ok(Val::F64(v.0.to_bits())),
+        _ => Err(InternalError(format!("unsupported wasm argument {:?}", scalar)).into()),
+    }
+}
+
+fn from_wasm_value(value: &wasmtime::Val, data_type: &DataType) -> Result<ScalarImpl> {
+    use wasmtime::Val;
+    match (value, data_type) {
+        (Val::I32(v), DataType::Boolean) => Ok(ScalarImpl::Bool(*v != 0)),
+        (Val::I32(v), DataType::Int16) => i16::try_from(*v)
+            .map(ScalarImpl::Int16)
+            .map_err(|_| NumericValueOutOfRange.into()),
+        (Val::I32(v), DataType::Int32) => Ok(ScalarImpl::Int32(*v)),
+        (Val::I64(v), DataType::Int64) => Ok(ScalarImpl::Int64(*v)),
+        (Val::F32(v), DataType::Float32) => Ok(ScalarImpl::Float32(f32::from_bits(*v).into())),
+        (Val::F64(v), DataType::Float64) => Ok(ScalarImpl::Float64(f64::from_bits(*v).into())),
+        _ => Err(InternalError(format!(
+            "unexpected wasm result {:?} for type {:?}",
+            value, data_type
+        ))
+        .into()),
+    }
+}
+
+fn wasm_error(e: impl std::fmt::Display) -> RwError {
+    InvalidParameterValue(format!("wasm function failed: {}", e)).into()
+}
+
+/// Calls a user-defined function on the results of `children`.
+pub struct UdfExpression {
+    children: Vec<BoxedExpression>,
+    function: Arc<WasmFunction>,
+    /// Created on the first evaluation, and reused by the later ones.
+    instance: Mutex<Option<WasmInstance>>,
+}
+
+impl std::fmt::Debug for UdfExpression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("UdfExpression")
+            .field("children", &self.children)
+            .field("function", &self.function)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Expression for UdfExpression {
+    fn return_type(&self) -> DataType {
+        self.function.return_type.clone()
+    }
+
+    fn eval(&self, input: &DataChunk) -> Result<ArrayRef> {
+        let children = self
+            .children
+            .iter()
+            .map(|c| c.eval(input))
+            .collect::<Result<Vec<_>>>()?;
+        let mut builder = self
+            .function
+            .return_type
+            .create_array_builder(input.capacity())?;
+        self.with_instance(|instance| {
+            for i in 0..input.capacity() {
+                // Skip the invisible rows, as the function may fail on them.
+                if let Some(visibility) = input.visibility() {
+                    if !visibility.is_set(i)? {
+                        builder.append_datum(&None)?;
+                        continue;
+                    }
+                }
+                let args = children.iter().map(|c| c.datum_at(i)).collect::<Vec<_>>();
+                let datum = self.function.call(instance, &args)?;
+                builder.append_datum(&datum)?;
+            }
+            Ok(())
+        })?;
+        Ok(Arc::new(builder.finish()?))
+    }
+
+    fn eval_row(&self, input: &Row) -> Result<Datum> {
+        let args = self
+            .children
+            .iter()
+            .map(|c| c.eval_row(input))
+            .collect::<Result<Vec<_>>>()?;
+        self.with_instance(|instance| self.function.call(instance, &args))
+    }
+}
+
+impl UdfExpression {
+    pub fn new(children: Vec<BoxedExpression>, function: WasmFunction) -> Self {
+        Self {
+            children,
+            function: Arc::new(function),
+            instance: Mutex::new(None),
+        }
+    }
+
+    /// Runs `f` on the instance of the function, which is created if there is none. The instance
+    /// is dropped if `f` fails, as a trap may leave its memory in an inconsistent state.
+    fn with_instance<R>(&self, f: impl FnOnce(&mut WasmInstance) -> Result<R>) -> Result<R> {
+        let mut instance = self.instance.lock().unwrap();
+        if instance.is_none() {
+            *instance = Some(self.function.instantiate()?);
+        }
+        let result = f(instance.as_mut().unwrap());
+        if result.is_err() {
+            *instance = None;
+        }
+        result
+    }
+}
+
+impl<'a> TryFrom<&'a ExprNode> for UdfExpression {
+    type Error = RwError;
+
+    fn try_from(prost: &'a ExprNode) -> Result<Self> {
+        ensure!(prost.get_expr_type()? == Type::Udf);
+
+        let return_type = DataType::from(prost.get_return_type()?);
+        let udf = try_match_expand!(prost.get_rex_node().unwrap(), RexNode::Udf)?;
+        if !udf.language.eq_ignore_ascii_case("wasm") {
+            return Err(InternalError(format!("{} is not a wasm function", udf.name)).into());
+        }
+        let children = udf
+            .children
+            .iter()
+            .map(expr_build_from_prost)
+            .collect::<Result<Vec<_>>>()?;
+        let function = WasmFunction::new(
+            &udf.body,
+            &udf.link_symbol,
+            udf.arg_types.iter().map(DataType::from).collect(),
+            return_type,
+        )?;
+        Ok(Self::new(children, function))
+    }
+}
+
+/// Builds a call to a user-defined function, which is either run in place if written in wasm, or
+/// called on an external UDF server otherwise.
+pub fn build_udf_expr(prost: &ExprNode) -> Result<BoxedExpression> {
+    let udf = try_match_expand!(prost.get_rex_node().unwrap(), RexNode::Udf)?;
+    if udf.language.eq_ignore_ascii_case("wasm") {
+        return UdfExpression::try_from(prost).map(Expression::boxed);
+    }
+    build_external_udf_expr(prost, udf)
+}
+
+#[cfg(not(madsim))]
+fn build_external_udf_expr(prost: &ExprNode, udf: &UserDefinedFunction) -> Result<BoxedExpression> {
+    let children = udf
+        .children
+        .iter()
+        .map(expr_build_from_prost)
+        .collect::<Result<Vec<_>>>()?;
+    let name = std::str::from_utf8(&udf.body)
+        .map_err(|e| InternalError(format!("invalid name of function {}: {}", udf.name, e)))?;
+    let function = ExternalFunction::new(
+        name,
+        &udf.link,
+        udf.arg_types.iter().map(DataType::from).collect(),
+        DataType::from(prost.get_return_type()?),
+        udf.timeout_ms,
+        udf.max_retries,
+    )?;
+    Ok(ExternalUdfExpression::new(children, function).boxed())
+}
+
+/// The clients of external UDF servers run on the real network stack, which is not available in
+/// the simulation.
+#[cfg(madsim)]
+fn build_external_udf_expr(
+    _prost: &ExprNode,
+    udf: &UserDefinedFunction,
+) -> Result<BoxedExpression> {
+    Err(NotImplemented(
+        format!("calling external function {} in the simulation", udf.name),
+        None.into(),
+    )
+    .into())
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_common::test_prelude::DataChunkTestExt;
+
+    use super::*;
+    use crate::expr::InputRefExpression;
+
+    const GCD: &str = r#"
+        (module
+          (func (export "gcd") (param i32 i32) (result i32)
+            (if (result i32) (i32.eqz (local.get 1))
+              (then (local.get 0))
+              (else (call 0 (local.get 1) (i32.rem_s (local.get 0) (local.get 1)))))))
+    "#;
+
+    const LOOP: &str = r#"
+        (module
+          (func (export "spin") (param i32) (result i32)
+            (loop (br 0))
+            (local.get 0)))
+    "#;
+
+    const COUNTER: &str = r#"
+        (module
+          (global $n (mut i32) (i32.const 0))
+          (func (export "count") (param i32) (result i32)
+            (global.set $n (i32.add (global.get $n) (local.get 0)))
+            (global.get $n)))
+    "#;
+
+    fn gcd_expr() -> UdfExpression {
+        let function = WasmFunction::new(
+            GCD.as_bytes(),
+            "gcd",
+            vec![DataType::Int32, DataType::Int32],
+            DataType::Int32,
+        )
+        .unwrap();
+        UdfExpression::new(
+            vec![
+                Box::new(InputRefExpression::new(DataType::Int32, 0)),
+                Box::new(InputRefExpression::new(DataType::Int32, 1)),
+            ],
+            function,
+        )
+    }
+
+    #[test]
+    fn test_udf_eval() {
+        let expr = gcd_expr();
+        let chunk = DataChunk::from_pretty(
+            "i i
+             12 18
+             7 .
+             25 15",
+        );
+        let result = expr.eval(&chunk).unwrap();
+        assert_eq!(result.datum_at(0), Some(ScalarImpl::Int32(6)));
+        assert_eq!(result.datum_at(1), None);
+        assert_eq!(result.datum_at(2), Some(ScalarImpl::Int32(5)));
+
+        let row = Row::new(vec![Some(ScalarImpl::Int32(9)), Some(ScalarImpl::Int32(6))]);
+        assert_eq!(expr.eval_row(&row).unwrap(), Some(ScalarImpl::Int32(3)));
+    }
+
+    #[test]
+    fn test_udf_instance_reused() {
+        let function = WasmFunction::new(
+            COUNTER.as_bytes(),
+            "count",
+            vec![DataType::Int32],
+            DataType::Int32,
+        )
+        .unwrap();
+        assert!(MODULES
+            .lock()
+            .unwrap()
+            .contains(&COUNTER.as_bytes().to_vec()));
+        let expr = UdfExpression::new(
+            vec![Box::new(InputRefExpression::new(DataType::Int32, 0))],
+            function,
+        );
+
+        // The global of the instance is kept across the evaluations.
+        let chunk = DataChunk::from_pretty(
+            "i
+             1
+             2",
+        );
+        let result = expr.eval(&chunk).unwrap();
+        assert_eq!(result.datum_at(1), Some(ScalarImpl::Int32(3)));
+        let row = Row::new(vec![Some(ScalarImpl::Int32(4))]);
+        assert_eq!(expr.eval_row(&row).unwrap(), Some(ScalarImpl::Int32(7)));
+    }
+
+    #[test]
+    fn test_udf_signature_mismatch() {
+        assert!(WasmFunction::new(
+            GCD.as_bytes(),
+            "gcd",
+            vec![DataType::Int64, DataType::Int64],
+            DataType::Int64,
+        )
+        .is_err());
+        assert!(WasmFunction::new(
+            GCD.as_bytes(),
+            "lcm",
+            vec![DataType::Int32],
+            DataType::Int32
+        )
+        .is_err());
+        assert!(WasmFunction::new(b"not a module", "gcd", vec![], DataType::Int32).is_err());
+    }
+
+    #[test]
+    fn test_udf_out_of_fuel() {
+        let function = WasmFunction::new(
+            LOOP.as_bytes(),
+            "spin",
+            vec![DataType::Int32],
+            DataType::Int32,
+        )
+        .unwrap();
+        let expr = UdfExpression::new(
+            vec![Box::new(InputRefExpression::new(DataType::Int32, 0))],
+            function,
+        );
+        let row = Row::new(vec![Some(ScalarImpl::Int32(1))]);
+        assert!(expr.eval_row(&row).is_err());
+    }
+}
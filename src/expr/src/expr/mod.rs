@@ -19,15 +19,20 @@ mod expr_array;
 mod expr_binary_bytes;
 pub mod expr_binary_nonnull;
 pub mod expr_binary_nullable;
+mod expr_binary_primitive;
 mod expr_case;
 mod expr_coalesce;
 mod expr_concat_ws;
+#[cfg(not(madsim))]
+mod expr_external_udf;
 mod expr_field;
 mod expr_in;
 mod expr_input_ref;
 mod expr_is_null;
 mod expr_literal;
+mod expr_now;
 mod expr_ternary_bytes;
+mod expr_udf;
 pub mod expr_unary;
 mod template;
 
@@ -36,8 +41,12 @@ use std::slice;
 use std::sync::Arc;
 
 pub use agg::AggKind;
+#[cfg(not(madsim))]
+pub use expr_external_udf::{ExternalFunction, ExternalUdfExpression};
 pub use expr_input_ref::InputRefExpression;
 pub use expr_literal::*;
+pub use expr_now::{epoch_to_timestamp, with_epoch, NowExpression};
+pub use expr_udf::{UdfExpression, WasmFunction};
 use risingwave_common::array::{ArrayRef, DataChunk, Row};
 use risingwave_common::error::ErrorCode::InternalError;
 use risingwave_common::error::Result;
@@ -49,6 +58,7 @@ use crate::expr::expr_array::ArrayExpression;
 use crate::expr::expr_coalesce::CoalesceExpression;
 use crate::expr::expr_concat_ws::ConcatWsExpression;
 use crate::expr::expr_field::FieldExpression;
+use crate::expr::expr_udf::build_udf_expr;
 
 pub type ExpressionRef = Arc<dyn Expression>;
 
@@ -94,6 +104,7 @@ pub fn build_from_prost(prost: &ExprNode) -> Result<BoxedExpression> {
         Substr => build_substr_expr(prost),
         Length => build_length_expr(prost),
         Replace => build_replace_expr(prost),
+        DecimalRescale => build_decimal_rescale_expr(prost),
         Like => build_like_expr(prost),
         Trim => build_trim_expr(prost),
         Ltrim => build_ltrim_expr(prost),
@@ -107,6 +118,8 @@ pub fn build_from_prost(prost: &ExprNode) -> Result<BoxedExpression> {
         In => build_in_expr(prost),
         Field => FieldExpression::try_from(prost).map(Expression::boxed),
         Array => ArrayExpression::try_from(prost).map(Expression::boxed),
+        Udf => build_udf_expr(prost),
+        Now => NowExpression::try_from(prost).map(Expression::boxed),
         _ => Err(InternalError(format!(
             "Unsupported expression type: {:?}",
             prost.get_expr_type()
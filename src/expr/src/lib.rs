@@ -32,5 +32,6 @@
 #![feature(fn_traits)]
 #![feature(assert_matches)]
 
+pub mod arrow;
 pub mod expr;
 pub mod vector_op;
@@ -16,12 +16,14 @@ use std::any::type_name;
 use std::convert::TryInto;
 use std::fmt::Debug;
 
+use chrono::{Duration, NaiveDateTime};
+use num_traits::ops::overflowing::{OverflowingAdd, OverflowingMul, OverflowingSub};
 use num_traits::{CheckedAdd, CheckedDiv, CheckedMul, CheckedNeg, CheckedRem, CheckedSub, Signed};
 use risingwave_common::error::ErrorCode::{InternalError, NumericValueOutOfRange};
 use risingwave_common::error::{Result, RwError};
 use risingwave_common::types::{
     CheckedAdd as NaiveDateTimeCheckedAdd, Decimal, IntervalUnit, NaiveDateTimeWrapper,
-    NaiveDateWrapper,
+    NaiveDateWrapper, NaiveTimeWrapper, OrderedF64,
 };
 
 use super::cast::date_to_timestamp;
@@ -141,7 +143,9 @@ pub fn timestamp_timestamp_sub<T1, T2, T3>(
     r: NaiveDateTimeWrapper,
 ) -> Result<IntervalUnit> {
     let tmp = l.0 - r.0;
-    Ok(IntervalUnit::new(0, tmp.num_days() as i32, 0))
+    let days = tmp.num_days();
+    let ms = (tmp - Duration::days(days)).num_milliseconds();
+    Ok(IntervalUnit::new(0, days as i32, ms))
 }
 
 #[inline(always)]
@@ -149,6 +153,72 @@ pub fn date_date_sub<T1, T2, T3>(l: NaiveDateWrapper, r: NaiveDateWrapper) -> Re
     Ok((l.0 - r.0).num_days() as i32)
 }
 
+#[inline(always)]
+pub fn date_int_add<T1, T2, T3>(l: NaiveDateWrapper, r: i32) -> Result<NaiveDateWrapper> {
+    l.0.checked_add_signed(Duration::days(r as i64))
+        .map(NaiveDateWrapper::new)
+        .ok_or_else(|| NumericValueOutOfRange.into())
+}
+
+#[inline(always)]
+pub fn int_date_add<T1, T2, T3>(l: i32, r: NaiveDateWrapper) -> Result<NaiveDateWrapper> {
+    date_int_add::<T2, T1, T3>(r, l)
+}
+
+#[inline(always)]
+pub fn date_int_sub<T1, T2, T3>(l: NaiveDateWrapper, r: i32) -> Result<NaiveDateWrapper> {
+    date_int_add::<T1, T2, T3>(l, r.checked_neg().ok_or(NumericValueOutOfRange)?)
+}
+
+#[inline(always)]
+pub fn date_time_add<T1, T2, T3>(
+    l: NaiveDateWrapper,
+    r: NaiveTimeWrapper,
+) -> Result<NaiveDateTimeWrapper> {
+    Ok(NaiveDateTimeWrapper::new(l.0.and_time(r.0)))
+}
+
+#[inline(always)]
+pub fn time_date_add<T1, T2, T3>(
+    l: NaiveTimeWrapper,
+    r: NaiveDateWrapper,
+) -> Result<NaiveDateTimeWrapper> {
+    date_time_add::<T2, T1, T3>(r, l)
+}
+
+/// Adds the time part of the interval to the time, wrapping around midnight like PostgreSQL. The
+/// months and days of the interval are ignored.
+#[inline(always)]
+pub fn time_interval_add<T1, T2, T3>(
+    l: NaiveTimeWrapper,
+    r: IntervalUnit,
+) -> Result<NaiveTimeWrapper> {
+    let (time, _) =
+        l.0.overflowing_add_signed(Duration::milliseconds(r.get_ms()));
+    Ok(NaiveTimeWrapper::new(time))
+}
+
+#[inline(always)]
+pub fn interval_time_add<T1, T2, T3>(
+    l: IntervalUnit,
+    r: NaiveTimeWrapper,
+) -> Result<NaiveTimeWrapper> {
+    time_interval_add::<T2, T1, T3>(r, l)
+}
+
+#[inline(always)]
+pub fn time_interval_sub<T1, T2, T3>(
+    l: NaiveTimeWrapper,
+    r: IntervalUnit,
+) -> Result<NaiveTimeWrapper> {
+    time_interval_add::<T1, T2, T3>(l, r.negative())
+}
+
+#[inline(always)]
+pub fn time_time_sub<T1, T2, T3>(l: NaiveTimeWrapper, r: NaiveTimeWrapper) -> Result<IntervalUnit> {
+    Ok(IntervalUnit::from_millis((l.0 - r.0).num_milliseconds()))
+}
+
 #[inline(always)]
 pub fn interval_timestamp_add<T1, T2, T3>(
     l: IntervalUnit,
@@ -197,6 +267,35 @@ pub fn timestamp_interval_sub<T1, T2, T3>(
     interval_timestamp_add::<T1, T2, T3>(r.negative(), l)
 }
 
+/// Adds an interval to a `timestamptz`, which is the microseconds since the unix epoch. The
+/// calculation is done in UTC.
+#[inline(always)]
+pub fn timestampz_interval_add<T1, T2, T3>(l: i64, r: IntervalUnit) -> Result<i64> {
+    let secs = l.div_euclid(1_000_000);
+    let nsecs = (l.rem_euclid(1_000_000) * 1000) as u32;
+    let timestamp = NaiveDateTime::from_timestamp_opt(secs, nsecs).ok_or(NumericValueOutOfRange)?;
+    let timestamp = NaiveDateTimeWrapper::new(timestamp).checked_add(r)?;
+    Ok(timestamp.0.timestamp() * 1_000_000 + timestamp.0.timestamp_subsec_micros() as i64)
+}
+
+#[inline(always)]
+pub fn interval_timestampz_add<T1, T2, T3>(l: IntervalUnit, r: i64) -> Result<i64> {
+    timestampz_interval_add::<T2, T1, T3>(r, l)
+}
+
+#[inline(always)]
+pub fn timestampz_interval_sub<T1, T2, T3>(l: i64, r: IntervalUnit) -> Result<i64> {
+    timestampz_interval_add::<T1, T2, T3>(l, r.negative())
+}
+
+#[inline(always)]
+pub fn timestampz_timestampz_sub<T1, T2, T3>(l: i64, r: i64) -> Result<IntervalUnit> {
+    let diff = l.checked_sub(r).ok_or(NumericValueOutOfRange)?;
+    let days = diff / (86_400 * 1_000_000);
+    let ms = diff % (86_400 * 1_000_000) / 1000;
+    Ok(IntervalUnit::new(0, days as i32, ms))
+}
+
 #[inline(always)]
 pub fn interval_int_mul<T1, T2, T3>(l: IntervalUnit, r: T2) -> Result<IntervalUnit>
 where
@@ -214,13 +313,82 @@ where
     interval_int_mul::<T2, T1, T3>(r, l)
 }
 
+#[inline(always)]
+pub fn interval_float_mul<T1, T2, T3>(l: IntervalUnit, r: T2) -> Result<IntervalUnit>
+where
+    T2: TryInto<OrderedF64> + Debug,
+{
+    l.checked_mul_float(r)
+        .ok_or_else(|| NumericValueOutOfRange.into())
+}
+
+#[inline(always)]
+pub fn float_interval_mul<T1, T2, T3>(l: T1, r: IntervalUnit) -> Result<IntervalUnit>
+where
+    T1: TryInto<OrderedF64> + Debug,
+{
+    interval_float_mul::<T2, T1, T3>(r, l)
+}
+
+#[inline(always)]
+pub fn interval_float_div<T1, T2, T3>(l: IntervalUnit, r: T2) -> Result<IntervalUnit>
+where
+    T2: TryInto<OrderedF64> + Debug,
+{
+    l.checked_div_float(r)
+        .ok_or_else(|| NumericValueOutOfRange.into())
+}
+
+/// Adds two integers with wrapping semantics and tells whether it overflows. Unlike
+/// [`general_add`], it never fails, so it can be applied to whole arrays in a loop that compiles to
+/// SIMD instructions. The caller should raise the overflow error afterwards.
+#[inline(always)]
+pub fn primitive_add<T1, T2, T3>(l: T1, r: T2) -> (T3, bool)
+where
+    T1: Into<T3>,
+    T2: Into<T3>,
+    T3: OverflowingAdd,
+{
+    let (l, r): (T3, T3) = (l.into(), r.into());
+    l.overflowing_add(&r)
+}
+
+/// Subtracts two integers with wrapping semantics and tells whether it overflows. See
+/// [`primitive_add`].
+#[inline(always)]
+pub fn primitive_sub<T1, T2, T3>(l: T1, r: T2) -> (T3, bool)
+where
+    T1: Into<T3>,
+    T2: Into<T3>,
+    T3: OverflowingSub,
+{
+    let (l, r): (T3, T3) = (l.into(), r.into());
+    l.overflowing_sub(&r)
+}
+
+/// Multiplies two integers with wrapping semantics and tells whether it overflows. See
+/// [`primitive_add`].
+#[inline(always)]
+pub fn primitive_mul<T1, T2, T3>(l: T1, r: T2) -> (T3, bool)
+where
+    T1: Into<T3>,
+    T2: Into<T3>,
+    T3: OverflowingMul,
+{
+    let (l, r): (T3, T3) = (l.into(), r.into());
+    l.overflowing_mul(&r)
+}
+
 #[cfg(test)]
 mod tests {
     use std::str::FromStr;
 
-    use risingwave_common::types::Decimal;
+    use chrono::{NaiveDate, NaiveTime};
+    use risingwave_common::types::{
+        Decimal, IntervalUnit, NaiveDateTimeWrapper, NaiveDateWrapper, NaiveTimeWrapper,
+    };
 
-    use crate::vector_op::arithmetic_op::general_add;
+    use crate::vector_op::arithmetic_op::*;
 
     #[test]
     fn test() {
@@ -229,4 +397,75 @@ mod tests {
             Decimal::from_str("2").unwrap()
         );
     }
+
+    #[test]
+    fn test_temporal_arithmetic() {
+        let date = NaiveDateWrapper::new(NaiveDate::from_ymd(2022, 2, 28));
+        let time = NaiveTimeWrapper::new(NaiveTime::from_hms(23, 0, 0));
+        assert_eq!(
+            date_int_add::<NaiveDateWrapper, i32, NaiveDateWrapper>(date, 1).unwrap(),
+            NaiveDateWrapper::new(NaiveDate::from_ymd(2022, 3, 1))
+        );
+        assert_eq!(
+            date_int_sub::<NaiveDateWrapper, i32, NaiveDateWrapper>(date, 59).unwrap(),
+            NaiveDateWrapper::new(NaiveDate::from_ymd(2021, 12, 31))
+        );
+        assert_eq!(
+            date_time_add::<NaiveDateWrapper, NaiveTimeWrapper, NaiveDateTimeWrapper>(date, time)
+                .unwrap(),
+            NaiveDateTimeWrapper::new(NaiveDate::from_ymd(2022, 2, 28).and_hms(23, 0, 0))
+        );
+        // Adding an interval to a time wraps around midnight and ignores the days.
+        assert_eq!(
+            time_interval_add::<NaiveTimeWrapper, IntervalUnit, NaiveTimeWrapper>(
+                time,
+                IntervalUnit::new(0, 1, 2 * 3600 * 1000)
+            )
+            .unwrap(),
+            NaiveTimeWrapper::new(NaiveTime::from_hms(1, 0, 0))
+        );
+        assert_eq!(
+            time_time_sub::<NaiveTimeWrapper, NaiveTimeWrapper, IntervalUnit>(
+                NaiveTimeWrapper::new(NaiveTime::from_hms(1, 0, 0)),
+                time
+            )
+            .unwrap(),
+            IntervalUnit::from_millis(-22 * 3600 * 1000)
+        );
+        assert_eq!(
+            timestamp_timestamp_sub::<NaiveDateTimeWrapper, NaiveDateTimeWrapper, IntervalUnit>(
+                NaiveDateTimeWrapper::new(NaiveDate::from_ymd(2022, 3, 1).and_hms(1, 0, 0)),
+                NaiveDateTimeWrapper::new(NaiveDate::from_ymd(2022, 2, 27).and_hms(0, 0, 0))
+            )
+            .unwrap(),
+            IntervalUnit::new(0, 2, 3600 * 1000)
+        );
+        // 2022-01-01 00:00:00 UTC plus one month and one hour.
+        assert_eq!(
+            timestampz_interval_add::<i64, IntervalUnit, i64>(
+                1_640_995_200_000_000,
+                IntervalUnit::new(1, 0, 3600 * 1000)
+            )
+            .unwrap(),
+            1_643_677_200_000_000
+        );
+    }
+
+    #[test]
+    fn test_interval_float_arithmetic() {
+        let interval = IntervalUnit::new(1, 0, 0);
+        assert_eq!(
+            interval_float_mul::<IntervalUnit, Decimal, IntervalUnit>(
+                interval,
+                Decimal::from_str("0.5").unwrap()
+            )
+            .unwrap(),
+            IntervalUnit::new(0, 15, 0)
+        );
+        assert_eq!(
+            interval_float_div::<IntervalUnit, i32, IntervalUnit>(interval, 4).unwrap(),
+            IntervalUnit::new(0, 7, 12 * 3600 * 1000)
+        );
+        assert!(interval_float_div::<IntervalUnit, i32, IntervalUnit>(interval, 0).is_err());
+    }
 }
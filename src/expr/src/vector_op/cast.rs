@@ -18,11 +18,14 @@ use std::str::FromStr;
 
 use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime};
 use num_traits::ToPrimitive;
-use risingwave_common::error::ErrorCode::{InternalError, InvalidInputSyntax};
+use risingwave_common::error::ErrorCode::{
+    InternalError, InvalidInputSyntax, NumericValueOutOfRange,
+};
 use risingwave_common::error::{parse_error, Result, RwError};
 use risingwave_common::types::{
     Decimal, NaiveDateTimeWrapper, NaiveDateWrapper, NaiveTimeWrapper, OrderedF32, OrderedF64,
 };
+use rust_decimal::Decimal as RustDecimal;
 
 /// String literals for bool type.
 ///
@@ -101,6 +104,24 @@ pub fn str_to_timestampz(elem: &str) -> Result<i64> {
         })
 }
 
+/// Rounds `input` to `scale` digits after the decimal point, and checks that it has at most
+/// `precision` digits in total, as `CAST(input AS NUMERIC(precision, scale))` does.
+#[inline(always)]
+pub fn rescale_decimal(input: Decimal, precision: i32, scale: i32) -> Result<Decimal> {
+    match input.round_dp(scale as u32) {
+        Decimal::Normalized(mut d) => {
+            let limit =
+                RustDecimal::from_i128_with_scale(10i128.pow((precision - scale) as u32), 0);
+            if d.abs() >= limit {
+                return Err(NumericValueOutOfRange.into());
+            }
+            d.rescale(scale as u32);
+            Ok(Decimal::Normalized(d))
+        }
+        d => Ok(d),
+    }
+}
+
 #[inline(always)]
 pub fn str_parse<T>(elem: &str) -> Result<T>
 where
@@ -250,6 +271,22 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_rescale_decimal() {
+        use super::*;
+        let rescale = |s: &str, precision, scale| {
+            rescale_decimal(s.parse().unwrap(), precision, scale).map(|d| d.to_string())
+        };
+        assert_eq!(rescale("123.456", 5, 2).unwrap(), "123.46");
+        assert_eq!(rescale("-123.455", 5, 2).unwrap(), "-123.46");
+        assert_eq!(rescale("1", 5, 2).unwrap(), "1.00");
+        assert_eq!(rescale("0.05", 1, 1).unwrap(), "0.1");
+        assert!(rescale("123.4", 3, 1).is_err());
+        assert!(rescale("99.95", 3, 1).is_err());
+        assert!(rescale("-100", 3, 0).is_ok());
+        assert!(rescale("-1000", 3, 0).is_err());
+    }
+
     #[test]
     fn integer_cast_to_bool() {
         use super::*;
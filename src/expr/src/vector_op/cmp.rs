@@ -105,6 +105,34 @@ where
     general_cmp(l, r, |a, b| a < b)
 }
 
+/// Generates comparison functions between integers which never fail, so they can be applied to
+/// whole arrays in a loop that compiles to SIMD instructions.
+macro_rules! gen_primitive_cmp {
+    ($($func:ident: $op:tt),*) => {
+        $(
+            #[inline(always)]
+            pub fn $func<T1, T2, T3>(l: T1, r: T2) -> bool
+            where
+                T1: Into<T3>,
+                T2: Into<T3>,
+                T3: Ord,
+            {
+                let (l, r): (T3, T3) = (l.into(), r.into());
+                l $op r
+            }
+        )*
+    };
+}
+
+gen_primitive_cmp! {
+    primitive_eq: ==,
+    primitive_ne: !=,
+    primitive_ge: >=,
+    primitive_gt: >,
+    primitive_le: <=,
+    primitive_lt: <
+}
+
 pub fn general_is_distinct_from<T1, T2, T3>(l: Option<T1>, r: Option<T2>) -> Result<Option<bool>>
 where
     T1: TryInto<T3> + Debug,
@@ -0,0 +1,162 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use risingwave_common::error::{ErrorCode, Result, RwError};
+use risingwave_common::types::{DataType, ScalarImpl};
+
+use super::{BoundQuery, BoundSetExpr, BoundStatement, Relation};
+use crate::expr::{Expr as _, ExprImpl, ExprRewriter, Literal, Parameter, Subquery, SubqueryKind};
+
+/// Substitutes the [`Parameter`]s of a prepared statement with their values in text format cast to
+/// the parameter types.
+struct ParamRewriter {
+    params: Vec<Option<String>>,
+    /// The first error encountered, since [`ExprRewriter`] is infallible.
+    error: Option<RwError>,
+}
+
+impl ParamRewriter {
+    fn rewrite(&mut self, expr: &mut ExprImpl) {
+        let placeholder = ExprImpl::literal_bool(false);
+        let bound = std::mem::replace(expr, placeholder);
+        *expr = self.rewrite_expr(bound);
+    }
+
+    fn rewrite_query(&mut self, query: &mut BoundQuery) {
+        match &mut query.body {
+            BoundSetExpr::Select(select) => {
+                if let Some(from) = &mut select.from {
+                    self.rewrite_relation(from);
+                }
+                select
+                    .select_items
+                    .iter_mut()
+                    .chain(select.where_clause.iter_mut())
+                    .chain(select.group_by.iter_mut())
+                    .chain(select.having.iter_mut())
+                    .for_each(|expr| self.rewrite(expr));
+            }
+            BoundSetExpr::Values(values) => values
+                .rows
+                .iter_mut()
+                .flatten()
+                .for_each(|expr| self.rewrite(expr)),
+        }
+        query
+            .extra_order_exprs
+            .iter_mut()
+            .for_each(|expr| self.rewrite(expr));
+    }
+
+    fn rewrite_relation(&mut self, relation: &mut Relation) {
+        match relation {
+            Relation::Source(_) | Relation::BaseTable(_) | Relation::SystemTable(_) => {}
+            Relation::Subquery(subquery) => self.rewrite_query(&mut subquery.query),
+            Relation::Join(join) => {
+                self.rewrite_relation(&mut join.left);
+                self.rewrite_relation(&mut join.right);
+                self.rewrite(&mut join.cond);
+            }
+            Relation::WindowTableFunction(window) => {
+                self.rewrite_relation(&mut window.input);
+                window.args.iter_mut().for_each(|expr| self.rewrite(expr));
+            }
+            Relation::GenerateSeriesFunction(generate_series) => generate_series
+                .args
+                .iter_mut()
+                .for_each(|expr| self.rewrite(expr)),
+            Relation::UnnestFunction(unnest) => self.rewrite(&mut unnest.arg),
+        }
+    }
+
+    fn rewrite_statement(&mut self, stmt: &mut BoundStatement) {
+        match stmt {
+            BoundStatement::Insert(insert) => {
+                self.rewrite_query(&mut insert.source);
+                insert
+                    .cast_exprs
+                    .iter_mut()
+                    .for_each(|expr| self.rewrite(expr));
+            }
+            BoundStatement::Delete(delete) => {
+                delete
+                    .selection
+                    .iter_mut()
+                    .for_each(|expr| self.rewrite(expr));
+            }
+            BoundStatement::Update(update) => {
+                self.rewrite_relation(&mut update.table);
+                update
+                    .selection
+                    .iter_mut()
+                    .chain(update.exprs.iter_mut())
+                    .for_each(|expr| self.rewrite(expr));
+            }
+            BoundStatement::Query(query) => self.rewrite_query(query),
+        }
+    }
+}
+
+impl ExprRewriter for ParamRewriter {
+    fn rewrite_parameter(&mut self, parameter: Parameter) -> ExprImpl {
+        let value = match self.params.get(parameter.index() as usize - 1) {
+            Some(value) => value.clone(),
+            None => {
+                self.error.get_or_insert_with(|| {
+                    ErrorCode::BindError(format!(
+                        "no value supplied for parameter ${}",
+                        parameter.index()
+                    ))
+                    .into()
+                });
+                return parameter.into();
+            }
+        };
+        let data_type = parameter.return_type();
+        let value: ExprImpl = Literal::new(value.map(ScalarImpl::Utf8), DataType::Varchar).into();
+        match value.cast_explicit(data_type) {
+            Ok(expr) => expr,
+            Err(err) => {
+                self.error.get_or_insert(err);
+                parameter.into()
+            }
+        }
+    }
+
+    fn rewrite_subquery(&mut self, mut subquery: Subquery) -> ExprImpl {
+        self.rewrite_query(&mut subquery.query);
+        match &mut subquery.kind {
+            SubqueryKind::Scalar | SubqueryKind::Existential => {}
+            SubqueryKind::In(expr) | SubqueryKind::Some(expr, _) | SubqueryKind::All(expr, _) => {
+                self.rewrite(expr)
+            }
+        }
+        subquery.into()
+    }
+}
+
+impl BoundStatement {
+    /// Substitutes the parameters of a statement bound when it's prepared with their values in
+    /// text format, so that the statement is not bound again on each execution.
+    pub fn bind_param_values(mut self, params: Vec<Option<String>>) -> Result<BoundStatement> {
+        let mut rewriter = ParamRewriter {
+            params,
+            error: None,
+        };
+        rewriter.rewrite_statement(&mut self);
+        match rewriter.error {
+            Some(err) => Err(err),
+            None => Ok(self),
+        }
+    }
+}
@@ -13,12 +13,13 @@
 // limitations under the License.
 
 use risingwave_common::error::Result;
+use risingwave_pb::user::grant_privilege::Action;
 use risingwave_sqlparser::ast::{Expr, ObjectName};
 
 use super::{Binder, BoundBaseTable, BoundTableSource};
 use crate::expr::ExprImpl;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct BoundDelete {
     /// Used for injecting deletion chunks to the source.
     pub table_source: BoundTableSource,
@@ -36,7 +37,7 @@ impl Binder {
         selection: Option<Expr>,
     ) -> Result<BoundDelete> {
         let (schema_name, table_name) = Self::resolve_table_name(source_name.clone())?;
-        let table_source = self.bind_table_source(source_name)?;
+        let table_source = self.bind_table_source(source_name, Action::Delete)?;
         let table = self.bind_table(&schema_name, &table_name, None)?;
         let delete = BoundDelete {
             table_source,
@@ -16,7 +16,7 @@ use risingwave_common::error::{ErrorCode, Result};
 use risingwave_sqlparser::ast::{BinaryOperator, Expr};
 
 use crate::binder::Binder;
-use crate::expr::{ExprImpl, ExprType, FunctionCall};
+use crate::expr::{Expr as _, ExprImpl, ExprType, FunctionCall};
 
 impl Binder {
     pub(super) fn bind_binary_op(
@@ -25,8 +25,7 @@ impl Binder {
         op: BinaryOperator,
         right: Expr,
     ) -> Result<ExprImpl> {
-        let bound_left = self.bind_expr(left)?;
-        let bound_right = self.bind_expr(right)?;
+        let (bound_left, bound_right) = self.bind_binary_operands(left, right)?;
         let func_type = match op {
             BinaryOperator::Plus => ExprType::Add,
             BinaryOperator::Minus => ExprType::Subtract,
@@ -54,6 +53,24 @@ impl Binder {
         Ok(FunctionCall::new(func_type, vec![bound_left, bound_right])?.into())
     }
 
+    /// Binds the operands of a binary operator. An untyped parameter operand, e.g. `$1` in
+    /// `v1 = $1`, is inferred to be of the type of the other operand.
+    fn bind_binary_operands(&mut self, left: Expr, right: Expr) -> Result<(ExprImpl, ExprImpl)> {
+        match (&left, &right) {
+            (Expr::Parameter { index }, _) if self.is_untyped_param(*index) => {
+                let bound_right = self.bind_expr(right)?;
+                self.infer_param_type(*index, &bound_right.return_type())?;
+                Ok((self.bind_expr(left)?, bound_right))
+            }
+            (_, Expr::Parameter { index }) if self.is_untyped_param(*index) => {
+                let bound_left = self.bind_expr(left)?;
+                self.infer_param_type(*index, &bound_left.return_type())?;
+                Ok((bound_left, self.bind_expr(right)?))
+            }
+            _ => Ok((self.bind_expr(left)?, self.bind_expr(right)?)),
+        }
+    }
+
     /// Apply a NOT on top of LIKE.
     fn bind_not_like(&mut self, left: ExprImpl, right: ExprImpl) -> Result<ExprImpl> {
         Ok(FunctionCall::new(
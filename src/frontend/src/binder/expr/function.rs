@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use itertools::Itertools;
+use risingwave_common::catalog::DEFAULT_SCHEMA_NAME;
 use risingwave_common::error::{ErrorCode, Result};
 use risingwave_common::types::DataType;
 use risingwave_expr::expr::AggKind;
@@ -20,7 +21,7 @@ use risingwave_sqlparser::ast::{Function, FunctionArg, FunctionArgExpr};
 
 use crate::binder::bind_context::Clause;
 use crate::binder::Binder;
-use crate::expr::{AggCall, Expr, ExprImpl, ExprType, FunctionCall, Literal};
+use crate::expr::{AggCall, Expr, ExprImpl, ExprType, FunctionCall, Literal, UserDefinedFunction};
 
 impl Binder {
     pub(super) fn bind_function(&mut self, f: Function) -> Result<ExprImpl> {
@@ -87,6 +88,7 @@ impl Binder {
                     ExprType::Floor
                 }
                 "abs" => ExprType::Abs,
+                "now" | "proctime" => ExprType::Now,
                 "booleq" => {
                     inputs = Self::rewrite_two_bool_inputs(inputs)?;
                     ExprType::Equal
@@ -95,13 +97,7 @@ impl Binder {
                     inputs = Self::rewrite_two_bool_inputs(inputs)?;
                     ExprType::NotEqual
                 }
-                _ => {
-                    return Err(ErrorCode::NotImplemented(
-                        format!("unsupported function: {:?}", function_name),
-                        112.into(),
-                    )
-                    .into());
-                }
+                _ => return self.bind_user_defined_function(&function_name, inputs),
             };
             Ok(FunctionCall::new(function_type, inputs)?.into())
         } else {
@@ -113,6 +109,44 @@ impl Binder {
         }
     }
 
+    /// Binds a call to a user-defined function in the default schema, casting the inputs to the
+    /// argument types of the function.
+    fn bind_user_defined_function(
+        &mut self,
+        function_name: &str,
+        inputs: Vec<ExprImpl>,
+    ) -> Result<ExprImpl> {
+        let catalog = match self.catalog.get_function_by_name(
+            &self.db_name,
+            DEFAULT_SCHEMA_NAME,
+            function_name,
+        ) {
+            Ok(catalog) => catalog.clone(),
+            Err(_) => {
+                return Err(ErrorCode::NotImplemented(
+                    format!("unsupported function: {:?}", function_name),
+                    112.into(),
+                )
+                .into())
+            }
+        };
+        if inputs.len() != catalog.arg_types.len() {
+            return Err(ErrorCode::BindError(format!(
+                "Function `{}` takes {} arguments ({} given)",
+                function_name,
+                catalog.arg_types.len(),
+                inputs.len()
+            ))
+            .into());
+        }
+        let inputs = inputs
+            .into_iter()
+            .zip_eq(catalog.arg_types.iter())
+            .map(|(input, t)| input.cast_implicit(t.clone()))
+            .try_collect()?;
+        Ok(UserDefinedFunction::new(catalog, inputs).into())
+    }
+
     /// Make sure inputs only have 2 value and rewrite the arguments.
     /// Nullif(expr1,expr2) -> Case(Equal(expr1 = expr2),null,expr1).
     fn rewrite_nullif_to_case_when(inputs: Vec<ExprImpl>) -> Result<Vec<ExprImpl>> {
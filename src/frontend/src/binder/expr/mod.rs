@@ -13,7 +13,7 @@
 // limitations under the License.
 
 use itertools::zip_eq;
-use risingwave_common::catalog::{ColumnDesc, ColumnId};
+use risingwave_common::catalog::{ColumnDesc, ColumnId, Field};
 use risingwave_common::error::{ErrorCode, Result};
 use risingwave_common::types::DataType;
 use risingwave_sqlparser::ast::{
@@ -21,7 +21,7 @@ use risingwave_sqlparser::ast::{
     TrimWhereField, UnaryOperator,
 };
 
-use crate::binder::Binder;
+use crate::binder::{BindContext, Binder};
 use crate::expr::{Expr as _, ExprImpl, ExprType, FunctionCall, SubqueryKind};
 
 mod binary_op;
@@ -31,13 +31,38 @@ mod subquery;
 mod value;
 
 impl Binder {
+    /// Binds the expression of a generated column of a source, which refers to the other columns
+    /// of the source by their indices in `columns`.
+    pub fn bind_generated_column(
+        &mut self,
+        columns: &[ColumnDesc],
+        expr: Expr,
+    ) -> Result<ExprImpl> {
+        self.context = BindContext::new();
+        self.bind_context(
+            columns.iter().map(|c| (false, Field::from(c))),
+            String::new(),
+            None,
+        )?;
+        let expr = self.bind_expr(expr)?;
+        if expr.has_agg_call() || expr.has_subquery() {
+            return Err(ErrorCode::BindError(
+                "aggregate functions and subqueries are not allowed in generated columns"
+                    .to_string(),
+            )
+            .into());
+        }
+        Ok(expr)
+    }
+
     pub(super) fn bind_expr(&mut self, expr: Expr) -> Result<ExprImpl> {
         match expr {
             // literal
             Expr::Value(v) => Ok(ExprImpl::Literal(Box::new(self.bind_value(v)?))),
+            Expr::Parameter { index } => self.bind_parameter(index),
             Expr::TypedString { data_type, value } => {
                 let s: ExprImpl = self.bind_string(value)?.into();
-                s.cast_explicit(bind_data_type(&data_type)?)
+                cast_explicit(s, &data_type)
             }
             Expr::Row(exprs) => Ok(ExprImpl::Literal(Box::new(self.bind_row(&exprs)?))),
             // input ref
@@ -341,8 +366,30 @@ impl Binder {
     }
 
     pub(super) fn bind_cast(&mut self, expr: Expr, data_type: AstDataType) -> Result<ExprImpl> {
-        self.bind_expr(expr)?
-            .cast_explicit(bind_data_type(&data_type)?)
+        // `$1::INT` gives the type of an untyped parameter.
+        if let Expr::Parameter { index } = &expr {
+            self.infer_param_type(*index, &bind_data_type(&data_type)?)?;
+        }
+        cast_explicit(self.bind_expr(expr)?, &data_type)
+    }
+}
+
+/// Casts `expr` to `data_type` explicitly. Decimals are always stored with the maximum precision,
+/// so a cast to `NUMERIC(p, s)` rounds the value to `s` digits after the decimal point, and fails
+/// if the value has more than `p` digits.
+fn cast_explicit(expr: ExprImpl, data_type: &AstDataType) -> Result<ExprImpl> {
+    let expr = expr.cast_explicit(bind_data_type(data_type)?)?;
+    match data_type {
+        AstDataType::Decimal(Some(precision), scale) => Ok(FunctionCall::new(
+            ExprType::DecimalRescale,
+            vec![
+                expr,
+                ExprImpl::literal_int(*precision as i32),
+                ExprImpl::literal_int(scale.unwrap_or(0) as i32),
+            ],
+        )?
+        .into()),
+        _ => Ok(expr),
     }
 }
 
@@ -350,22 +397,14 @@ impl Binder {
 pub fn bind_struct_field(column_def: &StructField) -> Result<ColumnDesc> {
     let field_descs = if let AstDataType::Struct(defs) = &column_def.data_type {
         defs.iter()
-            .map(|f| {
-                Ok(ColumnDesc {
-                    data_type: bind_data_type(&f.data_type)?,
-                    // Literals don't have `column_id`.
-                    column_id: ColumnId::new(0),
-                    name: f.name.value.clone(),
-                    field_descs: vec![],
-                    type_name: "".to_string(),
-                })
-            })
+            .map(bind_struct_field)
             .collect::<Result<Vec<_>>>()?
     } else {
         vec![]
     };
     Ok(ColumnDesc {
         data_type: bind_data_type(&column_def.data_type)?,
+        // Literals don't have `column_id`.
         column_id: ColumnId::new(0),
         name: column_def.name.value.clone(),
         field_descs,
@@ -382,6 +421,27 @@ pub fn bind_data_type(data_type: &AstDataType) -> Result<DataType> {
         AstDataType::Real | AstDataType::Float(Some(1..=24)) => DataType::Float32,
         AstDataType::Double | AstDataType::Float(Some(25..=53) | None) => DataType::Float64,
         AstDataType::Decimal(None, None) => DataType::Decimal,
+        AstDataType::Decimal(precision, scale) => {
+            // The precision and scale are only validated here. Decimals are always stored with the
+            // maximum precision, which is 28 digits. See `cast_explicit`.
+            let precision = precision.unwrap_or(28);
+            let scale = scale.unwrap_or(0);
+            if !(1..=28).contains(&precision) {
+                return Err(ErrorCode::BindError(format!(
+                    "NUMERIC precision {} must be between 1 and 28",
+                    precision
+                ))
+                .into());
+            }
+            if scale > precision {
+                return Err(ErrorCode::BindError(format!(
+                    "NUMERIC scale {} must be between 0 and precision {}",
+                    scale, precision
+                ))
+                .into());
+            }
+            DataType::Decimal
+        }
         AstDataType::Varchar(_) => DataType::Varchar,
         AstDataType::Date => DataType::Date,
         AstDataType::Time(false) => DataType::Time,
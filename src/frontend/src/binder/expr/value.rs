@@ -18,7 +18,18 @@ use risingwave_expr::vector_op::cast::str_parse;
 use risingwave_sqlparser::ast::{DateTimeField, Expr, Value};
 
 use crate::binder::Binder;
-use crate::expr::{align_types, Expr as _, ExprImpl, ExprType, FunctionCall, Literal};
+use crate::expr::{align_types, Expr as _, ExprImpl, ExprType, FunctionCall, Literal, Parameter};
+
+/// The maximum number of parameters of a statement, the same as Postgres.
+const MAX_PARAMS: u64 = 65535;
+
+/// Returns the position of the parameter `$index` in the parameters.
+fn param_position(index: u64) -> Result<usize> {
+    if index == 0 || index > MAX_PARAMS {
+        return Err(ErrorCode::BindError(format!("there is no parameter ${}", index)).into());
+    }
+    Ok(index as usize - 1)
+}
 
 impl Binder {
     pub fn bind_value(&mut self, value: Value) -> Result<Literal> {
@@ -41,6 +52,58 @@ impl Binder {
         }
     }
 
+    /// Binds the parameter `$index`. When executing a prepared statement, the parameter is bound to
+    /// its value in text format cast to the parameter type. When preparing a statement, it's bound
+    /// to a [`Parameter`] to be substituted with the value on each execution.
+    pub(super) fn bind_parameter(&mut self, index: u64) -> Result<ExprImpl> {
+        let i = param_position(index)?;
+        match &self.param_values {
+            Some(values) => {
+                let value = values.get(i).ok_or_else(|| {
+                    ErrorCode::BindError(format!("no value supplied for parameter ${}", index))
+                })?;
+                let data_type = self
+                    .param_types
+                    .get(i)
+                    .cloned()
+                    .flatten()
+                    .unwrap_or(DataType::Varchar);
+                let value: ExprImpl =
+                    Literal::new(value.clone().map(ScalarImpl::Utf8), DataType::Varchar).into();
+                value.cast_explicit(data_type)
+            }
+            None => {
+                if i >= self.param_types.len() {
+                    self.param_types.resize(i + 1, None);
+                }
+                // Parameters whose types can't be inferred are of type varchar.
+                let data_type = self.param_types[i].get_or_insert(DataType::Varchar).clone();
+                Ok(Parameter::new(index, data_type).into())
+            }
+        }
+    }
+
+    /// Whether `$index` is a parameter whose type is not known yet.
+    pub(super) fn is_untyped_param(&self, index: u64) -> bool {
+        self.param_values.is_none()
+            && matches!(
+                param_position(index),
+                Ok(i) if self.param_types.get(i).map_or(true, Option::is_none)
+            )
+    }
+
+    /// Sets the type of `$index` if it's not known yet.
+    pub(super) fn infer_param_type(&mut self, index: u64, data_type: &DataType) -> Result<()> {
+        let i = param_position(index)?;
+        if self.is_untyped_param(index) {
+            if i >= self.param_types.len() {
+                self.param_types.resize(i + 1, None);
+            }
+            self.param_types[i] = Some(data_type.clone());
+        }
+        Ok(())
+    }
+
     pub(super) fn bind_string(&mut self, s: String) -> Result<Literal> {
         Ok(Literal::new(Some(ScalarImpl::Utf8(s)), DataType::Varchar))
     }
@@ -16,13 +16,14 @@ use itertools::Itertools;
 use risingwave_common::array::StructValue;
 use risingwave_common::error::{ErrorCode, Result};
 use risingwave_common::types::{DataType, ScalarImpl};
+use risingwave_pb::user::grant_privilege::Action;
 use risingwave_sqlparser::ast::{Ident, ObjectName, Query, SetExpr};
 
 use super::{BoundQuery, BoundSetExpr};
 use crate::binder::{Binder, BoundTableSource};
 use crate::expr::{Expr, ExprImpl, InputRef, Literal};
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct BoundInsert {
     /// Used for injecting deletion chunks to the source.
     pub table_source: BoundTableSource,
@@ -30,7 +31,9 @@ pub struct BoundInsert {
     pub source: BoundQuery,
 
     /// Used as part of an extra `Project` when the column types of `source` query does not match
-    /// `table_source`. This does not include a simple `VALUE`. See comments in code for details.
+    /// `table_source`, or when the target columns are not all the columns of `table_source` in
+    /// order. This does not include a simple `VALUE` into all the columns. See comments in code
+    /// for details.
     pub cast_exprs: Vec<ExprImpl>,
 }
 
@@ -38,16 +41,26 @@ impl Binder {
     pub(super) fn bind_insert(
         &mut self,
         source_name: ObjectName,
-        _columns: Vec<Ident>,
+        columns: Vec<Ident>,
         source: Query,
     ) -> Result<BoundInsert> {
-        let table_source = self.bind_table_source(source_name)?;
+        let table_source = self.bind_table_source(source_name, Action::Insert)?;
 
-        let expected_types = table_source
+        let table_types = table_source
             .columns
             .iter()
             .map(|c| c.data_type.clone())
-            .collect();
+            .collect_vec();
+
+        // The indices of the columns in `table_source` that the `source` query inserts into.
+        let target_indices = match columns.is_empty() {
+            true => (0..table_types.len()).collect_vec(),
+            false => Self::bind_insert_columns(&table_source, columns)?,
+        };
+        let expected_types = target_indices
+            .iter()
+            .map(|&i| table_types[i].clone())
+            .collect_vec();
 
         // When the column types of `source` query does not match `expected_types`, casting is
         // needed.
@@ -80,7 +93,7 @@ impl Binder {
                 offset: None,
                 fetch: None,
             } if order.is_empty() => {
-                let values = self.bind_values(values, Some(expected_types))?;
+                let values = self.bind_values(values, Some(expected_types.clone()))?;
                 let body = BoundSetExpr::Values(values.into());
                 (
                     BoundQuery {
@@ -99,7 +112,7 @@ impl Binder {
                 let cast_exprs = match expected_types == actual_types {
                     true => vec![],
                     false => Self::cast_on_insert(
-                        expected_types,
+                        expected_types.clone(),
                         actual_types
                             .into_iter()
                             .enumerate()
@@ -111,6 +124,29 @@ impl Binder {
             }
         };
 
+        // When only some of the columns are specified, or they are not in the order of the table,
+        // project the `source` to all the columns of the table, filling the others with NULLs.
+        let cast_exprs = if target_indices.iter().copied().eq(0..table_types.len()) {
+            cast_exprs
+        } else {
+            let source_exprs = match cast_exprs.is_empty() {
+                true => expected_types
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, t)| InputRef::new(i, t).into())
+                    .collect_vec(),
+                false => cast_exprs,
+            };
+            let mut exprs = table_types
+                .into_iter()
+                .map(|t| Literal::new(None, t).into())
+                .collect_vec();
+            for (expr, i) in source_exprs.into_iter().zip_eq(target_indices) {
+                exprs[i] = expr;
+            }
+            exprs
+        };
+
         let insert = BoundInsert {
             table_source,
             source,
@@ -120,6 +156,35 @@ impl Binder {
         Ok(insert)
     }
 
+    /// Resolves the target `columns` of an insert to their indices in `table_source`.
+    pub(crate) fn bind_insert_columns(
+        table_source: &BoundTableSource,
+        columns: Vec<Ident>,
+    ) -> Result<Vec<usize>> {
+        let mut indices: Vec<usize> = Vec::with_capacity(columns.len());
+        for column in columns {
+            let index = table_source
+                .columns
+                .iter()
+                .position(|c| c.name == column.value)
+                .ok_or_else(|| {
+                    ErrorCode::ItemNotFound(format!(
+                        "column \"{}\" of relation \"{}\"",
+                        column.value, table_source.name
+                    ))
+                })?;
+            if indices.contains(&index) {
+                return Err(ErrorCode::BindError(format!(
+                    "column \"{}\" specified more than once",
+                    column.value
+                ))
+                .into());
+            }
+            indices.push(index);
+        }
+        Ok(indices)
+    }
+
     /// Cast a list of `exprs` to corresponding `expected_types` IN ASSIGNMENT CONTEXT. Make sure
     /// you understand the difference of implicit, assignment and explicit cast before reusing it.
     pub(super) fn cast_on_insert(
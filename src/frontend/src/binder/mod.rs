@@ -15,9 +15,11 @@
 use std::collections::HashMap;
 
 use risingwave_common::error::Result;
+use risingwave_common::types::DataType;
 use risingwave_sqlparser::ast::{Statement, TableAlias};
 
 pub mod bind_context;
+mod bind_param;
 mod delete;
 pub(crate) mod expr;
 mod insert;
@@ -35,8 +37,9 @@ pub use delete::BoundDelete;
 pub use insert::BoundInsert;
 pub use query::BoundQuery;
 pub use relation::{
-    BoundBaseTable, BoundGenerateSeriesFunction, BoundJoin, BoundSource, BoundTableSource,
-    BoundWindowTableFunction, Relation, WindowTableFunctionKind,
+    BoundBaseTable, BoundGenerateSeriesFunction, BoundJoin, BoundSource, BoundSystemTable,
+    BoundTableSource, BoundUnnestFunction, BoundWindowTableFunction, Relation,
+    WindowTableFunctionKind,
 };
 pub use select::BoundSelect;
 pub use set_expr::BoundSetExpr;
@@ -45,6 +48,7 @@ pub use update::BoundUpdate;
 pub use values::BoundValues;
 
 use crate::catalog::catalog_service::CatalogReadGuard;
+use crate::user::user_privilege::ObjectCheckItem;
 
 /// `Binder` binds the identifiers in AST to columns in relations
 pub struct Binder {
@@ -60,10 +64,38 @@ pub struct Binder {
     next_subquery_id: usize,
     /// Map the cte's name to its Relation::Subquery.
     cte_to_relation: HashMap<String, (BoundQuery, TableAlias)>,
+
+    /// Types of the parameters (`$1`, `$2`, ...), where `None` means the type is not known yet
+    /// and will be inferred from the context.
+    param_types: Vec<Option<DataType>>,
+    /// Values of the parameters in text format. `None` when preparing a statement, in which case
+    /// the parameters are bound as [`Parameter`](crate::expr::Parameter)s.
+    param_values: Option<Vec<Option<String>>>,
+
+    /// Privileges on the objects accessed by the statement, which are checked against the user
+    /// after binding.
+    object_check_items: Vec<ObjectCheckItem>,
+}
+
+/// Parameters of a prepared statement to execute it with.
+#[derive(Debug, Clone, Default)]
+pub struct ParamValues {
+    pub types: Vec<DataType>,
+    /// Values in text format.
+    pub values: Vec<Option<String>>,
 }
 
 impl Binder {
     pub fn new(catalog: CatalogReadGuard, db_name: String) -> Binder {
+        Self::new_with_params(catalog, db_name, ParamValues::default())
+    }
+
+    /// Creates a binder of a statement to execute with the given parameters.
+    pub fn new_with_params(
+        catalog: CatalogReadGuard,
+        db_name: String,
+        params: ParamValues,
+    ) -> Binder {
         Binder {
             catalog,
             db_name,
@@ -71,14 +103,45 @@ impl Binder {
             upper_contexts: vec![],
             next_subquery_id: 0,
             cte_to_relation: HashMap::new(),
+            param_types: params.types.into_iter().map(Some).collect(),
+            param_values: Some(params.values),
+            object_check_items: vec![],
+        }
+    }
+
+    /// Creates a binder of a statement to prepare, where the parameters in `param_types` without
+    /// a type are inferred. Call [`Binder::param_types`] after binding to get the types.
+    pub fn new_with_param_types(
+        catalog: CatalogReadGuard,
+        db_name: String,
+        param_types: Vec<Option<DataType>>,
+    ) -> Binder {
+        Binder {
+            param_types,
+            param_values: None,
+            ..Self::new(catalog, db_name)
         }
     }
 
+    /// Returns the types of the parameters bound so far. Parameters whose types can't be inferred
+    /// are of type varchar.
+    pub fn param_types(&self) -> Vec<DataType> {
+        self.param_types
+            .iter()
+            .map(|t| t.clone().unwrap_or(DataType::Varchar))
+            .collect()
+    }
+
     /// Bind a [`Statement`].
     pub fn bind(&mut self, stmt: Statement) -> Result<BoundStatement> {
         self.bind_statement(stmt)
     }
 
+    /// Returns the privileges required by the statements bound so far.
+    pub fn object_check_items(&self) -> &[ObjectCheckItem] {
+        &self.object_check_items
+    }
+
     fn push_context(&mut self) {
         let new_context = std::mem::take(&mut self.context);
         self.upper_contexts.push(new_context);
@@ -26,11 +26,13 @@ mod generate_series;
 mod join;
 mod subquery;
 mod table_or_source;
+mod unnest;
 mod window_table_function;
 pub use generate_series::BoundGenerateSeriesFunction;
 pub use join::BoundJoin;
 pub use subquery::BoundSubquery;
-pub use table_or_source::{BoundBaseTable, BoundSource, BoundTableSource};
+pub use table_or_source::{BoundBaseTable, BoundSource, BoundSystemTable, BoundTableSource};
+pub use unnest::BoundUnnestFunction;
 pub use window_table_function::{BoundWindowTableFunction, WindowTableFunctionKind};
 
 /// A validated item that refers to a table-like entity, including base table, subquery, join, etc.
@@ -39,10 +41,12 @@ pub use window_table_function::{BoundWindowTableFunction, WindowTableFunctionKin
 pub enum Relation {
     Source(Box<BoundSource>),
     BaseTable(Box<BoundBaseTable>),
+    SystemTable(Box<BoundSystemTable>),
     Subquery(Box<BoundSubquery>),
     Join(Box<BoundJoin>),
     WindowTableFunction(Box<BoundWindowTableFunction>),
     GenerateSeriesFunction(Box<BoundGenerateSeriesFunction>),
+    UnnestFunction(Box<BoundUnnestFunction>),
 }
 
 impl Binder {
@@ -193,6 +197,11 @@ impl Binder {
                             self.bind_generate_series_function(args)?,
                         )));
                     }
+                    if func_name.eq_ignore_ascii_case("unnest") {
+                        return Ok(Relation::UnnestFunction(Box::new(
+                            self.bind_unnest_function(args)?,
+                        )));
+                    }
                     let kind = WindowTableFunctionKind::from_str(func_name).map_err(|_| {
                         ErrorCode::NotImplemented(
                             format!("unknown window function kind: {}", name.0[0].value),
@@ -14,14 +14,18 @@
 
 use std::sync::Arc;
 
-use risingwave_common::catalog::ColumnDesc;
-use risingwave_common::error::{ErrorCode, Result, RwError};
+use risingwave_common::catalog::{ColumnDesc, Schema};
+use risingwave_common::error::{Result, RwError};
+use risingwave_common::types::Datum;
+use risingwave_pb::user::grant_privilege::{Action, Object};
 use risingwave_sqlparser::ast::{ObjectName, TableAlias};
 
 use crate::binder::{Binder, Relation};
 use crate::catalog::source_catalog::SourceCatalog;
+use crate::catalog::system_catalog::{SystemTable, PG_CATALOG_SCHEMA_NAME};
 use crate::catalog::table_catalog::TableCatalog;
 use crate::catalog::{CatalogError, TableId};
+use crate::user::user_privilege::ObjectCheckItem;
 
 #[derive(Debug, Clone)]
 pub struct BoundBaseTable {
@@ -32,7 +36,7 @@ pub struct BoundBaseTable {
 }
 
 /// `BoundTableSource` is used by DML statement on table source like insert, update.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct BoundTableSource {
     pub name: String,       // explain-only
     pub source_id: TableId, // TODO: refactor to source id
@@ -44,6 +48,14 @@ pub struct BoundSource {
     pub catalog: SourceCatalog,
 }
 
+/// A virtual table in `pg_catalog`, whose rows are generated when it's bound.
+#[derive(Debug, Clone)]
+pub struct BoundSystemTable {
+    pub name: String, // explain-only
+    pub schema: Schema,
+    pub rows: Vec<Vec<Datum>>,
+}
+
 impl From<&SourceCatalog> for BoundSource {
     fn from(s: &SourceCatalog) -> Self {
         Self { catalog: s.clone() }
@@ -57,20 +69,24 @@ impl Binder {
         table_name: &str,
         alias: Option<TableAlias>,
     ) -> Result<Relation> {
-        if schema_name == "pg_catalog" {
-            // TODO: support pg_catalog.
-            return Err(ErrorCode::NotImplemented(
-                // TODO: We can ref the document of `SHOW` commands here if ready.
-                r###"pg_catalog is not supported, please use `SHOW` commands for now.
-`SHOW TABLES`,
-`SHOW MATERIALIZED VIEWS`,
-`DESCRIBE <table>`,
-`SHOW COLUMNS FROM [table]`
-"###
-                .into(),
-                1695.into(),
-            )
-            .into());
+        if schema_name == PG_CATALOG_SCHEMA_NAME {
+            let Some(table) = SystemTable::read(&self.catalog, &self.db_name, table_name)? else {
+                return Err(CatalogError::NotFound(
+                    "system table",
+                    format!("{}.{}", schema_name, table_name),
+                )
+                .into());
+            };
+            self.bind_context(
+                table.fields.iter().map(|f| (false, f.clone())),
+                table_name.to_string(),
+                alias,
+            )?;
+            return Ok(Relation::SystemTable(Box::new(BoundSystemTable {
+                name: table_name.to_string(),
+                schema: Schema::new(table.fields),
+                rows: table.rows,
+            })));
         }
 
         let (ret, columns) = {
@@ -82,6 +98,14 @@ impl Binder {
                 let table_catalog = table_catalog.clone();
                 let columns = table_catalog.columns.clone();
                 let table_indexes = self.resolve_table_indexes(schema_name, table_id)?;
+                self.object_check_items.push(
+                    ObjectCheckItem::new(
+                        table_name,
+                        Object::TableId(table_id.table_id),
+                        Action::Select,
+                    )
+                    .with_owner(&table_catalog.owner),
+                );
 
                 let table = BoundBaseTable {
                     name: table_name.to_string(),
@@ -93,6 +117,10 @@ impl Binder {
                 (Relation::BaseTable(Box::new(table)), columns)
             } else if let Ok(s) = catalog.get_source_by_name(&self.db_name, schema_name, table_name)
             {
+                self.object_check_items.push(
+                    ObjectCheckItem::new(table_name, Object::SourceId(s.id), Action::Select)
+                        .with_owner(&s.owner),
+                );
                 (Relation::Source(Box::new(s.into())), s.columns.clone())
             } else {
                 return Err(RwError::from(CatalogError::NotFound(
@@ -158,7 +186,12 @@ impl Binder {
         })
     }
 
-    pub(crate) fn bind_table_source(&mut self, name: ObjectName) -> Result<BoundTableSource> {
+    /// Binds the table source to apply the DML `action` on.
+    pub(crate) fn bind_table_source(
+        &mut self,
+        name: ObjectName,
+        action: Action,
+    ) -> Result<BoundTableSource> {
         let (schema_name, source_name) = Self::resolve_table_name(name)?;
         let source = self
             .catalog
@@ -166,6 +199,17 @@ impl Binder {
 
         let source_id = TableId::new(source.id);
 
+        // Privileges of a table are granted on the table instead of its associated source.
+        let table = self
+            .catalog
+            .get_table_by_name(&self.db_name, &schema_name, &source_name);
+        let (object, owner) = match table {
+            Ok(table) => (Object::TableId(table.id().table_id), &table.owner),
+            Err(_) => (Object::SourceId(source.id), &source.owner),
+        };
+        self.object_check_items
+            .push(ObjectCheckItem::new(&source_name, object, action).with_owner(owner));
+
         let columns = source
             .columns
             .iter()
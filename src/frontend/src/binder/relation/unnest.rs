@@ -0,0 +1,76 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use itertools::Itertools;
+use risingwave_common::catalog::Field;
+use risingwave_common::error::ErrorCode;
+use risingwave_common::types::DataType;
+use risingwave_sqlparser::ast::FunctionArg;
+
+use super::{Binder, Result};
+use crate::expr::{Expr, ExprImpl};
+
+#[derive(Debug, Clone)]
+pub struct BoundUnnestFunction {
+    pub(crate) arg: ExprImpl,
+    /// The type of the elements of `arg`.
+    pub(crate) data_type: DataType,
+}
+
+impl Binder {
+    pub(super) fn bind_unnest_function(
+        &mut self,
+        args: Vec<FunctionArg>,
+    ) -> Result<BoundUnnestFunction> {
+        // unnest ( anyarray )
+        let mut exprs: Vec<_> = args
+            .into_iter()
+            .map(|arg| self.bind_function_arg(arg))
+            .flatten_ok()
+            .try_collect()?;
+        if exprs.len() != 1 {
+            return Err(ErrorCode::BindError(
+                "the length of args of unnest function should be 1".to_string(),
+            )
+            .into());
+        }
+        let arg = exprs.pop().unwrap();
+
+        let data_type = match arg.return_type() {
+            DataType::List { datatype } => *datatype,
+            t => {
+                return Err(ErrorCode::BindError(format!(
+                    "the argument of unnest function should be an array, but got {:?}",
+                    t
+                ))
+                .into())
+            }
+        };
+
+        let columns = [(
+            false,
+            Field {
+                data_type: data_type.clone(),
+                name: "unnest".to_string(),
+                sub_fields: vec![],
+                type_name: "".to_string(),
+            },
+        )]
+        .into_iter();
+
+        self.bind_context(columns, "unnest".to_string(), None)?;
+
+        Ok(BoundUnnestFunction { arg, data_type })
+    }
+}
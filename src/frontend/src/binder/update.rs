@@ -19,12 +19,13 @@ use std::collections::HashMap;
 use itertools::Itertools;
 use risingwave_common::ensure;
 use risingwave_common::error::{ErrorCode, Result};
+use risingwave_pb::user::grant_privilege::Action;
 use risingwave_sqlparser::ast::{Assignment, Expr, TableFactor, TableWithJoins};
 
 use super::{Binder, BoundTableSource, Relation};
 use crate::expr::{Expr as _, ExprImpl};
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct BoundUpdate {
     /// Used for injecting new chunks to the source.
     pub table_source: BoundTableSource,
@@ -52,7 +53,7 @@ impl Binder {
                 TableFactor::Table { name, .. } => name.clone(),
                 _ => unreachable!(),
             };
-            self.bind_table_source(name)?
+            self.bind_table_source(name, Action::Update)?
         };
 
         let table = self.bind_vec_table_with_joins(vec![table])?.unwrap();
@@ -20,14 +20,16 @@ use risingwave_common::catalog::{CatalogVersion, TableId};
 use risingwave_common::error::ErrorCode::InternalError;
 use risingwave_common::error::{Result, RwError};
 use risingwave_pb::catalog::{
-    Database as ProstDatabase, Schema as ProstSchema, Source as ProstSource, Table as ProstTable,
+    Database as ProstDatabase, Function as ProstFunction, Schema as ProstSchema,
+    Source as ProstSource, Table as ProstTable,
 };
+use risingwave_pb::plan_common::ColumnCatalog as ProstColumnCatalog;
 use risingwave_pb::stream_plan::StreamFragmentGraph;
 use risingwave_rpc_client::MetaClient;
 use tokio::sync::watch::Receiver;
 
 use super::root_catalog::Catalog;
-use super::DatabaseId;
+use super::{DatabaseId, FunctionId};
 
 pub type CatalogReadGuard = ArcRwLockReadGuard<RawRwLock, Catalog>;
 
@@ -74,9 +76,27 @@ pub trait CatalogWriter: Send + Sync {
 
     async fn drop_source(&self, source_id: u32) -> Result<()>;
 
+    async fn alter_source_add_column(
+        &self,
+        source_id: u32,
+        column: ProstColumnCatalog,
+    ) -> Result<()>;
+
+    async fn alter_table_name(&self, table_id: u32, new_name: &str) -> Result<()>;
+
+    async fn alter_source_name(&self, source_id: u32, new_name: &str) -> Result<()>;
+
+    async fn alter_table_owner(&self, table_id: u32, new_owner: &str) -> Result<()>;
+
+    async fn alter_source_owner(&self, source_id: u32, new_owner: &str) -> Result<()>;
+
     async fn drop_database(&self, database_id: u32) -> Result<()>;
 
     async fn drop_schema(&self, schema_id: u32) -> Result<()>;
+
+    async fn create_function(&self, function: ProstFunction) -> Result<()>;
+
+    async fn drop_function(&self, function_id: FunctionId) -> Result<()>;
 }
 
 #[derive(Clone)]
@@ -159,6 +179,50 @@ impl CatalogWriter for CatalogWriterImpl {
         self.wait_version(version).await
     }
 
+    async fn alter_source_add_column(
+        &self,
+        source_id: u32,
+        column: ProstColumnCatalog,
+    ) -> Result<()> {
+        let version = self
+            .meta_client
+            .alter_source_add_column(source_id, column)
+            .await?;
+        self.wait_version(version).await
+    }
+
+    async fn alter_table_name(&self, table_id: u32, new_name: &str) -> Result<()> {
+        let version = self
+            .meta_client
+            .alter_table_name(table_id, new_name)
+            .await?;
+        self.wait_version(version).await
+    }
+
+    async fn alter_source_name(&self, source_id: u32, new_name: &str) -> Result<()> {
+        let version = self
+            .meta_client
+            .alter_source_name(source_id, new_name)
+            .await?;
+        self.wait_version(version).await
+    }
+
+    async fn alter_table_owner(&self, table_id: u32, new_owner: &str) -> Result<()> {
+        let version = self
+            .meta_client
+            .alter_table_owner(table_id, new_owner)
+            .await?;
+        self.wait_version(version).await
+    }
+
+    async fn alter_source_owner(&self, source_id: u32, new_owner: &str) -> Result<()> {
+        let version = self
+            .meta_client
+            .alter_source_owner(source_id, new_owner)
+            .await?;
+        self.wait_version(version).await
+    }
+
     async fn drop_schema(&self, schema_id: u32) -> Result<()> {
         let version = self.meta_client.drop_schema(schema_id).await?;
         self.wait_version(version).await
@@ -168,6 +232,16 @@ impl CatalogWriter for CatalogWriterImpl {
         let version = self.meta_client.drop_database(database_id).await?;
         self.wait_version(version).await
     }
+
+    async fn create_function(&self, function: ProstFunction) -> Result<()> {
+        let (_, version) = self.meta_client.create_function(function).await?;
+        self.wait_version(version).await
+    }
+
+    async fn drop_function(&self, function_id: FunctionId) -> Result<()> {
+        let version = self.meta_client.drop_function(function_id).await?;
+        self.wait_version(version).await
+    }
 }
 
 impl CatalogWriterImpl {
@@ -52,6 +52,7 @@ impl ColumnCatalog {
         ProstColumnCatalog {
             column_desc: Some(self.column_desc.to_protobuf()),
             is_hidden: self.is_hidden,
+            generated_expr: None,
         }
     }
 
@@ -0,0 +1,52 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use risingwave_common::types::DataType;
+use risingwave_pb::catalog::Function as ProstFunction;
+
+use super::FunctionId;
+
+/// A user-defined function. Functions can not be overloaded, so a function is identified by its
+/// name in the schema.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct FunctionCatalog {
+    pub id: FunctionId,
+    pub name: String,
+    pub arg_types: Vec<DataType>,
+    pub return_type: DataType,
+    pub language: String,
+    pub body: Vec<u8>,
+    pub link_symbol: String,
+    /// The address of the UDF server, only for external functions.
+    pub link: String,
+    pub timeout_ms: u64,
+    pub max_retries: u32,
+}
+
+impl From<&ProstFunction> for FunctionCatalog {
+    fn from(prost: &ProstFunction) -> Self {
+        Self {
+            id: prost.id,
+            name: prost.name.clone(),
+            arg_types: prost.arg_types.iter().map(DataType::from).collect(),
+            return_type: DataType::from(prost.get_return_type().unwrap()),
+            language: prost.language.clone(),
+            body: prost.body.clone(),
+            link_symbol: prost.link_symbol.clone(),
+            link: prost.link.clone(),
+            timeout_ms: prost.timeout_ms,
+            max_retries: prost.max_retries,
+        }
+    }
+}
@@ -20,12 +20,15 @@ pub(crate) mod catalog_service;
 
 pub(crate) mod column_catalog;
 pub(crate) mod database_catalog;
+pub(crate) mod function_catalog;
 pub(crate) mod root_catalog;
 pub(crate) mod schema_catalog;
 pub(crate) mod source_catalog;
+pub(crate) mod system_catalog;
 pub(crate) mod table_catalog;
 
 pub(crate) type SourceId = u32;
+pub(crate) type FunctionId = u32;
 
 pub(crate) type DatabaseId = u32;
 pub(crate) type SchemaId = u32;
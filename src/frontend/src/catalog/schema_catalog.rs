@@ -15,12 +15,15 @@
 use std::collections::HashMap;
 
 use risingwave_common::catalog::TableId;
-use risingwave_pb::catalog::{Schema as ProstSchema, Source as ProstSource, Table as ProstTable};
+use risingwave_pb::catalog::{
+    Function as ProstFunction, Schema as ProstSchema, Source as ProstSource, Table as ProstTable,
+};
 use risingwave_pb::stream_plan::source_node::SourceType;
 
+use super::function_catalog::FunctionCatalog;
 use super::source_catalog::SourceCatalog;
 use crate::catalog::table_catalog::TableCatalog;
-use crate::catalog::SchemaId;
+use crate::catalog::{FunctionId, SchemaId};
 
 pub type SourceId = u32;
 
@@ -33,6 +36,8 @@ pub struct SchemaCatalog {
     table_name_by_id: HashMap<TableId, String>,
     source_by_name: HashMap<String, SourceCatalog>,
     source_name_by_id: HashMap<SourceId, String>,
+    function_by_name: HashMap<String, FunctionCatalog>,
+    function_name_by_id: HashMap<FunctionId, String>,
 }
 
 impl SchemaCatalog {
@@ -50,6 +55,12 @@ impl SchemaCatalog {
         self.table_by_name.remove(&name).unwrap();
     }
 
+    /// Replaces the table of the same id, whose name may have changed.
+    pub fn update_table(&mut self, prost: &ProstTable) {
+        self.drop_table(prost.id.into());
+        self.create_table(prost);
+    }
+
     pub fn create_source(&mut self, prost: ProstSource) {
         let name = prost.name.clone();
         let id = prost.id;
@@ -65,6 +76,27 @@ impl SchemaCatalog {
         self.source_by_name.remove(&name).unwrap();
     }
 
+    /// Replaces the source of the same id, whose name may have changed.
+    pub fn update_source(&mut self, prost: ProstSource) {
+        self.drop_source(prost.id);
+        self.create_source(prost);
+    }
+
+    pub fn create_function(&mut self, prost: &ProstFunction) {
+        let name = prost.name.clone();
+        let id = prost.id;
+
+        self.function_by_name
+            .try_insert(name.clone(), FunctionCatalog::from(prost))
+            .unwrap();
+        self.function_name_by_id.try_insert(id, name).unwrap();
+    }
+
+    pub fn drop_function(&mut self, id: FunctionId) {
+        let name = self.function_name_by_id.remove(&id).unwrap();
+        self.function_by_name.remove(&name).unwrap();
+    }
+
     pub fn iter_table(&self) -> impl Iterator<Item = &TableCatalog> {
         self.table_by_name
             .iter()
@@ -77,11 +109,21 @@ impl SchemaCatalog {
             .map(|(_, v)| v)
     }
 
-    /// Iterate all materialized views, excluding the indexs.
+    /// Iterate all materialized views, excluding the indexs and the internal tables.
     pub fn iter_mv(&self) -> impl Iterator<Item = &TableCatalog> {
         self.table_by_name
             .iter()
-            .filter(|(_, v)| v.associated_source_id.is_none() && v.is_index_on.is_none())
+            .filter(|(_, v)| {
+                v.associated_source_id.is_none() && v.is_index_on.is_none() && !v.is_internal
+            })
+            .map(|(_, v)| v)
+    }
+
+    /// Iterate all internal tables of the materialized views.
+    pub fn iter_internal_table(&self) -> impl Iterator<Item = &TableCatalog> {
+        self.table_by_name
+            .iter()
+            .filter(|(_, v)| v.is_internal)
             .map(|(_, v)| v)
     }
 
@@ -120,6 +162,10 @@ impl SchemaCatalog {
         self.source_by_name.get(source_name)
     }
 
+    pub fn get_function_by_name(&self, function_name: &str) -> Option<&FunctionCatalog> {
+        self.function_by_name.get(function_name)
+    }
+
     pub fn id(&self) -> SchemaId {
         self.id
     }
@@ -134,6 +180,8 @@ impl From<&ProstSchema> for SchemaCatalog {
             table_name_by_id: HashMap::new(),
             source_by_name: HashMap::new(),
             source_name_by_id: HashMap::new(),
+            function_by_name: HashMap::new(),
+            function_name_by_id: HashMap::new(),
         }
     }
 }
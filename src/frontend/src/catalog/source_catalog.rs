@@ -13,7 +13,7 @@
 // limitations under the License.
 use itertools::Itertools;
 use risingwave_pb::catalog::source::Info;
-use risingwave_pb::catalog::Source as ProstSource;
+use risingwave_pb::catalog::{Source as ProstSource, SourceDedup, StreamSourceInfo};
 use risingwave_pb::stream_plan::source_node::SourceType;
 
 use super::column_catalog::ColumnCatalog;
@@ -27,6 +27,12 @@ pub struct SourceCatalog {
     pub columns: Vec<ColumnCatalog>,
     pub pk_col_ids: Vec<ColumnId>,
     pub source_type: SourceType,
+    /// The name of the user owning the source.
+    pub owner: String,
+    /// How the rows are deduplicated on ingestion, if they are.
+    pub dedup: Option<SourceDedup>,
+    /// The definition of the connector source, to read it in batch. Unset for table sources.
+    pub stream_source_info: Option<StreamSourceInfo>,
 }
 
 impl SourceCatalog {
@@ -56,7 +62,7 @@ impl From<&ProstSource> for SourceCatalog {
     fn from(prost: &ProstSource) -> Self {
         let id = prost.id;
         let name = prost.name.clone();
-        let (source_type, prost_columns, pk_col_ids) = match &prost.info {
+        let (source_type, prost_columns, pk_col_ids, dedup) = match &prost.info {
             Some(Info::StreamSource(source)) => (
                 SourceType::Source,
                 source.columns.clone(),
@@ -65,14 +71,20 @@ impl From<&ProstSource> for SourceCatalog {
                     .iter()
                     .map(|id| ColumnId::new(*id))
                     .collect(),
+                source.dedup.clone(),
             ),
             Some(Info::TableSource(source)) => (
                 SourceType::Table,
                 source.columns.clone(),
                 vec![TABLE_SOURCE_PK_COLID],
+                None,
             ),
             None => unreachable!(),
         };
+        let stream_source_info = match &prost.info {
+            Some(Info::StreamSource(source)) => Some(source.clone()),
+            _ => None,
+        };
         let columns = prost_columns.into_iter().map(ColumnCatalog::from).collect();
         Self {
             id,
@@ -80,6 +92,9 @@ impl From<&ProstSource> for SourceCatalog {
             columns,
             pk_col_ids,
             source_type,
+            owner: prost.owner.clone(),
+            dedup,
+            stream_source_info,
         }
     }
 }
@@ -0,0 +1,200 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Virtual tables in the `pg_catalog` schema. They are not stored anywhere, but generated from the
+//! catalog cached in frontend when they are bound, so that tools relying on the PostgreSQL system
+//! catalogs can introspect the cluster.
+
+use itertools::Itertools;
+use risingwave_common::catalog::Field;
+use risingwave_common::error::Result;
+use risingwave_common::types::{DataType, Datum, ScalarImpl};
+
+use crate::catalog::root_catalog::Catalog;
+use crate::catalog::schema_catalog::SchemaCatalog;
+
+pub const PG_CATALOG_SCHEMA_NAME: &str = "pg_catalog";
+
+/// A system table with all its rows.
+pub struct SystemTable {
+    pub fields: Vec<Field>,
+    pub rows: Vec<Vec<Datum>>,
+}
+
+impl SystemTable {
+    /// Generates the system table `table_name` in `pg_catalog` of the database `db_name`. Returns
+    /// `None` if there's no such system table.
+    pub fn read(catalog: &Catalog, db_name: &str, table_name: &str) -> Result<Option<Self>> {
+        let table = match table_name {
+            "pg_database" => Self::new(
+                &[("oid", DataType::Int32), ("datname", DataType::Varchar)],
+                catalog
+                    .get_all_database_names()
+                    .into_iter()
+                    .map(|name| {
+                        let id = catalog.get_database_by_name(&name)?.id();
+                        Ok(vec![int32(id), varchar(name)])
+                    })
+                    .try_collect()?,
+            ),
+            "pg_namespace" => Self::new(
+                &[("oid", DataType::Int32), ("nspname", DataType::Varchar)],
+                Self::schemas(catalog, db_name)?
+                    .into_iter()
+                    .map(|(name, schema)| vec![int32(schema.id()), varchar(name)])
+                    .collect(),
+            ),
+            "pg_class" => Self::new(
+                &[
+                    ("oid", DataType::Int32),
+                    ("relname", DataType::Varchar),
+                    ("relnamespace", DataType::Int32),
+                    ("relkind", DataType::Varchar),
+                ],
+                Self::schemas(catalog, db_name)?
+                    .into_iter()
+                    .flat_map(|(_, schema)| {
+                        let tables = schema.iter_table().map(|t| (t, "r"));
+                        let mvs = schema.iter_mv().map(|t| (t, "m"));
+                        let indexes = schema.iter_index().map(|t| (t, "i"));
+                        tables.chain(mvs).chain(indexes).map(move |(table, kind)| {
+                            vec![
+                                int32(table.id().table_id),
+                                varchar(table.name()),
+                                int32(schema.id()),
+                                varchar(kind),
+                            ]
+                        })
+                    })
+                    .collect(),
+            ),
+            "pg_tables" => Self::new(
+                &[
+                    ("schemaname", DataType::Varchar),
+                    ("tablename", DataType::Varchar),
+                ],
+                Self::schemas(catalog, db_name)?
+                    .into_iter()
+                    .flat_map(|(name, schema)| {
+                        schema
+                            .iter_table()
+                            .map(move |table| vec![varchar(&name), varchar(table.name())])
+                    })
+                    .collect(),
+            ),
+            "pg_matviews" => Self::new(
+                &[
+                    ("schemaname", DataType::Varchar),
+                    ("matviewname", DataType::Varchar),
+                ],
+                Self::schemas(catalog, db_name)?
+                    .into_iter()
+                    .flat_map(|(name, schema)| {
+                        schema
+                            .iter_mv()
+                            .map(move |table| vec![varchar(&name), varchar(table.name())])
+                    })
+                    .collect(),
+            ),
+            _ => return Ok(None),
+        };
+        Ok(Some(table))
+    }
+
+    fn new(columns: &[(&str, DataType)], mut rows: Vec<Vec<Datum>>) -> Self {
+        // The catalog is stored in hash maps, so sort the rows to make the output deterministic.
+        rows.sort();
+        Self {
+            fields: columns
+                .iter()
+                .map(|(name, data_type)| Field::with_name(data_type.clone(), *name))
+                .collect(),
+            rows,
+        }
+    }
+
+    fn schemas<'a>(
+        catalog: &'a Catalog,
+        db_name: &str,
+    ) -> Result<Vec<(String, &'a SchemaCatalog)>> {
+        catalog
+            .get_all_schema_names(db_name)?
+            .into_iter()
+            .map(|name| {
+                let schema = catalog.get_schema_by_name(db_name, &name)?;
+                Ok((name, schema))
+            })
+            .try_collect()
+    }
+}
+
+fn int32(v: u32) -> Datum {
+    Some(ScalarImpl::Int32(v as i32))
+}
+
+fn varchar(v: impl Into<String>) -> Datum {
+    Some(ScalarImpl::Utf8(v.into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_common::catalog::{DEFAULT_DATABASE_NAME, DEFAULT_SCHEMA_NAME};
+
+    use super::*;
+    use crate::test_utils::LocalFrontend;
+
+    #[tokio::test]
+    async fn test_pg_catalog() {
+        let frontend = LocalFrontend::new(Default::default()).await;
+        frontend.run_sql("CREATE TABLE t (v1 INT)").await.unwrap();
+        frontend
+            .run_sql("CREATE MATERIALIZED VIEW mv AS SELECT v1 FROM t")
+            .await
+            .unwrap();
+
+        {
+            let session = frontend.session_ref();
+            let catalog = session.env().catalog_reader().read_guard();
+            let read = |table_name: &str| {
+                SystemTable::read(&catalog, DEFAULT_DATABASE_NAME, table_name)
+                    .unwrap()
+                    .unwrap()
+                    .rows
+            };
+            assert_eq!(
+                read("pg_tables"),
+                vec![vec![varchar(DEFAULT_SCHEMA_NAME), varchar("t")]]
+            );
+            assert_eq!(
+                read("pg_matviews"),
+                vec![vec![varchar(DEFAULT_SCHEMA_NAME), varchar("mv")]]
+            );
+            assert_eq!(read("pg_class").len(), 2);
+            assert!(
+                SystemTable::read(&catalog, DEFAULT_DATABASE_NAME, "pg_unknown")
+                    .unwrap()
+                    .is_none()
+            );
+        }
+
+        let rows = frontend
+            .query_formatted_result("EXPLAIN SELECT relname FROM pg_catalog.pg_class")
+            .await;
+        assert!(
+            rows.iter().any(|row| row.contains("BatchValues")),
+            "{:?}",
+            rows
+        );
+    }
+}
@@ -47,6 +47,16 @@ pub struct TableCatalog {
 
     /// If set to Some(TableId), then this table is an index on another table.
     pub is_index_on: Option<TableId>,
+
+    /// The name of the user owning the table.
+    pub owner: String,
+
+    /// Whether the table is an internal state table of an executor of a materialized view, which
+    /// can only be scanned in batch queries.
+    pub is_internal: bool,
+
+    /// Retention in seconds of the rows of the table, or 0 to keep the rows forever.
+    pub retention_seconds: u32,
 }
 
 impl TableCatalog {
@@ -79,6 +89,7 @@ impl TableCatalog {
             pks: self.pks.clone(),
             columns: self.columns.iter().map(|c| c.column_desc.clone()).collect(),
             distribution_keys: self.distribution_keys.clone(),
+            retention_seconds: self.retention_seconds,
         }
     }
 
@@ -123,6 +134,9 @@ impl TableCatalog {
                 .iter()
                 .map(|k| *k as i32)
                 .collect_vec(),
+            owner: self.owner.clone(),
+            is_internal: self.is_internal,
+            retention_seconds: self.retention_seconds,
         }
     }
 }
@@ -180,6 +194,9 @@ impl From<ProstTable> for TableCatalog {
                 .map(|k| *k as usize)
                 .collect_vec(),
             pks: tb.pk.iter().map(|x| *x as _).collect(),
+            owner: tb.owner,
+            is_internal: tb.is_internal,
+            retention_seconds: tb.retention_seconds,
         }
     }
 }
@@ -219,6 +236,7 @@ mod tests {
                 ProstColumnCatalog {
                     column_desc: Some((&row_id_column_desc()).into()),
                     is_hidden: true,
+                    generated_expr: None,
                 },
                 ProstColumnCatalog {
                     column_desc: Some(ProstColumnDesc::new_struct(
@@ -239,6 +257,7 @@ mod tests {
                         ],
                     )),
                     is_hidden: false,
+                    generated_expr: None,
                 },
             ],
             order_column_ids: vec![0],
@@ -248,6 +267,9 @@ mod tests {
             distribution_keys: vec![],
             optional_associated_source_id: OptionalAssociatedSourceId::AssociatedSourceId(233)
                 .into(),
+            owner: "root".to_string(),
+            is_internal: false,
+            retention_seconds: 0,
         }
         .into();
 
@@ -294,6 +316,9 @@ mod tests {
                     order: OrderType::Ascending
                 }],
                 distribution_keys: vec![],
+                owner: "root".to_string(),
+                is_internal: false,
+                retention_seconds: 0,
             }
         );
     }
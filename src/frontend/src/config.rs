@@ -14,12 +14,361 @@
 
 //! Contains configurations that could be accessed via "set" command.
 
-use risingwave_common::error::ErrorCode::InvalidConfigValue;
-use risingwave_common::error::RwError;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::time::Duration;
+
+use chrono_tz::{Tz, TZ_VARIANTS};
+use risingwave_common::error::ErrorCode::{self, InvalidConfigValue};
+use risingwave_common::error::{Result, RwError};
 
 use crate::config::QueryMode::{Distributed, Local};
 
-pub static QUERY_MODE: &str = "query_mode";
+/// If `RW_IMPLICIT_FLUSH` is on, then every INSERT/UPDATE/DELETE statement will block until the
+/// entire dataflow is refreshed.
+pub const IMPLICIT_FLUSH: &str = "RW_IMPLICIT_FLUSH";
+/// Whether to execute batch queries locally on the frontend or distributedly.
+pub const QUERY_MODE: &str = "QUERY_MODE";
+/// The historical epoch that batch queries read from, which must not have been GCed by meta. `0`
+/// means the latest committed epoch.
+pub const QUERY_EPOCH: &str = "QUERY_EPOCH";
+/// The number of actors of each fragment of newly created streaming jobs. `0` means using all
+/// the parallel units in the cluster.
+pub const STREAMING_PARALLELISM: &str = "STREAMING_PARALLELISM";
+/// Whether to plan joins as lookup joins when the inner side is a table scan on the join keys.
+pub const BATCH_ENABLE_LOOKUP_JOIN: &str = "BATCH_ENABLE_LOOKUP_JOIN";
+/// Whether to plan streaming joins as delta joins.
+pub const FORCE_DELTA_JOIN: &str = "RW_FORCE_DELTA_JOIN";
+/// How long in ms the cached results of point lookups on materialized views may be stale. `0`
+/// disables the cache.
+pub const QUERY_RESULT_CACHE_STALENESS_MS: &str = "QUERY_RESULT_CACHE_STALENESS_MS";
+/// Batch queries running longer than this in ms are logged along with their plans. `0` disables
+/// the log.
+pub const SLOW_QUERY_THRESHOLD_MS: &str = "SLOW_QUERY_THRESHOLD_MS";
+/// Statements running longer than this are cancelled. `0` disables the timeout.
+pub const STATEMENT_TIMEOUT: &str = "STATEMENT_TIMEOUT";
+/// Sessions idle in a transaction for longer than this are terminated. `0` disables the timeout.
+pub const IDLE_IN_TRANSACTION_SESSION_TIMEOUT: &str = "IDLE_IN_TRANSACTION_SESSION_TIMEOUT";
+/// Sessions idle outside of a transaction for longer than this are terminated. `0` disables the
+/// timeout.
+pub const IDLE_SESSION_TIMEOUT: &str = "IDLE_SESSION_TIMEOUT";
+/// Cursors not fetched from for longer than this are closed, which releases their snapshots and
+/// queries. `0` disables the timeout.
+pub const CURSOR_IDLE_TIMEOUT: &str = "CURSOR_IDLE_TIMEOUT";
+/// The time zone of the session.
+pub const TIMEZONE: &str = "TIMEZONE";
+/// Set by clients like the JDBC driver when connecting. They are only stored for compatibility.
+pub const APPLICATION_NAME: &str = "APPLICATION_NAME";
+pub const EXTRA_FLOAT_DIGITS: &str = "EXTRA_FLOAT_DIGITS";
+
+/// The definition of a session configuration.
+struct ConfigDef {
+    name: &'static str,
+    default: &'static str,
+    description: &'static str,
+    /// Checks whether the value can be assigned to the configuration.
+    check: fn(&'static str, &str) -> Result<()>,
+}
+
+/// All the configurations that can be set in a session. To add a new one, append its definition
+/// here and add a typed getter to [`ConfigMap`].
+static CONFIG_DEFS: &[ConfigDef] = &[
+    ConfigDef {
+        name: IMPLICIT_FLUSH,
+        default: "false",
+        description: "If set to true, every INSERT/UPDATE/DELETE statement blocks until the \
+                      dataflow is refreshed.",
+        check: check_bool,
+    },
+    ConfigDef {
+        name: QUERY_MODE,
+        default: "distributed",
+        description: "Executes batch queries in `local` or `distributed` mode.",
+        check: |name, value| QueryMode::parse(name, value).map(|_| ()),
+    },
+    ConfigDef {
+        name: QUERY_EPOCH,
+        default: "0",
+        description: "The historical epoch that batch queries read from. 0 means the latest \
+                      committed epoch.",
+        check: |name, value| parse_number::<u64>(name, value).map(|_| ()),
+    },
+    ConfigDef {
+        name: STREAMING_PARALLELISM,
+        default: "0",
+        description: "The parallelism of newly created streaming jobs. 0 means using all the \
+                      parallel units.",
+        check: |name, value| parse_number::<u32>(name, value).map(|_| ()),
+    },
+    ConfigDef {
+        name: BATCH_ENABLE_LOOKUP_JOIN,
+        default: "true",
+        description: "Plans batch joins as lookup joins when possible.",
+        check: check_bool,
+    },
+    ConfigDef {
+        name: FORCE_DELTA_JOIN,
+        default: "false",
+        description: "Plans streaming joins as delta joins.",
+        check: check_bool,
+    },
+    ConfigDef {
+        name: QUERY_RESULT_CACHE_STALENESS_MS,
+        default: "0",
+        description: "How long in milliseconds the cached results of point lookups may be stale. \
+                      0 disables the cache.",
+        check: |name, value| parse_number::<u64>(name, value).map(|_| ()),
+    },
+    ConfigDef {
+        name: SLOW_QUERY_THRESHOLD_MS,
+        default: "0",
+        description: "Batch queries running longer than this in milliseconds are logged along \
+                      with their plans. 0 disables the log.",
+        check: |name, value| parse_number::<u64>(name, value).map(|_| ()),
+    },
+    ConfigDef {
+        name: STATEMENT_TIMEOUT,
+        default: "0",
+        description: "Cancels statements running longer than this, in milliseconds unless a unit \
+                      is given. 0 disables the timeout.",
+        check: |name, value| parse_timeout(name, value).map(|_| ()),
+    },
+    ConfigDef {
+        name: IDLE_IN_TRANSACTION_SESSION_TIMEOUT,
+        default: "0",
+        description: "Terminates sessions idle in a transaction for longer than this, in \
+                      milliseconds unless a unit is given. 0 disables the timeout.",
+        check: |name, value| parse_timeout(name, value).map(|_| ()),
+    },
+    ConfigDef {
+        name: IDLE_SESSION_TIMEOUT,
+        default: "0",
+        description: "Terminates sessions idle outside of a transaction for longer than this, in \
+                      milliseconds unless a unit is given. 0 disables the timeout.",
+        check: |name, value| parse_timeout(name, value).map(|_| ()),
+    },
+    ConfigDef {
+        name: CURSOR_IDLE_TIMEOUT,
+        default: "10min",
+        description: "Closes cursors not fetched from for longer than this, in milliseconds \
+                      unless a unit is given. 0 disables the timeout.",
+        check: |name, value| parse_timeout(name, value).map(|_| ()),
+    },
+    ConfigDef {
+        name: TIMEZONE,
+        default: "UTC",
+        description: "The time zone of the session, e.g. `UTC` or `Asia/Shanghai`.",
+        check: check_timezone,
+    },
+    ConfigDef {
+        name: APPLICATION_NAME,
+        default: "",
+        description: "The name of the client application.",
+        check: |_, _| Ok(()),
+    },
+    ConfigDef {
+        name: EXTRA_FLOAT_DIGITS,
+        default: "1",
+        description: "The number of extra digits displayed for floating-point values.",
+        check: |name, value| parse_number::<i32>(name, value).map(|_| ()),
+    },
+];
+
+fn find_config_def(name: &str) -> Result<&'static ConfigDef> {
+    CONFIG_DEFS
+        .iter()
+        .find(|def| def.name.eq_ignore_ascii_case(name))
+        .ok_or_else(|| {
+            ErrorCode::InvalidInputSyntax(format!(
+                "unrecognized configuration parameter \"{}\"",
+                name
+            ))
+            .into()
+        })
+}
+
+/// The values of the configurations of a session.
+#[derive(Debug, Clone)]
+pub struct ConfigMap {
+    /// Keyed by the name of the configuration. All values here have been checked.
+    values: HashMap<&'static str, String>,
+}
+
+impl Default for ConfigMap {
+    fn default() -> Self {
+        Self {
+            values: CONFIG_DEFS
+                .iter()
+                .map(|def| (def.name, def.default.to_string()))
+                .collect(),
+        }
+    }
+}
+
+impl ConfigMap {
+    /// Sets the configuration `name`, which is case-insensitive, to `value`. Unknown
+    /// configurations are ignored with a warning, since clients like `psql` and JDBC drivers set
+    /// some PostgreSQL parameters that are not supported yet on connection.
+    pub fn set(&mut self, name: &str, value: &str) -> Result<()> {
+        let Ok(def) = find_config_def(name) else {
+            tracing::warn!("ignored unrecognized configuration parameter \"{}\"", name);
+            return Ok(());
+        };
+        (def.check)(def.name, value)?;
+        self.values.insert(def.name, value.to_string());
+        Ok(())
+    }
+
+    /// Gets the value of the configuration `name`, which is case-insensitive.
+    pub fn get(&self, name: &str) -> Result<&str> {
+        let def = find_config_def(name)?;
+        Ok(self.value(def.name))
+    }
+
+    /// Lists all the configurations as `(name, value, description)`, in the order of definition.
+    pub fn show_all(&self) -> Vec<(&'static str, &str, &'static str)> {
+        CONFIG_DEFS
+            .iter()
+            .map(|def| (def.name, self.value(def.name), def.description))
+            .collect()
+    }
+
+    pub fn implicit_flush(&self) -> bool {
+        parse_bool(IMPLICIT_FLUSH, self.value(IMPLICIT_FLUSH)).unwrap()
+    }
+
+    pub fn query_mode(&self) -> QueryMode {
+        QueryMode::parse(QUERY_MODE, self.value(QUERY_MODE)).unwrap()
+    }
+
+    /// Returns `None` if batch queries should read the latest committed epoch.
+    pub fn query_epoch(&self) -> Option<u64> {
+        match parse_number(QUERY_EPOCH, self.value(QUERY_EPOCH)).unwrap() {
+            0 => None,
+            epoch => Some(epoch),
+        }
+    }
+
+    /// Returns `0` if streaming jobs should use all the parallel units.
+    pub fn streaming_parallelism(&self) -> u32 {
+        parse_number(STREAMING_PARALLELISM, self.value(STREAMING_PARALLELISM)).unwrap()
+    }
+
+    pub fn batch_enable_lookup_join(&self) -> bool {
+        parse_bool(
+            BATCH_ENABLE_LOOKUP_JOIN,
+            self.value(BATCH_ENABLE_LOOKUP_JOIN),
+        )
+        .unwrap()
+    }
+
+    pub fn force_delta_join(&self) -> bool {
+        parse_bool(FORCE_DELTA_JOIN, self.value(FORCE_DELTA_JOIN)).unwrap()
+    }
+
+    /// Returns `0` if the results of point lookups should not be cached.
+    pub fn query_result_cache_staleness_ms(&self) -> u64 {
+        parse_number(
+            QUERY_RESULT_CACHE_STALENESS_MS,
+            self.value(QUERY_RESULT_CACHE_STALENESS_MS),
+        )
+        .unwrap()
+    }
+
+    /// Returns `0` if slow queries should not be logged.
+    pub fn slow_query_threshold_ms(&self) -> u64 {
+        parse_number(SLOW_QUERY_THRESHOLD_MS, self.value(SLOW_QUERY_THRESHOLD_MS)).unwrap()
+    }
+
+    pub fn statement_timeout(&self) -> Option<Duration> {
+        parse_timeout(STATEMENT_TIMEOUT, self.value(STATEMENT_TIMEOUT)).unwrap()
+    }
+
+    pub fn idle_in_transaction_session_timeout(&self) -> Option<Duration> {
+        parse_timeout(
+            IDLE_IN_TRANSACTION_SESSION_TIMEOUT,
+            self.value(IDLE_IN_TRANSACTION_SESSION_TIMEOUT),
+        )
+        .unwrap()
+    }
+
+    pub fn idle_session_timeout(&self) -> Option<Duration> {
+        parse_timeout(IDLE_SESSION_TIMEOUT, self.value(IDLE_SESSION_TIMEOUT)).unwrap()
+    }
+
+    pub fn cursor_idle_timeout(&self) -> Option<Duration> {
+        parse_timeout(CURSOR_IDLE_TIMEOUT, self.value(CURSOR_IDLE_TIMEOUT)).unwrap()
+    }
+
+    pub fn timezone(&self) -> &str {
+        self.value(TIMEZONE)
+    }
+
+    fn value(&self, name: &'static str) -> &str {
+        &self.values[name]
+    }
+}
+
+fn invalid_value(name: &str, value: &str) -> RwError {
+    InvalidConfigValue {
+        config_entry: name.to_string(),
+        config_value: value.to_string(),
+    }
+    .into()
+}
+
+/// Parses a boolean in the same way as PostgreSQL.
+fn parse_bool(name: &str, value: &str) -> Result<bool> {
+    match value.to_ascii_lowercase().as_str() {
+        "true" | "on" | "yes" | "1" => Ok(true),
+        "false" | "off" | "no" | "0" => Ok(false),
+        _ => Err(invalid_value(name, value)),
+    }
+}
+
+fn check_bool(name: &'static str, value: &str) -> Result<()> {
+    parse_bool(name, value).map(|_| ())
+}
+
+fn parse_number<T: std::str::FromStr>(name: &str, value: &str) -> Result<T> {
+    value.parse().map_err(|_| invalid_value(name, value))
+}
+
+/// Checks that `value` names a time zone in the tz database, case-insensitively like PostgreSQL.
+fn check_timezone(name: &'static str, value: &str) -> Result<()> {
+    if Tz::from_str(value).is_ok()
+        || TZ_VARIANTS
+            .iter()
+            .any(|tz| tz.name().eq_ignore_ascii_case(value))
+    {
+        Ok(())
+    } else {
+        Err(invalid_value(name, value))
+    }
+}
+
+/// Parses a timeout like PostgreSQL, which is in milliseconds unless one of the units `ms`, `s`,
+/// `min`, `h` and `d` is given. Returns `None` if the timeout is `0`, i.e. disabled.
+fn parse_timeout(name: &str, value: &str) -> Result<Option<Duration>> {
+    let value_lower = value.trim().to_ascii_lowercase();
+    let digits_end = value_lower
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value_lower.len());
+    let (number, unit) = value_lower.split_at(digits_end);
+    let number: u64 = number.parse().map_err(|_| invalid_value(name, value))?;
+    let unit_ms = match unit.trim() {
+        "" | "ms" => 1,
+        "s" => 1000,
+        "min" => 60 * 1000,
+        "h" => 60 * 60 * 1000,
+        "d" => 24 * 60 * 60 * 1000,
+        _ => return Err(invalid_value(name, value)),
+    };
+    let timeout_ms = number
+        .checked_mul(unit_ms)
+        .ok_or_else(|| invalid_value(name, value))?;
+    Ok((timeout_ms > 0).then(|| Duration::from_millis(timeout_ms)))
+}
 
 #[derive(Debug, Clone)]
 pub enum QueryMode {
@@ -33,29 +382,32 @@ impl Default for QueryMode {
     }
 }
 
-/// Parse query mode from string.
-impl<'a> TryFrom<&'a str> for QueryMode {
-    type Error = RwError;
-
-    fn try_from(s: &'a str) -> Result<Self, RwError> {
+impl QueryMode {
+    fn parse(name: &str, s: &str) -> Result<Self> {
         if s.eq_ignore_ascii_case("local") {
             Ok(Local)
         } else if s.eq_ignore_ascii_case("distributed") {
             Ok(Distributed)
         } else {
-            Err(InvalidConfigValue {
-                config_entry: QUERY_MODE.to_string(),
-                config_value: s.to_string(),
-            })?
+            Err(invalid_value(name, s))
         }
     }
 }
 
+/// Parse query mode from string.
+impl<'a> TryFrom<&'a str> for QueryMode {
+    type Error = RwError;
+
+    fn try_from(s: &'a str) -> Result<Self> {
+        Self::parse(QUERY_MODE, s)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::assert_matches::assert_matches;
 
-    use crate::config::QueryMode;
+    use super::*;
 
     #[test]
     fn parse_query_mode() {
@@ -65,4 +417,70 @@ mod tests {
         assert_matches!("diStributed".try_into().unwrap(), QueryMode::Distributed);
         assert!(QueryMode::try_from("ab").is_err());
     }
+
+    #[test]
+    fn test_config_map() {
+        let mut config = ConfigMap::default();
+        assert!(!config.implicit_flush());
+        assert_eq!(config.query_epoch(), None);
+        assert_eq!(config.streaming_parallelism(), 0);
+        assert!(config.batch_enable_lookup_join());
+        assert_eq!(config.query_result_cache_staleness_ms(), 0);
+        assert_eq!(config.slow_query_threshold_ms(), 0);
+        assert_eq!(config.statement_timeout(), None);
+        assert_eq!(config.cursor_idle_timeout(), Some(Duration::from_secs(600)));
+
+        config.set("rw_implicit_flush", "on").unwrap();
+        config.set("Query_Epoch", "42").unwrap();
+        config.set("streaming_parallelism", "4").unwrap();
+        config.set("batch_enable_lookup_join", "false").unwrap();
+        config.set("query_mode", "local").unwrap();
+        config
+            .set("query_result_cache_staleness_ms", "1000")
+            .unwrap();
+        config.set("slow_query_threshold_ms", "500").unwrap();
+        config.set("statement_timeout", "1500").unwrap();
+        config.set("idle_session_timeout", "2 min").unwrap();
+        config
+            .set("idle_in_transaction_session_timeout", "3s")
+            .unwrap();
+        config.set("cursor_idle_timeout", "0").unwrap();
+        config.set("timezone", "asia/shanghai").unwrap();
+        assert!(config.implicit_flush());
+        assert_eq!(config.query_epoch(), Some(42));
+        assert_eq!(config.streaming_parallelism(), 4);
+        assert!(!config.batch_enable_lookup_join());
+        assert_matches!(config.query_mode(), QueryMode::Local);
+        assert_eq!(config.query_result_cache_staleness_ms(), 1000);
+        assert_eq!(config.slow_query_threshold_ms(), 500);
+        assert_eq!(
+            config.statement_timeout(),
+            Some(Duration::from_millis(1500))
+        );
+        assert_eq!(
+            config.idle_session_timeout(),
+            Some(Duration::from_secs(120))
+        );
+        assert_eq!(
+            config.idle_in_transaction_session_timeout(),
+            Some(Duration::from_secs(3))
+        );
+        assert_eq!(config.cursor_idle_timeout(), None);
+        assert_eq!(config.timezone(), "asia/shanghai");
+        assert_eq!(config.get("QUERY_EPOCH").unwrap(), "42");
+
+        // Invalid values are rejected and unknown configurations are ignored, and the values are
+        // unchanged.
+        assert!(config.set("query_epoch", "-1").is_err());
+        assert!(config.set("rw_implicit_flush", "maybe").is_err());
+        assert!(config.set("no_such_config", "1").is_ok());
+        assert!(config.set("statement_timeout", "1 week").is_err());
+        assert!(config.set("timezone", "Mars/Olympus_Mons").is_err());
+        assert!(config.set("timezone", "").is_err());
+        assert!(config.get("no_such_config").is_err());
+        assert_eq!(config.query_epoch(), Some(42));
+        assert!(config.implicit_flush());
+
+        assert_eq!(config.show_all().len(), CONFIG_DEFS.len());
+    }
 }
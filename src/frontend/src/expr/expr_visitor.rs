@@ -12,7 +12,10 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use super::{AggCall, CorrelatedInputRef, ExprImpl, FunctionCall, InputRef, Literal, Subquery};
+use super::{
+    AggCall, CorrelatedInputRef, ExprImpl, FunctionCall, InputRef, Literal, Parameter, Subquery,
+    UserDefinedFunction,
+};
 
 /// Traverse an expression tree.
 ///
@@ -31,6 +34,8 @@ pub trait ExprVisitor {
             ExprImpl::AggCall(inner) => self.visit_agg_call(inner),
             ExprImpl::Subquery(inner) => self.visit_subquery(inner),
             ExprImpl::CorrelatedInputRef(inner) => self.visit_correlated_input_ref(inner),
+            ExprImpl::UserDefinedFunction(inner) => self.visit_user_defined_function(inner),
+            ExprImpl::Parameter(inner) => self.visit_parameter(inner),
         }
     }
     fn visit_function_call(&mut self, func_call: &FunctionCall) {
@@ -45,8 +50,12 @@ pub trait ExprVisitor {
             .iter()
             .for_each(|expr| self.visit_expr(expr))
     }
+    fn visit_user_defined_function(&mut self, udf: &UserDefinedFunction) {
+        udf.args().iter().for_each(|expr| self.visit_expr(expr))
+    }
     fn visit_literal(&mut self, _: &Literal) {}
     fn visit_input_ref(&mut self, _: &InputRef) {}
     fn visit_subquery(&mut self, _: &Subquery) {}
     fn visit_correlated_input_ref(&mut self, _: &CorrelatedInputRef) {}
+    fn visit_parameter(&mut self, _: &Parameter) {}
 }
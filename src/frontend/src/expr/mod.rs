@@ -25,7 +25,9 @@ mod correlated_input_ref;
 mod function_call;
 mod input_ref;
 mod literal;
+mod parameter;
 mod subquery;
+mod user_defined_function;
 
 mod expr_rewriter;
 mod expr_visitor;
@@ -37,7 +39,9 @@ pub use correlated_input_ref::CorrelatedInputRef;
 pub use function_call::FunctionCall;
 pub use input_ref::{as_alias_display, input_ref_to_column_indices, InputRef, InputRefDisplay};
 pub use literal::Literal;
+pub use parameter::Parameter;
 pub use subquery::{Subquery, SubqueryKind};
+pub use user_defined_function::UserDefinedFunction;
 
 pub type ExprType = risingwave_pb::expr::expr_node::Type;
 
@@ -64,6 +68,8 @@ pub enum ExprImpl {
     FunctionCall(Box<FunctionCall>),
     AggCall(Box<AggCall>),
     Subquery(Box<Subquery>),
+    UserDefinedFunction(Box<UserDefinedFunction>),
+    Parameter(Box<Parameter>),
 }
 
 impl ExprImpl {
@@ -192,7 +198,8 @@ impl ExprImpl {
 
     /// Checks whether this is a constant expr that can be evaluated over a dummy chunk.
     /// Equivalent to `!has_input_ref && !has_agg_call && !has_subquery &&
-    /// !has_correlated_input_ref` but checks them in one pass.
+    /// !has_correlated_input_ref` but checks them in one pass. `NOW()` is not constant, since it
+    /// changes with the epoch.
     pub fn is_const(&self) -> bool {
         struct Has {
             has: bool,
@@ -201,7 +208,9 @@ impl ExprImpl {
             fn visit_expr(&mut self, expr: &ExprImpl) {
                 match expr {
                     ExprImpl::Literal(_inner) => {}
-                    ExprImpl::FunctionCall(inner) => self.visit_function_call(inner),
+                    ExprImpl::FunctionCall(inner) if inner.get_expr_type() != ExprType::Now => {
+                        self.visit_function_call(inner)
+                    }
                     _ => self.has = true,
                 }
             }
@@ -221,6 +230,8 @@ impl Expr for ExprImpl {
             ExprImpl::AggCall(expr) => expr.return_type(),
             ExprImpl::Subquery(expr) => expr.return_type(),
             ExprImpl::CorrelatedInputRef(expr) => expr.return_type(),
+            ExprImpl::UserDefinedFunction(expr) => expr.return_type(),
+            ExprImpl::Parameter(expr) => expr.return_type(),
         }
     }
 
@@ -232,6 +243,8 @@ impl Expr for ExprImpl {
             ExprImpl::AggCall(e) => e.to_expr_proto(),
             ExprImpl::Subquery(e) => e.to_expr_proto(),
             ExprImpl::CorrelatedInputRef(e) => e.to_expr_proto(),
+            ExprImpl::UserDefinedFunction(e) => e.to_expr_proto(),
+            ExprImpl::Parameter(e) => e.to_expr_proto(),
         }
     }
 }
@@ -272,6 +285,18 @@ impl From<CorrelatedInputRef> for ExprImpl {
     }
 }
 
+impl From<UserDefinedFunction> for ExprImpl {
+    fn from(udf: UserDefinedFunction) -> Self {
+        ExprImpl::UserDefinedFunction(Box::new(udf))
+    }
+}
+
+impl From<Parameter> for ExprImpl {
+    fn from(parameter: Parameter) -> Self {
+        ExprImpl::Parameter(Box::new(parameter))
+    }
+}
+
 impl From<Condition> for ExprImpl {
     fn from(c: Condition) -> Self {
         merge_expr_by_binary(
@@ -297,6 +322,10 @@ impl std::fmt::Debug for ExprImpl {
                 Self::CorrelatedInputRef(arg0) => {
                     f.debug_tuple("CorrelatedInputRef").field(arg0).finish()
                 }
+                Self::UserDefinedFunction(arg0) => {
+                    f.debug_tuple("UserDefinedFunction").field(arg0).finish()
+                }
+                Self::Parameter(arg0) => f.debug_tuple("Parameter").field(arg0).finish(),
             };
         }
         match self {
@@ -306,6 +335,8 @@ impl std::fmt::Debug for ExprImpl {
             Self::AggCall(x) => write!(f, "{:?}", x),
             Self::Subquery(x) => write!(f, "{:?}", x),
             Self::CorrelatedInputRef(x) => write!(f, "{:?}", x),
+            Self::UserDefinedFunction(x) => write!(f, "{:?}", x),
+            Self::Parameter(x) => write!(f, "{:?}", x),
         }
     }
 }
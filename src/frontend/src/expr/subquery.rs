@@ -19,7 +19,7 @@ use risingwave_common::types::DataType;
 use super::{Expr, ExprImpl, ExprType};
 use crate::binder::BoundQuery;
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum SubqueryKind {
     /// Returns a scalar value (single column single row).
     Scalar,
@@ -33,7 +33,9 @@ pub enum SubqueryKind {
     All(ExprImpl, ExprType),
 }
 
-/// Subquery expression.
+/// Subquery expression. It can be cloned before being planned, e.g. when a prepared statement is
+/// executed.
+#[derive(Clone)]
 pub struct Subquery {
     pub query: BoundQuery,
     pub kind: SubqueryKind,
@@ -49,12 +51,6 @@ impl Subquery {
     }
 }
 
-impl Clone for Subquery {
-    fn clone(&self) -> Self {
-        unreachable!("Subquery {:?} has not been unnested", self)
-    }
-}
-
 impl PartialEq for Subquery {
     fn eq(&self, _other: &Self) -> bool {
         unreachable!("Subquery {:?} has not been unnested", self)
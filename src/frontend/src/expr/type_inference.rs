@@ -255,6 +255,10 @@ fn build_type_derive_map() -> HashMap<FuncSign, DataTypeName> {
         FuncSign::new(E::RoundDigit, vec![T::Decimal, T::Int32]),
         T::Decimal,
     );
+    map.insert(
+        FuncSign::new(E::DecimalRescale, vec![T::Decimal, T::Int32, T::Int32]),
+        T::Decimal,
+    );
 
     // build bitwise operator
     // bitwise operator
@@ -356,6 +360,8 @@ fn build_type_derive_map() -> HashMap<FuncSign, DataTypeName> {
         FuncSign::new(E::SplitPart, vec![T::Varchar, T::Varchar, T::Int32]),
         T::Varchar,
     );
+    map.insert(FuncSign::new(E::Now, vec![]), T::Timestamp);
+
     // TODO: Support more `to_char` types.
     map.insert(
         FuncSign::new(E::ToChar, vec![T::Timestamp, T::Varchar]),
@@ -0,0 +1,90 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use risingwave_common::types::DataType;
+
+use super::{Expr, ExprImpl};
+use crate::catalog::function_catalog::FunctionCatalog;
+
+/// A call to a user-defined function. The whole definition of the function is carried in the
+/// plan, so that compute nodes can run it without looking up the catalog.
+#[derive(Clone, Eq, PartialEq, Hash)]
+pub struct UserDefinedFunction {
+    args: Vec<ExprImpl>,
+    catalog: FunctionCatalog,
+}
+
+impl std::fmt::Debug for UserDefinedFunction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if f.alternate() {
+            f.debug_struct("UserDefinedFunction")
+                .field("name", &self.catalog.name)
+                .field("return_type", &self.catalog.return_type)
+                .field("args", &self.args)
+                .finish()
+        } else {
+            let mut builder = f.debug_tuple(&self.catalog.name);
+            self.args.iter().for_each(|arg| {
+                builder.field(arg);
+            });
+            builder.finish()
+        }
+    }
+}
+
+impl UserDefinedFunction {
+    /// The `args` must have been casted to the argument types of the function.
+    pub fn new(catalog: FunctionCatalog, args: Vec<ExprImpl>) -> Self {
+        Self { args, catalog }
+    }
+
+    pub fn args(&self) -> &[ExprImpl] {
+        &self.args
+    }
+
+    pub fn decompose(self) -> (FunctionCatalog, Vec<ExprImpl>) {
+        (self.catalog, self.args)
+    }
+}
+
+impl Expr for UserDefinedFunction {
+    fn return_type(&self) -> DataType {
+        self.catalog.return_type.clone()
+    }
+
+    fn to_expr_proto(&self) -> risingwave_pb::expr::ExprNode {
+        use risingwave_pb::expr::expr_node::*;
+        use risingwave_pb::expr::*;
+        ExprNode {
+            expr_type: Type::Udf.into(),
+            return_type: Some(self.return_type().to_protobuf()),
+            rex_node: Some(RexNode::Udf(UserDefinedFunction {
+                children: self.args.iter().map(Expr::to_expr_proto).collect(),
+                name: self.catalog.name.clone(),
+                arg_types: self
+                    .catalog
+                    .arg_types
+                    .iter()
+                    .map(DataType::to_protobuf)
+                    .collect(),
+                language: self.catalog.language.clone(),
+                body: self.catalog.body.clone(),
+                link_symbol: self.catalog.link_symbol.clone(),
+                link: self.catalog.link.clone(),
+                timeout_ms: self.catalog.timeout_ms,
+                max_retries: self.catalog.max_retries,
+            })),
+        }
+    }
+}
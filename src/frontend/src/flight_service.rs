@@ -0,0 +1,315 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Serves the results of batch queries as Arrow record batches over Arrow Flight, so that
+//! analytics clients can pull the data in columnar format instead of row by row over pgwire.
+//!
+//! `DoGet` takes the text of a `SELECT` query as the ticket, and responds the schema of the
+//! results followed by a record batch for each chunk of the results. A snapshot of a materialized
+//! view is read with `SELECT * FROM <mv>`. The session is specified by the `database`, `user` and
+//! `password` entries of the request metadata. The database defaults to `dev` and the user to the
+//! default superuser.
+
+use std::sync::Arc;
+
+use arrow::ipc::writer::IpcWriteOptions;
+use arrow_flight::flight_service_server::{FlightService, FlightServiceServer};
+use arrow_flight::utils::flight_data_from_arrow_batch;
+use arrow_flight::{
+    Action, ActionType, Criteria, Empty, FlightData, FlightDescriptor, FlightInfo,
+    HandshakeRequest, HandshakeResponse, PutResult, SchemaAsIpc, SchemaResult, Ticket,
+};
+use futures::stream::BoxStream;
+use futures::{stream, StreamExt};
+use pgwire::pg_server::{Session, SessionManager, UserAuthenticator};
+use risingwave_common::catalog::{DEFAULT_DATABASE_NAME, DEFAULT_SUPPER_USER};
+use risingwave_common::error::tonic_err;
+use risingwave_expr::arrow::{arrow_schema, to_record_batch};
+use risingwave_sqlparser::ast::Statement;
+use risingwave_sqlparser::parser::Parser;
+use tonic::metadata::MetadataMap;
+use tonic::{Request, Response, Status, Streaming};
+
+use crate::binder::ParamValues;
+use crate::handler::query::execute_query;
+use crate::session::{OptimizerContext, SessionImpl};
+use crate::user::encrypt_md5;
+
+/// Serves Arrow Flight at `addr` until the process exits.
+pub async fn flight_serve<SM>(addr: String, session_mgr: Arc<SM>)
+where
+    SM: SessionManager<Session = SessionImpl>,
+{
+    let addr = addr.parse().unwrap();
+    tracing::info!("Arrow Flight server listening on {}", addr);
+    tonic::transport::Server::builder()
+        .add_service(FlightServiceServer::new(FlightServiceImpl::new(
+            session_mgr,
+        )))
+        .serve(addr)
+        .await
+        .unwrap();
+}
+
+pub struct FlightServiceImpl<SM> {
+    session_mgr: Arc<SM>,
+}
+
+impl<SM> FlightServiceImpl<SM>
+where
+    SM: SessionManager<Session = SessionImpl>,
+{
+    pub fn new(session_mgr: Arc<SM>) -> Self {
+        Self { session_mgr }
+    }
+
+    /// Connects to the database as the user in `metadata`.
+    fn connect(&self, metadata: &MetadataMap) -> Result<Arc<SessionImpl>, Status> {
+        let get = |key: &str, default: &str| -> Result<String, Status> {
+            match metadata.get(key) {
+                Some(value) => value
+                    .to_str()
+                    .map(str::to_string)
+                    .map_err(|_| Status::invalid_argument(format!("invalid {}", key))),
+                None => Ok(default.to_string()),
+            }
+        };
+        let database = get("database", DEFAULT_DATABASE_NAME)?;
+        let user_name = get("user", DEFAULT_SUPPER_USER)?;
+        let password = get("password", "")?;
+
+        let session = self
+            .session_mgr
+            .connect(&database, &user_name)
+            .map_err(|e| Status::unauthenticated(e.to_string()))?;
+        if !authenticate(session.user_authenticator(), &user_name, &password) {
+            return Err(Status::unauthenticated(format!(
+                "password authentication failed for user \"{}\"",
+                user_name
+            )));
+        }
+        Ok(session)
+    }
+}
+
+/// Checks the `password` in clear text, as pgwire would if the client sent it.
+fn authenticate(authenticator: &UserAuthenticator, user_name: &str, password: &str) -> bool {
+    match authenticator {
+        UserAuthenticator::Md5WithSalt { salt, .. } => {
+            let mut ctx = md5::Context::new();
+            ctx.consume(encrypt_md5(user_name, password));
+            ctx.consume(salt);
+            let response = format!("md5{:x}", ctx.compute());
+            authenticator.authenticate(response.as_bytes())
+        }
+        _ => authenticator.authenticate(password.as_bytes()),
+    }
+}
+
+type FlightStream<T> = BoxStream<'static, Result<T, Status>>;
+
+#[async_trait::async_trait]
+impl<SM> FlightService for FlightServiceImpl<SM>
+where
+    SM: SessionManager<Session = SessionImpl>,
+{
+    type DoActionStream = FlightStream<arrow_flight::Result>;
+    type DoExchangeStream = FlightStream<FlightData>;
+    type DoGetStream = FlightStream<FlightData>;
+    type DoPutStream = FlightStream<PutResult>;
+    type HandshakeStream = FlightStream<HandshakeResponse>;
+    type ListActionsStream = FlightStream<ActionType>;
+    type ListFlightsStream = FlightStream<FlightInfo>;
+
+    async fn handshake(
+        &self,
+        _request: Request<Streaming<HandshakeRequest>>,
+    ) -> Result<Response<Self::HandshakeStream>, Status> {
+        Err(Status::unimplemented("handshake"))
+    }
+
+    async fn list_flights(
+        &self,
+        _request: Request<Criteria>,
+    ) -> Result<Response<Self::ListFlightsStream>, Status> {
+        Err(Status::unimplemented("list_flights"))
+    }
+
+    async fn get_flight_info(
+        &self,
+        _request: Request<FlightDescriptor>,
+    ) -> Result<Response<FlightInfo>, Status> {
+        Err(Status::unimplemented("get_flight_info"))
+    }
+
+    async fn get_schema(
+        &self,
+        _request: Request<FlightDescriptor>,
+    ) -> Result<Response<SchemaResult>, Status> {
+        Err(Status::unimplemented("get_schema"))
+    }
+
+    async fn do_get(
+        &self,
+        request: Request<Ticket>,
+    ) -> Result<Response<Self::DoGetStream>, Status> {
+        let session = self.connect(request.metadata())?;
+        let sql = String::from_utf8(request.into_inner().ticket)
+            .map_err(|_| Status::invalid_argument("the ticket is not a valid UTF-8 query"))?;
+        let mut stmts =
+            Parser::parse_sql(&sql).map_err(|e| Status::invalid_argument(e.to_string()))?;
+        let stmt = match (stmts.pop(), stmts.is_empty()) {
+            (Some(stmt @ Statement::Query(_)), true) => stmt,
+            _ => {
+                return Err(Status::invalid_argument(
+                    "the ticket must be a single SELECT query",
+                ))
+            }
+        };
+
+        let (chunks, schema) =
+            execute_query(OptimizerContext::new(session), stmt, ParamValues::default())
+                .await
+                .map_err(tonic_err)?;
+        let schema = Arc::new(arrow_schema(schema.fields()).map_err(tonic_err)?);
+
+        let options = IpcWriteOptions::default();
+        let schema_data: FlightData = SchemaAsIpc::new(&schema, &options).into();
+        let batches = chunks.map(move |chunk| {
+            let batch =
+                to_record_batch(schema.clone(), chunk.map_err(tonic_err)?).map_err(tonic_err)?;
+            // There are no dictionary-encoded columns.
+            let (_, data) = flight_data_from_arrow_batch(&batch, &options);
+            Ok(data)
+        });
+        Ok(Response::new(
+            stream::once(async { Ok(schema_data) })
+                .chain(batches)
+                .boxed(),
+        ))
+    }
+
+    async fn do_put(
+        &self,
+        _request: Request<Streaming<FlightData>>,
+    ) -> Result<Response<Self::DoPutStream>, Status> {
+        Err(Status::unimplemented("do_put"))
+    }
+
+    async fn do_exchange(
+        &self,
+        _request: Request<Streaming<FlightData>>,
+    ) -> Result<Response<Self::DoExchangeStream>, Status> {
+        Err(Status::unimplemented("do_exchange"))
+    }
+
+    async fn do_action(
+        &self,
+        _request: Request<Action>,
+    ) -> Result<Response<Self::DoActionStream>, Status> {
+        Err(Status::unimplemented("do_action"))
+    }
+
+    async fn list_actions(
+        &self,
+        _request: Request<Empty>,
+    ) -> Result<Response<Self::ListActionsStream>, Status> {
+        Err(Status::unimplemented("list_actions"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use arrow::array::Int32Array;
+    use arrow::datatypes::Schema;
+    use arrow_flight::utils::flight_data_to_arrow_batch;
+    use futures::TryStreamExt;
+    use pgwire::pg_server::BoxedError;
+    use tonic::Code;
+
+    use super::*;
+    use crate::test_utils::LocalFrontend;
+
+    /// Connects to the sessions of a [`LocalFrontend`], which run queries in local mode as there
+    /// are no compute nodes.
+    struct LocalSessionManager(LocalFrontend);
+
+    impl SessionManager for LocalSessionManager {
+        type Session = SessionImpl;
+
+        fn connect(
+            &self,
+            database: &str,
+            user_name: &str,
+        ) -> std::result::Result<Arc<Self::Session>, BoxedError> {
+            let session = self.0.connect(database, user_name)?;
+            session.set_config("query_mode", "local")?;
+            Ok(session)
+        }
+    }
+
+    fn ticket(sql: &str) -> Request<Ticket> {
+        Request::new(Ticket {
+            ticket: sql.as_bytes().to_vec(),
+        })
+    }
+
+    #[tokio::test]
+    async fn test_do_get() {
+        let frontend = LocalFrontend::new(Default::default()).await;
+        let service = FlightServiceImpl::new(Arc::new(LocalSessionManager(frontend)));
+
+        let data: Vec<FlightData> = service
+            .do_get(ticket("SELECT 1 AS v"))
+            .await
+            .unwrap()
+            .into_inner()
+            .try_collect()
+            .await
+            .unwrap();
+        let schema = Arc::new(Schema::try_from(&data[0]).unwrap());
+        assert_eq!(schema.fields().len(), 1);
+        assert_eq!(schema.field(0).name(), "v");
+        let values = data[1..]
+            .iter()
+            .flat_map(|data| {
+                let batch =
+                    flight_data_to_arrow_batch(data, schema.clone(), &HashMap::new()).unwrap();
+                let column = batch.column(0).clone();
+                let column = column.as_any().downcast_ref::<Int32Array>().unwrap();
+                column.iter().collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(values, vec![Some(1)]);
+
+        for sql in ["CREATE TABLE t (v INT)", "SELECT 1; SELECT 2", "SELEC 1"] {
+            let status = service.do_get(ticket(sql)).await.err().unwrap();
+            assert_eq!(status.code(), Code::InvalidArgument, "{}", sql);
+        }
+    }
+
+    #[test]
+    fn test_authenticate() {
+        let encrypted_password = encrypt_md5("foo", "bar");
+        let authenticator = UserAuthenticator::Md5WithSalt {
+            encrypted_password,
+            salt: [1, 2, 3, 4],
+        };
+        assert!(authenticate(&authenticator, "foo", "bar"));
+        assert!(!authenticate(&authenticator, "foo", "baz"));
+        assert!(authenticate(&UserAuthenticator::None, "foo", ""));
+    }
+}
@@ -0,0 +1,315 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use pgwire::pg_response::{PgResponse, StatementType};
+use risingwave_common::error::{ErrorCode, Result, RwError};
+use risingwave_pb::stream_plan::source_node::SourceType;
+use risingwave_sqlparser::ast::{Ident, ObjectName};
+
+use crate::binder::Binder;
+use crate::session::{OptimizerContext, SessionImpl};
+
+/// The kind of relation altered by `ALTER TABLE`, `ALTER SOURCE` or `ALTER MATERIALIZED VIEW`.
+#[derive(Debug, Clone, Copy)]
+pub enum RelationKind {
+    Table,
+    Source,
+    MaterializedView,
+}
+
+impl RelationKind {
+    fn statement_type(self) -> StatementType {
+        match self {
+            RelationKind::Table => StatementType::ALTER_TABLE,
+            RelationKind::Source => StatementType::ALTER_SOURCE,
+            RelationKind::MaterializedView => StatementType::ALTER_MATERIALIZED_VIEW,
+        }
+    }
+}
+
+/// The relation to alter in the catalog. Tables are altered through their associated sources,
+/// and the meta service alters the associated relation together.
+enum RelationId {
+    Table(u32),
+    Source(u32),
+}
+
+/// Resolves the relation of `kind` named `relation_name`, and checks that the user of the
+/// session owns it.
+fn resolve_relation(
+    session: &SessionImpl,
+    kind: RelationKind,
+    schema_name: &str,
+    relation_name: &str,
+) -> Result<RelationId> {
+    let catalog_reader = session.env().catalog_reader();
+    let reader = catalog_reader.read_guard();
+    let (relation_id, owner) = match kind {
+        RelationKind::Table | RelationKind::Source => {
+            let source =
+                reader.get_source_by_name(session.database(), schema_name, relation_name)?;
+            match (kind, source.source_type) {
+                (RelationKind::Table, SourceType::Source) => {
+                    return Err(RwError::from(ErrorCode::InvalidInputSyntax(
+                        "Use `ALTER SOURCE` to alter a source.".to_owned(),
+                    )))
+                }
+                (RelationKind::Source, SourceType::Table) => {
+                    return Err(RwError::from(ErrorCode::InvalidInputSyntax(
+                        "Use `ALTER TABLE` to alter a table.".to_owned(),
+                    )))
+                }
+                _ => (RelationId::Source(source.id), source.owner.as_str()),
+            }
+        }
+        RelationKind::MaterializedView => {
+            let table = reader.get_table_by_name(session.database(), schema_name, relation_name)?;
+            if table.associated_source_id().is_some() {
+                return Err(RwError::from(ErrorCode::InvalidInputSyntax(
+                    "Use `ALTER TABLE` to alter a table.".to_owned(),
+                )));
+            }
+            if table.is_internal {
+                return Err(RwError::from(ErrorCode::InvalidInputSyntax(
+                    "Internal tables can't be altered.".to_owned(),
+                )));
+            }
+            if table.is_index_on.is_some() {
+                return Err(
+                    ErrorCode::NotImplemented("alter index".to_string(), None.into()).into(),
+                );
+            }
+            (RelationId::Table(table.id().table_id), table.owner.as_str())
+        }
+    };
+    session.check_owner(owner, relation_name)?;
+    Ok(relation_id)
+}
+
+pub async fn handle_rename_relation(
+    context: OptimizerContext,
+    kind: RelationKind,
+    relation_name: ObjectName,
+    new_name: ObjectName,
+) -> Result<PgResponse> {
+    let session = context.session_ctx;
+    let (schema_name, relation_name) = Binder::resolve_table_name(relation_name)?;
+    let (new_schema_name, new_name) = Binder::resolve_table_name(new_name)?;
+    if new_schema_name != schema_name {
+        return Err(ErrorCode::NotImplemented(
+            "move relation to another schema".to_string(),
+            None.into(),
+        )
+        .into());
+    }
+
+    let relation_id = resolve_relation(&session, kind, &schema_name, &relation_name)?;
+    session
+        .env()
+        .catalog_reader()
+        .read_guard()
+        .check_relation_name_duplicated(session.database(), &schema_name, &new_name)?;
+
+    let catalog_writer = session.env().catalog_writer();
+    match relation_id {
+        RelationId::Table(table_id) => catalog_writer.alter_table_name(table_id, &new_name).await?,
+        RelationId::Source(source_id) => {
+            catalog_writer
+                .alter_source_name(source_id, &new_name)
+                .await?
+        }
+    }
+
+    Ok(PgResponse::empty_result(kind.statement_type()))
+}
+
+pub async fn handle_change_relation_owner(
+    context: OptimizerContext,
+    kind: RelationKind,
+    relation_name: ObjectName,
+    new_owner_name: Ident,
+) -> Result<PgResponse> {
+    let session = context.session_ctx;
+    let (schema_name, relation_name) = Binder::resolve_table_name(relation_name)?;
+    let new_owner_name = Binder::resolve_user_name(ObjectName(vec![new_owner_name]))?;
+
+    let relation_id = resolve_relation(&session, kind, &schema_name, &relation_name)?;
+    if session
+        .env()
+        .user_info_reader()
+        .read_guard()
+        .get_user_by_name(&new_owner_name)
+        .is_none()
+    {
+        return Err(ErrorCode::InvalidInputSyntax(format!(
+            "user {} does not exist",
+            new_owner_name
+        ))
+        .into());
+    }
+
+    let catalog_writer = session.env().catalog_writer();
+    match relation_id {
+        RelationId::Table(table_id) => {
+            catalog_writer
+                .alter_table_owner(table_id, &new_owner_name)
+                .await?
+        }
+        RelationId::Source(source_id) => {
+            catalog_writer
+                .alter_source_owner(source_id, &new_owner_name)
+                .await?
+        }
+    }
+
+    Ok(PgResponse::empty_result(kind.statement_type()))
+}
+
+/// Reschedules the vnodes of each hash-distributed fragment of a materialized view to
+/// `parallelism` of its actors. The actors created with the materialized view are not dropped or
+/// added, so the parallelism can't exceed the one it was created with.
+pub async fn handle_set_parallelism(
+    context: OptimizerContext,
+    relation_name: ObjectName,
+    parallelism: u64,
+) -> Result<PgResponse> {
+    let session = context.session_ctx;
+    let (schema_name, relation_name) = Binder::resolve_table_name(relation_name)?;
+    let parallelism = u32::try_from(parallelism)
+        .ok()
+        .filter(|parallelism| *parallelism > 0)
+        .ok_or_else(|| {
+            ErrorCode::InvalidInputSyntax(format!("invalid parallelism {}", parallelism))
+        })?;
+
+    let table_id = match resolve_relation(
+        &session,
+        RelationKind::MaterializedView,
+        &schema_name,
+        &relation_name,
+    )? {
+        RelationId::Table(table_id) => table_id,
+        RelationId::Source(_) => unreachable!("materialized views are resolved to tables"),
+    };
+    session
+        .env()
+        .meta_client()
+        .alter_parallelism(table_id, parallelism)
+        .await?;
+
+    Ok(PgResponse::empty_result(
+        RelationKind::MaterializedView.statement_type(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_common::catalog::{DEFAULT_DATABASE_NAME, DEFAULT_SCHEMA_NAME};
+
+    use crate::test_utils::LocalFrontend;
+
+    #[tokio::test]
+    async fn test_alter_relation_handler() {
+        let frontend = LocalFrontend::new(Default::default()).await;
+        frontend
+            .run_sql("create table t (v1 smallint);")
+            .await
+            .unwrap();
+        frontend
+            .run_sql("create materialized view mv as select v1 from t;")
+            .await
+            .unwrap();
+        frontend
+            .run_sql("create user user1 with password 'password1';")
+            .await
+            .unwrap();
+
+        frontend
+            .run_sql("alter table t rename to t1;")
+            .await
+            .unwrap();
+        frontend
+            .run_sql("alter table t1 owner to user1;")
+            .await
+            .unwrap();
+        frontend
+            .run_sql("alter materialized view mv rename to mv1;")
+            .await
+            .unwrap();
+
+        let session = frontend.session_ref();
+        let catalog_reader = session.env().catalog_reader();
+        let reader = catalog_reader.read_guard();
+
+        // The table is renamed and re-owned together with its associated source.
+        let source = reader
+            .get_source_by_name(DEFAULT_DATABASE_NAME, DEFAULT_SCHEMA_NAME, "t1")
+            .unwrap();
+        assert_eq!(source.owner, "user1");
+        let table = reader
+            .get_table_by_name(DEFAULT_DATABASE_NAME, DEFAULT_SCHEMA_NAME, "t1")
+            .unwrap();
+        assert_eq!(table.owner, "user1");
+        assert!(reader
+            .get_table_by_name(DEFAULT_DATABASE_NAME, DEFAULT_SCHEMA_NAME, "t")
+            .is_err());
+        assert!(reader
+            .get_table_by_name(DEFAULT_DATABASE_NAME, DEFAULT_SCHEMA_NAME, "mv1")
+            .is_ok());
+        drop(reader);
+
+        assert!(frontend
+            .run_sql("alter materialized view mv1 rename to t1;")
+            .await
+            .is_err());
+        assert!(frontend
+            .run_sql("alter materialized view t1 rename to t2;")
+            .await
+            .is_err());
+        assert!(frontend
+            .run_sql("alter source t1 rename to t2;")
+            .await
+            .is_err());
+        assert!(frontend
+            .run_sql("alter table t1 owner to user2;")
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_set_parallelism_handler() {
+        let frontend = LocalFrontend::new(Default::default()).await;
+        frontend
+            .run_sql("create table t (v1 smallint);")
+            .await
+            .unwrap();
+        frontend
+            .run_sql("create materialized view mv with (parallelism = 4) as select v1 from t;")
+            .await
+            .unwrap();
+
+        frontend
+            .run_sql("alter materialized view mv set parallelism = 2;")
+            .await
+            .unwrap();
+        assert!(frontend
+            .run_sql("alter materialized view mv set parallelism = 0;")
+            .await
+            .is_err());
+        assert!(frontend
+            .run_sql("alter materialized view t set parallelism = 2;")
+            .await
+            .is_err());
+    }
+}
@@ -0,0 +1,201 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use pgwire::pg_response::{PgResponse, StatementType};
+use risingwave_common::catalog::{ColumnDesc, ColumnId};
+use risingwave_common::error::{ErrorCode, Result, RwError};
+use risingwave_pb::plan_common::ColumnCatalog;
+use risingwave_pb::stream_plan::source_node::SourceType;
+use risingwave_sqlparser::ast::{ColumnDef, DataType as AstDataType, ObjectName};
+
+use crate::binder::expr::bind_data_type;
+use crate::binder::Binder;
+use crate::catalog::check_valid_column_name;
+use crate::catalog::source_catalog::SourceCatalog;
+use crate::session::OptimizerContext;
+
+/// Binds the column added to `source`. Only nullable columns of non-struct types without default
+/// values can be added, whose values of the existing rows are nulls.
+fn bind_added_column(source: &SourceCatalog, column: ColumnDef) -> Result<ColumnCatalog> {
+    if !column.options.is_empty() {
+        return Err(ErrorCode::NotImplemented(
+            "options of the column to add".to_string(),
+            None.into(),
+        )
+        .into());
+    }
+    let Some(data_type) = &column.data_type else {
+        return Err(
+            ErrorCode::NotImplemented("add generated column".to_string(), None.into()).into(),
+        );
+    };
+    if matches!(data_type, AstDataType::Struct(_)) {
+        return Err(ErrorCode::NotImplemented(
+            "add column of struct type".to_string(),
+            None.into(),
+        )
+        .into());
+    }
+    let name = column.name.value;
+    check_valid_column_name(&name)?;
+    if source.columns.iter().any(|c| c.name() == name) {
+        return Err(RwError::from(ErrorCode::InvalidInputSyntax(format!(
+            "column \"{}\" of relation \"{}\" already exists",
+            name, source.name
+        ))));
+    }
+
+    let column_id = source
+        .columns
+        .iter()
+        .map(|c| c.column_id().get_id())
+        .max()
+        .unwrap_or(0)
+        + 1;
+    let column_desc = ColumnDesc {
+        data_type: bind_data_type(data_type)?,
+        column_id: ColumnId::new(column_id),
+        name,
+        field_descs: vec![],
+        type_name: "".to_string(),
+    };
+    Ok(ColumnCatalog {
+        column_desc: column_desc.to_protobuf().into(),
+        is_hidden: false,
+        generated_expr: None,
+    })
+}
+
+pub async fn handle_alter_table_add_column(
+    context: OptimizerContext,
+    table_name: ObjectName,
+    column: ColumnDef,
+) -> Result<PgResponse> {
+    let session = context.session_ctx;
+    let (schema_name, table_name) = Binder::resolve_table_name(table_name)?;
+
+    let catalog_reader = session.env().catalog_reader();
+    let (source_id, column) = {
+        let reader = catalog_reader.read_guard();
+        let source = reader.get_source_by_name(session.database(), &schema_name, &table_name);
+        match source {
+            Ok(source) if source.source_type == SourceType::Table => {
+                (source.id, bind_added_column(source, column)?)
+            }
+            Ok(_) => {
+                return Err(RwError::from(ErrorCode::InvalidInputSyntax(
+                    "Use `ALTER SOURCE` to alter a source.".to_owned(),
+                )))
+            }
+            Err(_) => {
+                // Check whether it's a materialized view.
+                reader.get_table_by_name(session.database(), &schema_name, &table_name)?;
+                return Err(ErrorCode::NotImplemented(
+                    "add column to materialized view".to_string(),
+                    None.into(),
+                )
+                .into());
+            }
+        }
+    };
+
+    let catalog_writer = session.env().catalog_writer();
+    catalog_writer
+        .alter_source_add_column(source_id, column)
+        .await?;
+
+    Ok(PgResponse::empty_result(StatementType::ALTER_TABLE))
+}
+
+pub async fn handle_alter_source_add_column(
+    context: OptimizerContext,
+    source_name: ObjectName,
+    column: ColumnDef,
+) -> Result<PgResponse> {
+    let session = context.session_ctx;
+    let (schema_name, source_name) = Binder::resolve_table_name(source_name)?;
+
+    let catalog_reader = session.env().catalog_reader();
+    let (source_id, column) = {
+        let reader = catalog_reader.read_guard();
+        let source = reader.get_source_by_name(session.database(), &schema_name, &source_name)?;
+        if source.source_type == SourceType::Table {
+            return Err(RwError::from(ErrorCode::InvalidInputSyntax(
+                "Use `ALTER TABLE` to alter a table.".to_owned(),
+            )));
+        }
+        (source.id, bind_added_column(source, column)?)
+    };
+
+    let catalog_writer = session.env().catalog_writer();
+    catalog_writer
+        .alter_source_add_column(source_id, column)
+        .await?;
+
+    Ok(PgResponse::empty_result(StatementType::ALTER_SOURCE))
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_common::catalog::{DEFAULT_DATABASE_NAME, DEFAULT_SCHEMA_NAME};
+    use risingwave_common::types::DataType;
+
+    use crate::test_utils::LocalFrontend;
+
+    #[tokio::test]
+    async fn test_alter_table_add_column_handler() {
+        let frontend = LocalFrontend::new(Default::default()).await;
+        frontend
+            .run_sql("create table t (v1 smallint);")
+            .await
+            .unwrap();
+        frontend
+            .run_sql("alter table t add column v2 varchar;")
+            .await
+            .unwrap();
+
+        let session = frontend.session_ref();
+        let catalog_reader = session.env().catalog_reader();
+        let reader = catalog_reader.read_guard();
+
+        // The column is added to both the source and the table.
+        let source = reader
+            .get_source_by_name(DEFAULT_DATABASE_NAME, DEFAULT_SCHEMA_NAME, "t")
+            .unwrap();
+        let column = source.columns.last().unwrap();
+        assert_eq!(column.name(), "v2");
+        assert_eq!(column.column_id().get_id(), 2);
+        assert_eq!(column.data_type(), &DataType::Varchar);
+        assert!(!column.is_hidden());
+
+        let table = reader
+            .get_table_by_name(DEFAULT_DATABASE_NAME, DEFAULT_SCHEMA_NAME, "t")
+            .unwrap();
+        assert_eq!(table.columns().last().unwrap().name(), "v2");
+        drop(reader);
+
+        assert!(frontend
+            .run_sql("alter table t add column v2 int;")
+            .await
+            .is_err());
+        assert!(frontend
+            .run_sql("alter table t add column v3 int not null;")
+            .await
+            .is_err());
+        assert!(frontend
+            .run_sql("alter source t add column v3 int;")
+            .await
+            .is_err());
+    }
+}
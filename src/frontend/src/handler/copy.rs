@@ -0,0 +1,476 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use fixedbitset::FixedBitSet;
+use futures::future::try_join_all;
+use futures::TryStreamExt;
+use itertools::Itertools;
+use pgwire::pg_copy::CopyFormat;
+use pgwire::pg_response::{PgResponse, StatementType};
+use risingwave_batch::executor::split_file_location;
+use risingwave_common::array::DataChunk;
+use risingwave_common::error::{ErrorCode, Result};
+use risingwave_common::types::ScalarRefImpl;
+use risingwave_object_store::object::parse_object_store;
+use risingwave_pb::batch_plan::file_scan_node::FileFormat;
+use risingwave_pb::batch_plan::FileScanNode;
+use risingwave_pb::user::grant_privilege::Action;
+use risingwave_sqlparser::ast::{CopySource, Ident, ObjectName, SqlOption, Statement, Value};
+use risingwave_sqlparser::parser::Parser;
+use tracing::info;
+
+use crate::binder::{Binder, ParamValues};
+use crate::handler::query;
+use crate::optimizer::plan_node::{
+    BatchExchange, BatchExport, BatchFileScan, BatchInsert, LogicalInsert, PlanRef,
+};
+use crate::optimizer::property::{Distribution, Order, RequiredDist};
+use crate::optimizer::PlanRoot;
+use crate::planner::Planner;
+use crate::scheduler::{BatchPlanFragmenter, ExecutionContext, ExecutionContextRef};
+use crate::session::{OptimizerContext, OptimizerContextRef};
+
+/// Checks the `WITH` options of `COPY TO`. Only the `parquet` format is supported for now.
+fn check_copy_to_options(with_options: &[SqlOption]) -> Result<()> {
+    for option in with_options {
+        match (option.name.value.to_lowercase().as_str(), &option.value) {
+            ("format", Value::SingleQuotedString(format)) => match format.to_lowercase().as_str() {
+                "parquet" => {}
+                "iceberg" => {
+                    return Err(ErrorCode::NotImplemented(
+                        "COPY TO in iceberg format".to_string(),
+                        None.into(),
+                    )
+                    .into())
+                }
+                _ => {
+                    return Err(ErrorCode::InvalidParameterValue(format!(
+                        "unknown format {} of COPY TO",
+                        format
+                    ))
+                    .into())
+                }
+            },
+            _ => {
+                return Err(ErrorCode::InvalidParameterValue(format!(
+                    "invalid option of COPY TO: {}",
+                    option
+                ))
+                .into())
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Builds the query selecting `columns` of a table, or all of them if `columns` is empty.
+fn select_columns(table_name: ObjectName, columns: Vec<Ident>) -> Result<Statement> {
+    let columns = match columns.is_empty() {
+        true => "*".to_string(),
+        false => columns.iter().join(", "),
+    };
+    let sql = format!("SELECT {} FROM {}", columns, table_name);
+    Ok(Parser::parse_sql(&sql)
+        .map_err(|e| ErrorCode::InternalError(e.to_string()))?
+        .remove(0))
+}
+
+/// Exports a snapshot of the rows of a table or a materialized view as Parquet files at
+/// `location`. The files are written by the compute nodes scanning the rows in parallel, and all
+/// of them read the same epoch of the storage.
+pub async fn handle_copy_to(
+    context: OptimizerContext,
+    table_name: ObjectName,
+    columns: Vec<Ident>,
+    location: String,
+    with_options: Vec<SqlOption>,
+) -> Result<PgResponse> {
+    check_copy_to_options(&with_options)?;
+    // Fail early on an invalid location, rather than in each task.
+    split_file_location(&location)?;
+
+    let session = context.session_ctx.clone();
+    let stmt = select_columns(table_name, columns)?;
+    let bound = {
+        let mut binder = Binder::new(
+            session.env().catalog_reader().read_guard(),
+            session.database().to_string(),
+        );
+        let bound = binder.bind(stmt)?;
+        session.check_privileges(binder.object_check_items())?;
+        bound
+    };
+
+    // Subblock to make sure PlanRef (an Rc) is dropped before `await` below.
+    let query = {
+        // Rows are exported by the tasks scanning them, so no distribution is required.
+        let plan = Planner::new(context.into()).plan(bound)?.as_subplan();
+        let mut out_fields = FixedBitSet::with_capacity(plan.schema().len());
+        out_fields.insert_range(..);
+        let out_names = plan.schema().names();
+        let root = PlanRoot::new(
+            plan,
+            RequiredDist::Any,
+            Order::any().clone(),
+            out_fields,
+            out_names,
+        );
+        let plan: PlanRef = BatchExport::new(root.gen_batch_query_plan()?, location).into();
+        // Gathers the number of rows exported by each task.
+        let plan: PlanRef =
+            BatchExchange::new(plan, Order::any().clone(), Distribution::Single).into();
+
+        info!("Generated export plan: {:?}", plan.explain_to_string()?);
+
+        let plan_fragmenter = BatchPlanFragmenter::new(session.env().worker_node_manager_ref());
+        plan_fragmenter.split(plan)?
+    };
+
+    let execution_context: ExecutionContextRef = ExecutionContext::new(session.clone()).into();
+    let query_manager = execution_context.session().env().query_manager().clone();
+    let chunks: Vec<DataChunk> = query_manager
+        .schedule(execution_context, query)
+        .await?
+        .try_collect()
+        .await?;
+
+    Ok(PgResponse::new(
+        StatementType::COPY,
+        sum_counts(&chunks) as i32,
+        vec![],
+        vec![],
+    ))
+}
+
+/// Sums the numbers of rows exported or imported by the tasks.
+fn sum_counts(chunks: &[DataChunk]) -> i64 {
+    chunks
+        .iter()
+        .flat_map(|chunk| chunk.rows())
+        .filter_map(|row| match row.value_at(0) {
+            Some(ScalarRefImpl::Int64(count)) => Some(count),
+            _ => None,
+        })
+        .sum()
+}
+
+/// Parses the format of the rows sent by `COPY TO STDOUT`. The rows are in text format by default.
+fn copy_to_stdout_format(with_options: &[SqlOption]) -> Result<CopyFormat> {
+    let mut csv = false;
+    let mut header = false;
+    let mut delimiter = None;
+    for option in with_options {
+        match (option.name.value.to_lowercase().as_str(), &option.value) {
+            ("format", Value::SingleQuotedString(format)) => {
+                csv = match format.to_lowercase().as_str() {
+                    "text" => false,
+                    "csv" => true,
+                    _ => {
+                        return Err(ErrorCode::InvalidParameterValue(format!(
+                            "unknown format {} of COPY TO STDOUT",
+                            format
+                        ))
+                        .into())
+                    }
+                }
+            }
+            ("header", Value::Boolean(value)) => header = *value,
+            ("delimiter", Value::SingleQuotedString(value)) if value.len() == 1 => {
+                delimiter = Some(value.as_bytes()[0])
+            }
+            _ => {
+                return Err(ErrorCode::InvalidParameterValue(format!(
+                    "invalid option of COPY TO STDOUT: {}",
+                    option
+                ))
+                .into())
+            }
+        }
+    }
+    match csv {
+        true => Ok(CopyFormat::Csv {
+            delimiter: delimiter.unwrap_or(b','),
+            header,
+        }),
+        false if header => Err(ErrorCode::InvalidParameterValue(
+            "COPY TO STDOUT only supports the header in CSV format".to_string(),
+        )
+        .into()),
+        false => Ok(CopyFormat::Text {
+            delimiter: delimiter.unwrap_or(b'\t'),
+        }),
+    }
+}
+
+/// Sends the rows of a table or a query to the client by the `COPY` protocol. Like the result of a
+/// query, the rows are sent batch by batch while the query is running.
+pub async fn handle_copy_to_stdout(
+    context: OptimizerContext,
+    source: CopySource,
+    with_options: Vec<SqlOption>,
+    params: ParamValues,
+) -> Result<PgResponse> {
+    let format = copy_to_stdout_format(&with_options)?;
+    let stmt = match source {
+        CopySource::Table {
+            table_name,
+            columns,
+        } => select_columns(table_name, columns)?,
+        CopySource::Query(query) => Statement::Query(query),
+    };
+    let response = query::handle_query(context, stmt, params).await?;
+    Ok(response.into_copy_out(format))
+}
+
+/// Options of the files to import by `COPY FROM`.
+#[derive(Debug, PartialEq)]
+struct CopyFromOptions {
+    format: FileFormat,
+    csv_has_header: bool,
+    csv_delimiter: String,
+}
+
+impl CopyFromOptions {
+    fn from_sql_options(with_options: &[SqlOption]) -> Result<Self> {
+        let mut options = Self {
+            format: FileFormat::Parquet,
+            csv_has_header: false,
+            csv_delimiter: ",".to_string(),
+        };
+        for option in with_options {
+            match (option.name.value.to_lowercase().as_str(), &option.value) {
+                ("format", Value::SingleQuotedString(format)) => {
+                    options.format = match format.to_lowercase().as_str() {
+                        "parquet" => FileFormat::Parquet,
+                        "csv" => FileFormat::Csv,
+                        _ => {
+                            return Err(ErrorCode::InvalidParameterValue(format!(
+                                "unknown format {} of COPY FROM",
+                                format
+                            ))
+                            .into())
+                        }
+                    }
+                }
+                ("header", Value::Boolean(header)) => options.csv_has_header = *header,
+                ("delimiter", Value::SingleQuotedString(delimiter)) if delimiter.len() == 1 => {
+                    options.csv_delimiter = delimiter.clone()
+                }
+                _ => {
+                    return Err(ErrorCode::InvalidParameterValue(format!(
+                        "invalid option of COPY FROM: {}",
+                        option
+                    ))
+                    .into())
+                }
+            }
+        }
+        Ok(options)
+    }
+}
+
+/// Imports the rows of the Parquet or CSV files at `location` into a table. The files are split
+/// among the compute nodes, each of which reads its files and inserts the rows through the DML
+/// path. The import is flushed at the end, so the rows are committed when the statement returns.
+pub async fn handle_copy_from(
+    context: OptimizerContext,
+    table_name: ObjectName,
+    columns: Vec<Ident>,
+    location: String,
+    with_options: Vec<SqlOption>,
+) -> Result<PgResponse> {
+    let options = CopyFromOptions::from_sql_options(&with_options)?;
+    let (store_url, prefix) = split_file_location(&location)?;
+
+    let session = context.session_ctx.clone();
+    let (table_source, file_column_indices) = {
+        let mut binder = Binder::new(
+            session.env().catalog_reader().read_guard(),
+            session.database().to_string(),
+        );
+        let table_source = binder.bind_table_source(table_name, Action::Insert)?;
+        session.check_privileges(binder.object_check_items())?;
+        let file_column_indices = match columns.is_empty() {
+            true => (0..table_source.columns.len()).collect_vec(),
+            false => Binder::bind_insert_columns(&table_source, columns)?,
+        };
+        (table_source, file_column_indices)
+    };
+
+    let object_store = parse_object_store(&store_url, false).await;
+    let mut paths = object_store
+        .list(&prefix)
+        .await
+        .map_err(|e| ErrorCode::InternalError(format!("failed to list {}: {}", location, e)))?;
+    // Skip the placeholders of directories.
+    paths.retain(|path| !path.ends_with('/'));
+    paths.sort();
+    info!("Importing {} files from {}", paths.len(), location);
+
+    // Split the files among the compute nodes, with a plan inserting the rows of each group.
+    let groups = session
+        .env()
+        .worker_node_manager()
+        .worker_node_count()
+        .clamp(1, paths.len().max(1));
+    let mut path_groups = vec![vec![]; groups];
+    for (i, path) in paths.into_iter().enumerate() {
+        path_groups[i % groups].push(path);
+    }
+    // Subblock to make sure PlanRef (an Rc) is dropped before `await` below.
+    let plans = {
+        let ctx: OptimizerContextRef = context.into();
+        path_groups
+            .into_iter()
+            .filter(|paths| !paths.is_empty())
+            .map(|paths| {
+                let file_scan = BatchFileScan::new(
+                    ctx.clone(),
+                    FileScanNode {
+                        store_url: store_url.clone(),
+                        paths,
+                        format: options.format as i32,
+                        column_descs: table_source
+                            .columns
+                            .iter()
+                            .map(|column| column.to_protobuf())
+                            .collect(),
+                        file_column_indices: file_column_indices
+                            .iter()
+                            .map(|&index| index as u32)
+                            .collect(),
+                        csv_has_header: options.csv_has_header,
+                        csv_delimiter: options.csv_delimiter.clone(),
+                    },
+                );
+                let insert = BatchInsert::new(LogicalInsert::create(
+                    file_scan.into(),
+                    table_source.name.clone(),
+                    table_source.source_id,
+                )?);
+                Ok(PlanRef::from(insert).to_batch_prost())
+            })
+            .collect::<Result<Vec<_>>>()?
+    };
+
+    let execution_context: ExecutionContextRef = ExecutionContext::new(session.clone()).into();
+    let query_manager = execution_context.session().env().query_manager().clone();
+    let chunks = try_join_all(plans.into_iter().map(|plan| {
+        let query_manager = query_manager.clone();
+        let execution_context = execution_context.clone();
+        async move {
+            query_manager
+                .schedule_single(execution_context, plan)
+                .await?
+                .try_collect::<Vec<DataChunk>>()
+                .await
+        }
+    }))
+    .await?
+    .concat();
+
+    let committed_epoch = session.env().meta_client().flush().await?;
+    session.update_write_epoch(committed_epoch);
+
+    Ok(PgResponse::new(
+        StatementType::COPY,
+        sum_counts(&chunks) as i32,
+        vec![],
+        vec![],
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn with_options_of(sql: &str) -> Vec<SqlOption> {
+        match Parser::parse_sql(sql).unwrap().remove(0) {
+            Statement::CopyTo { with_options, .. }
+            | Statement::CopyToStdout { with_options, .. }
+            | Statement::CopyFrom { with_options, .. } => with_options,
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_copy_from_options() {
+        let options = CopyFromOptions::from_sql_options(&with_options_of(
+            "COPY t FROM 's3://bucket' WITH (format = 'csv', header = true, delimiter = '|')",
+        ))
+        .unwrap();
+        assert_eq!(
+            options,
+            CopyFromOptions {
+                format: FileFormat::Csv,
+                csv_has_header: true,
+                csv_delimiter: "|".to_string(),
+            }
+        );
+        assert_eq!(
+            CopyFromOptions::from_sql_options(&[]).unwrap().format,
+            FileFormat::Parquet
+        );
+        assert!(CopyFromOptions::from_sql_options(&with_options_of(
+            "COPY t FROM 's3://bucket' WITH (delimiter = '||')"
+        ))
+        .is_err());
+    }
+
+    #[test]
+    fn test_check_copy_to_options() {
+        assert!(check_copy_to_options(&with_options_of("COPY t TO 's3://bucket'")).is_ok());
+        assert!(check_copy_to_options(&with_options_of(
+            "COPY t TO 's3://bucket' WITH (format = 'PARQUET')"
+        ))
+        .is_ok());
+        assert!(check_copy_to_options(&with_options_of(
+            "COPY t TO 's3://bucket' WITH (format = 'iceberg')"
+        ))
+        .is_err());
+        assert!(check_copy_to_options(&with_options_of(
+            "COPY t TO 's3://bucket' WITH (compression = 'snappy')"
+        ))
+        .is_err());
+    }
+
+    #[test]
+    fn test_copy_to_stdout_format() {
+        assert_eq!(
+            copy_to_stdout_format(&with_options_of("COPY t TO STDOUT")).unwrap(),
+            CopyFormat::Text { delimiter: b'\t' }
+        );
+        assert_eq!(
+            copy_to_stdout_format(&with_options_of(
+                "COPY (SELECT 1) TO STDOUT WITH CSV HEADER"
+            ))
+            .unwrap(),
+            CopyFormat::Csv {
+                delimiter: b',',
+                header: true,
+            }
+        );
+        assert_eq!(
+            copy_to_stdout_format(&with_options_of(
+                "COPY t TO STDOUT WITH (format = 'text', delimiter = '|')"
+            ))
+            .unwrap(),
+            CopyFormat::Text { delimiter: b'|' }
+        );
+        assert!(copy_to_stdout_format(&with_options_of(
+            "COPY t TO STDOUT WITH (format = 'text', header = true)"
+        ))
+        .is_err());
+    }
+}
@@ -13,7 +13,7 @@
 // limitations under the License.
 
 use pgwire::pg_response::{PgResponse, StatementType};
-use risingwave_common::error::Result;
+use risingwave_common::error::{ErrorCode, Result};
 use risingwave_sqlparser::ast::ObjectName;
 
 use crate::binder::Binder;
@@ -28,6 +28,21 @@ pub async fn handle_create_database(
     let session = context.session_ctx;
     let database_name = Binder::resolve_database_name(database_name)?;
 
+    {
+        let user_reader = session.env().user_info_reader();
+        let reader = user_reader.read_guard();
+        match reader.get_user_by_name(session.user_name()) {
+            Some(user) if user.is_supper || user.can_create_db => {}
+            _ => {
+                return Err(ErrorCode::PermissionDenied(format!(
+                    "user {} is not allowed to create database",
+                    session.user_name()
+                ))
+                .into())
+            }
+        }
+    }
+
     {
         let catalog_reader = session.env().catalog_reader();
         let reader = catalog_reader.read_guard();
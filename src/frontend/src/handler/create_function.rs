@@ -0,0 +1,249 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use itertools::Itertools;
+use pgwire::pg_response::{PgResponse, StatementType};
+use risingwave_common::error::{ErrorCode, Result};
+use risingwave_common::types::DataType;
+#[cfg(not(madsim))]
+use risingwave_expr::expr::ExternalFunction;
+use risingwave_expr::expr::WasmFunction;
+use risingwave_pb::catalog::Function as ProstFunction;
+use risingwave_sqlparser::ast::{DataType as AstDataType, Ident, ObjectName, SqlOption, Value};
+
+use crate::binder::expr::bind_data_type;
+use crate::binder::Binder;
+use crate::catalog::CatalogError;
+use crate::session::OptimizerContext;
+
+/// The options of an external function in the `WITH` clause.
+#[derive(Default)]
+struct ExternalOptions {
+    link: String,
+    timeout_ms: u64,
+    max_retries: u32,
+}
+
+impl ExternalOptions {
+    fn from_sql_options(options: Vec<SqlOption>) -> Result<Self> {
+        let mut external = Self::default();
+        for option in options {
+            let value = match option.value {
+                Value::SingleQuotedString(s) | Value::Number(s, _) => s,
+                value => {
+                    return Err(ErrorCode::InvalidParameterValue(format!(
+                        "invalid value of option {}: {}",
+                        option.name, value
+                    ))
+                    .into())
+                }
+            };
+            let invalid = || {
+                ErrorCode::InvalidParameterValue(format!(
+                    "invalid value of option {}: {}",
+                    option.name, value
+                ))
+            };
+            match option.name.value.to_lowercase().as_str() {
+                "link" => external.link = value.clone(),
+                "timeout_ms" => external.timeout_ms = value.parse().map_err(|_| invalid())?,
+                "max_retries" => external.max_retries = value.parse().map_err(|_| invalid())?,
+                _ => {
+                    return Err(ErrorCode::InvalidParameterValue(format!(
+                        "unknown option {} of function",
+                        option.name
+                    ))
+                    .into())
+                }
+            }
+        }
+        Ok(external)
+    }
+}
+
+/// Checks that the function `name` exists on the UDF server with the given signature.
+#[cfg(not(madsim))]
+async fn check_external_function(
+    name: &str,
+    external: &ExternalOptions,
+    arg_types: Vec<DataType>,
+    return_type: DataType,
+) -> Result<()> {
+    ExternalFunction::new(
+        name,
+        &external.link,
+        arg_types,
+        return_type,
+        external.timeout_ms,
+        external.max_retries,
+    )?
+    .check()
+    .await
+}
+
+/// External functions can't be called in the simulation, see `build_udf_expr`.
+#[cfg(madsim)]
+async fn check_external_function(
+    _name: &str,
+    _external: &ExternalOptions,
+    _arg_types: Vec<DataType>,
+    _return_type: DataType,
+) -> Result<()> {
+    Err(ErrorCode::NotImplemented(
+        "external functions in the simulation".to_string(),
+        None.into(),
+    )
+    .into())
+}
+
+#[allow(clippy::too_many_arguments)]
+pub async fn handle_create_function(
+    context: OptimizerContext,
+    name: ObjectName,
+    args: Vec<AstDataType>,
+    return_type: AstDataType,
+    language: Ident,
+    body: String,
+    link_symbol: Option<String>,
+    with_options: Vec<SqlOption>,
+) -> Result<PgResponse> {
+    let session = context.session_ctx;
+    let (schema_name, function_name) = Binder::resolve_table_name(name)?;
+
+    let arg_types: Vec<_> = args.iter().map(bind_data_type).try_collect()?;
+    let return_type = bind_data_type(&return_type)?;
+
+    // Check the function before it is stored in the catalog: wasm modules are compiled, and
+    // external functions are looked up on the UDF server.
+    let language = language.value.to_lowercase();
+    let (link_symbol, external) = match language.as_str() {
+        "wasm" => {
+            if !with_options.is_empty() {
+                return Err(ErrorCode::InvalidParameterValue(
+                    "wasm functions do not take options".to_string(),
+                )
+                .into());
+            }
+            let link_symbol = link_symbol.unwrap_or_else(|| function_name.clone());
+            WasmFunction::new(
+                body.as_bytes(),
+                &link_symbol,
+                arg_types.clone(),
+                return_type.clone(),
+            )?;
+            (link_symbol, ExternalOptions::default())
+        }
+        "python" => {
+            if link_symbol.is_some() {
+                return Err(ErrorCode::InvalidParameterValue(
+                    "external functions do not take a link symbol".to_string(),
+                )
+                .into());
+            }
+            let external = ExternalOptions::from_sql_options(with_options)?;
+            check_external_function(&body, &external, arg_types.clone(), return_type.clone())
+                .await?;
+            (String::new(), external)
+        }
+        _ => {
+            return Err(ErrorCode::NotImplemented(
+                format!("functions in language {}", language),
+                None.into(),
+            )
+            .into())
+        }
+    };
+
+    let (database_id, schema_id) = {
+        let catalog_reader = session.env().catalog_reader();
+        let reader = catalog_reader.read_guard();
+        let schema = reader.get_schema_by_name(session.database(), &schema_name)?;
+        if schema.get_function_by_name(&function_name).is_some() {
+            return Err(CatalogError::Duplicated("function", function_name).into());
+        }
+        (
+            reader.get_database_by_name(session.database())?.id(),
+            schema.id(),
+        )
+    };
+
+    let function = ProstFunction {
+        id: 0,
+        schema_id,
+        database_id,
+        name: function_name,
+        arg_types: arg_types.iter().map(|t| t.to_protobuf()).collect(),
+        return_type: Some(return_type.to_protobuf()),
+        language,
+        body: body.into_bytes(),
+        link_symbol,
+        link: external.link,
+        timeout_ms: external.timeout_ms,
+        max_retries: external.max_retries,
+    };
+    let catalog_writer = session.env().catalog_writer();
+    catalog_writer.create_function(function).await?;
+
+    Ok(PgResponse::empty_result(StatementType::CREATE_FUNCTION))
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_common::catalog::{DEFAULT_DATABASE_NAME, DEFAULT_SCHEMA_NAME};
+    use risingwave_common::types::DataType;
+
+    use crate::test_utils::LocalFrontend;
+
+    #[tokio::test]
+    async fn test_create_function() {
+        let frontend = LocalFrontend::new(Default::default()).await;
+        let session = frontend.session_ref();
+        let catalog_reader = session.env().catalog_reader();
+
+        let sql = r#"CREATE FUNCTION add(INT, INT) RETURNS INT LANGUAGE wasm AS '
+            (module
+              (func (export "add") (param i32 i32) (result i32)
+                (i32.add (local.get 0) (local.get 1))))'"#;
+        frontend.run_sql(sql).await.unwrap();
+
+        let function = catalog_reader
+            .read_guard()
+            .get_function_by_name(DEFAULT_DATABASE_NAME, DEFAULT_SCHEMA_NAME, "add")
+            .unwrap()
+            .clone();
+        assert_eq!(function.arg_types, vec![DataType::Int32, DataType::Int32]);
+        assert_eq!(function.return_type, DataType::Int32);
+        assert_eq!(function.link_symbol, "add");
+
+        // Duplicated names and mismatched signatures are rejected.
+        assert!(frontend.run_sql(sql).await.is_err());
+        let sql = r#"CREATE FUNCTION add2(BIGINT) RETURNS INT LANGUAGE wasm AS '
+            (module
+              (func (export "add2") (param i32) (result i32) (local.get 0)))'"#;
+        assert!(frontend.run_sql(sql).await.is_err());
+
+        // External functions require a valid link to the UDF server.
+        let sql = "CREATE FUNCTION f(INT) RETURNS INT LANGUAGE python AS 'f'";
+        assert!(frontend.run_sql(sql).await.is_err());
+        let sql =
+            "CREATE FUNCTION f(INT) RETURNS INT LANGUAGE python AS 'f' WITH (timeout_ms = 'x')";
+        assert!(frontend.run_sql(sql).await.is_err());
+
+        frontend.run_sql("DROP FUNCTION add").await.unwrap();
+        assert!(catalog_reader
+            .read_guard()
+            .get_function_by_name(DEFAULT_DATABASE_NAME, DEFAULT_SCHEMA_NAME, "add")
+            .is_err());
+    }
+}
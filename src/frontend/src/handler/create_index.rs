@@ -20,6 +20,7 @@ use itertools::Itertools;
 use pgwire::pg_response::{PgResponse, StatementType};
 use risingwave_common::error::{ErrorCode, Result, RwError};
 use risingwave_pb::catalog::Table as ProstTable;
+use risingwave_pb::user::grant_privilege::{Action, Object};
 use risingwave_sqlparser::ast::{ObjectName, OrderByExpr};
 
 use crate::binder::Binder;
@@ -28,6 +29,7 @@ use crate::optimizer::property::{FieldOrder, Order, RequiredDist};
 use crate::optimizer::{PlanRef, PlanRoot};
 use crate::session::{OptimizerContext, OptimizerContextRef, SessionImpl};
 use crate::stream_fragmenter::StreamFragmenter;
+use crate::user::user_privilege::ObjectCheckItem;
 
 pub(crate) fn gen_create_index_plan(
     session: &SessionImpl,
@@ -134,6 +136,11 @@ pub(crate) fn gen_create_index_plan(
             &index_schema_name,
             &index_table_name,
         )?;
+    session.check_privileges(&[ObjectCheckItem::new(
+        &index_schema_name,
+        Object::SchemaId(index_schema_id),
+        Action::Create,
+    )])?;
 
     let index_table = materialize
         .table()
@@ -159,7 +166,8 @@ pub async fn handle_create_index(
             columns,
         )?;
         let plan = plan.to_stream_prost();
-        let graph = StreamFragmenter::build_graph(plan);
+        let mut graph = StreamFragmenter::build_graph(plan);
+        graph.parallelism = session.config().streaming_parallelism();
 
         (graph, table)
     };
@@ -174,6 +182,8 @@ pub async fn handle_create_index(
     catalog_writer
         .create_materialized_view(table, graph)
         .await?;
+    // The index is used by the batch queries of the session, so its data should be visible.
+    session.track_write_epoch().await?;
 
     Ok(PgResponse::empty_result(StatementType::CREATE_TABLE))
 }
@@ -15,7 +15,9 @@
 use pgwire::pg_response::{PgResponse, StatementType};
 use risingwave_common::error::{ErrorCode, Result};
 use risingwave_pb::catalog::Table as ProstTable;
-use risingwave_sqlparser::ast::{ObjectName, Query};
+use risingwave_pb::stream_plan::stream_node::NodeBody;
+use risingwave_pb::user::grant_privilege::{Action, Object};
+use risingwave_sqlparser::ast::{EmitMode, ObjectName, Query, SqlOption, Value};
 
 use crate::binder::{Binder, BoundSetExpr};
 use crate::optimizer::property::RequiredDist;
@@ -23,6 +25,7 @@ use crate::optimizer::PlanRef;
 use crate::planner::Planner;
 use crate::session::{OptimizerContext, OptimizerContextRef, SessionImpl};
 use crate::stream_fragmenter::StreamFragmenter;
+use crate::user::user_privilege::ObjectCheckItem;
 
 /// Generate create MV plan, return plan and mv table info.
 pub fn gen_create_mv_plan(
@@ -30,6 +33,7 @@ pub fn gen_create_mv_plan(
     context: OptimizerContextRef,
     query: Box<Query>,
     name: ObjectName,
+    emit_mode: Option<EmitMode>,
 ) -> Result<(PlanRef, ProstTable)> {
     let (schema_name, table_name) = Binder::resolve_table_name(name)?;
     let (database_id, schema_id) = session
@@ -43,7 +47,15 @@ pub fn gen_create_mv_plan(
             session.env().catalog_reader().read_guard(),
             session.database().to_string(),
         );
-        binder.bind_query(*query)?
+        let query = binder.bind_query(*query)?;
+        let mut object_check_items = binder.object_check_items().to_vec();
+        object_check_items.push(ObjectCheckItem::new(
+            &schema_name,
+            Object::SchemaId(schema_id),
+            Action::Create,
+        ));
+        session.check_privileges(&object_check_items)?;
+        query
     };
 
     if let BoundSetExpr::Select(select) = &bound.body {
@@ -59,6 +71,7 @@ pub fn gen_create_mv_plan(
 
     let mut plan_root = Planner::new(context).plan_query(bound)?;
     plan_root.set_required_dist(RequiredDist::Any);
+    plan_root.set_emit_on_window_close(emit_mode == Some(EmitMode::OnWindowClose));
     let materialize = plan_root.gen_create_mv_plan(table_name)?;
     let table = materialize.table().to_prost(schema_id, database_id);
     let plan: PlanRef = materialize.into();
@@ -66,17 +79,119 @@ pub fn gen_create_mv_plan(
     Ok((plan, table))
 }
 
+/// The `WITH` options of a materialized view.
+#[derive(Debug, Default)]
+pub(super) struct MvOptions {
+    /// The resource group of the actors, or an empty string for the default group.
+    pub resource_group: String,
+    /// The number of actors of each non-singleton fragment, overriding the
+    /// `streaming_parallelism` of the session.
+    pub parallelism: Option<u32>,
+    /// Retention in seconds of the rows of the materialized view, or 0 to keep the rows forever.
+    pub retention_seconds: u32,
+    /// Whether the changes of each epoch are counted and stored in an internal table.
+    pub stats: bool,
+}
+
+impl MvOptions {
+    pub fn from_with_options(with_options: Vec<SqlOption>) -> Result<Self> {
+        let mut options = Self::default();
+        for option in with_options {
+            let invalid_value = |value: &Value| {
+                ErrorCode::InvalidParameterValue(format!(
+                    "invalid value of option {}: {}",
+                    option.name, value
+                ))
+            };
+            match option.name.value.to_lowercase().as_str() {
+                "resource_group" => match option.value {
+                    Value::SingleQuotedString(s) => options.resource_group = s,
+                    ref value => return Err(invalid_value(value).into()),
+                },
+                "parallelism" => {
+                    let parallelism = match &option.value {
+                        Value::Number(n, _) | Value::SingleQuotedString(n) => {
+                            n.parse::<u32>().ok().filter(|n| *n > 0)
+                        }
+                        _ => None,
+                    };
+                    options.parallelism =
+                        Some(parallelism.ok_or_else(|| invalid_value(&option.value))?);
+                }
+                "retention" => {
+                    options.retention_seconds = parse_retention(&option.value)
+                        .ok_or_else(|| invalid_value(&option.value))?;
+                }
+                "stats" => match option.value {
+                    Value::Boolean(stats) => options.stats = stats,
+                    ref value => return Err(invalid_value(value).into()),
+                },
+                _ => {
+                    return Err(ErrorCode::InvalidParameterValue(format!(
+                        "unknown option {} of materialized view",
+                        option.name
+                    ))
+                    .into())
+                }
+            }
+        }
+        Ok(options)
+    }
+}
+
+/// Parses the value of the `retention` option of a table or a materialized view into seconds. The
+/// value is either a number of seconds, or a number followed by a unit, e.g. `'7 days'`.
+pub(super) fn parse_retention(value: &Value) -> Option<u32> {
+    let value = match value {
+        Value::Number(n, _) | Value::SingleQuotedString(n) => n.trim().to_lowercase(),
+        _ => return None,
+    };
+    let (number, unit) = match value.split_once(char::is_whitespace) {
+        Some((number, unit)) => (number, unit.trim()),
+        None => (value.as_str(), "seconds"),
+    };
+    let unit_seconds = match unit.trim_end_matches('s') {
+        "second" | "sec" => 1,
+        "minute" | "min" => 60,
+        "hour" => 60 * 60,
+        "day" => 24 * 60 * 60,
+        "week" => 7 * 24 * 60 * 60,
+        _ => return None,
+    };
+    number
+        .parse::<u32>()
+        .ok()?
+        .checked_mul(unit_seconds)
+        .filter(|seconds| *seconds > 0)
+}
+
 pub async fn handle_create_mv(
     context: OptimizerContext,
     name: ObjectName,
     query: Box<Query>,
+    with_options: Vec<SqlOption>,
+    emit_mode: Option<EmitMode>,
 ) -> Result<PgResponse> {
     let session = context.session_ctx.clone();
+    let options = MvOptions::from_with_options(with_options)?;
 
     let (table, graph) = {
-        let (plan, table) = gen_create_mv_plan(&session, context.into(), query, name)?;
-        let stream_plan = plan.to_stream_prost();
-        let graph = StreamFragmenter::build_graph(stream_plan);
+        let (plan, mut table) =
+            gen_create_mv_plan(&session, context.into(), query, name, emit_mode)?;
+        table.retention_seconds = options.retention_seconds;
+        let mut stream_plan = plan.to_stream_prost();
+        if options.stats {
+            // The stream plan of a materialized view is rooted at its materialize node.
+            match stream_plan.node_body.as_mut().unwrap() {
+                NodeBody::Materialize(node) => node.collect_stats = true,
+                _ => unreachable!("the root of the plan is not a materialize node"),
+            }
+        }
+        let mut graph = StreamFragmenter::build_graph(stream_plan);
+        graph.parallelism = options
+            .parallelism
+            .unwrap_or_else(|| session.config().streaming_parallelism());
+        graph.resource_group = options.resource_group;
 
         (table, graph)
     };
@@ -85,6 +200,9 @@ pub async fn handle_create_mv(
     catalog_writer
         .create_materialized_view(table, graph)
         .await?;
+    // The data of the mview are visible to the batch queries of the session once the epoch is
+    // committed.
+    session.track_write_epoch().await?;
 
     Ok(PgResponse::empty_result(
         StatementType::CREATE_MATERIALIZED_VIEW,
@@ -196,4 +314,104 @@ pub mod tests {
             "Bind error: An alias must be specified for an expression"
         );
     }
+
+    #[tokio::test]
+    async fn test_emit_on_window_close() {
+        let frontend = LocalFrontend::new(Default::default()).await;
+        let sql = "create table t (v int, ts timestamp)";
+        frontend.run_sql(sql).await.unwrap();
+
+        let sql = "create materialized view mv1 as select window_end, count(*) as cnt from tumble(t, ts, interval '1' minute) group by window_end emit on window close";
+        frontend.run_sql(sql).await.unwrap();
+
+        // The results are not grouped by windows.
+        let sql = "create materialized view mv2 as select v, count(*) as cnt from t group by v emit on window close";
+        let err = frontend.run_sql(sql).await.unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid input syntax: EMIT ON WINDOW CLOSE requires the window_end of a TUMBLE or HOP window as a group key"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_resource_group() {
+        let frontend = LocalFrontend::new(Default::default()).await;
+        let sql = "create table t (v int)";
+        frontend.run_sql(sql).await.unwrap();
+
+        let sql =
+            "create materialized view mv1 with (resource_group = 'critical') as select v from t";
+        frontend.run_sql(sql).await.unwrap();
+
+        let sql = "create materialized view mv2 with (priority = 'high') as select v from t";
+        let err = frontend.run_sql(sql).await.unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid Parameter Value: unknown option priority of materialized view"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_parallelism() {
+        let frontend = LocalFrontend::new(Default::default()).await;
+        let sql = "create table t (v int)";
+        frontend.run_sql(sql).await.unwrap();
+
+        let sql = "create materialized view mv1 with (parallelism = 2) as select v from t";
+        frontend.run_sql(sql).await.unwrap();
+        let sql = "create materialized view mv2 with (parallelism = '2') as select v from t";
+        frontend.run_sql(sql).await.unwrap();
+
+        let sql = "create materialized view mv3 with (parallelism = 0) as select v from t";
+        let err = frontend.run_sql(sql).await.unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid Parameter Value: invalid value of option parallelism: 0"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_retention() {
+        let frontend = LocalFrontend::new(Default::default()).await;
+        let sql = "create table t (v int) with (retention = '7 days')";
+        frontend.run_sql(sql).await.unwrap();
+        let sql = "create materialized view mv1 with (retention = 3600) as select v from t";
+        frontend.run_sql(sql).await.unwrap();
+
+        let session = frontend.session_ref();
+        let catalog_reader = session.env().catalog_reader();
+        let retention_seconds = |name| {
+            catalog_reader
+                .read_guard()
+                .get_table_by_name(DEFAULT_DATABASE_NAME, DEFAULT_SCHEMA_NAME, name)
+                .unwrap()
+                .retention_seconds
+        };
+        assert_eq!(retention_seconds("t"), 7 * 24 * 60 * 60);
+        assert_eq!(retention_seconds("mv1"), 3600);
+
+        let sql =
+            "create materialized view mv2 with (retention = '1 fortnight') as select v from t";
+        let err = frontend.run_sql(sql).await.unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid Parameter Value: invalid value of option retention: '1 fortnight'"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_stats() {
+        let frontend = LocalFrontend::new(Default::default()).await;
+        let sql = "create table t (v int)";
+        frontend.run_sql(sql).await.unwrap();
+        let sql = "create materialized view mv1 with (stats = true) as select v from t";
+        frontend.run_sql(sql).await.unwrap();
+
+        let sql = "create materialized view mv2 with (stats = 'yes') as select v from t";
+        let err = frontend.run_sql(sql).await.unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid Parameter Value: invalid value of option stats: 'yes'"
+        );
+    }
 }
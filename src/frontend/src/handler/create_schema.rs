@@ -14,11 +14,13 @@
 
 use pgwire::pg_response::{PgResponse, StatementType};
 use risingwave_common::error::Result;
+use risingwave_pb::user::grant_privilege::{Action, Object};
 use risingwave_sqlparser::ast::ObjectName;
 
 use crate::binder::Binder;
 use crate::catalog::CatalogError;
 use crate::session::OptimizerContext;
+use crate::user::user_privilege::ObjectCheckItem;
 
 pub async fn handle_create_schema(
     context: OptimizerContext,
@@ -48,6 +50,11 @@ pub async fn handle_create_schema(
         }
         reader.get_database_by_name(&database_name)?.id()
     };
+    session.check_privileges(&[ObjectCheckItem::new(
+        &database_name,
+        Object::DatabaseId(db_id),
+        Action::Create,
+    )])?;
 
     let catalog_writer = session.env().catalog_writer();
     catalog_writer.create_schema(db_id, &schema_name).await?;
@@ -13,24 +13,39 @@
 // limitations under the License.
 
 use std::collections::HashMap;
+use std::str::FromStr;
+use std::time::Duration;
 
+use bytes::Bytes;
 use itertools::Itertools;
+use pgwire::pg_field_descriptor::{PgFieldDescriptor, TypeOid};
 use pgwire::pg_response::{PgResponse, StatementType};
+use pgwire::types::Row;
+use risingwave_common::catalog::ColumnDesc;
 use risingwave_common::error::ErrorCode::ProtocolError;
 use risingwave_common::error::{Result, RwError};
+use risingwave_common::types::DataType;
 use risingwave_pb::catalog::source::Info;
-use risingwave_pb::catalog::{Source as ProstSource, StreamSourceInfo};
-use risingwave_pb::plan_common::{ColumnCatalog as ProstColumnCatalog, RowFormatType};
-use risingwave_source::ProtobufParser;
+use risingwave_pb::catalog::{Source as ProstSource, SourceDedup, StreamSourceInfo};
+use risingwave_pb::plan_common::{
+    ColumnCatalog as ProstColumnCatalog, MessageMetadata, MetadataColumn, RowFormatType,
+};
+use risingwave_pb::user::grant_privilege::{Action, Object};
+use risingwave_source::{sample_source_messages, ProtobufParser};
 use risingwave_sqlparser::ast::{
-    CreateSourceStatement, ObjectName, ProtobufSchema, SourceSchema, SqlOption, Value,
+    ColumnDef, CreateSourceStatement, DataType as AstDataType, Ident, ObjectName, ProtobufSchema,
+    SourceSchema, SqlOption, Statement, StructField, Value,
 };
+use serde_json::Value as JsonValue;
 
 use super::create_table::{bind_sql_columns, gen_materialized_source_plan};
 use crate::binder::Binder;
+use crate::catalog::check_valid_column_name;
 use crate::catalog::column_catalog::ColumnCatalog;
+use crate::expr::Expr as _;
 use crate::session::{OptimizerContext, SessionImpl};
 use crate::stream_fragmenter::StreamFragmenter;
+use crate::user::user_privilege::ObjectCheckItem;
 
 pub(crate) fn make_prost_source(
     session: &SessionImpl,
@@ -44,6 +59,11 @@ pub(crate) fn make_prost_source(
         .catalog_reader()
         .read_guard()
         .check_relation_name_duplicated(session.database(), &schema_name, &name)?;
+    session.check_privileges(&[ObjectCheckItem::new(
+        &schema_name,
+        Object::SchemaId(schema_id),
+        Action::Create,
+    )])?;
 
     Ok(ProstSource {
         id: 0,
@@ -51,6 +71,7 @@ pub(crate) fn make_prost_source(
         database_id,
         name,
         info: Some(source_info),
+        owner: session.user_name().to_string(),
     })
 }
 
@@ -64,6 +85,7 @@ fn extract_protobuf_table_schema(schema: &ProtobufSchema) -> Result<Vec<ProstCol
         .map(|col| ProstColumnCatalog {
             column_desc: Some(col),
             is_hidden: false,
+            generated_expr: None,
         })
         .collect_vec())
 }
@@ -80,12 +102,414 @@ fn handle_source_with_properties(options: Vec<SqlOption>) -> Result<HashMap<Stri
         .collect()
 }
 
+/// The options of inferring the columns of a JSON source declared without any column, which are
+/// given in the `WITH` clause but not passed to the connector.
+const SCHEMA_INFER_SAMPLES: &str = "schema.infer.samples";
+const SCHEMA_INFER_TIMEOUT_MS: &str = "schema.infer.timeout.ms";
+const SCHEMA_INFER_FLATTEN: &str = "schema.infer.flatten";
+const SCHEMA_INFER_OPTIONS: &[&str] = &[
+    SCHEMA_INFER_SAMPLES,
+    SCHEMA_INFER_TIMEOUT_MS,
+    SCHEMA_INFER_FLATTEN,
+];
+
+#[derive(Debug)]
+struct SchemaInferenceOptions {
+    /// The maximum number of messages to sample from the source.
+    samples: usize,
+    /// How long to wait for the messages to be sampled.
+    timeout: Duration,
+    /// Whether to flatten nested objects into top-level columns named by the paths of their
+    /// fields, e.g. `"a.b"`, instead of inferring them as structs.
+    flatten: bool,
+}
+
+impl SchemaInferenceOptions {
+    /// Removes the options from the properties of the source.
+    fn extract(properties: &mut HashMap<String, String>) -> Result<Self> {
+        fn take<T: FromStr>(
+            properties: &mut HashMap<String, String>,
+            key: &str,
+            default: T,
+        ) -> Result<T> {
+            match properties.remove(key) {
+                Some(value) => value.trim().to_lowercase().parse().map_err(|_| {
+                    RwError::from(ProtocolError(format!("invalid {}: '{}'", key, value)))
+                }),
+                None => Ok(default),
+            }
+        }
+
+        Ok(Self {
+            samples: take(properties, SCHEMA_INFER_SAMPLES, 100)?,
+            timeout: Duration::from_millis(take(properties, SCHEMA_INFER_TIMEOUT_MS, 5000)?),
+            flatten: take(properties, SCHEMA_INFER_FLATTEN, false)?,
+        })
+    }
+}
+
+/// The type of the values of a JSON field, inferred from the sampled messages.
+#[derive(Clone, Debug, PartialEq)]
+enum JsonType {
+    /// Only nulls are sampled.
+    Null,
+    Boolean,
+    Int,
+    Float,
+    String,
+    Array(Box<JsonType>),
+    /// The fields in the order they first appear.
+    Object(Vec<(String, JsonType)>),
+}
+
+impl JsonType {
+    fn of(value: &JsonValue) -> Self {
+        match value {
+            JsonValue::Null => Self::Null,
+            JsonValue::Bool(_) => Self::Boolean,
+            JsonValue::Number(n) if n.is_i64() => Self::Int,
+            JsonValue::Number(_) => Self::Float,
+            JsonValue::String(_) => Self::String,
+            JsonValue::Array(values) => Self::Array(Box::new(
+                values.iter().map(Self::of).fold(Self::Null, Self::merge),
+            )),
+            JsonValue::Object(fields) => Self::Object(
+                fields
+                    .iter()
+                    .map(|(name, value)| (name.clone(), Self::of(value)))
+                    .collect(),
+            ),
+        }
+    }
+
+    /// Merges the types of a field sampled from different messages into one that reads both of
+    /// them. Conflicting types are merged into strings, which read the JSON text of the values.
+    fn merge(self, other: Self) -> Self {
+        match (self, other) {
+            (Self::Null, ty) | (ty, Self::Null) => ty,
+            (Self::Int, Self::Float) | (Self::Float, Self::Int) => Self::Float,
+            (Self::Array(a), Self::Array(b)) => Self::Array(Box::new(a.merge(*b))),
+            (Self::Object(mut fields), Self::Object(other_fields)) => {
+                for (name, ty) in other_fields {
+                    match fields.iter_mut().find(|(n, _)| *n == name) {
+                        Some((_, field_ty)) => {
+                            *field_ty = std::mem::replace(field_ty, Self::Null).merge(ty)
+                        }
+                        None => fields.push((name, ty)),
+                    }
+                }
+                Self::Object(fields)
+            }
+            (a, b) if a == b => a,
+            _ => Self::String,
+        }
+    }
+
+    fn to_data_type(&self) -> AstDataType {
+        match self {
+            Self::Boolean => AstDataType::Boolean,
+            Self::Int => AstDataType::BigInt(None),
+            Self::Float => AstDataType::Double,
+            Self::Null | Self::String => AstDataType::Varchar(None),
+            Self::Array(ty) => AstDataType::Array(Box::new(ty.to_element_type())),
+            // Empty objects are read as JSON text, as structs must have fields.
+            Self::Object(fields) if fields.is_empty() => AstDataType::Varchar(None),
+            Self::Object(fields) => AstDataType::Struct(
+                fields
+                    .iter()
+                    .map(|(name, ty)| StructField {
+                        name: Ident::with_quote('"', name),
+                        data_type: ty.to_data_type(),
+                    })
+                    .collect(),
+            ),
+        }
+    }
+
+    /// Objects in arrays are read as JSON text, as the columns can't describe the fields of the
+    /// elements of lists yet.
+    fn to_element_type(&self) -> AstDataType {
+        match self {
+            Self::Object(_) => AstDataType::Varchar(None),
+            Self::Array(ty) => AstDataType::Array(Box::new(ty.to_element_type())),
+            ty => ty.to_data_type(),
+        }
+    }
+}
+
+/// Infers the columns of a JSON source from the messages sampled from it. The messages that are
+/// not JSON objects are skipped.
+fn infer_json_columns(samples: &[Bytes], flatten: bool) -> Result<Vec<ColumnDef>> {
+    fn add_column(columns: &mut Vec<ColumnDef>, name: String, ty: JsonType, flatten: bool) {
+        match ty {
+            JsonType::Object(fields) if flatten && !fields.is_empty() => {
+                for (field_name, ty) in fields {
+                    add_column(columns, format!("{}.{}", name, field_name), ty, flatten);
+                }
+            }
+            ty => columns.push(ColumnDef::new(
+                Ident::with_quote('"', name),
+                ty.to_data_type(),
+                None,
+                vec![],
+            )),
+        }
+    }
+
+    let ty = samples
+        .iter()
+        .filter_map(|sample| serde_json::from_slice::<JsonValue>(sample).ok())
+        .filter(JsonValue::is_object)
+        .map(|value| JsonType::of(&value))
+        .reduce(JsonType::merge);
+    let Some(JsonType::Object(fields)) = ty else {
+        return Err(RwError::from(ProtocolError(format!(
+            "no JSON object is sampled from the {} messages to infer the columns from, please \
+             declare the columns of the source",
+            samples.len()
+        ))));
+    };
+
+    let mut columns = vec![];
+    for (name, ty) in fields {
+        add_column(&mut columns, name, ty, flatten);
+    }
+    Ok(columns)
+}
+
+/// Fills the columns of a JSON source declared without any column except the generated ones,
+/// which are inferred from the messages sampled from the source. The options of the inference are
+/// removed from the `WITH` clause.
+async fn infer_json_source_columns(stmt: &mut CreateSourceStatement) -> Result<()> {
+    let mut properties = handle_source_with_properties(stmt.with_properties.0.clone())?;
+    let options = SchemaInferenceOptions::extract(&mut properties)?;
+    stmt.with_properties
+        .0
+        .retain(|option| !SCHEMA_INFER_OPTIONS.contains(&option.name.value.as_str()));
+
+    if stmt.columns.iter().all(|column| column.data_type.is_none()) {
+        let samples = sample_source_messages(properties, options.samples, options.timeout).await?;
+        let columns = infer_json_columns(&samples, options.flatten)?;
+        stmt.columns.splice(0..0, columns);
+    }
+    Ok(())
+}
+
+/// Returns the id of the column to append to `columns`.
+fn next_column_id(columns: &[ProstColumnCatalog]) -> i32 {
+    // The nested fields of structs have their own ids.
+    columns
+        .iter()
+        .flat_map(|c| ColumnDesc::from(c.column_desc.as_ref().unwrap()).flatten())
+        .map(|c| c.column_id.get_id())
+        .max()
+        .unwrap_or(0)
+        + 1
+}
+
+/// The `WITH` option listing the metadata of the messages to expose as hidden columns, e.g.
+/// `'metadata.columns' = 'partition, offset'`, which is not passed to the connector.
+const METADATA_COLUMNS: &str = "metadata.columns";
+
+/// Returns the name and the type of the hidden column exposing `metadata`.
+fn metadata_column(metadata: MessageMetadata) -> (&'static str, DataType) {
+    match metadata {
+        MessageMetadata::Partition => ("_partition", DataType::Varchar),
+        MessageMetadata::Offset => ("_offset", DataType::Varchar),
+        MessageMetadata::Timestamp => ("_timestamp", DataType::Timestamp),
+        // Keys are not necessarily valid UTF-8, and are decoded lossily.
+        MessageMetadata::Key => ("_key", DataType::Varchar),
+    }
+}
+
+/// Appends the hidden columns exposing the metadata listed in the `metadata.columns` option, which
+/// is removed from the properties of the source.
+fn add_metadata_columns(source: &mut StreamSourceInfo) -> Result<()> {
+    let value = match source.properties.remove(METADATA_COLUMNS) {
+        Some(value) => value,
+        None => return Ok(()),
+    };
+    for item in value.split(',') {
+        let metadata = match item.trim().to_lowercase().as_str() {
+            "partition" => MessageMetadata::Partition,
+            "offset" => MessageMetadata::Offset,
+            "timestamp" => MessageMetadata::Timestamp,
+            "key" => MessageMetadata::Key,
+            _ => {
+                return Err(RwError::from(ProtocolError(format!(
+                    "invalid {}: unknown metadata '{}', expect partition, offset, timestamp or \
+                     key",
+                    METADATA_COLUMNS,
+                    item.trim()
+                ))))
+            }
+        };
+        if source
+            .metadata_columns
+            .iter()
+            .any(|m| m.metadata() == metadata)
+        {
+            continue;
+        }
+        let (name, data_type) = metadata_column(metadata);
+        if source
+            .columns
+            .iter()
+            .any(|c| c.column_desc.as_ref().unwrap().name == name)
+        {
+            return Err(RwError::from(ProtocolError(format!(
+                "column \"{}\" conflicts with the metadata column",
+                name
+            ))));
+        }
+        let column_desc = ColumnDesc::new_atomic(data_type, name, next_column_id(&source.columns));
+        source.metadata_columns.push(MetadataColumn {
+            column_index: source.columns.len() as i32,
+            metadata: metadata as i32,
+        });
+        source.columns.push(ProstColumnCatalog {
+            column_desc: Some(column_desc.to_protobuf()),
+            is_hidden: true,
+            generated_expr: None,
+        });
+    }
+    Ok(())
+}
+
+/// The `WITH` options deduplicating the rows of a source on ingestion by the listed columns, e.g.
+/// `'dedup.columns' = '_partition, _offset'`, within the TTL, which are not passed to the
+/// connector.
+const DEDUP_COLUMNS: &str = "dedup.columns";
+const DEDUP_TTL_MS: &str = "dedup.ttl.ms";
+const DEFAULT_DEDUP_TTL_MS: u64 = 24 * 60 * 60 * 1000;
+
+/// Sets up the deduplication of the rows of the source by the `dedup.*` options, which are removed
+/// from the properties of the source.
+fn add_dedup(source: &mut StreamSourceInfo) -> Result<()> {
+    let ttl_ms = source.properties.remove(DEDUP_TTL_MS);
+    let columns = match source.properties.remove(DEDUP_COLUMNS) {
+        Some(columns) => columns,
+        None if ttl_ms.is_some() => {
+            return Err(RwError::from(ProtocolError(format!(
+                "{} requires {}",
+                DEDUP_TTL_MS, DEDUP_COLUMNS
+            ))))
+        }
+        None => return Ok(()),
+    };
+    let ttl_ms = match ttl_ms {
+        Some(ttl_ms) => match ttl_ms.trim().parse::<u64>() {
+            Ok(ttl_ms) if ttl_ms > 0 => ttl_ms,
+            _ => {
+                return Err(RwError::from(ProtocolError(format!(
+                    "invalid {}: '{}'",
+                    DEDUP_TTL_MS, ttl_ms
+                ))))
+            }
+        },
+        None => DEFAULT_DEDUP_TTL_MS,
+    };
+    let column_ids = columns
+        .split(',')
+        .map(|name| {
+            let name = name.trim().to_lowercase();
+            source
+                .columns
+                .iter()
+                .map(|c| c.column_desc.as_ref().unwrap())
+                .find(|c| c.name == name)
+                .map(|c| c.column_id)
+                .ok_or_else(|| {
+                    RwError::from(ProtocolError(format!(
+                        "invalid {}: column \"{}\" not found",
+                        DEDUP_COLUMNS, name
+                    )))
+                })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    source.dedup = Some(SourceDedup {
+        column_ids: column_ids.into_iter().unique().collect(),
+        ttl_ms,
+    });
+    Ok(())
+}
+
+/// Binds the generated columns of a source, which are appended to the other columns, and are
+/// computed from them when the messages are ingested.
+fn bind_generated_columns(
+    session: &SessionImpl,
+    columns: &mut Vec<ProstColumnCatalog>,
+    generated_columns: Vec<ColumnDef>,
+) -> Result<()> {
+    if generated_columns.is_empty() {
+        return Ok(());
+    }
+    let column_descs = columns
+        .iter()
+        .map(|c| ColumnDesc::from(c.column_desc.as_ref().unwrap()))
+        .collect_vec();
+    let mut next_column_id = next_column_id(columns);
+
+    let mut binder = Binder::new(
+        session.env().catalog_reader().read_guard(),
+        session.database().to_string(),
+    );
+    for column in generated_columns {
+        check_valid_column_name(&column.name.value)?;
+        let expr = column.generated_expr().unwrap().clone();
+        let expr = binder.bind_generated_column(&column_descs, expr)?;
+        let column_desc =
+            ColumnDesc::new_atomic(expr.return_type(), &column.name.value, next_column_id);
+        columns.push(ProstColumnCatalog {
+            column_desc: Some(column_desc.to_protobuf()),
+            is_hidden: false,
+            generated_expr: Some(expr.to_expr_proto()),
+        });
+        next_column_id += 1;
+    }
+    Ok(())
+}
+
+/// Handles `EXPLAIN CREATE SOURCE`, which shows the statement with the inferred columns of a JSON
+/// source declared without any column, without creating the source.
+pub async fn handle_explain_create_source(
+    is_materialized: bool,
+    mut stmt: CreateSourceStatement,
+) -> Result<PgResponse> {
+    if matches!(stmt.source_schema, SourceSchema::Json) {
+        infer_json_source_columns(&mut stmt).await?;
+    }
+    let ddl = Statement::CreateSource {
+        is_materialized,
+        stmt,
+    }
+    .to_string();
+
+    Ok(PgResponse::new(
+        StatementType::EXPLAIN,
+        1,
+        vec![Row::new(vec![Some(ddl)])],
+        vec![PgFieldDescriptor::new(
+            "QUERY PLAN".to_owned(),
+            TypeOid::Varchar,
+        )],
+    ))
+}
+
 pub async fn handle_create_source(
     context: OptimizerContext,
     is_materialized: bool,
-    stmt: CreateSourceStatement,
+    mut stmt: CreateSourceStatement,
 ) -> Result<PgResponse> {
-    let source = match &stmt.source_schema {
+    if matches!(stmt.source_schema, SourceSchema::Json) {
+        infer_json_source_columns(&mut stmt).await?;
+    }
+    let (generated_columns, columns): (Vec<_>, Vec<_>) = stmt
+        .columns
+        .into_iter()
+        .partition(|column| column.data_type.is_none());
+
+    let mut source = match &stmt.source_schema {
         SourceSchema::Protobuf(protobuf_schema) => {
             let mut columns = vec![ColumnCatalog::row_id_column().to_protobuf()];
             columns.extend(extract_protobuf_table_schema(protobuf_schema)?.into_iter());
@@ -96,6 +520,8 @@ pub async fn handle_create_source(
                 row_id_index: 0,
                 columns,
                 pk_column_ids: vec![0],
+                metadata_columns: vec![],
+                dedup: None,
             }
         }
         SourceSchema::Json => StreamSourceInfo {
@@ -103,19 +529,25 @@ pub async fn handle_create_source(
             row_format: RowFormatType::Json as i32,
             row_schema_location: "".to_string(),
             row_id_index: 0,
-            columns: bind_sql_columns(stmt.columns)?,
+            columns: bind_sql_columns(columns)?,
             pk_column_ids: vec![0],
+            metadata_columns: vec![],
+            dedup: None,
         },
     };
+    add_metadata_columns(&mut source)?;
 
     let session = context.session_ctx.clone();
+    bind_generated_columns(&session, &mut source.columns, generated_columns)?;
+    add_dedup(&mut source)?;
     let source = make_prost_source(&session, stmt.source_name, Info::StreamSource(source))?;
     let catalog_writer = session.env().catalog_writer();
     if is_materialized {
         let (graph, table) = {
             let (plan, table) = gen_materialized_source_plan(context.into(), source.clone())?;
             let plan = plan.to_stream_prost();
-            let graph = StreamFragmenter::build_graph(plan);
+            let mut graph = StreamFragmenter::build_graph(plan);
+            graph.parallelism = session.config().streaming_parallelism();
 
             (graph, table)
         };
@@ -133,9 +565,14 @@ pub async fn handle_create_source(
 pub mod tests {
     use std::collections::HashMap;
 
+    use bytes::Bytes;
+    use futures::StreamExt;
+    use itertools::Itertools;
     use risingwave_common::catalog::{DEFAULT_DATABASE_NAME, DEFAULT_SCHEMA_NAME};
     use risingwave_common::types::DataType;
+    use risingwave_pb::catalog::SourceDedup;
 
+    use super::infer_json_columns;
     use crate::catalog::row_id_column_name;
     use crate::test_utils::{create_proto_file, LocalFrontend, PROTO_FILE_DATA};
 
@@ -193,4 +630,270 @@ pub mod tests {
         };
         assert_eq!(columns, expected_columns);
     }
+
+    #[test]
+    fn test_infer_json_columns() {
+        let samples = [
+            r#"{"id":1,"price":10,"tags":["a"],"user":{"name":"x","age":null}}"#,
+            r#"{"id":2,"price":9.5,"tags":[],"user":{"name":"y","age":20},"extra":[{"k":1}]}"#,
+            r#"{"id":"3","note":{}}"#,
+            r#"not json"#,
+            r#"[1, 2]"#,
+        ]
+        .into_iter()
+        .map(Bytes::from)
+        .collect_vec();
+        let infer = |flatten| {
+            infer_json_columns(&samples, flatten)
+                .unwrap()
+                .iter()
+                .map(ToString::to_string)
+                .collect_vec()
+        };
+
+        assert_eq!(
+            infer(false),
+            vec![
+                r#""id" CHARACTER VARYING"#,
+                r#""price" DOUBLE"#,
+                r#""tags" CHARACTER VARYING[]"#,
+                r#""user" STRUCT<"name" CHARACTER VARYING, "age" BIGINT>"#,
+                r#""extra" CHARACTER VARYING[]"#,
+                r#""note" CHARACTER VARYING"#,
+            ]
+        );
+        assert_eq!(
+            infer(true),
+            vec![
+                r#""id" CHARACTER VARYING"#,
+                r#""price" DOUBLE"#,
+                r#""tags" CHARACTER VARYING[]"#,
+                r#""user.name" CHARACTER VARYING"#,
+                r#""user.age" BIGINT"#,
+                r#""extra" CHARACTER VARYING[]"#,
+                r#""note" CHARACTER VARYING"#,
+            ]
+        );
+
+        assert!(infer_json_columns(&[Bytes::from("not json")], false).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_create_source_with_inferred_columns() {
+        let frontend = LocalFrontend::new(Default::default()).await;
+        let with_properties = "WITH ('connector' = 'nexmark', 'nexmark.table.type' = 'Bid', \
+                               'schema.infer.samples' = '10')";
+
+        let mut explain = frontend
+            .run_sql(format!(
+                "EXPLAIN CREATE SOURCE bid {} ROW FORMAT JSON",
+                with_properties
+            ))
+            .await
+            .unwrap();
+        let rows = explain.values_stream().next().await.unwrap().unwrap();
+        assert_eq!(
+            rows[0][0].as_deref(),
+            Some(
+                "CREATE SOURCE bid (\"auction\" BIGINT, \"bidder\" BIGINT, \"price\" BIGINT, \
+                 \"b_date_time\" CHARACTER VARYING) WITH ('connector' = 'nexmark', \
+                 'nexmark.table.type' = 'Bid') ROW FORMAT JSON"
+            )
+        );
+
+        frontend
+            .run_sql(format!(
+                "CREATE SOURCE bid {} ROW FORMAT JSON",
+                with_properties
+            ))
+            .await
+            .unwrap();
+        let session = frontend.session_ref();
+        let source = session
+            .env()
+            .catalog_reader()
+            .read_guard()
+            .get_source_by_name(DEFAULT_DATABASE_NAME, DEFAULT_SCHEMA_NAME, "bid")
+            .unwrap()
+            .clone();
+        let columns = source
+            .columns
+            .iter()
+            .map(|c| (c.name(), c.data_type().clone()))
+            .collect_vec();
+        assert_eq!(
+            columns,
+            vec![
+                (row_id_column_name().as_str(), DataType::Int64),
+                ("auction", DataType::Int64),
+                ("bidder", DataType::Int64),
+                ("price", DataType::Int64),
+                ("b_date_time", DataType::Varchar),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_create_source_with_generated_columns() {
+        let frontend = LocalFrontend::new(Default::default()).await;
+        frontend
+            .run_sql(
+                "CREATE SOURCE s (v1 INT, v2 AS v1 + 1, proc_time AS PROCTIME()) \
+                 WITH ('kafka.topic' = 'abc', 'kafka.servers' = 'localhost:1001') \
+                 ROW FORMAT JSON",
+            )
+            .await
+            .unwrap();
+        let session = frontend.session_ref();
+        let source = session
+            .env()
+            .catalog_reader()
+            .read_guard()
+            .get_source_by_name(DEFAULT_DATABASE_NAME, DEFAULT_SCHEMA_NAME, "s")
+            .unwrap()
+            .clone();
+        let columns = source
+            .columns
+            .iter()
+            .map(|c| (c.name(), c.data_type().clone()))
+            .collect_vec();
+        assert_eq!(
+            columns,
+            vec![
+                (row_id_column_name().as_str(), DataType::Int64),
+                ("v1", DataType::Int32),
+                ("v2", DataType::Int32),
+                ("proc_time", DataType::Timestamp),
+            ]
+        );
+
+        assert!(frontend
+            .run_sql(
+                "CREATE SOURCE s2 (v1 INT, v2 AS count(v1)) \
+                 WITH ('kafka.topic' = 'abc', 'kafka.servers' = 'localhost:1001') \
+                 ROW FORMAT JSON",
+            )
+            .await
+            .is_err());
+        assert!(frontend
+            .run_sql("CREATE TABLE t (v1 INT, v2 AS v1 + 1)")
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_create_source_with_metadata_columns() {
+        let frontend = LocalFrontend::new(Default::default()).await;
+        frontend
+            .run_sql(
+                "CREATE SOURCE s (v1 INT, ts AS _timestamp) \
+                 WITH ('kafka.topic' = 'abc', 'kafka.servers' = 'localhost:1001', \
+                 'metadata.columns' = 'partition, offset, timestamp') \
+                 ROW FORMAT JSON",
+            )
+            .await
+            .unwrap();
+        let session = frontend.session_ref();
+        let source = session
+            .env()
+            .catalog_reader()
+            .read_guard()
+            .get_source_by_name(DEFAULT_DATABASE_NAME, DEFAULT_SCHEMA_NAME, "s")
+            .unwrap()
+            .clone();
+        let columns = source
+            .columns
+            .iter()
+            .map(|c| (c.name(), c.data_type().clone(), c.is_hidden))
+            .collect_vec();
+        assert_eq!(
+            columns,
+            vec![
+                (row_id_column_name().as_str(), DataType::Int64, true),
+                ("v1", DataType::Int32, false),
+                ("_partition", DataType::Varchar, true),
+                ("_offset", DataType::Varchar, true),
+                ("_timestamp", DataType::Timestamp, true),
+                ("ts", DataType::Timestamp, false),
+            ]
+        );
+
+        assert!(frontend
+            .run_sql(
+                "CREATE SOURCE s2 (v1 INT) \
+                 WITH ('kafka.topic' = 'abc', 'metadata.columns' = 'headers') \
+                 ROW FORMAT JSON",
+            )
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_create_source_with_dedup() {
+        let frontend = LocalFrontend::new(Default::default()).await;
+        frontend
+            .run_sql(
+                "CREATE SOURCE s (v1 INT, v2 INT) \
+                 WITH ('kafka.topic' = 'abc', 'kafka.servers' = 'localhost:1001', \
+                 'dedup.columns' = 'v1', 'dedup.ttl.ms' = '60000') \
+                 ROW FORMAT JSON",
+            )
+            .await
+            .unwrap();
+        let session = frontend.session_ref();
+        let source = session
+            .env()
+            .catalog_reader()
+            .read_guard()
+            .get_source_by_name(DEFAULT_DATABASE_NAME, DEFAULT_SCHEMA_NAME, "s")
+            .unwrap()
+            .clone();
+        let v1 = source.columns.iter().find(|c| c.name() == "v1").unwrap();
+        assert_eq!(
+            source.dedup,
+            Some(SourceDedup {
+                column_ids: vec![v1.column_id().get_id()],
+                ttl_ms: 60000,
+            })
+        );
+
+        let mut explain = frontend
+            .run_sql("EXPLAIN CREATE MATERIALIZED VIEW mv AS SELECT v2 FROM s")
+            .await
+            .unwrap();
+        let mut plan = vec![];
+        while let Some(rows) = explain.values_stream().next().await {
+            plan.extend(rows.unwrap().into_iter().map(|row| row[0].clone().unwrap()));
+        }
+        assert!(plan
+            .iter()
+            .any(|line| line.contains("StreamDedup { dedup_cols: [v1], ttl_ms: 60000 }")));
+
+        assert!(frontend
+            .run_sql(
+                "CREATE SOURCE s2 (v1 INT) \
+                 WITH ('kafka.topic' = 'abc', 'dedup.columns' = 'v3') \
+                 ROW FORMAT JSON",
+            )
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_batch_read_source() {
+        let frontend = LocalFrontend::new(Default::default()).await;
+        frontend
+            .run_sql(
+                "CREATE SOURCE s (v1 INT, v2 INT) \
+                 WITH ('kafka.topic' = 'abc', 'kafka.servers' = 'localhost:1001') \
+                 ROW FORMAT JSON",
+            )
+            .await
+            .unwrap();
+        let output = frontend
+            .query_formatted_result("EXPLAIN SELECT v1 FROM s WHERE v2 > 1")
+            .await
+            .join("\n");
+        assert!(output.contains("BatchSource { source: s"), "{}", output);
+    }
 }
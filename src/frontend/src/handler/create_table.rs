@@ -18,16 +18,17 @@ use fixedbitset::FixedBitSet;
 use itertools::Itertools;
 use pgwire::pg_response::{PgResponse, StatementType};
 use risingwave_common::catalog::{ColumnDesc, ColumnId};
-use risingwave_common::error::Result;
+use risingwave_common::error::{ErrorCode, Result};
 use risingwave_pb::catalog::source::Info;
 use risingwave_pb::catalog::{Source as ProstSource, Table as ProstTable, TableSourceInfo};
 use risingwave_pb::plan_common::ColumnCatalog;
-use risingwave_sqlparser::ast::{ColumnDef, DataType as AstDataType, ObjectName};
+use risingwave_sqlparser::ast::{ColumnDef, DataType as AstDataType, ObjectName, SqlOption};
 
+use super::create_mv::parse_retention;
 use super::create_source::make_prost_source;
 use crate::binder::expr::{bind_data_type, bind_struct_field};
 use crate::catalog::{check_valid_column_name, row_id_column_desc};
-use crate::optimizer::plan_node::{LogicalSource, StreamSource};
+use crate::optimizer::plan_node::{LogicalSource, ToStream};
 use crate::optimizer::property::{Order, RequiredDist};
 use crate::optimizer::{PlanRef, PlanRoot};
 use crate::session::{OptimizerContext, OptimizerContextRef, SessionImpl};
@@ -44,7 +45,16 @@ pub fn bind_sql_columns(columns: Vec<ColumnDef>) -> Result<Vec<ColumnCatalog>> {
         // Then user columns.
         for (i, column) in columns.into_iter().enumerate() {
             check_valid_column_name(&column.name.value)?;
-            let field_descs = if let AstDataType::Struct(fields) = &column.data_type {
+            let data_type = column.data_type.as_ref().ok_or_else(|| {
+                ErrorCode::NotImplemented(
+                    format!(
+                        "generated column \"{}\" is only supported on sources",
+                        column.name.value
+                    ),
+                    None.into(),
+                )
+            })?;
+            let field_descs = if let AstDataType::Struct(fields) = data_type {
                 fields
                     .iter()
                     .map(bind_struct_field)
@@ -53,7 +63,7 @@ pub fn bind_sql_columns(columns: Vec<ColumnDef>) -> Result<Vec<ColumnCatalog>> {
                 vec![]
             };
             column_descs.push(ColumnDesc {
-                data_type: bind_data_type(&column.data_type)?,
+                data_type: bind_data_type(data_type)?,
                 column_id: ColumnId::new((i + 1) as i32),
                 name: column.name.value,
                 field_descs,
@@ -69,6 +79,7 @@ pub fn bind_sql_columns(columns: Vec<ColumnDef>) -> Result<Vec<ColumnCatalog>> {
         .map(|(i, c)| ColumnCatalog {
             column_desc: c.to_protobuf().into(),
             is_hidden: i == 0, // the row id column is hidden
+            generated_expr: None,
         })
         .collect_vec();
     Ok(columns_catalog)
@@ -99,8 +110,7 @@ pub(crate) fn gen_materialized_source_plan(
 ) -> Result<(PlanRef, ProstTable)> {
     let materialize = {
         // Manually assemble the materialization plan for the table.
-        let source_node: PlanRef =
-            StreamSource::new(LogicalSource::new(Rc::new((&source).into()), context)).into();
+        let source_node = LogicalSource::new(Rc::new((&source).into()), context).to_stream()?;
         let mut required_cols = FixedBitSet::with_capacity(source_node.schema().len());
         required_cols.toggle_range(..);
         required_cols.toggle(0);
@@ -123,18 +133,48 @@ pub(crate) fn gen_materialized_source_plan(
     Ok((materialize.into(), table))
 }
 
+/// Returns the retention in seconds of the rows of the table in its `WITH` options, or 0 to keep
+/// the rows forever.
+fn table_retention_seconds(with_options: Vec<SqlOption>) -> Result<u32> {
+    let mut retention_seconds = 0;
+    for option in with_options {
+        match option.name.value.to_lowercase().as_str() {
+            "retention" => {
+                retention_seconds = parse_retention(&option.value).ok_or_else(|| {
+                    ErrorCode::InvalidParameterValue(format!(
+                        "invalid value of option {}: {}",
+                        option.name, option.value
+                    ))
+                })?;
+            }
+            _ => {
+                return Err(ErrorCode::InvalidParameterValue(format!(
+                    "unknown option {} of table",
+                    option.name
+                ))
+                .into())
+            }
+        }
+    }
+    Ok(retention_seconds)
+}
+
 pub async fn handle_create_table(
     context: OptimizerContext,
     table_name: ObjectName,
     columns: Vec<ColumnDef>,
+    with_options: Vec<SqlOption>,
 ) -> Result<PgResponse> {
     let session = context.session_ctx.clone();
+    let retention_seconds = table_retention_seconds(with_options)?;
 
     let (graph, source, table) = {
-        let (plan, source, table) =
+        let (plan, source, mut table) =
             gen_create_table_plan(&session, context.into(), table_name.clone(), columns)?;
+        table.retention_seconds = retention_seconds;
         let plan = plan.to_stream_prost();
-        let graph = StreamFragmenter::build_graph(plan);
+        let mut graph = StreamFragmenter::build_graph(plan);
+        graph.parallelism = session.config().streaming_parallelism();
 
         (graph, source, table)
     };
@@ -46,7 +46,7 @@ fn make_prost_user_info(name: ObjectName, options: &CreateUserWithOptions) -> Re
             }
             CreateUserOption::Password(opt) => {
                 if let Some(password) = opt {
-                    user_info.auth_info = try_extract(&user_info.name, &password.0);
+                    user_info.auth_info = try_extract(&user_info.name, &password.0)?;
                 }
             }
         }
@@ -0,0 +1,628 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+
+use futures::future::try_join_all;
+use futures::StreamExt;
+use itertools::Itertools;
+use pgwire::pg_field_descriptor::{PgFieldDescriptor, TypeOid};
+use pgwire::pg_response::{PgResponse, StatementType};
+use pgwire::types::Row;
+use risingwave_batch::executor::BoxedDataChunkStream;
+use risingwave_common::array::{Op, StreamChunk};
+use risingwave_common::error::{ErrorCode, Result, RwError, ToRwResult};
+use risingwave_pb::data::StreamChunk as ProstStreamChunk;
+use risingwave_pb::stream_plan::FragmentType;
+use risingwave_pb::stream_service::{ChangelogBatch, ChangelogFormat, SubscribeChangelogRequest};
+use risingwave_sqlparser::ast::{Ident, ObjectName, Query, Statement};
+use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+use tonic::Streaming;
+
+use crate::binder::{Binder, ParamValues};
+use crate::handler::query;
+use crate::handler::util::{data_type_to_type_oid, pg_value_format, to_pg_field, to_pg_rows};
+use crate::scheduler::HummockSnapshotManagerRef;
+use crate::session::OptimizerContext;
+
+/// A cursor declared by `DECLARE ... CURSOR FOR`, or by `DECLARE ... SUBSCRIPTION CURSOR FOR`
+/// to tail the changes of a materialized view.
+struct Cursor {
+    /// Tells a cursor from a later one of the same name.
+    id: u64,
+    source: CursorSource,
+    pg_descs: Vec<PgFieldDescriptor>,
+    last_fetched: Instant,
+}
+
+enum CursorSource {
+    /// The result of a query, which keeps the query running and its snapshot pinned until the
+    /// cursor is closed, so that the rows fetched across `FETCH`es are consistent.
+    Query {
+        data_stream: BoxedDataChunkStream,
+        /// Rows pulled from `data_stream` but not fetched yet.
+        pending_rows: VecDeque<Row>,
+    },
+    /// The changes of a materialized view after an epoch.
+    Subscription(Subscription),
+}
+
+impl CursorSource {
+    fn query(data_stream: BoxedDataChunkStream) -> Self {
+        CursorSource::Query {
+            data_stream,
+            pending_rows: VecDeque::new(),
+        }
+    }
+}
+
+impl Cursor {
+    /// Fetches at most `count` rows, or all the remaining rows if `count` is `None`. A
+    /// subscription cursor only returns the changes committed so far without waiting for more.
+    async fn fetch(&mut self, count: Option<u64>) -> Result<Vec<Row>> {
+        self.last_fetched = Instant::now();
+        let count = count.map_or(usize::MAX, |count| count as usize);
+        let (data_stream, pending_rows) = match &mut self.source {
+            CursorSource::Query {
+                data_stream,
+                pending_rows,
+            } => (data_stream, pending_rows),
+            CursorSource::Subscription(subscription) => return subscription.fetch(count).await,
+        };
+        let mut rows = vec![];
+        while rows.len() < count {
+            if let Some(row) = pending_rows.pop_front() {
+                rows.push(row);
+                continue;
+            }
+            match data_stream.next().await {
+                Some(chunk) => pending_rows.extend(to_pg_rows(chunk?)),
+                None => break,
+            }
+        }
+        Ok(rows)
+    }
+}
+
+/// The changes of a materialized view, merged from the changelog of all the compute nodes running
+/// its materialize actors. The changes of an epoch are fetched once they're received from all the
+/// compute nodes and the epoch is committed, in the order of epochs.
+struct Subscription {
+    /// Number of compute nodes subscribed to.
+    workers: usize,
+    /// Indices of the visible columns of the materialized view in the changes.
+    column_indices: Vec<usize>,
+    received: Arc<parking_lot::Mutex<ReceivedChanges>>,
+    /// Rows of the epochs taken from `received` but not fetched yet.
+    pending_rows: VecDeque<Row>,
+    hummock_snapshot_manager: HummockSnapshotManagerRef,
+    /// Receives the changes into `received` in the background.
+    receiver: JoinHandle<()>,
+}
+
+#[derive(Debug, Default)]
+struct ReceivedChanges {
+    /// Changes of each epoch, with the number of compute nodes they're received from.
+    epochs: BTreeMap<u64, (usize, Vec<ProstStreamChunk>)>,
+    /// Set once the changelog from any compute node fails, after which the changes received so
+    /// far are still fetched.
+    error: Option<String>,
+}
+
+impl Subscription {
+    async fn fetch(&mut self, count: usize) -> Result<Vec<Row>> {
+        let committed_epoch = self.hummock_snapshot_manager.latest_epoch().await;
+        let (ready, error) = {
+            let mut received = self.received.lock();
+            let ready_epochs = received
+                .epochs
+                .iter()
+                .take_while(|(&epoch, (workers, _))| {
+                    epoch <= committed_epoch && *workers >= self.workers
+                })
+                .map(|(&epoch, _)| epoch)
+                .collect_vec();
+            let ready = ready_epochs
+                .into_iter()
+                .map(|epoch| (epoch, received.epochs.remove(&epoch).unwrap().1))
+                .collect_vec();
+            (ready, received.error.clone())
+        };
+        for (epoch, chunks) in ready {
+            for chunk in chunks {
+                let rows = self.format_changes(epoch, &chunk)?;
+                self.pending_rows.extend(rows);
+            }
+        }
+        if let Some(error) = error && self.pending_rows.is_empty() {
+            return Err(ErrorCode::InternalError(format!("subscription failed: {}", error)).into());
+        }
+        let count = count.min(self.pending_rows.len());
+        Ok(self.pending_rows.drain(..count).collect())
+    }
+
+    /// Converts the changes to rows of the visible columns, followed by the op and the epoch.
+    fn format_changes(&self, epoch: u64, chunk: &ProstStreamChunk) -> Result<Vec<Row>> {
+        let (data, ops) = StreamChunk::from_protobuf(chunk)?.compact()?.into_parts();
+        let rows = data
+            .rows()
+            .zip_eq(ops)
+            .map(|(row, op)| {
+                let mut values = self
+                    .column_indices
+                    .iter()
+                    .map(|&idx| row.value_at(idx).map(pg_value_format))
+                    .collect_vec();
+                values.push(Some(op_name(op).to_owned()));
+                values.push(Some(epoch.to_string()));
+                Row::new(values)
+            })
+            .collect();
+        Ok(rows)
+    }
+}
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        self.receiver.abort();
+    }
+}
+
+fn op_name(op: Op) -> &'static str {
+    match op {
+        Op::Insert => "insert",
+        Op::Delete => "delete",
+        Op::UpdateDelete => "update_delete",
+        Op::UpdateInsert => "update_insert",
+    }
+}
+
+/// Merges the changelog from the compute nodes into `received`, skipping the epochs not after
+/// `start_after_epoch`.
+async fn receive_changes(
+    changelogs: Vec<Streaming<ChangelogBatch>>,
+    received: Arc<parking_lot::Mutex<ReceivedChanges>>,
+    start_after_epoch: u64,
+) {
+    let mut batches = futures::stream::select_all(changelogs);
+    let error = loop {
+        match batches.next().await {
+            Some(Ok(batch)) => {
+                if batch.epoch <= start_after_epoch {
+                    continue;
+                }
+                let mut received = received.lock();
+                let (workers, chunks) = received.epochs.entry(batch.epoch).or_default();
+                *workers += 1;
+                chunks.extend(batch.chunks);
+            }
+            Some(Err(e)) => break e.to_string(),
+            None => break "the changelog is closed by a compute node".to_string(),
+        }
+    };
+    tracing::warn!("subscription stopped: {}", error);
+    received.lock().error = Some(error);
+}
+
+/// The cursors declared in a session, keyed by their names. A cursor is closed once it's not
+/// fetched from for longer than `CURSOR_IDLE_TIMEOUT`.
+#[derive(Default)]
+pub struct CursorManager {
+    cursors: Mutex<HashMap<String, Cursor>>,
+    next_id: AtomicU64,
+}
+
+impl CursorManager {
+    async fn add_cursor(
+        self: &Arc<Self>,
+        name: String,
+        source: CursorSource,
+        pg_descs: Vec<PgFieldDescriptor>,
+        idle_timeout: Option<Duration>,
+    ) -> Result<()> {
+        let mut cursors = self.cursors.lock().await;
+        if cursors.contains_key(&name) {
+            return Err(cursor_error(&name, "already exists"));
+        }
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        cursors.insert(
+            name.clone(),
+            Cursor {
+                id,
+                source,
+                pg_descs,
+                last_fetched: Instant::now(),
+            },
+        );
+        if let Some(idle_timeout) = idle_timeout {
+            tokio::spawn(Self::close_when_idle(
+                Arc::downgrade(self),
+                name,
+                id,
+                idle_timeout,
+            ));
+        }
+        Ok(())
+    }
+
+    /// Closes the cursor once it's idle for `idle_timeout`. The task exits once the cursor is
+    /// closed by others, or the session is gone.
+    async fn close_when_idle(manager: Weak<Self>, name: String, id: u64, idle_timeout: Duration) {
+        let mut wait = idle_timeout;
+        loop {
+            tokio::time::sleep(wait).await;
+            let manager = match manager.upgrade() {
+                Some(manager) => manager,
+                None => return,
+            };
+            let mut cursors = manager.cursors.lock().await;
+            let idle = match cursors.get(&name) {
+                Some(cursor) if cursor.id == id => cursor.last_fetched.elapsed(),
+                _ => return,
+            };
+            if idle >= idle_timeout {
+                cursors.remove(&name);
+                tracing::info!("cursor {} is closed after being idle for {:?}", name, idle);
+                return;
+            }
+            wait = idle_timeout - idle;
+        }
+    }
+
+    async fn fetch(&self, name: &str, count: Option<u64>) -> Result<PgResponse> {
+        let mut cursors = self.cursors.lock().await;
+        let cursor = cursors
+            .get_mut(name)
+            .ok_or_else(|| cursor_error(name, "does not exist"))?;
+        let rows = cursor.fetch(count).await?;
+        Ok(PgResponse::new(
+            StatementType::FETCH,
+            rows.len() as i32,
+            rows,
+            cursor.pg_descs.clone(),
+        ))
+    }
+
+    /// Closes the cursor `name`, or all the cursors if `name` is `None`.
+    async fn close(&self, name: Option<&str>) -> Result<()> {
+        let mut cursors = self.cursors.lock().await;
+        match name {
+            Some(name) => {
+                cursors
+                    .remove(name)
+                    .ok_or_else(|| cursor_error(name, "does not exist"))?;
+            }
+            None => cursors.clear(),
+        }
+        Ok(())
+    }
+}
+
+fn cursor_error(name: &str, reason: &str) -> RwError {
+    ErrorCode::InvalidInputSyntax(format!("cursor \"{}\" {}", name, reason)).into()
+}
+
+/// Declares a cursor over the result of `query`. The query starts running right away, and its
+/// rows are pulled as they're fetched.
+pub async fn handle_declare_cursor(
+    context: OptimizerContext,
+    cursor_name: Ident,
+    query: Box<Query>,
+    params: ParamValues,
+) -> Result<PgResponse> {
+    let session = context.session_ctx.clone();
+    let (data_stream, schema) =
+        query::execute_query(context, Statement::Query(query), params).await?;
+    let pg_descs = schema.fields().iter().map(to_pg_field).collect();
+    let idle_timeout = session.config().cursor_idle_timeout();
+    session
+        .cursor_manager()
+        .add_cursor(
+            cursor_name.value,
+            CursorSource::query(data_stream),
+            pg_descs,
+            idle_timeout,
+        )
+        .await?;
+    Ok(PgResponse::empty_result(StatementType::DECLARE_CURSOR))
+}
+
+/// Declares a cursor over the changes of materialized view `mview_name` after epoch `since`, or
+/// after the latest committed epoch if `since` is `None`. Each row fetched is a change of the
+/// visible columns, followed by the `op` of the change and the `epoch` it's committed in.
+///
+/// Changes are collected by the compute nodes only after the materialized view is subscribed for
+/// the first time, and only the recent epochs are retained for `SINCE`.
+pub async fn handle_declare_subscription_cursor(
+    context: OptimizerContext,
+    cursor_name: Ident,
+    mview_name: ObjectName,
+    since: Option<u64>,
+) -> Result<PgResponse> {
+    let session = context.session_ctx;
+    let (schema_name, table_name) = Binder::resolve_table_name(mview_name)?;
+    let (table_id, column_indices, mut pg_descs) = {
+        let reader = session.env().catalog_reader().read_guard();
+        let table = reader.get_table_by_name(session.database(), &schema_name, &table_name)?;
+        if table.associated_source_id().is_some() || table.is_internal {
+            return Err(ErrorCode::InvalidInputSyntax(format!(
+                "\"{}\" is not a materialized view",
+                table_name
+            ))
+            .into());
+        }
+        let (column_indices, pg_descs): (Vec<_>, Vec<_>) = table
+            .columns()
+            .iter()
+            .enumerate()
+            .filter(|(_, column)| !column.is_hidden())
+            .map(|(idx, column)| {
+                let type_oid = data_type_to_type_oid(column.data_type().clone());
+                (
+                    idx,
+                    PgFieldDescriptor::new(column.name().to_owned(), type_oid),
+                )
+            })
+            .unzip();
+        (table.id().table_id, column_indices, pg_descs)
+    };
+    pg_descs.push(PgFieldDescriptor::new("op".to_owned(), TypeOid::Varchar));
+    pg_descs.push(PgFieldDescriptor::new("epoch".to_owned(), TypeOid::BigInt));
+
+    // Only the materialize actors report the changes, so subscribe to the compute nodes running
+    // them.
+    let table_fragments = session
+        .env()
+        .meta_client()
+        .list_table_fragments(&[table_id])
+        .await?
+        .into_iter()
+        .next()
+        .ok_or_else(|| {
+            ErrorCode::InternalError(format!("fragments of \"{}\" not found", table_name))
+        })?;
+    let mut worker_ids = HashSet::new();
+    for fragment in table_fragments.fragments.values() {
+        if fragment.fragment_type() != FragmentType::Sink {
+            continue;
+        }
+        for actor in &fragment.actors {
+            let status = table_fragments
+                .actor_status
+                .get(&actor.actor_id)
+                .ok_or_else(|| {
+                    ErrorCode::InternalError(format!(
+                        "status of actor {} not found",
+                        actor.actor_id
+                    ))
+                })?;
+            worker_ids.insert(status.get_parallel_unit()?.worker_node_id);
+        }
+    }
+    let workers = session
+        .env()
+        .worker_node_manager()
+        .list_worker_nodes()
+        .into_iter()
+        .filter(|worker| worker_ids.contains(&worker.id))
+        .collect_vec();
+    if workers.len() < worker_ids.len() {
+        return Err(ErrorCode::InternalError(format!(
+            "compute nodes running \"{}\" not found",
+            table_name
+        ))
+        .into());
+    }
+
+    let hummock_snapshot_manager = session.env().hummock_snapshot_manager().clone();
+    let start_after_epoch = match since {
+        Some(since) => since,
+        None => hummock_snapshot_manager.latest_epoch().await,
+    };
+    let request = SubscribeChangelogRequest {
+        table_id,
+        // Without `SINCE`, the retained epochs are received but skipped, so that the changes
+        // are not missed if they're not retained on some compute node yet.
+        start_after_epoch: since.unwrap_or(0),
+        format: ChangelogFormat::Retract as i32,
+        dedup_by_pk: false,
+    };
+    let stream_client_pool = session.env().stream_client_pool();
+    let changelogs = try_join_all(workers.iter().map(|worker| {
+        let request = request.clone();
+        async move {
+            let changelog = stream_client_pool
+                .get(worker)
+                .await?
+                .subscribe_changelog(request)
+                .await
+                .to_rw_result()?
+                .into_inner();
+            Ok::<_, RwError>(changelog)
+        }
+    }))
+    .await?;
+
+    let received = Arc::new(parking_lot::Mutex::new(ReceivedChanges::default()));
+    let subscription = Subscription {
+        workers: workers.len(),
+        column_indices,
+        received: received.clone(),
+        pending_rows: VecDeque::new(),
+        hummock_snapshot_manager,
+        receiver: tokio::spawn(receive_changes(changelogs, received, start_after_epoch)),
+    };
+    let idle_timeout = session.config().cursor_idle_timeout();
+    session
+        .cursor_manager()
+        .add_cursor(
+            cursor_name.value,
+            CursorSource::Subscription(subscription),
+            pg_descs,
+            idle_timeout,
+        )
+        .await?;
+    Ok(PgResponse::empty_result(StatementType::DECLARE_CURSOR))
+}
+
+pub async fn handle_fetch_cursor(
+    context: OptimizerContext,
+    cursor_name: Ident,
+    count: Option<u64>,
+) -> Result<PgResponse> {
+    let session = context.session_ctx;
+    session
+        .cursor_manager()
+        .fetch(&cursor_name.value, count)
+        .await
+}
+
+pub async fn handle_close_cursor(
+    context: OptimizerContext,
+    cursor_name: Option<Ident>,
+) -> Result<PgResponse> {
+    let session = context.session_ctx;
+    session
+        .cursor_manager()
+        .close(cursor_name.as_ref().map(|name| name.value.as_str()))
+        .await?;
+    Ok(PgResponse::empty_result(StatementType::CLOSE_CURSOR))
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::stream;
+    use risingwave_common::array::DataChunk;
+    use risingwave_common::test_prelude::{DataChunkTestExt, StreamChunkTestExt};
+
+    use super::*;
+    use crate::scheduler::HummockSnapshotManager;
+    use crate::test_utils::MockFrontendMetaClient;
+
+    async fn add_numbers(manager: &Arc<CursorManager>, idle_timeout: Option<Duration>) {
+        let chunks = vec![
+            Ok(DataChunk::from_pretty(
+                "i
+                 1
+                 2
+                 3",
+            )),
+            Ok(DataChunk::from_pretty(
+                "i
+                 4
+                 5",
+            )),
+        ];
+        manager
+            .add_cursor(
+                "c".to_string(),
+                CursorSource::query(stream::iter(chunks).boxed()),
+                vec![],
+                idle_timeout,
+            )
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_cursor_manager() {
+        let manager = Arc::new(CursorManager::default());
+        add_numbers(&manager, None).await;
+        assert!(manager
+            .add_cursor(
+                "c".to_string(),
+                CursorSource::query(stream::empty().boxed()),
+                vec![],
+                None,
+            )
+            .await
+            .is_err());
+
+        // Rows are fetched across the chunks.
+        let fetched = |rsp: PgResponse| rsp.get_effected_rows_cnt();
+        assert_eq!(fetched(manager.fetch("c", Some(2)).await.unwrap()), 2);
+        assert_eq!(fetched(manager.fetch("c", Some(2)).await.unwrap()), 2);
+        assert_eq!(fetched(manager.fetch("c", None).await.unwrap()), 1);
+        assert_eq!(fetched(manager.fetch("c", None).await.unwrap()), 0);
+
+        manager.close(Some("c")).await.unwrap();
+        assert!(manager.fetch("c", Some(1)).await.is_err());
+        assert!(manager.close(Some("c")).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_cursor_idle_timeout() {
+        let manager = Arc::new(CursorManager::default());
+        add_numbers(&manager, Some(Duration::from_millis(100))).await;
+        // Fetching keeps the cursor alive.
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        manager.fetch("c", Some(1)).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        manager.fetch("c", Some(1)).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert!(manager.fetch("c", Some(1)).await.is_err());
+    }
+
+    /// Returns the values of the first row.
+    fn values(rows: &[Row]) -> Vec<&str> {
+        rows[0]
+            .values()
+            .iter()
+            .flatten()
+            .map(String::as_str)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_subscription_fetch() {
+        let hummock_snapshot_manager = Arc::new(HummockSnapshotManager::new(Arc::new(
+            MockFrontendMetaClient {},
+        )));
+        let received = Arc::new(parking_lot::Mutex::new(ReceivedChanges::default()));
+        let mut subscription = Subscription {
+            workers: 2,
+            column_indices: vec![1],
+            received: received.clone(),
+            pending_rows: VecDeque::new(),
+            hummock_snapshot_manager: hummock_snapshot_manager.clone(),
+            receiver: tokio::spawn(async {}),
+        };
+        let chunk = StreamChunk::from_pretty(
+            " I I
+            + 1 10
+            - 2 20",
+        )
+        .to_protobuf();
+        received.lock().epochs.insert(1, (2, vec![chunk.clone()]));
+        received.lock().epochs.insert(2, (1, vec![chunk]));
+
+        // Nothing is fetched before the epoch is committed.
+        assert!(subscription.fetch(usize::MAX).await.unwrap().is_empty());
+        hummock_snapshot_manager.update_snapshot_status(2).await;
+        let rows = subscription.fetch(1).await.unwrap();
+        assert_eq!(values(&rows), ["10", "insert", "1"]);
+        let rows = subscription.fetch(usize::MAX).await.unwrap();
+        assert_eq!(values(&rows), ["20", "delete", "1"]);
+
+        // Epoch 2 is only received from one of the compute nodes.
+        assert!(subscription.fetch(usize::MAX).await.unwrap().is_empty());
+        received.lock().error = Some("closed".to_string());
+        assert!(subscription.fetch(usize::MAX).await.is_err());
+    }
+}
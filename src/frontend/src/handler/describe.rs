@@ -99,6 +99,9 @@ mod tests {
     use std::collections::HashMap;
     use std::ops::Index;
 
+    use futures::TryStreamExt;
+    use pgwire::types::Row;
+
     use crate::test_utils::LocalFrontend;
 
     #[tokio::test]
@@ -115,9 +118,10 @@ mod tests {
             .unwrap();
 
         let sql = "describe t";
-        let pg_response = frontend.run_sql(sql).await.unwrap();
+        let mut pg_response = frontend.run_sql(sql).await.unwrap();
+        let rows: Vec<Row> = pg_response.values_stream().try_concat().await.unwrap();
 
-        let columns = pg_response
+        let columns = rows
             .iter()
             .map(|row| {
                 (
@@ -17,38 +17,52 @@ use pgwire::pg_response::{PgResponse, StatementType};
 use risingwave_common::error::Result;
 use risingwave_sqlparser::ast::Statement;
 
-use crate::binder::Binder;
+use crate::binder::{Binder, BoundStatement, ParamValues};
 use crate::handler::util::{to_pg_field, to_pg_rows};
 use crate::planner::Planner;
 use crate::scheduler::{ExecutionContext, ExecutionContextRef};
 use crate::session::{OptimizerContext, SessionImpl};
 
-/// If `RW_IMPLICIT_FLUSH` is on, then every INSERT/UPDATE/DELETE statement will block
-/// until the entire dataflow is refreshed. In other words, every related table & MV will
-/// be able to see the write.
-pub static IMPLICIT_FLUSH: &str = "RW_IMPLICIT_FLUSH";
-
-pub async fn handle_dml(context: OptimizerContext, stmt: Statement) -> Result<PgResponse> {
-    let stmt_type = to_statement_type(&stmt);
+pub async fn handle_dml(
+    context: OptimizerContext,
+    stmt: Statement,
+    params: ParamValues,
+) -> Result<PgResponse> {
     let session = context.session_ctx.clone();
-
     let bound = {
-        let mut binder = Binder::new(
+        let mut binder = Binder::new_with_params(
             session.env().catalog_reader().read_guard(),
             session.database().to_string(),
+            params,
         );
-        binder.bind(stmt)?
+        let bound = binder.bind(stmt)?;
+        session.check_privileges(binder.object_check_items())?;
+        bound
     };
+    handle_bound_dml(context, bound).await
+}
+
+/// Handles the DML statement `stmt` which has been bound, i.e. a prepared statement with its
+/// parameters substituted.
+pub async fn handle_bound_dml(
+    context: OptimizerContext,
+    stmt: BoundStatement,
+) -> Result<PgResponse> {
+    let stmt_type = to_statement_type(&stmt);
+    let session = context.session_ctx.clone();
 
     let (plan, pg_descs) = {
         // Subblock to make sure PlanRef (an Rc) is dropped before `await` below.
-        let root = Planner::new(context.into()).plan(bound)?;
+        let root = Planner::new(context.into()).plan(stmt)?;
         let pg_descs = root.schema().fields().iter().map(to_pg_field).collect();
         let plan = root.gen_batch_query_plan()?;
 
         (plan.to_batch_prost(), pg_descs)
     };
 
+    // Read the data written by the session before, e.g. in `DELETE ... WHERE`.
+    session.wait_for_writes().await;
+
     let execution_context: ExecutionContextRef = ExecutionContext::new(session.clone()).into();
     let query_manager = execution_context.session().env().query_manager().clone();
 
@@ -74,11 +88,13 @@ pub async fn handle_dml(context: OptimizerContext, stmt: Statement) -> Result<Pg
         _ => unreachable!(),
     };
 
-    // Implicitly flush the writes.
-    if let Some(flag) = session.get_config(IMPLICIT_FLUSH) {
-        if flag.is_set(false) {
-            flush_for_write(&session, stmt_type).await?;
-        }
+    // Implicitly flush the writes, so that every related table & MV will be able to see them.
+    // Otherwise, only the later batch queries of the session wait for the writes to be committed.
+    let implicit_flush = session.config().implicit_flush();
+    if implicit_flush {
+        flush_for_write(&session, stmt_type).await?;
+    } else {
+        session.track_write_epoch().await?;
     }
 
     Ok(PgResponse::new(stmt_type, rows_count, rows, pg_descs))
@@ -88,19 +104,21 @@ async fn flush_for_write(session: &SessionImpl, stmt_type: StatementType) -> Res
     match stmt_type {
         StatementType::INSERT | StatementType::DELETE | StatementType::UPDATE => {
             let client = session.env().meta_client();
-            client.flush().await
+            let committed_epoch = client.flush().await?;
+            session.update_write_epoch(committed_epoch);
+            Ok(())
         }
         _ => Ok(()),
     }
 }
 
-fn to_statement_type(stmt: &Statement) -> StatementType {
+fn to_statement_type(stmt: &BoundStatement) -> StatementType {
     use StatementType::*;
 
     match stmt {
-        Statement::Insert { .. } => INSERT,
-        Statement::Delete { .. } => DELETE,
-        Statement::Update { .. } => UPDATE,
-        _ => unreachable!(),
+        BoundStatement::Insert(_) => INSERT,
+        BoundStatement::Delete(_) => DELETE,
+        BoundStatement::Update(_) => UPDATE,
+        BoundStatement::Query(_) => unreachable!(),
     }
 }
@@ -14,25 +14,27 @@
 
 use pgwire::pg_response::{PgResponse, StatementType};
 use risingwave_common::error::Result;
-use risingwave_sqlparser::ast::{Ident, SetVariableValue};
+use risingwave_sqlparser::ast::ObjectName;
 
+use crate::binder::Binder;
 use crate::session::OptimizerContext;
 
-pub(super) fn handle_set(
+pub async fn handle_drop_function(
     context: OptimizerContext,
-    name: Ident,
-    value: Vec<SetVariableValue>,
+    name: ObjectName,
 ) -> Result<PgResponse> {
-    let string_val = to_string(&value[0]);
-    // Currently store the config variable simply as String -> ConfigEntry(String).
-    // In future we can add converter/parser to make the API more robust.
-    context.session_ctx.set_config(&name.value, &string_val);
+    let session = context.session_ctx;
+    let (schema_name, function_name) = Binder::resolve_table_name(name)?;
 
-    Ok(PgResponse::empty_result(StatementType::SET_OPTION))
-}
+    let function_id = session
+        .env()
+        .catalog_reader()
+        .read_guard()
+        .get_function_by_name(session.database(), &schema_name, &function_name)?
+        .id;
+
+    let catalog_writer = session.env().catalog_writer();
+    catalog_writer.drop_function(function_id).await?;
 
-/// Convert any set variable to String.
-/// For example, TRUE -> "TRUE", 1 -> "1".
-fn to_string(value: &SetVariableValue) -> String {
-    format!("{}", value)
+    Ok(PgResponse::empty_result(StatementType::DROP_FUNCTION))
 }
@@ -42,12 +42,19 @@ pub async fn handle_drop_mv(
             )));
         }
 
+        if table.is_internal {
+            return Err(RwError::from(ErrorCode::InvalidInputSyntax(
+                "Internal tables are dropped together with their materialized views.".to_owned(),
+            )));
+        }
+
         // If is index on is `Some`, then it is a actually an index.
         if table.is_index_on.is_some() {
             return Err(RwError::from(ErrorCode::InvalidInputSyntax(
                 "Use `DROP INDEX` to drop an index.".to_owned(),
             )));
         }
+        session.check_owner(&table.owner, &table_name)?;
         table.id()
     };
 
@@ -37,6 +37,7 @@ pub async fn handle_drop_source(context: OptimizerContext, name: ObjectName) ->
             )));
         }
         SourceType::Source => {
+            session.check_owner(&source.owner, &source_name)?;
             let table = catalog_reader
                 .read_guard()
                 .get_table_by_name(session.database(), &schema_name, &source_name)
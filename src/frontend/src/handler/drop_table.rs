@@ -56,14 +56,16 @@ pub async fn handle_drop_table(
         let table = reader.get_table_by_name(session.database(), &schema_name, &table_name)?;
 
         // If associated source is `None`, then it is a normal mview.
-        match table.associated_source_id() {
-            Some(source_id) => (source_id, table.id()),
+        let source_id = match table.associated_source_id() {
+            Some(source_id) => source_id,
             None => {
                 return Err(RwError::from(ErrorCode::InvalidInputSyntax(
                     "Use `DROP MATERIALIZED VIEW` to drop a materialized view.".to_owned(),
                 )))
             }
-        }
+        };
+        session.check_owner(&table.owner, &table_name)?;
+        (source_id, table.id())
     };
 
     let catalog_writer = session.env().catalog_writer();
@@ -76,8 +78,12 @@ pub async fn handle_drop_table(
 
 #[cfg(test)]
 mod tests {
+    use std::sync::Arc;
+
+    use pgwire::pg_server::{Session, UserAuthenticator};
     use risingwave_common::catalog::{DEFAULT_DATABASE_NAME, DEFAULT_SCHEMA_NAME};
 
+    use crate::session::SessionImpl;
     use crate::test_utils::LocalFrontend;
 
     #[tokio::test]
@@ -105,4 +111,44 @@ mod tests {
             .cloned();
         assert!(table.is_none());
     }
+
+    #[tokio::test]
+    async fn test_drop_by_non_owner() {
+        let frontend = LocalFrontend::new(Default::default()).await;
+        for sql in [
+            "CREATE TABLE t (v1 INT)",
+            "CREATE MATERIALIZED VIEW mv AS SELECT v1 FROM t",
+            "CREATE SOURCE s ROW FORMAT JSON",
+            "CREATE USER user1 WITH PASSWORD 'password1'",
+        ] {
+            frontend.run_sql(sql).await.unwrap();
+        }
+
+        let session = Arc::new(SessionImpl::new(
+            frontend.session_ref().env().clone(),
+            DEFAULT_DATABASE_NAME.to_string(),
+            "user1".to_string(),
+            UserAuthenticator::None,
+        ));
+        for (sql, name) in [
+            ("DROP MATERIALIZED VIEW mv", "mv"),
+            ("DROP TABLE t", "t"),
+            ("DROP SOURCE s", "s"),
+        ] {
+            assert_eq!(
+                session
+                    .clone()
+                    .run_statement(sql)
+                    .await
+                    .unwrap_err()
+                    .to_string(),
+                format!("Permission denied: must be owner of relation {}", name)
+            );
+        }
+
+        // The owner can drop the relations.
+        for sql in ["DROP MATERIALIZED VIEW mv", "DROP TABLE t", "DROP SOURCE s"] {
+            frontend.run_sql(sql).await.unwrap();
+        }
+    }
 }
@@ -12,26 +12,64 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+use std::time::{Duration, Instant};
+
+use futures::future::try_join_all;
+use futures::{pin_mut, StreamExt};
+use itertools::Itertools;
 use pgwire::pg_field_descriptor::{PgFieldDescriptor, TypeOid};
 use pgwire::pg_response::{PgResponse, StatementType};
 use pgwire::types::Row;
-use risingwave_common::error::Result;
-use risingwave_sqlparser::ast::Statement;
+use risingwave_batch::executor::{OperatorProfile, TaskProfile};
+use risingwave_common::error::{ErrorCode, Result, ToRwResult};
+use risingwave_pb::common::WorkerNode;
+use risingwave_pb::meta::TableFragments;
+use risingwave_pb::stream_plan::stream_node::NodeBody;
+use risingwave_pb::stream_plan::{DispatchStrategy, StreamFragmentGraph, StreamNode};
+use risingwave_pb::stream_service::{ActorProfile, ExecutorProfile, ProfileActorsRequest};
+use risingwave_rpc_client::StreamClientPool;
+use risingwave_sqlparser::ast::{
+    ExplainFormat, ExplainOptions, ExplainType, ObjectName, Statement,
+};
+use serde_json::{json, Value};
 
-use super::create_mv::gen_create_mv_plan;
+use super::create_mv::{gen_create_mv_plan, MvOptions};
 use super::create_table::gen_create_table_plan;
 use crate::binder::Binder;
+use crate::config::QueryMode;
+use crate::optimizer::plan_node::{Convention, PlanNodeType};
+use crate::optimizer::PlanRef;
 use crate::planner::Planner;
+use crate::scheduler::{
+    BatchPlanFragmenter, ExecutionContext, ExecutionContextRef, LocalQueryExecution,
+};
 use crate::session::OptimizerContext;
+use crate::stream_fragmenter::{state_table_ids, StreamFragmenter};
 
 pub(super) fn handle_explain(
     context: OptimizerContext,
     stmt: Statement,
-    _verbose: bool,
+    options: ExplainOptions,
 ) -> Result<PgResponse> {
+    if options.explain_format != ExplainFormat::Text && options.explain_type != ExplainType::DistSql
+    {
+        return Err(ErrorCode::NotImplemented(
+            format!(
+                "EXPLAIN with FORMAT {} is only supported for TYPE DISTSQL",
+                options.explain_format
+            ),
+            None.into(),
+        )
+        .into());
+    }
+
     let session = context.session_ctx.clone();
     // bind, plan, optimize, and serialize here
     let mut planner = Planner::new(context.into());
+    // The parallelism specified with `CREATE MATERIALIZED VIEW .. WITH (parallelism = ..)`.
+    let mut mv_parallelism = None;
 
     let plan = match stmt {
         Statement::CreateView {
@@ -39,13 +77,28 @@ pub(super) fn handle_explain(
             materialized: true,
             query,
             name,
+            with_options,
+            emit_mode,
             ..
-        } => gen_create_mv_plan(&*session, planner.ctx(), query, name)?.0,
+        } if options.explain_type != ExplainType::Logical => {
+            mv_parallelism = MvOptions::from_with_options(with_options)?.parallelism;
+            gen_create_mv_plan(&*session, planner.ctx(), query, name, emit_mode)?.0
+        }
 
-        Statement::CreateTable { name, columns, .. } => {
+        Statement::CreateTable { name, columns, .. }
+            if options.explain_type != ExplainType::Logical =>
+        {
             gen_create_table_plan(&*session, planner.ctx(), name, columns)?.0
         }
 
+        Statement::CreateView { .. } | Statement::CreateTable { .. } => {
+            return Err(ErrorCode::NotImplemented(
+                "EXPLAIN (TYPE LOGICAL) of streaming jobs".to_string(),
+                None.into(),
+            )
+            .into())
+        }
+
         stmt => {
             let bound = {
                 let mut binder = Binder::new(
@@ -55,17 +108,221 @@ pub(super) fn handle_explain(
                 binder.bind(stmt)?
             };
             let logical = planner.plan(bound)?;
-            logical.gen_batch_query_plan()?
+            match options.explain_type {
+                ExplainType::Logical => logical.gen_optimized_logical_plan(),
+                ExplainType::Physical | ExplainType::DistSql => logical.gen_batch_query_plan()?,
+            }
+        }
+    };
+
+    let output = match options.explain_type {
+        ExplainType::Logical | ExplainType::Physical => plan.explain_to_string()?,
+        ExplainType::DistSql => {
+            let worker_node_manager = session.env().worker_node_manager();
+            let distributed_plan = if plan.convention() == Convention::Stream {
+                // Meta never creates more actors for a fragment than parallel units.
+                let parallel_unit_count = worker_node_manager.parallel_unit_owners().len();
+                let parallelism = match mv_parallelism
+                    .unwrap_or_else(|| session.config().streaming_parallelism())
+                    as usize
+                {
+                    0 => parallel_unit_count,
+                    parallelism => parallelism.min(parallel_unit_count),
+                };
+                DistributedPlan::from_stream_plan(&plan, parallelism)?
+            } else {
+                DistributedPlan::from_batch_plan(plan, worker_node_manager.worker_node_count())
+            };
+            match options.explain_format {
+                ExplainFormat::Text => distributed_plan.to_text(),
+                ExplainFormat::Json => serde_json::to_string_pretty(&distributed_plan.to_json())
+                    .map_err(|e| ErrorCode::InternalError(e.to_string()))?,
+                ExplainFormat::Dot => distributed_plan.to_dot(),
+            }
+        }
+    };
+
+    // Like PostgreSQL, JSON and dot outputs are returned in a single row so that they can be
+    // rendered directly.
+    let rows = match options.explain_format {
+        ExplainFormat::Text => output
+            .lines()
+            .map(|s| Row::new(vec![Some(s.into())]))
+            .collect::<Vec<_>>(),
+        ExplainFormat::Json | ExplainFormat::Dot => vec![Row::new(vec![Some(output)])],
+    };
+
+    Ok(PgResponse::new(
+        StatementType::EXPLAIN,
+        rows.len() as i32,
+        rows,
+        vec![PgFieldDescriptor::new(
+            "QUERY PLAN".to_owned(),
+            TypeOid::Varchar,
+        )],
+    ))
+}
+
+/// Runs the query, and shows its batch plan with the runtime statistics of each operator added up
+/// over the tasks running it.
+pub(super) async fn handle_explain_analyze(
+    context: OptimizerContext,
+    stmt: Statement,
+    options: ExplainOptions,
+) -> Result<PgResponse> {
+    if options.explain_type != ExplainType::Physical
+        || options.explain_format != ExplainFormat::Text
+    {
+        return Err(ErrorCode::NotImplemented(
+            "EXPLAIN ANALYZE with TYPE or FORMAT".to_string(),
+            None.into(),
+        )
+        .into());
+    }
+    if !matches!(stmt, Statement::Query(_)) {
+        return Err(ErrorCode::NotImplemented(
+            "EXPLAIN ANALYZE of statements other than queries".to_string(),
+            None.into(),
+        )
+        .into());
+    }
+
+    let session = context.session_ctx.clone();
+    let bound = {
+        let mut binder = Binder::new(
+            session.env().catalog_reader().read_guard(),
+            session.database().to_string(),
+        );
+        let bound = binder.bind(stmt)?;
+        session.check_privileges(binder.object_check_items())?;
+        bound
+    };
+    let (query_mode, query_epoch) = {
+        let config = session.config();
+        (config.query_mode(), config.query_epoch())
+    };
+    if query_epoch.is_none() {
+        session.wait_for_writes().await;
+    }
+
+    // Subblock to make sure PlanRef (an Rc) is dropped before `await` below.
+    let (plan_nodes, query) = {
+        let root = Planner::new(context.into()).plan(bound)?;
+        let plan = match query_mode {
+            QueryMode::Local => root.gen_batch_local_plan()?,
+            QueryMode::Distributed => root.gen_batch_query_plan()?,
+        };
+        let mut plan_nodes = vec![];
+        AnalyzedPlanNode::collect(&plan, 0, &mut plan_nodes);
+        let plan_fragmenter = BatchPlanFragmenter::new(session.env().worker_node_manager_ref());
+        (plan_nodes, plan_fragmenter.split(plan)?)
+    };
+
+    let start_time = Instant::now();
+    let (rows, profiles) = match query_mode {
+        QueryMode::Local => {
+            let profile = TaskProfile::default();
+            let hummock_snapshot_manager = session.env().hummock_snapshot_manager().clone();
+            let execution =
+                LocalQueryExecution::new(query, hummock_snapshot_manager, "", query_epoch)
+                    .with_profile(profile.clone());
+            let data_stream = execution.run();
+            pin_mut!(data_stream);
+            let mut rows = 0;
+            while let Some(chunk) = data_stream.next().await {
+                rows += chunk?.cardinality();
+            }
+            (rows, profile.operators())
+        }
+        QueryMode::Distributed => {
+            let execution_context: ExecutionContextRef =
+                ExecutionContext::new(session.clone()).into();
+            let query_manager = session.env().query_manager().clone();
+            query_manager.profile(execution_context, query).await?
         }
     };
 
-    let output = plan.explain_to_string()?;
+    let rows = format_analyzed_plan(&plan_nodes, &profiles, rows, start_time.elapsed())
+        .into_iter()
+        .map(|line| Row::new(vec![Some(line)]))
+        .collect_vec();
+    Ok(PgResponse::new(
+        StatementType::EXPLAIN,
+        rows.len() as i32,
+        rows,
+        vec![PgFieldDescriptor::new(
+            "QUERY PLAN".to_owned(),
+            TypeOid::Varchar,
+        )],
+    ))
+}
 
-    let rows = output
-        .lines()
-        .map(|s| Row::new(vec![Some(s.into())]))
-        .collect::<Vec<_>>();
+/// How long `EXPLAIN ANALYZE MATERIALIZED VIEW` samples the actors of the materialized view for.
+const MVIEW_PROFILE_DURATION: Duration = Duration::from_secs(5);
 
+/// Samples the actors of a running materialized view for a few seconds, and shows its fragments
+/// with the runtime statistics of each operator added up over the actors, to diagnose a slow
+/// materialized view in place.
+pub(super) async fn handle_explain_analyze_mview(
+    context: OptimizerContext,
+    name: ObjectName,
+) -> Result<PgResponse> {
+    let session = context.session_ctx;
+    let (schema_name, table_name) = Binder::resolve_table_name(name)?;
+    let table_id = {
+        let reader = session.env().catalog_reader().read_guard();
+        let table = reader.get_table_by_name(session.database(), &schema_name, &table_name)?;
+        if table.associated_source_id().is_some() || table.is_internal {
+            return Err(ErrorCode::InvalidInputSyntax(format!(
+                "\"{}\" is not a materialized view",
+                table_name
+            ))
+            .into());
+        }
+        table.id().table_id
+    };
+    let table_fragments = session
+        .env()
+        .meta_client()
+        .list_table_fragments(&[table_id])
+        .await?
+        .into_iter()
+        .next()
+        .ok_or_else(|| {
+            ErrorCode::InternalError(format!("fragments of \"{}\" not found", table_name))
+        })?;
+
+    // Sample the actors on each worker node running them.
+    let mut worker_actors: HashMap<u32, Vec<u32>> = HashMap::new();
+    for (actor_id, status) in &table_fragments.actor_status {
+        worker_actors
+            .entry(status.get_parallel_unit()?.worker_node_id)
+            .or_default()
+            .push(*actor_id);
+    }
+    let workers: HashMap<_, _> = session
+        .env()
+        .worker_node_manager()
+        .list_worker_nodes()
+        .into_iter()
+        .map(|node| (node.id, node))
+        .collect();
+    let samples = worker_actors
+        .into_iter()
+        .filter_map(|(worker_id, actor_ids)| Some((workers.get(&worker_id)?.clone(), actor_ids)))
+        .map(|(worker, actor_ids)| {
+            profile_actors(session.env().stream_client_pool(), worker, actor_ids)
+        });
+    let actors = try_join_all(samples)
+        .await?
+        .into_iter()
+        .flatten()
+        .collect_vec();
+
+    let rows = format_mview_profile(&table_fragments, &actors, MVIEW_PROFILE_DURATION)
+        .into_iter()
+        .map(|line| Row::new(vec![Some(line)]))
+        .collect_vec();
     Ok(PgResponse::new(
         StatementType::EXPLAIN,
         rows.len() as i32,
@@ -76,3 +333,774 @@ pub(super) fn handle_explain(
         )],
     ))
 }
+
+async fn profile_actors(
+    stream_client_pool: &StreamClientPool,
+    worker: WorkerNode,
+    actor_ids: Vec<u32>,
+) -> Result<Vec<ActorProfile>> {
+    let response = stream_client_pool
+        .get(&worker)
+        .await?
+        .profile_actors(ProfileActorsRequest {
+            actor_ids,
+            duration_ms: MVIEW_PROFILE_DURATION.as_millis() as u64,
+        })
+        .await
+        .to_rw_result()?;
+    Ok(response.into_inner().actors)
+}
+
+/// Formats the fragments of a materialized view in the same way as `EXPLAIN (TYPE DISTSQL)`, with
+/// the statistics of each operator over the sampled actors following it, as rates over the
+/// sampling `duration`.
+fn format_mview_profile(
+    table_fragments: &TableFragments,
+    actors: &[ActorProfile],
+    duration: Duration,
+) -> Vec<String> {
+    let actor_fragments: HashMap<u32, u32> = table_fragments
+        .fragments
+        .values()
+        .flat_map(|fragment| {
+            fragment
+                .actors
+                .iter()
+                .map(|actor| (actor.actor_id, fragment.fragment_id))
+        })
+        .collect();
+    let mut fragment_profiles: HashMap<u32, FragmentProfile> = HashMap::new();
+    for actor in actors {
+        if let Some(fragment_id) = actor_fragments.get(&actor.actor_id) {
+            fragment_profiles
+                .entry(*fragment_id)
+                .or_default()
+                .add(actor);
+        }
+    }
+
+    let mut lines = vec![];
+    for fragment in table_fragments
+        .fragments
+        .values()
+        .sorted_by_key(|fragment| fragment.fragment_id)
+    {
+        lines.push(format!(
+            "Fragment {} (parallelism: {})",
+            fragment.fragment_id,
+            fragment.actors.len()
+        ));
+        // All actors of a fragment run the same plan.
+        if let Some(node) = fragment
+            .actors
+            .first()
+            .and_then(|actor| actor.nodes.as_ref())
+        {
+            let profile = fragment_profiles
+                .remove(&fragment.fragment_id)
+                .unwrap_or_default();
+            profile.write_node(node, 1, &actor_fragments, duration, &mut lines);
+        }
+    }
+    lines.push(format!(
+        "Sampled {} of {} actors for {:.3}s",
+        actors.len(),
+        actor_fragments.len(),
+        duration.as_secs_f64()
+    ));
+    lines
+}
+
+/// Statistics of the sampled actors of a fragment added up.
+#[derive(Default)]
+struct FragmentProfile {
+    actors: usize,
+    /// Statistics of the executors by their operator ids.
+    executors: HashMap<u64, ExecutorProfile>,
+    materialize_cache_hits: u64,
+    materialize_cache_misses: u64,
+    join_cache_evictions: u64,
+}
+
+impl FragmentProfile {
+    fn add(&mut self, actor: &ActorProfile) {
+        self.actors += 1;
+        for executor in &actor.executors {
+            let total = self.executors.entry(executor.operator_id).or_default();
+            total.rows += executor.rows;
+            total.busy_time_ns += executor.busy_time_ns;
+            total.barrier_align_time_ns += executor.barrier_align_time_ns;
+        }
+        self.materialize_cache_hits += actor.materialize_cache_hits;
+        self.materialize_cache_misses += actor.materialize_cache_misses;
+        self.join_cache_evictions += actor.join_cache_evictions;
+    }
+
+    fn write_node(
+        &self,
+        node: &StreamNode,
+        level: usize,
+        actor_fragments: &HashMap<u32, u32>,
+        duration: Duration,
+        lines: &mut Vec<String>,
+    ) {
+        let secs = duration.as_secs_f64();
+        // Total time of the sampled actors, which the busy ratios are relative to.
+        let actor_ns = secs * 1e9 * self.actors as f64;
+        let mut stats = vec![];
+        if let Some(executor) = self.executors.get(&node.operator_id) {
+            // The time spent in the inputs is counted in their own busy ratios.
+            let inputs_busy_time_ns: u64 = node
+                .input
+                .iter()
+                .filter_map(|input| self.executors.get(&input.operator_id))
+                .map(|input| input.busy_time_ns)
+                .sum();
+            let busy_time_ns = executor.busy_time_ns.saturating_sub(inputs_busy_time_ns);
+            stats.push(format!("rows/s: {:.1}", executor.rows as f64 / secs));
+            stats.push(format!(
+                "busy: {:.1}%",
+                busy_time_ns as f64 / actor_ns * 100.0
+            ));
+            if executor.barrier_align_time_ns > 0 {
+                stats.push(format!(
+                    "barrier alignment: {:.1}%",
+                    executor.barrier_align_time_ns as f64 / actor_ns * 100.0
+                ));
+            }
+        }
+        let mut upstream_fragment = None;
+        match node.node_body.as_ref() {
+            Some(NodeBody::Materialize(_)) => {
+                let lookups = self.materialize_cache_hits + self.materialize_cache_misses;
+                if lookups > 0 {
+                    stats.push(format!(
+                        "cache hit rate: {:.1}%",
+                        self.materialize_cache_hits as f64 / lookups as f64 * 100.0
+                    ));
+                }
+            }
+            Some(NodeBody::HashJoin(_)) => {
+                stats.push(format!(
+                    "cache evictions/s: {:.1}",
+                    self.join_cache_evictions as f64 / secs
+                ));
+            }
+            Some(NodeBody::Merge(merge)) => {
+                upstream_fragment = merge
+                    .upstream_actor_id
+                    .first()
+                    .and_then(|actor_id| actor_fragments.get(actor_id));
+            }
+            _ => {}
+        }
+
+        let mut line = format!("{}{}", " ".repeat(level * 2), node.identity);
+        if let Some(upstream) = upstream_fragment {
+            write!(line, " (from fragment {})", upstream).unwrap();
+        }
+        if stats.is_empty() {
+            line.push_str(" (not sampled)");
+        } else {
+            write!(line, " ({})", stats.join(", ")).unwrap();
+        }
+        lines.push(line);
+        for input in &node.input {
+            self.write_node(input, level + 1, actor_fragments, duration, lines);
+        }
+    }
+}
+
+/// A node of the plan shown by `EXPLAIN ANALYZE`.
+struct AnalyzedPlanNode {
+    level: usize,
+    plan_node_id: i32,
+    identity: String,
+}
+
+impl AnalyzedPlanNode {
+    /// Collects the nodes of the plan rooted at `node` in pre-order.
+    fn collect(node: &PlanRef, level: usize, nodes: &mut Vec<Self>) {
+        nodes.push(Self {
+            level,
+            plan_node_id: node.id().0,
+            identity: format!("{}", node),
+        });
+        for input in node.inputs() {
+            Self::collect(&input, level + 1, nodes);
+        }
+    }
+}
+
+/// Formats the plan in the same way as `EXPLAIN`, with the statistics of each operator following
+/// it, and the summary of the execution in the end.
+fn format_analyzed_plan(
+    plan_nodes: &[AnalyzedPlanNode],
+    profiles: &HashMap<i32, OperatorProfile>,
+    rows: usize,
+    elapsed: Duration,
+) -> Vec<String> {
+    let mut lines = plan_nodes
+        .iter()
+        .map(|node| {
+            let stats = match profiles.get(&node.plan_node_id) {
+                Some(profile) => format!(
+                    "rows: {}, bytes: {}, time: {:.3}ms",
+                    profile.rows,
+                    profile.bytes,
+                    profile.elapsed.as_secs_f64() * 1000.0
+                ),
+                None => "never executed".to_string(),
+            };
+            format!(
+                "{}{} ({})",
+                " ".repeat(node.level * 2),
+                node.identity,
+                stats
+            )
+        })
+        .collect_vec();
+    lines.push(format!("Result rows: {}", rows));
+    lines.push(format!(
+        "Execution time: {:.3}ms",
+        elapsed.as_secs_f64() * 1000.0
+    ));
+    lines
+}
+
+/// A physical plan split into fragments connected by exchanges, which is how the plan is
+/// distributed to the compute nodes.
+struct DistributedPlan {
+    /// Ordered by fragment id. The first fragment is the one producing the final output.
+    fragments: Vec<PlanFragment>,
+    edges: Vec<FragmentEdge>,
+}
+
+struct PlanFragment {
+    id: u32,
+    /// Number of parallel actors or tasks of the fragment. `None` if there's no worker node to
+    /// derive it from yet.
+    parallelism: Option<usize>,
+    root: FragmentNode,
+}
+
+struct FragmentNode {
+    identity: String,
+    /// Ids of the state tables of a stateful streaming operator, which are local to the plan.
+    state_tables: Vec<u32>,
+    /// The fragment that an exchange node receives from.
+    upstream_fragment: Option<u32>,
+    children: Vec<FragmentNode>,
+}
+
+struct FragmentEdge {
+    upstream: u32,
+    downstream: u32,
+    exchange: String,
+}
+
+impl DistributedPlan {
+    fn from_stream_plan(plan: &PlanRef, parallelism: usize) -> Result<Self> {
+        let graph = StreamFragmenter::build_graph(plan.to_stream_prost());
+        let StreamFragmentGraph {
+            fragments, edges, ..
+        } = graph;
+
+        let upstreams: HashMap<_, _> = edges
+            .iter()
+            .map(|edge| ((edge.downstream_id, edge.link_id), edge.upstream_id))
+            .collect();
+
+        let fragments = fragments
+            .into_values()
+            .sorted_by_key(|fragment| fragment.fragment_id)
+            .map(|fragment| -> Result<_> {
+                Ok(PlanFragment {
+                    id: fragment.fragment_id,
+                    parallelism: if fragment.is_singleton {
+                        Some(1)
+                    } else {
+                        Some(parallelism).filter(|&n| n > 0)
+                    },
+                    root: FragmentNode::from_stream_node(
+                        fragment.get_node()?,
+                        fragment.fragment_id,
+                        &upstreams,
+                    ),
+                })
+            })
+            .try_collect()?;
+
+        let edges = edges
+            .iter()
+            .sorted_by_key(|edge| (edge.downstream_id, edge.upstream_id))
+            .map(|edge| -> Result<_> {
+                Ok(FragmentEdge {
+                    upstream: edge.upstream_id,
+                    downstream: edge.downstream_id,
+                    exchange: dispatch_strategy_to_string(edge.get_dispatch_strategy()?)?,
+                })
+            })
+            .try_collect()?;
+
+        Ok(Self { fragments, edges })
+    }
+
+    fn from_batch_plan(plan: PlanRef, worker_node_count: usize) -> Self {
+        let mut builder = BatchFragmentsBuilder {
+            fragments: BTreeMap::new(),
+            edges: vec![],
+            next_fragment_id: 0,
+            parallelism: Some(worker_node_count).filter(|&n| n > 0),
+        };
+        // The root fragment is always executed by a single task on the frontend.
+        builder.add_fragment(plan, Some(1));
+        Self {
+            fragments: builder.fragments.into_values().collect(),
+            edges: builder.edges,
+        }
+    }
+
+    fn to_text(&self) -> String {
+        let mut output = String::new();
+        for fragment in &self.fragments {
+            writeln!(
+                output,
+                "Fragment {} (parallelism: {})",
+                fragment.id,
+                parallelism_to_string(fragment.parallelism)
+            )
+            .unwrap();
+            fragment.root.write_text(1, &mut output);
+        }
+        output
+    }
+
+    fn to_json(&self) -> Value {
+        json!({
+            "fragments": self.fragments.iter().map(|fragment| json!({
+                "id": fragment.id,
+                "parallelism": fragment.parallelism,
+                "root": fragment.root.to_json(),
+            })).collect_vec(),
+            "edges": self.edges.iter().map(|edge| json!({
+                "upstream": edge.upstream,
+                "downstream": edge.downstream,
+                "exchange": edge.exchange,
+            })).collect_vec(),
+        })
+    }
+
+    /// Renders the plan in Graphviz dot, with each fragment as a cluster. Edges point in the
+    /// direction of data flow.
+    fn to_dot(&self) -> String {
+        let mut output = String::new();
+        let mut next_node_id = 0;
+        // Root node of each fragment.
+        let mut roots = HashMap::new();
+        // Exchange nodes receiving from each (upstream, downstream) fragment pair.
+        let mut exchanges = HashMap::new();
+
+        writeln!(output, "digraph {{").unwrap();
+        for fragment in &self.fragments {
+            writeln!(output, "  subgraph cluster_{} {{", fragment.id).unwrap();
+            writeln!(
+                output,
+                "    label={};",
+                dot_quote(&format!(
+                    "Fragment {} (parallelism: {})",
+                    fragment.id,
+                    parallelism_to_string(fragment.parallelism)
+                ))
+            )
+            .unwrap();
+            let root = fragment.root.write_dot(
+                fragment.id,
+                &mut next_node_id,
+                &mut exchanges,
+                &mut output,
+            );
+            roots.insert(fragment.id, root);
+            writeln!(output, "  }}").unwrap();
+        }
+        for edge in &self.edges {
+            if let (Some(from), Some(to)) = (
+                roots.get(&edge.upstream),
+                exchanges.get(&(edge.upstream, edge.downstream)),
+            ) {
+                writeln!(
+                    output,
+                    "  n{} -> n{} [label={}];",
+                    from,
+                    to,
+                    dot_quote(&edge.exchange)
+                )
+                .unwrap();
+            }
+        }
+        writeln!(output, "}}").unwrap();
+        output
+    }
+}
+
+impl FragmentNode {
+    fn from_stream_node(
+        node: &StreamNode,
+        fragment_id: u32,
+        upstreams: &HashMap<(u32, u64), u32>,
+    ) -> Self {
+        let state_tables = state_table_ids(node);
+        let upstream_fragment = match node.node_body.as_ref() {
+            Some(NodeBody::Exchange(_)) => upstreams.get(&(fragment_id, node.operator_id)).copied(),
+            _ => None,
+        };
+        Self {
+            identity: node.identity.clone(),
+            state_tables,
+            upstream_fragment,
+            children: node
+                .input
+                .iter()
+                .map(|input| Self::from_stream_node(input, fragment_id, upstreams))
+                .collect(),
+        }
+    }
+
+    fn write_text(&self, level: usize, output: &mut String) {
+        write!(output, "{}{}", " ".repeat(level * 2), self.identity).unwrap();
+        if !self.state_tables.is_empty() {
+            write!(
+                output,
+                " (state tables: [{}])",
+                self.state_tables.iter().join(", ")
+            )
+            .unwrap();
+        }
+        if let Some(upstream) = self.upstream_fragment {
+            write!(output, " (from fragment {})", upstream).unwrap();
+        }
+        writeln!(output).unwrap();
+        for child in &self.children {
+            child.write_text(level + 1, output);
+        }
+    }
+
+    fn to_json(&self) -> Value {
+        let mut node = json!({ "identity": self.identity });
+        if !self.state_tables.is_empty() {
+            node["state_tables"] = json!(self.state_tables);
+        }
+        if let Some(upstream) = self.upstream_fragment {
+            node["upstream_fragment"] = json!(upstream);
+        }
+        if !self.children.is_empty() {
+            node["children"] = self.children.iter().map(Self::to_json).collect();
+        }
+        node
+    }
+
+    /// Writes the node and its children as dot nodes, and returns the id of this node.
+    fn write_dot(
+        &self,
+        fragment_id: u32,
+        next_node_id: &mut usize,
+        exchanges: &mut HashMap<(u32, u32), usize>,
+        output: &mut String,
+    ) -> usize {
+        let id = *next_node_id;
+        *next_node_id += 1;
+
+        let mut label = self.identity.clone();
+        if !self.state_tables.is_empty() {
+            write!(
+                label,
+                "\nstate tables: [{}]",
+                self.state_tables.iter().join(", ")
+            )
+            .unwrap();
+        }
+        writeln!(
+            output,
+            "    n{} [shape=box, label={}];",
+            id,
+            dot_quote(&label)
+        )
+        .unwrap();
+        if let Some(upstream) = self.upstream_fragment {
+            exchanges.insert((upstream, fragment_id), id);
+        }
+
+        for child in &self.children {
+            let child_id = child.write_dot(fragment_id, next_node_id, exchanges, output);
+            writeln!(output, "    n{} -> n{};", child_id, id).unwrap();
+        }
+        id
+    }
+}
+
+struct BatchFragmentsBuilder {
+    fragments: BTreeMap<u32, PlanFragment>,
+    edges: Vec<FragmentEdge>,
+    next_fragment_id: u32,
+    /// Parallelism of the fragments other than the root one.
+    parallelism: Option<usize>,
+}
+
+impl BatchFragmentsBuilder {
+    /// Splits the plan rooted at `root` by exchanges into fragments in the same way as
+    /// `BatchPlanFragmenter`, and returns the id of the fragment of `root`.
+    fn add_fragment(&mut self, root: PlanRef, parallelism: Option<usize>) -> u32 {
+        let id = self.next_fragment_id;
+        self.next_fragment_id += 1;
+        let root = self.visit(root, id);
+        self.fragments.insert(
+            id,
+            PlanFragment {
+                id,
+                parallelism,
+                root,
+            },
+        );
+        id
+    }
+
+    fn visit(&mut self, node: PlanRef, fragment_id: u32) -> FragmentNode {
+        if node.node_type() == PlanNodeType::BatchExchange {
+            let upstream = self.add_fragment(node.inputs()[0].clone(), self.parallelism);
+            self.edges.push(FragmentEdge {
+                upstream,
+                downstream: fragment_id,
+                exchange: format!("{:?}", node.distribution()),
+            });
+            FragmentNode {
+                identity: format!("{}", node),
+                state_tables: vec![],
+                upstream_fragment: Some(upstream),
+                children: vec![],
+            }
+        } else {
+            FragmentNode {
+                identity: format!("{}", node),
+                state_tables: vec![],
+                upstream_fragment: None,
+                children: node
+                    .inputs()
+                    .into_iter()
+                    .map(|input| self.visit(input, fragment_id))
+                    .collect(),
+            }
+        }
+    }
+}
+
+fn dispatch_strategy_to_string(strategy: &DispatchStrategy) -> Result<String> {
+    let dispatcher_type = strategy.get_type()?;
+    Ok(if strategy.column_indices.is_empty() {
+        format!("{:?}", dispatcher_type)
+    } else {
+        format!("{:?}({:?})", dispatcher_type, strategy.column_indices)
+    })
+}
+
+fn parallelism_to_string(parallelism: Option<usize>) -> String {
+    parallelism.map_or_else(|| "unknown".to_string(), |n| n.to_string())
+}
+
+fn dot_quote(s: &str) -> String {
+    format!(
+        "\"{}\"",
+        s.replace('\\', "\\\\")
+            .replace('"', "\\\"")
+            .replace('\n', "\\n")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::LocalFrontend;
+
+    #[tokio::test]
+    async fn test_explain_distsql() {
+        let frontend = LocalFrontend::new(Default::default()).await;
+        frontend
+            .run_sql("CREATE TABLE t (v1 INT, v2 INT)")
+            .await
+            .unwrap();
+
+        let rows = frontend
+            .query_formatted_result(
+                "EXPLAIN (TYPE DISTSQL) CREATE MATERIALIZED VIEW mv AS SELECT v1, count(*) AS \
+                 cnt FROM t GROUP BY v1",
+            )
+            .await;
+        let output = rows.join("\n");
+        assert!(output.contains("Fragment 0"), "{}", output);
+        assert!(output.contains("Fragment 1"), "{}", output);
+        assert!(output.contains("state tables: ["), "{}", output);
+        assert!(output.contains("(from fragment "), "{}", output);
+
+        let rows = frontend
+            .query_formatted_result("EXPLAIN (TYPE DISTSQL, FORMAT JSON) SELECT v1 FROM t")
+            .await;
+        assert_eq!(rows.len(), 1);
+        assert!(rows[0].contains("\\\"fragments\\\""), "{}", rows[0]);
+
+        let rows = frontend
+            .query_formatted_result("EXPLAIN (TYPE DISTSQL, FORMAT DOT) SELECT v1 FROM t")
+            .await;
+        assert_eq!(rows.len(), 1);
+        assert!(rows[0].contains("digraph"), "{}", rows[0]);
+
+        assert!(frontend
+            .run_sql("EXPLAIN (FORMAT JSON) SELECT v1 FROM t")
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_explain_analyze() {
+        let frontend = LocalFrontend::new(Default::default()).await;
+        frontend
+            .run_sql("CREATE TABLE t (v1 INT, v2 INT)")
+            .await
+            .unwrap();
+        let err = frontend
+            .run_sql("EXPLAIN ANALYZE CREATE MATERIALIZED VIEW mv AS SELECT v1 FROM t")
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("other than queries"), "{}", err);
+        let err = frontend
+            .run_sql("EXPLAIN ANALYZE (TYPE DISTSQL) SELECT v1 FROM t")
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("TYPE or FORMAT"), "{}", err);
+
+        let plan_nodes = [(0, 1, "BatchExchange"), (1, 2, "BatchScan")]
+            .into_iter()
+            .map(|(level, plan_node_id, identity)| AnalyzedPlanNode {
+                level,
+                plan_node_id,
+                identity: identity.to_string(),
+            })
+            .collect_vec();
+        let profiles = HashMap::from([(
+            1,
+            OperatorProfile {
+                rows: 3,
+                bytes: 100,
+                elapsed: Duration::from_micros(1500),
+            },
+        )]);
+        assert_eq!(
+            format_analyzed_plan(&plan_nodes, &profiles, 3, Duration::from_millis(2)),
+            vec![
+                "BatchExchange (rows: 3, bytes: 100, time: 1.500ms)",
+                "  BatchScan (never executed)",
+                "Result rows: 3",
+                "Execution time: 2.000ms",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_explain_analyze_mview() {
+        use risingwave_pb::meta::table_fragments::Fragment;
+        use risingwave_pb::stream_plan::{MaterializeNode, MergeNode, SourceNode, StreamActor};
+
+        let frontend = LocalFrontend::new(Default::default()).await;
+        frontend
+            .run_sql("CREATE TABLE t (v1 INT, v2 INT)")
+            .await
+            .unwrap();
+        let err = frontend
+            .run_sql("EXPLAIN ANALYZE MATERIALIZED VIEW t")
+            .await
+            .unwrap_err();
+        assert!(
+            err.to_string().contains("not a materialized view"),
+            "{}",
+            err
+        );
+
+        // Fragment 1 materializes the rows merged from the source in fragment 2.
+        let mview_node = StreamNode {
+            operator_id: 2,
+            identity: "StreamMaterialize".to_string(),
+            node_body: Some(NodeBody::Materialize(MaterializeNode::default())),
+            input: vec![StreamNode {
+                operator_id: 1,
+                identity: "MergeExecutor".to_string(),
+                node_body: Some(NodeBody::Merge(MergeNode {
+                    upstream_actor_id: vec![3],
+                    ..Default::default()
+                })),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let source_node = StreamNode {
+            operator_id: 1,
+            identity: "StreamSource".to_string(),
+            node_body: Some(NodeBody::Source(SourceNode::default())),
+            ..Default::default()
+        };
+        let fragment = |fragment_id: u32, actor_ids: &[u32], node: &StreamNode| Fragment {
+            fragment_id,
+            actors: actor_ids
+                .iter()
+                .map(|actor_id| StreamActor {
+                    actor_id: *actor_id,
+                    fragment_id,
+                    nodes: Some(node.clone()),
+                    ..Default::default()
+                })
+                .collect(),
+            ..Default::default()
+        };
+        let table_fragments = TableFragments {
+            fragments: [
+                (1, fragment(1, &[1, 2], &mview_node)),
+                (2, fragment(2, &[3], &source_node)),
+            ]
+            .into_iter()
+            .collect(),
+            ..Default::default()
+        };
+
+        // Only the actors of fragment 1 are sampled, for 2 seconds.
+        let executor = |operator_id: u64, rows: u64, busy_ms: u64| ExecutorProfile {
+            operator_id,
+            rows,
+            busy_time_ns: busy_ms * 1_000_000,
+            barrier_align_time_ns: 0,
+        };
+        let actors = [
+            ActorProfile {
+                actor_id: 1,
+                executors: vec![executor(1, 100, 100), executor(2, 100, 500)],
+                materialize_cache_hits: 80,
+                materialize_cache_misses: 10,
+                join_cache_evictions: 0,
+            },
+            ActorProfile {
+                actor_id: 2,
+                executors: vec![executor(1, 300, 100), executor(2, 300, 300)],
+                materialize_cache_hits: 10,
+                materialize_cache_misses: 0,
+                join_cache_evictions: 0,
+            },
+        ];
+        assert_eq!(
+            format_mview_profile(&table_fragments, &actors, Duration::from_secs(2)),
+            vec![
+                "Fragment 1 (parallelism: 2)",
+                "  StreamMaterialize (rows/s: 200.0, busy: 15.0%, cache hit rate: 90.0%)",
+                "    MergeExecutor (from fragment 2) (rows/s: 200.0, busy: 5.0%)",
+                "Fragment 2 (parallelism: 1)",
+                "  StreamSource (not sampled)",
+                "Sampled 2 of 3 actors for 2.000s",
+            ]
+        );
+    }
+}
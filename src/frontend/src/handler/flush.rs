@@ -12,14 +12,35 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use pgwire::pg_field_descriptor::{PgFieldDescriptor, TypeOid};
 use pgwire::pg_response::{PgResponse, StatementType};
+use pgwire::types::Row;
 use risingwave_common::error::Result;
 
 use crate::session::OptimizerContext;
 
+/// Flushes the stream pipelines with a checkpoint barrier, and returns the epoch committed by it.
+/// Batch queries issued after `FLUSH` read all the data written before it.
 pub(super) async fn handle_flush(context: OptimizerContext) -> Result<PgResponse> {
     let client = context.session_ctx.env().meta_client();
-    client.flush().await?;
+    let committed_epoch = client.flush().await?;
 
-    Ok(PgResponse::empty_result(StatementType::FLUSH))
+    Ok(PgResponse::new(
+        StatementType::FLUSH,
+        1,
+        vec![Row::new(vec![Some(committed_epoch.to_string())])],
+        vec![PgFieldDescriptor::new("Epoch".to_owned(), TypeOid::BigInt)],
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::LocalFrontend;
+
+    #[tokio::test]
+    async fn test_handle_flush() {
+        let frontend = LocalFrontend::new(Default::default()).await;
+        let rows = frontend.query_formatted_result("flush;").await;
+        assert_eq!(rows, vec!["Row([Some(\"0\")])".to_string()]);
+    }
 }
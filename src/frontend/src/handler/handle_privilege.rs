@@ -23,8 +23,7 @@ use risingwave_sqlparser::ast::{Action, GrantObjects, Privileges, Statement};
 use crate::binder::Binder;
 use crate::session::{OptimizerContext, SessionImpl};
 
-// TODO: add user_privilege mod under user manager and move check and expand logic there, and bitmap
-// impl for privilege check.
+// TODO: move the check and expand logic to `user_privilege`, and bitmap impl for privilege check.
 static AVAILABLE_ACTION_ON_DATABASE: &[Action] = &[Action::Connect, Action::Create];
 static AVAILABLE_ACTION_ON_SCHEMA: &[Action] = &[Action::Create];
 static AVAILABLE_ACTION_ON_SOURCE: &[Action] = &[
@@ -33,6 +32,12 @@ static AVAILABLE_ACTION_ON_SOURCE: &[Action] = &[
     Action::Insert { columns: None },
     Action::Delete,
 ];
+static AVAILABLE_ACTION_ON_TABLE: &[Action] = &[
+    Action::Select { columns: None },
+    Action::Update { columns: None },
+    Action::Insert { columns: None },
+    Action::Delete,
+];
 static AVAILABLE_ACTION_ON_MVIEW: &[Action] = &[Action::Select { columns: None }];
 
 pub(crate) fn check_privilege_type(privilege: &Privileges, objects: &GrantObjects) -> Result<()> {
@@ -49,6 +54,9 @@ pub(crate) fn check_privilege_type(privilege: &Privileges, objects: &GrantObject
                 GrantObjects::Sources(_) | GrantObjects::AllSourcesInSchema { .. } => actions
                     .iter()
                     .all(|action| AVAILABLE_ACTION_ON_SOURCE.contains(action)),
+                GrantObjects::Tables(_) | GrantObjects::AllTablesInSchema { .. } => actions
+                    .iter()
+                    .all(|action| AVAILABLE_ACTION_ON_TABLE.contains(action)),
                 GrantObjects::Mviews(_) | GrantObjects::AllMviewsInSchema { .. } => actions
                     .iter()
                     .all(|action| AVAILABLE_ACTION_ON_MVIEW.contains(action)),
@@ -73,6 +81,9 @@ pub(crate) fn available_privilege_actions(objects: &GrantObjects) -> Result<Vec<
         GrantObjects::Sources(_) | GrantObjects::AllSourcesInSchema { .. } => {
             Ok(AVAILABLE_ACTION_ON_SOURCE.to_vec())
         }
+        GrantObjects::Tables(_) | GrantObjects::AllTablesInSchema { .. } => {
+            Ok(AVAILABLE_ACTION_ON_TABLE.to_vec())
+        }
         GrantObjects::Mviews(_) | GrantObjects::AllMviewsInSchema { .. } => {
             Ok(AVAILABLE_ACTION_ON_MVIEW.to_vec())
         }
@@ -112,7 +123,7 @@ fn make_prost_privilege(
                 grant_objs.push(ProstObject::SchemaId(schema.id()));
             }
         }
-        GrantObjects::Mviews(tables) => {
+        GrantObjects::Mviews(tables) | GrantObjects::Tables(tables) => {
             for name in tables {
                 let (schema_name, table_name) = Binder::resolve_table_name(name)?;
                 let table =
@@ -136,7 +147,8 @@ fn make_prost_privilege(
                 grant_objs.push(ProstObject::AllSourcesSchemaId(schema.id()));
             }
         }
-        GrantObjects::AllMviewsInSchema { schemas } => {
+        GrantObjects::AllMviewsInSchema { schemas }
+        | GrantObjects::AllTablesInSchema { schemas } => {
             for schema in schemas {
                 let (database_name, schema_name) =
                     Binder::resolve_schema_name(session.database(), schema)?;
@@ -261,8 +273,15 @@ pub async fn handle_revoke_privilege(
 
 #[cfg(test)]
 mod tests {
+    use std::sync::Arc;
+
+    use pgwire::pg_server::{Session, UserAuthenticator};
+    use risingwave_common::catalog::{DEFAULT_DATABASE_NAME, DEFAULT_SCHEMA_NAME};
+    use risingwave_sqlparser::parser::Parser;
+
     use super::*;
     use crate::test_utils::LocalFrontend;
+    use crate::user::user_privilege::ObjectCheckItem;
 
     #[tokio::test]
     async fn test_grant_privilege() {
@@ -335,4 +354,90 @@ mod tests {
             assert!(user_info.grant_privileges.is_empty());
         }
     }
+
+    #[tokio::test]
+    async fn test_check_granted_privilege() {
+        let frontend = LocalFrontend::new(Default::default()).await;
+        frontend.run_sql("CREATE TABLE t (v1 INT)").await.unwrap();
+        frontend
+            .run_sql("CREATE USER user1 WITH PASSWORD 'password1'")
+            .await
+            .unwrap();
+
+        let session = Arc::new(SessionImpl::new(
+            frontend.session_ref().env().clone(),
+            DEFAULT_DATABASE_NAME.to_string(),
+            "user1".to_string(),
+            UserAuthenticator::None,
+        ));
+        let table_id = {
+            let catalog_reader = session.env().catalog_reader();
+            let reader = catalog_reader.read_guard();
+            reader
+                .get_table_by_name(DEFAULT_DATABASE_NAME, DEFAULT_SCHEMA_NAME, "t")
+                .unwrap()
+                .id()
+                .table_id
+        };
+        let items = [ObjectCheckItem::new(
+            "t",
+            ProstObject::TableId(table_id),
+            ProstAction::Select,
+        )];
+
+        assert!(session.check_privileges(&items).is_err());
+        frontend
+            .run_sql("GRANT SELECT ON TABLE t TO user1")
+            .await
+            .unwrap();
+        session.check_privileges(&items).unwrap();
+
+        frontend
+            .run_sql("REVOKE SELECT ON TABLE t FROM user1")
+            .await
+            .unwrap();
+        assert!(session.check_privileges(&items).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_check_owner_privilege() {
+        let frontend = LocalFrontend::new(Default::default()).await;
+        frontend
+            .run_sql("CREATE USER user1 WITH PASSWORD 'password1'")
+            .await
+            .unwrap();
+        frontend
+            .run_sql("GRANT CREATE ON SCHEMA dev TO user1")
+            .await
+            .unwrap();
+
+        let session = Arc::new(SessionImpl::new(
+            frontend.session_ref().env().clone(),
+            DEFAULT_DATABASE_NAME.to_string(),
+            "user1".to_string(),
+            UserAuthenticator::None,
+        ));
+        session
+            .clone()
+            .run_statement("CREATE TABLE t (v1 INT)")
+            .await
+            .unwrap();
+
+        // The owner of a table needs no privilege granted to query or modify it.
+        for sql in [
+            "SELECT * FROM t",
+            "INSERT INTO t VALUES (1)",
+            "DELETE FROM t",
+        ] {
+            let stmt = Parser::parse_sql(sql).unwrap().remove(0);
+            let mut binder = Binder::new(
+                session.env().catalog_reader().read_guard(),
+                DEFAULT_DATABASE_NAME.to_string(),
+            );
+            binder.bind(stmt).unwrap();
+            session
+                .check_privileges(binder.object_check_items())
+                .unwrap();
+        }
+    }
 }
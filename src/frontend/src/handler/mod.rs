@@ -15,22 +15,33 @@
 use std::sync::Arc;
 
 use pgwire::pg_response::PgResponse;
-use pgwire::pg_response::StatementType::{ABORT, START_TRANSACTION};
+use pgwire::pg_response::StatementType::{ABORT, COMMIT, ROLLBACK, START_TRANSACTION};
 use risingwave_common::error::{ErrorCode, Result};
-use risingwave_sqlparser::ast::{DropStatement, ObjectType, Statement};
+use risingwave_sqlparser::ast::{
+    AlterSourceOperation, AlterTableOperation, AlterViewOperation, DropStatement, ObjectType,
+    Statement,
+};
 
+use crate::binder::{BoundStatement, ParamValues};
+use crate::handler::alter_relation::RelationKind;
 use crate::session::{OptimizerContext, SessionImpl};
 
+pub mod alter_relation;
+pub mod alter_table;
+mod copy;
 mod create_database;
+mod create_function;
 pub mod create_index;
 pub mod create_mv;
 mod create_schema;
 pub mod create_source;
 pub mod create_table;
 pub mod create_user;
+pub mod cursor;
 mod describe;
 pub mod dml;
 mod drop_database;
+mod drop_function;
 mod drop_index;
 pub mod drop_mv;
 mod drop_schema;
@@ -42,23 +53,63 @@ mod flush;
 pub mod handle_privilege;
 #[allow(dead_code)]
 pub mod query;
-mod set;
 mod show;
 pub mod util;
+mod variable;
 
-pub(super) async fn handle(session: Arc<SessionImpl>, stmt: Statement) -> Result<PgResponse> {
+/// Handles a query or DML statement which has been bound, i.e. a prepared statement with its
+/// parameters substituted. `sql` is only used for logging.
+pub(super) async fn handle_bound(
+    session: Arc<SessionImpl>,
+    stmt: BoundStatement,
+    sql: String,
+) -> Result<PgResponse> {
+    let context = OptimizerContext::new(session);
+    match stmt {
+        BoundStatement::Query(_) => query::handle_bound_query(context, stmt, sql).await,
+        BoundStatement::Insert(_) | BoundStatement::Delete(_) | BoundStatement::Update(_) => {
+            dml::handle_bound_dml(context, stmt).await
+        }
+    }
+}
+
+/// Handles a statement. `params` are the parameters of a prepared statement, which are only
+/// supported in queries and DML.
+pub(super) async fn handle(
+    session: Arc<SessionImpl>,
+    stmt: Statement,
+    params: ParamValues,
+) -> Result<PgResponse> {
     let context = OptimizerContext::new(session.clone());
     match stmt {
         Statement::Explain {
-            statement, verbose, ..
-        } => explain::handle_explain(context, *statement, verbose),
+            statement,
+            analyze: true,
+            options,
+            ..
+        } => explain::handle_explain_analyze(context, *statement, options).await,
+        Statement::ExplainAnalyzeMaterializedView { name } => {
+            explain::handle_explain_analyze_mview(context, name).await
+        }
+        Statement::Explain {
+            statement, options, ..
+        } => match *statement {
+            Statement::CreateSource {
+                is_materialized,
+                stmt,
+            } => create_source::handle_explain_create_source(is_materialized, stmt).await,
+            statement => explain::handle_explain(context, statement, options),
+        },
         Statement::CreateSource {
             is_materialized,
             stmt,
         } => create_source::handle_create_source(context, is_materialized, stmt).await,
-        Statement::CreateTable { name, columns, .. } => {
-            create_table::handle_create_table(context, name, columns).await
-        }
+        Statement::CreateTable {
+            name,
+            columns,
+            with_options,
+            ..
+        } => create_table::handle_create_table(context, name, columns, with_options).await,
         Statement::CreateDatabase {
             db_name,
             if_not_exists,
@@ -69,10 +120,104 @@ pub(super) async fn handle(session: Arc<SessionImpl>, stmt: Statement) -> Result
             if_not_exists,
             ..
         } => create_schema::handle_create_schema(context, schema_name, if_not_exists).await,
+        Statement::CreateFunction {
+            name,
+            args,
+            return_type,
+            language,
+            body,
+            link_symbol,
+            with_options,
+        } => {
+            create_function::handle_create_function(
+                context,
+                name,
+                args,
+                return_type,
+                language,
+                body,
+                link_symbol,
+                with_options,
+            )
+            .await
+        }
         Statement::CreateUser(stmt) => create_user::handle_create_user(context, stmt).await,
         Statement::Grant { .. } => handle_privilege::handle_grant_privilege(context, stmt).await,
         Statement::Revoke { .. } => handle_privilege::handle_revoke_privilege(context, stmt).await,
         Statement::Describe { name } => describe::handle_describe(context, name).await,
+        Statement::AlterTable {
+            name,
+            operation: AlterTableOperation::AddColumn { column_def },
+        } => alter_table::handle_alter_table_add_column(context, name, column_def).await,
+        Statement::AlterSource {
+            name,
+            operation: AlterSourceOperation::AddColumn { column_def },
+        } => alter_table::handle_alter_source_add_column(context, name, column_def).await,
+        Statement::AlterTable {
+            name,
+            operation: AlterTableOperation::RenameTable { table_name },
+        } => {
+            alter_relation::handle_rename_relation(context, RelationKind::Table, name, table_name)
+                .await
+        }
+        Statement::AlterTable {
+            name,
+            operation: AlterTableOperation::ChangeOwner { new_owner_name },
+        } => {
+            alter_relation::handle_change_relation_owner(
+                context,
+                RelationKind::Table,
+                name,
+                new_owner_name,
+            )
+            .await
+        }
+        Statement::AlterSource {
+            name,
+            operation: AlterSourceOperation::RenameSource { source_name },
+        } => {
+            alter_relation::handle_rename_relation(context, RelationKind::Source, name, source_name)
+                .await
+        }
+        Statement::AlterSource {
+            name,
+            operation: AlterSourceOperation::ChangeOwner { new_owner_name },
+        } => {
+            alter_relation::handle_change_relation_owner(
+                context,
+                RelationKind::Source,
+                name,
+                new_owner_name,
+            )
+            .await
+        }
+        Statement::AlterView {
+            materialized: true,
+            name,
+            operation,
+        } => match operation {
+            AlterViewOperation::RenameView { view_name } => {
+                alter_relation::handle_rename_relation(
+                    context,
+                    RelationKind::MaterializedView,
+                    name,
+                    view_name,
+                )
+                .await
+            }
+            AlterViewOperation::ChangeOwner { new_owner_name } => {
+                alter_relation::handle_change_relation_owner(
+                    context,
+                    RelationKind::MaterializedView,
+                    name,
+                    new_owner_name,
+                )
+                .await
+            }
+            AlterViewOperation::SetParallelism { parallelism } => {
+                alter_relation::handle_set_parallelism(context, name, parallelism).await
+            }
+        },
         Statement::ShowObjects(show_object) => show::handle_show_object(context, show_object).await,
         Statement::Drop(DropStatement {
             object_type,
@@ -97,6 +242,7 @@ pub(super) async fn handle(session: Arc<SessionImpl>, stmt: Statement) -> Result
                 drop_schema::handle_drop_schema(context, object_name, if_exists, drop_mode.into())
                     .await
             }
+            ObjectType::Function => drop_function::handle_drop_function(context, object_name).await,
             ObjectType::User => {
                 drop_user::handle_drop_user(context, object_name, if_exists, drop_mode.into()).await
             }
@@ -105,23 +251,59 @@ pub(super) async fn handle(session: Arc<SessionImpl>, stmt: Statement) -> Result
                     .into(),
             ),
         },
-        Statement::Query(_) => query::handle_query(context, stmt).await,
+        Statement::Query(_) => query::handle_query(context, stmt, params).await,
         Statement::Insert { .. } | Statement::Delete { .. } | Statement::Update { .. } => {
-            dml::handle_dml(context, stmt).await
+            dml::handle_dml(context, stmt, params).await
         }
         Statement::CreateView {
             materialized: true,
             or_replace: false,
             name,
             query,
+            with_options,
+            emit_mode,
             ..
-        } => create_mv::handle_create_mv(context, name, query).await,
+        } => create_mv::handle_create_mv(context, name, query, with_options, emit_mode).await,
+        Statement::CopyTo {
+            table_name,
+            columns,
+            location,
+            with_options,
+        } => copy::handle_copy_to(context, table_name, columns, location, with_options).await,
+        Statement::DeclareCursor { cursor_name, query } => {
+            cursor::handle_declare_cursor(context, cursor_name, query, params).await
+        }
+        Statement::DeclareSubscriptionCursor {
+            cursor_name,
+            mview_name,
+            since,
+        } => {
+            cursor::handle_declare_subscription_cursor(context, cursor_name, mview_name, since)
+                .await
+        }
+        Statement::FetchCursor { cursor_name, count } => {
+            cursor::handle_fetch_cursor(context, cursor_name, count).await
+        }
+        Statement::CloseCursor { cursor_name } => {
+            cursor::handle_close_cursor(context, cursor_name).await
+        }
+        Statement::CopyToStdout {
+            source,
+            with_options,
+        } => copy::handle_copy_to_stdout(context, source, with_options, params).await,
+        Statement::CopyFrom {
+            table_name,
+            columns,
+            location,
+            with_options,
+        } => copy::handle_copy_from(context, table_name, columns, location, with_options).await,
         Statement::Flush => flush::handle_flush(context).await,
         Statement::SetVariable {
             local: _,
             variable,
             value,
-        } => set::handle_set(context, variable, value),
+        } => variable::handle_set(context, variable, value),
+        Statement::ShowVariable { variable } => variable::handle_show(context, variable),
         Statement::CreateIndex {
             name,
             table_name,
@@ -147,14 +329,29 @@ pub(super) async fn handle(session: Arc<SessionImpl>, stmt: Statement) -> Result
         // 1. Fully support transaction is too hard and gives few benefits to us.
         // 2. Some client e.g. psycopg2 will use this statement.
         // TODO: Track issues #2595 #2541
-        Statement::StartTransaction { .. } => Ok(PgResponse::empty_result_with_notice(
-            START_TRANSACTION,
-            "Ignored temporarily.See detail in issue#2541".to_string(),
-        )),
-        Statement::Abort { .. } => Ok(PgResponse::empty_result_with_notice(
-            ABORT,
-            "Ignored temporarily.See detail in issue#2541".to_string(),
-        )),
+        // The transaction block is only tracked for the idle timeouts of the session.
+        Statement::StartTransaction { .. } => {
+            session.set_in_transaction(true);
+            Ok(PgResponse::empty_result_with_notice(
+                START_TRANSACTION,
+                "Ignored temporarily.See detail in issue#2541".to_string(),
+            ))
+        }
+        Statement::Abort { .. } => {
+            session.set_in_transaction(false);
+            Ok(PgResponse::empty_result_with_notice(
+                ABORT,
+                "Ignored temporarily.See detail in issue#2541".to_string(),
+            ))
+        }
+        Statement::Commit { .. } => {
+            session.set_in_transaction(false);
+            Ok(PgResponse::empty_result(COMMIT))
+        }
+        Statement::Rollback { .. } => {
+            session.set_in_transaction(false);
+            Ok(PgResponse::empty_result(ROLLBACK))
+        }
         _ => {
             Err(ErrorCode::NotImplemented(format!("Unhandled ast: {:?}", stmt), None.into()).into())
         }
@@ -12,59 +12,226 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use futures_async_stream::for_await;
-use pgwire::pg_field_descriptor::PgFieldDescriptor;
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+use futures::{stream, StreamExt, TryStreamExt};
+use futures_async_stream::{for_await, try_stream};
 use pgwire::pg_response::{PgResponse, StatementType};
+use pgwire::pg_server::BoxedError;
 use risingwave_batch::executor::BoxedDataChunkStream;
-use risingwave_common::error::Result;
+use risingwave_common::array::DataChunk;
+use risingwave_common::catalog::Schema;
+use risingwave_common::error::{Result, RwError};
+use risingwave_common::util::epoch::INVALID_EPOCH;
 use risingwave_sqlparser::ast::Statement;
 use tracing::info;
 
-use crate::binder::{Binder, BoundStatement};
+use crate::binder::{Binder, BoundStatement, ParamValues};
 use crate::config::QueryMode;
 use crate::handler::util::{to_pg_field, to_pg_rows};
 use crate::planner::Planner;
 use crate::scheduler::{
-    BatchPlanFragmenter, ExecutionContext, ExecutionContextRef, LocalQueryExecution,
+    BatchPlanFragmenter, ExecutionContext, ExecutionContextRef, LocalQueryExecution, ResultCacheKey,
 };
-use crate::session::OptimizerContext;
-
-pub static QUERY_MODE: &str = "query_mode";
+use crate::session::{OptimizerContext, SessionImpl};
 
-pub async fn handle_query(context: OptimizerContext, stmt: Statement) -> Result<PgResponse> {
+pub async fn handle_query(
+    context: OptimizerContext,
+    stmt: Statement,
+    params: ParamValues,
+) -> Result<PgResponse> {
     let stmt_type = to_statement_type(&stmt);
-    let session = context.session_ctx.clone();
+    let (data_stream, schema) = execute_query(context, stmt, params).await?;
+    Ok(to_query_response(stmt_type, data_stream, schema))
+}
+
+/// Handles the query `stmt` which has been bound, i.e. a prepared statement with its parameters
+/// substituted. `sql` is only used for logging.
+pub async fn handle_bound_query(
+    context: OptimizerContext,
+    stmt: BoundStatement,
+    sql: String,
+) -> Result<PgResponse> {
+    let (data_stream, schema) = execute_bound_query(context, stmt, sql).await?;
+    Ok(to_query_response(
+        StatementType::SELECT,
+        data_stream,
+        schema,
+    ))
+}
+
+fn to_query_response(
+    stmt_type: StatementType,
+    data_stream: BoxedDataChunkStream,
+    schema: Schema,
+) -> PgResponse {
+    let pg_descs = schema.fields().iter().map(to_pg_field).collect();
+
+    // The rows are converted chunk by chunk while being sent to the client, instead of collecting
+    // the whole result set here.
+    let rows_stream = data_stream
+        .map(|chunk| chunk.map(to_pg_rows).map_err(|e| Box::new(e) as BoxedError))
+        .boxed();
 
+    // The number of rows is counted when the rows are sent.
+    PgResponse::new_for_stream(stmt_type, 0, rows_stream, pg_descs)
+}
+
+/// Runs the query `stmt`, and returns the stream of its results along with their schema.
+pub async fn execute_query(
+    context: OptimizerContext,
+    stmt: Statement,
+    params: ParamValues,
+) -> Result<(BoxedDataChunkStream, Schema)> {
+    let session = context.session_ctx.clone();
+    let sql = stmt.to_string();
     let bound = {
-        let mut binder = Binder::new(
+        let mut binder = Binder::new_with_params(
             session.env().catalog_reader().read_guard(),
             session.database().to_string(),
+            params,
         );
-        binder.bind(stmt)?
+        let bound = binder.bind(stmt)?;
+        session.check_privileges(binder.object_check_items())?;
+        bound
     };
+    execute_bound_query(context, bound, sql).await
+}
 
-    let query_mode = session
-        .get_config(QUERY_MODE)
-        .map(|entry| entry.get_val(QueryMode::default()))
-        .unwrap_or_default();
+/// Runs the query `stmt` which has been bound, and returns the stream of its results along with
+/// their schema.
+pub async fn execute_bound_query(
+    context: OptimizerContext,
+    stmt: BoundStatement,
+    sql: String,
+) -> Result<(BoxedDataChunkStream, Schema)> {
+    let session = context.session_ctx.clone();
+    let start_time = Instant::now();
+    let slow_query_threshold_ms = session.config().slow_query_threshold_ms();
+    let slow_query_log = (slow_query_threshold_ms > 0).then(|| SlowQueryLog {
+        threshold: Duration::from_millis(slow_query_threshold_ms),
+        start_time,
+        sql,
+        plan: String::new(),
+        execution_context: None,
+    });
 
-    let (data_stream, pg_descs) = match query_mode {
-        QueryMode::Local => local_execute(context, bound).await?,
-        QueryMode::Distributed => distribute_execute(context, bound).await?,
+    let (query_mode, query_epoch) = {
+        let config = session.config();
+        (config.query_mode(), config.query_epoch())
     };
+    // Read the data written by the session, unless reading a historical snapshot.
+    if query_epoch.is_none() {
+        session.wait_for_writes().await;
+    }
+
+    match query_mode {
+        QueryMode::Local => local_execute(context, stmt, slow_query_log).await,
+        QueryMode::Distributed => distribute_execute(context, stmt, slow_query_log).await,
+    }
+}
+
+/// Logs a batch query running longer than `SLOW_QUERY_THRESHOLD_MS` when dropped, i.e. once all of
+/// its results are produced or the query is given up.
+struct SlowQueryLog {
+    threshold: Duration,
+    start_time: Instant,
+    sql: String,
+    /// The explained plan of the query.
+    plan: String,
+    /// The context of distributed queries, which records the time taken to schedule each stage.
+    execution_context: Option<ExecutionContextRef>,
+}
+
+impl Drop for SlowQueryLog {
+    fn drop(&mut self) {
+        let elapsed = self.start_time.elapsed();
+        if elapsed < self.threshold {
+            return;
+        }
+        let stage_timings = self
+            .execution_context
+            .as_ref()
+            .map(|context| context.stage_timings().lock().clone())
+            .unwrap_or_default();
+        tracing::warn!(
+            elapsed_ms = elapsed.as_millis() as u64,
+            "slow query: {}\nstages scheduled after: {:?}\nplan:\n{}",
+            self.sql,
+            stage_timings,
+            self.plan
+        );
+    }
+}
 
-    let mut rows = vec![];
+/// Passes through `data_stream`, and keeps `slow_query_log` until the stream is exhausted or
+/// dropped.
+#[try_stream(boxed, ok = DataChunk, error = RwError)]
+async fn with_slow_query_log(data_stream: BoxedDataChunkStream, slow_query_log: SlowQueryLog) {
+    let _slow_query_log = slow_query_log;
     #[for_await]
     for chunk in data_stream {
-        rows.extend(to_pg_rows(chunk?));
+        yield chunk?;
     }
+}
 
-    let rows_count = match stmt_type {
-        StatementType::SELECT => rows.len() as i32,
-        _ => unreachable!(),
+fn maybe_log_slow_query(
+    data_stream: BoxedDataChunkStream,
+    slow_query_log: Option<SlowQueryLog>,
+) -> BoxedDataChunkStream {
+    match slow_query_log {
+        Some(slow_query_log) => with_slow_query_log(data_stream, slow_query_log),
+        None => data_stream,
+    }
+}
+
+/// Returns the latest epoch and the staleness tolerance in ms if the results of point lookups can
+/// be served from the query result cache.
+async fn result_cache_options(session: &SessionImpl) -> Option<(u64, u64)> {
+    let (staleness_ms, query_epoch) = {
+        let config = session.config();
+        (
+            config.query_result_cache_staleness_ms(),
+            config.query_epoch(),
+        )
     };
+    if staleness_ms == 0 || query_epoch.is_some() {
+        return None;
+    }
+    let epoch = session
+        .env()
+        .hummock_snapshot_manager()
+        .latest_epoch()
+        .await;
+    if epoch == INVALID_EPOCH {
+        return None;
+    }
+    Some((epoch, staleness_ms))
+}
 
-    Ok(PgResponse::new(stmt_type, rows_count, rows, pg_descs))
+/// Serves the query from the query result cache if `cache_key` is cached. Otherwise runs
+/// `execute`, and caches the results if `cache_key` is set.
+async fn execute_with_result_cache(
+    session: &SessionImpl,
+    cache_key: Option<ResultCacheKey>,
+    execute: impl Future<Output = Result<BoxedDataChunkStream>>,
+) -> Result<BoxedDataChunkStream> {
+    let cache_key = match cache_key {
+        Some(cache_key) => cache_key,
+        None => return execute.await,
+    };
+    let cache = session.env().query_result_cache();
+    let chunks = match cache.get(&cache_key) {
+        Some(chunks) => chunks,
+        None => {
+            // Results of point lookups are small, so they are collected before being sent.
+            let chunks: Vec<DataChunk> = execute.await?.try_collect().await?;
+            cache.insert(cache_key, chunks.clone());
+            chunks
+        }
+    };
+    Ok(Box::pin(stream::iter(chunks.into_iter().map(Ok))))
 }
 
 fn to_statement_type(stmt: &Statement) -> StatementType {
@@ -79,73 +246,91 @@ fn to_statement_type(stmt: &Statement) -> StatementType {
 async fn distribute_execute(
     context: OptimizerContext,
     stmt: BoundStatement,
-) -> Result<(BoxedDataChunkStream, Vec<PgFieldDescriptor>)> {
+    mut slow_query_log: Option<SlowQueryLog>,
+) -> Result<(BoxedDataChunkStream, Schema)> {
     let session = context.session_ctx.clone();
+    let cache_options = result_cache_options(&session).await;
     // Subblock to make sure PlanRef (an Rc) is dropped before `await` below.
-    let (query, pg_descs) = {
+    let (query, schema, cache_key) = {
         let root = Planner::new(context.into()).plan(stmt)?;
-
-        let pg_descs = root
-            .schema()
-            .fields()
-            .iter()
-            .map(to_pg_field)
-            .collect::<Vec<PgFieldDescriptor>>();
+        let schema = root.schema().clone();
 
         let plan = root.gen_batch_query_plan()?;
+        let cache_key = match cache_options {
+            Some((epoch, staleness_ms)) => {
+                ResultCacheKey::for_point_lookup(&plan, epoch, staleness_ms)?
+            }
+            None => None,
+        };
 
-        info!(
-            "Generated distributed plan: {:?}",
-            plan.explain_to_string()?
-        );
+        let explained = plan.explain_to_string()?;
+        info!("Generated distributed plan: {:?}", explained);
+        if let Some(slow_query_log) = &mut slow_query_log {
+            slow_query_log.plan = explained;
+        }
 
         let plan_fragmenter = BatchPlanFragmenter::new(session.env().worker_node_manager_ref());
         let query = plan_fragmenter.split(plan)?;
         info!("Generated query after plan fragmenter: {:?}", &query);
-        (query, pg_descs)
+        (query, schema, cache_key)
     };
 
     let execution_context: ExecutionContextRef = ExecutionContext::new(session.clone()).into();
+    if let Some(slow_query_log) = &mut slow_query_log {
+        slow_query_log.execution_context = Some(execution_context.clone());
+    }
     let query_manager = execution_context.session().env().query_manager().clone();
-    Ok((
-        Box::pin(query_manager.schedule(execution_context, query).await?),
-        pg_descs,
-    ))
+    let data_stream = execute_with_result_cache(&session, cache_key, async move {
+        let data_stream: BoxedDataChunkStream =
+            Box::pin(query_manager.schedule(execution_context, query).await?);
+        Ok(data_stream)
+    })
+    .await?;
+    Ok((maybe_log_slow_query(data_stream, slow_query_log), schema))
 }
 
 async fn local_execute(
     context: OptimizerContext,
     stmt: BoundStatement,
-) -> Result<(BoxedDataChunkStream, Vec<PgFieldDescriptor>)> {
+    mut slow_query_log: Option<SlowQueryLog>,
+) -> Result<(BoxedDataChunkStream, Schema)> {
     let session = context.session_ctx.clone();
+    let cache_options = result_cache_options(&session).await;
 
     // Subblock to make sure PlanRef (an Rc) is dropped before `await` below.
-    let (query, pg_descs) = {
+    let (query, schema, cache_key) = {
         let root = Planner::new(context.into()).plan(stmt)?;
-
-        let pg_descs = root
-            .schema()
-            .fields()
-            .iter()
-            .map(to_pg_field)
-            .collect::<Vec<PgFieldDescriptor>>();
+        let schema = root.schema().clone();
 
         let plan = root.gen_batch_local_plan()?;
+        let cache_key = match cache_options {
+            Some((epoch, staleness_ms)) => {
+                ResultCacheKey::for_point_lookup(&plan, epoch, staleness_ms)?
+            }
+            None => None,
+        };
 
-        info!(
-            "Generated local execution plan: {:?}",
-            plan.explain_to_string()?
-        );
+        let explained = plan.explain_to_string()?;
+        info!("Generated local execution plan: {:?}", explained);
+        if let Some(slow_query_log) = &mut slow_query_log {
+            slow_query_log.plan = explained;
+        }
 
         let plan_fragmenter = BatchPlanFragmenter::new(session.env().worker_node_manager_ref());
         let query = plan_fragmenter.split(plan)?;
         info!("Generated query after plan fragmenter: {:?}", &query);
-        (query, pg_descs)
+        (query, schema, cache_key)
     };
 
     let hummock_snapshot_manager = session.env().hummock_snapshot_manager().clone();
 
     // TODO: Passing sql here
-    let execution = LocalQueryExecution::new(query, hummock_snapshot_manager, "");
-    Ok((Box::pin(execution.run()), pg_descs))
+    let query_epoch = session.config().query_epoch();
+    let execution = LocalQueryExecution::new(query, hummock_snapshot_manager, "", query_epoch);
+    let data_stream = execute_with_result_cache(&session, cache_key, async move {
+        let data_stream: BoxedDataChunkStream = Box::pin(execution.run());
+        Ok(data_stream)
+    })
+    .await?;
+    Ok((maybe_log_slow_query(data_stream, slow_query_log), schema))
 }
@@ -12,17 +12,22 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
+
 use itertools::Itertools;
 use pgwire::pg_field_descriptor::{PgFieldDescriptor, TypeOid};
 use pgwire::pg_response::{PgResponse, StatementType};
 use pgwire::types::Row;
 use risingwave_common::catalog::{ColumnDesc, DEFAULT_SCHEMA_NAME};
 use risingwave_common::error::Result;
+use risingwave_common::types::NaiveDateTimeWrapper;
+use risingwave_pb::meta::{EventLog, TableFragments};
 use risingwave_sqlparser::ast::{Ident, ObjectName, ShowObject};
 
 use crate::binder::Binder;
 use crate::handler::util::col_descs_to_rows;
 use crate::session::{OptimizerContext, SessionImpl};
+use crate::stream_fragmenter::state_table_ids;
 
 pub fn get_columns_from_table(
     session: &SessionImpl,
@@ -55,11 +60,196 @@ fn schema_or_default(schema: &Option<Ident>) -> &str {
         .map_or_else(|| DEFAULT_SCHEMA_NAME, |s| &s.value)
 }
 
+/// Lists the fragments of the tables, materialized views and indexes in the schema from meta,
+/// together with their names.
+async fn list_table_fragments(
+    session: &SessionImpl,
+    schema: &Option<Ident>,
+) -> Result<Vec<(String, TableFragments)>> {
+    let names: HashMap<u32, String> = {
+        let catalog_reader = session.env().catalog_reader().read_guard();
+        let schema =
+            catalog_reader.get_schema_by_name(session.database(), schema_or_default(schema))?;
+        schema
+            .iter_table()
+            .chain(schema.iter_mv())
+            .chain(schema.iter_index())
+            .map(|t| (t.id().table_id, t.name().to_owned()))
+            .collect()
+    };
+    // An empty list means all the tables to meta.
+    if names.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let table_ids = names.keys().copied().collect_vec();
+    let table_fragments = session
+        .env()
+        .meta_client()
+        .list_table_fragments(&table_ids)
+        .await?;
+    Ok(table_fragments
+        .into_iter()
+        .filter_map(|table_fragments| {
+            let name = names.get(&table_fragments.table_id)?.clone();
+            Some((name, table_fragments))
+        })
+        .sorted_by(|(a, _), (b, _)| a.cmp(b))
+        .collect())
+}
+
+fn handle_show_fragments(table_fragments: Vec<(String, TableFragments)>) -> PgResponse {
+    let rows = table_fragments
+        .iter()
+        .flat_map(|(name, table_fragments)| {
+            table_fragments
+                .fragments
+                .values()
+                .sorted_by_key(|fragment| fragment.fragment_id)
+                .map(move |fragment| {
+                    Row::new(vec![
+                        Some(fragment.fragment_id.to_string()),
+                        Some(name.clone()),
+                        Some(format!("{:?}", fragment.get_fragment_type().unwrap())),
+                        Some(format!("{:?}", fragment.get_distribution_type().unwrap())),
+                        Some(fragment.actors.len().to_string()),
+                        Some(fragment.actors.iter().map(|a| a.actor_id).join(", ")),
+                    ])
+                })
+        })
+        .collect_vec();
+
+    PgResponse::new(
+        StatementType::SHOW_COMMAND,
+        rows.len() as i32,
+        rows,
+        vec![
+            PgFieldDescriptor::new("Id".to_owned(), TypeOid::Int32),
+            PgFieldDescriptor::new("Relation".to_owned(), TypeOid::Varchar),
+            PgFieldDescriptor::new("Type".to_owned(), TypeOid::Varchar),
+            PgFieldDescriptor::new("Distribution".to_owned(), TypeOid::Varchar),
+            PgFieldDescriptor::new("Parallelism".to_owned(), TypeOid::Int32),
+            PgFieldDescriptor::new("Actors".to_owned(), TypeOid::Varchar),
+        ],
+    )
+}
+
+/// Lists the internal tables of the relations in `table_fragments`. The internal tables whose
+/// states can be scanned are named by `internal_table_names`.
+fn handle_show_internal_tables(
+    table_fragments: Vec<(String, TableFragments)>,
+    internal_table_names: HashMap<u32, String>,
+) -> PgResponse {
+    let internal_table_names = &internal_table_names;
+    let rows = table_fragments
+        .iter()
+        .flat_map(|(name, table_fragments)| {
+            // All actors of a fragment share the same plan, so looking at one of them is enough to
+            // tell which fragment a state table belongs to.
+            let mut fragment_by_table_id = HashMap::new();
+            for fragment in table_fragments.fragments.values() {
+                if let Some(actor) = fragment.actors.first() {
+                    let mut stack = actor.nodes.iter().collect_vec();
+                    while let Some(node) = stack.pop() {
+                        for table_id in state_table_ids(node) {
+                            fragment_by_table_id.insert(table_id, fragment.fragment_id);
+                        }
+                        stack.extend(&node.input);
+                    }
+                }
+            }
+
+            table_fragments
+                .internal_table_ids
+                .iter()
+                .sorted()
+                .map(move |table_id| {
+                    Row::new(vec![
+                        Some(table_id.to_string()),
+                        internal_table_names.get(table_id).cloned(),
+                        Some(name.clone()),
+                        fragment_by_table_id
+                            .get(table_id)
+                            .map(|fragment_id| fragment_id.to_string()),
+                    ])
+                })
+        })
+        .collect_vec();
+
+    PgResponse::new(
+        StatementType::SHOW_COMMAND,
+        rows.len() as i32,
+        rows,
+        vec![
+            PgFieldDescriptor::new("Id".to_owned(), TypeOid::Int32),
+            PgFieldDescriptor::new("Name".to_owned(), TypeOid::Varchar),
+            PgFieldDescriptor::new("Relation".to_owned(), TypeOid::Varchar),
+            PgFieldDescriptor::new("Fragment".to_owned(), TypeOid::Int32),
+        ],
+    )
+}
+
+fn handle_show_events(events: Vec<EventLog>) -> Result<PgResponse> {
+    let rows = events
+        .iter()
+        .map(|event| {
+            let time = NaiveDateTimeWrapper::from_protobuf(event.timestamp as i64 * 1000)?;
+            Ok(Row::new(vec![
+                Some(event.id.to_string()),
+                Some(time.to_string()),
+                Some(format!("{:?}", event.event_type())),
+                Some(event.info.clone()),
+            ]))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(PgResponse::new(
+        StatementType::SHOW_COMMAND,
+        rows.len() as i32,
+        rows,
+        vec![
+            PgFieldDescriptor::new("Id".to_owned(), TypeOid::BigInt),
+            PgFieldDescriptor::new("Time".to_owned(), TypeOid::Timestamp),
+            PgFieldDescriptor::new("Type".to_owned(), TypeOid::Varchar),
+            PgFieldDescriptor::new("Info".to_owned(), TypeOid::Varchar),
+        ],
+    ))
+}
+
 pub async fn handle_show_object(
     context: OptimizerContext,
     command: ShowObject,
 ) -> Result<PgResponse> {
     let session = context.session_ctx;
+
+    // These are listed from meta, so handle them before locking the catalog.
+    match &command {
+        ShowObject::Fragment { schema } => {
+            let table_fragments = list_table_fragments(&session, schema).await?;
+            return Ok(handle_show_fragments(table_fragments));
+        }
+        ShowObject::InternalTable { schema } => {
+            let table_fragments = list_table_fragments(&session, schema).await?;
+            let internal_table_names = session
+                .env()
+                .catalog_reader()
+                .read_guard()
+                .get_schema_by_name(session.database(), schema_or_default(schema))?
+                .iter_internal_table()
+                .map(|t| (t.id().table_id, t.name().to_owned()))
+                .collect();
+            return Ok(handle_show_internal_tables(
+                table_fragments,
+                internal_table_names,
+            ));
+        }
+        ShowObject::Event => {
+            let events = session.env().meta_client().list_event_log(0).await?;
+            return handle_show_events(events);
+        }
+        _ => {}
+    }
+
     let catalog_reader = session.env().catalog_reader().read_guard();
 
     let names = match command {
@@ -101,6 +291,9 @@ pub async fn handle_show_object(
                 ],
             ));
         }
+        ShowObject::Fragment { .. } | ShowObject::InternalTable { .. } | ShowObject::Event => {
+            unreachable!()
+        }
     };
 
     let rows = names
@@ -121,6 +314,9 @@ mod tests {
     use std::collections::HashMap;
     use std::ops::Index;
 
+    use futures::TryStreamExt;
+    use pgwire::types::Row;
+
     use crate::test_utils::{create_proto_file, LocalFrontend, PROTO_FILE_DATA};
 
     #[tokio::test]
@@ -153,6 +349,25 @@ mod tests {
         assert_eq!(rows, vec!["Row([Some(\"t2\")])".to_string()]);
     }
 
+    #[tokio::test]
+    async fn test_show_fragments() {
+        let frontend = LocalFrontend::new(Default::default()).await;
+        frontend.run_sql("CREATE TABLE t (v1 INT)").await.unwrap();
+
+        // The mock meta client doesn't know any fragments.
+        let rows = frontend.query_formatted_result("SHOW FRAGMENTS").await;
+        assert!(rows.is_empty());
+        let rows = frontend
+            .query_formatted_result("SHOW INTERNAL TABLES FROM dev")
+            .await;
+        assert!(rows.is_empty());
+        assert!(frontend.run_sql("SHOW FRAGMENTS FROM s1").await.is_err());
+
+        // Nor any events.
+        let rows = frontend.query_formatted_result("SHOW EVENTS").await;
+        assert!(rows.is_empty());
+    }
+
     #[tokio::test]
     async fn test_show_column() {
         let proto_file = create_proto_file(PROTO_FILE_DATA);
@@ -166,9 +381,10 @@ mod tests {
         frontend.run_sql(sql).await.unwrap();
 
         let sql = "show columns from t";
-        let pg_response = frontend.run_sql(sql).await.unwrap();
+        let mut pg_response = frontend.run_sql(sql).await.unwrap();
+        let rows: Vec<Row> = pg_response.values_stream().try_concat().await.unwrap();
 
-        let columns = pg_response
+        let columns = rows
             .iter()
             .map(|row| {
                 (
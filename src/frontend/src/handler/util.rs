@@ -21,7 +21,7 @@ use risingwave_common::catalog::{ColumnDesc, Field};
 use risingwave_common::types::{DataType, ScalarRefImpl};
 
 /// Format scalars according to postgres convention.
-fn pg_value_format(d: ScalarRefImpl) -> String {
+pub fn pg_value_format(d: ScalarRefImpl) -> String {
     match d {
         ScalarRefImpl::Bool(b) => if b { "t" } else { "f" }.to_string(),
         ScalarRefImpl::Float32(v) => pg_float_format(v),
@@ -103,6 +103,24 @@ pub fn data_type_to_type_oid(data_type: DataType) -> TypeOid {
     }
 }
 
+/// Returns the data type of a parameter given by its type oid.
+pub fn type_oid_to_data_type(type_oid: TypeOid) -> DataType {
+    match type_oid {
+        TypeOid::Boolean => DataType::Boolean,
+        TypeOid::BigInt => DataType::Int64,
+        TypeOid::SmallInt => DataType::Int16,
+        TypeOid::Int => DataType::Int32,
+        TypeOid::Float4 => DataType::Float32,
+        TypeOid::Float8 => DataType::Float64,
+        TypeOid::CharArray | TypeOid::Varchar => DataType::Varchar,
+        TypeOid::Date => DataType::Date,
+        TypeOid::Time => DataType::Time,
+        TypeOid::Timestamp => DataType::Timestamp,
+        TypeOid::Timestampz => DataType::Timestampz,
+        TypeOid::Decimal => DataType::Decimal,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use risingwave_common::array::*;
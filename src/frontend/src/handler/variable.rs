@@ -0,0 +1,121 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use itertools::Itertools;
+use pgwire::pg_field_descriptor::{PgFieldDescriptor, TypeOid};
+use pgwire::pg_response::{PgResponse, StatementType};
+use pgwire::types::Row;
+use risingwave_common::error::{ErrorCode, Result};
+use risingwave_sqlparser::ast::{Ident, SetVariableValue, Value};
+
+use crate::session::OptimizerContext;
+
+pub(super) fn handle_set(
+    context: OptimizerContext,
+    name: Ident,
+    value: Vec<SetVariableValue>,
+) -> Result<PgResponse> {
+    // All the configurations take a single value for now.
+    let [value] = <[SetVariableValue; 1]>::try_from(value).map_err(|_| {
+        ErrorCode::InvalidInputSyntax(format!("SET {} takes only one argument", name))
+    })?;
+    context
+        .session_ctx
+        .set_config(&name.value, &to_string(&value))?;
+
+    Ok(PgResponse::empty_result(StatementType::SET_OPTION))
+}
+
+/// Handles `SHOW <name>` and `SHOW ALL`.
+pub(super) fn handle_show(context: OptimizerContext, variable: Vec<Ident>) -> Result<PgResponse> {
+    let config = context.session_ctx.config();
+    let name = variable.iter().map(|ident| &ident.value).join(" ");
+
+    if name.eq_ignore_ascii_case("ALL") {
+        let rows = config
+            .show_all()
+            .into_iter()
+            .map(|(name, value, description)| {
+                Row::new(vec![
+                    Some(name.to_ascii_lowercase()),
+                    Some(value.to_string()),
+                    Some(description.to_string()),
+                ])
+            })
+            .collect_vec();
+        return Ok(PgResponse::new(
+            StatementType::SHOW_COMMAND,
+            rows.len() as i32,
+            rows,
+            vec![
+                PgFieldDescriptor::new("Name".to_owned(), TypeOid::Varchar),
+                PgFieldDescriptor::new("Setting".to_owned(), TypeOid::Varchar),
+                PgFieldDescriptor::new("Description".to_owned(), TypeOid::Varchar),
+            ],
+        ));
+    }
+
+    let value = config.get(&name)?.to_string();
+    Ok(PgResponse::new(
+        StatementType::SHOW_COMMAND,
+        1,
+        vec![Row::new(vec![Some(value)])],
+        vec![PgFieldDescriptor::new(
+            name.to_ascii_lowercase(),
+            TypeOid::Varchar,
+        )],
+    ))
+}
+
+/// Convert any set variable to String.
+/// For example, TRUE -> "TRUE", 1 -> "1", 'UTC' -> "UTC".
+fn to_string(value: &SetVariableValue) -> String {
+    match value {
+        SetVariableValue::Literal(Value::SingleQuotedString(s)) => s.clone(),
+        _ => format!("{}", value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::LocalFrontend;
+
+    #[tokio::test]
+    async fn test_set_and_show() {
+        let frontend = LocalFrontend::new(Default::default()).await;
+        frontend
+            .run_sql("SET streaming_parallelism TO 2")
+            .await
+            .unwrap();
+        frontend
+            .run_sql("SET timezone = 'Asia/Shanghai'")
+            .await
+            .unwrap();
+
+        let rows = frontend
+            .query_formatted_result("SHOW STREAMING_PARALLELISM")
+            .await;
+        assert_eq!(rows, vec!["Row([Some(\"2\")])".to_string()]);
+        let rows = frontend.query_formatted_result("SHOW timezone").await;
+        assert_eq!(rows, vec!["Row([Some(\"Asia/Shanghai\")])".to_string()]);
+        let rows = frontend.query_formatted_result("SHOW ALL").await;
+        assert!(rows
+            .iter()
+            .any(|row| row.contains("streaming_parallelism") && row.contains("\"2\"")));
+
+        assert!(frontend.run_sql("SET query_epoch TO abc").await.is_err());
+        assert!(frontend.run_sql("SET no_such_config TO 1").await.is_ok());
+        assert!(frontend.run_sql("SHOW no_such_config").await.is_err());
+    }
+}
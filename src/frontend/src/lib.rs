@@ -38,6 +38,8 @@
 pub mod catalog;
 pub mod binder;
 pub mod expr;
+#[cfg(not(madsim))]
+pub mod flight_service;
 pub mod handler;
 pub mod observer;
 pub mod optimizer;
@@ -53,7 +55,7 @@ pub mod test_utils;
 extern crate core;
 extern crate risingwave_common;
 
-mod config;
+pub mod config;
 pub mod user;
 
 use std::ffi::OsString;
@@ -61,7 +63,7 @@ use std::iter;
 use std::sync::Arc;
 
 use clap::Parser;
-use pgwire::pg_server::pg_serve;
+use pgwire::pg_server::{pg_serve, ConnectionLimits, PgServerConfig, TlsConfig};
 use session::SessionManagerImpl;
 
 #[derive(Parser, Clone, Debug)]
@@ -84,6 +86,53 @@ pub struct FrontendOpts {
     /// No given `config_path` means to use default config.
     #[clap(long, default_value = "")]
     pub config_path: String,
+
+    /// The address to serve the results of batch queries over Arrow Flight at. Not served if not
+    /// specified.
+    #[clap(long)]
+    pub flight_listen_addr: Option<String>,
+
+    /// The certificate in PEM to serve connections over TLS with. Connections are only upgraded to
+    /// TLS if both the certificate and the key are specified.
+    #[clap(long, requires = "ssl_key")]
+    pub ssl_cert: Option<String>,
+
+    /// The private key in PEM of `ssl_cert`.
+    #[clap(long, requires = "ssl_cert")]
+    pub ssl_key: Option<String>,
+
+    /// The CA certificates in PEM to verify the certificates of clients with. Clients connecting
+    /// over TLS are required to present a certificate if specified.
+    #[clap(long, requires = "ssl_cert")]
+    pub ssl_ca: Option<String>,
+
+    /// The maximum number of connections to the frontend. `0` means unlimited.
+    #[clap(long, default_value = "0")]
+    pub max_connections: usize,
+
+    /// The maximum number of connections of each user to the frontend. `0` means unlimited.
+    #[clap(long, default_value = "0")]
+    pub max_connections_per_user: usize,
+}
+
+impl FrontendOpts {
+    fn pg_server_config(&self) -> PgServerConfig {
+        let tls = match (&self.ssl_cert, &self.ssl_key) {
+            (Some(cert_path), Some(key_path)) => Some(TlsConfig {
+                cert_path: cert_path.clone(),
+                key_path: key_path.clone(),
+                client_ca_path: self.ssl_ca.clone(),
+            }),
+            _ => None,
+        };
+        PgServerConfig {
+            tls,
+            limits: ConnectionLimits {
+                max_connections: self.max_connections,
+                max_connections_per_user: self.max_connections_per_user,
+            },
+        }
+    }
 }
 
 impl Default for FrontendOpts {
@@ -101,6 +150,18 @@ pub fn start(opts: FrontendOpts) -> Pin<Box<dyn Future<Output = ()> + Send>> {
     // slow compile in release mode.
     Box::pin(async move {
         let session_mgr = Arc::new(SessionManagerImpl::new(&opts).await.unwrap());
-        pg_serve(&opts.host, session_mgr).await.unwrap();
+        if let Some(addr) = &opts.flight_listen_addr {
+            // Arrow Flight runs on the real tonic, which is not available in the simulation.
+            #[cfg(not(madsim))]
+            tokio::spawn(flight_service::flight_serve(
+                addr.clone(),
+                session_mgr.clone(),
+            ));
+            #[cfg(madsim)]
+            tracing::warn!("ignore the Arrow Flight address {} in the simulation", addr);
+        }
+        pg_serve(&opts.host, session_mgr, opts.pg_server_config())
+            .await
+            .unwrap();
     })
 }
@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use risingwave_common::error::Result;
+use risingwave_pb::meta::{EventLog, TableFragments};
 use risingwave_rpc_client::{HummockMetaClient, MetaClient};
 
 /// A wrapper around the `MetaClient` that only provides a minor set of meta rpc.
@@ -24,7 +25,17 @@ use risingwave_rpc_client::{HummockMetaClient, MetaClient};
 pub trait FrontendMetaClient: Send + Sync {
     async fn pin_snapshot(&self, last_pinned: u64) -> Result<u64>;
 
-    async fn flush(&self) -> Result<()>;
+    async fn pin_specific_snapshot(&self, epoch: u64) -> Result<u64>;
+
+    async fn flush(&self) -> Result<u64>;
+
+    async fn get_current_epoch(&self) -> Result<u64>;
+
+    async fn list_table_fragments(&self, table_ids: &[u32]) -> Result<Vec<TableFragments>>;
+
+    async fn list_event_log(&self, limit: u32) -> Result<Vec<EventLog>>;
+
+    async fn alter_parallelism(&self, table_id: u32, parallelism: u32) -> Result<()>;
 
     async fn unpin_snapshot(&self, epoch: u64) -> Result<()>;
 
@@ -39,10 +50,30 @@ impl FrontendMetaClient for FrontendMetaClientImpl {
         self.0.pin_snapshot(last_pinned).await
     }
 
-    async fn flush(&self) -> Result<()> {
+    async fn pin_specific_snapshot(&self, epoch: u64) -> Result<u64> {
+        self.0.pin_specific_snapshot(epoch).await
+    }
+
+    async fn flush(&self) -> Result<u64> {
         self.0.flush().await
     }
 
+    async fn get_current_epoch(&self) -> Result<u64> {
+        self.0.get_current_epoch().await
+    }
+
+    async fn list_table_fragments(&self, table_ids: &[u32]) -> Result<Vec<TableFragments>> {
+        self.0.list_table_fragments(table_ids).await
+    }
+
+    async fn list_event_log(&self, limit: u32) -> Result<Vec<EventLog>> {
+        self.0.list_event_log(limit).await
+    }
+
+    async fn alter_parallelism(&self, table_id: u32, parallelism: u32) -> Result<()> {
+        self.0.alter_parallelism(table_id, parallelism).await
+    }
+
     async fn unpin_snapshot(&self, epoch: u64) -> Result<()> {
         self.0.unpin_snapshot(&[epoch]).await
     }
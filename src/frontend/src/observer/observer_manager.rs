@@ -97,6 +97,9 @@ impl ObserverManager {
                 for source in snapshot.source {
                     catalog_guard.create_source(source)
                 }
+                for function in snapshot.functions {
+                    catalog_guard.create_function(&function)
+                }
                 for user in snapshot.users {
                     user_guard.create_user(user)
                 }
@@ -137,6 +140,7 @@ impl ObserverManager {
                 Operation::Delete => {
                     catalog_guard.drop_table(table.database_id, table.schema_id, table.id.into())
                 }
+                Operation::Update => catalog_guard.update_table(table),
                 _ => panic!("receive an unsupported notify {:?}", resp),
             },
             Info::Source(source) => match resp.operation() {
@@ -144,6 +148,16 @@ impl ObserverManager {
                 Operation::Delete => {
                     catalog_guard.drop_source(source.database_id, source.schema_id, source.id)
                 }
+                Operation::Update => catalog_guard.update_source(source.clone()),
+                _ => panic!("receive an unsupported notify {:?}", resp),
+            },
+            Info::Function(function) => match resp.operation() {
+                Operation::Add => catalog_guard.create_function(function),
+                Operation::Delete => catalog_guard.drop_function(
+                    function.database_id,
+                    function.schema_id,
+                    function.id,
+                ),
                 _ => panic!("receive an unsupported notify {:?}", resp),
             },
             _ => unreachable!(),
@@ -189,7 +203,11 @@ impl ObserverManager {
         };
 
         match info {
-            Info::Database(_) | Info::Schema(_) | Info::Table(_) | Info::Source(_) => {
+            Info::Database(_)
+            | Info::Schema(_)
+            | Info::Table(_)
+            | Info::Source(_)
+            | Info::Function(_) => {
                 self.handle_catalog_notification(resp);
             }
             Info::Node(node) => {
@@ -209,6 +227,14 @@ impl ObserverManager {
                     .update_snapshot_status(hummock_snapshot.epoch)
                     .await;
             }
+            Info::HummockVersion(_) => {
+                panic!("receive an unsupported notify {:?}", resp)
+            }
+            Info::LogFilter(filter) => {
+                if let Err(e) = risingwave_logging::set_log_filter(&filter.directives) {
+                    tracing::warn!("failed to change the log filter: {}", e);
+                }
+            }
         }
     }
 
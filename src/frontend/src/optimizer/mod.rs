@@ -29,7 +29,9 @@ use risingwave_common::catalog::Schema;
 use risingwave_common::error::Result;
 
 use self::heuristic::{ApplyOrder, HeuristicOptimizer};
-use self::plan_node::{BatchProject, Convention, LogicalProject, StreamMaterialize};
+use self::plan_node::{
+    BatchProject, Convention, LogicalProject, StreamEmitOnWindowClose, StreamMaterialize,
+};
 use self::property::RequiredDist;
 use self::rule::*;
 use crate::catalog::TableId;
@@ -54,6 +56,7 @@ pub struct PlanRoot {
     out_fields: FixedBitSet,
     out_names: Vec<String>,
     schema: Schema,
+    emit_on_window_close: bool,
 }
 
 impl PlanRoot {
@@ -86,6 +89,7 @@ impl PlanRoot {
             out_fields,
             out_names,
             schema,
+            emit_on_window_close: false,
         }
     }
 
@@ -253,7 +257,10 @@ impl PlanRoot {
 
     /// Optimize and generate a create materialize view plan.
     pub fn gen_create_mv_plan(&mut self, mv_name: String) -> Result<StreamMaterialize> {
-        let stream_plan = self.gen_stream_plan()?;
+        let mut stream_plan = self.gen_stream_plan()?;
+        if self.emit_on_window_close {
+            stream_plan = StreamEmitOnWindowClose::create(stream_plan)?;
+        }
         StreamMaterialize::create(
             stream_plan,
             mv_name,
@@ -285,6 +292,11 @@ impl PlanRoot {
     pub fn set_required_dist(&mut self, required_dist: RequiredDist) {
         self.required_dist = required_dist;
     }
+
+    /// Make the created materialized view emit the results of windows once they're closed.
+    pub fn set_emit_on_window_close(&mut self, emit_on_window_close: bool) {
+        self.emit_on_window_close = emit_on_window_close;
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,92 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+
+use risingwave_common::catalog::{Field, Schema};
+use risingwave_common::error::Result;
+use risingwave_common::types::DataType;
+use risingwave_pb::batch_plan::plan_node::NodeBody;
+use risingwave_pb::batch_plan::ExportNode;
+
+use super::{PlanBase, PlanRef, PlanTreeNodeUnary, ToBatchProst, ToDistributedBatch};
+use crate::optimizer::plan_node::ToLocalBatch;
+use crate::optimizer::property::Order;
+
+/// `BatchExport` writes the rows of its input to Parquet files at `location`, and outputs the
+/// number of rows written by each task. It keeps the distribution of its input, so that the files
+/// are written in parallel.
+#[derive(Debug, Clone)]
+pub struct BatchExport {
+    pub base: PlanBase,
+    input: PlanRef,
+    location: String,
+}
+
+impl BatchExport {
+    pub fn new(input: PlanRef, location: String) -> Self {
+        let ctx = input.ctx();
+        let schema = Schema::new(vec![Field::with_name(DataType::Int64, "count")]);
+        let base = PlanBase::new_batch(
+            ctx,
+            schema,
+            input.distribution().clone(),
+            Order::any().clone(),
+        );
+        BatchExport {
+            base,
+            input,
+            location,
+        }
+    }
+}
+
+impl fmt::Display for BatchExport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "BatchExport {{ location: {:?} }}", self.location)
+    }
+}
+
+impl PlanTreeNodeUnary for BatchExport {
+    fn input(&self) -> PlanRef {
+        self.input.clone()
+    }
+
+    fn clone_with_input(&self, input: PlanRef) -> Self {
+        Self::new(input, self.location.clone())
+    }
+}
+
+impl_plan_tree_node_for_unary! { BatchExport }
+
+impl ToDistributedBatch for BatchExport {
+    fn to_distributed(&self) -> Result<PlanRef> {
+        let new_input = self.input().to_distributed()?;
+        Ok(self.clone_with_input(new_input).into())
+    }
+}
+
+impl ToBatchProst for BatchExport {
+    fn to_batch_prost_body(&self) -> NodeBody {
+        NodeBody::Export(ExportNode {
+            location: self.location.clone(),
+        })
+    }
+}
+
+impl ToLocalBatch for BatchExport {
+    fn to_local(&self) -> Result<PlanRef> {
+        unreachable!()
+    }
+}
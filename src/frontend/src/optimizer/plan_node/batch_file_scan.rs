@@ -0,0 +1,82 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+
+use risingwave_common::catalog::{ColumnDesc, Field, Schema};
+use risingwave_common::error::Result;
+use risingwave_pb::batch_plan::plan_node::NodeBody;
+use risingwave_pb::batch_plan::FileScanNode;
+
+use super::{PlanBase, PlanRef, PlanTreeNodeLeaf, ToBatchProst, ToDistributedBatch};
+use crate::optimizer::plan_node::ToLocalBatch;
+use crate::optimizer::property::{Distribution, Order};
+use crate::session::OptimizerContextRef;
+
+/// `BatchFileScan` reads the rows of the files in `node`, whose schema is the columns of the node.
+/// The files are assigned to the plan by the caller, so it's always a singleton.
+#[derive(Debug, Clone)]
+pub struct BatchFileScan {
+    pub base: PlanBase,
+    node: FileScanNode,
+}
+
+impl PlanTreeNodeLeaf for BatchFileScan {}
+impl_plan_tree_node_for_leaf!(BatchFileScan);
+
+impl BatchFileScan {
+    pub fn new(ctx: OptimizerContextRef, node: FileScanNode) -> Self {
+        let fields = node
+            .column_descs
+            .iter()
+            .map(|column_desc| Field::from(&ColumnDesc::from(column_desc)))
+            .collect();
+        let base = PlanBase::new_batch(
+            ctx,
+            Schema { fields },
+            Distribution::Single,
+            Order::any().clone(),
+        );
+        BatchFileScan { base, node }
+    }
+}
+
+impl fmt::Display for BatchFileScan {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "BatchFileScan {{ store: {}, files: {} }}",
+            self.node.store_url,
+            self.node.paths.len()
+        )
+    }
+}
+
+impl ToDistributedBatch for BatchFileScan {
+    fn to_distributed(&self) -> Result<PlanRef> {
+        Ok(self.clone().into())
+    }
+}
+
+impl ToBatchProst for BatchFileScan {
+    fn to_batch_prost_body(&self) -> NodeBody {
+        NodeBody::FileScan(self.node.clone())
+    }
+}
+
+impl ToLocalBatch for BatchFileScan {
+    fn to_local(&self) -> Result<PlanRef> {
+        Ok(self.clone().into())
+    }
+}
@@ -0,0 +1,146 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+
+use risingwave_common::error::Result;
+use risingwave_pb::batch_plan::plan_node::NodeBody;
+use risingwave_pb::batch_plan::LookupJoinNode;
+use risingwave_pb::plan_common::{
+    CellBasedTableDesc, ColumnDesc as ProstColumnDesc, OrderedColumnDesc as ProstOrderedColumnDesc,
+};
+
+use super::{
+    LogicalJoin, LogicalScan, PlanBase, PlanRef, PlanTreeNodeBinary, PlanTreeNodeUnary,
+    ToBatchProst, ToDistributedBatch,
+};
+use crate::expr::Expr;
+use crate::optimizer::plan_node::ToLocalBatch;
+use crate::optimizer::property::{Order, RequiredDist};
+
+/// `BatchLookupJoin` implements [`super::LogicalJoin`] by looking up the right-side table with the
+/// join key of each row from the left side, instead of scanning the whole table. The right side
+/// must be a table scan whose order key prefix is covered by the equal join keys.
+#[derive(Debug, Clone)]
+pub struct BatchLookupJoin {
+    pub base: PlanBase,
+    logical: LogicalJoin,
+
+    /// The table scanned on the right side.
+    right_scan: LogicalScan,
+
+    /// Columns of the left side to look up the right table with, in the order of the table's
+    /// order key.
+    outer_side_key: Vec<usize>,
+}
+
+impl BatchLookupJoin {
+    pub fn new(logical: LogicalJoin, right_scan: LogicalScan, outer_side_key: Vec<usize>) -> Self {
+        let ctx = logical.base.ctx.clone();
+        let dist = logical
+            .l2i_col_mapping()
+            .composite(&logical.i2o_col_mapping())
+            .rewrite_provided_distribution(logical.left().distribution());
+        let base = PlanBase::new_batch(ctx, logical.schema().clone(), dist, Order::any().clone());
+
+        Self {
+            base,
+            logical,
+            right_scan,
+            outer_side_key,
+        }
+    }
+
+    /// Get a reference to the batch lookup join's outer side key.
+    pub fn outer_side_key(&self) -> &[usize] {
+        &self.outer_side_key
+    }
+}
+
+impl fmt::Display for BatchLookupJoin {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "BatchLookupJoin {{ type: {:?}, predicate: {}, table: {} }}",
+            self.logical.join_type(),
+            self.logical.on(),
+            self.right_scan.table_name()
+        )
+    }
+}
+
+impl PlanTreeNodeUnary for BatchLookupJoin {
+    fn input(&self) -> PlanRef {
+        self.logical.left()
+    }
+
+    fn clone_with_input(&self, input: PlanRef) -> Self {
+        Self::new(
+            self.logical
+                .clone_with_left_right(input, self.logical.right()),
+            self.right_scan.clone(),
+            self.outer_side_key.clone(),
+        )
+    }
+}
+
+impl_plan_tree_node_for_unary! { BatchLookupJoin }
+
+impl ToDistributedBatch for BatchLookupJoin {
+    fn to_distributed(&self) -> Result<PlanRef> {
+        let new_input = self.input().to_distributed()?;
+        Ok(self.clone_with_input(new_input).into())
+    }
+}
+
+impl ToBatchProst for BatchLookupJoin {
+    fn to_batch_prost_body(&self) -> NodeBody {
+        let table_desc = self.right_scan.table_desc();
+        NodeBody::LookupJoin(LookupJoinNode {
+            join_type: self.logical.join_type() as i32,
+            outer_side_key: self.outer_side_key.iter().map(|&a| a as i32).collect(),
+            table_desc: Some(CellBasedTableDesc {
+                table_id: table_desc.table_id.into(),
+                pk: table_desc
+                    .order_desc
+                    .iter()
+                    .map(|c| ProstOrderedColumnDesc {
+                        column_desc: Some((&c.column_desc).into()),
+                        order: c.order.to_prost() as i32,
+                    })
+                    .collect(),
+                retention_seconds: table_desc.retention_seconds,
+            }),
+            inner_column_descs: self
+                .right_scan
+                .column_descs()
+                .iter()
+                .map(ProstColumnDesc::from)
+                .collect(),
+            condition: self
+                .logical
+                .on()
+                .as_expr_unless_true()
+                .map(|x| x.to_expr_proto()),
+        })
+    }
+}
+
+impl ToLocalBatch for BatchLookupJoin {
+    fn to_local(&self) -> Result<PlanRef> {
+        let input = RequiredDist::single()
+            .enforce_if_not_satisfies(self.input().to_local()?, Order::any())?;
+        Ok(self.clone_with_input(input).into())
+    }
+}
@@ -82,10 +82,12 @@ impl ToBatchProst for BatchSeqScan {
             .map(ProstColumnDesc::from)
             .collect();
 
+        let table_desc = self.logical.table_desc();
         NodeBody::RowSeqScan(RowSeqScanNode {
             table_desc: Some(CellBasedTableDesc {
-                table_id: self.logical.table_desc().table_id.into(),
+                table_id: table_desc.table_id.into(),
                 pk: vec![], // TODO:
+                retention_seconds: table_desc.retention_seconds,
             }),
             column_descs,
         })
@@ -0,0 +1,82 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+
+use risingwave_common::error::Result;
+use risingwave_pb::batch_plan::plan_node::NodeBody;
+use risingwave_pb::batch_plan::SourceScanNode;
+
+use super::{LogicalSource, PlanBase, PlanRef, ToBatchProst, ToDistributedBatch, ToLocalBatch};
+use crate::optimizer::property::{Distribution, Order};
+
+/// `BatchSource` reads the messages of a connector source from the earliest offsets to the latest
+/// ones when the query starts. The splits are listed by the executor, so it's always a singleton.
+#[derive(Debug, Clone)]
+pub struct BatchSource {
+    pub base: PlanBase,
+    logical: LogicalSource,
+}
+
+impl BatchSource {
+    pub fn new(logical: LogicalSource) -> Self {
+        let base = PlanBase::new_batch(
+            logical.ctx(),
+            logical.schema().clone(),
+            Distribution::Single,
+            Order::any().clone(),
+        );
+        Self { base, logical }
+    }
+}
+
+impl_plan_tree_node_for_leaf! { BatchSource }
+
+impl fmt::Display for BatchSource {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "BatchSource {{ source: {}, columns: [{}] }}",
+            self.logical.source_catalog.name,
+            self.logical.column_names().join(", ")
+        )
+    }
+}
+
+impl ToDistributedBatch for BatchSource {
+    fn to_distributed(&self) -> Result<PlanRef> {
+        Ok(self.clone().into())
+    }
+}
+
+impl ToBatchProst for BatchSource {
+    fn to_batch_prost_body(&self) -> NodeBody {
+        let source_catalog = &self.logical.source_catalog;
+        NodeBody::SourceScan(SourceScanNode {
+            source_id: source_catalog.id,
+            info: source_catalog.stream_source_info.clone(),
+            column_ids: source_catalog
+                .columns
+                .iter()
+                .map(|c| c.column_id().get_id())
+                .collect(),
+        })
+    }
+}
+
+impl ToLocalBatch for BatchSource {
+    fn to_local(&self) -> Result<PlanRef> {
+        Ok(self.clone().into())
+    }
+}
@@ -0,0 +1,76 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+
+use risingwave_common::error::Result;
+use risingwave_pb::batch_plan::plan_node::NodeBody;
+use risingwave_pb::batch_plan::UnnestNode;
+
+use super::{LogicalUnnest, PlanBase, PlanRef, PlanTreeNodeLeaf, ToBatchProst, ToDistributedBatch};
+use crate::expr::Expr;
+use crate::optimizer::plan_node::ToLocalBatch;
+use crate::optimizer::property::{Distribution, Order};
+
+#[derive(Debug, Clone)]
+pub struct BatchUnnest {
+    pub base: PlanBase,
+    logical: LogicalUnnest,
+}
+
+impl PlanTreeNodeLeaf for BatchUnnest {}
+impl_plan_tree_node_for_leaf!(BatchUnnest);
+
+impl BatchUnnest {
+    pub fn new(logical: LogicalUnnest) -> Self {
+        Self::with_dist(logical, Distribution::Single)
+    }
+
+    pub fn with_dist(logical: LogicalUnnest, dist: Distribution) -> Self {
+        let ctx = logical.base.ctx.clone();
+        let base = PlanBase::new_batch(ctx, logical.schema().clone(), dist, Order::any().clone());
+        BatchUnnest { base, logical }
+    }
+
+    #[must_use]
+    pub fn logical(&self) -> &LogicalUnnest {
+        &self.logical
+    }
+}
+
+impl fmt::Display for BatchUnnest {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.logical.fmt_with_name(f, "BatchUnnest")
+    }
+}
+
+impl ToDistributedBatch for BatchUnnest {
+    fn to_distributed(&self) -> Result<PlanRef> {
+        Ok(Self::with_dist(self.logical().clone(), Distribution::Single).into())
+    }
+}
+
+impl ToBatchProst for BatchUnnest {
+    fn to_batch_prost_body(&self) -> NodeBody {
+        NodeBody::Unnest(UnnestNode {
+            array: Some(self.logical.array.to_expr_proto()),
+        })
+    }
+}
+
+impl ToLocalBatch for BatchUnnest {
+    fn to_local(&self) -> Result<PlanRef> {
+        Ok(Self::with_dist(self.logical().clone(), Distribution::Single).into())
+    }
+}
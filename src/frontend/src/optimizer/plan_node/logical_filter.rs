@@ -22,7 +22,7 @@ use super::{
     PredicatePushdown, ToBatch, ToStream,
 };
 use crate::expr::{assert_input_ref, ExprImpl};
-use crate::optimizer::plan_node::{BatchFilter, StreamFilter};
+use crate::optimizer::plan_node::{BatchFilter, StreamFilter, StreamTemporalFilter};
 use crate::risingwave_common::error::Result;
 use crate::utils::{ColIndexMapping, Condition};
 
@@ -161,8 +161,24 @@ impl ToBatch for LogicalFilter {
 impl ToStream for LogicalFilter {
     fn to_stream(&self) -> Result<PlanRef> {
         let new_input = self.input().to_stream()?;
-        let new_logical = self.clone_with_input(new_input);
-        Ok(StreamFilter::new(new_logical).into())
+        // Temporal filters on `NOW()` are evaluated by `StreamTemporalFilter`s, which also delete
+        // the rows expired later.
+        let (temporal_filters, conjunctions): (Vec<_>, Vec<_>) = self
+            .predicate
+            .conjunctions
+            .iter()
+            .cloned()
+            .partition(StreamTemporalFilter::is_temporal_filter);
+        let mut plan = if conjunctions.is_empty() {
+            new_input
+        } else {
+            let new_logical = Self::new(new_input, Condition { conjunctions });
+            StreamFilter::new(new_logical).into()
+        };
+        for cond in &temporal_filters {
+            plan = StreamTemporalFilter::create(plan, cond)?;
+        }
+        Ok(plan)
     }
 
     fn logical_rewrite_for_stream(&self) -> Result<(PlanRef, ColIndexMapping)> {
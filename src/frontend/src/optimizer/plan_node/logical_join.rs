@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
 use std::fmt;
 
 use fixedbitset::FixedBitSet;
@@ -26,8 +27,8 @@ use super::{
 };
 use crate::expr::{ExprImpl, ExprType};
 use crate::optimizer::plan_node::{
-    BatchFilter, BatchHashJoin, BatchNestedLoopJoin, BatchProject, EqJoinPredicate, LogicalFilter,
-    StreamFilter, StreamProject,
+    BatchFilter, BatchHashJoin, BatchLookupJoin, BatchNestedLoopJoin, BatchProject,
+    EqJoinPredicate, LogicalFilter, StreamFilter, StreamProject,
 };
 use crate::optimizer::property::RequiredDist;
 use crate::utils::{ColIndexMapping, Condition};
@@ -655,6 +656,75 @@ impl PredicatePushdown for LogicalJoin {
     }
 }
 
+impl LogicalJoin {
+    /// Try to convert the join to a [`BatchLookupJoin`]. It's only possible when the right side is
+    /// a table scan and the equal join keys cover the primary key of the table with a prefix of
+    /// its order key, so that each row of the left side looks up the table by the prefix. It can
+    /// be disabled by the session configuration `BATCH_ENABLE_LOOKUP_JOIN`.
+    fn to_batch_lookup_join(
+        &self,
+        logical_join: &LogicalJoin,
+        predicate: &EqJoinPredicate,
+    ) -> Option<PlanRef> {
+        let enabled = self
+            .base
+            .ctx
+            .inner()
+            .session_ctx
+            .config()
+            .batch_enable_lookup_join();
+        if !enabled {
+            return None;
+        }
+        if !matches!(
+            self.join_type,
+            JoinType::Inner | JoinType::LeftOuter | JoinType::LeftSemi | JoinType::LeftAnti
+        ) {
+            return None;
+        }
+        let scan = self.right.as_logical_scan()?;
+        if !scan.predicate().always_true() {
+            return None;
+        }
+
+        let table_desc = scan.table_desc();
+        let r2l_eq_indexes: HashMap<usize, usize> = predicate
+            .eq_indexes()
+            .into_iter()
+            .map(|(left, right)| (right, left))
+            .collect();
+        let mut lookup_table_idx = vec![];
+        let mut outer_side_key = vec![];
+        for order in &table_desc.order_desc {
+            let lookup = table_desc
+                .columns
+                .iter()
+                .position(|c| c.column_id == order.column_desc.column_id)
+                .and_then(|tb_idx| {
+                    let right_idx = scan.output_col_idx().iter().position(|&i| i == tb_idx)?;
+                    Some((tb_idx, *r2l_eq_indexes.get(&right_idx)?))
+                });
+            match lookup {
+                Some((tb_idx, left_idx)) => {
+                    lookup_table_idx.push(tb_idx);
+                    outer_side_key.push(left_idx);
+                }
+                None => break,
+            }
+        }
+        if outer_side_key.is_empty()
+            || !table_desc
+                .pks
+                .iter()
+                .all(|pk| lookup_table_idx.contains(pk))
+        {
+            return None;
+        }
+
+        Some(BatchLookupJoin::new(logical_join.clone(), scan.clone(), outer_side_key).into())
+    }
+}
+
 impl ToBatch for LogicalJoin {
     fn to_batch(&self) -> Result<PlanRef> {
         let predicate = EqJoinPredicate::create(
@@ -671,7 +741,10 @@ impl ToBatch for LogicalJoin {
         let default_indices = (0..new_internal_column_num).collect::<Vec<_>>();
         let logical_join = logical_join.clone_with_output_indices(default_indices.clone());
 
-        let plan = if predicate.has_eq() {
+        let plan = if let Some(lookup_join) = self.to_batch_lookup_join(&logical_join, &predicate) {
+            // Convert to Lookup Join if the equal keys can be used to look up the right table
+            lookup_join
+        } else if predicate.has_eq() {
             // Convert to Hash Join for equal joins
             // For inner joins, pull non-equal conditions to a filter operator on top of it
             let pull_filter = self.join_type == JoinType::Inner && predicate.has_non_eq();
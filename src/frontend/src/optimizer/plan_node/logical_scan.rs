@@ -131,6 +131,12 @@ impl LogicalScan {
             .collect()
     }
 
+    /// Get a reference to the logical scan's output column indices in the table.
+    #[must_use]
+    pub fn output_col_idx(&self) -> &[usize] {
+        &self.output_col_idx
+    }
+
     pub fn table_name(&self) -> &str {
         &self.table_name
     }
@@ -150,6 +156,12 @@ impl LogicalScan {
             .collect()
     }
 
+    /// Get a reference to the logical scan's pushed down predicate.
+    #[must_use]
+    pub fn predicate(&self) -> &Condition {
+        &self.predicate
+    }
+
     /// Get all indexes on this table
     #[must_use]
     pub fn indexes(&self) -> &[(String, Rc<TableDesc>)] {
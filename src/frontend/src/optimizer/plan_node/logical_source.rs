@@ -18,10 +18,11 @@ use std::rc::Rc;
 
 use risingwave_common::catalog::Schema;
 use risingwave_common::error::{ErrorCode, Result, RwError};
+use risingwave_pb::plan_common::RowFormatType;
 
 use super::{
-    ColPrunable, LogicalFilter, LogicalProject, PlanBase, PlanRef, PredicatePushdown, StreamSource,
-    ToBatch, ToStream,
+    BatchSource, ColPrunable, LogicalFilter, LogicalProject, PlanBase, PlanRef, PredicatePushdown,
+    StreamDedup, StreamSource, ToBatch, ToStream,
 };
 use crate::catalog::source_catalog::SourceCatalog;
 use crate::session::OptimizerContextRef;
@@ -97,16 +98,43 @@ impl PredicatePushdown for LogicalSource {
 
 impl ToBatch for LogicalSource {
     fn to_batch(&self) -> Result<PlanRef> {
-        Err(RwError::from(ErrorCode::NotImplemented(
-            "there is no batch source operator".to_string(),
-            None.into(),
-        )))
+        let Some(info) = &self.source_catalog.stream_source_info else {
+            return Err(RwError::from(ErrorCode::NotImplemented(
+                "batch read of table sources".to_string(),
+                None.into(),
+            )));
+        };
+        // The changes can't be applied without the state of the rows.
+        if info.row_format() == RowFormatType::DebeziumJson {
+            return Err(RwError::from(ErrorCode::NotImplemented(
+                "batch read of sources with row format DEBEZIUM_JSON".to_string(),
+                None.into(),
+            )));
+        }
+        Ok(BatchSource::new(self.clone()).into())
     }
 }
 
 impl ToStream for LogicalSource {
     fn to_stream(&self) -> Result<PlanRef> {
-        Ok(StreamSource::new(self.clone()).into())
+        let source = StreamSource::new(self.clone()).into();
+        match &self.source_catalog.dedup {
+            Some(dedup) => {
+                let dedup_cols = dedup
+                    .column_ids
+                    .iter()
+                    .map(|id| {
+                        self.source_catalog
+                            .columns
+                            .iter()
+                            .position(|c| c.column_id().get_id() == *id)
+                            .expect("dedup column not found")
+                    })
+                    .collect();
+                StreamDedup::create(source, dedup_cols, dedup.ttl_ms)
+            }
+            None => Ok(source),
+        }
     }
 
     fn logical_rewrite_for_stream(&self) -> Result<(PlanRef, ColIndexMapping)> {
@@ -144,6 +144,7 @@ impl dyn PlanNode {
             } else {
                 "".into()
             },
+            plan_node_id: self.id().0,
             node_body,
         }
     }
@@ -207,6 +208,8 @@ pub use predicate_pushdown::*;
 
 mod batch_delete;
 mod batch_exchange;
+mod batch_export;
+mod batch_file_scan;
 mod batch_filter;
 mod batch_generate_series;
 mod batch_hash_agg;
@@ -214,12 +217,15 @@ mod batch_hash_join;
 mod batch_hop_window;
 mod batch_insert;
 mod batch_limit;
+mod batch_lookup_join;
 mod batch_nested_loop_join;
 mod batch_project;
 mod batch_seq_scan;
 mod batch_simple_agg;
 mod batch_sort;
+mod batch_source;
 mod batch_topn;
+mod batch_unnest;
 mod batch_update;
 mod batch_values;
 mod logical_agg;
@@ -236,9 +242,12 @@ mod logical_project;
 mod logical_scan;
 mod logical_source;
 mod logical_topn;
+mod logical_unnest;
 mod logical_update;
 mod logical_values;
+mod stream_dedup;
 mod stream_delta_join;
+mod stream_emit_on_window_close;
 mod stream_exchange;
 mod stream_filter;
 mod stream_hash_agg;
@@ -250,10 +259,13 @@ mod stream_project;
 mod stream_simple_agg;
 mod stream_source;
 mod stream_table_scan;
+mod stream_temporal_filter;
 mod stream_topn;
 
 pub use batch_delete::BatchDelete;
 pub use batch_exchange::BatchExchange;
+pub use batch_export::BatchExport;
+pub use batch_file_scan::BatchFileScan;
 pub use batch_filter::BatchFilter;
 pub use batch_generate_series::BatchGenerateSeries;
 pub use batch_hash_agg::BatchHashAgg;
@@ -261,12 +273,15 @@ pub use batch_hash_join::BatchHashJoin;
 pub use batch_hop_window::BatchHopWindow;
 pub use batch_insert::BatchInsert;
 pub use batch_limit::BatchLimit;
+pub use batch_lookup_join::BatchLookupJoin;
 pub use batch_nested_loop_join::BatchNestedLoopJoin;
 pub use batch_project::BatchProject;
 pub use batch_seq_scan::BatchSeqScan;
 pub use batch_simple_agg::BatchSimpleAgg;
 pub use batch_sort::BatchSort;
+pub use batch_source::BatchSource;
 pub use batch_topn::BatchTopN;
+pub use batch_unnest::BatchUnnest;
 pub use batch_update::BatchUpdate;
 pub use batch_values::BatchValues;
 pub use logical_agg::{LogicalAgg, PlanAggCall};
@@ -283,9 +298,12 @@ pub use logical_project::LogicalProject;
 pub use logical_scan::LogicalScan;
 pub use logical_source::LogicalSource;
 pub use logical_topn::LogicalTopN;
+pub use logical_unnest::LogicalUnnest;
 pub use logical_update::LogicalUpdate;
 pub use logical_values::LogicalValues;
+pub use stream_dedup::StreamDedup;
 pub use stream_delta_join::StreamDeltaJoin;
+pub use stream_emit_on_window_close::StreamEmitOnWindowClose;
 pub use stream_exchange::StreamExchange;
 pub use stream_filter::StreamFilter;
 pub use stream_hash_agg::StreamHashAgg;
@@ -297,6 +315,7 @@ pub use stream_project::StreamProject;
 pub use stream_simple_agg::StreamSimpleAgg;
 pub use stream_source::StreamSource;
 pub use stream_table_scan::StreamTableScan;
+pub use stream_temporal_filter::StreamTemporalFilter;
 pub use stream_topn::StreamTopN;
 
 use crate::session::OptimizerContextRef;
@@ -333,6 +352,7 @@ macro_rules! for_all_plan_nodes {
             , { Logical, TopN }
             , { Logical, HopWindow }
             , { Logical, GenerateSeries }
+            , { Logical, Unnest }
             , { Logical, MultiJoin }
             // , { Logical, Sort } we don't need a LogicalSort, just require the Order
             , { Batch, SimpleAgg }
@@ -345,6 +365,7 @@ macro_rules! for_all_plan_nodes {
             , { Batch, SeqScan }
             , { Batch, HashJoin }
             , { Batch, NestedLoopJoin }
+            , { Batch, LookupJoin }
             , { Batch, Values }
             , { Batch, Sort }
             , { Batch, Exchange }
@@ -352,6 +373,10 @@ macro_rules! for_all_plan_nodes {
             , { Batch, TopN }
             , { Batch, HopWindow }
             , { Batch, GenerateSeries }
+            , { Batch, Unnest }
+            , { Batch, Export }
+            , { Batch, FileScan }
+            , { Batch, Source }
             , { Stream, Project }
             , { Stream, Filter }
             , { Stream, TableScan }
@@ -365,6 +390,9 @@ macro_rules! for_all_plan_nodes {
             , { Stream, HopWindow }
             , { Stream, DeltaJoin }
             , { Stream, IndexScan }
+            , { Stream, EmitOnWindowClose }
+            , { Stream, TemporalFilter }
+            , { Stream, Dedup }
         }
     };
 }
@@ -390,6 +418,7 @@ macro_rules! for_logical_plan_nodes {
             , { Logical, TopN }
             , { Logical, HopWindow }
             , { Logical, GenerateSeries }
+            , { Logical, Unnest }
             , { Logical, MultiJoin }
             // , { Logical, Sort} not sure if we will support Order by clause in subquery/view/MV
             // if we dont support thatk, we don't need LogicalSort, just require the Order at the top of query
@@ -410,6 +439,7 @@ macro_rules! for_batch_plan_nodes {
             , { Batch, SeqScan }
             , { Batch, HashJoin }
             , { Batch, NestedLoopJoin }
+            , { Batch, LookupJoin }
             , { Batch, Values }
             , { Batch, Limit }
             , { Batch, Sort }
@@ -420,6 +450,10 @@ macro_rules! for_batch_plan_nodes {
             , { Batch, Update }
             , { Batch, HopWindow }
             , { Batch, GenerateSeries }
+            , { Batch, Unnest }
+            , { Batch, Export }
+            , { Batch, FileScan }
+            , { Batch, Source }
         }
     };
 }
@@ -443,6 +477,9 @@ macro_rules! for_stream_plan_nodes {
             , { Stream, HopWindow }
             , { Stream, DeltaJoin }
             , { Stream, IndexScan }
+            , { Stream, EmitOnWindowClose }
+            , { Stream, TemporalFilter }
+            , { Stream, Dedup }
         }
     };
 }
@@ -0,0 +1,105 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+
+use itertools::Itertools;
+use risingwave_common::error::Result;
+use risingwave_pb::stream_plan::stream_node::NodeBody as ProstStreamNode;
+use risingwave_pb::stream_plan::DedupNode;
+
+use super::{PlanBase, PlanRef, PlanTreeNodeUnary, ToStreamProst};
+use crate::optimizer::property::{Order, RequiredDist};
+
+/// `StreamDedup` drops the insertions with the same values of the dedup columns as an insertion
+/// seen within the TTL, e.g. the rows sent again by an at-least-once producer of a source.
+#[derive(Debug, Clone)]
+pub struct StreamDedup {
+    pub base: PlanBase,
+    input: PlanRef,
+    dedup_cols: Vec<usize>,
+    ttl_ms: u64,
+}
+
+impl StreamDedup {
+    fn new(input: PlanRef, dedup_cols: Vec<usize>, ttl_ms: u64) -> Self {
+        let ctx = input.ctx();
+        let base = PlanBase::new_stream(
+            ctx,
+            input.schema().clone(),
+            input.pk_indices().to_vec(),
+            input.distribution().clone(),
+            input.append_only(),
+        );
+        StreamDedup {
+            base,
+            input,
+            dedup_cols,
+            ttl_ms,
+        }
+    }
+
+    /// Creates a `StreamDedup` on `input` by the columns `dedup_cols`.
+    pub fn create(input: PlanRef, dedup_cols: Vec<usize>, ttl_ms: u64) -> Result<PlanRef> {
+        // The duplicates must be seen by the same actor.
+        let input = RequiredDist::shard_by_key(input.schema().len(), &dedup_cols)
+            .enforce_if_not_satisfies(input, Order::any())?;
+        Ok(Self::new(input, dedup_cols, ttl_ms).into())
+    }
+}
+
+impl fmt::Display for StreamDedup {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let fields = self.input.schema().fields();
+        write!(
+            f,
+            "StreamDedup {{ dedup_cols: [{}], ttl_ms: {} }}",
+            self.dedup_cols
+                .iter()
+                .map(|idx| fields[*idx].name.as_str())
+                .join(", "),
+            self.ttl_ms
+        )
+    }
+}
+
+impl PlanTreeNodeUnary for StreamDedup {
+    fn input(&self) -> PlanRef {
+        self.input.clone()
+    }
+
+    fn clone_with_input(&self, input: PlanRef) -> Self {
+        Self::new(input, self.dedup_cols.clone(), self.ttl_ms)
+    }
+}
+
+impl_plan_tree_node_for_unary! { StreamDedup }
+
+impl ToStreamProst for StreamDedup {
+    fn to_stream_prost_body(&self) -> ProstStreamNode {
+        ProstStreamNode::Dedup(DedupNode {
+            dedup_col_indices: self.dedup_cols.iter().map(|idx| *idx as u32).collect(),
+            ttl_ms: self.ttl_ms,
+            dist_key_indices: self
+                .input
+                .distribution()
+                .dist_column_indices()
+                .iter()
+                .map(|idx| *idx as u32)
+                .collect(),
+            // Assigned by the fragmenter.
+            table_id: 0,
+        })
+    }
+}
@@ -0,0 +1,156 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+
+use risingwave_common::error::{ErrorCode, Result};
+use risingwave_common::types::{IntervalUnit, ScalarImpl};
+use risingwave_pb::stream_plan::stream_node::NodeBody as ProstStreamNode;
+use risingwave_pb::stream_plan::EmitOnWindowCloseNode;
+
+use super::{PlanBase, PlanRef, PlanTreeNodeUnary, ToStreamProst};
+use crate::expr::{ExprImpl, ExprType};
+use crate::optimizer::property::{Order, RequiredDist};
+
+/// `StreamEmitOnWindowClose` buffers the results of a windowed aggregation, and emits the final
+/// result of each window once the window is closed.
+#[derive(Debug, Clone)]
+pub struct StreamEmitOnWindowClose {
+    pub base: PlanBase,
+    input: PlanRef,
+    window_end_index: usize,
+    window_slide: IntervalUnit,
+}
+
+impl StreamEmitOnWindowClose {
+    fn new(input: PlanRef, window_end_index: usize, window_slide: IntervalUnit) -> Self {
+        let ctx = input.ctx();
+        let base = PlanBase::new_stream(
+            ctx,
+            input.schema().clone(),
+            input.pk_indices().to_vec(),
+            input.distribution().clone(),
+            true,
+        );
+        StreamEmitOnWindowClose {
+            base,
+            input,
+            window_end_index,
+            window_slide,
+        }
+    }
+
+    /// Emits the results of `input` on window close. `input` must output a `window_end` of a
+    /// `TUMBLE` or `HOP` window as a group key of an aggregation.
+    pub fn create(input: PlanRef) -> Result<PlanRef> {
+        let (window_end_index, window_slide) = (0..input.schema().len())
+            .find_map(|idx| Self::find_window_end(&input, idx, false).map(|slide| (idx, slide)))
+            .ok_or_else(|| {
+                ErrorCode::InvalidInputSyntax(
+                    "EMIT ON WINDOW CLOSE requires the window_end of a TUMBLE or HOP window as a group key"
+                        .to_string(),
+                )
+            })?;
+        // The closing of windows is tracked on the results of all windows.
+        let input = RequiredDist::single().enforce_if_not_satisfies(input, Order::any())?;
+        Ok(Self::new(input, window_end_index, window_slide).into())
+    }
+
+    /// Returns the slide of the window if column `idx` of `plan` is the `window_end` of a window,
+    /// and it's a group key of an aggregation.
+    fn find_window_end(plan: &PlanRef, idx: usize, aggregated: bool) -> Option<IntervalUnit> {
+        if let Some(project) = plan.as_stream_project() {
+            match &project.as_logical().exprs()[idx] {
+                ExprImpl::InputRef(input_ref) => {
+                    Self::find_window_end(&project.input(), input_ref.index(), aggregated)
+                }
+                expr if aggregated => Self::tumble_window_end(expr),
+                _ => None,
+            }
+        } else if let Some(filter) = plan.as_stream_filter() {
+            Self::find_window_end(&filter.input(), idx, aggregated)
+        } else if let Some(exchange) = plan.as_stream_exchange() {
+            Self::find_window_end(&exchange.input(), idx, aggregated)
+        } else if let Some(agg) = plan.as_stream_hash_agg() {
+            let group_key = *agg.distribution_keys().get(idx)?;
+            Self::find_window_end(&agg.input(), group_key, true)
+        } else if let Some(hop_window) = plan.as_stream_hop_window() {
+            let logical = hop_window.as_logical();
+            let window_end_index = logical.input().schema().len() + 1;
+            (aggregated && logical.output_indices[idx] == window_end_index)
+                .then(|| logical.window_slide)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the size of the window if `expr` is the `window_end` of a `TUMBLE` window,
+    /// i.e. `tumble_start(time_col, size) + size`.
+    fn tumble_window_end(expr: &ExprImpl) -> Option<IntervalUnit> {
+        let interval = |expr: &ExprImpl| match expr {
+            ExprImpl::Literal(literal) => match literal.get_data() {
+                Some(ScalarImpl::Interval(interval)) => Some(*interval),
+                _ => None,
+            },
+            _ => None,
+        };
+        let ExprImpl::FunctionCall(window_end) = expr else {
+            return None;
+        };
+        let [ExprImpl::FunctionCall(window_start), size] = window_end.inputs() else {
+            return None;
+        };
+        if window_end.get_expr_type() != ExprType::Add
+            || window_start.get_expr_type() != ExprType::TumbleStart
+        {
+            return None;
+        }
+        let size = interval(size)?;
+        (interval(window_start.inputs().get(1)?)? == size).then(|| size)
+    }
+}
+
+impl fmt::Display for StreamEmitOnWindowClose {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "StreamEmitOnWindowClose {{ window_end: {}, window_slide: {} }}",
+            self.input.schema().fields()[self.window_end_index].name,
+            self.window_slide
+        )
+    }
+}
+
+impl PlanTreeNodeUnary for StreamEmitOnWindowClose {
+    fn input(&self) -> PlanRef {
+        self.input.clone()
+    }
+
+    fn clone_with_input(&self, input: PlanRef) -> Self {
+        Self::new(input, self.window_end_index, self.window_slide)
+    }
+}
+
+impl_plan_tree_node_for_unary! { StreamEmitOnWindowClose }
+
+impl ToStreamProst for StreamEmitOnWindowClose {
+    fn to_stream_prost_body(&self) -> ProstStreamNode {
+        ProstStreamNode::EmitOnWindowClose(EmitOnWindowCloseNode {
+            window_end_index: self.window_end_index as u32,
+            window_slide: Some(self.window_slide.into()),
+            // Assigned by the fragmenter.
+            table_id: 0,
+        })
+    }
+}
@@ -41,6 +41,10 @@ impl StreamHopWindow {
         );
         Self { base, logical }
     }
+
+    pub fn as_logical(&self) -> &LogicalHopWindow {
+        &self.logical
+    }
 }
 
 impl fmt::Display for StreamHopWindow {
@@ -166,6 +166,9 @@ impl StreamMaterialize {
             pks: pk_indices.clone(),
             is_index_on,
             distribution_keys: base.dist.dist_column_indices().to_vec(),
+            owner: base.ctx.inner().session_ctx.user_name().to_string(),
+            is_internal: false,
+            retention_seconds: 0,
         };
 
         Ok(Self { base, input, table })
@@ -278,6 +281,12 @@ impl ToStreamProst for StreamMaterialize {
                 .iter()
                 .map(|idx| *idx as u32)
                 .collect_vec(),
+            // The pk of the input is unique, as tables are keyed by the hidden row id.
+            handle_pk_conflict: false,
+            // Set by the handler if the statistics are asked for.
+            collect_stats: false,
+            // Assigned by the fragmenter.
+            stats_table_id: 0,
         })
     }
 }
@@ -0,0 +1,167 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+
+use risingwave_common::error::Result;
+use risingwave_common::types::{IntervalUnit, ScalarImpl};
+use risingwave_pb::stream_plan::stream_node::NodeBody as ProstStreamNode;
+use risingwave_pb::stream_plan::TemporalFilterNode;
+
+use super::{PlanBase, PlanRef, PlanTreeNodeUnary, ToStreamProst};
+use crate::expr::{ExprImpl, ExprType};
+use crate::optimizer::property::{Distribution, Order, RequiredDist};
+
+/// `StreamTemporalFilter` keeps the rows with `time_col > NOW() + offset`, and deletes them once
+/// they no longer satisfy the filter as `NOW()` advances.
+#[derive(Debug, Clone)]
+pub struct StreamTemporalFilter {
+    pub base: PlanBase,
+    input: PlanRef,
+    time_col_index: usize,
+    offset: IntervalUnit,
+    inclusive: bool,
+}
+
+impl StreamTemporalFilter {
+    fn new(input: PlanRef, time_col_index: usize, offset: IntervalUnit, inclusive: bool) -> Self {
+        let ctx = input.ctx();
+        // Rows are deleted on expiration, so the output is never append-only.
+        let base = PlanBase::new_stream(
+            ctx,
+            input.schema().clone(),
+            input.pk_indices().to_vec(),
+            input.distribution().clone(),
+            false,
+        );
+        StreamTemporalFilter {
+            base,
+            input,
+            time_col_index,
+            offset,
+            inclusive,
+        }
+    }
+
+    /// Whether `cond` is a temporal filter, i.e. `time_col > NOW() [+/- interval]` or its flipped
+    /// form, with `>=` also allowed.
+    pub fn is_temporal_filter(cond: &ExprImpl) -> bool {
+        Self::extract(cond).is_some()
+    }
+
+    /// Creates a `StreamTemporalFilter` on `input` for the temporal filter `cond`.
+    pub fn create(input: PlanRef, cond: &ExprImpl) -> Result<PlanRef> {
+        let (time_col_index, offset, inclusive) =
+            Self::extract(cond).expect("not a temporal filter");
+        // Each actor expires the rows of its own vnodes, which are decided by the distribution
+        // keys.
+        let input = match input.distribution() {
+            Distribution::Single | Distribution::HashShard(_) => input,
+            Distribution::SomeShard => {
+                RequiredDist::shard_by_key(input.schema().len(), input.pk_indices())
+                    .enforce_if_not_satisfies(input, Order::any())?
+            }
+        };
+        Ok(Self::new(input, time_col_index, offset, inclusive).into())
+    }
+
+    /// Returns the time column, the offset to `NOW()` and whether the bound is inclusive if
+    /// `cond` is a temporal filter.
+    fn extract(cond: &ExprImpl) -> Option<(usize, IntervalUnit, bool)> {
+        let ExprImpl::FunctionCall(call) = cond else {
+            return None;
+        };
+        let [lhs, rhs] = call.inputs() else {
+            return None;
+        };
+        let (time_col, now, inclusive) = match call.get_expr_type() {
+            ExprType::GreaterThan => (lhs, rhs, false),
+            ExprType::GreaterThanOrEqual => (lhs, rhs, true),
+            ExprType::LessThan => (rhs, lhs, false),
+            ExprType::LessThanOrEqual => (rhs, lhs, true),
+            _ => return None,
+        };
+        let ExprImpl::InputRef(time_col) = time_col else {
+            return None;
+        };
+        Some((time_col.index(), Self::now_offset(now)?, inclusive))
+    }
+
+    /// Returns `offset` if `expr` is `NOW() + offset`.
+    fn now_offset(expr: &ExprImpl) -> Option<IntervalUnit> {
+        let ExprImpl::FunctionCall(call) = expr else {
+            return None;
+        };
+        let interval = |expr: &ExprImpl| match expr {
+            ExprImpl::Literal(literal) => match literal.get_data() {
+                Some(ScalarImpl::Interval(interval)) => Some(*interval),
+                _ => None,
+            },
+            _ => None,
+        };
+        let is_now = |expr: &ExprImpl| matches!(expr, ExprImpl::FunctionCall(call) if call.get_expr_type() == ExprType::Now);
+        match (call.get_expr_type(), call.inputs()) {
+            (ExprType::Now, []) => Some(IntervalUnit::default()),
+            (ExprType::Add, [now, offset]) if is_now(now) => interval(offset),
+            (ExprType::Add, [offset, now]) if is_now(now) => interval(offset),
+            (ExprType::Subtract, [now, offset]) if is_now(now) => {
+                interval(offset).map(|offset| offset.negative())
+            }
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for StreamTemporalFilter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "StreamTemporalFilter {{ time_col: {}, cutoff: NOW() + {}, inclusive: {} }}",
+            self.input.schema().fields()[self.time_col_index].name,
+            self.offset,
+            self.inclusive
+        )
+    }
+}
+
+impl PlanTreeNodeUnary for StreamTemporalFilter {
+    fn input(&self) -> PlanRef {
+        self.input.clone()
+    }
+
+    fn clone_with_input(&self, input: PlanRef) -> Self {
+        Self::new(input, self.time_col_index, self.offset, self.inclusive)
+    }
+}
+
+impl_plan_tree_node_for_unary! { StreamTemporalFilter }
+
+impl ToStreamProst for StreamTemporalFilter {
+    fn to_stream_prost_body(&self) -> ProstStreamNode {
+        ProstStreamNode::TemporalFilter(TemporalFilterNode {
+            time_col_index: self.time_col_index as u32,
+            offset: Some(self.offset.into()),
+            inclusive: self.inclusive,
+            dist_key_indices: self
+                .input
+                .distribution()
+                .dist_column_indices()
+                .iter()
+                .map(|idx| *idx as u32)
+                .collect(),
+            // Assigned by the fragmenter.
+            table_id: 0,
+        })
+    }
+}
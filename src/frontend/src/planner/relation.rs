@@ -20,13 +20,13 @@ use risingwave_common::error::{ErrorCode, Result};
 use risingwave_common::types::ScalarImpl;
 
 use crate::binder::{
-    BoundBaseTable, BoundGenerateSeriesFunction, BoundJoin, BoundSource, BoundWindowTableFunction,
-    Relation, WindowTableFunctionKind,
+    BoundBaseTable, BoundGenerateSeriesFunction, BoundJoin, BoundSource, BoundSystemTable,
+    BoundUnnestFunction, BoundWindowTableFunction, Relation, WindowTableFunctionKind,
 };
-use crate::expr::{ExprImpl, ExprType, FunctionCall, InputRef};
+use crate::expr::{ExprImpl, ExprType, FunctionCall, InputRef, Literal};
 use crate::optimizer::plan_node::{
     LogicalGenerateSeries, LogicalHopWindow, LogicalJoin, LogicalProject, LogicalScan,
-    LogicalSource, PlanRef,
+    LogicalSource, LogicalUnnest, LogicalValues, PlanRef,
 };
 use crate::planner::Planner;
 
@@ -34,12 +34,14 @@ impl Planner {
     pub(super) fn plan_relation(&mut self, relation: Relation) -> Result<PlanRef> {
         match relation {
             Relation::BaseTable(t) => self.plan_base_table(*t),
+            Relation::SystemTable(t) => self.plan_system_table(*t),
             // TODO: order is ignored in the subquery
             Relation::Subquery(q) => Ok(self.plan_query(q.query)?.as_subplan()),
             Relation::Join(join) => self.plan_join(*join),
             Relation::WindowTableFunction(tf) => self.plan_window_table_function(*tf),
             Relation::Source(s) => self.plan_source(*s),
             Relation::GenerateSeriesFunction(gs) => self.plan_generate_series_function(*gs),
+            Relation::UnnestFunction(unnest) => self.plan_unnest_function(*unnest),
         }
     }
 
@@ -57,6 +59,20 @@ impl Planner {
         .into())
     }
 
+    pub(super) fn plan_system_table(&mut self, table: BoundSystemTable) -> Result<PlanRef> {
+        let rows = table
+            .rows
+            .into_iter()
+            .map(|row| {
+                row.into_iter()
+                    .zip_eq(table.schema.fields())
+                    .map(|(datum, field)| Literal::new(datum, field.data_type()).into())
+                    .collect()
+            })
+            .collect();
+        Ok(LogicalValues::create(rows, table.schema, self.ctx()))
+    }
+
     pub(super) fn plan_source(&mut self, source: BoundSource) -> Result<PlanRef> {
         Ok(LogicalSource::new(Rc::new(source.catalog), self.ctx()).into())
     }
@@ -112,6 +128,18 @@ impl Planner {
         ))
     }
 
+    pub(super) fn plan_unnest_function(
+        &mut self,
+        table_function: BoundUnnestFunction,
+    ) -> Result<PlanRef> {
+        let schema = Schema::new(vec![Field::with_name(table_function.data_type, "unnest")]);
+        Ok(LogicalUnnest::create(
+            table_function.arg,
+            schema,
+            self.ctx(),
+        ))
+    }
+
     fn plan_tumble_window(
         &mut self,
         input: Relation,
@@ -15,10 +15,12 @@
 use std::collections::{HashMap, HashSet};
 use std::mem::swap;
 use std::sync::Arc;
+use std::time::Instant;
 
 use risingwave_common::error::ErrorCode::InternalError;
 use risingwave_common::error::{ErrorCode, Result};
 use risingwave_pb::batch_plan::{TaskId as TaskIdProst, TaskOutputId as TaskOutputIdProst};
+use risingwave_pb::common::HostAddress;
 use risingwave_rpc_client::ComputeClientPoolRef;
 use tokio::sync::mpsc::{channel, Receiver, Sender};
 use tokio::sync::{oneshot, RwLock};
@@ -31,8 +33,8 @@ use crate::scheduler::distributed::query::QueryState::{Failed, Pending};
 use crate::scheduler::distributed::StageEvent::Scheduled;
 use crate::scheduler::distributed::StageExecution;
 use crate::scheduler::plan_fragmenter::{Query, StageId, ROOT_TASK_ID, ROOT_TASK_OUTPUT_ID};
-use crate::scheduler::worker_node_manager::WorkerNodeManagerRef;
-use crate::scheduler::HummockSnapshotManagerRef;
+use crate::scheduler::worker_node_manager::{QueryTaskLoads, WorkerNodeManagerRef};
+use crate::scheduler::{HummockSnapshotManagerRef, StageTimings};
 
 /// Message sent to a `QueryRunner` to control its execution.
 #[derive(Debug)]
@@ -74,7 +76,7 @@ enum QueryState {
 pub struct QueryExecution {
     query: Arc<Query>,
     state: Arc<RwLock<QueryState>>,
-    _stage_executions: Arc<HashMap<StageId, Arc<StageExecution>>>,
+    stage_executions: Arc<HashMap<StageId, Arc<StageExecution>>>,
 }
 
 struct QueryRunner {
@@ -92,18 +94,25 @@ struct QueryRunner {
     epoch: u64,
     hummock_snapshot_manager: HummockSnapshotManagerRef,
     compute_client_pool: ComputeClientPoolRef,
+    task_loads: QueryTaskLoads,
+    stage_timings: StageTimings,
 }
 
 impl QueryExecution {
+    /// Creates the execution of `query`, whose tasks are profiled if `profile` is set. The time
+    /// taken to schedule each stage is recorded into `stage_timings`.
     pub fn new(
         query: Query,
         epoch: u64,
+        profile: bool,
+        stage_timings: StageTimings,
         worker_node_manager: WorkerNodeManagerRef,
         hummock_snapshot_manager: HummockSnapshotManagerRef,
         compute_client_pool: ComputeClientPoolRef,
     ) -> Self {
         let query = Arc::new(query);
         let (sender, receiver) = channel(100);
+        let task_loads = QueryTaskLoads::default();
 
         let stage_executions = {
             let mut stage_executions: HashMap<StageId, Arc<StageExecution>> =
@@ -119,11 +128,13 @@ impl QueryExecution {
 
                 let stage_exec = Arc::new(StageExecution::new(
                     epoch,
+                    profile,
                     query.stage_graph.stages[&stage_id].clone(),
                     worker_node_manager.clone(),
                     sender.clone(),
                     children_stages,
                     compute_client_pool.clone(),
+                    task_loads.clone(),
                 ));
                 stage_executions.insert(stage_id, stage_exec);
             }
@@ -143,6 +154,8 @@ impl QueryExecution {
             epoch,
             hummock_snapshot_manager,
             compute_client_pool,
+            task_loads,
+            stage_timings,
         };
 
         let state = Pending {
@@ -153,10 +166,18 @@ impl QueryExecution {
         Self {
             query,
             state: Arc::new(RwLock::new(state)),
-            _stage_executions: stage_executions,
+            stage_executions,
         }
     }
 
+    /// Returns the ids of the scheduled tasks of all stages along with their hosts.
+    pub fn task_locations(&self) -> Vec<(TaskIdProst, HostAddress)> {
+        self.stage_executions
+            .values()
+            .flat_map(|stage_execution| stage_execution.task_locations())
+            .collect()
+    }
+
     /// Start execution of this query.
     pub async fn start(&self) -> Result<QueryResultFetcher> {
         let mut state = self.state.write().await;
@@ -209,6 +230,7 @@ impl QueryExecution {
 
 impl QueryRunner {
     async fn run(mut self) -> Result<()> {
+        let start_time = Instant::now();
         // Start leaf stages.
         let leaf_stages = self.query.leaf_stages();
         for stage_id in &leaf_stages {
@@ -247,17 +269,29 @@ impl QueryRunner {
                         self.query.query_id, stage_id
                     );
                     self.scheduled_stages_count += 1;
+                    self.stage_timings
+                        .lock()
+                        .insert(stage_id, start_time.elapsed());
                     stages_has_table_scan.remove(&stage_id);
-                    if stages_has_table_scan.is_empty() {
+                    // Lookup joins read tables while running, so the snapshot is unpinned by the
+                    // `QueryAbortGuard` once the query finishes instead.
+                    if stages_has_table_scan.is_empty() && !self.query.has_lookup_join() {
                         // Since all the iterators are created during building the leaf tasks in the
                         // backend, we can be sure here that all the
                         // iterator have been created, thus they all successfully pinned a
                         // HummockVersion. So we can now unpin their epoch.
                         info!("Query {:?} has scheduled all of its stages that have table scan (iterator creation).", self.query.query_id);
-                        self.hummock_snapshot_manager
+                        if let Err(e) = self
+                            .hummock_snapshot_manager
                             .clone()
                             .unpin_snapshot(self.epoch, self.query.query_id())
-                            .await?;
+                            .await
+                        {
+                            warn!(
+                                "Failed to unpin the snapshot of query {:?}: {}",
+                                self.query.query_id, e
+                            );
+                        }
                     }
 
                     if self.scheduled_stages_count == self.stage_executions.len() {
@@ -340,7 +374,8 @@ impl QueryRunner {
             root_task_output_id,
             root_task_status.task_host_unchecked(),
             self.compute_client_pool.clone(),
-        );
+        )
+        .with_task_loads(self.task_loads.clone());
 
         // Consume sender here.
         let mut tmp_sender = None;
@@ -405,6 +440,8 @@ mod tests {
         let query_execution = QueryExecution::new(
             create_query().await,
             100,
+            false,
+            Default::default(),
             worker_node_manager,
             Arc::new(HummockSnapshotManager::new(Arc::new(
                 MockFrontendMetaClient {},
@@ -431,6 +468,7 @@ mod tests {
                 table_id: 0.into(),
                 pks: vec![],
                 order_desc: vec![],
+                retention_seconds: 0,
                 columns: vec![
                     ColumnDesc {
                         data_type: DataType::Int32,
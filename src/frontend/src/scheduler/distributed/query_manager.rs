@@ -12,21 +12,25 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
 use std::fmt::{Debug, Formatter};
+use std::time::Instant;
 
-use futures::{Stream, StreamExt};
+use futures::{pin_mut, Stream, StreamExt};
 use futures_async_stream::try_stream;
-use log::debug;
+use log::{debug, warn};
+use risingwave_batch::executor::OperatorProfile;
 use risingwave_common::array::DataChunk;
 use risingwave_common::error::{Result, RwError, ToRwResult};
 use risingwave_pb::batch_plan::{PlanNode as BatchPlanProst, TaskId, TaskOutputId};
 use risingwave_pb::common::HostAddress;
+use risingwave_pb::task_service::OperatorProfile as ProstOperatorProfile;
 use risingwave_rpc_client::ComputeClientPoolRef;
 use uuid::Uuid;
 
 use super::QueryExecution;
 use crate::scheduler::plan_fragmenter::{Query, QueryId};
-use crate::scheduler::worker_node_manager::WorkerNodeManagerRef;
+use crate::scheduler::worker_node_manager::{QueryTaskLoads, WorkerNodeManagerRef};
 use crate::scheduler::{DataChunkStream, ExecutionContextRef, HummockSnapshotManagerRef};
 
 pub struct QueryResultFetcher {
@@ -37,6 +41,71 @@ pub struct QueryResultFetcher {
     task_output_id: TaskOutputId,
     task_host: HostAddress,
     compute_client_pool: ComputeClientPoolRef,
+
+    /// Aborts the query if the fetcher is dropped before all results are fetched.
+    abort_guard: Option<QueryAbortGuard>,
+
+    /// The tasks of the query count in the loads of their worker nodes until the fetcher is
+    /// dropped.
+    _task_loads: QueryTaskLoads,
+}
+
+/// Unpins the snapshot of a query and aborts the query on all compute nodes when dropped, unless
+/// disarmed, after which only the snapshot is unpinned.
+///
+/// The guard lives from the snapshot of the query being pinned until the end of the result stream
+/// of the query. Once the query is given up before the end, e.g. the client disconnects or the
+/// statement times out, all tasks of the query are aborted so that they no longer hold resources
+/// and pinned snapshots on compute nodes, and the snapshot pinned by the frontend is unpinned.
+pub struct QueryAbortGuard {
+    query_id: QueryId,
+    epoch: u64,
+    query_manager: QueryManager,
+    /// Unset once the query finishes normally and there's nothing to abort.
+    armed: bool,
+}
+
+impl QueryAbortGuard {
+    pub fn new(query_id: QueryId, epoch: u64, query_manager: QueryManager) -> Self {
+        Self {
+            query_id,
+            epoch,
+            query_manager,
+            armed: true,
+        }
+    }
+
+    /// The query finished normally and there's nothing to abort. The snapshot is still unpinned,
+    /// in case it's kept pinned while the query is running.
+    pub fn disarm(mut self) {
+        self.armed = false;
+    }
+}
+
+impl Drop for QueryAbortGuard {
+    fn drop(&mut self) {
+        let query_id = self.query_id.clone();
+        let epoch = self.epoch;
+        let query_manager = self.query_manager.clone();
+        let armed = self.armed;
+        tokio::spawn(async move {
+            // Abort the tasks before unpinning, so that they never read an unpinned snapshot.
+            if armed {
+                query_manager.abort_query(&query_id).await;
+            }
+            // Unpinning is a no-op if the snapshot has been unpinned after scheduling.
+            if let Err(e) = query_manager
+                .hummock_snapshot_manager
+                .unpin_snapshot(epoch, &query_id)
+                .await
+            {
+                warn!(
+                    "Failed to unpin the snapshot of query {:?}: {}",
+                    query_id, e
+                );
+            }
+        });
+    }
 }
 
 /// Manages execution of distributed batch queries.
@@ -68,7 +137,8 @@ impl QueryManager {
         _context: ExecutionContextRef,
         plan: BatchPlanProst,
     ) -> Result<impl Stream<Item = Result<DataChunk>>> {
-        let worker_node_addr = self.worker_node_manager.next_random()?.host.unwrap();
+        let worker_node = self.worker_node_manager.next_least_loaded()?;
+        let worker_node_addr = worker_node.host.unwrap();
         let compute_client = self
             .compute_client_pool
             .get_client_for_addr((&worker_node_addr).into())
@@ -93,14 +163,28 @@ impl QueryManager {
             .hummock_snapshot_manager
             .get_epoch(query_id.clone())
             .await?;
+        let abort_guard = QueryAbortGuard::new(query_id.clone(), epoch, self.clone());
 
+        let task_loads = QueryTaskLoads::default();
+        task_loads.add(self.worker_node_manager.start_task(worker_node.id));
+        let start_time = Instant::now();
         let creat_task_resp = compute_client
             .create_task(task_id.clone(), plan, epoch)
             .await;
-        self.hummock_snapshot_manager
-            .unpin_snapshot(epoch, &query_id)
-            .await?;
+        self.worker_node_manager
+            .report_task_latency(worker_node.id, start_time.elapsed());
+        // On failure, the guard aborts the query before unpinning the snapshot.
         creat_task_resp?;
+        if let Err(e) = self
+            .hummock_snapshot_manager
+            .unpin_snapshot(epoch, &query_id)
+            .await
+        {
+            warn!(
+                "Failed to unpin the snapshot of query {:?}: {}",
+                query_id, e
+            );
+        }
 
         let query_result_fetcher = QueryResultFetcher::new(
             epoch,
@@ -108,42 +192,133 @@ impl QueryManager {
             task_output_id,
             worker_node_addr,
             self.compute_client_pool.clone(),
-        );
+        )
+        .with_task_loads(task_loads)
+        .with_abort_guard(abort_guard);
 
         Ok(query_result_fetcher.run())
     }
 
     pub async fn schedule(
         &self,
-        _context: ExecutionContextRef,
+        context: ExecutionContextRef,
         query: Query,
     ) -> Result<impl DataChunkStream> {
+        let (_, query_result_fetcher) = self.start_query(context, query, false).await?;
+        Ok(query_result_fetcher.run())
+    }
+
+    /// Runs the query with the executors of its tasks profiled until all the results are produced.
+    /// Returns the number of the result rows, along with the runtime statistics of the operators
+    /// merged over their tasks by their plan node ids.
+    pub async fn profile(
+        &self,
+        context: ExecutionContextRef,
+        query: Query,
+    ) -> Result<(usize, HashMap<i32, OperatorProfile>)> {
+        let (query_execution, query_result_fetcher) =
+            self.start_query(context, query, true).await?;
+        let mut rows = 0;
+        let data_stream = query_result_fetcher.run();
+        pin_mut!(data_stream);
+        while let Some(chunk) = data_stream.next().await {
+            rows += chunk?.cardinality();
+        }
+
+        // The statistics are complete since the outputs of all tasks are consumed.
+        let task_profiles = query_execution
+            .task_locations()
+            .into_iter()
+            .map(|(task_id, host)| self.get_task_profile(task_id, host));
+        let mut profiles: HashMap<i32, OperatorProfile> = HashMap::new();
+        for task_profile in futures::future::try_join_all(task_profiles).await? {
+            for operator in task_profile {
+                profiles
+                    .entry(operator.plan_node_id)
+                    .or_default()
+                    .merge(&OperatorProfile::from_prost(&operator));
+            }
+        }
+        Ok((rows, profiles))
+    }
+
+    async fn get_task_profile(
+        &self,
+        task_id: TaskId,
+        host: HostAddress,
+    ) -> Result<Vec<ProstOperatorProfile>> {
+        self.compute_client_pool
+            .get_client_for_addr((&host).into())
+            .await?
+            .get_task_profile(task_id)
+            .await
+    }
+
+    /// Starts the execution of the query, and returns it along with the fetcher of its results.
+    async fn start_query(
+        &self,
+        context: ExecutionContextRef,
+        query: Query,
+        profile: bool,
+    ) -> Result<(QueryExecution, QueryResultFetcher)> {
         let query_id = query.query_id().clone();
-        // Cheat compiler to resolve type
-        let epoch = self
-            .hummock_snapshot_manager
-            .get_epoch(query_id.clone())
-            .await?;
+        let query_epoch = context.session().config().query_epoch();
+        let epoch = match query_epoch {
+            Some(epoch) => {
+                self.hummock_snapshot_manager
+                    .pin_specific_epoch(query_id.clone(), epoch)
+                    .await?
+            }
+            None => {
+                self.hummock_snapshot_manager
+                    .get_epoch(query_id.clone())
+                    .await?
+            }
+        };
 
         let query_execution = QueryExecution::new(
             query,
             epoch,
+            profile,
+            context.stage_timings().clone(),
             self.worker_node_manager.clone(),
             self.hummock_snapshot_manager.clone(),
             self.compute_client_pool.clone(),
         );
 
-        let query_result_fetcher = match query_execution.start().await {
-            Ok(query_result_fetcher) => query_result_fetcher,
-            Err(e) => {
-                self.hummock_snapshot_manager
-                    .unpin_snapshot(epoch, &query_id)
-                    .await?;
-                return Err(e);
-            }
-        };
+        // The guard is created before starting the query, so that the query is aborted even if it
+        // fails or is cancelled before all of its stages are scheduled.
+        let abort_guard = QueryAbortGuard::new(query_id, epoch, self.clone());
+        let query_result_fetcher = query_execution.start().await?.with_abort_guard(abort_guard);
+        Ok((query_execution, query_result_fetcher))
+    }
 
-        Ok(query_result_fetcher.run())
+    /// Aborts all tasks of the query on every compute node. Failures are only logged since the
+    /// tasks are also cleaned up when their consumers are gone.
+    pub async fn abort_query(&self, query_id: &QueryId) {
+        let futures = self
+            .worker_node_manager
+            .list_worker_nodes()
+            .into_iter()
+            .map(|worker_node| async move {
+                let host = worker_node.host.unwrap();
+                let res = async {
+                    self.compute_client_pool
+                        .get_client_for_addr((&host).into())
+                        .await?
+                        .abort_query(query_id.id.clone())
+                        .await
+                }
+                .await;
+                match res {
+                    Ok(aborted_tasks) => debug!(
+                        "Aborted {} tasks of query {:?} on {:?}",
+                        aborted_tasks, query_id, host
+                    ),
+                    Err(e) => warn!("Failed to abort query {:?} on {:?}: {}", query_id, host, e),
+                }
+            });
+        futures::future::join_all(futures).await;
     }
 }
 
@@ -161,11 +336,23 @@ impl QueryResultFetcher {
             task_output_id,
             task_host,
             compute_client_pool,
+            abort_guard: None,
+            _task_loads: QueryTaskLoads::default(),
         }
     }
 
+    pub(super) fn with_task_loads(mut self, task_loads: QueryTaskLoads) -> Self {
+        self._task_loads = task_loads;
+        self
+    }
+
+    fn with_abort_guard(mut self, abort_guard: QueryAbortGuard) -> Self {
+        self.abort_guard = Some(abort_guard);
+        self
+    }
+
     #[try_stream(ok = DataChunk, error = RwError)]
-    async fn run(self) {
+    async fn run(mut self) {
         debug!(
             "Starting to run query result fetcher, task output id: {:?}, task_host: {:?}",
             self.task_output_id, self.task_host
@@ -178,6 +365,9 @@ impl QueryResultFetcher {
         while let Some(response) = stream.next().await {
             yield DataChunk::from_protobuf(response.to_rw_result()?.get_record_batch()?)?
         }
+        if let Some(abort_guard) = self.abort_guard.take() {
+            abort_guard.disarm();
+        }
     }
 }
 
@@ -15,6 +15,7 @@
 use std::collections::HashMap;
 use std::mem::swap;
 use std::sync::Arc;
+use std::time::Instant;
 
 use arc_swap::ArcSwap;
 use futures::{stream, StreamExt};
@@ -39,7 +40,7 @@ use crate::optimizer::plan_node::PlanNodeType;
 use crate::scheduler::distributed::stage::StageState::Pending;
 use crate::scheduler::distributed::QueryMessage;
 use crate::scheduler::plan_fragmenter::{ExecutionPlanNode, QueryStageRef, StageId, TaskId};
-use crate::scheduler::worker_node_manager::WorkerNodeManagerRef;
+use crate::scheduler::worker_node_manager::{QueryTaskLoads, WorkerNodeManagerRef};
 
 const TASK_SCHEDULING_PARALLELISM: usize = 10;
 
@@ -86,6 +87,8 @@ struct TaskStatusHolder {
 
 pub struct StageExecution {
     epoch: u64,
+    /// Whether the runtime statistics of the executors of the tasks are collected.
+    profile: bool,
     stage: QueryStageRef,
     worker_node_manager: WorkerNodeManagerRef,
     tasks: Arc<HashMap<TaskId, TaskStatusHolder>>,
@@ -97,10 +100,13 @@ pub struct StageExecution {
     /// We use `Vec` here since children's size is usually small.
     children: Vec<Arc<StageExecution>>,
     compute_client_pool: ComputeClientPoolRef,
+    /// Loads of the scheduled tasks of the query, shared by all stages.
+    task_loads: QueryTaskLoads,
 }
 
 struct StageRunner {
     epoch: u64,
+    profile: bool,
     state: Arc<RwLock<StageState>>,
     stage: QueryStageRef,
     worker_node_manager: WorkerNodeManagerRef,
@@ -110,6 +116,8 @@ struct StageRunner {
     msg_sender: Sender<QueryMessage>,
     children: Vec<Arc<StageExecution>>,
     compute_client_pool: ComputeClientPoolRef,
+    /// Loads of the scheduled tasks of the query, shared by all stages.
+    task_loads: QueryTaskLoads,
 }
 
 impl TaskStatusHolder {
@@ -130,13 +138,16 @@ impl TaskStatusHolder {
 }
 
 impl StageExecution {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         epoch: u64,
+        profile: bool,
         stage: QueryStageRef,
         worker_node_manager: WorkerNodeManagerRef,
         msg_sender: Sender<QueryMessage>,
         children: Vec<Arc<StageExecution>>,
         compute_client_pool: ComputeClientPoolRef,
+        task_loads: QueryTaskLoads,
     ) -> Self {
         let tasks = (0..stage.parallelism)
             .into_iter()
@@ -144,6 +155,7 @@ impl StageExecution {
             .collect();
         Self {
             epoch,
+            profile,
             stage,
             worker_node_manager,
             tasks: Arc::new(tasks),
@@ -151,6 +163,7 @@ impl StageExecution {
             msg_sender,
             children,
             compute_client_pool,
+            task_loads,
         }
     }
 
@@ -162,6 +175,7 @@ impl StageExecution {
                 let (sender, receiver) = channel(100);
                 let runner = StageRunner {
                     epoch: self.epoch,
+                    profile: self.profile,
                     stage: self.stage.clone(),
                     worker_node_manager: self.worker_node_manager.clone(),
                     tasks: self.tasks.clone(),
@@ -170,6 +184,7 @@ impl StageExecution {
                     children: self.children.clone(),
                     state: self.state.clone(),
                     compute_client_pool: self.compute_client_pool.clone(),
+                    task_loads: self.task_loads.clone(),
                 };
                 let handle = spawn(async move {
                     if let Err(e) = runner.run().await {
@@ -208,6 +223,22 @@ impl StageExecution {
         self.tasks[&task_id].get_status()
     }
 
+    /// Returns the ids of the scheduled tasks along with the hosts they're scheduled on.
+    pub fn task_locations(&self) -> Vec<(TaskIdProst, HostAddress)> {
+        self.tasks
+            .iter()
+            .filter_map(|(task_id, status_holder)| {
+                let location = status_holder.get_status().location.clone()?;
+                let task_id = TaskIdProst {
+                    query_id: self.stage.query_id.id.clone(),
+                    stage_id: self.stage.id,
+                    task_id: *task_id,
+                };
+                Some((task_id, location))
+            })
+            .collect()
+    }
+
     /// Returns all exchange sources for `output_id`. Each `ExchangeSource` is identified by
     /// producer `TaskId` and `output_id`, since each task may produce output to several channels.
     ///
@@ -314,16 +345,21 @@ impl StageRunner {
     }
 
     async fn schedule_task(&self, task_id: TaskIdProst, plan_fragment: PlanFragment) -> Result<()> {
-        let worker_node = self.worker_node_manager.next_random()?;
+        let worker_node = self.worker_node_manager.next_least_loaded()?;
         let compute_client = self
             .compute_client_pool
             .get_client_for_addr(worker_node.host.as_ref().unwrap().into())
             .await?;
 
         let t_id = task_id.task_id;
+        let load_guard = self.worker_node_manager.start_task(worker_node.id);
+        let start_time = Instant::now();
         compute_client
-            .create_task2(task_id, plan_fragment, self.epoch)
+            .create_task2(task_id, plan_fragment, self.epoch, self.profile)
             .await?;
+        self.worker_node_manager
+            .report_task_latency(worker_node.id, start_time.elapsed());
+        self.task_loads.add(load_guard);
 
         self.tasks[&t_id].inner.store(Arc::new(TaskStatus {
             _task_id: t_id,
@@ -366,6 +402,7 @@ impl StageRunner {
                             children: vec![],
                             // TODO: Generate meaningful identify
                             identity: Uuid::new_v4().to_string(),
+                            plan_node_id: execution_plan_node.plan_node_id.0,
                             node_body: Some(NodeBody::Exchange(ExchangeNode {
                                 sources: exchange_sources,
                                 input_schema: execution_plan_node.schema.clone(),
@@ -377,6 +414,7 @@ impl StageRunner {
                             children: vec![],
                             // TODO: Generate meaningful identify
                             identity: Uuid::new_v4().to_string(),
+                            plan_node_id: execution_plan_node.plan_node_id.0,
                             node_body: Some(NodeBody::MergeSortExchange(MergeSortExchangeNode {
                                 exchange: Some(ExchangeNode {
                                     sources: exchange_sources,
@@ -400,6 +438,7 @@ impl StageRunner {
                     children,
                     // TODO: Generate meaningful identify
                     identity: Uuid::new_v4().to_string(),
+                    plan_node_id: execution_plan_node.plan_node_id.0,
                     node_body: Some(execution_plan_node.node.clone()),
                 }
             }
@@ -17,7 +17,7 @@ use std::sync::Arc;
 
 use log::error;
 use risingwave_common::error::Result;
-use tokio::sync::Mutex;
+use tokio::sync::{watch, Mutex};
 
 use crate::meta_client::FrontendMetaClient;
 use crate::scheduler::plan_fragmenter::QueryId;
@@ -26,6 +26,8 @@ use crate::scheduler::plan_fragmenter::QueryId;
 pub struct HummockSnapshotManager {
     core: Mutex<HummockSnapshotManagerCore>,
     meta_client: Arc<dyn FrontendMetaClient>,
+    /// Sends the latest committed epoch known by the frontend whenever it advances.
+    latest_epoch_tx: watch::Sender<u64>,
 }
 pub type HummockSnapshotManagerRef = Arc<HummockSnapshotManager>;
 
@@ -34,6 +36,7 @@ impl HummockSnapshotManager {
         Self {
             core: Mutex::new(HummockSnapshotManagerCore::new()),
             meta_client,
+            latest_epoch_tx: watch::channel(0).0,
         }
     }
 
@@ -46,7 +49,12 @@ impl HummockSnapshotManager {
                 .await?;
             core_guard.is_outdated = false;
             core_guard.last_pinned = epoch;
-            core_guard.epoch_to_query_ids.insert(epoch, HashSet::new());
+            core_guard.latest_epoch = core_guard.latest_epoch.max(epoch);
+            self.latest_epoch_tx.send_replace(core_guard.latest_epoch);
+            // Meta may return the epoch pinned last time, which may be still pinned by running
+            // queries. Their pins must be kept, otherwise the snapshot would be unpinned while
+            // they are still reading it.
+            core_guard.epoch_to_query_ids.entry(epoch).or_default();
         }
         let last_pinned = core_guard.last_pinned;
         tracing::info!("Pin epoch {} for query {:?}", last_pinned, &query_id);
@@ -58,6 +66,23 @@ impl HummockSnapshotManager {
         Ok(core_guard.last_pinned)
     }
 
+    /// Pins the snapshot of a historical `epoch` for a time-travel query. Meta rejects the epoch if
+    /// it's not readable any more. It's unpinned by [`Self::unpin_snapshot`] like other snapshots.
+    pub async fn pin_specific_epoch(&self, query_id: QueryId, epoch: u64) -> Result<u64> {
+        let mut core_guard = self.core.lock().await;
+        if !core_guard.epoch_to_query_ids.contains_key(&epoch) {
+            self.meta_client.pin_specific_snapshot(epoch).await?;
+            core_guard.epoch_to_query_ids.insert(epoch, HashSet::new());
+        }
+        tracing::info!("Pin epoch {} for query {:?}", epoch, &query_id);
+        core_guard
+            .epoch_to_query_ids
+            .get_mut(&epoch)
+            .unwrap()
+            .insert(query_id);
+        Ok(epoch)
+    }
+
     pub async fn unpin_snapshot(&self, epoch: u64, query_id: &QueryId) -> Result<()> {
         tracing::info!("Unpin epoch {} for query {:?}", epoch, &query_id);
         let local_count = async {
@@ -95,6 +120,65 @@ impl HummockSnapshotManager {
         if core_guard.last_pinned < epoch {
             core_guard.is_outdated = true;
         }
+        core_guard.latest_epoch = core_guard.latest_epoch.max(epoch);
+        self.latest_epoch_tx.send_replace(core_guard.latest_epoch);
+    }
+
+    /// Returns the latest committed epoch known by the frontend without pinning it, or `0` if
+    /// unknown yet.
+    pub async fn latest_epoch(&self) -> u64 {
+        self.core.lock().await.latest_epoch
+    }
+
+    /// Waits until the latest committed epoch known by the frontend reaches `epoch`, after which
+    /// the snapshots pinned by [`Self::get_epoch`] are not older than `epoch`.
+    pub async fn wait_epoch(&self, epoch: u64) {
+        let mut rx = self.latest_epoch_tx.subscribe();
+        while *rx.borrow_and_update() < epoch {
+            // The sender is owned by `self`, so it's never dropped here.
+            rx.changed().await.unwrap();
+        }
+    }
+}
+
+/// Unpins the snapshot pinned by a query when dropped, unless it's unpinned by [`Self::unpin`]
+/// already. It keeps the snapshot from being leaked by a query cancelled in the middle, e.g. by the
+/// statement timeout.
+pub struct PinnedSnapshotGuard {
+    manager: HummockSnapshotManagerRef,
+    epoch: u64,
+    query_id: Option<QueryId>,
+}
+
+impl PinnedSnapshotGuard {
+    pub fn new(manager: HummockSnapshotManagerRef, epoch: u64, query_id: QueryId) -> Self {
+        Self {
+            manager,
+            epoch,
+            query_id: Some(query_id),
+        }
+    }
+
+    pub async fn unpin(mut self) -> Result<()> {
+        let query_id = self.query_id.take().unwrap();
+        self.manager.unpin_snapshot(self.epoch, &query_id).await
+    }
+}
+
+impl Drop for PinnedSnapshotGuard {
+    fn drop(&mut self) {
+        if let Some(query_id) = self.query_id.take() {
+            let manager = self.manager.clone();
+            let epoch = self.epoch;
+            tokio::spawn(async move {
+                if let Err(e) = manager.unpin_snapshot(epoch, &query_id).await {
+                    error!(
+                        "Failed to unpin the snapshot of query {:?}: {}",
+                        query_id, e
+                    );
+                }
+            });
+        }
     }
 }
 
@@ -102,6 +186,8 @@ impl HummockSnapshotManager {
 struct HummockSnapshotManagerCore {
     is_outdated: bool,
     last_pinned: u64,
+    /// The latest committed epoch notified by meta.
+    latest_epoch: u64,
     /// Record the query ids that pin each snapshot.
     /// Send an `unpin_snapshot` RPC when a snapshot is not pinned any more.
     epoch_to_query_ids: HashMap<u64, HashSet<QueryId>>,
@@ -116,3 +202,67 @@ impl HummockSnapshotManagerCore {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::test_utils::MockFrontendMetaClient;
+
+    #[tokio::test]
+    async fn test_pin_specific_epoch() {
+        let manager = HummockSnapshotManager::new(Arc::new(MockFrontendMetaClient {}));
+        let query_id = |id: &str| QueryId { id: id.to_string() };
+
+        assert_eq!(manager.pin_specific_epoch(query_id("q1"), 42).await.unwrap(), 42);
+        assert_eq!(manager.pin_specific_epoch(query_id("q2"), 42).await.unwrap(), 42);
+        assert_eq!(manager.core.lock().await.epoch_to_query_ids[&42].len(), 2);
+
+        manager.unpin_snapshot(42, &query_id("q1")).await.unwrap();
+        manager.unpin_snapshot(42, &query_id("q2")).await.unwrap();
+        assert!(manager.core.lock().await.epoch_to_query_ids.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_pin_across_checkpoints() {
+        let manager = HummockSnapshotManager::new(Arc::new(MockFrontendMetaClient {}));
+        let query_id = |id: &str| QueryId { id: id.to_string() };
+
+        // A checkpoint outdates the snapshot while q1 is running, but meta returns the same epoch
+        // when it's pinned again for q2.
+        let epoch = manager.get_epoch(query_id("q1")).await.unwrap();
+        manager.update_snapshot_status(42).await;
+        assert_eq!(manager.get_epoch(query_id("q2")).await.unwrap(), epoch);
+        assert_eq!(
+            manager.core.lock().await.epoch_to_query_ids[&epoch].len(),
+            2
+        );
+
+        // q1 still holds the snapshot after q2 finishes.
+        manager
+            .unpin_snapshot(epoch, &query_id("q2"))
+            .await
+            .unwrap();
+        assert!(manager.core.lock().await.epoch_to_query_ids[&epoch].contains(&query_id("q1")));
+        manager
+            .unpin_snapshot(epoch, &query_id("q1"))
+            .await
+            .unwrap();
+        assert!(manager.core.lock().await.epoch_to_query_ids.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_wait_epoch() {
+        let manager = HummockSnapshotManager::new(Arc::new(MockFrontendMetaClient {}));
+        manager.wait_epoch(0).await;
+
+        manager.update_snapshot_status(42).await;
+        manager.wait_epoch(42).await;
+        assert!(
+            tokio::time::timeout(Duration::from_millis(10), manager.wait_epoch(43))
+                .await
+                .is_err()
+        );
+    }
+}
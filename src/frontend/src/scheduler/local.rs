@@ -15,7 +15,7 @@
 //! Local execution for batch query.
 
 use futures_async_stream::try_stream;
-use risingwave_batch::executor::ExecutorBuilder;
+use risingwave_batch::executor::{ExecutorBuilder, TaskProfile};
 use risingwave_batch::task::TaskId;
 use risingwave_common::array::DataChunk;
 use risingwave_common::error::{internal_error, Result, RwError};
@@ -26,12 +26,17 @@ use uuid::Uuid;
 use crate::optimizer::plan_node::PlanNodeType;
 use crate::scheduler::plan_fragmenter::{ExecutionPlanNode, Query};
 use crate::scheduler::task_context::FrontendBatchTaskContext;
-use crate::scheduler::HummockSnapshotManagerRef;
+use crate::scheduler::{HummockSnapshotManagerRef, PinnedSnapshotGuard};
 
 pub struct LocalQueryExecution {
     sql: String,
     query: Query,
     hummock_snapshot_manager: HummockSnapshotManagerRef,
+    /// The historical epoch to read specified by the session. `None` means reading the latest
+    /// snapshot.
+    query_epoch: Option<u64>,
+    /// Set if the runtime statistics of the executors are collected.
+    profile: Option<TaskProfile>,
 }
 
 impl LocalQueryExecution {
@@ -39,14 +44,23 @@ impl LocalQueryExecution {
         query: Query,
         hummock_snapshot_manager: HummockSnapshotManagerRef,
         sql: S,
+        query_epoch: Option<u64>,
     ) -> Self {
         Self {
             sql: sql.into(),
             query,
             hummock_snapshot_manager,
+            query_epoch,
+            profile: None,
         }
     }
 
+    /// Records the runtime statistics of the executors into `profile`.
+    pub fn with_profile(mut self, profile: TaskProfile) -> Self {
+        self.profile = Some(profile);
+        self
+    }
+
     #[try_stream(ok = DataChunk, error = RwError)]
     pub async fn run(self) {
         debug!(
@@ -66,15 +80,45 @@ impl LocalQueryExecution {
             task_id: 0,
         };
 
-        let epoch = self.hummock_snapshot_manager.get_epoch(query_id).await?;
+        let epoch = match self.query_epoch {
+            Some(epoch) => {
+                self.hummock_snapshot_manager
+                    .pin_specific_epoch(query_id.clone(), epoch)
+                    .await?
+            }
+            None => {
+                self.hummock_snapshot_manager
+                    .get_epoch(query_id.clone())
+                    .await?
+            }
+        };
+        let pinned_snapshot =
+            PinnedSnapshotGuard::new(self.hummock_snapshot_manager.clone(), epoch, query_id);
         let plan_node = plan_fragment.root.unwrap();
-        let executor = ExecutorBuilder::new(&plan_node, &task_id, context, epoch);
-        let executor = executor.build().await?;
+        let mut executor = ExecutorBuilder::new(&plan_node, &task_id, context, epoch);
+        if let Some(profile) = &self.profile {
+            executor = executor.with_profile(profile.clone());
+        }
+        let executor = executor.build().await;
+        // All iterators of table scans have been created while building the executors, so the
+        // snapshot can be released no matter whether the query is finished or cancelled later.
+        // Lookup joins read tables while running though, so the snapshot is kept pinned until the
+        // query finishes, or unpinned by the guard if the query is cancelled.
+        let pinned_snapshot = if self.query.has_lookup_join() {
+            Some(pinned_snapshot)
+        } else {
+            pinned_snapshot.unpin().await?;
+            None
+        };
+        let executor = executor?;
 
         #[for_await]
         for chunk in executor.execute() {
             yield chunk?;
         }
+        if let Some(pinned_snapshot) = pinned_snapshot {
+            pinned_snapshot.unpin().await?;
+        }
     }
 
     /// Convert query to plan fragment.
@@ -121,6 +165,7 @@ impl LocalQueryExecution {
                     children,
                     // TODO: Generate meaningful identify
                     identity: Uuid::new_v4().to_string(),
+                    plan_node_id: execution_plan_node.plan_node_id.0,
                     node_body: Some(execution_plan_node.node.clone()),
                 })
             }
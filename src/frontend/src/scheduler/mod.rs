@@ -12,9 +12,12 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::BTreeMap;
 use std::sync::Arc;
+use std::time::Duration;
 
 use futures::Stream;
+use parking_lot::Mutex;
 use risingwave_common::array::DataChunk;
 use risingwave_common::error::Result;
 
@@ -25,7 +28,9 @@ pub use distributed::QueryManager;
 mod hummock_snapshot_manager;
 pub use hummock_snapshot_manager::*;
 mod plan_fragmenter;
-pub use plan_fragmenter::BatchPlanFragmenter;
+pub use plan_fragmenter::{BatchPlanFragmenter, StageId};
+mod query_result_cache;
+pub use query_result_cache::*;
 mod local;
 pub use local::*;
 mod task_context;
@@ -36,16 +41,28 @@ pub trait DataChunkStream = Stream<Item = Result<DataChunk>>;
 /// Context for mpp query execution.
 pub struct ExecutionContext {
     session: Arc<SessionImpl>,
+    stage_timings: StageTimings,
 }
 
 pub type ExecutionContextRef = Arc<ExecutionContext>;
 
+/// The time from the start of a distributed query to all tasks of each of its stages being
+/// scheduled, by the ids of the stages.
+pub type StageTimings = Arc<Mutex<BTreeMap<StageId, Duration>>>;
+
 impl ExecutionContext {
     pub fn new(session: Arc<SessionImpl>) -> Self {
-        Self { session }
+        Self {
+            session,
+            stage_timings: Default::default(),
+        }
     }
 
     pub fn session(&self) -> &SessionImpl {
         &self.session
     }
+
+    pub fn stage_timings(&self) -> &StageTimings {
+        &self.stage_timings
+    }
 }
@@ -146,6 +146,15 @@ impl Query {
             .map(|(id, _)| *id)
             .collect::<Vec<_>>()
     }
+
+    /// Whether some stage of the query reads tables while running, so that the snapshot of the
+    /// query is kept pinned until the query finishes.
+    pub fn has_lookup_join(&self) -> bool {
+        self.stage_graph
+            .stages
+            .values()
+            .any(|stage| stage.has_lookup_join)
+    }
 }
 
 /// Fragment part of `Query`.
@@ -159,6 +168,11 @@ pub struct QueryStage {
     /// Hummock iterators to read data from table. The iterator is initialized during
     /// the executor building process on the batch execution engine.
     pub has_table_scan: bool,
+    /// Whether this stage contains some lookup join, which reads the inner table by the rows
+    /// of the outer side while running rather than creating the iterators while being built. The
+    /// snapshot of the query must be pinned until the query finishes then, otherwise the inner
+    /// table may be read after the versions of the epoch are compacted away.
+    pub has_lookup_join: bool,
 }
 
 impl Debug for QueryStage {
@@ -168,6 +182,7 @@ impl Debug for QueryStage {
             .field("parallelism", &self.parallelism)
             .field("exchange_info", &self.exchange_info)
             .field("has_table_scan", &self.has_table_scan)
+            .field("has_lookup_join", &self.has_lookup_join)
             .finish()
     }
 }
@@ -183,6 +198,7 @@ struct QueryStageBuilder {
 
     children_stages: Vec<QueryStageRef>,
     has_table_scan: bool,
+    has_lookup_join: bool,
 }
 
 impl QueryStageBuilder {
@@ -195,6 +211,7 @@ impl QueryStageBuilder {
             exchange_info,
             children_stages: vec![],
             has_table_scan: false,
+            has_lookup_join: false,
         }
     }
 
@@ -206,6 +223,7 @@ impl QueryStageBuilder {
             exchange_info: self.exchange_info,
             parallelism: self.parallelism,
             has_table_scan: self.has_table_scan,
+            has_lookup_join: self.has_lookup_join,
         });
 
         stage_graph_builder.add_node(stage.clone());
@@ -362,8 +380,10 @@ impl BatchPlanFragmenter {
                 } else {
                     builder.root = Some(Arc::new(execution_plan_node));
                 }
-                // Check out the comments for `has_table_scan` in `QueryStage`.
-                builder.has_table_scan = node.node_type() == PlanNodeType::BatchSeqScan;
+                // Check out the comments for `has_table_scan` and `has_lookup_join` in
+                // `QueryStage`.
+                builder.has_table_scan |= node.node_type() == PlanNodeType::BatchSeqScan;
+                builder.has_lookup_join |= node.node_type() == PlanNodeType::BatchLookupJoin;
             }
         }
     }
@@ -409,8 +429,8 @@ mod tests {
 
     use crate::expr::InputRef;
     use crate::optimizer::plan_node::{
-        BatchExchange, BatchHashJoin, BatchSeqScan, EqJoinPredicate, LogicalJoin, LogicalScan,
-        PlanNodeType,
+        BatchExchange, BatchFilter, BatchHashJoin, BatchSeqScan, EqJoinPredicate, LogicalFilter,
+        LogicalJoin, LogicalScan, PlanNodeType,
     };
     use crate::optimizer::property::{Distribution, Order};
     use crate::optimizer::PlanRef;
@@ -436,6 +456,7 @@ mod tests {
                 table_id: 0.into(),
                 pks: vec![],
                 order_desc: vec![],
+                retention_seconds: 0,
                 columns: vec![
                     ColumnDesc {
                         data_type: DataType::Int32,
@@ -618,6 +639,67 @@ mod tests {
         assert_eq!(scan_node2.root.node_type(), PlanNodeType::BatchSeqScan);
         assert_eq!(scan_node2.root.stage_id, None);
         assert_eq!(0, scan_node2.root.children.len());
+
+        let mut stages_has_table_scan = query.stage_has_table_scan();
+        stages_has_table_scan.sort_unstable();
+        assert_eq!(stages_has_table_scan, vec![2, 3]);
+        assert!(!query.has_lookup_join());
+    }
+
+    #[tokio::test]
+    async fn test_stage_has_table_scan() {
+        // The scan is found even if it's not the root of its stage, so that the snapshot is kept
+        // pinned until the scan is scheduled.
+        //
+        //   Exchange
+        //      |
+        //    Filter
+        //      |
+        //     Scan
+        //
+        let ctx = OptimizerContext::mock().await;
+        let scan: PlanRef = BatchSeqScan::new(LogicalScan::create(
+            "".to_string(),
+            Rc::new(TableDesc {
+                table_id: 1.into(),
+                pks: vec![],
+                order_desc: vec![],
+                retention_seconds: 0,
+                columns: vec![ColumnDesc {
+                    data_type: DataType::Int32,
+                    column_id: 0.into(),
+                    name: "a".to_string(),
+                    type_name: String::new(),
+                    field_descs: vec![],
+                }],
+                distribution_keys: vec![],
+            }),
+            vec![],
+            ctx,
+        ))
+        .into();
+        let filter: PlanRef =
+            BatchFilter::new(LogicalFilter::new(scan, Condition::true_cond())).into();
+        let exchange: PlanRef =
+            BatchExchange::new(filter, Order::default(), Distribution::Single).into();
+
+        let worker = WorkerNode {
+            id: 0,
+            r#type: WorkerType::ComputeNode as i32,
+            host: Some(HostAddress {
+                host: "127.0.0.1".to_string(),
+                port: 5687,
+            }),
+            state: risingwave_pb::common::worker_node::State::Running as i32,
+            parallel_units: generate_parallel_units(0, 0),
+        };
+        let worker_node_manager = Arc::new(WorkerNodeManager::mock(vec![worker]));
+        let query = BatchPlanFragmenter::new(worker_node_manager)
+            .split(exchange)
+            .unwrap();
+        let filter_stage = query.stage_graph.stages.get(&1).unwrap();
+        assert_eq!(filter_stage.root.node_type(), PlanNodeType::BatchFilter);
+        assert_eq!(query.stage_has_table_scan(), vec![1]);
     }
 
     fn generate_parallel_units(start_id: u32, node_id: u32) -> Vec<ParallelUnit> {
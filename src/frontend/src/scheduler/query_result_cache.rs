@@ -0,0 +1,177 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A frontend-side cache of the results of point lookups on materialized views, for workloads
+//! like dashboards that repeat the same lookups. The results are allowed to be stale for a
+//! configured time. Epochs whose physical time falls in the same window of that length share the
+//! results, which are dropped once the committed epoch advances past their window.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+
+use risingwave_common::array::DataChunk;
+use risingwave_common::catalog::TableId;
+use risingwave_common::error::Result;
+use risingwave_common::util::epoch::Epoch;
+
+use crate::expr::{ExprImpl, ExprType};
+use crate::optimizer::plan_node::{PlanRef, PlanTreeNode, PlanTreeNodeUnary};
+
+/// Max number of the cached results.
+const QUERY_RESULT_CACHE_CAPACITY: usize = 4096;
+
+/// Identifies the result of a point lookup read in a window of epochs.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ResultCacheKey {
+    table_id: TableId,
+    /// The explained plan, which contains the looked-up key and the output columns.
+    lookup: String,
+    staleness_ms: u64,
+    /// The physical time of the epoch divided by `staleness_ms`.
+    bucket: u64,
+}
+
+impl ResultCacheKey {
+    /// Returns the key of `plan` read in `epoch`, or `None` if the plan is not a point lookup,
+    /// i.e. it doesn't look up a single table by all columns of the primary key.
+    pub fn for_point_lookup(plan: &PlanRef, epoch: u64, staleness_ms: u64) -> Result<Option<Self>> {
+        if staleness_ms == 0 {
+            return Ok(None);
+        }
+        let table_id = match point_lookup_table(plan) {
+            Some(table_id) => table_id,
+            None => return Ok(None),
+        };
+        Ok(Some(Self {
+            table_id,
+            lookup: plan.explain_to_string()?,
+            staleness_ms,
+            bucket: Epoch(epoch).physical_time() / staleness_ms,
+        }))
+    }
+
+    /// Whether the epoch of `self` has advanced past the window of `other`.
+    fn is_newer_than_window_of(&self, other: &Self) -> bool {
+        self.bucket * self.staleness_ms >= (other.bucket + 1) * other.staleness_ms
+    }
+}
+
+/// Returns the table looked up by `plan`, if the plan only filters a scan of the table with
+/// equal conditions on all columns of its primary key, with projections and exchanges on top.
+fn point_lookup_table(plan: &PlanRef) -> Option<TableId> {
+    let mut plan = plan.clone();
+    while plan.as_batch_exchange().is_some() || plan.as_batch_project().is_some() {
+        plan = plan.inputs()[0].clone();
+    }
+    let filter = plan.as_batch_filter()?;
+    // Exchanges keep the schema, so the predicate still refers to the columns of the scan.
+    let mut input = filter.input();
+    while input.as_batch_exchange().is_some() {
+        input = input.inputs()[0].clone();
+    }
+    let scan = input.as_batch_seq_scan()?.logical();
+
+    let output_col_idx = scan.output_col_idx();
+    let looked_up_columns: HashSet<usize> = filter
+        .predicate()
+        .conjunctions
+        .iter()
+        .filter_map(|expr| match expr {
+            ExprImpl::FunctionCall(call) if call.get_expr_type() == ExprType::Equal => {
+                match call.inputs() {
+                    [ExprImpl::InputRef(input_ref), value]
+                    | [value, ExprImpl::InputRef(input_ref)]
+                        if value.is_const() =>
+                    {
+                        Some(output_col_idx[input_ref.index()])
+                    }
+                    _ => None,
+                }
+            }
+            _ => None,
+        })
+        .collect();
+    let table_desc = scan.table_desc();
+    if !table_desc.pks.is_empty()
+        && table_desc
+            .pks
+            .iter()
+            .all(|pk| looked_up_columns.contains(pk))
+    {
+        Some(table_desc.table_id)
+    } else {
+        None
+    }
+}
+
+/// Results of point lookups shared by all sessions of the frontend.
+#[derive(Default)]
+pub struct QueryResultCache {
+    entries: Mutex<HashMap<ResultCacheKey, Vec<DataChunk>>>,
+}
+
+pub type QueryResultCacheRef = Arc<QueryResultCache>;
+
+impl QueryResultCache {
+    /// Returns the cached result of `key`. Results of the windows that the epoch of `key` has
+    /// advanced past are dropped.
+    pub fn get(&self, key: &ResultCacheKey) -> Option<Vec<DataChunk>> {
+        let mut entries = self.entries.lock().unwrap();
+        entries.retain(|cached, _| !key.is_newer_than_window_of(cached));
+        entries.get(key).cloned()
+    }
+
+    /// Caches the result of `key`. The result is not cached if the cache is full.
+    pub fn insert(&self, key: ResultCacheKey, chunks: Vec<DataChunk>) {
+        let mut entries = self.entries.lock().unwrap();
+        entries.retain(|cached, _| !key.is_newer_than_window_of(cached));
+        if entries.len() < QUERY_RESULT_CACHE_CAPACITY {
+            entries.insert(key, chunks);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_common::array::Row;
+    use risingwave_common::types::{DataType, ScalarImpl};
+
+    use super::*;
+
+    fn key(lookup: &str, bucket: u64) -> ResultCacheKey {
+        ResultCacheKey {
+            table_id: TableId::new(1),
+            lookup: lookup.to_string(),
+            staleness_ms: 1000,
+            bucket,
+        }
+    }
+
+    #[test]
+    fn test_query_result_cache() {
+        let cache = QueryResultCache::default();
+        let chunk =
+            DataChunk::from_rows(&[Row(vec![Some(ScalarImpl::Int32(1))])], &[DataType::Int32])
+                .unwrap();
+
+        cache.insert(key("a", 1), vec![chunk.clone()]);
+        cache.insert(key("b", 1), vec![]);
+        assert_eq!(cache.get(&key("a", 1)).unwrap()[0].cardinality(), 1);
+        assert!(cache.get(&key("c", 1)).is_none());
+
+        // The epoch advances past the window of the cached results.
+        assert!(cache.get(&key("a", 2)).is_none());
+        assert!(cache.get(&key("b", 1)).is_none());
+    }
+}
@@ -15,7 +15,10 @@
 use std::sync::Arc;
 
 use risingwave_batch::executor::BatchMetrics;
-use risingwave_batch::task::{BatchTaskContext, TaskOutput, TaskOutputId};
+use risingwave_batch::task::{
+    BatchMemoryTracker, BatchMemoryTrackerRef, BatchTaskContext, TaskOutput, TaskOutputId,
+};
+use risingwave_common::config::BatchConfig;
 use risingwave_common::error::Result;
 use risingwave_common::util::addr::HostAddr;
 use risingwave_source::SourceManagerRef;
@@ -44,4 +47,12 @@ impl BatchTaskContext for FrontendBatchTaskContext {
     fn stats(&self) -> Arc<BatchMetrics> {
         todo!()
     }
+
+    fn batch_config(&self) -> Arc<BatchConfig> {
+        Arc::new(BatchConfig::default())
+    }
+
+    fn mem_tracker(&self) -> BatchMemoryTrackerRef {
+        Arc::new(BatchMemoryTracker::default())
+    }
 }
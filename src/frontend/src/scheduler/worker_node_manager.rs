@@ -12,17 +12,64 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::sync::{Arc, RwLock};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
 
 use rand::distributions::{Distribution as RandDistribution, Uniform};
+use rand::Rng;
 use risingwave_common::error::ErrorCode::InternalError;
 use risingwave_common::error::Result;
 use risingwave_pb::common::{WorkerNode, WorkerType};
 use risingwave_rpc_client::MetaClient;
 
-/// `WorkerNodeManager` manages live worker nodes.
+pub type ParallelUnitId = u32;
+
+/// `WorkerNodeManager` manages live worker nodes and their loads.
 pub struct WorkerNodeManager {
     worker_nodes: RwLock<Vec<WorkerNode>>,
+    /// Load of each worker node, keyed by worker id.
+    worker_loads: Mutex<HashMap<u32, WorkerLoad>>,
+}
+
+/// Load of a worker node seen by this frontend, used to route new tasks to the least loaded node.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct WorkerLoad {
+    /// Tasks scheduled to the node whose queries are not finished yet.
+    pub outstanding_tasks: usize,
+    /// Moving average of the latency of creating tasks on the node, in microseconds.
+    pub latency_us: u64,
+}
+
+/// An outstanding task on a worker node. The task counts in the load of the node until the guard
+/// is dropped.
+pub struct TaskLoadGuard {
+    worker_node_manager: WorkerNodeManagerRef,
+    worker_id: u32,
+}
+
+impl Drop for TaskLoadGuard {
+    fn drop(&mut self) {
+        if let Some(load) = self
+            .worker_node_manager
+            .worker_loads
+            .lock()
+            .unwrap()
+            .get_mut(&self.worker_id)
+        {
+            load.outstanding_tasks -= 1;
+        }
+    }
+}
+
+/// Load guards of the tasks of a query, released together when the query finishes.
+#[derive(Clone, Default)]
+pub struct QueryTaskLoads(Arc<Mutex<Vec<TaskLoadGuard>>>);
+
+impl QueryTaskLoads {
+    pub fn add(&self, guard: TaskLoadGuard) {
+        self.0.lock().unwrap().push(guard);
+    }
 }
 
 pub type WorkerNodeManagerRef = Arc<WorkerNodeManager>;
@@ -34,13 +81,19 @@ impl WorkerNodeManager {
                 .list_all_nodes(WorkerType::ComputeNode, false)
                 .await?,
         );
-        Ok(Self { worker_nodes })
+        Ok(Self {
+            worker_nodes,
+            worker_loads: Default::default(),
+        })
     }
 
     /// Used in tests.
     pub fn mock(worker_nodes: Vec<WorkerNode>) -> Self {
         let worker_nodes = RwLock::new(worker_nodes);
-        Self { worker_nodes }
+        Self {
+            worker_nodes,
+            worker_loads: Default::default(),
+        }
     }
 
     pub fn list_worker_nodes(&self) -> Vec<WorkerNode> {
@@ -73,9 +126,83 @@ impl WorkerNodeManager {
         Ok(current_nodes.get(die.sample(&mut rng)).unwrap().clone())
     }
 
+    /// Get the worker node with the fewest outstanding tasks, preferring the one with lower latency
+    /// among equally loaded nodes. Ties are broken randomly.
+    pub fn next_least_loaded(&self) -> Result<WorkerNode> {
+        let current_nodes = self.worker_nodes.read().unwrap();
+        if current_nodes.is_empty() {
+            tracing::error!("No worker node available.");
+            return Err(InternalError("No worker node available".to_string()).into());
+        }
+
+        let loads = self.worker_loads.lock().unwrap();
+        // The first node with the minimum load is picked, so start from a random one.
+        let offset = rand::thread_rng().gen_range(0..current_nodes.len());
+        let node = current_nodes
+            .iter()
+            .cycle()
+            .skip(offset)
+            .take(current_nodes.len())
+            .min_by_key(|node| {
+                let load = loads.get(&node.id).copied().unwrap_or_default();
+                (load.outstanding_tasks, load.latency_us)
+            })
+            .unwrap();
+        Ok(node.clone())
+    }
+
+    /// Counts a task in the load of worker `worker_id` until the returned guard is dropped.
+    pub fn start_task(self: &Arc<Self>, worker_id: u32) -> TaskLoadGuard {
+        self.worker_loads
+            .lock()
+            .unwrap()
+            .entry(worker_id)
+            .or_default()
+            .outstanding_tasks += 1;
+        TaskLoadGuard {
+            worker_node_manager: self.clone(),
+            worker_id,
+        }
+    }
+
+    /// Records the latency of creating a task on worker `worker_id`.
+    pub fn report_task_latency(&self, worker_id: u32, latency: Duration) {
+        let mut loads = self.worker_loads.lock().unwrap();
+        let load = loads.entry(worker_id).or_default();
+        let latency_us = latency.as_micros() as u64;
+        load.latency_us = if load.latency_us == 0 {
+            latency_us
+        } else {
+            (load.latency_us * 7 + latency_us) / 8
+        };
+    }
+
+    pub fn worker_load(&self, worker_id: u32) -> WorkerLoad {
+        self.worker_loads
+            .lock()
+            .unwrap()
+            .get(&worker_id)
+            .copied()
+            .unwrap_or_default()
+    }
+
     pub fn worker_node_count(&self) -> usize {
         self.worker_nodes.read().unwrap().len()
     }
+
+    /// Returns the worker node that owns each parallel unit.
+    pub fn parallel_unit_owners(&self) -> HashMap<ParallelUnitId, WorkerNode> {
+        self.worker_nodes
+            .read()
+            .unwrap()
+            .iter()
+            .flat_map(|node| {
+                node.parallel_units
+                    .iter()
+                    .map(move |parallel_unit| (parallel_unit.id, node.clone()))
+            })
+            .collect()
+    }
 }
 
 #[cfg(test)]
@@ -121,4 +248,42 @@ mod tests {
             worker_nodes.as_slice()[1..].to_vec()
         );
     }
+
+    #[test]
+    fn test_least_loaded() {
+        use super::*;
+
+        let worker_node = |id| WorkerNode {
+            id,
+            r#type: WorkerType::ComputeNode as i32,
+            host: Some(HostAddr::try_from("127.0.0.1:1234").unwrap().to_protobuf()),
+            state: worker_node::State::Running as i32,
+            parallel_units: vec![],
+        };
+        let manager = Arc::new(WorkerNodeManager::mock(vec![
+            worker_node(1),
+            worker_node(2),
+        ]));
+
+        let guard_1 = manager.start_task(1);
+        assert_eq!(manager.next_least_loaded().unwrap().id, 2);
+        let guard_2 = manager.start_task(2);
+        assert_eq!(manager.worker_load(2).outstanding_tasks, 1);
+
+        // Equally loaded nodes are ordered by latency.
+        manager.report_task_latency(1, Duration::from_millis(10));
+        manager.report_task_latency(2, Duration::from_millis(1));
+        assert_eq!(manager.next_least_loaded().unwrap().id, 2);
+
+        drop(guard_1);
+        assert_eq!(manager.worker_load(1).outstanding_tasks, 0);
+        assert_eq!(manager.next_least_loaded().unwrap().id, 1);
+
+        let loads = QueryTaskLoads::default();
+        loads.add(guard_2);
+        loads.add(manager.start_task(2));
+        assert_eq!(manager.worker_load(2).outstanding_tasks, 2);
+        drop(loads);
+        assert_eq!(manager.worker_load(2).outstanding_tasks, 0);
+    }
 }
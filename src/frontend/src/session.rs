@@ -14,37 +14,55 @@
 
 use std::collections::HashMap;
 use std::fmt::Formatter;
+use std::future::Future;
 use std::io::{Error, ErrorKind};
 use std::marker::Sync;
-use std::path::PathBuf;
-use std::sync::atomic::{AtomicI32, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicI32, AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 
-use parking_lot::RwLock;
-use pgwire::pg_response::PgResponse;
-use pgwire::pg_server::{BoxedError, Session, SessionManager};
-use risingwave_common::config::FrontendConfig;
-use risingwave_common::error::{Result, RwError};
+use futures::StreamExt;
+use futures_async_stream::try_stream;
+use parking_lot::{RwLock, RwLockReadGuard};
+use pgwire::pg_field_descriptor::TypeOid;
+use pgwire::pg_response::{PgResponse, RowSet, RowSetStream};
+use pgwire::pg_server::{
+    BoxedError, IdleTimeout, Session, SessionManager, StatementDesc, UserAuthenticator,
+};
+use risingwave_common::catalog::DEFAULT_SUPPER_USER;
+use risingwave_common::config::RwConfig;
+use risingwave_common::error::{ErrorCode, Result, RwError};
+use risingwave_common::types::DataType;
 use risingwave_common::util::addr::HostAddr;
+use risingwave_common::util::epoch::INVALID_EPOCH;
 use risingwave_pb::common::WorkerType;
-use risingwave_rpc_client::{ComputeClientPool, MetaClient};
+use risingwave_pb::user::auth_info::EncryptionType;
+use risingwave_rpc_client::{ComputeClientPool, MetaClient, StreamClientPool, StreamClientPoolRef};
+use risingwave_sqlparser::ast::Statement;
 use risingwave_sqlparser::parser::Parser;
 use tokio::sync::oneshot::Sender;
 use tokio::sync::watch;
 use tokio::task::JoinHandle;
+use tokio::time::Instant;
 
+use crate::binder::{Binder, BoundStatement, ParamValues};
 use crate::catalog::catalog_service::{CatalogReader, CatalogWriter, CatalogWriterImpl};
 use crate::catalog::root_catalog::Catalog;
-use crate::handler::dml::IMPLICIT_FLUSH;
-use crate::handler::handle;
+use crate::config::ConfigMap;
+use crate::handler::cursor::CursorManager;
+use crate::handler::util::{data_type_to_type_oid, to_pg_field, type_oid_to_data_type};
+use crate::handler::{handle, handle_bound};
 use crate::meta_client::{FrontendMetaClient, FrontendMetaClientImpl};
 use crate::observer::observer_manager::ObserverManager;
 use crate::optimizer::plan_node::PlanNodeId;
 use crate::scheduler::worker_node_manager::{WorkerNodeManager, WorkerNodeManagerRef};
-use crate::scheduler::{HummockSnapshotManager, HummockSnapshotManagerRef, QueryManager};
+use crate::scheduler::{
+    HummockSnapshotManager, HummockSnapshotManagerRef, QueryManager, QueryResultCacheRef,
+};
 use crate::test_utils::MockUserInfoWriter;
+use crate::user::encrypt_md5;
 use crate::user::user_manager::UserInfoManager;
+use crate::user::user_privilege::ObjectCheckItem;
 use crate::user::user_service::{UserInfoReader, UserInfoWriter, UserInfoWriterImpl};
 use crate::FrontendOpts;
 
@@ -111,13 +129,8 @@ impl std::fmt::Debug for OptimizerContext {
     }
 }
 
-fn load_config(opts: &FrontendOpts) -> FrontendConfig {
-    if opts.config_path.is_empty() {
-        return FrontendConfig::default();
-    }
-
-    let config_path = PathBuf::from(opts.config_path.to_owned());
-    FrontendConfig::init(config_path).unwrap()
+fn load_config(opts: &FrontendOpts) -> RwConfig {
+    risingwave_common::config::load_config(&opts.config_path)
 }
 
 /// The global environment for the frontend server.
@@ -133,6 +146,8 @@ pub struct FrontendEnv {
     worker_node_manager: WorkerNodeManagerRef,
     query_manager: QueryManager,
     hummock_snapshot_manager: HummockSnapshotManagerRef,
+    query_result_cache: QueryResultCacheRef,
+    stream_client_pool: StreamClientPoolRef,
 }
 
 impl FrontendEnv {
@@ -170,6 +185,8 @@ impl FrontendEnv {
             worker_node_manager,
             query_manager,
             hummock_snapshot_manager,
+            query_result_cache: Default::default(),
+            stream_client_pool: Arc::new(StreamClientPool::default()),
         }
     }
 
@@ -249,6 +266,8 @@ impl FrontendEnv {
                 meta_client: frontend_meta_client,
                 query_manager,
                 hummock_snapshot_manager,
+                query_result_cache: Default::default(),
+                stream_client_pool: Arc::new(StreamClientPool::default()),
             },
             observer_join_handle,
             heartbeat_join_handle,
@@ -299,44 +318,70 @@ impl FrontendEnv {
     pub fn hummock_snapshot_manager(&self) -> &HummockSnapshotManagerRef {
         &self.hummock_snapshot_manager
     }
+
+    pub fn query_result_cache(&self) -> &QueryResultCacheRef {
+        &self.query_result_cache
+    }
+
+    pub fn stream_client_pool(&self) -> &StreamClientPoolRef {
+        &self.stream_client_pool
+    }
 }
 
 pub struct SessionImpl {
     env: FrontendEnv,
     database: String,
+    user_name: String,
+    user_authenticator: UserAuthenticator,
     /// Stores the value of configurations.
-    config_map: RwLock<HashMap<String, ConfigEntry>>,
+    config_map: RwLock<ConfigMap>,
+    /// Prepared statements of the extended query protocol, keyed by the statement name.
+    prepared_statements: RwLock<HashMap<String, PreparedStatement>>,
+    /// The data written by the session are visible to batch queries once this epoch is committed.
+    write_epoch: AtomicU64,
+    /// Whether the session is in a transaction block started by `BEGIN`.
+    in_transaction: AtomicBool,
+    cursor_manager: Arc<CursorManager>,
 }
 
-#[derive(Clone)]
-pub struct ConfigEntry {
-    str_val: String,
+/// A statement parsed once when it's prepared. Queries and DML are also bound then, and executing
+/// them only substitutes the parameter values and plans the statement.
+struct PreparedStatement {
+    /// `None` if the sql is empty.
+    stmt: Option<Statement>,
+    param_types: Vec<DataType>,
+    /// `None` if the statement is not a query or DML.
+    bound: Option<BoundPrepared>,
 }
 
-impl ConfigEntry {
-    pub fn new(str_val: String) -> Self {
-        ConfigEntry { str_val }
-    }
-
-    /// Only used for boolean configurations.
-    pub fn is_set(&self, default: bool) -> bool {
-        self.str_val.parse().unwrap_or(default)
-    }
-
-    pub fn get_val<V>(&self, default: V) -> V
-    where
-        for<'a> V: TryFrom<&'a str, Error = RwError>,
-    {
-        V::try_from(&self.str_val).unwrap_or(default)
-    }
+/// A query or DML statement bound with [`Parameter`](crate::expr::Parameter)s in place of the
+/// parameter values.
+#[derive(Clone)]
+struct BoundPrepared {
+    stmt: BoundStatement,
+    object_check_items: Vec<ObjectCheckItem>,
+    /// The version of the catalog that the statement is bound with. The statement is bound again
+    /// once the catalog changes.
+    catalog_version: u64,
 }
 
 impl SessionImpl {
-    pub fn new(env: FrontendEnv, database: String) -> Self {
+    pub fn new(
+        env: FrontendEnv,
+        database: String,
+        user_name: String,
+        user_authenticator: UserAuthenticator,
+    ) -> Self {
         Self {
             env,
             database,
-            config_map: Self::init_config_map(),
+            user_name,
+            user_authenticator,
+            config_map: RwLock::new(ConfigMap::default()),
+            prepared_statements: RwLock::new(HashMap::new()),
+            write_epoch: AtomicU64::new(INVALID_EPOCH),
+            in_transaction: AtomicBool::new(false),
+            cursor_manager: Default::default(),
         }
     }
 
@@ -345,7 +390,13 @@ impl SessionImpl {
         Self {
             env: FrontendEnv::mock(),
             database: "dev".to_string(),
-            config_map: Self::init_config_map(),
+            user_name: DEFAULT_SUPPER_USER.to_string(),
+            user_authenticator: UserAuthenticator::None,
+            config_map: RwLock::new(ConfigMap::default()),
+            prepared_statements: RwLock::new(HashMap::new()),
+            write_epoch: AtomicU64::new(INVALID_EPOCH),
+            in_transaction: AtomicBool::new(false),
+            cursor_manager: Default::default(),
         }
     }
 
@@ -357,30 +408,141 @@ impl SessionImpl {
         &self.database
     }
 
+    pub fn user_name(&self) -> &str {
+        &self.user_name
+    }
+
+    /// Checks whether the user of the session has all the privileges in `items`.
+    pub fn check_privileges(&self, items: &[ObjectCheckItem]) -> Result<()> {
+        self.env
+            .user_info_reader()
+            .read_guard()
+            .check_privileges(&self.user_name, items)
+    }
+
+    /// Checks whether the user of the session owns the relation `name` owned by `owner`.
+    pub fn check_owner(&self, owner: &str, name: &str) -> Result<()> {
+        self.env
+            .user_info_reader()
+            .read_guard()
+            .check_owner(&self.user_name, owner, name)
+    }
+
+    /// Records that the data written by the session so far are visible to batch queries once
+    /// `epoch` is committed.
+    pub fn update_write_epoch(&self, epoch: u64) {
+        self.write_epoch.fetch_max(epoch, Ordering::Relaxed);
+    }
+
+    /// Fetches the epoch of the data being written into the stream graph from meta, and records it
+    /// by [`Self::update_write_epoch`]. It's called after the statements writing data.
+    pub async fn track_write_epoch(&self) -> Result<()> {
+        let epoch = self.env.meta_client().get_current_epoch().await?;
+        self.update_write_epoch(epoch);
+        Ok(())
+    }
+
+    /// Waits for the data written by the session to be committed, so that the batch queries of the
+    /// session read their own writes.
+    pub async fn wait_for_writes(&self) {
+        let epoch = self.write_epoch.load(Ordering::Relaxed);
+        self.env.hummock_snapshot_manager().wait_epoch(epoch).await;
+    }
+
+    /// Records whether the session is in a transaction block, which decides the idle timeout of
+    /// the session.
+    pub fn set_in_transaction(&self, in_transaction: bool) {
+        self.in_transaction.store(in_transaction, Ordering::Relaxed);
+    }
+
+    pub fn cursor_manager(&self) -> &Arc<CursorManager> {
+        &self.cursor_manager
+    }
+
+    /// Handles `stmt`, which is cancelled once it runs longer than `STATEMENT_TIMEOUT`, including
+    /// the time taken to send its results. Cancelling a distributed query drops its results, which
+    /// aborts its tasks and unpins its snapshot.
+    async fn handle_with_timeout(
+        self: Arc<Self>,
+        stmt: Statement,
+        params: ParamValues,
+    ) -> Result<PgResponse> {
+        let handle = handle(self.clone(), stmt, params);
+        self.with_statement_timeout(handle).await
+    }
+
+    /// Runs `handle` within `STATEMENT_TIMEOUT`, including sending the results.
+    async fn with_statement_timeout(
+        &self,
+        handle: impl Future<Output = Result<PgResponse>>,
+    ) -> Result<PgResponse> {
+        let timeout = self.config().statement_timeout();
+        let deadline = match timeout {
+            Some(timeout) => Instant::now() + timeout,
+            None => return handle.await,
+        };
+        let rsp = tokio::time::timeout_at(deadline, handle)
+            .await
+            .map_err(|_| statement_timeout_error())??;
+        Ok(rsp.map_values_stream(|values_stream| with_deadline(values_stream, deadline)))
+    }
+
+    /// Binds the query or DML statement `stmt` to prepare, and returns it along with the types of
+    /// its parameters.
+    fn bind_prepared(
+        &self,
+        stmt: Statement,
+        param_types: Vec<Option<DataType>>,
+    ) -> Result<(BoundPrepared, Vec<DataType>)> {
+        let catalog = self.env.catalog_reader().read_guard();
+        let catalog_version = catalog.version();
+        let mut binder = Binder::new_with_param_types(catalog, self.database.clone(), param_types);
+        let stmt = binder.bind(stmt)?;
+        let bound = BoundPrepared {
+            stmt,
+            object_check_items: binder.object_check_items().to_vec(),
+            catalog_version,
+        };
+        Ok((bound, binder.param_types()))
+    }
+
+    /// Runs the prepared query or DML statement `stmt` bound as `bound` with the parameter values
+    /// `params`. The statement is bound again if the catalog has changed since it was bound.
+    async fn run_bound_prepared(
+        self: Arc<Self>,
+        name: &str,
+        stmt: Statement,
+        param_types: Vec<DataType>,
+        bound: BoundPrepared,
+        params: Vec<Option<String>>,
+    ) -> Result<PgResponse> {
+        let catalog_version = self.env.catalog_reader().read_guard().version();
+        let bound = if bound.catalog_version == catalog_version {
+            bound
+        } else {
+            let param_types = param_types.into_iter().map(Some).collect();
+            let (bound, _) = self.bind_prepared(stmt.clone(), param_types)?;
+            if let Some(prepared) = self.prepared_statements.write().get_mut(name) {
+                prepared.bound = Some(bound.clone());
+            }
+            bound
+        };
+        self.check_privileges(&bound.object_check_items)?;
+        let bound_stmt = bound.stmt.bind_param_values(params)?;
+        let handle = handle_bound(self.clone(), bound_stmt, stmt.to_string());
+        self.with_statement_timeout(handle).await
+    }
+
     /// Set configuration values in this session.
-    /// For example, `set_config("RW_IMPLICIT_FLUSH", true)` will implicit flush for every inserts.
-    pub fn set_config(&self, key: &str, val: &str) {
-        let key = key.to_ascii_lowercase();
-        self.config_map
-            .write()
-            .insert(key, ConfigEntry::new(val.to_string()));
-    }
-
-    /// Get configuration values in this session.
-    pub fn get_config(&self, key: &str) -> Option<ConfigEntry> {
-        let key = key.to_ascii_lowercase();
-        let reader = self.config_map.read();
-        reader.get(&key).cloned()
-    }
-
-    fn init_config_map() -> RwLock<HashMap<String, ConfigEntry>> {
-        let mut map = HashMap::new();
-        // FIXME: May need better init way + default config.
-        map.insert(
-            IMPLICIT_FLUSH.to_string().to_ascii_lowercase(),
-            ConfigEntry::new("false".to_string()),
-        );
-        RwLock::new(map)
+    /// For example, `set_config("RW_IMPLICIT_FLUSH", "true")` will implicit flush for every
+    /// inserts.
+    pub fn set_config(&self, key: &str, val: &str) -> Result<()> {
+        self.config_map.write().set(key, val)
+    }
+
+    /// Get the configuration values of this session.
+    pub fn config(&self) -> RwLockReadGuard<'_, ConfigMap> {
+        self.config_map.read()
     }
 }
 
@@ -394,7 +556,11 @@ pub struct SessionManagerImpl {
 impl SessionManager for SessionManagerImpl {
     type Session = SessionImpl;
 
-    fn connect(&self, database: &str) -> std::result::Result<Arc<Self::Session>, BoxedError> {
+    fn connect(
+        &self,
+        database: &str,
+        user_name: &str,
+    ) -> std::result::Result<Arc<Self::Session>, BoxedError> {
         let catalog_reader = self.env.catalog_reader();
         let reader = catalog_reader.read_guard();
         if reader.get_database_by_name(database).is_err() {
@@ -403,7 +569,60 @@ impl SessionManager for SessionManagerImpl {
                 format!("Not found database name: {}", database),
             )));
         }
-        Ok(SessionImpl::new(self.env.clone(), database.to_string()).into())
+
+        // The reason of a failure is only logged, so that clients can't tell whether a user exists.
+        let auth_failed = |reason: &str| -> BoxedError {
+            tracing::warn!("failed to authenticate user {}: {}", user_name, reason);
+            Box::new(Error::new(
+                ErrorKind::InvalidInput,
+                "password authentication failed",
+            ))
+        };
+        let user_reader = self.env.user_info_reader();
+        let reader = user_reader.read_guard();
+        let user = reader
+            .get_user_by_name(user_name)
+            .ok_or_else(|| auth_failed("the user does not exist"))?;
+        if !user.can_login {
+            return Err(auth_failed("the user is not allowed to login"));
+        }
+        let user_authenticator = match &user.auth_info {
+            None => UserAuthenticator::None,
+            Some(auth_info) => match auth_info.encryption_type() {
+                // The MD5 hash is computed from a plaintext password so that it's never sent in
+                // clear text.
+                EncryptionType::Plaintext => UserAuthenticator::Md5WithSalt {
+                    encrypted_password: encrypt_md5(
+                        user_name,
+                        &String::from_utf8_lossy(&auth_info.encrypted_value),
+                    ),
+                    salt: rand::random(),
+                },
+                EncryptionType::Md5 => UserAuthenticator::Md5WithSalt {
+                    encrypted_password: auth_info.encrypted_value.clone(),
+                    salt: rand::random(),
+                },
+                // A SHA-256 hash, which was stored by older versions, can only be checked against
+                // the password in clear text, which is never asked for. New ones are rejected by
+                // `try_extract`.
+                EncryptionType::Sha256 => {
+                    return Err(auth_failed(
+                        "the password is hashed with SHA-256, set it again to hash it with MD5",
+                    ));
+                }
+                EncryptionType::Unknown => {
+                    return Err(auth_failed("the password encryption is unknown"));
+                }
+            },
+        };
+
+        Ok(SessionImpl::new(
+            self.env.clone(),
+            database.to_string(),
+            user_name.to_string(),
+            user_authenticator,
+        )
+        .into())
     }
 }
 
@@ -448,23 +667,262 @@ impl Session for SessionImpl {
             ));
         }
         let stmt = stmts.swap_remove(0);
-        let rsp = handle(self, stmt).await.map_err(|e| {
-            tracing::error!("failed to handle sql:\n{}:\n{}", sql, e);
+        let rsp = self
+            .handle_with_timeout(stmt, ParamValues::default())
+            .await
+            .map_err(|e| {
+                tracing::error!("failed to handle sql:\n{}:\n{}", sql, e);
+                e
+            })?;
+        Ok(rsp)
+    }
+
+    fn prepare_statement(
+        &self,
+        name: &str,
+        sql: &str,
+        param_types: Vec<Option<TypeOid>>,
+    ) -> std::result::Result<StatementDesc, BoxedError> {
+        let mut stmts = Parser::parse_sql(sql).map_err(|e| {
+            tracing::error!("failed to parse sql:\n{}:\n{}", sql, e);
+            e
+        })?;
+        if stmts.len() > 1 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "cannot insert multiple commands into a prepared statement",
+            )
+            .into());
+        }
+        let stmt = stmts.pop();
+        let param_types = param_types
+            .into_iter()
+            .map(|t| t.map(type_oid_to_data_type))
+            .collect::<Vec<_>>();
+
+        // Bind the statement to infer the types of the parameters and the result columns.
+        let (bound, param_types, row_desc) = match &stmt {
+            Some(
+                stmt @ (Statement::Query(_)
+                | Statement::Insert { .. }
+                | Statement::Update { .. }
+                | Statement::Delete { .. }),
+            ) => {
+                let (bound, param_types) = self.bind_prepared(stmt.clone(), param_types)?;
+                let row_desc = match &bound.stmt {
+                    BoundStatement::Query(query) => {
+                        query.schema().fields().iter().map(to_pg_field).collect()
+                    }
+                    _ => vec![],
+                };
+                (Some(bound), param_types, row_desc)
+            }
+            // Parameters are not supported in other statements.
+            _ => (
+                None,
+                param_types
+                    .into_iter()
+                    .map(|t| t.unwrap_or(DataType::Varchar))
+                    .collect(),
+                vec![],
+            ),
+        };
+
+        let desc = StatementDesc {
+            param_types: param_types
+                .iter()
+                .cloned()
+                .map(data_type_to_type_oid)
+                .collect(),
+            row_desc,
+        };
+        self.prepared_statements.write().insert(
+            name.to_string(),
+            PreparedStatement {
+                stmt,
+                param_types,
+                bound,
+            },
+        );
+        Ok(desc)
+    }
+
+    async fn run_prepared_statement(
+        self: Arc<Self>,
+        name: &str,
+        params: Vec<Option<String>>,
+    ) -> std::result::Result<PgResponse, BoxedError> {
+        let (stmt, param_types, bound) = {
+            let prepared_statements = self.prepared_statements.read();
+            let prepared = prepared_statements.get(name).ok_or_else(|| {
+                Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("prepared statement \"{}\" does not exist", name),
+                )
+            })?;
+            (
+                prepared.stmt.clone(),
+                prepared.param_types.clone(),
+                prepared.bound.clone(),
+            )
+        };
+        let stmt = match stmt {
+            Some(stmt) => stmt,
+            None => {
+                return Ok(PgResponse::new(
+                    pgwire::pg_response::StatementType::EMPTY,
+                    0,
+                    vec![],
+                    vec![],
+                ))
+            }
+        };
+        let rsp = match bound {
+            Some(bound) => {
+                self.run_bound_prepared(name, stmt, param_types, bound, params)
+                    .await
+            }
+            None => {
+                let params = ParamValues {
+                    types: param_types,
+                    values: params,
+                };
+                self.handle_with_timeout(stmt, params).await
+            }
+        };
+        let rsp = rsp.map_err(|e| {
+            tracing::error!("failed to handle prepared statement {}:\n{}", name, e);
             e
         })?;
         Ok(rsp)
     }
+
+    fn close_statement(&self, name: &str) {
+        self.prepared_statements.write().remove(name);
+    }
+
+    fn user_authenticator(&self) -> &UserAuthenticator {
+        &self.user_authenticator
+    }
+
+    fn idle_timeout(&self) -> Option<IdleTimeout> {
+        let config = self.config();
+        if self.in_transaction.load(Ordering::Relaxed) {
+            config
+                .idle_in_transaction_session_timeout()
+                .map(IdleTimeout::InTransaction)
+        } else {
+            config.idle_session_timeout().map(IdleTimeout::Session)
+        }
+    }
+}
+
+fn statement_timeout_error() -> RwError {
+    ErrorCode::QueryCancelled("canceling statement due to statement timeout".to_string()).into()
+}
+
+/// Fails `values_stream` with the statement timeout error once `deadline` passes.
+#[try_stream(boxed, ok = RowSet, error = BoxedError)]
+async fn with_deadline(mut values_stream: RowSetStream, deadline: Instant) {
+    loop {
+        match tokio::time::timeout_at(deadline, values_stream.next()).await {
+            Ok(Some(rows)) => yield rows?,
+            Ok(None) => break,
+            Err(_) => return Err(statement_timeout_error().into()),
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use assert_impl::assert_impl;
+    use pgwire::pg_field_descriptor::TypeOid;
+    use pgwire::pg_server::Session;
 
     use crate::session::OptimizerContextRef;
+    use crate::test_utils::LocalFrontend;
 
     #[test]
     fn check_query_context_ref() {
         assert_impl!(Send: OptimizerContextRef);
         assert_impl!(!Sync: OptimizerContextRef);
     }
+
+    #[tokio::test]
+    async fn test_prepare_statement() {
+        let frontend = LocalFrontend::new(Default::default()).await;
+        frontend
+            .run_sql("CREATE TABLE t (v1 INT, v2 DOUBLE)")
+            .await
+            .unwrap();
+        let session = frontend.session_ref();
+
+        // The types of parameters are inferred from the context, or given by the client.
+        let desc = session
+            .prepare_statement(
+                "s1",
+                "SELECT v2, $3 FROM t WHERE v1 = $1 AND v2 < $2::BIGINT",
+                vec![None, None, Some(TypeOid::Boolean)],
+            )
+            .unwrap();
+        let param_types = desc
+            .param_types
+            .iter()
+            .map(TypeOid::as_number)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            param_types,
+            vec![
+                TypeOid::Int.as_number(),
+                TypeOid::BigInt.as_number(),
+                TypeOid::Boolean.as_number()
+            ]
+        );
+        let row_desc = desc
+            .row_desc
+            .iter()
+            .map(|field| field.get_type_oid().as_number())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            row_desc,
+            vec![TypeOid::Float8.as_number(), TypeOid::Boolean.as_number()]
+        );
+
+        // Parameters whose types can't be inferred are of type varchar.
+        let desc = session
+            .prepare_statement("s2", "SELECT $1", vec![])
+            .unwrap();
+        assert_eq!(
+            desc.param_types[0].as_number(),
+            TypeOid::Varchar.as_number()
+        );
+
+        // Statements other than queries and DML return no rows.
+        let desc = session.prepare_statement("s3", "FLUSH", vec![]).unwrap();
+        assert!(desc.param_types.is_empty());
+        assert!(desc.row_desc.is_empty());
+
+        // Parameters are numbered from 1 to 65535.
+        assert!(session
+            .prepare_statement("s4", "SELECT $0", vec![])
+            .is_err());
+        assert!(session
+            .prepare_statement("s4", "SELECT $65536", vec![])
+            .is_err());
+
+        let err = session
+            .prepare_statement("s4", "SELECT 1; SELECT 2", vec![])
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "cannot insert multiple commands into a prepared statement"
+        );
+
+        session.close_statement("s1");
+        assert!(session
+            .clone()
+            .run_prepared_statement("s1", vec![])
+            .await
+            .is_err());
+    }
 }
@@ -350,11 +350,46 @@ impl StreamFragmenter {
                 append_only_top_n_node.table_id = state.gen_table_id();
             }
 
+            NodeBody::EmitOnWindowClose(node) => {
+                node.table_id = state.gen_table_id();
+            }
+
+            NodeBody::TemporalFilter(node) => {
+                node.table_id = state.gen_table_id();
+            }
+
+            NodeBody::Dedup(node) => {
+                node.table_id = state.gen_table_id();
+            }
+
+            NodeBody::Materialize(node) if node.collect_stats => {
+                node.stats_table_id = state.gen_table_id();
+            }
+
             _ => {}
         }
     }
 }
 
+/// Returns the ids of the state tables of a stateful operator, which are assigned in
+/// [`StreamFragmenter::build_graph`].
+pub fn state_table_ids(stream_node: &StreamNode) -> Vec<u32> {
+    match stream_node.node_body.as_ref() {
+        Some(NodeBody::HashJoin(node)) => vec![node.left_table_id, node.right_table_id],
+        Some(NodeBody::GlobalSimpleAgg(node) | NodeBody::LocalSimpleAgg(node)) => {
+            node.table_ids.clone()
+        }
+        Some(NodeBody::HashAgg(node)) => node.table_ids.clone(),
+        Some(NodeBody::TopN(node)) => vec![node.table_id],
+        Some(NodeBody::AppendOnlyTopN(node)) => vec![node.table_id],
+        Some(NodeBody::EmitOnWindowClose(node)) => vec![node.table_id],
+        Some(NodeBody::TemporalFilter(node)) => vec![node.table_id],
+        Some(NodeBody::Dedup(node)) => vec![node.table_id],
+        Some(NodeBody::Materialize(node)) if node.collect_stats => vec![node.stats_table_id],
+        _ => vec![],
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use risingwave_pb::data::data_type::TypeName;
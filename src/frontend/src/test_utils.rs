@@ -17,18 +17,23 @@ use std::io::Write;
 use std::sync::atomic::{AtomicU32, Ordering};
 use std::sync::Arc;
 
+use futures::StreamExt;
 use parking_lot::RwLock;
 use pgwire::pg_response::PgResponse;
-use pgwire::pg_server::{BoxedError, Session, SessionManager};
+use pgwire::pg_server::{BoxedError, Session, SessionManager, UserAuthenticator};
 use risingwave_common::catalog::{
     TableId, DEFAULT_DATABASE_NAME, DEFAULT_SCHEMA_NAME, DEFAULT_SUPPER_USER,
     DEFAULT_SUPPER_USER_PASSWORD,
 };
 use risingwave_common::error::Result;
+use risingwave_pb::catalog::source::Info;
 use risingwave_pb::catalog::table::OptionalAssociatedSourceId;
 use risingwave_pb::catalog::{
-    Database as ProstDatabase, Schema as ProstSchema, Source as ProstSource, Table as ProstTable,
+    Database as ProstDatabase, Function as ProstFunction, Schema as ProstSchema,
+    Source as ProstSource, Table as ProstTable,
 };
+use risingwave_pb::meta::{EventLog, TableFragments};
+use risingwave_pb::plan_common::ColumnCatalog as ProstColumnCatalog;
 use risingwave_pb::stream_plan::StreamFragmentGraph;
 use risingwave_pb::user::auth_info::EncryptionType;
 use risingwave_pb::user::{AuthInfo, GrantPrivilege, UserInfo};
@@ -39,7 +44,7 @@ use tempfile::{Builder, NamedTempFile};
 use crate::binder::Binder;
 use crate::catalog::catalog_service::CatalogWriter;
 use crate::catalog::root_catalog::Catalog;
-use crate::catalog::{DatabaseId, SchemaId};
+use crate::catalog::{DatabaseId, FunctionId, SchemaId};
 use crate::meta_client::FrontendMetaClient;
 use crate::optimizer::PlanRef;
 use crate::planner::Planner;
@@ -58,7 +63,11 @@ pub struct LocalFrontend {
 impl SessionManager for LocalFrontend {
     type Session = SessionImpl;
 
-    fn connect(&self, _database: &str) -> std::result::Result<Arc<Self::Session>, BoxedError> {
+    fn connect(
+        &self,
+        _database: &str,
+        _user_name: &str,
+    ) -> std::result::Result<Arc<Self::Session>, BoxedError> {
         Ok(self.session_ref())
     }
 }
@@ -78,12 +87,12 @@ impl LocalFrontend {
     }
 
     pub async fn query_formatted_result(&self, sql: impl Into<String>) -> Vec<String> {
-        self.run_sql(sql)
-            .await
-            .unwrap()
-            .iter()
-            .map(|row| format!("{:?}", row))
-            .collect()
+        let mut rsp = self.run_sql(sql).await.unwrap();
+        let mut res = vec![];
+        while let Some(rows) = rsp.values_stream().next().await {
+            res.extend(rows.unwrap().iter().map(|row| format!("{:?}", row)));
+        }
+        res
     }
 
     /// Convert a sql (must be an `Query`) into an unoptimized batch plan.
@@ -113,6 +122,8 @@ impl LocalFrontend {
         Arc::new(SessionImpl::new(
             self.env.clone(),
             DEFAULT_DATABASE_NAME.to_string(),
+            DEFAULT_SUPPER_USER.to_string(),
+            UserAuthenticator::None,
         ))
     }
 }
@@ -122,6 +133,9 @@ pub struct MockCatalogWriter {
     id: AtomicU32,
     table_id_to_schema_id: RwLock<HashMap<u32, SchemaId>>,
     schema_id_to_database_id: RwLock<HashMap<u32, DatabaseId>>,
+    /// The created tables and sources, which are updated when columns are added.
+    tables: RwLock<HashMap<u32, ProstTable>>,
+    sources: RwLock<HashMap<u32, ProstSource>>,
 }
 
 #[async_trait::async_trait]
@@ -153,6 +167,7 @@ impl CatalogWriter for MockCatalogWriter {
         table.id = self.gen_id();
         self.catalog.write().create_table(&table);
         self.add_table_or_source_id(table.id, table.schema_id, table.database_id);
+        self.tables.write().insert(table.id, table);
         Ok(())
     }
 
@@ -201,6 +216,49 @@ impl CatalogWriter for MockCatalogWriter {
         Ok(())
     }
 
+    async fn alter_source_add_column(
+        &self,
+        source_id: u32,
+        column: ProstColumnCatalog,
+    ) -> Result<()> {
+        let mut sources = self.sources.write();
+        let source = sources.get_mut(&source_id).unwrap();
+        match source.info.as_mut().unwrap() {
+            Info::StreamSource(info) => info.columns.push(column.clone()),
+            Info::TableSource(info) => info.columns.push(column.clone()),
+        }
+        for table in self.tables.write().values_mut() {
+            if table.optional_associated_source_id
+                == Some(OptionalAssociatedSourceId::AssociatedSourceId(source_id))
+            {
+                table.columns.push(column.clone());
+                self.catalog.write().update_table(table);
+            }
+        }
+        self.catalog.write().update_source(source.clone());
+        Ok(())
+    }
+
+    async fn alter_table_name(&self, table_id: u32, new_name: &str) -> Result<()> {
+        self.update_relation(table_id, |name, _| *name = new_name.to_string());
+        Ok(())
+    }
+
+    async fn alter_source_name(&self, source_id: u32, new_name: &str) -> Result<()> {
+        self.update_relation(source_id, |name, _| *name = new_name.to_string());
+        Ok(())
+    }
+
+    async fn alter_table_owner(&self, table_id: u32, new_owner: &str) -> Result<()> {
+        self.update_relation(table_id, |_, owner| *owner = new_owner.to_string());
+        Ok(())
+    }
+
+    async fn alter_source_owner(&self, source_id: u32, new_owner: &str) -> Result<()> {
+        self.update_relation(source_id, |_, owner| *owner = new_owner.to_string());
+        Ok(())
+    }
+
     async fn drop_database(&self, database_id: u32) -> Result<()> {
         self.catalog.write().drop_database(database_id);
         Ok(())
@@ -211,6 +269,21 @@ impl CatalogWriter for MockCatalogWriter {
         self.catalog.write().drop_schema(database_id, schema_id);
         Ok(())
     }
+
+    async fn create_function(&self, mut function: ProstFunction) -> Result<()> {
+        function.id = self.gen_id();
+        self.catalog.write().create_function(&function);
+        self.add_table_or_source_id(function.id, function.schema_id, function.database_id);
+        Ok(())
+    }
+
+    async fn drop_function(&self, function_id: FunctionId) -> Result<()> {
+        let (database_id, schema_id) = self.drop_table_or_source_id(function_id);
+        self.catalog
+            .write()
+            .drop_function(database_id, schema_id, function_id);
+        Ok(())
+    }
 }
 
 impl MockCatalogWriter {
@@ -231,6 +304,8 @@ impl MockCatalogWriter {
             id: AtomicU32::new(0),
             table_id_to_schema_id: Default::default(),
             schema_id_to_database_id: RwLock::new(map),
+            tables: Default::default(),
+            sources: Default::default(),
         }
     }
 
@@ -271,9 +346,38 @@ impl MockCatalogWriter {
         source.id = self.gen_id();
         self.catalog.write().create_source(source.clone());
         self.add_table_or_source_id(source.id, source.schema_id, source.database_id);
+        self.sources.write().insert(source.id, source.clone());
         Ok(source.id)
     }
 
+    /// Updates the name and the owner of the table or source `id` by `update`, together with its
+    /// associated source or table.
+    fn update_relation(&self, id: u32, update: impl Fn(&mut String, &mut String)) {
+        let mut tables = self.tables.write();
+        let mut sources = self.sources.write();
+        let source_id = match tables.get(&id) {
+            Some(table) => match table.optional_associated_source_id {
+                Some(OptionalAssociatedSourceId::AssociatedSourceId(source_id)) => Some(source_id),
+                None => None,
+            },
+            None => Some(id),
+        };
+        for table in tables.values_mut() {
+            if table.id == id
+                || (source_id.is_some()
+                    && table.optional_associated_source_id
+                        == source_id.map(OptionalAssociatedSourceId::AssociatedSourceId))
+            {
+                update(&mut table.name, &mut table.owner);
+                self.catalog.write().update_table(table);
+            }
+        }
+        if let Some(source) = source_id.and_then(|source_id| sources.get_mut(&source_id)) {
+            update(&mut source.name, &mut source.owner);
+            self.catalog.write().update_source(source.clone());
+        }
+    }
+
     fn get_database_id_by_schema(&self, schema_id: u32) -> DatabaseId {
         *self
             .schema_id_to_database_id
@@ -391,7 +495,27 @@ impl FrontendMetaClient for MockFrontendMetaClient {
         Ok(0)
     }
 
-    async fn flush(&self) -> Result<()> {
+    async fn pin_specific_snapshot(&self, epoch: u64) -> Result<u64> {
+        Ok(epoch)
+    }
+
+    async fn flush(&self) -> Result<u64> {
+        Ok(0)
+    }
+
+    async fn get_current_epoch(&self) -> Result<u64> {
+        Ok(0)
+    }
+
+    async fn list_table_fragments(&self, _table_ids: &[u32]) -> Result<Vec<TableFragments>> {
+        Ok(vec![])
+    }
+
+    async fn list_event_log(&self, _limit: u32) -> Result<Vec<EventLog>> {
+        Ok(vec![])
+    }
+
+    async fn alter_parallelism(&self, _table_id: u32, _parallelism: u32) -> Result<()> {
         Ok(())
     }
 
@@ -13,11 +13,13 @@
 // limitations under the License.
 
 use md5;
+use risingwave_common::error::{ErrorCode, Result};
 use risingwave_pb::user::auth_info::EncryptionType;
 use risingwave_pb::user::AuthInfo;
 use sha2::{Digest, Sha256};
 
 pub(crate) mod user_manager;
+pub(crate) mod user_privilege;
 pub(crate) mod user_service;
 
 pub type UserName = String;
@@ -31,48 +33,51 @@ const VALID_MD5_ENCRYPTED_LEN: usize = MD5_ENCRYPTED_PREFIX.len() + 32;
 
 /// Try to extract the encryption password from given password. The password is always stored
 /// encrypted in the system catalogs. The ENCRYPTED keyword has no effect, but is accepted for
-/// backwards compatibility. The method of encryption is by default SHA-256-encrypted. If the
-/// presented password string is already in MD5-encrypted or SHA-256-encrypted format, then it is
-/// stored as-is regardless of `password_encryption` (since the system cannot decrypt the specified
-/// encrypted password string, to encrypt it in a different format).
+/// backwards compatibility. The method of encryption is by default MD5-encrypted. If the
+/// presented password string is already in MD5-encrypted format, then it is stored as-is (since
+/// the system cannot decrypt the specified encrypted password string, to encrypt it in a different
+/// format).
 ///
 /// For an MD5 encrypted password, rolpassword column will begin with the string md5 followed by a
 /// 32-character hexadecimal MD5 hash. The MD5 hash will be of the user's password concatenated to
 /// their user name. For example, if user joe has password xyzzy, we will store the md5 hash of
 /// xyzzyjoe.
 ///
-/// For an SHA-256 encrypted password, rolpassword column will begin with the string SHA-256:
-/// followed by a 64-character hexadecimal SHA-256 hash, which is the SHA-256 hash of the user's
-/// password concatenated to their user name. The SHA-256 will be the default hash algorithm for
-/// Risingwave.
+/// An SHA-256 encrypted password, which begins with the string SHA-256: followed by a
+/// 64-character hexadecimal SHA-256 hash of the user's password concatenated to their user name,
+/// is rejected. Such a hash can only be checked against the password sent in clear text, so the
+/// user could never log in.
 ///
 /// A password that does not follow either of those formats is assumed to be unencrypted.
-pub fn try_extract(name: &str, password: &str) -> Option<AuthInfo> {
+pub fn try_extract(name: &str, password: &str) -> Result<Option<AuthInfo>> {
     // Specifying an empty string will also set the auth info to null.
     if password.is_empty() {
-        return None;
+        return Ok(None);
     }
 
     if valid_sha256_password(password) {
-        Some(AuthInfo {
-            encryption_type: EncryptionType::Sha256 as i32,
-            encrypted_value: password.trim_start_matches(SHA256_ENCRYPTED_PREFIX).into(),
-        })
+        Err(ErrorCode::InvalidInputSyntax(
+            "SHA-256 encrypted passwords are not supported, please use an MD5 encrypted or \
+             unencrypted password instead"
+                .to_string(),
+        )
+        .into())
     } else if valid_md5_password(password) {
-        Some(AuthInfo {
+        Ok(Some(AuthInfo {
             encryption_type: EncryptionType::Md5 as i32,
             encrypted_value: password.trim_start_matches(MD5_ENCRYPTED_PREFIX).into(),
-        })
+        }))
     } else {
-        Some(encrypt_default(name, password))
+        Ok(Some(encrypt_default(name, password)))
     }
 }
 
-/// Encrypt the password with SHA-256 as default.
+/// Encrypt the password with MD5 as default, which is checked by MD5 challenge-response when the
+/// user connects.
 pub fn encrypt_default(name: &str, password: &str) -> AuthInfo {
     AuthInfo {
-        encryption_type: EncryptionType::Sha256 as i32,
-        encrypted_value: encrypt_sha256(name, password),
+        encryption_type: EncryptionType::Md5 as i32,
+        encrypted_value: encrypt_md5(name, password),
     }
 }
 
@@ -116,31 +121,28 @@ mod tests {
             encrypt_sha256(user_name, password)
         );
 
-        let input_passwords = vec![
-            "bar",
-            "",
-            "md596948aad3fcae80c08a35c9b5958cd89",
-            "SHA-256:88ecde925da3c6f8ec3d140683da9d2a422f26c1ae1d9212da1e5a53416dcc88",
-        ];
+        let input_passwords = vec!["bar", "", "md596948aad3fcae80c08a35c9b5958cd89"];
         let expected_output_passwords = vec![
             Some(AuthInfo {
-                encryption_type: EncryptionType::Sha256 as i32,
-                encrypted_value: encrypt_sha256(user_name, password),
+                encryption_type: EncryptionType::Md5 as i32,
+                encrypted_value: encrypt_md5(user_name, password),
             }),
             None,
             Some(AuthInfo {
                 encryption_type: EncryptionType::Md5 as i32,
                 encrypted_value: encrypt_md5(user_name, password),
             }),
-            Some(AuthInfo {
-                encryption_type: EncryptionType::Sha256 as i32,
-                encrypted_value: encrypt_sha256(user_name, password),
-            }),
         ];
         let output_passwords = input_passwords
             .iter()
-            .map(|&p| try_extract(user_name, p))
+            .map(|&p| try_extract(user_name, p).unwrap())
             .collect::<Vec<_>>();
         assert_eq!(output_passwords, expected_output_passwords);
+
+        assert!(try_extract(
+            user_name,
+            "SHA-256:88ecde925da3c6f8ec3d140683da9d2a422f26c1ae1d9212da1e5a53416dcc88"
+        )
+        .is_err());
     }
 }
@@ -14,8 +14,10 @@
 
 use std::collections::HashMap;
 
-use risingwave_pb::user::{GrantPrivilege, UserInfo};
+use risingwave_common::error::{ErrorCode, Result};
+use risingwave_pb::user::UserInfo;
 
+use crate::user::user_privilege::{check_owner, check_privileges, ObjectCheckItem};
 use crate::user::{UserInfoVersion, UserName};
 
 /// `UserInfoManager` is responsible for managing users.
@@ -61,8 +63,20 @@ impl UserInfoManager {
         todo!()
     }
 
-    pub fn verify(&self, _user_name: &str, _privileges: &[GrantPrivilege]) -> bool {
-        todo!()
+    /// Checks whether the user has all the privileges in `items`.
+    pub fn check_privileges(&self, user_name: &str, items: &[ObjectCheckItem]) -> Result<()> {
+        let user = self.get_user_by_name(user_name).ok_or_else(|| {
+            ErrorCode::PermissionDenied(format!("user {} does not exist", user_name))
+        })?;
+        check_privileges(user, items)
+    }
+
+    /// Checks whether the user owns the relation `name` owned by `owner`.
+    pub fn check_owner(&self, user_name: &str, owner: &str, name: &str) -> Result<()> {
+        let user = self.get_user_by_name(user_name).ok_or_else(|| {
+            ErrorCode::PermissionDenied(format!("user {} does not exist", user_name))
+        })?;
+        check_owner(user, owner, name)
     }
 
     pub fn clear(&mut self) {
@@ -0,0 +1,141 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use risingwave_common::error::{ErrorCode, Result};
+use risingwave_pb::user::grant_privilege::{Action, Object};
+use risingwave_pb::user::{GrantPrivilege, UserInfo};
+
+/// A privilege on an object required by a statement.
+#[derive(Debug, Clone)]
+pub struct ObjectCheckItem {
+    /// Name of the object, used in the error message.
+    pub name: String,
+    pub object: Object,
+    pub action: Action,
+    /// Owner of the object, who has all the privileges on it. `None` if the owner is not known,
+    /// e.g. for a database or schema.
+    pub owner: Option<String>,
+}
+
+impl ObjectCheckItem {
+    pub fn new(name: impl Into<String>, object: Object, action: Action) -> Self {
+        Self {
+            name: name.into(),
+            object,
+            action,
+            owner: None,
+        }
+    }
+
+    /// Sets the owner of the object, who doesn't need any privilege granted on it.
+    pub fn with_owner(mut self, owner: impl Into<String>) -> Self {
+        self.owner = Some(owner.into());
+        self
+    }
+
+    /// Whether the privilege is granted by `privilege`. Privileges on all the tables or sources
+    /// of a schema have been expanded to each object by the meta service, so only the privileges
+    /// on the object itself are checked.
+    fn is_granted_by(&self, privilege: &GrantPrivilege) -> bool {
+        privilege.object.as_ref() == Some(&self.object)
+            && privilege
+                .action_with_opts
+                .iter()
+                .any(|ao| ao.action == self.action as i32)
+    }
+}
+
+/// Checks whether `user` has all the privileges in `items`. A superuser has all the privileges, and
+/// the owner of an object has all the privileges on it.
+pub fn check_privileges(user: &UserInfo, items: &[ObjectCheckItem]) -> Result<()> {
+    if user.is_supper {
+        return Ok(());
+    }
+    for item in items {
+        if item.owner.as_ref() != Some(&user.name)
+            && !user
+                .grant_privileges
+                .iter()
+                .any(|privilege| item.is_granted_by(privilege))
+        {
+            return Err(ErrorCode::PermissionDenied(format!(
+                "user {} has no {:?} privilege on {}",
+                user.name, item.action, item.name
+            ))
+            .into());
+        }
+    }
+    Ok(())
+}
+
+/// Checks whether `user` owns the relation `name` owned by `owner`. A superuser owns all the
+/// relations.
+pub fn check_owner(user: &UserInfo, owner: &str, name: &str) -> Result<()> {
+    if user.is_supper || user.name == owner {
+        Ok(())
+    } else {
+        Err(ErrorCode::PermissionDenied(format!("must be owner of relation {}", name)).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_pb::user::grant_privilege::ActionWithGrantOption;
+
+    use super::*;
+
+    #[test]
+    fn test_check_privileges() {
+        let mut user = UserInfo {
+            name: "user".to_string(),
+            grant_privileges: vec![GrantPrivilege {
+                object: Some(Object::TableId(1)),
+                action_with_opts: vec![ActionWithGrantOption {
+                    action: Action::Select as i32,
+                    with_grant_option: false,
+                }],
+            }],
+            ..Default::default()
+        };
+        let select_t1 = ObjectCheckItem::new("t1", Object::TableId(1), Action::Select);
+        let insert_t1 = ObjectCheckItem::new("t1", Object::TableId(1), Action::Insert);
+        let select_t2 = ObjectCheckItem::new("t2", Object::TableId(2), Action::Select);
+        let select_s1 = ObjectCheckItem::new("s1", Object::SourceId(1), Action::Select);
+
+        assert!(check_privileges(&user, &[select_t1.clone()]).is_ok());
+        assert!(check_privileges(&user, &[select_t1.clone(), insert_t1.clone()]).is_err());
+        assert!(check_privileges(&user, &[select_t2.clone()]).is_err());
+        // Tables and sources with the same id are different objects.
+        assert!(check_privileges(&user, &[select_s1.clone()]).is_err());
+        // The owner needs no privilege granted.
+        assert!(check_privileges(&user, &[select_t2.clone().with_owner("user")]).is_ok());
+        assert!(check_privileges(&user, &[select_t2.clone().with_owner("other")]).is_err());
+
+        user.is_supper = true;
+        assert!(check_privileges(&user, &[select_t1, insert_t1, select_t2, select_s1]).is_ok());
+    }
+
+    #[test]
+    fn test_check_owner() {
+        let mut user = UserInfo {
+            name: "user".to_string(),
+            ..Default::default()
+        };
+        assert!(check_owner(&user, "user", "t").is_ok());
+        assert!(check_owner(&user, "root", "t").is_err());
+
+        user.is_supper = true;
+        assert!(check_owner(&user, "root", "t").is_ok());
+    }
+}
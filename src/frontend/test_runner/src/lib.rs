@@ -178,7 +178,7 @@ impl TestCase {
 
         if let Some(ref config_map) = self.with_config_map {
             for (key, val) in config_map {
-                session.set_config(key, val);
+                session.set_config(key, val)?;
             }
         }
 
@@ -286,9 +286,12 @@ impl TestCase {
                     or_replace: false,
                     name,
                     query,
+                    with_options,
+                    emit_mode,
                     ..
                 } => {
-                    create_mv::handle_create_mv(context, name, query).await?;
+                    create_mv::handle_create_mv(context, name, query, with_options, emit_mode)
+                        .await?;
                 }
                 Statement::Drop(drop_statement) => {
                     drop_table::handle_drop_table(context, drop_statement.object_name).await?;
@@ -379,6 +382,7 @@ impl TestCase {
                 context,
                 Box::new(q),
                 ObjectName(vec!["test".into()]),
+                None,
             )?;
 
             // Only generate stream_plan if it is specified in test case
@@ -0,0 +1,248 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Backup and restore of the meta store.
+//!
+//! A backup is a snapshot of all column families of the meta store, including the catalog, the
+//! fragments and the Hummock versions. Backups are uploaded to the backup storage as
+//! `<directory>/<id>.backup`, and listed in `<directory>/manifest`.
+//!
+//! SSTs referenced by a backup are not protected from being vacuumed, so a backup can only be
+//! restored while its SSTs still exist, which is validated by [`restore_meta`].
+
+mod restore;
+
+use std::sync::Arc;
+
+use itertools::Itertools;
+use prost::Message;
+pub use restore::*;
+use risingwave_common::error::ErrorCode::InternalError;
+use risingwave_common::error::{Result, RwError};
+use risingwave_object_store::object::object_metrics::ObjectStoreMetrics;
+use risingwave_object_store::object::{parse_object_store, ObjectStoreImpl};
+use risingwave_pb::catalog::{Database, Function, Schema, Source, Table};
+use risingwave_pb::hummock::{
+    CompactTaskAssignment, HummockPinnedSnapshot, HummockPinnedVersion, HummockStaleSstables,
+    HummockVersion, SstableIdInfo,
+};
+use risingwave_pb::meta::{MetaBackup, MetaBackupInfo, MetaBackupManifest, MetaStoreEntry};
+use risingwave_pb::user::UserInfo;
+use tokio::sync::Mutex;
+
+use crate::hummock::compaction_group::CompactionGroup;
+use crate::hummock::HUMMOCK_DEFAULT_CF_NAME;
+use crate::model::{MetadataModel, TableFragments, Worker};
+use crate::storage::{MetaStore, Snapshot, DEFAULT_COLUMN_FAMILY};
+
+/// Bumped whenever the layout of the meta store changes incompatibly.
+pub const META_BACKUP_FORMAT_VERSION: u32 = 1;
+
+const MANIFEST_NAME: &str = "manifest";
+
+/// Column families of the meta store, which are all included in a backup.
+fn all_column_families() -> Vec<String> {
+    vec![
+        DEFAULT_COLUMN_FAMILY.to_string(),
+        Worker::cf_name(),
+        UserInfo::cf_name(),
+        Database::cf_name(),
+        Schema::cf_name(),
+        Table::cf_name(),
+        Source::cf_name(),
+        Function::cf_name(),
+        TableFragments::cf_name(),
+        HUMMOCK_DEFAULT_CF_NAME.to_string(),
+        HummockVersion::cf_name(),
+        HummockPinnedVersion::cf_name(),
+        HummockPinnedSnapshot::cf_name(),
+        HummockStaleSstables::cf_name(),
+        SstableIdInfo::cf_name(),
+        CompactionGroup::cf_name(),
+        CompactTaskAssignment::cf_name(),
+    ]
+}
+
+fn backup_error(msg: impl ToString) -> RwError {
+    InternalError(msg.to_string()).into()
+}
+
+/// Object storage where the backups are kept.
+pub struct BackupStorage {
+    store: ObjectStoreImpl,
+    directory: String,
+}
+
+impl BackupStorage {
+    /// Creates a backup storage at `directory` of the object store `url`, e.g. `s3://bucket`.
+    pub async fn new(url: &str, directory: String) -> Self {
+        Self {
+            store: ObjectStoreImpl::new(
+                parse_object_store(url, false).await,
+                Arc::new(ObjectStoreMetrics::unused()),
+            ),
+            directory,
+        }
+    }
+
+    fn manifest_path(&self) -> String {
+        format!("{}/{}", self.directory, MANIFEST_NAME)
+    }
+
+    fn backup_path(&self, id: u64) -> String {
+        format!("{}/{}.backup", self.directory, id)
+    }
+
+    /// Reads the manifest, which is empty if no backup has been taken.
+    pub async fn read_manifest(&self) -> Result<MetaBackupManifest> {
+        let path = self.manifest_path();
+        if self.store.metadata(&path).await.is_err() {
+            return Ok(MetaBackupManifest::default());
+        }
+        let bytes = self.store.read(&path, None).await.map_err(backup_error)?;
+        Ok(MetaBackupManifest::decode(bytes)?)
+    }
+
+    async fn write_manifest(&self, manifest: &MetaBackupManifest) -> Result<()> {
+        self.store
+            .upload(&self.manifest_path(), manifest.encode_to_vec().into())
+            .await
+            .map_err(backup_error)
+    }
+
+    pub async fn read_backup(&self, id: u64) -> Result<MetaBackup> {
+        let bytes = self
+            .store
+            .read(&self.backup_path(id), None)
+            .await
+            .map_err(|e| backup_error(format!("failed to read backup {}: {}", id, e)))?;
+        let backup = MetaBackup::decode(bytes)?;
+        if backup.format_version != META_BACKUP_FORMAT_VERSION {
+            return Err(backup_error(format!(
+                "backup {} is of format version {}, but {} is expected",
+                id, backup.format_version, META_BACKUP_FORMAT_VERSION
+            )));
+        }
+        Ok(backup)
+    }
+
+    async fn write_backup(&self, backup: &MetaBackup) -> Result<()> {
+        let id = backup.get_info()?.id;
+        let path = self.backup_path(id);
+        // Never overwrite a backup, even if the manifest is lost.
+        if self.store.metadata(&path).await.is_ok() {
+            return Err(backup_error(format!("backup {} already exists", id)));
+        }
+        self.store
+            .upload(&path, backup.encode_to_vec().into())
+            .await
+            .map_err(backup_error)
+    }
+}
+
+/// Takes a consistent snapshot of the whole meta store as backup `id`.
+async fn take_backup<S: MetaStore>(meta_store: &S, id: u64) -> Result<MetaBackup> {
+    let snapshot = meta_store.snapshot().await;
+    let mut entries = vec![];
+    for cf in all_column_families() {
+        for (key, value) in snapshot.list_cf_kv(&cf).await? {
+            entries.push(MetaStoreEntry {
+                cf: cf.clone(),
+                key,
+                value,
+            });
+        }
+    }
+
+    // Versions are only persisted after the current one advances, so the latest is the current.
+    let version_cf = HummockVersion::cf_name();
+    let mut current_version: Option<HummockVersion> = None;
+    for entry in entries.iter().filter(|entry| entry.cf == version_cf) {
+        let version = HummockVersion::decode(entry.value.as_slice())?;
+        if current_version.as_ref().map_or(true, |v| v.id < version.id) {
+            current_version = Some(version);
+        }
+    }
+    let current_version =
+        current_version.ok_or_else(|| backup_error("no Hummock version in meta store"))?;
+    let sst_ids = current_version
+        .levels
+        .values()
+        .flat_map(|levels| levels.levels.iter())
+        .flat_map(|level| level.table_infos.iter().map(|sst| sst.id))
+        .sorted()
+        .dedup()
+        .collect();
+
+    Ok(MetaBackup {
+        format_version: META_BACKUP_FORMAT_VERSION,
+        info: Some(MetaBackupInfo {
+            id,
+            hummock_version_id: current_version.id,
+            max_committed_epoch: current_version.max_committed_epoch,
+            sst_ids,
+        }),
+        entries,
+    })
+}
+
+pub type BackupManagerRef<S> = Arc<BackupManager<S>>;
+
+/// `BackupManager` takes backups of the meta store on request.
+pub struct BackupManager<S: MetaStore> {
+    meta_store: Arc<S>,
+    /// `None` if no backup storage is configured.
+    storage: Option<BackupStorage>,
+    /// Serializes backups, which assign ids and update the manifest.
+    lock: Mutex<()>,
+}
+
+impl<S: MetaStore> BackupManager<S> {
+    pub fn new(meta_store: Arc<S>, storage: Option<BackupStorage>) -> Self {
+        Self {
+            meta_store,
+            storage,
+            lock: Mutex::new(()),
+        }
+    }
+
+    /// Takes a backup of the meta store, and returns its info.
+    pub async fn backup(&self) -> Result<MetaBackupInfo> {
+        let storage = self
+            .storage
+            .as_ref()
+            .ok_or_else(|| backup_error("backup storage is not configured"))?;
+        let _guard = self.lock.lock().await;
+        let mut manifest = storage.read_manifest().await?;
+        let id = manifest
+            .backups
+            .iter()
+            .map(|backup| backup.id)
+            .max()
+            .unwrap_or(0)
+            + 1;
+        let backup = take_backup(&*self.meta_store, id).await?;
+        storage.write_backup(&backup).await?;
+        let info = backup.info.unwrap();
+        manifest.backups.push(info.clone());
+        storage.write_manifest(&manifest).await?;
+        tracing::info!(
+            "backup {} is taken at Hummock version {} with {} entries",
+            id,
+            info.hummock_version_id,
+            backup.entries.len()
+        );
+        Ok(info)
+    }
+}
@@ -0,0 +1,256 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use clap::Parser;
+use etcd_client::{Client as EtcdClient, ConnectOptions};
+use risingwave_common::error::Result;
+use risingwave_hummock_sdk::is_remote_sst_id;
+use risingwave_object_store::object::object_metrics::ObjectStoreMetrics;
+use risingwave_object_store::object::{parse_object_store, ObjectStoreImpl};
+
+use super::{all_column_families, backup_error, BackupStorage};
+use crate::storage::{EtcdMetaStore, MetaStore};
+
+/// Options to restore the meta store of a fresh cluster from a backup.
+#[derive(Debug, Clone, Parser)]
+pub struct RestoreOpts {
+    /// Id of the backup to restore.
+    #[clap(long)]
+    pub backup_id: u64,
+
+    /// Object store of the backups, e.g. `s3://bucket`.
+    #[clap(long)]
+    pub backup_storage_url: String,
+
+    #[clap(long, default_value = "backup")]
+    pub backup_storage_directory: String,
+
+    /// Comma-separated endpoints of the etcd of the meta node to restore.
+    #[clap(long)]
+    pub etcd_endpoints: String,
+
+    /// Object store of the SSTs, e.g. `hummock+s3://bucket`.
+    #[clap(long)]
+    pub hummock_url: String,
+
+    /// Directory of the SSTs in the object store.
+    #[clap(long, default_value = "hummock_001")]
+    pub data_directory: String,
+}
+
+/// Restores the meta store of a fresh cluster from a backup, specified by `opts`.
+pub async fn restore(opts: RestoreOpts) -> Result<()> {
+    let client = EtcdClient::connect(
+        opts.etcd_endpoints.split(',').collect::<Vec<_>>(),
+        Some(
+            ConnectOptions::default()
+                .with_keep_alive(Duration::from_secs(3), Duration::from_secs(5)),
+        ),
+    )
+    .await
+    .map_err(|e| backup_error(format!("failed to connect etcd {}", e)))?;
+    let meta_store = EtcdMetaStore::new(client);
+    let backup_storage =
+        BackupStorage::new(&opts.backup_storage_url, opts.backup_storage_directory).await;
+    let hummock_url = opts
+        .hummock_url
+        .strip_prefix("hummock+")
+        .unwrap_or(&opts.hummock_url);
+    let sst_store = ObjectStoreImpl::new(
+        parse_object_store(hummock_url, false).await,
+        Arc::new(ObjectStoreMetrics::unused()),
+    );
+    restore_meta(
+        &meta_store,
+        &backup_storage,
+        opts.backup_id,
+        &sst_store,
+        &opts.data_directory,
+    )
+    .await
+}
+
+/// Restores backup `backup_id` into `meta_store`, which must be empty, i.e. no meta node has
+/// been started on it. SSTs referenced by the backup are validated to exist in `sst_store`
+/// under `sst_directory` before anything is written.
+///
+/// The entries are not written atomically, so a failed restore should be retried on an empty meta
+/// store.
+pub async fn restore_meta<S: MetaStore>(
+    meta_store: &S,
+    backup_storage: &BackupStorage,
+    backup_id: u64,
+    sst_store: &ObjectStoreImpl,
+    sst_directory: &str,
+) -> Result<()> {
+    let backup = backup_storage.read_backup(backup_id).await?;
+    let info = backup.get_info()?;
+
+    for cf in all_column_families() {
+        if !meta_store.list_cf(&cf).await?.is_empty() {
+            return Err(backup_error(format!(
+                "meta store to restore is not empty: {} has entries",
+                cf
+            )));
+        }
+    }
+
+    let mut missing_ssts = vec![];
+    for &sst_id in &info.sst_ids {
+        if !is_remote_sst_id(sst_id) {
+            // Local SSTs are on the disks of compute nodes, which can't be checked here.
+            tracing::warn!("local SST {} is referenced by backup {}", sst_id, backup_id);
+            continue;
+        }
+        for path in [
+            format!("{}/{}.meta", sst_directory, sst_id),
+            format!("{}/{}.data", sst_directory, sst_id),
+        ] {
+            if sst_store.metadata(&path).await.is_err() {
+                missing_ssts.push(path);
+            }
+        }
+    }
+    if !missing_ssts.is_empty() {
+        return Err(backup_error(format!(
+            "backup {} references missing SSTs: {:?}",
+            backup_id, missing_ssts
+        )));
+    }
+
+    for entry in &backup.entries {
+        meta_store
+            .put_cf(&entry.cf, entry.key.clone(), entry.value.clone())
+            .await?;
+    }
+    tracing::info!(
+        "backup {} is restored at Hummock version {} with max committed epoch {}",
+        backup_id,
+        info.hummock_version_id,
+        info.max_committed_epoch
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+    use risingwave_object_store::object::InMemObjectStore;
+    use risingwave_pb::hummock::hummock_version::Levels;
+    use risingwave_pb::hummock::{HummockVersion, Level, SstableInfo};
+    use risingwave_pb::meta::MetaBackupInfo;
+
+    use super::*;
+    use crate::backup::BackupManager;
+    use crate::model::MetadataModel;
+    use crate::storage::MemStore;
+
+    fn new_object_store() -> ObjectStoreImpl {
+        ObjectStoreImpl::new(
+            Box::new(InMemObjectStore::new(false)),
+            Arc::new(ObjectStoreMetrics::unused()),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_backup_and_restore() {
+        let meta_store = Arc::new(MemStore::default());
+        let version = HummockVersion {
+            id: 3,
+            levels: [(
+                2,
+                Levels {
+                    levels: vec![Level {
+                        table_infos: vec![SstableInfo {
+                            id: 1,
+                            ..Default::default()
+                        }],
+                        ..Default::default()
+                    }],
+                },
+            )]
+            .into_iter()
+            .collect(),
+            max_committed_epoch: 100,
+            safe_epoch: 0,
+        };
+        version.insert(&*meta_store).await.unwrap();
+        meta_store
+            .put_cf("cf/worker", b"worker".to_vec(), b"value".to_vec())
+            .await
+            .unwrap();
+
+        let backup_manager = BackupManager::new(
+            meta_store.clone(),
+            Some(BackupStorage {
+                store: new_object_store(),
+                directory: "backup".to_string(),
+            }),
+        );
+        let info = backup_manager.backup().await.unwrap();
+        assert_eq!(
+            info,
+            MetaBackupInfo {
+                id: 1,
+                hummock_version_id: 3,
+                max_committed_epoch: 100,
+                sst_ids: vec![1],
+            }
+        );
+        assert_eq!(backup_manager.backup().await.unwrap().id, 2);
+        let backup_storage = backup_manager.storage.as_ref().unwrap();
+        assert_eq!(
+            backup_storage.read_manifest().await.unwrap().backups.len(),
+            2
+        );
+
+        // The meta store must be empty.
+        let sst_store = new_object_store();
+        assert!(
+            restore_meta(&*meta_store, backup_storage, 1, &sst_store, "hummock")
+                .await
+                .is_err()
+        );
+
+        // The SST is missing.
+        let restored_store = MemStore::default();
+        assert!(
+            restore_meta(&restored_store, backup_storage, 1, &sst_store, "hummock")
+                .await
+                .is_err()
+        );
+        assert!(HummockVersion::list(&restored_store)
+            .await
+            .unwrap()
+            .is_empty());
+
+        for path in ["hummock/1.meta", "hummock/1.data"] {
+            sst_store.upload(path, Bytes::from("sst")).await.unwrap();
+        }
+        restore_meta(&restored_store, backup_storage, 1, &sst_store, "hummock")
+            .await
+            .unwrap();
+        assert_eq!(
+            HummockVersion::list(&restored_store).await.unwrap(),
+            vec![version]
+        );
+        assert_eq!(
+            restored_store.get_cf("cf/worker", b"worker").await.unwrap(),
+            b"value".to_vec()
+        );
+    }
+}
@@ -12,23 +12,26 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 
-use futures::future::try_join_all;
 use risingwave_common::catalog::TableId;
 use risingwave_common::error::{Result, RwError, ToRwResult};
 use risingwave_common::util::epoch::Epoch;
 use risingwave_pb::common::ActorInfo;
 use risingwave_pb::data::barrier::Mutation;
-use risingwave_pb::data::{AddMutation, DispatcherMutation, NothingMutation, StopMutation};
+use risingwave_pb::data::{
+    AddColumnsMutation, AddMutation, AddedColumn, DispatcherMutation, NothingMutation,
+    PauseMutation, RescheduleMutation, ResumeMutation, StopMutation,
+};
 use risingwave_pb::stream_service::DropActorsRequest;
 use risingwave_rpc_client::StreamClientPoolRef;
 use uuid::Uuid;
 
 use super::info::BarrierActorInfo;
-use crate::model::{ActorId, DispatcherId, TableFragments};
+use crate::manager::SourceId;
+use crate::model::{ActorId, DispatcherId, FragmentId, TableFragments};
 use crate::storage::MetaStore;
-use crate::stream::FragmentManagerRef;
+use crate::stream::{join_node_rpcs, FragmentManagerRef};
 
 /// [`Command`] is the action of [`crate::barrier::GlobalBarrierManager`]. For different commands,
 /// we'll build different barriers to send, and may do different stuffs after the barrier is
@@ -61,6 +64,32 @@ pub enum Command {
         table_sink_map: HashMap<TableId, Vec<ActorId>>,
         dispatches: HashMap<(ActorId, DispatcherId), Vec<ActorInfo>>,
     },
+
+    /// `RescheduleVnodes` command generates a `Reschedule` barrier, which reassigns the vnodes of
+    /// each fragment in `vnode_mappings` to its actors by the mapping, i.e., the owner actor of
+    /// each vnode. Fragments connected by no-shuffle exchanges must be rescheduled by the same
+    /// barrier, so that the paired actors never own different vnodes.
+    ///
+    /// The actors flush the states of their old vnodes at the previous epoch, and those owning
+    /// new vnodes wait for the previous epoch to be committed before reading them. Upstream hash
+    /// dispatchers route the data after the barrier by the new mapping.
+    /// After the barrier is collected, the new vnodes are persisted in the table fragments.
+    RescheduleVnodes {
+        table_id: TableId,
+        vnode_mappings: BTreeMap<FragmentId, Vec<ActorId>>,
+    },
+
+    /// `AddColumns` command generates an `AddColumns` barrier, which appends `columns` to the
+    /// source `source_id` and the materialized view `table_id` of a table.
+    ///
+    /// The source actors output the new columns after the barrier, filled with nulls for the rows
+    /// inserted before it, and the materialize actors store them from then on.
+    /// After the barrier is collected, the new columns are persisted in the table fragments.
+    AddColumns {
+        source_id: SourceId,
+        table_id: TableId,
+        columns: Vec<AddedColumn>,
+    },
 }
 
 impl Command {
@@ -68,6 +97,23 @@ impl Command {
         Self::Plain(Mutation::Nothing(NothingMutation {}))
     }
 
+    /// Stops all sources from reading new data.
+    pub fn pause() -> Self {
+        Self::Plain(Mutation::Pause(PauseMutation {}))
+    }
+
+    /// Resumes the sources stopped by [`Command::pause`].
+    pub fn resume() -> Self {
+        Self::Plain(Mutation::Resume(ResumeMutation {}))
+    }
+
+    /// Whether the barrier of this command must be a checkpoint. Only barriers of plain `Nothing`
+    /// mutations can skip syncing the states, since the changes made by other commands are
+    /// persisted once they're collected, and must not be rolled back by recovery.
+    pub fn need_checkpoint(&self) -> bool {
+        !matches!(self, Command::Plain(Mutation::Nothing(_)))
+    }
+
     pub fn creating_table_id(&self) -> Option<TableId> {
         match self {
             Command::CreateMaterializedView {
@@ -92,6 +138,10 @@ pub struct CommandContext<'a, S> {
     pub prev_epoch: &'a Epoch,
     pub curr_epoch: &'a Epoch,
 
+    /// Whether the barrier is a checkpoint, on which the states since the last checkpoint are
+    /// synced and committed to Hummock.
+    pub checkpoint: bool,
+
     command: Command,
 }
 
@@ -103,6 +153,7 @@ impl<'a, S> CommandContext<'a, S> {
         prev_epoch: &'a Epoch,
         curr_epoch: &'a Epoch,
         command: Command,
+        checkpoint: bool,
     ) -> Self {
         Self {
             fragment_manager,
@@ -110,6 +161,7 @@ impl<'a, S> CommandContext<'a, S> {
             info,
             prev_epoch,
             curr_epoch,
+            checkpoint,
             command,
         }
     }
@@ -142,6 +194,46 @@ where
                     .collect();
                 Mutation::Add(AddMutation { mutations })
             }
+
+            Command::RescheduleVnodes {
+                table_id,
+                vnode_mappings,
+            } => {
+                let mut table_fragments = self
+                    .fragment_manager
+                    .select_table_fragments_by_table_id(table_id)
+                    .await?;
+                let mut mutation = RescheduleMutation::default();
+                for (fragment_id, vnode_mapping) in vnode_mappings {
+                    let fragment_mutation =
+                        table_fragments.reschedule_vnodes(*fragment_id, vnode_mapping)?;
+                    mutation
+                        .hash_mappings
+                        .extend(fragment_mutation.hash_mappings);
+                    mutation
+                        .vnode_bitmaps
+                        .extend(fragment_mutation.vnode_bitmaps);
+                }
+                Mutation::Reschedule(mutation)
+            }
+
+            Command::AddColumns {
+                source_id,
+                table_id,
+                columns,
+            } => {
+                let actor_ids = self
+                    .fragment_manager
+                    .select_table_fragments_by_table_id(table_id)
+                    .await?
+                    .source_actor_ids();
+                Mutation::AddColumns(AddColumnsMutation {
+                    source_id: *source_id,
+                    table_id: table_id.table_id,
+                    columns: columns.clone(),
+                    actor_ids,
+                })
+            }
         };
 
         Ok(mutation)
@@ -172,7 +264,7 @@ where
                     let node = self.info.node_map.get(node_id).unwrap();
                     let request_id = Uuid::new_v4().to_string();
 
-                    async move {
+                    let future = async move {
                         let mut client = self.client_pool.get(node).await?;
                         let request = DropActorsRequest {
                             request_id,
@@ -181,10 +273,11 @@ where
                         client.drop_actors(request).await.to_rw_result()?;
 
                         Ok::<_, RwError>(())
-                    }
+                    };
+                    (*node_id, future)
                 });
 
-                try_join_all(futures).await?;
+                join_node_rpcs("drop_actors", futures).await?;
 
                 // Drop fragment info in meta store.
                 self.fragment_manager.drop_table_fragments(table_id).await?;
@@ -219,6 +312,25 @@ where
                     )
                     .await?;
             }
+
+            Command::RescheduleVnodes {
+                table_id,
+                vnode_mappings,
+            } => {
+                self.fragment_manager
+                    .reschedule_vnodes(table_id, vnode_mappings)
+                    .await?;
+            }
+
+            Command::AddColumns {
+                source_id,
+                table_id,
+                columns,
+            } => {
+                self.fragment_manager
+                    .add_columns(table_id, *source_id, columns)
+                    .await?;
+            }
         }
 
         Ok(())
@@ -0,0 +1,159 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::{HashMap, VecDeque};
+use std::time::Duration;
+
+use risingwave_common::util::epoch::Epoch;
+use risingwave_pb::stream_service::InjectBarrierResponse;
+
+/// The number of recent barriers kept in [`BarrierHistory`].
+const BARRIER_HISTORY_CAPACITY: usize = 256;
+
+/// A barrier that has been collected from all compute nodes.
+#[derive(Debug, Clone)]
+pub struct BarrierRecord {
+    pub prev_epoch: u64,
+    pub curr_epoch: u64,
+
+    /// The time from injecting the barrier to committing its epoch.
+    pub latency: Duration,
+
+    /// Rows read from each source in the epoch.
+    pub source_rows: HashMap<u32, u64>,
+}
+
+impl BarrierRecord {
+    pub fn new(
+        prev_epoch: u64,
+        curr_epoch: u64,
+        latency: Duration,
+        responses: &[InjectBarrierResponse],
+    ) -> Self {
+        let mut source_rows = HashMap::new();
+        for source in responses.iter().flat_map(|r| r.source_rows.iter()) {
+            *source_rows.entry(source.source_id).or_default() += source.rows;
+        }
+        Self {
+            prev_epoch,
+            curr_epoch,
+            latency,
+            source_rows,
+        }
+    }
+}
+
+/// Recent barriers collected by the barrier manager, for the dashboard to show the barrier latency
+/// and the throughput of sources.
+#[derive(Debug)]
+pub struct BarrierHistory {
+    records: VecDeque<BarrierRecord>,
+    capacity: usize,
+}
+
+impl Default for BarrierHistory {
+    fn default() -> Self {
+        Self::with_capacity(BARRIER_HISTORY_CAPACITY)
+    }
+}
+
+impl BarrierHistory {
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            records: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    pub fn push(&mut self, record: BarrierRecord) {
+        if self.records.len() == self.capacity {
+            self.records.pop_front();
+        }
+        self.records.push_back(record);
+    }
+
+    /// Records from the oldest to the newest.
+    pub fn records(&self) -> impl Iterator<Item = &BarrierRecord> {
+        self.records.iter()
+    }
+
+    pub fn latest(&self) -> Option<&BarrierRecord> {
+        self.records.back()
+    }
+
+    /// Rows per second read from each source, averaged over the epochs in the history.
+    pub fn source_throughput(&self) -> HashMap<u32, f64> {
+        let (first, last) = match (self.records.front(), self.records.back()) {
+            (Some(first), Some(last)) => (first, last),
+            _ => return HashMap::new(),
+        };
+        let window_ms = Epoch::from(last.curr_epoch)
+            .physical_time()
+            .saturating_sub(Epoch::from(first.prev_epoch).physical_time());
+        if window_ms == 0 {
+            return HashMap::new();
+        }
+
+        let mut source_rows: HashMap<u32, u64> = HashMap::new();
+        for record in &self.records {
+            for (source_id, rows) in &record.source_rows {
+                *source_rows.entry(*source_id).or_default() += rows;
+            }
+        }
+        source_rows
+            .into_iter()
+            .map(|(source_id, rows)| (source_id, rows as f64 * 1000.0 / window_ms as f64))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn epoch(physical_ms: u64) -> u64 {
+        physical_ms << 16
+    }
+
+    fn record(prev_ms: u64, curr_ms: u64, source_rows: &[(u32, u64)]) -> BarrierRecord {
+        BarrierRecord {
+            prev_epoch: epoch(prev_ms),
+            curr_epoch: epoch(curr_ms),
+            latency: Duration::from_millis(10),
+            source_rows: source_rows.iter().copied().collect(),
+        }
+    }
+
+    #[test]
+    fn test_barrier_history() {
+        let mut history = BarrierHistory::with_capacity(2);
+        assert!(history.source_throughput().is_empty());
+
+        history.push(record(0, 1000, &[(1, 100)]));
+        history.push(record(1000, 2000, &[(1, 300), (2, 50)]));
+        assert_eq!(
+            history.source_throughput(),
+            HashMap::from([(1, 200.0), (2, 25.0)])
+        );
+
+        // The oldest record is evicted.
+        history.push(record(2000, 3000, &[(2, 150)]));
+        assert_eq!(history.records().count(), 2);
+        assert_eq!(history.latest().unwrap().curr_epoch, epoch(3000));
+        assert_eq!(
+            history.source_throughput(),
+            HashMap::from([(1, 150.0), (2, 100.0)])
+        );
+    }
+}
@@ -14,8 +14,9 @@
 
 use std::collections::VecDeque;
 use std::iter::once;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use futures::future::try_join_all;
 use itertools::Itertools;
@@ -26,19 +27,21 @@ use risingwave_hummock_sdk::HummockEpoch;
 use risingwave_pb::common::worker_node::State::Running;
 use risingwave_pb::common::WorkerType;
 use risingwave_pb::data::Barrier;
+use risingwave_pb::meta::event_log::EventType;
 use risingwave_pb::stream_service::{InjectBarrierRequest, InjectBarrierResponse};
 use smallvec::SmallVec;
 use tokio::sync::oneshot::{Receiver, Sender};
-use tokio::sync::{oneshot, watch, RwLock};
+use tokio::sync::{oneshot, watch, Notify, RwLock};
 use tokio::task::JoinHandle;
 use uuid::Uuid;
 
 pub use self::command::Command;
 use self::command::CommandContext;
+pub use self::history::{BarrierHistory, BarrierRecord};
 use self::info::BarrierActorInfo;
 use self::notifier::Notifier;
 use self::progress::CreateMviewProgressTracker;
-use crate::cluster::{ClusterManagerRef, META_NODE_ID};
+use crate::cluster::{ClusterManagerRef, WorkerId, META_NODE_ID};
 use crate::hummock::HummockManagerRef;
 use crate::manager::{CatalogManagerRef, MetaSrvEnv};
 use crate::model::BarrierManagerState;
@@ -47,6 +50,7 @@ use crate::storage::MetaStore;
 use crate::stream::FragmentManagerRef;
 
 mod command;
+mod history;
 mod info;
 mod notifier;
 mod progress;
@@ -80,6 +84,11 @@ impl ScheduledBarriers {
             .unwrap_or_else(|| (Command::checkpoint(), Default::default()))
     }
 
+    /// The number of scheduled barriers in the buffer.
+    async fn len(&self) -> usize {
+        self.buffer.read().await.len()
+    }
+
     /// Wait for at least one scheduled barrier in the buffer.
     async fn wait_one(&self) {
         let buffer = self.buffer.read().await;
@@ -160,6 +169,17 @@ pub struct GlobalBarrierManager<S: MetaStore> {
     metrics: Arc<MetaMetrics>,
 
     env: MetaSrvEnv<S>,
+
+    /// Recent collected barriers, shown in the dashboard.
+    history: parking_lot::RwLock<BarrierHistory>,
+
+    /// Notified when a recovery is triggered manually.
+    recovery_requested: Notify,
+
+    /// The epoch of the data being written into the stream graph, which is committed by the next
+    /// collected checkpoint. It's the max committed epoch if there're no actors to send barriers
+    /// to.
+    current_epoch: AtomicU64,
 }
 
 impl<S> GlobalBarrierManager<S>
@@ -193,9 +213,23 @@ where
             hummock_manager,
             metrics,
             env,
+            history: Default::default(),
+            recovery_requested: Notify::new(),
+            current_epoch: AtomicU64::new(INVALID_EPOCH),
         }
     }
 
+    /// Returns the recent collected barriers.
+    pub fn history(&self) -> parking_lot::RwLockReadGuard<'_, BarrierHistory> {
+        self.history.read()
+    }
+
+    /// Returns the epoch of the data being written into the stream graph. Data written before
+    /// calling this are visible to batch queries once this epoch is committed to Hummock.
+    pub fn current_epoch(&self) -> HummockEpoch {
+        self.current_epoch.load(Ordering::Relaxed)
+    }
+
     pub async fn start(barrier_manager: BarrierManagerRef<S>) -> (JoinHandle<()>, Sender<()>) {
         let (shutdown_tx, shutdown_rx) = tokio::sync::oneshot::channel();
         let join_handle = tokio::spawn(async move {
@@ -217,6 +251,10 @@ where
             assert!(new_epoch > state.prev_epoch);
             state.prev_epoch = new_epoch;
 
+            self.env
+                .event_log_manager()
+                .log(EventType::Recovery, "recover on meta node startup")
+                .await;
             let (new_epoch, actors_to_track, create_mview_progress) =
                 self.recovery(state.prev_epoch).await;
             tracker.add(new_epoch, actors_to_track, vec![]);
@@ -224,12 +262,26 @@ where
                 tracker.update(progress);
             }
             state.prev_epoch = new_epoch;
+            self.current_epoch.store(new_epoch.0, Ordering::Relaxed);
             state.update(self.env.meta_store()).await.unwrap();
         }
 
         let mut min_interval = tokio::time::interval(self.interval);
         min_interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        let dynamic_config = self.env.opts.dynamic_config.clone();
+        let mut interval_ms = dynamic_config.borrow().checkpoint_interval_ms;
+        // The number of barriers collected since the last checkpoint.
+        let mut uncheckpointed_barriers = 0;
         loop {
+            // Follow the changes of the checkpoint interval in the config file.
+            let new_interval_ms = dynamic_config.borrow().checkpoint_interval_ms;
+            if new_interval_ms != interval_ms {
+                tracing::info!("checkpoint interval is changed to {}ms", new_interval_ms);
+                interval_ms = new_interval_ms;
+                min_interval = tokio::time::interval(Duration::from_millis(interval_ms as u64));
+                min_interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            }
+            let mut recovery_requested = false;
             tokio::select! {
                 biased;
                 // Shutdown
@@ -237,25 +289,68 @@ where
                     tracing::info!("Barrier manager is shutting down");
                     return;
                 }
+                // Recovery triggered manually.
+                _ = self.recovery_requested.notified() => {
+                    recovery_requested = true;
+                }
                 // there's barrier scheduled.
                 _ = self.scheduled_barriers.wait_one() => {}
                 // Wait for the minimal interval,
                 _ = min_interval.tick() => {},
             }
+            if recovery_requested {
+                self.env
+                    .event_log_manager()
+                    .log(EventType::Recovery, "recover on request")
+                    .await;
+                let (new_epoch, actors_to_track, create_mview_progress) =
+                    self.recovery(state.prev_epoch.next()).await;
+                tracker = CreateMviewProgressTracker::default();
+                tracker.add(new_epoch, actors_to_track, vec![]);
+                for progress in create_mview_progress {
+                    tracker.update(progress);
+                }
+                uncheckpointed_barriers = 0;
+                state.prev_epoch = new_epoch;
+                self.current_epoch.store(new_epoch.0, Ordering::Relaxed);
+                state.update(self.env.meta_store()).await.unwrap();
+                continue;
+            }
             // Get a barrier to send.
             let (command, notifiers) = self.scheduled_barriers.pop_or_default().await;
+            self.metrics
+                .scheduled_barrier_num
+                .set(self.scheduled_barriers.len().await as i64);
             let info = self.resolve_actor_info(command.creating_table_id()).await;
             // When there's no actors exist in the cluster, we don't need to send the barrier. This
             // is an advance optimization. Besides if another barrier comes immediately,
             // it may send a same epoch and fail the epoch check.
             if info.nothing_to_do() {
+                // Nothing is committed, so the flushed data are visible since the latest committed
+                // epoch.
+                let committed_epoch = self
+                    .hummock_manager
+                    .get_current_version()
+                    .await
+                    .max_committed_epoch;
+                self.current_epoch.store(committed_epoch, Ordering::Relaxed);
                 let mut notifiers = notifiers;
                 notifiers.iter_mut().for_each(Notifier::notify_to_send);
-                notifiers.iter_mut().for_each(Notifier::notify_collected);
+                notifiers
+                    .iter_mut()
+                    .for_each(|n| n.notify_collected(committed_epoch));
                 continue;
             }
             let new_epoch = state.prev_epoch.next();
             assert!(new_epoch > state.prev_epoch);
+            // The data written from now on are committed by the checkpoint after this barrier.
+            self.current_epoch.store(new_epoch.0, Ordering::Relaxed);
+            // Notifiers wait for the data before the barrier to be committed, so the barrier
+            // must be a checkpoint if there're any.
+            let checkpoint_frequency = dynamic_config.borrow().checkpoint_frequency.max(1);
+            let checkpoint = command.need_checkpoint()
+                || !notifiers.is_empty()
+                || uncheckpointed_barriers + 1 >= checkpoint_frequency;
             let command_ctx = CommandContext::new(
                 self.fragment_manager.clone(),
                 self.env.stream_client_pool_ref(),
@@ -263,6 +358,7 @@ where
                 &state.prev_epoch,
                 &new_epoch,
                 command,
+                checkpoint,
             );
 
             let mut notifiers = notifiers;
@@ -270,7 +366,9 @@ where
             match self.run_inner(&command_ctx).await {
                 Ok(responses) => {
                     // Notify about collected first.
-                    notifiers.iter_mut().for_each(Notifier::notify_collected);
+                    notifiers
+                        .iter_mut()
+                        .for_each(|n| n.notify_collected(command_ctx.prev_epoch.0));
 
                     // Then try to finish the barrier for Create MVs.
                     let actors_to_track = command_ctx.actors_to_track();
@@ -279,6 +377,11 @@ where
                         tracker.update(progress);
                     }
 
+                    uncheckpointed_barriers = if checkpoint {
+                        0
+                    } else {
+                        uncheckpointed_barriers + 1
+                    };
                     state.prev_epoch = new_epoch;
                 }
                 Err(e) => {
@@ -287,6 +390,16 @@ where
                         .for_each(|notifier| notifier.notify_collection_failed(e.clone()));
                     if self.enable_recovery {
                         // If failed, enter recovery mode.
+                        self.env
+                            .event_log_manager()
+                            .log(
+                                EventType::Recovery,
+                                format!(
+                                    "recover from failed barrier of epoch {}: {}",
+                                    new_epoch.0, e
+                                ),
+                            )
+                            .await;
                         let (new_epoch, actors_to_track, create_mview_progress) =
                             self.recovery(new_epoch).await;
                         tracker = CreateMviewProgressTracker::default(); // Reset progress tracker
@@ -294,8 +407,10 @@ where
                         for progress in create_mview_progress {
                             tracker.update(progress);
                         }
+                        uncheckpointed_barriers = 0;
 
                         state.prev_epoch = new_epoch;
+                        self.current_epoch.store(new_epoch.0, Ordering::Relaxed);
                     } else {
                         panic!("failed to execute barrier: {:?}", e);
                     }
@@ -315,8 +430,9 @@ where
 
         // Wait for all barriers collected
         let result = self.inject_barrier(command_context).await;
-        // Commit this epoch to Hummock
-        if command_context.prev_epoch.0 != INVALID_EPOCH {
+        // Commit this epoch to Hummock if it's a checkpoint. The states of the barriers since the
+        // last checkpoint are synced along with it, and committed by this epoch altogether.
+        if command_context.checkpoint && command_context.prev_epoch.0 != INVALID_EPOCH {
             match &result {
                 Ok(resps) => {
                     // We must ensure all epochs are committed in ascending order, because
@@ -332,8 +448,8 @@ where
                 }
                 Err(err) => {
                     tracing::warn!(
-                        "Failed to commit epoch {}: {:#?}",
-                        command_context.prev_epoch.0,
+                        epoch = command_context.prev_epoch.0,
+                        "Failed to commit epoch: {:#?}",
                         err
                     );
                 }
@@ -341,7 +457,16 @@ where
         }
         let responses = result?;
 
-        timer.observe_duration();
+        let latency = Duration::from_secs_f64(timer.stop_and_record());
+        self.metrics
+            .barrier_epoch
+            .set(command_context.curr_epoch.0 as i64);
+        self.history.write().push(BarrierRecord::new(
+            command_context.prev_epoch.0,
+            command_context.curr_epoch.0,
+            latency,
+            &responses,
+        ));
         command_context.post_collect().await?; // do some post stuffs
 
         Ok(responses)
@@ -354,6 +479,7 @@ where
     ) -> Result<Vec<InjectBarrierResponse>> {
         let mutation = command_context.to_mutation().await?;
         let info = command_context.info;
+        let start = Instant::now();
 
         let collect_futures = info.node_map.iter().filter_map(|(node_id, node)| {
             let actor_ids_to_send = info.actor_ids_to_send(node_id).collect_vec();
@@ -384,24 +510,75 @@ where
                         barrier: Some(barrier),
                         actor_ids_to_send,
                         actor_ids_to_collect,
+                        checkpoint: command_context.checkpoint,
                     };
                     tracing::trace!(
                         target: "events::meta::barrier::inject_barrier",
+                        epoch = command_context.curr_epoch.0,
+                        worker_id = *node_id,
                         "inject barrier request: {:?}", request
                     );
 
                     // This RPC returns only if this worker node has collected this barrier.
-                    client
+                    let response = client
                         .inject_barrier(request)
                         .await
                         .map(tonic::Response::<_>::into_inner)
-                        .to_rw_result()
+                        .to_rw_result()?;
+                    Ok::<_, RwError>((*node_id, start.elapsed(), response))
                 }
                 .into()
             }
         });
 
-        try_join_all(collect_futures).await
+        // Unlike other RPCs sent to all nodes, this fails as soon as any node fails, since the
+        // other nodes may never collect the barrier then.
+        let collected = try_join_all(collect_futures).await?;
+
+        let threshold_ms = self
+            .env
+            .opts
+            .dynamic_config
+            .borrow()
+            .slow_barrier_threshold_ms;
+        if threshold_ms > 0 && start.elapsed() >= Duration::from_millis(threshold_ms) {
+            self.log_slow_barrier(command_context, &collected).await;
+        }
+        Ok(collected
+            .into_iter()
+            .map(|(_, _, response)| response)
+            .collect())
+    }
+
+    /// Logs the worker node collecting the barrier last, along with the actor on it collecting the
+    /// barrier last, which is likely the one slowing down the barrier.
+    async fn log_slow_barrier<'a>(
+        &self,
+        command_context: &CommandContext<'a, S>,
+        collected: &[(WorkerId, Duration, InjectBarrierResponse)],
+    ) {
+        let slowest = collected.iter().max_by_key(|(_, elapsed, _)| *elapsed);
+        let Some((worker_id, elapsed, response)) = slowest else {
+            return;
+        };
+        let actor_id = response.last_collected_actor_id;
+        let (table_id, fragment_id) = self
+            .fragment_manager
+            .locate_actor(actor_id)
+            .await
+            .map_or((None, None), |(table_id, fragment_id)| {
+                (Some(table_id.table_id()), Some(fragment_id))
+            });
+        tracing::warn!(
+            epoch = command_context.curr_epoch.0,
+            worker_id,
+            actor_id,
+            ?fragment_id,
+            ?table_id,
+            "barrier collected in {:?}, actor {} collected it last",
+            elapsed,
+            actor_id
+        );
     }
 
     /// Resolve actor information from cluster and fragment manager.
@@ -485,9 +662,21 @@ where
         Ok(())
     }
 
-    /// Wait for the next barrier to collect. Note that the barrier flowing in our stream graph is
-    /// ignored, if exists.
-    pub async fn wait_for_next_barrier_to_collect(&self) -> Result<()> {
+    /// Trigger a recovery of the cluster, which rebuilds all actors from the latest committed
+    /// epoch. Returns once the recovery is scheduled.
+    pub fn trigger_recovery(&self) -> Result<()> {
+        if !self.enable_recovery {
+            return Err(RwError::from(ErrorCode::InternalError(
+                "recovery is disabled".to_string(),
+            )));
+        }
+        self.recovery_requested.notify_one();
+        Ok(())
+    }
+
+    /// Wait for the next barrier to collect, and return the epoch committed to Hummock by it. Note
+    /// that the barrier flowing in our stream graph is ignored, if exists.
+    pub async fn wait_for_next_barrier_to_collect(&self) -> Result<HummockEpoch> {
         let (tx, rx) = oneshot::channel();
         let notifier = Notifier {
             collected: Some(tx),
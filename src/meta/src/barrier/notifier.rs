@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use risingwave_common::error::{Result, RwError};
+use risingwave_hummock_sdk::HummockEpoch;
 use tokio::sync::oneshot;
 
 /// Used for notifying the status of a scheduled command/barrier.
@@ -21,8 +22,9 @@ pub(super) struct Notifier {
     /// Get notified when scheduled barrier is about to send.
     pub to_send: Option<oneshot::Sender<()>>,
 
-    /// Get notified when scheduled barrier is collected or failed.
-    pub collected: Option<oneshot::Sender<Result<()>>>,
+    /// Get notified when scheduled barrier is collected or failed. The epoch committed to Hummock
+    /// on collection is sent.
+    pub collected: Option<oneshot::Sender<Result<HummockEpoch>>>,
 
     /// Get notified when scheduled barrier is finished.
     pub finished: Option<oneshot::Sender<()>>,
@@ -36,10 +38,10 @@ impl Notifier {
         }
     }
 
-    /// Notify when we have collected a barrier from all actors.
-    pub fn notify_collected(&mut self) {
+    /// Notify when we have collected a barrier from all actors, and committed `committed_epoch`.
+    pub fn notify_collected(&mut self, committed_epoch: HummockEpoch) {
         if let Some(tx) = self.collected.take() {
-            tx.send(Ok(())).ok();
+            tx.send(Ok(committed_epoch)).ok();
         }
     }
 
@@ -137,7 +137,7 @@ impl CreateMviewProgressTracker {
                 progress.update(actor, new_state);
 
                 if progress.is_done() {
-                    tracing::debug!("all actors done for creating mview with epoch {}!", epoch);
+                    tracing::debug!(epoch = epoch.0, "all actors done for creating mview");
 
                     // Clean-up the mapping from actors to DDL epoch.
                     for actor in o.get().0.actors() {
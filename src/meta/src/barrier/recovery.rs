@@ -12,16 +12,18 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::iter::Map;
 use std::time::Duration;
 
 use futures::future::try_join_all;
+use itertools::Itertools;
 use log::{debug, error};
 use risingwave_common::error::{ErrorCode, Result, RwError, ToRwResult};
 use risingwave_common::util::epoch::Epoch;
-use risingwave_pb::common::ActorInfo;
+use risingwave_pb::common::{ActorInfo, ParallelUnit};
 use risingwave_pb::data::Epoch as ProstEpoch;
+use risingwave_pb::meta::event_log::EventType;
 use risingwave_pb::stream_service::inject_barrier_response::CreateMviewProgress;
 use risingwave_pb::stream_service::{
     BroadcastActorInfoTableRequest, BuildActorsRequest, ForceStopActorsRequest, SyncSourcesRequest,
@@ -33,8 +35,10 @@ use uuid::Uuid;
 use crate::barrier::command::CommandContext;
 use crate::barrier::info::BarrierActorInfo;
 use crate::barrier::{Command, GlobalBarrierManager};
+use crate::cluster::ParallelUnitId;
 use crate::model::ActorId;
 use crate::storage::MetaStore;
+use crate::stream::join_node_rpcs;
 
 pub type RecoveryResult = (Epoch, HashSet<ActorId>, Vec<CreateMviewProgress>);
 
@@ -61,8 +65,16 @@ where
         self.scheduled_barriers.abort().await;
 
         debug!("recovery start!");
+        self.metrics.recovery_count.inc();
+        let timer = self.metrics.recovery_latency.start_timer();
         let retry_strategy = Self::get_retry_strategy();
         let (new_epoch, responses) = tokio_retry::Retry::spawn(retry_strategy, || async {
+            // Move the actors off the workers that have left the cluster.
+            if let Err(err) = self.migrate_actors().await {
+                error!("migrate_actors failed: {}", err);
+                return Err(err);
+            }
+
             let info = self.resolve_actor_info(None).await;
             let mut new_epoch = prev_epoch.next();
 
@@ -96,6 +108,7 @@ where
                 &prev_epoch,
                 &new_epoch,
                 Command::checkpoint(),
+                false,
             );
 
             match self.inject_barrier(&command_ctx).await {
@@ -114,6 +127,7 @@ where
         })
         .await
         .expect("Retry until recovery success.");
+        timer.observe_duration();
         debug!("recovery success");
 
         return (
@@ -126,17 +140,105 @@ where
         );
     }
 
+    /// Moves the actors on the parallel units of the workers that have left the cluster to the
+    /// parallel units of the remaining workers, otherwise recovery would wait for the workers
+    /// forever. All actors of a parallel unit move to the same parallel unit, so that the actors
+    /// required to be on the same worker node as their upstreams stay with them.
+    ///
+    /// Each parallel unit left is replaced by the remaining one of the same type with the fewest
+    /// actors. If the cluster has shrunk below the parallelism of a fragment, several actors of the
+    /// fragment end up sharing a parallel unit, which can be resolved by scaling in the table.
+    async fn migrate_actors(&self) -> Result<()> {
+        let parallel_units = self.cluster_manager.list_parallel_units(None).await;
+        let mut actor_counts: HashMap<ParallelUnitId, usize> = parallel_units
+            .iter()
+            .map(|parallel_unit| (parallel_unit.id, 0))
+            .collect();
+        let mut parallel_units_left: BTreeMap<ParallelUnitId, (ParallelUnit, usize)> =
+            BTreeMap::new();
+        for table_fragments in self.fragment_manager.list_table_fragments().await? {
+            for parallel_unit in table_fragments.actor_parallel_units() {
+                match actor_counts.get_mut(&parallel_unit.id) {
+                    Some(count) => *count += 1,
+                    None => {
+                        parallel_units_left
+                            .entry(parallel_unit.id)
+                            .or_insert_with(|| (parallel_unit.clone(), 0))
+                            .1 += 1;
+                    }
+                }
+            }
+        }
+        if parallel_units_left.is_empty() {
+            return Ok(());
+        }
+        if parallel_units.is_empty() {
+            return Err(RwError::from(ErrorCode::InternalError(format!(
+                "no compute node to migrate the actors on parallel units {:?} to, add compute nodes to the cluster to recover",
+                parallel_units_left.keys().collect_vec()
+            ))));
+        }
+
+        let mut migration = HashMap::new();
+        for (parallel_unit_id, (parallel_unit, count)) in parallel_units_left {
+            let same_type = parallel_units
+                .iter()
+                .filter(|p| p.r#type == parallel_unit.r#type)
+                .collect_vec();
+            let candidates = if same_type.is_empty() {
+                parallel_units.iter().collect_vec()
+            } else {
+                same_type
+            };
+            let target = candidates
+                .into_iter()
+                .min_by_key(|p| (actor_counts[&p.id], p.id))
+                .unwrap();
+            *actor_counts.get_mut(&target.id).unwrap() += count;
+            migration.insert(parallel_unit_id, target.clone());
+        }
+
+        let migrated_tables = self.fragment_manager.migrate_actors(&migration).await?;
+        self.env
+            .event_log_manager()
+            .log(
+                EventType::Scaling,
+                format!(
+                    "migrate actors from parallel units of removed workers: {:?}",
+                    migration
+                        .iter()
+                        .map(|(from, to)| (*from, to.id))
+                        .sorted()
+                        .collect_vec()
+                ),
+            )
+            .await;
+        for table_fragments in migrated_tables {
+            let table_id = table_fragments.table_id();
+            let crowded_fragments = table_fragments.fragments_sharing_parallel_units();
+            if !crowded_fragments.is_empty() {
+                tracing::warn!(
+                    table_id = table_id.table_id(),
+                    fragment_ids = ?crowded_fragments,
+                    "fragments run several actors on one parallel unit after migration, consider `ALTER MATERIALIZED VIEW .. SET PARALLELISM` to scale them in"
+                );
+            }
+        }
+
+        Ok(())
+    }
+
     /// Sync all sources in compute nodes, the local source manager in compute nodes may be dirty
     /// already.
     async fn sync_sources(&self, info: &BarrierActorInfo) -> Result<()> {
         let catalog_guard = self.catalog_manager.get_catalog_core_guard().await;
         let sources = catalog_guard.list_sources().await?;
 
-        let futures = info.node_map.iter().map(|(_, node)| {
+        let futures = info.node_map.iter().map(|(node_id, node)| {
             let request = SyncSourcesRequest {
                 sources: sources.clone(),
             };
-            async move {
+            let future = async move {
                 let client = &self.env.stream_client_pool().get(node).await?;
                 client
                     .to_owned()
@@ -145,10 +247,11 @@ where
                     .to_rw_result()?;
 
                 Ok::<_, RwError>(())
-            }
+            };
+            (*node_id, future)
         });
 
-        try_join_all(futures).await?;
+        join_node_rpcs("sync_sources", futures).await?;
 
         Ok(())
     }
@@ -173,63 +276,79 @@ where
             }));
         }
 
-        let node_actors = self.fragment_manager.all_node_actors(false).await;
-        for (node_id, actors) in &info.actor_map {
+        let mut node_actors = self.fragment_manager.all_node_actors(false).await;
+        let futures = info.actor_map.iter().map(|(node_id, actors)| {
             let node = info.node_map.get(node_id).unwrap();
-            let client = self.env.stream_client_pool().get(node).await?;
-
-            client
-                .to_owned()
-                .broadcast_actor_info_table(BroadcastActorInfoTableRequest {
-                    info: actor_infos.clone(),
-                })
-                .await
-                .to_rw_result_with(|| format!("failed to connect to {}", node_id))?;
-
+            let broadcast_request = BroadcastActorInfoTableRequest {
+                info: actor_infos.clone(),
+            };
             let request_id = Uuid::new_v4().to_string();
-            tracing::debug!(request_id = request_id.as_str(), actors = ?actors, "update actors");
-            client
-                .to_owned()
-                .update_actors(UpdateActorsRequest {
-                    request_id,
-                    actors: node_actors.get(node_id).cloned().unwrap_or_default(),
-                    ..Default::default()
-                })
-                .await
-                .to_rw_result_with(|| format!("failed to connect to {}", node_id))?;
-        }
+            tracing::debug!(request_id = request_id.as_str(), actor_ids = ?actors, "update actors");
+            let update_request = UpdateActorsRequest {
+                request_id,
+                actors: node_actors.remove(node_id).unwrap_or_default(),
+                ..Default::default()
+            };
+            let future = async move {
+                let mut client = self.env.stream_client_pool().get(node).await?;
+                client
+                    .broadcast_actor_info_table(broadcast_request)
+                    .await
+                    .to_rw_result_with(|| format!("failed to connect to {}", node_id))?;
+                client
+                    .update_actors(update_request)
+                    .await
+                    .to_rw_result_with(|| format!("failed to connect to {}", node_id))?;
+
+                Ok::<_, RwError>(())
+            };
+            (*node_id, future)
+        });
 
+        join_node_rpcs("update_actors", futures).await?;
         Ok(())
     }
 
     /// Build all actors in compute nodes.
     async fn build_actors(&self, info: &BarrierActorInfo) -> Result<()> {
-        for (node_id, actors) in &info.actor_map {
+        let futures = info.actor_map.iter().map(|(node_id, actors)| {
             let node = info.node_map.get(node_id).unwrap();
-            let client = self.env.stream_client_pool().get(node).await?;
-
             let request_id = Uuid::new_v4().to_string();
-            tracing::debug!(request_id = request_id.as_str(), actors = ?actors, "build actors");
-            client
-                .to_owned()
-                .build_actors(BuildActorsRequest {
-                    request_id,
-                    actor_id: actors.to_owned(),
-                })
-                .await
-                .to_rw_result_with(|| format!("failed to connect to {}", node_id))?;
-        }
+            tracing::debug!(request_id = request_id.as_str(), actor_ids = ?actors, "build actors");
+            let future = async move {
+                let mut client = self.env.stream_client_pool().get(node).await?;
+                client
+                    .build_actors(BuildActorsRequest {
+                        request_id,
+                        actor_id: actors.to_owned(),
+                    })
+                    .await
+                    .to_rw_result_with(|| format!("failed to connect to {}", node_id))?;
+
+                Ok::<_, RwError>(())
+            };
+            (*node_id, future)
+        });
 
+        join_node_rpcs("build_actors", futures).await?;
         Ok(())
     }
 
-    /// Reset all compute nodes by calling `force_stop_actors`.
+    /// Reset all compute nodes by calling `force_stop_actors`. The states written after the last
+    /// committed checkpoint are dropped by the nodes, so that the actors restart from the
+    /// checkpoint, and the sources replay the data since the offsets persisted by it.
     async fn reset_compute_nodes(
         &self,
         info: &BarrierActorInfo,
         prev_epoch: &Epoch,
         new_epoch: &Epoch,
     ) {
+        let committed_epoch = self
+            .hummock_manager
+            .get_current_version()
+            .await
+            .max_committed_epoch;
+        tracing::debug!(epoch = committed_epoch, "reset compute nodes to checkpoint");
         let futures = info.node_map.iter().map(|(_, worker_node)| {
             let retry_strategy = Self::get_retry_strategy();
 
@@ -245,6 +364,7 @@ where
                                 curr: new_epoch.0,
                                 prev: prev_epoch.0,
                             }),
+                            committed_epoch,
                         })
                         .await
                         .to_rw_result()
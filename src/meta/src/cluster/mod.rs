@@ -25,6 +25,7 @@ use risingwave_common::error::{internal_error, ErrorCode, Result};
 use risingwave_common::try_match_expand;
 use risingwave_pb::common::worker_node::State;
 use risingwave_pb::common::{HostAddress, ParallelUnit, ParallelUnitType, WorkerNode, WorkerType};
+use risingwave_pb::meta::event_log::EventType;
 use risingwave_pb::meta::subscribe_response::{Info, Operation};
 use tokio::sync::oneshot::Sender;
 use tokio::sync::{RwLock, RwLockReadGuard};
@@ -32,6 +33,7 @@ use tokio::task::JoinHandle;
 
 use crate::manager::{IdCategory, LocalNotification, MetaSrvEnv};
 use crate::model::{MetadataModel, Worker, INVALID_EXPIRE_AT};
+use crate::rpc::metrics::MetaMetrics;
 use crate::storage::MetaStore;
 
 pub type WorkerId = u32;
@@ -136,6 +138,17 @@ where
                 // Update core.
                 core.add_worker_node(worker);
 
+                self.env
+                    .event_log_manager()
+                    .log(
+                        EventType::WorkerJoin,
+                        format!(
+                            "{:?} {} joined at {}:{}",
+                            r#type, worker_id, host_address.host, host_address.port
+                        ),
+                    )
+                    .await;
+
                 Ok((worker_node, true))
             }
         }
@@ -175,6 +188,17 @@ where
         // Update core.
         core.delete_worker_node(worker);
 
+        self.env
+            .event_log_manager()
+            .log(
+                EventType::WorkerLeave,
+                format!(
+                    "{:?} {} left from {}:{}",
+                    worker_type, worker_node.id, host_address.host, host_address.port
+                ),
+            )
+            .await;
+
         // Notify frontends to delete compute node.
         if worker_type == WorkerType::ComputeNode {
             self.env
@@ -281,6 +305,56 @@ where
         (join_handle, shutdown_tx)
     }
 
+    /// Start a worker to report the number of workers of each type and state to the metrics
+    /// periodically.
+    pub async fn start_worker_num_monitor(
+        cluster_manager: ClusterManagerRef<S>,
+        interval: Duration,
+        meta_metrics: Arc<MetaMetrics>,
+    ) -> (JoinHandle<()>, Sender<()>) {
+        let (shutdown_tx, mut shutdown_rx) = tokio::sync::oneshot::channel();
+        let join_handle = tokio::spawn(async move {
+            let mut monitor_interval = tokio::time::interval(interval);
+            monitor_interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                tokio::select! {
+                    // Wait for interval
+                    _ = monitor_interval.tick() => {},
+                    // Shutdown
+                    _ = &mut shutdown_rx => {
+                        tracing::info!("Worker number monitor is stopping");
+                        return;
+                    }
+                }
+
+                let mut worker_num = HashMap::new();
+                for worker in cluster_manager.core.read().await.workers.values() {
+                    let worker_node = &worker.worker_node;
+                    *worker_num
+                        .entry((worker_node.r#type(), worker_node.state()))
+                        .or_insert(0) += 1;
+                }
+                for worker_type in [
+                    WorkerType::Frontend,
+                    WorkerType::ComputeNode,
+                    WorkerType::RiseCtl,
+                    WorkerType::Compactor,
+                ] {
+                    for state in [State::Starting, State::Running] {
+                        meta_metrics
+                            .worker_num
+                            .with_label_values(&[
+                                &format!("{:?}", worker_type),
+                                &format!("{:?}", state),
+                            ])
+                            .set(*worker_num.get(&(worker_type, state)).unwrap_or(&0));
+                    }
+                }
+            }
+        });
+        (join_handle, shutdown_tx)
+    }
+
     /// Get live nodes with the specified type and state.
     /// # Arguments
     /// * `worker_type` `WorkerType` of the nodes
@@ -389,7 +463,7 @@ impl ClusterManagerCore {
             .ok_or_else(|| internal_error("Worker node does not exist!"))
     }
 
-    fn get_worker_by_host(&self, host_address: HostAddress) -> Option<Worker> {
+    pub fn get_worker_by_host(&self, host_address: HostAddress) -> Option<Worker> {
         self.workers.get(&WorkerKey(host_address)).cloned()
     }
 
@@ -27,7 +27,9 @@ use tower_http::add_extension::AddExtensionLayer;
 use tower_http::cors::{self, CorsLayer};
 use tower_http::services::ServeDir;
 
+use crate::barrier::BarrierManagerRef;
 use crate::cluster::ClusterManagerRef;
+use crate::hummock::HummockManagerRef;
 use crate::storage::MetaStore;
 use crate::stream::FragmentManagerRef;
 
@@ -36,6 +38,8 @@ pub struct DashboardService<S: MetaStore> {
     pub dashboard_addr: SocketAddr,
     pub cluster_manager: ClusterManagerRef<S>,
     pub fragment_manager: FragmentManagerRef<S>,
+    pub barrier_manager: BarrierManagerRef<S>,
+    pub hummock_manager: HummockManagerRef<S>,
 
     // TODO: replace with catalog manager.
     pub meta_store: Arc<S>,
@@ -45,11 +49,12 @@ pub type Service<S> = Arc<DashboardService<S>>;
 
 mod handlers {
     use axum::Json;
+    use itertools::Itertools;
     use risingwave_pb::catalog::Table;
     use risingwave_pb::common::WorkerNode;
     use risingwave_pb::meta::ActorLocation;
     use risingwave_pb::stream_plan::StreamActor;
-    use serde_json::json;
+    use serde_json::{json, Value};
 
     use super::*;
 
@@ -135,6 +140,120 @@ mod handlers {
 
         Ok(Json(table_fragments))
     }
+
+    /// A summary of the cluster: the workers, the streaming jobs, the storage and the barriers.
+    pub async fn get_overview<S: MetaStore>(
+        Extension(srv): Extension<Service<S>>,
+    ) -> Result<Json<Value>> {
+        use risingwave_pb::common::worker_node::State;
+        use risingwave_pb::common::WorkerType;
+
+        let mut workers = serde_json::Map::new();
+        for (name, ty) in [
+            ("frontend", WorkerType::Frontend),
+            ("compute_node", WorkerType::ComputeNode),
+            ("compactor", WorkerType::Compactor),
+        ] {
+            let nodes = srv.cluster_manager.list_worker_node(ty, None).await;
+            let running = nodes
+                .iter()
+                .filter(|node| node.state == State::Running as i32)
+                .count();
+            workers.insert(
+                name.to_string(),
+                json!({ "total": nodes.len(), "running": running }),
+            );
+        }
+
+        let table_fragments = srv
+            .fragment_manager
+            .list_table_fragments()
+            .await
+            .map_err(err)?;
+        let actor_count: usize = table_fragments.iter().map(|f| f.actor_ids().len()).sum();
+
+        let version = srv.hummock_manager.get_current_version().await;
+        let latest_barrier = srv.barrier_manager.history().latest().map(|record| {
+            json!({
+                "epoch": record.prev_epoch,
+                "latency_ms": record.latency.as_millis() as u64,
+            })
+        });
+
+        Ok(Json(json!({
+            "workers": workers,
+            "materialized_view_count": table_fragments.len(),
+            "actor_count": actor_count,
+            "hummock_version_id": version.id,
+            "max_committed_epoch": version.max_committed_epoch,
+            "latest_barrier": latest_barrier,
+        })))
+    }
+
+    /// The latency of recent barriers, from the oldest to the newest.
+    pub async fn list_barriers<S: MetaStore>(
+        Extension(srv): Extension<Service<S>>,
+    ) -> Result<Json<Vec<Value>>> {
+        let barriers = srv
+            .barrier_manager
+            .history()
+            .records()
+            .map(|record| {
+                json!({
+                    "prev_epoch": record.prev_epoch,
+                    "curr_epoch": record.curr_epoch,
+                    "latency_ms": record.latency.as_millis() as u64,
+                })
+            })
+            .collect();
+        Ok(Json(barriers))
+    }
+
+    /// The throughput of each source, averaged over recent barriers.
+    pub async fn list_source_throughput<S: MetaStore>(
+        Extension(srv): Extension<Service<S>>,
+    ) -> Result<Json<Vec<Value>>> {
+        let throughput = srv.barrier_manager.history().source_throughput();
+        let sources = throughput
+            .into_iter()
+            .sorted_by_key(|(source_id, _)| *source_id)
+            .map(|(source_id, rows_per_second)| {
+                json!({ "source_id": source_id, "rows_per_second": rows_per_second })
+            })
+            .collect();
+        Ok(Json(sources))
+    }
+
+    /// The number and size of SSTs in each level of each compaction group.
+    pub async fn list_hummock_levels<S: MetaStore>(
+        Extension(srv): Extension<Service<S>>,
+    ) -> Result<Json<Vec<Value>>> {
+        let version = srv.hummock_manager.get_current_version().await;
+        let groups = version
+            .levels
+            .iter()
+            .sorted_by_key(|(group_id, _)| **group_id)
+            .map(|(group_id, levels)| {
+                let levels = levels
+                    .levels
+                    .iter()
+                    .map(|level| {
+                        json!({
+                            "level_idx": level.level_idx,
+                            "file_count": level.table_infos.len(),
+                            "total_file_size": level
+                                .table_infos
+                                .iter()
+                                .map(|sst| sst.file_size)
+                                .sum::<u64>(),
+                        })
+                    })
+                    .collect::<Vec<_>>();
+                json!({ "compaction_group_id": group_id, "levels": levels })
+            })
+            .collect();
+        Ok(Json(groups))
+    }
 }
 
 impl<S> DashboardService<S>
@@ -150,6 +269,10 @@ where
             .route("/actors", get(list_actors::<S>))
             .route("/fragments", get(list_table_fragments::<S>))
             .route("/materialized_views", get(list_materialized_views::<S>))
+            .route("/overview", get(get_overview::<S>))
+            .route("/barriers", get(list_barriers::<S>))
+            .route("/sources", get(list_source_throughput::<S>))
+            .route("/hummock/levels", get(list_hummock_levels::<S>))
             .layer(
                 ServiceBuilder::new()
                     .layer(AddExtensionLayer::new(srv.clone()))
@@ -12,9 +12,11 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
+use itertools::Itertools;
+use risingwave_hummock_sdk::HummockSSTableId;
 use risingwave_pb::hummock::{Level, SstableInfo};
 
 use crate::hummock::compaction::overlap_strategy::OverlapStrategy;
@@ -177,12 +179,92 @@ impl CompactionPicker for MinOverlappingPicker {
                 level_type: levels[self.level].level_type,
                 table_infos: select_input_ssts,
                 total_file_size: 0,
+                sub_levels: vec![],
             },
             target_level: Level {
                 level_idx: target_level as u32,
                 level_type: levels[target_level].level_type,
                 table_infos: target_input_ssts,
                 total_file_size: 0,
+                sub_levels: vec![],
+            },
+            split_ranges: vec![],
+        })
+    }
+}
+
+/// Picks the SST whose reads merge the most other SSTs, and compacts it with the SSTs overlapping
+/// with it in the next level.
+pub struct ReadTriggeredPicker {
+    compact_task_id: u64,
+    overlap_strategy: Arc<dyn OverlapStrategy>,
+    level: usize,
+    /// The overlapped reads of the SSTs in the level.
+    overlapped_reads: HashMap<HummockSSTableId, u64>,
+}
+
+impl ReadTriggeredPicker {
+    pub fn new(
+        compact_task_id: u64,
+        level: usize,
+        overlap_strategy: Arc<dyn OverlapStrategy>,
+        overlapped_reads: HashMap<HummockSSTableId, u64>,
+    ) -> ReadTriggeredPicker {
+        ReadTriggeredPicker {
+            compact_task_id,
+            overlap_strategy,
+            level,
+            overlapped_reads,
+        }
+    }
+}
+
+impl CompactionPicker for ReadTriggeredPicker {
+    fn pick_compaction(
+        &self,
+        levels: &[Level],
+        level_handlers: &mut [LevelHandler],
+    ) -> Option<SearchResult> {
+        let target_level = self.level + 1;
+        let (table, target_input_ssts) = levels[self.level]
+            .table_infos
+            .iter()
+            .filter(|table| {
+                !level_handlers[self.level].is_pending_compact(&table.id)
+                    && self.overlapped_reads.get(&table.id).copied().unwrap_or(0) > 0
+            })
+            .sorted_by_key(|table| std::cmp::Reverse(self.overlapped_reads[&table.id]))
+            .find_map(|table| {
+                let target_input_ssts = self
+                    .overlap_strategy
+                    .check_base_level_overlap(&[table.clone()], &levels[target_level].table_infos);
+                if target_input_ssts
+                    .iter()
+                    .any(|other| level_handlers[target_level].is_pending_compact(&other.id))
+                {
+                    return None;
+                }
+                Some((table.clone(), target_input_ssts))
+            })?;
+        let select_input_ssts = vec![table];
+        level_handlers[self.level].add_pending_task(self.compact_task_id, &select_input_ssts);
+        if !target_input_ssts.is_empty() {
+            level_handlers[target_level].add_pending_task(self.compact_task_id, &target_input_ssts);
+        }
+        Some(SearchResult {
+            select_level: Level {
+                level_idx: self.level as u32,
+                level_type: levels[self.level].level_type,
+                table_infos: select_input_ssts,
+                total_file_size: 0,
+                sub_levels: vec![],
+            },
+            target_level: Level {
+                level_idx: target_level as u32,
+                level_type: levels[target_level].level_type,
+                table_infos: target_input_ssts,
+                total_file_size: 0,
+                sub_levels: vec![],
             },
             split_ranges: vec![],
         })
@@ -206,6 +288,7 @@ pub mod tests {
                 level_type: LevelType::Overlapping as i32,
                 table_infos: vec![],
                 total_file_size: 0,
+                sub_levels: vec![],
             },
             Level {
                 level_idx: 1,
@@ -216,6 +299,7 @@ pub mod tests {
                     generate_table(2, 1, 222, 300, 1),
                 ],
                 total_file_size: 0,
+                sub_levels: vec![],
             },
             Level {
                 level_idx: 2,
@@ -228,6 +312,7 @@ pub mod tests {
                     generate_table(8, 2, 301, 400, 1),
                 ],
                 total_file_size: 0,
+                sub_levels: vec![],
             },
         ];
         let mut levels_handler = vec![
@@ -277,6 +362,7 @@ pub mod tests {
                 level_type: LevelType::Overlapping as i32,
                 table_infos: vec![],
                 total_file_size: 0,
+                sub_levels: vec![],
             },
             Level {
                 level_idx: 1,
@@ -287,6 +373,7 @@ pub mod tests {
                     generate_table(2, 1, 150, 249, 2),
                 ],
                 total_file_size: 0,
+                sub_levels: vec![],
             },
             Level {
                 level_idx: 2,
@@ -296,6 +383,7 @@ pub mod tests {
                     generate_table(5, 1, 200, 399, 1),
                 ],
                 total_file_size: 0,
+                sub_levels: vec![],
             },
         ];
         let mut levels_handler = vec![
@@ -318,4 +406,76 @@ pub mod tests {
         assert_eq!(ret.target_level.table_infos.len(), 1);
         assert_eq!(ret.target_level.table_infos[0].id, 4);
     }
+
+    #[test]
+    fn test_read_triggered_picker() {
+        let levels = vec![
+            Level {
+                level_idx: 0,
+                level_type: LevelType::Overlapping as i32,
+                table_infos: vec![],
+                total_file_size: 0,
+                sub_levels: vec![],
+            },
+            Level {
+                level_idx: 1,
+                level_type: LevelType::Nonoverlapping as i32,
+                table_infos: vec![
+                    generate_table(0, 1, 0, 100, 1),
+                    generate_table(1, 1, 101, 200, 1),
+                    generate_table(2, 1, 222, 300, 1),
+                ],
+                total_file_size: 0,
+                sub_levels: vec![],
+            },
+            Level {
+                level_idx: 2,
+                level_type: LevelType::Nonoverlapping as i32,
+                table_infos: vec![
+                    generate_table(4, 1, 0, 100, 1),
+                    generate_table(5, 1, 101, 150, 1),
+                    generate_table(6, 1, 151, 201, 1),
+                ],
+                total_file_size: 0,
+                sub_levels: vec![],
+            },
+        ];
+        let mut levels_handler = vec![
+            LevelHandler::new(0),
+            LevelHandler::new(1),
+            LevelHandler::new(2),
+        ];
+        let picker = ReadTriggeredPicker::new(
+            0,
+            1,
+            Arc::new(RangeOverlapStrategy::default()),
+            HashMap::from([(0, 10), (1, 20)]),
+        );
+
+        // The most read SST is picked, though it overlaps with more SSTs.
+        let ret = picker
+            .pick_compaction(&levels, &mut levels_handler)
+            .unwrap();
+        assert_eq!(ret.select_level.level_idx, 1);
+        assert_eq!(ret.target_level.level_idx, 2);
+        assert_eq!(ret.select_level.table_infos[0].id, 1);
+        assert_eq!(
+            ret.target_level
+                .table_infos
+                .iter()
+                .map(|table| table.id)
+                .collect_vec(),
+            vec![5, 6]
+        );
+
+        let ret = picker
+            .pick_compaction(&levels, &mut levels_handler)
+            .unwrap();
+        assert_eq!(ret.select_level.table_infos[0].id, 0);
+
+        // SSTs without overlapped reads are not picked.
+        assert!(picker
+            .pick_compaction(&levels, &mut levels_handler)
+            .is_none());
+    }
 }
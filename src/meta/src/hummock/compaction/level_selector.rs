@@ -17,12 +17,18 @@
 // COPYING file in the root directory) and Apache 2.0 License
 // (found in the LICENSE.Apache file in the root directory).
 
+use std::collections::HashMap;
 use std::sync::Arc;
 
-use risingwave_hummock_sdk::HummockCompactionTaskId;
+use risingwave_hummock_sdk::{HummockCompactionTaskId, HummockSSTableId};
 use risingwave_pb::hummock::Level;
 
-use crate::hummock::compaction::compaction_picker::{CompactionPicker, MinOverlappingPicker};
+use crate::hummock::compaction::compaction_picker::{
+    CompactionPicker, MinOverlappingPicker, ReadTriggeredPicker,
+};
+use crate::hummock::compaction::manual_compaction_picker::{
+    ManualCompactionOption, ManualCompactionPicker,
+};
 use crate::hummock::compaction::overlap_strategy::{
     HashStrategy, OverlapStrategy, RangeOverlapStrategy,
 };
@@ -30,19 +36,33 @@ use crate::hummock::compaction::tier_compaction_picker::{
     LevelCompactionPicker, TierCompactionPicker,
 };
 use crate::hummock::compaction::CompactionMode::{ConsistentHashMode, RangeMode};
-use crate::hummock::compaction::{CompactionConfig, SearchResult};
+use crate::hummock::compaction::{CompactionConfig, ReadStatistics, SearchResult};
 use crate::hummock::level_handler::LevelHandler;
 
 const SCORE_BASE: u64 = 100;
 
 pub trait LevelSelector: Sync + Send {
-    fn need_compaction(&self, levels: &[Level], level_handlers: &mut [LevelHandler]) -> bool;
+    fn need_compaction(
+        &self,
+        levels: &[Level],
+        level_handlers: &mut [LevelHandler],
+        read_statistics: &ReadStatistics,
+    ) -> bool;
 
     fn pick_compaction(
         &self,
         task_id: HummockCompactionTaskId,
         levels: &[Level],
         level_handlers: &mut [LevelHandler],
+        read_statistics: &ReadStatistics,
+    ) -> Option<SearchResult>;
+
+    fn manual_pick_compaction(
+        &self,
+        task_id: HummockCompactionTaskId,
+        levels: &[Level],
+        level_handlers: &mut [LevelHandler],
+        option: ManualCompactionOption,
     ) -> Option<SearchResult>;
 
     fn name(&self) -> &'static str;
@@ -58,6 +78,9 @@ pub struct SelectContext {
     // level, which equals to `base_level -= 1;`.
     base_level: usize,
     score_levels: Vec<(u64, usize, usize)>,
+    // The input levels whose scores are decided by the reads of their SSTs, with the overlapped
+    // reads of the idle SSTs in them.
+    read_triggered_levels: HashMap<usize, HashMap<HummockSSTableId, u64>>,
 }
 
 // TODO: Set these configurations by meta rpc
@@ -181,6 +204,7 @@ impl DynamicLevelSelector {
         &self,
         levels: &[Level],
         handlers: &mut [LevelHandler],
+        read_statistics: &ReadStatistics,
     ) -> SelectContext {
         let mut ctx = self.calculate_level_base_size(levels);
 
@@ -193,20 +217,35 @@ impl DynamicLevelSelector {
             if total_size == 0 {
                 continue;
             }
+            // Compact the SSTs whose reads merge too many other SSTs earlier, even if the level is
+            // not large enough to be compacted.
+            let overlapped_reads: HashMap<_, _> = level
+                .table_infos
+                .iter()
+                .filter(|sst| !handlers[level_idx].is_pending_compact(&sst.id))
+                .map(|sst| (sst.id, read_statistics.get(sst.id).overlapped_reads()))
+                .filter(|(_, reads)| *reads > 0)
+                .collect();
+            let read_score = overlapped_reads.values().max().map_or(0, |reads| {
+                reads.saturating_mul(SCORE_BASE) / self.config.read_compaction_trigger
+            });
             if level_idx == 0 {
                 // trigger intra-l0 compaction at first when the number of files is too large.
                 let score = idle_file_count * SCORE_BASE
                     / self.config.level0_tier_compact_file_number as u64;
                 ctx.score_levels.push((score, 0, 0));
                 let score = 2 * total_size * SCORE_BASE / self.config.max_bytes_for_level_base
-                    + idle_file_count * SCORE_BASE / self.config.level0_tigger_file_numer as u64;
+                    + idle_file_count * SCORE_BASE / self.config.level0_tigger_file_numer as u64
+                    + read_score;
                 ctx.score_levels.push((score, 0, ctx.base_level));
             } else {
-                ctx.score_levels.push((
-                    total_size * SCORE_BASE / ctx.level_max_bytes[level_idx],
-                    level_idx,
-                    level_idx + 1,
-                ));
+                let score = total_size * SCORE_BASE / ctx.level_max_bytes[level_idx];
+                if read_score > score {
+                    ctx.read_triggered_levels
+                        .insert(level_idx, overlapped_reads);
+                }
+                ctx.score_levels
+                    .push((std::cmp::max(score, read_score), level_idx, level_idx + 1));
             }
         }
 
@@ -217,8 +256,13 @@ impl DynamicLevelSelector {
 }
 
 impl LevelSelector for DynamicLevelSelector {
-    fn need_compaction(&self, levels: &[Level], level_handlers: &mut [LevelHandler]) -> bool {
-        let ctx = self.get_priority_levels(levels, level_handlers);
+    fn need_compaction(
+        &self,
+        levels: &[Level],
+        level_handlers: &mut [LevelHandler],
+        read_statistics: &ReadStatistics,
+    ) -> bool {
+        let ctx = self.get_priority_levels(levels, level_handlers, read_statistics);
         ctx.score_levels
             .first()
             .map(|(score, _, _)| *score > SCORE_BASE)
@@ -230,13 +274,22 @@ impl LevelSelector for DynamicLevelSelector {
         task_id: HummockCompactionTaskId,
         levels: &[Level],
         level_handlers: &mut [LevelHandler],
+        read_statistics: &ReadStatistics,
     ) -> Option<SearchResult> {
-        let ctx = self.get_priority_levels(levels, level_handlers);
+        let mut ctx = self.get_priority_levels(levels, level_handlers, read_statistics);
         for (score, select_level, target_level) in ctx.score_levels {
             if score <= SCORE_BASE {
                 return None;
             }
-            let picker = self.create_compaction_picker(select_level, target_level, task_id);
+            let picker = match ctx.read_triggered_levels.remove(&select_level) {
+                Some(overlapped_reads) => Box::new(ReadTriggeredPicker::new(
+                    task_id,
+                    select_level,
+                    self.overlap_strategy.clone(),
+                    overlapped_reads,
+                )),
+                None => self.create_compaction_picker(select_level, target_level, task_id),
+            };
             if let Some(ret) = picker.pick_compaction(levels, level_handlers) {
                 return Some(ret);
             }
@@ -244,6 +297,17 @@ impl LevelSelector for DynamicLevelSelector {
         None
     }
 
+    fn manual_pick_compaction(
+        &self,
+        task_id: HummockCompactionTaskId,
+        levels: &[Level],
+        level_handlers: &mut [LevelHandler],
+        option: ManualCompactionOption,
+    ) -> Option<SearchResult> {
+        let picker = ManualCompactionPicker::new(task_id, option, self.overlap_strategy.clone());
+        picker.pick_compaction(levels, level_handlers)
+    }
+
     fn name(&self) -> &'static str {
         "DynamicLevelSelector"
     }
@@ -254,7 +318,7 @@ pub mod tests {
     use std::ops::Range;
 
     use itertools::Itertools;
-    use risingwave_pb::hummock::{LevelType, SstableInfo};
+    use risingwave_pb::hummock::{LevelType, SstReadStatistics, SstableInfo};
 
     use super::*;
     use crate::hummock::compaction::overlap_strategy::RangeOverlapStrategy;
@@ -286,6 +350,7 @@ pub mod tests {
             level_type: LevelType::Nonoverlapping as i32,
             table_infos,
             total_file_size,
+            sub_levels: vec![],
         }
     }
 
@@ -299,6 +364,7 @@ pub mod tests {
             min_compaction_bytes: 1,
             level0_tigger_file_numer: 1,
             level0_tier_compact_file_number: 2,
+            read_compaction_trigger: u64::MAX,
             compaction_mode: RangeMode,
         };
         let selector =
@@ -309,6 +375,7 @@ pub mod tests {
                 level_type: LevelType::Overlapping as i32,
                 table_infos: vec![],
                 total_file_size: 0,
+                sub_levels: vec![],
             },
             generate_level(1, vec![]),
             generate_level(2, generate_tables(0..5, 0..1000, 3, 10)),
@@ -381,6 +448,7 @@ pub mod tests {
             min_compaction_bytes: 200,
             level0_tigger_file_numer: 8,
             level0_tier_compact_file_number: 4,
+            read_compaction_trigger: u64::MAX,
             compaction_mode: RangeMode,
         };
         let mut levels = vec![
@@ -391,6 +459,7 @@ pub mod tests {
             generate_level(4, generate_tables(10..15, 0..1000, 1, 200)),
         ];
         levels[0].level_type = LevelType::Overlapping as i32;
+        let read_statistics = ReadStatistics::default();
 
         let selector = DynamicLevelSelector::new(
             Arc::new(config.clone()),
@@ -398,7 +467,7 @@ pub mod tests {
         );
         let mut levels_handlers = (0..5).into_iter().map(LevelHandler::new).collect_vec();
         let compaction = selector
-            .pick_compaction(1, &levels, &mut levels_handlers)
+            .pick_compaction(1, &levels, &mut levels_handlers, &read_statistics)
             .unwrap();
         assert_eq!(compaction.select_level.level_idx, 0);
         assert_eq!(compaction.target_level.level_idx, 0);
@@ -412,7 +481,7 @@ pub mod tests {
             DynamicLevelSelector::new(Arc::new(config), Arc::new(RangeOverlapStrategy::default()));
         let mut levels_handlers = (0..5).into_iter().map(LevelHandler::new).collect_vec();
         let compaction = selector
-            .pick_compaction(1, &levels, &mut levels_handlers)
+            .pick_compaction(1, &levels, &mut levels_handlers, &read_statistics)
             .unwrap();
         assert_eq!(compaction.select_level.level_idx, 0);
         assert_eq!(compaction.target_level.level_idx, 2);
@@ -424,7 +493,7 @@ pub mod tests {
         levels[0].table_infos.clear();
         levels[2].table_infos = generate_tables(20..30, 0..1000, 3, 10);
         let compaction = selector
-            .pick_compaction(2, &levels, &mut levels_handlers)
+            .pick_compaction(2, &levels, &mut levels_handlers, &read_statistics)
             .unwrap();
         assert_eq!(compaction.select_level.level_idx, 3);
         assert_eq!(compaction.target_level.level_idx, 4);
@@ -433,7 +502,70 @@ pub mod tests {
 
         // no compaction need to be scheduled because we do not calculate the size of pending files
         // to score.
-        let compaction = selector.pick_compaction(2, &levels, &mut levels_handlers);
+        let compaction =
+            selector.pick_compaction(2, &levels, &mut levels_handlers, &read_statistics);
         assert!(compaction.is_none());
     }
+
+    #[test]
+    fn test_read_triggered_compaction() {
+        let config = CompactionConfig {
+            max_bytes_for_level_base: 200,
+            max_level: 4,
+            max_bytes_for_level_multiplier: 5,
+            max_compaction_bytes: 10000,
+            min_compaction_bytes: 200,
+            level0_tigger_file_numer: 8,
+            level0_tier_compact_file_number: 4,
+            read_compaction_trigger: 100,
+            compaction_mode: RangeMode,
+        };
+        let mut levels = vec![
+            generate_level(0, vec![]),
+            generate_level(1, vec![]),
+            generate_level(2, generate_tables(0..5, 0..1000, 3, 10)),
+            generate_level(3, generate_tables(5..10, 0..1000, 2, 30)),
+            generate_level(4, generate_tables(10..15, 0..1000, 1, 200)),
+        ];
+        levels[0].level_type = LevelType::Overlapping as i32;
+        let selector =
+            DynamicLevelSelector::new(Arc::new(config), Arc::new(RangeOverlapStrategy::default()));
+        let mut levels_handlers = (0..5).into_iter().map(LevelHandler::new).collect_vec();
+
+        // No level is large enough to be compacted.
+        let mut read_statistics = ReadStatistics::default();
+        assert!(selector
+            .pick_compaction(1, &levels, &mut levels_handlers, &read_statistics)
+            .is_none());
+
+        // The reads of SST 1 merge SSTs of the levels below too many times.
+        read_statistics.add([SstReadStatistics {
+            sst_id: 1,
+            read_count: 10,
+            merge_width: 1010,
+        }]);
+        let compaction = selector
+            .pick_compaction(1, &levels, &mut levels_handlers, &read_statistics)
+            .unwrap();
+        assert_eq!(compaction.select_level.level_idx, 2);
+        assert_eq!(compaction.target_level.level_idx, 3);
+        assert_eq!(
+            compaction
+                .select_level
+                .table_infos
+                .iter()
+                .map(|sst| sst.id)
+                .collect_vec(),
+            vec![1]
+        );
+        assert_eq!(
+            compaction
+                .target_level
+                .table_infos
+                .iter()
+                .map(|sst| sst.id)
+                .collect_vec(),
+            vec![6]
+        );
+    }
 }
@@ -0,0 +1,245 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use risingwave_hummock_sdk::key_range::KeyRangeCommon;
+use risingwave_hummock_sdk::prost_key_range::KeyRangeExt;
+use risingwave_pb::hummock::{KeyRange, Level, SstableInfo};
+
+use crate::hummock::compaction::compaction_picker::CompactionPicker;
+use crate::hummock::compaction::overlap_strategy::OverlapStrategy;
+use crate::hummock::compaction::SearchResult;
+use crate::hummock::level_handler::LevelHandler;
+
+/// Which SSTs to compact when a compaction is triggered manually.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ManualCompactionOption {
+    /// SSTs overlapping with this range are compacted.
+    pub key_range: KeyRange,
+    /// The level to compact. SSTs are compacted into the next level, or rewritten in place if it
+    /// is the bottommost level.
+    pub level: usize,
+}
+
+impl Default for ManualCompactionOption {
+    fn default() -> Self {
+        Self {
+            key_range: KeyRange::inf(),
+            level: 0,
+        }
+    }
+}
+
+pub struct ManualCompactionPicker {
+    compact_task_id: u64,
+    overlap_strategy: Arc<dyn OverlapStrategy>,
+    option: ManualCompactionOption,
+}
+
+impl ManualCompactionPicker {
+    pub fn new(
+        compact_task_id: u64,
+        option: ManualCompactionOption,
+        overlap_strategy: Arc<dyn OverlapStrategy>,
+    ) -> Self {
+        Self {
+            compact_task_id,
+            overlap_strategy,
+            option,
+        }
+    }
+}
+
+impl CompactionPicker for ManualCompactionPicker {
+    fn pick_compaction(
+        &self,
+        levels: &[Level],
+        level_handlers: &mut [LevelHandler],
+    ) -> Option<SearchResult> {
+        let level = self.option.level;
+        if level >= levels.len() {
+            return None;
+        }
+        let target_level = std::cmp::min(level + 1, levels.len() - 1);
+        let select_input_ssts = if level == 0 {
+            // SSTs of L0 may overlap with each other, and an older SST left in L0 could shadow
+            // the newer data moved to the next level. So we always compact the whole L0.
+            levels[0].table_infos.clone()
+        } else {
+            levels[level]
+                .table_infos
+                .iter()
+                .filter(|table| {
+                    self.option
+                        .key_range
+                        .full_key_overlap(table.key_range.as_ref().unwrap())
+                })
+                .cloned()
+                .collect::<Vec<SstableInfo>>()
+        };
+        if select_input_ssts.is_empty()
+            || select_input_ssts
+                .iter()
+                .any(|table| level_handlers[level].is_pending_compact(&table.id))
+        {
+            return None;
+        }
+        let target_input_ssts = if target_level == level {
+            vec![]
+        } else {
+            self.overlap_strategy
+                .check_base_level_overlap(&select_input_ssts, &levels[target_level].table_infos)
+        };
+        if target_input_ssts
+            .iter()
+            .any(|table| level_handlers[target_level].is_pending_compact(&table.id))
+        {
+            return None;
+        }
+
+        level_handlers[level].add_pending_task(self.compact_task_id, &select_input_ssts);
+        if !target_input_ssts.is_empty() {
+            level_handlers[target_level].add_pending_task(self.compact_task_id, &target_input_ssts);
+        }
+        Some(SearchResult {
+            select_level: Level {
+                level_idx: level as u32,
+                level_type: levels[level].level_type,
+                table_infos: select_input_ssts,
+                total_file_size: 0,
+                sub_levels: vec![],
+            },
+            target_level: Level {
+                level_idx: target_level as u32,
+                level_type: levels[target_level].level_type,
+                table_infos: target_input_ssts,
+                total_file_size: 0,
+                sub_levels: vec![],
+            },
+            split_ranges: vec![],
+        })
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use risingwave_pb::hummock::LevelType;
+
+    use super::*;
+    use crate::hummock::compaction::overlap_strategy::RangeOverlapStrategy;
+    use crate::hummock::compaction::tier_compaction_picker::tests::generate_table;
+    use crate::hummock::test_utils::iterator_test_key_of_epoch;
+
+    fn generate_levels() -> Vec<Level> {
+        vec![
+            Level {
+                level_idx: 0,
+                level_type: LevelType::Overlapping as i32,
+                table_infos: vec![
+                    generate_table(0, 1, 0, 500, 2),
+                    generate_table(1, 1, 300, 400, 3),
+                ],
+                total_file_size: 0,
+                sub_levels: vec![],
+            },
+            Level {
+                level_idx: 1,
+                level_type: LevelType::Nonoverlapping as i32,
+                table_infos: vec![
+                    generate_table(2, 1, 0, 100, 1),
+                    generate_table(3, 1, 101, 200, 1),
+                    generate_table(4, 1, 222, 300, 1),
+                ],
+                total_file_size: 0,
+                sub_levels: vec![],
+            },
+            Level {
+                level_idx: 2,
+                level_type: LevelType::Nonoverlapping as i32,
+                table_infos: vec![
+                    generate_table(5, 1, 0, 150, 1),
+                    generate_table(6, 1, 151, 201, 1),
+                    generate_table(7, 1, 501, 800, 1),
+                ],
+                total_file_size: 0,
+                sub_levels: vec![],
+            },
+        ]
+    }
+
+    fn ids(level: &Level) -> Vec<u64> {
+        level.table_infos.iter().map(|table| table.id).collect()
+    }
+
+    #[test]
+    fn test_manual_compaction_picker() {
+        let levels = generate_levels();
+        let mut level_handlers = vec![
+            LevelHandler::new(0),
+            LevelHandler::new(1),
+            LevelHandler::new(2),
+        ];
+
+        // L1 -> L2, only SSTs overlapping with the key range.
+        let option = ManualCompactionOption {
+            key_range: KeyRange::new(
+                iterator_test_key_of_epoch(1, 50, 1),
+                iterator_test_key_of_epoch(1, 150, 1),
+            ),
+            level: 1,
+        };
+        let picker =
+            ManualCompactionPicker::new(0, option, Arc::new(RangeOverlapStrategy::default()));
+        let ret = picker
+            .pick_compaction(&levels, &mut level_handlers)
+            .unwrap();
+        assert_eq!(ret.select_level.level_idx, 1);
+        assert_eq!(ret.target_level.level_idx, 2);
+        assert_eq!(ids(&ret.select_level), vec![2, 3]);
+        assert_eq!(ids(&ret.target_level), vec![5, 6]);
+
+        // The whole L0 is compacted, but the SSTs in L1 are being compacted.
+        let picker = ManualCompactionPicker::new(
+            1,
+            ManualCompactionOption::default(),
+            Arc::new(RangeOverlapStrategy::default()),
+        );
+        assert!(picker
+            .pick_compaction(&levels, &mut level_handlers)
+            .is_none());
+        level_handlers[1].remove_task(0);
+        level_handlers[2].remove_task(0);
+        let ret = picker
+            .pick_compaction(&levels, &mut level_handlers)
+            .unwrap();
+        assert_eq!(ids(&ret.select_level), vec![0, 1]);
+        assert_eq!(ids(&ret.target_level), vec![2, 3, 4]);
+
+        // The bottommost level is compacted in place.
+        let option = ManualCompactionOption {
+            level: 2,
+            ..Default::default()
+        };
+        let picker =
+            ManualCompactionPicker::new(2, option, Arc::new(RangeOverlapStrategy::default()));
+        let ret = picker
+            .pick_compaction(&levels, &mut level_handlers)
+            .unwrap();
+        assert_eq!(ret.select_level.level_idx, 2);
+        assert_eq!(ret.target_level.level_idx, 2);
+        assert_eq!(ids(&ret.select_level), vec![5, 6, 7]);
+        assert!(ret.target_level.table_infos.is_empty());
+    }
+}
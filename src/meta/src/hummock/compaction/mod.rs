@@ -14,10 +14,12 @@
 
 mod compaction_picker;
 mod level_selector;
+mod manual_compaction_picker;
 mod overlap_strategy;
+mod read_statistics;
 mod tier_compaction_picker;
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::fmt::{Debug, Formatter};
 use std::io::Cursor;
 use std::sync::Arc;
@@ -25,6 +27,9 @@ use std::sync::Arc;
 use itertools::Itertools;
 use prost::Message;
 use risingwave_common::error::Result;
+use risingwave_hummock_sdk::compaction_group::hummock_version_ext::HummockVersionExt;
+use risingwave_hummock_sdk::compaction_group::{CompactionGroupId, StaticCompactionGroupId};
+use risingwave_hummock_sdk::level::replace_l0_ssts;
 use risingwave_hummock_sdk::prost_key_range::KeyRangeExt;
 use risingwave_hummock_sdk::{HummockCompactionTaskId, HummockEpoch};
 use risingwave_pb::hummock::{
@@ -32,9 +37,11 @@ use risingwave_pb::hummock::{
 };
 
 use crate::hummock::compaction::level_selector::{DynamicLevelSelector, LevelSelector};
+pub use crate::hummock::compaction::manual_compaction_picker::ManualCompactionOption;
 use crate::hummock::compaction::overlap_strategy::{
     HashStrategy, OverlapStrategy, RangeOverlapStrategy,
 };
+pub use crate::hummock::compaction::read_statistics::ReadStatistics;
 use crate::hummock::compaction::CompactionMode::{ConsistentHashMode, RangeMode};
 use crate::hummock::level_handler::LevelHandler;
 use crate::hummock::model::HUMMOCK_DEFAULT_CF_NAME;
@@ -42,12 +49,14 @@ use crate::model::Transactional;
 use crate::storage;
 use crate::storage::{MetaStore, Transaction};
 
-/// Hummock `compact_status` key
-/// `cf(hummock_default)`: `hummock_compact_status_key` -> `CompactStatus`
+/// Hummock `compact_status` key prefix
+/// `cf(hummock_default)`: `hummock_compact_status_key`_`compaction_group_id` -> `CompactStatus`
 pub(crate) const HUMMOCK_COMPACT_STATUS_KEY: &str = "compact_status";
 const DEFAULT_MAX_COMPACTION_BYTES: u64 = 4 * 1024 * 1024 * 1024; // 4GB
 const DEFAULT_MIN_COMPACTION_BYTES: u64 = 128 * 1024 * 1024; // 128MB
 const DEFAULT_MAX_BYTES_FOR_LEVEL_BASE: u64 = 1024 * 1024 * 1024; // 1GB
+                                                                  // An SST is compacted once its reads have merged this many other SSTs or batches.
+const DEFAULT_READ_COMPACTION_TRIGGER: u64 = 100_000;
 
 // decrease this configure when the generation of checkpoint barrier is not frequent.
 const DEFAULT_TIER_COMPACT_TRIGGER_NUMBER: usize = 16;
@@ -55,6 +64,7 @@ const DEFAULT_TIER_COMPACT_TRIGGER_NUMBER: usize = 16;
 const MAX_LEVEL: usize = 6;
 
 pub struct CompactStatus {
+    compaction_group_id: CompactionGroupId,
     pub(crate) level_handlers: Vec<LevelHandler>,
     compaction_selector: Arc<dyn LevelSelector>,
 }
@@ -62,6 +72,7 @@ pub struct CompactStatus {
 impl Debug for CompactStatus {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("CompactStatus")
+            .field("compaction_group_id", &self.compaction_group_id)
             .field("level_handlers", &self.level_handlers)
             .field("compaction_selector", &self.compaction_selector.name())
             .finish()
@@ -70,7 +81,8 @@ impl Debug for CompactStatus {
 
 impl PartialEq for CompactStatus {
     fn eq(&self, other: &Self) -> bool {
-        self.level_handlers.eq(&other.level_handlers)
+        self.compaction_group_id == other.compaction_group_id
+            && self.level_handlers.eq(&other.level_handlers)
             && self.compaction_selector.name() == other.compaction_selector.name()
     }
 }
@@ -78,6 +90,7 @@ impl PartialEq for CompactStatus {
 impl Clone for CompactStatus {
     fn clone(&self) -> Self {
         Self {
+            compaction_group_id: self.compaction_group_id,
             level_handlers: self.level_handlers.clone(),
             compaction_selector: self.compaction_selector.clone(),
         }
@@ -105,6 +118,7 @@ pub struct CompactionConfig {
     pub min_compaction_bytes: u64,
     pub level0_tigger_file_numer: usize,
     pub level0_tier_compact_file_number: usize,
+    pub read_compaction_trigger: u64,
     pub compaction_mode: CompactionMode,
 }
 
@@ -118,13 +132,17 @@ impl Default for CompactionConfig {
             min_compaction_bytes: DEFAULT_MIN_COMPACTION_BYTES,
             level0_tigger_file_numer: DEFAULT_TIER_COMPACT_TRIGGER_NUMBER * 2,
             level0_tier_compact_file_number: DEFAULT_TIER_COMPACT_TRIGGER_NUMBER,
+            read_compaction_trigger: DEFAULT_READ_COMPACTION_TRIGGER,
             compaction_mode: ConsistentHashMode,
         }
     }
 }
 
 impl CompactStatus {
-    pub fn new(config: Arc<CompactionConfig>) -> CompactStatus {
+    pub fn new(
+        compaction_group_id: CompactionGroupId,
+        config: Arc<CompactionConfig>,
+    ) -> CompactStatus {
         let mut level_handlers = vec![];
         for level in 0..=config.max_level {
             level_handlers.push(LevelHandler::new(level as u32));
@@ -134,6 +152,7 @@ impl CompactStatus {
             ConsistentHashMode => Arc::new(HashStrategy::default()),
         };
         CompactStatus {
+            compaction_group_id,
             level_handlers,
             // TODO: create selector and overlap strategy by configure.
             compaction_selector: Arc::new(DynamicLevelSelector::new(config, overlap_strategy)),
@@ -144,13 +163,21 @@ impl CompactStatus {
         HUMMOCK_DEFAULT_CF_NAME
     }
 
-    fn key() -> &'static str {
-        HUMMOCK_COMPACT_STATUS_KEY
+    fn key(&self) -> String {
+        format!(
+            "{}_{}",
+            HUMMOCK_COMPACT_STATUS_KEY,
+            u64::from(self.compaction_group_id)
+        )
+    }
+
+    pub fn compaction_group_id(&self) -> CompactionGroupId {
+        self.compaction_group_id
     }
 
     pub async fn load<S: MetaStore>(&mut self, meta_store: &S) -> Result<()> {
         match meta_store
-            .get_cf(CompactStatus::cf_name(), CompactStatus::key().as_bytes())
+            .get_cf(CompactStatus::cf_name(), self.key().as_bytes())
             .await
             .map(|v| risingwave_pb::hummock::CompactStatus::decode(&mut Cursor::new(v)).unwrap())
         {
@@ -171,16 +198,40 @@ impl CompactStatus {
         &mut self,
         levels: &[Level],
         task_id: HummockCompactionTaskId,
+        read_statistics: &ReadStatistics,
     ) -> Option<CompactTask> {
         // When we compact the files, we must make the result of compaction meet the following
         // conditions, for any user key, the epoch of it in the file existing in the lower
         // layer must be larger.
 
-        let ret = match self.pick_compaction(levels, task_id) {
+        let ret = match self.pick_compaction(levels, task_id, read_statistics) {
             Some(ret) => ret,
             None => return None,
         };
+        Some(self.create_compact_task(ret, task_id))
+    }
 
+    /// Picks the SSTs selected by `option` to compact, regardless of the scores of the levels.
+    pub fn manual_get_compact_task(
+        &mut self,
+        levels: &[Level],
+        task_id: HummockCompactionTaskId,
+        option: ManualCompactionOption,
+    ) -> Option<CompactTask> {
+        let ret = self.compaction_selector.manual_pick_compaction(
+            task_id,
+            levels,
+            &mut self.level_handlers,
+            option,
+        )?;
+        Some(self.create_compact_task(ret, task_id))
+    }
+
+    fn create_compact_task(
+        &self,
+        ret: SearchResult,
+        task_id: HummockCompactionTaskId,
+    ) -> CompactTask {
         let select_level_id = ret.select_level.level_idx;
         let target_level_id = ret.target_level.level_idx;
 
@@ -189,7 +240,7 @@ impl CompactStatus {
         } else {
             ret.split_ranges
         };
-        let compact_task = CompactTask {
+        CompactTask {
             input_ssts: vec![ret.select_level, ret.target_level],
             splits,
             watermark: HummockEpoch::MAX,
@@ -220,17 +271,26 @@ impl CompactStatus {
             // TODO: fill with compaction group info
             prefix_pairs: vec![],
             vnode_mappings: vec![],
-        };
-        Some(compact_task)
+            compaction_group_id: self.compaction_group_id.into(),
+            // Filled by the hummock manager.
+            gc_blob_ids: vec![],
+            dropped_table_ids: vec![],
+            table_options: HashMap::default(),
+        }
     }
 
     fn pick_compaction(
         &mut self,
         levels: &[Level],
         task_id: HummockCompactionTaskId,
+        read_statistics: &ReadStatistics,
     ) -> Option<SearchResult> {
-        self.compaction_selector
-            .pick_compaction(task_id, levels, &mut self.level_handlers)
+        self.compaction_selector.pick_compaction(
+            task_id,
+            levels,
+            &mut self.level_handlers,
+            read_statistics,
+        )
     }
 
     /// Declares a task is either finished or canceled.
@@ -266,48 +326,39 @@ impl CompactStatus {
                 removed_table.insert(table.id);
             }
         }
+        let levels =
+            new_version.get_compaction_group_levels_mut(compact_task.compaction_group_id.into());
         if compact_task.target_level == 0 {
             assert_eq!(compact_task.input_ssts[0].level_idx, 0);
-            let mut new_table_infos = vec![];
-            let mut find_remove_position = false;
-            let mut new_total_file_size = 0;
-            for (idx, table) in new_version.levels[0].table_infos.iter().enumerate() {
-                if !removed_table.contains(&table.id) {
-                    new_table_infos.push(new_version.levels[0].table_infos[idx].clone());
-                    new_total_file_size += table.file_size;
-                } else if !find_remove_position {
-                    new_total_file_size += compact_task
-                        .sorted_output_ssts
-                        .iter()
-                        .map(|sst| sst.file_size)
-                        .sum::<u64>();
-                    new_table_infos.extend(compact_task.sorted_output_ssts.clone());
-                    find_remove_position = true;
-                }
-            }
-            new_version.levels[compact_task.target_level as usize].table_infos = new_table_infos;
-            new_version.levels[compact_task.target_level as usize].total_file_size =
-                new_total_file_size;
+            replace_l0_ssts(
+                &mut levels[0],
+                &removed_table,
+                compact_task.sorted_output_ssts.clone(),
+            );
         } else {
             for input_level in &compact_task.input_ssts {
-                new_version.levels[input_level.level_idx as usize].total_file_size -= input_level
+                if input_level.level_idx == 0 {
+                    replace_l0_ssts(&mut levels[0], &removed_table, vec![]);
+                    continue;
+                }
+                levels[input_level.level_idx as usize].total_file_size -= input_level
                     .table_infos
                     .iter()
                     .map(|sst| sst.file_size)
                     .sum::<u64>();
-                new_version.levels[input_level.level_idx as usize]
+                levels[input_level.level_idx as usize]
                     .table_infos
                     .retain(|sst| !removed_table.contains(&sst.id));
             }
-            new_version.levels[compact_task.target_level as usize].total_file_size += compact_task
+            levels[compact_task.target_level as usize].total_file_size += compact_task
                 .sorted_output_ssts
                 .iter()
                 .map(|sst| sst.file_size)
                 .sum::<u64>();
-            new_version.levels[compact_task.target_level as usize]
+            levels[compact_task.target_level as usize]
                 .table_infos
                 .extend(compact_task.sorted_output_ssts.clone());
-            new_version.levels[compact_task.target_level as usize]
+            levels[compact_task.target_level as usize]
                 .table_infos
                 .sort_by(|sst1, sst2| {
                     let a = sst1.key_range.as_ref().unwrap();
@@ -323,7 +374,7 @@ impl Transactional for CompactStatus {
     fn upsert_in_transaction(&self, trx: &mut Transaction) -> Result<()> {
         trx.put(
             CompactStatus::cf_name().to_string(),
-            CompactStatus::key().as_bytes().to_vec(),
+            self.key().as_bytes().to_vec(),
             risingwave_pb::hummock::CompactStatus::from(self).encode_to_vec(),
         );
         Ok(())
@@ -332,7 +383,7 @@ impl Transactional for CompactStatus {
     fn delete_in_transaction(&self, trx: &mut Transaction) -> Result<()> {
         trx.delete(
             CompactStatus::cf_name().to_string(),
-            CompactStatus::key().as_bytes().to_vec(),
+            self.key().as_bytes().to_vec(),
         );
         Ok(())
     }
@@ -340,7 +391,10 @@ impl Transactional for CompactStatus {
 
 impl Default for CompactStatus {
     fn default() -> Self {
-        Self::new(Arc::new(CompactionConfig::default()))
+        Self::new(
+            StaticCompactionGroupId::StateDefault.into(),
+            Arc::new(CompactionConfig::default()),
+        )
     }
 }
 
@@ -348,6 +402,7 @@ impl From<&CompactStatus> for risingwave_pb::hummock::CompactStatus {
     fn from(status: &CompactStatus) -> Self {
         risingwave_pb::hummock::CompactStatus {
             level_handlers: status.level_handlers.iter().map_into().collect(),
+            compaction_group_id: status.compaction_group_id.into(),
         }
     }
 }
@@ -355,6 +410,7 @@ impl From<&CompactStatus> for risingwave_pb::hummock::CompactStatus {
 impl From<&risingwave_pb::hummock::CompactStatus> for CompactStatus {
     fn from(status: &risingwave_pb::hummock::CompactStatus) -> Self {
         CompactStatus {
+            compaction_group_id: status.compaction_group_id.into(),
             level_handlers: status.level_handlers.iter().map_into().collect(),
             compaction_selector: Arc::new(DynamicLevelSelector::default()),
         }
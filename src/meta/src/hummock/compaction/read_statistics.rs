@@ -0,0 +1,62 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+
+use risingwave_hummock_sdk::HummockSSTableId;
+use risingwave_pb::hummock::SstReadStatistics;
+
+/// Reads of an SST reported by the compute nodes since the SST was created.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct SstReadStat {
+    pub read_count: u64,
+    /// The total number of SSTs and batches merged by the reads, including the SST itself.
+    pub merge_width: u64,
+}
+
+impl SstReadStat {
+    /// The number of other SSTs and batches merged by the reads of the SST, which may be reduced
+    /// by compacting the SST with the SSTs overlapping with it.
+    pub fn overlapped_reads(&self) -> u64 {
+        self.merge_width.saturating_sub(self.read_count)
+    }
+}
+
+/// Read statistics of the SSTs, which are used to compact the SSTs slowing down reads earlier than
+/// their sizes would trigger. They are kept in memory only, and dropped once the SSTs are
+/// compacted.
+#[derive(Clone, Debug, Default)]
+pub struct ReadStatistics {
+    ssts: HashMap<HummockSSTableId, SstReadStat>,
+}
+
+impl ReadStatistics {
+    pub fn add(&mut self, stats: impl IntoIterator<Item = SstReadStatistics>) {
+        for stat in stats {
+            let entry = self.ssts.entry(stat.sst_id).or_default();
+            entry.read_count += stat.read_count;
+            entry.merge_width += stat.merge_width;
+        }
+    }
+
+    pub fn get(&self, sst_id: HummockSSTableId) -> SstReadStat {
+        self.ssts.get(&sst_id).copied().unwrap_or_default()
+    }
+
+    pub fn remove(&mut self, sst_ids: impl IntoIterator<Item = HummockSSTableId>) {
+        for sst_id in sst_ids {
+            self.ssts.remove(&sst_id);
+        }
+    }
+}
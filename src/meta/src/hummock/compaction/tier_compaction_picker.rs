@@ -15,7 +15,9 @@
 use std::collections::HashSet;
 use std::sync::Arc;
 
+use itertools::Itertools;
 use risingwave_hummock_sdk::key::{user_key, FullKey};
+use risingwave_hummock_sdk::level::{sst_runs, SstRun};
 use risingwave_hummock_sdk::prost_key_range::KeyRangeExt;
 use risingwave_hummock_sdk::HummockEpoch;
 use risingwave_pb::hummock::{KeyRange, Level, LevelType, SstableInfo};
@@ -48,55 +50,66 @@ impl CompactionPicker for TierCompactionPicker {
         levels: &[Level],
         level_handlers: &mut [LevelHandler],
     ) -> Option<SearchResult> {
+        // Pick consecutive sub-levels, so that the output can replace them as a new sub-level.
+        let sub_levels = sst_runs(&levels[0]);
+        let is_pending = |run: &SstRun<'_>| {
+            run.table_infos
+                .iter()
+                .any(|table| level_handlers[0].is_pending_compact(&table.id))
+        };
         let mut idx = 0;
-        while idx < levels[0].table_infos.len() {
-            let table = &levels[0].table_infos[idx];
-            if level_handlers[0].is_pending_compact(&table.id) {
+        while idx < sub_levels.len() {
+            let sub_level = &sub_levels[idx];
+            if is_pending(sub_level) {
                 idx += 1;
                 continue;
             }
-            let mut compaction_bytes = table.file_size;
-            let mut select_level_inputs = vec![table.clone()];
-            if table.file_size > self.config.min_compaction_bytes {
-                // only merge small file until we support sub-level.
+            let mut compaction_bytes = sub_level.total_file_size();
+            let mut select_sub_levels = vec![sub_level];
+            if compaction_bytes > self.config.min_compaction_bytes {
+                // only merge small sub-levels.
                 idx += 1;
                 continue;
             }
 
             let mut next_offset = idx + 1;
 
-            for other in &levels[0].table_infos[idx + 1..] {
-                if level_handlers[0].is_pending_compact(&other.id) {
+            for other in &sub_levels[idx + 1..] {
+                if is_pending(other) {
                     break;
                 }
                 // no need to trigger a bigger compaction
                 if compaction_bytes >= self.config.min_compaction_bytes {
                     break;
                 }
-                compaction_bytes += other.file_size;
-                select_level_inputs.push(other.clone());
+                compaction_bytes += other.total_file_size();
+                select_sub_levels.push(other);
                 next_offset += 1;
             }
 
-            // to make compaction tree balance, we do not need to merge a large file and a few small
-            // files.
-            while let Some(first) = select_level_inputs.first() {
-                if first.file_size * 2 > compaction_bytes
-                    && select_level_inputs.len() >= self.config.level0_tier_compact_file_number
+            // to make compaction tree balance, we do not need to merge a large sub-level and a few
+            // small ones.
+            while let Some(first) = select_sub_levels.first() {
+                if first.total_file_size() * 2 > compaction_bytes
+                    && select_sub_levels.len() >= self.config.level0_tier_compact_file_number
                     && compaction_bytes > MIN_COMPACTION_BYTES
                 {
-                    compaction_bytes -= first.file_size;
-                    select_level_inputs.remove(0);
+                    compaction_bytes -= first.total_file_size();
+                    select_sub_levels.remove(0);
                 } else {
                     break;
                 }
             }
 
-            if select_level_inputs.len() < self.config.level0_tier_compact_file_number {
+            if select_sub_levels.len() < self.config.level0_tier_compact_file_number {
                 idx = next_offset;
                 continue;
             }
 
+            let select_level_inputs = select_sub_levels
+                .iter()
+                .flat_map(|sub_level| sub_level.table_infos.iter().cloned())
+                .collect_vec();
             level_handlers[0].add_pending_task(self.compact_task_id, &select_level_inputs);
 
             return Some(SearchResult {
@@ -105,12 +118,14 @@ impl CompactionPicker for TierCompactionPicker {
                     level_type: LevelType::Overlapping as i32,
                     table_infos: select_level_inputs,
                     total_file_size: 0,
+                    sub_levels: vec![],
                 },
                 target_level: Level {
                     level_idx: 0,
                     level_type: LevelType::Overlapping as i32,
                     table_infos: vec![],
                     total_file_size: 0,
+                    sub_levels: vec![],
                 },
                 split_ranges: vec![],
             });
@@ -202,12 +217,14 @@ impl CompactionPicker for LevelCompactionPicker {
                 table_infos: select_level_inputs,
                 // no use
                 total_file_size: 0,
+                sub_levels: vec![],
             },
             target_level: Level {
                 level_idx: target_level as u32,
                 level_type: LevelType::Nonoverlapping as i32,
                 table_infos: target_level_inputs,
                 total_file_size: 0,
+                sub_levels: vec![],
             },
             split_ranges: splits,
         })
@@ -379,12 +396,14 @@ pub mod tests {
             }),
             file_size: (right - left + 1) as u64,
             vnode_bitmaps: vec![],
+            blob_ids: vec![],
         }
     }
 
     fn create_compaction_picker_for_test() -> LevelCompactionPicker {
         let config = Arc::new(CompactionConfig {
             level0_tier_compact_file_number: 2,
+            read_compaction_trigger: u64::MAX,
             min_compaction_bytes: 0,
             ..Default::default()
         });
@@ -403,6 +422,7 @@ pub mod tests {
                     generate_table(4, 1, 112, 200, 2),
                 ],
                 total_file_size: 0,
+                sub_levels: vec![],
             },
             Level {
                 level_idx: 1,
@@ -414,6 +434,7 @@ pub mod tests {
                     generate_table(0, 1, 301, 400, 1),
                 ],
                 total_file_size: 0,
+                sub_levels: vec![],
             },
         ];
         let mut levels_handler = vec![LevelHandler::new(0), LevelHandler::new(1)];
@@ -495,6 +516,7 @@ pub mod tests {
 
         let config = Arc::new(CompactionConfig {
             level0_tier_compact_file_number: 2,
+            read_compaction_trigger: u64::MAX,
             min_compaction_bytes: 0,
             compaction_mode: CompactionMode::RangeMode,
             ..Default::default()
@@ -511,6 +533,7 @@ pub mod tests {
                     generate_table(2, 1, 400, 500, 2),
                 ],
                 total_file_size: 0,
+                sub_levels: vec![],
             },
             Level {
                 level_idx: 1,
@@ -524,6 +547,7 @@ pub mod tests {
                     generate_table(6, 1, 1000, 2000, 1),
                 ],
                 total_file_size: 0,
+                sub_levels: vec![],
             },
         ];
 
@@ -571,12 +595,14 @@ pub mod tests {
                     generate_table(2, 1, 450, 500, 2),
                 ],
                 total_file_size: 0,
+                sub_levels: vec![],
             },
             Level {
                 level_idx: 1,
                 level_type: LevelType::Nonoverlapping as i32,
                 table_infos: vec![],
                 total_file_size: 0,
+                sub_levels: vec![],
             },
         ];
 
@@ -609,12 +635,14 @@ pub mod tests {
                 level_type: LevelType::Overlapping as i32,
                 table_infos: vec![generate_table(1, 1, 200, 250, 2)],
                 total_file_size: 0,
+                sub_levels: vec![],
             },
             Level {
                 level_idx: 1,
                 level_type: LevelType::Nonoverlapping as i32,
                 table_infos: vec![generate_table(2, 1, 150, 300, 2)],
                 total_file_size: 0,
+                sub_levels: vec![],
             },
         ];
 
@@ -667,12 +695,14 @@ pub mod tests {
                     generate_table(2, 1, 190, 250, 2),
                 ],
                 total_file_size: 0,
+                sub_levels: vec![],
             },
             Level {
                 level_idx: 1,
                 level_type: LevelType::Nonoverlapping as i32,
                 table_infos: vec![generate_table(3, 1, 200, 300, 2)],
                 total_file_size: 0,
+                sub_levels: vec![],
             },
         ];
 
@@ -707,6 +737,7 @@ pub mod tests {
                     generate_table(3, 1, 200, 300, 2),
                 ],
                 total_file_size: 0,
+                sub_levels: vec![],
             },
             Level {
                 level_idx: 1,
@@ -717,6 +748,7 @@ pub mod tests {
                     generate_table(6, 1, 300, 600, 2),
                 ],
                 total_file_size: 0,
+                sub_levels: vec![],
             },
         ];
         let mut levels_handler = vec![LevelHandler::new(0), LevelHandler::new(1)];
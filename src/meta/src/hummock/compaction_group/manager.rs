@@ -0,0 +1,364 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::Arc;
+
+use itertools::Itertools;
+use risingwave_hummock_sdk::compaction_group::{
+    CompactionGroupId, Prefix, StaticCompactionGroupId,
+};
+use risingwave_pb::hummock::{SstableInfo, TableOption};
+use tokio::sync::RwLock;
+
+use crate::hummock::compaction_group::CompactionGroup;
+use crate::hummock::error::{Error, Result};
+use crate::manager::MetaSrvEnv;
+use crate::model::{MetadataModel, Transactional, ValTransaction, VarTransaction};
+use crate::storage::{MetaStore, Transaction};
+
+pub type CompactionGroupManagerRef<S> = Arc<CompactionGroupManager<S>>;
+
+/// `CompactionGroupManager` manages the compaction groups, and which compaction group the state of
+/// each table goes to.
+pub struct CompactionGroupManager<S: MetaStore> {
+    env: MetaSrvEnv<S>,
+    inner: RwLock<CompactionGroupManagerInner>,
+}
+
+impl<S: MetaStore> CompactionGroupManager<S> {
+    pub async fn new(env: MetaSrvEnv<S>) -> Result<Self> {
+        let instance = Self {
+            env,
+            inner: RwLock::new(Default::default()),
+        };
+        instance
+            .inner
+            .write()
+            .await
+            .load(instance.env.meta_store())
+            .await?;
+        Ok(instance)
+    }
+
+    pub async fn compaction_groups(&self) -> Vec<CompactionGroup> {
+        self.inner
+            .read()
+            .await
+            .compaction_groups
+            .values()
+            .cloned()
+            .collect_vec()
+    }
+
+    pub async fn compaction_group_ids(&self) -> Vec<CompactionGroupId> {
+        self.inner
+            .read()
+            .await
+            .compaction_groups
+            .keys()
+            .cloned()
+            .collect_vec()
+    }
+
+    /// Returns the compaction group that `sst` goes to, according to the tables whose keys are in
+    /// it. Keys of unregistered tables, e.g. dropped ones, go to the default compaction group.
+    pub async fn compaction_group_of_sst(&self, sst: &SstableInfo) -> Result<CompactionGroupId> {
+        let inner = self.inner.read().await;
+        // The vnode bitmaps cover every table with keys in the SST.
+        let group_ids = sst
+            .vnode_bitmaps
+            .iter()
+            .filter_map(|bitmap| inner.index.get(&Prefix::from(bitmap.table_id)).cloned())
+            .collect::<HashSet<_>>();
+        match group_ids.len() {
+            0 => Ok(StaticCompactionGroupId::StateDefault.into()),
+            1 => Ok(group_ids.into_iter().next().unwrap()),
+            _ => Err(Error::InternalError(format!(
+                "SST {} spans compaction groups {:?}",
+                sst.id, group_ids
+            ))),
+        }
+    }
+
+    /// Returns the ids of the dropped tables whose state may still be in the compaction group.
+    pub async fn dropped_table_ids(&self, compaction_group_id: CompactionGroupId) -> Vec<u32> {
+        self.inner
+            .read()
+            .await
+            .compaction_groups
+            .get(&compaction_group_id)
+            .map(|compaction_group| {
+                compaction_group
+                    .dropped_prefixes()
+                    .iter()
+                    .map(|prefix| u32::from(*prefix))
+                    .sorted()
+                    .collect_vec()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Returns the options of the member tables of the compaction group other than the default
+    /// ones, by their ids.
+    pub async fn table_options(
+        &self,
+        compaction_group_id: CompactionGroupId,
+    ) -> HashMap<u32, TableOption> {
+        self.inner
+            .read()
+            .await
+            .compaction_groups
+            .get(&compaction_group_id)
+            .map(|compaction_group| {
+                compaction_group
+                    .table_options()
+                    .iter()
+                    .map(|(prefix, option)| (u32::from(*prefix), option.clone()))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Adds tables with their options to compaction groups. Tables must be registered before any
+    /// of their state is written.
+    pub async fn register_table_ids(
+        &self,
+        pairs: &[(Prefix, CompactionGroupId, TableOption)],
+    ) -> Result<()> {
+        let mut inner = self.inner.write().await;
+        let inner = &mut *inner;
+        let mut compaction_groups = VarTransaction::new(&mut inner.compaction_groups);
+        for (prefix, compaction_group_id, table_option) in pairs {
+            let compaction_group =
+                compaction_groups
+                    .get_mut(compaction_group_id)
+                    .ok_or_else(|| {
+                        Error::InternalError(format!(
+                            "compaction group {:?} not found",
+                            compaction_group_id
+                        ))
+                    })?;
+            compaction_group.member_prefixes.insert(*prefix);
+            compaction_group.dropped_prefixes.remove(prefix);
+            if *table_option != TableOption::default() {
+                compaction_group
+                    .table_options
+                    .insert(*prefix, table_option.clone());
+            }
+        }
+        let mut trx = Transaction::default();
+        compaction_groups.apply_to_txn(&mut trx)?;
+        self.env.meta_store().txn(trx).await?;
+        compaction_groups.commit();
+        for (prefix, compaction_group_id, _) in pairs {
+            inner.index.insert(*prefix, *compaction_group_id);
+        }
+        Ok(())
+    }
+
+    /// Removes tables from their compaction groups, after they are dropped. Their state is deleted
+    /// by the following compactions of the groups.
+    pub async fn unregister_table_ids(&self, prefixes: &[Prefix]) -> Result<()> {
+        let mut inner = self.inner.write().await;
+        let inner = &mut *inner;
+        let mut compaction_groups = VarTransaction::new(&mut inner.compaction_groups);
+        for prefix in prefixes {
+            if let Some(compaction_group_id) = inner.index.get(prefix) {
+                if let Some(compaction_group) = compaction_groups.get_mut(compaction_group_id) {
+                    compaction_group.member_prefixes.remove(prefix);
+                    compaction_group.dropped_prefixes.insert(*prefix);
+                    compaction_group.table_options.remove(prefix);
+                }
+            }
+        }
+        let mut trx = Transaction::default();
+        compaction_groups.apply_to_txn(&mut trx)?;
+        self.env.meta_store().txn(trx).await?;
+        compaction_groups.commit();
+        for prefix in prefixes {
+            inner.index.remove(prefix);
+        }
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+struct CompactionGroupManagerInner {
+    compaction_groups: BTreeMap<CompactionGroupId, CompactionGroup>,
+    /// The compaction group of each registered table.
+    index: BTreeMap<Prefix, CompactionGroupId>,
+}
+
+impl CompactionGroupManagerInner {
+    async fn load<S: MetaStore>(&mut self, meta_store: &S) -> Result<()> {
+        let compaction_groups = CompactionGroup::list(meta_store)
+            .await?
+            .into_iter()
+            .map(|compaction_group| (compaction_group.group_id(), compaction_group))
+            .collect::<BTreeMap<_, _>>();
+        if compaction_groups.is_empty() {
+            // Create the static compaction groups on the first start.
+            let mut trx = Transaction::default();
+            for compaction_group_id in StaticCompactionGroupId::all() {
+                let compaction_group = CompactionGroup::new(compaction_group_id.into());
+                compaction_group.upsert_in_transaction(&mut trx)?;
+                self.compaction_groups
+                    .insert(compaction_group.group_id(), compaction_group);
+            }
+            meta_store.txn(trx).await?;
+        } else {
+            self.compaction_groups = compaction_groups;
+        }
+        self.index = self
+            .compaction_groups
+            .values()
+            .flat_map(|compaction_group| {
+                compaction_group
+                    .member_prefixes()
+                    .iter()
+                    .map(|prefix| (*prefix, compaction_group.group_id()))
+            })
+            .collect();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_pb::common::VNodeBitmap;
+
+    use super::*;
+
+    fn sst_of_tables(table_ids: &[u32]) -> SstableInfo {
+        SstableInfo {
+            id: 1,
+            key_range: None,
+            file_size: 1,
+            vnode_bitmaps: table_ids
+                .iter()
+                .map(|table_id| VNodeBitmap {
+                    table_id: *table_id,
+                    bitmap: vec![],
+                })
+                .collect(),
+            blob_ids: vec![],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_compaction_group_manager() {
+        let env = MetaSrvEnv::for_test().await;
+        let manager = CompactionGroupManager::new(env.clone()).await.unwrap();
+        assert_eq!(
+            manager.compaction_group_ids().await,
+            vec![
+                StaticCompactionGroupId::StateDefault.into(),
+                StaticCompactionGroupId::MaterializedView.into()
+            ]
+        );
+
+        manager
+            .register_table_ids(&[
+                (
+                    1.into(),
+                    StaticCompactionGroupId::MaterializedView.into(),
+                    TableOption {
+                        retention_seconds: 60,
+                    },
+                ),
+                (
+                    2.into(),
+                    StaticCompactionGroupId::StateDefault.into(),
+                    TableOption::default(),
+                ),
+            ])
+            .await
+            .unwrap();
+        assert_eq!(
+            manager
+                .compaction_group_of_sst(&sst_of_tables(&[1]))
+                .await
+                .unwrap(),
+            StaticCompactionGroupId::MaterializedView.into()
+        );
+        assert_eq!(
+            manager
+                .compaction_group_of_sst(&sst_of_tables(&[2, 3]))
+                .await
+                .unwrap(),
+            StaticCompactionGroupId::StateDefault.into()
+        );
+        assert!(manager
+            .compaction_group_of_sst(&sst_of_tables(&[1, 2]))
+            .await
+            .is_err());
+
+        // The registration is persisted.
+        let manager = CompactionGroupManager::new(env.clone()).await.unwrap();
+        assert_eq!(
+            manager
+                .compaction_group_of_sst(&sst_of_tables(&[1]))
+                .await
+                .unwrap(),
+            StaticCompactionGroupId::MaterializedView.into()
+        );
+        assert_eq!(
+            manager
+                .table_options(StaticCompactionGroupId::MaterializedView.into())
+                .await,
+            HashMap::from([(
+                1,
+                TableOption {
+                    retention_seconds: 60
+                }
+            )])
+        );
+        assert!(manager
+            .table_options(StaticCompactionGroupId::StateDefault.into())
+            .await
+            .is_empty());
+
+        manager.unregister_table_ids(&[1.into()]).await.unwrap();
+        assert_eq!(
+            manager
+                .compaction_group_of_sst(&sst_of_tables(&[1, 2]))
+                .await
+                .unwrap(),
+            StaticCompactionGroupId::StateDefault.into()
+        );
+        // The state of the dropped table is left to compaction.
+        assert!(manager
+            .table_options(StaticCompactionGroupId::MaterializedView.into())
+            .await
+            .is_empty());
+        assert_eq!(
+            manager
+                .dropped_table_ids(StaticCompactionGroupId::MaterializedView.into())
+                .await,
+            vec![1]
+        );
+        let manager = CompactionGroupManager::new(env.clone()).await.unwrap();
+        assert_eq!(
+            manager
+                .dropped_table_ids(StaticCompactionGroupId::MaterializedView.into())
+                .await,
+            vec![1]
+        );
+        assert!(manager
+            .dropped_table_ids(StaticCompactionGroupId::StateDefault.into())
+            .await
+            .is_empty());
+    }
+}
@@ -0,0 +1,106 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+pub mod manager;
+
+use std::collections::{HashMap, HashSet};
+
+use risingwave_hummock_sdk::compaction_group::{CompactionGroupId, Prefix};
+use risingwave_pb::hummock::TableOption;
+
+/// A compaction group holds the state of a set of tables. SSTs never span compaction groups, and
+/// each compaction group has its own levels, which are compacted independently.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompactionGroup {
+    group_id: CompactionGroupId,
+    member_prefixes: HashSet<Prefix>,
+    /// The dropped tables whose state may still be in the compaction group, which is deleted by
+    /// compaction.
+    dropped_prefixes: HashSet<Prefix>,
+    /// Options of the member tables other than the default ones, e.g. their retention.
+    table_options: HashMap<Prefix, TableOption>,
+}
+
+impl CompactionGroup {
+    pub fn new(group_id: CompactionGroupId) -> Self {
+        Self {
+            group_id,
+            member_prefixes: Default::default(),
+            dropped_prefixes: Default::default(),
+            table_options: Default::default(),
+        }
+    }
+
+    pub fn group_id(&self) -> CompactionGroupId {
+        self.group_id
+    }
+
+    pub fn member_prefixes(&self) -> &HashSet<Prefix> {
+        &self.member_prefixes
+    }
+
+    pub fn dropped_prefixes(&self) -> &HashSet<Prefix> {
+        &self.dropped_prefixes
+    }
+
+    pub fn table_options(&self) -> &HashMap<Prefix, TableOption> {
+        &self.table_options
+    }
+}
+
+impl From<&risingwave_pb::hummock::CompactionGroup> for CompactionGroup {
+    fn from(compaction_group: &risingwave_pb::hummock::CompactionGroup) -> Self {
+        Self {
+            group_id: compaction_group.id.into(),
+            member_prefixes: compaction_group
+                .member_table_ids
+                .iter()
+                .map(|table_id| Prefix::from(*table_id))
+                .collect(),
+            dropped_prefixes: compaction_group
+                .dropped_table_ids
+                .iter()
+                .map(|table_id| Prefix::from(*table_id))
+                .collect(),
+            table_options: compaction_group
+                .table_options
+                .iter()
+                .map(|(table_id, option)| (Prefix::from(*table_id), option.clone()))
+                .collect(),
+        }
+    }
+}
+
+impl From<&CompactionGroup> for risingwave_pb::hummock::CompactionGroup {
+    fn from(compaction_group: &CompactionGroup) -> Self {
+        let to_table_ids = |prefixes: &HashSet<Prefix>| {
+            let mut table_ids = prefixes
+                .iter()
+                .map(|prefix| u32::from(*prefix))
+                .collect::<Vec<_>>();
+            table_ids.sort_unstable();
+            table_ids
+        };
+        Self {
+            id: compaction_group.group_id.into(),
+            member_table_ids: to_table_ids(&compaction_group.member_prefixes),
+            dropped_table_ids: to_table_ids(&compaction_group.dropped_prefixes),
+            table_options: compaction_group
+                .table_options
+                .iter()
+                .map(|(prefix, option)| (u32::from(*prefix), option.clone()))
+                .collect(),
+        }
+    }
+}
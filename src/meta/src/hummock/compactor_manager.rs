@@ -131,6 +131,9 @@ impl CompactorManager {
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashMap;
+
+    use risingwave_hummock_sdk::compaction_group::StaticCompactionGroupId;
     use risingwave_pb::hummock::{CompactMetrics, CompactTask, TableSetStatistics};
     use tokio::sync::mpsc::error::TryRecvError;
 
@@ -172,6 +175,10 @@ mod tests {
             task_status: false,
             prefix_pairs: vec![],
             vnode_mappings: vec![],
+            compaction_group_id: StaticCompactionGroupId::StateDefault.into(),
+            gc_blob_ids: vec![],
+            dropped_table_ids: vec![],
+            table_options: HashMap::default(),
         }
     }
 
@@ -238,7 +245,11 @@ mod tests {
             TryRecvError::Empty
         ));
 
-        let task = hummock_manager.get_compact_task().await.unwrap().unwrap();
+        let task = hummock_manager
+            .get_compact_task(StaticCompactionGroupId::StateDefault.into())
+            .await
+            .unwrap()
+            .unwrap();
         compactor.send_task(Some(task.clone()), None).await.unwrap();
         // Get a compact task.
         assert_eq!(
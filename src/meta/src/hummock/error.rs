@@ -13,7 +13,7 @@
 // limitations under the License.
 
 use risingwave_common::error::{ErrorCode, RwError, ToErrorStr};
-use risingwave_hummock_sdk::HummockContextId;
+use risingwave_hummock_sdk::{HummockContextId, HummockEpoch};
 use thiserror::Error;
 
 use crate::storage::meta_store;
@@ -32,6 +32,12 @@ pub enum Error {
     CompactorBusy(HummockContextId),
     #[error("compaction task {0} already assigned to compactor {1}")]
     CompactionTaskAlreadyAssigned(u64, HummockContextId),
+    #[error("epoch {epoch} is out of the readable range [{safe_epoch}, {max_committed_epoch}]")]
+    UnreadableEpoch {
+        epoch: HummockEpoch,
+        safe_epoch: HummockEpoch,
+        max_committed_epoch: HummockEpoch,
+    },
     #[error("internal error: {0}")]
     InternalError(String),
 }
@@ -66,6 +72,7 @@ impl From<Error> for ErrorCode {
             }
             Error::MetaStoreError(err) => ErrorCode::MetaError(err.to_error_str()),
             Error::InternalError(err) => ErrorCode::InternalError(err),
+            Error::UnreadableEpoch { .. } => ErrorCode::InvalidParameterValue(error.to_string()),
             Error::CompactorBusy(context_id) => {
                 ErrorCode::InternalError(format!("compactor {} is busy", context_id))
             }
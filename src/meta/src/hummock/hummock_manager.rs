@@ -12,7 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::{BTreeMap, HashSet, VecDeque};
+use std::collections::{BTreeMap, BTreeSet, HashSet, VecDeque};
 use std::future::Future;
 use std::ops::DerefMut;
 use std::sync::Arc;
@@ -23,22 +23,30 @@ use prost::Message;
 use risingwave_common::util::compress::compress_data;
 use risingwave_common::util::epoch::INVALID_EPOCH;
 use risingwave_hummock_sdk::compact::compact_task_to_string;
+use risingwave_hummock_sdk::compaction_group::hummock_version_ext::HummockVersionExt;
 use risingwave_hummock_sdk::compaction_group::CompactionGroupId;
+use risingwave_hummock_sdk::level::add_l0_sub_level;
 use risingwave_hummock_sdk::{
     get_remote_sst_id, HummockCompactionTaskId, HummockContextId, HummockEpoch, HummockRefCount,
     HummockSSTableId, HummockVersionId,
 };
 use risingwave_pb::common::ParallelUnitMapping;
+use risingwave_pb::hummock::hummock_version::Levels;
 use risingwave_pb::hummock::{
     CompactTask, CompactTaskAssignment, HummockPinnedSnapshot, HummockPinnedVersion,
-    HummockSnapshot, HummockStaleSstables, HummockVersion, Level, LevelType, SstableIdInfo,
-    SstableInfo,
+    HummockSnapshot, HummockStaleSstables, HummockVersion, Level, LevelType, SstReadStatistics,
+    SstableIdInfo, SstableInfo,
 };
 use risingwave_pb::meta::subscribe_response::{Info, Operation};
+use risingwave_pb::meta::{MetaSnapshot, SubscribeResponse};
+use tokio::sync::mpsc::UnboundedSender;
 use tokio::sync::RwLock;
 
-use crate::cluster::{ClusterManagerRef, META_NODE_ID};
-use crate::hummock::compaction::{CompactStatus, CompactionConfig};
+use crate::cluster::{ClusterManagerRef, WorkerKey, META_NODE_ID};
+use crate::hummock::compaction::{
+    CompactStatus, CompactionConfig, ManualCompactionOption, ReadStatistics,
+};
+use crate::hummock::compaction_group::manager::CompactionGroupManagerRef;
 use crate::hummock::compaction_scheduler::CompactionRequestChannelRef;
 use crate::hummock::error::{Error, Result};
 use crate::hummock::metrics_utils::{trigger_commit_stat, trigger_rw_stat, trigger_sst_stat};
@@ -46,8 +54,10 @@ use crate::hummock::model::{
     sstable_id_info, CurrentHummockVersionId, HummockPinnedSnapshotExt, HummockPinnedVersionExt,
     INVALID_TIMESTAMP,
 };
-use crate::manager::{IdCategory, MetaSrvEnv};
-use crate::model::{MetadataModel, ValTransaction, VarTransaction, Worker};
+use crate::manager::{IdCategory, MetaSrvEnv, Notification};
+use crate::model::{
+    BTreeMapEntryTransaction, MetadataModel, ValTransaction, VarTransaction, Worker,
+};
 use crate::rpc::metrics::MetaMetrics;
 use crate::storage::{MetaStore, Transaction};
 
@@ -69,17 +79,19 @@ pub struct HummockManager<S: MetaStore> {
 
     /// `compaction_scheduler` is used to schedule a compaction for specified CompactionGroupId
     compaction_scheduler: parking_lot::RwLock<Option<CompactionRequestChannelRef>>,
+    compaction_group_manager: CompactionGroupManagerRef<S>,
     config: Arc<CompactionConfig>,
 }
 
 pub type HummockManagerRef<S> = Arc<HummockManager<S>>;
 
 struct Compaction {
-    // TODO: `compact_status` will be managed per `compaction_groups` soon
-    compact_status: CompactStatus,
+    compaction_statuses: BTreeMap<CompactionGroupId, CompactStatus>,
     compact_task_assignment: BTreeMap<u64, CompactTaskAssignment>,
     /// Available compaction task ids for use
     next_task_ids: VecDeque<HummockCompactionTaskId>,
+    /// Reads of the SSTs reported by the compute nodes, which are not persisted.
+    read_statistics: ReadStatistics,
 }
 
 impl Compaction {
@@ -140,6 +152,9 @@ struct Versioning {
     pinned_snapshots: BTreeMap<HummockContextId, HummockPinnedSnapshot>,
     stale_sstables: BTreeMap<HummockVersionId, HummockStaleSstables>,
     sstable_id_infos: BTreeMap<HummockSSTableId, SstableIdInfo>,
+    /// The compute nodes subscribing to new versions, which are pinned for them and pushed to
+    /// them on creation. It's not persisted, since the nodes subscribe again after meta restarts.
+    version_subscribers: BTreeSet<HummockContextId>,
 }
 
 impl Versioning {
@@ -154,6 +169,23 @@ impl Versioning {
     }
 }
 
+/// Pins `version_id` for the compute nodes subscribing to new versions, before it's pushed to them.
+fn pin_version_for_subscribers(
+    pinned_versions: &mut VarTransaction<'_, BTreeMap<HummockContextId, HummockPinnedVersion>>,
+    version_subscribers: &BTreeSet<HummockContextId>,
+    version_id: HummockVersionId,
+) {
+    for context_id in version_subscribers {
+        pinned_versions
+            .entry(*context_id)
+            .or_insert_with(|| HummockPinnedVersion {
+                context_id: *context_id,
+                version_id: vec![],
+            })
+            .pin_version(version_id);
+    }
+}
+
 impl<S> HummockManager<S>
 where
     S: MetaStore,
@@ -163,6 +195,7 @@ where
         env: MetaSrvEnv<S>,
         cluster_manager: ClusterManagerRef<S>,
         metrics: Arc<MetaMetrics>,
+        compaction_group_manager: CompactionGroupManagerRef<S>,
         config: CompactionConfig,
     ) -> Result<HummockManager<S>> {
         let config = Arc::new(config);
@@ -175,15 +208,18 @@ where
                 pinned_snapshots: Default::default(),
                 stale_sstables: Default::default(),
                 sstable_id_infos: Default::default(),
+                version_subscribers: Default::default(),
             }),
             compaction: RwLock::new(Compaction {
-                compact_status: CompactStatus::new(config.clone()),
+                compaction_statuses: Default::default(),
                 compact_task_assignment: Default::default(),
                 next_task_ids: Default::default(),
+                read_statistics: Default::default(),
             }),
             metrics,
             cluster_manager,
             compaction_scheduler: parking_lot::RwLock::new(None),
+            compaction_group_manager,
             config,
         };
 
@@ -199,19 +235,31 @@ where
         env: MetaSrvEnv<S>,
         cluster_manager: ClusterManagerRef<S>,
         metrics: Arc<MetaMetrics>,
+        compaction_group_manager: CompactionGroupManagerRef<S>,
     ) -> Result<HummockManager<S>> {
         // TODO: load it from etcd or configuration file.
-        Self::new_with_config(env, cluster_manager, metrics, CompactionConfig::default()).await
+        Self::new_with_config(
+            env,
+            cluster_manager,
+            metrics,
+            compaction_group_manager,
+            CompactionConfig::default(),
+        )
+        .await
     }
 
     /// Load state from meta store.
     async fn load_meta_store_state(&self) -> Result<()> {
+        let compaction_group_ids = self.compaction_group_manager.compaction_group_ids().await;
         let mut compaction_guard = self.compaction.write().await;
 
-        compaction_guard
-            .compact_status
-            .load(self.env.meta_store())
-            .await?;
+        let mut compaction_statuses = BTreeMap::new();
+        for compaction_group_id in &compaction_group_ids {
+            let mut compact_status = CompactStatus::new(*compaction_group_id, self.config.clone());
+            compact_status.load(self.env.meta_store()).await?;
+            compaction_statuses.insert(*compaction_group_id, compact_status);
+        }
+        compaction_guard.compaction_statuses = compaction_statuses;
 
         compaction_guard.compact_task_assignment =
             CompactTaskAssignment::list(self.env.meta_store())
@@ -235,22 +283,30 @@ where
         if versioning_guard.hummock_versions.is_empty() {
             let mut init_version = HummockVersion {
                 id: versioning_guard.current_version_id.id(),
-                levels: vec![Level {
-                    level_idx: 0,
-                    level_type: LevelType::Overlapping as i32,
-                    table_infos: vec![],
-                    total_file_size: 0,
-                }],
+                levels: Default::default(),
                 max_committed_epoch: INVALID_EPOCH,
                 safe_epoch: INVALID_EPOCH,
             };
-            for l in 0..self.config.max_level {
-                init_version.levels.push(Level {
-                    level_idx: (l + 1) as u32,
-                    level_type: LevelType::Nonoverlapping as i32,
+            for compaction_group_id in &compaction_group_ids {
+                let mut levels = vec![Level {
+                    level_idx: 0,
+                    level_type: LevelType::Overlapping as i32,
                     table_infos: vec![],
                     total_file_size: 0,
-                });
+                    sub_levels: vec![],
+                }];
+                for l in 0..self.config.max_level {
+                    levels.push(Level {
+                        level_idx: (l + 1) as u32,
+                        level_type: LevelType::Nonoverlapping as i32,
+                        table_infos: vec![],
+                        total_file_size: 0,
+                        sub_levels: vec![],
+                    });
+                }
+                init_version
+                    .levels
+                    .insert((*compaction_group_id).into(), Levels { levels });
             }
             init_version.insert(self.env.meta_store()).await?;
             versioning_guard
@@ -392,6 +448,77 @@ where
         Ok(())
     }
 
+    /// Subscribes the compute node of `context_id` to new versions, which are pinned for it and
+    /// pushed to `sender` on creation. The current version is pinned and sent to `sender` as a
+    /// snapshot first. Versions greater than `last_pinned` were pushed to a former subscription of
+    /// the node but never received, so they're unpinned.
+    pub async fn subscribe_version(
+        &self,
+        context_id: HummockContextId,
+        last_pinned: HummockVersionId,
+        worker_key: WorkerKey,
+        sender: UnboundedSender<Notification>,
+    ) -> Result<()> {
+        let mut versioning_guard = self.versioning.write().await;
+        let versioning = versioning_guard.deref_mut();
+        let current_version_id = versioning.current_version_id.id();
+        let mut pinned_versions = VarTransaction::new(&mut versioning.pinned_versions);
+        let mut context_pinned_version = pinned_versions.new_entry_txn_or_default(
+            context_id,
+            HummockPinnedVersion {
+                context_id,
+                version_id: vec![],
+            },
+        );
+        context_pinned_version
+            .version_id
+            .retain(|version_id| *version_id <= last_pinned);
+        context_pinned_version.pin_version(current_version_id);
+        commit_multi_var!(self, Some(context_id), context_pinned_version)?;
+        versioning.version_subscribers.insert(context_id);
+
+        // New versions are pushed while holding the versioning lock, so the node receives all the
+        // versions after the snapshot as long as the sender is inserted before releasing it.
+        let notification_manager = self.env.notification_manager();
+        sender
+            .send(Ok(SubscribeResponse {
+                status: None,
+                operation: Operation::Snapshot as i32,
+                info: Some(Info::Snapshot(MetaSnapshot {
+                    hummock_version: Some(versioning.current_version()),
+                    ..Default::default()
+                })),
+                version: notification_manager.current_version().await,
+            }))
+            .unwrap();
+        notification_manager
+            .insert_compute_sender(worker_key, sender)
+            .await;
+
+        #[cfg(test)]
+        {
+            drop(versioning_guard);
+            self.check_state_consistency().await;
+        }
+
+        Ok(())
+    }
+
+    /// Pushes the current version to the compute nodes subscribing to new versions, for which it
+    /// has been pinned. It must be called before releasing the versioning lock, so that versions
+    /// are pushed in order.
+    fn notify_version_subscribers(&self, versioning: &Versioning) {
+        if versioning.version_subscribers.is_empty() {
+            return;
+        }
+        self.env
+            .notification_manager()
+            .notify_compute_asynchronously(
+                Operation::Update,
+                Info::HummockVersion(versioning.current_version()),
+            );
+    }
+
     /// Make sure `max_commited_epoch` is pinned and return it.
     /// Assume that frontend will only pass the latest epoch value recorded by frontend to
     /// `last_pinned`. Meta will unpin snapshots which are pinned and in (`last_pinned`,
@@ -459,6 +586,50 @@ where
         })
     }
 
+    /// Pins the snapshot of a historical `epoch` for time-travel queries, which fails if the epoch
+    /// has been GCed or not committed yet.
+    pub async fn pin_specific_snapshot(
+        &self,
+        context_id: HummockContextId,
+        epoch: HummockEpoch,
+    ) -> Result<HummockSnapshot> {
+        let mut versioning_guard = self.versioning.write().await;
+
+        let current_version = versioning_guard.current_version_ref();
+        let safe_epoch = current_version.safe_epoch;
+        let max_committed_epoch = current_version.max_committed_epoch;
+        if epoch < safe_epoch || epoch > max_committed_epoch {
+            return Err(Error::UnreadableEpoch {
+                epoch,
+                safe_epoch,
+                max_committed_epoch,
+            });
+        }
+
+        let mut pinned_snapshots = VarTransaction::new(&mut versioning_guard.pinned_snapshots);
+        let mut context_pinned_snapshot = pinned_snapshots.new_entry_txn_or_default(
+            context_id,
+            HummockPinnedSnapshot {
+                context_id,
+                snapshot_id: vec![],
+            },
+        );
+        if context_pinned_snapshot.snapshot_id.contains(&epoch) {
+            abort_multi_var!(context_pinned_snapshot);
+        } else {
+            context_pinned_snapshot.pin_snapshot(epoch);
+            commit_multi_var!(self, Some(context_id), context_pinned_snapshot)?;
+        }
+
+        #[cfg(test)]
+        {
+            drop(versioning_guard);
+            self.check_state_consistency().await;
+        }
+
+        Ok(HummockSnapshot { epoch })
+    }
+
     pub async fn unpin_snapshot(
         &self,
         context_id: HummockContextId,
@@ -548,7 +719,29 @@ where
         Ok(())
     }
 
-    pub async fn get_compact_task(&self) -> Result<Option<CompactTask>> {
+    pub async fn get_compact_task(
+        &self,
+        compaction_group_id: CompactionGroupId,
+    ) -> Result<Option<CompactTask>> {
+        self.get_compact_task_impl(compaction_group_id, None).await
+    }
+
+    /// Gets a compaction task of the SSTs selected by `manual_compaction_option`, which is
+    /// created no matter whether the levels need compaction.
+    pub async fn manual_get_compact_task(
+        &self,
+        compaction_group_id: CompactionGroupId,
+        manual_compaction_option: ManualCompactionOption,
+    ) -> Result<Option<CompactTask>> {
+        self.get_compact_task_impl(compaction_group_id, Some(manual_compaction_option))
+            .await
+    }
+
+    async fn get_compact_task_impl(
+        &self,
+        compaction_group_id: CompactionGroupId,
+        manual_compaction_option: Option<ManualCompactionOption>,
+    ) -> Result<Option<CompactTask>> {
         let start_time = Instant::now();
         let mut compaction_guard = self.compaction.write().await;
         let compaction = compaction_guard.deref_mut();
@@ -567,10 +760,38 @@ where
                     .map_err(Error::from)
             })
             .await?;
-        let mut compact_status = VarTransaction::new(&mut compaction.compact_status);
+        let mut compact_status = BTreeMapEntryTransaction::new(
+            &mut compaction.compaction_statuses,
+            compaction_group_id,
+        )
+        .ok_or_else(|| {
+            Error::InternalError(format!(
+                "compaction group {:?} not found",
+                compaction_group_id
+            ))
+        })?;
         let current_version = self.versioning.read().await.current_version();
-        let compact_task = compact_status
-            .get_compact_task(&current_version.levels, task_id as HummockCompactionTaskId);
+        let levels = current_version.get_compaction_group_levels(compaction_group_id);
+        let compact_task = match manual_compaction_option {
+            None => compact_status.get_compact_task(
+                levels,
+                task_id as HummockCompactionTaskId,
+                &compaction.read_statistics,
+            ),
+            Some(option) => {
+                if option.level >= levels.len() {
+                    return Err(Error::InternalError(format!(
+                        "level {} not found in compaction group {:?}",
+                        option.level, compaction_group_id
+                    )));
+                }
+                compact_status.manual_get_compact_task(
+                    levels,
+                    task_id as HummockCompactionTaskId,
+                    option,
+                )
+            }
+        };
         let ret = match compact_task {
             None => Ok(None),
             Some(mut compact_task) => {
@@ -588,6 +809,14 @@ where
                         .flat_map(|v| v.snapshot_id.clone())
                         .fold(max_committed_epoch, std::cmp::min)
                 };
+                compact_task.dropped_table_ids = self
+                    .compaction_group_manager
+                    .dropped_table_ids(compaction_group_id)
+                    .await;
+                compact_task.table_options = self
+                    .compaction_group_manager
+                    .table_options(compaction_group_id)
+                    .await;
                 if compact_task.target_level != 0 {
                     let table_ids = compact_task
                         .input_ssts
@@ -619,13 +848,32 @@ where
                         }
                     }
                 }
+                if compact_task.is_target_ultimate_and_leveling {
+                    // Rewrite the values in blob files whose SSTs have been compacted away, so
+                    // that the blob files can be vacuumed.
+                    let live_sst_ids = current_version
+                        .get_combined_levels()
+                        .into_iter()
+                        .flat_map(|level| level.table_infos.iter().map(|sst_info| sst_info.id))
+                        .collect::<HashSet<_>>();
+                    compact_task.gc_blob_ids = compact_task
+                        .input_ssts
+                        .iter()
+                        .flat_map(|level| level.table_infos.iter())
+                        .flat_map(|sst_info| sst_info.blob_ids.iter().copied())
+                        .filter(|blob_id| !live_sst_ids.contains(blob_id))
+                        .unique()
+                        .collect_vec();
+                }
 
                 commit_multi_var!(self, None, compact_status)?;
                 tracing::trace!(
-                    "pick up {} tables in level {} to compact, The number of total tables is {}. cost time: {:?}",
+                    "pick up {} tables in level {} of compaction group {:?} to compact, The number of total tables is {}. cost time: {:?}",
                     compact_task.input_ssts[0].table_infos.len(),
                     compact_task.input_ssts[0].level_idx,
-                    current_version.levels[compact_task.input_ssts[0].level_idx as usize]
+                    compaction_group_id,
+                    current_version.get_compaction_group_levels(compaction_group_id)
+                        [compact_task.input_ssts[0].level_idx as usize]
                         .table_infos
                         .len(),
                     start_time.elapsed()
@@ -682,6 +930,41 @@ where
         Ok(())
     }
 
+    /// Cancels a compaction task that has not been assigned to any compactor, so that its SSTs can
+    /// be picked again.
+    pub async fn cancel_unassigned_compact_task(&self, compact_task: &CompactTask) -> Result<()> {
+        let mut compaction_guard = self.compaction.write().await;
+        let compaction = compaction_guard.deref_mut();
+        if compaction
+            .compact_task_assignment
+            .contains_key(&compact_task.task_id)
+        {
+            return Err(Error::InternalError(format!(
+                "compaction task {} has been assigned",
+                compact_task.task_id
+            )));
+        }
+        let compaction_group_id: CompactionGroupId = compact_task.compaction_group_id.into();
+        let mut compact_status =
+            BTreeMapEntryTransaction::new(&mut compaction.compaction_statuses, compaction_group_id)
+                .ok_or_else(|| {
+                    Error::InternalError(format!(
+                        "compaction group {:?} not found",
+                        compaction_group_id
+                    ))
+                })?;
+        compact_status.report_compact_task(compact_task);
+        commit_multi_var!(self, None, compact_status)?;
+
+        #[cfg(test)]
+        {
+            drop(compaction_guard);
+            self.check_state_consistency().await;
+        }
+
+        Ok(())
+    }
+
     /// `report_compact_task` is retryable. `task_id` in `compact_task` parameter is used as the
     /// idempotency key. Return Ok(false) to indicate the `task_id` is not found, which may have
     /// been processed previously.
@@ -689,7 +972,15 @@ where
         let mut compaction_guard = self.compaction.write().await;
         let start_time = Instant::now();
         let compaction = compaction_guard.deref_mut();
-        let mut compact_status = VarTransaction::new(&mut compaction.compact_status);
+        let compaction_group_id: CompactionGroupId = compact_task.compaction_group_id.into();
+        let mut compact_status =
+            BTreeMapEntryTransaction::new(&mut compaction.compaction_statuses, compaction_group_id)
+                .ok_or_else(|| {
+                    Error::InternalError(format!(
+                        "compaction group {:?} not found",
+                        compaction_group_id
+                    ))
+                })?;
         let mut compact_task_assignment =
             VarTransaction::new(&mut compaction.compact_task_assignment);
         let assignee_context_id = match compact_task_assignment.remove(&compact_task.task_id) {
@@ -709,6 +1000,7 @@ where
             let mut hummock_versions = VarTransaction::new(&mut versioning.hummock_versions);
             let mut stale_sstables = VarTransaction::new(&mut versioning.stale_sstables);
             let mut sstable_id_infos = VarTransaction::new(&mut versioning.sstable_id_infos);
+            let mut pinned_versions = VarTransaction::new(&mut versioning.pinned_versions);
             let mut version_stale_sstables = stale_sstables.new_entry_txn_or_default(
                 old_version.id,
                 HummockStaleSstables {
@@ -720,11 +1012,19 @@ where
                 version_stale_sstables
                     .id
                     .extend(level.table_infos.iter().map(|sst| sst.id).collect_vec());
+                compaction
+                    .read_statistics
+                    .remove(level.table_infos.iter().map(|sst| sst.id));
             }
             let mut new_version = CompactStatus::apply_compact_result(compact_task, old_version);
             current_version_id.increase();
             new_version.id = current_version_id.id();
             hummock_versions.insert(new_version.id, new_version);
+            pin_version_for_subscribers(
+                &mut pinned_versions,
+                &versioning.version_subscribers,
+                current_version_id.id(),
+            );
 
             for SstableInfo { id: ref sst_id, .. } in &compact_task.sorted_output_ssts {
                 match sstable_id_infos.get_mut(sst_id) {
@@ -748,8 +1048,10 @@ where
                 current_version_id,
                 hummock_versions,
                 version_stale_sstables,
-                sstable_id_infos
+                sstable_id_infos,
+                pinned_versions
             )?;
+            self.notify_version_subscribers(versioning);
         } else {
             // The compaction task is cancelled.
             commit_multi_var!(
@@ -768,15 +1070,17 @@ where
 
         trigger_sst_stat(
             &self.metrics,
-            &compaction_guard.compact_status,
+            compaction_guard
+                .compaction_statuses
+                .get(&compaction_group_id)
+                .unwrap(),
             self.versioning.read().await.current_version_ref(),
         );
         if let Some(ref compact_task_metrics) = compact_task.metrics {
             trigger_rw_stat(&self.metrics, compact_task_metrics);
         }
 
-        // TODO: use correct id after compaction group is supported.
-        self.try_send_compaction_request(0.into());
+        self.try_send_compaction_request(compaction_group_id);
 
         #[cfg(test)]
         {
@@ -787,18 +1091,46 @@ where
         Ok(true)
     }
 
+    /// Adds the reads of SSTs reported by a compute node, and schedules compaction in case the
+    /// reads make some SSTs worth compacting.
+    pub async fn report_read_statistics(&self, stats: Vec<SstReadStatistics>) {
+        if stats.is_empty() {
+            return;
+        }
+        self.compaction.write().await.read_statistics.add(stats);
+        for compaction_group in self.compaction_group_manager.compaction_groups().await {
+            self.try_send_compaction_request(compaction_group.group_id());
+        }
+    }
+
     /// Caller should ensure `epoch` > `max_committed_epoch`
     pub async fn commit_epoch(
         &self,
         epoch: HummockEpoch,
         sstables: Vec<SstableInfo>,
     ) -> Result<()> {
+        // SSTs never span compaction groups, because the shared buffer is flushed into SSTs by
+        // compaction groups.
+        let mut sstables_by_group: BTreeMap<CompactionGroupId, Vec<SstableInfo>> = BTreeMap::new();
+        for sst in sstables {
+            let compaction_group_id = self
+                .compaction_group_manager
+                .compaction_group_of_sst(&sst)
+                .await?;
+            sstables_by_group
+                .entry(compaction_group_id)
+                .or_default()
+                .push(sst);
+        }
+        let compaction_group_ids = sstables_by_group.keys().cloned().collect_vec();
+
         let mut versioning_guard = self.versioning.write().await;
         let old_version = versioning_guard.current_version();
         let versioning = versioning_guard.deref_mut();
         let mut current_version_id = VarTransaction::new(&mut versioning.current_version_id);
         let mut hummock_versions = VarTransaction::new(&mut versioning.hummock_versions);
         let mut sstable_id_infos = VarTransaction::new(&mut versioning.sstable_id_infos);
+        let mut pinned_versions = VarTransaction::new(&mut versioning.pinned_versions);
         current_version_id.increase();
         let mut new_hummock_version =
             hummock_versions.new_entry_txn_or_default(current_version_id.id(), old_version);
@@ -815,8 +1147,7 @@ where
         // the meta store transaction. To avoid etcd errors if the aforementioned case
         // happens, we temporarily set a large value for etcd's max-txn-ops. But we need to
         // formally fix this because the performance degradation is not acceptable anyway.
-        let mut total_files_size = 0;
-        for sst in &sstables {
+        for sst in sstables_by_group.values().flatten() {
             match sstable_id_infos.get_mut(&sst.id) {
                 None => {
                     return Err(Error::InternalError(format!(
@@ -838,31 +1169,38 @@ where
                         )));
                     }
                     sst_id_info.meta_create_timestamp = sstable_id_info::get_timestamp_now();
-                    total_files_size += sst.file_size;
                 }
             }
         }
 
         // Create a new_version, possibly merely to bump up the version id and max_committed_epoch.
-        let version_first_level = new_hummock_version
-            .levels
-            .first_mut()
-            .expect("Expect at least one level");
-        assert_eq!(version_first_level.level_idx, 0);
-        assert_eq!(
-            version_first_level.level_type,
-            LevelType::Overlapping as i32
-        );
-        version_first_level.table_infos.extend(sstables);
-        version_first_level.total_file_size += total_files_size;
+        for (compaction_group_id, sstables) in sstables_by_group {
+            let version_first_level = new_hummock_version
+                .get_compaction_group_levels_mut(compaction_group_id)
+                .first_mut()
+                .expect("Expect at least one level");
+            assert_eq!(version_first_level.level_idx, 0);
+            assert_eq!(
+                version_first_level.level_type,
+                LevelType::Overlapping as i32
+            );
+            add_l0_sub_level(version_first_level, epoch, sstables);
+        }
         new_hummock_version.max_committed_epoch = epoch;
+        pin_version_for_subscribers(
+            &mut pinned_versions,
+            &versioning.version_subscribers,
+            current_version_id.id(),
+        );
         commit_multi_var!(
             self,
             None,
             new_hummock_version,
             current_version_id,
-            sstable_id_infos
+            sstable_id_infos,
+            pinned_versions
         )?;
+        self.notify_version_subscribers(versioning);
 
         // Update metrics
         trigger_commit_stat(&self.metrics, versioning.current_version_ref());
@@ -876,8 +1214,9 @@ where
                 Info::HummockSnapshot(HummockSnapshot { epoch }),
             );
 
-        // TODO: use correct id after compaction group is supported.
-        self.try_send_compaction_request(0.into());
+        for compaction_group_id in compaction_group_ids {
+            self.try_send_compaction_request(compaction_group_id);
+        }
 
         #[cfg(test)]
         {
@@ -931,7 +1270,7 @@ where
     ) -> Result<()> {
         let mut compaction_guard = self.compaction.write().await;
         let compaction = compaction_guard.deref_mut();
-        let mut compact_status = VarTransaction::new(&mut compaction.compact_status);
+        let mut compaction_statuses = VarTransaction::new(&mut compaction.compaction_statuses);
         let mut compact_task_assignment =
             VarTransaction::new(&mut compaction.compact_task_assignment);
         let mut versioning_guard = self.versioning.write().await;
@@ -944,17 +1283,21 @@ where
             tracing::debug!("Release context {}", *context_id);
             for assignment in compact_task_assignment.values() {
                 if assignment.context_id == *context_id {
-                    compact_status.report_compact_task(
-                        assignment
-                            .compact_task
-                            .as_ref()
-                            .expect("compact_task shouldn't be None"),
-                    );
+                    let compact_task = assignment
+                        .compact_task
+                        .as_ref()
+                        .expect("compact_task shouldn't be None");
+                    if let Some(compact_status) = compaction_statuses
+                        .get_mut(&CompactionGroupId::from(compact_task.compaction_group_id))
+                    {
+                        compact_status.report_compact_task(compact_task);
+                    }
                 }
             }
             compact_task_assignment.retain(|_, v| v.context_id != *context_id);
             to_commit = pinned_versions.remove(context_id).is_some() || to_commit;
             to_commit = pinned_snapshots.remove(context_id).is_some() || to_commit;
+            versioning.version_subscribers.remove(context_id);
         }
         if !to_commit {
             return Ok(());
@@ -964,14 +1307,14 @@ where
             commit_multi_var!(
                 self,
                 None,
-                compact_status,
+                compaction_statuses,
                 compact_task_assignment,
                 pinned_versions,
                 pinned_snapshots
             )?;
         } else {
             abort_multi_var!(
-                compact_status,
+                compaction_statuses,
                 compact_task_assignment,
                 pinned_versions,
                 pinned_snapshots
@@ -1106,7 +1449,7 @@ where
         let get_state = || async {
             let compaction_guard = self.compaction.read().await;
             let versioning_guard = self.versioning.read().await;
-            let compact_status_copy = compaction_guard.compact_status.clone();
+            let compaction_statuses_copy = compaction_guard.compaction_statuses.clone();
             let compact_task_assignment_copy = compaction_guard.compact_task_assignment.clone();
             let current_version_id_copy = versioning_guard.current_version_id.clone();
             let hummmock_versions_copy = versioning_guard.hummock_versions.clone();
@@ -1115,7 +1458,7 @@ where
             let stale_sstables_copy = versioning_guard.stale_sstables.clone();
             let sst_id_infos_copy = versioning_guard.sstable_id_infos.clone();
             (
-                compact_status_copy,
+                compaction_statuses_copy,
                 compact_task_assignment_copy,
                 current_version_id_copy,
                 hummmock_versions_copy,
@@ -1156,8 +1499,8 @@ where
             versioning
                 .map(|versioning| {
                     versioning
-                        .levels
-                        .iter()
+                        .get_combined_levels()
+                        .into_iter()
                         .flat_map(|level| {
                             level.table_infos.iter().map(|table_info| {
                                 versioning_guard
@@ -1178,6 +1521,23 @@ where
         }
     }
 
+    /// Returns the blob files referred to by any SST of the kept versions. A blob file is named
+    /// after the SST that created it, so such an SST can't be vacuumed yet.
+    pub async fn get_referenced_blob_ids(&self) -> HashSet<HummockSSTableId> {
+        let versioning_guard = self.versioning.read().await;
+        versioning_guard
+            .hummock_versions
+            .values()
+            .flat_map(|version| {
+                version
+                    .get_combined_levels()
+                    .into_iter()
+                    .flat_map(|level| level.table_infos.iter())
+                    .flat_map(|sst_info| sst_info.blob_ids.iter().copied())
+            })
+            .collect()
+    }
+
     pub async fn delete_sstable_ids(&self, sst_ids: impl AsRef<[HummockSSTableId]>) -> Result<()> {
         let mut versioning_guard = self.versioning.write().await;
         let mut sstable_id_infos = VarTransaction::new(&mut versioning_guard.sstable_id_infos);
@@ -1283,18 +1643,25 @@ where
         *self.compaction_scheduler.write() = Some(sender);
     }
 
+    pub fn compaction_group_manager(&self) -> CompactionGroupManagerRef<S> {
+        self.compaction_group_manager.clone()
+    }
+
     /// Cancels pending compaction tasks which are not yet assigned to any compactor.
     async fn cancel_unassigned_compaction_task(&self) -> Result<()> {
         let mut compaction_guard = self.compaction.write().await;
         let compaction = compaction_guard.deref_mut();
-        let mut compact_status = VarTransaction::new(&mut compaction.compact_status);
-        if compact_status.cancel_compaction_tasks_if(|pending_task_id| {
-            !compaction
-                .compact_task_assignment
-                .contains_key(&pending_task_id)
-        }) > 0
-        {
-            commit_multi_var!(self, None, compact_status)?;
+        let mut compaction_statuses = VarTransaction::new(&mut compaction.compaction_statuses);
+        let mut canceled_count = 0;
+        for compact_status in compaction_statuses.values_mut() {
+            canceled_count += compact_status.cancel_compaction_tasks_if(|pending_task_id| {
+                !compaction
+                    .compact_task_assignment
+                    .contains_key(&pending_task_id)
+            });
+        }
+        if canceled_count > 0 {
+            commit_multi_var!(self, None, compaction_statuses)?;
         }
         #[cfg(test)]
         {
@@ -18,6 +18,8 @@ use std::time::Duration;
 use itertools::Itertools;
 use risingwave_common::util::epoch::INVALID_EPOCH;
 use risingwave_hummock_sdk::compact::compact_task_to_string;
+use risingwave_hummock_sdk::compaction_group::hummock_version_ext::HummockVersionExt;
+use risingwave_hummock_sdk::compaction_group::StaticCompactionGroupId;
 use risingwave_hummock_sdk::{
     HummockContextId, HummockSSTableId, FIRST_VERSION_ID, INVALID_VERSION_ID,
 };
@@ -25,7 +27,9 @@ use risingwave_pb::common::{HostAddress, ParallelUnitType, WorkerType};
 use risingwave_pb::hummock::{
     HummockPinnedSnapshot, HummockPinnedVersion, HummockSnapshot, HummockVersion,
 };
+use risingwave_pb::meta::subscribe_response::{Info, Operation};
 
+use crate::cluster::WorkerKey;
 use crate::hummock::error::Error;
 use crate::hummock::model::CurrentHummockVersionId;
 use crate::hummock::test_utils::*;
@@ -56,9 +60,11 @@ async fn test_hummock_pin_unpin() {
             .await
             .unwrap();
         assert_eq!(version_id, hummock_version.id);
-        assert_eq!(7, hummock_version.levels.len());
-        assert_eq!(0, hummock_version.levels[0].table_infos.len());
-        assert_eq!(0, hummock_version.levels[1].table_infos.len());
+        let levels = hummock_version
+            .get_compaction_group_levels(StaticCompactionGroupId::StateDefault.into());
+        assert_eq!(7, levels.len());
+        assert_eq!(0, levels[0].table_infos.len());
+        assert_eq!(0, levels[1].table_infos.len());
 
         let pinned_versions = HummockPinnedVersion::list(env.meta_store()).await.unwrap();
         assert_eq!(pin_versions_sum(&pinned_versions), 1);
@@ -152,6 +158,47 @@ async fn test_unpin_snapshot_before() {
     }
 }
 
+#[tokio::test]
+async fn test_pin_specific_snapshot() {
+    let (env, hummock_manager, _cluster_manager, worker_node) = setup_compute_env(80).await;
+    let context_id = worker_node.id;
+    for epoch in 1..=2 {
+        let tables = generate_test_tables(epoch, get_sst_ids(&hummock_manager, 1).await);
+        hummock_manager.commit_epoch(epoch, tables).await.unwrap();
+    }
+
+    // Historical epochs can be pinned repeatedly.
+    for _ in 0..2 {
+        let pin_result = hummock_manager
+            .pin_specific_snapshot(context_id, 1)
+            .await
+            .unwrap();
+        assert_eq!(pin_result.epoch, 1);
+        let pinned_snapshots = HummockPinnedSnapshot::list(env.meta_store()).await.unwrap();
+        assert_eq!(pin_snapshots_sum(&pinned_snapshots), 1);
+        assert_eq!(pinned_snapshots[0].snapshot_id, vec![1]);
+    }
+
+    // Epochs that haven't been committed can't be read.
+    assert!(matches!(
+        hummock_manager.pin_specific_snapshot(context_id, 3).await,
+        Err(Error::UnreadableEpoch {
+            epoch: 3,
+            max_committed_epoch: 2,
+            ..
+        })
+    ));
+
+    hummock_manager
+        .unpin_snapshot(context_id, vec![HummockSnapshot { epoch: 1 }])
+        .await
+        .unwrap();
+    assert_eq!(
+        pin_snapshots_sum(&HummockPinnedSnapshot::list(env.meta_store()).await.unwrap()),
+        0
+    );
+}
+
 #[tokio::test]
 async fn test_hummock_compaction_task() {
     let (env, hummock_manager, cluster_manager, worker_node) = setup_compute_env(80).await;
@@ -170,7 +217,10 @@ async fn test_hummock_compaction_task() {
     }
 
     // No compaction task available.
-    let task = hummock_manager.get_compact_task().await.unwrap();
+    let task = hummock_manager
+        .get_compact_task(StaticCompactionGroupId::StateDefault.into())
+        .await
+        .unwrap();
     assert_eq!(task, None);
 
     // Add some sstables and commit.
@@ -195,7 +245,11 @@ async fn test_hummock_compaction_task() {
     assert_eq!(INVALID_EPOCH, hummock_version1.safe_epoch);
 
     // Get a compaction task.
-    let mut compact_task = hummock_manager.get_compact_task().await.unwrap().unwrap();
+    let mut compact_task = hummock_manager
+        .get_compact_task(StaticCompactionGroupId::StateDefault.into())
+        .await
+        .unwrap()
+        .unwrap();
     hummock_manager
         .assign_compaction_task(&compact_task, context_id, async { true })
         .await
@@ -241,7 +295,11 @@ async fn test_hummock_compaction_task() {
     assert_eq!(INVALID_EPOCH, hummock_version2.safe_epoch);
 
     // Get a compaction task.
-    let mut compact_task = hummock_manager.get_compact_task().await.unwrap().unwrap();
+    let mut compact_task = hummock_manager
+        .get_compact_task(StaticCompactionGroupId::StateDefault.into())
+        .await
+        .unwrap()
+        .unwrap();
     hummock_manager
         .assign_compaction_task(&compact_task, context_id, async { true })
         .await
@@ -294,8 +352,8 @@ async fn test_hummock_table() {
     assert_eq!(
         Ordering::Equal,
         pinned_version
-            .levels
-            .iter()
+            .get_combined_levels()
+            .into_iter()
             .flat_map(|level| level.table_infos.iter())
             .map(|info| info.id)
             .sorted()
@@ -840,7 +898,11 @@ async fn test_print_compact_task() {
         .unwrap();
 
     // Get a compaction task.
-    let compact_task = hummock_manager.get_compact_task().await.unwrap().unwrap();
+    let compact_task = hummock_manager
+        .get_compact_task(StaticCompactionGroupId::StateDefault.into())
+        .await
+        .unwrap()
+        .unwrap();
     assert_eq!(
         compact_task
             .get_input_ssts()
@@ -899,3 +961,100 @@ async fn test_mark_orphan_ssts() {
         .unwrap_err();
     assert!(matches!(error, Error::InternalError(_)));
 }
+
+#[tokio::test]
+async fn test_subscribe_version() {
+    let (_env, hummock_manager, _cluster_manager, worker_node) = setup_compute_env(80).await;
+    let context_id = worker_node.id;
+    let worker_key = || WorkerKey(worker_node.host.clone().unwrap());
+    let commit_epoch = |epoch| {
+        let hummock_manager = hummock_manager.clone();
+        async move {
+            let tables = generate_test_tables(epoch, get_sst_ids(&hummock_manager, 1).await);
+            hummock_manager.commit_epoch(epoch, tables).await.unwrap();
+        }
+    };
+
+    // The current version is pinned and sent as a snapshot on subscription.
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+    hummock_manager
+        .subscribe_version(context_id, INVALID_VERSION_ID, worker_key(), tx)
+        .await
+        .unwrap();
+    let resp = rx.recv().await.unwrap().unwrap();
+    assert_eq!(resp.operation(), Operation::Snapshot);
+    match resp.info {
+        Some(Info::Snapshot(snapshot)) => {
+            assert_eq!(snapshot.hummock_version.unwrap().id, FIRST_VERSION_ID)
+        }
+        _ => panic!("unexpected notification {:?}", resp),
+    }
+    assert_eq!(
+        hummock_manager
+            .get_version_pin_count(FIRST_VERSION_ID)
+            .await
+            .unwrap(),
+        1
+    );
+
+    // New versions are pinned and pushed to the subscriber.
+    commit_epoch(1).await;
+    let resp = rx.recv().await.unwrap().unwrap();
+    match resp.info {
+        Some(Info::HummockVersion(version)) => {
+            assert_eq!(version.id, FIRST_VERSION_ID + 1);
+            assert_eq!(version.max_committed_epoch, 1);
+        }
+        _ => panic!("unexpected notification {:?}", resp),
+    }
+    assert_eq!(
+        hummock_manager
+            .get_version_pin_count(FIRST_VERSION_ID + 1)
+            .await
+            .unwrap(),
+        1
+    );
+
+    // The versions pushed but not received are unpinned on re-subscription.
+    commit_epoch(2).await;
+    commit_epoch(3).await;
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+    hummock_manager
+        .subscribe_version(context_id, FIRST_VERSION_ID + 1, worker_key(), tx)
+        .await
+        .unwrap();
+    let resp = rx.recv().await.unwrap().unwrap();
+    match resp.info {
+        Some(Info::Snapshot(snapshot)) => {
+            assert_eq!(snapshot.hummock_version.unwrap().id, FIRST_VERSION_ID + 3)
+        }
+        _ => panic!("unexpected notification {:?}", resp),
+    }
+    for (version_id, pin_count) in [
+        (FIRST_VERSION_ID + 1, 1),
+        (FIRST_VERSION_ID + 2, 0),
+        (FIRST_VERSION_ID + 3, 1),
+    ] {
+        assert_eq!(
+            hummock_manager
+                .get_version_pin_count(version_id)
+                .await
+                .unwrap(),
+            pin_count
+        );
+    }
+
+    // New versions are no longer pinned once the context is released.
+    hummock_manager
+        .release_contexts([context_id])
+        .await
+        .unwrap();
+    commit_epoch(4).await;
+    assert_eq!(
+        hummock_manager
+            .get_version_pin_count(FIRST_VERSION_ID + 4)
+            .await
+            .unwrap(),
+        0
+    );
+}
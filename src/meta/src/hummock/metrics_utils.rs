@@ -18,6 +18,7 @@ use std::time::{SystemTime, UNIX_EPOCH};
 use itertools::enumerate;
 use prometheus::Histogram;
 use prost::Message;
+use risingwave_hummock_sdk::compaction_group::hummock_version_ext::HummockVersionExt;
 use risingwave_pb::hummock::{CompactMetrics, HummockVersion, TableSetStatistics};
 
 use crate::hummock::compaction::CompactStatus;
@@ -27,6 +28,8 @@ pub fn trigger_commit_stat(metrics: &MetaMetrics, current_version: &HummockVersi
     metrics
         .max_committed_epoch
         .set(current_version.max_committed_epoch as i64);
+    metrics.version_id.set(current_version.id as i64);
+    metrics.safe_epoch.set(current_version.safe_epoch as i64);
     metrics
         .version_size
         .set(current_version.encoded_len() as i64);
@@ -37,13 +40,15 @@ pub fn trigger_sst_stat(
     compact_status: &CompactStatus,
     current_version: &HummockVersion,
 ) {
-    let level_sst_cnt = |level_idx: usize| current_version.levels[level_idx].table_infos.len();
-    let level_sst_size =
-        |level_idx: usize| current_version.levels[level_idx].total_file_size / 1024;
+    metrics.version_id.set(current_version.id as i64);
+    let compaction_group_id = compact_status.compaction_group_id();
+    let levels = current_version.get_compaction_group_levels(compaction_group_id);
+    let level_sst_cnt = |level_idx: usize| levels[level_idx].table_infos.len();
+    let level_sst_size = |level_idx: usize| levels[level_idx].total_file_size / 1024;
     for (idx, level_handler) in enumerate(compact_status.level_handlers.iter()) {
         let sst_num = level_sst_cnt(idx);
         let compact_cnt = level_handler.get_pending_file_count();
-        let level_label = idx.to_string();
+        let level_label = format!("cg{}_L{}", u64::from(compaction_group_id), idx);
         metrics
             .level_sst_num
             .with_label_values(&[&level_label])
@@ -60,6 +65,7 @@ pub fn trigger_sst_stat(
 
     use std::sync::atomic::AtomicU64;
 
+    // TODO: log each compaction group at its own rate.
     static TIME_AFTER_LAST_OBSERVATION: AtomicU64 = AtomicU64::new(0);
     let previous_time = TIME_AFTER_LAST_OBSERVATION.load(Ordering::Relaxed);
     let current_time = SystemTime::now()
@@ -81,8 +87,9 @@ pub fn trigger_sst_stat(
             let sst_size = level_sst_size(idx);
             let compact_cnt = level_handler.get_pending_file_count();
             tracing::info!(
-                "Level {} has {} SSTs, the total size of which is {}KB, while {} of those are being compacted to bottom levels",
+                "Level {} of compaction group {:?} has {} SSTs, the total size of which is {}KB, while {} of those are being compacted to bottom levels",
                 idx,
+                compaction_group_id,
                 sst_num,
                 sst_size,
                 compact_cnt,
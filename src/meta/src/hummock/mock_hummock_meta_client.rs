@@ -18,8 +18,8 @@ use async_trait::async_trait;
 use risingwave_common::error::{ErrorCode, Result};
 use risingwave_hummock_sdk::{HummockContextId, HummockEpoch, HummockSSTableId, HummockVersionId};
 use risingwave_pb::hummock::{
-    CompactTask, HummockSnapshot, HummockVersion, SstableInfo, SubscribeCompactTasksResponse,
-    VacuumTask,
+    CompactTask, CompactionGroup, HummockSnapshot, HummockVersion, SstReadStatistics, SstableInfo,
+    SubscribeCompactTasksResponse, VacuumTask,
 };
 use risingwave_rpc_client::HummockMetaClient;
 use tonic::Streaming;
@@ -43,9 +43,10 @@ impl MockHummockMetaClient {
         }
     }
 
+    /// Gets a compaction task of the default compaction group.
     pub async fn get_compact_task(&self) -> Option<CompactTask> {
         self.hummock_manager
-            .get_compact_task()
+            .get_compact_task(StaticCompactionGroupId::StateDefault.into())
             .await
             .unwrap_or(None)
     }
@@ -133,6 +134,22 @@ impl HummockMetaClient for MockHummockMetaClient {
     async fn report_vacuum_task(&self, _vacuum_task: VacuumTask) -> Result<()> {
         Ok(())
     }
+
+    async fn get_compaction_groups(&self) -> Result<Vec<CompactionGroup>> {
+        Ok(self
+            .hummock_manager
+            .compaction_group_manager()
+            .compaction_groups()
+            .await
+            .iter()
+            .map(|cg| cg.into())
+            .collect())
+    }
+
+    async fn report_read_statistics(&self, stats: Vec<SstReadStatistics>) -> Result<()> {
+        self.hummock_manager.report_read_statistics(stats).await;
+        Ok(())
+    }
 }
 
 impl MockHummockMetaClient {
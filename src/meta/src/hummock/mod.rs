@@ -13,6 +13,7 @@
 // limitations under the License.
 
 mod compaction;
+pub mod compaction_group;
 mod compaction_scheduler;
 mod compactor_manager;
 pub mod error;
@@ -32,11 +33,13 @@ mod vacuum;
 use std::sync::Arc;
 use std::time::Duration;
 
+pub use compaction::ManualCompactionOption;
 pub use compaction_scheduler::CompactionScheduler;
 pub use compactor_manager::*;
 pub use hummock_manager::*;
 #[cfg(any(test, feature = "test"))]
 pub use mock_hummock_meta_client::MockHummockMetaClient;
+pub(crate) use model::HUMMOCK_DEFAULT_CF_NAME;
 use tokio::sync::oneshot::Sender;
 use tokio::task::JoinHandle;
 use tokio_retry::strategy::{jitter, ExponentialBackoff};
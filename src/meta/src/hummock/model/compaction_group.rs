@@ -0,0 +1,47 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use prost::Message;
+
+use crate::hummock::compaction_group::CompactionGroup;
+use crate::model::MetadataModel;
+
+/// Column family name for hummock compaction group.
+/// `cf(hummock_compaction_group)`: `CompactionGroupId` -> `CompactionGroup`
+const HUMMOCK_COMPACTION_GROUP_CF_NAME: &str = "cf/hummock_compaction_group";
+
+impl MetadataModel for CompactionGroup {
+    type KeyType = u64;
+    type ProstType = risingwave_pb::hummock::CompactionGroup;
+
+    fn cf_name() -> String {
+        HUMMOCK_COMPACTION_GROUP_CF_NAME.to_string()
+    }
+
+    fn to_protobuf(&self) -> Self::ProstType {
+        self.into()
+    }
+
+    fn to_protobuf_encoded_vec(&self) -> Vec<u8> {
+        self.to_protobuf().encode_to_vec()
+    }
+
+    fn from_protobuf(prost: Self::ProstType) -> Self {
+        (&prost).into()
+    }
+
+    fn key(&self) -> risingwave_common::error::Result<Self::KeyType> {
+        Ok(self.group_id().into())
+    }
+}
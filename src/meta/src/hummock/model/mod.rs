@@ -13,6 +13,7 @@
 // limitations under the License.
 
 mod compact_task_assignment;
+mod compaction_group;
 mod current_version_id;
 mod pinned_snapshot;
 mod pinned_version;
@@ -16,6 +16,8 @@ use std::sync::Arc;
 use std::time::Duration;
 
 use itertools::Itertools;
+use risingwave_hummock_sdk::compaction_group::hummock_version_ext::HummockVersionExt;
+use risingwave_hummock_sdk::compaction_group::StaticCompactionGroupId;
 use risingwave_hummock_sdk::key::key_with_epoch;
 use risingwave_hummock_sdk::{HummockContextId, HummockEpoch, HummockSSTableId};
 use risingwave_pb::common::{HostAddress, VNodeBitmap, WorkerNode, WorkerType};
@@ -23,6 +25,7 @@ use risingwave_pb::hummock::{HummockVersion, KeyRange, SstableInfo};
 
 use crate::cluster::{ClusterManager, ClusterManagerRef};
 use crate::hummock::compaction::CompactionConfig;
+use crate::hummock::compaction_group::manager::CompactionGroupManager;
 use crate::hummock::{HummockManager, HummockManagerRef};
 use crate::manager::MetaSrvEnv;
 use crate::rpc::metrics::MetaMetrics;
@@ -50,7 +53,11 @@ where
     // Current state: {v0: [], v1: [test_tables]}
 
     // Simulate a compaction and increase version by 1.
-    let mut compact_task = hummock_manager.get_compact_task().await.unwrap().unwrap();
+    let mut compact_task = hummock_manager
+        .get_compact_task(StaticCompactionGroupId::StateDefault.into())
+        .await
+        .unwrap()
+        .unwrap();
     hummock_manager
         .assign_compaction_task(&compact_task, context_id, async { true })
         .await
@@ -103,6 +110,7 @@ pub fn generate_test_tables(epoch: u64, sst_ids: Vec<HummockSSTableId>) -> Vec<S
                     bitmap: vec![],
                 },
             ],
+            blob_ids: vec![],
         });
     }
     sst_info
@@ -129,8 +137,8 @@ pub fn get_sorted_sstable_ids(sstables: &[SstableInfo]) -> Vec<HummockSSTableId>
 
 pub fn get_sorted_committed_sstable_ids(hummock_version: &HummockVersion) -> Vec<HummockSSTableId> {
     hummock_version
-        .levels
-        .iter()
+        .get_combined_levels()
+        .into_iter()
         .flat_map(|level| level.table_infos.iter().map(|info| info.id))
         .sorted()
         .collect_vec()
@@ -153,15 +161,19 @@ pub async fn setup_compute_env(
     let config = CompactionConfig {
         level0_tigger_file_numer: 2,
         level0_tier_compact_file_number: 1,
+        read_compaction_trigger: u64::MAX,
         min_compaction_bytes: 1,
         max_bytes_for_level_base: 1,
         ..Default::default()
     };
+    let compaction_group_manager =
+        Arc::new(CompactionGroupManager::new(env.clone()).await.unwrap());
     let hummock_manager = Arc::new(
         HummockManager::new_with_config(
             env.clone(),
             cluster_manager.clone(),
             Arc::new(MetaMetrics::new()),
+            compaction_group_manager,
             config,
         )
         .await
@@ -143,6 +143,9 @@ where
                 self.hummock_manager
                     .mark_orphan_ssts(orphan_sst_retention_interval)
                     .await?;
+                // An SST whose blob file is still referred to by other SSTs is kept until the
+                // blob file is rewritten by compaction, since they are vacuumed together.
+                let referenced_blob_ids = self.hummock_manager.get_referenced_blob_ids().await;
                 let ssts_to_delete = self
                     .hummock_manager
                     .list_sstable_id_infos(None)
@@ -150,6 +153,7 @@ where
                     .into_iter()
                     .filter(|sstable_id_info| {
                         sstable_id_info.meta_delete_timestamp != INVALID_TIMESTAMP
+                            && !referenced_blob_ids.contains(&sstable_id_info.id)
                     })
                     .map(|sstable_id_info| sstable_id_info.id)
                     .collect_vec();
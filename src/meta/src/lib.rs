@@ -35,6 +35,7 @@
 #![feature(drain_filter)]
 #![cfg_attr(coverage, feature(no_coverage))]
 
+pub mod backup;
 mod barrier;
 pub mod cluster;
 mod dashboard;
@@ -49,7 +50,7 @@ pub mod test_utils;
 use std::time::Duration;
 
 use clap::{ArgEnum, Parser};
-use risingwave_common::config::ComputeNodeConfig;
+use risingwave_common::config::{start_config_reloader, RwConfig};
 
 use crate::manager::MetaOpts;
 use crate::rpc::server::{rpc_serve, MetaStoreBackend};
@@ -64,7 +65,7 @@ enum Backend {
 pub struct MetaNodeOpts {
     // TODO: rename to listen_address and separate out the port.
     #[clap(long, default_value = "127.0.0.1:5690")]
-    host: String,
+    pub host: String,
 
     #[clap(long)]
     dashboard_host: Option<String>,
@@ -93,21 +94,51 @@ pub struct MetaNodeOpts {
     /// e2e tests.
     #[clap(long)]
     disable_recovery: bool,
+
+    /// Object store to keep the backups of the meta store, e.g. `s3://bucket`. Backups are
+    /// disabled if not set.
+    #[clap(long)]
+    backup_storage_url: Option<String>,
+
+    /// Directory of the backups in the backup storage.
+    #[clap(long, default_value = "backup")]
+    backup_storage_directory: String,
+
+    /// How long in seconds the responses of DDL requests are kept to deduplicate their retries.
+    #[clap(long, default_value = "600")]
+    idempotency_window_sec: u64,
 }
 
-fn load_config(opts: &MetaNodeOpts) -> ComputeNodeConfig {
+fn load_config(opts: &MetaNodeOpts) -> RwConfig {
     risingwave_common::config::load_config(&opts.config_path)
 }
 
 use std::future::Future;
 use std::pin::Pin;
 
+use tokio::sync::oneshot::Sender;
+use tokio::task::JoinHandle;
+
 /// Start meta node
 pub fn start(opts: MetaNodeOpts) -> Pin<Box<dyn Future<Output = ()> + Send>> {
     // WARNING: don't change the function signature. Making it `async fn` will cause
     // slow compile in release mode.
+    Box::pin(async move {
+        let (join_handle, _shutdown_send) = serve(opts).await;
+        join_handle.await.unwrap();
+    })
+}
+
+/// Starts the meta service in the current process. Returns once the service is started, with the
+/// handle to wait for it and the sender to shut it down. Used to embed the meta node into another
+/// process, e.g. the playground.
+pub fn serve(
+    opts: MetaNodeOpts,
+) -> Pin<Box<dyn Future<Output = (JoinHandle<()>, Sender<()>)> + Send>> {
     Box::pin(async move {
         let compute_config = load_config(&opts);
+        let (dynamic_config, config_reloader) =
+            start_config_reloader(&opts.config_path, &compute_config);
         let addr = opts.host.parse().unwrap();
         let dashboard_addr = opts.dashboard_host.map(|x| x.parse().unwrap());
         let prometheus_addr = opts.prometheus_host.map(|x| x.parse().unwrap());
@@ -126,7 +157,7 @@ pub fn start(opts: MetaNodeOpts) -> Pin<Box<dyn Future<Output = ()> + Send>> {
             Duration::from_millis(compute_config.streaming.checkpoint_interval_ms as u64);
 
         tracing::info!("Meta server listening at {}", addr);
-        let (join_handle, _shutdown_send) = rpc_serve(
+        let (join_handle, shutdown_send) = rpc_serve(
             addr,
             prometheus_addr,
             dashboard_addr,
@@ -136,10 +167,19 @@ pub fn start(opts: MetaNodeOpts) -> Pin<Box<dyn Future<Output = ()> + Send>> {
             MetaOpts {
                 enable_recovery: !opts.disable_recovery,
                 checkpoint_interval,
+                backup_storage_url: opts.backup_storage_url,
+                backup_storage_directory: opts.backup_storage_directory,
+                dynamic_config,
+                idempotency_window: Duration::from_secs(opts.idempotency_window_sec),
             },
         )
         .await
         .unwrap();
-        join_handle.await.unwrap();
+        let join_handle = tokio::spawn(async move {
+            // Keep the reloader running until the meta node exits.
+            let _config_reloader = config_reloader;
+            join_handle.await.unwrap();
+        });
+        (join_handle, shutdown_send)
     })
 }
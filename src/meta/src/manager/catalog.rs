@@ -22,9 +22,12 @@ use risingwave_common::catalog::{DEFAULT_DATABASE_NAME, DEFAULT_SCHEMA_NAME};
 use risingwave_common::ensure;
 use risingwave_common::error::ErrorCode::{CatalogError, InternalError};
 use risingwave_common::error::{Result, RwError};
+use risingwave_pb::catalog::source::Info as SourceInfo;
 use risingwave_pb::catalog::table::OptionalAssociatedSourceId;
-use risingwave_pb::catalog::{Database, Schema, Source, Table};
+use risingwave_pb::catalog::{Database, Function, Schema, Source, Table};
+use risingwave_pb::meta::IdempotencyRecord;
 use risingwave_pb::meta::subscribe_response::{Info, Operation};
+use risingwave_pb::plan_common::ColumnCatalog;
 use tokio::sync::{Mutex, MutexGuard};
 
 use super::IdCategory;
@@ -37,9 +40,34 @@ pub type SchemaId = u32;
 pub type TableId = u32;
 pub type SourceId = u32;
 pub type RelationId = u32;
+pub type FunctionId = u32;
 
-pub type Catalog = (Vec<Database>, Vec<Schema>, Vec<Table>, Vec<Source>);
+/// A table or a source, identified by its id.
+#[derive(Clone, Copy, Debug)]
+pub enum RelationIdEnum {
+    Table(TableId),
+    Source(SourceId),
+}
+
+pub type Catalog = (
+    Vec<Database>,
+    Vec<Schema>,
+    Vec<Table>,
+    Vec<Source>,
+    Vec<Function>,
+);
+
+/// Writes the idempotency record of the request applied by `transaction`, if any.
+fn upsert_record(transaction: &mut Transaction, record: Option<IdempotencyRecord>) -> Result<()> {
+    match record {
+        Some(record) => record.upsert_in_transaction(transaction),
+        None => Ok(()),
+    }
+}
 
+/// [`CatalogManager`] manages the catalogs in the meta store. The methods applying DDL requests
+/// take the [`IdempotencyRecord`] of the request, if any, and write it in the same transaction as
+/// the catalogs, so that a retry after a failover never applies the request again.
 pub struct CatalogManager<S: MetaStore> {
     env: MetaSrvEnv<S>,
     core: Mutex<CatalogManagerCore<S>>,
@@ -72,7 +100,7 @@ where
                 .id_gen_manager()
                 .generate::<{ IdCategory::Database }>()
                 .await? as u32;
-            self.create_database(&database).await?;
+            self.create_database(&database, None).await?;
         }
         let databases = Database::list(self.env.meta_store())
             .await?
@@ -92,7 +120,7 @@ where
                 .id_gen_manager()
                 .generate::<{ IdCategory::Schema }>()
                 .await? as u32;
-            self.create_schema(&schema).await?;
+            self.create_schema(&schema, None).await?;
         }
         Ok(())
     }
@@ -108,10 +136,17 @@ where
         core.get_catalog().await
     }
 
-    pub async fn create_database(&self, database: &Database) -> Result<NotificationVersion> {
+    pub async fn create_database(
+        &self,
+        database: &Database,
+        record: Option<IdempotencyRecord>,
+    ) -> Result<NotificationVersion> {
         let mut core = self.core.lock().await;
         if !core.has_database(database) {
-            database.insert(self.env.meta_store()).await?;
+            let mut transaction = Transaction::default();
+            database.upsert_in_transaction(&mut transaction)?;
+            upsert_record(&mut transaction, record)?;
+            self.env.meta_store().txn(transaction).await?;
             core.add_database(database);
 
             let version = self
@@ -128,11 +163,18 @@ where
         }
     }
 
-    pub async fn drop_database(&self, database_id: DatabaseId) -> Result<NotificationVersion> {
+    pub async fn drop_database(
+        &self,
+        database_id: DatabaseId,
+        record: Option<IdempotencyRecord>,
+    ) -> Result<NotificationVersion> {
         let mut core = self.core.lock().await;
         let database = Database::select(self.env.meta_store(), &database_id).await?;
         if let Some(database) = database {
-            Database::delete(self.env.meta_store(), &database_id).await?;
+            let mut transaction = Transaction::default();
+            database.delete_in_transaction(&mut transaction)?;
+            upsert_record(&mut transaction, record)?;
+            self.env.meta_store().txn(transaction).await?;
             core.drop_database(&database);
 
             let version = self
@@ -149,10 +191,17 @@ where
         }
     }
 
-    pub async fn create_schema(&self, schema: &Schema) -> Result<NotificationVersion> {
+    pub async fn create_schema(
+        &self,
+        schema: &Schema,
+        record: Option<IdempotencyRecord>,
+    ) -> Result<NotificationVersion> {
         let mut core = self.core.lock().await;
         if !core.has_schema(schema) {
-            schema.insert(self.env.meta_store()).await?;
+            let mut transaction = Transaction::default();
+            schema.upsert_in_transaction(&mut transaction)?;
+            upsert_record(&mut transaction, record)?;
+            self.env.meta_store().txn(transaction).await?;
             core.add_schema(schema);
 
             let version = self
@@ -169,11 +218,18 @@ where
         }
     }
 
-    pub async fn drop_schema(&self, schema_id: SchemaId) -> Result<NotificationVersion> {
+    pub async fn drop_schema(
+        &self,
+        schema_id: SchemaId,
+        record: Option<IdempotencyRecord>,
+    ) -> Result<NotificationVersion> {
         let mut core = self.core.lock().await;
         let schema = Schema::select(self.env.meta_store(), &schema_id).await?;
         if let Some(schema) = schema {
-            Schema::delete(self.env.meta_store(), &schema_id).await?;
+            let mut transaction = Transaction::default();
+            schema.delete_in_transaction(&mut transaction)?;
+            upsert_record(&mut transaction, record)?;
+            self.env.meta_store().txn(transaction).await?;
             core.drop_schema(&schema);
 
             let version = self
@@ -193,6 +249,21 @@ where
     pub async fn start_create_table_procedure(&self, table: &Table) -> Result<()> {
         let mut core = self.core.lock().await;
         let key = (table.database_id, table.schema_id, table.name.clone());
+        for &dependent_relation_id in &table.dependent_relations {
+            if let Some(dependent_table) =
+                Table::select(self.env.meta_store(), &dependent_relation_id).await?
+                && dependent_table.is_internal
+            {
+                return Err(CatalogError(
+                    anyhow!(
+                        "internal table `{}` can only be scanned in batch queries",
+                        dependent_table.name
+                    )
+                    .into(),
+                )
+                .into());
+            }
+        }
         if !core.has_table(table) && !core.has_in_progress_creation(&key) {
             core.mark_creating(&key);
             for &dependent_relation_id in &table.dependent_relations {
@@ -206,15 +277,31 @@ where
         }
     }
 
+    /// Finishes creating `table`, together with the catalogs of its `internal_tables`.
     pub async fn finish_create_table_procedure(
         &self,
+        internal_tables: Vec<Table>,
         table: &Table,
+        record: Option<IdempotencyRecord>,
     ) -> Result<NotificationVersion> {
         let mut core = self.core.lock().await;
         let key = (table.database_id, table.schema_id, table.name.clone());
         if !core.has_table(table) && core.has_in_progress_creation(&key) {
             core.unmark_creating(&key);
-            table.insert(self.env.meta_store()).await?;
+            let mut transaction = Transaction::default();
+            for internal_table in &internal_tables {
+                internal_table.upsert_in_transaction(&mut transaction)?;
+            }
+            table.upsert_in_transaction(&mut transaction)?;
+            upsert_record(&mut transaction, record)?;
+            core.env.meta_store().txn(transaction).await?;
+            for internal_table in internal_tables {
+                core.add_table(&internal_table);
+                self.env
+                    .notification_manager()
+                    .notify_frontend(Operation::Add, Info::Table(internal_table))
+                    .await;
+            }
             core.add_table(table);
 
             let version = self
@@ -270,7 +357,13 @@ where
         }
     }
 
-    pub async fn drop_table(&self, table_id: TableId) -> Result<NotificationVersion> {
+    /// Drops the table `table_id`, together with the catalogs of its `internal_table_ids`.
+    pub async fn drop_table(
+        &self,
+        table_id: TableId,
+        internal_table_ids: Vec<TableId>,
+        record: Option<IdempotencyRecord>,
+    ) -> Result<NotificationVersion> {
         let mut core = self.core.lock().await;
         let table = Table::select(self.env.meta_store(), &table_id).await?;
         if let Some(table) = table {
@@ -285,7 +378,21 @@ where
                 )
                 .into()),
                 None => {
-                    Table::delete(self.env.meta_store(), &table_id).await?;
+                    let internal_tables = core.select_internal_tables(internal_table_ids).await?;
+                    let mut transaction = Transaction::default();
+                    for internal_table in &internal_tables {
+                        internal_table.delete_in_transaction(&mut transaction)?;
+                    }
+                    table.delete_in_transaction(&mut transaction)?;
+                    upsert_record(&mut transaction, record)?;
+                    core.env.meta_store().txn(transaction).await?;
+                    for internal_table in internal_tables {
+                        core.drop_table(&internal_table);
+                        self.env
+                            .notification_manager()
+                            .notify_frontend(Operation::Delete, Info::Table(internal_table))
+                            .await;
+                    }
                     core.drop_table(&table);
                     for &dependent_relation_id in &table.dependent_relations {
                         core.decrease_ref_count(dependent_relation_id);
@@ -323,12 +430,16 @@ where
     pub async fn finish_create_source_procedure(
         &self,
         source: &Source,
+        record: Option<IdempotencyRecord>,
     ) -> Result<NotificationVersion> {
         let mut core = self.core.lock().await;
         let key = (source.database_id, source.schema_id, source.name.clone());
         if !core.has_source(source) && core.has_in_progress_creation(&key) {
             core.unmark_creating(&key);
-            source.insert(self.env.meta_store()).await?;
+            let mut transaction = Transaction::default();
+            source.upsert_in_transaction(&mut transaction)?;
+            upsert_record(&mut transaction, record)?;
+            self.env.meta_store().txn(transaction).await?;
             core.add_source(source);
 
             let version = self
@@ -378,7 +489,11 @@ where
         }
     }
 
-    pub async fn drop_source(&self, source_id: SourceId) -> Result<NotificationVersion> {
+    pub async fn drop_source(
+        &self,
+        source_id: SourceId,
+        record: Option<IdempotencyRecord>,
+    ) -> Result<NotificationVersion> {
         let mut core = self.core.lock().await;
         let source = Source::select(self.env.meta_store(), &source_id).await?;
         if let Some(source) = source {
@@ -393,7 +508,10 @@ where
                 )
                 .into()),
                 None => {
-                    Source::delete(self.env.meta_store(), &source_id).await?;
+                    let mut transaction = Transaction::default();
+                    source.delete_in_transaction(&mut transaction)?;
+                    upsert_record(&mut transaction, record)?;
+                    self.env.meta_store().txn(transaction).await?;
                     core.drop_source(&source);
 
                     let version = self
@@ -436,10 +554,14 @@ where
         }
     }
 
+    /// Finishes creating `source` and `mview`, together with the catalogs of the
+    /// `internal_tables` of `mview`.
     pub async fn finish_create_materialized_source_procedure(
         &self,
         source: &Source,
+        internal_tables: Vec<Table>,
         mview: &Table,
+        record: Option<IdempotencyRecord>,
     ) -> Result<NotificationVersion> {
         let mut core = self.core.lock().await;
         let source_key = (source.database_id, source.schema_id, source.name.clone());
@@ -454,9 +576,20 @@ where
 
             let mut transaction = Transaction::default();
             source.upsert_in_transaction(&mut transaction)?;
+            for internal_table in &internal_tables {
+                internal_table.upsert_in_transaction(&mut transaction)?;
+            }
             mview.upsert_in_transaction(&mut transaction)?;
+            upsert_record(&mut transaction, record)?;
             core.env.meta_store().txn(transaction).await?;
             core.add_source(source);
+            for internal_table in internal_tables {
+                core.add_table(&internal_table);
+                self.env
+                    .notification_manager()
+                    .notify_frontend(Operation::Add, Info::Table(internal_table))
+                    .await;
+            }
             core.add_table(mview);
 
             self.env
@@ -500,10 +633,14 @@ where
         }
     }
 
+    /// Drops `source_id` and `mview_id`, together with the catalogs of the `internal_table_ids` of
+    /// the mview.
     pub async fn drop_materialized_source(
         &self,
         source_id: SourceId,
         mview_id: TableId,
+        internal_table_ids: Vec<TableId>,
+        record: Option<IdempotencyRecord>,
     ) -> Result<NotificationVersion> {
         let mut core = self.core.lock().await;
         let mview = Table::select(self.env.meta_store(), &mview_id).await?;
@@ -549,10 +686,22 @@ where
                 }
 
                 // now is safe to delete both mview and source
+                let internal_tables = core.select_internal_tables(internal_table_ids).await?;
                 let mut transaction = Transaction::default();
+                for internal_table in &internal_tables {
+                    internal_table.delete_in_transaction(&mut transaction)?;
+                }
                 mview.delete_in_transaction(&mut transaction)?;
                 source.delete_in_transaction(&mut transaction)?;
+                upsert_record(&mut transaction, record)?;
                 core.env.meta_store().txn(transaction).await?;
+                for internal_table in internal_tables {
+                    core.drop_table(&internal_table);
+                    self.env
+                        .notification_manager()
+                        .notify_frontend(Operation::Delete, Info::Table(internal_table))
+                        .await;
+                }
                 core.drop_table(&mview);
                 core.drop_source(&source);
                 for &dependent_relation_id in &mview.dependent_relations {
@@ -577,6 +726,273 @@ where
         }
     }
 
+    /// Returns the id of the table whose rows are stored by the source `source_id`, if any.
+    pub async fn get_associated_table_id(&self, source_id: SourceId) -> Result<Option<TableId>> {
+        let core = self.core.lock().await;
+        let tables = Table::list(core.env.meta_store()).await?;
+        Ok(tables
+            .iter()
+            .find(|table| {
+                table.optional_associated_source_id
+                    == Some(OptionalAssociatedSourceId::AssociatedSourceId(source_id))
+            })
+            .map(|table| table.id))
+    }
+
+    /// Appends `column` to the source `source_id`, and to its associated table if any.
+    pub async fn add_source_column(
+        &self,
+        source_id: SourceId,
+        column: &ColumnCatalog,
+        record: Option<IdempotencyRecord>,
+    ) -> Result<NotificationVersion> {
+        let core = self.core.lock().await;
+        let mut source = Source::select(self.env.meta_store(), &source_id)
+            .await?
+            .ok_or_else(|| InternalError("source doesn't exist".to_string()))?;
+        let columns = match source.info.as_mut() {
+            Some(SourceInfo::StreamSource(info)) => &mut info.columns,
+            Some(SourceInfo::TableSource(info)) => &mut info.columns,
+            None => return Err(InternalError("source info is missing".to_string()).into()),
+        };
+        let column_desc = column.get_column_desc()?;
+        if columns.iter().any(|c| {
+            let desc = c.column_desc.as_ref().unwrap();
+            desc.name == column_desc.name || desc.column_id == column_desc.column_id
+        }) {
+            return Err(CatalogError(
+                anyhow!(
+                    "column `{}` of source `{}` already exists",
+                    column_desc.name,
+                    source.name
+                )
+                .into(),
+            )
+            .into());
+        }
+        columns.push(column.clone());
+
+        let tables = Table::list(core.env.meta_store()).await?;
+        let table = tables.into_iter().find(|table| {
+            table.optional_associated_source_id
+                == Some(OptionalAssociatedSourceId::AssociatedSourceId(source_id))
+        });
+        let table = table.map(|mut table| {
+            table.columns.push(column.clone());
+            table
+        });
+
+        let mut transaction = Transaction::default();
+        source.upsert_in_transaction(&mut transaction)?;
+        if let Some(table) = &table {
+            table.upsert_in_transaction(&mut transaction)?;
+        }
+        upsert_record(&mut transaction, record)?;
+        core.env.meta_store().txn(transaction).await?;
+
+        if let Some(table) = table {
+            self.env
+                .notification_manager()
+                .notify_frontend(Operation::Update, Info::Table(table))
+                .await;
+        }
+        let version = self
+            .env
+            .notification_manager()
+            .notify_frontend(Operation::Update, Info::Source(source))
+            .await;
+        Ok(version)
+    }
+
+    /// Returns the table or source `relation`, together with its associated source or table.
+    async fn select_relation(
+        &self,
+        relation: RelationIdEnum,
+    ) -> Result<(Option<Table>, Option<Source>)> {
+        let meta_store = self.env.meta_store();
+        match relation {
+            RelationIdEnum::Table(table_id) => {
+                let table = Table::select(meta_store, &table_id)
+                    .await?
+                    .ok_or_else(|| InternalError("table doesn't exist".to_string()))?;
+                let source = match table.optional_associated_source_id {
+                    Some(OptionalAssociatedSourceId::AssociatedSourceId(source_id)) => {
+                        Source::select(meta_store, &source_id).await?
+                    }
+                    None => None,
+                };
+                Ok((Some(table), source))
+            }
+            RelationIdEnum::Source(source_id) => {
+                let source = Source::select(meta_store, &source_id)
+                    .await?
+                    .ok_or_else(|| InternalError("source doesn't exist".to_string()))?;
+                let table = Table::list(meta_store).await?.into_iter().find(|table| {
+                    table.optional_associated_source_id
+                        == Some(OptionalAssociatedSourceId::AssociatedSourceId(source_id))
+                });
+                Ok((table, Some(source)))
+            }
+        }
+    }
+
+    /// Persists the updated `table` and `source` in a transaction, and notifies frontends.
+    async fn update_relation(
+        &self,
+        table: Option<Table>,
+        source: Option<Source>,
+        record: Option<IdempotencyRecord>,
+    ) -> Result<NotificationVersion> {
+        let mut transaction = Transaction::default();
+        if let Some(table) = &table {
+            table.upsert_in_transaction(&mut transaction)?;
+        }
+        if let Some(source) = &source {
+            source.upsert_in_transaction(&mut transaction)?;
+        }
+        upsert_record(&mut transaction, record)?;
+        self.env.meta_store().txn(transaction).await?;
+
+        let mut version = NotificationVersion::default();
+        if let Some(table) = table {
+            version = self
+                .env
+                .notification_manager()
+                .notify_frontend(Operation::Update, Info::Table(table))
+                .await;
+        }
+        if let Some(source) = source {
+            version = self
+                .env
+                .notification_manager()
+                .notify_frontend(Operation::Update, Info::Source(source))
+                .await;
+        }
+        Ok(version)
+    }
+
+    /// Renames the table or source `relation`, together with its associated source or table.
+    /// Relations are referred to by ids everywhere else, so nothing else changes.
+    pub async fn alter_relation_name(
+        &self,
+        relation: RelationIdEnum,
+        new_name: &str,
+        record: Option<IdempotencyRecord>,
+    ) -> Result<NotificationVersion> {
+        let mut core = self.core.lock().await;
+        let (mut table, mut source) = self.select_relation(relation).await?;
+        let (database_id, schema_id) = match (&table, &source) {
+            (Some(table), _) => (table.database_id, table.schema_id),
+            (None, Some(source)) => (source.database_id, source.schema_id),
+            (None, None) => unreachable!(),
+        };
+        let key = (database_id, schema_id, new_name.to_string());
+        if core.tables.contains(&key)
+            || core.sources.contains(&key)
+            || core.has_in_progress_creation(&key)
+        {
+            return Err(
+                CatalogError(anyhow!("relation `{}` already exists", new_name).into()).into(),
+            );
+        }
+
+        let old_table = table.clone();
+        let old_source = source.clone();
+        if let Some(table) = &mut table {
+            table.name = new_name.to_string();
+        }
+        if let Some(source) = &mut source {
+            source.name = new_name.to_string();
+        }
+        let version = self
+            .update_relation(table.clone(), source.clone(), record)
+            .await?;
+
+        if let (Some(old_table), Some(table)) = (old_table, table) {
+            core.drop_table(&old_table);
+            core.add_table(&table);
+        }
+        if let (Some(old_source), Some(source)) = (old_source, source) {
+            core.drop_source(&old_source);
+            core.add_source(&source);
+        }
+        Ok(version)
+    }
+
+    /// Changes the owner of the table or source `relation`, together with its associated source or
+    /// table.
+    pub async fn alter_relation_owner(
+        &self,
+        relation: RelationIdEnum,
+        new_owner: &str,
+        record: Option<IdempotencyRecord>,
+    ) -> Result<NotificationVersion> {
+        let _core = self.core.lock().await;
+        let (mut table, mut source) = self.select_relation(relation).await?;
+        if let Some(table) = &mut table {
+            table.owner = new_owner.to_string();
+        }
+        if let Some(source) = &mut source {
+            source.owner = new_owner.to_string();
+        }
+        self.update_relation(table, source, record).await
+    }
+
+    pub async fn create_function(
+        &self,
+        function: &Function,
+        record: Option<IdempotencyRecord>,
+    ) -> Result<NotificationVersion> {
+        let mut core = self.core.lock().await;
+        if !core.has_function(function) {
+            let mut transaction = Transaction::default();
+            function.upsert_in_transaction(&mut transaction)?;
+            upsert_record(&mut transaction, record)?;
+            self.env.meta_store().txn(transaction).await?;
+            core.add_function(function);
+
+            let version = self
+                .env
+                .notification_manager()
+                .notify_frontend(Operation::Add, Info::Function(function.to_owned()))
+                .await;
+
+            Ok(version)
+        } else {
+            Err(RwError::from(CatalogError(
+                anyhow!("function `{}` already exists", function.name).into(),
+            )))
+        }
+    }
+
+    pub async fn drop_function(
+        &self,
+        function_id: FunctionId,
+        record: Option<IdempotencyRecord>,
+    ) -> Result<NotificationVersion> {
+        let mut core = self.core.lock().await;
+        let function = Function::select(self.env.meta_store(), &function_id).await?;
+        if let Some(function) = function {
+            let mut transaction = Transaction::default();
+            function.delete_in_transaction(&mut transaction)?;
+            upsert_record(&mut transaction, record)?;
+            self.env.meta_store().txn(transaction).await?;
+            core.drop_function(&function);
+
+            let version = self
+                .env
+                .notification_manager()
+                .notify_frontend(Operation::Delete, Info::Function(function))
+                .await;
+
+            Ok(version)
+        } else {
+            Err(RwError::from(InternalError(
+                "function doesn't exist".to_string(),
+            )))
+        }
+    }
+
     pub async fn list_tables(&self, schema_id: SchemaId) -> Result<Vec<TableId>> {
         let core = self.core.lock().await;
         let tables = Table::list(core.env.meta_store()).await?;
@@ -603,6 +1019,7 @@ type SchemaKey = (DatabaseId, String);
 type TableKey = (DatabaseId, SchemaId, String);
 type SourceKey = (DatabaseId, SchemaId, String);
 type RelationKey = (DatabaseId, SchemaId, String);
+type FunctionKey = (DatabaseId, SchemaId, String);
 
 /// [`CatalogManagerCore`] caches meta catalog information and maintains dependent relationship
 /// between tables.
@@ -616,6 +1033,8 @@ pub struct CatalogManagerCore<S: MetaStore> {
     sources: HashSet<SourceKey>,
     /// Cached table key information.
     tables: HashSet<TableKey>,
+    /// Cached function key information.
+    functions: HashSet<FunctionKey>,
     /// Relation refer count mapping.
     relation_ref_count: HashMap<RelationId, usize>,
 
@@ -632,6 +1051,7 @@ where
         let schemas = Schema::list(env.meta_store()).await?;
         let sources = Source::list(env.meta_store()).await?;
         let tables = Table::list(env.meta_store()).await?;
+        let functions = Function::list(env.meta_store()).await?;
 
         let mut relation_ref_count = HashMap::new();
 
@@ -648,10 +1068,15 @@ where
         );
         let tables = HashSet::from_iter(tables.into_iter().map(|table| {
             for depend_relation_id in &table.dependent_relations {
-                relation_ref_count.entry(*depend_relation_id).or_insert(0);
+                *relation_ref_count.entry(*depend_relation_id).or_insert(0) += 1;
             }
             (table.database_id, table.schema_id, table.name)
         }));
+        let functions = HashSet::from_iter(
+            functions
+                .into_iter()
+                .map(|function| (function.database_id, function.schema_id, function.name)),
+        );
 
         let in_progress_creation_tracker = HashSet::new();
 
@@ -661,6 +1086,7 @@ where
             schemas,
             sources,
             tables,
+            functions,
             relation_ref_count,
             in_progress_creation_tracker,
         })
@@ -672,6 +1098,7 @@ where
             Schema::list(self.env.meta_store()).await?,
             Table::list(self.env.meta_store()).await?,
             Source::list(self.env.meta_store()).await?,
+            Function::list(self.env.meta_store()).await?,
         ))
     }
 
@@ -736,10 +1163,46 @@ where
             .remove(&(source.database_id, source.schema_id, source.name.clone()))
     }
 
+    fn has_function(&self, function: &Function) -> bool {
+        self.functions.contains(&(
+            function.database_id,
+            function.schema_id,
+            function.name.clone(),
+        ))
+    }
+
+    fn add_function(&mut self, function: &Function) {
+        self.functions.insert((
+            function.database_id,
+            function.schema_id,
+            function.name.clone(),
+        ));
+    }
+
+    fn drop_function(&mut self, function: &Function) -> bool {
+        self.functions.remove(&(
+            function.database_id,
+            function.schema_id,
+            function.name.clone(),
+        ))
+    }
+
     pub async fn get_source(&self, id: SourceId) -> Result<Option<Source>> {
         Source::select(self.env.meta_store(), &id).await
     }
 
+    /// Selects the catalogs of the internal tables `ids`. The internal tables whose states are not
+    /// scannable have no catalogs, which are skipped.
+    async fn select_internal_tables(&self, ids: Vec<TableId>) -> Result<Vec<Table>> {
+        let mut tables = Vec::with_capacity(ids.len());
+        for id in ids {
+            if let Some(table) = Table::select(self.env.meta_store(), &id).await? {
+                tables.push(table);
+            }
+        }
+        Ok(tables)
+    }
+
     fn get_ref_count(&self, relation_id: RelationId) -> Option<usize> {
         self.relation_ref_count.get(&relation_id).cloned()
     }
@@ -773,3 +1236,67 @@ where
         self.in_progress_creation_tracker.remove(&relation.clone());
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table_on(id: TableId, name: &str, dependent_relations: Vec<RelationId>) -> Table {
+        Table {
+            id,
+            name: name.to_string(),
+            dependent_relations,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_ref_count_rebuilt_on_load() -> Result<()> {
+        let env = MetaSrvEnv::for_test().await;
+        // `mv2` is created on `mv1`, which is created on the table `t`.
+        let t = table_on(1, "t", vec![]);
+        let mv1 = table_on(2, "mv1", vec![t.id]);
+        let mv2 = table_on(3, "mv2", vec![mv1.id]);
+        for table in [&t, &mv1, &mv2] {
+            table.insert(env.meta_store()).await?;
+        }
+
+        // Load the catalogs from the store, as the meta node does on restart.
+        let catalog_manager = CatalogManager::new(env.clone()).await?;
+        assert!(catalog_manager.drop_table(mv1.id, vec![], None).await.is_err());
+        assert!(catalog_manager.drop_table(t.id, vec![], None).await.is_err());
+
+        // The relations can be dropped from the downstream.
+        catalog_manager.drop_table(mv2.id, vec![], None).await?;
+        catalog_manager.drop_table(mv1.id, vec![], None).await?;
+        catalog_manager.drop_table(t.id, vec![], None).await?;
+        Ok(())
+    }
+    #[tokio::test]
+    async fn test_record_written_with_ddl() -> Result<()> {
+        let env = MetaSrvEnv::for_test().await;
+        let catalog_manager = CatalogManager::new(env.clone()).await?;
+        let record = IdempotencyRecord {
+            key: "a".to_string(),
+            ..Default::default()
+        };
+
+        // The record is not written if the DDL fails.
+        assert!(catalog_manager
+            .drop_table(1, vec![], Some(record.clone()))
+            .await
+            .is_err());
+        assert!(IdempotencyRecord::select(env.meta_store(), &record.key)
+            .await?
+            .is_none());
+
+        catalog_manager
+            .create_function(&Function::default(), Some(record.clone()))
+            .await?;
+        assert_eq!(
+            IdempotencyRecord::select(env.meta_store(), &record.key).await?,
+            Some(record)
+        );
+        Ok(())
+    }
+}
@@ -16,11 +16,13 @@ use std::ops::Deref;
 use std::sync::Arc;
 use std::time::Duration;
 
+use risingwave_common::config::{DynamicConfig, DynamicConfigReceiver};
 use risingwave_rpc_client::{StreamClientPool, StreamClientPoolRef};
 
 use super::{HashMappingManager, HashMappingManagerRef};
 use crate::manager::{
-    IdGeneratorManager, IdGeneratorManagerRef, NotificationManager, NotificationManagerRef,
+    EventLogManager, EventLogManagerRef, IdGeneratorManager, IdGeneratorManagerRef,
+    IdempotencyManager, IdempotencyManagerRef, NotificationManager, NotificationManagerRef,
 };
 #[cfg(any(test, feature = "test"))]
 use crate::storage::MemStore;
@@ -48,6 +50,12 @@ where
     /// stream client pool memorization.
     stream_client_pool: StreamClientPoolRef,
 
+    /// event log of the cluster.
+    event_log_manager: EventLogManagerRef<S>,
+
+    /// responses of the applied requests, to deduplicate their retries.
+    idempotency_manager: IdempotencyManagerRef<S>,
+
     /// options read by all services
     pub opts: Arc<MetaOpts>,
 }
@@ -56,6 +64,14 @@ where
 pub struct MetaOpts {
     pub enable_recovery: bool,
     pub checkpoint_interval: Duration,
+    /// Object store of the meta backups, e.g. `s3://bucket`. Backups are disabled if not set.
+    pub backup_storage_url: Option<String>,
+    /// Directory of the meta backups in the object store.
+    pub backup_storage_directory: String,
+    /// Knobs that can be changed at runtime. The checkpoint interval follows its changes.
+    pub dynamic_config: DynamicConfigReceiver,
+    /// How long the response of a request with an idempotency key is kept for its retries.
+    pub idempotency_window: Duration,
 }
 
 impl Default for MetaOpts {
@@ -63,6 +79,10 @@ impl Default for MetaOpts {
         Self {
             enable_recovery: false,
             checkpoint_interval: Duration::from_millis(100),
+            backup_storage_url: None,
+            backup_storage_directory: "backup".to_string(),
+            dynamic_config: DynamicConfig::default().fixed(),
+            idempotency_window: Duration::from_secs(600),
         }
     }
 }
@@ -77,6 +97,14 @@ where
         let stream_client_pool = Arc::new(StreamClientPool::default());
         let notification_manager = Arc::new(NotificationManager::new());
         let hash_mapping_manager = Arc::new(HashMappingManager::new());
+        let event_log_manager = Arc::new(EventLogManager::new(
+            meta_store.clone(),
+            id_gen_manager.clone(),
+        ));
+        let idempotency_manager = Arc::new(IdempotencyManager::new(
+            meta_store.clone(),
+            opts.idempotency_window,
+        ));
 
         Self {
             id_gen_manager,
@@ -84,6 +112,8 @@ where
             notification_manager,
             hash_mapping_manager,
             stream_client_pool,
+            event_log_manager,
+            idempotency_manager,
             opts: opts.into(),
         }
     }
@@ -127,6 +157,22 @@ where
     pub fn stream_client_pool(&self) -> &StreamClientPool {
         self.stream_client_pool.deref()
     }
+
+    pub fn event_log_manager_ref(&self) -> EventLogManagerRef<S> {
+        self.event_log_manager.clone()
+    }
+
+    pub fn event_log_manager(&self) -> &EventLogManager<S> {
+        self.event_log_manager.deref()
+    }
+
+    pub fn idempotency_manager_ref(&self) -> IdempotencyManagerRef<S> {
+        self.idempotency_manager.clone()
+    }
+
+    pub fn idempotency_manager(&self) -> &IdempotencyManager<S> {
+        self.idempotency_manager.deref()
+    }
 }
 
 #[cfg(any(test, feature = "test"))]
@@ -139,6 +185,15 @@ impl MetaSrvEnv<MemStore> {
         let notification_manager = Arc::new(NotificationManager::new());
         let stream_client_pool = Arc::new(StreamClientPool::default());
         let hash_mapping_manager = Arc::new(HashMappingManager::new());
+        let event_log_manager = Arc::new(EventLogManager::new(
+            meta_store.clone(),
+            id_gen_manager.clone(),
+        ));
+        let opts = MetaOpts::default();
+        let idempotency_manager = Arc::new(IdempotencyManager::new(
+            meta_store.clone(),
+            opts.idempotency_window,
+        ));
 
         Self {
             id_gen_manager,
@@ -146,7 +201,9 @@ impl MetaSrvEnv<MemStore> {
             notification_manager,
             hash_mapping_manager,
             stream_client_pool,
-            opts: MetaOpts::default().into(),
+            event_log_manager,
+            idempotency_manager,
+            opts: opts.into(),
         }
     }
 }
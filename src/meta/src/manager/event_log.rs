@@ -0,0 +1,158 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use itertools::Itertools;
+use risingwave_common::error::Result;
+use risingwave_pb::meta::event_log::EventType;
+use risingwave_pb::meta::EventLog;
+
+use crate::manager::{IdCategory, IdGeneratorManagerRef};
+use crate::model::MetadataModel;
+use crate::storage::MetaStore;
+
+pub type EventLogManagerRef<S> = Arc<EventLogManager<S>>;
+
+/// [`EventLogManager`] appends the events of the cluster, e.g. DDL operations, recoveries and
+/// worker joins and leaves, to the meta store. Events are never updated or deleted.
+pub struct EventLogManager<S: MetaStore> {
+    meta_store: Arc<S>,
+    id_gen_manager: IdGeneratorManagerRef<S>,
+}
+
+impl<S: MetaStore> EventLogManager<S> {
+    pub fn new(meta_store: Arc<S>, id_gen_manager: IdGeneratorManagerRef<S>) -> Self {
+        Self {
+            meta_store,
+            id_gen_manager,
+        }
+    }
+
+    /// Appends an event to the log.
+    pub async fn add_event(&self, event_type: EventType, info: String) -> Result<EventLog> {
+        let id = self
+            .id_gen_manager
+            .generate::<{ IdCategory::EventLog }>()
+            .await? as u64;
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("Clock may have gone backwards")
+            .as_millis() as u64;
+        let event = EventLog {
+            id,
+            timestamp,
+            event_type: event_type as i32,
+            info,
+        };
+        event.insert(self.meta_store.as_ref()).await?;
+        Ok(event)
+    }
+
+    /// Appends an event to the log. The event log is only for diagnosis, so a failure to persist
+    /// the event is logged instead of failing the operation that triggers it.
+    pub async fn log(&self, event_type: EventType, info: impl Into<String>) {
+        let info = info.into();
+        if let Err(err) = self.add_event(event_type, info.clone()).await {
+            tracing::warn!("failed to log event {:?} {}: {}", event_type, info, err);
+        }
+    }
+
+    /// Appends a config change event if `config` differs from the latest logged config, e.g. the
+    /// meta node is restarted with different options.
+    pub async fn log_config(&self, config: String) {
+        let changed = match self.list_events(0).await {
+            Ok(events) => events
+                .iter()
+                .find(|event| event.event_type() == EventType::ConfigChange)
+                .map_or(true, |event| event.info != config),
+            Err(err) => {
+                tracing::warn!("failed to list events: {}", err);
+                return;
+            }
+        };
+        if changed {
+            self.log(EventType::ConfigChange, config).await;
+        }
+    }
+
+    /// Returns the latest `limit` events from the newest to the oldest, or all events if `limit`
+    /// is 0.
+    pub async fn list_events(&self, limit: usize) -> Result<Vec<EventLog>> {
+        let events = EventLog::list(self.meta_store.as_ref())
+            .await?
+            .into_iter()
+            .sorted_by_key(|event| std::cmp::Reverse(event.id));
+        Ok(if limit == 0 {
+            events.collect()
+        } else {
+            events.take(limit).collect()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::manager::MetaSrvEnv;
+
+    #[tokio::test]
+    async fn test_event_log() -> Result<()> {
+        let env = MetaSrvEnv::for_test().await;
+        let event_log_manager = env.event_log_manager();
+
+        event_log_manager
+            .add_event(EventType::WorkerJoin, "compute node 1".to_string())
+            .await?;
+        event_log_manager
+            .add_event(EventType::Ddl, "create materialized view mv".to_string())
+            .await?;
+        event_log_manager
+            .log(EventType::Recovery, "barrier failed")
+            .await;
+
+        let events = event_log_manager.list_events(0).await?;
+        assert_eq!(
+            events
+                .iter()
+                .map(|event| (event.event_type(), event.info.as_str()))
+                .collect_vec(),
+            vec![
+                (EventType::Recovery, "barrier failed"),
+                (EventType::Ddl, "create materialized view mv"),
+                (EventType::WorkerJoin, "compute node 1"),
+            ]
+        );
+        assert!(events.windows(2).all(|w| w[0].timestamp >= w[1].timestamp));
+
+        let events = event_log_manager.list_events(1).await?;
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].info, "barrier failed");
+
+        // Only changed configs are logged.
+        event_log_manager.log_config("a".to_string()).await;
+        event_log_manager.log_config("a".to_string()).await;
+        event_log_manager.log_config("b".to_string()).await;
+        let configs = event_log_manager
+            .list_events(0)
+            .await?
+            .into_iter()
+            .filter(|event| event.event_type() == EventType::ConfigChange)
+            .map(|event| event.info)
+            .collect_vec();
+        assert_eq!(configs, vec!["b", "a"]);
+        Ok(())
+    }
+}
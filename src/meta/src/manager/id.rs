@@ -133,6 +133,8 @@ pub mod IdCategory {
     pub const ParallelUnit: IdCategoryType = 9;
     pub const Source: IdCategoryType = 10;
     pub const HummockCompactionTask: IdCategoryType = 11;
+    pub const Function: IdCategoryType = 12;
+    pub const EventLog: IdCategoryType = 13;
 }
 
 pub type IdGeneratorManagerRef<S> = Arc<IdGeneratorManager<S>>;
@@ -152,6 +154,8 @@ pub struct IdGeneratorManager<S> {
     hummock_ss_table_id: Arc<StoredIdGenerator<S>>,
     hummock_compaction_task: Arc<StoredIdGenerator<S>>,
     parallel_unit: Arc<StoredIdGenerator<S>>,
+    function: Arc<StoredIdGenerator<S>>,
+    event_log: Arc<StoredIdGenerator<S>>,
 }
 
 impl<S> IdGeneratorManager<S>
@@ -186,6 +190,10 @@ where
             parallel_unit: Arc::new(
                 StoredIdGenerator::new(meta_store.clone(), "parallel_unit", None).await,
             ),
+            function: Arc::new(StoredIdGenerator::new(meta_store.clone(), "function", None).await),
+            event_log: Arc::new(
+                StoredIdGenerator::new(meta_store.clone(), "event_log", Some(1)).await,
+            ),
         }
     }
 
@@ -203,6 +211,8 @@ where
             IdCategory::HummockSSTableId => &self.hummock_ss_table_id,
             IdCategory::ParallelUnit => &self.parallel_unit,
             IdCategory::HummockCompactionTask => &self.hummock_compaction_task,
+            IdCategory::Function => &self.function,
+            IdCategory::EventLog => &self.event_log,
             _ => unreachable!(),
         }
     }
@@ -0,0 +1,237 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use parking_lot::Mutex;
+use prost::Message;
+use risingwave_common::error::Result;
+use risingwave_pb::meta::IdempotencyRecord;
+use tokio::sync::OwnedMutexGuard;
+
+use crate::model::MetadataModel;
+use crate::storage::MetaStore;
+
+pub type IdempotencyManagerRef<S> = Arc<IdempotencyManager<S>>;
+
+/// [`IdempotencyManager`] deduplicates the retries of mutating requests by their idempotency
+/// keys. The response of a request is persisted in the same meta-store transaction that applies the
+/// request, so that a retry within the dedup window, even one sent to a new leader after a
+/// failover, gets the original response instead of applying the request again.
+///
+/// The notification version in a response is only known after the transaction commits, so the
+/// persisted response carries no version. Replays should fill in the current version instead.
+pub struct IdempotencyManager<S: MetaStore> {
+    meta_store: Arc<S>,
+    /// How long the response of a request is kept for its retries.
+    window: Duration,
+    /// Locks of the keys being applied, so that concurrent attempts of a request are serialized.
+    in_flight: Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>>,
+    /// When the expired records were last purged, in ms since the unix epoch.
+    last_purge: AtomicU64,
+}
+
+/// The outcome of [`IdempotencyManager::begin`].
+pub enum Dedup<'a, T, S: MetaStore> {
+    /// The request has been applied. Returns the original response.
+    Replay(T),
+    /// The request should be applied, along with the record built by the guard.
+    Apply(IdempotencyGuard<'a, S>),
+}
+
+/// Holds the key of a request being applied. Other attempts of the request wait until the guard
+/// is dropped, after which they replay the record if the request has been applied, or apply the
+/// request again otherwise.
+pub struct IdempotencyGuard<'a, S: MetaStore> {
+    manager: &'a IdempotencyManager<S>,
+    /// `None` if the request has no idempotency key.
+    key: Option<String>,
+    lock: Option<OwnedMutexGuard<()>>,
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("Clock may have gone backwards")
+        .as_millis() as u64
+}
+
+impl<S: MetaStore> IdempotencyManager<S> {
+    pub fn new(meta_store: Arc<S>, window: Duration) -> Self {
+        Self {
+            meta_store,
+            window,
+            in_flight: Default::default(),
+            last_purge: AtomicU64::new(0),
+        }
+    }
+
+    /// Starts to handle a request with idempotency key `key`. Returns the response of the request
+    /// if it has been applied within the dedup window. Requests with an empty key are always
+    /// applied.
+    pub async fn begin<T: Message + Default>(&self, key: &str) -> Result<Dedup<'_, T, S>> {
+        if key.is_empty() {
+            return Ok(Dedup::Apply(IdempotencyGuard {
+                manager: self,
+                key: None,
+                lock: None,
+            }));
+        }
+        let lock = self
+            .in_flight
+            .lock()
+            .entry(key.to_string())
+            .or_default()
+            .clone();
+        let guard = IdempotencyGuard {
+            manager: self,
+            key: Some(key.to_string()),
+            lock: Some(lock.lock_owned().await),
+        };
+        let record = IdempotencyRecord::select(self.meta_store.as_ref(), &key.to_string()).await?;
+        if let Some(record) = record.filter(|record| !self.is_expired(record, now_ms())) {
+            return Ok(Dedup::Replay(T::decode(record.response.as_slice())?));
+        }
+        Ok(Dedup::Apply(guard))
+    }
+
+    fn is_expired(&self, record: &IdempotencyRecord, now: u64) -> bool {
+        now.saturating_sub(record.timestamp) >= self.window.as_millis() as u64
+    }
+
+    /// Deletes the expired records, at most once per window.
+    async fn purge_expired(&self, now: u64) {
+        let last_purge = self.last_purge.load(Ordering::Relaxed);
+        if now.saturating_sub(last_purge) < self.window.as_millis() as u64
+            || self
+                .last_purge
+                .compare_exchange(last_purge, now, Ordering::Relaxed, Ordering::Relaxed)
+                .is_err()
+        {
+            return;
+        }
+        if let Err(err) = self.delete_expired(now).await {
+            tracing::warn!("failed to purge expired idempotency records: {}", err);
+        }
+    }
+
+    async fn delete_expired(&self, now: u64) -> Result<()> {
+        for record in IdempotencyRecord::list(self.meta_store.as_ref()).await? {
+            if self.is_expired(&record, now) {
+                IdempotencyRecord::delete(self.meta_store.as_ref(), &record.key).await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'a, S: MetaStore> IdempotencyGuard<'a, S> {
+    /// Builds the record of the request with its `response`, to be written in the same
+    /// transaction that applies the request. Returns `None` if the request has no key.
+    pub fn record(&self, response: &impl Message) -> Option<IdempotencyRecord> {
+        self.key.as_ref().map(|key| IdempotencyRecord {
+            key: key.clone(),
+            timestamp: now_ms(),
+            response: response.encode_to_vec(),
+        })
+    }
+
+    /// Finishes the request after it has been applied, and purges the expired records.
+    pub async fn finish(self) {
+        if self.key.is_some() {
+            self.manager.purge_expired(now_ms()).await;
+        }
+    }
+}
+
+impl<'a, S: MetaStore> Drop for IdempotencyGuard<'a, S> {
+    fn drop(&mut self) {
+        if let Some(key) = &self.key {
+            self.lock.take();
+            let mut in_flight = self.manager.in_flight.lock();
+            // Keep the lock if other attempts of the request are waiting for it.
+            if in_flight
+                .get(key)
+                .map_or(false, |lock| Arc::strong_count(lock) == 1)
+            {
+                in_flight.remove(key);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_pb::ddl_service::DropDatabaseResponse;
+
+    use super::*;
+    use crate::manager::MetaSrvEnv;
+
+    fn response_of(version: u64) -> DropDatabaseResponse {
+        DropDatabaseResponse {
+            status: None,
+            version,
+        }
+    }
+
+    async fn apply(
+        manager: &IdempotencyManager<impl MetaStore>,
+        key: &str,
+        version: u64,
+    ) -> Result<DropDatabaseResponse> {
+        match manager.begin(key).await? {
+            Dedup::Replay(response) => Ok(response),
+            Dedup::Apply(guard) => {
+                let response = response_of(version);
+                if let Some(record) = guard.record(&response) {
+                    record.insert(manager.meta_store.as_ref()).await?;
+                }
+                guard.finish().await;
+                Ok(response)
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_idempotency() -> Result<()> {
+        let env = MetaSrvEnv::for_test().await;
+        let manager = env.idempotency_manager();
+
+        // Retries get the original response.
+        assert_eq!(apply(manager, "a", 1).await?, response_of(1));
+        assert_eq!(apply(manager, "a", 2).await?, response_of(1));
+        assert_eq!(apply(manager, "b", 3).await?, response_of(3));
+
+        // Requests without keys are always applied.
+        assert_eq!(apply(manager, "", 4).await?, response_of(4));
+        assert_eq!(apply(manager, "", 5).await?, response_of(5));
+
+        // A failed attempt doesn't block the retries.
+        match manager.begin::<DropDatabaseResponse>("c").await? {
+            Dedup::Apply(guard) => drop(guard),
+            Dedup::Replay(_) => panic!("request c has not been applied"),
+        }
+        assert_eq!(apply(manager, "c", 6).await?, response_of(6));
+        assert!(manager.in_flight.lock().is_empty());
+
+        // Responses are not kept beyond the window.
+        let manager = IdempotencyManager::new(env.meta_store_ref(), Duration::ZERO);
+        assert_eq!(apply(&manager, "d", 7).await?, response_of(7));
+        assert_eq!(apply(&manager, "d", 8).await?, response_of(8));
+        Ok(())
+    }
+}
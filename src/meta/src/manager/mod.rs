@@ -14,14 +14,18 @@
 
 mod catalog;
 mod env;
+mod event_log;
 mod hash_mapping;
 mod id;
+mod idempotency;
 mod notification;
 mod user;
 
 pub use catalog::*;
 pub use env::*;
+pub use event_log::*;
 pub use hash_mapping::*;
 pub use id::*;
+pub use idempotency::*;
 pub use notification::*;
 pub use user::*;
@@ -15,6 +15,8 @@
 mod barrier;
 mod catalog;
 mod cluster;
+mod event_log;
+mod idempotency;
 mod stream;
 mod user;
 
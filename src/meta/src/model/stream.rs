@@ -16,12 +16,21 @@ use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
 
 use itertools::Itertools;
 use risingwave_common::catalog::TableId;
-use risingwave_common::error::Result;
+use risingwave_common::error::ErrorCode::InternalError;
+use risingwave_common::error::{Result, RwError};
+use risingwave_common::hash::{VIRTUAL_NODE_COUNT, VNODE_BITMAP_LEN};
+use risingwave_common::util::compress::{compress_data, decompress_data};
+use risingwave_pb::common::{ParallelUnit, ParallelUnitMapping};
+use risingwave_pb::data::{AddedColumn, DispatcherHashMapping, RescheduleMutation};
+use risingwave_pb::meta::table_fragments::fragment::FragmentDistributionType;
 use risingwave_pb::meta::table_fragments::{ActorState, ActorStatus, Fragment};
 use risingwave_pb::meta::TableFragments as ProstTableFragments;
+use risingwave_pb::plan_common::Field;
 use risingwave_pb::stream_plan::source_node::SourceType;
 use risingwave_pb::stream_plan::stream_node::NodeBody;
-use risingwave_pb::stream_plan::{FragmentType, StreamActor, StreamNode};
+use risingwave_pb::stream_plan::{
+    ActorMapping, DispatcherType, FragmentType, StreamActor, StreamNode,
+};
 
 use super::{ActorId, FragmentId};
 use crate::cluster::{ParallelUnitId, WorkerId};
@@ -48,6 +57,9 @@ pub struct TableFragments {
 
     /// Internal TableIds from all Fragment
     internal_table_ids: Vec<u32>,
+
+    /// The number of actors owning vnodes in each hash-distributed fragment.
+    parallelism: u32,
 }
 
 impl MetadataModel for TableFragments {
@@ -64,6 +76,7 @@ impl MetadataModel for TableFragments {
             fragments: self.fragments.clone().into_iter().collect(),
             actor_status: self.actor_status.clone().into_iter().collect(),
             internal_table_ids: self.internal_table_ids.clone(),
+            parallelism: self.parallelism,
         }
     }
 
@@ -73,6 +86,7 @@ impl MetadataModel for TableFragments {
             fragments: prost.fragments.into_iter().collect(),
             actor_status: prost.actor_status.into_iter().collect(),
             internal_table_ids: prost.internal_table_ids,
+            parallelism: prost.parallelism,
         }
     }
 
@@ -92,6 +106,7 @@ impl TableFragments {
             fragments,
             actor_status: BTreeMap::default(),
             internal_table_ids: Vec::from_iter(internal_table_id_set),
+            parallelism: 0,
         }
     }
 
@@ -109,6 +124,16 @@ impl TableFragments {
         self.table_id
     }
 
+    /// Returns the number of actors owning vnodes in each hash-distributed fragment.
+    pub fn parallelism(&self) -> u32 {
+        self.parallelism
+    }
+
+    /// Set the number of actors owning vnodes in each hash-distributed fragment.
+    pub fn set_parallelism(&mut self, parallelism: u32) {
+        self.parallelism = parallelism;
+    }
+
     /// Update state of all actors
     pub fn update_actors_state(&mut self, state: ActorState) {
         for actor_status in self.actor_status.values_mut() {
@@ -274,6 +299,70 @@ impl TableFragments {
         map
     }
 
+    /// Returns the parallel units that the actors are scheduled to, one for each actor.
+    pub fn actor_parallel_units(&self) -> impl Iterator<Item = &ParallelUnit> + '_ {
+        self.actor_status
+            .values()
+            .map(|actor_status| actor_status.get_parallel_unit().unwrap())
+    }
+
+    /// Moves the actors on the parallel units in `migration` to the parallel units they map to,
+    /// and updates the vnode mappings of the fragments accordingly. Returns whether any actor is
+    /// moved.
+    pub fn migrate_actors(&mut self, migration: &HashMap<ParallelUnitId, ParallelUnit>) -> bool {
+        let mut migrated = false;
+        for actor_status in self.actor_status.values_mut() {
+            let parallel_unit_id = actor_status.get_parallel_unit().unwrap().id;
+            if let Some(parallel_unit) = migration.get(&parallel_unit_id) {
+                actor_status.parallel_unit = Some(parallel_unit.clone());
+                migrated = true;
+            }
+        }
+        if !migrated {
+            return false;
+        }
+
+        for fragment in self.fragments.values_mut() {
+            let mapping = fragment.vnode_mapping.as_mut().unwrap();
+            let parallel_unit_ids = decompress_data(&mapping.original_indices, &mapping.data)
+                .into_iter()
+                .map(|id| {
+                    migration
+                        .get(&id)
+                        .map_or(id, |parallel_unit| parallel_unit.id)
+                })
+                .collect_vec();
+            let (original_indices, data) = compress_data(&parallel_unit_ids);
+            mapping.original_indices = original_indices;
+            mapping.data = data;
+        }
+        true
+    }
+
+    /// Returns the hash-distributed fragments with several actors owning vnodes on one parallel
+    /// unit, which happens when the actors are migrated to fewer parallel units.
+    pub fn fragments_sharing_parallel_units(&self) -> Vec<FragmentId> {
+        self.fragments
+            .values()
+            .filter(|fragment| fragment.distribution_type() == FragmentDistributionType::Hash)
+            .filter(|fragment| {
+                let actor_parallel_units = fragment
+                    .actors
+                    .iter()
+                    .filter(|actor| actor.vnode_bitmap.iter().any(|byte| *byte != 0))
+                    .map(|actor| {
+                        self.actor_status[&actor.actor_id]
+                            .get_parallel_unit()
+                            .unwrap()
+                            .id
+                    })
+                    .collect_vec();
+                actor_parallel_units.iter().unique().count() < actor_parallel_units.len()
+            })
+            .map(|fragment| fragment.fragment_id)
+            .collect()
+    }
+
     /// Returns actor map: `actor_id` => `StreamActor`.
     pub fn actor_map(&self) -> HashMap<ActorId, StreamActor> {
         let mut actor_map = HashMap::default();
@@ -382,4 +471,483 @@ impl TableFragments {
     pub fn internal_table_ids(&self) -> Vec<u32> {
         self.internal_table_ids.clone()
     }
+
+    /// Returns the actors owning vnodes in each hash-distributed fragment when the parallelism is
+    /// `parallelism`, which are the first `parallelism` actors of the fragment by id. Paired
+    /// actors of fragments connected by no-shuffle exchanges are created in the same order, so
+    /// they are kept or left idle together.
+    pub fn actors_at_parallelism(
+        &self,
+        parallelism: u32,
+    ) -> Result<BTreeMap<FragmentId, Vec<ActorId>>> {
+        let mut actors = BTreeMap::new();
+        for fragment in self
+            .fragments
+            .values()
+            .filter(|fragment| fragment.distribution_type() == FragmentDistributionType::Hash)
+        {
+            let actor_ids = fragment
+                .actors
+                .iter()
+                .map(|actor| actor.actor_id)
+                .sorted()
+                .collect_vec();
+            if parallelism == 0 || parallelism as usize > actor_ids.len() {
+                return Err(RwError::from(InternalError(format!(
+                    "parallelism {} of table {} is not between 1 and its {} actors",
+                    parallelism,
+                    self.table_id,
+                    actor_ids.len()
+                ))));
+            }
+            actors.insert(
+                fragment.fragment_id,
+                actor_ids[..parallelism as usize].to_vec(),
+            );
+        }
+        Ok(actors)
+    }
+
+    /// Builds a vnode mapping that spreads the vnodes of a fragment evenly over `actor_ids`, while
+    /// keeping as many vnodes as possible on the actors currently owning them. The keys of the
+    /// vnodes kept are still dispatched to the same actors, so that their caches stay warm.
+    pub fn sticky_vnode_mapping(
+        &self,
+        fragment_id: FragmentId,
+        actor_ids: &[ActorId],
+    ) -> Result<Vec<ActorId>> {
+        let fragment = self.fragments.get(&fragment_id).ok_or_else(|| {
+            RwError::from(InternalError(format!(
+                "fragment not exist: id={}",
+                fragment_id
+            )))
+        })?;
+        if actor_ids.is_empty() {
+            return Err(RwError::from(InternalError(format!(
+                "no actors to assign the vnodes of fragment {} to",
+                fragment_id
+            ))));
+        }
+        if let Some(actor_id) = actor_ids
+            .iter()
+            .find(|actor_id| !fragment.actors.iter().any(|a| a.actor_id == **actor_id))
+        {
+            return Err(RwError::from(InternalError(format!(
+                "actor {} is not in fragment {}",
+                actor_id, fragment_id
+            ))));
+        }
+
+        let mut current_owners: Vec<Option<ActorId>> = vec![None; VIRTUAL_NODE_COUNT];
+        for actor in &fragment.actors {
+            for (vnode, owner) in current_owners.iter_mut().enumerate() {
+                if actor
+                    .vnode_bitmap
+                    .get(vnode >> 3)
+                    .map_or(false, |byte| byte & (1 << (vnode & 0b111)) != 0)
+                {
+                    *owner = Some(actor.actor_id);
+                }
+            }
+        }
+
+        // The remainder goes to the actors owning the most vnodes, which have the least to move.
+        let owned_count = |actor_id: &ActorId| {
+            current_owners
+                .iter()
+                .filter(|owner| **owner == Some(*actor_id))
+                .count()
+        };
+        let mut quotas: HashMap<ActorId, usize> = actor_ids
+            .iter()
+            .map(|actor_id| (*actor_id, VIRTUAL_NODE_COUNT / actor_ids.len()))
+            .collect();
+        for actor_id in actor_ids
+            .iter()
+            .sorted_by_key(|actor_id| std::cmp::Reverse(owned_count(actor_id)))
+            .take(VIRTUAL_NODE_COUNT % actor_ids.len())
+        {
+            *quotas.get_mut(actor_id).unwrap() += 1;
+        }
+
+        let mut vnode_mapping = vec![None; VIRTUAL_NODE_COUNT];
+        for (vnode, owner) in current_owners.iter().enumerate() {
+            if let Some(quota) = owner.and_then(|owner| quotas.get_mut(&owner)) && *quota > 0 {
+                *quota -= 1;
+                vnode_mapping[vnode] = *owner;
+            }
+        }
+        let mut free_actors = actor_ids
+            .iter()
+            .flat_map(|actor_id| std::iter::repeat(*actor_id).take(quotas[actor_id]));
+        Ok(vnode_mapping
+            .into_iter()
+            .map(|owner| owner.unwrap_or_else(|| free_actors.next().unwrap()))
+            .collect())
+    }
+
+    /// Reassigns the vnodes of a fragment to its actors by `vnode_mapping`, which maps each vnode
+    /// to the actor owning it. The vnode bitmaps of the actors, the vnode mapping of the fragment
+    /// and the hash mappings of the upstream dispatchers are updated accordingly. Returns the
+    /// mutation that makes the actors and dispatchers switch to the new vnodes.
+    pub fn reschedule_vnodes(
+        &mut self,
+        fragment_id: FragmentId,
+        vnode_mapping: &[ActorId],
+    ) -> Result<RescheduleMutation> {
+        if vnode_mapping.len() != VIRTUAL_NODE_COUNT {
+            return Err(RwError::from(InternalError(format!(
+                "vnode mapping of length {} is expected to be {}",
+                vnode_mapping.len(),
+                VIRTUAL_NODE_COUNT
+            ))));
+        }
+        let fragment = self.fragments.get_mut(&fragment_id).ok_or_else(|| {
+            RwError::from(InternalError(format!(
+                "fragment not exist: id={}",
+                fragment_id
+            )))
+        })?;
+        let fragment_actor_ids: HashSet<ActorId> =
+            fragment.actors.iter().map(|actor| actor.actor_id).collect();
+        if let Some(actor_id) = vnode_mapping
+            .iter()
+            .find(|actor_id| !fragment_actor_ids.contains(actor_id))
+        {
+            return Err(RwError::from(InternalError(format!(
+                "actor {} is not in fragment {}",
+                actor_id, fragment_id
+            ))));
+        }
+
+        // Actors with no vnodes left still get an empty bitmap, so that they drop their states.
+        let mut vnode_bitmaps: HashMap<ActorId, Vec<u8>> = fragment_actor_ids
+            .iter()
+            .map(|actor_id| (*actor_id, vec![0; VNODE_BITMAP_LEN]))
+            .collect();
+        for (vnode, actor_id) in vnode_mapping.iter().enumerate() {
+            vnode_bitmaps.get_mut(actor_id).unwrap()[vnode >> 3] |= 1 << (vnode & 0b111);
+        }
+        for actor in &mut fragment.actors {
+            actor.vnode_bitmap = vnode_bitmaps[&actor.actor_id].clone();
+        }
+        let parallel_unit_mapping = vnode_mapping
+            .iter()
+            .map(|actor_id| self.actor_status[actor_id].get_parallel_unit().unwrap().id)
+            .collect_vec();
+        let (original_indices, data) = compress_data(&parallel_unit_mapping);
+        fragment.vnode_mapping = Some(ParallelUnitMapping {
+            original_indices,
+            data,
+            ..Default::default()
+        });
+
+        let (original_indices, data) = compress_data(vnode_mapping);
+        let mut hash_mappings = vec![];
+        for actor in self
+            .fragments
+            .values_mut()
+            .flat_map(|fragment| fragment.actors.iter_mut())
+        {
+            for dispatcher in &mut actor.dispatcher {
+                if dispatcher.get_type().unwrap() == DispatcherType::Hash
+                    && dispatcher
+                        .downstream_actor_id
+                        .iter()
+                        .any(|actor_id| fragment_actor_ids.contains(actor_id))
+                {
+                    dispatcher.hash_mapping = Some(ActorMapping {
+                        original_indices: original_indices.clone(),
+                        data: data.clone(),
+                    });
+                    hash_mappings.push(DispatcherHashMapping {
+                        actor_id: actor.actor_id,
+                        dispatcher_id: dispatcher.dispatcher_id,
+                        original_indices: original_indices.clone(),
+                        data: data.clone(),
+                    });
+                }
+            }
+        }
+
+        Ok(RescheduleMutation {
+            hash_mappings,
+            vnode_bitmaps,
+        })
+    }
+
+    /// Appends `columns` added to source `source_id` to the outputs of the nodes, from the source
+    /// to the materialize node. The fragments must be those of the materialized view of the
+    /// source, whose nodes output all the columns of the source.
+    pub fn add_columns(&mut self, source_id: SourceId, columns: &[AddedColumn]) {
+        fn add_columns_to_node(
+            node: &mut StreamNode,
+            source_id: SourceId,
+            columns: &[AddedColumn],
+        ) {
+            node.fields.extend(columns.iter().map(|column| Field {
+                data_type: column.column_type.clone(),
+                name: column.name.clone(),
+            }));
+            let column_ids = match node.node_body.as_mut() {
+                Some(NodeBody::Source(source))
+                    if source.table_ref_id.as_ref().unwrap().table_id as SourceId == source_id =>
+                {
+                    Some(&mut source.column_ids)
+                }
+                Some(NodeBody::Materialize(materialize)) => Some(&mut materialize.column_ids),
+                _ => None,
+            };
+            if let Some(column_ids) = column_ids {
+                column_ids.extend(columns.iter().map(|column| column.column_id));
+            }
+            for input in &mut node.input {
+                add_columns_to_node(input, source_id, columns);
+            }
+        }
+
+        for actor in self
+            .fragments
+            .values_mut()
+            .flat_map(|fragment| fragment.actors.iter_mut())
+        {
+            add_columns_to_node(actor.nodes.as_mut().unwrap(), source_id, columns);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sticky_vnode_mapping() {
+        // Actor 1 owns the first half of the vnodes, and actor 2 the second half.
+        let actors = [1, 2]
+            .into_iter()
+            .map(|actor_id| {
+                let mut vnode_bitmap = vec![0; VNODE_BITMAP_LEN];
+                let half = VNODE_BITMAP_LEN / 2;
+                vnode_bitmap[(actor_id as usize - 1) * half..actor_id as usize * half].fill(0xff);
+                StreamActor {
+                    actor_id,
+                    vnode_bitmap,
+                    ..Default::default()
+                }
+            })
+            .chain([StreamActor {
+                actor_id: 3,
+                ..Default::default()
+            }])
+            .collect_vec();
+        let table_fragments = TableFragments::new(
+            TableId::new(1),
+            BTreeMap::from([(
+                1,
+                Fragment {
+                    fragment_id: 1,
+                    actors,
+                    ..Default::default()
+                },
+            )]),
+            HashSet::default(),
+        );
+
+        // Scaling out to actor 3 only moves the vnodes it takes over.
+        let mapping = table_fragments
+            .sticky_vnode_mapping(1, &[1, 2, 3])
+            .unwrap();
+        let counts = mapping.iter().counts();
+        assert_eq!(counts[&1], 86);
+        assert_eq!(counts[&2], 85);
+        assert_eq!(counts[&3], 85);
+        assert!(mapping[..VIRTUAL_NODE_COUNT / 2]
+            .iter()
+            .all(|actor_id| *actor_id != 2));
+        assert!(mapping[VIRTUAL_NODE_COUNT / 2..]
+            .iter()
+            .all(|actor_id| *actor_id != 1));
+
+        // Scaling in to actor 1 keeps all of its vnodes.
+        let mapping = table_fragments.sticky_vnode_mapping(1, &[1]).unwrap();
+        assert!(mapping.iter().all(|actor_id| *actor_id == 1));
+
+        assert!(table_fragments.sticky_vnode_mapping(1, &[4]).is_err());
+    }
+
+    #[test]
+    fn test_actors_at_parallelism() {
+        let fragment =
+            |fragment_id, distribution_type: FragmentDistributionType, actor_ids: &[u32]| {
+                Fragment {
+                    fragment_id,
+                    distribution_type: distribution_type as i32,
+                    actors: actor_ids
+                        .iter()
+                        .map(|actor_id| StreamActor {
+                            actor_id: *actor_id,
+                            ..Default::default()
+                        })
+                        .collect(),
+                    ..Default::default()
+                }
+            };
+        let table_fragments = TableFragments::new(
+            TableId::new(1),
+            BTreeMap::from([
+                (1, fragment(1, FragmentDistributionType::Single, &[1])),
+                (2, fragment(2, FragmentDistributionType::Hash, &[4, 2, 3])),
+                (3, fragment(3, FragmentDistributionType::Hash, &[5, 6, 7])),
+            ]),
+            HashSet::default(),
+        );
+
+        // Singleton fragments are left alone, and the actors with the smallest ids are kept.
+        let actors = table_fragments.actors_at_parallelism(2).unwrap();
+        assert_eq!(actors, BTreeMap::from([(2, vec![2, 3]), (3, vec![5, 6])]));
+
+        assert!(table_fragments.actors_at_parallelism(0).is_err());
+        assert!(table_fragments.actors_at_parallelism(4).is_err());
+    }
+    #[test]
+    fn test_migrate_actors() {
+        let parallel_unit = |id| ParallelUnit {
+            id,
+            worker_node_id: id / 2,
+            ..Default::default()
+        };
+        // Both fragments have actors on parallel units 2 and 3, which are migrated to 4.
+        let fragment = |fragment_id, actor_ids: [ActorId; 2]| {
+            let (original_indices, data) = compress_data(
+                &(0..VIRTUAL_NODE_COUNT)
+                    .map(|vnode| (vnode % 2) as ParallelUnitId + 2)
+                    .collect_vec(),
+            );
+            Fragment {
+                fragment_id,
+                distribution_type: FragmentDistributionType::Hash as i32,
+                actors: actor_ids
+                    .iter()
+                    .map(|actor_id| StreamActor {
+                        actor_id: *actor_id,
+                        vnode_bitmap: vec![0b1010_1010; VNODE_BITMAP_LEN],
+                        ..Default::default()
+                    })
+                    .collect(),
+                vnode_mapping: Some(ParallelUnitMapping {
+                    original_indices,
+                    data,
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }
+        };
+        let mut table_fragments = TableFragments::new(
+            TableId::new(1),
+            BTreeMap::from([(1, fragment(1, [1, 2])), (2, fragment(2, [3, 4]))]),
+            HashSet::default(),
+        );
+        table_fragments.set_actor_status(
+            [(1, 2), (2, 3), (3, 2), (4, 3)]
+                .into_iter()
+                .map(|(actor_id, parallel_unit_id)| {
+                    (
+                        actor_id,
+                        ActorStatus {
+                            parallel_unit: Some(parallel_unit(parallel_unit_id)),
+                            ..Default::default()
+                        },
+                    )
+                })
+                .collect(),
+        );
+        assert!(table_fragments
+            .fragments_sharing_parallel_units()
+            .is_empty());
+
+        let migration = HashMap::from([(2, parallel_unit(4)), (3, parallel_unit(4))]);
+        assert!(table_fragments.migrate_actors(&migration));
+        assert!(table_fragments
+            .actor_parallel_units()
+            .all(|parallel_unit| parallel_unit.id == 4 && parallel_unit.worker_node_id == 2));
+        for fragment in table_fragments.fragments.values() {
+            let mapping = fragment.vnode_mapping.as_ref().unwrap();
+            assert!(decompress_data(&mapping.original_indices, &mapping.data)
+                .into_iter()
+                .all(|parallel_unit_id| parallel_unit_id == 4));
+        }
+        assert_eq!(
+            table_fragments.fragments_sharing_parallel_units(),
+            vec![1, 2]
+        );
+
+        // Nothing is left on the migrated parallel units.
+        assert!(!table_fragments.migrate_actors(&migration));
+    }
+
+    #[test]
+    fn test_add_columns() {
+        use assert_matches::assert_matches;
+        use risingwave_pb::data::DataType as ProstDataType;
+        use risingwave_pb::plan_common::TableRefId;
+        use risingwave_pb::stream_plan::{MaterializeNode, SourceNode};
+
+        let source = StreamNode {
+            node_body: Some(NodeBody::Source(SourceNode {
+                table_ref_id: Some(TableRefId {
+                    table_id: 2,
+                    ..Default::default()
+                }),
+                column_ids: vec![0, 1],
+                ..Default::default()
+            })),
+            ..Default::default()
+        };
+        let materialize = StreamNode {
+            node_body: Some(NodeBody::Materialize(MaterializeNode {
+                column_ids: vec![0, 1],
+                ..Default::default()
+            })),
+            input: vec![source],
+            ..Default::default()
+        };
+        let mut table_fragments = TableFragments::new(
+            TableId::new(1),
+            BTreeMap::from([(
+                1,
+                Fragment {
+                    fragment_id: 1,
+                    actors: vec![StreamActor {
+                        actor_id: 1,
+                        nodes: Some(materialize),
+                        ..Default::default()
+                    }],
+                    ..Default::default()
+                },
+            )]),
+            HashSet::default(),
+        );
+
+        let column = AddedColumn {
+            column_id: 2,
+            column_type: Some(ProstDataType::default()),
+            name: "v".to_string(),
+        };
+        table_fragments.add_columns(2, &[column]);
+
+        let materialize = table_fragments.fragments[&1].actors[0]
+            .nodes
+            .as_ref()
+            .unwrap();
+        assert_eq!(materialize.fields.len(), 1);
+        assert_eq!(materialize.fields[0].name, "v");
+        assert_matches!(
+            materialize.node_body.as_ref(),
+            Some(NodeBody::Materialize(node)) if node.column_ids == vec![0, 1, 2]
+        );
+        assert_matches!(
+            materialize.input[0].node_body.as_ref(),
+            Some(NodeBody::Source(node)) if node.column_ids == vec![0, 1, 2]
+        );
+    }
 }
@@ -19,8 +19,9 @@ use hyper::{Body, Request, Response};
 use prometheus::{
     exponential_buckets, histogram_opts, register_histogram_vec_with_registry,
     register_histogram_with_registry, register_int_counter_vec_with_registry,
-    register_int_gauge_vec_with_registry, register_int_gauge_with_registry, Encoder, Histogram,
-    HistogramVec, IntCounterVec, IntGauge, IntGaugeVec, Registry, TextEncoder,
+    register_int_counter_with_registry, register_int_gauge_vec_with_registry,
+    register_int_gauge_with_registry, Encoder, Histogram, HistogramVec, IntCounter, IntCounterVec,
+    IntGauge, IntGaugeVec, Registry, TextEncoder,
 };
 use tower::make::Shared;
 use tower::ServiceBuilder;
@@ -33,9 +34,25 @@ pub struct MetaMetrics {
     pub grpc_latency: HistogramVec,
     /// latency of each barrier
     pub barrier_latency: Histogram,
+    /// num of barriers scheduled but not sent yet
+    pub scheduled_barrier_num: IntGauge,
+    /// epoch of the latest collected barrier
+    pub barrier_epoch: IntGauge,
+
+    /// num of recoveries of the streaming graph
+    pub recovery_count: IntCounter,
+    /// latency of each recovery
+    pub recovery_latency: Histogram,
+
+    /// num of workers of each type and state
+    pub worker_num: IntGaugeVec,
 
     /// max committed epoch
     pub max_committed_epoch: IntGauge,
+    /// id of the current hummock version
+    pub version_id: IntGauge,
+    /// safe epoch of the current hummock version
+    pub safe_epoch: IntGauge,
     /// num of uncommitted SSTs,
     pub uncommitted_sst_num: IntGauge,
     /// num of SSTs in each level
@@ -79,6 +96,42 @@ impl MetaMetrics {
         );
         let barrier_latency = register_histogram_with_registry!(opts, registry).unwrap();
 
+        let scheduled_barrier_num = register_int_gauge_with_registry!(
+            "meta_scheduled_barrier_num",
+            "num of barriers scheduled but not sent yet",
+            registry
+        )
+        .unwrap();
+
+        let barrier_epoch = register_int_gauge_with_registry!(
+            "meta_barrier_epoch",
+            "epoch of the latest collected barrier",
+            registry
+        )
+        .unwrap();
+
+        let recovery_count = register_int_counter_with_registry!(
+            "meta_recovery_count",
+            "num of recoveries of the streaming graph",
+            registry
+        )
+        .unwrap();
+
+        let opts = histogram_opts!(
+            "meta_recovery_duration_seconds",
+            "recovery latency",
+            exponential_buckets(0.1, 1.5, 20).unwrap() // max 221s
+        );
+        let recovery_latency = register_histogram_with_registry!(opts, registry).unwrap();
+
+        let worker_num = register_int_gauge_vec_with_registry!(
+            "meta_worker_num",
+            "num of workers of each type and state",
+            &["worker_type", "state"],
+            registry
+        )
+        .unwrap();
+
         let max_committed_epoch = register_int_gauge_with_registry!(
             "storage_max_committed_epoch",
             "max committed epoch",
@@ -86,6 +139,20 @@ impl MetaMetrics {
         )
         .unwrap();
 
+        let version_id = register_int_gauge_with_registry!(
+            "storage_version_id",
+            "id of the current hummock version",
+            registry
+        )
+        .unwrap();
+
+        let safe_epoch = register_int_gauge_with_registry!(
+            "storage_safe_epoch",
+            "safe epoch of the current hummock version",
+            registry
+        )
+        .unwrap();
+
         let uncommitted_sst_num = register_int_gauge_with_registry!(
             "storage_uncommitted_sst_num",
             "num of uncommitted SSTs",
@@ -184,8 +251,17 @@ impl MetaMetrics {
 
             grpc_latency,
             barrier_latency,
+            scheduled_barrier_num,
+            barrier_epoch,
+
+            recovery_count,
+            recovery_latency,
+
+            worker_num,
 
             max_committed_epoch,
+            version_id,
+            safe_epoch,
             uncommitted_sst_num,
             level_sst_num,
             level_compact_cnt,
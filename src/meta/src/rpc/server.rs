@@ -21,6 +21,7 @@ use risingwave_common::error::ErrorCode::InternalError;
 use risingwave_common::error::{Result, RwError};
 use risingwave_pb::ddl_service::ddl_service_server::DdlServiceServer;
 use risingwave_pb::hummock::hummock_manager_service_server::HummockManagerServiceServer;
+use risingwave_pb::meta::backup_service_server::BackupServiceServer;
 use risingwave_pb::meta::cluster_service_server::ClusterServiceServer;
 use risingwave_pb::meta::heartbeat_service_server::HeartbeatServiceServer;
 use risingwave_pb::meta::notification_service_server::NotificationServiceServer;
@@ -32,13 +33,16 @@ use tokio::task::JoinHandle;
 use super::intercept::MetricsMiddlewareLayer;
 use super::service::notification_service::NotificationServiceImpl;
 use super::DdlServiceImpl;
+use crate::backup::{BackupManager, BackupStorage};
 use crate::barrier::GlobalBarrierManager;
 use crate::cluster::ClusterManager;
 use crate::dashboard::DashboardService;
 use crate::hummock;
+use crate::hummock::compaction_group::manager::CompactionGroupManager;
 use crate::hummock::CompactionScheduler;
 use crate::manager::{CatalogManager, MetaOpts, MetaSrvEnv, UserManager};
 use crate::rpc::metrics::MetaMetrics;
+use crate::rpc::service::backup_service::BackupServiceImpl;
 use crate::rpc::service::cluster_service::ClusterServiceImpl;
 use crate::rpc::service::heartbeat_service::HeartbeatServiceImpl;
 use crate::rpc::service::hummock_service::HummockServiceImpl;
@@ -110,7 +114,12 @@ pub async fn rpc_serve_with_store<S: MetaStore>(
     ui_path: Option<String>,
     opts: MetaOpts,
 ) -> (JoinHandle<()>, Sender<()>) {
+    let config = format!(
+        "enable_recovery: {}, checkpoint_interval: {:?}, max_heartbeat_interval: {:?}",
+        opts.enable_recovery, opts.checkpoint_interval, max_heartbeat_interval
+    );
     let env = MetaSrvEnv::<S>::new(opts, meta_store.clone()).await;
+    env.event_log_manager().log_config(config).await;
 
     let fragment_manager = Arc::new(FragmentManager::new(env.clone()).await.unwrap());
     let meta_metrics = Arc::new(MetaMetrics::new());
@@ -121,23 +130,19 @@ pub async fn rpc_serve_with_store<S: MetaStore>(
             .await
             .unwrap(),
     );
+    let compaction_group_manager =
+        Arc::new(CompactionGroupManager::new(env.clone()).await.unwrap());
     let hummock_manager = Arc::new(
-        hummock::HummockManager::new(env.clone(), cluster_manager.clone(), meta_metrics.clone())
-            .await
-            .unwrap(),
+        hummock::HummockManager::new(
+            env.clone(),
+            cluster_manager.clone(),
+            meta_metrics.clone(),
+            compaction_group_manager.clone(),
+        )
+        .await
+        .unwrap(),
     );
 
-    if let Some(dashboard_addr) = dashboard_addr {
-        let dashboard_service = DashboardService {
-            dashboard_addr,
-            cluster_manager: cluster_manager.clone(),
-            fragment_manager: fragment_manager.clone(),
-            meta_store: env.meta_store_ref(),
-        };
-        // TODO: join dashboard service back to local thread.
-        tokio::spawn(dashboard_service.serve(ui_path));
-    }
-
     let catalog_manager = Arc::new(CatalogManager::new(env.clone()).await.unwrap());
     let user_manager = Arc::new(UserManager::new(env.clone()).await.unwrap());
 
@@ -150,6 +155,19 @@ pub async fn rpc_serve_with_store<S: MetaStore>(
         meta_metrics.clone(),
     ));
 
+    if let Some(dashboard_addr) = dashboard_addr {
+        let dashboard_service = DashboardService {
+            dashboard_addr,
+            cluster_manager: cluster_manager.clone(),
+            fragment_manager: fragment_manager.clone(),
+            barrier_manager: barrier_manager.clone(),
+            hummock_manager: hummock_manager.clone(),
+            meta_store: env.meta_store_ref(),
+        };
+        // TODO: join dashboard service back to local thread.
+        tokio::spawn(dashboard_service.serve(ui_path));
+    }
+
     let source_manager = Arc::new(
         SourceManager::new(
             env.clone(),
@@ -176,6 +194,7 @@ pub async fn rpc_serve_with_store<S: MetaStore>(
             barrier_manager.clone(),
             cluster_manager.clone(),
             source_manager.clone(),
+            compaction_group_manager,
         )
         .await
         .unwrap(),
@@ -190,6 +209,12 @@ pub async fn rpc_serve_with_store<S: MetaStore>(
         compactor_manager.clone(),
     ));
 
+    let backup_storage = match &env.opts.backup_storage_url {
+        Some(url) => Some(BackupStorage::new(url, env.opts.backup_storage_directory.clone()).await),
+        None => None,
+    };
+    let backup_manager = Arc::new(BackupManager::new(env.meta_store_ref(), backup_storage));
+
     let heartbeat_srv = HeartbeatServiceImpl::new(cluster_manager.clone());
     let ddl_srv = DdlServiceImpl::<S>::new(
         env.clone(),
@@ -200,16 +225,26 @@ pub async fn rpc_serve_with_store<S: MetaStore>(
         fragment_manager.clone(),
     );
     let user_srv = UserServiceImpl::<S>::new(catalog_manager.clone(), user_manager.clone());
-    let cluster_srv = ClusterServiceImpl::<S>::new(cluster_manager.clone());
-    let stream_srv = StreamServiceImpl::<S>::new(stream_manager);
+    let cluster_srv = ClusterServiceImpl::<S>::new(
+        cluster_manager.clone(),
+        env.event_log_manager_ref(),
+        env.notification_manager_ref(),
+    );
+    let stream_srv = StreamServiceImpl::<S>::new(stream_manager, fragment_manager);
     let hummock_srv = HummockServiceImpl::new(
         hummock_manager.clone(),
         compactor_manager.clone(),
         vacuum_trigger.clone(),
     );
+    let backup_srv = BackupServiceImpl::new(backup_manager);
     let notification_manager = env.notification_manager_ref();
-    let notification_srv =
-        NotificationServiceImpl::new(env, catalog_manager, cluster_manager.clone(), user_manager);
+    let notification_srv = NotificationServiceImpl::new(
+        env,
+        catalog_manager,
+        cluster_manager.clone(),
+        user_manager,
+        hummock_manager.clone(),
+    );
 
     if let Some(prometheus_addr) = prometheus_addr {
         meta_metrics.boot_metrics_service(prometheus_addr);
@@ -228,6 +263,14 @@ pub async fn rpc_serve_with_store<S: MetaStore>(
     );
     #[cfg(not(test))]
     {
+        sub_tasks.push(
+            ClusterManager::start_worker_num_monitor(
+                cluster_manager.clone(),
+                Duration::from_secs(10),
+                meta_metrics.clone(),
+            )
+            .await,
+        );
         sub_tasks.push(
             ClusterManager::start_heartbeat_checker(cluster_manager, Duration::from_secs(1)).await,
         );
@@ -245,6 +288,7 @@ pub async fn rpc_serve_with_store<S: MetaStore>(
             .add_service(NotificationServiceServer::new(notification_srv))
             .add_service(DdlServiceServer::new(ddl_srv))
             .add_service(UserServiceServer::new(user_srv))
+            .add_service(BackupServiceServer::new(backup_srv))
             .serve_with_shutdown(addr, async move {
                 tokio::select! {
                     _ = tokio::signal::ctrl_c() => {},
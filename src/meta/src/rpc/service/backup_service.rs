@@ -0,0 +1,57 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use risingwave_pb::meta::backup_service_server::BackupService;
+use risingwave_pb::meta::{BackupMetaRequest, BackupMetaResponse};
+use tonic::{Request, Response, Status};
+
+use crate::backup::BackupManagerRef;
+use crate::storage::MetaStore;
+
+#[derive(Clone)]
+pub struct BackupServiceImpl<S>
+where
+    S: MetaStore,
+{
+    backup_manager: BackupManagerRef<S>,
+}
+
+impl<S> BackupServiceImpl<S>
+where
+    S: MetaStore,
+{
+    pub fn new(backup_manager: BackupManagerRef<S>) -> Self {
+        BackupServiceImpl { backup_manager }
+    }
+}
+
+#[async_trait::async_trait]
+impl<S> BackupService for BackupServiceImpl<S>
+where
+    S: MetaStore,
+{
+    #[cfg_attr(coverage, no_coverage)]
+    async fn backup_meta(
+        &self,
+        _request: Request<BackupMetaRequest>,
+    ) -> Result<Response<BackupMetaResponse>, Status> {
+        match self.backup_manager.backup().await {
+            Ok(backup) => Ok(Response::new(BackupMetaResponse {
+                status: None,
+                backup: Some(backup),
+            })),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
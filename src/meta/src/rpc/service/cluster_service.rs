@@ -15,27 +15,40 @@
 use risingwave_common::error::tonic_err;
 use risingwave_common::try_match_expand;
 use risingwave_pb::meta::cluster_service_server::ClusterService;
+use risingwave_pb::meta::subscribe_response::{Info, Operation};
 use risingwave_pb::meta::{
     ActivateWorkerNodeRequest, ActivateWorkerNodeResponse, AddWorkerNodeRequest,
     AddWorkerNodeResponse, DeleteWorkerNodeRequest, DeleteWorkerNodeResponse, ListAllNodesRequest,
-    ListAllNodesResponse,
+    ListAllNodesResponse, ListEventLogRequest, ListEventLogResponse, SetLogFilterRequest,
+    SetLogFilterResponse,
 };
 use tonic::{Request, Response, Status};
 
 use crate::cluster::ClusterManagerRef;
+use crate::manager::{EventLogManagerRef, NotificationManagerRef};
 use crate::storage::MetaStore;
 
 #[derive(Clone)]
 pub struct ClusterServiceImpl<S: MetaStore> {
     cluster_manager: ClusterManagerRef<S>,
+    event_log_manager: EventLogManagerRef<S>,
+    notification_manager: NotificationManagerRef,
 }
 
 impl<S> ClusterServiceImpl<S>
 where
     S: MetaStore,
 {
-    pub fn new(cluster_manager: ClusterManagerRef<S>) -> Self {
-        ClusterServiceImpl { cluster_manager }
+    pub fn new(
+        cluster_manager: ClusterManagerRef<S>,
+        event_log_manager: EventLogManagerRef<S>,
+        notification_manager: NotificationManagerRef,
+    ) -> Self {
+        ClusterServiceImpl {
+            cluster_manager,
+            event_log_manager,
+            notification_manager,
+        }
     }
 }
 
@@ -101,4 +114,35 @@ where
             nodes: node_list,
         }))
     }
+
+    async fn list_event_log(
+        &self,
+        request: Request<ListEventLogRequest>,
+    ) -> Result<Response<ListEventLogResponse>, Status> {
+        let req = request.into_inner();
+        let events = self
+            .event_log_manager
+            .list_events(req.limit as usize)
+            .await?;
+        Ok(Response::new(ListEventLogResponse {
+            status: None,
+            events,
+        }))
+    }
+
+    async fn set_log_filter(
+        &self,
+        request: Request<SetLogFilterRequest>,
+    ) -> Result<Response<SetLogFilterResponse>, Status> {
+        let req = request.into_inner();
+        let filter = try_match_expand!(req.filter, Some, "SetLogFilterRequest::filter is empty")?;
+        // The directives are validated by the meta node before being sent to the other nodes.
+        risingwave_logging::set_log_filter(&filter.directives).map_err(Status::invalid_argument)?;
+        tracing::info!(directives = %filter.directives, "log filter changed");
+        self.notification_manager
+            .notify_frontend_asynchronously(Operation::Update, Info::LogFilter(filter.clone()));
+        self.notification_manager
+            .notify_compute_asynchronously(Operation::Update, Info::LogFilter(filter));
+        Ok(Response::new(SetLogFilterResponse { status: None }))
+    }
 }
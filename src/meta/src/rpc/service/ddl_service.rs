@@ -19,15 +19,22 @@ use risingwave_common::error::{tonic_err, Result as RwResult};
 use risingwave_pb::catalog::table::OptionalAssociatedSourceId;
 use risingwave_pb::catalog::*;
 use risingwave_pb::common::ParallelUnitType;
+use risingwave_pb::data::AddedColumn;
 use risingwave_pb::ddl_service::ddl_service_server::DdlService;
 use risingwave_pb::ddl_service::*;
+use risingwave_pb::hummock::TableOption;
+use risingwave_pb::meta::event_log::EventType;
+use risingwave_pb::meta::IdempotencyRecord;
 use risingwave_pb::plan_common::TableRefId;
 use risingwave_pb::stream_plan::stream_node::NodeBody;
 use risingwave_pb::stream_plan::{StreamFragmentGraph, StreamNode};
 use tonic::{Request, Response, Status};
 
 use crate::cluster::ClusterManagerRef;
-use crate::manager::{CatalogManagerRef, IdCategory, MetaSrvEnv, SourceId, TableId};
+use crate::manager::{
+    CatalogManagerRef, Dedup, IdCategory, IdempotencyGuard, MetaSrvEnv, RelationIdEnum, SourceId,
+    TableId,
+};
 use crate::model::TableFragments;
 use crate::storage::MetaStore;
 use crate::stream::{
@@ -78,6 +85,19 @@ where
         request: Request<CreateDatabaseRequest>,
     ) -> Result<Response<CreateDatabaseResponse>, Status> {
         let req = request.into_inner();
+        let guard = match self
+            .env
+            .idempotency_manager()
+            .begin(&req.idempotency_key)
+            .await
+            .map_err(tonic_err)?
+        {
+            Dedup::Replay(mut response) => {
+                response.version = self.env.notification_manager().current_version().await;
+                return Ok(Response::new(response));
+            }
+            Dedup::Apply(guard) => guard,
+        };
         let id = self
             .env
             .id_gen_manager()
@@ -86,17 +106,30 @@ where
             .map_err(tonic_err)? as u32;
         let mut database = req.get_db().map_err(tonic_err)?.clone();
         database.id = id;
+        let record = guard.record(&CreateDatabaseResponse {
+            database_id: id,
+            ..Default::default()
+        });
         let version = self
             .catalog_manager
-            .create_database(&database)
+            .create_database(&database, record)
             .await
             .map_err(tonic_err)?;
 
-        Ok(Response::new(CreateDatabaseResponse {
+        self.env
+            .event_log_manager()
+            .log(
+                EventType::Ddl,
+                format!("create database {} (id {})", database.name, id),
+            )
+            .await;
+        let response = CreateDatabaseResponse {
             status: None,
             database_id: id,
             version,
-        }))
+        };
+        guard.finish().await;
+        Ok(Response::new(response))
     }
 
     async fn drop_database(
@@ -104,16 +137,36 @@ where
         request: Request<DropDatabaseRequest>,
     ) -> Result<Response<DropDatabaseResponse>, Status> {
         let req = request.into_inner();
+        let guard = match self
+            .env
+            .idempotency_manager()
+            .begin(&req.idempotency_key)
+            .await
+            .map_err(tonic_err)?
+        {
+            Dedup::Replay(mut response) => {
+                response.version = self.env.notification_manager().current_version().await;
+                return Ok(Response::new(response));
+            }
+            Dedup::Apply(guard) => guard,
+        };
         let database_id = req.get_database_id();
+        let record = guard.record(&DropDatabaseResponse::default());
         let version = self
             .catalog_manager
-            .drop_database(database_id)
+            .drop_database(database_id, record)
             .await
             .map_err(tonic_err)?;
-        Ok(Response::new(DropDatabaseResponse {
+        self.env
+            .event_log_manager()
+            .log(EventType::Ddl, format!("drop database {}", database_id))
+            .await;
+        let response = DropDatabaseResponse {
             status: None,
             version,
-        }))
+        };
+        guard.finish().await;
+        Ok(Response::new(response))
     }
 
     async fn create_schema(
@@ -121,6 +174,19 @@ where
         request: Request<CreateSchemaRequest>,
     ) -> Result<Response<CreateSchemaResponse>, Status> {
         let req = request.into_inner();
+        let guard = match self
+            .env
+            .idempotency_manager()
+            .begin(&req.idempotency_key)
+            .await
+            .map_err(tonic_err)?
+        {
+            Dedup::Replay(mut response) => {
+                response.version = self.env.notification_manager().current_version().await;
+                return Ok(Response::new(response));
+            }
+            Dedup::Apply(guard) => guard,
+        };
         let id = self
             .env
             .id_gen_manager()
@@ -129,17 +195,30 @@ where
             .map_err(tonic_err)? as u32;
         let mut schema = req.get_schema().map_err(tonic_err)?.clone();
         schema.id = id;
+        let record = guard.record(&CreateSchemaResponse {
+            schema_id: id,
+            ..Default::default()
+        });
         let version = self
             .catalog_manager
-            .create_schema(&schema)
+            .create_schema(&schema, record)
             .await
             .map_err(tonic_err)?;
 
-        Ok(Response::new(CreateSchemaResponse {
+        self.env
+            .event_log_manager()
+            .log(
+                EventType::Ddl,
+                format!("create schema {} (id {})", schema.name, id),
+            )
+            .await;
+        let response = CreateSchemaResponse {
             status: None,
             schema_id: id,
             version,
-        }))
+        };
+        guard.finish().await;
+        Ok(Response::new(response))
     }
 
     async fn drop_schema(
@@ -147,23 +226,57 @@ where
         request: Request<DropSchemaRequest>,
     ) -> Result<Response<DropSchemaResponse>, Status> {
         let req = request.into_inner();
+        let guard = match self
+            .env
+            .idempotency_manager()
+            .begin(&req.idempotency_key)
+            .await
+            .map_err(tonic_err)?
+        {
+            Dedup::Replay(mut response) => {
+                response.version = self.env.notification_manager().current_version().await;
+                return Ok(Response::new(response));
+            }
+            Dedup::Apply(guard) => guard,
+        };
         let schema_id = req.get_schema_id();
+        let record = guard.record(&DropSchemaResponse::default());
         let version = self
             .catalog_manager
-            .drop_schema(schema_id)
+            .drop_schema(schema_id, record)
             .await
             .map_err(tonic_err)?;
-        Ok(Response::new(DropSchemaResponse {
+        self.env
+            .event_log_manager()
+            .log(EventType::Ddl, format!("drop schema {}", schema_id))
+            .await;
+        let response = DropSchemaResponse {
             status: None,
             version,
-        }))
+        };
+        guard.finish().await;
+        Ok(Response::new(response))
     }
 
     async fn create_source(
         &self,
         request: Request<CreateSourceRequest>,
     ) -> Result<Response<CreateSourceResponse>, Status> {
-        let mut source = request.into_inner().source.unwrap();
+        let req = request.into_inner();
+        let guard = match self
+            .env
+            .idempotency_manager()
+            .begin(&req.idempotency_key)
+            .await
+            .map_err(tonic_err)?
+        {
+            Dedup::Replay(mut response) => {
+                response.version = self.env.notification_manager().current_version().await;
+                return Ok(Response::new(response));
+            }
+            Dedup::Apply(guard) => guard,
+        };
+        let mut source = req.source.unwrap();
 
         let id = self
             .env
@@ -187,28 +300,56 @@ where
             return Err(e.into());
         }
 
+        let record = guard.record(&CreateSourceResponse {
+            source_id: id,
+            ..Default::default()
+        });
         let version = self
             .catalog_manager
-            .finish_create_source_procedure(&source)
+            .finish_create_source_procedure(&source, record)
             .await
             .map_err(tonic_err)?;
-        Ok(Response::new(CreateSourceResponse {
+        self.env
+            .event_log_manager()
+            .log(
+                EventType::Ddl,
+                format!("create source {} (id {})", source.name, id),
+            )
+            .await;
+        let response = CreateSourceResponse {
             status: None,
             source_id: id,
             version,
-        }))
+        };
+        guard.finish().await;
+        Ok(Response::new(response))
     }
 
     async fn drop_source(
         &self,
         request: Request<DropSourceRequest>,
     ) -> Result<Response<DropSourceResponse>, Status> {
-        let source_id = request.into_inner().source_id;
+        let req = request.into_inner();
+        let guard = match self
+            .env
+            .idempotency_manager()
+            .begin(&req.idempotency_key)
+            .await
+            .map_err(tonic_err)?
+        {
+            Dedup::Replay(mut response) => {
+                response.version = self.env.notification_manager().current_version().await;
+                return Ok(Response::new(response));
+            }
+            Dedup::Apply(guard) => guard,
+        };
+        let source_id = req.source_id;
 
         // 1. Drop source in catalog. Ref count will be checked.
+        let record = guard.record(&DropSourceResponse::default());
         let version = self
             .catalog_manager
-            .drop_source(source_id)
+            .drop_source(source_id, record)
             .await
             .map_err(tonic_err)?;
 
@@ -218,10 +359,87 @@ where
             .await
             .map_err(tonic_err)?;
 
-        Ok(Response::new(DropSourceResponse {
+        self.env
+            .event_log_manager()
+            .log(EventType::Ddl, format!("drop source {}", source_id))
+            .await;
+        let response = DropSourceResponse {
             status: None,
             version,
-        }))
+        };
+        guard.finish().await;
+        Ok(Response::new(response))
+    }
+
+    async fn alter_source_add_column(
+        &self,
+        request: Request<AlterSourceAddColumnRequest>,
+    ) -> Result<Response<AlterSourceAddColumnResponse>, Status> {
+        use risingwave_common::catalog::TableId;
+
+        let req = request.into_inner();
+        let guard = match self
+            .env
+            .idempotency_manager()
+            .begin(&req.idempotency_key)
+            .await
+            .map_err(tonic_err)?
+        {
+            Dedup::Replay(mut response) => {
+                response.version = self.env.notification_manager().current_version().await;
+                return Ok(Response::new(response));
+            }
+            Dedup::Apply(guard) => guard,
+        };
+        let source_id = req.source_id;
+        let column = req.get_column().map_err(tonic_err)?;
+        let column_desc = column.get_column_desc().map_err(tonic_err)?;
+
+        // 1. Add the column to the source on compute nodes.
+        self.source_manager
+            .add_source_columns(source_id, &[column_desc.clone()])
+            .await
+            .map_err(tonic_err)?;
+
+        // 2. Add the column to the source and materialize actors of the table, if any.
+        let table_id = self
+            .catalog_manager
+            .get_associated_table_id(source_id)
+            .await
+            .map_err(tonic_err)?;
+        if let Some(table_id) = table_id {
+            let added_column = AddedColumn {
+                column_id: column_desc.column_id,
+                column_type: column_desc.column_type.clone(),
+                name: column_desc.name.clone(),
+            };
+            self.stream_manager
+                .add_columns(source_id, &TableId::new(table_id), vec![added_column])
+                .await
+                .map_err(tonic_err)?;
+        }
+
+        // 3. Add the column to the source and the table in catalog.
+        let record = guard.record(&AlterSourceAddColumnResponse::default());
+        let version = self
+            .catalog_manager
+            .add_source_column(source_id, column, record)
+            .await
+            .map_err(tonic_err)?;
+
+        self.env
+            .event_log_manager()
+            .log(
+                EventType::Ddl,
+                format!("add column {} to source {}", column_desc.name, source_id),
+            )
+            .await;
+        let response = AlterSourceAddColumnResponse {
+            status: None,
+            version,
+        };
+        guard.finish().await;
+        Ok(Response::new(response))
     }
 
     async fn create_materialized_view(
@@ -229,6 +447,19 @@ where
         request: Request<CreateMaterializedViewRequest>,
     ) -> Result<Response<CreateMaterializedViewResponse>, Status> {
         let req = request.into_inner();
+        let guard = match self
+            .env
+            .idempotency_manager()
+            .begin(&req.idempotency_key)
+            .await
+            .map_err(tonic_err)?
+        {
+            Dedup::Replay(mut response) => {
+                response.version = self.env.notification_manager().current_version().await;
+                return Ok(Response::new(response));
+            }
+            Dedup::Apply(guard) => guard,
+        };
         let mut mview = req.get_materialized_view().map_err(tonic_err)?.clone();
         let fragment_graph = req.get_fragment_graph().map_err(tonic_err)?.clone();
 
@@ -285,29 +516,45 @@ where
             .map_err(tonic_err)?;
 
         // 3. Create mview in stream manager. The id in stream node will be filled.
-        if let Err(e) = self
-            .create_mview_on_compute_node(fragment_graph, id, None)
+        let internal_tables = match self
+            .create_mview_on_compute_node(fragment_graph, id, table_option_of(&mview), None)
             .await
         {
-            self.catalog_manager
-                .cancel_create_table_procedure(&mview)
-                .await
-                .map_err(tonic_err)?;
-            return Err(e.into());
-        }
+            Ok(internal_tables) => fill_internal_tables(&mview, internal_tables),
+            Err(e) => {
+                self.catalog_manager
+                    .cancel_create_table_procedure(&mview)
+                    .await
+                    .map_err(tonic_err)?;
+                return Err(e.into());
+            }
+        };
 
         // 4. Finally, update the catalog.
+        let record = guard.record(&CreateMaterializedViewResponse {
+            table_id: id,
+            ..Default::default()
+        });
         let version = self
             .catalog_manager
-            .finish_create_table_procedure(&mview)
+            .finish_create_table_procedure(internal_tables, &mview, record)
             .await
             .map_err(tonic_err)?;
 
-        Ok(Response::new(CreateMaterializedViewResponse {
+        self.env
+            .event_log_manager()
+            .log(
+                EventType::Ddl,
+                format!("create materialized view {} (id {})", mview.name, id),
+            )
+            .await;
+        let response = CreateMaterializedViewResponse {
             status: None,
             table_id: id,
             version,
-        }))
+        };
+        guard.finish().await;
+        Ok(Response::new(response))
     }
 
     async fn drop_materialized_view(
@@ -316,11 +563,32 @@ where
     ) -> Result<Response<DropMaterializedViewResponse>, Status> {
         use risingwave_common::catalog::TableId;
 
-        let table_id = request.into_inner().table_id;
+        let req = request.into_inner();
+        let guard = match self
+            .env
+            .idempotency_manager()
+            .begin(&req.idempotency_key)
+            .await
+            .map_err(tonic_err)?
+        {
+            Dedup::Replay(mut response) => {
+                response.version = self.env.notification_manager().current_version().await;
+                return Ok(Response::new(response));
+            }
+            Dedup::Apply(guard) => guard,
+        };
+        let table_id = req.table_id;
+        let internal_table_ids = self
+            .fragment_manager
+            .select_table_fragments_by_table_id(&TableId::new(table_id))
+            .await
+            .map_err(tonic_err)?
+            .internal_table_ids();
         // 1. Drop table in catalog. Ref count will be checked.
+        let record = guard.record(&DropMaterializedViewResponse::default());
         let version = self
             .catalog_manager
-            .drop_table(table_id)
+            .drop_table(table_id, internal_table_ids, record)
             .await
             .map_err(tonic_err)?;
 
@@ -330,10 +598,19 @@ where
             .await
             .map_err(tonic_err)?;
 
-        Ok(Response::new(DropMaterializedViewResponse {
+        self.env
+            .event_log_manager()
+            .log(
+                EventType::Ddl,
+                format!("drop materialized view {}", table_id),
+            )
+            .await;
+        let response = DropMaterializedViewResponse {
             status: None,
             version,
-        }))
+        };
+        guard.finish().await;
+        Ok(Response::new(response))
     }
 
     async fn create_materialized_source(
@@ -341,21 +618,47 @@ where
         request: Request<CreateMaterializedSourceRequest>,
     ) -> Result<Response<CreateMaterializedSourceResponse>, Status> {
         let request = request.into_inner();
+        let guard = match self
+            .env
+            .idempotency_manager()
+            .begin(&request.idempotency_key)
+            .await
+            .map_err(tonic_err)?
+        {
+            Dedup::Replay(mut response) => {
+                response.version = self.env.notification_manager().current_version().await;
+                return Ok(Response::new(response));
+            }
+            Dedup::Apply(guard) => guard,
+        };
         let source = request.source.unwrap();
         let mview = request.materialized_view.unwrap();
         let fragment_graph = request.fragment_graph.unwrap();
+        let source_name = source.name.clone();
 
         let (source_id, table_id, version) = self
-            .create_materialized_source_inner(source, mview, fragment_graph)
+            .create_materialized_source_inner(source, mview, fragment_graph, &guard)
             .await
             .map_err(tonic_err)?;
 
-        Ok(Response::new(CreateMaterializedSourceResponse {
+        self.env
+            .event_log_manager()
+            .log(
+                EventType::Ddl,
+                format!(
+                    "create materialized source {} (source id {}, table id {})",
+                    source_name, source_id, table_id
+                ),
+            )
+            .await;
+        let response = CreateMaterializedSourceResponse {
             status: None,
             source_id,
             table_id,
             version,
-        }))
+        };
+        guard.finish().await;
+        Ok(Response::new(response))
     }
 
     async fn drop_materialized_source(
@@ -363,18 +666,226 @@ where
         request: Request<DropMaterializedSourceRequest>,
     ) -> Result<Response<DropMaterializedSourceResponse>, Status> {
         let request = request.into_inner();
+        let guard = match self
+            .env
+            .idempotency_manager()
+            .begin(&request.idempotency_key)
+            .await
+            .map_err(tonic_err)?
+        {
+            Dedup::Replay(mut response) => {
+                response.version = self.env.notification_manager().current_version().await;
+                return Ok(Response::new(response));
+            }
+            Dedup::Apply(guard) => guard,
+        };
         let source_id = request.source_id;
         let table_id = request.table_id;
 
         let version = self
-            .drop_materialized_source_inner(source_id, table_id)
+            .drop_materialized_source_inner(
+                source_id,
+                table_id,
+                guard.record(&DropMaterializedSourceResponse::default()),
+            )
+            .await
+            .map_err(tonic_err)?;
+
+        self.env
+            .event_log_manager()
+            .log(
+                EventType::Ddl,
+                format!(
+                    "drop materialized source {} (table id {})",
+                    source_id, table_id
+                ),
+            )
+            .await;
+        let response = DropMaterializedSourceResponse {
+            status: None,
+            version,
+        };
+        guard.finish().await;
+        Ok(Response::new(response))
+    }
+
+    async fn alter_relation_name(
+        &self,
+        request: Request<AlterRelationNameRequest>,
+    ) -> Result<Response<AlterRelationNameResponse>, Status> {
+        let req = request.into_inner();
+        let guard = match self
+            .env
+            .idempotency_manager()
+            .begin(&req.idempotency_key)
+            .await
+            .map_err(tonic_err)?
+        {
+            Dedup::Replay(mut response) => {
+                response.version = self.env.notification_manager().current_version().await;
+                return Ok(Response::new(response));
+            }
+            Dedup::Apply(guard) => guard,
+        };
+        let relation = match req.get_relation().map_err(tonic_err)? {
+            alter_relation_name_request::Relation::TableId(id) => RelationIdEnum::Table(*id),
+            alter_relation_name_request::Relation::SourceId(id) => RelationIdEnum::Source(*id),
+        };
+
+        let record = guard.record(&AlterRelationNameResponse::default());
+        let version = self
+            .catalog_manager
+            .alter_relation_name(relation, &req.new_name, record)
+            .await
+            .map_err(tonic_err)?;
+
+        self.env
+            .event_log_manager()
+            .log(
+                EventType::Ddl,
+                format!("rename {:?} to {}", relation, req.new_name),
+            )
+            .await;
+        let response = AlterRelationNameResponse {
+            status: None,
+            version,
+        };
+        guard.finish().await;
+        Ok(Response::new(response))
+    }
+
+    async fn alter_relation_owner(
+        &self,
+        request: Request<AlterRelationOwnerRequest>,
+    ) -> Result<Response<AlterRelationOwnerResponse>, Status> {
+        let req = request.into_inner();
+        let guard = match self
+            .env
+            .idempotency_manager()
+            .begin(&req.idempotency_key)
+            .await
+            .map_err(tonic_err)?
+        {
+            Dedup::Replay(mut response) => {
+                response.version = self.env.notification_manager().current_version().await;
+                return Ok(Response::new(response));
+            }
+            Dedup::Apply(guard) => guard,
+        };
+        let relation = match req.get_relation().map_err(tonic_err)? {
+            alter_relation_owner_request::Relation::TableId(id) => RelationIdEnum::Table(*id),
+            alter_relation_owner_request::Relation::SourceId(id) => RelationIdEnum::Source(*id),
+        };
+
+        let record = guard.record(&AlterRelationOwnerResponse::default());
+        let version = self
+            .catalog_manager
+            .alter_relation_owner(relation, &req.new_owner, record)
+            .await
+            .map_err(tonic_err)?;
+
+        self.env
+            .event_log_manager()
+            .log(
+                EventType::Ddl,
+                format!("change owner of {:?} to {}", relation, req.new_owner),
+            )
+            .await;
+        let response = AlterRelationOwnerResponse {
+            status: None,
+            version,
+        };
+        guard.finish().await;
+        Ok(Response::new(response))
+    }
+
+    async fn create_function(
+        &self,
+        request: Request<CreateFunctionRequest>,
+    ) -> Result<Response<CreateFunctionResponse>, Status> {
+        let req = request.into_inner();
+        let guard = match self
+            .env
+            .idempotency_manager()
+            .begin(&req.idempotency_key)
+            .await
+            .map_err(tonic_err)?
+        {
+            Dedup::Replay(mut response) => {
+                response.version = self.env.notification_manager().current_version().await;
+                return Ok(Response::new(response));
+            }
+            Dedup::Apply(guard) => guard,
+        };
+        let id = self
+            .env
+            .id_gen_manager()
+            .generate::<{ IdCategory::Function }>()
+            .await
+            .map_err(tonic_err)? as u32;
+        let mut function = req.get_function().map_err(tonic_err)?.clone();
+        function.id = id;
+        let record = guard.record(&CreateFunctionResponse {
+            function_id: id,
+            ..Default::default()
+        });
+        let version = self
+            .catalog_manager
+            .create_function(&function, record)
             .await
             .map_err(tonic_err)?;
 
-        Ok(Response::new(DropMaterializedSourceResponse {
+        self.env
+            .event_log_manager()
+            .log(
+                EventType::Ddl,
+                format!("create function {} (id {})", function.name, id),
+            )
+            .await;
+        let response = CreateFunctionResponse {
             status: None,
+            function_id: id,
             version,
-        }))
+        };
+        guard.finish().await;
+        Ok(Response::new(response))
+    }
+
+    async fn drop_function(
+        &self,
+        request: Request<DropFunctionRequest>,
+    ) -> Result<Response<DropFunctionResponse>, Status> {
+        let req = request.into_inner();
+        let guard = match self
+            .env
+            .idempotency_manager()
+            .begin(&req.idempotency_key)
+            .await
+            .map_err(tonic_err)?
+        {
+            Dedup::Replay(mut response) => {
+                response.version = self.env.notification_manager().current_version().await;
+                return Ok(Response::new(response));
+            }
+            Dedup::Apply(guard) => guard,
+        };
+        let function_id = req.get_function_id();
+        let record = guard.record(&DropFunctionResponse::default());
+        let version = self
+            .catalog_manager
+            .drop_function(function_id, record)
+            .await
+            .map_err(tonic_err)?;
+        self.env
+            .event_log_manager()
+            .log(EventType::Ddl, format!("drop function {}", function_id))
+            .await;
+        let response = DropFunctionResponse {
+            status: None,
+            version,
+        };
+        guard.finish().await;
+        Ok(Response::new(response))
     }
 }
 
@@ -386,8 +897,9 @@ where
         &self,
         mut fragment_graph: StreamFragmentGraph,
         id: TableId,
+        table_option: TableOption,
         affiliated_source: Option<Source>,
-    ) -> RwResult<()> {
+    ) -> RwResult<Vec<Table>> {
         use risingwave_common::catalog::TableId;
 
         use crate::stream::CreateMaterializedViewContext;
@@ -417,16 +929,22 @@ where
             "require exactly 1 materialize node when creating materialized view"
         );
 
-        // Resolve fragments.
-        let parallel_degree = self
+        // Resolve fragments. The parallelism requested by the session is capped by the number of
+        // parallel units, since each actor of a fragment is scheduled to a distinct one.
+        let parallel_unit_count = self
             .cluster_manager
             .get_parallel_unit_count(Some(ParallelUnitType::Hash))
             .await;
+        let parallel_degree = match fragment_graph.parallelism as usize {
+            0 => parallel_unit_count,
+            parallelism => parallelism.min(parallel_unit_count),
+        };
         let mut ctx = CreateMaterializedViewContext {
             affiliated_source,
+            table_option,
             ..Default::default()
         };
-        let graph = ActorGraphBuilder::generate_graph(
+        let mut graph = ActorGraphBuilder::generate_graph(
             self.env.id_gen_manager_ref(),
             self.fragment_manager.clone(),
             parallel_degree as u32,
@@ -434,20 +952,31 @@ where
             &mut ctx,
         )
         .await?;
+        // All the actors of the materialized view run in its resource group.
+        for actor in graph
+            .values_mut()
+            .flat_map(|fragment| fragment.actors.iter_mut())
+        {
+            actor.resource_group = fragment_graph.resource_group.clone();
+        }
         assert_eq!(
             fragment_graph.table_ids_cnt,
             ctx.internal_table_id_set.len() as u32
         );
 
-        let table_fragments =
+        let mut table_fragments =
             TableFragments::new(mview_id, graph, ctx.internal_table_id_set.clone());
+        table_fragments.set_parallelism(parallel_degree as u32);
+        let internal_tables = std::mem::take(&mut ctx.internal_tables)
+            .into_values()
+            .collect();
 
         // Create on compute node.
         self.stream_manager
             .create_materialized_view(table_fragments, ctx)
             .await?;
 
-        Ok(())
+        Ok(internal_tables)
     }
 
     async fn create_materialized_source_inner(
@@ -455,6 +984,7 @@ where
         mut source: Source,
         mut mview: Table,
         mut fragment_graph: StreamFragmentGraph,
+        guard: &IdempotencyGuard<'_, S>,
     ) -> RwResult<(SourceId, TableId, CatalogVersion)> {
         // Generate source id.
         let source_id = self
@@ -514,22 +1044,35 @@ where
 
         // Create mview on compute node.
         // Noted that this progress relies on the source just created, so we pass it here.
-        if let Err(e) = self
-            .create_mview_on_compute_node(fragment_graph, mview_id, Some(source.clone()))
+        let internal_tables = match self
+            .create_mview_on_compute_node(
+                fragment_graph,
+                mview_id,
+                table_option_of(&mview),
+                Some(source.clone()),
+            )
             .await
         {
-            self.catalog_manager
-                .cancel_create_materialized_source_procedure(&source, &mview)
-                .await?;
-            // drop previously created source
-            self.source_manager.drop_source(source_id).await?;
-            return Err(e);
-        }
+            Ok(internal_tables) => fill_internal_tables(&mview, internal_tables),
+            Err(e) => {
+                self.catalog_manager
+                    .cancel_create_materialized_source_procedure(&source, &mview)
+                    .await?;
+                // drop previously created source
+                self.source_manager.drop_source(source_id).await?;
+                return Err(e);
+            }
+        };
 
         // Finally, update the catalog.
+        let record = guard.record(&CreateMaterializedSourceResponse {
+            source_id,
+            table_id: mview_id,
+            ..Default::default()
+        });
         let version = self
             .catalog_manager
-            .finish_create_materialized_source_procedure(&source, &mview)
+            .finish_create_materialized_source_procedure(&source, internal_tables, &mview, record)
             .await?;
 
         Ok((source_id, mview_id, version))
@@ -539,14 +1082,20 @@ where
         &self,
         source_id: SourceId,
         table_id: TableId,
+        record: Option<IdempotencyRecord>,
     ) -> RwResult<CatalogVersion> {
         use risingwave_common::catalog::TableId;
 
+        let internal_table_ids = self
+            .fragment_manager
+            .select_table_fragments_by_table_id(&TableId::new(table_id))
+            .await?
+            .internal_table_ids();
         // 1. Drop materialized source in catalog, source_id will be checked if it is
         // associated_source_id in mview.
         let version = self
             .catalog_manager
-            .drop_materialized_source(source_id, table_id)
+            .drop_materialized_source(source_id, table_id, internal_table_ids, record)
             .await?;
 
         // 2. Drop source and mv separately.
@@ -558,3 +1107,25 @@ where
         Ok(version)
     }
 }
+
+/// Fills the catalogs of the `internal_tables` of `mview` with its database, schema and owner, and
+/// names them after it, e.g., `__internal_mv_hashagg_1001`.
+fn fill_internal_tables(mview: &Table, internal_tables: Vec<Table>) -> Vec<Table> {
+    internal_tables
+        .into_iter()
+        .map(|table| Table {
+            name: format!("__internal_{}_{}_{}", mview.name, table.name, table.id),
+            database_id: mview.database_id,
+            schema_id: mview.schema_id,
+            owner: mview.owner.clone(),
+            ..table
+        })
+        .collect()
+}
+
+/// The storage options of the table of `mview`, e.g. its retention.
+fn table_option_of(mview: &Table) -> TableOption {
+    TableOption {
+        retention_seconds: mview.retention_seconds,
+    }
+}
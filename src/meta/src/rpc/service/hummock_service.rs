@@ -13,13 +13,15 @@
 // limitations under the License.
 
 use std::sync::Arc;
+use std::time::Duration;
 
 use risingwave_common::error::{tonic_err, ErrorCode};
+use risingwave_hummock_sdk::compaction_group::CompactionGroupId;
 use risingwave_pb::hummock::hummock_manager_service_server::HummockManagerService;
 use risingwave_pb::hummock::*;
 use tonic::{Request, Response, Status};
 
-use crate::hummock::{CompactorManager, HummockManagerRef, VacuumTrigger};
+use crate::hummock::{CompactorManager, HummockManagerRef, ManualCompactionOption, VacuumTrigger};
 use crate::rpc::service::RwReceiverStream;
 use crate::storage::MetaStore;
 
@@ -131,6 +133,24 @@ where
         }
     }
 
+    async fn pin_specific_snapshot(
+        &self,
+        request: Request<PinSpecificSnapshotRequest>,
+    ) -> Result<Response<PinSnapshotResponse>, Status> {
+        let req = request.into_inner();
+        let result = self
+            .hummock_manager
+            .pin_specific_snapshot(req.context_id, req.epoch)
+            .await;
+        match result {
+            Ok(hummock_snapshot) => Ok(Response::new(PinSnapshotResponse {
+                status: None,
+                snapshot: Some(hummock_snapshot),
+            })),
+            Err(e) => Err(tonic_err(e)),
+        }
+    }
+
     async fn unpin_snapshot(
         &self,
         request: Request<UnpinSnapshotRequest>,
@@ -204,4 +224,93 @@ where
         }
         Ok(Response::new(ReportVacuumTaskResponse { status: None }))
     }
+
+    async fn get_compaction_groups(
+        &self,
+        _request: Request<GetCompactionGroupsRequest>,
+    ) -> Result<Response<GetCompactionGroupsResponse>, Status> {
+        let compaction_groups = self
+            .hummock_manager
+            .compaction_group_manager()
+            .compaction_groups()
+            .await
+            .iter()
+            .map(|cg| cg.into())
+            .collect();
+        Ok(Response::new(GetCompactionGroupsResponse {
+            status: None,
+            compaction_groups,
+        }))
+    }
+
+    async fn report_read_statistics(
+        &self,
+        request: Request<ReportReadStatisticsRequest>,
+    ) -> Result<Response<ReportReadStatisticsResponse>, Status> {
+        self.hummock_manager
+            .report_read_statistics(request.into_inner().sst_read_statistics)
+            .await;
+        Ok(Response::new(ReportReadStatisticsResponse { status: None }))
+    }
+
+    async fn trigger_manual_compaction(
+        &self,
+        request: Request<TriggerManualCompactionRequest>,
+    ) -> Result<Response<TriggerManualCompactionResponse>, Status> {
+        let req = request.into_inner();
+        let compaction_group_id = CompactionGroupId::from(req.compaction_group_id);
+        let mut option = ManualCompactionOption {
+            level: req.level as usize,
+            ..Default::default()
+        };
+        if let Some(key_range) = req.key_range {
+            option.key_range = key_range;
+        }
+
+        let compactor = self.compactor_manager.next_compactor().ok_or_else(|| {
+            tonic_err(ErrorCode::MetaError(
+                "no compactor is available".to_string(),
+            ))
+        })?;
+        let compact_task = self
+            .hummock_manager
+            .manual_get_compact_task(compaction_group_id, option)
+            .await
+            .map_err(tonic_err)?
+            .ok_or_else(|| {
+                tonic_err(ErrorCode::MetaError(format!(
+                    "no SST to compact in level {} of compaction group {:?}, or they are being compacted",
+                    req.level, compaction_group_id
+                )))
+            })?;
+        let send_task = async {
+            tokio::time::timeout(Duration::from_secs(5), async {
+                compactor
+                    .send_task(Some(compact_task.clone()), None)
+                    .await
+                    .is_ok()
+            })
+            .await
+            .unwrap_or(false)
+        };
+        if let Err(e) = self
+            .hummock_manager
+            .assign_compaction_task(&compact_task, compactor.context_id(), send_task)
+            .await
+        {
+            self.hummock_manager
+                .cancel_unassigned_compact_task(&compact_task)
+                .await
+                .map_err(tonic_err)?;
+            return Err(tonic_err(e));
+        }
+        tracing::info!(
+            "Triggered manual compaction task {} of compaction group {:?}",
+            compact_task.task_id,
+            compaction_group_id
+        );
+        Ok(Response::new(TriggerManualCompactionResponse {
+            status: None,
+        }))
+    }
 }
@@ -23,6 +23,7 @@ use tokio_stream::wrappers::UnboundedReceiverStream;
 use tonic::{Request, Response, Status};
 
 use crate::cluster::{ClusterManagerRef, WorkerKey};
+use crate::hummock::HummockManagerRef;
 use crate::manager::{CatalogManagerRef, MetaSrvEnv, Notification, UserInfoManagerRef};
 use crate::storage::MetaStore;
 pub struct NotificationServiceImpl<S: MetaStore> {
@@ -31,6 +32,7 @@ pub struct NotificationServiceImpl<S: MetaStore> {
     catalog_manager: CatalogManagerRef<S>,
     cluster_manager: ClusterManagerRef<S>,
     user_manager: UserInfoManagerRef<S>,
+    hummock_manager: HummockManagerRef<S>,
 }
 
 impl<S> NotificationServiceImpl<S>
@@ -42,12 +44,14 @@ where
         catalog_manager: CatalogManagerRef<S>,
         cluster_manager: ClusterManagerRef<S>,
         user_manager: UserInfoManagerRef<S>,
+        hummock_manager: HummockManagerRef<S>,
     ) -> Self {
         Self {
             env,
             catalog_manager,
             cluster_manager,
             user_manager,
+            hummock_manager,
         }
     }
 }
@@ -72,14 +76,29 @@ where
 
         match worker_type {
             WorkerType::ComputeNode => {
-                self.env
-                    .notification_manager()
-                    .insert_compute_sender(WorkerKey(host_address), tx)
+                let worker_id = self
+                    .cluster_manager
+                    .get_cluster_core_guard()
+                    .await
+                    .get_worker_by_host(host_address.clone())
+                    .ok_or_else(|| Status::not_found("worker node does not exist"))?
+                    .worker_id();
+                // The hummock manager sends the snapshot of the current version, and pushes the
+                // new versions after that.
+                self.hummock_manager
+                    .subscribe_version(
+                        worker_id,
+                        req.last_pinned_version_id,
+                        WorkerKey(host_address),
+                        tx,
+                    )
                     .await
+                    .map_err(tonic_err)?;
             }
             WorkerType::Frontend => {
                 let catalog_guard = self.catalog_manager.get_catalog_core_guard().await;
-                let (database, schema, table, source) = catalog_guard.get_catalog().await?;
+                let (database, schema, table, source, functions) =
+                    catalog_guard.get_catalog().await?;
 
                 let cluster_guard = self.cluster_manager.get_cluster_core_guard().await;
                 let nodes = cluster_guard.list_worker_node(WorkerType::ComputeNode, Some(Running));
@@ -96,6 +115,7 @@ where
                     table,
                     users,
                     view: Default::default(),
+                    functions,
                 };
                 tx.send(Ok(SubscribeResponse {
                     status: None,
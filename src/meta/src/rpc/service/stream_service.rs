@@ -12,12 +12,15 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use risingwave_common::catalog::TableId;
+use risingwave_common::try_match_expand;
 use risingwave_pb::meta::stream_manager_service_server::StreamManagerService;
 use risingwave_pb::meta::*;
 use tonic::{Request, Response, Status};
 
+use crate::model::MetadataModel;
 use crate::storage::MetaStore;
-use crate::stream::GlobalStreamManagerRef;
+use crate::stream::{FragmentManagerRef, GlobalStreamManagerRef};
 
 pub type TonicResponse<T> = Result<Response<T>, Status>;
 
@@ -27,15 +30,20 @@ where
     S: MetaStore,
 {
     global_stream_manager: GlobalStreamManagerRef<S>,
+    fragment_manager: FragmentManagerRef<S>,
 }
 
 impl<S> StreamServiceImpl<S>
 where
     S: MetaStore,
 {
-    pub fn new(global_stream_manager: GlobalStreamManagerRef<S>) -> Self {
+    pub fn new(
+        global_stream_manager: GlobalStreamManagerRef<S>,
+        fragment_manager: FragmentManagerRef<S>,
+    ) -> Self {
         StreamServiceImpl {
             global_stream_manager,
+            fragment_manager,
         }
     }
 }
@@ -49,7 +57,101 @@ where
     async fn flush(&self, request: Request<FlushRequest>) -> TonicResponse<FlushResponse> {
         let _req = request.into_inner();
 
-        self.global_stream_manager.flush().await?;
-        Ok(Response::new(FlushResponse { status: None }))
+        let committed_epoch = self.global_stream_manager.flush().await?;
+        Ok(Response::new(FlushResponse {
+            status: None,
+            committed_epoch,
+        }))
+    }
+
+    #[cfg_attr(coverage, no_coverage)]
+    async fn get_current_epoch(
+        &self,
+        request: Request<GetCurrentEpochRequest>,
+    ) -> TonicResponse<GetCurrentEpochResponse> {
+        let _req = request.into_inner();
+
+        let current_epoch = self.global_stream_manager.current_epoch();
+        Ok(Response::new(GetCurrentEpochResponse {
+            status: None,
+            current_epoch,
+        }))
+    }
+
+    #[cfg_attr(coverage, no_coverage)]
+    async fn pause(&self, request: Request<PauseRequest>) -> TonicResponse<PauseResponse> {
+        let _req = request.into_inner();
+
+        self.global_stream_manager.pause().await?;
+        Ok(Response::new(PauseResponse { status: None }))
+    }
+
+    #[cfg_attr(coverage, no_coverage)]
+    async fn resume(&self, request: Request<ResumeRequest>) -> TonicResponse<ResumeResponse> {
+        let _req = request.into_inner();
+
+        self.global_stream_manager.resume().await?;
+        Ok(Response::new(ResumeResponse { status: None }))
+    }
+
+    #[cfg_attr(coverage, no_coverage)]
+    async fn trigger_recovery(
+        &self,
+        request: Request<TriggerRecoveryRequest>,
+    ) -> TonicResponse<TriggerRecoveryResponse> {
+        let _req = request.into_inner();
+
+        self.global_stream_manager.trigger_recovery()?;
+        Ok(Response::new(TriggerRecoveryResponse { status: None }))
+    }
+
+    #[cfg_attr(coverage, no_coverage)]
+    async fn drain_worker(
+        &self,
+        request: Request<DrainWorkerRequest>,
+    ) -> TonicResponse<DrainWorkerResponse> {
+        let req = request.into_inner();
+        let host = try_match_expand!(req.host, Some, "DrainWorkerRequest::host is empty")?;
+
+        self.global_stream_manager.drain_worker(host).await?;
+        Ok(Response::new(DrainWorkerResponse { status: None }))
+    }
+
+    #[cfg_attr(coverage, no_coverage)]
+    async fn alter_parallelism(
+        &self,
+        request: Request<AlterParallelismRequest>,
+    ) -> TonicResponse<AlterParallelismResponse> {
+        let req = request.into_inner();
+
+        self.global_stream_manager
+            .alter_parallelism(&TableId::new(req.table_id), req.parallelism)
+            .await?;
+        Ok(Response::new(AlterParallelismResponse { status: None }))
+    }
+
+    #[cfg_attr(coverage, no_coverage)]
+    async fn list_table_fragments(
+        &self,
+        request: Request<ListTableFragmentsRequest>,
+    ) -> TonicResponse<ListTableFragmentsResponse> {
+        let req = request.into_inner();
+
+        let table_fragments = self
+            .fragment_manager
+            .list_table_fragments()
+            .await?
+            .into_iter()
+            .filter(|table_fragments| {
+                req.table_ids.is_empty()
+                    || req
+                        .table_ids
+                        .contains(&table_fragments.table_id().table_id())
+            })
+            .map(|table_fragments| table_fragments.to_protobuf())
+            .collect();
+        Ok(Response::new(ListTableFragmentsResponse {
+            table_fragments,
+        }))
     }
 }
@@ -123,7 +123,8 @@ struct ListViewer {
 }
 
 impl SnapshotViewer for ListViewer {
-    type Output = Vec<Vec<u8>>;
+    /// Key-value pairs, with the prefix stripped from the keys.
+    type Output = Vec<(Vec<u8>, Vec<u8>)>;
 
     type OutputFuture<'a> = impl Future<Output = Result<(i64, Self::Output)>> + 'a;
 
@@ -142,8 +143,12 @@ impl SnapshotViewer for ListViewer {
                     "Etcd response missing header"
                 )));
             };
-            let value = res.kvs().iter().map(|kv| kv.value().to_vec()).collect();
-            Ok((new_revision, value))
+            let kvs = res
+                .kvs()
+                .iter()
+                .map(|kv| (kv.key()[self.key.len()..].to_vec(), kv.value().to_vec()))
+                .collect();
+            Ok((new_revision, kvs))
         }
     }
 }
@@ -151,6 +156,15 @@ impl SnapshotViewer for ListViewer {
 #[async_trait]
 impl Snapshot for EtcdSnapshot {
     async fn list_cf(&self, cf: &str) -> Result<Vec<Vec<u8>>> {
+        Ok(self
+            .list_cf_kv(cf)
+            .await?
+            .into_iter()
+            .map(|(_, value)| value)
+            .collect())
+    }
+
+    async fn list_cf_kv(&self, cf: &str) -> Result<Vec<(Key, Value)>> {
         let view = ListViewer {
             key: encode_etcd_key(cf, &[]),
         };
@@ -22,13 +22,15 @@ use risingwave_common::error::{Result, RwError};
 use risingwave_common::hash::VIRTUAL_NODE_COUNT;
 use risingwave_common::try_match_expand;
 use risingwave_common::util::compress::decompress_data;
+use risingwave_pb::common::ParallelUnit;
+use risingwave_pb::data::AddedColumn;
 use risingwave_pb::meta::table_fragments::ActorState;
 use risingwave_pb::stream_plan::{FragmentType, StreamActor};
 use tokio::sync::RwLock;
 
 use crate::cluster::{ParallelUnitId, WorkerId};
-use crate::manager::{HashMappingManagerRef, MetaSrvEnv};
-use crate::model::{ActorId, MetadataModel, TableFragments, Transactional};
+use crate::manager::{HashMappingManagerRef, MetaSrvEnv, SourceId};
+use crate::model::{ActorId, FragmentId, MetadataModel, TableFragments, Transactional};
 use crate::storage::{MetaStore, Transaction};
 use crate::stream::record_table_vnode_mappings;
 
@@ -40,6 +42,8 @@ struct FragmentManagerCore {
 pub struct FragmentManager<S> {
     meta_store: Arc<S>,
 
+    hash_mapping_manager: HashMappingManagerRef,
+
     core: RwLock<FragmentManagerCore>,
 }
 
@@ -76,10 +80,12 @@ where
             .map(|tf| (tf.table_id(), tf))
             .collect();
 
-        Self::restore_vnode_mappings(env.hash_mapping_manager_ref(), &table_fragments)?;
+        let hash_mapping_manager = env.hash_mapping_manager_ref();
+        Self::restore_vnode_mappings(hash_mapping_manager.clone(), &table_fragments)?;
 
         Ok(Self {
             meta_store,
+            hash_mapping_manager,
             core: RwLock::new(FragmentManagerCore { table_fragments }),
         })
     }
@@ -107,6 +113,113 @@ where
         }
     }
 
+    /// Persists the vnodes of the fragments reassigned by `vnode_mappings`, after the actors have
+    /// switched to them. See [`TableFragments::reschedule_vnodes`].
+    pub async fn reschedule_vnodes(
+        &self,
+        table_id: &TableId,
+        vnode_mappings: &BTreeMap<FragmentId, Vec<ActorId>>,
+    ) -> Result<()> {
+        let map = &mut self.core.write().await.table_fragments;
+        let mut table_fragments = map.get(table_id).cloned().ok_or_else(|| {
+            RwError::from(InternalError(format!(
+                "table_fragment not exist: id={}",
+                table_id
+            )))
+        })?;
+        for (fragment_id, vnode_mapping) in vnode_mappings {
+            table_fragments.reschedule_vnodes(*fragment_id, vnode_mapping)?;
+        }
+        table_fragments.insert(&*self.meta_store).await?;
+
+        for fragment_id in vnode_mappings.keys() {
+            let mapping = table_fragments.fragments[fragment_id]
+                .vnode_mapping
+                .as_ref()
+                .unwrap();
+            self.hash_mapping_manager.set_fragment_hash_mapping(
+                *fragment_id,
+                decompress_data(&mapping.original_indices, &mapping.data),
+            );
+        }
+        map.insert(*table_id, table_fragments);
+
+        Ok(())
+    }
+
+    /// Persists the parallelism of table `table_id`, after the vnodes of its fragments have been
+    /// rescheduled to that many actors.
+    pub async fn set_parallelism(&self, table_id: &TableId, parallelism: u32) -> Result<()> {
+        let map = &mut self.core.write().await.table_fragments;
+        let mut table_fragments = map.get(table_id).cloned().ok_or_else(|| {
+            RwError::from(InternalError(format!(
+                "table_fragment not exist: id={}",
+                table_id
+            )))
+        })?;
+        table_fragments.set_parallelism(parallelism);
+        table_fragments.insert(&*self.meta_store).await?;
+        map.insert(*table_id, table_fragments);
+
+        Ok(())
+    }
+
+    /// Moves the actors on the parallel units in `migration` to the parallel units they map to,
+    /// and returns the tables whose actors are moved. See [`TableFragments::migrate_actors`].
+    pub async fn migrate_actors(
+        &self,
+        migration: &HashMap<ParallelUnitId, ParallelUnit>,
+    ) -> Result<Vec<TableFragments>> {
+        let map = &mut self.core.write().await.table_fragments;
+        let mut transaction = Transaction::default();
+        let mut migrated_tables = vec![];
+        for table_fragments in map.values() {
+            let mut table_fragments = table_fragments.clone();
+            if table_fragments.migrate_actors(migration) {
+                table_fragments.upsert_in_transaction(&mut transaction)?;
+                migrated_tables.push(table_fragments);
+            }
+        }
+        if migrated_tables.is_empty() {
+            return Ok(migrated_tables);
+        }
+        self.meta_store.txn(transaction).await?;
+
+        for table_fragments in &migrated_tables {
+            for (fragment_id, fragment) in &table_fragments.fragments {
+                let mapping = fragment.vnode_mapping.as_ref().unwrap();
+                self.hash_mapping_manager.set_fragment_hash_mapping(
+                    *fragment_id,
+                    decompress_data(&mapping.original_indices, &mapping.data),
+                );
+            }
+            map.insert(table_fragments.table_id(), table_fragments.clone());
+        }
+
+        Ok(migrated_tables)
+    }
+
+    /// Persists the `columns` added to source `source_id` in the fragments of table `table_id`.
+    pub async fn add_columns(
+        &self,
+        table_id: &TableId,
+        source_id: SourceId,
+        columns: &[AddedColumn],
+    ) -> Result<()> {
+        let map = &mut self.core.write().await.table_fragments;
+        let mut table_fragments = map.get(table_id).cloned().ok_or_else(|| {
+            RwError::from(InternalError(format!(
+                "table_fragment not exist: id={}",
+                table_id
+            )))
+        })?;
+        table_fragments.add_columns(source_id, columns);
+        table_fragments.insert(&*self.meta_store).await?;
+        map.insert(*table_id, table_fragments);
+
+        Ok(())
+    }
+
     pub async fn select_table_fragments_by_table_id(
         &self,
         table_id: &TableId,
@@ -331,6 +444,23 @@ where
             .collect::<HashSet<_>>()
     }
 
+    /// Returns the table and the fragment the actor belongs to, if it exists.
+    pub async fn locate_actor(&self, actor_id: ActorId) -> Option<(TableId, FragmentId)> {
+        let map = &self.core.read().await.table_fragments;
+        map.iter().find_map(|(table_id, table_fragment)| {
+            table_fragment
+                .fragments()
+                .into_iter()
+                .find(|fragment| {
+                    fragment
+                        .actors
+                        .iter()
+                        .any(|actor| actor.actor_id == actor_id)
+                })
+                .map(|fragment| (*table_id, fragment.fragment_id))
+        })
+    }
+
     pub async fn table_node_actors(
         &self,
         table_id: &TableId,
@@ -13,6 +13,7 @@
 // limitations under the License.
 
 mod meta;
+mod node_rpc;
 mod scheduler;
 mod source_manager;
 mod stream_graph;
@@ -22,6 +23,7 @@ mod stream_manager;
 mod test_fragmenter;
 
 pub use meta::*;
+pub use node_rpc::*;
 use risingwave_common::error::Result;
 use risingwave_pb::stream_plan::stream_node::NodeBody;
 use risingwave_pb::stream_plan::StreamNode;
@@ -0,0 +1,130 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+
+use futures::future::join_all;
+use futures::Future;
+use itertools::Itertools;
+use risingwave_common::error::{ErrorCode, Result, RwError};
+
+use crate::cluster::WorkerId;
+
+/// Failures of an RPC sent to multiple compute nodes.
+#[derive(Debug)]
+pub struct NodeRpcError {
+    pub rpc_name: &'static str,
+    /// Number of nodes the RPC is sent to.
+    pub node_count: usize,
+    /// The nodes that failed, with their errors.
+    pub failures: Vec<(WorkerId, RwError)>,
+}
+
+impl fmt::Display for NodeRpcError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} failed on {} of {} nodes: {}",
+            self.rpc_name,
+            self.failures.len(),
+            self.node_count,
+            self.failures
+                .iter()
+                .map(|(node_id, err)| format!("node {}: {}", node_id, err))
+                .join("; ")
+        )
+    }
+}
+
+impl From<NodeRpcError> for RwError {
+    fn from(err: NodeRpcError) -> Self {
+        ErrorCode::InternalError(err.to_string()).into()
+    }
+}
+
+/// Sends an RPC to multiple compute nodes concurrently, and waits for all of them. Unlike
+/// `try_join_all`, a failure on one node doesn't cancel the RPCs to the others, so that every
+/// failed node is reported. The responses are returned in the order of `futures`.
+pub async fn join_node_rpcs<T, F>(
+    rpc_name: &'static str,
+    futures: impl IntoIterator<Item = (WorkerId, F)>,
+) -> std::result::Result<Vec<T>, NodeRpcError>
+where
+    F: Future<Output = Result<T>>,
+{
+    let results = join_all(
+        futures
+            .into_iter()
+            .map(|(node_id, future)| async move { (node_id, future.await) }),
+    )
+    .await;
+    let node_count = results.len();
+    let mut responses = Vec::with_capacity(node_count);
+    let mut failures = vec![];
+    for (node_id, result) in results {
+        match result {
+            Ok(response) => responses.push(response),
+            Err(err) => {
+                tracing::warn!("{} failed on node {}: {}", rpc_name, node_id, err);
+                failures.push((node_id, err));
+            }
+        }
+    }
+    if failures.is_empty() {
+        Ok(responses)
+    } else {
+        Err(NodeRpcError {
+            rpc_name,
+            node_count,
+            failures,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_join_node_rpcs() {
+        let futures = (1..=4).map(|node_id| {
+            (node_id, async move {
+                if node_id % 2 == 0 {
+                    Err(ErrorCode::InternalError(format!("node {} is down", node_id)).into())
+                } else {
+                    Ok::<_, RwError>(node_id * 10)
+                }
+            })
+        });
+        let err = join_node_rpcs("test_rpc", futures).await.unwrap_err();
+        assert_eq!(err.node_count, 4);
+        assert_eq!(
+            err.failures
+                .iter()
+                .map(|(node_id, _)| *node_id)
+                .collect_vec(),
+            vec![2, 4]
+        );
+        assert!(err
+            .to_string()
+            .starts_with("test_rpc failed on 2 of 4 nodes"));
+
+        let futures =
+            (1..=3).map(|node_id| (node_id, async move { Ok::<_, RwError>(node_id * 10) }));
+        assert_eq!(
+            join_node_rpcs("test_rpc", futures).await.unwrap(),
+            vec![10, 20, 30]
+        );
+    }
+}
@@ -199,11 +199,29 @@ where
         } else {
             // Normal fragment
 
-            // Find out all the hash parallel units in the cluster.
-            let parallel_units = self
-                .cluster_manager
-                .list_parallel_units(Some(ParallelUnitType::Hash))
-                .await;
+            let colocated = fragment.actors.iter().all(|actor| {
+                actor.same_worker_node_as_upstream && !actor.upstream_actor_id.is_empty()
+            });
+            let parallel_units = if colocated {
+                // The fragment has an identical distribution as its upstream fragment, so every
+                // actor is scheduled to the parallel unit of its upstream actor. The parallel units
+                // are in the same order as the upstream ones, which builds the same vnode mapping.
+                fragment
+                    .actors
+                    .iter()
+                    .map(|actor| locations.schedule_colocate_with(&actor.upstream_actor_id))
+                    .collect::<Result<Vec<_>>>()?
+            } else {
+                // Find out all the hash parallel units in the cluster. If the fragment has fewer
+                // actors than parallel units, only the first ones are used, so that every vnode is
+                // owned by an actor.
+                let mut parallel_units = self
+                    .cluster_manager
+                    .list_parallel_units(Some(ParallelUnitType::Hash))
+                    .await;
+                parallel_units.truncate(fragment.actors.len());
+                parallel_units
+            };
 
             // Build vnode mapping according to the parallel units.
             self.set_fragment_vnode_mapping(fragment, &parallel_units)?;
@@ -338,6 +356,7 @@ mod test {
                         upstream_actor_id: vec![],
                         same_worker_node_as_upstream: false,
                         vnode_bitmap: vec![],
+                        resource_group: String::new(),
                     }],
                     vnode_mapping: None,
                 };
@@ -367,6 +386,7 @@ mod test {
                         upstream_actor_id: vec![],
                         same_worker_node_as_upstream: false,
                         vnode_bitmap: vec![],
+                        resource_group: String::new(),
                     })
                     .collect_vec();
                 actor_id += node_count * 7;
@@ -448,4 +468,87 @@ mod test {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_schedule_colocated() -> Result<()> {
+        let env = MetaSrvEnv::for_test().await;
+        let cluster_manager =
+            Arc::new(ClusterManager::new(env.clone(), Duration::from_secs(3600)).await?);
+        for i in 0..2 {
+            let host = HostAddress {
+                host: "127.0.0.1".to_string(),
+                port: i as i32,
+            };
+            cluster_manager
+                .add_worker_node(host.clone(), WorkerType::ComputeNode)
+                .await?;
+            cluster_manager.activate_worker_node(host).await?;
+        }
+
+        let scheduler = Scheduler::new(cluster_manager, env.hash_mapping_manager_ref());
+        let mut locations = ScheduledLocations::new();
+
+        let make_actor =
+            |actor_id: u32, fragment_id: u32, upstream_actor_id: Vec<u32>| StreamActor {
+                actor_id,
+                fragment_id,
+                nodes: Some(StreamNode {
+                    node_body: Some(NodeBody::Materialize(MaterializeNode {
+                        table_ref_id: Some(TableRefId {
+                            table_id: fragment_id as i32,
+                            ..Default::default()
+                        }),
+                        ..Default::default()
+                    })),
+                    ..Default::default()
+                }),
+                dispatcher: vec![],
+                same_worker_node_as_upstream: !upstream_actor_id.is_empty(),
+                upstream_actor_id,
+                vnode_bitmap: vec![],
+                resource_group: String::new(),
+            };
+        let make_fragment = |fragment_id: u32, actors: Vec<StreamActor>| Fragment {
+            fragment_id,
+            fragment_type: 0,
+            distribution_type: FragmentDistributionType::Hash as i32,
+            actors,
+            vnode_mapping: None,
+        };
+
+        // The upstream fragment has fewer actors than parallel units, and each downstream actor
+        // is linked to an upstream actor by a local no-shuffle edge.
+        let mut upstream_fragment =
+            make_fragment(1, (1..4).map(|id| make_actor(id, 1, vec![])).collect());
+        let mut downstream_fragment = make_fragment(
+            2,
+            (4..7).map(|id| make_actor(id, 2, vec![id - 3])).collect(),
+        );
+        scheduler
+            .schedule(&mut upstream_fragment, &mut locations)
+            .await?;
+        scheduler
+            .schedule(&mut downstream_fragment, &mut locations)
+            .await?;
+
+        // The downstream actors are scheduled to the parallel units of their upstream actors, and
+        // own the same vnodes.
+        for (upstream_actor, downstream_actor) in upstream_fragment
+            .actors
+            .iter()
+            .zip_eq(&downstream_fragment.actors)
+        {
+            assert_eq!(
+                locations.actor_locations[&upstream_actor.actor_id],
+                locations.actor_locations[&downstream_actor.actor_id]
+            );
+            assert_eq!(upstream_actor.vnode_bitmap, downstream_actor.vnode_bitmap);
+        }
+        assert_eq!(
+            env.hash_mapping_manager().get_fragment_hash_mapping(&1),
+            env.hash_mapping_manager().get_fragment_hash_mapping(&2)
+        );
+
+        Ok(())
+    }
 }
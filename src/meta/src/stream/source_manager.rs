@@ -28,9 +28,13 @@ use risingwave_pb::catalog::source::Info;
 use risingwave_pb::catalog::{Source, StreamSourceInfo};
 use risingwave_pb::common::worker_node::State::Running;
 use risingwave_pb::common::WorkerType;
+use risingwave_pb::plan_common::ColumnDesc;
+use risingwave_pb::stream_service::validate_source_response::error::Kind as ValidationErrorKind;
 use risingwave_pb::stream_service::{
+    AddSourceColumnsRequest as ComputeNodeAddSourceColumnsRequest,
     CreateSourceRequest as ComputeNodeCreateSourceRequest,
     DropSourceRequest as ComputeNodeDropSourceRequest,
+    ValidateSourceRequest as ComputeNodeValidateSourceRequest,
 };
 use risingwave_rpc_client::StreamClient;
 use tokio::sync::{oneshot, Mutex};
@@ -358,8 +362,40 @@ where
         Ok(all_stream_clients)
     }
 
-    /// Broadcast the create source request to all compute nodes.
+    /// Asks a compute node to validate the source against its upstream, so that a misconfigured
+    /// source fails the DDL before any actor is built on it.
+    async fn validate_source(&self, source: &Source) -> Result<()> {
+        let Some(mut client) = self.all_stream_clients().await?.next() else {
+            return Ok(());
+        };
+        let request = ComputeNodeValidateSourceRequest {
+            source: Some(source.clone()),
+        };
+        let response = client
+            .validate_source(request)
+            .await
+            .to_rw_result()?
+            .into_inner();
+
+        if let Some(error) = response.error {
+            let reason = match error.kind() {
+                ValidationErrorKind::Unspecified => "invalid source",
+                ValidationErrorKind::InvalidProperties => "invalid properties",
+                ValidationErrorKind::UpstreamUnavailable => "upstream unavailable",
+                ValidationErrorKind::IncompatibleSchema => "incompatible schema",
+            };
+            return Err(RwError::from(ConnectorError(format!(
+                "failed to validate source {}, {}: {}",
+                source.name, reason, error.message
+            ))));
+        }
+        Ok(())
+    }
+
+    /// Validates the source and broadcasts the create source request to all compute nodes.
     pub async fn create_source(&self, source: &Source) -> Result<()> {
+        self.validate_source(source).await?;
+
         let futures = self
             .all_stream_clients()
             .await?
@@ -389,6 +425,29 @@ where
         Ok(())
     }
 
+    /// Appends `columns` to the source `source_id` on all compute nodes, for the rows read from
+    /// then on.
+    pub async fn add_source_columns(
+        &self,
+        source_id: SourceId,
+        columns: &[ColumnDesc],
+    ) -> Result<()> {
+        let futures = self
+            .all_stream_clients()
+            .await?
+            .into_iter()
+            .map(|mut client| {
+                let request = ComputeNodeAddSourceColumnsRequest {
+                    source_id,
+                    columns: columns.to_vec(),
+                };
+                async move { client.add_source_columns(request).await.to_rw_result() }
+            });
+        let _responses: Vec<_> = try_join_all(futures).await?;
+
+        Ok(())
+    }
+
     pub async fn run(&self) -> Result<()> {
         // todo: in the future, split change will be pushed as a long running service
         Ok(())
@@ -13,7 +13,7 @@
 // limitations under the License.
 
 use std::collections::hash_map::HashMap;
-use std::collections::{BTreeMap, VecDeque};
+use std::collections::{BTreeMap, HashSet, VecDeque};
 use std::ops::{Deref, Range};
 use std::sync::Arc;
 
@@ -21,8 +21,12 @@ use assert_matches::assert_matches;
 use itertools::Itertools;
 use risingwave_common::catalog::TableId;
 use risingwave_common::error::{ErrorCode, Result};
+use risingwave_pb::catalog::Table;
+use risingwave_pb::data::data_type::TypeName;
+use risingwave_pb::data::DataType;
 use risingwave_pb::meta::table_fragments::fragment::FragmentDistributionType;
 use risingwave_pb::meta::table_fragments::Fragment;
+use risingwave_pb::plan_common::{ColumnCatalog, ColumnDesc, Field};
 use risingwave_pb::stream_plan::lookup_node::ArrangementTableId;
 use risingwave_pb::stream_plan::stream_fragment_graph::{StreamFragment, StreamFragmentEdge};
 use risingwave_pb::stream_plan::stream_node::NodeBody;
@@ -337,10 +341,119 @@ impl StreamActorBuilder {
                     )| *same_worker_node,
                 ),
             vnode_bitmap: vec![],
+            // will be filled later by ddl service
+            resource_group: String::new(),
         }
     }
 }
 
+/// Builds the catalog of the internal table `table_id` of an executor of `kind`, with the columns
+/// stored as cells in it. The database, schema, owner and name are filled by the ddl service with
+/// the ones of the mview, and `kind` is used in the name of the table.
+///
+/// The columns encoded in the keys of the table, e.g., the group keys of an aggregation, are not
+/// included, as they can't be scanned from the table.
+fn internal_table(table_id: u32, kind: &str, columns: Vec<ColumnDesc>) -> Table {
+    Table {
+        id: table_id,
+        name: kind.to_string(),
+        columns: columns
+            .into_iter()
+            .map(|column_desc| ColumnCatalog {
+                column_desc: Some(column_desc),
+                is_hidden: false,
+                generated_expr: None,
+            })
+            .collect(),
+        is_internal: true,
+        ..Default::default()
+    }
+}
+
+/// The columns of the internal table of an aggregation, which stores the state of the aggregation
+/// call.
+fn agg_state_columns(return_type: &DataType) -> Vec<ColumnDesc> {
+    vec![ColumnDesc {
+        column_type: Some(return_type.clone()),
+        column_id: 0,
+        name: "state".to_string(),
+        ..Default::default()
+    }]
+}
+
+/// The columns of the internal table of a dedup executor, which stores the seen dedup keys prefixed
+/// by the generations they're seen in.
+fn dedup_state_columns(input_fields: &[Field], dedup_col_indices: &[u32]) -> Vec<ColumnDesc> {
+    let generation = ColumnDesc {
+        column_type: Some(DataType {
+            type_name: TypeName::Int64 as i32,
+            ..Default::default()
+        }),
+        column_id: 0,
+        name: "generation".to_string(),
+        ..Default::default()
+    };
+    let key_fields = dedup_col_indices
+        .iter()
+        .map(|idx| input_fields[*idx as usize].clone())
+        .collect_vec();
+    std::iter::once(generation)
+        .chain(input_columns(&key_fields).into_iter().map(|mut column| {
+            column.column_id += 1;
+            column
+        }))
+        .collect()
+}
+
+/// The columns of the internal table of a materialize executor, which stores the numbers of rows
+/// changed by each actor in each epoch and the estimated change of the size of the rows in bytes.
+fn materialize_stats_columns() -> Vec<ColumnDesc> {
+    [
+        ("epoch", TypeName::Int64),
+        ("actor_id", TypeName::Int32),
+        ("time", TypeName::Timestamp),
+        ("inserted", TypeName::Int64),
+        ("deleted", TypeName::Int64),
+        ("updated", TypeName::Int64),
+        ("size_delta", TypeName::Int64),
+    ]
+    .into_iter()
+    .enumerate()
+    .map(|(column_id, (name, type_name))| ColumnDesc {
+        column_type: Some(DataType {
+            type_name: type_name as i32,
+            ..Default::default()
+        }),
+        column_id: column_id as i32,
+        name: name.to_string(),
+        ..Default::default()
+    })
+    .collect()
+}
+
+/// The columns of an internal table storing the rows from the input of the executor. The
+/// duplicated names of the input fields are suffixed with the column ids.
+fn input_columns(input_fields: &[Field]) -> Vec<ColumnDesc> {
+    let mut names = HashSet::new();
+    input_fields
+        .iter()
+        .enumerate()
+        .map(|(column_id, field)| {
+            let name = if names.insert(field.name.as_str()) {
+                field.name.clone()
+            } else {
+                format!("{}_{}", field.name, column_id)
+            };
+            ColumnDesc {
+                column_type: field.data_type.clone(),
+                column_id: column_id as i32,
+                name,
+                ..Default::default()
+            }
+        })
+        .collect()
+}
+
 /// [`StreamGraphBuilder`] build a stream graph. It injects some information to achieve
 /// dependencies. See `build_inner` for more details.
 #[derive(Default)]
@@ -541,6 +654,11 @@ impl StreamGraphBuilder {
                 let mut new_stream_node = stream_node.clone();
 
                 // Table id rewrite done below.
+                let input_fields = stream_node
+                    .input
+                    .first()
+                    .map(|input| input.fields.as_slice())
+                    .unwrap_or_default();
                 match new_stream_node.node_body.as_mut().unwrap() {
                     NodeBody::HashJoin(node) => {
                         // The operator id must be assigned with table ids. Otherwise it is a logic
@@ -565,23 +683,113 @@ impl StreamGraphBuilder {
                     NodeBody::Arrange(node) => {
                         node.table_id += table_id_offset;
                         ctx.internal_table_id_set.insert(node.table_id);
+                        let columns = node.get_table_info()?.column_descs.clone();
+                        ctx.internal_tables.insert(
+                            node.table_id,
+                            internal_table(node.table_id, "arrange", columns),
+                        );
                     }
 
                     NodeBody::HashAgg(node) => {
                         assert_eq!(node.table_ids.len(), node.agg_calls.len());
                         // In-place update the table id. Convert from local to global.
-                        for table_id in &mut node.table_ids {
+                        for (table_id, agg_call) in
+                            node.table_ids.iter_mut().zip_eq(&node.agg_calls)
+                        {
                             *table_id += table_id_offset;
                             ctx.internal_table_id_set.insert(*table_id);
+                            ctx.internal_tables.insert(
+                                *table_id,
+                                internal_table(
+                                    *table_id,
+                                    "hashagg",
+                                    agg_state_columns(agg_call.get_return_type()?),
+                                ),
+                            );
                         }
                     }
 
                     NodeBody::TopN(node) | NodeBody::AppendOnlyTopN(node) => {
                         node.table_id += table_id_offset;
                         ctx.internal_table_id_set.insert(node.table_id);
+                        ctx.internal_tables.insert(
+                            node.table_id,
+                            internal_table(node.table_id, "topn", input_columns(input_fields)),
+                        );
+                    }
+
+                    NodeBody::EmitOnWindowClose(node) => {
+                        node.table_id += table_id_offset;
+                        ctx.internal_table_id_set.insert(node.table_id);
+                        ctx.internal_tables.insert(
+                            node.table_id,
+                            internal_table(
+                                node.table_id,
+                                "emitonwindowclose",
+                                input_columns(input_fields),
+                            ),
+                        );
+                    }
+
+                    NodeBody::TemporalFilter(node) => {
+                        node.table_id += table_id_offset;
+                        ctx.internal_table_id_set.insert(node.table_id);
+                        ctx.internal_tables.insert(
+                            node.table_id,
+                            internal_table(
+                                node.table_id,
+                                "temporalfilter",
+                                input_columns(input_fields),
+                            ),
+                        );
+                    }
+
+                    NodeBody::Dedup(node) => {
+                        node.table_id += table_id_offset;
+                        ctx.internal_table_id_set.insert(node.table_id);
+                        ctx.internal_tables.insert(
+                            node.table_id,
+                            internal_table(
+                                node.table_id,
+                                "dedup",
+                                dedup_state_columns(input_fields, &node.dedup_col_indices),
+                            ),
+                        );
                     }
 
-                    NodeBody::GlobalSimpleAgg(node) | NodeBody::LocalSimpleAgg(node) => {
+                    NodeBody::Materialize(node) if node.collect_stats => {
+                        node.stats_table_id += table_id_offset;
+                        ctx.internal_table_id_set.insert(node.stats_table_id);
+                        ctx.internal_tables.insert(
+                            node.stats_table_id,
+                            internal_table(
+                                node.stats_table_id,
+                                "stats",
+                                materialize_stats_columns(),
+                            ),
+                        );
+                    }
+
+                    NodeBody::GlobalSimpleAgg(node) => {
+                        assert_eq!(node.table_ids.len(), node.agg_calls.len());
+                        // In-place update the table id. Convert from local to global.
+                        for (table_id, agg_call) in
+                            node.table_ids.iter_mut().zip_eq(&node.agg_calls)
+                        {
+                            *table_id += table_id_offset;
+                            ctx.internal_table_id_set.insert(*table_id);
+                            ctx.internal_tables.insert(
+                                *table_id,
+                                internal_table(
+                                    *table_id,
+                                    "simpleagg",
+                                    agg_state_columns(agg_call.get_return_type()?),
+                                ),
+                            );
+                        }
+                    }
+
+                    NodeBody::LocalSimpleAgg(node) => {
                         assert_eq!(node.table_ids.len(), node.agg_calls.len());
                         // In-place update the table id. Convert from local to global.
                         for table_id in &mut node.table_ids {
@@ -885,6 +1093,8 @@ impl ActorGraphBuilder {
                 .expect("downstream fragment not processed yet");
 
             let dispatch_strategy = dispatch_edge.dispatch_strategy.as_ref().unwrap();
+            let same_worker_node =
+                dispatch_edge.same_worker_node || Self::is_colocated(fragment_graph, dispatch_edge);
             match dispatch_strategy.get_type()? {
                 DispatcherType::Hash
                 | DispatcherType::Simple
@@ -895,7 +1105,7 @@ impl ActorGraphBuilder {
                         downstream_actors,
                         dispatch_edge.link_id,
                         dispatch_strategy.clone(),
-                        dispatch_edge.same_worker_node,
+                        same_worker_node,
                     );
                 }
                 DispatcherType::Invalid => unreachable!(),
@@ -913,6 +1123,31 @@ impl ActorGraphBuilder {
 
         Ok(())
     }
+
+    /// Whether the fragments linked by `edge` have an identical distribution, i.e., the edge is
+    /// no-shuffle and both fragments have the same parallelism. If so, the edge is marked as local,
+    /// so that the downstream actors are scheduled to the same parallel units as their upstream
+    /// actors, and the actors exchange data through in-process channels.
+    ///
+    /// The downstream fragment can be colocated with only one upstream fragment, so it must have no
+    /// other upstream fragment or chain.
+    fn is_colocated(fragment_graph: &StreamFragmentGraph, edge: &StreamFragmentEdge) -> bool {
+        fn has_chain(stream_node: &StreamNode) -> bool {
+            matches!(stream_node.node_body, Some(NodeBody::Chain(_)))
+                || stream_node.input.iter().any(has_chain)
+        }
+
+        if edge.dispatch_strategy.as_ref().unwrap().r#type != DispatcherType::NoShuffle as i32 {
+            return false;
+        }
+        let upstream_id = GlobalFragmentId(edge.upstream_id);
+        let downstream_id = GlobalFragmentId(edge.downstream_id);
+        let upstream = fragment_graph.get_fragment(upstream_id).unwrap();
+        let downstream = fragment_graph.get_fragment(downstream_id).unwrap();
+        upstream.is_singleton == downstream.is_singleton
+            && fragment_graph.get_upstreams(downstream_id).len() == 1
+            && !has_chain(downstream.node.as_ref().unwrap())
+    }
 }
 
 #[derive(Default)]
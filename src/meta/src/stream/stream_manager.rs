@@ -16,14 +16,20 @@ use std::collections::{BTreeMap, HashMap, HashSet};
 use std::sync::Arc;
 use std::time::Instant;
 
+use futures::FutureExt;
 use itertools::Itertools;
-use log::{debug, info};
+use log::debug;
 use risingwave_common::catalog::TableId;
-use risingwave_common::error::{internal_error, Result, ToRwResult};
+use risingwave_common::error::{internal_error, Result, RwError, ToRwResult};
 use risingwave_common::hash::VIRTUAL_NODE_COUNT;
 use risingwave_common::util::compress::compress_data;
-use risingwave_pb::catalog::Source;
-use risingwave_pb::common::{ActorInfo, ParallelUnitMapping, WorkerType};
+use risingwave_hummock_sdk::compaction_group::{Prefix, StaticCompactionGroupId};
+use risingwave_hummock_sdk::HummockEpoch;
+use risingwave_pb::catalog::{Source, Table};
+use risingwave_pb::common::{ActorInfo, HostAddress, ParallelUnitMapping, WorkerType};
+use risingwave_pb::data::AddedColumn;
+use risingwave_pb::hummock::TableOption;
+use risingwave_pb::meta::event_log::EventType;
 use risingwave_pb::meta::table_fragments::{ActorState, ActorStatus};
 use risingwave_pb::stream_plan::stream_node::NodeBody;
 use risingwave_pb::stream_plan::{ActorMapping, DispatcherType, StreamNode, StreamSourceState};
@@ -36,10 +42,11 @@ use uuid::Uuid;
 use super::ScheduledLocations;
 use crate::barrier::{BarrierManagerRef, Command};
 use crate::cluster::{ClusterManagerRef, ParallelUnitId, WorkerId};
-use crate::manager::{HashMappingManagerRef, MetaSrvEnv};
-use crate::model::{ActorId, DispatcherId, TableFragments};
+use crate::hummock::compaction_group::manager::CompactionGroupManagerRef;
+use crate::manager::{EventLogManagerRef, HashMappingManagerRef, MetaSrvEnv, SourceId};
+use crate::model::{ActorId, DispatcherId, FragmentId, TableFragments};
 use crate::storage::MetaStore;
-use crate::stream::{FragmentManagerRef, Scheduler, SourceManagerRef};
+use crate::stream::{join_node_rpcs, FragmentManagerRef, Scheduler, SourceManagerRef};
 
 pub type GlobalStreamManagerRef<S> = Arc<GlobalStreamManager<S>>;
 
@@ -60,6 +67,10 @@ pub struct CreateMaterializedViewContext {
     pub table_id_offset: u32,
     /// Internal TableID for MaterializedView.
     pub internal_table_id_set: HashSet<u32>,
+    /// Catalogs of the internal tables whose states are scannable, indexed by their ids.
+    pub internal_tables: HashMap<u32, Table>,
+    /// Storage options of the table of the materialized view, e.g. its retention.
+    pub table_option: TableOption,
 }
 
 /// `GlobalStreamManager` manages all the streams in the system.
@@ -84,6 +95,12 @@ pub struct GlobalStreamManager<S: MetaStore> {
 
     /// Client Pool to stream service on compute nodes
     client_pool: StreamClientPoolRef,
+
+    /// Assigns the state of created tables to compaction groups
+    compaction_group_manager: CompactionGroupManagerRef<S>,
+
+    /// Records the scaling events
+    event_log_manager: EventLogManagerRef<S>,
 }
 
 impl<S> GlobalStreamManager<S>
@@ -96,6 +113,7 @@ where
         barrier_manager: BarrierManagerRef<S>,
         cluster_manager: ClusterManagerRef<S>,
         source_manager: SourceManagerRef<S>,
+        compaction_group_manager: CompactionGroupManagerRef<S>,
     ) -> Result<Self> {
         Ok(Self {
             scheduler: Scheduler::new(cluster_manager.clone(), env.hash_mapping_manager_ref()),
@@ -105,6 +123,8 @@ where
             source_manager,
             hash_mapping_manager: env.hash_mapping_manager_ref(),
             client_pool: env.stream_client_pool_ref(),
+            compaction_group_manager,
+            event_log_manager: env.event_log_manager_ref(),
         })
     }
 
@@ -140,7 +160,11 @@ where
                 let Some(NodeBody::Chain(ref mut chain)) = stream_node.node_body else {
                     // If node is not chain node, recursively deal with input nodes
                     for input in &mut stream_node.input {
-                        self.resolve_chain_node_inner(input, actor_id, same_worker_node_as_upstream)?;
+                        self.resolve_chain_node_inner(
+                            input,
+                            actor_id,
+                            same_worker_node_as_upstream,
+                        )?;
                     }
                     return Ok(());
                 };
@@ -295,7 +319,9 @@ where
             dependent_table_ids,
             affiliated_source,
             table_id_offset: _,
-            internal_table_id_set: _,
+            internal_table_id_set,
+            internal_tables: _,
+            table_option,
         }: CreateMaterializedViewContext,
     ) -> Result<()> {
         let nodes = self
@@ -472,10 +498,10 @@ where
                 }
 
                 if let Some(NodeBody::Source(s)) = node.node_body.as_mut() {
-                    log::debug!(
-                        "patching source node #{} with splits {:?}",
-                        actor_id,
-                        splits
+                    tracing::debug!(
+                        actor_id = *actor_id,
+                        ?splits,
+                        "patching source node with splits"
                     );
 
                     if !splits.is_empty() {
@@ -560,78 +586,110 @@ where
         // The first stage does 2 things: broadcast actor info, and send local actor ids to
         // different WorkerNodes. Such that each WorkerNode knows the overall actor
         // allocation, but not actually builds it. We initialize all channels in this stage.
+        // Requests to different WorkerNodes are sent concurrently in each stage.
+        let mut update_futures = Vec::with_capacity(node_actors.len());
         for (node_id, actors) in &node_actors {
             let node = locations.node_locations.get(node_id).unwrap();
-
-            let client = self.client_pool.get(node).await?;
-
-            client
-                .to_owned()
-                .broadcast_actor_info_table(BroadcastActorInfoTableRequest {
-                    info: actor_infos_to_broadcast.clone(),
-                })
-                .await
-                .to_rw_result_with(|| format!("failed to connect to {}", node_id))?;
-
+            let broadcast_request = BroadcastActorInfoTableRequest {
+                info: actor_infos_to_broadcast.clone(),
+            };
             let stream_actors = actors
                 .iter()
                 .map(|actor_id| actor_map.get(actor_id).cloned().unwrap())
                 .collect::<Vec<_>>();
 
             let request_id = Uuid::new_v4().to_string();
-            tracing::debug!(request_id = request_id.as_str(), actors = ?actors, "update actors");
-            client
-                .to_owned()
-                .update_actors(UpdateActorsRequest {
-                    request_id,
-                    actors: stream_actors.clone(),
-                    hanging_channels: node_hanging_channels.remove(node_id).unwrap_or_default(),
-                })
-                .await
-                .to_rw_result_with(|| format!("failed to connect to {}", node_id))?;
+            tracing::debug!(request_id = request_id.as_str(), actor_ids = ?actors, "update actors");
+            let update_request = UpdateActorsRequest {
+                request_id,
+                actors: stream_actors,
+                hanging_channels: node_hanging_channels.remove(node_id).unwrap_or_default(),
+            };
+            let future = async move {
+                let mut client = self.client_pool.get(node).await?;
+                client
+                    .broadcast_actor_info_table(broadcast_request)
+                    .await
+                    .to_rw_result_with(|| format!("failed to connect to {}", node_id))?;
+                client
+                    .update_actors(update_request)
+                    .await
+                    .to_rw_result_with(|| format!("failed to connect to {}", node_id))?;
+                Ok::<_, RwError>(())
+            }
+            .boxed();
+            update_futures.push((*node_id, future));
         }
-
         for (node_id, hanging_channels) in node_hanging_channels {
             let node = locations.node_locations.get(&node_id).unwrap();
-
-            let client = self.client_pool.get(node).await?;
-            let request_id = Uuid::new_v4().to_string();
-
-            client
-                .to_owned()
-                .update_actors(UpdateActorsRequest {
-                    request_id,
-                    actors: vec![],
-                    hanging_channels,
-                })
-                .await
-                .to_rw_result_with(|| format!("failed to connect to {}", node_id))?;
+            let request = UpdateActorsRequest {
+                request_id: Uuid::new_v4().to_string(),
+                actors: vec![],
+                hanging_channels,
+            };
+            let future = async move {
+                let mut client = self.client_pool.get(node).await?;
+                client
+                    .update_actors(request)
+                    .await
+                    .to_rw_result_with(|| format!("failed to connect to {}", node_id))?;
+                Ok::<_, RwError>(())
+            }
+            .boxed();
+            update_futures.push((node_id, future));
         }
+        join_node_rpcs("update_actors", update_futures).await?;
 
         // In the second stage, each [`WorkerNode`] builds local actors and connect them with
         // channels.
-        for (node_id, actors) in node_actors {
+        let build_futures = node_actors.into_iter().map(|(node_id, actors)| {
             let node = locations.node_locations.get(&node_id).unwrap();
-
-            let client = self.client_pool.get(node).await?;
-
             let request_id = Uuid::new_v4().to_string();
-            tracing::debug!(request_id = request_id.as_str(), actors = ?actors, "build actors");
-            client
-                .to_owned()
-                .build_actors(BuildActorsRequest {
-                    request_id,
-                    actor_id: actors,
-                })
-                .await
-                .to_rw_result_with(|| format!("failed to connect to {}", node_id))?;
-        }
+            tracing::debug!(request_id = request_id.as_str(), actor_ids = ?actors, "build actors");
+            let future = async move {
+                let mut client = self.client_pool.get(node).await?;
+                client
+                    .build_actors(BuildActorsRequest {
+                        request_id,
+                        actor_id: actors,
+                    })
+                    .await
+                    .to_rw_result_with(|| format!("failed to connect to {}", node_id))?;
+                Ok::<_, RwError>(())
+            };
+            (node_id, future)
+        });
+        join_node_rpcs("build_actors", build_futures).await?;
 
         // Add table fragments to meta store with state: `State::Creating`.
         self.fragment_manager
             .start_create_table_fragments(table_fragments.clone())
             .await?;
         let table_id = table_fragments.table_id();
+        // Register the tables to compaction groups before any state of them is written.
+        let table_ids_to_register = std::iter::once((
+            table_id.table_id().into(),
+            StaticCompactionGroupId::MaterializedView.into(),
+            table_option,
+        ))
+        .chain(internal_table_id_set.iter().map(|table_id| {
+            (
+                (*table_id).into(),
+                StaticCompactionGroupId::StateDefault.into(),
+                TableOption::default(),
+            )
+        }))
+        .collect_vec();
+        if let Err(err) = self
+            .compaction_group_manager
+            .register_table_ids(&table_ids_to_register)
+            .await
+        {
+            self.fragment_manager
+                .cancel_create_table_fragments(&table_id)
+                .await?;
+            return Err(err.into());
+        }
         if let Err(err) = self
             .barrier_manager
             .run_command(Command::CreateMaterializedView {
@@ -644,6 +702,14 @@ where
             self.fragment_manager
                 .cancel_create_table_fragments(&table_id)
                 .await?;
+            self.compaction_group_manager
+                .unregister_table_ids(
+                    &table_ids_to_register
+                        .iter()
+                        .map(|(prefix, _, _)| *prefix)
+                        .collect_vec(),
+                )
+                .await?;
             return Err(err);
         }
 
@@ -653,25 +719,172 @@ where
     /// Dropping materialized view is done by barrier manager. Check
     /// [`Command::DropMaterializedView`] for details.
     pub async fn drop_materialized_view(&self, table_id: &TableId) -> Result<()> {
+        let table_fragments = self
+            .fragment_manager
+            .select_table_fragments_by_table_id(table_id)
+            .await?;
         self.barrier_manager
             .run_command(Command::DropMaterializedView(*table_id))
             .await?;
+        let table_ids_to_unregister = std::iter::once(table_id.table_id())
+            .chain(table_fragments.internal_table_ids())
+            .map(Prefix::from)
+            .collect_vec();
+        self.compaction_group_manager
+            .unregister_table_ids(&table_ids_to_unregister)
+            .await?;
+        Ok(())
+    }
+
+    /// Reassigns the vnodes of the fragments in `vnode_mappings` to their actors, which is done
+    /// by barrier manager. Check [`Command::RescheduleVnodes`] for details.
+    pub async fn reschedule_vnodes(
+        &self,
+        table_id: &TableId,
+        vnode_mappings: BTreeMap<FragmentId, Vec<ActorId>>,
+    ) -> Result<()> {
+        self.barrier_manager
+            .run_command(Command::RescheduleVnodes {
+                table_id: *table_id,
+                vnode_mappings: vnode_mappings.clone(),
+            })
+            .await?;
+        for (fragment_id, vnode_mapping) in &vnode_mappings {
+            self.event_log_manager
+                .log(
+                    EventType::Scaling,
+                    format!(
+                        "reschedule vnodes of fragment {} of table {} to actors {:?}",
+                        fragment_id,
+                        table_id,
+                        vnode_mapping.iter().unique().sorted().collect_vec()
+                    ),
+                )
+                .await;
+        }
+        Ok(())
+    }
+
+    /// Appends `columns` to the source `source_id` and the materialized view `table_id` of a
+    /// table, which is done by barrier manager. Check [`Command::AddColumns`] for details.
+    pub async fn add_columns(
+        &self,
+        source_id: SourceId,
+        table_id: &TableId,
+        columns: Vec<AddedColumn>,
+    ) -> Result<()> {
+        self.barrier_manager
+            .run_command(Command::AddColumns {
+                source_id,
+                table_id: *table_id,
+                columns,
+            })
+            .await?;
+        Ok(())
+    }
+
+    /// Spreads the vnodes of each fragment in `actor_ids` evenly over the actors, moving as few
+    /// vnodes as possible so that the caches of the actors survive scaling. See
+    /// [`TableFragments::sticky_vnode_mapping`].
+    pub async fn rebalance_vnodes(
+        &self,
+        table_id: &TableId,
+        actor_ids: &BTreeMap<FragmentId, Vec<ActorId>>,
+    ) -> Result<()> {
+        let table_fragments = self
+            .fragment_manager
+            .select_table_fragments_by_table_id(table_id)
+            .await?;
+        let vnode_mappings = actor_ids
+            .iter()
+            .map(|(fragment_id, actor_ids)| {
+                Ok((
+                    *fragment_id,
+                    table_fragments.sticky_vnode_mapping(*fragment_id, actor_ids)?,
+                ))
+            })
+            .collect::<Result<_>>()?;
+        self.reschedule_vnodes(table_id, vnode_mappings).await
+    }
+
+    /// Changes the parallelism of table `table_id`, by rebalancing the vnodes of each
+    /// hash-distributed fragment over the first `parallelism` actors. The other actors are left
+    /// idle rather than dropped, so the parallelism can't exceed the number of actors the table
+    /// was created with. See [`TableFragments::actors_at_parallelism`].
+    pub async fn alter_parallelism(&self, table_id: &TableId, parallelism: u32) -> Result<()> {
+        let actor_ids = self
+            .fragment_manager
+            .select_table_fragments_by_table_id(table_id)
+            .await?
+            .actors_at_parallelism(parallelism)?;
+        // All fragments switch at the same barrier, which keeps the actors paired by no-shuffle
+        // exchanges owning the same vnodes.
+        self.rebalance_vnodes(table_id, &actor_ids).await?;
+        self.fragment_manager
+            .set_parallelism(table_id, parallelism)
+            .await
+    }
+
+    /// Stops all sources from reading new data, until [`Self::resume`] is called. Barriers still
+    /// flow through the streaming graph, so that checkpoints keep going on. Note that the sources
+    /// are resumed on recovery.
+    pub async fn pause(&self) -> Result<()> {
+        self.barrier_manager.run_command(Command::pause()).await?;
+        self.event_log_manager
+            .log(EventType::Pause, "pause all sources")
+            .await;
         Ok(())
     }
 
-    /// Flush means waiting for the next barrier to collect.
-    pub async fn flush(&self) -> Result<()> {
+    /// Resumes the sources stopped by [`Self::pause`].
+    pub async fn resume(&self) -> Result<()> {
+        self.barrier_manager.run_command(Command::resume()).await?;
+        self.event_log_manager
+            .log(EventType::Resume, "resume all sources")
+            .await;
+        Ok(())
+    }
+
+    /// Triggers a recovery of the cluster.
+    pub fn trigger_recovery(&self) -> Result<()> {
+        self.barrier_manager.trigger_recovery()
+    }
+
+    /// Prepares the worker at `host` to leave the cluster. Actors can't be migrated to other
+    /// workers yet, so all sources are paused and the collected pause barrier makes a final
+    /// checkpoint, after which no data is lost when the worker exits. The sources are resumed by
+    /// the recovery after the worker is gone.
+    pub async fn drain_worker(&self, host: HostAddress) -> Result<()> {
+        self.event_log_manager
+            .log(
+                EventType::WorkerLeave,
+                format!("drain worker {}:{}", host.host, host.port),
+            )
+            .await;
+        self.pause().await
+    }
+
+    /// Flush means waiting for the next barrier to collect. Returns the epoch committed to Hummock
+    /// by the barrier, after which all the data written before the flush are visible.
+    pub async fn flush(&self) -> Result<HummockEpoch> {
         let start = Instant::now();
 
         debug!("start barrier flush");
-        self.barrier_manager
+        let committed_epoch = self
+            .barrier_manager
             .wait_for_next_barrier_to_collect()
             .await?;
 
         let elapsed = Instant::now().duration_since(start);
-        info!("barrier flushed in {:?}", elapsed);
+        tracing::info!(epoch = committed_epoch, "barrier flushed in {:?}", elapsed);
 
-        Ok(())
+        Ok(committed_epoch)
+    }
+
+    /// Returns the epoch of the data being written into the stream graph. See
+    /// [`crate::barrier::GlobalBarrierManager::current_epoch`].
+    pub fn current_epoch(&self) -> HummockEpoch {
+        self.barrier_manager.current_epoch()
     }
 }
 
@@ -704,6 +917,7 @@ mod tests {
     use super::*;
     use crate::barrier::GlobalBarrierManager;
     use crate::cluster::ClusterManager;
+    use crate::hummock::compaction_group::manager::CompactionGroupManager;
     use crate::hummock::HummockManager;
     use crate::manager::{CatalogManager, MetaSrvEnv};
     use crate::model::ActorId;
@@ -791,6 +1005,13 @@ mod tests {
             unimplemented!()
         }
 
+        async fn validate_source(
+            &self,
+            _request: Request<ValidateSourceRequest>,
+        ) -> std::result::Result<Response<ValidateSourceResponse>, Status> {
+            Ok(Response::new(ValidateSourceResponse::default()))
+        }
+
         async fn drop_source(
             &self,
             _request: Request<DropSourceRequest>,
@@ -798,6 +1019,13 @@ mod tests {
             unimplemented!()
         }
 
+        async fn add_source_columns(
+            &self,
+            _request: Request<AddSourceColumnsRequest>,
+        ) -> std::result::Result<Response<AddSourceColumnsResponse>, Status> {
+            unimplemented!()
+        }
+
         async fn force_stop_actors(
             &self,
             _request: Request<ForceStopActorsRequest>,
@@ -811,6 +1039,20 @@ mod tests {
         ) -> std::result::Result<Response<SyncSourcesResponse>, Status> {
             Ok(Response::new(SyncSourcesResponse::default()))
         }
+
+        async fn get_backpressure(
+            &self,
+            _request: Request<GetBackpressureRequest>,
+        ) -> std::result::Result<Response<GetBackpressureResponse>, Status> {
+            Ok(Response::new(GetBackpressureResponse::default()))
+        }
+
+        async fn profile_actors(
+            &self,
+            _request: Request<ProfileActorsRequest>,
+        ) -> std::result::Result<Response<ProfileActorsResponse>, Status> {
+            Ok(Response::new(ProfileActorsResponse::default()))
+        }
     }
 
     struct MockServices {
@@ -859,9 +1101,16 @@ mod tests {
             let catalog_manager = Arc::new(CatalogManager::new(env.clone()).await?);
             let fragment_manager = Arc::new(FragmentManager::new(env.clone()).await?);
             let meta_metrics = Arc::new(MetaMetrics::new());
+            let compaction_group_manager =
+                Arc::new(CompactionGroupManager::new(env.clone()).await?);
             let hummock_manager = Arc::new(
-                HummockManager::new(env.clone(), cluster_manager.clone(), meta_metrics.clone())
-                    .await?,
+                HummockManager::new(
+                    env.clone(),
+                    cluster_manager.clone(),
+                    meta_metrics.clone(),
+                    compaction_group_manager.clone(),
+                )
+                .await?,
             );
             let barrier_manager = Arc::new(GlobalBarrierManager::new(
                 env.clone(),
@@ -889,6 +1138,7 @@ mod tests {
                 barrier_manager.clone(),
                 cluster_manager.clone(),
                 source_manager.clone(),
+                compaction_group_manager,
             )
             .await?;
 
@@ -245,6 +245,9 @@ fn make_stream_node() -> StreamNode {
             column_ids: vec![0_i32, 1_i32],
             column_orders: vec![make_column_order(1), make_column_order(2)],
             distribution_keys: Default::default(),
+            handle_pk_conflict: false,
+            collect_stats: false,
+            stats_table_id: 0,
         })),
         fields: vec![], // TODO: fill this later
         operator_id: 7,
@@ -288,6 +291,15 @@ async fn test_fragmenter() -> Result<()> {
     let expected_internal_table_ids = vec![0, 1, 2, 3];
     assert_eq!(expected_internal_table_ids, internal_table_ids);
 
+    // The states of the simple aggregations can be scanned, so all the internal tables have
+    // catalogs.
+    for table_id in &internal_table_ids {
+        let table = ctx.internal_tables.get(table_id).unwrap();
+        assert_eq!(table.name, "simpleagg");
+        assert!(table.is_internal);
+        assert_eq!(table.columns.len(), 1);
+    }
+
     let mut expected_downstream = HashMap::new();
     expected_downstream.insert(1, vec![]);
     expected_downstream.insert(2, vec![1]);
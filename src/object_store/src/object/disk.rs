@@ -253,6 +253,44 @@ impl ObjectStore for LocalDiskObjectStore {
             .map_err(|e| ObjectError::disk(format!("failed to delete {}", path), e))?;
         Ok(())
     }
+
+    async fn list(&self, prefix: &str) -> ObjectResult<Vec<String>> {
+        let prefix = strip_path_local(prefix, self.is_local).to_string();
+        let root = PathBuf::from(&self.path_prefix);
+        utils::asyncify(move || {
+            let mut paths = vec![];
+            let mut dirs = vec![root.clone()];
+            while let Some(dir) = dirs.pop() {
+                let entries = match std::fs::read_dir(&dir) {
+                    Ok(entries) => entries,
+                    // Nothing has been uploaded yet.
+                    Err(e) if e.kind() == ErrorKind::NotFound => continue,
+                    Err(e) => {
+                        return Err(ObjectError::disk(format!("failed to list {:?}", dir), e))
+                    }
+                };
+                for entry in entries {
+                    let path = entry
+                        .map_err(|e| ObjectError::disk(format!("failed to list {:?}", dir), e))?
+                        .path();
+                    if path.is_dir() {
+                        dirs.push(path);
+                        continue;
+                    }
+                    let path = path
+                        .strip_prefix(&root)
+                        .unwrap()
+                        .to_string_lossy()
+                        .to_string();
+                    if path.starts_with(&prefix) {
+                        paths.push(path);
+                    }
+                }
+            }
+            Ok(paths)
+        })
+        .await
+    }
 }
 
 #[cfg(test)]
@@ -480,4 +518,19 @@ mod tests {
             .await
             .is_err());
     }
+
+    #[tokio::test]
+    async fn test_list() {
+        let test_dir = TempDir::new().unwrap();
+        let test_root_path = test_dir.path().to_str().unwrap();
+        let store = LocalDiskObjectStore::new(test_root_path, false);
+        for path in ["a/1.obj", "a/b/2.obj", "c.obj"] {
+            store.upload(path, Bytes::from("123")).await.unwrap();
+        }
+        let mut paths = store.list("a/").await.unwrap();
+        paths.sort();
+        assert_eq!(paths, vec!["a/1.obj", "a/b/2.obj"]);
+        assert_eq!(store.list("").await.unwrap().len(), 3);
+        assert!(store.list("d").await.unwrap().is_empty());
+    }
 }
@@ -0,0 +1,92 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use bytes::Bytes;
+use risingwave_common::util::failure_injection::{FailureInjectorRef, Injection};
+
+use crate::object::{BlockLocation, ObjectError, ObjectMetadata, ObjectResult, ObjectStore};
+
+/// Injects failures into the operations of an [`ObjectStore`], with the operations named like
+/// `object_store.upload`. See [`risingwave_common::util::failure_injection`] for the rules.
+pub struct FailureInjectedObjectStore {
+    inner: Box<dyn ObjectStore>,
+    failure_injector: FailureInjectorRef,
+}
+
+impl FailureInjectedObjectStore {
+    pub fn new(inner: Box<dyn ObjectStore>, failure_injector: FailureInjectorRef) -> Self {
+        Self {
+            inner,
+            failure_injector,
+        }
+    }
+
+    /// Decides the failures to inject into `operation`, and applies the delay and the drop.
+    async fn inject(&self, operation: &str) -> ObjectResult<Injection> {
+        let injection = self.failure_injector.decide(operation);
+        injection.delay().await;
+        if injection.drop {
+            return Err(ObjectError::internal(format!(
+                "injected failure: {} is dropped",
+                operation
+            )));
+        }
+        Ok(injection)
+    }
+}
+
+#[async_trait::async_trait]
+impl ObjectStore for FailureInjectedObjectStore {
+    async fn upload(&self, path: &str, obj: Bytes) -> ObjectResult<()> {
+        if self.inject("object_store.upload").await?.duplicate {
+            self.inner.upload(path, obj.clone()).await?;
+        }
+        self.inner.upload(path, obj).await
+    }
+
+    async fn read(&self, path: &str, block_loc: Option<BlockLocation>) -> ObjectResult<Bytes> {
+        if self.inject("object_store.read").await?.duplicate {
+            self.inner.read(path, block_loc).await?;
+        }
+        self.inner.read(path, block_loc).await
+    }
+
+    async fn readv(&self, path: &str, block_locs: &[BlockLocation]) -> ObjectResult<Vec<Bytes>> {
+        if self.inject("object_store.readv").await?.duplicate {
+            self.inner.readv(path, block_locs).await?;
+        }
+        self.inner.readv(path, block_locs).await
+    }
+
+    async fn metadata(&self, path: &str) -> ObjectResult<ObjectMetadata> {
+        if self.inject("object_store.metadata").await?.duplicate {
+            self.inner.metadata(path).await?;
+        }
+        self.inner.metadata(path).await
+    }
+
+    async fn delete(&self, path: &str) -> ObjectResult<()> {
+        if self.inject("object_store.delete").await?.duplicate {
+            self.inner.delete(path).await?;
+        }
+        self.inner.delete(path).await
+    }
+
+    async fn list(&self, prefix: &str) -> ObjectResult<Vec<String>> {
+        if self.inject("object_store.list").await?.duplicate {
+            self.inner.list(prefix).await?;
+        }
+        self.inner.list(prefix).await
+    }
+}
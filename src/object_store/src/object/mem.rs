@@ -80,6 +80,18 @@ impl ObjectStore for InMemObjectStore {
         self.objects.lock().await.remove(path);
         Ok(())
     }
+
+    async fn list(&self, prefix: &str) -> ObjectResult<Vec<String>> {
+        let prefix = strip_path_local(prefix, self.is_local);
+        Ok(self
+            .objects
+            .lock()
+            .await
+            .keys()
+            .filter(|path| path.starts_with(prefix))
+            .cloned()
+            .collect())
+    }
 }
 
 impl InMemObjectStore {
@@ -158,4 +170,15 @@ mod tests {
         let metadata = obj_store.metadata("/abc").await.unwrap();
         assert_eq!(metadata.total_size, 6);
     }
+
+    #[tokio::test]
+    async fn test_list() {
+        let obj_store = InMemObjectStore::new(false);
+        for path in ["/a/1", "/a/2", "/b/1"] {
+            obj_store.upload(path, Bytes::from("123")).await.unwrap();
+        }
+        let mut paths = obj_store.list("/a/").await.unwrap();
+        paths.sort();
+        assert_eq!(paths, vec!["/a/1", "/a/2"]);
+    }
 }
@@ -24,12 +24,15 @@ pub use s3::*;
 
 mod disk;
 pub mod error;
+pub mod failure_injection;
 pub mod object_metrics;
 
 pub use error::*;
 use object_metrics::ObjectStoreMetrics;
+use risingwave_common::util::failure_injection::FailureInjector;
 
 use crate::object::disk::LocalDiskObjectStore;
+use crate::object::failure_injection::FailureInjectedObjectStore;
 
 pub const LOCAL_OBJECT_STORE_PATH_PREFIX: &str = "@local:";
 
@@ -93,6 +96,9 @@ pub trait ObjectStore: Send + Sync {
 
     /// Deletes blob permanently.
     async fn delete(&self, path: &str) -> ObjectResult<()>;
+
+    /// Lists the paths of the objects whose paths start with `prefix`, in no particular order.
+    async fn list(&self, prefix: &str) -> ObjectResult<Vec<String>>;
 }
 
 pub struct HybridObjectStore {
@@ -155,6 +161,14 @@ impl ObjectStore for HybridObjectStore {
             self.remote.delete(path).await
         }
     }
+
+    async fn list(&self, prefix: &str) -> ObjectResult<Vec<String>> {
+        if is_local_path(prefix) {
+            self.local.list(prefix).await
+        } else {
+            self.remote.list(prefix).await
+        }
+    }
 }
 
 pub type ObjectStoreRef = Arc<ObjectStoreImpl>;
@@ -241,10 +255,21 @@ impl ObjectStoreImpl {
             .start_timer();
         self.inner.delete(path).await
     }
+
+    pub async fn list(&self, prefix: &str) -> ObjectResult<Vec<String>> {
+        let _timer = self
+            .object_store_metrics
+            .operation_latency
+            .with_label_values(&["list"])
+            .start_timer();
+        self.inner.list(prefix).await
+    }
 }
 
+/// Creates the object store of `url`. Failures are injected into its operations if
+/// `RW_FAILURE_INJECTION` is set.
 pub async fn parse_object_store(url: &str, is_local: bool) -> Box<dyn ObjectStore> {
-    match url {
+    let store: Box<dyn ObjectStore> = match url {
         s3 if s3.starts_with("s3://") => {
             assert!(!is_local, "s3 cannot be used as local object store");
             Box::new(S3ObjectStore::new(s3.strip_prefix("s3://").unwrap().to_string()).await)
@@ -277,5 +302,11 @@ pub async fn parse_object_store(url: &str, is_local: bool) -> Box<dyn ObjectStor
                 other
             )
         }
+    };
+    match FailureInjector::from_env() {
+        Some(failure_injector) => {
+            Box::new(FailureInjectedObjectStore::new(store, failure_injector))
+        }
+        None => store,
     }
 }
@@ -114,6 +114,35 @@ impl ObjectStore for S3ObjectStore {
             .await?;
         Ok(())
     }
+
+    async fn list(&self, prefix: &str) -> ObjectResult<Vec<String>> {
+        fail_point!("s3_list_err", |_| Err(ObjectError::internal(
+            "s3 list error"
+        )));
+        let mut paths = vec![];
+        let mut continuation_token = None;
+        loop {
+            let resp = self
+                .client
+                .list_objects_v2()
+                .bucket(&self.bucket)
+                .prefix(prefix)
+                .set_continuation_token(continuation_token)
+                .send()
+                .await?;
+            paths.extend(
+                resp.contents()
+                    .unwrap_or_default()
+                    .iter()
+                    .filter_map(|object| object.key().map(str::to_string)),
+            );
+            // The keys are listed by pages of at most 1000 keys.
+            match resp.next_continuation_token() {
+                Some(token) => continuation_token = Some(token.to_string()),
+                None => return Ok(paths),
+            }
+        }
+    }
 }
 
 impl S3ObjectStore {
@@ -33,6 +33,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         "stream_service",
         "hummock",
         "user",
+        "monitor_service",
     ];
     let protos: Vec<String> = proto_files
         .iter()
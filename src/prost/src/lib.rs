@@ -40,6 +40,9 @@ pub mod hummock;
 #[rustfmt::skip]
 #[cfg_attr(madsim, path = "sim/user.rs")]
 pub mod user;
+#[rustfmt::skip]
+#[cfg_attr(madsim, path = "sim/monitor_service.rs")]
+pub mod monitor_service;
 
 #[rustfmt::skip]
 #[path = "catalog.serde.rs"]
@@ -80,6 +83,9 @@ pub mod hummock_serde;
 #[rustfmt::skip]
 #[path = "user.serde.rs"]
 pub mod user_serde;
+#[rustfmt::skip]
+#[path = "monitor_service.serde.rs"]
+pub mod monitor_service_serde;
 
 
 #[derive(Clone, PartialEq, Eq, Debug)]
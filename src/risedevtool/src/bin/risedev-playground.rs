@@ -218,9 +218,12 @@ fn task_main(
                 writeln!(
                     log_buffer,
                     "* Run {} to start Postgres interactive shell.",
-                    style(format!("psql -h localhost -p {}", c.port))
-                        .blue()
-                        .bold()
+                    style(format!(
+                        "psql -h localhost -p {} -d dev -U risingwave",
+                        c.port
+                    ))
+                    .blue()
+                    .bold()
                 )?;
             }
             ServiceConfig::Compactor(c) => {
@@ -21,11 +21,15 @@ use risingwave_common::error::{Result, ToRwResult};
 use risingwave_common::util::addr::HostAddr;
 use risingwave_pb::batch_plan::exchange_info::DistributionMode;
 use risingwave_pb::batch_plan::{ExchangeInfo, PlanFragment, PlanNode, TaskId, TaskOutputId};
+use risingwave_pb::monitor_service::monitor_service_client::MonitorServiceClient;
+use risingwave_pb::monitor_service::{
+    ProfileFormat, ProfileType, ProfilingRequest, ProfilingResponse,
+};
 use risingwave_pb::task_service::exchange_service_client::ExchangeServiceClient;
 use risingwave_pb::task_service::task_service_client::TaskServiceClient;
 use risingwave_pb::task_service::{
-    CreateTaskRequest, CreateTaskResponse, ExecuteRequest, GetDataRequest, GetDataResponse,
-    GetStreamRequest, GetStreamResponse,
+    AbortQueryRequest, CreateTaskRequest, CreateTaskResponse, ExecuteRequest, GetDataRequest,
+    GetDataResponse, GetStreamRequest, GetStreamResponse, GetTaskProfileRequest, OperatorProfile,
 };
 use tonic::transport::{Channel, Endpoint};
 use tonic::Streaming;
@@ -34,6 +38,7 @@ use tonic::Streaming;
 pub struct ComputeClient {
     pub exchange_client: ExchangeServiceClient<Channel>,
     pub task_client: TaskServiceClient<Channel>,
+    pub monitor_client: MonitorServiceClient<Channel>,
     pub addr: HostAddr,
 }
 
@@ -46,10 +51,12 @@ impl ComputeClient {
             .await
             .to_rw_result_with(|| format!("failed to connect to {}", &addr))?;
         let exchange_client = ExchangeServiceClient::new(channel.clone());
-        let task_client = TaskServiceClient::new(channel);
+        let task_client = TaskServiceClient::new(channel.clone());
+        let monitor_client = MonitorServiceClient::new(channel);
         Ok(Self {
             exchange_client,
             task_client,
+            monitor_client,
             addr,
         })
     }
@@ -102,22 +109,27 @@ impl ComputeClient {
                 task_id: Some(task_id),
                 plan: Some(plan),
                 epoch,
+                profile: false,
             })
             .await?;
         Ok(())
     }
 
+    /// Creates a task running `plan`, whose runtime statistics of the executors are collected if
+    /// `profile` is set.
     pub async fn create_task2(
         &self,
         task_id: TaskId,
         plan: PlanFragment,
         epoch: u64,
+        profile: bool,
     ) -> Result<()> {
         let _ = self
             .create_task_inner(CreateTaskRequest {
                 task_id: Some(task_id),
                 plan: Some(plan),
                 epoch,
+                profile,
             })
             .await?;
         Ok(())
@@ -133,6 +145,53 @@ impl ComputeClient {
             .into_inner())
     }
 
+    /// Aborts all tasks of the query on this compute node. Returns the number of aborted tasks.
+    pub async fn abort_query(&self, query_id: String) -> Result<u32> {
+        Ok(self
+            .task_client
+            .to_owned()
+            .abort_query(AbortQueryRequest { query_id })
+            .await
+            .to_rw_result()?
+            .into_inner()
+            .aborted_tasks)
+    }
+
+    /// Returns the runtime statistics of the executors of a task created with profiling.
+    pub async fn get_task_profile(&self, task_id: TaskId) -> Result<Vec<OperatorProfile>> {
+        Ok(self
+            .task_client
+            .to_owned()
+            .get_task_profile(GetTaskProfileRequest {
+                task_id: Some(task_id),
+            })
+            .await
+            .to_rw_result()?
+            .into_inner()
+            .profiles)
+    }
+
+    /// Takes a profile of the compute node for `duration_sec` seconds, which is streamed back in
+    /// parts once taken.
+    pub async fn profiling(
+        &self,
+        profile_type: ProfileType,
+        format: ProfileFormat,
+        duration_sec: u64,
+    ) -> Result<Streaming<ProfilingResponse>> {
+        Ok(self
+            .monitor_client
+            .to_owned()
+            .profiling(ProfilingRequest {
+                r#type: profile_type as i32,
+                format: format as i32,
+                duration_sec,
+            })
+            .await
+            .to_rw_result()?
+            .into_inner())
+    }
+
     pub async fn execute(&self, req: ExecuteRequest) -> Result<Streaming<GetDataResponse>> {
         Ok(self
             .task_client
@@ -23,38 +23,52 @@ use risingwave_common::try_match_expand;
 use risingwave_common::util::addr::HostAddr;
 use risingwave_hummock_sdk::{HummockEpoch, HummockSSTableId, HummockVersionId};
 use risingwave_pb::catalog::{
-    Database as ProstDatabase, Schema as ProstSchema, Source as ProstSource, Table as ProstTable,
+    Database as ProstDatabase, Function as ProstFunction, Schema as ProstSchema,
+    Source as ProstSource, Table as ProstTable,
 };
 use risingwave_pb::common::{WorkerNode, WorkerType};
 use risingwave_pb::ddl_service::ddl_service_client::DdlServiceClient;
 use risingwave_pb::ddl_service::{
-    CreateDatabaseRequest, CreateDatabaseResponse, CreateMaterializedSourceRequest,
-    CreateMaterializedSourceResponse, CreateMaterializedViewRequest,
-    CreateMaterializedViewResponse, CreateSchemaRequest, CreateSchemaResponse, CreateSourceRequest,
-    CreateSourceResponse, DropDatabaseRequest, DropDatabaseResponse, DropMaterializedSourceRequest,
+    alter_relation_name_request, alter_relation_owner_request, AlterRelationNameRequest,
+    AlterRelationNameResponse, AlterRelationOwnerRequest, AlterRelationOwnerResponse,
+    AlterSourceAddColumnRequest, AlterSourceAddColumnResponse, CreateDatabaseRequest,
+    CreateDatabaseResponse, CreateFunctionRequest, CreateFunctionResponse,
+    CreateMaterializedSourceRequest, CreateMaterializedSourceResponse,
+    CreateMaterializedViewRequest, CreateMaterializedViewResponse, CreateSchemaRequest,
+    CreateSchemaResponse, CreateSourceRequest, CreateSourceResponse, DropDatabaseRequest,
+    DropDatabaseResponse, DropFunctionRequest, DropFunctionResponse, DropMaterializedSourceRequest,
     DropMaterializedSourceResponse, DropMaterializedViewRequest, DropMaterializedViewResponse,
     DropSchemaRequest, DropSchemaResponse, DropSourceRequest, DropSourceResponse,
 };
 use risingwave_pb::hummock::hummock_manager_service_client::HummockManagerServiceClient;
 use risingwave_pb::hummock::{
-    CompactTask, GetNewTableIdRequest, GetNewTableIdResponse, HummockSnapshot, HummockVersion,
-    PinSnapshotRequest, PinSnapshotResponse, PinVersionRequest, PinVersionResponse,
-    ReportCompactionTasksRequest, ReportCompactionTasksResponse, ReportVacuumTaskRequest,
-    ReportVacuumTaskResponse, SstableInfo, SubscribeCompactTasksRequest,
-    SubscribeCompactTasksResponse, UnpinSnapshotBeforeRequest, UnpinSnapshotBeforeResponse,
-    UnpinSnapshotRequest, UnpinSnapshotResponse, UnpinVersionRequest, UnpinVersionResponse,
-    VacuumTask,
+    CompactTask, CompactionGroup, GetCompactionGroupsRequest, GetCompactionGroupsResponse,
+    GetNewTableIdRequest, GetNewTableIdResponse, HummockSnapshot, HummockVersion, KeyRange,
+    PinSnapshotRequest, PinSnapshotResponse, PinSpecificSnapshotRequest, PinVersionRequest,
+    PinVersionResponse, ReportCompactionTasksRequest, ReportCompactionTasksResponse,
+    ReportReadStatisticsRequest, ReportReadStatisticsResponse, ReportVacuumTaskRequest,
+    ReportVacuumTaskResponse, SstReadStatistics, SstableInfo, SubscribeCompactTasksRequest,
+    SubscribeCompactTasksResponse, TriggerManualCompactionRequest, TriggerManualCompactionResponse,
+    UnpinSnapshotBeforeRequest, UnpinSnapshotBeforeResponse, UnpinSnapshotRequest,
+    UnpinSnapshotResponse, UnpinVersionRequest, UnpinVersionResponse, VacuumTask,
 };
+use risingwave_pb::meta::backup_service_client::BackupServiceClient;
 use risingwave_pb::meta::cluster_service_client::ClusterServiceClient;
 use risingwave_pb::meta::heartbeat_service_client::HeartbeatServiceClient;
 use risingwave_pb::meta::notification_service_client::NotificationServiceClient;
 use risingwave_pb::meta::stream_manager_service_client::StreamManagerServiceClient;
 use risingwave_pb::meta::{
     ActivateWorkerNodeRequest, ActivateWorkerNodeResponse, AddWorkerNodeRequest,
-    AddWorkerNodeResponse, DeleteWorkerNodeRequest, DeleteWorkerNodeResponse, FlushRequest,
-    FlushResponse, HeartbeatRequest, HeartbeatResponse, ListAllNodesRequest, ListAllNodesResponse,
-    SubscribeRequest, SubscribeResponse,
+    AddWorkerNodeResponse, AlterParallelismRequest, AlterParallelismResponse, BackupMetaRequest,
+    BackupMetaResponse, DeleteWorkerNodeRequest, DeleteWorkerNodeResponse, DrainWorkerRequest,
+    DrainWorkerResponse, EventLog, FlushRequest, FlushResponse, GetCurrentEpochRequest,
+    GetCurrentEpochResponse, HeartbeatRequest, HeartbeatResponse, ListAllNodesRequest,
+    ListAllNodesResponse, ListEventLogRequest, ListEventLogResponse, ListTableFragmentsRequest,
+    ListTableFragmentsResponse, LogFilter, MetaBackupInfo, PauseRequest, PauseResponse,
+    ResumeRequest, ResumeResponse, SetLogFilterRequest, SetLogFilterResponse, SubscribeRequest,
+    SubscribeResponse, TableFragments, TriggerRecoveryRequest, TriggerRecoveryResponse,
 };
+use risingwave_pb::plan_common::ColumnCatalog as ProstColumnCatalog;
 use risingwave_pb::stream_plan::StreamFragmentGraph;
 use risingwave_pb::user::user_service_client::UserServiceClient;
 use risingwave_pb::user::{
@@ -66,13 +80,18 @@ use tokio::sync::mpsc::Receiver;
 use tokio::sync::oneshot::Sender;
 use tokio::task::JoinHandle;
 use tonic::transport::{Channel, Endpoint};
-use tonic::{Status, Streaming};
+use tonic::{Code, Status, Streaming};
 
 use crate::hummock_meta_client::HummockMetaClient;
 
 type DatabaseId = u32;
 type SchemaId = u32;
 
+/// Generates the idempotency key of a DDL request, shared by all retries of the request.
+fn new_idempotency_key() -> String {
+    uuid::Uuid::new_v4().to_string()
+}
+
 /// Client to meta server. Cloning the instance is lightweight.
 #[derive(Clone)]
 pub struct MetaClient {
@@ -106,6 +125,22 @@ impl MetaClient {
         let request = SubscribeRequest {
             worker_type: worker_type as i32,
             host: Some(addr.to_protobuf()),
+            last_pinned_version_id: 0,
+        };
+        self.inner.subscribe(request).await
+    }
+
+    /// Subscribe to notification from meta as a compute node, which has pinned the hummock version
+    /// `last_pinned_version_id`. New hummock versions are pinned for the node and pushed to it.
+    pub async fn subscribe_compute_node(
+        &self,
+        addr: &HostAddr,
+        last_pinned_version_id: HummockVersionId,
+    ) -> Result<Box<dyn NotificationStream>> {
+        let request = SubscribeRequest {
+            worker_type: WorkerType::ComputeNode as i32,
+            host: Some(addr.to_protobuf()),
+            last_pinned_version_id,
         };
         self.inner.subscribe(request).await
     }
@@ -143,7 +178,10 @@ impl MetaClient {
     }
 
     pub async fn create_database(&self, db: ProstDatabase) -> Result<(DatabaseId, CatalogVersion)> {
-        let request = CreateDatabaseRequest { db: Some(db) };
+        let request = CreateDatabaseRequest {
+            db: Some(db),
+            idempotency_key: new_idempotency_key(),
+        };
         let resp = self.inner.create_database(request).await?;
         // TODO: handle error in `resp.status` here
         Ok((resp.database_id, resp.version))
@@ -152,6 +190,7 @@ impl MetaClient {
     pub async fn create_schema(&self, schema: ProstSchema) -> Result<(SchemaId, CatalogVersion)> {
         let request = CreateSchemaRequest {
             schema: Some(schema),
+            idempotency_key: new_idempotency_key(),
         };
         let resp = self.inner.create_schema(request).await?;
         // TODO: handle error in `resp.status` here
@@ -166,6 +205,7 @@ impl MetaClient {
         let request = CreateMaterializedViewRequest {
             materialized_view: Some(table),
             fragment_graph: Some(graph),
+            idempotency_key: new_idempotency_key(),
         };
         let resp = self.inner.create_materialized_view(request).await?;
         // TODO: handle error in `resp.status` here
@@ -175,6 +215,7 @@ impl MetaClient {
     pub async fn drop_materialized_view(&self, table_id: TableId) -> Result<CatalogVersion> {
         let request = DropMaterializedViewRequest {
             table_id: table_id.table_id(),
+            idempotency_key: new_idempotency_key(),
         };
 
         let resp = self.inner.drop_materialized_view(request).await?;
@@ -184,6 +225,7 @@ impl MetaClient {
     pub async fn create_source(&self, source: ProstSource) -> Result<(u32, CatalogVersion)> {
         let request = CreateSourceRequest {
             source: Some(source),
+            idempotency_key: new_idempotency_key(),
         };
 
         let resp = self.inner.create_source(request).await?;
@@ -200,6 +242,7 @@ impl MetaClient {
             materialized_view: Some(table),
             fragment_graph: Some(graph),
             source: Some(source),
+            idempotency_key: new_idempotency_key(),
         };
         let resp = self.inner.create_materialized_source(request).await?;
         // TODO: handle error in `resp.status` here
@@ -214,6 +257,7 @@ impl MetaClient {
         let request = DropMaterializedSourceRequest {
             source_id,
             table_id: table_id.table_id(),
+            idempotency_key: new_idempotency_key(),
         };
 
         let resp = self.inner.drop_materialized_source(request).await?;
@@ -221,23 +265,116 @@ impl MetaClient {
     }
 
     pub async fn drop_source(&self, source_id: u32) -> Result<CatalogVersion> {
-        let request = DropSourceRequest { source_id };
+        let request = DropSourceRequest {
+            source_id,
+            idempotency_key: new_idempotency_key(),
+        };
         let resp = self.inner.drop_source(request).await?;
         Ok(resp.version)
     }
 
+    pub async fn alter_source_add_column(
+        &self,
+        source_id: u32,
+        column: ProstColumnCatalog,
+    ) -> Result<CatalogVersion> {
+        let request = AlterSourceAddColumnRequest {
+            source_id,
+            column: Some(column),
+            idempotency_key: new_idempotency_key(),
+        };
+        let resp = self.inner.alter_source_add_column(request).await?;
+        Ok(resp.version)
+    }
+
+    pub async fn alter_table_name(&self, table_id: u32, new_name: &str) -> Result<CatalogVersion> {
+        let request = AlterRelationNameRequest {
+            relation: Some(alter_relation_name_request::Relation::TableId(table_id)),
+            new_name: new_name.to_string(),
+            idempotency_key: new_idempotency_key(),
+        };
+        let resp = self.inner.alter_relation_name(request).await?;
+        Ok(resp.version)
+    }
+
+    pub async fn alter_source_name(
+        &self,
+        source_id: u32,
+        new_name: &str,
+    ) -> Result<CatalogVersion> {
+        let request = AlterRelationNameRequest {
+            relation: Some(alter_relation_name_request::Relation::SourceId(source_id)),
+            new_name: new_name.to_string(),
+            idempotency_key: new_idempotency_key(),
+        };
+        let resp = self.inner.alter_relation_name(request).await?;
+        Ok(resp.version)
+    }
+
+    pub async fn alter_table_owner(
+        &self,
+        table_id: u32,
+        new_owner: &str,
+    ) -> Result<CatalogVersion> {
+        let request = AlterRelationOwnerRequest {
+            relation: Some(alter_relation_owner_request::Relation::TableId(table_id)),
+            new_owner: new_owner.to_string(),
+            idempotency_key: new_idempotency_key(),
+        };
+        let resp = self.inner.alter_relation_owner(request).await?;
+        Ok(resp.version)
+    }
+
+    pub async fn alter_source_owner(
+        &self,
+        source_id: u32,
+        new_owner: &str,
+    ) -> Result<CatalogVersion> {
+        let request = AlterRelationOwnerRequest {
+            relation: Some(alter_relation_owner_request::Relation::SourceId(source_id)),
+            new_owner: new_owner.to_string(),
+            idempotency_key: new_idempotency_key(),
+        };
+        let resp = self.inner.alter_relation_owner(request).await?;
+        Ok(resp.version)
+    }
+
     pub async fn drop_database(&self, database_id: u32) -> Result<CatalogVersion> {
-        let request = DropDatabaseRequest { database_id };
+        let request = DropDatabaseRequest {
+            database_id,
+            idempotency_key: new_idempotency_key(),
+        };
         let resp = self.inner.drop_database(request).await?;
         Ok(resp.version)
     }
 
     pub async fn drop_schema(&self, schema_id: u32) -> Result<CatalogVersion> {
-        let request = DropSchemaRequest { schema_id };
+        let request = DropSchemaRequest {
+            schema_id,
+            idempotency_key: new_idempotency_key(),
+        };
         let resp = self.inner.drop_schema(request).await?;
         Ok(resp.version)
     }
 
+    pub async fn create_function(&self, function: ProstFunction) -> Result<(u32, CatalogVersion)> {
+        let request = CreateFunctionRequest {
+            function: Some(function),
+            idempotency_key: new_idempotency_key(),
+        };
+        let resp = self.inner.create_function(request).await?;
+        Ok((resp.function_id, resp.version))
+    }
+
+    pub async fn drop_function(&self, function_id: u32) -> Result<CatalogVersion> {
+        let request = DropFunctionRequest {
+            function_id,
+            idempotency_key: new_idempotency_key(),
+        };
+        let resp = self.inner.drop_function(request).await?;
+        Ok(resp.version)
+    }
+
     // TODO: using UserInfoVersion instead as return type.
     pub async fn create_user(&self, user: UserInfo) -> Result<u64> {
         let request = CreateUserRequest { user: Some(user) };
@@ -309,6 +446,24 @@ impl MetaClient {
         Ok(resp.nodes)
     }
 
+    /// Lists the latest `limit` events of the cluster from the newest to the oldest, or all events
+    /// if `limit` is 0.
+    pub async fn list_event_log(&self, limit: u32) -> Result<Vec<EventLog>> {
+        let request = ListEventLogRequest { limit };
+        let resp = self.inner.list_event_log(request).await?;
+        Ok(resp.events)
+    }
+
+    /// Changes the log filter of the cluster to `directives`, e.g. `events::stream::barrier=trace`,
+    /// or restores the default one if empty.
+    pub async fn set_log_filter(&self, directives: String) -> Result<()> {
+        let request = SetLogFilterRequest {
+            filter: Some(LogFilter { directives }),
+        };
+        let _resp = self.inner.set_log_filter(request).await?;
+        Ok(())
+    }
+
     pub fn start_heartbeat_loop(
         meta_client: MetaClient,
         min_interval: Duration,
@@ -353,11 +508,103 @@ impl MetaClient {
         (join_handle, shutdown_tx)
     }
 
-    pub async fn flush(&self) -> Result<()> {
+    /// Flushes the stream pipelines, and returns the epoch committed to Hummock by the flush.
+    pub async fn flush(&self) -> Result<HummockEpoch> {
         let request = FlushRequest::default();
-        self.inner.flush(request).await?;
+        let resp = self.inner.flush(request).await?;
+        Ok(resp.committed_epoch)
+    }
+
+    /// Returns the epoch of the data being written into the stream graph, which makes the data
+    /// written before visible to batch queries once committed.
+    pub async fn get_current_epoch(&self) -> Result<HummockEpoch> {
+        let request = GetCurrentEpochRequest::default();
+        let resp = self.inner.get_current_epoch(request).await?;
+        Ok(resp.current_epoch)
+    }
+
+    /// Lists the fragments of the given tables, or of all tables if `table_ids` is empty.
+    pub async fn list_table_fragments(&self, table_ids: &[u32]) -> Result<Vec<TableFragments>> {
+        let request = ListTableFragmentsRequest {
+            table_ids: table_ids.to_vec(),
+        };
+        let resp = self.inner.list_table_fragments(request).await?;
+        Ok(resp.table_fragments)
+    }
+
+    /// Stops all sources from reading new data.
+    pub async fn pause(&self) -> Result<()> {
+        let request = PauseRequest::default();
+        self.inner.pause(request).await?;
         Ok(())
     }
+
+    /// Resumes the paused sources.
+    pub async fn resume(&self) -> Result<()> {
+        let request = ResumeRequest::default();
+        self.inner.resume(request).await?;
+        Ok(())
+    }
+
+    /// Triggers a recovery of the cluster.
+    pub async fn trigger_recovery(&self) -> Result<()> {
+        let request = TriggerRecoveryRequest::default();
+        self.inner.trigger_recovery(request).await?;
+        Ok(())
+    }
+
+    /// Asks meta to make a final checkpoint before the worker at `addr` leaves the cluster.
+    pub async fn drain_worker(&self, addr: &HostAddr) -> Result<()> {
+        let request = DrainWorkerRequest {
+            host: Some(addr.to_protobuf()),
+        };
+        self.inner.drain_worker(request).await?;
+        Ok(())
+    }
+
+    /// Reschedules the vnodes of the materialized view `table_id` to `parallelism` of its actors.
+    pub async fn alter_parallelism(&self, table_id: u32, parallelism: u32) -> Result<()> {
+        let request = AlterParallelismRequest {
+            table_id,
+            parallelism,
+        };
+        self.inner.alter_parallelism(request).await?;
+        Ok(())
+    }
+
+    /// Pins the snapshot of a historical epoch for time-travel queries. Fails if the epoch is not
+    /// readable any more or not committed yet.
+    pub async fn pin_specific_snapshot(&self, epoch: HummockEpoch) -> Result<HummockEpoch> {
+        let req = PinSpecificSnapshotRequest {
+            context_id: self.worker_id(),
+            epoch,
+        };
+        let resp = self.inner.pin_specific_snapshot(req).await?;
+        Ok(resp.snapshot.unwrap().epoch)
+    }
+
+    /// Compacts the SSTs in `level` of the compaction group that overlap with `key_range`, or all
+    /// SSTs of the level if `key_range` is `None`.
+    pub async fn trigger_manual_compaction(
+        &self,
+        compaction_group_id: u64,
+        key_range: Option<KeyRange>,
+        level: u32,
+    ) -> Result<()> {
+        let req = TriggerManualCompactionRequest {
+            compaction_group_id,
+            key_range,
+            level,
+        };
+        self.inner.trigger_manual_compaction(req).await?;
+        Ok(())
+    }
+
+    /// Takes a backup of the meta store.
+    pub async fn backup_meta(&self) -> Result<MetaBackupInfo> {
+        let resp = self.inner.backup_meta(BackupMetaRequest {}).await?;
+        Ok(resp.backup.unwrap())
+    }
 }
 
 #[async_trait]
@@ -446,6 +693,20 @@ impl HummockMetaClient for MetaClient {
         self.inner.report_vacuum_task(req).await?;
         Ok(())
     }
+
+    async fn get_compaction_groups(&self) -> Result<Vec<CompactionGroup>> {
+        let req = GetCompactionGroupsRequest {};
+        let resp = self.inner.get_compaction_groups(req).await?;
+        Ok(resp.compaction_groups)
+    }
+
+    async fn report_read_statistics(&self, stats: Vec<SstReadStatistics>) -> Result<()> {
+        let req = ReportReadStatisticsRequest {
+            sst_read_statistics: stats,
+        };
+        self.inner.report_read_statistics(req).await?;
+        Ok(())
+    }
 }
 
 /// Client to meta server. Cloning the instance is lightweight.
@@ -458,6 +719,7 @@ pub struct GrpcMetaClient {
     pub notification_client: NotificationServiceClient<Channel>,
     pub stream_client: StreamManagerServiceClient<Channel>,
     pub user_client: UserServiceClient<Channel>,
+    pub backup_client: BackupServiceClient<Channel>,
 }
 
 impl GrpcMetaClient {
@@ -475,7 +737,8 @@ impl GrpcMetaClient {
         let hummock_client = HummockManagerServiceClient::new(channel.clone());
         let notification_client = NotificationServiceClient::new(channel.clone());
         let stream_client = StreamManagerServiceClient::new(channel.clone());
-        let user_client = UserServiceClient::new(channel);
+        let user_client = UserServiceClient::new(channel.clone());
+        let backup_client = BackupServiceClient::new(channel);
         Ok(Self {
             cluster_client,
             heartbeat_client,
@@ -484,6 +747,7 @@ impl GrpcMetaClient {
             notification_client,
             stream_client,
             user_client,
+            backup_client,
         })
     }
 }
@@ -514,37 +778,102 @@ macro_rules! for_all_meta_rpc {
             ,{ cluster_client, activate_worker_node, ActivateWorkerNodeRequest, ActivateWorkerNodeResponse }
             ,{ cluster_client, delete_worker_node, DeleteWorkerNodeRequest, DeleteWorkerNodeResponse }
             ,{ cluster_client, list_all_nodes, ListAllNodesRequest, ListAllNodesResponse }
+            ,{ cluster_client, list_event_log, ListEventLogRequest, ListEventLogResponse }
+            ,{ cluster_client, set_log_filter, SetLogFilterRequest, SetLogFilterResponse }
             ,{ heartbeat_client, heartbeat, HeartbeatRequest, HeartbeatResponse }
             ,{ stream_client, flush, FlushRequest, FlushResponse }
-            ,{ ddl_client, create_materialized_source, CreateMaterializedSourceRequest, CreateMaterializedSourceResponse }
-            ,{ ddl_client, create_materialized_view, CreateMaterializedViewRequest, CreateMaterializedViewResponse }
-            ,{ ddl_client, create_source, CreateSourceRequest, CreateSourceResponse }
-            ,{ ddl_client, create_schema, CreateSchemaRequest, CreateSchemaResponse }
-            ,{ ddl_client, create_database, CreateDatabaseRequest, CreateDatabaseResponse }
-            ,{ ddl_client, drop_materialized_source, DropMaterializedSourceRequest, DropMaterializedSourceResponse }
-            ,{ ddl_client, drop_materialized_view, DropMaterializedViewRequest, DropMaterializedViewResponse }
-            ,{ ddl_client, drop_source, DropSourceRequest, DropSourceResponse }
-            ,{ ddl_client, drop_database, DropDatabaseRequest, DropDatabaseResponse }
-            ,{ ddl_client, drop_schema, DropSchemaRequest, DropSchemaResponse }
+            ,{ stream_client, get_current_epoch, GetCurrentEpochRequest, GetCurrentEpochResponse }
+            ,{ stream_client, list_table_fragments, ListTableFragmentsRequest, ListTableFragmentsResponse }
+            ,{ stream_client, pause, PauseRequest, PauseResponse }
+            ,{ stream_client, resume, ResumeRequest, ResumeResponse }
+            ,{ stream_client, trigger_recovery, TriggerRecoveryRequest, TriggerRecoveryResponse }
+            ,{ stream_client, drain_worker, DrainWorkerRequest, DrainWorkerResponse }
+            ,{ stream_client, alter_parallelism, AlterParallelismRequest, AlterParallelismResponse }
             ,{ hummock_client, pin_version, PinVersionRequest, PinVersionResponse }
             ,{ hummock_client, unpin_version, UnpinVersionRequest, UnpinVersionResponse }
             ,{ hummock_client, pin_snapshot, PinSnapshotRequest, PinSnapshotResponse }
+            ,{ hummock_client, pin_specific_snapshot, PinSpecificSnapshotRequest, PinSnapshotResponse }
             ,{ hummock_client, unpin_snapshot, UnpinSnapshotRequest, UnpinSnapshotResponse }
             ,{ hummock_client, unpin_snapshot_before, UnpinSnapshotBeforeRequest, UnpinSnapshotBeforeResponse }
             ,{ hummock_client, report_compaction_tasks, ReportCompactionTasksRequest, ReportCompactionTasksResponse }
             ,{ hummock_client, get_new_table_id, GetNewTableIdRequest, GetNewTableIdResponse }
             ,{ hummock_client, subscribe_compact_tasks, SubscribeCompactTasksRequest, Streaming<SubscribeCompactTasksResponse> }
             ,{ hummock_client, report_vacuum_task, ReportVacuumTaskRequest, ReportVacuumTaskResponse }
+            ,{ hummock_client, get_compaction_groups, GetCompactionGroupsRequest, GetCompactionGroupsResponse }
+            ,{ hummock_client, trigger_manual_compaction, TriggerManualCompactionRequest, TriggerManualCompactionResponse }
+            ,{ hummock_client, report_read_statistics, ReportReadStatisticsRequest, ReportReadStatisticsResponse }
             ,{ user_client, create_user, CreateUserRequest, CreateUserResponse }
             ,{ user_client, drop_user, DropUserRequest, DropUserResponse }
             ,{ user_client, grant_privilege, GrantPrivilegeRequest, GrantPrivilegeResponse }
             ,{ user_client, revoke_privilege, RevokePrivilegeRequest, RevokePrivilegeResponse }
+            ,{ backup_client, backup_meta, BackupMetaRequest, BackupMetaResponse }
         }
     };
 }
 
 for_all_meta_rpc! { grpc_meta_client_impl }
 
+/// Max retries of a DDL request when the meta server is unavailable, e.g. during a failover.
+const DDL_MAX_RETRIES: usize = 3;
+const DDL_RETRY_INTERVAL: Duration = Duration::from_secs(1);
+
+/// DDL requests are retried with the same idempotency key if the meta server is unavailable, so
+/// that a request applied before the failure is not applied again.
+macro_rules! grpc_ddl_client_impl {
+    ([], $( { $fn_name:ident, $req:ty, $resp:ty }),*) => {
+        $(paste! {
+            impl GrpcMetaClient {
+                pub async fn [<$fn_name>](&self, request: $req) -> Result<$resp> {
+                    let mut retries = 0;
+                    loop {
+                        match self.ddl_client.to_owned().$fn_name(request.clone()).await {
+                            Err(status)
+                                if status.code() == Code::Unavailable
+                                    && retries < DDL_MAX_RETRIES =>
+                            {
+                                retries += 1;
+                                tracing::warn!(
+                                    "{} failed: {}, retry {}",
+                                    stringify!($fn_name),
+                                    status.message(),
+                                    retries
+                                );
+                                tokio::time::sleep(DDL_RETRY_INTERVAL).await;
+                            }
+                            result => return Ok(result.to_rw_result()?.into_inner()),
+                        }
+                    }
+                }
+            }
+        })*
+    }
+}
+
+macro_rules! for_all_ddl_rpc {
+    ($macro:ident $(, $x:tt)*) => {
+        $macro! {
+            [$($x),*]
+            ,{ create_materialized_source, CreateMaterializedSourceRequest, CreateMaterializedSourceResponse }
+            ,{ create_materialized_view, CreateMaterializedViewRequest, CreateMaterializedViewResponse }
+            ,{ create_source, CreateSourceRequest, CreateSourceResponse }
+            ,{ create_schema, CreateSchemaRequest, CreateSchemaResponse }
+            ,{ create_database, CreateDatabaseRequest, CreateDatabaseResponse }
+            ,{ drop_materialized_source, DropMaterializedSourceRequest, DropMaterializedSourceResponse }
+            ,{ drop_materialized_view, DropMaterializedViewRequest, DropMaterializedViewResponse }
+            ,{ drop_source, DropSourceRequest, DropSourceResponse }
+            ,{ alter_source_add_column, AlterSourceAddColumnRequest, AlterSourceAddColumnResponse }
+            ,{ alter_relation_name, AlterRelationNameRequest, AlterRelationNameResponse }
+            ,{ alter_relation_owner, AlterRelationOwnerRequest, AlterRelationOwnerResponse }
+            ,{ drop_database, DropDatabaseRequest, DropDatabaseResponse }
+            ,{ drop_schema, DropSchemaRequest, DropSchemaResponse }
+            ,{ create_function, CreateFunctionRequest, CreateFunctionResponse }
+            ,{ drop_function, DropFunctionRequest, DropFunctionResponse }
+        }
+    };
+}
+
+for_all_ddl_rpc! { grpc_ddl_client_impl }
+
 impl GrpcMetaClient {
     // TODO(TaoWu): Use macro to refactor the following methods.
 
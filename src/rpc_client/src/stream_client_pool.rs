@@ -19,11 +19,87 @@ use moka::future::Cache;
 use risingwave_common::error::ErrorCode::{self, InternalError};
 use risingwave_common::error::{Result, RwError, ToRwResult};
 use risingwave_common::util::addr::HostAddr;
+use risingwave_common::util::failure_injection::{FailureInjector, FailureInjectorRef};
 use risingwave_pb::common::WorkerNode;
+use risingwave_pb::stream_service::changelog_service_client::ChangelogServiceClient;
 use risingwave_pb::stream_service::stream_service_client::StreamServiceClient;
+use risingwave_pb::stream_service::{
+    BroadcastActorInfoTableRequest, BroadcastActorInfoTableResponse, BuildActorsRequest,
+    BuildActorsResponse, ChangelogBatch, CreateSourceRequest, CreateSourceResponse,
+    DropActorsRequest, DropActorsResponse, DropSourceRequest, DropSourceResponse,
+    ForceStopActorsRequest, ForceStopActorsResponse, GetBackpressureRequest,
+    GetBackpressureResponse, InjectBarrierRequest, InjectBarrierResponse, ProfileActorsRequest,
+    ProfileActorsResponse, SubscribeChangelogRequest, SyncSourcesRequest, SyncSourcesResponse,
+    UpdateActorsRequest, UpdateActorsResponse,
+};
 use tonic::transport::{Channel, Endpoint};
+use tonic::{Response, Status, Streaming};
 
-pub type StreamClient = StreamServiceClient<Channel>;
+/// Client to the stream service of a compute node. Failures are injected into the RPCs if a
+/// [`FailureInjector`] is configured, with the RPCs named like `stream.inject_barrier`.
+#[derive(Clone)]
+pub struct StreamClient {
+    inner: StreamServiceClient<Channel>,
+    /// Client to the changelog service on the same connection.
+    changelog: ChangelogServiceClient<Channel>,
+    failure_injector: Option<FailureInjectorRef>,
+}
+
+macro_rules! stream_client_impl {
+    ($( { $fn_name:ident, $req:ty, $resp:ty }),*) => {
+        impl StreamClient {
+            $(
+                pub async fn $fn_name(
+                    &mut self,
+                    request: $req,
+                ) -> std::result::Result<Response<$resp>, Status> {
+                    let injector = match &self.failure_injector {
+                        Some(injector) => injector,
+                        None => return self.inner.$fn_name(request).await,
+                    };
+                    let injection = injector.decide(concat!("stream.", stringify!($fn_name)));
+                    injection.delay().await;
+                    if injection.drop {
+                        return Err(Status::unavailable(concat!(
+                            "injected failure: stream.",
+                            stringify!($fn_name),
+                            " is dropped"
+                        )));
+                    }
+                    if injection.duplicate {
+                        let _ = self.inner.$fn_name(request.clone()).await;
+                    }
+                    self.inner.$fn_name(request).await
+                }
+            )*
+        }
+    }
+}
+
+stream_client_impl! {
+    { update_actors, UpdateActorsRequest, UpdateActorsResponse }
+    ,{ build_actors, BuildActorsRequest, BuildActorsResponse }
+    ,{ broadcast_actor_info_table, BroadcastActorInfoTableRequest, BroadcastActorInfoTableResponse }
+    ,{ drop_actors, DropActorsRequest, DropActorsResponse }
+    ,{ force_stop_actors, ForceStopActorsRequest, ForceStopActorsResponse }
+    ,{ inject_barrier, InjectBarrierRequest, InjectBarrierResponse }
+    ,{ create_source, CreateSourceRequest, CreateSourceResponse }
+    ,{ sync_sources, SyncSourcesRequest, SyncSourcesResponse }
+    ,{ drop_source, DropSourceRequest, DropSourceResponse }
+    ,{ get_backpressure, GetBackpressureRequest, GetBackpressureResponse }
+    ,{ profile_actors, ProfileActorsRequest, ProfileActorsResponse }
+}
+
+impl StreamClient {
+    /// Subscribes to the changes of a materialized view made by its actors on the compute node.
+    /// Failures are not injected, as the subscriber resumes from the last epoch it received.
+    pub async fn subscribe_changelog(
+        &mut self,
+        request: SubscribeChangelogRequest,
+    ) -> std::result::Result<Response<Streaming<ChangelogBatch>>, Status> {
+        self.changelog.subscribe_changelog(request).await
+    }
+}
 
 pub type WorkerId = u32;
 
@@ -31,6 +107,7 @@ pub type WorkerId = u32;
 pub struct StreamClientPool {
     /// Stores the [`StreamClient`] mapping: `node_id` => client.
     clients: Cache<WorkerId, StreamClient>,
+    failure_injector: Option<FailureInjectorRef>,
 }
 
 impl Default for StreamClientPool {
@@ -40,28 +117,36 @@ impl Default for StreamClientPool {
 }
 
 impl StreamClientPool {
+    /// Creates a pool whose clients inject failures as configured by `RW_FAILURE_INJECTION`.
     pub fn new() -> Self {
+        Self::with_failure_injector(FailureInjector::from_env())
+    }
+
+    pub fn with_failure_injector(failure_injector: Option<FailureInjectorRef>) -> Self {
         Self {
             clients: Cache::new(u64::MAX),
+            failure_injector,
         }
     }
 
     /// Get the stream service client for the given node. If the connection is not established, a
     /// new client will be created and returned.
-    pub async fn get(&self, node: &WorkerNode) -> Result<StreamServiceClient<Channel>> {
+    pub async fn get(&self, node: &WorkerNode) -> Result<StreamClient> {
         self.clients
             .try_get_with(node.id, async {
                 let addr: HostAddr = node.get_host()?.into();
                 let endpoint = Endpoint::from_shared(format!("http://{}", addr));
-                let client = StreamServiceClient::new(
-                    endpoint
-                        .map_err(|e| InternalError(e.to_string()))?
-                        .connect_timeout(Duration::from_secs(5))
-                        .connect()
-                        .await
-                        .to_rw_result_with(|| format!("failed to connect to {}", node.get_id()))?,
-                );
-                Ok::<_, RwError>(client)
+                let channel = endpoint
+                    .map_err(|e| InternalError(e.to_string()))?
+                    .connect_timeout(Duration::from_secs(5))
+                    .connect()
+                    .await
+                    .to_rw_result_with(|| format!("failed to connect to {}", node.get_id()))?;
+                Ok::<_, RwError>(StreamClient {
+                    inner: StreamServiceClient::new(channel.clone()),
+                    changelog: ChangelogServiceClient::new(channel),
+                    failure_injector: self.failure_injector.clone(),
+                })
             })
             .await
             .map_err(|e| {
@@ -14,17 +14,24 @@
 
 use std::borrow::BorrowMut;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use async_trait::async_trait;
 use futures::future::{try_join_all, Either};
 use itertools::Itertools;
 use madsim::collections::HashMap;
-use risingwave_common::array::StreamChunk;
+use risingwave_common::array::{Column, DataChunk, Op, StreamChunk};
 use risingwave_common::catalog::ColumnId;
 use risingwave_common::error::{internal_error, Result, RwError, ToRwResult};
+use risingwave_common::types::{Datum, NaiveDateTimeWrapper, ScalarImpl};
+use risingwave_common::util::chunk_coalesce::DEFAULT_CHUNK_BUFFER_SIZE;
+use risingwave_connector::monitor::SourceMetrics;
 use risingwave_connector::{
-    Column, ConnectorProperties, ConnectorState, SourceMessage, SplitReaderImpl,
+    ConnectorProperties, ConnectorState, SourceMessage, SplitImpl, SplitReaderImpl,
 };
+use risingwave_expr::expr::{build_from_prost, BoxedExpression};
+use risingwave_pb::expr::ExprNode;
+use risingwave_pb::plan_common::MessageMetadata;
 use tokio::sync::mpsc::{Receiver, Sender};
 use tokio::sync::{mpsc, oneshot};
 use tokio::task::JoinHandle;
@@ -45,6 +52,44 @@ struct InnerConnectorSourceReaderHandle {
 
 const CONNECTOR_MESSAGE_BUFFER_SIZE: usize = 512;
 
+/// Bounds of the number of messages gathered into a chunk.
+const MIN_CHUNK_MESSAGES: usize = 64;
+const MAX_CHUNK_MESSAGES: usize = DEFAULT_CHUNK_BUFFER_SIZE;
+
+/// Time budget of gathering messages into a chunk, after which the chunk is emitted even if it's
+/// not full yet.
+const CHUNK_TIME_BUDGET: Duration = Duration::from_millis(10);
+
+/// Number of messages to gather into a chunk, which adapts to the backlog of received messages.
+/// It grows toward [`MAX_CHUNK_MESSAGES`] while messages pile up, i.e., downstream is
+/// backpressuring the source, so that the per-chunk overhead is amortized. It shrinks toward
+/// [`MIN_CHUNK_MESSAGES`] when the backlog is drained, so that the messages of a low-throughput
+/// source are emitted as soon as they arrive.
+#[derive(Debug)]
+struct AdaptiveChunkSize {
+    size: usize,
+}
+
+impl AdaptiveChunkSize {
+    fn new() -> Self {
+        Self {
+            size: MIN_CHUNK_MESSAGES,
+        }
+    }
+
+    fn size(&self) -> usize {
+        self.size
+    }
+
+    fn grow(&mut self) {
+        self.size = (self.size * 2).min(MAX_CHUNK_MESSAGES);
+    }
+
+    fn shrink(&mut self) {
+        self.size = (self.size / 2).max(MIN_CHUNK_MESSAGES);
+    }
+}
+
 /// [`ConnectorSource`] serves as a bridge between external components and streaming or
 /// batch processing. [`ConnectorSource`] introduces schema at this level while
 /// [`SplitReaderImpl`] simply loads raw content from message queue or file system.
@@ -54,13 +99,25 @@ pub struct ConnectorSourceReader {
     pub config: ConnectorProperties,
     pub parser: Arc<SourceParserImpl>,
     pub columns: Vec<SourceColumnDesc>,
+    pub metrics: Arc<SourceMetrics>,
 
     handles: Option<HashMap<String, InnerConnectorSourceReaderHandle>>,
     message_rx: Receiver<Either<Vec<SourceMessage>, RwError>>,
     // We need to keep this tx, otherwise the channel will return none with 0 inner readers, and we
-    // need to clone this tx when adding new inner readers in the future.
-    #[allow(dead_code)]
-    message_tx: Sender<Either<Vec<SourceMessage>, RwError>>,
+    // need to clone this tx when adding new inner readers in the future. A batch reader doesn't
+    // keep it, so that the channel is closed once all the inner readers finish.
+    message_tx: Option<Sender<Either<Vec<SourceMessage>, RwError>>>,
+
+    chunk_size: AdaptiveChunkSize,
+    /// Error received while gathering messages into the last chunk.
+    pending_error: Option<RwError>,
+
+    /// The generated columns, and their indices in `columns`.
+    generated_columns: Vec<(usize, BoxedExpression)>,
+    /// Indices in `columns` of the columns to output, if not all of them are output.
+    output_indices: Option<Vec<usize>>,
+    /// The metadata columns, and their indices in `columns`.
+    metadata_columns: Vec<(usize, MessageMetadata)>,
 }
 
 impl InnerConnectorSourceReader {
@@ -68,6 +125,7 @@ impl InnerConnectorSourceReader {
         prop: ConnectorProperties,
         split: ConnectorState,
         columns: Vec<SourceColumnDesc>,
+        metrics: Arc<SourceMetrics>,
     ) -> Result<Self> {
         log::debug!(
             "Spawning new connector source inner reader with config {:?}, split {:?}",
@@ -83,12 +141,13 @@ impl InnerConnectorSourceReader {
                 columns
                     .iter()
                     .cloned()
-                    .map(|col| Column {
+                    .map(|col| risingwave_connector::Column {
                         name: col.name,
                         data_type: col.data_type,
                     })
                     .collect_vec(),
             ),
+            metrics,
         )
         .await
         .to_rw_result()?;
@@ -140,66 +199,143 @@ impl SourceChunkBuilder for ConnectorSourceReader {}
 #[async_trait]
 impl StreamSourceReader for ConnectorSourceReader {
     async fn next(&mut self) -> Result<StreamChunkWithState> {
-        let batch = self.message_rx.recv().await.unwrap();
+        self.next_chunk()
+            .await?
+            .ok_or_else(|| internal_error("connector source readers are closed"))
+    }
+}
+
+impl Drop for ConnectorSourceReader {
+    fn drop(&mut self) {
+        let handles = self.handles.take().unwrap();
+
+        for (_, handle) in handles {
+            handle.join_handle.abort();
+        }
+    }
+}
+
+impl ConnectorSourceReader {
+    /// Returns the next chunk parsed from the messages, or `None` once all the inner readers
+    /// finish, which only happens to a batch reader.
+    pub async fn next_chunk(&mut self) -> Result<Option<StreamChunkWithState>> {
+        if let Some(e) = self.pending_error.take() {
+            return Err(e);
+        }
+        let batch = match self.message_rx.recv().await {
+            Some(batch) => batch,
+            None => return Ok(None),
+        };
 
         let batch = match batch {
             Either::Left(batch) => batch,
             Either::Right(e) => return Err(e),
         };
 
-        let mut events = Vec::with_capacity(batch.len());
+        let start = Instant::now();
+        let mut ops = vec![];
+        let mut rows = vec![];
         let mut split_offset_mapping: HashMap<String, String> = HashMap::new();
+        let mut num_messages =
+            self.parse_batch(batch, &mut ops, &mut rows, &mut split_offset_mapping)?;
+
+        // Gather more messages that are already received into the chunk.
+        loop {
+            if num_messages >= self.chunk_size.size() {
+                // Messages pile up, as downstream doesn't keep up with the source.
+                self.chunk_size.grow();
+                break;
+            }
+            if start.elapsed() >= CHUNK_TIME_BUDGET {
+                break;
+            }
+            match self.message_rx.try_recv() {
+                Ok(Either::Left(batch)) => {
+                    num_messages +=
+                        self.parse_batch(batch, &mut ops, &mut rows, &mut split_offset_mapping)?;
+                }
+                Ok(Either::Right(e)) => {
+                    // Return the messages gathered so far, and the error in the next call.
+                    self.pending_error = Some(e);
+                    break;
+                }
+                Err(_) => {
+                    // All messages are consumed, and downstream is waiting for more.
+                    self.chunk_size.shrink();
+                    break;
+                }
+            }
+        }
+
+        let mut columns = Self::build_columns(&self.columns, rows.as_ref())?;
+        self.eval_generated_columns(&mut columns, rows.len())?;
+        let columns = match &self.output_indices {
+            Some(indices) => indices.iter().map(|i| columns[*i].clone()).collect(),
+            None => columns,
+        };
+
+        Ok(Some(StreamChunkWithState {
+            chunk: StreamChunk::new(ops, columns, None),
+            split_offset_mapping: Some(split_offset_mapping),
+        }))
+    }
 
+    /// Parses the messages of `batch` into `ops` and `rows`, and returns the number of messages.
+    fn parse_batch(
+        &self,
+        batch: Vec<SourceMessage>,
+        ops: &mut Vec<Op>,
+        rows: &mut Vec<Vec<Datum>>,
+        split_offset_mapping: &mut HashMap<String, String>,
+    ) -> Result<usize> {
+        let num_messages = batch.len();
         for msg in batch {
-            if let Some(content) = msg.payload {
+            if let Some(content) = &msg.payload {
                 *split_offset_mapping
                     .entry(msg.split_id.clone())
                     .or_insert_with(|| "".to_string()) = msg.offset.to_string();
-                events.push(self.parser.parse(content.as_ref(), &self.columns)?);
+                let event = self.parser.parse(content.as_ref(), &self.columns)?;
+                ops.extend(event.ops);
+                rows.extend(event.rows.into_iter().map(|mut row| {
+                    for (index, metadata) in &self.metadata_columns {
+                        row[*index] = metadata_datum(&msg, *metadata);
+                    }
+                    row
+                }));
             }
         }
-        let mut ops = Vec::with_capacity(events.iter().map(|e| e.ops.len()).sum());
-        let mut rows = Vec::with_capacity(events.iter().map(|e| e.rows.len()).sum());
-
-        for event in events {
-            rows.extend(event.rows);
-            ops.extend(event.ops);
-        }
-        Ok(StreamChunkWithState {
-            chunk: StreamChunk::new(
-                ops,
-                Self::build_columns(&self.columns, rows.as_ref())?,
-                None,
-            ),
-            split_offset_mapping: Some(split_offset_mapping),
-        })
+        Ok(num_messages)
     }
-}
 
-impl Drop for ConnectorSourceReader {
-    fn drop(&mut self) {
-        let handles = self.handles.take().unwrap();
-
-        for (_, handle) in handles {
-            handle.join_handle.abort();
+    /// Evaluates the generated columns from the other columns parsed from the messages.
+    fn eval_generated_columns(&self, columns: &mut [Column], cardinality: usize) -> Result<()> {
+        if self.generated_columns.is_empty() {
+            return Ok(());
+        }
+        let chunk = DataChunk::new(columns.to_vec(), cardinality);
+        for (index, expr) in &self.generated_columns {
+            columns[*index] = Column::new(expr.eval(&chunk)?);
         }
+        Ok(())
     }
-}
 
-impl ConnectorSourceReader {
     pub async fn add_split(&mut self, split: ConnectorState) -> Result<()> {
         if let Some(append_splits) = split {
             for split in append_splits {
                 let split_id = split.id();
+                let sender = self
+                    .message_tx
+                    .clone()
+                    .ok_or_else(|| internal_error("cannot add splits to a batch reader"))?;
 
                 let mut reader = InnerConnectorSourceReader::new(
                     self.config.clone(),
                     Some(vec![split]),
                     self.columns.clone(),
+                    self.metrics.clone(),
                 )
                 .await?;
                 let (stop_tx, stop_rx) = oneshot::channel();
-                let sender = self.message_tx.clone();
                 let join_handle = tokio::spawn(async move { reader.run(stop_rx, sender).await });
 
                 if let Some(handles) = self.handles.as_mut() {
@@ -232,11 +368,39 @@ impl ConnectorSourceReader {
     }
 }
 
+/// The value of the metadata of `msg`.
+fn metadata_datum(msg: &SourceMessage, metadata: MessageMetadata) -> Datum {
+    match metadata {
+        MessageMetadata::Partition => Some(ScalarImpl::Utf8(msg.split_id.clone())),
+        MessageMetadata::Offset => Some(ScalarImpl::Utf8(msg.offset.clone())),
+        MessageMetadata::Timestamp => msg
+            .timestamp
+            .and_then(|millis| {
+                NaiveDateTimeWrapper::new_with_secs_nsecs_value_encoding(
+                    millis.div_euclid(1000),
+                    millis.rem_euclid(1000) as u32 * 1_000_000,
+                )
+                .ok()
+            })
+            .map(ScalarImpl::NaiveDateTime),
+        MessageMetadata::Key => msg
+            .key
+            .as_ref()
+            .map(|key| ScalarImpl::Utf8(String::from_utf8_lossy(key).into_owned())),
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct ConnectorSource {
     pub config: ConnectorProperties,
     pub columns: Vec<SourceColumnDesc>,
     pub parser: Arc<SourceParserImpl>,
+    pub metrics: Arc<SourceMetrics>,
+    /// The generated columns, and their indices in `columns`. The expressions refer to the other
+    /// columns by their indices in `columns`.
+    pub generated_columns: Vec<(usize, ExprNode)>,
+    /// The columns populated from the metadata of the messages, and their indices in `columns`.
+    pub metadata_columns: Vec<(usize, MessageMetadata)>,
 }
 
 impl ConnectorSource {
@@ -270,7 +434,48 @@ impl ConnectorSource {
             1
         });
         let config = self.config.clone();
-        let columns = self.get_target_columns(column_ids)?;
+        let (columns, generated_columns, output_indices) = if self.generated_columns.is_empty() {
+            (self.get_target_columns(column_ids)?, vec![], None)
+        } else {
+            // The generated columns may refer to any other column, so all the columns are parsed
+            // and the target ones are projected afterwards.
+            let output_indices = column_ids
+                .iter()
+                .map(|id| {
+                    self.columns
+                        .iter()
+                        .position(|c| c.column_id == *id)
+                        .ok_or_else(|| {
+                            internal_error(format!(
+                                "Failed to find column id: {} in source: {:?}",
+                                id, self
+                            ))
+                        })
+                })
+                .collect::<Result<Vec<_>>>()?;
+            let generated_columns = self
+                .generated_columns
+                .iter()
+                .map(|(index, expr)| Ok((*index, build_from_prost(expr)?)))
+                .collect::<Result<Vec<_>>>()?;
+            (
+                self.columns.clone(),
+                generated_columns,
+                Some(output_indices),
+            )
+        };
+
+        let metadata_columns = self
+            .metadata_columns
+            .iter()
+            .filter_map(|(index, metadata)| {
+                let column_id = self.columns[*index].column_id;
+                columns
+                    .iter()
+                    .position(|c| c.column_id == column_id)
+                    .map(|index| (index, *metadata))
+            })
+            .collect();
 
         let to_reader_splits = match splits {
             Some(vec_split_impl) => vec_split_impl
@@ -283,7 +488,8 @@ impl ConnectorSource {
             log::debug!("spawning connector split reader for split {:?}", split);
             let props = config.clone();
             let columns = columns.clone();
-            async move { InnerConnectorSourceReader::new(props, split, columns).await }
+            let metrics = self.metrics.clone();
+            async move { InnerConnectorSourceReader::new(props, split, columns, metrics).await }
         }))
         .await?;
 
@@ -311,7 +517,198 @@ impl ConnectorSource {
             message_rx: rx,
             parser: self.parser.clone(),
             columns,
-            message_tx: tx,
+            metrics: self.metrics.clone(),
+            message_tx: Some(tx),
+            chunk_size: AdaptiveChunkSize::new(),
+            pending_error: None,
+            generated_columns,
+            output_indices,
+            metadata_columns,
         })
     }
+
+    /// Creates a reader of `splits` for a batch read, which ends once the splits are all read to
+    /// their ends, e.g. those listed by
+    /// [`risingwave_connector::SplitEnumeratorImpl::list_bounded_splits`].
+    pub async fn batch_reader(
+        &self,
+        splits: Vec<SplitImpl>,
+        column_ids: Vec<ColumnId>,
+    ) -> Result<ConnectorSourceReader> {
+        let mut reader = self.stream_reader(Some(splits), column_ids).await?;
+        reader.message_tx = None;
+        Ok(reader)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+    use risingwave_common::array::Array;
+    use risingwave_common::types::DataType;
+    use risingwave_connector::SplitEnumeratorImpl;
+    use risingwave_expr::expr::{make_i32_literal, make_input_ref};
+    use risingwave_pb::data::data_type::TypeName;
+    use risingwave_pb::expr::expr_node::{RexNode, Type};
+    use risingwave_pb::expr::FunctionCall;
+
+    use super::*;
+    use crate::JSONParser;
+
+    #[test]
+    fn test_adaptive_chunk_size() {
+        let mut chunk_size = AdaptiveChunkSize::new();
+        assert_eq!(chunk_size.size(), MIN_CHUNK_MESSAGES);
+        chunk_size.shrink();
+        assert_eq!(chunk_size.size(), MIN_CHUNK_MESSAGES);
+
+        // Grows under load.
+        chunk_size.grow();
+        assert_eq!(chunk_size.size(), MIN_CHUNK_MESSAGES * 2);
+        for _ in 0..16 {
+            chunk_size.grow();
+        }
+        assert_eq!(chunk_size.size(), MAX_CHUNK_MESSAGES);
+
+        // Shrinks when idle.
+        chunk_size.shrink();
+        assert_eq!(chunk_size.size(), MAX_CHUNK_MESSAGES / 2);
+    }
+
+    #[tokio::test]
+    async fn test_generated_columns() {
+        let properties = maplit::hashmap! {
+            "connector".to_string() => "datagen".to_string(),
+            "fields.v1.kind".to_string() => "sequence".to_string(),
+            "fields.v1.start".to_string() => "1".to_string(),
+            "fields.v1.end".to_string() => "100".to_string(),
+        };
+        let config = ConnectorProperties::extract(properties).unwrap();
+        let splits = SplitEnumeratorImpl::create(config.clone())
+            .await
+            .unwrap()
+            .list_splits()
+            .await
+            .unwrap();
+
+        let column = |name: &str, column_id: i32, skip_parse: bool| SourceColumnDesc {
+            name: name.to_string(),
+            data_type: DataType::Int32,
+            column_id: ColumnId::from(column_id),
+            skip_parse,
+            fields: vec![],
+        };
+        // `v2 AS v1 + 1`
+        let v2_expr = ExprNode {
+            expr_type: Type::Add as i32,
+            return_type: Some(DataType::Int32.to_protobuf()),
+            rex_node: Some(RexNode::FuncCall(FunctionCall {
+                children: vec![make_input_ref(1, TypeName::Int32), make_i32_literal(1)],
+            })),
+        };
+        let source = ConnectorSource {
+            config,
+            columns: vec![
+                column("_row_id", 0, true),
+                column("v1", 1, false),
+                column("v2", 2, true),
+            ],
+            parser: Arc::new(SourceParserImpl::Json(JSONParser)),
+            metrics: Arc::new(SourceMetrics::unused()),
+            generated_columns: vec![(2, v2_expr)],
+            metadata_columns: vec![],
+        };
+
+        // Only `v2` is read, but `v1` is still parsed to evaluate it.
+        let mut reader = source
+            .stream_reader(Some(splits), vec![ColumnId::from(2)])
+            .await
+            .unwrap();
+        let chunk = reader.next().await.unwrap().chunk;
+        assert_eq!(chunk.columns().len(), 1);
+        assert!(chunk.cardinality() > 0);
+        let v2 = chunk.column_at(0).array_ref().as_int32();
+        for (i, v) in v2.iter().enumerate() {
+            assert_eq!(v, Some(i as i32 + 2));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_batch_reader() {
+        let properties = maplit::hashmap! {
+            "connector".to_string() => "nexmark".to_string(),
+            "nexmark.table.type".to_string() => "Bid".to_string(),
+            "nexmark.event.num".to_string() => "100".to_string(),
+            "nexmark.min.event.gap.in.ns".to_string() => "0".to_string(),
+        };
+        let config = ConnectorProperties::extract(properties).unwrap();
+        let splits = SplitEnumeratorImpl::create(config.clone())
+            .await
+            .unwrap()
+            .list_bounded_splits()
+            .await
+            .unwrap();
+        let source = ConnectorSource {
+            config,
+            columns: vec![SourceColumnDesc {
+                name: "auction".to_string(),
+                data_type: DataType::Int64,
+                column_id: ColumnId::from(0),
+                skip_parse: false,
+                fields: vec![],
+            }],
+            parser: Arc::new(SourceParserImpl::Json(JSONParser)),
+            metrics: Arc::new(SourceMetrics::unused()),
+            generated_columns: vec![],
+            metadata_columns: vec![],
+        };
+
+        let mut reader = source
+            .batch_reader(splits, vec![ColumnId::from(0)])
+            .await
+            .unwrap();
+        let mut num_rows = 0;
+        while let Some(chunk) = reader.next_chunk().await.unwrap() {
+            num_rows += chunk.chunk.cardinality();
+        }
+        // The reader ends after the bids among the 100 events.
+        assert!(num_rows > 0 && num_rows < 100);
+    }
+
+    #[test]
+    fn test_metadata_datum() {
+        let msg = SourceMessage {
+            payload: None,
+            offset: "42".to_string(),
+            split_id: "3".to_string(),
+            key: Some(Bytes::from_static(b"user-1")),
+            timestamp: Some(1_500),
+        };
+        assert_eq!(
+            metadata_datum(&msg, MessageMetadata::Partition),
+            Some(ScalarImpl::Utf8("3".to_string()))
+        );
+        assert_eq!(
+            metadata_datum(&msg, MessageMetadata::Offset),
+            Some(ScalarImpl::Utf8("42".to_string()))
+        );
+        assert_eq!(
+            metadata_datum(&msg, MessageMetadata::Key),
+            Some(ScalarImpl::Utf8("user-1".to_string()))
+        );
+        assert_eq!(
+            metadata_datum(&msg, MessageMetadata::Timestamp),
+            Some(ScalarImpl::NaiveDateTime(
+                NaiveDateTimeWrapper::new_with_secs_nsecs_value_encoding(1, 500_000_000).unwrap()
+            ))
+        );
+
+        let msg = SourceMessage {
+            key: None,
+            timestamp: None,
+            ..msg
+        };
+        assert_eq!(metadata_datum(&msg, MessageMetadata::Key), None);
+        assert_eq!(metadata_datum(&msg, MessageMetadata::Timestamp), None);
+    }
 }
@@ -15,8 +15,11 @@
 use std::collections::HashMap;
 use std::fmt::Debug;
 use std::sync::Arc;
+use std::time::Duration;
 
 use async_trait::async_trait;
+use bytes::Bytes;
+use itertools::Itertools;
 use parking_lot::{Mutex, MutexGuard};
 use risingwave_common::catalog::{ColumnDesc, ColumnId, TableId};
 use risingwave_common::ensure;
@@ -24,9 +27,12 @@ use risingwave_common::error::ErrorCode::{ConnectorError, InternalError, Protoco
 use risingwave_common::error::{Result, RwError};
 use risingwave_common::types::DataType;
 use risingwave_common::util::epoch::UNIX_SINGULARITY_DATE_EPOCH;
-use risingwave_connector::ConnectorProperties;
+use risingwave_connector::monitor::SourceMetrics;
+use risingwave_connector::{ConnectorProperties, SplitEnumeratorImpl, SplitReaderImpl};
 use risingwave_pb::catalog::StreamSourceInfo;
 use risingwave_pb::plan_common::RowFormatType;
+use risingwave_pb::stream_service::validate_source_response::error::Kind as ValidationErrorKind;
+use risingwave_pb::stream_service::validate_source_response::Error as ValidationError;
 
 use crate::row_id::{RowId, RowIdGenerator};
 use crate::table_v2::TableSourceV2;
@@ -43,6 +49,10 @@ pub trait SourceManager: Debug + Sync + Send {
     fn get_source(&self, source_id: &TableId) -> Result<SourceDesc>;
     fn drop_source(&self, source_id: &TableId) -> Result<()>;
 
+    /// Appends nullable `columns` to a source, skipping those already added. The readers created
+    /// afterwards read them.
+    fn add_columns(&self, source_id: &TableId, columns: Vec<ColumnDesc>) -> Result<()>;
+
     /// Clear sources, this is used when failover happens.
     fn clear_sources(&self) -> Result<()>;
 }
@@ -55,6 +65,9 @@ pub struct SourceColumnDesc {
     pub data_type: DataType,
     pub column_id: ColumnId,
     pub skip_parse: bool,
+    /// The descriptions of the nested fields if the column is a struct, used by parsers to look
+    /// up the fields by name.
+    pub fields: Vec<ColumnDesc>,
 }
 
 impl From<&ColumnDesc> for SourceColumnDesc {
@@ -64,6 +77,7 @@ impl From<&ColumnDesc> for SourceColumnDesc {
             data_type: c.data_type.clone(),
             column_id: c.column_id,
             skip_parse: false,
+            fields: c.field_descs.clone(),
         }
     }
 }
@@ -94,6 +108,34 @@ impl SourceDesc {
         }
         result
     }
+
+    /// Appends nullable `columns` to the source, skipping those already added. A connector
+    /// source is replaced with one parsing the columns, while a table source is updated in place
+    /// as it's shared with the writers.
+    pub fn add_columns(&mut self, columns: &[ColumnDesc]) {
+        let columns = columns
+            .iter()
+            .filter(|column| !self.columns.iter().any(|c| c.column_id == column.column_id))
+            .collect_vec();
+        if columns.is_empty() {
+            return;
+        }
+        let source_columns = columns
+            .iter()
+            .map(|&c| SourceColumnDesc::from(c))
+            .collect_vec();
+        self.columns.extend(source_columns.iter().cloned());
+        match self.source.as_ref() {
+            SourceImpl::TableV2(table) => {
+                table.add_columns(&columns.into_iter().cloned().collect_vec());
+            }
+            SourceImpl::Connector(connector) => {
+                let mut connector = connector.clone();
+                connector.columns.extend(source_columns);
+                self.source = Arc::new(SourceImpl::Connector(connector));
+            }
+        }
+    }
 }
 
 pub type SourceManagerRef = Arc<dyn SourceManager>;
@@ -103,73 +145,183 @@ pub struct MemSourceManager {
     sources: Mutex<HashMap<TableId, SourceDesc>>,
     /// Located worker id.
     worker_id: u32,
+    /// Metrics of the connectors of the sources.
+    metrics: Arc<SourceMetrics>,
 }
 
-#[async_trait]
-impl SourceManager for MemSourceManager {
-    async fn create_source(&self, source_id: &TableId, info: StreamSourceInfo) -> Result<()> {
-        let format = match info.get_row_format()? {
-            RowFormatType::Json => SourceFormat::Json,
-            RowFormatType::Protobuf => SourceFormat::Protobuf,
-            RowFormatType::DebeziumJson => SourceFormat::DebeziumJson,
-            RowFormatType::Avro => SourceFormat::Avro,
-        };
+/// Creates the parser of the row format of a connector source.
+async fn create_source_parser(
+    info: &StreamSourceInfo,
+) -> Result<(SourceFormat, Arc<SourceParserImpl>)> {
+    let format = match info.get_row_format()? {
+        RowFormatType::Json => SourceFormat::Json,
+        RowFormatType::Protobuf => SourceFormat::Protobuf,
+        RowFormatType::DebeziumJson => SourceFormat::DebeziumJson,
+        RowFormatType::Avro => SourceFormat::Avro,
+    };
+
+    if format == SourceFormat::Protobuf && info.row_schema_location.is_empty() {
+        return Err(RwError::from(ProtocolError(
+            "protobuf file location not provided".to_string(),
+        )));
+    }
+    let parser =
+        SourceParserImpl::create(&format, &info.properties, info.row_schema_location.as_str())
+            .await?;
+    Ok((format, parser))
+}
 
-        if format == SourceFormat::Protobuf && info.row_schema_location.is_empty() {
-            return Err(RwError::from(ProtocolError(
-                "protobuf file location not provided".to_string(),
-            )));
-        }
-        let source_parser_rs =
-            SourceParserImpl::create(&format, &info.properties, info.row_schema_location.as_str())
-                .await;
-        let parser = if let Ok(source_parser) = source_parser_rs {
-            source_parser
-        } else {
-            return Err(source_parser_rs.err().unwrap());
-        };
+/// Validates a connector source before it's created: the connector parses the properties and
+/// accesses the upstream with them, and the row format parses the schema.
+pub async fn validate_stream_source(
+    info: &StreamSourceInfo,
+) -> std::result::Result<(), ValidationError> {
+    let error = |kind: ValidationErrorKind, message: String| ValidationError {
+        kind: kind as i32,
+        message,
+    };
+
+    let config = ConnectorProperties::extract(info.properties.clone())
+        .map_err(|e| error(ValidationErrorKind::InvalidProperties, e.to_string()))?;
+    SplitEnumeratorImpl::validate(config)
+        .await
+        .map_err(|e| error(ValidationErrorKind::UpstreamUnavailable, e.to_string()))?;
+    create_source_parser(info)
+        .await
+        .map_err(|e| error(ValidationErrorKind::IncompatibleSchema, e.to_string()))?;
+    Ok(())
+}
 
-        let columns = info
-            .columns
-            .iter()
-            .enumerate()
-            .map(|(idx, c)| {
-                let c = c.column_desc.as_ref().unwrap().clone();
-                SourceColumnDesc {
-                    name: c.name.clone(),
-                    data_type: DataType::from(&c.column_type.unwrap()),
-                    column_id: ColumnId::from(c.column_id),
-                    skip_parse: idx as i32 == info.row_id_index,
+/// Samples at most `max_messages` payloads from the splits of a connector source, e.g. to infer
+/// the schema of the source from. The splits are read one after another from the offsets given by
+/// the properties, until enough messages are sampled or `timeout` elapses.
+pub async fn sample_source_messages(
+    properties: HashMap<String, String>,
+    max_messages: usize,
+    timeout: Duration,
+) -> Result<Vec<Bytes>> {
+    let connector_error = |e: anyhow::Error| RwError::from(ConnectorError(e.to_string()));
+
+    let config = ConnectorProperties::extract(properties).map_err(connector_error)?;
+    let splits = SplitEnumeratorImpl::create(config.clone())
+        .await
+        .map_err(connector_error)?
+        .list_splits()
+        .await
+        .map_err(connector_error)?;
+
+    let mut samples = vec![];
+    let sample = async {
+        for split in splits {
+            let mut reader = SplitReaderImpl::create(
+                config.clone(),
+                Some(vec![split]),
+                None,
+                Arc::new(SourceMetrics::unused()),
+            )
+            .await
+            .map_err(connector_error)?;
+            while samples.len() < max_messages {
+                match reader.next().await.map_err(connector_error)? {
+                    Some(messages) => {
+                        samples.extend(messages.into_iter().filter_map(|m| m.payload))
+                    }
+                    None => break,
                 }
-            })
-            .collect::<Vec<SourceColumnDesc>>();
-
-        assert!(
-            info.row_id_index >= 0,
-            "expected row_id_index >= 0, got {}",
-            info.row_id_index
-        );
-        let row_id_index = info.row_id_index as usize;
-
-        let config = ConnectorProperties::extract(info.properties)
-            .map_err(|e| RwError::from(ConnectorError(e.to_string())))?;
+            }
+            if samples.len() >= max_messages {
+                break;
+            }
+        }
+        Ok::<_, RwError>(())
+    };
+    // Returns what's sampled so far on timeout, as an idle split may never yield a message.
+    if let Ok(result) = tokio::time::timeout(timeout, sample).await {
+        result?;
+    }
+    samples.truncate(max_messages);
+    Ok(samples)
+}
 
-        let source = SourceImpl::Connector(ConnectorSource {
-            config,
-            columns: columns.clone(),
-            parser,
-        });
+/// Creates the description of a connector source from its definition, e.g. to read the source on
+/// a worker that it's not created on.
+pub async fn create_connector_source_desc(
+    info: StreamSourceInfo,
+    worker_id: u32,
+    metrics: Arc<SourceMetrics>,
+) -> Result<SourceDesc> {
+    let (format, parser) = create_source_parser(&info).await?;
+
+    let columns = info
+        .columns
+        .iter()
+        .enumerate()
+        .map(|(idx, catalog)| {
+            let c = catalog.column_desc.as_ref().unwrap().clone();
+            SourceColumnDesc {
+                name: c.name.clone(),
+                data_type: DataType::from(&c.column_type.unwrap()),
+                column_id: ColumnId::from(c.column_id),
+                // The generated columns are evaluated after the others are parsed, and the
+                // metadata columns are populated from the messages.
+                skip_parse: idx as i32 == info.row_id_index
+                    || catalog.generated_expr.is_some()
+                    || info
+                        .metadata_columns
+                        .iter()
+                        .any(|m| m.column_index == idx as i32),
+                fields: c.field_descs.iter().map(ColumnDesc::from).collect(),
+            }
+        })
+        .collect::<Vec<SourceColumnDesc>>();
+
+    assert!(
+        info.row_id_index >= 0,
+        "expected row_id_index >= 0, got {}",
+        info.row_id_index
+    );
+    let row_id_index = info.row_id_index as usize;
+
+    let generated_columns = info
+        .columns
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, c)| c.generated_expr.clone().map(|expr| (idx, expr)))
+        .collect();
+    let metadata_columns = info
+        .metadata_columns
+        .iter()
+        .map(|m| (m.column_index as usize, m.metadata()))
+        .collect();
+
+    let config = ConnectorProperties::extract(info.properties)
+        .map_err(|e| RwError::from(ConnectorError(e.to_string())))?;
+
+    let source = SourceImpl::Connector(ConnectorSource {
+        config,
+        columns: columns.clone(),
+        parser,
+        metrics,
+        generated_columns,
+        metadata_columns,
+    });
+
+    Ok(SourceDesc {
+        source: Arc::new(source),
+        format,
+        columns,
+        row_id_index,
+        row_id_generator: Arc::new(Mutex::new(RowIdGenerator::with_epoch(
+            worker_id,
+            *UNIX_SINGULARITY_DATE_EPOCH,
+        ))),
+    })
+}
 
-        let desc = SourceDesc {
-            source: Arc::new(source),
-            format,
-            columns,
-            row_id_index,
-            row_id_generator: Arc::new(Mutex::new(RowIdGenerator::with_epoch(
-                self.worker_id,
-                *UNIX_SINGULARITY_DATE_EPOCH,
-            ))),
-        };
+#[async_trait]
+impl SourceManager for MemSourceManager {
+    async fn create_source(&self, source_id: &TableId, info: StreamSourceInfo) -> Result<()> {
+        let desc = create_connector_source_desc(info, self.worker_id, self.metrics.clone()).await?;
 
         let mut tables = self.get_sources()?;
         ensure!(
@@ -228,6 +380,18 @@ impl SourceManager for MemSourceManager {
         Ok(())
     }
 
+    fn add_columns(&self, source_id: &TableId, columns: Vec<ColumnDesc>) -> Result<()> {
+        let mut sources = self.get_sources()?;
+        let desc = sources.get_mut(source_id).ok_or_else(|| {
+            RwError::from(InternalError(format!(
+                "Source does not exist: {:?}",
+                source_id
+            )))
+        })?;
+        desc.add_columns(&columns);
+        Ok(())
+    }
+
     fn clear_sources(&self) -> Result<()> {
         let mut sources = self.get_sources()?;
         sources.clear();
@@ -240,9 +404,16 @@ impl MemSourceManager {
         MemSourceManager {
             sources: Mutex::new(HashMap::new()),
             worker_id,
+            metrics: Arc::new(SourceMetrics::unused()),
         }
     }
 
+    /// Reports the metrics of the connectors to `metrics`.
+    pub fn with_metrics(mut self, metrics: Arc<SourceMetrics>) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
     fn get_sources(&self) -> Result<MutexGuard<HashMap<TableId, SourceDesc>>> {
         Ok(self.sources.lock())
     }
@@ -273,6 +444,7 @@ mod tests {
             .map(|c| ColumnCatalog {
                 column_desc: Some(c.to_owned()),
                 is_hidden: false,
+                generated_expr: None,
             })
             .collect();
         let info = StreamSourceInfo {
@@ -282,6 +454,8 @@ mod tests {
             row_id_index: 0,
             pk_column_ids: vec![0],
             columns,
+            metadata_columns: vec![],
+            dedup: None,
         };
         let source_id = TableId::default();
 
@@ -293,6 +467,68 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_validate_stream_source() {
+        use risingwave_pb::plan_common::RowFormatType;
+        use risingwave_pb::stream_service::validate_source_response::error::Kind;
+
+        let validate = |properties: &[(&str, &str)], row_format: RowFormatType| {
+            let info = StreamSourceInfo {
+                properties: properties
+                    .iter()
+                    .map(|(k, v)| (k.to_string(), v.to_string()))
+                    .collect(),
+                row_format: row_format as i32,
+                ..Default::default()
+            };
+            async move { validate_stream_source(&info).await.map_err(|e| e.kind()) }
+        };
+
+        assert_eq!(
+            validate(&[("connector", "datagen")], RowFormatType::Json).await,
+            Ok(())
+        );
+        assert_eq!(
+            validate(&[], RowFormatType::Json).await,
+            Err(Kind::InvalidProperties)
+        );
+        assert_eq!(
+            validate(
+                &[("connector", "datagen"), ("datagen.split.num", "0")],
+                RowFormatType::Json
+            )
+            .await,
+            Err(Kind::UpstreamUnavailable)
+        );
+        assert_eq!(
+            validate(&[("connector", "datagen")], RowFormatType::Protobuf).await,
+            Err(Kind::IncompatibleSchema)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sample_source_messages() {
+        let properties = maplit::hashmap! {
+            "connector".to_string() => "nexmark".to_string(),
+            "nexmark.table.type".to_string() => "Bid".to_string(),
+        };
+        let samples = sample_source_messages(properties, 10, Duration::from_secs(10))
+            .await
+            .unwrap();
+        assert_eq!(samples.len(), 10);
+        for sample in samples {
+            let bid: serde_json::Value = serde_json::from_slice(&sample).unwrap();
+            assert!(bid.get("auction").unwrap().is_u64());
+        }
+
+        let properties = maplit::hashmap! { "connector".to_string() => "datagen".to_string() };
+        assert!(
+            sample_source_messages(properties, 10, Duration::from_secs(10))
+                .await
+                .is_err()
+        );
+    }
+
     #[tokio::test]
     async fn test_table_source_v2() -> Result<()> {
         let table_id = TableId::default();
@@ -327,6 +563,17 @@ mod tests {
         let get_source_res = mem_source_manager.get_source(&table_id);
         assert!(get_source_res.is_ok());
 
+        // add columns
+        let new_column = ColumnDesc::unnamed(ColumnId::from(2), DataType::Int64);
+        let add_columns_res = mem_source_manager.add_columns(&table_id, vec![new_column.clone()]);
+        assert!(add_columns_res.is_ok());
+        mem_source_manager
+            .add_columns(&table_id, vec![new_column])
+            .unwrap();
+        let source_desc = mem_source_manager.get_source(&table_id).unwrap();
+        assert_eq!(source_desc.columns.len(), 3);
+        assert_eq!(source_desc.columns[2].column_id, ColumnId::from(2));
+
         // drop source
         let drop_source_res = mem_source_manager.drop_source(&table_id);
         assert!(drop_source_res.is_ok());
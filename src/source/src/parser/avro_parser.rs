@@ -20,8 +20,10 @@ use std::path::Path;
 use apache_avro::types::Value;
 use apache_avro::{Reader, Schema};
 use chrono::{Datelike, NaiveDate};
+use itertools::Itertools;
 use num_traits::FromPrimitive;
-use risingwave_common::array::Op;
+use risingwave_common::array::{ListValue, Op, StructValue};
+use risingwave_common::catalog::ColumnDesc;
 use risingwave_common::error::ErrorCode::{InternalError, InvalidConfigValue, ProtocolError};
 use risingwave_common::error::{ErrorCode, Result, RwError};
 use risingwave_common::types::{
@@ -119,8 +121,20 @@ macro_rules! from_avro_primitive {
 ///  - string: String
 ///  - Date (the number of days from the unix epoch, 1970-1-1 UTC)
 ///  - Timestamp (the number of milliseconds from the unix epoch,  1970-1-1 00:00:00.000 UTC)
+///  - record: struct, whose fields are looked up by name
+///  - array: list
 pub(crate) fn from_avro_value(column: &SourceColumnDesc, field_value: Value) -> Result<ScalarImpl> {
-    match column.data_type {
+    do_from_avro_value(&column.data_type, &column.fields, field_value)
+}
+
+/// Converts `field_value` to `data_type`. `fields` describes the nested fields if `data_type` is a
+/// struct, or the fields of the elements if it's a list of structs.
+fn do_from_avro_value(
+    data_type: &DataType,
+    fields: &[ColumnDesc],
+    field_value: Value,
+) -> Result<ScalarImpl> {
+    match data_type {
         DataType::Boolean => {
             from_avro_primitive!(field_value, Boolean, |b: bool| Ok(ScalarImpl::Bool(b)))
         }
@@ -170,6 +184,39 @@ pub(crate) fn from_avro_value(column: &SourceColumnDesc, field_value: Value) ->
                 ScalarImpl::NaiveDateTime
             )
         }
+        DataType::Struct {
+            fields: field_types,
+        } => {
+            if field_types.len() != fields.len() {
+                return Err(RwError::from(InternalError(
+                    "missing field descriptions of struct".to_string(),
+                )));
+            }
+            from_avro_primitive!(field_value, Record, |record: Vec<(String, Value)>| {
+                // Missing or mistyped fields are parsed as nulls, like the top-level columns.
+                let values = fields
+                    .iter()
+                    .map(|field| {
+                        let value = record.iter().find(|(name, _)| field.name.eq(name))?;
+                        do_from_avro_value(&field.data_type, &field.field_descs, value.1.clone())
+                            .ok()
+                    })
+                    .collect();
+                Ok(ScalarImpl::Struct(StructValue::new(values)))
+            })
+        }
+        DataType::List { datatype } => {
+            from_avro_primitive!(field_value, Array, |array: Vec<Value>| {
+                let values: Vec<Datum> = array
+                    .into_iter()
+                    .map(|v| match v {
+                        Value::Null => Ok(None),
+                        v => do_from_avro_value(datatype, fields, v).map(Some),
+                    })
+                    .try_collect()?;
+                Ok(ScalarImpl::List(ListValue::new(values)))
+            })
+        }
         _ => Err(ErrorCode::NotImplemented(
             "unsupported type for avro parser".to_string(),
             None.into(),
@@ -479,48 +526,56 @@ mod test {
                 data_type: DataType::Int32,
                 column_id: ColumnId::from(0),
                 skip_parse: false,
+                fields: vec![],
             },
             SourceColumnDesc {
                 name: "sequence_id".to_string(),
                 data_type: DataType::Int64,
                 column_id: ColumnId::from(1),
                 skip_parse: false,
+                fields: vec![],
             },
             SourceColumnDesc {
                 name: "name".to_string(),
                 data_type: DataType::Varchar,
                 column_id: ColumnId::from(2),
                 skip_parse: false,
+                fields: vec![],
             },
             SourceColumnDesc {
                 name: "score".to_string(),
                 data_type: DataType::Float32,
                 column_id: ColumnId::from(3),
                 skip_parse: false,
+                fields: vec![],
             },
             SourceColumnDesc {
                 name: "avg_score".to_string(),
                 data_type: DataType::Float64,
                 column_id: ColumnId::from(4),
                 skip_parse: false,
+                fields: vec![],
             },
             SourceColumnDesc {
                 name: "is_lasted".to_string(),
                 data_type: DataType::Boolean,
                 column_id: ColumnId::from(5),
                 skip_parse: false,
+                fields: vec![],
             },
             SourceColumnDesc {
                 name: "entrance_date".to_string(),
                 data_type: DataType::Date,
                 column_id: ColumnId::from(6),
                 skip_parse: false,
+                fields: vec![],
             },
             SourceColumnDesc {
                 name: "birthday".to_string(),
                 data_type: DataType::Timestamp,
                 column_id: ColumnId::from(7),
                 skip_parse: false,
+                fields: vec![],
             },
         ]
     }
@@ -12,10 +12,13 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use itertools::Itertools;
 use num_traits::FromPrimitive;
+use risingwave_common::array::{ListValue, StructValue};
+use risingwave_common::catalog::ColumnDesc;
 use risingwave_common::error::ErrorCode::{self, InternalError};
 use risingwave_common::error::{Result, RwError};
-use risingwave_common::types::{DataType, Decimal, ScalarImpl, ScalarRef};
+use risingwave_common::types::{DataType, Datum, Decimal, ScalarImpl, ScalarRef};
 use risingwave_expr::vector_op::cast::{str_to_date, str_to_timestamp};
 use serde_json::Value;
 
@@ -34,7 +37,17 @@ pub(crate) fn json_parse_value(
     column: &SourceColumnDesc,
     value: Option<&Value>,
 ) -> Result<ScalarImpl> {
-    match column.data_type {
+    do_json_parse_value(&column.data_type, &column.fields, value)
+}
+
+/// Parses `value` as `data_type`. `fields` describes the nested fields if `data_type` is a struct,
+/// or the fields of the elements if it's a list of structs.
+fn do_json_parse_value(
+    data_type: &DataType,
+    fields: &[ColumnDesc],
+    value: Option<&Value>,
+) -> Result<ScalarImpl> {
+    match data_type {
         DataType::Boolean => {
             make_ScalarImpl!(value.and_then(|v| v.as_bool()), |x| ScalarImpl::Bool(
                 x as bool
@@ -75,11 +88,14 @@ pub(crate) fn json_parse_value(
             },
             None => Err(RwError::from(InternalError("json parse error".to_string()))),
         },
-        DataType::Varchar => {
-            make_ScalarImpl!(value.and_then(|v| v.as_str()), |v: &str| ScalarImpl::Utf8(
-                v.to_owned_scalar()
-            ))
-        }
+        // Values of other types, e.g. nested objects and arrays, are read as their JSON text.
+        DataType::Varchar => match value {
+            None | Some(Value::Null) => {
+                Err(RwError::from(InternalError("json parse error".to_string())))
+            }
+            Some(Value::String(v)) => Ok(ScalarImpl::Utf8(v.as_str().to_owned_scalar())),
+            Some(v) => Ok(ScalarImpl::Utf8(v.to_string())),
+        },
         DataType::Date => match value.and_then(|v| v.as_str()) {
             None => Err(RwError::from(InternalError("parse error".to_string()))),
             Some(date_str) => match str_to_date(date_str) {
@@ -94,6 +110,41 @@ pub(crate) fn json_parse_value(
                 Err(e) => Err(e),
             },
         },
+        DataType::Struct {
+            fields: field_types,
+        } => match value.and_then(|v| v.as_object()) {
+            None => Err(RwError::from(InternalError("json parse error".to_string()))),
+            Some(_) if field_types.len() != fields.len() => Err(RwError::from(InternalError(
+                "missing field descriptions of struct".to_string(),
+            ))),
+            // Missing or mistyped fields are parsed as nulls, like the top-level columns.
+            Some(object) => Ok(ScalarImpl::Struct(StructValue::new(
+                fields
+                    .iter()
+                    .map(|field| {
+                        do_json_parse_value(
+                            &field.data_type,
+                            &field.field_descs,
+                            object.get(&field.name),
+                        )
+                        .ok()
+                    })
+                    .collect(),
+            ))),
+        },
+        DataType::List { datatype } => match value.and_then(|v| v.as_array()) {
+            None => Err(RwError::from(InternalError("json parse error".to_string()))),
+            Some(array) => {
+                let values: Vec<Datum> = array
+                    .iter()
+                    .map(|v| match v {
+                        Value::Null => Ok(None),
+                        v => do_json_parse_value(datatype, fields, Some(v)).map(Some),
+                    })
+                    .try_collect()?;
+                Ok(ScalarImpl::List(ListValue::new(values)))
+            }
+        },
         _ => Err(ErrorCode::NotImplemented(
             "unsupported type for json_parse_value".to_string(),
             None.into(),
@@ -21,6 +21,16 @@ use serde_json::Value;
 use crate::parser::common::json_parse_value;
 use crate::{Event, SourceColumnDesc, SourceParser};
 
+/// Gets the field of a column from a JSON object. The name of a column flattened from nested
+/// objects is the path of the field joined by dots, e.g. `a.b` for `{"a": {"b": 1}}`, which is
+/// looked up if there's no field named exactly by the column.
+fn get_field<'a>(value: &'a Value, name: &str) -> Option<&'a Value> {
+    value.get(name).or_else(|| {
+        name.split('.')
+            .try_fold(value, |value, field| value.get(field))
+    })
+}
+
 /// Parser for JSON format
 #[derive(Debug)]
 pub struct JSONParser;
@@ -38,7 +48,7 @@ impl SourceParser for JSONParser {
                     if column.skip_parse {
                         None
                     } else {
-                        json_parse_value(column, value.get(&column.name)).ok()
+                        json_parse_value(column, get_field(&value, &column.name)).ok()
                     }
                 })
                 .collect::<Vec<Datum>>()],
@@ -48,7 +58,8 @@ impl SourceParser for JSONParser {
 
 #[cfg(test)]
 mod tests {
-    use risingwave_common::catalog::ColumnId;
+    use risingwave_common::array::{ListValue, StructValue};
+    use risingwave_common::catalog::{ColumnDesc, ColumnId};
     use risingwave_common::types::{DataType, ScalarImpl};
     use risingwave_expr::vector_op::cast::{str_to_date, str_to_timestamp};
 
@@ -64,54 +75,63 @@ mod tests {
                 data_type: DataType::Int32,
                 column_id: ColumnId::from(0),
                 skip_parse: false,
+                fields: vec![],
             },
             SourceColumnDesc {
                 name: "bool".to_string(),
                 data_type: DataType::Boolean,
                 column_id: ColumnId::from(2),
                 skip_parse: false,
+                fields: vec![],
             },
             SourceColumnDesc {
                 name: "i16".to_string(),
                 data_type: DataType::Int16,
                 column_id: ColumnId::from(3),
                 skip_parse: false,
+                fields: vec![],
             },
             SourceColumnDesc {
                 name: "i64".to_string(),
                 data_type: DataType::Int64,
                 column_id: ColumnId::from(4),
                 skip_parse: false,
+                fields: vec![],
             },
             SourceColumnDesc {
                 name: "f32".to_string(),
                 data_type: DataType::Float32,
                 column_id: ColumnId::from(5),
                 skip_parse: false,
+                fields: vec![],
             },
             SourceColumnDesc {
                 name: "f64".to_string(),
                 data_type: DataType::Float64,
                 column_id: ColumnId::from(6),
                 skip_parse: false,
+                fields: vec![],
             },
             SourceColumnDesc {
                 name: "varchar".to_string(),
                 data_type: DataType::Varchar,
                 column_id: ColumnId::from(7),
                 skip_parse: false,
+                fields: vec![],
             },
             SourceColumnDesc {
                 name: "date".to_string(),
                 data_type: DataType::Date,
                 column_id: ColumnId::from(8),
                 skip_parse: false,
+                fields: vec![],
             },
             SourceColumnDesc {
                 name: "timestamp".to_string(),
                 data_type: DataType::Timestamp,
                 column_id: ColumnId::from(9),
                 skip_parse: false,
+                fields: vec![],
             },
         ];
 
@@ -147,4 +167,108 @@ mod tests {
         let result = parser.parse(payload, &descs);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_json_parser_nested() {
+        let parser = JSONParser {};
+        let payload = r#"{"s":{"v1":1,"v2":"a"},"l":[1,null,3],"ls":[{"v1":2},{"v1":3,"v2":"b"}]}"#
+            .as_bytes();
+        let fields = vec![
+            ColumnDesc {
+                data_type: DataType::Int32,
+                column_id: ColumnId::from(0),
+                name: "v1".to_string(),
+                field_descs: vec![],
+                type_name: "".to_string(),
+            },
+            ColumnDesc {
+                data_type: DataType::Varchar,
+                column_id: ColumnId::from(0),
+                name: "v2".to_string(),
+                field_descs: vec![],
+                type_name: "".to_string(),
+            },
+        ];
+        let struct_type = DataType::Struct {
+            fields: vec![DataType::Int32, DataType::Varchar].into(),
+        };
+        let descs = vec![
+            SourceColumnDesc {
+                name: "s".to_string(),
+                data_type: struct_type.clone(),
+                column_id: ColumnId::from(0),
+                skip_parse: false,
+                fields: fields.clone(),
+            },
+            SourceColumnDesc {
+                name: "l".to_string(),
+                data_type: DataType::List {
+                    datatype: Box::new(DataType::Int32),
+                },
+                column_id: ColumnId::from(1),
+                skip_parse: false,
+                fields: vec![],
+            },
+            SourceColumnDesc {
+                name: "ls".to_string(),
+                data_type: DataType::List {
+                    datatype: Box::new(struct_type),
+                },
+                column_id: ColumnId::from(2),
+                skip_parse: false,
+                fields,
+            },
+        ];
+
+        let event = parser.parse(payload, &descs).unwrap();
+        let row = event.rows.first().unwrap();
+        let struct_value = |v1: i32, v2: Option<&str>| {
+            Some(ScalarImpl::Struct(StructValue::new(vec![
+                Some(ScalarImpl::Int32(v1)),
+                v2.map(|v| ScalarImpl::Utf8(v.to_string())),
+            ])))
+        };
+        assert_eq!(row[0], struct_value(1, Some("a")));
+        assert_eq!(
+            row[1],
+            Some(ScalarImpl::List(ListValue::new(vec![
+                Some(ScalarImpl::Int32(1)),
+                None,
+                Some(ScalarImpl::Int32(3)),
+            ])))
+        );
+        assert_eq!(
+            row[2],
+            Some(ScalarImpl::List(ListValue::new(vec![
+                struct_value(2, None),
+                struct_value(3, Some("b")),
+            ])))
+        );
+    }
+
+    #[test]
+    fn test_json_parser_flattened() {
+        let parser = JSONParser {};
+        let payload = r#"{"a":{"b":{"c":1}},"a.d":2,"e":[{"f":3}]}"#.as_bytes();
+        let column = |name: &str, data_type: DataType| SourceColumnDesc {
+            name: name.to_string(),
+            data_type,
+            column_id: ColumnId::from(0),
+            skip_parse: false,
+            fields: vec![],
+        };
+        let descs = vec![
+            column("a.b.c", DataType::Int32),
+            column("a.d", DataType::Int32),
+            column("a.b.x", DataType::Int32),
+            column("e", DataType::Varchar),
+        ];
+
+        let event = parser.parse(payload, &descs).unwrap();
+        let row = event.rows.first().unwrap();
+        assert_eq!(row[0], Some(ScalarImpl::Int32(1)));
+        assert_eq!(row[1], Some(ScalarImpl::Int32(2)));
+        assert_eq!(row[2], None);
+        assert_eq!(row[3], Some(ScalarImpl::Utf8(r#"[{"f":3}]"#.to_string())));
+    }
 }
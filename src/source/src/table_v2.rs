@@ -43,8 +43,8 @@ struct TableSourceV2Core {
 pub struct TableSourceV2 {
     core: RwLock<TableSourceV2Core>,
 
-    /// All columns in this table.
-    column_descs: Vec<ColumnDesc>,
+    /// All columns in this table, which may grow as columns are added.
+    column_descs: RwLock<Vec<ColumnDesc>>,
 
     /// Current allocated row id.
     next_row_id: AtomicUsize,
@@ -58,7 +58,7 @@ impl TableSourceV2 {
 
         Self {
             core: RwLock::new(core),
-            column_descs,
+            column_descs: RwLock::new(column_descs),
             next_row_id: 0.into(),
         }
     }
@@ -92,6 +92,17 @@ impl TableSourceV2 {
         Ok(notifier_rx)
     }
 
+    /// Appends nullable `columns` to the table, skipping those already added. The chunks written
+    /// afterwards contain the columns at the end.
+    pub fn add_columns(&self, columns: &[ColumnDesc]) {
+        let mut column_descs = self.column_descs.write().unwrap();
+        for column in columns {
+            if !column_descs.iter().any(|c| c.column_id == column.column_id) {
+                column_descs.push(column.clone());
+            }
+        }
+    }
+
     /// Write stream chunk into table using `write_chunk`, and then block until a reader consumes
     /// the chunk.
     ///
@@ -119,6 +130,10 @@ pub struct TableV2StreamReader {
 
     /// Mappings from the source column to the column to be read.
     column_indices: Vec<usize>,
+
+    /// Number of the columns of the table when the reader is created. The columns added
+    /// afterwards are read after the selected ones.
+    column_count: usize,
 }
 
 #[async_trait]
@@ -139,6 +154,7 @@ impl StreamSourceReader for TableV2StreamReader {
             .column_indices
             .iter()
             .map(|i| columns[*i].clone())
+            .chain(columns.iter().skip(self.column_count).cloned())
             .collect();
         let chunk = StreamChunk::new(ops, selected_columns, bitmap);
 
@@ -155,21 +171,28 @@ impl StreamSourceReader for TableV2StreamReader {
 impl TableSourceV2 {
     /// Create a new stream reader.
     pub async fn stream_reader(&self, column_ids: Vec<ColumnId>) -> Result<TableV2StreamReader> {
+        let column_descs = self.column_descs.read().unwrap();
         let column_indices = column_ids
             .into_iter()
             .map(|id| {
-                self.column_descs
+                column_descs
                     .iter()
                     .position(|c| c.column_id == id)
                     .expect("column id not exists")
             })
             .collect();
+        let column_count = column_descs.len();
+        drop(column_descs);
 
         let mut core = self.core.write().unwrap();
         let (tx, rx) = mpsc::unbounded_channel();
         core.changes_txs.push(tx);
 
-        Ok(TableV2StreamReader { rx, column_indices })
+        Ok(TableV2StreamReader {
+            rx,
+            column_indices,
+            column_count,
+        })
     }
 }
 
@@ -231,6 +254,42 @@ mod tests {
         write_chunk!(1);
         check_next_chunk!(1);
 
+        Ok(())
+    }
+    #[tokio::test]
+    async fn test_table_source_v2_add_columns() -> Result<()> {
+        let source = Arc::new(new_source());
+        let mut reader = source.stream_reader(vec![ColumnId::from(0)]).await?;
+
+        source.add_columns(&[ColumnDesc::unnamed(ColumnId::from(1), DataType::Int64)]);
+        // Adding the column again is a no-op.
+        source.add_columns(&[ColumnDesc::unnamed(ColumnId::from(1), DataType::Int64)]);
+        assert_eq!(source.column_descs.read().unwrap().len(), 2);
+
+        let chunk = StreamChunk::new(
+            vec![Op::Insert],
+            vec![
+                column_nonnull!(I64Array, [1]),
+                column_nonnull!(I64Array, [2]),
+            ],
+            None,
+        );
+        let writer = source.clone();
+        tokio::spawn(async move {
+            writer.blocking_write_chunk(chunk).await.unwrap();
+        });
+
+        // The reader created before reads the added column after the selected ones.
+        let chunk = reader.next().await?.chunk;
+        assert_eq!(
+            chunk
+                .columns()
+                .iter()
+                .map(|column| column.array_ref().as_int64().value_at(0))
+                .collect_vec(),
+            vec![Some(1), Some(2)]
+        );
+
         Ok(())
     }
 }
@@ -62,6 +62,8 @@ pub enum AlterTableOperation {
         column_name: Ident,
         op: AlterColumnOperation,
     },
+    /// `OWNER TO <new_owner_name>`
+    ChangeOwner { new_owner_name: Ident },
 }
 
 impl fmt::Display for AlterTableOperation {
@@ -113,6 +115,65 @@ impl fmt::Display for AlterTableOperation {
             AlterTableOperation::RenameConstraint { old_name, new_name } => {
                 write!(f, "RENAME CONSTRAINT {} TO {}", old_name, new_name)
             }
+            AlterTableOperation::ChangeOwner { new_owner_name } => {
+                write!(f, "OWNER TO {}", new_owner_name)
+            }
+        }
+    }
+}
+
+/// An `ALTER SOURCE` (`Statement::AlterSource`) operation
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum AlterSourceOperation {
+    /// `ADD [ COLUMN ] <column_def>`
+    AddColumn { column_def: ColumnDef },
+    /// `RENAME TO <source_name>`
+    RenameSource { source_name: ObjectName },
+    /// `OWNER TO <new_owner_name>`
+    ChangeOwner { new_owner_name: Ident },
+}
+
+impl fmt::Display for AlterSourceOperation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AlterSourceOperation::AddColumn { column_def } => {
+                write!(f, "ADD COLUMN {}", column_def)
+            }
+            AlterSourceOperation::RenameSource { source_name } => {
+                write!(f, "RENAME TO {}", source_name)
+            }
+            AlterSourceOperation::ChangeOwner { new_owner_name } => {
+                write!(f, "OWNER TO {}", new_owner_name)
+            }
+        }
+    }
+}
+
+/// An `ALTER [ MATERIALIZED ] VIEW` (`Statement::AlterView`) operation
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum AlterViewOperation {
+    /// `RENAME TO <view_name>`
+    RenameView { view_name: ObjectName },
+    /// `OWNER TO <new_owner_name>`
+    ChangeOwner { new_owner_name: Ident },
+    /// `SET PARALLELISM = <parallelism>`
+    SetParallelism { parallelism: u64 },
+}
+
+impl fmt::Display for AlterViewOperation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AlterViewOperation::RenameView { view_name } => {
+                write!(f, "RENAME TO {}", view_name)
+            }
+            AlterViewOperation::ChangeOwner { new_owner_name } => {
+                write!(f, "OWNER TO {}", new_owner_name)
+            }
+            AlterViewOperation::SetParallelism { parallelism } => {
+                write!(f, "SET PARALLELISM = {}", parallelism)
+            }
         }
     }
 }
@@ -241,7 +302,8 @@ impl fmt::Display for TableConstraint {
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct ColumnDef {
     pub name: Ident,
-    pub data_type: DataType,
+    /// `None` for a generated column, whose type is that of the expression.
+    pub data_type: Option<DataType>,
     pub collation: Option<ObjectName>,
     pub options: Vec<ColumnOptionDef>,
 }
@@ -255,16 +317,27 @@ impl ColumnDef {
     ) -> Self {
         ColumnDef {
             name,
-            data_type,
+            data_type: Some(data_type),
             collation,
             options,
         }
     }
+
+    /// Returns the expression of a generated column, i.e., `<name> AS <expr>`.
+    pub fn generated_expr(&self) -> Option<&Expr> {
+        self.options.iter().find_map(|option| match &option.option {
+            ColumnOption::GeneratedColumns(expr) => Some(expr),
+            _ => None,
+        })
+    }
 }
 
 impl fmt::Display for ColumnDef {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{} {}", self.name, self.data_type)?;
+        write!(f, "{}", self.name)?;
+        if let Some(data_type) = &self.data_type {
+            write!(f, " {}", data_type)?;
+        }
         for option in &self.options {
             write!(f, " {}", option)?;
         }
@@ -327,6 +400,8 @@ pub enum ColumnOption {
     },
     /// `CHECK (<expr>)`
     Check(Expr),
+    /// `AS <expr>`, a column computed from the other columns.
+    GeneratedColumns(Expr),
     /// Dialect-specific options, such as:
     /// - MySQL's `AUTO_INCREMENT` or SQLite's `AUTOINCREMENT`
     /// - ...
@@ -362,6 +437,7 @@ impl fmt::Display for ColumnOption {
                 Ok(())
             }
             Check(expr) => write!(f, "CHECK ({})", expr),
+            GeneratedColumns(expr) => write!(f, "AS {}", expr),
             DialectSpecific(val) => write!(f, "{}", display_separated(val, " ")),
         }
     }
@@ -32,8 +32,8 @@ use serde::{Deserialize, Serialize};
 
 pub use self::data_type::{DataType, StructField};
 pub use self::ddl::{
-    AlterColumnOperation, AlterTableOperation, ColumnDef, ColumnOption, ColumnOptionDef,
-    ReferentialAction, TableConstraint,
+    AlterColumnOperation, AlterSourceOperation, AlterTableOperation, AlterViewOperation, ColumnDef,
+    ColumnOption, ColumnOptionDef, ReferentialAction, TableConstraint,
 };
 pub use self::operator::{BinaryOperator, UnaryOperator};
 pub use self::query::{
@@ -260,6 +260,8 @@ pub enum Expr {
     Nested(Box<Expr>),
     /// A literal value, such as string, number, date or NULL
     Value(Value),
+    /// A positional parameter of a prepared statement, e.g. `$1`
+    Parameter { index: u64 },
     /// A constant of form `<data_type> 'value'`.
     /// This can represent ANSI SQL `DATE`, `TIME`, and `TIMESTAMP` literals (such as `DATE
     /// '2020-01-01'`), as well as constants of other types (a non-standard PostgreSQL extension).
@@ -359,6 +361,7 @@ impl fmt::Display for Expr {
             Expr::Collate { expr, collation } => write!(f, "{} COLLATE {}", expr, collation),
             Expr::Nested(ast) => write!(f, "({})", ast),
             Expr::Value(v) => write!(f, "{}", v),
+            Expr::Parameter { index } => write!(f, "${}", index),
             Expr::TypedString { data_type, value } => {
                 write!(f, "{}", data_type)?;
                 write!(f, " '{}'", &value::escape_single_quote_string(value))
@@ -622,6 +625,9 @@ pub enum ShowObject {
     Source { schema: Option<Ident> },
     MaterializedSource { schema: Option<Ident> },
     Columns { table: ObjectName },
+    InternalTable { schema: Option<Ident> },
+    Fragment { schema: Option<Ident> },
+    Event,
 }
 
 impl fmt::Display for ShowObject {
@@ -648,6 +654,11 @@ impl fmt::Display for ShowObject {
                 write!(f, "MATERIALIZED SOURCES{}", fmt_schema(schema))
             }
             ShowObject::Columns { table } => write!(f, "COLUMNS FROM {}", table),
+            ShowObject::InternalTable { schema } => {
+                write!(f, "INTERNAL TABLES{}", fmt_schema(schema))
+            }
+            ShowObject::Fragment { schema } => write!(f, "FRAGMENTS{}", fmt_schema(schema)),
+            ShowObject::Event => f.write_str("EVENTS"),
         }
     }
 }
@@ -696,6 +707,35 @@ pub enum Statement {
         /// VALUES a vector of values to be copied
         values: Vec<Option<String>>,
     },
+    /// COPY TO, exports the rows of a table or a materialized view as files
+    CopyTo {
+        /// TABLE
+        table_name: ObjectName,
+        /// COLUMNS, all the columns if empty
+        columns: Vec<Ident>,
+        /// The location to export the files to, e.g. `s3://bucket/path`
+        location: String,
+        /// WITH options, e.g. the format of the files
+        with_options: Vec<SqlOption>,
+    },
+    /// COPY TO STDOUT, sends the rows of a table or a query to the client
+    CopyToStdout {
+        /// The table or the query to copy the rows of
+        source: CopySource,
+        /// WITH options, e.g. the format of the rows
+        with_options: Vec<SqlOption>,
+    },
+    /// COPY FROM, imports the rows of files into a table
+    CopyFrom {
+        /// TABLE
+        table_name: ObjectName,
+        /// COLUMNS in the files, all the columns if empty
+        columns: Vec<Ident>,
+        /// The location of the files, e.g. `s3://bucket/path`
+        location: String,
+        /// WITH options, e.g. the format of the files
+        with_options: Vec<SqlOption>,
+    },
     /// UPDATE
     Update {
         /// TABLE
@@ -721,6 +761,8 @@ pub enum Statement {
         columns: Vec<Ident>,
         query: Box<Query>,
         with_options: Vec<SqlOption>,
+        /// `EMIT IMMEDIATELY | EMIT ON WINDOW CLOSE`
+        emit_mode: Option<EmitMode>,
     },
     /// CREATE TABLE
     CreateTable {
@@ -746,6 +788,22 @@ pub enum Statement {
         unique: bool,
         if_not_exists: bool,
     },
+    /// CREATE FUNCTION
+    ///
+    /// Postgres: <https://www.postgresql.org/docs/current/sql-createfunction.html>
+    CreateFunction {
+        name: ObjectName,
+        args: Vec<DataType>,
+        return_type: DataType,
+        language: Ident,
+        /// The body of the function, e.g. the module for `wasm`, or the name of the function on
+        /// the UDF server for external languages.
+        body: String,
+        /// The symbol to link in `body`, which defaults to the name of the function.
+        link_symbol: Option<String>,
+        /// Options of the function, e.g. the address of the UDF server for external functions.
+        with_options: Vec<SqlOption>,
+    },
     /// CREATE SOURCE
     CreateSource {
         is_materialized: bool,
@@ -757,6 +815,19 @@ pub enum Statement {
         name: ObjectName,
         operation: AlterTableOperation,
     },
+    /// ALTER SOURCE
+    AlterSource {
+        /// Source name
+        name: ObjectName,
+        operation: AlterSourceOperation,
+    },
+    /// ALTER [ MATERIALIZED ] VIEW
+    AlterView {
+        materialized: bool,
+        /// View name
+        name: ObjectName,
+        operation: AlterViewOperation,
+    },
     /// DESCRIBE TABLE OR SOURCE
     Describe {
         /// Table or Source name
@@ -847,17 +918,40 @@ pub enum Statement {
         data_types: Vec<DataType>,
         statement: Box<Statement>,
     },
+    /// `DECLARE name CURSOR FOR query`
+    DeclareCursor {
+        cursor_name: Ident,
+        query: Box<Query>,
+    },
+    /// `DECLARE name SUBSCRIPTION CURSOR FOR mview [ SINCE epoch ]`
+    DeclareSubscriptionCursor {
+        cursor_name: Ident,
+        mview_name: ObjectName,
+        since: Option<u64>,
+    },
+    /// `FETCH [ count | NEXT | ALL ] [ FROM | IN ] name`, where `count` is `None` for `ALL`
+    FetchCursor {
+        cursor_name: Ident,
+        count: Option<u64>,
+    },
+    /// `CLOSE { name | ALL }`, where `cursor_name` is `None` for `ALL`
+    CloseCursor { cursor_name: Option<Ident> },
     /// EXPLAIN / DESCRIBE for select_statement
     Explain {
         // If true, query used the MySQL `DESCRIBE` alias for explain
         describe_alias: bool,
         /// Carry out the command and show actual run times and other statistics.
         analyze: bool,
-        // Display additional information regarding the plan.
-        verbose: bool,
         /// A SQL query that specifies what to explain
         statement: Box<Statement>,
+        /// Options of the explain statement
+        options: ExplainOptions,
     },
+    /// EXPLAIN ANALYZE MATERIALIZED VIEW, which samples the runtime statistics of a running
+    /// materialized view.
+    ///
+    /// Note: RisingWave specific statement.
+    ExplainAnalyzeMaterializedView { name: ObjectName },
     /// CREATE USER
     CreateUser(CreateUserStatement),
     /// FLUSH the current barrier.
@@ -874,9 +968,9 @@ impl fmt::Display for Statement {
         match self {
             Statement::Explain {
                 describe_alias,
-                verbose,
                 analyze,
                 statement,
+                options,
             } => {
                 if *describe_alias {
                     write!(f, "DESCRIBE ")?;
@@ -888,12 +982,16 @@ impl fmt::Display for Statement {
                     write!(f, "ANALYZE ")?;
                 }
 
-                if *verbose {
-                    write!(f, "VERBOSE ")?;
+                let options = options.to_string();
+                if !options.is_empty() {
+                    write!(f, "{} ", options)?;
                 }
 
                 write!(f, "{}", statement)
             }
+            Statement::ExplainAnalyzeMaterializedView { name } => {
+                write!(f, "EXPLAIN ANALYZE MATERIALIZED VIEW {}", name)
+            }
             Statement::Query(s) => write!(f, "{}", s),
             Statement::Truncate { table_name } => {
                 write!(f, "TRUNCATE TABLE {}", table_name)?;
@@ -948,6 +1046,48 @@ impl fmt::Display for Statement {
                 }
                 write!(f, "\n\\.")
             }
+            Statement::CopyTo {
+                table_name,
+                columns,
+                location,
+                with_options,
+            } => {
+                write!(f, "COPY {}", table_name)?;
+                if !columns.is_empty() {
+                    write!(f, " ({})", display_comma_separated(columns))?;
+                }
+                write!(f, " TO '{}'", value::escape_single_quote_string(location))?;
+                if !with_options.is_empty() {
+                    write!(f, " WITH ({})", display_comma_separated(with_options))?;
+                }
+                Ok(())
+            }
+            Statement::CopyToStdout {
+                source,
+                with_options,
+            } => {
+                write!(f, "COPY {} TO STDOUT", source)?;
+                if !with_options.is_empty() {
+                    write!(f, " WITH ({})", display_comma_separated(with_options))?;
+                }
+                Ok(())
+            }
+            Statement::CopyFrom {
+                table_name,
+                columns,
+                location,
+                with_options,
+            } => {
+                write!(f, "COPY {}", table_name)?;
+                if !columns.is_empty() {
+                    write!(f, " ({})", display_comma_separated(columns))?;
+                }
+                write!(f, " FROM '{}'", value::escape_single_quote_string(location))?;
+                if !with_options.is_empty() {
+                    write!(f, " WITH ({})", display_comma_separated(with_options))?;
+                }
+                Ok(())
+            }
             Statement::Update {
                 table,
                 assignments,
@@ -998,6 +1138,7 @@ impl fmt::Display for Statement {
                 query,
                 materialized,
                 with_options,
+                emit_mode,
             } => {
                 write!(
                     f,
@@ -1012,7 +1153,11 @@ impl fmt::Display for Statement {
                 if !columns.is_empty() {
                     write!(f, " ({})", display_comma_separated(columns))?;
                 }
-                write!(f, " AS {}", query)
+                write!(f, " AS {}", query)?;
+                if let Some(emit_mode) = emit_mode {
+                    write!(f, " EMIT {}", emit_mode)?;
+                }
+                Ok(())
             }
             Statement::CreateTable {
                 name,
@@ -1077,6 +1222,32 @@ impl fmt::Display for Statement {
                 table_name = table_name,
                 columns = display_separated(columns, ",")
             ),
+            Statement::CreateFunction {
+                name,
+                args,
+                return_type,
+                language,
+                body,
+                link_symbol,
+                with_options,
+            } => {
+                write!(
+                    f,
+                    "CREATE FUNCTION {name}({args}) RETURNS {return_type} LANGUAGE {language} AS '{body}'",
+                    name = name,
+                    args = display_comma_separated(args),
+                    return_type = return_type,
+                    language = language,
+                    body = value::escape_single_quote_string(body),
+                )?;
+                if let Some(link_symbol) = link_symbol {
+                    write!(f, ", '{}'", value::escape_single_quote_string(link_symbol))?;
+                }
+                if !with_options.is_empty() {
+                    write!(f, " WITH ({})", display_comma_separated(with_options))?;
+                }
+                Ok(())
+            }
             Statement::CreateSource {
                 is_materialized,
                 stmt,
@@ -1093,6 +1264,20 @@ impl fmt::Display for Statement {
             Statement::AlterTable { name, operation } => {
                 write!(f, "ALTER TABLE {} {}", name, operation)
             }
+            Statement::AlterSource { name, operation } => {
+                write!(f, "ALTER SOURCE {} {}", name, operation)
+            }
+            Statement::AlterView {
+                materialized,
+                name,
+                operation,
+            } => write!(
+                f,
+                "ALTER {}VIEW {} {}",
+                if *materialized { "MATERIALIZED " } else { "" },
+                name,
+                operation
+            ),
             Statement::Drop(stmt) => write!(f, "DROP {}", stmt),
             Statement::SetVariable {
                 local,
@@ -1229,6 +1414,32 @@ impl fmt::Display for Statement {
                 }
                 write!(f, "AS {}", statement)
             }
+            Statement::DeclareCursor { cursor_name, query } => {
+                write!(f, "DECLARE {} CURSOR FOR {}", cursor_name, query)
+            }
+            Statement::DeclareSubscriptionCursor {
+                cursor_name,
+                mview_name,
+                since,
+            } => {
+                write!(
+                    f,
+                    "DECLARE {} SUBSCRIPTION CURSOR FOR {}",
+                    cursor_name, mview_name
+                )?;
+                if let Some(since) = since {
+                    write!(f, " SINCE {}", since)?;
+                }
+                Ok(())
+            }
+            Statement::FetchCursor { cursor_name, count } => match count {
+                Some(count) => write!(f, "FETCH {} FROM {}", count, cursor_name),
+                None => write!(f, "FETCH ALL FROM {}", cursor_name),
+            },
+            Statement::CloseCursor { cursor_name } => match cursor_name {
+                Some(cursor_name) => write!(f, "CLOSE {}", cursor_name),
+                None => write!(f, "CLOSE ALL"),
+            },
             Statement::Comment {
                 object_type,
                 object_name,
@@ -1251,6 +1462,56 @@ impl fmt::Display for Statement {
     }
 }
 
+/// The rows copied by `COPY ... TO STDOUT`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum CopySource {
+    /// The columns of a table, or all of them if `columns` is empty.
+    Table {
+        table_name: ObjectName,
+        columns: Vec<Ident>,
+    },
+    /// The result of a query.
+    Query(Box<Query>),
+}
+
+impl fmt::Display for CopySource {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CopySource::Table {
+                table_name,
+                columns,
+            } => {
+                write!(f, "{}", table_name)?;
+                if !columns.is_empty() {
+                    write!(f, " ({})", display_comma_separated(columns))?;
+                }
+                Ok(())
+            }
+            CopySource::Query(query) => write!(f, "({})", query),
+        }
+    }
+}
+
+/// When a materialized view emits the results of windowed aggregations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum EmitMode {
+    /// Emits the changes of a window as soon as they're computed.
+    Immediately,
+    /// Emits the final result of a window once the window is closed.
+    OnWindowClose,
+}
+
+impl fmt::Display for EmitMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EmitMode::Immediately => write!(f, "IMMEDIATELY"),
+            EmitMode::OnWindowClose => write!(f, "ON WINDOW CLOSE"),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[non_exhaustive]
@@ -1530,6 +1791,7 @@ pub enum ObjectType {
     MaterializedSource,
     Database,
     User,
+    Function,
 }
 
 impl fmt::Display for ObjectType {
@@ -1544,6 +1806,7 @@ impl fmt::Display for ObjectType {
             ObjectType::MaterializedSource => "MATERIALIZED SOURCE",
             ObjectType::Database => "DATABASE",
             ObjectType::User => "USER",
+            ObjectType::Function => "FUNCTION",
         })
     }
 }
@@ -1568,9 +1831,11 @@ impl ParseTo for ObjectType {
             ObjectType::Database
         } else if parser.parse_keyword(Keyword::USER) {
             ObjectType::User
+        } else if parser.parse_keyword(Keyword::FUNCTION) {
+            ObjectType::Function
         } else {
             return parser.expected(
-                "TABLE, VIEW, INDEX, MATERIALIZED VIEW, SOURCE, MATERIALIZED SOURCE, SCHEMA, DATABASE or USER after DROP",
+                "TABLE, VIEW, INDEX, MATERIALIZED VIEW, SOURCE, MATERIALIZED SOURCE, SCHEMA, DATABASE, USER or FUNCTION after DROP",
                 parser.peek_token(),
             );
         };
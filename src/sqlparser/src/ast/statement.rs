@@ -178,6 +178,14 @@ impl fmt::Display for CreateSourceStatement {
         let mut v: Vec<String> = vec![];
         impl_fmt_display!(if_not_exists => [Keyword::IF, Keyword::NOT, Keyword::EXISTS], v, self);
         impl_fmt_display!(source_name, v, self);
+        if !self.columns.is_empty() || !self.constraints.is_empty() {
+            let elements = self
+                .columns
+                .iter()
+                .map(ToString::to_string)
+                .chain(self.constraints.iter().map(ToString::to_string));
+            v.push(format!("({})", elements.join(", ")));
+        }
         impl_fmt_display!(with_properties, v, self);
         impl_fmt_display!([Keyword::ROW, Keyword::FORMAT], v);
         impl_fmt_display!(source_schema, v, self);
@@ -499,3 +507,88 @@ impl fmt::Display for DropMode {
         })
     }
 }
+
+/// Options of an `EXPLAIN` statement, given as `EXPLAIN [VERBOSE] [( option [, ...] )]`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ExplainOptions {
+    /// Display additional information regarding the plan.
+    pub verbose: bool,
+    /// Which plan to explain.
+    pub explain_type: ExplainType,
+    /// How to format the explained plan.
+    pub explain_format: ExplainFormat,
+}
+
+impl fmt::Display for ExplainOptions {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut options = vec![];
+        if self.verbose {
+            options.push("VERBOSE".to_string());
+        }
+        if self.explain_type != ExplainType::default() {
+            options.push(format!("TYPE {}", self.explain_type));
+        }
+        if self.explain_format != ExplainFormat::default() {
+            options.push(format!("FORMAT {}", self.explain_format));
+        }
+        match options.as_slice() {
+            [] => Ok(()),
+            [verbose] if self.verbose => write!(f, "{}", verbose),
+            _ => write!(f, "({})", options.iter().join(", ")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ExplainType {
+    /// The optimized logical plan.
+    Logical,
+    /// The physical batch or stream plan.
+    Physical,
+    /// The physical plan split into fragments connected by exchanges, as it is distributed to the
+    /// compute nodes.
+    DistSql,
+}
+
+impl Default for ExplainType {
+    fn default() -> Self {
+        ExplainType::Physical
+    }
+}
+
+impl fmt::Display for ExplainType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            ExplainType::Logical => "LOGICAL",
+            ExplainType::Physical => "PHYSICAL",
+            ExplainType::DistSql => "DISTSQL",
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ExplainFormat {
+    Text,
+    Json,
+    /// Graphviz dot.
+    Dot,
+}
+
+impl Default for ExplainFormat {
+    fn default() -> Self {
+        ExplainFormat::Text
+    }
+}
+
+impl fmt::Display for ExplainFormat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            ExplainFormat::Text => "TEXT",
+            ExplainFormat::Json => "JSON",
+            ExplainFormat::Dot => "DOT",
+        })
+    }
+}
@@ -179,12 +179,15 @@ define_keywords!(
     DIRECTORY,
     DISCONNECT,
     DISTINCT,
+    DISTSQL,
+    DOT,
     DOUBLE,
     DROP,
     DYNAMIC,
     EACH,
     ELEMENT,
     ELSE,
+    EMIT,
     ENCRYPTED,
     END,
     END_EXEC = "END-EXEC",
@@ -194,6 +197,7 @@ define_keywords!(
     ERROR,
     ESCAPE,
     EVENT,
+    EVENTS,
     EVERY,
     EXCEPT,
     EXEC,
@@ -215,6 +219,7 @@ define_keywords!(
     FOR,
     FOREIGN,
     FORMAT,
+    FRAGMENTS,
     FRAME_ROW,
     FREE,
     FROM,
@@ -236,6 +241,7 @@ define_keywords!(
     IF,
     IGNORE,
     ILIKE,
+    IMMEDIATELY,
     IN,
     INDEX,
     INDICATOR,
@@ -245,6 +251,7 @@ define_keywords!(
     INSERT,
     INT,
     INTEGER,
+    INTERNAL,
     INTERSECT,
     INTERSECTION,
     INTERVAL,
@@ -268,6 +275,7 @@ define_keywords!(
     LOCALTIME,
     LOCALTIMESTAMP,
     LOCATION,
+    LOGICAL,
     LOGIN,
     LOWER,
     MATCH,
@@ -322,6 +330,8 @@ define_keywords!(
     OVER,
     OVERLAPS,
     OVERLAY,
+    OWNER,
+    PARALLELISM,
     PARAMETER,
     PARQUET,
     PARTITION,
@@ -333,6 +343,7 @@ define_keywords!(
     PERCENTILE_DISC,
     PERCENT_RANK,
     PERIOD,
+    PHYSICAL,
     PORTION,
     POSITION,
     POSITION_REGEX,
@@ -403,6 +414,7 @@ define_keywords!(
     SETS,
     SHOW,
     SIMILAR,
+    SINCE,
     SMALLINT,
     SNAPSHOT,
     SOME,
@@ -422,10 +434,12 @@ define_keywords!(
     STDDEV_POP,
     STDDEV_SAMP,
     STDIN,
+    STDOUT,
     STORED,
     STRING,
     STRUCT,
     SUBMULTISET,
+    SUBSCRIPTION,
     SUBSTRING,
     SUBSTRING_REGEX,
     SUCCEEDS,
@@ -529,6 +543,7 @@ pub const RESERVED_FOR_TABLE_ALIAS: &[Keyword] = &[
     Keyword::UNION,
     Keyword::EXCEPT,
     Keyword::INTERSECT,
+    Keyword::EMIT,
     // Reserved only as a table alias in the `FROM`/`JOIN` clauses:
     Keyword::ON,
     Keyword::JOIN,
@@ -567,6 +582,7 @@ pub const RESERVED_FOR_COLUMN_ALIAS: &[Keyword] = &[
     Keyword::UNION,
     Keyword::EXCEPT,
     Keyword::INTERSECT,
+    Keyword::EMIT,
     Keyword::CLUSTER,
     // Reserved only as a column alias in the `SELECT` clause
     Keyword::FROM,
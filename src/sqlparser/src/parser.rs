@@ -200,6 +200,9 @@ impl Parser {
                 Keyword::EXECUTE => Ok(self.parse_execute()?),
                 Keyword::PREPARE => Ok(self.parse_prepare()?),
                 Keyword::COMMENT => Ok(self.parse_comment()?),
+                Keyword::DECLARE => Ok(self.parse_declare_cursor()?),
+                Keyword::FETCH => Ok(self.parse_fetch_cursor()?),
+                Keyword::CLOSE => Ok(self.parse_close_cursor()?),
                 Keyword::FLUSH => Ok(Statement::Flush),
                 _ => self.expected("an SQL statement", Token::Word(w)),
             },
@@ -466,6 +469,10 @@ impl Parser {
                 self.prev_token();
                 Ok(Expr::Value(self.parse_value()?))
             }
+            Token::Parameter(s) => match s.parse::<u64>() {
+                Ok(index) if index > 0 => Ok(Expr::Parameter { index }),
+                _ => parser_err!(format!("Invalid parameter ${}", s)),
+            },
 
             Token::LParen => {
                 let expr =
@@ -1393,11 +1400,66 @@ impl Parser {
             self.parse_create_database()
         } else if self.parse_keyword(Keyword::USER) {
             self.parse_create_user()
+        } else if self.parse_keyword(Keyword::FUNCTION) {
+            self.parse_create_function()
         } else {
             self.expected("an object type after CREATE", self.peek_token())
         }
     }
 
+    /// Parses `CREATE FUNCTION name ( [ argtype [, ...] ] ) RETURNS rettype { LANGUAGE lang | AS
+    /// 'body' [, 'link_symbol'] } ...`. The `LANGUAGE` and `AS` clauses can be in any order.
+    pub fn parse_create_function(&mut self) -> Result<Statement, ParserError> {
+        let name = self.parse_object_name()?;
+        self.expect_token(&Token::LParen)?;
+        let args = if self.consume_token(&Token::RParen) {
+            vec![]
+        } else {
+            let args = self.parse_comma_separated(Parser::parse_data_type)?;
+            self.expect_token(&Token::RParen)?;
+            args
+        };
+        self.expect_keyword(Keyword::RETURNS)?;
+        let return_type = self.parse_data_type()?;
+
+        let mut language = None;
+        let mut definition = None;
+        loop {
+            if language.is_none() && self.parse_keyword(Keyword::LANGUAGE) {
+                language = Some(self.parse_identifier()?);
+            } else if definition.is_none() && self.parse_keyword(Keyword::AS) {
+                let body = self.parse_literal_string()?;
+                let link_symbol = if self.consume_token(&Token::Comma) {
+                    Some(self.parse_literal_string()?)
+                } else {
+                    None
+                };
+                definition = Some((body, link_symbol));
+            } else {
+                break;
+            }
+        }
+        let language = match language {
+            Some(language) => language,
+            None => return self.expected("LANGUAGE", self.peek_token()),
+        };
+        let (body, link_symbol) = match definition {
+            Some(definition) => definition,
+            None => return self.expected("AS", self.peek_token()),
+        };
+        let with_options = self.parse_with_properties()?;
+
+        Ok(Statement::CreateFunction {
+            name,
+            args,
+            return_type,
+            language,
+            body,
+            link_symbol,
+            with_options,
+        })
+    }
+
     pub fn parse_create_schema(&mut self) -> Result<Statement, ParserError> {
         let if_not_exists = self.parse_keywords(&[Keyword::IF, Keyword::NOT, Keyword::EXISTS]);
         let schema_name = self.parse_object_name()?;
@@ -1430,6 +1492,11 @@ impl Parser {
         let with_options = self.parse_options(Keyword::WITH)?;
         self.expect_keyword(Keyword::AS)?;
         let query = Box::new(self.parse_query()?);
+        let emit_mode = if materialized && self.parse_keyword(Keyword::EMIT) {
+            Some(self.parse_emit_mode()?)
+        } else {
+            None
+        };
         // Optional `WITH [ CASCADED | LOCAL ] CHECK OPTION` is widely supported here.
         Ok(Statement::CreateView {
             name,
@@ -1438,9 +1505,23 @@ impl Parser {
             materialized,
             or_replace,
             with_options,
+            emit_mode,
         })
     }
 
+    pub fn parse_emit_mode(&mut self) -> Result<EmitMode, ParserError> {
+        if self.parse_keyword(Keyword::IMMEDIATELY) {
+            Ok(EmitMode::Immediately)
+        } else if self.parse_keywords(&[Keyword::ON, Keyword::WINDOW, Keyword::CLOSE]) {
+            Ok(EmitMode::OnWindowClose)
+        } else {
+            self.expected(
+                "IMMEDIATELY or ON WINDOW CLOSE after EMIT",
+                self.peek_token(),
+            )
+        }
+    }
+
     // CREATE [OR REPLACE]?
     // [MATERIALIZED] SOURCE
     // [IF NOT EXISTS]?
@@ -1563,7 +1644,19 @@ impl Parser {
 
     fn parse_column_def(&mut self) -> Result<ColumnDef, ParserError> {
         let name = self.parse_identifier()?;
-        let data_type = self.parse_data_type()?;
+        if self.parse_keyword(Keyword::AS) {
+            let expr = self.parse_expr()?;
+            return Ok(ColumnDef {
+                name,
+                data_type: None,
+                collation: None,
+                options: vec![ColumnOptionDef {
+                    name: None,
+                    option: ColumnOption::GeneratedColumns(expr),
+                }],
+            });
+        }
+        let data_type = Some(self.parse_data_type()?);
 
         let collation = if self.parse_keyword(Keyword::COLLATE) {
             Some(self.parse_object_name()?)
@@ -1745,8 +1838,65 @@ impl Parser {
     }
 
     pub fn parse_alter(&mut self) -> Result<Statement, ParserError> {
-        self.expect_keyword(Keyword::TABLE)?;
-        self.parse_alter_table()
+        if self.parse_keyword(Keyword::TABLE) {
+            self.parse_alter_table()
+        } else if self.parse_keyword(Keyword::SOURCE) {
+            self.parse_alter_source()
+        } else if self.parse_keyword(Keyword::VIEW) {
+            self.parse_alter_view(false)
+        } else if self.parse_keywords(&[Keyword::MATERIALIZED, Keyword::VIEW]) {
+            self.parse_alter_view(true)
+        } else {
+            self.expected(
+                "TABLE, SOURCE or [MATERIALIZED] VIEW after ALTER",
+                self.peek_token(),
+            )
+        }
+    }
+
+    pub fn parse_alter_view(&mut self, materialized: bool) -> Result<Statement, ParserError> {
+        let view_name = self.parse_object_name()?;
+        let operation = if self.parse_keywords(&[Keyword::RENAME, Keyword::TO]) {
+            let view_name = self.parse_object_name()?;
+            AlterViewOperation::RenameView { view_name }
+        } else if self.parse_keywords(&[Keyword::OWNER, Keyword::TO]) {
+            let new_owner_name = self.parse_identifier()?;
+            AlterViewOperation::ChangeOwner { new_owner_name }
+        } else if self.parse_keywords(&[Keyword::SET, Keyword::PARALLELISM]) {
+            if !self.consume_token(&Token::Eq) && !self.parse_keyword(Keyword::TO) {
+                return self.expected("= or TO after SET PARALLELISM", self.peek_token());
+            }
+            let parallelism = self.parse_literal_uint()?;
+            AlterViewOperation::SetParallelism { parallelism }
+        } else {
+            return self.expected("RENAME, OWNER or SET after ALTER VIEW", self.peek_token());
+        };
+        Ok(Statement::AlterView {
+            materialized,
+            name: view_name,
+            operation,
+        })
+    }
+
+    pub fn parse_alter_source(&mut self) -> Result<Statement, ParserError> {
+        let source_name = self.parse_object_name()?;
+        let operation = if self.parse_keyword(Keyword::ADD) {
+            let _ = self.parse_keyword(Keyword::COLUMN);
+            let column_def = self.parse_column_def()?;
+            AlterSourceOperation::AddColumn { column_def }
+        } else if self.parse_keywords(&[Keyword::RENAME, Keyword::TO]) {
+            let source_name = self.parse_object_name()?;
+            AlterSourceOperation::RenameSource { source_name }
+        } else if self.parse_keywords(&[Keyword::OWNER, Keyword::TO]) {
+            let new_owner_name = self.parse_identifier()?;
+            AlterSourceOperation::ChangeOwner { new_owner_name }
+        } else {
+            return self.expected("ADD, RENAME or OWNER after ALTER SOURCE", self.peek_token());
+        };
+        Ok(Statement::AlterSource {
+            name: source_name,
+            operation,
+        })
     }
 
     pub fn parse_alter_table(&mut self) -> Result<Statement, ParserError> {
@@ -1822,8 +1972,14 @@ impl Parser {
                 );
             };
             AlterTableOperation::AlterColumn { column_name, op }
+        } else if self.parse_keywords(&[Keyword::OWNER, Keyword::TO]) {
+            let new_owner_name = self.parse_identifier()?;
+            AlterTableOperation::ChangeOwner { new_owner_name }
         } else {
-            return self.expected("ADD, RENAME or DROP after ALTER TABLE", self.peek_token());
+            return self.expected(
+                "ADD, RENAME, DROP, ALTER or OWNER after ALTER TABLE",
+                self.peek_token(),
+            );
         };
         Ok(Statement::AlterTable {
             name: table_name,
@@ -1833,9 +1989,47 @@ impl Parser {
 
     /// Parse a copy statement
     pub fn parse_copy(&mut self) -> Result<Statement, ParserError> {
+        if self.consume_token(&Token::LParen) {
+            let query = self.parse_query()?;
+            self.expect_token(&Token::RParen)?;
+            self.expect_keywords(&[Keyword::TO, Keyword::STDOUT])?;
+            return Ok(Statement::CopyToStdout {
+                source: CopySource::Query(Box::new(query)),
+                with_options: self.parse_copy_to_stdout_options()?,
+            });
+        }
         let table_name = self.parse_object_name()?;
         let columns = self.parse_parenthesized_column_list(Optional)?;
-        self.expect_keywords(&[Keyword::FROM, Keyword::STDIN])?;
+        if self.parse_keyword(Keyword::TO) {
+            if self.parse_keyword(Keyword::STDOUT) {
+                return Ok(Statement::CopyToStdout {
+                    source: CopySource::Table {
+                        table_name,
+                        columns,
+                    },
+                    with_options: self.parse_copy_to_stdout_options()?,
+                });
+            }
+            let location = self.parse_literal_string()?;
+            let with_options = self.parse_with_properties()?;
+            return Ok(Statement::CopyTo {
+                table_name,
+                columns,
+                location,
+                with_options,
+            });
+        }
+        self.expect_keyword(Keyword::FROM)?;
+        if !self.parse_keyword(Keyword::STDIN) {
+            let location = self.parse_literal_string()?;
+            let with_options = self.parse_with_properties()?;
+            return Ok(Statement::CopyFrom {
+                table_name,
+                columns,
+                location,
+                with_options,
+            });
+        }
         self.expect_token(&Token::SemiColon)?;
         let values = self.parse_tsv();
         Ok(Statement::Copy {
@@ -1845,6 +2039,25 @@ impl Parser {
         })
     }
 
+    /// Parses the options of `COPY TO STDOUT`, where the legacy `WITH CSV [HEADER]` of Postgres
+    /// is the same as `WITH (format = 'csv' [, header = true])`.
+    fn parse_copy_to_stdout_options(&mut self) -> Result<Vec<SqlOption>, ParserError> {
+        if !self.parse_keywords(&[Keyword::WITH, Keyword::CSV]) {
+            return self.parse_with_properties();
+        }
+        let mut options = vec![SqlOption {
+            name: Ident::new("format"),
+            value: Value::SingleQuotedString("csv".to_string()),
+        }];
+        if self.parse_keyword(Keyword::HEADER) {
+            options.push(SqlOption {
+                name: Ident::new("header"),
+                value: Value::Boolean(true),
+            });
+        }
+        Ok(options)
+    }
+
     /// Parse a tab separated values in
     /// COPY payload
     fn parse_tsv(&mut self) -> Vec<Option<String>> {
@@ -2263,14 +2476,76 @@ impl Parser {
 
     pub fn parse_explain(&mut self, describe_alias: bool) -> Result<Statement, ParserError> {
         let analyze = self.parse_keyword(Keyword::ANALYZE);
-        let verbose = self.parse_keyword(Keyword::VERBOSE);
+        if analyze && self.parse_keywords(&[Keyword::MATERIALIZED, Keyword::VIEW]) {
+            return Ok(Statement::ExplainAnalyzeMaterializedView {
+                name: self.parse_object_name()?,
+            });
+        }
+        let mut options = ExplainOptions {
+            verbose: self.parse_keyword(Keyword::VERBOSE),
+            ..Default::default()
+        };
+
+        // Only treat the parenthesis as the start of the option list if it's followed by an
+        // option, so that parenthesized queries still work.
+        let has_option_list = self.peek_token() == Token::LParen
+            && match self.peek_nth_token(1) {
+                Token::Word(w) => {
+                    matches!(
+                        w.keyword,
+                        Keyword::VERBOSE | Keyword::TYPE | Keyword::FORMAT
+                    )
+                }
+                _ => false,
+            };
+        if has_option_list {
+            self.expect_token(&Token::LParen)?;
+            loop {
+                match self.expect_one_of_keywords(&[
+                    Keyword::VERBOSE,
+                    Keyword::TYPE,
+                    Keyword::FORMAT,
+                ])? {
+                    Keyword::VERBOSE => options.verbose = true,
+                    Keyword::TYPE => {
+                        options.explain_type = match self.expect_one_of_keywords(&[
+                            Keyword::LOGICAL,
+                            Keyword::PHYSICAL,
+                            Keyword::DISTSQL,
+                        ])? {
+                            Keyword::LOGICAL => ExplainType::Logical,
+                            Keyword::PHYSICAL => ExplainType::Physical,
+                            Keyword::DISTSQL => ExplainType::DistSql,
+                            _ => unreachable!(),
+                        }
+                    }
+                    Keyword::FORMAT => {
+                        options.explain_format = match self.expect_one_of_keywords(&[
+                            Keyword::TEXT,
+                            Keyword::JSON,
+                            Keyword::DOT,
+                        ])? {
+                            Keyword::TEXT => ExplainFormat::Text,
+                            Keyword::JSON => ExplainFormat::Json,
+                            Keyword::DOT => ExplainFormat::Dot,
+                            _ => unreachable!(),
+                        }
+                    }
+                    _ => unreachable!(),
+                }
+                if !self.consume_token(&Token::Comma) {
+                    break;
+                }
+            }
+            self.expect_token(&Token::RParen)?;
+        }
 
         let statement = self.parse_statement()?;
         Ok(Statement::Explain {
             describe_alias,
             analyze,
-            verbose,
             statement: Box::new(statement),
+            options,
         })
     }
 
@@ -2577,6 +2852,20 @@ impl Parser {
                         schema: self.parse_from_and_identifier()?,
                     }));
                 }
+                Keyword::INTERNAL => {
+                    self.expect_keyword(Keyword::TABLES)?;
+                    return Ok(Statement::ShowObjects(ShowObject::InternalTable {
+                        schema: self.parse_from_and_identifier()?,
+                    }));
+                }
+                Keyword::FRAGMENTS => {
+                    return Ok(Statement::ShowObjects(ShowObject::Fragment {
+                        schema: self.parse_from_and_identifier()?,
+                    }));
+                }
+                Keyword::EVENTS => {
+                    return Ok(Statement::ShowObjects(ShowObject::Event));
+                }
                 Keyword::DATABASES => {
                     return Ok(Statement::ShowObjects(ShowObject::Database));
                 }
@@ -3239,6 +3528,51 @@ impl Parser {
         })
     }
 
+    fn parse_declare_cursor(&mut self) -> Result<Statement, ParserError> {
+        let cursor_name = self.parse_identifier()?;
+        if self.parse_keyword(Keyword::SUBSCRIPTION) {
+            self.expect_keywords(&[Keyword::CURSOR, Keyword::FOR])?;
+            let mview_name = self.parse_object_name()?;
+            let since = if self.parse_keyword(Keyword::SINCE) {
+                Some(self.parse_literal_uint()?)
+            } else {
+                None
+            };
+            return Ok(Statement::DeclareSubscriptionCursor {
+                cursor_name,
+                mview_name,
+                since,
+            });
+        }
+        self.expect_keywords(&[Keyword::CURSOR, Keyword::FOR])?;
+        let query = Box::new(self.parse_query()?);
+        Ok(Statement::DeclareCursor { cursor_name, query })
+    }
+
+    fn parse_fetch_cursor(&mut self) -> Result<Statement, ParserError> {
+        let count = if self.parse_keyword(Keyword::ALL) {
+            None
+        } else if self.parse_keyword(Keyword::NEXT) {
+            Some(1)
+        } else if let Token::Number(_, _) = self.peek_token() {
+            Some(self.parse_literal_uint()?)
+        } else {
+            Some(1)
+        };
+        let _ = self.parse_one_of_keywords(&[Keyword::FROM, Keyword::IN]);
+        let cursor_name = self.parse_identifier()?;
+        Ok(Statement::FetchCursor { cursor_name, count })
+    }
+
+    fn parse_close_cursor(&mut self) -> Result<Statement, ParserError> {
+        let cursor_name = if self.parse_keyword(Keyword::ALL) {
+            None
+        } else {
+            Some(self.parse_identifier()?)
+        };
+        Ok(Statement::CloseCursor { cursor_name })
+    }
+
     fn parse_comment(&mut self) -> Result<Statement, ParserError> {
         self.expect_keyword(Keyword::ON)?;
         let token = self.next_token();
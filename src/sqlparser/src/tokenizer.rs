@@ -138,6 +138,8 @@ pub enum Token {
     PGSquareRoot,
     /// `||/` , a cube root math operator in PostgreSQL
     PGCubeRoot,
+    /// `$1`, a positional parameter of a prepared statement
+    Parameter(String),
 }
 
 impl fmt::Display for Token {
@@ -193,6 +195,7 @@ impl fmt::Display for Token {
             Token::ShiftRight => f.write_str(">>"),
             Token::PGSquareRoot => f.write_str("|/"),
             Token::PGCubeRoot => f.write_str("||/"),
+            Token::Parameter(ref s) => write!(f, "${}", s),
         }
     }
 }
@@ -578,6 +581,15 @@ impl<'a> Tokenizer<'a> {
                 }
                 '#' => self.consume_and_return(chars, Token::Sharp),
                 '@' => self.consume_and_return(chars, Token::AtSign),
+                '$' => {
+                    chars.next(); // consume the '$'
+                    let s = peeking_take_while(chars, |ch| matches!(ch, '0'..='9'));
+                    if s.is_empty() {
+                        Ok(Some(Token::Char('$')))
+                    } else {
+                        Ok(Some(Token::Parameter(s)))
+                    }
+                }
                 other => self.consume_and_return(chars, Token::Char(other)),
             },
             None => Ok(None),
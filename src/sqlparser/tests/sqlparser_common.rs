@@ -1510,7 +1510,7 @@ fn parse_alter_table() {
         } => {
             assert_eq!("tab", name.to_string());
             assert_eq!("foo", column_def.name.to_string());
-            assert_eq!("TEXT", column_def.data_type.to_string());
+            assert_eq!("TEXT", column_def.data_type.unwrap().to_string());
         }
         _ => unreachable!(),
     };
@@ -1738,10 +1738,10 @@ fn run_explain_analyze(query: &str, expected_verbose: bool, expected_analyze: bo
         Statement::Explain {
             describe_alias: _,
             analyze,
-            verbose,
             statement,
+            options,
         } => {
-            assert_eq!(verbose, expected_verbose);
+            assert_eq!(options.verbose, expected_verbose);
             assert_eq!(analyze, expected_analyze);
             assert_eq!("SELECT sqrt(id) FROM foo", statement.to_string());
         }
@@ -1761,6 +1761,28 @@ fn parse_explain_analyze_with_simple_select() {
     );
 }
 
+#[test]
+fn parse_explain_with_options() {
+    match verified_stmt("EXPLAIN (TYPE DISTSQL, FORMAT JSON) SELECT sqrt(id) FROM foo") {
+        Statement::Explain { options, .. } => assert_eq!(
+            options,
+            ExplainOptions {
+                verbose: false,
+                explain_type: ExplainType::DistSql,
+                explain_format: ExplainFormat::Json,
+            }
+        ),
+        _ => panic!("Unexpected Statement, must be Explain"),
+    }
+    one_statement_parses_to(
+        "EXPLAIN (VERBOSE, TYPE PHYSICAL) SELECT 1",
+        "EXPLAIN VERBOSE SELECT 1",
+    );
+    verified_stmt("EXPLAIN (VERBOSE, FORMAT DOT) SELECT 1");
+    verified_stmt("EXPLAIN (SELECT 1)");
+    assert!(parse_sql_statements("EXPLAIN (TYPE DOT) SELECT 1").is_err());
+}
+
 #[test]
 fn parse_named_argument_function() {
     let sql = "SELECT FUN(a => '1', b => '2') FROM foo";
@@ -2762,7 +2784,9 @@ fn parse_create_view() {
             or_replace,
             materialized,
             with_options,
+            emit_mode,
         } => {
+            assert_eq!(emit_mode, None);
             assert_eq!("myschema.myview", name.to_string());
             assert_eq!(Vec::<Ident>::new(), columns);
             assert_eq!("SELECT foo FROM bar", query.to_string());
@@ -2808,7 +2832,9 @@ fn parse_create_view_with_columns() {
             with_options,
             query,
             materialized,
+            emit_mode,
         } => {
+            assert_eq!(emit_mode, None);
             assert_eq!("v", name.to_string());
             assert_eq!(columns, vec![Ident::new("has"), Ident::new("cols")]);
             assert_eq!(with_options, vec![]);
@@ -2830,7 +2856,9 @@ fn parse_create_or_replace_view() {
             with_options,
             query,
             materialized,
+            emit_mode,
         } => {
+            assert_eq!(emit_mode, None);
             assert_eq!("v", name.to_string());
             assert_eq!(columns, vec![]);
             assert_eq!(with_options, vec![]);
@@ -2857,7 +2885,9 @@ fn parse_create_or_replace_materialized_view() {
             with_options,
             query,
             materialized,
+            emit_mode,
         } => {
+            assert_eq!(emit_mode, None);
             assert_eq!("v", name.to_string());
             assert_eq!(columns, vec![]);
             assert_eq!(with_options, vec![]);
@@ -2880,7 +2910,9 @@ fn parse_create_materialized_view() {
             query,
             materialized,
             with_options,
+            emit_mode,
         } => {
+            assert_eq!(emit_mode, None);
             assert_eq!("myschema.myview", name.to_string());
             assert_eq!(Vec::<Ident>::new(), columns);
             assert_eq!("SELECT foo FROM bar", query.to_string());
@@ -2892,6 +2924,31 @@ fn parse_create_materialized_view() {
     }
 }
 
+#[test]
+fn parse_create_materialized_view_with_emit_mode() {
+    let sql = "CREATE MATERIALIZED VIEW v AS SELECT window_end, count(*) FROM t GROUP BY window_end EMIT ON WINDOW CLOSE";
+    match verified_stmt(sql) {
+        Statement::CreateView { emit_mode, .. } => {
+            assert_eq!(emit_mode, Some(EmitMode::OnWindowClose));
+        }
+        _ => unreachable!(),
+    }
+    let sql = "CREATE MATERIALIZED VIEW v AS SELECT * FROM t EMIT IMMEDIATELY";
+    match verified_stmt(sql) {
+        Statement::CreateView { emit_mode, .. } => {
+            assert_eq!(emit_mode, Some(EmitMode::Immediately));
+        }
+        _ => unreachable!(),
+    }
+    let res = parse_sql_statements("CREATE MATERIALIZED VIEW v AS SELECT * FROM t EMIT ON CLOSE");
+    assert_eq!(
+        ParserError::ParserError(
+            "Expected IMMEDIATELY or ON WINDOW CLOSE after EMIT, found: CLOSE".to_string()
+        ),
+        res.unwrap_err()
+    );
+}
+
 #[test]
 fn parse_drop_table() {
     let sql = "DROP TABLE foo";
@@ -0,0 +1,129 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use risingwave_hummock_sdk::key::{key_with_epoch, user_key};
+use risingwave_object_store::object::{InMemObjectStore, ObjectStoreImpl};
+use risingwave_storage::hummock::iterator::{HummockIterator, ReadOptions};
+use risingwave_storage::hummock::value::HummockValue;
+use risingwave_storage::hummock::{
+    get_from_sstable, CachePolicy, SSTableBuilder, SSTableBuilderOptions, SSTableIterator,
+    SSTableIteratorType, Sstable, SstableStore, SstableStoreRef,
+};
+use risingwave_storage::monitor::{ObjectStoreMetrics, StoreLocalStatistic};
+
+const KEY_COUNT: usize = 100_000;
+
+fn key_of(idx: usize) -> Vec<u8> {
+    key_with_epoch(format!("key_test_{:08}", idx).into_bytes(), 233)
+}
+
+async fn build_sstable(sstable_store: &SstableStore) -> Sstable {
+    let mut builder = SSTableBuilder::new(SSTableBuilderOptions {
+        capacity: 256 << 20,
+        ..Default::default()
+    });
+    for idx in 0..KEY_COUNT {
+        builder.add(&key_of(idx), HummockValue::put(&b"value"[..]));
+    }
+    let (data, meta, _, _) = builder.finish();
+    let sst = Sstable::new(1, meta);
+    sstable_store
+        .put(sst.clone(), data, CachePolicy::Fill)
+        .await
+        .unwrap();
+    sst
+}
+
+/// The point get path before `get_from_sstable`, which seeks a new `SSTableIterator`.
+async fn get_by_iterator(
+    sst: Sstable,
+    sstable_store: SstableStoreRef,
+    read_options: Arc<ReadOptions>,
+    key: &[u8],
+) -> bool {
+    let table = sstable_store
+        .sstable(sst.id, &mut StoreLocalStatistic::default())
+        .await
+        .unwrap();
+    let mut iter = SSTableIterator::create(table, sstable_store, read_options);
+    iter.seek(key).await.unwrap();
+    iter.is_valid() && user_key(iter.key()) == user_key(key)
+}
+
+fn bench_point_get(c: &mut Criterion) {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let sstable_store = Arc::new(SstableStore::new(
+        Arc::new(ObjectStoreImpl::new(
+            Box::new(InMemObjectStore::new(false)),
+            Arc::new(ObjectStoreMetrics::unused()),
+        )),
+        "test".to_string(),
+        256 << 20,
+        64 << 20,
+    ));
+    let sst = runtime.block_on(build_sstable(&sstable_store));
+    println!("block count: {}", sst.block_count());
+    let keys = (0..KEY_COUNT).step_by(97).map(key_of).collect::<Vec<_>>();
+
+    let read_options = Arc::new(ReadOptions {
+        cache_policy: CachePolicy::Fill,
+        ..Default::default()
+    });
+    c.bench_function("point get - sstable iterator", |b| {
+        b.iter(|| {
+            runtime.block_on(async {
+                for key in &keys {
+                    assert!(
+                        get_by_iterator(
+                            sst.clone(),
+                            sstable_store.clone(),
+                            read_options.clone(),
+                            key
+                        )
+                        .await
+                    );
+                }
+            })
+        })
+    });
+
+    c.bench_function("point get - get_from_sstable", |b| {
+        b.iter(|| {
+            runtime.block_on(async {
+                let mut block_iter = None;
+                let mut stats = StoreLocalStatistic::default();
+                for key in &keys {
+                    let table = sstable_store.sstable(sst.id, &mut stats).await.unwrap();
+                    assert!(get_from_sstable(
+                        table.value(),
+                        &sstable_store,
+                        key,
+                        CachePolicy::Fill,
+                        &mut block_iter,
+                        &mut stats,
+                    )
+                    .await
+                    .unwrap()
+                    .is_some());
+                }
+            })
+        })
+    });
+}
+
+criterion_group!(benches, bench_point_get);
+criterion_main!(benches);
@@ -0,0 +1,60 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use risingwave_pb::hummock::{HummockVersion, Level};
+
+use super::CompactionGroupId;
+
+pub trait HummockVersionExt {
+    /// Gets the levels of a compaction group. Panics if the compaction group doesn't exist.
+    fn get_compaction_group_levels(&self, compaction_group_id: CompactionGroupId) -> &Vec<Level>;
+
+    fn get_compaction_group_levels_mut(
+        &mut self,
+        compaction_group_id: CompactionGroupId,
+    ) -> &mut Vec<Level>;
+
+    /// Gets the levels of all compaction groups, ordered by compaction group id and then level.
+    fn get_combined_levels(&self) -> Vec<&Level>;
+}
+
+impl HummockVersionExt for HummockVersion {
+    fn get_compaction_group_levels(&self, compaction_group_id: CompactionGroupId) -> &Vec<Level> {
+        &self
+            .levels
+            .get(&compaction_group_id.into())
+            .unwrap_or_else(|| panic!("compaction group {:?} not found", compaction_group_id))
+            .levels
+    }
+
+    fn get_compaction_group_levels_mut(
+        &mut self,
+        compaction_group_id: CompactionGroupId,
+    ) -> &mut Vec<Level> {
+        &mut self
+            .levels
+            .get_mut(&compaction_group_id.into())
+            .unwrap_or_else(|| panic!("compaction group {:?} not found", compaction_group_id))
+            .levels
+    }
+
+    fn get_combined_levels(&self) -> Vec<&Level> {
+        let mut groups = self.levels.iter().collect::<Vec<_>>();
+        groups.sort_by_key(|(compaction_group_id, _)| **compaction_group_id);
+        groups
+            .into_iter()
+            .flat_map(|(_, levels)| levels.levels.iter())
+            .collect()
+    }
+}
@@ -12,7 +12,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-#[derive(Debug, Copy, Clone, Eq, Hash, PartialEq)]
+pub mod hummock_version_ext;
+
+#[derive(Debug, Copy, Clone, Eq, Hash, PartialEq, Ord, PartialOrd)]
 pub struct CompactionGroupId(u64);
 
 impl From<u64> for CompactionGroupId {
@@ -27,7 +29,7 @@ impl From<CompactionGroupId> for u64 {
     }
 }
 
-#[derive(Copy, Clone, Eq, Hash, PartialEq)]
+#[derive(Debug, Copy, Clone, Eq, Hash, PartialEq, Ord, PartialOrd)]
 pub struct Prefix(u32);
 
 impl From<u32> for Prefix {
@@ -42,11 +44,25 @@ impl From<Prefix> for u32 {
     }
 }
 
-#[allow(dead_code)]
-struct CompactionGroup {
-    group_id: CompactionGroupId,
-    prefixes: Vec<Prefix>,
-    /// If `is_scheduled`, no need to notify scheduler again. Scheduler will reschedule it if
-    /// necessary, e.g. more compaction task available.
-    is_scheduled: bool,
+/// Compaction groups created on the first start of the meta node. Tables are assigned to one of
+/// them when they are created.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum StaticCompactionGroupId {
+    /// Internal state of streaming executors, and any key without a registered table prefix.
+    StateDefault = 1,
+    /// Materialized views, so that writes to internal state don't cause write amplification on
+    /// them, and vice versa.
+    MaterializedView = 2,
+}
+
+impl StaticCompactionGroupId {
+    pub fn all() -> [StaticCompactionGroupId; 2] {
+        [Self::StateDefault, Self::MaterializedView]
+    }
+}
+
+impl From<StaticCompactionGroupId> for CompactionGroupId {
+    fn from(c: StaticCompactionGroupId) -> Self {
+        Self(c as u64)
+    }
 }
@@ -70,6 +70,17 @@ pub fn user_key(full_key: &[u8]) -> &[u8] {
     split_key_epoch(full_key).0
 }
 
+/// Length of the table prefix of keys, i.e. `b't'` followed by the table id.
+pub const TABLE_PREFIX_LEN: usize = 5;
+
+/// Returns the prefix of the keys of table `table_id`.
+pub fn table_prefix(table_id: u32) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(TABLE_PREFIX_LEN);
+    buf.put_u8(b't');
+    buf.put_u32(table_id);
+    buf
+}
+
 /// Extract table id in key prefix
 pub fn get_table_id(full_key: &[u8]) -> Option<u32> {
     if full_key[0] == b't' {
@@ -0,0 +1,266 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Organization of L0 into sub-levels.
+//!
+//! Each flush appends a sub-level to L0, whose SSTs are sorted and usually non-overlapping, so
+//! that reads can binary-search the SSTs in a sub-level instead of checking every SST of L0.
+
+use std::cmp::Ordering;
+use std::collections::HashSet;
+
+use risingwave_pb::hummock::{Level, LevelType, SstableInfo, SubLevel};
+
+use crate::key::user_key;
+use crate::prost_key_range::KeyRangeExt;
+use crate::{HummockEpoch, HummockSSTableId};
+
+/// SSTs of a level that can be read as a whole, i.e. a sub-level of L0 or a level below L0.
+#[derive(Debug, Clone, Copy)]
+pub struct SstRun<'a> {
+    pub level_type: LevelType,
+    pub table_infos: &'a [SstableInfo],
+}
+
+impl<'a> SstRun<'a> {
+    pub fn is_overlapping(&self) -> bool {
+        self.level_type == LevelType::Overlapping
+    }
+
+    pub fn total_file_size(&self) -> u64 {
+        self.table_infos.iter().map(|sst| sst.file_size).sum()
+    }
+}
+
+/// Returns the sub-levels of L0 from the oldest to the newest, or the whole level as a single run
+/// for other levels.
+pub fn sst_runs(level: &Level) -> Vec<SstRun<'_>> {
+    if level.level_idx != 0 {
+        return vec![SstRun {
+            level_type: level.level_type(),
+            table_infos: &level.table_infos,
+        }];
+    }
+    let mut runs = Vec::with_capacity(level.sub_levels.len());
+    let mut offset = 0;
+    for sub_level in &level.sub_levels {
+        let end = std::cmp::min(
+            offset + sub_level.table_count as usize,
+            level.table_infos.len(),
+        );
+        runs.push(SstRun {
+            level_type: sub_level.level_type(),
+            table_infos: &level.table_infos[offset..end],
+        });
+        offset = end;
+    }
+    // SSTs committed before sub-levels were introduced.
+    runs.extend(
+        level.table_infos[offset..]
+            .chunks(1)
+            .map(|table_infos| SstRun {
+                level_type: LevelType::Overlapping,
+                table_infos,
+            }),
+    );
+    runs
+}
+
+/// Whether the SSTs are sorted by their key ranges without overlapping each other.
+pub fn is_non_overlapping(ssts: &[SstableInfo]) -> bool {
+    ssts.windows(2).all(|pair| {
+        let left = pair[0].key_range.as_ref().unwrap();
+        let right = pair[1].key_range.as_ref().unwrap();
+        !left.inf
+            && !right.inf
+            && user_key(&left.right).cmp(user_key(&right.left)) == Ordering::Less
+    })
+}
+
+/// Creates a sub-level of `ssts`, which are sorted if they don't overlap. Overlapping SSTs are kept
+/// in their original order, so that newer SSTs still come later.
+fn new_sub_level(sub_level_id: u64, ssts: &mut Vec<SstableInfo>) -> SubLevel {
+    let mut sorted = ssts.clone();
+    sorted.sort_by(|a, b| {
+        a.key_range
+            .as_ref()
+            .unwrap()
+            .compare(b.key_range.as_ref().unwrap())
+    });
+    let level_type = if is_non_overlapping(&sorted) {
+        *ssts = sorted;
+        LevelType::Nonoverlapping
+    } else {
+        LevelType::Overlapping
+    };
+    SubLevel {
+        sub_level_id,
+        level_type: level_type as i32,
+        table_count: ssts.len() as u32,
+    }
+}
+
+/// Makes every SST of L0 covered by a sub-level.
+fn normalize_l0(l0: &mut Level) {
+    let covered: usize = l0
+        .sub_levels
+        .iter()
+        .map(|sub_level| sub_level.table_count as usize)
+        .sum();
+    for _ in covered..l0.table_infos.len() {
+        l0.sub_levels.push(SubLevel {
+            sub_level_id: 0,
+            level_type: LevelType::Overlapping as i32,
+            table_count: 1,
+        });
+    }
+}
+
+/// Appends the SSTs flushed in `epoch` to L0 as a new sub-level.
+pub fn add_l0_sub_level(l0: &mut Level, epoch: HummockEpoch, mut ssts: Vec<SstableInfo>) {
+    assert_eq!(l0.level_idx, 0);
+    if ssts.is_empty() {
+        return;
+    }
+    normalize_l0(l0);
+    l0.sub_levels.push(new_sub_level(epoch, &mut ssts));
+    l0.total_file_size += ssts.iter().map(|sst| sst.file_size).sum::<u64>();
+    l0.table_infos.extend(ssts);
+}
+
+/// Removes the SSTs in `removed` from L0, and inserts `outputs` as a new sub-level in place of the
+/// first sub-level with removed SSTs. Sub-levels that become empty are dropped.
+pub fn replace_l0_ssts(
+    l0: &mut Level,
+    removed: &HashSet<HummockSSTableId>,
+    mut outputs: Vec<SstableInfo>,
+) {
+    assert_eq!(l0.level_idx, 0);
+    normalize_l0(l0);
+    let output_sub_level_id = sst_runs(l0)
+        .iter()
+        .zip(l0.sub_levels.iter())
+        .filter(|(run, _)| run.table_infos.iter().any(|sst| removed.contains(&sst.id)))
+        .map(|(_, sub_level)| sub_level.sub_level_id)
+        .max()
+        .unwrap_or_default();
+
+    let mut sub_levels = Vec::with_capacity(l0.sub_levels.len());
+    let mut table_infos = Vec::with_capacity(l0.table_infos.len());
+    let mut old_table_infos = std::mem::take(&mut l0.table_infos).into_iter();
+    let mut output_inserted = outputs.is_empty();
+    for mut sub_level in std::mem::take(&mut l0.sub_levels) {
+        let mut kept = 0;
+        let mut any_removed = false;
+        for sst in old_table_infos
+            .by_ref()
+            .take(sub_level.table_count as usize)
+        {
+            if removed.contains(&sst.id) {
+                any_removed = true;
+            } else {
+                table_infos.push(sst);
+                kept += 1;
+            }
+        }
+        if kept > 0 {
+            sub_level.table_count = kept;
+            sub_levels.push(sub_level);
+        }
+        if any_removed && !output_inserted {
+            sub_levels.push(new_sub_level(output_sub_level_id, &mut outputs));
+            table_infos.append(&mut outputs);
+            output_inserted = true;
+        }
+    }
+    assert!(output_inserted, "the replaced SSTs are not in L0");
+    l0.total_file_size = table_infos.iter().map(|sst| sst.file_size).sum();
+    l0.table_infos = table_infos;
+    l0.sub_levels = sub_levels;
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_pb::hummock::KeyRange;
+
+    use super::*;
+    use crate::key::key_with_epoch;
+
+    fn sst(id: u64, left: &str, right: &str) -> SstableInfo {
+        SstableInfo {
+            id,
+            key_range: Some(KeyRange::new(
+                key_with_epoch(left.as_bytes().to_vec(), 1),
+                key_with_epoch(right.as_bytes().to_vec(), 1),
+            )),
+            file_size: 1,
+            vnode_bitmaps: vec![],
+            blob_ids: vec![],
+        }
+    }
+
+    fn run_ids(l0: &Level) -> Vec<(bool, Vec<u64>)> {
+        sst_runs(l0)
+            .iter()
+            .map(|run| {
+                (
+                    run.is_overlapping(),
+                    run.table_infos.iter().map(|sst| sst.id).collect(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_l0_sub_levels() {
+        let mut l0 = Level {
+            level_idx: 0,
+            level_type: LevelType::Overlapping as i32,
+            // Committed before sub-levels were introduced.
+            table_infos: vec![sst(1, "a", "z")],
+            total_file_size: 1,
+            sub_levels: vec![],
+        };
+        assert_eq!(run_ids(&l0), vec![(true, vec![1])]);
+
+        add_l0_sub_level(&mut l0, 2, vec![sst(3, "n", "z"), sst(2, "a", "m")]);
+        add_l0_sub_level(&mut l0, 3, vec![sst(4, "a", "m"), sst(5, "k", "z")]);
+        add_l0_sub_level(&mut l0, 4, vec![sst(6, "a", "z")]);
+        assert_eq!(
+            run_ids(&l0),
+            vec![
+                (true, vec![1]),
+                (false, vec![2, 3]),
+                (true, vec![4, 5]),
+                (false, vec![6]),
+            ]
+        );
+        assert_eq!(l0.total_file_size, 6);
+
+        // Compact the two sub-levels in the middle.
+        let removed = HashSet::from([2, 3, 4, 5]);
+        replace_l0_ssts(&mut l0, &removed, vec![sst(8, "n", "z"), sst(7, "a", "m")]);
+        assert_eq!(
+            run_ids(&l0),
+            vec![(true, vec![1]), (false, vec![7, 8]), (false, vec![6])]
+        );
+        assert_eq!(l0.sub_levels[1].sub_level_id, 3);
+        assert_eq!(l0.total_file_size, 4);
+
+        // Compact SSTs to L1.
+        replace_l0_ssts(&mut l0, &HashSet::from([1, 7, 8]), vec![]);
+        assert_eq!(run_ids(&l0), vec![(false, vec![6])]);
+        assert_eq!(l0.total_file_size, 1);
+    }
+}
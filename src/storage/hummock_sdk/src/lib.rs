@@ -18,6 +18,7 @@ pub mod compact;
 pub mod compaction_group;
 pub mod key;
 pub mod key_range;
+pub mod level;
 pub mod prost_key_range;
 
 pub type HummockSSTableId = u64;
@@ -145,6 +145,10 @@ impl BlockCache {
         self.inner.get_memory_usage()
     }
 
+    pub fn set_capacity(&self, capacity: usize) {
+        self.inner.set_capacity(capacity);
+    }
+
     #[cfg(test)]
     pub fn clear(&self) {
         // This is only a method for test. Therefore it should be safe to call the unsafe method.
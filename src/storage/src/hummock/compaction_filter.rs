@@ -0,0 +1,162 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::{HashMap, HashSet};
+
+use risingwave_common::util::epoch::Epoch;
+use risingwave_hummock_sdk::key::{get_epoch, get_table_id};
+use risingwave_pb::hummock::{CompactTask, TableOption};
+
+/// A `CompactionFilter` decides whether compaction deletes a key. It's asked about the versions of
+/// a user key from the latest to the oldest, and once it deletes a version, the older versions of
+/// the user key are deleted as well.
+pub trait CompactionFilter: Send {
+    fn should_delete(&mut self, full_key: &[u8]) -> bool;
+}
+
+/// Deletes the keys of the dropped tables.
+pub struct DroppedTableCompactionFilter {
+    dropped_table_ids: HashSet<u32>,
+}
+
+impl DroppedTableCompactionFilter {
+    pub fn new(dropped_table_ids: impl IntoIterator<Item = u32>) -> Self {
+        Self {
+            dropped_table_ids: dropped_table_ids.into_iter().collect(),
+        }
+    }
+}
+
+impl CompactionFilter for DroppedTableCompactionFilter {
+    fn should_delete(&mut self, full_key: &[u8]) -> bool {
+        get_table_id(full_key).map_or(false, |table_id| self.dropped_table_ids.contains(&table_id))
+    }
+}
+
+/// Returns the epoch before which the keys of a table with `retention_seconds` are expired at
+/// `now`.
+pub fn expire_epoch(retention_seconds: u32, now: Epoch) -> u64 {
+    let retention_ms = retention_seconds as u64 * 1000;
+    Epoch::from_physical_time(now.physical_time().saturating_sub(retention_ms)).0
+}
+
+/// Deletes the keys written earlier than the retention of their tables before `now`.
+pub struct TtlCompactionFilter {
+    /// The keys of a table written in the epochs before its expire epoch are deleted.
+    expire_epochs: HashMap<u32, u64>,
+}
+
+impl TtlCompactionFilter {
+    pub fn new(table_options: &HashMap<u32, TableOption>, now: Epoch) -> Self {
+        let expire_epochs = table_options
+            .iter()
+            .filter(|(_, option)| option.retention_seconds > 0)
+            .map(|(table_id, option)| (*table_id, expire_epoch(option.retention_seconds, now)))
+            .collect();
+        Self { expire_epochs }
+    }
+}
+
+impl CompactionFilter for TtlCompactionFilter {
+    fn should_delete(&mut self, full_key: &[u8]) -> bool {
+        get_table_id(full_key)
+            .and_then(|table_id| self.expire_epochs.get(&table_id))
+            .map_or(false, |expire_epoch| get_epoch(full_key) < *expire_epoch)
+    }
+}
+
+/// Deletes a key if any of the filters deletes it.
+#[derive(Default)]
+pub struct MultiCompactionFilter {
+    filters: Vec<Box<dyn CompactionFilter>>,
+}
+
+impl MultiCompactionFilter {
+    /// Builds the filters of the dropped tables and the retention of tables in `compact_task`.
+    pub fn for_compact_task(compact_task: &CompactTask) -> Self {
+        let mut filter = Self::default();
+        if !compact_task.dropped_table_ids.is_empty() {
+            filter.register(Box::new(DroppedTableCompactionFilter::new(
+                compact_task.dropped_table_ids.iter().copied(),
+            )));
+        }
+        if !compact_task.table_options.is_empty() {
+            filter.register(Box::new(TtlCompactionFilter::new(
+                &compact_task.table_options,
+                Epoch::now(),
+            )));
+        }
+        filter
+    }
+
+    pub fn register(&mut self, filter: Box<dyn CompactionFilter>) {
+        self.filters.push(filter);
+    }
+}
+
+impl CompactionFilter for MultiCompactionFilter {
+    fn should_delete(&mut self, full_key: &[u8]) -> bool {
+        self.filters
+            .iter_mut()
+            .any(|filter| filter.should_delete(full_key))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_hummock_sdk::key::{key_with_epoch, table_prefix};
+
+    use super::*;
+
+    fn full_key(table_id: u32, epoch: Epoch) -> Vec<u8> {
+        let mut user_key = table_prefix(table_id);
+        user_key.extend_from_slice(b"key");
+        key_with_epoch(user_key, epoch.0)
+    }
+
+    #[test]
+    fn test_dropped_table_compaction_filter() {
+        let mut filter = DroppedTableCompactionFilter::new([2]);
+        assert!(!filter.should_delete(&full_key(1, Epoch(1))));
+        assert!(filter.should_delete(&full_key(2, Epoch(1))));
+        assert!(!filter.should_delete(&key_with_epoch(b"key".to_vec(), 1)));
+    }
+
+    #[test]
+    fn test_ttl_compaction_filter() {
+        let now = Epoch::from_physical_time(100_000);
+        let table_options = [
+            (
+                1,
+                TableOption {
+                    retention_seconds: 10,
+                },
+            ),
+            (
+                2,
+                TableOption {
+                    retention_seconds: 0,
+                },
+            ),
+        ]
+        .into_iter()
+        .collect();
+        let mut filter = TtlCompactionFilter::new(&table_options, now);
+        assert!(filter.should_delete(&full_key(1, Epoch::from_physical_time(89_999))));
+        assert!(!filter.should_delete(&full_key(1, Epoch::from_physical_time(90_000))));
+        // Tables without retention keep their keys forever.
+        assert!(!filter.should_delete(&full_key(2, Epoch(1))));
+        assert!(!filter.should_delete(&full_key(3, Epoch(1))));
+    }
+}
@@ -0,0 +1,74 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use parking_lot::RwLock;
+use risingwave_hummock_sdk::compaction_group::{CompactionGroupId, Prefix};
+use risingwave_rpc_client::HummockMetaClient;
+
+use crate::hummock::{HummockError, HummockResult};
+
+/// Caches which compaction group the state of each table goes to, as registered in meta.
+pub struct CompactionGroupClient {
+    hummock_meta_client: Arc<dyn HummockMetaClient>,
+    index: RwLock<Arc<HashMap<Prefix, CompactionGroupId>>>,
+}
+
+impl CompactionGroupClient {
+    pub fn new(hummock_meta_client: Arc<dyn HummockMetaClient>) -> Self {
+        Self {
+            hummock_meta_client,
+            index: Default::default(),
+        }
+    }
+
+    /// Returns the compaction groups of all registered tables. The cache is refreshed from meta if
+    /// any of `prefixes` is unknown. Tables that are still unknown afterwards are not registered,
+    /// and their state goes to the default compaction group.
+    pub async fn get_compaction_groups(
+        &self,
+        prefixes: impl IntoIterator<Item = Prefix>,
+    ) -> HummockResult<Arc<HashMap<Prefix, CompactionGroupId>>> {
+        let index = self.index.read().clone();
+        let mut prefixes = prefixes.into_iter();
+        if prefixes.all(|prefix| index.contains_key(&prefix)) {
+            return Ok(index);
+        }
+        self.refresh().await
+    }
+
+    async fn refresh(&self) -> HummockResult<Arc<HashMap<Prefix, CompactionGroupId>>> {
+        let compaction_groups = self
+            .hummock_meta_client
+            .get_compaction_groups()
+            .await
+            .map_err(HummockError::meta_error)?;
+        let index = Arc::new(
+            compaction_groups
+                .into_iter()
+                .flat_map(|compaction_group| {
+                    let group_id = CompactionGroupId::from(compaction_group.id);
+                    compaction_group
+                        .member_table_ids
+                        .into_iter()
+                        .map(move |table_id| (Prefix::from(table_id), group_id))
+                })
+                .collect::<HashMap<_, _>>(),
+        );
+        *self.index.write() = index.clone();
+        Ok(index)
+    }
+}
@@ -25,6 +25,9 @@ use itertools::Itertools;
 use risingwave_common::config::StorageConfig;
 use risingwave_common::util::compress::decompress_data;
 use risingwave_hummock_sdk::compact::compact_task_to_string;
+use risingwave_hummock_sdk::compaction_group::{
+    CompactionGroupId, Prefix, StaticCompactionGroupId,
+};
 use risingwave_hummock_sdk::key::{get_epoch, Epoch, FullKey};
 use risingwave_hummock_sdk::key_range::KeyRange;
 use risingwave_hummock_sdk::{HummockSSTableId, VersionedComparator};
@@ -34,11 +37,14 @@ use risingwave_rpc_client::HummockMetaClient;
 use tokio::sync::oneshot::Sender;
 use tokio::task::JoinHandle;
 
-use super::group_builder::KeyValueGroupingImpl::VirtualNode;
-use super::group_builder::{GroupedSstableBuilder, VirtualNodeGrouping};
+use super::group_builder::KeyValueGroupingImpl::{CompactionGroup, VirtualNode};
+use super::group_builder::{
+    CompactionGroupGrouping, GroupedSstableBuilder, KeyValueGroupingImpl, VirtualNodeGrouping,
+};
 use super::iterator::{BoxedForwardHummockIterator, ConcatIterator, MergeIterator};
 use super::{HummockResult, SSTableBuilder, SSTableIterator, SSTableIteratorType, Sstable};
 use crate::hummock::compaction_executor::CompactionExecutor;
+use crate::hummock::compaction_filter::{CompactionFilter, MultiCompactionFilter};
 use crate::hummock::iterator::ReadOptions;
 use crate::hummock::shared_buffer::shared_buffer_uploader::UploadTaskPayload;
 use crate::hummock::shared_buffer::{build_ordered_merge_iter, UncommittedData};
@@ -102,7 +108,11 @@ pub struct Compactor {
     compact_task: CompactTask,
 }
 
-pub type CompactOutput = (usize, Vec<(Sstable, Vec<VNodeBitmap>)>);
+/// The SSTs built for a split, with their vnode bitmaps and the blob files they refer to.
+pub type CompactOutput = (
+    usize,
+    Vec<(Sstable, Vec<VNodeBitmap>, Vec<HummockSSTableId>)>,
+);
 
 impl Compactor {
     /// Create a new compactor.
@@ -117,7 +127,8 @@ impl Compactor {
     pub async fn compact_shared_buffer(
         context: Arc<CompactorContext>,
         payload: &UploadTaskPayload,
-    ) -> HummockResult<Vec<(Sstable, Vec<VNodeBitmap>)>> {
+        compaction_groups: Arc<HashMap<Prefix, CompactionGroupId>>,
+    ) -> HummockResult<Vec<(Sstable, Vec<VNodeBitmap>, Vec<HummockSSTableId>)>> {
         let mut start_user_keys = payload
             .iter()
             .flat_map(|data_list| data_list.iter().map(UncommittedData::start_user_key))
@@ -156,10 +167,15 @@ impl Compactor {
             is_target_ultimate_and_leveling: false,
             metrics: None,
             task_status: false,
-            // TODO: get compaction group info from meta
             prefix_pairs: vec![],
             // VNode mappings are not required when compacting shared buffer to L0
             vnode_mappings: vec![],
+            // The compaction group of each output SST is decided by meta on commit.
+            compaction_group_id: StaticCompactionGroupId::StateDefault.into(),
+            gc_blob_ids: vec![],
+            // Nothing is deleted when flushing the shared buffer.
+            dropped_table_ids: vec![],
+            table_options: HashMap::default(),
         };
 
         let sstable_store = context.sstable_store.clone();
@@ -171,8 +187,6 @@ impl Compactor {
         let mut compaction_futures = vec![];
         let compactor = Compactor::new(context, compact_task.clone());
 
-        let vnode2unit: Arc<HashMap<u32, Vec<u32>>> = Arc::new(HashMap::new());
-
         let mut local_stats = StoreLocalStatistic::default();
         for (split_index, _) in compact_task.splits.iter().enumerate() {
             let compactor = compactor.clone();
@@ -184,11 +198,11 @@ impl Compactor {
                 Arc::new(ReadOptions::default()),
             )
             .await? as BoxedForwardHummockIterator;
-            let vnode2unit = vnode2unit.clone();
+            let grouping = CompactionGroup(CompactionGroupGrouping::new(compaction_groups.clone()));
             let compaction_executor = compactor.context.compaction_executor.as_ref().cloned();
             let split_task = async move {
                 compactor
-                    .compact_key_range(split_index, iter, vnode2unit)
+                    .compact_key_range(split_index, iter, grouping)
                     .await
             };
             let rx = Compactor::request_execution(compaction_executor, split_task)?;
@@ -218,7 +232,7 @@ impl Compactor {
             let mut level0 = Vec::with_capacity(parallelism);
 
             for (_, sst) in output_ssts {
-                for (table, _) in &sst {
+                for (table, _, _) in &sst {
                     compactor
                         .context
                         .stats
@@ -353,7 +367,11 @@ impl Compactor {
             let split_task = async move {
                 let merge_iter = compactor.build_sst_iter().await?;
                 compactor
-                    .compact_key_range(split_index, merge_iter, vnode2unit)
+                    .compact_key_range(
+                        split_index,
+                        merge_iter,
+                        VirtualNode(VirtualNodeGrouping::new(vnode2unit)),
+                    )
                     .await
             };
             let rx = match Compactor::request_execution(compaction_executor, split_task) {
@@ -405,7 +423,7 @@ impl Compactor {
             .reserve(self.compact_task.splits.len());
         let mut compaction_write_bytes = 0;
         for (_, ssts) in output_ssts {
-            for (sst, vnode_bitmaps) in ssts {
+            for (sst, vnode_bitmaps, blob_ids) in ssts {
                 let sst_info = SstableInfo {
                     id: sst.id,
                     key_range: Some(risingwave_pb::hummock::KeyRange {
@@ -415,6 +433,7 @@ impl Compactor {
                     }),
                     file_size: sst.meta.estimated_size as u64,
                     vnode_bitmaps,
+                    blob_ids,
                 };
                 compaction_write_bytes += sst_info.file_size;
                 self.compact_task.sorted_output_ssts.push(sst_info);
@@ -450,7 +469,7 @@ impl Compactor {
         &self,
         split_index: usize,
         iter: BoxedForwardHummockIterator,
-        vnode2unit: Arc<HashMap<u32, Vec<u32>>>,
+        grouping: KeyValueGroupingImpl,
     ) -> HummockResult<CompactOutput> {
         let split = self.compact_task.splits[split_index].clone();
         let kr = KeyRange {
@@ -467,11 +486,12 @@ impl Compactor {
                 let timer = Instant::now();
                 let table_id = (self.context.sstable_id_generator)().await?;
                 let cost = (timer.elapsed().as_secs_f64() * 1000000.0).round() as u64;
-                let builder = SSTableBuilder::new(self.context.options.as_ref().into());
+                let builder = SSTableBuilder::new(self.context.options.as_ref().into())
+                    .with_blob_file(table_id);
                 get_id_time.fetch_add(cost, Ordering::Relaxed);
                 Ok((table_id, builder))
             },
-            VirtualNode(VirtualNodeGrouping::new(vnode2unit)),
+            grouping,
         );
 
         // Monitor time cost building shared buffer to SSTs.
@@ -486,6 +506,7 @@ impl Compactor {
             iter,
             !self.compact_task.is_target_ultimate_and_leveling,
             self.compact_task.watermark,
+            MultiCompactionFilter::for_compact_task(&self.compact_task),
         )
         .await?;
 
@@ -499,22 +520,26 @@ impl Compactor {
         let mut pending_requests = vec![];
         let files = builder.finish();
         let file_count = files.len();
-        for (table_id, data, meta, vnode_bitmaps) in files {
-            let sst = Sstable { id: table_id, meta };
+        for (table_id, data, meta, vnode_bitmaps, blobs) in files {
+            let sst = Sstable::new(table_id, meta);
             let len = data.len();
-            ssts.push((sst.clone(), vnode_bitmaps));
+            ssts.push((
+                sst.clone(),
+                vnode_bitmaps,
+                blobs.blob_ids.into_iter().collect_vec(),
+            ));
+            let sstable_store = self.context.sstable_store.clone();
+            let upload = async move {
+                // The blob file goes first, so that the SST never refers to a missing blob file.
+                if let Some(blob_data) = blobs.data {
+                    sstable_store.put_blob(sst.id, blob_data).await?;
+                }
+                sstable_store.put(sst, data, CachePolicy::Fill).await
+            };
             if file_count > 1 {
-                let sstable_store = self.context.sstable_store.clone();
-                let ret =
-                    tokio::spawn(
-                        async move { sstable_store.put(sst, data, CachePolicy::Fill).await },
-                    );
-                pending_requests.push(ret);
+                pending_requests.push(tokio::spawn(upload));
             } else {
-                self.context
-                    .sstable_store
-                    .put(sst, data, CachePolicy::Fill)
-                    .await?;
+                upload.await?;
             }
 
             if self.context.is_share_buffer_compact {
@@ -545,7 +570,12 @@ impl Compactor {
     async fn build_sst_iter(&self) -> HummockResult<BoxedForwardHummockIterator> {
         let mut table_iters: Vec<BoxedForwardHummockIterator> = Vec::new();
         let mut stats = StoreLocalStatistic::default();
-        let read_options = Arc::new(ReadOptions { prefetch: true });
+        let read_options = Arc::new(ReadOptions {
+            prefetch: true,
+            keep_blob_refs: true,
+            gc_blob_ids: self.compact_task.gc_blob_ids.iter().copied().collect(),
+            ..Default::default()
+        });
         for level in &self.compact_task.input_ssts {
             if level.table_infos.is_empty() {
                 continue;
@@ -725,6 +755,7 @@ impl Compactor {
         mut iter: BoxedForwardHummockIterator,
         has_user_key_overlap: bool,
         watermark: Epoch,
+        mut compaction_filter: impl CompactionFilter,
     ) -> HummockResult<()>
     where
         B: Clone + Fn() -> F,
@@ -766,6 +797,13 @@ impl Compactor {
                 last_key.extend_from_slice(iter_key);
             }
 
+            // The older versions of a deleted key are deleted as well.
+            if compaction_filter.should_delete(iter_key) {
+                skip_key = BytesMut::from(iter_key);
+                iter.next().await?;
+                continue;
+            }
+
             let epoch = get_epoch(iter_key);
 
             // Among keys with same user key, only retain keys which satisfy `epoch` >= `watermark`,
@@ -19,8 +19,12 @@ mod tests {
 
     use bytes::Bytes;
     use risingwave_common::config::StorageConfig;
+    use risingwave_hummock_sdk::compaction_group::hummock_version_ext::HummockVersionExt;
+    use risingwave_hummock_sdk::compaction_group::StaticCompactionGroupId;
+    use risingwave_hummock_sdk::key::table_prefix;
     use risingwave_meta::hummock::test_utils::setup_compute_env;
     use risingwave_meta::hummock::MockHummockMetaClient;
+    use risingwave_pb::hummock::TableOption;
     use risingwave_rpc_client::HummockMetaClient;
 
     use crate::hummock::compactor::{get_remote_sstable_id_generator, Compactor, CompactorContext};
@@ -105,7 +109,7 @@ mod tests {
 
         // 2. get compact task
         let compact_task = hummock_manager_ref
-            .get_compact_task()
+            .get_compact_task(StaticCompactionGroupId::StateDefault.into())
             .await
             .unwrap()
             .unwrap();
@@ -126,7 +130,7 @@ mod tests {
         // 4. get the latest version and check
         let version = hummock_manager_ref.get_current_version().await;
         let output_table_id = version
-            .get_levels()
+            .get_compaction_group_levels(StaticCompactionGroupId::StateDefault.into())
             .last()
             .unwrap()
             .table_infos
@@ -154,8 +158,95 @@ mod tests {
         assert_eq!(get_val, val);
 
         // 6. get compact task and there should be none
-        let compact_task = hummock_manager_ref.get_compact_task().await.unwrap();
+        let compact_task = hummock_manager_ref
+            .get_compact_task(StaticCompactionGroupId::StateDefault.into())
+            .await
+            .unwrap();
 
         assert!(compact_task.is_none());
     }
+
+    #[tokio::test]
+    async fn test_compaction_drop_dropped_table() {
+        let (_env, hummock_manager_ref, _cluster_manager_ref, worker_node) =
+            setup_compute_env(8080).await;
+        let hummock_meta_client = Arc::new(MockHummockMetaClient::new(
+            hummock_manager_ref.clone(),
+            worker_node.id,
+        ));
+        let storage = get_hummock_storage(hummock_meta_client.clone()).await;
+        let compact_ctx = CompactorContext {
+            options: storage.options().clone(),
+            sstable_store: storage.sstable_store(),
+            hummock_meta_client: hummock_meta_client.clone(),
+            stats: Arc::new(StateStoreMetrics::unused()),
+            is_share_buffer_compact: false,
+            sstable_id_generator: get_remote_sstable_id_generator(hummock_meta_client.clone()),
+            compaction_executor: None,
+        };
+        let compaction_group_manager = hummock_manager_ref.compaction_group_manager();
+        compaction_group_manager
+            .register_table_ids(&[(
+                1.into(),
+                StaticCompactionGroupId::StateDefault.into(),
+                TableOption::default(),
+            )])
+            .await
+            .unwrap();
+
+        // 1. add sstables of table 1 and table 2
+        let key_of = |table_id: u32| {
+            let mut key = table_prefix(table_id);
+            key.extend_from_slice(b"key");
+            Bytes::from(key)
+        };
+        let val = Bytes::from(&b"0"[..]);
+        let mut epoch: u64 = 1;
+        for _ in 0..2 {
+            epoch += 1;
+            storage
+                .ingest_batch(
+                    vec![
+                        (key_of(1), StorageValue::new_default_put(val.clone())),
+                        (key_of(2), StorageValue::new_default_put(val.clone())),
+                    ],
+                    epoch,
+                )
+                .await
+                .unwrap();
+            storage.sync(Some(epoch)).await.unwrap();
+            hummock_meta_client
+                .commit_epoch(
+                    epoch,
+                    storage.local_version_manager.get_uncommitted_ssts(epoch),
+                )
+                .await
+                .unwrap();
+        }
+
+        // 2. drop table 1 and compact
+        compaction_group_manager
+            .unregister_table_ids(&[1.into()])
+            .await
+            .unwrap();
+        let compact_task = hummock_manager_ref
+            .get_compact_task(StaticCompactionGroupId::StateDefault.into())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(compact_task.dropped_table_ids, vec![1]);
+        hummock_manager_ref
+            .assign_compaction_task(&compact_task, worker_node.id, async { true })
+            .await
+            .unwrap();
+        Compactor::compact(Arc::new(compact_ctx), compact_task.clone()).await;
+
+        // 3. only the keys of table 2 remain
+        let version = hummock_manager_ref.get_current_version().await;
+        storage
+            .local_version_manager()
+            .try_update_pinned_version(version);
+        assert!(storage.get(&key_of(1), epoch).await.unwrap().is_none());
+        assert_eq!(storage.get(&key_of(2), epoch).await.unwrap().unwrap(), val);
+    }
 }
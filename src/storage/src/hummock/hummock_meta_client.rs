@@ -17,7 +17,8 @@ use std::sync::Arc;
 use async_trait::async_trait;
 use risingwave_common::error::{ErrorCode, Result};
 use risingwave_pb::hummock::{
-    CompactTask, HummockVersion, SstableInfo, SubscribeCompactTasksResponse, VacuumTask,
+    CompactTask, CompactionGroup, HummockVersion, SstReadStatistics, SstableInfo,
+    SubscribeCompactTasksResponse, VacuumTask,
 };
 use risingwave_rpc_client::{HummockMetaClient, MetaClient};
 use tonic::Streaming;
@@ -102,4 +103,12 @@ impl HummockMetaClient for MonitoredHummockMetaClient {
     async fn report_vacuum_task(&self, vacuum_task: VacuumTask) -> Result<()> {
         self.meta_client.report_vacuum_task(vacuum_task).await
     }
+
+    async fn get_compaction_groups(&self) -> Result<Vec<CompactionGroup>> {
+        self.meta_client.get_compaction_groups().await
+    }
+
+    async fn report_read_statistics(&self, stats: Vec<SstReadStatistics>) -> Result<()> {
+        self.meta_client.report_read_statistics(stats).await
+    }
 }
@@ -53,6 +53,9 @@ pub struct BackwardUserIterator {
     /// Only reads values if `epoch <= self.read_epoch`.
     read_epoch: Epoch,
 
+    /// A key is expired if its latest value is written before `min_epoch`.
+    min_epoch: Epoch,
+
     /// Ensures the SSTs needed by `iterator` won't be vacuumed.
     _version: Option<Arc<PinnedVersion>>,
 }
@@ -83,10 +86,17 @@ impl BackwardUserIterator {
             last_val: Vec::new(),
             last_delete: true,
             read_epoch,
+            min_epoch: 0,
             _version: version,
         }
     }
 
+    /// Skips the keys whose latest values are written before `min_epoch`.
+    pub(crate) fn with_min_epoch(mut self, min_epoch: u64) -> Self {
+        self.min_epoch = min_epoch;
+        self
+    }
+
     fn out_of_range(&self, key: &[u8]) -> bool {
         match &self.key_range.0 {
             Included(begin_key) => key < begin_key.as_slice(),
@@ -174,14 +184,18 @@ impl BackwardUserIterator {
 
                 // 1 and 2(a)
                 match self.iterator.value() {
-                    HummockValue::Put(_, val) => {
+                    HummockValue::Put(_, val) if epoch >= self.min_epoch => {
                         self.last_val.clear();
                         self.last_val.extend_from_slice(val);
                         self.last_delete = false;
                     }
-                    HummockValue::Delete(_) => {
+                    // Expired keys are regarded as deleted.
+                    HummockValue::Put(..) | HummockValue::Delete(_) => {
                         self.last_delete = true;
                     }
+                    HummockValue::Blob(..) => {
+                        unreachable!("values in blob files are read by SST iterators")
+                    }
                 }
             }
             self.iterator.next().await?;
@@ -269,12 +283,14 @@ impl DirectedUserIteratorBuilder for BackwardUserIterator {
         stats: Arc<StateStoreMetrics>,
         key_range: (Bound<Vec<u8>>, Bound<Vec<u8>>),
         read_epoch: u64,
+        min_epoch: u64,
         version: Option<Arc<PinnedVersion>>,
     ) -> DirectedUserIterator {
         let iterator = UnorderedMergeIteratorInner::<Backward>::new(iterator_iter, stats);
-        DirectedUserIterator::Backward(BackwardUserIterator::with_epoch(
-            iterator, key_range, read_epoch, version,
-        ))
+        DirectedUserIterator::Backward(
+            BackwardUserIterator::with_epoch(iterator, key_range, read_epoch, version)
+                .with_min_epoch(min_epoch),
+        )
     }
 }
 
@@ -872,7 +888,7 @@ mod tests {
                 }
                 match inserts.first_key_value().unwrap().1 {
                     HummockValue::Put(_, _) => 1,
-                    HummockValue::Delete(_) => 0,
+                    _ => 0,
                 }
             })
             .reduce(|accum, item| accum + item)
@@ -1084,9 +1100,6 @@ mod tests {
     }
 
     fn clone_sst(sst: &Sstable) -> Sstable {
-        Sstable {
-            id: sst.id,
-            meta: sst.meta.clone(),
-        }
+        Sstable::new(sst.id, sst.meta.clone())
     }
 }
@@ -62,17 +62,24 @@ impl<TI: SSTableIteratorType> ConcatIteratorInner<TI> {
         }
     }
 
-    /// Seeks to a table, and then seeks to the key if `seek_key` is given.
+    /// Seeks to a table, and then seeks to the key if `seek_key` is given. Tables that surely
+    /// don't contain the prefix in `read_options` are skipped.
     async fn seek_idx(&mut self, idx: usize, seek_key: Option<&[u8]>) -> HummockResult<()> {
-        if idx >= self.tables.len() {
-            if let Some(old_iter) = self.sstable_iter.take() {
-                old_iter.collect_local_statistic(&mut self.stats);
-            }
-        } else {
+        if let Some(old_iter) = self.sstable_iter.take() {
+            old_iter.collect_local_statistic(&mut self.stats);
+        }
+        for idx in idx..self.tables.len() {
             let table = self
                 .sstable_store
                 .sstable(self.tables[idx].id, &mut self.stats)
                 .await?;
+            if let Some(prefix_hint) = self.read_options.prefix_hint.as_ref() {
+                self.sstable_store.load_filter(table.value()).await?;
+                if table.value().surely_not_have_table_prefix(prefix_hint) {
+                    self.stats.bloom_filter_true_negative_count += 1;
+                    continue;
+                }
+            }
             let mut sstable_iter =
                 TI::create(table, self.sstable_store.clone(), self.read_options.clone());
 
@@ -82,12 +89,9 @@ impl<TI: SSTableIteratorType> ConcatIteratorInner<TI> {
                 sstable_iter.rewind().await?;
             }
 
-            if let Some(old_iter) = self.sstable_iter.take() {
-                old_iter.collect_local_statistic(&mut self.stats);
-            }
-
             self.sstable_iter = Some(sstable_iter);
             self.cur_idx = idx;
+            break;
         }
         Ok(())
     }
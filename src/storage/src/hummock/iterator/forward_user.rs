@@ -35,12 +35,14 @@ pub enum DirectedUserIterator {
 pub trait DirectedUserIteratorBuilder {
     type Direction: HummockIteratorDirection;
     /// Initialize an `DirectedUserIterator`.
-    /// The `key_range` should be from smaller key to larger key.
+    /// The `key_range` should be from smaller key to larger key. The keys whose latest values
+    /// are written before `min_epoch` are regarded as expired.
     fn create(
         iterator_iter: impl IntoIterator<Item = BoxedHummockIterator<Self::Direction>>,
         stats: Arc<StateStoreMetrics>,
         key_range: (Bound<Vec<u8>>, Bound<Vec<u8>>),
         read_epoch: u64,
+        min_epoch: u64,
         version: Option<Arc<PinnedVersion>>,
     ) -> DirectedUserIterator;
 }
@@ -113,6 +115,9 @@ pub struct UserIterator {
     /// Only reads values if `ts <= self.read_epoch`.
     read_epoch: Epoch,
 
+    /// A key is expired if its latest value is written before `min_epoch`.
+    min_epoch: Epoch,
+
     /// Ensures the SSTs needed by `iterator` won't be vacuumed.
     _version: Option<Arc<PinnedVersion>>,
 }
@@ -142,10 +147,17 @@ impl UserIterator {
             last_key: Vec::new(),
             last_val: Vec::new(),
             read_epoch,
+            min_epoch: 0,
             _version: version,
         }
     }
 
+    /// Skips the keys whose latest values are written before `min_epoch`.
+    pub(crate) fn with_min_epoch(mut self, min_epoch: u64) -> Self {
+        self.min_epoch = min_epoch;
+        self
+    }
+
     /// Gets the iterator move to the next step.
     ///
     /// Returned result:
@@ -163,9 +175,9 @@ impl UserIterator {
                 self.last_key.clear();
                 self.last_key.extend_from_slice(key);
 
-                // handle delete operation
+                // handle delete operation and expired keys
                 match self.iterator.value() {
-                    HummockValue::Put(_, val) => {
+                    HummockValue::Put(_, val) if epoch >= self.min_epoch => {
                         self.last_val.clear();
                         self.last_val.extend_from_slice(val);
 
@@ -179,8 +191,11 @@ impl UserIterator {
                     }
                     // It means that the key is deleted from the storage.
                     // Deleted kv and the previous versions (if any) of the key should not be
-                    // returned to user.
-                    HummockValue::Delete(_) => {}
+                    // returned to user. So are expired keys.
+                    HummockValue::Put(..) | HummockValue::Delete(_) => {}
+                    HummockValue::Blob(..) => {
+                        unreachable!("values in blob files are read by SST iterators")
+                    }
                 }
             }
 
@@ -269,10 +284,13 @@ impl DirectedUserIteratorBuilder for UserIterator {
         stats: Arc<StateStoreMetrics>,
         key_range: (Bound<Vec<u8>>, Bound<Vec<u8>>),
         read_epoch: u64,
+        min_epoch: u64,
         version: Option<Arc<PinnedVersion>>,
     ) -> DirectedUserIterator {
         let iterator = UnorderedMergeIteratorInner::<Forward>::new(iterator_iter, stats);
-        DirectedUserIterator::Forward(Self::new(iterator, key_range, read_epoch, version))
+        DirectedUserIterator::Forward(
+            Self::new(iterator, key_range, read_epoch, version).with_min_epoch(min_epoch),
+        )
     }
 }
 
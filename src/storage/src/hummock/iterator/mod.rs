@@ -34,8 +34,12 @@ pub use merge_inner::{OrderedMergeIteratorInner, UnorderedMergeIteratorInner};
 #[cfg(test)]
 pub(crate) mod test_utils;
 
+use std::collections::HashSet;
+
 use async_trait::async_trait;
+use risingwave_hummock_sdk::HummockSSTableId;
 
+use crate::hummock::{BlobRef, CachePolicy};
 use crate::monitor::StoreLocalStatistic;
 
 /// `HummockIterator` defines the interface of all iterators, including `SSTableIterator`,
@@ -148,4 +152,21 @@ impl HummockIteratorDirection for Backward {
 #[derive(Default)]
 pub struct ReadOptions {
     pub prefetch: bool,
+    /// Table prefix of all the keys to read, if any. SSTs whose bloom filter rules out the prefix
+    /// are skipped.
+    pub prefix_hint: Option<Vec<u8>>,
+    /// Whether the data blocks read are filled into the block cache.
+    pub cache_policy: CachePolicy,
+    /// Whether values separated into blob files are returned as [`HummockValue::Blob`] instead of
+    /// being read from the blob files. Compaction sets it to carry blob refs over to its outputs.
+    pub keep_blob_refs: bool,
+    /// Blob files whose values are read even if `keep_blob_refs` is set, so that the values are
+    /// rewritten and the blob files can be deleted.
+    pub gc_blob_ids: HashSet<HummockSSTableId>,
+}
+
+impl ReadOptions {
+    pub fn should_read_blob(&self, blob_ref: &BlobRef) -> bool {
+        !self.keep_blob_refs || self.gc_blob_ids.contains(&blob_ref.blob_id)
+    }
 }
@@ -17,6 +17,7 @@ use std::sync::Arc;
 
 use parking_lot::lock_api::ArcRwLockReadGuard;
 use parking_lot::{RawRwLock, RwLock};
+use risingwave_hummock_sdk::compaction_group::hummock_version_ext::HummockVersionExt;
 use risingwave_hummock_sdk::{HummockEpoch, HummockVersionId};
 use risingwave_pb::hummock::{HummockVersion, Level};
 use tokio::sync::mpsc::UnboundedSender;
@@ -63,6 +64,10 @@ impl LocalVersion {
             .clone()
     }
 
+    pub fn clear_shared_buffer(&mut self) {
+        self.shared_buffer.clear();
+    }
+
     pub fn set_pinned_version(&mut self, new_pinned_version: HummockVersion) {
         // Clean shared buffer and uncommitted ssts below (<=) new max committed epoch
         if self.pinned_version.max_committed_epoch() < new_pinned_version.max_committed_epoch {
@@ -131,8 +136,9 @@ impl PinnedVersion {
         self.version.id
     }
 
-    pub fn levels(&self) -> &Vec<Level> {
-        &self.version.levels
+    /// Levels of all compaction groups. The state of a table is in exactly one of them.
+    pub fn levels(&self) -> Vec<&Level> {
+        self.version.get_combined_levels()
     }
 
     pub fn max_committed_epoch(&self) -> u64 {
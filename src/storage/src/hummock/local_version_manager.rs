@@ -19,8 +19,9 @@ use std::time::Duration;
 
 use bytes::Bytes;
 use itertools::Itertools;
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
 use risingwave_common::config::StorageConfig;
+use risingwave_hummock_sdk::compaction_group::hummock_version_ext::HummockVersionExt;
 use risingwave_hummock_sdk::key::FullKey;
 use risingwave_pb::hummock::{HummockVersion, SstableInfo};
 use risingwave_rpc_client::HummockMetaClient;
@@ -46,8 +47,11 @@ use crate::monitor::StateStoreMetrics;
 use crate::storage_value::StorageValue;
 
 struct WorkerContext {
-    version_update_notifier_tx: tokio::sync::watch::Sender<HummockVersionId>,
+    /// Sends the max committed epoch of the pinned version whenever a new version is pinned.
+    committed_epoch_tx: tokio::sync::watch::Sender<HummockEpoch>,
     shared_buffer_uploader_tx: UnboundedSender<UploadItem>,
+    /// Stops the pin worker polling new versions, which is `None` once it's stopped.
+    pin_worker_shutdown_tx: Mutex<Option<oneshot::Sender<()>>>,
 }
 
 struct BufferTracker {
@@ -98,8 +102,6 @@ impl LocalVersionManager {
             tokio::sync::mpsc::unbounded_channel();
         let (version_unpin_worker_tx, version_unpin_worker_rx) =
             tokio::sync::mpsc::unbounded_channel();
-        let (version_update_notifier_tx, _) = tokio::sync::watch::channel(INVALID_VERSION_ID);
-
         let pinned_version = Self::pin_version_with_retry(
             hummock_meta_client.clone(),
             INVALID_VERSION_ID,
@@ -110,6 +112,9 @@ impl LocalVersionManager {
         .await
         .expect("should be `Some` since `break_condition` is always false")
         .expect("should be able to pinned the first version");
+        let (committed_epoch_tx, _) =
+            tokio::sync::watch::channel(pinned_version.max_committed_epoch);
+        let (pin_worker_shutdown_tx, pin_worker_shutdown_rx) = oneshot::channel();
 
         let global_upload_batches_size = Arc::new(AtomicUsize::new(0));
         let global_replicate_batches_size = Arc::new(AtomicUsize::new(0));
@@ -117,8 +122,9 @@ impl LocalVersionManager {
         let local_version_manager = Arc::new(LocalVersionManager {
             local_version: RwLock::new(LocalVersion::new(pinned_version, version_unpin_worker_tx)),
             worker_context: WorkerContext {
-                version_update_notifier_tx,
+                committed_epoch_tx,
                 shared_buffer_uploader_tx,
+                pin_worker_shutdown_tx: Mutex::new(Some(pin_worker_shutdown_tx)),
             },
             buffer_tracker: BufferTracker {
                 capacity: (options.shared_buffer_capacity_mb as usize) * (1 << 20),
@@ -128,10 +134,11 @@ impl LocalVersionManager {
             write_conflict_detector: write_conflict_detector.clone(),
         });
 
-        // Pin and get the latest version.
+        // Pin and get the latest version, until new versions are pushed by meta.
         tokio::spawn(LocalVersionManager::start_pin_worker(
             Arc::downgrade(&local_version_manager),
             hummock_meta_client.clone(),
+            pin_worker_shutdown_rx,
         ));
 
         // Unpin unused version.
@@ -159,7 +166,7 @@ impl LocalVersionManager {
     /// being referenced by some readers.
     pub fn try_update_pinned_version(&self, newly_pinned_version: HummockVersion) -> bool {
         let new_version_id = newly_pinned_version.id;
-        if validate_table_key_range(&newly_pinned_version.levels).is_err() {
+        if validate_table_key_range(&newly_pinned_version.get_combined_levels()).is_err() {
             error!("invalid table key range: {:?}", newly_pinned_version.levels);
             return false;
         }
@@ -169,40 +176,51 @@ impl LocalVersionManager {
             return false;
         }
 
+        let max_committed_epoch = newly_pinned_version.max_committed_epoch;
         if let Some(conflict_detector) = self.write_conflict_detector.as_ref() {
-            conflict_detector.set_watermark(newly_pinned_version.max_committed_epoch);
+            conflict_detector.set_watermark(max_committed_epoch);
         }
         guard.set_pinned_version(newly_pinned_version);
 
+        // The committed epoch is visible to readers once it's sent.
         self.worker_context
-            .version_update_notifier_tx
-            .send(new_version_id)
-            .ok();
+            .committed_epoch_tx
+            .send_replace(max_committed_epoch);
         true
     }
 
-    /// Waits until the local hummock version contains the given committed epoch
-    pub async fn wait_epoch(&self, epoch: HummockEpoch) -> HummockResult<()> {
+    /// Stops polling new versions from meta, which pushes them to the node instead. Versions
+    /// pushed by meta have been pinned for the node, and are applied by
+    /// [`Self::try_update_pinned_version`].
+    pub fn stop_pin_worker(&self) {
+        if let Some(shutdown_tx) = self.worker_context.pin_worker_shutdown_tx.lock().take() {
+            // The pin worker may have been shut down already.
+            let _ = shutdown_tx.send(());
+        }
+    }
+
+    /// Waits until the local hummock version contains the given committed epoch, which is notified
+    /// once a version committing it is pinned. Fails if it's not committed within `timeout`.
+    pub async fn wait_epoch(&self, epoch: HummockEpoch, timeout: Duration) -> HummockResult<()> {
         if epoch == HummockEpoch::MAX {
             panic!("epoch should not be u64::MAX");
         }
-        let mut receiver = self.worker_context.version_update_notifier_tx.subscribe();
-        loop {
-            {
-                let current_version = self.local_version.read();
-                if current_version.pinned_version().max_committed_epoch() >= epoch {
-                    return Ok(());
-                }
-            }
-            match tokio::time::timeout(Duration::from_secs(10), receiver.changed()).await {
-                Err(_) => {
-                    return Err(HummockError::wait_epoch("timeout"));
-                }
-                Ok(Err(_)) => {
-                    return Err(HummockError::wait_epoch("tx dropped"));
-                }
-                Ok(Ok(_)) => {}
+        let mut receiver = self.worker_context.committed_epoch_tx.subscribe();
+        let wait_committed = async {
+            while *receiver.borrow_and_update() < epoch {
+                receiver
+                    .changed()
+                    .await
+                    .map_err(|_| HummockError::wait_epoch("tx dropped"))?;
             }
+            Ok(())
+        };
+        match tokio::time::timeout(timeout, wait_committed).await {
+            Ok(result) => result,
+            Err(_) => Err(HummockError::wait_epoch(format!(
+                "epoch {} is not committed in {:?}",
+                epoch, timeout
+            ))),
         }
     }
 
@@ -391,6 +409,12 @@ impl LocalVersionManager {
             .unwrap_or_default()
     }
 
+    /// Drops the shared buffers of all uncommitted epochs. The sizes of their batches are released
+    /// once the readers holding them are dropped.
+    pub fn clear_shared_buffer(&self) {
+        self.local_version.write().clear_shared_buffer();
+    }
+
     /// Pin a version with retry.
     ///
     /// Return:
@@ -440,12 +464,19 @@ impl LocalVersionManager {
     async fn start_pin_worker(
         local_version_manager_weak: Weak<LocalVersionManager>,
         hummock_meta_client: Arc<dyn HummockMetaClient>,
+        mut shutdown_rx: oneshot::Receiver<()>,
     ) {
         let min_execute_interval = Duration::from_millis(100);
         let mut min_execute_interval_tick = tokio::time::interval(min_execute_interval);
         min_execute_interval_tick.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
         loop {
-            min_execute_interval_tick.tick().await;
+            tokio::select! {
+                _ = min_execute_interval_tick.tick() => {},
+                _ = &mut shutdown_rx => {
+                    tracing::info!("Shutdown hummock pin worker");
+                    return;
+                }
+            }
             let local_version_manager = match local_version_manager_weak.upgrade() {
                 None => {
                     tracing::info!("Shutdown hummock pin worker");
@@ -557,7 +588,6 @@ impl LocalVersionManager {
         self.local_version.read().clone()
     }
 
-    #[cfg(test)]
     pub fn get_shared_buffer_size(&self) -> usize {
         self.buffer_tracker.get_replicate_size() + self.buffer_tracker.get_upload_size()
     }
@@ -567,6 +597,7 @@ impl LocalVersionManager {
 mod tests {
     use std::sync::atomic::AtomicUsize;
     use std::sync::Arc;
+    use std::time::Duration;
 
     use bytes::Bytes;
     use risingwave_meta::hummock::test_utils::setup_compute_env;
@@ -854,4 +885,46 @@ mod tests {
         assert!(local_version.get_shared_buffer(epochs[0]).is_none());
         assert!(local_version.get_shared_buffer(epochs[1]).is_none());
     }
+
+    #[tokio::test]
+    async fn test_wait_epoch() {
+        let opt = Arc::new(default_config_for_test());
+        let (_, hummock_manager_ref, _, worker_node) = setup_compute_env(8080).await;
+        let local_version_manager = LocalVersionManager::new(
+            opt.clone(),
+            mock_sstable_store(),
+            Arc::new(StateStoreMetrics::unused()),
+            Arc::new(MockHummockMetaClient::new(
+                hummock_manager_ref.clone(),
+                worker_node.id,
+            )),
+            ConflictDetector::new_from_config(opt),
+        )
+        .await;
+
+        let pinned_version = local_version_manager.get_pinned_version();
+        let initial_version_id = pinned_version.id();
+        let epoch = pinned_version.max_committed_epoch() + 1;
+
+        // The epoch is not committed yet.
+        local_version_manager
+            .wait_epoch(epoch, Duration::from_millis(10))
+            .await
+            .unwrap_err();
+
+        let version = HummockVersion {
+            id: initial_version_id + 1,
+            max_committed_epoch: epoch,
+            ..Default::default()
+        };
+        local_version_manager.try_update_pinned_version(version);
+        local_version_manager
+            .wait_epoch(epoch, Duration::from_millis(10))
+            .await
+            .unwrap();
+        local_version_manager
+            .wait_epoch(epoch - 1, Duration::from_millis(10))
+            .await
+            .unwrap();
+    }
 }
@@ -14,11 +14,14 @@
 
 //! Hummock is the state store of the streaming system.
 
+use std::collections::HashMap;
 use std::fmt;
 use std::sync::Arc;
 
 use bytes::Bytes;
+use parking_lot::RwLock;
 use risingwave_common::config::StorageConfig;
+use risingwave_common::util::epoch::Epoch;
 use risingwave_hummock_sdk::*;
 use risingwave_rpc_client::HummockMetaClient;
 
@@ -28,6 +31,8 @@ mod sstable;
 pub use sstable::*;
 
 pub mod compaction_executor;
+pub mod compaction_filter;
+pub mod compaction_group_client;
 pub mod compactor;
 #[cfg(test)]
 mod compactor_tests;
@@ -37,6 +42,7 @@ pub mod hummock_meta_client;
 pub mod iterator;
 mod local_version;
 pub mod local_version_manager;
+pub mod read_statistics;
 pub mod shared_buffer;
 #[cfg(test)]
 mod snapshot_tests;
@@ -55,14 +61,12 @@ pub use error::*;
 pub use risingwave_common::cache::{CachableEntry, LookupResult, LruCache};
 use value::*;
 
-use self::iterator::HummockIterator;
-use self::key::user_key;
 pub use self::sstable_store::*;
 pub use self::state_store::HummockStateStoreIter;
 use super::monitor::StateStoreMetrics;
 use crate::hummock::conflict_detector::ConflictDetector;
-use crate::hummock::iterator::ReadOptions;
 use crate::hummock::local_version_manager::LocalVersionManager;
+use crate::hummock::read_statistics::ReadStatistics;
 use crate::hummock::sstable_store::{SstableStoreRef, TableHolder};
 use crate::monitor::StoreLocalStatistic;
 
@@ -79,6 +83,15 @@ pub struct HummockStorage {
 
     /// Statistics
     stats: Arc<StateStoreMetrics>,
+
+    /// Cache policies of the data blocks read from the tables, if not filling the block cache.
+    table_cache_policies: Arc<RwLock<HashMap<u32, CachePolicy>>>,
+
+    /// Retention in seconds of the tables whose expired rows are not read.
+    table_retentions: Arc<RwLock<HashMap<u32, u32>>>,
+
+    /// Reads of the committed SSTs, reported to meta to compact the SSTs slowing down reads.
+    read_statistics: Arc<ReadStatistics>,
 }
 
 impl HummockStorage {
@@ -113,45 +126,52 @@ impl HummockStorage {
         )
         .await;
 
+        let read_statistics = Arc::new(ReadStatistics::default());
+        tokio::spawn(ReadStatistics::start_report_worker(
+            Arc::downgrade(&read_statistics),
+            hummock_meta_client.clone(),
+        ));
+
         let instance = Self {
             options: options.clone(),
             local_version_manager,
             hummock_meta_client,
             sstable_store,
             stats,
+            table_cache_policies: Default::default(),
+            table_retentions: Default::default(),
+            read_statistics,
         };
         Ok(instance)
     }
 
+    /// Gets `key` from `table`. Returns `Some(None)` if the key is deleted in the table.
     async fn get_from_table(
         &self,
         table: TableHolder,
         internal_key: &[u8],
         key: &[u8],
-        read_options: Arc<ReadOptions>,
+        cache_policy: CachePolicy,
+        block_iter: &mut Option<BlockIterator>,
         stats: &mut StoreLocalStatistic,
-    ) -> HummockResult<Option<Bytes>> {
+    ) -> HummockResult<Option<Option<Bytes>>> {
+        self.sstable_store.load_filter(table.value()).await?;
         if table.value().surely_not_have_user_key(key) {
             stats.bloom_filter_true_negative_count += 1;
             return Ok(None);
         }
         // Might have the key, take it as might positive.
         stats.bloom_filter_might_positive_count += 1;
-        let mut iter = SSTableIterator::create(table, self.sstable_store.clone(), read_options);
-        iter.seek(internal_key).await?;
-        // Iterator has seeked passed the borders.
-        if !iter.is_valid() {
-            return Ok(None);
-        }
-
-        // Iterator gets us the key, we tell if it's the key we want
-        // or key next to it.
-        let value = match user_key(iter.key()) == key {
-            true => iter.value().into_user_value().map(Bytes::copy_from_slice),
-            false => None,
-        };
-        iter.collect_local_statistic(stats);
-        Ok(value)
+        let value = get_from_sstable(
+            table.value(),
+            &self.sstable_store,
+            internal_key,
+            cache_policy,
+            block_iter,
+            stats,
+        )
+        .await?;
+        Ok(value.map(HummockValue::into_user_value))
     }
 
     pub fn hummock_meta_client(&self) -> &Arc<dyn HummockMetaClient> {
@@ -169,6 +189,45 @@ impl HummockStorage {
     pub fn local_version_manager(&self) -> &Arc<LocalVersionManager> {
         &self.local_version_manager
     }
+
+    /// Sets the cache policy of the data blocks read from table `table_id`, e.g. not to fill the
+    /// block cache with the state of a low priority materialized view.
+    pub fn set_table_cache_policy(&self, table_id: u32, policy: CachePolicy) {
+        let mut policies = self.table_cache_policies.write();
+        match policy {
+            CachePolicy::Fill => policies.remove(&table_id),
+            policy => policies.insert(table_id, policy),
+        };
+    }
+
+    /// Returns the cache policy of the data blocks read from table `table_id`, or of the blocks
+    /// of multiple tables if `None`.
+    fn table_cache_policy(&self, table_id: Option<u32>) -> CachePolicy {
+        table_id
+            .and_then(|table_id| self.table_cache_policies.read().get(&table_id).copied())
+            .unwrap_or_default()
+    }
+
+    /// Sets the retention of table `table_id`. The rows written earlier than the retention before
+    /// the time of the read are not read, even if compaction hasn't deleted them yet. A retention
+    /// of 0 keeps the rows forever.
+    pub fn set_table_retention(&self, table_id: u32, retention_seconds: u32) {
+        let mut retentions = self.table_retentions.write();
+        match retention_seconds {
+            0 => retentions.remove(&table_id),
+            retention_seconds => retentions.insert(table_id, retention_seconds),
+        };
+    }
+
+    /// Returns the minimum epoch of the rows to read from table `table_id`, or 0 if the table has
+    /// no retention or the rows are of multiple tables.
+    fn table_min_epoch(&self, table_id: Option<u32>) -> u64 {
+        table_id
+            .and_then(|table_id| self.table_retentions.read().get(&table_id).copied())
+            .map_or(0, |retention_seconds| {
+                compaction_filter::expire_epoch(retention_seconds, Epoch::now())
+            })
+    }
 }
 
 impl fmt::Debug for HummockStorage {
@@ -0,0 +1,101 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+
+use parking_lot::Mutex;
+use risingwave_hummock_sdk::HummockSSTableId;
+use risingwave_pb::hummock::SstReadStatistics;
+use risingwave_rpc_client::HummockMetaClient;
+
+const REPORT_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Collects the reads of the committed SSTs on this node, which are reported to meta periodically,
+/// so that the SSTs slowing down reads can be compacted earlier.
+#[derive(Default)]
+pub struct ReadStatistics {
+    ssts: Mutex<HashMap<HummockSSTableId, SstReadStatistics>>,
+}
+
+impl ReadStatistics {
+    /// Records a read of `sst_ids`, which merges `merge_width` SSTs and batches in total.
+    pub fn record_read(&self, sst_ids: &[HummockSSTableId], merge_width: usize) {
+        if sst_ids.is_empty() {
+            return;
+        }
+        let mut ssts = self.ssts.lock();
+        for sst_id in sst_ids {
+            let stat = ssts.entry(*sst_id).or_insert_with(|| SstReadStatistics {
+                sst_id: *sst_id,
+                ..Default::default()
+            });
+            stat.read_count += 1;
+            stat.merge_width += merge_width as u64;
+        }
+    }
+
+    /// Takes the reads recorded since the last call.
+    pub fn take(&self) -> Vec<SstReadStatistics> {
+        std::mem::take(&mut *self.ssts.lock())
+            .into_values()
+            .collect()
+    }
+
+    /// Reports the reads to meta until `read_statistics` is dropped. The reads failed to report
+    /// are discarded, as they are only hints for compaction.
+    pub(crate) async fn start_report_worker(
+        read_statistics: Weak<ReadStatistics>,
+        hummock_meta_client: Arc<dyn HummockMetaClient>,
+    ) {
+        let mut interval = tokio::time::interval(REPORT_INTERVAL);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            let stats = match read_statistics.upgrade() {
+                Some(read_statistics) => read_statistics.take(),
+                None => return,
+            };
+            if stats.is_empty() {
+                continue;
+            }
+            if let Err(e) = hummock_meta_client.report_read_statistics(stats).await {
+                tracing::warn!("Failed to report read statistics: {:?}", e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+
+    use super::*;
+
+    #[test]
+    fn test_read_statistics() {
+        let read_statistics = ReadStatistics::default();
+        read_statistics.record_read(&[1, 2], 3);
+        read_statistics.record_read(&[1], 1);
+        let stats = read_statistics
+            .take()
+            .into_iter()
+            .map(|stat| (stat.sst_id, stat.read_count, stat.merge_width))
+            .sorted()
+            .collect_vec();
+        assert_eq!(stats, vec![(1, 2, 4), (2, 1, 3)]);
+        assert!(read_statistics.take().is_empty());
+    }
+}
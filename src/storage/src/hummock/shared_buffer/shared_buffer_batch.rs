@@ -26,7 +26,7 @@ use crate::hummock::iterator::{
     Backward, DirectionEnum, Forward, HummockIterator, HummockIteratorDirection,
 };
 use crate::hummock::value::HummockValue;
-use crate::hummock::{key, HummockEpoch, HummockResult};
+use crate::hummock::{key, HummockEpoch, HummockResult, BLOB_REF_SIZE};
 use crate::storage_value::VALUE_META_SIZE;
 
 pub(crate) type SharedBufferItem = (Bytes, HummockValue<Bytes>);
@@ -111,6 +111,7 @@ impl SharedBufferBatch {
                     match v {
                         HummockValue::Put(_, val) => VALUE_META_SIZE + val.len(),
                         HummockValue::Delete(_) => VALUE_META_SIZE,
+                        HummockValue::Blob(..) => VALUE_META_SIZE + BLOB_REF_SIZE,
                     }
                 }
             })
@@ -12,13 +12,15 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashSet};
 use std::sync::atomic::AtomicU64;
 use std::sync::atomic::Ordering::Relaxed;
 use std::sync::Arc;
 
 use futures::FutureExt;
 use risingwave_common::config::StorageConfig;
+use risingwave_hummock_sdk::compaction_group::Prefix;
+use risingwave_hummock_sdk::key::get_table_id;
 use risingwave_hummock_sdk::{get_local_sst_id, HummockEpoch};
 use risingwave_pb::hummock::SstableInfo;
 use risingwave_rpc_client::HummockMetaClient;
@@ -26,6 +28,7 @@ use tokio::sync::{mpsc, oneshot};
 use tracing::error;
 
 use crate::hummock::compaction_executor::CompactionExecutor;
+use crate::hummock::compaction_group_client::CompactionGroupClient;
 use crate::hummock::compactor::{get_remote_sstable_id_generator, Compactor, CompactorContext};
 use crate::hummock::conflict_detector::ConflictDetector;
 use crate::hummock::shared_buffer::{OrderIndex, OrderSortedUncommittedData};
@@ -82,6 +85,7 @@ pub struct SharedBufferUploader {
     next_local_sstable_id: Arc<AtomicU64>,
     stats: Arc<StateStoreMetrics>,
     compaction_executor: Option<Arc<CompactionExecutor>>,
+    compaction_group_client: Arc<CompactionGroupClient>,
 }
 
 impl SharedBufferUploader {
@@ -106,6 +110,9 @@ impl SharedBufferUploader {
             write_conflict_detector,
             uploader_rx,
             sstable_store,
+            compaction_group_client: Arc::new(CompactionGroupClient::new(
+                hummock_meta_client.clone(),
+            )),
             hummock_meta_client,
             next_local_sstable_id: Arc::new(AtomicU64::new(0)),
             stats,
@@ -194,11 +201,29 @@ impl SharedBufferUploader {
             compaction_executor: self.compaction_executor.as_ref().cloned(),
         };
 
-        let tables = Compactor::compact_shared_buffer(Arc::new(mem_compactor_ctx), payload).await?;
+        // State of different compaction groups is flushed to different SSTs.
+        let prefixes = payload
+            .iter()
+            .flatten()
+            .flat_map(|data| [data.start_user_key(), data.end_user_key()])
+            .filter_map(get_table_id)
+            .map(Prefix::from)
+            .collect::<HashSet<_>>();
+        let compaction_groups = self
+            .compaction_group_client
+            .get_compaction_groups(prefixes)
+            .await?;
+
+        let tables = Compactor::compact_shared_buffer(
+            Arc::new(mem_compactor_ctx),
+            payload,
+            compaction_groups,
+        )
+        .await?;
 
         let uploaded_sst_info: Vec<SstableInfo> = tables
             .into_iter()
-            .map(|(sst, vnode_bitmaps)| SstableInfo {
+            .map(|(sst, vnode_bitmaps, blob_ids)| SstableInfo {
                 id: sst.id,
                 key_range: Some(risingwave_pb::hummock::KeyRange {
                     left: sst.meta.smallest_key.clone(),
@@ -207,6 +232,7 @@ impl SharedBufferUploader {
                 }),
                 file_size: sst.meta.estimated_size as u64,
                 vnode_bitmaps,
+                blob_ids,
             })
             .collect();
 
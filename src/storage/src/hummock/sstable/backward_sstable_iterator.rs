@@ -16,12 +16,13 @@ use std::cmp::Ordering::{Equal, Less};
 use std::sync::Arc;
 
 use async_trait::async_trait;
+use bytes::Bytes;
 use risingwave_hummock_sdk::VersionedComparator;
 
 use crate::hummock::iterator::{Backward, HummockIterator, ReadOptions};
 use crate::hummock::value::HummockValue;
 use crate::hummock::{
-    BlockIterator, HummockResult, SSTableIteratorType, SstableStoreRef, TableHolder,
+    BlockIterator, CachePolicy, HummockResult, SSTableIteratorType, SstableStoreRef, TableHolder,
 };
 use crate::monitor::StoreLocalStatistic;
 
@@ -38,7 +39,12 @@ pub struct BackwardSSTableIterator {
 
     sstable_store: SstableStoreRef,
 
+    cache_policy: CachePolicy,
+
     stats: StoreLocalStatistic,
+
+    /// The user value of the current key if it's read from a blob file.
+    blob_value: Option<Bytes>,
 }
 
 impl BackwardSSTableIterator {
@@ -48,7 +54,9 @@ impl BackwardSSTableIterator {
             cur_idx: table.value().meta.block_metas.len() - 1,
             sst: table,
             sstable_store,
+            cache_policy: CachePolicy::Fill,
             stats: StoreLocalStatistic::default(),
+            blob_value: None,
         }
     }
 
@@ -62,7 +70,7 @@ impl BackwardSSTableIterator {
                 .get(
                     self.sst.value(),
                     idx as u64,
-                    crate::hummock::CachePolicy::Fill,
+                    self.cache_policy,
                     &mut self.stats,
                 )
                 .await?;
@@ -77,6 +85,21 @@ impl BackwardSSTableIterator {
             self.cur_idx = idx as usize;
         }
 
+        self.read_blob().await
+    }
+
+    /// Reads the user value of the current key from its blob file if it's separated.
+    async fn read_blob(&mut self) -> HummockResult<()> {
+        self.blob_value = None;
+        let block_iter = match self.block_iter.as_ref() {
+            Some(block_iter) if block_iter.is_valid() => block_iter,
+            _ => return Ok(()),
+        };
+        let blob_ref = match HummockValue::from_slice(block_iter.value())? {
+            HummockValue::Blob(_, blob_ref) => blob_ref,
+            _ => return Ok(()),
+        };
+        self.blob_value = Some(self.sstable_store.get_blob(&blob_ref).await?);
         Ok(())
     }
 }
@@ -91,7 +114,7 @@ impl HummockIterator for BackwardSSTableIterator {
         block_iter.prev();
 
         if block_iter.is_valid() {
-            Ok(())
+            self.read_blob().await
         } else {
             // seek to the previous block
             self.seek_idx(self.cur_idx as isize - 1, None).await
@@ -105,7 +128,15 @@ impl HummockIterator for BackwardSSTableIterator {
     fn value(&self) -> HummockValue<&[u8]> {
         let raw_value = self.block_iter.as_ref().expect("no block iter").value();
 
-        HummockValue::from_slice(raw_value).expect("decode error")
+        match (
+            HummockValue::from_slice(raw_value).expect("decode error"),
+            &self.blob_value,
+        ) {
+            (HummockValue::Blob(meta, _), Some(blob_value)) => {
+                HummockValue::Put(meta, blob_value.as_ref())
+            }
+            (value, _) => value,
+        }
     }
 
     fn is_valid(&self) -> bool {
@@ -150,8 +181,14 @@ impl HummockIterator for BackwardSSTableIterator {
 }
 
 impl SSTableIteratorType for BackwardSSTableIterator {
-    fn create(table: TableHolder, sstable_store: SstableStoreRef, _: Arc<ReadOptions>) -> Self {
-        BackwardSSTableIterator::new(table, sstable_store)
+    fn create(
+        table: TableHolder,
+        sstable_store: SstableStoreRef,
+        options: Arc<ReadOptions>,
+    ) -> Self {
+        let mut iter = BackwardSSTableIterator::new(table, sstable_store);
+        iter.cache_policy = options.cache_policy;
+        iter
     }
 }
 
@@ -0,0 +1,162 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Separation of large values from SSTs.
+//!
+//! Values not smaller than a threshold are written into the blob file of the SST being built, and
+//! the SST stores a [`BlobRef`] in place of each of them. Compaction copies the blob refs instead
+//! of the values, unless the blob file is going to be vacuumed. A blob file is named after the SST
+//! that created it, and is only vacuumed with that SST after no SST refers to it anymore.
+
+use std::collections::BTreeSet;
+
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+use risingwave_hummock_sdk::HummockSSTableId;
+
+use super::utils::{xxhash64_checksum, xxhash64_verify};
+use crate::hummock::{HummockError, HummockResult};
+
+/// Size of an encoded [`BlobRef`].
+pub const BLOB_REF_SIZE: usize = 16;
+
+/// Location of a value in a blob file.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct BlobRef {
+    pub blob_id: HummockSSTableId,
+    pub offset: u32,
+    pub len: u32,
+}
+
+impl BlobRef {
+    /// Format:
+    ///
+    /// ```plain
+    /// | blob id (8B) | offset (4B) | len (4B) |
+    /// ```
+    pub fn encode(&self, buf: &mut impl BufMut) {
+        buf.put_u64_le(self.blob_id);
+        buf.put_u32_le(self.offset);
+        buf.put_u32_le(self.len);
+    }
+
+    pub fn decode(buf: &mut impl Buf) -> HummockResult<Self> {
+        if buf.remaining() < BLOB_REF_SIZE {
+            return Err(HummockError::decode_error("blob ref too short"));
+        }
+        Ok(Self {
+            blob_id: buf.get_u64_le(),
+            offset: buf.get_u32_le(),
+            len: buf.get_u32_le(),
+        })
+    }
+
+    /// Size of the value in the blob file, including its checksum.
+    pub fn stored_len(&self) -> usize {
+        self.len as usize + 8
+    }
+}
+
+/// Builds the blob file of an SST.
+///
+/// Format:
+///
+/// ```plain
+/// | value 0 | checksum (8B) | ... | value N-1 | checksum (8B) |
+/// ```
+pub struct BlobBuilder {
+    blob_id: HummockSSTableId,
+    buf: BytesMut,
+}
+
+impl BlobBuilder {
+    pub fn new(blob_id: HummockSSTableId) -> Self {
+        Self {
+            blob_id,
+            buf: BytesMut::new(),
+        }
+    }
+
+    /// Appends `value` to the blob file, and returns where it is.
+    pub fn add(&mut self, value: &[u8]) -> BlobRef {
+        let blob_ref = BlobRef {
+            blob_id: self.blob_id,
+            offset: self.buf.len() as u32,
+            len: value.len() as u32,
+        };
+        self.buf.put_slice(value);
+        self.buf.put_u64_le(xxhash64_checksum(value));
+        blob_ref
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.buf.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.buf.len()
+    }
+
+    pub fn finish(self) -> Bytes {
+        self.buf.freeze()
+    }
+}
+
+/// Values separated from an SST by its builder.
+#[derive(Clone, Debug, Default)]
+pub struct SstableBlobs {
+    /// The blob file created for the SST, if any value is separated into it.
+    pub data: Option<Bytes>,
+    /// Blob files referred to by the SST, including the one created for it.
+    pub blob_ids: BTreeSet<HummockSSTableId>,
+}
+
+/// Verifies a value read from a blob file, which is followed by its checksum.
+pub fn decode_blob_value(mut buf: Bytes) -> HummockResult<Bytes> {
+    let cursor = buf
+        .len()
+        .checked_sub(8)
+        .ok_or_else(|| HummockError::decode_error("blob value too short"))?;
+    let checksum = (&buf[cursor..]).get_u64_le();
+    buf.truncate(cursor);
+    xxhash64_verify(&buf, checksum)?;
+    Ok(buf)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blob_builder() {
+        let mut builder = BlobBuilder::new(5);
+        let refs = (0..3)
+            .map(|i| builder.add(format!("value_{}", i).as_bytes()))
+            .collect::<Vec<_>>();
+        let data = builder.finish();
+        for (i, blob_ref) in refs.into_iter().enumerate() {
+            let mut buf = vec![];
+            blob_ref.encode(&mut buf);
+            assert_eq!(buf.len(), BLOB_REF_SIZE);
+            assert_eq!(BlobRef::decode(&mut &buf[..]).unwrap(), blob_ref);
+            assert_eq!(blob_ref.blob_id, 5);
+
+            let offset = blob_ref.offset as usize;
+            let stored = data.slice(offset..offset + blob_ref.stored_len());
+            assert_eq!(
+                decode_blob_value(stored).unwrap(),
+                Bytes::from(format!("value_{}", i))
+            );
+        }
+    }
+}
@@ -49,6 +49,12 @@ impl BlockIterator {
         }
     }
 
+    /// Resets the iterator to iterate on `block`, reusing the buffer of keys.
+    pub fn reset(&mut self, block: BlockHolder) {
+        self.block = block;
+        self.invalidate();
+    }
+
     pub fn next(&mut self) {
         assert!(self.is_valid());
         self.next_inner();
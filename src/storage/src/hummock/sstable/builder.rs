@@ -12,19 +12,20 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 
 use bytes::{BufMut, Bytes, BytesMut};
 use risingwave_common::config::StorageConfig;
 use risingwave_common::hash::{VNODE_BITMAP_LEN, VNODE_BITS};
-use risingwave_hummock_sdk::key::{get_table_id, user_key};
+use risingwave_hummock_sdk::key::{get_table_id, user_key, TABLE_PREFIX_LEN};
+use risingwave_hummock_sdk::HummockSSTableId;
 use risingwave_pb::common::VNodeBitmap;
 
 use super::bloom::Bloom;
 use super::utils::CompressionAlgorithm;
 use super::{
-    BlockBuilder, BlockBuilderOptions, BlockMeta, SstableMeta, DEFAULT_BLOCK_SIZE,
-    DEFAULT_ENTRY_SIZE, DEFAULT_RESTART_INTERVAL, VERSION,
+    BlobBuilder, BlockBuilder, BlockBuilderOptions, BlockMeta, SstableBlobs, SstableMeta,
+    DEFAULT_BLOCK_SIZE, DEFAULT_ENTRY_SIZE, DEFAULT_RESTART_INTERVAL, VERSION,
 };
 use crate::hummock::value::HummockValue;
 
@@ -43,6 +44,9 @@ pub struct SSTableBuilderOptions {
     pub bloom_false_positive: f64,
     /// Compression algorithm.
     pub compression_algorithm: CompressionAlgorithm,
+    /// Values not smaller than this size are separated into the blob file of the SST, if the
+    /// builder has one. 0 disables the separation.
+    pub value_separation_threshold: usize,
 }
 
 impl From<&StorageConfig> for SSTableBuilderOptions {
@@ -54,6 +58,8 @@ impl From<&StorageConfig> for SSTableBuilderOptions {
             bloom_false_positive: options.bloom_false_positive,
             // TODO: Make this configurable.
             compression_algorithm: CompressionAlgorithm::None,
+            value_separation_threshold: (options.value_separation_threshold_kb as usize)
+                * (1 << 10),
         }
     }
 }
@@ -66,6 +72,7 @@ impl Default for SSTableBuilderOptions {
             restart_interval: DEFAULT_RESTART_INTERVAL,
             bloom_false_positive: DEFAULT_BLOOM_FALSE_POSITIVE,
             compression_algorithm: CompressionAlgorithm::None,
+            value_separation_threshold: 0,
         }
     }
 }
@@ -81,11 +88,17 @@ pub struct SSTableBuilder {
     block_metas: Vec<BlockMeta>,
     /// `table_id` -> Bitmaps of value meta.
     vnode_bitmaps: BTreeMap<u32, [u8; VNODE_BITMAP_LEN]>,
-    /// Hashes of user keys.
+    /// Hashes of user keys, and of the prefixes of the tables they belong to.
     user_key_hashes: Vec<u32>,
     /// Last added full key.
     last_full_key: Bytes,
+    /// Table id of the last added full key.
+    last_table_id: Option<u32>,
     key_count: usize,
+    /// Builder of the blob file that large values are separated into.
+    blob_builder: Option<BlobBuilder>,
+    /// Blob files referred to by the added values.
+    blob_ids: BTreeSet<HummockSSTableId>,
 }
 
 impl SSTableBuilder {
@@ -98,10 +111,22 @@ impl SSTableBuilder {
             vnode_bitmaps: BTreeMap::new(),
             user_key_hashes: Vec::with_capacity(options.capacity / DEFAULT_ENTRY_SIZE + 1),
             last_full_key: Bytes::default(),
+            last_table_id: None,
             key_count: 0,
+            blob_builder: None,
+            blob_ids: BTreeSet::new(),
         }
     }
 
+    /// Separates large values into the blob file `blob_id`, which is named after the SST being
+    /// built. See [`SSTableBuilderOptions::value_separation_threshold`].
+    pub fn with_blob_file(mut self, blob_id: HummockSSTableId) -> Self {
+        if self.options.value_separation_threshold > 0 {
+            self.blob_builder = Some(BlobBuilder::new(blob_id));
+        }
+        self
+    }
+
     /// Add kv pair to sstable.
     pub fn add(&mut self, full_key: &[u8], value: HummockValue<&[u8]>) {
         // Rotate block builder if the previous one has been built.
@@ -121,6 +146,18 @@ impl SSTableBuilder {
 
         let block_builder = self.block_builder.as_mut().unwrap();
 
+        let value = match (value, self.blob_builder.as_mut()) {
+            (HummockValue::Put(meta, user_value), Some(blob_builder))
+                if user_value.len() >= self.options.value_separation_threshold =>
+            {
+                HummockValue::Blob(meta, blob_builder.add(user_value))
+            }
+            (value, _) => value,
+        };
+        if let HummockValue::Blob(_, blob_ref) = &value {
+            self.blob_ids.insert(blob_ref.blob_id);
+        }
+
         // TODO: refine me
         let mut raw_value = BytesMut::default();
         let value_meta = value.encode(&mut raw_value) & ((1 << VNODE_BITS) - 1);
@@ -131,6 +168,13 @@ impl SSTableBuilder {
                 .entry(table_id)
                 .or_insert([0; VNODE_BITMAP_LEN])[(value_meta >> 3) as usize] |=
                 1 << (value_meta & 0b111);
+            // Keys of a table are added consecutively, so the table prefix is only added to the
+            // bloom filter once, which lets readers skip the SST for tables it doesn't contain.
+            if self.last_table_id != Some(table_id) {
+                self.last_table_id = Some(table_id);
+                self.user_key_hashes
+                    .push(farmhash::fingerprint32(&full_key[..TABLE_PREFIX_LEN]));
+            }
         }
         let raw_value = raw_value.freeze();
 
@@ -162,7 +206,10 @@ impl SSTableBuilder {
     /// ```plain
     /// | Block 0 | ... | Block N-1 | N (4B) |
     /// ```
-    pub fn finish(mut self) -> (Bytes, SstableMeta, Vec<VNodeBitmap>) {
+    ///
+    /// Separated values are returned along with the blob files referred to by the SST, which
+    /// should be uploaded before the SST.
+    pub fn finish(mut self) -> (Bytes, SstableMeta, Vec<VNodeBitmap>, SstableBlobs) {
         let smallest_key = self.block_metas[0].smallest_key.clone();
         let largest_key = self.last_full_key.to_vec();
         self.build_block();
@@ -196,11 +243,18 @@ impl SSTableBuilder {
                     bitmap: ::prost::alloc::vec::Vec::from(*vnode_bitmaps),
                 })
                 .collect(),
+            SstableBlobs {
+                data: self
+                    .blob_builder
+                    .filter(|blob_builder| !blob_builder.is_empty())
+                    .map(BlobBuilder::finish),
+                blob_ids: self.blob_ids,
+            },
         )
     }
 
     pub fn approximate_len(&self) -> usize {
-        self.buf.len() + 4
+        self.buf.len() + 4 + self.blob_builder.as_ref().map_or(0, BlobBuilder::len)
     }
 
     fn build_block(&mut self) {
@@ -230,12 +284,15 @@ impl SSTableBuilder {
 
 #[cfg(test)]
 pub(super) mod tests {
+    use risingwave_hummock_sdk::key::{key_with_epoch, table_prefix};
+
     use super::*;
     use crate::hummock::iterator::test_utils::mock_sstable_store;
     use crate::hummock::test_utils::{
         default_builder_opt_for_test, gen_default_test_sstable, test_key_of, test_value_of,
         TEST_KEYS_COUNT,
     };
+    use crate::hummock::{BlobRef, Sstable};
 
     #[test]
     #[should_panic]
@@ -246,6 +303,7 @@ pub(super) mod tests {
             restart_interval: 16,
             bloom_false_positive: 0.1,
             compression_algorithm: CompressionAlgorithm::None,
+            value_separation_threshold: 0,
         };
 
         let b = SSTableBuilder::new(opt);
@@ -261,7 +319,7 @@ pub(super) mod tests {
             b.add(&test_key_of(i), HummockValue::put(&test_value_of(i)));
         }
 
-        let (_, meta, _) = b.finish();
+        let (_, meta, _, _) = b.finish();
 
         assert_eq!(test_key_of(0), meta.smallest_key);
         assert_eq!(test_key_of(TEST_KEYS_COUNT - 1), meta.largest_key);
@@ -276,6 +334,7 @@ pub(super) mod tests {
             restart_interval: 16,
             bloom_false_positive: if with_blooms { 0.01 } else { 0.0 },
             compression_algorithm: CompressionAlgorithm::None,
+            value_separation_threshold: 0,
         };
 
         // build remote table
@@ -294,4 +353,61 @@ pub(super) mod tests {
         test_with_bloom_filter(false).await;
         test_with_bloom_filter(true).await;
     }
+
+    #[test]
+    fn test_table_prefix_bloom_filter() {
+        let mut b = SSTableBuilder::new(SSTableBuilderOptions {
+            bloom_false_positive: 0.01,
+            ..default_builder_opt_for_test()
+        });
+        for table_id in [1, 3] {
+            for i in 0..TEST_KEYS_COUNT {
+                let user_key = [table_prefix(table_id), format!("{:05}", i).into_bytes()].concat();
+                b.add(
+                    &key_with_epoch(user_key, 233),
+                    HummockValue::put(&test_value_of(i)),
+                );
+            }
+        }
+        let (_, meta, _, _) = b.finish();
+        let table = Sstable::new(0, meta);
+
+        assert!(!table.surely_not_have_table_prefix(&table_prefix(1)));
+        assert!(!table.surely_not_have_table_prefix(&table_prefix(3)));
+        assert!(table.surely_not_have_table_prefix(&table_prefix(2)));
+    }
+
+    #[test]
+    fn test_value_separation() {
+        let mut b = SSTableBuilder::new(SSTableBuilderOptions {
+            value_separation_threshold: 50,
+            ..default_builder_opt_for_test()
+        })
+        .with_blob_file(7);
+        let mut separated_len = 0;
+        for i in 0..TEST_KEYS_COUNT {
+            let value = test_value_of(i);
+            if value.len() >= 50 {
+                separated_len += value.len() + 8;
+            }
+            b.add(&test_key_of(i), HummockValue::put(&value));
+        }
+        // A blob ref copied from another SST.
+        b.add(
+            &key_with_epoch(b"key_test_99999".to_vec(), 233),
+            HummockValue::Blob(
+                Default::default(),
+                BlobRef {
+                    blob_id: 3,
+                    offset: 0,
+                    len: 100,
+                },
+            ),
+        );
+        let (_, meta, _, blobs) = b.finish();
+
+        assert_eq!(meta.key_count as usize, TEST_KEYS_COUNT + 1);
+        assert_eq!(blobs.data.unwrap().len(), separated_len);
+        assert_eq!(blobs.blob_ids.into_iter().collect::<Vec<_>>(), vec![3, 7]);
+    }
 }
@@ -16,6 +16,7 @@ use std::cmp::Ordering::{Equal, Less};
 use std::sync::Arc;
 
 use async_trait::async_trait;
+use bytes::Bytes;
 use risingwave_hummock_sdk::VersionedComparator;
 
 use super::super::{HummockResult, HummockValue};
@@ -45,6 +46,9 @@ pub struct SSTableIterator {
     sstable_store: SstableStoreRef,
     stats: StoreLocalStatistic,
     options: Arc<ReadOptions>,
+
+    /// The user value of the current key if it's read from a blob file.
+    blob_value: Option<Bytes>,
 }
 
 impl SSTableIterator {
@@ -60,6 +64,7 @@ impl SSTableIterator {
             sstable_store,
             stats: StoreLocalStatistic::default(),
             options,
+            blob_value: None,
         }
     }
 
@@ -83,7 +88,7 @@ impl SSTableIterator {
                     .get(
                         self.sst.value(),
                         idx as u64,
-                        crate::hummock::CachePolicy::Fill,
+                        self.options.cache_policy,
                         &mut self.stats,
                     )
                     .await?
@@ -99,6 +104,21 @@ impl SSTableIterator {
             self.cur_idx = idx;
         }
 
+        self.read_blob().await
+    }
+
+    /// Reads the user value of the current key from its blob file if it's separated.
+    async fn read_blob(&mut self) -> HummockResult<()> {
+        self.blob_value = None;
+        let block_iter = match self.block_iter.as_ref() {
+            Some(block_iter) if block_iter.is_valid() => block_iter,
+            _ => return Ok(()),
+        };
+        let blob_ref = match HummockValue::from_slice(block_iter.value())? {
+            HummockValue::Blob(_, blob_ref) if self.options.should_read_blob(&blob_ref) => blob_ref,
+            _ => return Ok(()),
+        };
+        self.blob_value = Some(self.sstable_store.get_blob(&blob_ref).await?);
         Ok(())
     }
 }
@@ -113,7 +133,7 @@ impl HummockIterator for SSTableIterator {
         block_iter.next();
 
         if block_iter.is_valid() {
-            Ok(())
+            self.read_blob().await
         } else {
             // seek to next block
             self.seek_idx(self.cur_idx + 1, None).await
@@ -127,7 +147,15 @@ impl HummockIterator for SSTableIterator {
     fn value(&self) -> HummockValue<&[u8]> {
         let raw_value = self.block_iter.as_ref().expect("no block iter").value();
 
-        HummockValue::from_slice(raw_value).expect("decode error")
+        match (
+            HummockValue::from_slice(raw_value).expect("decode error"),
+            &self.blob_value,
+        ) {
+            (HummockValue::Blob(meta, _), Some(blob_value)) => {
+                HummockValue::Put(meta, blob_value.as_ref())
+            }
+            (value, _) => value,
+        }
     }
 
     fn is_valid(&self) -> bool {
@@ -301,7 +329,7 @@ mod tests {
         let kv_iter =
             (0..TEST_KEYS_COUNT).map(|i| (test_key_of(i), HummockValue::put(test_value_of(i))));
         let (data, meta, _) = gen_test_sstable_data(default_builder_opt_for_test(), kv_iter);
-        let table = Sstable { id: 0, meta };
+        let table = Sstable::new(0, meta);
         sstable_store
             .put(table.clone(), data, CachePolicy::NotFill)
             .await
@@ -311,7 +339,10 @@ mod tests {
         let mut sstable_iter = SSTableIterator::create(
             block_on(sstable_store.sstable(table.id, &mut stats)).unwrap(),
             sstable_store,
-            Arc::new(ReadOptions { prefetch: true }),
+            Arc::new(ReadOptions {
+                prefetch: true,
+                ..Default::default()
+            }),
         );
         let mut cnt = 0;
         sstable_iter.rewind().await.unwrap();
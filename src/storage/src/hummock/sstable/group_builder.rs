@@ -25,7 +25,7 @@ use risingwave_pb::common::VNodeBitmap;
 
 use crate::hummock::multi_builder::CapacitySplitTableBuilder;
 use crate::hummock::value::HummockValue;
-use crate::hummock::{HummockResult, SSTableBuilder, SstableMeta};
+use crate::hummock::{HummockResult, SSTableBuilder, SstableBlobs, SstableMeta};
 
 pub type KeyValueGroupId = u64;
 const DEFAULT_KEY_VALUE_GROUP_ID: KeyValueGroupId = KeyValueGroupId::MAX;
@@ -59,11 +59,11 @@ impl KeyValueGrouping for KeyValueGroupingImpl {
 
 /// Groups key value by compaction group
 pub struct CompactionGroupGrouping {
-    prefixes: HashMap<Prefix, CompactionGroupId>,
+    prefixes: Arc<HashMap<Prefix, CompactionGroupId>>,
 }
 
 impl CompactionGroupGrouping {
-    pub fn new(prefixes: HashMap<Prefix, CompactionGroupId>) -> Self {
+    pub fn new(prefixes: Arc<HashMap<Prefix, CompactionGroupId>>) -> Self {
         Self { prefixes }
     }
 }
@@ -74,8 +74,9 @@ impl KeyValueGrouping for CompactionGroupGrouping {
         full_key: &FullKey<&[u8]>,
         _value: &HummockValue<&[u8]>,
     ) -> Option<KeyValueGroupId> {
-        let prefix = get_table_id(full_key.inner()).unwrap();
-        self.prefixes.get(&prefix.into()).cloned().map(|v| v.into())
+        get_table_id(full_key.inner())
+            .and_then(|prefix| self.prefixes.get(&prefix.into()).cloned())
+            .map(|v| v.into())
     }
 }
 
@@ -97,12 +98,9 @@ impl KeyValueGrouping for VirtualNodeGrouping {
         value: &HummockValue<&[u8]>,
     ) -> Option<KeyValueGroupId> {
         if let Some(table_id) = get_table_id(full_key.inner()) {
-            self.vnode2unit.get(&table_id).map(|mapping| {
-                mapping[match value {
-                    HummockValue::Put(meta, _) => meta.vnode,
-                    HummockValue::Delete(meta) => meta.vnode,
-                } as usize] as KeyValueGroupId
-            })
+            self.vnode2unit
+                .get(&table_id)
+                .map(|mapping| mapping[value.value_meta().vnode as usize] as KeyValueGroupId)
         } else {
             None
         }
@@ -166,7 +164,7 @@ where
             .for_each(|(_k, v)| v.seal_current());
     }
 
-    pub fn finish(self) -> Vec<(u64, Bytes, SstableMeta, Vec<VNodeBitmap>)> {
+    pub fn finish(self) -> Vec<(u64, Bytes, SstableMeta, Vec<VNodeBitmap>, SstableBlobs)> {
         self.builders
             .into_iter()
             .flat_map(|(_k, v)| v.finish())
@@ -199,13 +197,14 @@ mod tests {
                     restart_interval: DEFAULT_RESTART_INTERVAL,
                     bloom_false_positive: 0.1,
                     compression_algorithm: CompressionAlgorithm::None,
+                    value_separation_threshold: 0,
                 }),
             ))
         };
         let prefix = b"\x01\x02\x03\x04".as_slice().get_u32();
         // one compaction group defined
         let grouping = KeyValueGroupingImpl::CompactionGroup(CompactionGroupGrouping::new(
-            HashMap::from([(prefix.into(), 1.into())]),
+            Arc::new(HashMap::from([(prefix.into(), 1.into())])),
         ));
         let mut builder = GroupedSstableBuilder::new(get_id_and_builder, grouping);
         for i in 0..10 {
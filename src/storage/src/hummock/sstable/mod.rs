@@ -15,6 +15,8 @@
 //! Hummock state store's SST builder, format and iterator
 
 // Copyright 2021 TiKV Project Authors. Licensed under Apache-2.0.
+mod blob;
+pub use blob::*;
 mod block;
 pub use block::*;
 mod block_iterator;
@@ -25,13 +27,19 @@ pub mod builder;
 pub use builder::*;
 mod forward_sstable_iterator;
 pub mod multi_builder;
+use std::sync::Arc;
+
 use bytes::{Buf, BufMut};
 use fail::fail_point;
 pub use forward_sstable_iterator::*;
 mod backward_sstable_iterator;
 pub use backward_sstable_iterator::*;
+mod point_get;
+pub use point_get::*;
 use risingwave_hummock_sdk::HummockSSTableId;
+use risingwave_object_store::object::BlockLocation;
 use risingwave_pb::hummock::{KeyRange, SstableInfo};
+use tokio::sync::OnceCell;
 
 pub mod group_builder;
 mod utils;
@@ -44,22 +52,87 @@ use super::{HummockError, HummockResult};
 
 const DEFAULT_META_BUFFER_CAPACITY: usize = 4096;
 const MAGIC: u32 = 0x5785ab73;
-const VERSION: u32 = 1;
+const VERSION: u32 = 3;
+/// The oldest format version that can still be read.
+const MIN_VERSION: u32 = 1;
+/// Since this format version, the table prefixes of keys are added to bloom filters.
+const TABLE_PREFIX_BLOOM_VERSION: u32 = 2;
+/// Since this format version, the meta object consists of a filter block, an index block and a
+/// footer, so that the index block can be read without the bloom filter.
+const SEPARATE_FILTER_VERSION: u32 = 3;
+/// Size of the footer of a meta object with separate filter block.
+pub const META_FOOTER_SIZE: usize = 16;
+/// Size of the tail of a meta object, which ends with the format version and the magic number in
+/// all format versions.
+const META_TAIL_SIZE: usize = 8;
 
 #[derive(Clone, Debug)]
 /// [`Sstable`] is a handle for accessing SST.
 pub struct Sstable {
     pub id: HummockSSTableId,
     pub meta: SstableMeta,
+    /// The filter block that is not loaded with the meta, see
+    /// [`SstableStore::load_filter`](crate::hummock::SstableStore::load_filter).
+    lazy_filter: Option<LazyFilter>,
+}
+
+#[derive(Clone, Debug)]
+struct LazyFilter {
+    location: BlockLocation,
+    filter: Arc<OnceCell<Vec<u8>>>,
 }
 
 impl Sstable {
     pub fn new(id: HummockSSTableId, meta: SstableMeta) -> Self {
-        Self { id, meta }
+        Self {
+            id,
+            meta,
+            lazy_filter: None,
+        }
+    }
+
+    /// Creates an SST whose bloom filter is in the filter block at `filter_location` of the meta
+    /// object, and not loaded yet.
+    pub fn with_lazy_filter(
+        id: HummockSSTableId,
+        meta: SstableMeta,
+        filter_location: BlockLocation,
+    ) -> Self {
+        Self {
+            id,
+            meta,
+            lazy_filter: Some(LazyFilter {
+                location: filter_location,
+                filter: Arc::new(OnceCell::new()),
+            }),
+        }
+    }
+
+    /// Returns the location of the filter block in the meta object if it's not loaded yet.
+    pub fn unloaded_filter_location(&self) -> Option<BlockLocation> {
+        self.lazy_filter
+            .as_ref()
+            .filter(|lazy_filter| !lazy_filter.filter.initialized())
+            .map(|lazy_filter| lazy_filter.location)
+    }
+
+    /// Sets the bloom filter loaded from the filter block.
+    pub fn set_filter(&self, filter: Vec<u8>) {
+        if let Some(lazy_filter) = &self.lazy_filter {
+            let _ = lazy_filter.filter.set(filter);
+        }
+    }
+
+    /// Returns the bloom filter, which is empty if the SST has no bloom filter or it's not loaded.
+    pub fn bloom_filter(&self) -> &[u8] {
+        match &self.lazy_filter {
+            Some(lazy_filter) => lazy_filter.filter.get().map_or(&[], Vec::as_slice),
+            None => &self.meta.bloom_filter,
+        }
     }
 
     pub fn has_bloom_filter(&self) -> bool {
-        !self.meta.bloom_filter.is_empty()
+        !self.bloom_filter().is_empty()
     }
 
     pub fn surely_not_have_user_key(&self, user_key: &[u8]) -> bool {
@@ -69,13 +142,19 @@ impl Sstable {
         };
         if enable_bloom_filter() && self.has_bloom_filter() {
             let hash = farmhash::fingerprint32(user_key);
-            let bloom = Bloom::new(&self.meta.bloom_filter);
+            let bloom = Bloom::new(self.bloom_filter());
             bloom.surely_not_have_hash(hash)
         } else {
             false
         }
     }
 
+    /// Returns true if the SST surely contains no key of the table with `table_prefix`.
+    pub fn surely_not_have_table_prefix(&self, table_prefix: &[u8]) -> bool {
+        self.meta.version >= TABLE_PREFIX_BLOOM_VERSION
+            && self.surely_not_have_user_key(table_prefix)
+    }
+
     pub fn block_count(&self) -> usize {
         self.meta.block_metas.len()
     }
@@ -95,6 +174,7 @@ impl Sstable {
             }),
             file_size: self.meta.estimated_size as u64,
             vnode_bitmaps: vec![],
+            blob_ids: vec![],
         }
     }
 }
@@ -147,50 +227,192 @@ pub struct SstableMeta {
     pub version: u32,
 }
 
+/// Footer of a meta object, which locates the filter block and the index block.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct MetaFooter {
+    pub filter_len: u32,
+    pub index_len: u32,
+    pub version: u32,
+}
+
+impl MetaFooter {
+    /// Decodes the footer from the tail of a meta object, which is at least [`META_FOOTER_SIZE`]
+    /// bytes unless it's the whole object. Returns `None` if the meta object is of a version
+    /// before [`SEPARATE_FILTER_VERSION`], which must be decoded as a whole.
+    pub fn decode(tail: &[u8]) -> HummockResult<Option<Self>> {
+        if tail.len() < META_TAIL_SIZE {
+            return Err(HummockError::decode_error("meta object too short"));
+        }
+        let version = check_magic_and_version(tail)?;
+        if version < SEPARATE_FILTER_VERSION {
+            return Ok(None);
+        }
+        if tail.len() < META_FOOTER_SIZE {
+            return Err(HummockError::decode_error("meta object too short"));
+        }
+        let mut buf = &tail[tail.len() - META_FOOTER_SIZE..];
+        Ok(Some(Self {
+            filter_len: buf.get_u32_le(),
+            index_len: buf.get_u32_le(),
+            version,
+        }))
+    }
+
+    pub fn filter_location(&self) -> BlockLocation {
+        BlockLocation {
+            offset: 0,
+            size: self.filter_len as usize,
+        }
+    }
+
+    pub fn index_location(&self) -> BlockLocation {
+        BlockLocation {
+            offset: self.filter_len as usize,
+            size: self.index_len as usize,
+        }
+    }
+
+    /// Size of the whole meta object.
+    pub fn object_size(&self) -> usize {
+        (self.filter_len + self.index_len) as usize + META_FOOTER_SIZE
+    }
+}
+
+/// Checks the magic number at the end of a meta object, and returns the format version.
+fn check_magic_and_version(buf: &[u8]) -> HummockResult<u32> {
+    let mut cursor = buf.len();
+
+    cursor -= 4;
+    let magic = (&buf[cursor..]).get_u32_le();
+    if magic != MAGIC {
+        return Err(HummockError::magic_mismatch(MAGIC, magic));
+    }
+
+    cursor -= 4;
+    let version = (&buf[cursor..cursor + 4]).get_u32_le();
+    if !(MIN_VERSION..=VERSION).contains(&version) {
+        return Err(HummockError::invalid_format_version(version));
+    }
+    Ok(version)
+}
+
+/// Decodes the filter block of a meta object into the bloom filter.
+pub fn decode_filter_block(buf: &[u8]) -> HummockResult<Vec<u8>> {
+    let cursor = buf
+        .len()
+        .checked_sub(8)
+        .ok_or_else(|| HummockError::decode_error("filter block too short"))?;
+    let checksum = (&buf[cursor..]).get_u64_le();
+    xxhash64_verify(&buf[..cursor], checksum)?;
+    Ok(buf[..cursor].to_vec())
+}
+
 impl SstableMeta {
     /// Format:
     ///
     /// ```plain
-    /// | N (4B) |
-    /// | block meta 0 | ... | block meta N-1 |
-    /// | bloom filter len (4B) | bloom filter |
-    /// | estimated size (4B) | key count (4B) |
-    /// | smallest key len (4B) | smallest key |
-    /// | largest key len (4B) | largest key |
-    /// | checksum (8B) | version (4B) | magic (4B) |
+    /// | filter block: | bloom filter | checksum (8B) |
+    /// | index block:  | N (4B) | block meta 0 | ... | block meta N-1 |
+    /// |               | estimated size (4B) | key count (4B) |
+    /// |               | smallest key len (4B) | smallest key |
+    /// |               | largest key len (4B) | largest key |
+    /// |               | checksum (8B) |
+    /// | footer:       | filter block len (4B) | index block len (4B) | version (4B) | magic (4B) |
     /// ```
+    ///
+    /// The index block can be read without the filter block, see [`MetaFooter`].
     pub fn encode_to_bytes(&self) -> Vec<u8> {
-        let mut buf = Vec::with_capacity(DEFAULT_META_BUFFER_CAPACITY);
+        let mut buf = Vec::with_capacity(DEFAULT_META_BUFFER_CAPACITY + self.bloom_filter.len());
+        buf.put_slice(&self.bloom_filter);
+        buf.put_u64_le(xxhash64_checksum(&self.bloom_filter));
+        let filter_len = buf.len();
+
         buf.put_u32_le(self.block_metas.len() as u32);
         for block_meta in &self.block_metas {
             block_meta.encode(&mut buf);
         }
-        put_length_prefixed_slice(&mut buf, &self.bloom_filter);
         buf.put_u32_le(self.estimated_size as u32);
         buf.put_u32_le(self.key_count as u32);
         put_length_prefixed_slice(&mut buf, &self.smallest_key);
         put_length_prefixed_slice(&mut buf, &self.largest_key);
-        let checksum = xxhash64_checksum(&buf);
+        let checksum = xxhash64_checksum(&buf[filter_len..]);
         buf.put_u64_le(checksum);
+        let index_len = buf.len() - filter_len;
+
+        buf.put_u32_le(filter_len as u32);
+        buf.put_u32_le(index_len as u32);
         buf.put_u32_le(VERSION);
         buf.put_u32_le(MAGIC);
         buf
     }
 
+    /// Decodes a whole meta object of any supported format version, including the bloom filter.
     pub fn decode(buf: &mut &[u8]) -> HummockResult<Self> {
-        let mut cursor = buf.len();
-
-        cursor -= 4;
-        let magic = (&buf[cursor..]).get_u32_le();
-        if magic != MAGIC {
-            return Err(HummockError::magic_mismatch(MAGIC, magic));
+        let footer = match MetaFooter::decode(buf)? {
+            Some(footer) => footer,
+            None => return Self::decode_legacy(buf),
+        };
+        if buf.len() != footer.object_size() {
+            return Err(HummockError::decode_error("meta object size mismatch"));
         }
+        let filter_location = footer.filter_location();
+        let bloom_filter = decode_filter_block(
+            &buf[filter_location.offset..filter_location.offset + filter_location.size],
+        )?;
+        let index_location = footer.index_location();
+        let mut meta = Self::decode_index_block(
+            &buf[index_location.offset..index_location.offset + index_location.size],
+            footer.version,
+        )?;
+        meta.bloom_filter = bloom_filter;
+        Ok(meta)
+    }
 
-        cursor -= 4;
-        let version = (&buf[cursor..cursor + 4]).get_u32_le();
-        if version != VERSION {
-            return Err(HummockError::invalid_format_version(version));
+    /// Decodes the index block of a meta object. The bloom filter of the returned meta is empty.
+    pub fn decode_index_block(buf: &[u8], version: u32) -> HummockResult<Self> {
+        let cursor = buf
+            .len()
+            .checked_sub(8)
+            .ok_or_else(|| HummockError::decode_error("index block too short"))?;
+        let checksum = (&buf[cursor..]).get_u64_le();
+        let buf = &mut &buf[..cursor];
+        xxhash64_verify(buf, checksum)?;
+
+        let block_meta_count = buf.get_u32_le() as usize;
+        let mut block_metas = Vec::with_capacity(block_meta_count);
+        for _ in 0..block_meta_count {
+            block_metas.push(BlockMeta::decode(buf));
         }
+        let estimated_size = buf.get_u32_le();
+        let key_count = buf.get_u32_le();
+        let smallest_key = get_length_prefixed_slice(buf);
+        let largest_key = get_length_prefixed_slice(buf);
+
+        Ok(Self {
+            block_metas,
+            bloom_filter: vec![],
+            estimated_size,
+            key_count,
+            smallest_key,
+            largest_key,
+            version,
+        })
+    }
+
+    /// Decodes a meta object of a format version before [`SEPARATE_FILTER_VERSION`]:
+    ///
+    /// ```plain
+    /// | N (4B) |
+    /// | block meta 0 | ... | block meta N-1 |
+    /// | bloom filter len (4B) | bloom filter |
+    /// | estimated size (4B) | key count (4B) |
+    /// | smallest key len (4B) | smallest key |
+    /// | largest key len (4B) | largest key |
+    /// | checksum (8B) | version (4B) | magic (4B) |
+    /// ```
+    fn decode_legacy(buf: &mut &[u8]) -> HummockResult<Self> {
+        let version = check_magic_and_version(buf)?;
+        let mut cursor = buf.len() - META_TAIL_SIZE;
 
         cursor -= 8;
         let checksum = (&buf[cursor..cursor + 8]).get_u64_le();
@@ -221,23 +443,22 @@ impl SstableMeta {
 
     #[inline]
     pub fn encoded_size(&self) -> usize {
-        4 // block meta count
+        self.bloom_filter.len()
+            + 8 // filter checksum
+            + 4 // block meta count
             + self
             .block_metas
             .iter()
             .map(|block_meta| block_meta.encoded_size())
             .sum::<usize>()
-            + 4 // bloom filter len
-            + self.bloom_filter.len()
             + 4 // estimated size
             + 4 // key count
             + 4 // key len
             + self.smallest_key.len()
             + 4 // key len
             + self.largest_key.len()
-            + 8 // checksum
-            + 4 // version
-            + 4 // magic
+            + 8 // index checksum
+            + META_FOOTER_SIZE
     }
 }
 
@@ -245,9 +466,8 @@ impl SstableMeta {
 mod tests {
     use super::*;
 
-    #[test]
-    pub fn test_sstable_meta_enc_dec() {
-        let meta = SstableMeta {
+    fn test_meta() -> SstableMeta {
+        SstableMeta {
             block_metas: vec![
                 BlockMeta {
                     smallest_key: b"0-smallest-key".to_vec(),
@@ -266,8 +486,73 @@ mod tests {
             smallest_key: b"0-smallest-key".to_vec(),
             largest_key: b"9-largest-key".to_vec(),
             version: VERSION,
-        };
+        }
+    }
+
+    #[test]
+    pub fn test_sstable_meta_enc_dec() {
+        let meta = test_meta();
+        let buf = meta.encode_to_bytes();
+        assert_eq!(buf.len(), meta.encoded_size());
+        let decoded_meta = SstableMeta::decode(&mut &buf[..]).unwrap();
+        assert_eq!(decoded_meta, meta);
+    }
+
+    #[test]
+    pub fn test_sstable_meta_lazy_filter() {
+        let meta = test_meta();
         let buf = meta.encode_to_bytes();
+
+        let footer = MetaFooter::decode(&buf[buf.len() - META_FOOTER_SIZE..])
+            .unwrap()
+            .unwrap();
+        assert_eq!(footer.object_size(), buf.len());
+        let index_location = footer.index_location();
+        let index_meta = SstableMeta::decode_index_block(
+            &buf[index_location.offset..index_location.offset + index_location.size],
+            footer.version,
+        )
+        .unwrap();
+        assert!(index_meta.bloom_filter.is_empty());
+        assert_eq!(index_meta.block_metas, meta.block_metas);
+
+        let sst = Sstable::with_lazy_filter(0, index_meta, footer.filter_location());
+        assert!(!sst.has_bloom_filter());
+        let filter_location = sst.unloaded_filter_location().unwrap();
+        sst.set_filter(
+            decode_filter_block(
+                &buf[filter_location.offset..filter_location.offset + filter_location.size],
+            )
+            .unwrap(),
+        );
+        assert!(sst.unloaded_filter_location().is_none());
+        assert_eq!(sst.bloom_filter(), meta.bloom_filter.as_slice());
+    }
+
+    #[test]
+    pub fn test_sstable_meta_dec_legacy() {
+        let meta = SstableMeta {
+            version: TABLE_PREFIX_BLOOM_VERSION,
+            ..test_meta()
+        };
+        let mut buf = vec![];
+        buf.put_u32_le(meta.block_metas.len() as u32);
+        for block_meta in &meta.block_metas {
+            block_meta.encode(&mut buf);
+        }
+        put_length_prefixed_slice(&mut buf, &meta.bloom_filter);
+        buf.put_u32_le(meta.estimated_size);
+        buf.put_u32_le(meta.key_count);
+        put_length_prefixed_slice(&mut buf, &meta.smallest_key);
+        put_length_prefixed_slice(&mut buf, &meta.largest_key);
+        let checksum = xxhash64_checksum(&buf);
+        buf.put_u64_le(checksum);
+        buf.put_u32_le(TABLE_PREFIX_BLOOM_VERSION);
+        buf.put_u32_le(MAGIC);
+
+        assert!(MetaFooter::decode(&buf[buf.len() - META_TAIL_SIZE..])
+            .unwrap()
+            .is_none());
         let decoded_meta = SstableMeta::decode(&mut &buf[..]).unwrap();
         assert_eq!(decoded_meta, meta);
     }
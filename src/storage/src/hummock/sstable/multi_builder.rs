@@ -18,7 +18,7 @@ use risingwave_hummock_sdk::key::{Epoch, FullKey};
 use risingwave_hummock_sdk::HummockSSTableId;
 use risingwave_pb::common::VNodeBitmap;
 
-use super::SstableMeta;
+use super::{SstableBlobs, SstableMeta};
 use crate::hummock::value::HummockValue;
 use crate::hummock::{HummockResult, SSTableBuilder};
 
@@ -124,13 +124,21 @@ where
         }
     }
 
-    /// Finalizes all the tables to be ids, blocks and metadata.
-    pub fn finish(self) -> Vec<(HummockSSTableId, Bytes, SstableMeta, Vec<VNodeBitmap>)> {
+    /// Finalizes all the tables to be ids, blocks, metadata and separated values.
+    pub fn finish(
+        self,
+    ) -> Vec<(
+        HummockSSTableId,
+        Bytes,
+        SstableMeta,
+        Vec<VNodeBitmap>,
+        SstableBlobs,
+    )> {
         self.builders
             .into_iter()
             .map(|b| {
-                let (data, meta, vnode_bitmaps) = b.builder.finish();
-                (b.id, data, meta, vnode_bitmaps)
+                let (data, meta, vnode_bitmaps, blobs) = b.builder.finish();
+                (b.id, data, meta, vnode_bitmaps, blobs)
             })
             .collect()
     }
@@ -162,6 +170,7 @@ mod tests {
                     restart_interval: DEFAULT_RESTART_INTERVAL,
                     bloom_false_positive: 0.1,
                     compression_algorithm: CompressionAlgorithm::None,
+                    value_separation_threshold: 0,
                 }),
             ))
         };
@@ -185,6 +194,7 @@ mod tests {
                     restart_interval: DEFAULT_RESTART_INTERVAL,
                     bloom_false_positive: 0.1,
                     compression_algorithm: CompressionAlgorithm::None,
+                    value_separation_threshold: 0,
                 }),
             ))
         };
@@ -0,0 +1,145 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::cmp::Ordering::{Equal, Less};
+
+use bytes::Bytes;
+use risingwave_hummock_sdk::key::user_key;
+use risingwave_hummock_sdk::VersionedComparator;
+
+use super::{BlockIterator, Sstable};
+use crate::hummock::value::HummockValue;
+use crate::hummock::{CachePolicy, HummockResult, SstableStore};
+use crate::monitor::StoreLocalStatistic;
+
+/// Gets the newest version of the user key of `internal_key` in `sst` that is not newer than the
+/// epoch of `internal_key`. Returns `None` if `sst` has no such version.
+///
+/// Unlike seeking an [`SSTableIterator`](super::SSTableIterator), at most two blocks are read and
+/// nothing else is allocated. `block_iter` is reused across the SSTs read by a point get to avoid
+/// allocating the key buffer of a block iterator for each of them.
+pub async fn get_from_sstable(
+    sst: &Sstable,
+    sstable_store: &SstableStore,
+    internal_key: &[u8],
+    cache_policy: CachePolicy,
+    block_iter: &mut Option<BlockIterator>,
+    stats: &mut StoreLocalStatistic,
+) -> HummockResult<Option<HummockValue<Bytes>>> {
+    let block_idx = sst
+        .meta
+        .block_metas
+        .partition_point(|block_meta| {
+            let ord =
+                VersionedComparator::compare_key(block_meta.smallest_key.as_slice(), internal_key);
+            ord == Less || ord == Equal
+        })
+        .saturating_sub(1);
+
+    // The key may be the first one of the next block if it's larger than all keys of the block.
+    for idx in block_idx..std::cmp::min(block_idx + 2, sst.block_count()) {
+        let block = sstable_store
+            .get(sst, idx as u64, cache_policy, stats)
+            .await?;
+        let iter = match block_iter {
+            Some(iter) => {
+                iter.reset(block);
+                iter
+            }
+            None => block_iter.insert(BlockIterator::new(block)),
+        };
+        if idx == block_idx {
+            iter.seek(internal_key);
+        } else {
+            iter.seek_to_first();
+        }
+        if iter.is_valid() {
+            if user_key(iter.key()) != user_key(internal_key) {
+                return Ok(None);
+            }
+            let value = HummockValue::from_slice(iter.value())?;
+            return Ok(Some(match value {
+                HummockValue::Put(meta, value) => {
+                    HummockValue::Put(meta, Bytes::copy_from_slice(value))
+                }
+                HummockValue::Delete(meta) => HummockValue::Delete(meta),
+                HummockValue::Blob(meta, blob_ref) => {
+                    HummockValue::Put(meta, sstable_store.get_blob(&blob_ref).await?)
+                }
+            }));
+        }
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::executor::block_on;
+    use risingwave_hummock_sdk::key::key_with_epoch;
+
+    use super::*;
+    use crate::hummock::iterator::test_utils::mock_sstable_store;
+    use crate::hummock::test_utils::{default_builder_opt_for_test, gen_test_sstable};
+
+    #[tokio::test]
+    async fn test_get_from_sstable() {
+        let sstable_store = mock_sstable_store();
+        let mut opts = default_builder_opt_for_test();
+        // Small blocks, so that versions of a key span multiple blocks.
+        opts.block_capacity = 64;
+        let kv_iter = (0..100).flat_map(|i| {
+            let user_key = format!("key_test_{:05}", i).into_bytes();
+            [(300, i % 3 == 0), (200, false)]
+                .into_iter()
+                .map(move |(epoch, delete)| {
+                    let value = if delete {
+                        HummockValue::delete()
+                    } else {
+                        HummockValue::put(format!("value_{}_{}", i, epoch).into_bytes())
+                    };
+                    (key_with_epoch(user_key.clone(), epoch), value)
+                })
+        });
+        let sst = gen_test_sstable(opts, 0, kv_iter, sstable_store.clone()).await;
+        assert!(sst.block_count() > 1);
+
+        let mut block_iter = None;
+        let mut stats = StoreLocalStatistic::default();
+        let mut get = |i: usize, epoch: u64| {
+            let internal_key = key_with_epoch(format!("key_test_{:05}", i).into_bytes(), epoch);
+            block_on(get_from_sstable(
+                &sst,
+                &sstable_store,
+                &internal_key,
+                CachePolicy::Fill,
+                &mut block_iter,
+                &mut stats,
+            ))
+            .unwrap()
+        };
+        for i in 0..100 {
+            let old = HummockValue::put(Bytes::from(format!("value_{}_200", i)));
+            let new = if i % 3 == 0 {
+                HummockValue::delete()
+            } else {
+                HummockValue::put(Bytes::from(format!("value_{}_300", i)))
+            };
+            assert_eq!(get(i, 400), Some(new.clone()));
+            assert_eq!(get(i, 300), Some(new));
+            assert_eq!(get(i, 250), Some(old));
+            assert_eq!(get(i, 100), None);
+        }
+        assert_eq!(get(100, 400), None);
+    }
+}
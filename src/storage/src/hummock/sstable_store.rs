@@ -24,13 +24,19 @@ use futures::future::{try_join_all, FutureExt};
 use risingwave_hummock_sdk::{is_remote_sst_id, HummockSSTableId};
 use risingwave_object_store::object::{get_local_path, BlockLocation, ObjectStoreRef};
 
-use super::{Block, BlockCache, Sstable, SstableMeta};
+use super::{
+    decode_blob_value, decode_filter_block, BlobRef, Block, BlockCache, MetaFooter, Sstable,
+    SstableMeta,
+};
 use crate::hummock::{BlockHolder, CachableEntry, HummockError, HummockResult, LruCache};
 use crate::monitor::StoreLocalStatistic;
 
 const MAX_META_CACHE_SHARD_BITS: usize = 5;
 const MIN_BUFFER_SIZE_PER_SHARD: usize = 64 * 1024 * 1024; // 64MB
 const PREFETCH_BLOCK_COUNT: usize = 20;
+/// Size of the tail of a meta object read at first. The index block is usually covered by it, so
+/// that no other read is needed.
+const META_TAIL_PREFETCH_SIZE: usize = 64 * 1024;
 
 pub type TableHolder = CachableEntry<HummockSSTableId, Box<Sstable>>;
 
@@ -45,6 +51,12 @@ pub enum CachePolicy {
     NotFill,
 }
 
+impl Default for CachePolicy {
+    fn default() -> Self {
+        CachePolicy::Fill
+    }
+}
+
 pub struct SstableStore {
     path: String,
     store: ObjectStoreRef,
@@ -166,6 +178,31 @@ impl SstableStore {
             .map_err(HummockError::object_io_error)
     }
 
+    /// Uploads the blob file created along with the SST `sst_id`. It must be uploaded before the
+    /// SST.
+    pub async fn put_blob(&self, sst_id: HummockSSTableId, data: Bytes) -> HummockResult<()> {
+        let blob_path = self.get_sst_blob_path(sst_id);
+        self.store
+            .upload(&blob_path, data)
+            .await
+            .map_err(HummockError::object_io_error)
+    }
+
+    /// Reads a value separated into a blob file.
+    pub async fn get_blob(&self, blob_ref: &BlobRef) -> HummockResult<Bytes> {
+        let blob_path = self.get_sst_blob_path(blob_ref.blob_id);
+        let location = BlockLocation {
+            offset: blob_ref.offset as usize,
+            size: blob_ref.stored_len(),
+        };
+        let buf = self
+            .store
+            .read(&blob_path, Some(location))
+            .await
+            .map_err(HummockError::object_io_error)?;
+        decode_blob_value(buf)
+    }
+
     pub fn add_block_cache(
         &self,
         sst_id: HummockSSTableId,
@@ -283,18 +320,7 @@ impl SstableStore {
             let id = sst_id;
             let f = self
                 .meta_cache
-                .lookup_with_request_dedup(sst_id, sst_id, move || async move {
-                    let path = self.get_sst_meta_path(id);
-                    let buf = self
-                        .store
-                        .read(&path, None)
-                        .await
-                        .map_err(HummockError::object_io_error)?;
-                    let size = buf.len();
-                    let meta = SstableMeta::decode(&mut &buf[..])?;
-                    let sst = Box::new(Sstable { id, meta });
-                    Ok((sst, size))
-                })
+                .lookup_with_request_dedup(sst_id, sst_id, move || self.load_sstable(id))
                 .map(|result| match result {
                     Ok(inner_result) => inner_result,
                     Err(e) => Err(HummockError::other(format!(
@@ -317,18 +343,9 @@ impl SstableStore {
 
         let entry = self
             .meta_cache
-            .lookup_with_request_dedup::<_, HummockError, _>(sst_id, sst_id, || async {
+            .lookup_with_request_dedup::<_, HummockError, _>(sst_id, sst_id, || {
                 stats.cache_meta_block_miss += 1;
-                let path = self.get_sst_meta_path(sst_id);
-                let buf = self
-                    .store
-                    .read(&path, None)
-                    .await
-                    .map_err(HummockError::object_io_error)?;
-                let size = buf.len();
-                let meta = SstableMeta::decode(&mut &buf[..])?;
-                let sst = Box::new(Sstable { id: sst_id, meta });
-                Ok((sst, size))
+                self.load_sstable(sst_id)
             })
             .await
             .map_err(|e| {
@@ -340,6 +357,84 @@ impl SstableStore {
         Ok(entry)
     }
 
+    /// Reads the meta of an SST, and returns it with its charge in the meta cache. Unless the meta
+    /// object is small enough to be read as a whole, only the index block is read, and the filter
+    /// block is left to [`Self::load_filter`].
+    async fn load_sstable(&self, sst_id: HummockSSTableId) -> HummockResult<(Box<Sstable>, usize)> {
+        let path = self.get_sst_meta_path(sst_id);
+        let object_size = self
+            .store
+            .metadata(&path)
+            .await
+            .map_err(HummockError::object_io_error)?
+            .total_size;
+        let tail_offset = object_size.saturating_sub(META_TAIL_PREFETCH_SIZE);
+        let tail = self
+            .store
+            .read(
+                &path,
+                Some(BlockLocation {
+                    offset: tail_offset,
+                    size: object_size - tail_offset,
+                }),
+            )
+            .await
+            .map_err(HummockError::object_io_error)?;
+        if tail_offset == 0 {
+            let meta = SstableMeta::decode(&mut &tail[..])?;
+            return Ok((Box::new(Sstable::new(sst_id, meta)), object_size));
+        }
+        let footer = match MetaFooter::decode(&tail)? {
+            Some(footer) if footer.object_size() == object_size => footer,
+            Some(_) => {
+                return Err(HummockError::decode_error(format!(
+                    "size mismatch of meta object {}",
+                    path
+                )))
+            }
+            None => {
+                // A meta object of old format versions has to be read as a whole.
+                let buf = self
+                    .store
+                    .read(&path, None)
+                    .await
+                    .map_err(HummockError::object_io_error)?;
+                let meta = SstableMeta::decode(&mut &buf[..])?;
+                return Ok((Box::new(Sstable::new(sst_id, meta)), object_size));
+            }
+        };
+        let index_location = footer.index_location();
+        let index_block = if index_location.offset >= tail_offset {
+            let start = index_location.offset - tail_offset;
+            tail.slice(start..start + index_location.size)
+        } else {
+            self.store
+                .read(&path, Some(index_location))
+                .await
+                .map_err(HummockError::object_io_error)?
+        };
+        let meta = SstableMeta::decode_index_block(&index_block, footer.version)?;
+        let sst = Sstable::with_lazy_filter(sst_id, meta, footer.filter_location());
+        let charge = sst.encoded_size();
+        Ok((Box::new(sst), charge))
+    }
+
+    /// Loads the bloom filter of `sst` if it's not loaded with the meta.
+    pub async fn load_filter(&self, sst: &Sstable) -> HummockResult<()> {
+        let filter_location = match sst.unloaded_filter_location() {
+            Some(filter_location) => filter_location,
+            None => return Ok(()),
+        };
+        let path = self.get_sst_meta_path(sst.id);
+        let buf = self
+            .store
+            .read(&path, Some(filter_location))
+            .await
+            .map_err(HummockError::object_io_error)?;
+        sst.set_filter(decode_filter_block(&buf)?);
+        Ok(())
+    }
+
     pub fn get_sst_meta_path(&self, sst_id: HummockSSTableId) -> String {
         let mut ret = format!("{}/{}.meta", self.path, sst_id);
         if !is_remote_sst_id(sst_id) {
@@ -356,6 +451,14 @@ impl SstableStore {
         ret
     }
 
+    pub fn get_sst_blob_path(&self, sst_id: HummockSSTableId) -> String {
+        let mut ret = format!("{}/{}.blob", self.path, sst_id);
+        if !is_remote_sst_id(sst_id) {
+            ret = get_local_path(&ret);
+        }
+        ret
+    }
+
     pub fn store(&self) -> ObjectStoreRef {
         self.store.clone()
     }
@@ -368,6 +471,12 @@ impl SstableStore {
         self.block_cache.clone()
     }
 
+    /// Resizes the block cache and the meta cache, e.g. when the config is reloaded.
+    pub fn set_cache_capacity(&self, block_cache_capacity: usize, meta_cache_capacity: usize) {
+        self.block_cache.set_capacity(block_cache_capacity);
+        self.meta_cache.set_capacity(meta_cache_capacity);
+    }
+
     #[cfg(test)]
     pub fn clear_block_cache(&self) {
         self.block_cache.clear();
@@ -16,11 +16,13 @@ use std::future::Future;
 use std::ops::Bound::{Excluded, Included};
 use std::ops::RangeBounds;
 use std::sync::Arc;
+use std::time::Duration;
 
 use bytes::Bytes;
 use itertools::Itertools;
-use risingwave_hummock_sdk::key::key_with_epoch;
-use risingwave_hummock_sdk::HummockEpoch;
+use risingwave_hummock_sdk::key::{key_with_epoch, table_prefix};
+use risingwave_hummock_sdk::level::sst_runs;
+use risingwave_hummock_sdk::{HummockEpoch, HummockSSTableId};
 use risingwave_pb::common::VNodeBitmap;
 use risingwave_pb::hummock::SstableInfo;
 
@@ -39,7 +41,9 @@ use crate::hummock::shared_buffer::shared_buffer_batch::SharedBufferBatch;
 use crate::hummock::shared_buffer::{
     build_ordered_merge_iter, OrderSortedUncommittedData, UncommittedData,
 };
-use crate::hummock::utils::prune_ssts;
+use crate::hummock::utils::{
+    filter_single_sst, prune_ssts, sst_may_contain_table, table_id_of_key, table_id_of_range,
+};
 use crate::hummock::HummockResult;
 use crate::monitor::StoreLocalStatistic;
 use crate::storage_value::StorageValue;
@@ -82,7 +86,19 @@ impl HummockStorage {
         B: AsRef<[u8]> + Send,
         T: HummockIteratorType,
     {
-        let read_options = Arc::new(ReadOptions::default());
+        // When all the keys to read are of the same table, SSTs without the table are skipped. The
+        // range of backward iterators is from the larger key to the smaller one.
+        let table_id = match T::direction() {
+            DirectionEnum::Forward => table_id_of_range(&key_range),
+            DirectionEnum::Backward => {
+                table_id_of_range(&(key_range.end_bound(), key_range.start_bound()))
+            }
+        };
+        let read_options = Arc::new(ReadOptions {
+            prefix_hint: table_id.map(table_prefix),
+            cache_policy: self.table_cache_policy(table_id),
+            ..Default::default()
+        });
         let mut overlapped_iters = vec![];
 
         let (shared_buffer_data, pinned_version) = self.read_filter(epoch, &key_range, None)?;
@@ -107,52 +123,72 @@ impl HummockStorage {
         }
 
         // Generate iterators for versioned ssts by filter out ssts that do not overlap with given
-        // `key_range`
+        // `key_range`. The SSTs in a non-overlapping sub-level of L0 can be concatenated.
+        let mut read_ssts = vec![];
         for level in pinned_version.levels() {
-            let table_infos = prune_ssts(level.get_table_infos().iter(), &key_range, None);
-            if table_infos.is_empty() {
-                continue;
-            }
-            if can_concat(&table_infos) {
-                let start_table_idx = match key_range.start_bound() {
-                    Included(key) | Excluded(key) => search_sst_idx(&table_infos, key),
-                    _ => 0,
-                };
-                let end_table_idx = match key_range.end_bound() {
-                    Included(key) | Excluded(key) => search_sst_idx(&table_infos, key),
-                    _ => table_infos.len().saturating_sub(1),
-                };
-                assert!(start_table_idx < table_infos.len() && end_table_idx < table_infos.len());
-                let matched_table_infos = &table_infos[start_table_idx..=end_table_idx];
-
-                let tables = match T::Direction::direction() {
-                    DirectionEnum::Backward => matched_table_infos
-                        .iter()
-                        .rev()
-                        .map(|&info| info.clone())
-                        .collect_vec(),
-                    DirectionEnum::Forward => matched_table_infos
-                        .iter()
-                        .map(|&info| info.clone())
-                        .collect_vec(),
-                };
-
-                overlapped_iters.push(Box::new(ConcatIteratorInner::<T::SstableIteratorType>::new(
-                    tables,
-                    self.sstable_store(),
-                    read_options.clone(),
-                )) as BoxedHummockIterator<T::Direction>);
-            } else {
-                for table_info in table_infos.into_iter().rev() {
-                    let table = self
-                        .sstable_store
-                        .sstable(table_info.id, &mut stats)
-                        .await?;
-                    overlapped_iters.push(Box::new(T::SstableIteratorType::create(
-                        table,
-                        self.sstable_store(),
-                        read_options.clone(),
-                    )));
+            for run in sst_runs(level) {
+                let mut table_infos = prune_ssts(run.table_infos.iter(), &key_range, None);
+                if let Some(table_id) = table_id {
+                    table_infos.retain(|info| sst_may_contain_table(info, table_id));
+                }
+                if table_infos.is_empty() {
+                    continue;
+                }
+                if can_concat(&table_infos) {
+                    let start_table_idx = match key_range.start_bound() {
+                        Included(key) | Excluded(key) => search_sst_idx(&table_infos, key),
+                        _ => 0,
+                    };
+                    let end_table_idx = match key_range.end_bound() {
+                        Included(key) | Excluded(key) => search_sst_idx(&table_infos, key),
+                        _ => table_infos.len().saturating_sub(1),
+                    };
+                    assert!(
+                        start_table_idx < table_infos.len() && end_table_idx < table_infos.len()
+                    );
+                    let matched_table_infos = &table_infos[start_table_idx..=end_table_idx];
+                    read_ssts.extend(matched_table_infos.iter().map(|info| info.id));
+
+                    let tables = match T::Direction::direction() {
+                        DirectionEnum::Backward => matched_table_infos
+                            .iter()
+                            .rev()
+                            .map(|&info| info.clone())
+                            .collect_vec(),
+                        DirectionEnum::Forward => matched_table_infos
+                            .iter()
+                            .map(|&info| info.clone())
+                            .collect_vec(),
+                    };
+
+                    overlapped_iters.push(Box::new(
+                        ConcatIteratorInner::<T::SstableIteratorType>::new(
+                            tables,
+                            self.sstable_store(),
+                            read_options.clone(),
+                        ),
+                    )
+                        as BoxedHummockIterator<T::Direction>);
+                } else {
+                    for table_info in table_infos.into_iter().rev() {
+                        let table = self
+                            .sstable_store
+                            .sstable(table_info.id, &mut stats)
+                            .await?;
+                        if let Some(prefix_hint) = read_options.prefix_hint.as_ref() {
+                            self.sstable_store.load_filter(table.value()).await?;
+                            if table.value().surely_not_have_table_prefix(prefix_hint) {
+                                stats.bloom_filter_true_negative_count += 1;
+                                continue;
+                            }
+                        }
+                        read_ssts.push(table_info.id);
+                        overlapped_iters.push(Box::new(T::SstableIteratorType::create(
+                            table,
+                            self.sstable_store(),
+                            read_options.clone(),
+                        )));
+                    }
                 }
             }
         }
@@ -160,6 +196,8 @@ impl HummockStorage {
         self.stats
             .iter_merge_sstable_counts
             .observe(overlapped_iters.len() as f64);
+        self.read_statistics
+            .record_read(&read_ssts, overlapped_iters.len());
 
         let key_range = (
             key_range.start_bound().map(|b| b.as_ref().to_owned()),
@@ -171,6 +209,7 @@ impl HummockStorage {
             self.stats.clone(),
             key_range,
             epoch,
+            self.table_min_epoch(table_id),
             Some(pinned_version),
         );
 
@@ -191,6 +230,27 @@ impl HummockStorage {
         key: &'a [u8],
         epoch: u64,
         vnode_set: Option<VNodeBitmap>,
+    ) -> StorageResult<Option<Bytes>> {
+        if self.table_min_epoch(table_id_of_key(key)) > 0 {
+            // The epoch of the value is unknown to point gets, so the retention is applied by an
+            // iterator instead.
+            let mut iter = self.iter(key..=key, epoch).await?;
+            return Ok(iter.next().await?.map(|(_, value)| value));
+        }
+        let mut read_ssts = vec![];
+        let value = self.get_inner(key, epoch, vnode_set, &mut read_ssts).await;
+        self.read_statistics
+            .record_read(&read_ssts, read_ssts.len());
+        value
+    }
+
+    /// Gets the value of `key`, with the committed SSTs read appended to `read_ssts`.
+    async fn get_inner(
+        &self,
+        key: &[u8],
+        epoch: u64,
+        vnode_set: Option<VNodeBitmap>,
+        read_ssts: &mut Vec<HummockSSTableId>,
     ) -> StorageResult<Option<Bytes>> {
         let mut stats = StoreLocalStatistic::default();
         let (shared_buffer_data, pinned_version) =
@@ -206,8 +266,9 @@ impl HummockStorage {
 
         let mut table_counts = 0;
         let internal_key = key_with_epoch(key.to_vec(), epoch);
-        // TODO: may want to avoid use Arc in read options
-        let read_options = Arc::new(ReadOptions::default());
+        let cache_policy = self.table_cache_policy(table_id_of_key(key));
+        // Reused across the SSTs to read.
+        let mut block_iter = None;
 
         // Query shared buffer. Return the value without iterating SSTs if found
         for (replicated_batches, uncommitted_data) in shared_buffer_data {
@@ -236,12 +297,13 @@ impl HummockStorage {
                                     table,
                                     &internal_key,
                                     key,
-                                    read_options.clone(),
+                                    cache_policy,
+                                    &mut block_iter,
                                     &mut stats,
                                 )
                                 .await?
                             {
-                                return Ok(Some(v));
+                                return Ok(v);
                             }
                         }
                     }
@@ -253,20 +315,40 @@ impl HummockStorage {
             if level.table_infos.is_empty() {
                 continue;
             }
-            {
-                let table_infos =
-                    prune_ssts(level.table_infos.iter(), &(key..=key), vnode_set.as_ref());
+            // Search the sub-levels of L0 from the newest to the oldest.
+            for run in sst_runs(level).into_iter().rev() {
+                let table_infos = if run.is_overlapping() {
+                    prune_ssts(run.table_infos.iter(), &(key..=key), vnode_set.as_ref())
+                } else {
+                    // At most one SST in a non-overlapping run may contain the key.
+                    if run.table_infos.is_empty() {
+                        continue;
+                    }
+                    let table_info = &run.table_infos[search_sst_idx(run.table_infos, key)];
+                    if !filter_single_sst(table_info, &(key..=key), vnode_set.as_ref()) {
+                        continue;
+                    }
+                    vec![table_info]
+                };
                 for table_info in table_infos.into_iter().rev() {
                     let table = self
                         .sstable_store
                         .sstable(table_info.id, &mut stats)
                         .await?;
                     table_counts += 1;
+                    read_ssts.push(table_info.id);
                     if let Some(v) = self
-                        .get_from_table(table, &internal_key, key, read_options.clone(), &mut stats)
+                        .get_from_table(
+                            table,
+                            &internal_key,
+                            key,
+                            cache_policy,
+                            &mut block_iter,
+                            &mut stats,
+                        )
                         .await?
                     {
-                        return Ok(Some(v));
+                        return Ok(v);
                     }
                 }
             }
@@ -411,7 +493,13 @@ impl StateStore for HummockStorage {
     }
 
     fn wait_epoch(&self, epoch: u64) -> Self::WaitEpochFuture<'_> {
-        async move { Ok(self.local_version_manager.wait_epoch(epoch).await?) }
+        async move {
+            let timeout = Duration::from_millis(self.options.wait_epoch_timeout_ms);
+            Ok(self
+                .local_version_manager
+                .wait_epoch(epoch, timeout)
+                .await?)
+        }
     }
 
     fn sync(&self, epoch: Option<u64>) -> Self::SyncFuture<'_> {
@@ -426,6 +514,10 @@ impl StateStore for HummockStorage {
     fn get_uncommitted_ssts(&self, epoch: u64) -> Vec<SstableInfo> {
         self.local_version_manager.get_uncommitted_ssts(epoch)
     }
+
+    fn clear_shared_buffer(&self) {
+        self.local_version_manager.clear_shared_buffer()
+    }
 }
 
 pub struct HummockStateStoreIter {
@@ -17,6 +17,8 @@ use std::sync::Arc;
 use bytes::{BufMut, Bytes, BytesMut};
 use futures::executor::block_on;
 use risingwave_common::hash::VNODE_BITMAP_LEN;
+use risingwave_common::util::epoch::Epoch;
+use risingwave_hummock_sdk::key::table_prefix;
 use risingwave_hummock_sdk::HummockEpoch;
 use risingwave_meta::hummock::test_utils::setup_compute_env;
 use risingwave_meta::hummock::MockHummockMetaClient;
@@ -586,3 +588,217 @@ async fn test_write_anytime() {
     assert!(!hummock_storage.get_uncommitted_ssts(epoch1).is_empty());
     assert!(!hummock_storage.get_uncommitted_ssts(epoch2).is_empty());
 }
+
+#[tokio::test]
+async fn test_clear_shared_buffer() {
+    let sstable_store = mock_sstable_store();
+    let hummock_options = Arc::new(default_config_for_test());
+    let (_env, hummock_manager_ref, _cluster_manager_ref, worker_node) =
+        setup_compute_env(8080).await;
+    let meta_client = Arc::new(MockHummockMetaClient::new(
+        hummock_manager_ref.clone(),
+        worker_node.id,
+    ));
+    let hummock_storage = HummockStorage::with_default_stats(
+        hummock_options,
+        sstable_store,
+        meta_client.clone(),
+        Arc::new(StateStoreMetrics::unused()),
+    )
+    .await
+    .unwrap();
+
+    let initial_epoch = hummock_storage
+        .local_version_manager
+        .get_local_version()
+        .pinned_version()
+        .max_committed_epoch();
+    let epoch1 = initial_epoch + 1;
+    let epoch2 = epoch1 + 1;
+
+    let batch = vec![(Bytes::from("aa"), StorageValue::new_default_put("111"))];
+    hummock_storage
+        .ingest_batch(batch.clone(), epoch1)
+        .await
+        .unwrap();
+    hummock_storage.ingest_batch(batch, epoch2).await.unwrap();
+    hummock_storage.sync(Some(epoch1)).await.unwrap();
+    assert!(!hummock_storage.get_uncommitted_ssts(epoch1).is_empty());
+
+    // Both the synced and the unsynced epochs are dropped.
+    hummock_storage.clear_shared_buffer();
+    assert!(hummock_storage.get_uncommitted_ssts(epoch1).is_empty());
+    assert!(hummock_storage
+        .get("aa".as_bytes(), epoch2)
+        .await
+        .unwrap()
+        .is_none());
+    assert_eq!(
+        hummock_storage
+            .local_version_manager
+            .get_shared_buffer_size(),
+        0
+    );
+}
+
+#[tokio::test]
+async fn test_recover_from_checkpoint() {
+    let sstable_store = mock_sstable_store();
+    let hummock_options = Arc::new(default_config_for_test());
+    let (_env, hummock_manager_ref, _cluster_manager_ref, worker_node) =
+        setup_compute_env(8080).await;
+    let meta_client = Arc::new(MockHummockMetaClient::new(
+        hummock_manager_ref.clone(),
+        worker_node.id,
+    ));
+    let hummock_storage = HummockStorage::with_default_stats(
+        hummock_options,
+        sstable_store,
+        meta_client.clone(),
+        Arc::new(StateStoreMetrics::unused()),
+    )
+    .await
+    .unwrap();
+
+    let initial_epoch = hummock_storage
+        .local_version_manager
+        .get_local_version()
+        .pinned_version()
+        .max_committed_epoch();
+    let epoch1 = initial_epoch + 1;
+    let epoch2 = epoch1 + 1;
+    let epoch3 = epoch2 + 1;
+
+    // The checkpoint of epoch2 commits the states of epoch1 as well.
+    hummock_storage
+        .ingest_batch(
+            vec![(Bytes::from("aa"), StorageValue::new_default_put("111"))],
+            epoch1,
+        )
+        .await
+        .unwrap();
+    hummock_storage
+        .ingest_batch(
+            vec![(Bytes::from("bb"), StorageValue::new_default_put("222"))],
+            epoch2,
+        )
+        .await
+        .unwrap();
+    let mut ssts = vec![];
+    for epoch in [epoch1, epoch2] {
+        hummock_storage.sync(Some(epoch)).await.unwrap();
+        ssts.extend(hummock_storage.get_uncommitted_ssts(epoch));
+    }
+    meta_client.commit_epoch(epoch2, ssts).await.unwrap();
+    hummock_storage.wait_epoch(epoch2).await.unwrap();
+
+    // The states after the checkpoint are dropped on recovery.
+    hummock_storage
+        .ingest_batch(
+            vec![
+                (Bytes::from("aa"), StorageValue::new_default_put("333")),
+                (Bytes::from("cc"), StorageValue::new_default_put("333")),
+            ],
+            epoch3,
+        )
+        .await
+        .unwrap();
+    hummock_storage.clear_shared_buffer();
+
+    let read_epoch = epoch3 + 1;
+    assert_eq!(
+        hummock_storage
+            .get(&Bytes::from("aa"), read_epoch)
+            .await
+            .unwrap()
+            .unwrap(),
+        Bytes::from("111")
+    );
+    assert_eq!(
+        hummock_storage
+            .get(&Bytes::from("bb"), read_epoch)
+            .await
+            .unwrap()
+            .unwrap(),
+        Bytes::from("222")
+    );
+    assert!(hummock_storage
+        .get(&Bytes::from("cc"), read_epoch)
+        .await
+        .unwrap()
+        .is_none());
+}
+
+#[tokio::test]
+async fn test_table_retention() {
+    let sstable_store = mock_sstable_store();
+    let hummock_options = Arc::new(default_config_for_test());
+    let (_env, hummock_manager_ref, _cluster_manager_ref, worker_node) =
+        setup_compute_env(8080).await;
+    let meta_client = Arc::new(MockHummockMetaClient::new(
+        hummock_manager_ref.clone(),
+        worker_node.id,
+    ));
+    let hummock_storage = HummockStorage::with_default_stats(
+        hummock_options,
+        sstable_store,
+        meta_client.clone(),
+        Arc::new(StateStoreMetrics::unused()),
+    )
+    .await
+    .unwrap();
+
+    let key = |table_id: u32, key: &str| {
+        let mut full_key = table_prefix(table_id);
+        full_key.extend_from_slice(key.as_bytes());
+        Bytes::from(full_key)
+    };
+    let now = Epoch::now().physical_time();
+    let epoch1 = Epoch::from_physical_time(now - 20_000).0;
+    let epoch2 = Epoch::from_physical_time(now).0;
+    let batch1 = vec![
+        (key(1, "aa"), StorageValue::new_default_put("111")),
+        (key(1, "bb"), StorageValue::new_default_put("222")),
+        (key(2, "aa"), StorageValue::new_default_put("333")),
+    ];
+    hummock_storage.ingest_batch(batch1, epoch1).await.unwrap();
+    let batch2 = vec![(key(1, "bb"), StorageValue::new_default_put("444"))];
+    hummock_storage.ingest_batch(batch2, epoch2).await.unwrap();
+
+    // The rows of table 1 written 20 seconds ago are expired by its retention of 10 seconds.
+    hummock_storage.set_table_retention(1, 10);
+    assert!(hummock_storage
+        .get(&key(1, "aa"), epoch2)
+        .await
+        .unwrap()
+        .is_none());
+    assert_eq!(
+        hummock_storage.get(&key(1, "bb"), epoch2).await.unwrap(),
+        Some(Bytes::from("444"))
+    );
+    assert_eq!(
+        hummock_storage
+            .scan(key(1, "")..key(2, ""), None, epoch2)
+            .await
+            .unwrap(),
+        vec![(key(1, "bb"), Bytes::from("444"))]
+    );
+    assert_eq!(
+        hummock_storage
+            .backward_scan(key(1, "")..key(2, ""), None, epoch2)
+            .await
+            .unwrap(),
+        vec![(key(1, "bb"), Bytes::from("444"))]
+    );
+    assert_eq!(
+        hummock_storage.get(&key(2, "aa"), epoch2).await.unwrap(),
+        Some(Bytes::from("333"))
+    );
+
+    // The rows are read again without the retention.
+    hummock_storage.set_table_retention(1, 0);
+    assert_eq!(
+        hummock_storage.get(&key(1, "aa"), epoch2).await.unwrap(),
+        Some(Bytes::from("111"))
+    );
+}
@@ -43,6 +43,7 @@ pub fn default_config_for_test() -> StorageConfig {
         sstable_size_mb: 256,
         block_size_kb: 64,
         bloom_false_positive: 0.1,
+        value_separation_threshold_kb: 0,
         share_buffers_sync_parallelism: 2,
         share_buffer_compaction_worker_threads_number: 1,
         shared_buffer_capacity_mb: 64,
@@ -52,7 +53,9 @@ pub fn default_config_for_test() -> StorageConfig {
         meta_cache_capacity_mb: 64,
         disable_remote_compactor: false,
         enable_local_spill: false,
+        enable_state_store_sanity_check: false,
         local_object_store: "memory".to_string(),
+        wait_epoch_timeout_ms: 10_000,
     }
 }
 
@@ -83,6 +86,7 @@ pub fn gen_dummy_sst_info(id: HummockSSTableId, batches: Vec<SharedBufferBatch>)
         }),
         file_size: batches.len() as u64,
         vnode_bitmaps: vec![],
+        blob_ids: vec![],
     }
 }
 
@@ -114,6 +118,7 @@ pub fn default_builder_opt_for_test() -> SSTableBuilderOptions {
         restart_interval: DEFAULT_RESTART_INTERVAL,
         bloom_false_positive: 0.1,
         compression_algorithm: CompressionAlgorithm::None,
+        value_separation_threshold: 0,
     }
 }
 
@@ -126,7 +131,8 @@ pub fn gen_test_sstable_data(
     for (key, value) in kv_iter {
         b.add(&key, value.as_slice())
     }
-    b.finish()
+    let (data, meta, vnode_bitmaps, _) = b.finish();
+    (data, meta, vnode_bitmaps)
 }
 
 /// Generates a test table from the given `kv_iter` and put the kv value to `sstable_store`
@@ -138,7 +144,7 @@ pub async fn gen_test_sstable_inner(
     policy: CachePolicy,
 ) -> Sstable {
     let (data, meta, _) = gen_test_sstable_data(opts, kv_iter);
-    let sst = Sstable { id: sst_id, meta };
+    let sst = Sstable::new(sst_id, meta);
     sstable_store.put(sst.clone(), data, policy).await.unwrap();
     sst
 }
@@ -12,12 +12,13 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::borrow::Borrow;
 use std::cmp::Ordering;
 use std::ops::Bound::{Excluded, Included, Unbounded};
 use std::ops::RangeBounds;
 
 use risingwave_common::hash::VNODE_BITMAP_LEN;
-use risingwave_hummock_sdk::key::user_key;
+use risingwave_hummock_sdk::key::{get_table_id, next_key, user_key, TABLE_PREFIX_LEN};
 use risingwave_pb::common::VNodeBitmap;
 use risingwave_pb::hummock::{Level, SstableInfo};
 
@@ -60,7 +61,7 @@ pub fn validate_epoch(safe_epoch: u64, epoch: u64) -> HummockResult<()> {
     Ok(())
 }
 
-pub fn validate_table_key_range(levels: &[Level]) -> HummockResult<()> {
+pub fn validate_table_key_range(levels: &[&Level]) -> HummockResult<()> {
     for l in levels {
         for t in &l.table_infos {
             if t.key_range.is_none() {
@@ -93,6 +94,49 @@ pub fn bitmap_overlap(pattern: &VNodeBitmap, sst_bitmaps: &Vec<VNodeBitmap>) ->
     false
 }
 
+/// Returns the table id if all the keys in `key_range` are of the same table, e.g. when the range
+/// is the key space of a keyspace.
+pub fn table_id_of_range<R, B>(key_range: &R) -> Option<u32>
+where
+    R: RangeBounds<B>,
+    B: AsRef<[u8]>,
+{
+    let start = match key_range.start_bound() {
+        Included(key) | Excluded(key) => key.as_ref(),
+        Unbounded => return None,
+    };
+    if start.len() < TABLE_PREFIX_LEN {
+        return None;
+    }
+    let table_id = get_table_id(start)?;
+    let prefix = &start[..TABLE_PREFIX_LEN];
+    match key_range.end_bound() {
+        Included(end) if end.as_ref().starts_with(prefix) => Some(table_id),
+        Excluded(end) if end.as_ref().starts_with(prefix) || end.as_ref() == next_key(prefix) => {
+            Some(table_id)
+        }
+        _ => None,
+    }
+}
+
+/// Returns the table id of `key` if it's prefixed by one.
+pub fn table_id_of_key(key: &[u8]) -> Option<u32> {
+    if key.len() < TABLE_PREFIX_LEN {
+        return None;
+    }
+    get_table_id(key)
+}
+
+/// Returns false if the SST surely contains no key of table `table_id`, according to the tables
+/// recorded in its vnode bitmaps.
+pub fn sst_may_contain_table(info: &SstableInfo, table_id: u32) -> bool {
+    info.vnode_bitmaps.is_empty()
+        || info
+            .vnode_bitmaps
+            .binary_search_by_key(&table_id, |bitmap| bitmap.get_table_id())
+            .is_ok()
+}
+
 pub fn filter_single_sst<R, B>(
     info: &SstableInfo,
     key_range: &R,
@@ -141,13 +185,40 @@ pub fn can_concat(ssts: &[&SstableInfo]) -> bool {
 }
 
 /// Search the SST containing the specified key within a level, using binary search.
-pub(crate) fn search_sst_idx<B>(ssts: &[&SstableInfo], key: &B) -> usize
+pub(crate) fn search_sst_idx<S, B>(ssts: &[S], key: &B) -> usize
 where
+    S: Borrow<SstableInfo>,
     B: AsRef<[u8]> + Send + ?Sized,
 {
     ssts.partition_point(|table| {
+        let table = table.borrow();
         let ord = user_key(&table.key_range.as_ref().unwrap().left).cmp(key.as_ref());
         ord == Ordering::Less || ord == Ordering::Equal
     })
     .saturating_sub(1) // considering the boundary of 0
 }
+
+#[cfg(test)]
+mod tests {
+    use risingwave_hummock_sdk::key::table_prefix;
+
+    use super::*;
+
+    #[test]
+    fn test_table_id_of_range() {
+        let prefix = table_prefix(1);
+        let key = |suffix: &[u8]| [prefix.as_slice(), suffix].concat();
+
+        assert_eq!(
+            table_id_of_range(&(prefix.clone()..next_key(&prefix))),
+            Some(1)
+        );
+        assert_eq!(table_id_of_range(&(key(b"a")..=key(b"z"))), Some(1));
+        assert_eq!(table_id_of_range(&(key(b"a")..table_prefix(2))), Some(1));
+        assert_eq!(table_id_of_range(&(key(b"a")..key(b"z"))), Some(1));
+        assert_eq!(table_id_of_range(&(key(b"a")..=table_prefix(2))), None);
+        assert_eq!(table_id_of_range(&(key(b"a")..table_prefix(3))), None);
+        assert_eq!(table_id_of_range(&(key(b"a")..)), None);
+        assert_eq!(table_id_of_range(&(b"a".to_vec()..b"b".to_vec())), None);
+    }
+}
@@ -41,6 +41,11 @@ impl Vacuum {
                 .delete(sstable_store.get_sst_data_path(*sst_id).as_str())
                 .await
                 .map_err(HummockError::object_io_error)?;
+            // Blob, which may not exist.
+            store
+                .delete(sstable_store.get_sst_blob_path(*sst_id).as_str())
+                .await
+                .map_err(HummockError::object_io_error)?;
         }
 
         // TODO: report progress instead of in one go.
@@ -15,20 +15,25 @@
 use bytes::{Buf, BufMut, Bytes};
 use risingwave_common::hash::VirtualNode;
 
-use super::{HummockError, HummockResult};
+use super::{BlobRef, HummockError, HummockResult, BLOB_REF_SIZE};
 use crate::storage_value::{StorageValue, ValueMeta, VALUE_META_SIZE};
 
 pub const VALUE_DELETE: u8 = 1 << 0;
 pub const VALUE_PUT: u8 = 0;
+pub const VALUE_BLOB: u8 = 1 << 1;
 
 /// [`HummockValue`] can be created on either a `Vec<u8>` or a `&[u8]`.
 ///
 /// Its encoding is a 1-byte flag + storage value. For `Put`, storage value contains both value meta
-/// and user value. For `Delete`, storage value contains only value meta.
+/// and user value. For `Delete`, storage value contains only value meta. For `Blob`, storage value
+/// contains value meta and the location of the user value in a blob file.
 #[derive(Debug, Clone)]
 pub enum HummockValue<T> {
     Put(ValueMeta, T),
     Delete(ValueMeta),
+    /// A put whose user value is separated into a blob file. SST iterators read the user value
+    /// and return a `Put` instead, unless compaction keeps the blob ref.
+    Blob(ValueMeta, BlobRef),
 }
 
 impl<T> Copy for HummockValue<T> where T: Copy {}
@@ -38,6 +43,7 @@ impl<T: PartialEq> PartialEq for HummockValue<T> {
         match (self, other) {
             (Self::Put(_, l0), Self::Put(_, r0)) => l0.eq(r0),
             (Self::Delete(_), Self::Delete(_)) => true,
+            (Self::Blob(_, l0), Self::Blob(_, r0)) => l0.eq(r0),
             _ => false,
         }
     }
@@ -53,6 +59,7 @@ where
         match self {
             HummockValue::Put(_, val) => 1 + VALUE_META_SIZE + val.as_ref().len(),
             HummockValue::Delete(_) => 1 + VALUE_META_SIZE,
+            HummockValue::Blob(..) => 1 + VALUE_META_SIZE + BLOB_REF_SIZE,
         }
     }
 
@@ -72,15 +79,33 @@ where
                 meta.encode(buffer);
                 meta.vnode
             }
+            HummockValue::Blob(meta, blob_ref) => {
+                // set flag
+                buffer.put_u8(VALUE_BLOB);
+                meta.encode(buffer);
+                blob_ref.encode(buffer);
+                meta.vnode
+            }
         }
     }
 
     /// Gets the user value out of the `HummockValue`. If the current value is `Delete`, `None` will
     /// be returned.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the user value is in a blob file and not read yet.
     pub fn into_user_value(self) -> Option<T> {
         match self {
             Self::Put(_, val) => Some(val),
             Self::Delete(_) => None,
+            Self::Blob(_, blob_ref) => panic!("value in blob file not read: {:?}", blob_ref),
+        }
+    }
+
+    pub fn value_meta(&self) -> ValueMeta {
+        match self {
+            Self::Put(meta, _) | Self::Delete(meta) | Self::Blob(meta, _) => *meta,
         }
     }
 
@@ -109,6 +134,10 @@ impl HummockValue<Vec<u8>> {
                 Vec::from(&buffer.chunk()[VALUE_META_SIZE..]),
             )),
             VALUE_DELETE => Ok(Self::Delete(ValueMeta::decode(&mut buffer.chunk()))),
+            VALUE_BLOB => Ok(Self::Blob(
+                ValueMeta::decode(&mut buffer.chunk()),
+                BlobRef::decode(&mut &buffer.chunk()[VALUE_META_SIZE..])?,
+            )),
             _ => Err(HummockError::decode_error("non-empty but format error")),
         }
     }
@@ -117,6 +146,7 @@ impl HummockValue<Vec<u8>> {
         match self {
             HummockValue::Put(meta, data) => HummockValue::Put(*meta, data),
             HummockValue::Delete(meta) => HummockValue::Delete(*meta),
+            HummockValue::Blob(meta, blob_ref) => HummockValue::Blob(*meta, *blob_ref),
         }
     }
 }
@@ -130,6 +160,10 @@ impl<'a> HummockValue<&'a [u8]> {
         match buffer.get_u8() {
             VALUE_PUT => Ok(Self::Put(ValueMeta::decode(&mut buffer), buffer)),
             VALUE_DELETE => Ok(Self::Delete(ValueMeta::decode(&mut buffer))),
+            VALUE_BLOB => {
+                let meta = ValueMeta::decode(&mut buffer);
+                Ok(Self::Blob(meta, BlobRef::decode(&mut buffer)?))
+            }
             _ => Err(HummockError::decode_error("non-empty but format error")),
         }
     }
@@ -139,6 +173,7 @@ impl<'a> HummockValue<&'a [u8]> {
         match self {
             HummockValue::Put(meta, value) => HummockValue::Put(*meta, value.to_vec()),
             HummockValue::Delete(meta) => HummockValue::Delete(*meta),
+            HummockValue::Blob(meta, blob_ref) => HummockValue::Blob(*meta, *blob_ref),
         }
     }
 }
@@ -148,6 +183,7 @@ impl HummockValue<Bytes> {
         match self {
             HummockValue::Put(meta, data) => HummockValue::Put(*meta, &data[..]),
             HummockValue::Delete(meta) => HummockValue::Delete(*meta),
+            HummockValue::Blob(meta, blob_ref) => HummockValue::Blob(*meta, *blob_ref),
         }
     }
 
@@ -155,6 +191,7 @@ impl HummockValue<Bytes> {
         match self {
             HummockValue::Put(meta, data) => HummockValue::Put(*meta, data.to_vec()),
             HummockValue::Delete(meta) => HummockValue::Delete(*meta),
+            HummockValue::Blob(meta, blob_ref) => HummockValue::Blob(*meta, *blob_ref),
         }
     }
 }
@@ -164,6 +201,7 @@ impl From<HummockValue<Vec<u8>>> for HummockValue<Bytes> {
         match data {
             HummockValue::Put(meta, data) => HummockValue::Put(meta, data.into()),
             HummockValue::Delete(meta) => HummockValue::Delete(meta),
+            HummockValue::Blob(meta, blob_ref) => HummockValue::Blob(meta, blob_ref),
         }
     }
 }
@@ -202,4 +240,23 @@ mod tests {
             HummockValue::from_slice(&result).unwrap()
         );
     }
+
+    #[test]
+    fn test_blob_decode_encode() {
+        let mut result = vec![];
+        let value_meta = ValueMeta { vnode: 63492 };
+        let blob_ref = BlobRef {
+            blob_id: 1,
+            offset: 2,
+            len: 3,
+        };
+        let value = HummockValue::<Vec<u8>>::Blob(value_meta, blob_ref);
+        value.encode(&mut result);
+        assert_eq!(result.len(), value.encoded_len());
+        assert_eq!(value, HummockValue::decode(&mut &result[..]).unwrap());
+        assert_eq!(
+            HummockValue::Blob(value_meta, blob_ref),
+            HummockValue::from_slice(&result).unwrap()
+        );
+    }
 }
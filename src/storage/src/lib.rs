@@ -45,6 +45,7 @@ pub mod keyspace;
 pub mod memory;
 pub mod monitor;
 pub mod panic_store;
+pub mod sanity_check_store;
 pub mod storage_value;
 #[macro_use]
 pub mod store;
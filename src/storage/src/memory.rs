@@ -29,7 +29,7 @@ use crate::storage_value::StorageValue;
 use crate::store::*;
 use crate::{define_state_store_associated_type, StateStore, StateStoreIter};
 
-type KeyWithEpoch = (Bytes, Reverse<u64>);
+pub(crate) type KeyWithEpoch = (Bytes, Reverse<u64>);
 
 /// An in-memory state store
 ///
@@ -51,7 +51,7 @@ impl Default for MemoryStateStore {
     }
 }
 
-fn to_bytes_range<R, B>(range: R) -> (Bound<KeyWithEpoch>, Bound<KeyWithEpoch>)
+pub(crate) fn to_bytes_range<R, B>(range: R) -> (Bound<KeyWithEpoch>, Bound<KeyWithEpoch>)
 where
     R: RangeBounds<B> + Send,
     B: AsRef<[u8]>,
@@ -235,6 +235,10 @@ where
     fn get_uncommitted_ssts(&self, epoch: u64) -> Vec<SstableInfo> {
         self.inner.get_uncommitted_ssts(epoch)
     }
+
+    fn clear_shared_buffer(&self) {
+        self.inner.clear_shared_buffer()
+    }
 }
 
 /// A state store iterator wrapper for monitoring metrics.
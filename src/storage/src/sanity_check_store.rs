@@ -0,0 +1,441 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BTreeMap, VecDeque};
+use std::future::Future;
+use std::ops::RangeBounds;
+use std::sync::Arc;
+
+use bytes::Bytes;
+use parking_lot::RwLock;
+use risingwave_pb::hummock::SstableInfo;
+
+use crate::memory::{to_bytes_range, KeyWithEpoch};
+use crate::storage_value::StorageValue;
+use crate::store::*;
+use crate::{define_state_store_associated_type, StateStore, StateStoreIter};
+
+/// A state store that mirrors every write into an in-memory shadow, and panics if a read from the
+/// inner state store diverges from the shadow. Used to catch correctness regressions of Hummock,
+/// e.g. iterator ordering and epoch visibility, in tests.
+///
+/// Only keys written through this store are checked, so that data written by other compute nodes
+/// or before a restart is ignored. Data of epochs rolled back by recovery is still in the shadow,
+/// so the check should not be enabled in tests that trigger recovery.
+#[derive(Clone)]
+pub struct SanityCheckStateStore<S> {
+    inner: S,
+
+    /// Stores (key, epoch) -> user value of all writes. `None` means the key is deleted.
+    shadow: Arc<RwLock<BTreeMap<KeyWithEpoch, Option<Bytes>>>>,
+}
+
+impl<S> SanityCheckStateStore<S> {
+    pub fn new(inner: S) -> Self {
+        Self {
+            inner,
+            shadow: Default::default(),
+        }
+    }
+
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+
+    /// Returns the keys ever written in the range in ascending order, with their values visible
+    /// at `epoch`.
+    fn shadow_scan<R, B>(&self, key_range: R, epoch: u64) -> VecDeque<(Bytes, Option<Bytes>)>
+    where
+        R: RangeBounds<B> + Send,
+        B: AsRef<[u8]>,
+    {
+        let shadow = self.shadow.read();
+        let mut result: VecDeque<(Bytes, Option<Bytes>)> = VecDeque::new();
+        let mut resolved = false;
+        for ((key, Reverse(key_epoch)), value) in shadow.range(to_bytes_range(key_range)) {
+            if result.back().map_or(true, |(last_key, _)| last_key != key) {
+                result.push_back((key.clone(), None));
+                resolved = false;
+            }
+            // Versions of a key are sorted by epoch in descending order.
+            if !resolved && *key_epoch <= epoch {
+                result.back_mut().unwrap().1 = value.clone();
+                resolved = true;
+            }
+        }
+        result
+    }
+
+    fn checker<R, B>(
+        &self,
+        key_range: R,
+        epoch: u64,
+        backward: bool,
+        operation: &'static str,
+    ) -> ShadowChecker
+    where
+        R: RangeBounds<B> + Send,
+        B: AsRef<[u8]>,
+    {
+        let mut expected = self.shadow_scan(key_range, epoch);
+        if backward {
+            expected = expected.into_iter().rev().collect();
+        }
+        ShadowChecker {
+            expected,
+            last_key: None,
+            backward,
+            operation,
+            epoch,
+        }
+    }
+
+    fn write_shadow(&self, kv_pairs: Vec<(Bytes, Option<Bytes>)>, epoch: u64) {
+        let mut shadow = self.shadow.write();
+        for (key, value) in kv_pairs {
+            shadow.insert((key, Reverse(epoch)), value);
+        }
+    }
+}
+
+/// Checks the key-value pairs read from the inner state store one by one against the shadow.
+struct ShadowChecker {
+    /// Keys in the shadow not checked yet, in the order of the read.
+    expected: VecDeque<(Bytes, Option<Bytes>)>,
+    last_key: Option<Bytes>,
+    backward: bool,
+    operation: &'static str,
+    epoch: u64,
+}
+
+impl ShadowChecker {
+    /// Whether `key` comes before `other` in the order of the read.
+    fn precedes(&self, key: &Bytes, other: &Bytes) -> bool {
+        let ordering = if self.backward {
+            Ordering::Greater
+        } else {
+            Ordering::Less
+        };
+        key.cmp(other) == ordering
+    }
+
+    /// Checks the next pair read from the inner state store, where `None` means the read has
+    /// reached the end of the range.
+    fn check_next(&mut self, item: Option<&(Bytes, Bytes)>) {
+        if let Some((key, _)) = item {
+            if let Some(last_key) = self.last_key.as_ref() {
+                if !self.precedes(last_key, key) {
+                    self.fail(format!("key {:?} is read after key {:?}", key, last_key));
+                }
+            }
+            self.last_key = Some(key.clone());
+        }
+
+        // Keys visible in the shadow but skipped by the read are missing.
+        while let Some((expected_key, expected_value)) = self.expected.front() {
+            let skipped = match item {
+                Some((key, _)) => self.precedes(expected_key, key),
+                None => true,
+            };
+            if !skipped {
+                break;
+            }
+            if expected_value.is_some() {
+                self.fail(format!(
+                    "key {:?} is missing, expected value {:?}",
+                    expected_key, expected_value
+                ));
+            }
+            self.expected.pop_front();
+        }
+
+        if let Some((key, value)) = item {
+            // Keys not written through this store are not checked.
+            let written =
+                matches!(self.expected.front(), Some((expected_key, _)) if expected_key == key);
+            if written {
+                let (_, expected_value) = self.expected.pop_front().unwrap();
+                if expected_value.as_ref() != Some(value) {
+                    self.fail(format!(
+                        "key {:?} has value {:?}, expected {:?}",
+                        key, value, expected_value
+                    ));
+                }
+            }
+        }
+    }
+
+    fn fail(&self, msg: String) -> ! {
+        panic!(
+            "state store sanity check failed in {} at epoch {}: {}",
+            self.operation, self.epoch, msg
+        );
+    }
+}
+
+impl<S> StateStore for SanityCheckStateStore<S>
+where
+    S: StateStore,
+{
+    type Iter = SanityCheckStateStoreIter<S::Iter>;
+
+    define_state_store_associated_type!();
+
+    fn get<'a>(&'a self, key: &'a [u8], epoch: u64) -> Self::GetFuture<'_> {
+        async move {
+            let value = self.inner.get(key, epoch).await?;
+            let mut checker = self.checker(key..=key, epoch, false, "get");
+            if !checker.expected.is_empty() {
+                let item = value
+                    .clone()
+                    .map(|value| (Bytes::copy_from_slice(key), value));
+                checker.check_next(item.as_ref());
+                checker.check_next(None);
+            }
+            Ok(value)
+        }
+    }
+
+    fn scan<R, B>(
+        &self,
+        key_range: R,
+        limit: Option<usize>,
+        epoch: u64,
+    ) -> Self::ScanFuture<'_, R, B>
+    where
+        R: RangeBounds<B> + Send,
+        B: AsRef<[u8]> + Send,
+    {
+        async move {
+            let key_range = (
+                key_range.start_bound().map(|b| b.as_ref().to_vec()),
+                key_range.end_bound().map(|b| b.as_ref().to_vec()),
+            );
+            let result = self.inner.scan(key_range.clone(), limit, epoch).await?;
+            let mut checker = self.checker(key_range, epoch, false, "scan");
+            for item in &result {
+                checker.check_next(Some(item));
+            }
+            // Keys after the last one are not read if the limit is reached.
+            if limit.map_or(true, |limit| result.len() < limit) {
+                checker.check_next(None);
+            }
+            Ok(result)
+        }
+    }
+
+    fn backward_scan<R, B>(
+        &self,
+        key_range: R,
+        limit: Option<usize>,
+        epoch: u64,
+    ) -> Self::BackwardScanFuture<'_, R, B>
+    where
+        R: RangeBounds<B> + Send,
+        B: AsRef<[u8]> + Send,
+    {
+        async move {
+            let key_range = (
+                key_range.start_bound().map(|b| b.as_ref().to_vec()),
+                key_range.end_bound().map(|b| b.as_ref().to_vec()),
+            );
+            let result = self
+                .inner
+                .backward_scan(key_range.clone(), limit, epoch)
+                .await?;
+            let mut checker = self.checker(key_range, epoch, true, "backward_scan");
+            for item in &result {
+                checker.check_next(Some(item));
+            }
+            if limit.map_or(true, |limit| result.len() < limit) {
+                checker.check_next(None);
+            }
+            Ok(result)
+        }
+    }
+
+    fn ingest_batch(
+        &self,
+        kv_pairs: Vec<(Bytes, StorageValue)>,
+        epoch: u64,
+    ) -> Self::IngestBatchFuture<'_> {
+        async move {
+            let shadow_pairs = kv_pairs
+                .iter()
+                .map(|(key, value)| (key.clone(), value.user_value.clone()))
+                .collect();
+            let size = self.inner.ingest_batch(kv_pairs, epoch).await?;
+            self.write_shadow(shadow_pairs, epoch);
+            Ok(size)
+        }
+    }
+
+    fn replicate_batch(
+        &self,
+        kv_pairs: Vec<(Bytes, StorageValue)>,
+        epoch: u64,
+    ) -> Self::ReplicateBatchFuture<'_> {
+        async move {
+            let shadow_pairs = kv_pairs
+                .iter()
+                .map(|(key, value)| (key.clone(), value.user_value.clone()))
+                .collect();
+            self.inner.replicate_batch(kv_pairs, epoch).await?;
+            self.write_shadow(shadow_pairs, epoch);
+            Ok(())
+        }
+    }
+
+    fn iter<R, B>(&self, key_range: R, epoch: u64) -> Self::IterFuture<'_, R, B>
+    where
+        R: RangeBounds<B> + Send,
+        B: AsRef<[u8]> + Send,
+    {
+        async move {
+            let key_range = (
+                key_range.start_bound().map(|b| b.as_ref().to_vec()),
+                key_range.end_bound().map(|b| b.as_ref().to_vec()),
+            );
+            let inner = self.inner.iter(key_range.clone(), epoch).await?;
+            Ok(SanityCheckStateStoreIter {
+                inner,
+                checker: self.checker(key_range, epoch, false, "iter"),
+            })
+        }
+    }
+
+    fn backward_iter<R, B>(&self, key_range: R, epoch: u64) -> Self::BackwardIterFuture<'_, R, B>
+    where
+        R: RangeBounds<B> + Send,
+        B: AsRef<[u8]> + Send,
+    {
+        async move {
+            let key_range = (
+                key_range.start_bound().map(|b| b.as_ref().to_vec()),
+                key_range.end_bound().map(|b| b.as_ref().to_vec()),
+            );
+            let inner = self.inner.backward_iter(key_range.clone(), epoch).await?;
+            Ok(SanityCheckStateStoreIter {
+                inner,
+                checker: self.checker(key_range, epoch, true, "backward_iter"),
+            })
+        }
+    }
+
+    fn wait_epoch(&self, epoch: u64) -> Self::WaitEpochFuture<'_> {
+        async move { self.inner.wait_epoch(epoch).await }
+    }
+
+    fn sync(&self, epoch: Option<u64>) -> Self::SyncFuture<'_> {
+        async move { self.inner.sync(epoch).await }
+    }
+
+    fn get_uncommitted_ssts(&self, epoch: u64) -> Vec<SstableInfo> {
+        self.inner.get_uncommitted_ssts(epoch)
+    }
+
+    fn clear_shared_buffer(&self) {
+        // TODO: drop the shadow writes of the epochs cleared.
+        self.inner.clear_shared_buffer()
+    }
+}
+
+pub struct SanityCheckStateStoreIter<I> {
+    inner: I,
+    checker: ShadowChecker,
+}
+
+impl<I> StateStoreIter for SanityCheckStateStoreIter<I>
+where
+    I: StateStoreIter<Item = (Bytes, Bytes)>,
+{
+    type Item = (Bytes, Bytes);
+
+    type NextFuture<'a> =
+        impl Future<Output = crate::error::StorageResult<Option<Self::Item>>> + Send;
+
+    fn next(&mut self) -> Self::NextFuture<'_> {
+        async move {
+            let item = self.inner.next().await?;
+            self.checker.check_next(item.as_ref());
+            Ok(item)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::MemoryStateStore;
+
+    fn put(key: &str, value: &str) -> (Bytes, StorageValue) {
+        (
+            key.as_bytes().to_vec().into(),
+            StorageValue::new_default_put(value.as_bytes().to_vec()),
+        )
+    }
+
+    fn delete(key: &str) -> (Bytes, StorageValue) {
+        (
+            key.as_bytes().to_vec().into(),
+            StorageValue::new_default_delete(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_sanity_check() {
+        let inner = MemoryStateStore::new();
+        let state_store = SanityCheckStateStore::new(inner.clone());
+        state_store
+            .ingest_batch(vec![put("a", "v1"), put("b", "v1"), put("c", "v1")], 1)
+            .await
+            .unwrap();
+        state_store
+            .ingest_batch(vec![put("a", "v2"), delete("b")], 2)
+            .await
+            .unwrap();
+        // Keys not written through the sanity-checked store are ignored.
+        inner.ingest_batch(vec![put("bb", "v1")], 2).await.unwrap();
+
+        assert_eq!(state_store.scan("a"..="c", None, 1).await.unwrap().len(), 3);
+        assert_eq!(state_store.scan("a"..="c", None, 2).await.unwrap().len(), 3);
+        assert_eq!(
+            state_store.scan("a"..="c", Some(1), 2).await.unwrap().len(),
+            1
+        );
+        assert_eq!(state_store.get(b"b", 2).await.unwrap(), None);
+
+        let mut iter = state_store.iter("a"..="c", 2).await.unwrap();
+        let mut keys = vec![];
+        while let Some((key, _)) = iter.next().await.unwrap() {
+            keys.push(key);
+        }
+        assert_eq!(keys, vec![&b"a"[..], &b"bb"[..], &b"c"[..]]);
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "key b\"c\" is missing")]
+    async fn test_sanity_check_divergence() {
+        let inner = MemoryStateStore::new();
+        let state_store = SanityCheckStateStore::new(inner.clone());
+        state_store
+            .ingest_batch(vec![put("a", "v1"), put("c", "v1")], 1)
+            .await
+            .unwrap();
+        // Deletes a key behind the sanity-checked store.
+        inner.ingest_batch(vec![delete("c")], 1).await.unwrap();
+        state_store.scan("a"..="c", None, 1).await.unwrap();
+    }
+}
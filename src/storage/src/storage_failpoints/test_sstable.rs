@@ -39,7 +39,7 @@ async fn test_failpoint_table_read() {
     let kv_iter =
         (0..TEST_KEYS_COUNT).map(|i| (test_key_of(i), HummockValue::put(test_value_of(i))));
     let (data, meta, _) = gen_test_sstable_data(default_builder_opt_for_test(), kv_iter);
-    let table = Sstable { id: 0, meta };
+    let table = Sstable::new(0, meta);
     sstable_store
         .put(table.clone(), data, CachePolicy::NotFill)
         .await
@@ -90,7 +90,7 @@ async fn test_failpoint_vacuum_and_metadata() {
     let kv_iter =
         (0..TEST_KEYS_COUNT).map(|i| (test_key_of(i), HummockValue::put(test_value_of(i))));
     let (data, meta, _) = gen_test_sstable_data(default_builder_opt_for_test(), kv_iter);
-    let table = Sstable { id: 0, meta };
+    let table = Sstable::new(0, meta);
     let result = sstable_store
         .put(table.clone(), data.clone(), CachePolicy::NotFill)
         .await;
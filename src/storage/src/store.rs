@@ -165,6 +165,10 @@ pub trait StateStore: Send + Sync + 'static + Clone {
     fn get_uncommitted_ssts(&self, _epoch: u64) -> Vec<SstableInfo> {
         todo!()
     }
+
+    /// Drops the buffered data of all epochs not committed yet, e.g. when the actors writing them
+    /// are stopped for recovery, which restarts from the last committed epoch.
+    fn clear_shared_buffer(&self) {}
 }
 
 pub trait StateStoreIter: Send + 'static {
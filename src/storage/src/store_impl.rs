@@ -24,6 +24,7 @@ use crate::error::StorageResult;
 use crate::hummock::{HummockStorage, SstableStore};
 use crate::memory::MemoryStateStore;
 use crate::monitor::{MonitoredStateStore as Monitored, ObjectStoreMetrics, StateStoreMetrics};
+use crate::sanity_check_store::SanityCheckStateStore;
 use crate::StateStore;
 
 /// The type erased [`StateStore`].
@@ -43,12 +44,24 @@ pub enum StateStoreImpl {
     /// store misses some critical implementation to ensure the correctness of persisting streaming
     /// state. (e.g., no read_epoch support, no async checkpoint)
     MemoryStateStore(Monitored<MemoryStateStore>),
+    /// The Hummock state store with every read checked against an in-memory shadow of the
+    /// writes, enabled by `enable_state_store_sanity_check`. Only available in debug builds.
+    SanityCheckedHummockStateStore(Monitored<SanityCheckStateStore<HummockStorage>>),
 }
 
 impl StateStoreImpl {
     pub fn shared_in_memory_store(state_store_metrics: Arc<StateStoreMetrics>) -> Self {
         Self::MemoryStateStore(MemoryStateStore::shared().monitored(state_store_metrics))
     }
+
+    /// Returns the Hummock storage if the state store is backed by Hummock.
+    pub fn hummock_storage(&self) -> Option<&HummockStorage> {
+        match self {
+            StateStoreImpl::HummockStateStore(store) => Some(store.inner()),
+            StateStoreImpl::SanityCheckedHummockStateStore(store) => Some(store.inner().inner()),
+            StateStoreImpl::MemoryStateStore(_) => None,
+        }
+    }
 }
 
 impl Debug for StateStoreImpl {
@@ -56,6 +69,9 @@ impl Debug for StateStoreImpl {
         match self {
             StateStoreImpl::HummockStateStore(_) => write!(f, "HummockStateStore"),
             StateStoreImpl::MemoryStateStore(_) => write!(f, "MemoryStateStore"),
+            StateStoreImpl::SanityCheckedHummockStateStore(_) => {
+                write!(f, "SanityCheckedHummockStateStore")
+            }
         }
     }
 }
@@ -77,6 +93,18 @@ macro_rules! dispatch_state_store {
                     unimplemented!("memory state store should never be used in release mode");
                 }
             }
+            StateStoreImpl::SanityCheckedHummockStateStore($store) => {
+                // Same as the memory backend, the sanity check is only for tests.
+                #[cfg(debug_assertions)]
+                {
+                    $body
+                }
+                #[cfg(not(debug_assertions))]
+                {
+                    let _store = $store;
+                    unimplemented!("state store sanity check should never be used in release mode");
+                }
+            }
             StateStoreImpl::HummockStateStore($store) => $body,
         }
     };
@@ -122,7 +150,21 @@ impl StateStoreImpl {
                     state_store_stats.clone(),
                 )
                 .await?;
-                StateStoreImpl::HummockStateStore(inner.monitored(state_store_stats))
+                if config.enable_state_store_sanity_check && cfg!(debug_assertions) {
+                    tracing::warn!(
+                        "state store sanity check is enabled, which is slow and memory-consuming."
+                    );
+                    StateStoreImpl::SanityCheckedHummockStateStore(
+                        SanityCheckStateStore::new(inner).monitored(state_store_stats),
+                    )
+                } else {
+                    if config.enable_state_store_sanity_check {
+                        tracing::warn!(
+                            "state store sanity check is only available in debug builds."
+                        );
+                    }
+                    StateStoreImpl::HummockStateStore(inner.monitored(state_store_stats))
+                }
             }
 
             "in_memory" | "in-memory" => {
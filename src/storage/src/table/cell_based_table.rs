@@ -20,7 +20,9 @@ use futures_async_stream::try_stream;
 use itertools::Itertools;
 use risingwave_common::array::column::Column;
 use risingwave_common::array::{DataChunk, Row};
+use risingwave_common::buffer::Bitmap;
 use risingwave_common::catalog::{ColumnDesc, ColumnId, Field, Schema};
+use risingwave_common::error::ErrorCode::InternalError;
 use risingwave_common::error::RwError;
 use risingwave_common::util::hash_util::CRC32FastBuilder;
 use risingwave_common::util::ordered::*;
@@ -64,6 +66,11 @@ pub struct CellBasedTable<S: StateStore> {
     /// Indices of distribution keys in pk for computing value meta. None if value meta is not
     /// required.
     dist_key_indices: Option<Vec<usize>>,
+
+    /// Vnodes owned by the actor writing this table, which come from the vnode mapping of its
+    /// fragment. Writing rows of other vnodes fails, since they should have been dispatched to
+    /// other actors. `None` if the actor owns all the vnodes.
+    vnodes: Option<Arc<Bitmap>>,
 }
 
 impl<S: StateStore> std::fmt::Debug for CellBasedTable<S> {
@@ -104,6 +111,31 @@ impl<S: StateStore> CellBasedTable<S> {
             column_ids,
             stats,
             dist_key_indices,
+            vnodes: None,
+        }
+    }
+
+    /// Restricts the rows written with value meta to the `vnodes` owned by the actor.
+    pub fn with_vnodes(mut self, vnodes: Arc<Bitmap>) -> Self {
+        self.vnodes = Some(vnodes);
+        self
+    }
+
+    /// Updates the vnodes owned by the actor after some vnodes are moved to or from it.
+    pub fn update_vnodes(&mut self, vnodes: Arc<Bitmap>) {
+        self.vnodes = Some(vnodes);
+    }
+
+    /// Appends nullable `columns` to the table. The rows written before read nulls in them, as
+    /// their cells of the columns don't exist.
+    pub fn add_columns(&mut self, columns: &[ColumnDesc]) {
+        for column in columns {
+            self.schema.fields.push(Field::with_name(
+                column.data_type.clone(),
+                column.name.clone(),
+            ));
+            self.column_ids.push(column.column_id);
+            self.column_descs.push(column.clone());
         }
     }
 
@@ -196,6 +228,64 @@ impl<S: StateStore> CellBasedTable<S> {
         }
     }
 
+    /// Gets all rows whose pk starts with `pk_prefix` by a state store scan. The pk serializer of
+    /// the table should only contain the order types of the prefix columns.
+    pub async fn get_rows_by_pk_prefix(
+        &self,
+        pk_prefix: &Row,
+        epoch: u64,
+    ) -> StorageResult<Vec<Row>> {
+        let pk_serializer = self.pk_serializer.as_ref().expect("pk_serializer is None");
+        let start_key = self
+            .keyspace
+            .prefixed_key(&serialize_pk(pk_prefix, pk_serializer).map_err(err)?);
+        let end_key = next_key(&start_key);
+
+        let state_store_range_scan_res = self
+            .keyspace
+            .state_store()
+            .scan(start_key..end_key, None, epoch)
+            .await?;
+        let mut cell_based_row_deserializer =
+            CellBasedRowDeserializer::new(self.column_descs.clone());
+        let mut rows = vec![];
+        for (key, value) in state_store_range_scan_res {
+            if let Some((_pk, row)) = cell_based_row_deserializer
+                .deserialize(&key, &value)
+                .map_err(err)?
+            {
+                rows.push(row);
+            }
+        }
+        if let Some((_pk, row)) = cell_based_row_deserializer.take() {
+            rows.push(row);
+        }
+        Ok(rows)
+    }
+
+    /// Computes the value meta of `row`, which records the vnode that the row belongs to.
+    fn compute_value_meta(&self, row: &Row) -> StorageResult<ValueMeta> {
+        // If value meta is computed here, then the cell based table is guaranteed to have
+        // distribution keys. Also, it is guaranteed that distribution key indices will not exceed
+        // the length of pk. So we simply do unwrap here.
+        let vnode = row
+            .hash_by_indices(
+                self.dist_key_indices.as_ref().unwrap(),
+                &CRC32FastBuilder {},
+            )
+            .map_err(err)?
+            .to_vnode();
+        if let Some(vnodes) = &self.vnodes {
+            if !vnodes.is_set(vnode as usize).map_err(err)? {
+                return Err(err(InternalError(format!(
+                    "row {:?} belongs to vnode {}, which is not owned by this actor",
+                    row, vnode
+                ))));
+            }
+        }
+        Ok(ValueMeta::with_vnode(vnode))
+    }
+
     async fn batch_write_rows_inner<const WITH_VALUE_META: bool>(
         &mut self,
         buffer: BTreeMap<Vec<u8>, RowOp>,
@@ -204,20 +294,11 @@ impl<S: StateStore> CellBasedTable<S> {
         // stateful executors need to compute vnode.
         let mut batch = self.keyspace.state_store().start_write_batch();
         let mut local = batch.prefixify(&self.keyspace);
-        let hash_builder = CRC32FastBuilder {};
         for (pk, row_op) in buffer {
-            // If value meta is computed here, then the cell based table is guaranteed to have
-            // distribution keys. Also, it is guaranteed that distribution key indices will
-            // not exceed the length of pk. So we simply do unwrap here.
-
             match row_op {
                 RowOp::Insert(row) => {
                     let value_meta = if WITH_VALUE_META {
-                        let vnode = row
-                            .hash_by_indices(self.dist_key_indices.as_ref().unwrap(), &hash_builder)
-                            .unwrap()
-                            .to_vnode();
-                        ValueMeta::with_vnode(vnode)
+                        self.compute_value_meta(&row)?
                     } else {
                         ValueMeta::default()
                     };
@@ -232,11 +313,7 @@ impl<S: StateStore> CellBasedTable<S> {
                 RowOp::Delete(old_row) => {
                     // TODO(wcy-fdu): only serialize key on deletion
                     let value_meta = if WITH_VALUE_META {
-                        let vnode = old_row
-                            .hash_by_indices(self.dist_key_indices.as_ref().unwrap(), &hash_builder)
-                            .unwrap()
-                            .to_vnode();
-                        ValueMeta::with_vnode(vnode)
+                        self.compute_value_meta(&old_row)?
                     } else {
                         ValueMeta::default()
                     };
@@ -250,11 +327,7 @@ impl<S: StateStore> CellBasedTable<S> {
                 }
                 RowOp::Update((old_row, new_row)) => {
                     let value_meta = if WITH_VALUE_META {
-                        let vnode = new_row
-                            .hash_by_indices(self.dist_key_indices.as_ref().unwrap(), &hash_builder)
-                            .unwrap()
-                            .to_vnode();
-                        ValueMeta::with_vnode(vnode)
+                        self.compute_value_meta(&new_row)?
                     } else {
                         ValueMeta::default()
                     };
@@ -20,6 +20,7 @@ use std::sync::Arc;
 use futures::{pin_mut, Stream, StreamExt};
 use futures_async_stream::try_stream;
 use risingwave_common::array::Row;
+use risingwave_common::buffer::Bitmap;
 use risingwave_common::catalog::ColumnDesc;
 use risingwave_common::error::RwError;
 use risingwave_common::util::ordered::{serialize_pk, OrderedRowSerializer};
@@ -74,6 +75,33 @@ impl<S: StateStore> StateTable<S> {
         }
     }
 
+    /// Restricts the rows committed with value meta to the `vnodes` owned by the actor.
+    pub fn with_vnodes(mut self, vnodes: Arc<Bitmap>) -> Self {
+        self.cell_based_table = self.cell_based_table.with_vnodes(vnodes);
+        self
+    }
+
+    /// Updates the vnodes owned by the actor after some vnodes are moved to or from it. The
+    /// changes before must have been committed.
+    pub fn update_vnodes(&mut self, vnodes: Arc<Bitmap>) {
+        assert!(
+            self.mem_table.buffer.is_empty(),
+            "vnodes are updated with uncommitted changes"
+        );
+        self.cell_based_table.update_vnodes(vnodes);
+    }
+
+    /// Appends nullable `columns` to the table. The rows written before read nulls in them. The
+    /// changes before must have been committed.
+    pub fn add_columns(&mut self, columns: &[ColumnDesc]) {
+        assert!(
+            self.mem_table.buffer.is_empty(),
+            "columns are added with uncommitted changes"
+        );
+        self.column_descs.extend(columns.iter().cloned());
+        self.cell_based_table.add_columns(columns);
+    }
+
     /// read methods
     pub async fn get_row(&self, pk: &Row, epoch: u64) -> StorageResult<Option<Row>> {
         let pk_bytes =
@@ -12,11 +12,16 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::sync::Arc;
+
 use futures::pin_mut;
 use futures::stream::StreamExt;
 use risingwave_common::array::Row;
+use risingwave_common::buffer::Bitmap;
 use risingwave_common::catalog::{ColumnDesc, ColumnId, TableId};
+use risingwave_common::hash::VIRTUAL_NODE_COUNT;
 use risingwave_common::types::DataType;
+use risingwave_common::util::hash_util::CRC32FastBuilder;
 use risingwave_common::util::sort_util::OrderType;
 
 use crate::error::StorageResult;
@@ -893,6 +898,79 @@ async fn test_cell_based_get_row_by_scan() {
     assert_eq!(get_no_exist_res, None);
 }
 
+#[tokio::test]
+async fn test_cell_based_get_rows_by_pk_prefix() {
+    let state_store = MemoryStateStore::new();
+    let column_ids = vec![ColumnId::from(0), ColumnId::from(1), ColumnId::from(2)];
+    let column_descs = vec![
+        ColumnDesc::unnamed(column_ids[0], DataType::Int32),
+        ColumnDesc::unnamed(column_ids[1], DataType::Int32),
+        ColumnDesc::unnamed(column_ids[2], DataType::Int32),
+    ];
+    let pk_index = vec![0_usize, 1_usize];
+    let order_types = vec![OrderType::Ascending, OrderType::Descending];
+    let keyspace = Keyspace::table_root(state_store, &TableId::from(0x42));
+    let mut state = StateTable::new(
+        keyspace.clone(),
+        column_descs.clone(),
+        order_types,
+        None,
+        pk_index,
+    );
+    // Only the first column of the pk is used for lookup.
+    let table =
+        CellBasedTable::new_for_test(keyspace.clone(), column_descs, vec![OrderType::Ascending]);
+    let epoch: u64 = 0;
+
+    for (pk, row) in [
+        (
+            (1, 11),
+            Row(vec![Some(1_i32.into()), Some(11_i32.into()), None]),
+        ),
+        (
+            (1, 12),
+            Row(vec![
+                Some(1_i32.into()),
+                Some(12_i32.into()),
+                Some(112_i32.into()),
+            ]),
+        ),
+        (
+            (2, 22),
+            Row(vec![Some(2_i32.into()), Some(22_i32.into()), None]),
+        ),
+    ] {
+        state
+            .insert(&Row(vec![Some(pk.0.into()), Some(pk.1.into())]), row)
+            .unwrap();
+    }
+    state.commit(epoch).await.unwrap();
+
+    let epoch = u64::MAX;
+
+    let rows = table
+        .get_rows_by_pk_prefix(&Row(vec![Some(1_i32.into())]), epoch)
+        .await
+        .unwrap();
+    assert_eq!(
+        rows,
+        vec![
+            Row(vec![
+                Some(1_i32.into()),
+                Some(12_i32.into()),
+                Some(112_i32.into())
+            ]),
+            Row(vec![Some(1_i32.into()), Some(11_i32.into()), None]),
+        ]
+    );
+
+    let rows = table
+        .get_rows_by_pk_prefix(&Row(vec![Some(3_i32.into())]), epoch)
+        .await
+        .unwrap();
+    assert!(rows.is_empty());
+}
+
 // test cell_based table
 #[tokio::test]
 async fn test_cell_based_get_row_by_muti_get() {
@@ -1451,3 +1529,123 @@ async fn test_cell_based_scan_empty_column_ids_cardinality() {
     };
     assert_eq!(chunk.cardinality(), 2);
 }
+
+#[tokio::test]
+async fn test_state_table_owned_vnodes() {
+    let state_store = MemoryStateStore::new();
+    let keyspace = Keyspace::table_root(state_store, &TableId::from(0x42));
+    let column_descs = vec![
+        ColumnDesc::unnamed(ColumnId::from(0), DataType::Int32),
+        ColumnDesc::unnamed(ColumnId::from(1), DataType::Int32),
+    ];
+    let row = |i: i32| Row(vec![Some(i.into()), Some((i * 11).into())]);
+    let vnode = |row: &Row| {
+        row.hash_by_indices(&[0], &CRC32FastBuilder {})
+            .unwrap()
+            .to_vnode() as usize
+    };
+    let owned_row = row(1);
+    let owned_vnode = vnode(&owned_row);
+    let vnodes = Bitmap::from_bool_slice(
+        &(0..VIRTUAL_NODE_COUNT)
+            .map(|vnode| vnode == owned_vnode)
+            .collect::<Vec<_>>(),
+    );
+    let mut state = StateTable::new(
+        keyspace,
+        column_descs,
+        vec![OrderType::Ascending],
+        Some(vec![0]),
+        vec![0],
+    )
+    .with_vnodes(Arc::new(vnodes));
+
+    state
+        .insert(&Row(vec![Some(1_i32.into())]), owned_row)
+        .unwrap();
+    state.commit_with_value_meta(1).await.unwrap();
+
+    // A row dispatched to the wrong actor.
+    let other_row = (2..)
+        .map(row)
+        .find(|row| vnode(row) != owned_vnode)
+        .unwrap();
+    state
+        .insert(&Row(vec![other_row.0[0].clone()]), other_row)
+        .unwrap();
+    assert!(state.commit_with_value_meta(2).await.is_err());
+}
+
+#[tokio::test]
+async fn test_state_table_add_columns() -> StorageResult<()> {
+    let state_store = MemoryStateStore::new();
+    let keyspace = Keyspace::table_root(state_store, &TableId::from(0x42));
+    let column_descs = vec![
+        ColumnDesc::unnamed(ColumnId::from(0), DataType::Int32),
+        ColumnDesc::unnamed(ColumnId::from(1), DataType::Int32),
+    ];
+    let mut state_table = StateTable::new(
+        keyspace,
+        column_descs,
+        vec![OrderType::Ascending],
+        None,
+        vec![0],
+    );
+    let mut epoch: u64 = 0;
+    state_table
+        .insert(
+            &Row(vec![Some(1_i32.into())]),
+            Row(vec![Some(1_i32.into()), Some(11_i32.into())]),
+        )
+        .unwrap();
+    state_table.commit(epoch).await.unwrap();
+
+    epoch += 1;
+    state_table.add_columns(&[ColumnDesc::unnamed(ColumnId::from(2), DataType::Int32)]);
+    state_table
+        .insert(
+            &Row(vec![Some(2_i32.into())]),
+            Row(vec![
+                Some(2_i32.into()),
+                Some(22_i32.into()),
+                Some(222_i32.into()),
+            ]),
+        )
+        .unwrap();
+    state_table.commit(epoch).await.unwrap();
+
+    // The row written before the column is added reads null in it.
+    let row1 = state_table
+        .get_row(&Row(vec![Some(1_i32.into())]), epoch)
+        .await
+        .unwrap();
+    assert_eq!(
+        row1,
+        Some(Row(vec![Some(1_i32.into()), Some(11_i32.into()), None]))
+    );
+    let row2 = state_table
+        .get_row(&Row(vec![Some(2_i32.into())]), epoch)
+        .await
+        .unwrap();
+    assert_eq!(
+        row2,
+        Some(Row(vec![
+            Some(2_i32.into()),
+            Some(22_i32.into()),
+            Some(222_i32.into())
+        ]))
+    );
+
+    // The old row can be deleted with the nulls of the added columns.
+    epoch += 1;
+    state_table
+        .delete(&Row(vec![Some(1_i32.into())]), row1.unwrap())
+        .unwrap();
+    state_table.commit(epoch).await.unwrap();
+    let row1 = state_table
+        .get_row(&Row(vec![Some(1_i32.into())]), epoch)
+        .await
+        .unwrap();
+    assert_eq!(row1, None);
+    Ok(())
+}
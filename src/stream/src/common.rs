@@ -12,5 +12,19 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::sync::Arc;
+
+use bytes::Bytes;
+use risingwave_common::buffer::Bitmap;
+use risingwave_common::hash::VIRTUAL_NODE_COUNT;
+
 mod builder;
 pub use builder::*;
+
+/// Decodes the vnodes owned by an actor from the bitmap bytes in protobuf.
+pub fn vnode_bitmap_from_protobuf(bytes: &[u8]) -> Arc<Bitmap> {
+    Arc::new(Bitmap::from_bytes_with_num_bits(
+        Bytes::copy_from_slice(bytes),
+        VIRTUAL_NODE_COUNT,
+    ))
+}
@@ -0,0 +1,79 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use futures::StreamExt;
+use futures_async_stream::try_stream;
+use risingwave_common::catalog::Schema;
+
+use super::error::StreamExecutorError;
+use super::{BoxedExecutor, BoxedMessageStream, Executor, Message, PkIndicesRef};
+use crate::task::ChangelogActorHandle;
+
+/// [`ChangelogExecutor`] reports the changes of a materialized view to the
+/// [`ChangelogManager`](crate::task::ChangelogManager) once the materialized view is subscribed.
+pub struct ChangelogExecutor {
+    input: BoxedExecutor,
+
+    handle: ChangelogActorHandle,
+}
+
+impl ChangelogExecutor {
+    pub fn new(input: BoxedExecutor, handle: ChangelogActorHandle) -> Self {
+        Self { input, handle }
+    }
+
+    #[try_stream(ok = Message, error = StreamExecutorError)]
+    async fn execute_inner(self) {
+        let handle = self.handle;
+        // Changes are reported only if they're collected since the beginning of the epoch.
+        let mut collecting = false;
+        let mut chunks = vec![];
+
+        #[for_await]
+        for msg in self.input.execute() {
+            let msg = msg?;
+            match &msg {
+                Message::Chunk(chunk) => {
+                    if collecting {
+                        chunks.push(chunk.clone());
+                    }
+                }
+                Message::Barrier(barrier) => {
+                    let epoch_chunks = std::mem::take(&mut chunks);
+                    handle.report(barrier.epoch.prev, collecting.then(|| epoch_chunks));
+                    collecting = handle.is_tracked();
+                }
+            }
+            yield msg;
+        }
+    }
+}
+
+impl Executor for ChangelogExecutor {
+    fn execute(self: Box<Self>) -> BoxedMessageStream {
+        self.execute_inner().boxed()
+    }
+
+    fn schema(&self) -> &Schema {
+        self.input.schema()
+    }
+
+    fn pk_indices(&self) -> PkIndicesRef {
+        self.input.pk_indices()
+    }
+
+    fn identity(&self) -> &str {
+        self.input.identity()
+    }
+}
@@ -0,0 +1,367 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use futures::{pin_mut, StreamExt};
+use futures_async_stream::try_stream;
+use itertools::Itertools;
+use risingwave_common::array::{Op, Row};
+use risingwave_common::buffer::Bitmap;
+use risingwave_common::catalog::{ColumnDesc, ColumnId, Schema};
+use risingwave_common::types::{DataType, ScalarImpl};
+use risingwave_common::util::epoch::Epoch;
+use risingwave_common::util::hash_util::CRC32FastBuilder;
+use risingwave_common::util::sort_util::OrderType;
+use risingwave_storage::table::state_table::StateTable;
+use risingwave_storage::{Keyspace, StateStore};
+
+use super::error::{StreamExecutorError, StreamExecutorResult};
+use super::top_n_executor::generate_output;
+use super::{
+    expect_first_barrier, BoxedExecutor, BoxedMessageStream, Executor, ExecutorInfo, Message,
+    PkIndices, PkIndicesRef,
+};
+use crate::task::ActorId;
+
+/// [`DedupExecutor`] drops the insertions whose dedup keys were seen within the TTL, e.g. the rows
+/// sent again by an at-least-once producer of a source. The other changes pass through.
+///
+/// The seen keys are kept in generations of `ttl_ms` long, by the time of the epoch they're seen
+/// in. A key is a duplicate if it's seen in the current or the last generation, and the older
+/// generations are deleted on barriers. So a key is remembered for at least `ttl_ms` and at most
+/// twice as long. The input must be distributed by the dedup keys.
+pub struct DedupExecutor<S: StateStore> {
+    input: BoxedExecutor,
+
+    info: ExecutorInfo,
+
+    dedup_col_indices: Vec<usize>,
+
+    ttl_ms: u64,
+
+    /// Indices in the state of the distribution keys of the input, which decide the vnode of a
+    /// key.
+    state_dist_key_indices: Vec<usize>,
+
+    /// Id of the actor if it owns part of the vnodes, which may change on reschedule.
+    actor_id: Option<ActorId>,
+
+    /// Vnodes owned by the actor. Keys of other vnodes in the state are expired by other actors.
+    vnodes: Option<Arc<Bitmap>>,
+
+    /// The seen keys prefixed by their generations, ordered by the generations.
+    state_table: StateTable<S>,
+}
+
+impl<S: StateStore> DedupExecutor<S> {
+    pub fn new(
+        input: BoxedExecutor,
+        pk_indices: PkIndices,
+        executor_id: u64,
+        dedup_col_indices: Vec<usize>,
+        ttl_ms: u64,
+        dist_key_indices: Vec<usize>,
+        keyspace: Keyspace<S>,
+    ) -> Self {
+        let schema = input.schema().clone();
+        // The dedup keys follow the generation in the state.
+        let state_dist_key_indices = dist_key_indices
+            .iter()
+            .map(|idx| {
+                let pos = dedup_col_indices
+                    .iter()
+                    .position(|dedup_idx| dedup_idx == idx)
+                    .expect("the distribution keys should be dedup keys");
+                pos + 1
+            })
+            .collect();
+        let column_descs = std::iter::once(DataType::Int64)
+            .chain(
+                dedup_col_indices
+                    .iter()
+                    .map(|idx| schema.fields()[*idx].data_type()),
+            )
+            .enumerate()
+            .map(|(idx, data_type)| ColumnDesc::unnamed(ColumnId::new(idx as i32), data_type))
+            .collect_vec();
+        let state_table = StateTable::new(
+            keyspace,
+            column_descs.clone(),
+            vec![OrderType::Ascending; column_descs.len()],
+            None,
+            (0..column_descs.len()).collect(),
+        );
+        Self {
+            input,
+            info: ExecutorInfo {
+                schema,
+                pk_indices,
+                identity: format!("DedupExecutor {:X}", executor_id),
+            },
+            dedup_col_indices,
+            ttl_ms,
+            state_dist_key_indices,
+            actor_id: None,
+            vnodes: None,
+            state_table,
+        }
+    }
+
+    /// Restricts the keys expired by the actor to the `vnodes` it owns.
+    pub fn with_vnodes(mut self, actor_id: ActorId, vnodes: Arc<Bitmap>) -> Self {
+        self.actor_id = Some(actor_id);
+        self.vnodes = Some(vnodes);
+        self
+    }
+
+    fn generation(&self, epoch: u64) -> i64 {
+        (Epoch(epoch).physical_time() / self.ttl_ms) as i64
+    }
+
+    /// The state is keyed by the generation followed by the dedup keys.
+    fn state_key(&self, generation: i64, row: &Row) -> Row {
+        Row(std::iter::once(Some(ScalarImpl::Int64(generation)))
+            .chain(self.dedup_col_indices.iter().map(|idx| row[*idx].clone()))
+            .collect_vec())
+    }
+
+    fn state_generation(key: &Row) -> i64 {
+        match &key[0] {
+            Some(ScalarImpl::Int64(generation)) => *generation,
+            _ => unreachable!("the generation is never null"),
+        }
+    }
+
+    /// Returns whether the dedup keys of `row` are seen in the generation `generation` or the last
+    /// one, and remembers them in `generation` otherwise.
+    async fn check_duplicate(
+        &mut self,
+        row: &Row,
+        generation: i64,
+        epoch: u64,
+    ) -> StreamExecutorResult<bool> {
+        for generation in [generation, generation - 1] {
+            let key = self.state_key(generation, row);
+            if self.state_table.get_row(&key, epoch).await?.is_some() {
+                return Ok(true);
+            }
+        }
+        let key = self.state_key(generation, row);
+        self.state_table.insert(&key, key.clone())?;
+        Ok(false)
+    }
+
+    fn owns(&self, key: &Row) -> StreamExecutorResult<bool> {
+        match &self.vnodes {
+            Some(vnodes) => {
+                let vnode = key
+                    .hash_by_indices(&self.state_dist_key_indices, &CRC32FastBuilder {})
+                    .map_err(StreamExecutorError::eval_error)?
+                    .to_vnode();
+                vnodes
+                    .is_set(vnode as usize)
+                    .map_err(StreamExecutorError::eval_error)
+            }
+            None => Ok(true),
+        }
+    }
+
+    /// Deletes the keys of the generations older than the last one of `generation`.
+    async fn delete_expired(&mut self, generation: i64, epoch: u64) -> StreamExecutorResult<()> {
+        let mut expired = vec![];
+        {
+            let iter = self.state_table.iter(epoch).await?;
+            pin_mut!(iter);
+            while let Some(key) = iter.next().await {
+                let key = key?.into_owned();
+                if Self::state_generation(&key) >= generation - 1 {
+                    break;
+                }
+                if self.owns(&key)? {
+                    expired.push(key);
+                }
+            }
+        }
+        for key in expired {
+            self.state_table.delete(&key, key.clone())?;
+        }
+        Ok(())
+    }
+
+    #[try_stream(ok = Message, error = StreamExecutorError)]
+    async fn execute_inner(mut self) {
+        let mut input = self.input.execute();
+
+        let barrier = expect_first_barrier(&mut input).await?;
+        let mut epoch = barrier.epoch.curr;
+        let mut generation = self.generation(epoch);
+        self.delete_expired(generation, epoch).await?;
+        yield Message::Barrier(barrier);
+
+        #[for_await]
+        for msg in input {
+            match msg? {
+                Message::Chunk(chunk) => {
+                    let mut rows = vec![];
+                    let mut ops = vec![];
+                    for (op, row_ref) in chunk.rows() {
+                        let row = row_ref.to_owned_row();
+                        if op == Op::Insert && self.check_duplicate(&row, generation, epoch).await?
+                        {
+                            continue;
+                        }
+                        rows.push(row);
+                        ops.push(op);
+                    }
+                    if !rows.is_empty() {
+                        yield Message::Chunk(generate_output(rows, ops, &self.info.schema)?);
+                    }
+                }
+                Message::Barrier(barrier) => {
+                    self.state_table.commit(barrier.epoch.prev).await?;
+                    if let Some(vnodes) = self
+                        .actor_id
+                        .and_then(|actor_id| barrier.as_update_vnode_bitmap(actor_id))
+                    {
+                        self.vnodes = Some(vnodes);
+                    }
+                    epoch = barrier.epoch.curr;
+                    let new_generation = self.generation(epoch);
+                    if new_generation != generation {
+                        generation = new_generation;
+                        self.delete_expired(generation, epoch).await?;
+                    }
+                    yield Message::Barrier(barrier);
+                }
+            }
+        }
+    }
+}
+
+impl<S: StateStore> Executor for DedupExecutor<S> {
+    fn execute(self: Box<Self>) -> BoxedMessageStream {
+        self.execute_inner().boxed()
+    }
+
+    fn schema(&self) -> &Schema {
+        &self.info.schema
+    }
+
+    fn pk_indices(&self) -> PkIndicesRef {
+        &self.info.pk_indices
+    }
+
+    fn identity(&self) -> &str {
+        &self.info.identity
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_common::array::stream_chunk::StreamChunkTestExt;
+    use risingwave_common::array::StreamChunk;
+    use risingwave_common::catalog::Field;
+
+    use super::*;
+    use crate::executor::test_utils::{create_in_memory_keyspace, MockSource};
+    use crate::executor::{Barrier, Epoch as StreamEpoch};
+
+    /// Returns the barrier of the epoch at `secs` after the singularity date.
+    fn barrier_at(secs: u64, prev_secs: u64) -> Message {
+        let epoch = |secs: u64| Epoch::from_physical_time(secs * 1000).0;
+        Message::Barrier(Barrier {
+            epoch: StreamEpoch::new(epoch(secs), epoch(prev_secs)),
+            ..Barrier::default()
+        })
+    }
+
+    #[tokio::test]
+    async fn test_dedup() {
+        let schema = Schema::new(vec![
+            Field::unnamed(DataType::Int64),
+            Field::unnamed(DataType::Int64),
+        ]);
+        let source = MockSource::with_messages(
+            schema,
+            vec![1],
+            vec![
+                barrier_at(10, 9),
+                Message::Chunk(StreamChunk::from_pretty(
+                    " I I
+                    + 1 1
+                    + 2 2
+                    + 1 3",
+                )),
+                barrier_at(11, 10),
+                Message::Chunk(StreamChunk::from_pretty(
+                    " I I
+                    + 2 4
+                    + 3 5
+                    - 1 1",
+                )),
+                // The keys seen in the generation of [10s, 20s) are forgotten in that of [30s,
+                // 40s).
+                barrier_at(30, 11),
+                Message::Chunk(StreamChunk::from_pretty(
+                    " I I
+                    + 1 6
+                    + 2 7",
+                )),
+                barrier_at(31, 30),
+            ],
+        );
+        // Deduplicate by the first column within 10 seconds.
+        let mut dedup = DedupExecutor::new(
+            Box::new(source),
+            vec![1],
+            1,
+            vec![0],
+            10_000,
+            vec![0],
+            create_in_memory_keyspace(),
+        )
+        .boxed()
+        .execute();
+
+        assert!(dedup.next().await.unwrap().unwrap().as_barrier().is_some());
+        assert_eq!(
+            dedup.next().await.unwrap().unwrap().into_chunk().unwrap(),
+            StreamChunk::from_pretty(
+                " I I
+                + 1 1
+                + 2 2",
+            )
+        );
+        assert!(dedup.next().await.unwrap().unwrap().as_barrier().is_some());
+        assert_eq!(
+            dedup.next().await.unwrap().unwrap().into_chunk().unwrap(),
+            StreamChunk::from_pretty(
+                " I I
+                + 3 5
+                - 1 1",
+            )
+        );
+        assert!(dedup.next().await.unwrap().unwrap().as_barrier().is_some());
+        assert_eq!(
+            dedup.next().await.unwrap().unwrap().into_chunk().unwrap(),
+            StreamChunk::from_pretty(
+                " I I
+                + 1 6
+                + 2 7",
+            )
+        );
+        assert!(dedup.next().await.unwrap().unwrap().as_barrier().is_some());
+    }
+}
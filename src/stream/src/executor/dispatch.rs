@@ -18,19 +18,19 @@ use std::sync::Arc;
 
 use async_trait::async_trait;
 use futures::channel::mpsc::Sender;
-use futures::{SinkExt, Stream};
+use futures::Stream;
 use futures_async_stream::try_stream;
 use itertools::Itertools;
 use madsim::collections::{HashMap, HashSet};
 use risingwave_common::array::{Op, StreamChunk};
-use risingwave_common::error::{internal_error, Result};
+use risingwave_common::error::Result;
 use risingwave_common::hash::VIRTUAL_NODE_COUNT;
 use risingwave_common::util::addr::{is_local_address, HostAddr};
 use risingwave_common::util::hash_util::CRC32FastBuilder;
 use tracing::event;
 
 use crate::executor::{Barrier, BoxedExecutor, Message, Mutation, StreamConsumer};
-use crate::task::{ActorId, DispatcherId, SharedContext};
+use crate::task::{send_with_stats, ActorId, ChannelStatsRef, DispatcherId, SharedContext};
 
 /// `Output` provides an interface for `Dispatcher` to send data into downstream actors.
 #[async_trait]
@@ -47,6 +47,8 @@ pub struct LocalOutput {
     actor_id: ActorId,
 
     ch: Sender<Message>,
+
+    stats: Option<ChannelStatsRef>,
 }
 
 impl Debug for LocalOutput {
@@ -59,7 +61,16 @@ impl Debug for LocalOutput {
 
 impl LocalOutput {
     pub fn new(actor_id: ActorId, ch: Sender<Message>) -> Self {
-        Self { actor_id, ch }
+        Self {
+            actor_id,
+            ch,
+            stats: None,
+        }
+    }
+
+    pub fn with_stats(mut self, stats: ChannelStatsRef) -> Self {
+        self.stats = Some(stats);
+        self
     }
 }
 
@@ -67,11 +78,7 @@ impl LocalOutput {
 impl Output for LocalOutput {
     async fn send(&mut self, message: Message) -> Result<()> {
         // local channel should never fail
-        self.ch
-            .send(message)
-            .await
-            .map_err(|_| internal_error("failed to send"))?;
-        Ok(())
+        send_with_stats(&mut self.ch, message, self.stats.as_ref()).await
     }
 
     fn actor_id(&self) -> ActorId {
@@ -84,6 +91,8 @@ pub struct RemoteOutput {
     actor_id: ActorId,
 
     ch: Sender<Message>,
+
+    stats: Option<ChannelStatsRef>,
 }
 
 impl Debug for RemoteOutput {
@@ -96,7 +105,16 @@ impl Debug for RemoteOutput {
 
 impl RemoteOutput {
     pub fn new(actor_id: ActorId, ch: Sender<Message>) -> Self {
-        Self { actor_id, ch }
+        Self {
+            actor_id,
+            ch,
+            stats: None,
+        }
+    }
+
+    pub fn with_stats(mut self, stats: ChannelStatsRef) -> Self {
+        self.stats = Some(stats);
+        self
     }
 }
 
@@ -108,11 +126,7 @@ impl Output for RemoteOutput {
             _ => message,
         };
         // local channel should never fail
-        self.ch
-            .send(message)
-            .await
-            .map_err(|_| internal_error("failed to send"))?;
-        Ok(())
+        send_with_stats(&mut self.ch, message, self.stats.as_ref()).await
     }
 
     fn actor_id(&self) -> ActorId {
@@ -127,11 +141,12 @@ pub fn new_output(
     down_id: ActorId,
 ) -> Result<Box<dyn Output>> {
     let tx = context.take_sender(&(actor_id, down_id))?;
+    let stats = context.channel_stats((actor_id, down_id));
     if is_local_address(&addr, &context.addr) {
         // if this is a local downstream actor
-        Ok(Box::new(LocalOutput::new(down_id, tx)) as Box<dyn Output>)
+        Ok(Box::new(LocalOutput::new(down_id, tx).with_stats(stats)) as Box<dyn Output>)
     } else {
-        Ok(Box::new(RemoteOutput::new(down_id, tx)) as Box<dyn Output>)
+        Ok(Box::new(RemoteOutput::new(down_id, tx).with_stats(stats)) as Box<dyn Output>)
     }
 }
 
@@ -186,7 +201,7 @@ impl DispatchExecutorInner {
     /// For `Add` and `Update`, update the outputs before we dispatch the barrier.
     async fn pre_mutate_outputs(&mut self, mutation: &Option<Arc<Mutation>>) -> Result<()> {
         let Some(mutation) = mutation.as_deref() else {
-            return Ok(())
+            return Ok(());
         };
 
         match mutation {
@@ -246,15 +261,33 @@ impl DispatchExecutorInner {
         Ok(())
     }
 
-    /// For `Stop`, update the outputs after we dispatch the barrier.
+    /// For `Stop`, update the outputs after we dispatch the barrier. For `Reschedule`, update the
+    /// hash mappings after we dispatch the barrier, so that the rows before the barrier are still
+    /// processed by the old owners of their vnodes.
     async fn post_mutate_outputs(&mut self, mutation: &Option<Arc<Mutation>>) -> Result<()> {
-        if let Some(Mutation::Stop(stops)) = mutation.as_deref() {
-            // Remove outputs only if this actor itself is not to be stopped.
-            if !stops.contains(&self.actor_id) {
+        match mutation.as_deref() {
+            Some(Mutation::Stop(stops)) => {
+                // Remove outputs only if this actor itself is not to be stopped.
+                if !stops.contains(&self.actor_id) {
+                    for dispatcher in &mut self.dispatchers {
+                        dispatcher.remove_outputs(stops);
+                    }
+                }
+            }
+
+            Some(Mutation::Reschedule { hash_mappings, .. }) => {
                 for dispatcher in &mut self.dispatchers {
-                    dispatcher.remove_outputs(stops);
+                    if let DispatcherImpl::Hash(dispatcher) = dispatcher {
+                        if let Some(hash_mapping) =
+                            hash_mappings.get(&(self.actor_id, dispatcher.dispatcher_id))
+                        {
+                            dispatcher.set_hash_mapping(hash_mapping.clone());
+                        }
+                    }
                 }
             }
+
+            _ => {}
         }
 
         Ok(())
@@ -488,6 +521,13 @@ impl HashDataDispatcher {
             dispatcher_id,
         }
     }
+
+    /// Replaces the mapping from vnodes to downstream actors. The downstream actors are not
+    /// changed.
+    pub fn set_hash_mapping(&mut self, hash_mapping: Vec<ActorId>) {
+        assert_eq!(hash_mapping.len(), VIRTUAL_NODE_COUNT);
+        self.hash_mapping = hash_mapping;
+    }
 }
 
 impl Dispatcher for HashDataDispatcher {
@@ -771,7 +811,7 @@ mod tests {
     use std::sync::{Arc, Mutex};
 
     use futures::channel::mpsc::channel;
-    use futures::{pin_mut, StreamExt};
+    use futures::{pin_mut, SinkExt, StreamExt};
     use itertools::Itertools;
     use madsim::collections::HashMap;
     use risingwave_common::array::column::Column;
@@ -1003,6 +1043,80 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_reschedule_hash_dispatcher() {
+        let (mut tx, rx) = channel(16);
+        let input = Box::new(ReceiverExecutor::new(
+            Schema { fields: vec![] },
+            vec![],
+            rx,
+            ActorContext::create(),
+            0,
+        ));
+        let actor_id = 233;
+        let dispatcher_id = 666;
+        let output_data_vecs = (0..2)
+            .map(|_| Arc::new(Mutex::new(Vec::new())))
+            .collect_vec();
+        let outputs = output_data_vecs
+            .iter()
+            .enumerate()
+            .map(|(idx, data)| {
+                Box::new(MockOutput::new(1 + idx as u32, data.clone())) as BoxedOutput
+            })
+            .collect_vec();
+        let executor = Box::new(DispatchExecutor::new(
+            input,
+            vec![DispatcherImpl::Hash(HashDataDispatcher::new(
+                vec![0, 1],
+                outputs,
+                vec![0],
+                vec![1; VIRTUAL_NODE_COUNT],
+                dispatcher_id,
+            ))],
+            actor_id,
+            Arc::new(SharedContext::for_test()),
+        ))
+        .execute();
+        pin_mut!(executor);
+
+        let chunk = || {
+            StreamChunk::from_pretty(
+                " I
+                + 1
+                + 2",
+            )
+        };
+        let mut hash_mappings = HashMap::new();
+        hash_mappings.insert((actor_id, dispatcher_id), vec![2; VIRTUAL_NODE_COUNT]);
+        let reschedule = Barrier::new_test_barrier(1).with_mutation(Mutation::Reschedule {
+            hash_mappings,
+            vnode_bitmaps: HashMap::default(),
+        });
+
+        // All vnodes move from actor 1 to actor 2, with chunks in flight around the barrier.
+        tx.send(Message::Chunk(chunk())).await.unwrap();
+        tx.send(Message::Barrier(reschedule)).await.unwrap();
+        tx.send(Message::Chunk(chunk())).await.unwrap();
+        tx.send(Message::Barrier(Barrier::new_test_barrier(2)))
+            .await
+            .unwrap();
+        executor.next().await.unwrap().unwrap();
+        executor.next().await.unwrap().unwrap();
+
+        // The chunk before the barrier goes to the old owner, and the one after goes to the new.
+        let is_chunk = |idx: usize| {
+            output_data_vecs[idx]
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|msg| msg.is_chunk())
+                .collect_vec()
+        };
+        assert_eq!(is_chunk(0), vec![true, false, false]);
+        assert_eq!(is_chunk(1), vec![false, true, false]);
+    }
+
     #[tokio::test]
     async fn test_hash_dispatcher() {
         let num_outputs = 5; // actor id ranges from 1 to 5
@@ -0,0 +1,233 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use futures::{pin_mut, StreamExt};
+use futures_async_stream::try_stream;
+use itertools::Itertools;
+use risingwave_common::array::{Op, Row, StreamChunk};
+use risingwave_common::catalog::{ColumnDesc, ColumnId, Schema};
+use risingwave_common::types::{CheckedAdd, IntervalUnit, NaiveDateTimeWrapper, ScalarImpl};
+use risingwave_common::util::sort_util::OrderType;
+use risingwave_storage::table::state_table::StateTable;
+use risingwave_storage::{Keyspace, StateStore};
+
+use super::error::{StreamExecutorError, StreamExecutorResult};
+use super::top_n_executor::generate_output;
+use super::{
+    expect_first_barrier, BoxedExecutor, BoxedMessageStream, Executor, ExecutorInfo, Message,
+    PkIndices, PkIndicesRef,
+};
+
+/// [`EmitOnWindowCloseExecutor`] buffers the results of windowed aggregations, and emits the final
+/// result of each window as an insertion once the window is closed, so that the output is
+/// append-only.
+///
+/// A window is considered closed once the results of a window ending `window_slide` later are
+/// seen, i.e. some input of the aggregation has passed the end of the window. Results of closed
+/// windows arriving later are dropped. The executor must be a singleton, so that it sees the
+/// results of all windows.
+pub struct EmitOnWindowCloseExecutor<S: StateStore> {
+    input: BoxedExecutor,
+
+    info: ExecutorInfo,
+
+    window_end_index: usize,
+
+    window_slide: IntervalUnit,
+
+    /// Results of the windows not closed yet, ordered by `window_end`.
+    state_table: StateTable<S>,
+}
+
+impl<S: StateStore> EmitOnWindowCloseExecutor<S> {
+    pub fn new(
+        input: BoxedExecutor,
+        pk_indices: PkIndices,
+        executor_id: u64,
+        window_end_index: usize,
+        window_slide: IntervalUnit,
+        keyspace: Keyspace<S>,
+    ) -> Self {
+        let schema = input.schema().clone();
+        let column_descs = schema
+            .fields()
+            .iter()
+            .enumerate()
+            .map(|(idx, field)| ColumnDesc::unnamed(ColumnId::new(idx as i32), field.data_type()))
+            .collect();
+        let state_table = StateTable::new(
+            keyspace,
+            column_descs,
+            vec![OrderType::Ascending; pk_indices.len() + 1],
+            None,
+            pk_indices.clone(),
+        );
+        Self {
+            input,
+            info: ExecutorInfo {
+                schema,
+                pk_indices,
+                identity: format!("EmitOnWindowCloseExecutor {:X}", executor_id),
+            },
+            window_end_index,
+            window_slide,
+            state_table,
+        }
+    }
+
+    fn window_end(&self, row: &Row) -> Option<NaiveDateTimeWrapper> {
+        match &row[self.window_end_index] {
+            Some(ScalarImpl::NaiveDateTime(window_end)) => Some(*window_end),
+            _ => None,
+        }
+    }
+
+    /// The buffer is keyed by `window_end` followed by the pk of the input.
+    fn buffer_key(&self, row: &Row) -> Row {
+        Row(std::iter::once(self.window_end_index)
+            .chain(self.info.pk_indices.iter().copied())
+            .map(|idx| row[idx].clone())
+            .collect_vec())
+    }
+
+    /// Windows ending no later than the returned time are closed.
+    fn closed_before(
+        &self,
+        max_window_end: NaiveDateTimeWrapper,
+    ) -> StreamExecutorResult<NaiveDateTimeWrapper> {
+        max_window_end
+            .checked_add(self.window_slide.negative())
+            .map_err(StreamExecutorError::eval_error)
+    }
+
+    /// Removes the results of closed windows from the buffer, and returns them as insertions.
+    async fn take_closed_windows(
+        &mut self,
+        closed_before: NaiveDateTimeWrapper,
+        epoch: u64,
+    ) -> StreamExecutorResult<Vec<Row>> {
+        let mut closed = vec![];
+        {
+            let iter = self.state_table.iter(epoch).await?;
+            pin_mut!(iter);
+            while let Some(row) = iter.next().await {
+                let row = row?.into_owned();
+                match self.window_end(&row) {
+                    Some(window_end) if window_end > closed_before => break,
+                    _ => closed.push(row),
+                }
+            }
+        }
+        for row in &closed {
+            let key = self.buffer_key(row);
+            self.state_table.delete(&key, row.clone())?;
+        }
+        Ok(closed)
+    }
+
+    /// Returns the latest `window_end` in the buffer, which is read on recovery.
+    async fn max_buffered_window_end(
+        &self,
+        epoch: u64,
+    ) -> StreamExecutorResult<Option<NaiveDateTimeWrapper>> {
+        let mut max_window_end = None;
+        let iter = self.state_table.iter(epoch).await?;
+        pin_mut!(iter);
+        while let Some(row) = iter.next().await {
+            max_window_end = max_window_end.max(self.window_end(&row?));
+        }
+        Ok(max_window_end)
+    }
+
+    #[try_stream(ok = Message, error = StreamExecutorError)]
+    async fn execute_inner(mut self) {
+        let mut input = self.input.execute();
+
+        let barrier = expect_first_barrier(&mut input).await?;
+        let mut max_window_end = self.max_buffered_window_end(barrier.epoch.prev).await?;
+        // Windows ending no later than this have been emitted.
+        let mut emitted_before = None;
+        yield Message::Barrier(barrier);
+
+        #[for_await]
+        for msg in input {
+            match msg? {
+                Message::Chunk(chunk) => {
+                    for (op, row_ref) in chunk.rows() {
+                        let row = row_ref.to_owned_row();
+                        // Rows without `window_end` never close, so they're never emitted.
+                        let window_end = match self.window_end(&row) {
+                            Some(window_end) => window_end,
+                            None => continue,
+                        };
+                        if emitted_before
+                            .map_or(false, |emitted_before| window_end <= emitted_before)
+                        {
+                            tracing::debug!(
+                                "{}: drop late result of window ending at {}",
+                                self.info.identity,
+                                window_end
+                            );
+                            continue;
+                        }
+                        max_window_end = max_window_end.max(Some(window_end));
+                        let key = self.buffer_key(&row);
+                        match op {
+                            Op::Insert | Op::UpdateInsert => self.state_table.insert(&key, row)?,
+                            Op::Delete | Op::UpdateDelete => self.state_table.delete(&key, row)?,
+                        }
+                    }
+                }
+                Message::Barrier(barrier) => {
+                    let epoch = barrier.epoch.prev;
+                    if let Some(max_window_end) = max_window_end {
+                        let closed_before = self.closed_before(max_window_end)?;
+                        if emitted_before != Some(closed_before) {
+                            let rows = self.take_closed_windows(closed_before, epoch).await?;
+                            emitted_before = Some(closed_before);
+                            if !rows.is_empty() {
+                                let ops = vec![Op::Insert; rows.len()];
+                                yield Message::Chunk(generate_output(
+                                    rows,
+                                    ops,
+                                    &self.info.schema,
+                                )?);
+                            }
+                        }
+                    }
+                    self.state_table.commit(epoch).await?;
+                    yield Message::Barrier(barrier);
+                }
+            }
+        }
+    }
+}
+
+impl<S: StateStore> Executor for EmitOnWindowCloseExecutor<S> {
+    fn execute(self: Box<Self>) -> BoxedMessageStream {
+        self.execute_inner().boxed()
+    }
+
+    fn schema(&self) -> &Schema {
+        &self.info.schema
+    }
+
+    fn pk_indices(&self) -> PkIndicesRef {
+        &self.info.pk_indices
+    }
+
+    fn identity(&self) -> &str {
+        &self.info.identity
+    }
+}
@@ -19,7 +19,7 @@ use futures::{stream, StreamExt};
 use futures_async_stream::try_stream;
 use iter_chunks::IterChunks;
 use itertools::Itertools;
-use madsim::collections::HashMap;
+use madsim::collections::{HashMap, HashSet};
 use risingwave_common::array::column::Column;
 use risingwave_common::array::{StreamChunk, Vis};
 use risingwave_common::buffer::Bitmap;
@@ -41,7 +41,12 @@ use crate::executor::aggregation::{
     AggState,
 };
 use crate::executor::error::StreamExecutorError;
+use crate::executor::monitor::StreamingMetrics;
 use crate::executor::{BoxedMessageStream, Message, PkIndices, PROCESSING_WINDOW_SIZE};
+use crate::task::ActorId;
+
+/// Default target number of cached group states of [`HashAggExecutor`].
+const AGG_CACHE_SIZE: usize = 1 << 16;
 
 /// [`HashAggExecutor`] could process large amounts of data using a state backend. It works as
 /// follows:
@@ -88,6 +93,14 @@ struct HashAggExecutorExtra<S: StateStore> {
     key_indices: Vec<usize>,
 
     state_tables: Vec<StateTable<S>>,
+
+    /// Id of the actor, whose vnodes may change on reschedule.
+    actor_id: ActorId,
+
+    /// Target number of group states kept in the cache.
+    cache_capacity: usize,
+
+    metrics: Arc<StreamingMetrics>,
 }
 
 impl<K: HashKey, S: StateStore> Executor for HashAggExecutor<K, S> {
@@ -116,6 +129,7 @@ impl<K: HashKey, S: StateStore> HashAggExecutor<K, S> {
         pk_indices: PkIndices,
         executor_id: u64,
         key_indices: Vec<usize>,
+        actor_id: ActorId,
     ) -> Result<Self> {
         let input_info = input.info();
         let schema = generate_agg_schema(input.as_ref(), &agg_calls, Some(&key_indices));
@@ -148,11 +162,26 @@ impl<K: HashKey, S: StateStore> HashAggExecutor<K, S> {
                 agg_calls,
                 key_indices,
                 state_tables,
+                actor_id,
+                cache_capacity: AGG_CACHE_SIZE,
+                metrics: Arc::new(StreamingMetrics::unused()),
             },
             _phantom: PhantomData,
         })
     }
 
+    /// Keeps at most `cache_capacity` group states in the cache after each epoch.
+    pub fn with_cache_capacity(mut self, cache_capacity: usize) -> Self {
+        self.extra.cache_capacity = cache_capacity;
+        self
+    }
+
+    /// Reports the number of groups flushed on each barrier as the actor.
+    pub fn with_metrics(mut self, metrics: Arc<StreamingMetrics>) -> Self {
+        self.extra.metrics = metrics;
+        self
+    }
+
     /// Get unique keys, hash codes and visibility map of each key in a batch.
     ///
     /// The returned order is the same as how we get distinct final columns from original columns.
@@ -216,6 +245,7 @@ impl<K: HashKey, S: StateStore> HashAggExecutor<K, S> {
             ..
         }: &HashAggExecutorExtra<S>,
         state_map: &mut EvictableHashMap<K, Option<Box<AggState<S>>>>,
+        modified_keys: &mut HashSet<K>,
         chunk: StreamChunk,
         epoch: u64,
     ) -> StreamExecutorResult<()> {
@@ -262,6 +292,7 @@ impl<K: HashKey, S: StateStore> HashAggExecutor<K, S> {
         for (key, hash_code, vis_map) in unique_keys {
             // Retrieve previous state from the KeyedState.
             let states = state_map.put(key.to_owned(), None);
+            modified_keys.insert(key.to_owned());
 
             let key = key.clone();
             // To leverage more parallelism in IO operations, fetching and updating states for every
@@ -330,11 +361,17 @@ impl<K: HashKey, S: StateStore> HashAggExecutor<K, S> {
             ref keyspace,
             ref schema,
             ref mut state_tables,
+            actor_id,
+            ref metrics,
             ..
         }: &'a mut HashAggExecutorExtra<S>,
         state_map: &'a mut EvictableHashMap<K, Option<Box<AggState<S>>>>,
+        modified_keys: &'a mut HashSet<K>,
         epoch: u64,
     ) {
+        // Only the groups modified in this epoch are flushed, instead of all the cached ones.
+        let modified_keys = modified_keys.drain().collect_vec();
+
         // The state store of each keyspace is the same so just need the first.
         let store = keyspace[0].state_store();
         // --- Flush states to the state store ---
@@ -344,7 +381,8 @@ impl<K: HashKey, S: StateStore> HashAggExecutor<K, S> {
             let mut write_batch = store.start_write_batch();
             let mut dirty_cnt = 0;
 
-            for states in state_map.values_mut() {
+            for key in &modified_keys {
+                let states = state_map.get_mut(key).unwrap();
                 if states.as_ref().unwrap().is_dirty() {
                     dirty_cnt += 1;
                     for (state, state_table) in states
@@ -369,6 +407,10 @@ impl<K: HashKey, S: StateStore> HashAggExecutor<K, S> {
 
             (write_batch, dirty_cnt)
         };
+        metrics
+            .agg_flushed_group_count
+            .with_label_values(&[&actor_id.to_string()])
+            .inc_by(dirty_cnt as u64);
 
         if dirty_cnt == 0 {
             // Nothing to flush.
@@ -381,7 +423,7 @@ impl<K: HashKey, S: StateStore> HashAggExecutor<K, S> {
                 .map_err(StreamExecutorError::agg_state_error)?;
 
             // --- Produce the stream chunk ---
-            let mut batches = IterChunks::chunks(state_map.iter_mut(), PROCESSING_WINDOW_SIZE);
+            let mut batches = IterChunks::chunks(modified_keys.iter(), PROCESSING_WINDOW_SIZE);
             while let Some(batch) = batches.next() {
                 // --- Create array builders ---
                 // As the datatype is retrieved from schema, it contains both group key and
@@ -392,7 +434,8 @@ impl<K: HashKey, S: StateStore> HashAggExecutor<K, S> {
                 let mut new_ops = Vec::with_capacity(dirty_cnt);
 
                 // --- Retrieve modified states and put the changes into the builders ---
-                for (key, states) in batch {
+                for key in batch {
+                    let states = state_map.get_mut(key).unwrap();
                     let appended = states
                         .as_mut()
                         .unwrap()
@@ -437,29 +480,51 @@ impl<K: HashKey, S: StateStore> HashAggExecutor<K, S> {
         } = self;
 
         // The cached states. `HashKey -> (prev_value, value)`.
-        let mut state_map = EvictableHashMap::new(1 << 16);
+        let mut state_map = EvictableHashMap::new(extra.cache_capacity);
+
+        // The keys of the groups modified in the current epoch.
+        let mut modified_keys = HashSet::new();
 
         let mut input = input.execute();
         let barrier = expect_first_barrier(&mut input).await?;
         let mut epoch = barrier.epoch.curr;
         yield Message::Barrier(barrier);
 
+        // The epoch in which the vnodes of this actor changed, whose states must be committed
+        // before being read.
+        let mut vnodes_changed_epoch = None;
+
         #[for_await]
         for msg in input {
             let msg = msg?;
             match msg {
                 Message::Chunk(chunk) => {
-                    Self::apply_chunk(&extra, &mut state_map, chunk, epoch).await?;
+                    if let Some(changed_epoch) = vnodes_changed_epoch.take() {
+                        // The states of the vnodes moved to this actor are flushed by their old
+                        // owners, possibly on other compute nodes.
+                        extra.keyspace[0].state_store().wait_epoch(changed_epoch).await?;
+                    }
+                    Self::apply_chunk(&extra, &mut state_map, &mut modified_keys, chunk, epoch)
+                        .await?;
                 }
                 Message::Barrier(barrier) => {
                     let next_epoch = barrier.epoch.curr;
                     assert_eq!(epoch, barrier.epoch.prev);
 
                     #[for_await]
-                    for chunk in Self::flush_data(&mut extra, &mut state_map, epoch) {
+                    for chunk in
+                        Self::flush_data(&mut extra, &mut state_map, &mut modified_keys, epoch)
+                    {
                         yield Message::Chunk(chunk?);
                     }
 
+                    if barrier.as_update_vnode_bitmap(extra.actor_id).is_some() {
+                        // The cached states are flushed above, and those of the vnodes moved out
+                        // will be stale. Drop them all and reload from the state store.
+                        state_map.clear();
+                        vnodes_changed_epoch = Some(epoch);
+                    }
+
                     yield Message::Barrier(barrier);
                     epoch = next_epoch;
                 }
@@ -488,6 +553,7 @@ mod tests {
     use crate::executor::aggregation::{AggArgs, AggCall};
     use crate::executor::test_utils::*;
     use crate::executor::{Executor, HashAggExecutor, Message, PkIndices};
+    use crate::task::ActorId;
 
     struct HashAggExecutorDispatcher<S: StateStore>(PhantomData<S>);
 
@@ -498,6 +564,7 @@ mod tests {
         keyspace: Vec<Keyspace<S>>,
         pk_indices: PkIndices,
         executor_id: u64,
+        actor_id: ActorId,
     }
 
     impl<S: StateStore> HashKeyDispatcher for HashAggExecutorDispatcher<S> {
@@ -512,6 +579,7 @@ mod tests {
                 args.pk_indices,
                 args.executor_id,
                 args.key_indices,
+                args.actor_id,
             )?))
         }
     }
@@ -535,6 +603,7 @@ mod tests {
             keyspace,
             pk_indices,
             executor_id,
+            actor_id: 0,
         };
         let kind = calc_hash_key_kind(&keys);
         HashAggExecutorDispatcher::dispatch_by_kind(kind, args).unwrap()
@@ -12,6 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::sync::Arc;
+
 use futures::StreamExt;
 use futures_async_stream::try_stream;
 use itertools::Itertools;
@@ -27,9 +29,11 @@ use risingwave_storage::{Keyspace, StateStore};
 use super::barrier_align::*;
 use super::error::StreamExecutorError;
 use super::managed_state::join::*;
+use super::monitor::StreamingMetrics;
 use super::{BoxedExecutor, BoxedMessageStream, Executor, Message, PkIndices, PkIndicesRef};
 use crate::common::StreamChunkBuilder;
 use crate::executor::PROCESSING_WINDOW_SIZE;
+use crate::task::ActorId;
 
 pub const JOIN_CACHE_SIZE: usize = 1 << 16;
 
@@ -189,6 +193,11 @@ pub struct HashJoinExecutor<K: HashKey, S: StateStore, const T: JoinTypePrimitiv
 
     /// Whether the logic can be optimized for append-only stream
     append_only_optimize: bool,
+
+    /// Id of the actor for the cache metrics.
+    actor_id_str: String,
+
+    metrics: Arc<StreamingMetrics>,
 }
 
 impl<K: HashKey, S: StateStore, const T: JoinTypePrimitive> std::fmt::Debug
@@ -458,9 +467,25 @@ impl<K: HashKey, S: StateStore, const T: JoinTypePrimitive> HashJoinExecutor<K,
             key_indices,
             epoch: 0,
             append_only_optimize,
+            actor_id_str: String::new(),
+            metrics: Arc::new(StreamingMetrics::unused()),
         }
     }
 
+    /// Keeps at most `cache_capacity` join keys in the cache of each side after each epoch.
+    pub fn with_cache_capacity(mut self, cache_capacity: usize) -> Self {
+        self.side_l.ht.set_target_cap(cache_capacity);
+        self.side_r.ht.set_target_cap(cache_capacity);
+        self
+    }
+
+    /// Reports the cache size and evictions of each side as the actor.
+    pub fn with_metrics(mut self, actor_id: ActorId, metrics: Arc<StreamingMetrics>) -> Self {
+        self.actor_id_str = actor_id.to_string();
+        self.metrics = metrics;
+        self
+    }
+
     #[try_stream(ok = Message, error = StreamExecutorError)]
     async fn into_stream(mut self) {
         let input_l = self.input_l.take().unwrap();
@@ -522,10 +547,20 @@ impl<K: HashKey, S: StateStore, const T: JoinTypePrimitive> HashJoinExecutor<K,
         }
 
         // evict the LRU cache
-        assert!(!self.side_l.is_dirty());
-        self.side_l.ht.evict_to_target_cap();
-        assert!(!self.side_r.is_dirty());
-        self.side_r.ht.evict_to_target_cap();
+        for (side, side_name) in [(&mut self.side_l, "left"), (&mut self.side_r, "right")] {
+            assert!(!side.is_dirty());
+            let cached = side.ht.len();
+            side.ht.evict_to_target_cap();
+            let labels = [self.actor_id_str.as_str(), side_name];
+            self.metrics
+                .join_cache_evicted_count
+                .with_label_values(&labels)
+                .inc_by((cached - side.ht.len()) as u64);
+            self.metrics
+                .join_cached_entry_count
+                .with_label_values(&labels)
+                .set(side.ht.len() as i64);
+        }
         Ok(())
     }
 
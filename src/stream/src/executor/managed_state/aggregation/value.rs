@@ -37,6 +37,9 @@ pub struct ManagedValueState {
     /// Primary key to look up in relational table. For value state, there is only one row.
     /// If None, the pk is empty vector (simple agg). If not None, the pk is group key (hash agg).
     pk: Option<Row>,
+
+    /// The value known to be in the state store, if any. A flush with the same value is skipped.
+    persisted: Option<Datum>,
 }
 
 impl ManagedValueState {
@@ -47,7 +50,7 @@ impl ManagedValueState {
         pk: Option<&Row>,
         state_table: &StateTable<S>,
     ) -> Result<Self> {
-        let data = if row_count != Some(0) {
+        let persisted = if row_count != Some(0) {
             // TODO: use the correct epoch
             let epoch = u64::MAX;
 
@@ -57,7 +60,7 @@ impl ManagedValueState {
                 .get_row(pk.unwrap_or(&Row(vec![])), epoch)
                 .await?;
 
-            raw_data.and_then(|row| row.values().next().cloned())
+            Some(raw_data.and_then(|row| row.values().next().cloned()))
         } else {
             None
         };
@@ -68,10 +71,11 @@ impl ManagedValueState {
                 agg_call.args.arg_types(),
                 &agg_call.kind,
                 &agg_call.return_type,
-                data,
+                persisted.clone().flatten(),
             )?,
             is_dirty: false,
             pk: pk.cloned(),
+            persisted,
         })
     }
 
@@ -110,9 +114,15 @@ impl ManagedValueState {
         // cause incorrect result: it will only produce more I/O.
         debug_assert!(self.is_dirty());
 
-        // Persist value into relational table.
+        // Persist value into relational table, unless the changes in the epoch cancel out.
         let v = self.state.get_output()?;
-        state_table.insert(self.pk.as_ref().unwrap_or(&Row(vec![])), Row(vec![v]))?;
+        if self.persisted.as_ref() != Some(&v) {
+            state_table.insert(
+                self.pk.as_ref().unwrap_or(&Row(vec![])),
+                Row(vec![v.clone()]),
+            )?;
+            self.persisted = Some(v);
+        }
 
         self.is_dirty = false;
         Ok(())
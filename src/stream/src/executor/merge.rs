@@ -17,7 +17,7 @@ use std::task::{Context, Poll};
 
 use async_trait::async_trait;
 use futures::channel::mpsc::{Receiver, Sender};
-use futures::{SinkExt, Stream, StreamExt};
+use futures::{Stream, StreamExt};
 use futures_async_stream::for_await;
 use risingwave_common::catalog::Schema;
 use risingwave_common::error::Result;
@@ -28,7 +28,7 @@ use tonic::Streaming;
 use super::error::StreamExecutorError;
 use super::*;
 use crate::executor::monitor::StreamingMetrics;
-use crate::task::UpDownActorIds;
+use crate::task::{send_with_stats, ChannelStatsRef, ExecutorStatsRef, UpDownActorIds};
 
 /// Receive data from `gRPC` and forwards to `MergerExecutor`/`ReceiverExecutor`
 pub struct RemoteInput {
@@ -36,6 +36,8 @@ pub struct RemoteInput {
     sender: Sender<Message>,
     up_down_ids: UpDownActorIds,
     metrics: Arc<StreamingMetrics>,
+    /// Statistics of the local channel that forwards the received messages.
+    stats: Option<ChannelStatsRef>,
 }
 
 impl RemoteInput {
@@ -53,9 +55,15 @@ impl RemoteInput {
             sender,
             up_down_ids,
             metrics,
+            stats: None,
         })
     }
 
+    pub fn with_stats(mut self, stats: ChannelStatsRef) -> Self {
+        self.stats = Some(stats);
+        self
+    }
+
     pub async fn run(mut self) {
         let up_actor_id = self.up_down_ids.0.to_string();
         let down_actor_id = self.up_down_ids.1.to_string();
@@ -75,7 +83,9 @@ impl RemoteInput {
                         .inc_by(bytes as u64);
                     match msg_res {
                         Ok(msg) => {
-                            self.sender.send(msg).await.unwrap();
+                            send_with_stats(&mut self.sender, msg, self.stats.as_ref())
+                                .await
+                                .unwrap();
                         }
                         Err(e) => {
                             error!("RemoteInput forward message error:{}", e);
@@ -98,6 +108,12 @@ pub struct MergeExecutor {
     /// Upstream channels.
     upstreams: Vec<Receiver<Message>>,
 
+    /// Statistics of the upstream channels, in the same order as `upstreams`.
+    stats: Option<Vec<ChannelStatsRef>>,
+
+    /// Statistics of the executor, into which the barrier alignment of the upstreams is recorded.
+    executor_stats: Option<ExecutorStatsRef>,
+
     /// Belonged actor id.
     actor_id: u32,
 
@@ -118,6 +134,8 @@ impl MergeExecutor {
     ) -> Self {
         Self {
             upstreams: inputs,
+            stats: None,
+            executor_stats: None,
             actor_id,
             info: ExecutorInfo {
                 schema,
@@ -127,15 +145,34 @@ impl MergeExecutor {
             status: OperatorInfoStatus::new(actor_context, receiver_id),
         }
     }
+
+    pub fn with_stats(mut self, stats: Vec<ChannelStatsRef>) -> Self {
+        assert_eq!(stats.len(), self.upstreams.len());
+        self.stats = Some(stats);
+        self
+    }
+
+    pub fn with_executor_stats(mut self, executor_stats: ExecutorStatsRef) -> Self {
+        self.executor_stats = Some(executor_stats);
+        self
+    }
 }
 
 #[async_trait]
 impl Executor for MergeExecutor {
     fn execute(self: Box<Self>) -> BoxedMessageStream {
-        let upstreams = self.upstreams;
+        let upstreams = match self.stats {
+            Some(stats) => self
+                .upstreams
+                .into_iter()
+                .zip(stats.into_iter().map(Some))
+                .collect(),
+            None => self.upstreams.into_iter().map(|rx| (rx, None)).collect(),
+        };
         // Futures of all active upstreams.
         let status = self.status;
-        let select_all = SelectReceivers::new(self.actor_id, status, upstreams);
+        let select_all =
+            SelectReceivers::new(self.actor_id, status, upstreams, self.executor_stats);
         // Channels that're blocked by the barrier to align.
         select_all.boxed()
     }
@@ -153,17 +190,26 @@ impl Executor for MergeExecutor {
     }
 }
 
+/// An upstream channel, with its statistics if any.
+type Upstream = (Receiver<Message>, Option<ChannelStatsRef>);
+
 pub struct SelectReceivers {
-    blocks: Vec<Receiver<Message>>,
-    upstreams: Vec<Receiver<Message>>,
+    blocks: Vec<Upstream>,
+    upstreams: Vec<Upstream>,
     barrier: Option<Barrier>,
     last_base: usize,
     status: OperatorInfoStatus,
     actor_id: u32,
+    executor_stats: Option<ExecutorStatsRef>,
 }
 
 impl SelectReceivers {
-    fn new(actor_id: u32, status: OperatorInfoStatus, upstreams: Vec<Receiver<Message>>) -> Self {
+    fn new(
+        actor_id: u32,
+        status: OperatorInfoStatus,
+        upstreams: Vec<Upstream>,
+        executor_stats: Option<ExecutorStatsRef>,
+    ) -> Self {
         Self {
             blocks: Vec::with_capacity(upstreams.len()),
             upstreams,
@@ -171,6 +217,7 @@ impl SelectReceivers {
             actor_id,
             status,
             barrier: None,
+            executor_stats,
         }
     }
 }
@@ -184,7 +231,8 @@ impl Stream for SelectReceivers {
         let mut poll_count = 0;
         while poll_count < self.upstreams.len() {
             let idx = (poll_count + self.last_base) % self.upstreams.len();
-            match self.upstreams[idx].poll_next_unpin(cx) {
+            let (upstream, stats) = &mut self.upstreams[idx];
+            match upstream.poll_next_unpin(cx) {
                 Poll::Pending => {
                     poll_count += 1;
                     continue;
@@ -193,8 +241,17 @@ impl Stream for SelectReceivers {
                     let message = item.expect(
                         "upstream channel closed unexpectedly, please check error in upstream executors"
                     );
+                    if let Some(stats) = stats {
+                        stats.on_recv();
+                    }
                     match message {
                         Message::Barrier(barrier) => {
+                            if let Some(executor_stats) = &self.executor_stats {
+                                executor_stats.on_input_barrier(
+                                    barrier.epoch.curr,
+                                    self.upstreams.len() + self.blocks.len(),
+                                );
+                            }
                             let rc = self.upstreams.swap_remove(idx);
                             self.blocks.push(rc);
                             if let Some(current_barrier) = self.barrier.as_ref() {
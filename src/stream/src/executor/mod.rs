@@ -23,21 +23,24 @@ use madsim::collections::{HashMap, HashSet};
 use risingwave_common::array::column::Column;
 use risingwave_common::array::{ArrayImpl, ArrayRef, DataChunk, StreamChunk};
 use risingwave_common::buffer::Bitmap;
-use risingwave_common::catalog::Schema;
+use risingwave_common::catalog::{ColumnDesc, ColumnId, Schema, TableId};
 use risingwave_common::error::{Result, ToRwResult};
 use risingwave_common::types::DataType;
+use risingwave_common::util::compress::{compress_data, decompress_data};
 use risingwave_connector::{ConnectorState, SplitImpl};
 use risingwave_pb::common::ActorInfo;
 use risingwave_pb::data::barrier::Mutation as ProstMutation;
 use risingwave_pb::data::stream_message::StreamMessage;
 use risingwave_pb::data::{
-    AddMutation, Barrier as ProstBarrier, DispatcherMutation, Epoch as ProstEpoch, NothingMutation,
-    SourceChangeSplit, SourceChangeSplitMutation, StopMutation,
-    StreamMessage as ProstStreamMessage, UpdateMutation,
+    AddColumnsMutation, AddMutation, AddedColumn, Barrier as ProstBarrier, DispatcherHashMapping,
+    DispatcherMutation, Epoch as ProstEpoch, NothingMutation, PauseMutation,
+    RescheduleMutation as ProstRescheduleMutation, ResumeMutation, SourceChangeSplit,
+    SourceChangeSplitMutation, StopMutation, StreamMessage as ProstStreamMessage, UpdateMutation,
 };
 use smallvec::SmallVec;
 use tracing::trace_span;
 
+use crate::common::vnode_bitmap_from_protobuf;
 use crate::task::{ActorId, DispatcherId, ENABLE_BARRIER_AGGREGATION};
 
 mod actor;
@@ -45,8 +48,11 @@ pub mod aggregation;
 mod barrier_align;
 mod batch_query;
 mod chain;
+mod changelog;
 mod debug;
+mod dedup;
 pub mod dispatch;
+mod emit_on_window_close;
 mod error;
 mod filter;
 mod global_simple_agg;
@@ -60,11 +66,13 @@ mod managed_state;
 pub mod merge;
 pub mod monitor;
 mod mview;
+mod profile;
 mod project;
 mod rearranged_chain;
 pub mod receiver;
 mod simple;
 mod source;
+mod temporal_filter;
 mod top_n;
 mod top_n_appendonly;
 mod top_n_executor;
@@ -78,8 +86,11 @@ mod test_utils;
 pub use actor::{Actor, ActorContext, ActorContextRef, OperatorInfo, OperatorInfoStatus};
 pub use batch_query::BatchQueryExecutor;
 pub use chain::ChainExecutor;
+pub use changelog::ChangelogExecutor;
 pub use debug::DebugExecutor;
+pub use dedup::DedupExecutor;
 pub use dispatch::DispatchExecutor;
+pub use emit_on_window_close::EmitOnWindowCloseExecutor;
 pub use filter::FilterExecutor;
 pub use global_simple_agg::SimpleAggExecutor;
 pub use hash_agg::HashAggExecutor;
@@ -90,10 +101,12 @@ pub use lookup::*;
 pub use lookup_union::LookupUnionExecutor;
 pub use merge::MergeExecutor;
 pub use mview::*;
+pub use profile::ProfileExecutor;
 pub use project::ProjectExecutor;
 pub use rearranged_chain::RearrangedChainExecutor;
 use simple::{SimpleExecutor, SimpleExecutorWrapper};
 pub use source::*;
+pub use temporal_filter::TemporalFilterExecutor;
 pub use top_n::TopNExecutor;
 pub use top_n_appendonly::AppendOnlyTopNExecutor;
 pub use union::UnionExecutor;
@@ -168,6 +181,27 @@ pub enum Mutation {
     UpdateOutputs(HashMap<(ActorId, DispatcherId), Vec<ActorInfo>>),
     AddOutput(HashMap<(ActorId, DispatcherId), Vec<ActorInfo>>),
     SourceChangeSplit(HashMap<ActorId, ConnectorState>),
+    /// Moves vnodes between the actors of a fragment. See [`ProstRescheduleMutation`].
+    Reschedule {
+        /// New mappings from vnodes to downstream actors of hash dispatchers.
+        hash_mappings: HashMap<(ActorId, DispatcherId), Vec<ActorId>>,
+        /// New vnodes of the actors whose vnodes change.
+        vnode_bitmaps: HashMap<ActorId, Arc<Bitmap>>,
+    },
+    /// Stops the sources from reading new data.
+    Pause,
+    /// Resumes the paused sources.
+    Resume,
+    /// Appends nullable columns to a source and its materialized view. See
+    /// [`AddColumnsMutation`].
+    AddColumns {
+        source_id: TableId,
+        /// Id of the materialized view of the source.
+        table_id: TableId,
+        columns: Vec<ColumnDesc>,
+        /// The source actors of the materialized view, which output the columns.
+        actor_ids: HashSet<ActorId>,
+    },
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -264,6 +298,45 @@ impl Barrier {
                 .any(|info| info.actor_id == actor_id)
         )
     }
+
+    /// Returns the new vnodes of the actor if they change at this barrier.
+    pub fn as_update_vnode_bitmap(&self, actor_id: ActorId) -> Option<Arc<Bitmap>> {
+        match self.mutation.as_deref() {
+            Some(Mutation::Reschedule { vnode_bitmaps, .. }) => {
+                vnode_bitmaps.get(&actor_id).cloned()
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the columns appended to the output of the source actor at this barrier.
+    pub fn as_add_source_columns(
+        &self,
+        source_id: TableId,
+        actor_id: ActorId,
+    ) -> Option<&[ColumnDesc]> {
+        match self.mutation.as_deref() {
+            Some(Mutation::AddColumns {
+                source_id: id,
+                columns,
+                actor_ids,
+                ..
+            }) if *id == source_id && actor_ids.contains(&actor_id) => Some(columns),
+            _ => None,
+        }
+    }
+
+    /// Returns the columns appended to materialized view `table_id` at this barrier.
+    pub fn as_add_table_columns(&self, table_id: TableId) -> Option<&[ColumnDesc]> {
+        match self.mutation.as_deref() {
+            Some(Mutation::AddColumns {
+                table_id: id,
+                columns,
+                ..
+            }) if *id == table_id => Some(columns),
+            _ => None,
+        }
+    }
 }
 
 impl PartialEq for Barrier {
@@ -341,6 +414,47 @@ impl Barrier {
                             .collect(),
                     }))
                 }
+                Some(Mutation::Reschedule {
+                    hash_mappings,
+                    vnode_bitmaps,
+                }) => Some(ProstMutation::Reschedule(ProstRescheduleMutation {
+                    hash_mappings: hash_mappings
+                        .iter()
+                        .map(|(&(actor_id, dispatcher_id), mapping)| {
+                            let (original_indices, data) = compress_data(mapping);
+                            DispatcherHashMapping {
+                                actor_id,
+                                dispatcher_id,
+                                original_indices,
+                                data,
+                            }
+                        })
+                        .collect(),
+                    vnode_bitmaps: vnode_bitmaps
+                        .iter()
+                        .map(|(&actor_id, bitmap)| (actor_id, bitmap.bits.to_vec()))
+                        .collect(),
+                })),
+                Some(Mutation::Pause) => Some(ProstMutation::Pause(PauseMutation {})),
+                Some(Mutation::Resume) => Some(ProstMutation::Resume(ResumeMutation {})),
+                Some(Mutation::AddColumns {
+                    source_id,
+                    table_id,
+                    columns,
+                    actor_ids,
+                }) => Some(ProstMutation::AddColumns(AddColumnsMutation {
+                    source_id: source_id.table_id(),
+                    table_id: table_id.table_id(),
+                    columns: columns
+                        .iter()
+                        .map(|column| AddedColumn {
+                            column_id: column.column_id.get_id(),
+                            column_type: Some(column.data_type.to_protobuf()),
+                            name: column.name.clone(),
+                        })
+                        .collect(),
+                    actor_ids: actor_ids.iter().copied().collect(),
+                })),
             },
             span: vec![],
         }
@@ -411,6 +525,49 @@ impl Barrier {
                     .into(),
                 )
             }
+            ProstMutation::Reschedule(reschedule) => Some(
+                Mutation::Reschedule {
+                    hash_mappings: reschedule
+                        .hash_mappings
+                        .iter()
+                        .map(|mapping| {
+                            (
+                                (mapping.actor_id, mapping.dispatcher_id),
+                                decompress_data(&mapping.original_indices, &mapping.data),
+                            )
+                        })
+                        .collect(),
+                    vnode_bitmaps: reschedule
+                        .vnode_bitmaps
+                        .iter()
+                        .map(|(&actor_id, bitmap)| (actor_id, vnode_bitmap_from_protobuf(bitmap)))
+                        .collect(),
+                }
+                .into(),
+            ),
+            ProstMutation::Pause(_) => Some(Mutation::Pause.into()),
+            ProstMutation::Resume(_) => Some(Mutation::Resume.into()),
+            ProstMutation::AddColumns(add_columns) => Some(
+                Mutation::AddColumns {
+                    source_id: TableId::new(add_columns.source_id),
+                    table_id: TableId::new(add_columns.table_id),
+                    columns: add_columns
+                        .columns
+                        .iter()
+                        .map(|column| {
+                            Ok(ColumnDesc {
+                                data_type: DataType::from(column.get_column_type()?),
+                                column_id: ColumnId::new(column.column_id),
+                                name: column.name.clone(),
+                                field_descs: vec![],
+                                type_name: "".to_string(),
+                            })
+                        })
+                        .collect::<Result<_>>()?,
+                    actor_ids: add_columns.actor_ids.iter().copied().collect(),
+                }
+                .into(),
+            ),
         };
         let epoch = prost.get_epoch().unwrap();
         Ok(Barrier {
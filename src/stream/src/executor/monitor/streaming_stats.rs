@@ -12,9 +12,10 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use prometheus::core::{AtomicF64, AtomicU64, GenericCounterVec, GenericGaugeVec};
+use prometheus::core::{AtomicF64, AtomicI64, AtomicU64, GenericCounterVec, GenericGaugeVec};
 use prometheus::{
-    register_gauge_vec_with_registry, register_int_counter_vec_with_registry, Registry,
+    register_gauge_vec_with_registry, register_int_counter_vec_with_registry,
+    register_int_gauge_vec_with_registry, Registry,
 };
 
 pub struct StreamingMetrics {
@@ -24,6 +25,13 @@ pub struct StreamingMetrics {
     pub actor_barrier_time: GenericGaugeVec<AtomicF64>,
     pub source_output_row_count: GenericCounterVec<AtomicU64>,
     pub exchange_recv_size: GenericCounterVec<AtomicU64>,
+    pub actor_output_buffered_messages: GenericGaugeVec<AtomicI64>,
+    pub actor_output_blocking_duration_ns: GenericCounterVec<AtomicU64>,
+    pub materialize_cache_hit_count: GenericCounterVec<AtomicU64>,
+    pub materialize_cache_miss_count: GenericCounterVec<AtomicU64>,
+    pub join_cached_entry_count: GenericGaugeVec<AtomicI64>,
+    pub join_cache_evicted_count: GenericCounterVec<AtomicU64>,
+    pub agg_flushed_group_count: GenericCounterVec<AtomicU64>,
 }
 
 impl StreamingMetrics {
@@ -68,6 +76,62 @@ impl StreamingMetrics {
         )
         .unwrap();
 
+        let actor_output_buffered_messages = register_int_gauge_vec_with_registry!(
+            "stream_actor_output_buffered_messages",
+            "Number of messages buffered in the channel from up_actor_id to down_actor_id",
+            &["up_actor_id", "down_actor_id"],
+            registry
+        )
+        .unwrap();
+
+        let actor_output_blocking_duration_ns = register_int_counter_vec_with_registry!(
+            "stream_actor_output_blocking_duration_ns",
+            "Total duration that up_actor_id is blocked on sending to the full channel to down_actor_id",
+            &["up_actor_id", "down_actor_id"],
+            registry
+        )
+        .unwrap();
+
+        let materialize_cache_hit_count = register_int_counter_vec_with_registry!(
+            "stream_materialize_cache_hit_count",
+            "Total number of previous rows of the materialize executor found in its row cache",
+            &["actor_id"],
+            registry
+        )
+        .unwrap();
+
+        let materialize_cache_miss_count = register_int_counter_vec_with_registry!(
+            "stream_materialize_cache_miss_count",
+            "Total number of previous rows of the materialize executor read from the state store",
+            &["actor_id"],
+            registry
+        )
+        .unwrap();
+
+        let join_cached_entry_count = register_int_gauge_vec_with_registry!(
+            "stream_join_cached_entry_count",
+            "Number of join keys cached by each side of the hash join executor after eviction",
+            &["actor_id", "side"],
+            registry
+        )
+        .unwrap();
+
+        let join_cache_evicted_count = register_int_counter_vec_with_registry!(
+            "stream_join_cache_evicted_count",
+            "Total number of join keys evicted from the cache of each side of the hash join executor",
+            &["actor_id", "side"],
+            registry
+        )
+        .unwrap();
+
+        let agg_flushed_group_count = register_int_counter_vec_with_registry!(
+            "stream_agg_flushed_group_count",
+            "Total number of groups modified and flushed by the hash aggregation executor",
+            &["actor_id"],
+            registry
+        )
+        .unwrap();
+
         Self {
             registry,
             actor_row_count,
@@ -75,6 +139,13 @@ impl StreamingMetrics {
             actor_barrier_time,
             source_output_row_count,
             exchange_recv_size,
+            actor_output_buffered_messages,
+            actor_output_blocking_duration_ns,
+            materialize_cache_hit_count,
+            materialize_cache_miss_count,
+            join_cached_entry_count,
+            join_cache_evicted_count,
+            agg_flushed_group_count,
         }
     }
 
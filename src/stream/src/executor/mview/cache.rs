@@ -0,0 +1,53 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use risingwave_common::array::Row;
+use risingwave_common::collection::evictable::EvictableHashMap;
+
+/// A cache of the latest version of the rows materialized by an actor, keyed by the arrange key.
+///
+/// The actor is the only writer of the rows of the vnodes it owns, so an entry stays valid across
+/// epochs until the vnodes of the actor change.
+pub struct MaterializeCache {
+    /// `None` means the key is known to be absent.
+    data: EvictableHashMap<Row, Option<Row>>,
+}
+
+impl MaterializeCache {
+    pub fn new(target_cap: usize) -> Self {
+        Self {
+            data: EvictableHashMap::new(target_cap),
+        }
+    }
+
+    /// Returns the cached version of the row with `key`, or `None` if the key is not cached.
+    pub fn get(&mut self, key: &Row) -> Option<&Option<Row>> {
+        self.data.get(key)
+    }
+
+    /// Records the latest version of the row with `key`, `None` if it's deleted.
+    pub fn put(&mut self, key: Row, row: Option<Row>) {
+        self.data.push(key, row);
+    }
+
+    /// Evicts the least recently used rows at the end of an epoch.
+    pub fn flush(&mut self) {
+        self.data.evict_to_target_cap();
+    }
+
+    /// Drops all the rows, after the rows of some vnodes may be written by other actors.
+    pub fn clear(&mut self) {
+        self.data.clear();
+    }
+}
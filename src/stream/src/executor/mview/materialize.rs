@@ -13,21 +13,29 @@
 // limitations under the License.
 
 use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::UNIX_EPOCH;
 
 use futures::StreamExt;
 use futures_async_stream::try_stream;
 use itertools::Itertools;
 use risingwave_common::array::Op::*;
-use risingwave_common::array::Row;
-use risingwave_common::catalog::{ColumnDesc, ColumnId, Schema};
-use risingwave_common::util::sort_util::OrderPair;
+use risingwave_common::array::{Op, Row, StreamChunk};
+use risingwave_common::buffer::Bitmap;
+use risingwave_common::catalog::{ColumnDesc, ColumnId, Field, Schema, TableId};
+use risingwave_common::types::{DataType, NaiveDateTimeWrapper, ScalarImpl};
+use risingwave_common::util::epoch::Epoch;
+use risingwave_common::util::sort_util::{OrderPair, OrderType};
 use risingwave_storage::table::state_table::StateTable;
 use risingwave_storage::{Keyspace, StateStore};
 
-use crate::executor::error::StreamExecutorError;
+use super::cache::MaterializeCache;
+use crate::executor::error::{StreamExecutorError, StreamExecutorResult};
+use crate::executor::monitor::StreamingMetrics;
 use crate::executor::{
     BoxedExecutor, BoxedMessageStream, Executor, ExecutorInfo, Message, PkIndicesRef,
 };
+use crate::task::ActorId;
 
 /// `MaterializeExecutor` materializes changes in stream into a materialized view on storage.
 pub struct MaterializeExecutor<S: StateStore> {
@@ -38,9 +46,138 @@ pub struct MaterializeExecutor<S: StateStore> {
     /// Columns of arrange keys (including pk, group keys, join keys, etc.)
     arrange_columns: Vec<usize>,
 
+    /// Id of the actor if it owns part of the vnodes, which may change on reschedule.
+    actor_id: Option<ActorId>,
+
+    /// Set if an insert on an existing key overwrites the row, see [`PkConflictHandler`].
+    conflict_handler: Option<PkConflictHandler>,
+
+    /// Id of the materialized view, set if it stores the columns added to it by barriers.
+    table_id: Option<TableId>,
+
+    /// Set if the changes of each epoch are counted, see [`ChangeStats`].
+    stats: Option<ChangeStats<S>>,
+
     info: ExecutorInfo,
 }
 
+/// Counts the rows changed by the actor in an epoch, and stores the counts in the internal table of
+/// the statistics on the barrier. The epochs without changes are not stored.
+struct ChangeStats<S: StateStore> {
+    /// Keyed by the epoch and the actor id.
+    state_table: StateTable<S>,
+
+    actor_id: ActorId,
+
+    inserted: i64,
+
+    deleted: i64,
+
+    /// An update is a pair of `UpdateDelete` and `UpdateInsert`, counted once.
+    updated: i64,
+
+    /// The change of the value-encoded size of the rows in bytes.
+    size_delta: i64,
+}
+
+impl<S: StateStore> ChangeStats<S> {
+    fn new(keyspace: Keyspace<S>, actor_id: ActorId) -> Self {
+        let column_descs = [
+            DataType::Int64,
+            DataType::Int32,
+            DataType::Timestamp,
+            DataType::Int64,
+            DataType::Int64,
+            DataType::Int64,
+            DataType::Int64,
+        ]
+        .into_iter()
+        .enumerate()
+        .map(|(idx, data_type)| ColumnDesc::unnamed(ColumnId::new(idx as i32), data_type))
+        .collect_vec();
+        Self {
+            state_table: StateTable::new(
+                keyspace,
+                column_descs,
+                vec![OrderType::Ascending; 2],
+                None,
+                vec![0, 1],
+            ),
+            actor_id,
+            inserted: 0,
+            deleted: 0,
+            updated: 0,
+            size_delta: 0,
+        }
+    }
+
+    fn record(&mut self, chunk: &StreamChunk) -> StreamExecutorResult<()> {
+        for (op, row_ref) in chunk.rows() {
+            let size = row_ref
+                .to_owned_row()
+                .value_encode()
+                .map_err(StreamExecutorError::executor_v1)?
+                .len() as i64;
+            match op {
+                Insert => self.inserted += 1,
+                Delete => self.deleted += 1,
+                UpdateInsert => self.updated += 1,
+                UpdateDelete => {}
+            }
+            match op {
+                Insert | UpdateInsert => self.size_delta += size,
+                Delete | UpdateDelete => self.size_delta -= size,
+            }
+        }
+        Ok(())
+    }
+
+    /// Stores the counts of `epoch` if there are any changes, and commits them.
+    async fn flush(&mut self, epoch: u64) -> StreamExecutorResult<()> {
+        if self.inserted != 0 || self.deleted != 0 || self.updated != 0 {
+            let time_micros = Epoch(epoch)
+                .as_system_time()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_micros() as i64;
+            let time = NaiveDateTimeWrapper::from_protobuf(time_micros)
+                .map_err(StreamExecutorError::executor_v1)?;
+            let row = Row(vec![
+                Some(ScalarImpl::Int64(epoch as i64)),
+                Some(ScalarImpl::Int32(self.actor_id as i32)),
+                Some(ScalarImpl::NaiveDateTime(time)),
+                Some(ScalarImpl::Int64(self.inserted)),
+                Some(ScalarImpl::Int64(self.deleted)),
+                Some(ScalarImpl::Int64(self.updated)),
+                Some(ScalarImpl::Int64(self.size_delta)),
+            ]);
+            let pk = Row(row.0[..2].to_vec());
+            self.state_table.insert(&pk, row)?;
+            self.inserted = 0;
+            self.deleted = 0;
+            self.updated = 0;
+            self.size_delta = 0;
+        }
+        self.state_table.commit(epoch).await?;
+        Ok(())
+    }
+}
+
+/// Overwrites the rows on conflicting keys, and emits the retractions of the overwritten rows.
+/// The previous versions of the rows are looked up in a [`MaterializeCache`] before the state
+/// store.
+struct PkConflictHandler {
+    cache: MaterializeCache,
+
+    /// Epoch of the rows being materialized, used to read the previous versions.
+    epoch: u64,
+
+    /// Id of the actor for the cache metrics.
+    actor_id_str: String,
+
+    metrics: Arc<StreamingMetrics>,
+}
+
 impl<S: StateStore> MaterializeExecutor<S> {
     pub fn new(
         input: BoxedExecutor,
@@ -78,6 +215,10 @@ impl<S: StateStore> MaterializeExecutor<S> {
                 arrange_columns.clone(),
             ),
             arrange_columns: arrange_columns.clone(),
+            actor_id: None,
+            conflict_handler: None,
+            table_id: None,
+            stats: None,
             info: ExecutorInfo {
                 schema,
                 pk_indices: arrange_columns,
@@ -86,6 +227,120 @@ impl<S: StateStore> MaterializeExecutor<S> {
         }
     }
 
+    /// Restricts the rows materialized by the actor to the `vnodes` it owns.
+    pub fn with_vnodes(mut self, actor_id: ActorId, vnodes: Arc<Bitmap>) -> Self {
+        self.state_table = self.state_table.with_vnodes(vnodes);
+        self.actor_id = Some(actor_id);
+        self
+    }
+
+    /// Stores the nullable columns appended to materialized view `table_id` by barriers, which
+    /// come after the existing columns in the chunks after the barrier.
+    pub fn with_table_id(mut self, table_id: TableId) -> Self {
+        self.table_id = Some(table_id);
+        self
+    }
+
+    /// Counts the changes of each epoch made by actor `actor_id`, and stores them in the internal
+    /// table of `keyspace`.
+    pub fn with_stats(mut self, keyspace: Keyspace<S>, actor_id: ActorId) -> Self {
+        self.stats = Some(ChangeStats::new(keyspace, actor_id));
+        self
+    }
+
+    fn record_stats(&mut self, chunk: &StreamChunk) -> StreamExecutorResult<()> {
+        match &mut self.stats {
+            Some(stats) => stats.record(chunk),
+            None => Ok(()),
+        }
+    }
+
+    /// Overwrites the row on an existing key on insert instead of treating it as a new row, and
+    /// keeps at most `cache_capacity` previous rows in the cache after each epoch.
+    pub fn with_pk_conflict_handling(
+        mut self,
+        actor_id: ActorId,
+        cache_capacity: usize,
+        metrics: Arc<StreamingMetrics>,
+    ) -> Self {
+        self.conflict_handler = Some(PkConflictHandler {
+            cache: MaterializeCache::new(cache_capacity),
+            epoch: 0,
+            actor_id_str: actor_id.to_string(),
+            metrics,
+        });
+        self
+    }
+
+    /// Applies `chunk` to the state table, overwriting the rows on conflicting keys. Returns the
+    /// changes actually made, or `None` if there are none.
+    async fn handle_pk_conflict(
+        &mut self,
+        chunk: StreamChunk,
+    ) -> StreamExecutorResult<Option<StreamChunk>> {
+        let handler = self.conflict_handler.as_mut().unwrap();
+        let mut changes = vec![];
+
+        for (op, row_ref) in chunk.rows() {
+            let arrange_row = row_ref.row_by_indices(&self.arrange_columns);
+            let row = row_ref.to_owned_row();
+
+            let old_row = match handler.cache.get(&arrange_row) {
+                Some(old_row) => {
+                    handler
+                        .metrics
+                        .materialize_cache_hit_count
+                        .with_label_values(&[&handler.actor_id_str])
+                        .inc();
+                    old_row.clone()
+                }
+                None => {
+                    handler
+                        .metrics
+                        .materialize_cache_miss_count
+                        .with_label_values(&[&handler.actor_id_str])
+                        .inc();
+                    self.state_table
+                        .get_row(&arrange_row, handler.epoch)
+                        .await?
+                }
+            };
+
+            match op {
+                Insert | UpdateInsert => {
+                    if let Some(old_row) = old_row {
+                        if old_row == row {
+                            handler.cache.put(arrange_row, Some(row));
+                            continue;
+                        }
+                        self.state_table.delete(&arrange_row, old_row.clone())?;
+                        changes.push((Op::UpdateDelete, old_row));
+                        changes.push((Op::UpdateInsert, row.clone()));
+                    } else {
+                        changes.push((Op::Insert, row.clone()));
+                    }
+                    self.state_table.insert(&arrange_row, row.clone())?;
+                    handler.cache.put(arrange_row, Some(row));
+                }
+                Delete | UpdateDelete => {
+                    // The row to delete may have been overwritten, so delete the actual one.
+                    if let Some(old_row) = old_row {
+                        self.state_table.delete(&arrange_row, old_row.clone())?;
+                        changes.push((Op::Delete, old_row));
+                    }
+                    handler.cache.put(arrange_row, None);
+                }
+            }
+        }
+
+        if changes.is_empty() {
+            return Ok(None);
+        }
+        let chunk = StreamChunk::from_rows(&changes, &self.info.schema.data_types())
+            .map_err(StreamExecutorError::executor_v1)?;
+        Ok(Some(chunk))
+    }
+
     #[try_stream(ok = Message, error = StreamExecutorError)]
     async fn execute_inner(mut self) {
         let input = self.input.execute();
@@ -93,6 +348,15 @@ impl<S: StateStore> MaterializeExecutor<S> {
         for msg in input {
             let msg = msg?;
             yield match msg {
+                Message::Chunk(chunk) if self.conflict_handler.is_some() => {
+                    match self.handle_pk_conflict(chunk).await? {
+                        Some(chunk) => {
+                            self.record_stats(&chunk)?;
+                            Message::Chunk(chunk)
+                        }
+                        None => continue,
+                    }
+                }
                 Message::Chunk(chunk) => {
                     for (idx, op) in chunk.ops().iter().enumerate() {
                         // check visibility
@@ -128,6 +392,7 @@ impl<S: StateStore> MaterializeExecutor<S> {
                             }
                         }
                     }
+                    self.record_stats(&chunk)?;
 
                     Message::Chunk(chunk)
                 }
@@ -137,6 +402,38 @@ impl<S: StateStore> MaterializeExecutor<S> {
                         .commit_with_value_meta(b.epoch.prev)
                         .await
                         .map_err(StreamExecutorError::executor_v1)?;
+                    if let Some(stats) = &mut self.stats {
+                        stats.flush(b.epoch.prev).await?;
+                    }
+                    // The rows of moved vnodes before the barrier are committed above.
+                    if let Some(vnodes) = self
+                        .actor_id
+                        .and_then(|actor_id| b.as_update_vnode_bitmap(actor_id))
+                    {
+                        self.state_table.update_vnodes(vnodes);
+                        // The rows of the vnodes moved in may be written by other actors.
+                        if let Some(handler) = &mut self.conflict_handler {
+                            handler.cache.clear();
+                        }
+                    }
+                    if let Some(columns) = self
+                        .table_id
+                        .and_then(|table_id| b.as_add_table_columns(table_id))
+                    {
+                        self.state_table.add_columns(columns);
+                        self.info
+                            .schema
+                            .fields
+                            .extend(columns.iter().map(Field::from));
+                        // The cached rows lack the columns added.
+                        if let Some(handler) = &mut self.conflict_handler {
+                            handler.cache.clear();
+                        }
+                    }
+                    if let Some(handler) = &mut self.conflict_handler {
+                        handler.cache.flush();
+                        handler.epoch = b.epoch.curr;
+                    }
                     Message::Barrier(b)
                 }
             }
@@ -174,18 +471,25 @@ impl<S: StateStore> std::fmt::Debug for MaterializeExecutor<S> {
 #[cfg(test)]
 mod tests {
 
+    use std::sync::Arc;
+
     use futures::stream::StreamExt;
+    use madsim::collections::{HashMap, HashSet};
     use risingwave_common::array::stream_chunk::StreamChunkTestExt;
     use risingwave_common::array::Row;
+    use risingwave_common::buffer::Bitmap;
     use risingwave_common::catalog::{ColumnDesc, Field, Schema, TableId};
-    use risingwave_common::types::DataType;
+    use risingwave_common::hash::VIRTUAL_NODE_COUNT;
+    use risingwave_common::types::{DataType, ScalarImpl};
     use risingwave_common::util::sort_util::{OrderPair, OrderType};
     use risingwave_storage::memory::MemoryStateStore;
     use risingwave_storage::table::cell_based_table::CellBasedTable;
     use risingwave_storage::Keyspace;
 
+    use crate::executor::monitor::StreamingMetrics;
     use crate::executor::test_utils::*;
     use crate::executor::*;
+    use crate::task::ActorId;
 
     #[tokio::test]
     async fn test_materialize_executor() {
@@ -267,4 +571,392 @@ mod tests {
             _ => unreachable!(),
         }
     }
+
+    #[tokio::test]
+    async fn test_materialize_executor_add_columns() {
+        let memory_state_store = MemoryStateStore::new();
+        let table_id = TableId::new(1);
+        let schema = Schema::new(vec![
+            Field::unnamed(DataType::Int32),
+            Field::unnamed(DataType::Int32),
+        ]);
+        let column_ids = vec![0.into(), 1.into()];
+        let added_column = ColumnDesc::unnamed(2.into(), DataType::Int32);
+
+        let source = MockSource::with_messages(
+            schema,
+            PkIndices::new(),
+            vec![
+                Message::Barrier(Barrier::new_test_barrier(1)),
+                Message::Chunk(StreamChunk::from_pretty(
+                    " i i
+                    + 1 4",
+                )),
+                Message::Barrier(Barrier::new_test_barrier(2).with_mutation(
+                    Mutation::AddColumns {
+                        source_id: TableId::new(2),
+                        table_id,
+                        columns: vec![added_column.clone()],
+                        actor_ids: HashSet::default(),
+                    },
+                )),
+                Message::Chunk(StreamChunk::from_pretty(
+                    " i i i
+                    + 2 5 6",
+                )),
+                Message::Barrier(Barrier::new_test_barrier(3)),
+            ],
+        );
+
+        let keyspace = Keyspace::table_root(memory_state_store.clone(), &table_id);
+        let table = CellBasedTable::new_for_test(
+            keyspace.clone(),
+            vec![
+                ColumnDesc::unnamed(column_ids[0], DataType::Int32),
+                ColumnDesc::unnamed(column_ids[1], DataType::Int32),
+                added_column,
+            ],
+            vec![OrderType::Ascending],
+        );
+        let materialize = MaterializeExecutor::new(
+            Box::new(source),
+            keyspace,
+            vec![OrderPair::new(0, OrderType::Ascending)],
+            column_ids,
+            1,
+            vec![0],
+        )
+        .with_table_id(table_id);
+        let mut materialize = Box::new(materialize).execute();
+        for _ in 0..5 {
+            materialize.next().await.unwrap().unwrap();
+        }
+
+        // The row materialized before the column is added reads null in it.
+        let get_row = |pk: i32| table.get_row(&Row(vec![Some(pk.into())]), u64::MAX);
+        assert_eq!(
+            get_row(1).await.unwrap(),
+            Some(Row(vec![Some(1_i32.into()), Some(4_i32.into()), None]))
+        );
+        assert_eq!(
+            get_row(2).await.unwrap(),
+            Some(Row(vec![
+                Some(2_i32.into()),
+                Some(5_i32.into()),
+                Some(6_i32.into())
+            ]))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_materialize_executor_reschedule() {
+        let memory_state_store = MemoryStateStore::new();
+        let table_id = TableId::new(1);
+        let schema = Schema::new(vec![
+            Field::unnamed(DataType::Int32),
+            Field::unnamed(DataType::Int32),
+        ]);
+        let column_ids = vec![0.into(), 1.into()];
+        let keyspace = Keyspace::table_root(memory_state_store.clone(), &table_id);
+        let table = CellBasedTable::new_for_test(
+            keyspace.clone(),
+            vec![
+                ColumnDesc::unnamed(column_ids[0], DataType::Int32),
+                ColumnDesc::unnamed(column_ids[1], DataType::Int32),
+            ],
+            vec![OrderType::Ascending],
+        );
+        let all_vnodes = || Arc::new(Bitmap::from_bool_slice(&[true; VIRTUAL_NODE_COUNT]));
+        let no_vnodes = || Arc::new(Bitmap::from_bool_slice(&[false; VIRTUAL_NODE_COUNT]));
+
+        // Move all vnodes from actor 1 to actor 2.
+        let mut vnode_bitmaps = HashMap::new();
+        vnode_bitmaps.insert(1, no_vnodes());
+        vnode_bitmaps.insert(2, all_vnodes());
+        let reschedule = Barrier::new_test_barrier(2).with_mutation(Mutation::Reschedule {
+            hash_mappings: HashMap::default(),
+            vnode_bitmaps,
+        });
+
+        let materialize = |actor_id: ActorId, vnodes: Arc<Bitmap>, messages: Vec<Message>| {
+            Box::new(
+                MaterializeExecutor::new(
+                    Box::new(MockSource::with_messages(
+                        schema.clone(),
+                        PkIndices::new(),
+                        messages,
+                    )),
+                    keyspace.clone(),
+                    vec![OrderPair::new(0, OrderType::Ascending)],
+                    column_ids.clone(),
+                    actor_id as u64,
+                    vec![0],
+                )
+                .with_vnodes(actor_id, vnodes),
+            )
+            .execute()
+        };
+        let get_row = |pk: i32| table.get_row(&Row(vec![Some(pk.into())]), u64::MAX);
+
+        // The chunk in flight before the reschedule barrier is still materialized by the old
+        // owner, and committed with the barrier.
+        let mut old_owner = materialize(
+            1,
+            all_vnodes(),
+            vec![
+                Message::Chunk(StreamChunk::from_pretty(
+                    " i i
+                    + 1 4
+                    + 2 5",
+                )),
+                Message::Barrier(reschedule.clone()),
+                Message::Chunk(StreamChunk::from_pretty(
+                    " i i
+                    + 3 6",
+                )),
+            ],
+        );
+        old_owner.next().await.transpose().unwrap();
+        assert!(matches!(
+            old_owner.next().await.transpose().unwrap(),
+            Some(Message::Barrier(_))
+        ));
+        assert_eq!(
+            get_row(1).await.unwrap(),
+            Some(Row(vec![Some(1_i32.into()), Some(4_i32.into())]))
+        );
+        // The old owner refuses rows of the vnodes moved out.
+        assert!(old_owner.next().await.unwrap().is_err());
+
+        // The new owner takes over the rows written by the old owner.
+        let mut new_owner = materialize(
+            2,
+            no_vnodes(),
+            vec![
+                Message::Barrier(reschedule),
+                Message::Chunk(StreamChunk::from_pretty(
+                    " i i
+                    - 1 4
+                    + 3 6",
+                )),
+                Message::Barrier(Barrier::new_test_barrier(3)),
+            ],
+        );
+        new_owner.next().await.transpose().unwrap();
+        new_owner.next().await.transpose().unwrap();
+        assert!(matches!(
+            new_owner.next().await.transpose().unwrap(),
+            Some(Message::Barrier(_))
+        ));
+        assert_eq!(get_row(1).await.unwrap(), None);
+        assert_eq!(
+            get_row(2).await.unwrap(),
+            Some(Row(vec![Some(2_i32.into()), Some(5_i32.into())]))
+        );
+        assert_eq!(
+            get_row(3).await.unwrap(),
+            Some(Row(vec![Some(3_i32.into()), Some(6_i32.into())]))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_materialize_executor_stats() {
+        let memory_state_store = MemoryStateStore::new();
+        let schema = Schema::new(vec![
+            Field::unnamed(DataType::Int32),
+            Field::unnamed(DataType::Int32),
+        ]);
+        let source = MockSource::with_messages(
+            schema,
+            PkIndices::new(),
+            vec![
+                Message::Barrier(Barrier::new_test_barrier(1)),
+                Message::Chunk(StreamChunk::from_pretty(
+                    " i i
+                    + 1 4
+                    + 2 5",
+                )),
+                Message::Barrier(Barrier::new_test_barrier(2)),
+                Message::Chunk(StreamChunk::from_pretty(
+                    "  i i
+                    U- 1 4
+                    U+ 1 6
+                    -  2 5",
+                )),
+                Message::Barrier(Barrier::new_test_barrier(3)),
+                Message::Barrier(Barrier::new_test_barrier(4)),
+            ],
+        );
+        let stats_keyspace = Keyspace::table_root(memory_state_store.clone(), &TableId::new(2));
+        let mut materialize_executor = Box::new(
+            MaterializeExecutor::new(
+                Box::new(source),
+                Keyspace::table_root(memory_state_store, &TableId::new(1)),
+                vec![OrderPair::new(0, OrderType::Ascending)],
+                vec![0.into(), 1.into()],
+                1,
+                vec![0],
+            )
+            .with_stats(stats_keyspace.clone(), 42),
+        )
+        .execute();
+        for _ in 0..6 {
+            materialize_executor.next().await.unwrap().unwrap();
+        }
+
+        let table = CellBasedTable::new_for_test(
+            stats_keyspace,
+            vec![
+                DataType::Int64,
+                DataType::Int32,
+                DataType::Timestamp,
+                DataType::Int64,
+                DataType::Int64,
+                DataType::Int64,
+                DataType::Int64,
+            ]
+            .into_iter()
+            .enumerate()
+            .map(|(idx, data_type)| ColumnDesc::unnamed((idx as i32).into(), data_type))
+            .collect(),
+            vec![OrderType::Ascending; 2],
+        );
+        let size = |row: &[i32]| {
+            Row(row.iter().map(|v| Some((*v).into())).collect())
+                .value_encode()
+                .unwrap()
+                .len() as i64
+        };
+        let stats_of = |epoch: i64| {
+            let table = &table;
+            async move {
+                table
+                    .get_row(
+                        &Row(vec![Some(epoch.into()), Some(42_i32.into())]),
+                        u64::MAX,
+                    )
+                    .await
+                    .unwrap()
+                    .map(|row| {
+                        assert!(row[2].is_some());
+                        row.0[3..].to_vec()
+                    })
+            }
+        };
+        let counts = |counts: [i64; 4]| Some(counts.map(|c| Some(ScalarImpl::Int64(c))).to_vec());
+        // The changes are counted in the epochs they're written in.
+        assert_eq!(
+            stats_of(1).await,
+            counts([2, 0, 0, size(&[1, 4]) + size(&[2, 5])])
+        );
+        assert_eq!(
+            stats_of(2).await,
+            counts([0, 1, 1, size(&[1, 6]) - size(&[1, 4]) - size(&[2, 5])])
+        );
+        // No stats for the epochs without changes.
+        assert_eq!(stats_of(3).await, None);
+    }
+
+    #[tokio::test]
+    async fn test_materialize_executor_pk_conflict() {
+        let memory_state_store = MemoryStateStore::new();
+        let table_id = TableId::new(1);
+        let schema = Schema::new(vec![
+            Field::unnamed(DataType::Int32),
+            Field::unnamed(DataType::Int32),
+        ]);
+        let column_ids = vec![0.into(), 1.into()];
+        let source = MockSource::with_messages(
+            schema,
+            PkIndices::new(),
+            vec![
+                Message::Barrier(Barrier::new_test_barrier(1)),
+                Message::Chunk(StreamChunk::from_pretty(
+                    " i i
+                    + 1 4
+                    + 2 5",
+                )),
+                Message::Barrier(Barrier::new_test_barrier(2)),
+                Message::Chunk(StreamChunk::from_pretty(
+                    " i i
+                    + 1 6
+                    - 2 5
+                    - 3 7",
+                )),
+                Message::Chunk(StreamChunk::from_pretty(
+                    " i i
+                    + 1 6",
+                )),
+                Message::Barrier(Barrier::new_test_barrier(3)),
+            ],
+        );
+        let metrics = Arc::new(StreamingMetrics::unused());
+        let mut materialize_executor = Box::new(
+            MaterializeExecutor::new(
+                Box::new(source),
+                Keyspace::table_root(memory_state_store.clone(), &table_id),
+                vec![OrderPair::new(0, OrderType::Ascending)],
+                column_ids.clone(),
+                1,
+                vec![0],
+            )
+            .with_pk_conflict_handling(1, 16, metrics.clone()),
+        )
+        .execute();
+
+        let expected = vec![
+            None,
+            Some(StreamChunk::from_pretty(
+                " i i
+                + 1 4
+                + 2 5",
+            )),
+            None,
+            // The overwritten row is retracted, and the delete of a missing key is dropped.
+            Some(StreamChunk::from_pretty(
+                " i i
+                U- 1 4
+                U+ 1 6
+                -  2 5",
+            )),
+            // The repeated insert of the same row makes no change, so no chunk is emitted for it.
+            None,
+        ];
+        for expected in expected {
+            match (
+                materialize_executor.next().await.unwrap().unwrap(),
+                expected,
+            ) {
+                (Message::Chunk(chunk), Some(expected)) => assert_eq!(chunk, expected),
+                (Message::Barrier(_), None) => {}
+                _ => unreachable!(),
+            }
+        }
+
+        let hits = metrics
+            .materialize_cache_hit_count
+            .with_label_values(&["1"])
+            .get();
+        let misses = metrics
+            .materialize_cache_miss_count
+            .with_label_values(&["1"])
+            .get();
+        assert_eq!((hits, misses), (3, 3));
+
+        let table = CellBasedTable::new_for_test(
+            Keyspace::table_root(memory_state_store, &table_id),
+            vec![
+                ColumnDesc::unnamed(column_ids[0], DataType::Int32),
+                ColumnDesc::unnamed(column_ids[1], DataType::Int32),
+            ],
+            vec![OrderType::Ascending],
+        );
+        assert_eq!(
+            table
+                .get_row(&Row(vec![Some(1_i32.into())]), u64::MAX)
+                .await
+                .unwrap(),
+            Some(Row(vec![Some(1_i32.into()), Some(6_i32.into())]))
+        );
+    }
 }
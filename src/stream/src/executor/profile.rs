@@ -0,0 +1,177 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Instant;
+
+use futures::{Stream, StreamExt};
+use risingwave_common::catalog::Schema;
+
+use super::{
+    BoxedExecutor, BoxedMessageStream, Executor, Message, MessageStreamItem, PkIndicesRef,
+};
+use crate::task::ExecutorStatsRef;
+
+/// [`ProfileExecutor`] records the rows output by the wrapped executor and the time spent in
+/// polling it into its [`ExecutorStats`](crate::task::ExecutorStats). If the downstream executor
+/// has multiple inputs, the barriers output are also recorded into the stats of the downstream
+/// executor to measure its barrier alignment.
+pub struct ProfileExecutor {
+    input: BoxedExecutor,
+    stats: ExecutorStatsRef,
+    /// Stats of the downstream executor and its number of inputs, if it has multiple ones.
+    downstream: Option<(ExecutorStatsRef, usize)>,
+}
+
+impl ProfileExecutor {
+    pub fn new(
+        input: BoxedExecutor,
+        stats: ExecutorStatsRef,
+        downstream: Option<(ExecutorStatsRef, usize)>,
+    ) -> Self {
+        Self {
+            input,
+            stats,
+            downstream,
+        }
+    }
+}
+
+impl Executor for ProfileExecutor {
+    fn execute(self: Box<Self>) -> BoxedMessageStream {
+        let Self {
+            input,
+            stats,
+            downstream,
+        } = *self;
+        ProfiledStream::new(input.execute(), stats, downstream).boxed()
+    }
+
+    fn execute_with_epoch(self: Box<Self>, epoch: u64) -> BoxedMessageStream {
+        let Self {
+            input,
+            stats,
+            downstream,
+        } = *self;
+        ProfiledStream::new(input.execute_with_epoch(epoch), stats, downstream).boxed()
+    }
+
+    fn schema(&self) -> &Schema {
+        self.input.schema()
+    }
+
+    fn pk_indices(&self) -> PkIndicesRef {
+        self.input.pk_indices()
+    }
+
+    fn identity(&self) -> &str {
+        self.input.identity()
+    }
+}
+
+struct ProfiledStream {
+    inner: BoxedMessageStream,
+    stats: ExecutorStatsRef,
+    downstream: Option<(ExecutorStatsRef, usize)>,
+}
+
+impl ProfiledStream {
+    fn new(
+        inner: BoxedMessageStream,
+        stats: ExecutorStatsRef,
+        downstream: Option<(ExecutorStatsRef, usize)>,
+    ) -> Self {
+        Self {
+            inner,
+            stats,
+            downstream,
+        }
+    }
+}
+
+impl Stream for ProfiledStream {
+    type Item = MessageStreamItem;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let start_time = Instant::now();
+        let poll = self.inner.poll_next_unpin(cx);
+        self.stats.on_busy(start_time.elapsed());
+        match &poll {
+            Poll::Ready(Some(Ok(Message::Chunk(chunk)))) => {
+                self.stats.on_output(chunk.cardinality());
+            }
+            Poll::Ready(Some(Ok(Message::Barrier(barrier)))) => {
+                if let Some((stats, inputs)) = &self.downstream {
+                    stats.on_input_barrier(barrier.epoch.curr, *inputs);
+                }
+            }
+            _ => {}
+        }
+        poll
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use risingwave_common::array::stream_chunk::StreamChunkTestExt;
+    use risingwave_common::array::StreamChunk;
+    use risingwave_common::catalog::Field;
+    use risingwave_common::types::DataType;
+
+    use super::*;
+    use crate::executor::test_utils::MockSource;
+    use crate::executor::Barrier;
+
+    #[tokio::test]
+    async fn test_profile_executor() {
+        let schema = Schema::new(vec![Field::unnamed(DataType::Int64)]);
+        let source = MockSource::with_messages(
+            schema,
+            vec![0],
+            vec![
+                Message::Chunk(StreamChunk::from_pretty(
+                    " I
+                    + 1
+                    + 2",
+                )),
+                Message::Barrier(Barrier::new_test_barrier(1)),
+                Message::Chunk(StreamChunk::from_pretty(
+                    " I
+                    - 1",
+                )),
+            ],
+        );
+        let stats = ExecutorStatsRef::default();
+        let downstream_stats = ExecutorStatsRef::default();
+        let mut stream = ProfileExecutor::new(
+            Box::new(source),
+            stats.clone(),
+            Some((downstream_stats.clone(), 2)),
+        )
+        .boxed()
+        .execute();
+        while let Some(message) = stream.next().await {
+            message.unwrap();
+        }
+        assert_eq!(stats.snapshot().rows, 3);
+
+        // The downstream aligns the barrier once its other input receives it.
+        std::thread::sleep(Duration::from_millis(10));
+        downstream_stats.on_input_barrier(1, 2);
+        assert!(downstream_stats.snapshot().barrier_align_time >= Duration::from_millis(10));
+    }
+}
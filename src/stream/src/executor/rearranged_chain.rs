@@ -213,9 +213,9 @@ impl RearrangedChainExecutor {
 
             // 7. Rearranged task finished.
             // The reason for finish must be that we told it to stop.
-            tracing::trace!(actor = self.actor_id, "rearranged task finished");
+            tracing::trace!(actor_id = self.actor_id, "rearranged task finished");
             if stop_rearrange_tx.is_some() {
-                tracing::error!(actor = self.actor_id, "rearrangement finished passively");
+                tracing::error!(actor_id = self.actor_id, "rearrangement finished passively");
             }
 
             // 8. Consume remainings.
@@ -240,7 +240,10 @@ impl RearrangedChainExecutor {
             let mut remaining_upstream = upstream.try_lock().unwrap();
 
             // Consume remaining upstream.
-            tracing::trace!(actor = self.actor_id, "begin to consume remaining upstream");
+            tracing::trace!(
+                actor_id = self.actor_id,
+                "begin to consume remaining upstream"
+            );
 
             #[for_await]
             for msg in &mut *remaining_upstream {
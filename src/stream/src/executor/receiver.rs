@@ -20,6 +20,7 @@ use super::{ActorContextRef, OperatorInfoStatus};
 use crate::executor::{
     BoxedMessageStream, Executor, ExecutorInfo, Message, PkIndices, PkIndicesRef,
 };
+use crate::task::ChannelStatsRef;
 
 /// `ReceiverExecutor` is used along with a channel. After creating a mpsc channel,
 /// there should be a `ReceiverExecutor` running in the background, so as to push
@@ -27,6 +28,9 @@ use crate::executor::{
 pub struct ReceiverExecutor {
     receiver: Receiver<Message>,
 
+    /// Statistics of the channel.
+    stats: Option<ChannelStatsRef>,
+
     /// Logical Operator Info
     info: ExecutorInfo,
 
@@ -53,6 +57,7 @@ impl ReceiverExecutor {
     ) -> Self {
         Self {
             receiver,
+            stats: None,
             info: ExecutorInfo {
                 schema,
                 pk_indices,
@@ -61,13 +66,22 @@ impl ReceiverExecutor {
             status: OperatorInfoStatus::new(actor_context, receiver_id),
         }
     }
+
+    pub fn with_stats(mut self, stats: ChannelStatsRef) -> Self {
+        self.stats = Some(stats);
+        self
+    }
 }
 
 impl Executor for ReceiverExecutor {
     fn execute(self: Box<Self>) -> BoxedMessageStream {
         let mut status = self.status;
+        let stats = self.stats;
         self.receiver
             .map(move |msg| {
+                if let Some(stats) = &stats {
+                    stats.on_recv();
+                }
                 status.next_message(&msg);
                 Ok(msg)
             })
@@ -15,6 +15,7 @@
 use futures::StreamExt;
 use futures_async_stream::try_stream;
 use risingwave_common::catalog::Schema;
+use risingwave_expr::expr::with_epoch;
 
 use super::error::{StreamExecutorError, StreamExecutorResult};
 use super::{BoxedExecutor, BoxedMessageStream, Executor, Message, PkIndicesRef, StreamChunk};
@@ -70,15 +71,27 @@ where
     async fn execute_inner(self) {
         let input = self.input.execute();
         let mut inner = self.inner;
+        // The epoch of the chunks, i.e. the current epoch of the last barrier. `NOW()` in the
+        // expressions is evaluated at this epoch.
+        let mut epoch = None;
         #[for_await]
         for msg in input {
             let msg = msg?;
             match msg {
-                Message::Chunk(chunk) => match inner.map_filter_chunk(chunk)? {
-                    Some(new_chunk) => yield Message::Chunk(new_chunk),
-                    None => continue,
-                },
-                m => yield m,
+                Message::Chunk(chunk) => {
+                    let new_chunk = match epoch {
+                        Some(epoch) => with_epoch(epoch, || inner.map_filter_chunk(chunk))?,
+                        None => inner.map_filter_chunk(chunk)?,
+                    };
+                    match new_chunk {
+                        Some(new_chunk) => yield Message::Chunk(new_chunk),
+                        None => continue,
+                    }
+                }
+                Message::Barrier(barrier) => {
+                    epoch = Some(barrier.epoch.curr);
+                    yield Message::Barrier(barrier);
+                }
             }
         }
     }
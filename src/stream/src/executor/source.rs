@@ -14,6 +14,7 @@
 
 use std::fmt::{Debug, Formatter};
 use std::sync::Arc;
+use std::time::Duration;
 
 use either::Either;
 use futures::stream::{select_with_strategy, PollNext};
@@ -22,7 +23,8 @@ use futures_async_stream::try_stream;
 use paste::paste;
 use risingwave_common::array::column::Column;
 use risingwave_common::array::{ArrayBuilder, ArrayImpl, I64ArrayBuilder, StreamChunk};
-use risingwave_common::catalog::{ColumnId, Schema, TableId};
+use risingwave_common::catalog::{ColumnDesc, ColumnId, Field, Schema, TableId};
+use risingwave_common::config::DynamicConfigReceiver;
 use risingwave_common::error::{internal_error, Result, RwError, ToRwResult};
 use risingwave_connector::state::SourceStateHandler;
 use risingwave_connector::{
@@ -32,12 +34,13 @@ use risingwave_connector::{
 use risingwave_source::*;
 use risingwave_storage::{Keyspace, StateStore};
 use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver};
-use tokio::sync::Notify;
+use tokio::sync::{watch, Notify};
 use tokio::time::Instant;
 
 use super::error::StreamExecutorError;
 use super::monitor::StreamingMetrics;
 use super::*;
+use crate::task::SourceRowsReporter;
 
 /// [`SourceExecutor`] is a streaming source, from risingwave's batch table, or external systems
 /// such as Kafka.
@@ -70,6 +73,12 @@ pub struct SourceExecutor<S: StateStore> {
 
     /// Expected barrier latency
     expected_barrier_latency_ms: u64,
+
+    /// Reports the rows read from the source to the meta service.
+    rows_reporter: Option<SourceRowsReporter>,
+
+    /// Provides the rate limit of reading the source, which can be changed at runtime.
+    dynamic_config: Option<DynamicConfigReceiver>,
 }
 
 impl<S: StateStore> SourceExecutor<S> {
@@ -105,9 +114,21 @@ impl<S: StateStore> SourceExecutor<S> {
             split_state_store: SourceStateHandler::new(keyspace),
             state_cache: HashMap::new(),
             expected_barrier_latency_ms,
+            rows_reporter: None,
+            dynamic_config: None,
         })
     }
 
+    pub fn with_rows_reporter(mut self, rows_reporter: SourceRowsReporter) -> Self {
+        self.rows_reporter = Some(rows_reporter);
+        self
+    }
+
+    pub fn with_dynamic_config(mut self, dynamic_config: DynamicConfigReceiver) -> Self {
+        self.dynamic_config = Some(dynamic_config);
+        self
+    }
+
     /// Generate a row ID column.
     fn gen_row_id_column(&mut self, len: usize) -> Column {
         let mut builder = I64ArrayBuilder::new(len).unwrap();
@@ -135,6 +156,33 @@ impl<S: StateStore> SourceExecutor<S> {
         }
         chunk
     }
+
+    /// Appends the nullable `columns` added to the source to the output.
+    fn add_columns(&mut self, columns: &[ColumnDesc]) {
+        self.source_desc.add_columns(columns);
+        for column in columns {
+            if !self.column_ids.contains(&column.column_id) {
+                self.column_ids.push(column.column_id);
+                self.schema.fields.push(Field::from(column));
+            }
+        }
+    }
+
+    /// Fills the columns added to the output with nulls, for the chunks read before they're added.
+    fn fill_added_columns(&self, chunk: StreamChunk) -> StreamChunk {
+        if chunk.columns().len() >= self.schema.len() {
+            return chunk;
+        }
+        let (ops, mut columns, bitmap) = chunk.into_inner();
+        for field in &self.schema.fields[columns.len()..] {
+            let mut builder = field.data_type.create_array_builder(ops.len()).unwrap();
+            for _ in 0..ops.len() {
+                builder.append_datum(&None).unwrap();
+            }
+            columns.push(Column::new(Arc::new(builder.finish().unwrap())));
+        }
+        StreamChunk::new(ops, columns, bitmap)
+    }
 }
 
 struct SourceReader {
@@ -145,6 +193,8 @@ struct SourceReader {
     /// Expected barrier latency in ms. If there are no barrier within the expected barrier
     /// latency, source will stall.
     expected_barrier_latency_ms: u64,
+    /// Provides the rate limit of reading the source.
+    dynamic_config: Option<DynamicConfigReceiver>,
 }
 
 impl SourceReader {
@@ -154,6 +204,8 @@ impl SourceReader {
         notifier: Arc<Notify>,
         expected_barrier_latency_ms: u64,
         mut inject_source_rx: UnboundedReceiver<Box<SourceStreamReaderImpl>>,
+        mut paused_rx: watch::Receiver<bool>,
+        dynamic_config: Option<DynamicConfigReceiver>,
     ) {
         'outer: loop {
             let now = Instant::now();
@@ -163,8 +215,27 @@ impl SourceReader {
                 if let Ok(reader) = inject_source_rx.try_recv() {
                     stream_reader = reader;
                 }
+                // Stop reading new data until resumed.
+                while *paused_rx.borrow() {
+                    if paused_rx.changed().await.is_err() {
+                        break 'outer;
+                    }
+                }
                 match stream_reader.next().await {
-                    Ok(chunk) => yield chunk,
+                    Ok(chunk) => {
+                        let cardinality = chunk.chunk.cardinality();
+                        yield chunk;
+                        let rate_limit = dynamic_config
+                            .as_ref()
+                            .map(|config| config.borrow().source_rate_limit_rows_per_sec)
+                            .unwrap_or_default();
+                        if rate_limit > 0 {
+                            tokio::time::sleep(Duration::from_secs_f64(
+                                cardinality as f64 / rate_limit as f64,
+                            ))
+                            .await;
+                        }
+                    }
                     Err(e) => {
                         // TODO: report this error to meta service to mark the actors failed.
                         error!("hang up stream reader due to polling error: {}", e);
@@ -190,8 +261,21 @@ impl SourceReader {
     }
 
     #[try_stream(ok = Message, error = RwError)]
-    async fn barrier_receiver(mut rx: UnboundedReceiver<Barrier>, notifier: Arc<Notify>) {
+    async fn barrier_receiver(
+        mut rx: UnboundedReceiver<Barrier>,
+        notifier: Arc<Notify>,
+        paused_tx: watch::Sender<bool>,
+    ) {
         while let Some(barrier) = rx.recv().await {
+            match barrier.mutation.as_deref() {
+                Some(Mutation::Pause) => {
+                    let _ = paused_tx.send(true);
+                }
+                Some(Mutation::Resume) => {
+                    let _ = paused_tx.send(false);
+                }
+                _ => {}
+            }
             yield Message::Barrier(barrier);
             notifier.notify_one();
         }
@@ -205,13 +289,17 @@ impl SourceReader {
         inject_source: UnboundedReceiver<Box<SourceStreamReaderImpl>>,
     ) -> impl Stream<Item = Either<Result<Message>, Result<StreamChunkWithState>>> {
         let notifier = Arc::new(Notify::new());
+        let (paused_tx, paused_rx) = watch::channel(false);
 
-        let barrier_receiver = Self::barrier_receiver(self.barrier_receiver, notifier.clone());
+        let barrier_receiver =
+            Self::barrier_receiver(self.barrier_receiver, notifier.clone(), paused_tx);
         let stream_reader = Self::stream_reader(
             self.stream_reader,
             notifier,
             self.expected_barrier_latency_ms,
             inject_source,
+            paused_rx,
+            self.dynamic_config,
         );
         select_with_strategy(
             barrier_receiver.map(Either::Left),
@@ -305,6 +393,9 @@ impl<S: StateStore> SourceExecutor<S> {
         let mut barrier_receiver = self.barrier_receiver.take().unwrap();
         let barrier = barrier_receiver.recv().await.unwrap();
 
+        // The offsets are restored from the last checkpoint, since the states written after it
+        // are dropped on recovery. The data after the offsets are replayed, and re-ingested into
+        // the downstream states, which are rolled back to the same checkpoint.
         let epoch = barrier.epoch.prev;
 
         let mut boot_state = self.stream_source_splits.clone();
@@ -331,7 +422,6 @@ impl<S: StateStore> SourceExecutor<S> {
             Some(boot_state)
         };
 
-        // todo: use epoch from msg to restore state from state store
         let stream_reader = self
             .build_stream_source_reader(recover_state)
             .await
@@ -341,6 +431,7 @@ impl<S: StateStore> SourceExecutor<S> {
             stream_reader,
             barrier_receiver,
             expected_barrier_latency_ms: self.expected_barrier_latency_ms,
+            dynamic_config: self.dynamic_config.take(),
         };
         yield Message::Barrier(barrier);
 
@@ -379,7 +470,41 @@ impl<S: StateStore> SourceExecutor<S> {
                                     }
                                 }
                             }
+                            // Keep the latest offsets of the splits, from which the reader may
+                            // be rebuilt.
+                            for split in &mut self.stream_source_splits {
+                                if let Some(latest) = self.state_cache.get(&split.id()) {
+                                    *split = latest.clone();
+                                }
+                            }
+                            if let Some(columns) =
+                                barrier.as_add_source_columns(self.source_id, self.actor_id)
+                            {
+                                self.add_columns(columns);
+                                // The table source appends the added columns to the chunks read,
+                                // while a connector source needs a new reader to parse them.
+                                if matches!(
+                                    self.source_desc.source.as_ref(),
+                                    SourceImpl::Connector(_)
+                                ) {
+                                    let state = if self.stream_source_splits.is_empty() {
+                                        None
+                                    } else {
+                                        Some(self.stream_source_splits.clone())
+                                    };
+                                    let reader = self
+                                        .build_stream_source_reader(state)
+                                        .await
+                                        .map_err(StreamExecutorError::source_error)?;
+                                    inject_source_tx.send(reader).to_rw_result().map_err(|e| {
+                                        StreamExecutorError::channel_closed(e.to_string())
+                                    })?;
+                                }
+                            }
                             self.state_cache.clear();
+                            if let Some(rows_reporter) = &mut self.rows_reporter {
+                                rows_reporter.report();
+                            }
                             yield Message::Barrier(barrier)
                         }
                         _ => unreachable!(),
@@ -412,7 +537,7 @@ impl<S: StateStore> SourceExecutor<S> {
                             .map_err(StreamExecutorError::source_error)?;
                         self.state_cache.extend(state);
                     }
-                    let mut chunk = chunk_with_state.chunk;
+                    let mut chunk = self.fill_added_columns(chunk_with_state.chunk);
 
                     if !matches!(self.source_desc.source.as_ref(), SourceImpl::TableV2(_)) {
                         chunk = self.refill_row_id_column(chunk);
@@ -422,6 +547,9 @@ impl<S: StateStore> SourceExecutor<S> {
                         .source_output_row_count
                         .with_label_values(&[self.source_identify.as_str()])
                         .inc_by(chunk.cardinality() as u64);
+                    if let Some(rows_reporter) = &mut self.rows_reporter {
+                        rows_reporter.inc(chunk.cardinality());
+                    }
                     yield Message::Chunk(chunk);
                 }
             }
@@ -602,6 +730,125 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_table_source_add_columns() -> Result<()> {
+        let table_id = TableId::default();
+        let actor_id = 0x3f3f3f;
+
+        let table_columns = vec![
+            ColumnDesc::unnamed(ColumnId::from(0), DataType::Int64),
+            ColumnDesc::unnamed(ColumnId::from(1), DataType::Int32),
+        ];
+        let source_manager = MemSourceManager::default();
+        source_manager.create_table_source(&table_id, table_columns)?;
+        let source_desc = source_manager.get_source(&table_id)?;
+        let source = source_desc.clone().source;
+
+        let schema = Schema {
+            fields: vec![
+                Field::unnamed(DataType::Int64),
+                Field::unnamed(DataType::Int32),
+            ],
+        };
+        let column_ids = vec![0.into(), 1.into()];
+
+        let (barrier_sender, barrier_receiver) = unbounded_channel();
+        let keyspace = Keyspace::table_root(MemoryStateStore::new(), &TableId::from(0x2333));
+        let executor = SourceExecutor::new(
+            actor_id,
+            table_id,
+            source_desc,
+            keyspace,
+            column_ids,
+            schema,
+            vec![0],
+            barrier_receiver,
+            1,
+            1,
+            "SourceExecutor".to_string(),
+            Arc::new(StreamingMetrics::unused()),
+            vec![],
+            u64::MAX,
+        )
+        .unwrap();
+        let mut executor = Box::new(executor).execute();
+
+        let write_chunk = |chunk: StreamChunk| {
+            let source = source.clone();
+            tokio::spawn(async move {
+                let table_source = source.as_table_v2().unwrap();
+                table_source.blocking_write_chunk(chunk).await.unwrap();
+            });
+        };
+
+        barrier_sender.send(Barrier::new_test_barrier(1)).unwrap();
+        executor
+            .next()
+            .await
+            .unwrap()
+            .unwrap()
+            .into_barrier()
+            .unwrap();
+
+        let added_column = ColumnDesc::unnamed(ColumnId::from(2), DataType::Varchar);
+        barrier_sender
+            .send(
+                Barrier::new_test_barrier(2).with_mutation(Mutation::AddColumns {
+                    source_id: table_id,
+                    table_id: TableId::new(0x2333),
+                    columns: vec![added_column],
+                    actor_ids: [actor_id].into_iter().collect(),
+                }),
+            )
+            .unwrap();
+        executor
+            .next()
+            .await
+            .unwrap()
+            .unwrap()
+            .into_barrier()
+            .unwrap();
+
+        // The chunks written without the added column read nulls in it.
+        write_chunk(StreamChunk::from_pretty(
+            " I i
+            + 0 1",
+        ));
+        assert_eq!(
+            executor
+                .next()
+                .await
+                .unwrap()
+                .unwrap()
+                .into_chunk()
+                .unwrap(),
+            StreamChunk::from_pretty(
+                " I i T
+                + 0 1 .",
+            )
+        );
+
+        write_chunk(StreamChunk::from_pretty(
+            " I i T
+            + 0 2 foo",
+        ));
+        assert_eq!(
+            executor
+                .next()
+                .await
+                .unwrap()
+                .unwrap()
+                .into_chunk()
+                .unwrap(),
+            StreamChunk::from_pretty(
+                " I i T
+                + 0 2 foo",
+            )
+        );
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_table_dropped() -> Result<()> {
         let table_id = TableId::default();
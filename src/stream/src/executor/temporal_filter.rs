@@ -0,0 +1,410 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use futures::{pin_mut, StreamExt};
+use futures_async_stream::try_stream;
+use itertools::Itertools;
+use risingwave_common::array::{Op, Row, StreamChunk};
+use risingwave_common::buffer::Bitmap;
+use risingwave_common::catalog::{ColumnDesc, ColumnId, Schema};
+use risingwave_common::types::{CheckedAdd, IntervalUnit, NaiveDateTimeWrapper, ScalarImpl};
+use risingwave_common::util::hash_util::CRC32FastBuilder;
+use risingwave_common::util::sort_util::OrderType;
+use risingwave_expr::expr::epoch_to_timestamp;
+use risingwave_storage::table::state_table::StateTable;
+use risingwave_storage::{Keyspace, StateStore};
+
+use super::error::{StreamExecutorError, StreamExecutorResult};
+use super::top_n_executor::generate_output;
+use super::{
+    expect_first_barrier, BoxedExecutor, BoxedMessageStream, Executor, ExecutorInfo, Message,
+    PkIndices, PkIndicesRef,
+};
+use crate::task::ActorId;
+
+/// [`TemporalFilterExecutor`] evaluates a temporal filter like `time_col > NOW() - offset`. It
+/// passes through the rows satisfying the filter, and keeps them in the state until they expire as
+/// `NOW()` advances, at which point deletions of them are emitted. So the state of the downstream,
+/// e.g. a materialized view, only keeps the recent rows.
+///
+/// `NOW()` is the time of the epoch being processed, so that all rows of an epoch see the same
+/// `NOW()`. Expired rows are deleted right after the barrier of the epoch in which they expire.
+pub struct TemporalFilterExecutor<S: StateStore> {
+    input: BoxedExecutor,
+
+    info: ExecutorInfo,
+
+    time_col_index: usize,
+
+    /// Rows with `time_col > NOW() + offset` are kept.
+    offset: IntervalUnit,
+
+    /// Whether rows with `time_col = NOW() + offset` are kept as well.
+    inclusive: bool,
+
+    /// Indices of the distribution keys of the input, which decide the vnode of a row.
+    dist_key_indices: Vec<usize>,
+
+    /// Id of the actor if it owns part of the vnodes, which may change on reschedule.
+    actor_id: Option<ActorId>,
+
+    /// Vnodes owned by the actor. Rows of other vnodes in the state are expired by other actors.
+    vnodes: Option<Arc<Bitmap>>,
+
+    /// Rows not expired yet, ordered by `time_col`.
+    state_table: StateTable<S>,
+}
+
+impl<S: StateStore> TemporalFilterExecutor<S> {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        input: BoxedExecutor,
+        pk_indices: PkIndices,
+        executor_id: u64,
+        time_col_index: usize,
+        offset: IntervalUnit,
+        inclusive: bool,
+        dist_key_indices: Vec<usize>,
+        keyspace: Keyspace<S>,
+    ) -> Self {
+        let schema = input.schema().clone();
+        let column_descs = schema
+            .fields()
+            .iter()
+            .enumerate()
+            .map(|(idx, field)| ColumnDesc::unnamed(ColumnId::new(idx as i32), field.data_type()))
+            .collect();
+        let state_table = StateTable::new(
+            keyspace,
+            column_descs,
+            vec![OrderType::Ascending; pk_indices.len() + 1],
+            None,
+            pk_indices.clone(),
+        );
+        Self {
+            input,
+            info: ExecutorInfo {
+                schema,
+                pk_indices,
+                identity: format!("TemporalFilterExecutor {:X}", executor_id),
+            },
+            time_col_index,
+            offset,
+            inclusive,
+            dist_key_indices,
+            actor_id: None,
+            vnodes: None,
+            state_table,
+        }
+    }
+
+    /// Restricts the rows expired by the actor to the `vnodes` it owns.
+    pub fn with_vnodes(mut self, actor_id: ActorId, vnodes: Arc<Bitmap>) -> Self {
+        self.actor_id = Some(actor_id);
+        self.vnodes = Some(vnodes);
+        self
+    }
+
+    fn time(&self, row: &Row) -> Option<NaiveDateTimeWrapper> {
+        match &row[self.time_col_index] {
+            Some(ScalarImpl::NaiveDateTime(time)) => Some(*time),
+            _ => None,
+        }
+    }
+
+    /// The state is keyed by `time_col` followed by the pk of the input.
+    fn state_key(&self, row: &Row) -> Row {
+        Row(std::iter::once(self.time_col_index)
+            .chain(self.info.pk_indices.iter().copied())
+            .map(|idx| row[idx].clone())
+            .collect_vec())
+    }
+
+    /// Returns `NOW() + offset` at `epoch`.
+    fn cutoff(&self, epoch: u64) -> StreamExecutorResult<NaiveDateTimeWrapper> {
+        epoch_to_timestamp(epoch)
+            .checked_add(self.offset)
+            .map_err(StreamExecutorError::eval_error)
+    }
+
+    fn is_valid(&self, time: NaiveDateTimeWrapper, cutoff: NaiveDateTimeWrapper) -> bool {
+        if self.inclusive {
+            time >= cutoff
+        } else {
+            time > cutoff
+        }
+    }
+
+    fn owns(&self, row: &Row) -> StreamExecutorResult<bool> {
+        match &self.vnodes {
+            Some(vnodes) => {
+                let vnode = row
+                    .hash_by_indices(&self.dist_key_indices, &CRC32FastBuilder {})
+                    .map_err(StreamExecutorError::eval_error)?
+                    .to_vnode();
+                vnodes
+                    .is_set(vnode as usize)
+                    .map_err(StreamExecutorError::eval_error)
+            }
+            None => Ok(true),
+        }
+    }
+
+    /// Removes the rows expired at `cutoff` from the state, and returns them as deletions.
+    async fn take_expired(
+        &mut self,
+        cutoff: NaiveDateTimeWrapper,
+        epoch: u64,
+    ) -> StreamExecutorResult<Option<StreamChunk>> {
+        let mut expired = vec![];
+        {
+            let iter = self.state_table.iter(epoch).await?;
+            pin_mut!(iter);
+            while let Some(row) = iter.next().await {
+                let row = row?.into_owned();
+                if self
+                    .time(&row)
+                    .map_or(false, |time| self.is_valid(time, cutoff))
+                {
+                    break;
+                }
+                if self.owns(&row)? {
+                    expired.push(row);
+                }
+            }
+        }
+        if expired.is_empty() {
+            return Ok(None);
+        }
+        for row in &expired {
+            let key = self.state_key(row);
+            self.state_table.delete(&key, row.clone())?;
+        }
+        let ops = vec![Op::Delete; expired.len()];
+        generate_output(expired, ops, &self.info.schema).map(Some)
+    }
+
+    #[try_stream(ok = Message, error = StreamExecutorError)]
+    async fn execute_inner(mut self) {
+        let mut input = self.input.execute();
+
+        let barrier = expect_first_barrier(&mut input).await?;
+        let mut cutoff = self.cutoff(barrier.epoch.curr)?;
+        // The rows kept before recovery may have expired.
+        let expired = self.take_expired(cutoff, barrier.epoch.curr).await?;
+        yield Message::Barrier(barrier);
+        if let Some(expired) = expired {
+            yield Message::Chunk(expired);
+        }
+
+        #[for_await]
+        for msg in input {
+            match msg? {
+                Message::Chunk(chunk) => {
+                    let mut rows = vec![];
+                    let mut ops = vec![];
+                    for (op, row_ref) in chunk.rows() {
+                        let row = row_ref.to_owned_row();
+                        if !self
+                            .time(&row)
+                            .map_or(false, |time| self.is_valid(time, cutoff))
+                        {
+                            continue;
+                        }
+                        let key = self.state_key(&row);
+                        // Updates may be split by the filter, so they're emitted as deletions and
+                        // insertions.
+                        match op {
+                            Op::Insert | Op::UpdateInsert => {
+                                self.state_table.insert(&key, row.clone())?;
+                                ops.push(Op::Insert);
+                            }
+                            Op::Delete | Op::UpdateDelete => {
+                                self.state_table.delete(&key, row.clone())?;
+                                ops.push(Op::Delete);
+                            }
+                        }
+                        rows.push(row);
+                    }
+                    if !rows.is_empty() {
+                        yield Message::Chunk(generate_output(rows, ops, &self.info.schema)?);
+                    }
+                }
+                Message::Barrier(barrier) => {
+                    self.state_table.commit(barrier.epoch.prev).await?;
+                    if let Some(vnodes) = self
+                        .actor_id
+                        .and_then(|actor_id| barrier.as_update_vnode_bitmap(actor_id))
+                    {
+                        self.vnodes = Some(vnodes);
+                    }
+                    cutoff = self.cutoff(barrier.epoch.curr)?;
+                    let expired = self.take_expired(cutoff, barrier.epoch.curr).await?;
+                    yield Message::Barrier(barrier);
+                    if let Some(expired) = expired {
+                        yield Message::Chunk(expired);
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl<S: StateStore> Executor for TemporalFilterExecutor<S> {
+    fn execute(self: Box<Self>) -> BoxedMessageStream {
+        self.execute_inner().boxed()
+    }
+
+    fn schema(&self) -> &Schema {
+        &self.info.schema
+    }
+
+    fn pk_indices(&self) -> PkIndicesRef {
+        &self.info.pk_indices
+    }
+
+    fn identity(&self) -> &str {
+        &self.info.identity
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_common::array::stream_chunk::StreamChunkTestExt;
+    use risingwave_common::catalog::Field;
+    use risingwave_common::types::DataType;
+    use risingwave_common::util::epoch::Epoch as PhysicalEpoch;
+
+    use super::*;
+    use crate::executor::test_utils::{create_in_memory_keyspace, MockSource};
+    use crate::executor::{Barrier, Epoch};
+
+    /// Returns the barrier of the epoch at `secs` after the singularity date.
+    fn barrier_at(secs: u64, prev_secs: u64) -> Message {
+        let epoch = |secs: u64| PhysicalEpoch::from_physical_time(secs * 1000).0;
+        Message::Barrier(Barrier {
+            epoch: Epoch::new(epoch(secs), epoch(prev_secs)),
+            ..Barrier::default()
+        })
+    }
+
+    #[tokio::test]
+    async fn test_temporal_filter() {
+        let schema = Schema::new(vec![
+            Field::unnamed(DataType::Timestamp),
+            Field::unnamed(DataType::Int64),
+        ]);
+        let source = MockSource::with_messages(
+            schema,
+            vec![1],
+            vec![
+                barrier_at(10, 9),
+                Message::Chunk(StreamChunk::from_pretty(
+                    " TS                  I
+                    + 2021-04-01T00:00:05 1
+                    + 2021-04-01T00:00:09 2
+                    + 2021-04-01T00:00:11 3
+                    + .                   4",
+                )),
+                barrier_at(12, 10),
+                Message::Chunk(StreamChunk::from_pretty(
+                    " TS                  I
+                    - 2021-04-01T00:00:09 2
+                    - 2021-04-01T00:00:11 3",
+                )),
+                barrier_at(20, 12),
+            ],
+        );
+        // Keep the rows of the last 2 seconds.
+        let mut temporal_filter = TemporalFilterExecutor::new(
+            Box::new(source),
+            vec![1],
+            1,
+            0,
+            IntervalUnit::from_millis(-2000),
+            false,
+            vec![1],
+            create_in_memory_keyspace(),
+        )
+        .boxed()
+        .execute();
+
+        assert!(temporal_filter
+            .next()
+            .await
+            .unwrap()
+            .unwrap()
+            .as_barrier()
+            .is_some());
+        assert_eq!(
+            temporal_filter
+                .next()
+                .await
+                .unwrap()
+                .unwrap()
+                .into_chunk()
+                .unwrap(),
+            StreamChunk::from_pretty(
+                " TS                  I
+                + 2021-04-01T00:00:09 2
+                + 2021-04-01T00:00:11 3",
+            )
+        );
+
+        // The row at 00:00:09 expires at 00:00:12.
+        assert!(temporal_filter
+            .next()
+            .await
+            .unwrap()
+            .unwrap()
+            .as_barrier()
+            .is_some());
+        assert_eq!(
+            temporal_filter
+                .next()
+                .await
+                .unwrap()
+                .unwrap()
+                .into_chunk()
+                .unwrap(),
+            StreamChunk::from_pretty(
+                " TS                  I
+                - 2021-04-01T00:00:09 2",
+            )
+        );
+        // The deletion of the expired row is dropped.
+        assert_eq!(
+            temporal_filter
+                .next()
+                .await
+                .unwrap()
+                .unwrap()
+                .into_chunk()
+                .unwrap(),
+            StreamChunk::from_pretty(
+                " TS                  I
+                - 2021-04-01T00:00:11 3",
+            )
+        );
+        assert!(temporal_filter
+            .next()
+            .await
+            .unwrap()
+            .unwrap()
+            .as_barrier()
+            .is_some());
+    }
+}
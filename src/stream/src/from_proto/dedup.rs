@@ -0,0 +1,52 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use risingwave_common::catalog::TableId;
+
+use super::*;
+use crate::executor::DedupExecutor;
+
+pub struct DedupExecutorBuilder;
+
+impl ExecutorBuilder for DedupExecutorBuilder {
+    fn new_boxed_executor(
+        mut params: ExecutorParams,
+        node: &StreamNode,
+        store: impl StateStore,
+        _stream: &mut LocalStreamManagerCore,
+    ) -> Result<BoxedExecutor> {
+        let node = try_match_expand!(node.get_node_body().unwrap(), NodeBody::Dedup)?;
+        let keyspace = Keyspace::table_root(store, &TableId::new(node.table_id));
+
+        let mut executor = DedupExecutor::new(
+            params.input.remove(0),
+            params.pk_indices.clone(),
+            params.executor_id,
+            node.dedup_col_indices
+                .iter()
+                .map(|idx| *idx as usize)
+                .collect(),
+            node.ttl_ms,
+            node.dist_key_indices
+                .iter()
+                .map(|idx| *idx as usize)
+                .collect(),
+            keyspace,
+        );
+        if let Some(vnodes) = params.vnodes() {
+            executor = executor.with_vnodes(params.actor_id, vnodes);
+        }
+        Ok(executor.boxed())
+    }
+}
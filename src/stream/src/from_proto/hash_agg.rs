@@ -15,13 +15,16 @@
 //! Global Streaming Hash Aggregators
 
 use std::marker::PhantomData;
+use std::sync::Arc;
 
 use risingwave_common::catalog::TableId;
 use risingwave_common::hash::{calc_hash_key_kind, HashKey, HashKeyDispatcher};
 
 use super::*;
 use crate::executor::aggregation::AggCall;
+use crate::executor::monitor::StreamingMetrics;
 use crate::executor::{HashAggExecutor, PkIndices};
+use crate::task::ActorId;
 
 struct HashAggExecutorDispatcher<S: StateStore>(PhantomData<S>);
 
@@ -32,6 +35,9 @@ struct HashAggExecutorDispatcherArgs<S: StateStore> {
     keyspace: Vec<Keyspace<S>>,
     pk_indices: PkIndices,
     executor_id: u64,
+    actor_id: ActorId,
+    cache_capacity: usize,
+    metrics: Arc<StreamingMetrics>,
 }
 
 impl<S: StateStore> HashKeyDispatcher for HashAggExecutorDispatcher<S> {
@@ -46,7 +52,10 @@ impl<S: StateStore> HashKeyDispatcher for HashAggExecutorDispatcher<S> {
             args.pk_indices,
             args.executor_id,
             args.key_indices,
+            args.actor_id,
         )?
+        .with_cache_capacity(args.cache_capacity)
+        .with_metrics(args.metrics)
         .boxed())
     }
 }
@@ -92,6 +101,9 @@ impl ExecutorBuilder for HashAggExecutorBuilder {
             keyspace,
             pk_indices: params.pk_indices,
             executor_id: params.executor_id,
+            actor_id: params.actor_id,
+            cache_capacity: params.cache_capacity,
+            metrics: params.executor_stats,
         };
         HashAggExecutorDispatcher::dispatch_by_kind(kind, args)
     }
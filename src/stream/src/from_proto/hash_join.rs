@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use std::marker::PhantomData;
+use std::sync::Arc;
 
 use risingwave_common::catalog::TableId;
 use risingwave_common::hash::{calc_hash_key_kind, HashKey, HashKeyDispatcher, HashKeyKind};
@@ -21,7 +22,9 @@ use risingwave_pb::plan_common::JoinType as JoinTypeProto;
 
 use super::*;
 use crate::executor::hash_join::*;
+use crate::executor::monitor::StreamingMetrics;
 use crate::executor::PkIndices;
+use crate::task::ActorId;
 
 pub struct HashJoinExecutorBuilder;
 
@@ -115,6 +118,9 @@ impl ExecutorBuilder for HashJoinExecutorBuilder {
             keyspace_l: Keyspace::table_root(store.clone(), &left_table_id),
             keyspace_r: Keyspace::table_root(store, &right_table_id),
             append_only,
+            cache_capacity: params.cache_capacity,
+            actor_id: params.actor_id,
+            metrics: params.executor_stats,
         };
 
         for_all_join_types! { impl_create_hash_join_executor };
@@ -138,6 +144,9 @@ struct HashJoinExecutorDispatcherArgs<S: StateStore> {
     keyspace_l: Keyspace<S>,
     keyspace_r: Keyspace<S>,
     append_only: bool,
+    cache_capacity: usize,
+    actor_id: ActorId,
+    metrics: Arc<StreamingMetrics>,
 }
 
 impl<S: StateStore, const T: JoinTypePrimitive> HashKeyDispatcher
@@ -147,19 +156,23 @@ impl<S: StateStore, const T: JoinTypePrimitive> HashKeyDispatcher
     type Output = Result<BoxedExecutor>;
 
     fn dispatch<K: HashKey>(args: Self::Input) -> Self::Output {
-        Ok(Box::new(HashJoinExecutor::<K, S, T>::new(
-            args.source_l,
-            args.source_r,
-            args.params_l,
-            args.params_r,
-            args.pk_indices,
-            args.executor_id,
-            args.cond,
-            args.op_info,
-            args.key_indices,
-            args.keyspace_l,
-            args.keyspace_r,
-            args.append_only,
-        )))
+        Ok(Box::new(
+            HashJoinExecutor::<K, S, T>::new(
+                args.source_l,
+                args.source_r,
+                args.params_l,
+                args.params_r,
+                args.pk_indices,
+                args.executor_id,
+                args.cond,
+                args.op_info,
+                args.key_indices,
+                args.keyspace_l,
+                args.keyspace_r,
+                args.append_only,
+            )
+            .with_cache_capacity(args.cache_capacity)
+            .with_metrics(args.actor_id, args.metrics),
+        ))
     }
 }
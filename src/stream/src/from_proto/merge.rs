@@ -32,6 +32,10 @@ impl ExecutorBuilder for MergeExecutorBuilder {
         let fields = node.fields.iter().map(Field::from).collect();
         let schema = Schema::new(fields);
         let mut rxs = stream.get_receive_message(params.actor_id, upstreams)?;
+        let mut stats = upstreams
+            .iter()
+            .map(|up_id| stream.context.channel_stats((*up_id, params.actor_id)))
+            .collect_vec();
         let actor_context = params.actor_context;
 
         if upstreams.len() == 1 {
@@ -42,6 +46,7 @@ impl ExecutorBuilder for MergeExecutorBuilder {
                 actor_context,
                 x_node.operator_id,
             )
+            .with_stats(stats.remove(0))
             .boxed())
         } else {
             Ok(MergeExecutor::new(
@@ -52,6 +57,12 @@ impl ExecutorBuilder for MergeExecutorBuilder {
                 actor_context,
                 x_node.operator_id,
             )
+            .with_stats(stats)
+            .with_executor_stats(
+                stream
+                    .context
+                    .executor_stats(params.actor_id, x_node.operator_id),
+            )
             .boxed())
         }
     }
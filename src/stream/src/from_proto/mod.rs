@@ -16,6 +16,8 @@
 
 mod batch_query;
 mod chain;
+mod dedup;
+mod emit_on_window_close;
 mod filter;
 mod global_simple_agg;
 mod hash_agg;
@@ -28,6 +30,7 @@ mod merge;
 mod mview;
 mod project;
 mod source;
+mod temporal_filter;
 mod top_n;
 mod top_n_appendonly;
 mod union;
@@ -42,6 +45,8 @@ use risingwave_storage::{Keyspace, StateStore};
 
 use self::batch_query::*;
 use self::chain::*;
+use self::dedup::*;
+use self::emit_on_window_close::*;
 use self::filter::*;
 use self::global_simple_agg::*;
 use self::hash_agg::*;
@@ -54,6 +59,7 @@ use self::merge::*;
 use self::mview::*;
 use self::project::*;
 use self::source::*;
+use self::temporal_filter::*;
 use self::top_n::*;
 use self::top_n_appendonly::*;
 use self::union::*;
@@ -118,5 +124,8 @@ pub fn create_executor(
         NodeBody::Lookup => LookupExecutorBuilder,
         NodeBody::Union => UnionExecutorBuilder,
         NodeBody::LookupUnion => LookupUnionExecutorBuilder,
+        NodeBody::EmitOnWindowClose => EmitOnWindowCloseExecutorBuilder,
+        NodeBody::TemporalFilter => TemporalFilterExecutorBuilder,
+        NodeBody::Dedup => DedupExecutorBuilder,
     }
 }
@@ -16,7 +16,8 @@ use risingwave_common::catalog::{ColumnId, TableId};
 use risingwave_common::util::sort_util::OrderPair;
 
 use super::*;
-use crate::executor::MaterializeExecutor;
+use crate::executor::{ChangelogExecutor, MaterializeExecutor};
+use crate::task::ChangelogSchema;
 
 pub struct MaterializeExecutorBuilder;
 
@@ -41,8 +42,7 @@ impl ExecutorBuilder for MaterializeExecutorBuilder {
             .map(|id| ColumnId::from(*id))
             .collect();
 
-        // TODO(Yuanxin): Use `params.vnode_bitmap` to initialize keyspace.
-        let keyspace = Keyspace::table_root(store, &table_id);
+        let keyspace = Keyspace::table_root(store.clone(), &table_id);
 
         let distribution_keys = node
             .distribution_keys
@@ -50,16 +50,43 @@ impl ExecutorBuilder for MaterializeExecutorBuilder {
             .map(|key| *key as usize)
             .collect();
 
-        let executor = MaterializeExecutor::new(
+        let mut executor = MaterializeExecutor::new(
             params.input.remove(0),
             keyspace,
             keys,
             column_ids,
             params.executor_id,
             distribution_keys,
-        );
-
-        Ok(executor.boxed())
+        )
+        .with_table_id(table_id);
+        if let Some(vnodes) = params.vnodes() {
+            executor = executor.with_vnodes(params.actor_id, vnodes);
+        }
+        if node.handle_pk_conflict {
+            executor = executor.with_pk_conflict_handling(
+                params.actor_id,
+                params.cache_capacity,
+                params.executor_stats.clone(),
+            );
+        }
+        if node.collect_stats {
+            let stats_table_id = TableId::new(node.stats_table_id);
+            executor = executor.with_stats(
+                Keyspace::table_root(store, &stats_table_id),
+                params.actor_id,
+            );
+        }
+        let schema = ChangelogSchema {
+            data_types: executor.schema().data_types(),
+            pk_indices: executor.pk_indices().to_vec(),
+        };
+        let handle =
+            params
+                .env
+                .changelog_manager()
+                .register_actor(table_id, params.actor_id, schema);
+
+        Ok(ChangelogExecutor::new(executor.boxed(), handle).boxed())
     }
 }
 
@@ -96,7 +123,7 @@ impl ExecutorBuilder for ArrangeExecutorBuilder {
             .map(|key| *key as usize)
             .collect();
 
-        let executor = MaterializeExecutor::new(
+        let mut executor = MaterializeExecutor::new(
             params.input.remove(0),
             keyspace,
             keys,
@@ -104,6 +131,9 @@ impl ExecutorBuilder for ArrangeExecutorBuilder {
             params.executor_id,
             distribution_keys,
         );
+        if let Some(vnodes) = params.vnodes() {
+            executor = executor.with_vnodes(params.actor_id, vnodes);
+        }
 
         Ok(executor.boxed())
     }
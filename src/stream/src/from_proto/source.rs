@@ -66,21 +66,29 @@ impl ExecutorBuilder for SourceExecutorBuilder {
         let schema = Schema::new(fields);
         let keyspace = Keyspace::table_root(store, &source_id);
 
-        Ok(Box::new(SourceExecutor::new(
-            params.actor_id,
-            source_id,
-            source_desc,
-            keyspace,
-            column_ids,
-            schema,
-            params.pk_indices,
-            barrier_receiver,
-            params.executor_id,
-            params.operator_id,
-            params.op_info,
-            params.executor_stats,
-            stream_source_splits,
-            stream.config.checkpoint_interval_ms as u64,
-        )?))
+        let rows_reporter = stream
+            .context
+            .register_source_rows_reporter(source_id.table_id());
+
+        Ok(Box::new(
+            SourceExecutor::new(
+                params.actor_id,
+                source_id,
+                source_desc,
+                keyspace,
+                column_ids,
+                schema,
+                params.pk_indices,
+                barrier_receiver,
+                params.executor_id,
+                params.operator_id,
+                params.op_info,
+                params.executor_stats,
+                stream_source_splits,
+                stream.config.checkpoint_interval_ms as u64,
+            )?
+            .with_rows_reporter(rows_reporter)
+            .with_dynamic_config(params.env.dynamic_config()),
+        ))
     }
 }
@@ -0,0 +1,178 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::future::Future;
+
+use risingwave_common::config::StreamingConfig;
+use tokio::task::JoinHandle;
+
+#[cfg(not(madsim))]
+use super::cpu_affinity;
+
+/// A dedicated tokio runtime running on its own thread. The runtime is shut down on the thread
+/// once the shard is dropped. The worker threads are pinned to `cores` if given.
+#[cfg(not(madsim))]
+struct RuntimeShard {
+    handle: tokio::runtime::Handle,
+    _shutdown_tx: tokio::sync::oneshot::Sender<()>,
+    _runtime_thread: std::thread::JoinHandle<()>,
+}
+
+#[cfg(not(madsim))]
+impl RuntimeShard {
+    fn new(name: &str, index: usize, worker_threads_num: usize, cores: Option<Vec<usize>>) -> Self {
+        let (handle_tx, handle_rx) = std::sync::mpsc::channel();
+        let (shutdown_tx, shutdown_rx) = tokio::sync::oneshot::channel::<()>();
+        let thread_name = format!("risingwave-{}-{}", name, index);
+        let runtime_thread = std::thread::Builder::new()
+            .name(format!("risingwave-{}-runtime-{}", name, index))
+            .spawn(move || {
+                let mut builder = tokio::runtime::Builder::new_multi_thread();
+                if let Some(cores) = cores {
+                    builder.on_thread_start(move || cpu_affinity::pin_current_thread(&cores));
+                }
+                let runtime = builder
+                    .worker_threads(worker_threads_num)
+                    .thread_name(thread_name)
+                    .enable_all()
+                    .build()
+                    .unwrap();
+                handle_tx.send(runtime.handle().clone()).unwrap();
+                runtime.block_on(async {
+                    let _ = shutdown_rx.await;
+                });
+            })
+            .unwrap();
+        Self {
+            handle: handle_rx.recv().unwrap(),
+            _shutdown_tx: shutdown_tx,
+            _runtime_thread: runtime_thread,
+        }
+    }
+}
+
+/// Spawns stream actors either on the shared runtime of the compute node, or on dedicated
+/// runtimes as configured by [`StreamingConfig::actor_runtime_worker_threads_number`]. With
+/// multiple dedicated runtimes, the actors of a fragment always run on the same runtime.
+///
+/// The dispatchers of an actor, which serialize and send its output to the downstream actors, run
+/// within the actor, so they stay on the cores of the runtime when
+/// [`StreamingConfig::actor_runtime_pin_cores`] is set.
+pub struct ActorRuntime {
+    #[cfg(not(madsim))]
+    shards: Vec<RuntimeShard>,
+}
+
+impl ActorRuntime {
+    pub fn new(config: &StreamingConfig) -> Self {
+        Self::build(
+            "actor",
+            config.actor_runtime_worker_threads_number,
+            config.actor_runtime_shards,
+            config.actor_runtime_pin_cores,
+        )
+    }
+
+    /// Creates `shards` dedicated runtimes with `worker_threads_number` worker threads each, whose
+    /// threads are named after `name`. Actors run on the shared runtime if `worker_threads_number`
+    /// is 0.
+    pub fn with_worker_threads(name: &str, worker_threads_number: usize, shards: usize) -> Self {
+        Self::build(name, worker_threads_number, shards, false)
+    }
+
+    #[cfg(not(madsim))]
+    fn build(name: &str, worker_threads_number: usize, shards: usize, pin_cores: bool) -> Self {
+        if worker_threads_number == 0 {
+            return Self { shards: vec![] };
+        }
+        let shards = shards.max(1);
+        let core_sets = if pin_cores {
+            let nodes = cpu_affinity::detect_numa_nodes();
+            let core_sets = cpu_affinity::assign_core_sets(&nodes, shards);
+            tracing::info!(
+                "pin {} actor runtimes to cores {:?} of NUMA nodes {:?}",
+                name,
+                core_sets,
+                nodes
+            );
+            core_sets.into_iter().map(Some).collect()
+        } else {
+            vec![None; shards]
+        };
+        let shards = core_sets
+            .into_iter()
+            .enumerate()
+            .map(|(index, cores)| RuntimeShard::new(name, index, worker_threads_number, cores))
+            .collect();
+        Self { shards }
+    }
+
+    // FIXME: simulation doesn't support new thread or tokio runtime.
+    //        this is a workaround to make it compile.
+    #[cfg(madsim)]
+    fn build(_name: &str, _worker_threads_number: usize, _shards: usize, _pin_cores: bool) -> Self {
+        Self {}
+    }
+
+    /// Spawns the future of an actor in `fragment_id`.
+    pub fn spawn<F>(&self, fragment_id: u32, future: F) -> JoinHandle<F::Output>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        #[cfg(not(madsim))]
+        if !self.shards.is_empty() {
+            let shard = &self.shards[fragment_id as usize % self.shards.len()];
+            return shard.handle.spawn(future);
+        }
+        let _ = fragment_id;
+        tokio::spawn(future)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_actor_runtime() {
+        let config = StreamingConfig {
+            actor_runtime_worker_threads_number: 1,
+            actor_runtime_shards: 2,
+            ..Default::default()
+        };
+        let runtime = ActorRuntime::new(&config);
+        let thread_name = || std::thread::current().name().unwrap().to_string();
+
+        // Actors of the same fragment run on the same runtime.
+        let names = futures::future::try_join_all(
+            [1, 3, 2]
+                .into_iter()
+                .map(|fragment_id| runtime.spawn(fragment_id, async move { thread_name() })),
+        )
+        .await
+        .unwrap();
+        assert_eq!(names[0], "risingwave-actor-1");
+        assert_eq!(names[1], "risingwave-actor-1");
+        assert_eq!(names[2], "risingwave-actor-0");
+
+        // Actors run on the shared runtime by default.
+        let runtime = ActorRuntime::new(&StreamingConfig::default());
+        let name = runtime
+            .spawn(1, async move { thread_name() })
+            .await
+            .unwrap();
+        assert!(!name.starts_with("risingwave-actor"));
+    }
+}
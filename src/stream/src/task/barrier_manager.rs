@@ -15,7 +15,9 @@
 use madsim::collections::{HashMap, HashSet};
 use risingwave_common::error::Result;
 use risingwave_pb::hummock::SstableInfo;
-use risingwave_pb::stream_service::inject_barrier_response::CreateMviewProgress as ProstCreateMviewProgress;
+use risingwave_pb::stream_service::inject_barrier_response::{
+    CreateMviewProgress as ProstCreateMviewProgress, SourceRows as ProstSourceRows,
+};
 use tokio::sync::mpsc::UnboundedSender;
 use tokio::sync::oneshot;
 
@@ -25,10 +27,12 @@ use crate::task::ActorId;
 
 mod managed_state;
 mod progress;
+mod source_rows;
 #[cfg(test)]
 mod tests;
 
 pub use progress::CreateMviewProgress;
+pub use source_rows::SourceRowsReporter;
 
 /// If enabled, all actors will be grouped in the same tracing span within one epoch.
 /// Note that this option will significantly increase the overhead of tracing.
@@ -40,6 +44,11 @@ pub struct CollectResult {
     pub create_mview_progress: Vec<ProstCreateMviewProgress>,
 
     pub synced_sstables: Vec<SstableInfo>,
+
+    pub source_rows: Vec<ProstSourceRows>,
+
+    /// The actor that collected the barrier last.
+    pub last_collected_actor_id: Option<ActorId>,
 }
 
 enum BarrierState {
@@ -116,11 +125,12 @@ impl LocalBarrierManager {
             }
         };
         let to_collect: HashSet<ActorId> = actor_ids_to_collect.into_iter().collect();
-        trace!(
-            "send barrier {:?}, senders = {:?}, actor_ids_to_collect = {:?}",
-            barrier,
-            to_send,
-            to_collect
+        tracing::trace!(
+            epoch = barrier.epoch.curr,
+            ?barrier,
+            actor_ids_to_send = ?to_send,
+            actor_ids_to_collect = ?to_collect,
+            "send barrier"
         );
 
         let rx = match &mut self.state {
@@ -147,7 +157,7 @@ impl LocalBarrierManager {
 
         // Actors to stop should still accept this barrier, but won't get sent to in next times.
         if let Some(Mutation::Stop(actors)) = barrier.mutation.as_deref() {
-            trace!("remove actors {:?} from senders", actors);
+            tracing::trace!(actor_ids = ?actors, "remove actors from senders");
             for actor in actors {
                 self.senders.remove(actor);
             }
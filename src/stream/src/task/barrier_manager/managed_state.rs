@@ -16,7 +16,7 @@ use std::collections::HashMap;
 use std::iter::once;
 
 use madsim::collections::HashSet;
-use risingwave_pb::stream_service::inject_barrier_response::CreateMviewProgress;
+use risingwave_pb::stream_service::inject_barrier_response::{CreateMviewProgress, SourceRows};
 use tokio::sync::oneshot;
 
 use super::progress::ChainState;
@@ -60,6 +60,12 @@ pub(super) struct ManagedBarrierState {
     inner: ManagedBarrierStateInner,
 
     pub create_mview_progress: HashMap<ActorId, ChainState>,
+
+    /// Rows read from each source since the last barrier.
+    pub source_rows: HashMap<u32, u64>,
+
+    /// The actor that collected the current barrier last so far.
+    last_collected_actor_id: Option<ActorId>,
 }
 
 impl ManagedBarrierState {
@@ -71,6 +77,8 @@ impl ManagedBarrierState {
                 last_epoch: None,
             },
             create_mview_progress: Default::default(),
+            source_rows: Default::default(),
+            last_collected_actor_id: None,
         }
     }
 
@@ -111,6 +119,10 @@ impl ManagedBarrierState {
                     },
                 })
                 .collect();
+            let source_rows = std::mem::take(&mut self.source_rows)
+                .into_iter()
+                .map(|(source_id, rows)| SourceRows { source_id, rows })
+                .collect();
 
             match state {
                 ManagedBarrierStateInner::Issued {
@@ -120,9 +132,11 @@ impl ManagedBarrierState {
                     let result = CollectResult {
                         create_mview_progress,
                         synced_sstables: vec![],
+                        source_rows,
+                        last_collected_actor_id: self.last_collected_actor_id.take(),
                     };
                     if collect_notifier.send(result).is_err() {
-                        warn!("failed to notify barrier collection with epoch {}", epoch)
+                        tracing::warn!(epoch, "failed to notify barrier collection")
                     }
                 }
 
@@ -135,11 +149,12 @@ impl ManagedBarrierState {
     pub(super) fn collect(&mut self, actor_id: ActorId, barrier: &Barrier) {
         tracing::trace!(
             target: "events::stream::barrier::collect_barrier",
-            "collect_barrier: epoch = {}, actor_id = {}, state = {:#?}",
-            barrier.epoch.curr,
+            epoch = barrier.epoch.curr,
             actor_id,
-            self
+            state = ?self,
+            "collect barrier"
         );
+        self.last_collected_actor_id = Some(actor_id);
 
         match self.inner_mut() {
             ManagedBarrierStateInner::Pending { last_epoch } => {
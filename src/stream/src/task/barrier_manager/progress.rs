@@ -107,7 +107,7 @@ impl SharedContext {
     /// When all chain executors of the creating mview finish, the creation progress will be done at
     /// frontend and the mview will be exposed to the user.
     pub fn register_create_mview_progress(&self, chain_actor_id: ActorId) -> CreateMviewProgress {
-        trace!("register create mview progress: {}", chain_actor_id);
+        tracing::trace!(actor_id = chain_actor_id, "register create mview progress");
         CreateMviewProgress::new(self.barrier_manager.clone(), chain_actor_id)
     }
 }
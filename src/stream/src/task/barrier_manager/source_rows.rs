@@ -0,0 +1,77 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use super::{BarrierState, LocalBarrierManager};
+use crate::task::SharedContext;
+
+impl LocalBarrierManager {
+    fn report_source_rows(&mut self, source_id: u32, rows: u64) {
+        match &mut self.state {
+            #[cfg(test)]
+            BarrierState::Local => {}
+
+            BarrierState::Managed(managed_state) => {
+                *managed_state.source_rows.entry(source_id).or_default() += rows;
+            }
+        }
+    }
+}
+
+/// Counts the rows read by a source executor, which are reported to the meta service along with
+/// each barrier to track the throughput of the source.
+pub struct SourceRowsReporter {
+    barrier_manager: Arc<parking_lot::Mutex<LocalBarrierManager>>,
+
+    source_id: u32,
+
+    /// Rows read since the last report.
+    rows: u64,
+}
+
+impl SourceRowsReporter {
+    pub fn new(
+        barrier_manager: Arc<parking_lot::Mutex<LocalBarrierManager>>,
+        source_id: u32,
+    ) -> Self {
+        Self {
+            barrier_manager,
+            source_id,
+            rows: 0,
+        }
+    }
+
+    pub fn inc(&mut self, rows: usize) {
+        self.rows += rows as u64;
+    }
+
+    /// Reports the rows read since the last report. Should be called before the barrier is yielded
+    /// so that the rows are collected in the epoch of the barrier.
+    pub fn report(&mut self) {
+        if self.rows == 0 {
+            return;
+        }
+        self.barrier_manager
+            .lock()
+            .report_source_rows(self.source_id, std::mem::take(&mut self.rows));
+    }
+}
+
+impl SharedContext {
+    /// Create a reporter for the rows read by the source executor of `source_id`.
+    pub fn register_source_rows_reporter(&self, source_id: u32) -> SourceRowsReporter {
+        SourceRowsReporter::new(self.barrier_manager.clone(), source_id)
+    }
+}
@@ -0,0 +1,276 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+
+use risingwave_common::array::{Op, Row, StreamChunk};
+use risingwave_common::catalog::TableId;
+use risingwave_common::error::ErrorCode::InternalError;
+use risingwave_common::error::{Result, RwError};
+use risingwave_pb::stream_service::{ChangelogBatch, ChangelogFormat};
+
+use super::ChangelogSchema;
+
+/// A change with the primary key of the row.
+type Change = (Op, Row, Row);
+
+/// Converts the changelog batches of a materialized view for consumers that can't handle
+/// retractions.
+#[derive(Debug)]
+pub struct ChangelogFormatter {
+    table_id: TableId,
+    format: ChangelogFormat,
+    dedup_by_pk: bool,
+    schema: ChangelogSchema,
+}
+
+impl ChangelogFormatter {
+    pub fn new(
+        table_id: TableId,
+        format: ChangelogFormat,
+        dedup_by_pk: bool,
+        schema: ChangelogSchema,
+    ) -> Self {
+        Self {
+            table_id,
+            format,
+            dedup_by_pk,
+            schema,
+        }
+    }
+
+    /// Converts the changes of an epoch into a single chunk. Fails if the changes can't be
+    /// represented in the format, i.e., an append-only changelog has retractions.
+    pub fn format(&self, batch: &ChangelogBatch) -> Result<ChangelogBatch> {
+        let mut changes = vec![];
+        for chunk in &batch.chunks {
+            let chunk = StreamChunk::from_protobuf(chunk)?;
+            for (op, row) in chunk.rows() {
+                let pk = row.row_by_indices(&self.schema.pk_indices);
+                changes.push((op, row.to_owned_row(), pk));
+            }
+        }
+        if self.dedup_by_pk {
+            changes = dedup_by_pk(changes);
+        }
+        let rows = match self.format {
+            ChangelogFormat::Retract => changes
+                .into_iter()
+                .map(|(op, row, _)| (op, row))
+                .collect::<Vec<_>>(),
+            ChangelogFormat::Upsert => to_upsert(changes),
+            ChangelogFormat::AppendOnly => {
+                if changes
+                    .iter()
+                    .any(|(op, _, _)| matches!(op, Op::Delete | Op::UpdateDelete))
+                {
+                    return Err(RwError::from(InternalError(format!(
+                        "changelog of table {} has deletions or updates in epoch {}, which are not \
+                         supported in APPEND_ONLY format",
+                        self.table_id, batch.epoch
+                    ))));
+                }
+                changes
+                    .into_iter()
+                    .map(|(_, row, _)| (Op::Insert, row))
+                    .collect()
+            }
+        };
+        let chunks = if rows.is_empty() {
+            vec![]
+        } else {
+            vec![StreamChunk::from_rows(&rows, &self.schema.data_types)?.to_protobuf()]
+        };
+        Ok(ChangelogBatch {
+            epoch: batch.epoch,
+            chunks,
+        })
+    }
+}
+
+/// Changes of a primary key in an epoch.
+#[derive(Default)]
+struct KeyChanges {
+    /// The row before the epoch, if it's deleted.
+    old: Option<Row>,
+    /// The row after the epoch, if it's inserted.
+    new: Option<Row>,
+}
+
+/// Merges the changes of each primary key into at most a deletion and an insertion, in the order
+/// that the keys first appear.
+fn dedup_by_pk(changes: Vec<Change>) -> Vec<Change> {
+    let mut keys: HashMap<Row, usize> = HashMap::new();
+    let mut merged: Vec<(Row, KeyChanges)> = vec![];
+    for (op, row, pk) in changes {
+        let idx = *keys.entry(pk.clone()).or_insert_with(|| {
+            merged.push((pk, KeyChanges::default()));
+            merged.len() - 1
+        });
+        let key_changes = &mut merged[idx].1;
+        match op {
+            Op::Insert | Op::UpdateInsert => key_changes.new = Some(row),
+            Op::Delete | Op::UpdateDelete => {
+                if key_changes.new.take().is_none() {
+                    key_changes.old = Some(row);
+                }
+            }
+        }
+    }
+
+    let mut changes = vec![];
+    for (pk, key_changes) in merged {
+        match (key_changes.old, key_changes.new) {
+            (Some(old), Some(new)) => {
+                if old != new {
+                    changes.push((Op::UpdateDelete, old, pk.clone()));
+                    changes.push((Op::UpdateInsert, new, pk));
+                }
+            }
+            (Some(old), None) => changes.push((Op::Delete, old, pk)),
+            (None, Some(new)) => changes.push((Op::Insert, new, pk)),
+            (None, None) => {}
+        }
+    }
+    changes
+}
+
+/// Drops the deletions of updates that keep the primary key, since the insertions overwrite the
+/// old rows.
+fn to_upsert(changes: Vec<Change>) -> Vec<(Op, Row)> {
+    let mut rows = Vec::with_capacity(changes.len());
+    let mut changes = changes.into_iter().peekable();
+    while let Some((op, row, pk)) = changes.next() {
+        match op {
+            Op::Insert | Op::UpdateInsert => rows.push((Op::Insert, row)),
+            Op::Delete => rows.push((Op::Delete, row)),
+            Op::UpdateDelete => {
+                let overwritten = matches!(
+                    changes.peek(),
+                    Some((Op::UpdateInsert, _, next_pk)) if *next_pk == pk
+                );
+                if !overwritten {
+                    rows.push((Op::Delete, row));
+                }
+            }
+        }
+    }
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_common::test_prelude::StreamChunkTestExt;
+    use risingwave_common::types::DataType;
+
+    use super::*;
+
+    fn formatter(format: ChangelogFormat, dedup_by_pk: bool) -> ChangelogFormatter {
+        ChangelogFormatter::new(
+            TableId::new(1),
+            format,
+            dedup_by_pk,
+            ChangelogSchema {
+                data_types: vec![DataType::Int64, DataType::Int64],
+                pk_indices: vec![0],
+            },
+        )
+    }
+
+    fn batch(chunks: &[&str]) -> ChangelogBatch {
+        ChangelogBatch {
+            epoch: 1,
+            chunks: chunks
+                .iter()
+                .map(|chunk| StreamChunk::from_pretty(chunk).to_protobuf())
+                .collect(),
+        }
+    }
+
+    fn assert_formatted(formatter: &ChangelogFormatter, input: &ChangelogBatch, expected: &str) {
+        let output = formatter.format(input).unwrap();
+        assert_eq!(output.epoch, input.epoch);
+        assert_eq!(output.chunks.len(), 1);
+        assert_eq!(
+            StreamChunk::from_protobuf(&output.chunks[0]).unwrap(),
+            StreamChunk::from_pretty(expected)
+        );
+    }
+
+    #[test]
+    fn test_changelog_formatter() {
+        let input = batch(&[
+            " I I
+            + 1 1
+            + 2 2
+            - 3 3",
+            " I I
+            U- 1 1
+            U+ 1 4
+            U- 4 4
+            U+ 5 5
+            - 2 2",
+        ]);
+
+        assert_formatted(
+            &formatter(ChangelogFormat::Upsert, false),
+            &input,
+            " I I
+            + 1 1
+            + 2 2
+            - 3 3
+            + 1 4
+            - 4 4
+            + 5 5
+            - 2 2",
+        );
+        assert_formatted(
+            &formatter(ChangelogFormat::Retract, true),
+            &input,
+            " I I
+            + 1 4
+            - 3 3
+            - 4 4
+            + 5 5",
+        );
+        assert_formatted(
+            &formatter(ChangelogFormat::Upsert, true),
+            &input,
+            " I I
+            + 1 4
+            - 3 3
+            - 4 4
+            + 5 5",
+        );
+        assert!(formatter(ChangelogFormat::AppendOnly, true)
+            .format(&input)
+            .is_err());
+
+        // Deletions of rows inserted in the same epoch are eliminated by dedup.
+        let input = batch(&[" I I
+            + 1 1
+            + 2 2
+            - 1 1
+            + 2 3"]);
+        assert!(formatter(ChangelogFormat::AppendOnly, false)
+            .format(&input)
+            .is_err());
+        assert_formatted(
+            &formatter(ChangelogFormat::AppendOnly, true),
+            &input,
+            " I I
+            + 2 3",
+        );
+    }
+}
@@ -0,0 +1,364 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Changelog of materialized views, which can be subscribed from other clusters or external
+//! consumers through the changelog service of compute nodes.
+//!
+//! The changes of a materialized view are collected from all its actors on this compute node, and
+//! emitted as a batch for each epoch once all the actors have passed the barrier of the epoch.
+//! Changes are only collected after the materialized view is subscribed for the first time, and
+//! the recent epochs are retained so that subscribers can resume from the last epoch they
+//! received.
+//!
+//! Note that batches are emitted before the epoch is committed, so the changes of an epoch that
+//! fails to commit are emitted again with a new epoch after recovery.
+
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+use risingwave_common::array::StreamChunk;
+use risingwave_common::catalog::TableId;
+use risingwave_common::error::ErrorCode::InternalError;
+use risingwave_common::error::{Result, RwError};
+use risingwave_common::types::DataType;
+use risingwave_pb::stream_service::ChangelogBatch;
+use tokio::sync::mpsc::error::TrySendError;
+use tokio::sync::mpsc::{channel, Receiver, Sender};
+
+use super::ActorId;
+
+/// Number of batches buffered for a subscriber besides the retained ones. A subscriber that falls
+/// further behind is disconnected, and should resume from the last epoch it received.
+const SUBSCRIBER_BUFFER_SIZE: usize = 16;
+
+pub type ChangelogManagerRef = Arc<ChangelogManager>;
+
+/// Changes of an epoch reported by some of the actors.
+#[derive(Debug)]
+struct PendingEpoch {
+    /// Actors that haven't reported.
+    waiting_actors: HashSet<ActorId>,
+    chunks: Vec<StreamChunk>,
+    /// Whether any actor missed some changes of the epoch.
+    incomplete: bool,
+}
+
+/// Schema of the changes of a materialized view.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChangelogSchema {
+    pub data_types: Vec<DataType>,
+    pub pk_indices: Vec<usize>,
+}
+
+#[derive(Debug, Default)]
+struct TableChangelog {
+    /// Set once the table is subscribed, which makes its actors collect changes.
+    tracked: Arc<AtomicBool>,
+    actors: HashSet<ActorId>,
+    /// `None` if no actor has ever been registered.
+    schema: Option<ChangelogSchema>,
+    pending: BTreeMap<u64, PendingEpoch>,
+    retained: VecDeque<Arc<ChangelogBatch>>,
+    /// Changes of epochs after this one are available. `None` before any epoch is collected.
+    available_after: Option<u64>,
+    subscribers: Vec<Subscriber>,
+}
+
+#[derive(Debug)]
+struct Subscriber {
+    sender: Sender<Arc<ChangelogBatch>>,
+    /// Whether the subscriber relies on the continuity of epochs, i.e., it resumes from an epoch
+    /// or has received some batches.
+    continuous: bool,
+}
+
+impl TableChangelog {
+    /// Emits the batches of pending epochs reported by all actors, in the order of epochs.
+    fn emit_ready_epochs(&mut self, retained_epochs: usize) {
+        while let Some(entry) = self.pending.first_entry() {
+            if !entry.get().waiting_actors.is_empty() {
+                break;
+            }
+            let (epoch, pending) = entry.remove_entry();
+            if pending.incomplete {
+                self.truncate(epoch);
+                continue;
+            }
+            let batch = Arc::new(ChangelogBatch {
+                epoch,
+                chunks: pending
+                    .chunks
+                    .iter()
+                    .map(|chunk| chunk.to_protobuf())
+                    .collect(),
+            });
+            self.subscribers.retain_mut(|subscriber| {
+                match subscriber.sender.try_send(batch.clone()) {
+                    Ok(()) => {
+                        subscriber.continuous = true;
+                        true
+                    }
+                    Err(TrySendError::Full(_)) => {
+                        tracing::warn!(epoch, "changelog subscriber falls behind");
+                        false
+                    }
+                    Err(TrySendError::Closed(_)) => false,
+                }
+            });
+            self.retained.push_back(batch);
+            while self.retained.len() > retained_epochs {
+                let evicted = self.retained.pop_front().unwrap();
+                self.available_after = Some(evicted.epoch);
+            }
+            if self.available_after.is_none() {
+                self.available_after = Some(0);
+            }
+        }
+    }
+
+    /// Drops the changes of epochs up to `epoch`, which leaves a gap in the changelog. Subscribers
+    /// that rely on the continuity are disconnected.
+    fn truncate(&mut self, epoch: u64) {
+        self.retained.clear();
+        self.available_after = Some(epoch);
+        self.subscribers.retain(|subscriber| !subscriber.continuous);
+    }
+}
+
+/// `ChangelogManager` collects the changes of subscribed materialized views on this compute node.
+#[derive(Debug)]
+pub struct ChangelogManager {
+    retained_epochs: usize,
+    tables: Mutex<HashMap<TableId, TableChangelog>>,
+}
+
+impl ChangelogManager {
+    pub fn new(retained_epochs: usize) -> Self {
+        Self {
+            retained_epochs,
+            tables: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Registers an actor of the materialized view `table_id`, which reports its changes through
+    /// the returned handle until it's dropped.
+    pub fn register_actor(
+        self: &Arc<Self>,
+        table_id: TableId,
+        actor_id: ActorId,
+        schema: ChangelogSchema,
+    ) -> ChangelogActorHandle {
+        let mut tables = self.tables.lock();
+        let table = tables.entry(table_id).or_default();
+        table.actors.insert(actor_id);
+        table.schema = Some(schema);
+        ChangelogActorHandle {
+            manager: self.clone(),
+            table_id,
+            actor_id,
+            tracked: table.tracked.clone(),
+        }
+    }
+
+    fn unregister_actor(&self, table_id: TableId, actor_id: ActorId) {
+        let mut tables = self.tables.lock();
+        let table = tables.get_mut(&table_id).unwrap();
+        table.actors.remove(&actor_id);
+        // Changes of the actor in the pending epochs are lost.
+        for pending in table.pending.values_mut() {
+            if pending.waiting_actors.remove(&actor_id) {
+                pending.incomplete = true;
+            }
+        }
+        table.emit_ready_epochs(self.retained_epochs);
+        if table.actors.is_empty() && !table.tracked.load(Ordering::Relaxed) {
+            tables.remove(&table_id);
+        }
+    }
+
+    /// Reports the changes of an actor in `epoch`, or `None` if some changes are not collected.
+    fn report(
+        &self,
+        table_id: TableId,
+        actor_id: ActorId,
+        epoch: u64,
+        chunks: Option<Vec<StreamChunk>>,
+    ) {
+        let mut tables = self.tables.lock();
+        let table = tables.get_mut(&table_id).unwrap();
+        // The actor will never report the earlier epochs, e.g., if it's created after they began.
+        for (_, pending) in table.pending.range_mut(..epoch) {
+            if pending.waiting_actors.remove(&actor_id) {
+                pending.incomplete = true;
+            }
+        }
+        let actors = &table.actors;
+        let pending = table.pending.entry(epoch).or_insert_with(|| PendingEpoch {
+            waiting_actors: actors.clone(),
+            chunks: vec![],
+            incomplete: false,
+        });
+        if !pending.waiting_actors.remove(&actor_id) {
+            // The actor is registered after other actors reported the epoch.
+            return;
+        }
+        match chunks {
+            Some(chunks) => pending.chunks.extend(chunks),
+            None => pending.incomplete = true,
+        }
+        table.emit_ready_epochs(self.retained_epochs);
+    }
+
+    /// Returns the schema of the changes of materialized view `table_id`, or `None` if it has no
+    /// actors on this compute node.
+    pub fn schema(&self, table_id: TableId) -> Option<ChangelogSchema> {
+        self.tables
+            .lock()
+            .get(&table_id)
+            .and_then(|table| table.schema.clone())
+    }
+
+    /// Subscribes to the changelog of materialized view `table_id` after `start_after_epoch`, or
+    /// from the oldest retained epoch if it's 0. Fails if some epochs after `start_after_epoch`
+    /// are no longer retained.
+    pub fn subscribe(
+        &self,
+        table_id: TableId,
+        start_after_epoch: u64,
+    ) -> Result<Receiver<Arc<ChangelogBatch>>> {
+        let mut tables = self.tables.lock();
+        let table = tables.entry(table_id).or_default();
+        table.tracked.store(true, Ordering::Relaxed);
+        if start_after_epoch != 0
+            && !table
+                .available_after
+                .map_or(false, |epoch| start_after_epoch >= epoch)
+        {
+            return Err(RwError::from(InternalError(format!(
+                "changelog of table {} after epoch {} is no longer retained",
+                table_id, start_after_epoch
+            ))));
+        }
+        let (tx, rx) = channel(self.retained_epochs + SUBSCRIBER_BUFFER_SIZE);
+        let mut continuous = start_after_epoch != 0;
+        for batch in &table.retained {
+            if batch.epoch > start_after_epoch {
+                tx.try_send(batch.clone()).unwrap();
+                continuous = true;
+            }
+        }
+        table.subscribers.push(Subscriber {
+            sender: tx,
+            continuous,
+        });
+        Ok(rx)
+    }
+}
+
+/// Reports the changes of an actor to the [`ChangelogManager`].
+pub struct ChangelogActorHandle {
+    manager: ChangelogManagerRef,
+    table_id: TableId,
+    actor_id: ActorId,
+    tracked: Arc<AtomicBool>,
+}
+
+impl ChangelogActorHandle {
+    /// Whether the changes should be collected.
+    pub fn is_tracked(&self) -> bool {
+        self.tracked.load(Ordering::Relaxed)
+    }
+
+    /// Reports the changes in `epoch`, or `None` if some changes are not collected.
+    pub fn report(&self, epoch: u64, chunks: Option<Vec<StreamChunk>>) {
+        self.manager
+            .report(self.table_id, self.actor_id, epoch, chunks)
+    }
+}
+
+impl Drop for ChangelogActorHandle {
+    fn drop(&mut self) {
+        self.manager.unregister_actor(self.table_id, self.actor_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_common::test_prelude::StreamChunkTestExt;
+
+    use super::*;
+
+    fn chunk(i: i64) -> StreamChunk {
+        StreamChunk::from_pretty(&format!(" I\n + {}", i))
+    }
+
+    fn schema() -> ChangelogSchema {
+        ChangelogSchema {
+            data_types: vec![DataType::Int64],
+            pk_indices: vec![0],
+        }
+    }
+
+    fn epochs(rx: &mut Receiver<Arc<ChangelogBatch>>) -> Vec<(u64, usize)> {
+        let mut epochs = vec![];
+        while let Ok(batch) = rx.try_recv() {
+            epochs.push((batch.epoch, batch.chunks.len()));
+        }
+        epochs
+    }
+
+    #[tokio::test]
+    async fn test_changelog() {
+        let manager = Arc::new(ChangelogManager::new(2));
+        let table_id = TableId::new(1);
+        let actor_1 = manager.register_actor(table_id, 1, schema());
+        let actor_2 = manager.register_actor(table_id, 2, schema());
+        assert!(!actor_1.is_tracked());
+        assert_eq!(manager.schema(table_id), Some(schema()));
+        assert!(manager.subscribe(table_id, 1).is_err());
+
+        let mut rx = manager.subscribe(table_id, 0).unwrap();
+        assert!(actor_1.is_tracked());
+        // Changes in epoch 1 were not collected.
+        actor_1.report(1, None);
+        actor_2.report(1, None);
+        actor_1.report(2, Some(vec![chunk(1)]));
+        assert!(epochs(&mut rx).is_empty());
+        actor_2.report(2, Some(vec![chunk(2), chunk(3)]));
+        actor_2.report(3, Some(vec![]));
+        actor_1.report(3, Some(vec![chunk(4)]));
+        actor_1.report(4, Some(vec![]));
+        actor_2.report(4, Some(vec![]));
+        assert_eq!(epochs(&mut rx), vec![(2, 3), (3, 1), (4, 0)]);
+
+        // Resume from the retained epochs.
+        assert!(manager.subscribe(table_id, 1).is_err());
+        let mut rx = manager.subscribe(table_id, 3).unwrap();
+        assert_eq!(epochs(&mut rx), vec![(4, 0)]);
+        let mut rx_all = manager.subscribe(table_id, 0).unwrap();
+        assert_eq!(epochs(&mut rx_all), vec![(3, 1), (4, 0)]);
+
+        // An actor is dropped in the middle of epoch 5, which leaves a gap.
+        actor_1.report(5, Some(vec![chunk(5)]));
+        drop(actor_2);
+        assert!(epochs(&mut rx).is_empty());
+        assert!(rx.recv().await.is_none());
+        assert!(manager.subscribe(table_id, 4).is_err());
+        let mut rx = manager.subscribe(table_id, 5).unwrap();
+        actor_1.report(6, Some(vec![chunk(6)]));
+        assert_eq!(epochs(&mut rx), vec![(6, 1)]);
+    }
+}
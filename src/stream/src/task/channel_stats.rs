@@ -0,0 +1,142 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use futures::channel::mpsc::Sender;
+use futures::SinkExt;
+use prometheus::{IntCounter, IntGauge};
+use risingwave_common::error::{internal_error, Result};
+
+use super::UpDownActorIds;
+use crate::executor::monitor::StreamingMetrics;
+use crate::executor::Message;
+
+/// Statistics of the channel from an actor to one of its downstream actors. They are updated by
+/// the output of the upstream actor and the receiver of the downstream actor, and exported as
+/// metrics labeled by both actors.
+pub struct ChannelStats {
+    /// Number of messages sent into the channel but not received yet.
+    buffered_messages: IntGauge,
+    /// Total time that the upstream actor is blocked on sending to the full channel.
+    blocked_time_ns: IntCounter,
+}
+
+pub type ChannelStatsRef = Arc<ChannelStats>;
+
+impl ChannelStats {
+    pub fn new(metrics: &StreamingMetrics, (up_actor_id, down_actor_id): UpDownActorIds) -> Self {
+        let labels = [up_actor_id.to_string(), down_actor_id.to_string()];
+        let labels = [labels[0].as_str(), labels[1].as_str()];
+        Self {
+            buffered_messages: metrics
+                .actor_output_buffered_messages
+                .with_label_values(&labels),
+            blocked_time_ns: metrics
+                .actor_output_blocking_duration_ns
+                .with_label_values(&labels),
+        }
+    }
+
+    pub fn on_send(&self) {
+        self.buffered_messages.inc();
+    }
+
+    pub fn on_recv(&self) {
+        self.buffered_messages.dec();
+    }
+
+    pub fn on_blocked(&self, duration: Duration) {
+        self.blocked_time_ns.inc_by(duration.as_nanos() as u64);
+    }
+
+    pub fn buffered_messages(&self) -> u64 {
+        self.buffered_messages.get().max(0) as u64
+    }
+
+    pub fn blocked_time(&self) -> Duration {
+        Duration::from_nanos(self.blocked_time_ns.get())
+    }
+}
+
+/// Sends `message` to `ch`, and records the time blocked on the full channel into `stats`.
+pub(crate) async fn send_with_stats(
+    ch: &mut Sender<Message>,
+    message: Message,
+    stats: Option<&ChannelStatsRef>,
+) -> Result<()> {
+    let stats = match stats {
+        Some(stats) => stats,
+        None => {
+            return ch
+                .send(message)
+                .await
+                .map_err(|_| internal_error("failed to send"))
+        }
+    };
+    // Count the message before sending, so that it's never received before being counted.
+    stats.on_send();
+    match ch.try_send(message) {
+        Ok(()) => Ok(()),
+        Err(e) if e.is_full() => {
+            let start = Instant::now();
+            ch.send(e.into_inner())
+                .await
+                .map_err(|_| internal_error("failed to send"))?;
+            stats.on_blocked(start.elapsed());
+            Ok(())
+        }
+        Err(_) => Err(internal_error("failed to send")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::channel::mpsc::channel;
+    use futures::StreamExt;
+
+    use super::*;
+    use crate::executor::Barrier;
+
+    #[tokio::test]
+    async fn test_send_with_stats() {
+        let stats = Arc::new(ChannelStats::new(&StreamingMetrics::unused(), (1, 2)));
+        // The channel can buffer only one message from the sender.
+        let (mut tx, mut rx) = channel(0);
+        let message = || Message::Barrier(Barrier::new_test_barrier(1));
+
+        send_with_stats(&mut tx, message(), Some(&stats))
+            .await
+            .unwrap();
+        assert_eq!(stats.buffered_messages(), 1);
+        assert_eq!(stats.blocked_time(), Duration::ZERO);
+
+        // The sender is blocked until the downstream receives the first message.
+        let sender_stats = stats.clone();
+        let handle = tokio::spawn(async move {
+            send_with_stats(&mut tx, message(), Some(&sender_stats))
+                .await
+                .unwrap();
+            tx
+        });
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        assert_eq!(stats.buffered_messages(), 2);
+        rx.next().await.unwrap();
+        stats.on_recv();
+        let _tx = handle.await.unwrap();
+        assert_eq!(stats.buffered_messages(), 1);
+        assert!(stats.blocked_time() >= Duration::from_millis(10));
+    }
+}
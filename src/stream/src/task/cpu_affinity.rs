@@ -0,0 +1,144 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Core sets of the dedicated actor runtimes, see [`StreamingConfig::actor_runtime_pin_cores`].
+//!
+//! [`StreamingConfig::actor_runtime_pin_cores`]: risingwave_common::config::StreamingConfig::actor_runtime_pin_cores
+
+/// Returns the cores of each NUMA node of the machine, or all the cores as a single node if the
+/// topology is unknown.
+pub fn detect_numa_nodes() -> Vec<Vec<usize>> {
+    let nodes = read_numa_nodes().unwrap_or_default();
+    if !nodes.is_empty() {
+        return nodes;
+    }
+    let cores = std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1);
+    vec![(0..cores).collect()]
+}
+
+/// Reads the cores of the NUMA nodes from sysfs.
+fn read_numa_nodes() -> std::io::Result<Vec<Vec<usize>>> {
+    let mut nodes = vec![];
+    for entry in std::fs::read_dir("/sys/devices/system/node")? {
+        let entry = entry?;
+        let name = entry.file_name();
+        let id = match name
+            .to_str()
+            .and_then(|name| name.strip_prefix("node"))
+            .and_then(|id| id.parse::<usize>().ok())
+        {
+            Some(id) => id,
+            None => continue,
+        };
+        let cpu_list = std::fs::read_to_string(entry.path().join("cpulist"))?;
+        let cores = parse_cpu_list(cpu_list.trim());
+        if !cores.is_empty() {
+            nodes.push((id, cores));
+        }
+    }
+    nodes.sort();
+    Ok(nodes.into_iter().map(|(_, cores)| cores).collect())
+}
+
+/// Parses a cpu list of the kernel, e.g. `0-3,8-11`.
+fn parse_cpu_list(cpu_list: &str) -> Vec<usize> {
+    let mut cores = vec![];
+    for range in cpu_list.split(',').filter(|range| !range.is_empty()) {
+        let (start, end) = match range.split_once('-') {
+            Some((start, end)) => (start.parse(), end.parse()),
+            None => (range.parse(), range.parse()),
+        };
+        if let (Ok(start), Ok(end)) = (start, end) {
+            cores.extend(start..=end);
+        }
+    }
+    cores
+}
+
+/// Assigns the cores of `nodes` to `shards` runtimes. The runtimes are spread over the nodes in
+/// round robin, and the runtimes on a node split its cores evenly. If a node has fewer cores than
+/// runtimes, they share all the cores of the node.
+pub fn assign_core_sets(nodes: &[Vec<usize>], shards: usize) -> Vec<Vec<usize>> {
+    (0..shards)
+        .map(|shard| {
+            let cores = &nodes[shard % nodes.len()];
+            let shards_on_node = (shards - shard % nodes.len() + nodes.len() - 1) / nodes.len();
+            if cores.len() < shards_on_node {
+                return cores.clone();
+            }
+            let index_on_node = shard / nodes.len();
+            let per_shard = cores.len() / shards_on_node;
+            let start = index_on_node * per_shard;
+            // The last runtime on the node takes the remainder.
+            let end = if index_on_node + 1 == shards_on_node {
+                cores.len()
+            } else {
+                start + per_shard
+            };
+            cores[start..end].to_vec()
+        })
+        .collect()
+}
+
+/// Pins the current thread to `cores`.
+#[cfg(target_os = "linux")]
+pub fn pin_current_thread(cores: &[usize]) {
+    // SAFETY: `cpu_set_t` is a plain bitmap, and `CPU_SET` ignores cores out of its range.
+    let ret = unsafe {
+        let mut set: libc::cpu_set_t = std::mem::zeroed();
+        for &core in cores {
+            libc::CPU_SET(core, &mut set);
+        }
+        libc::sched_setaffinity(0, std::mem::size_of::<libc::cpu_set_t>(), &set)
+    };
+    if ret != 0 {
+        tracing::warn!(
+            "failed to pin thread to cores {:?}: {}",
+            cores,
+            std::io::Error::last_os_error()
+        );
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn pin_current_thread(_cores: &[usize]) {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_cpu_list() {
+        assert_eq!(parse_cpu_list("0-3,8-9,12"), vec![0, 1, 2, 3, 8, 9, 12]);
+        assert_eq!(parse_cpu_list(""), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn test_assign_core_sets() {
+        let nodes = vec![(0..4).collect(), (4..8).collect()];
+        assert_eq!(
+            assign_core_sets(&nodes, 3),
+            vec![vec![0, 1], vec![4, 5, 6, 7], vec![2, 3]]
+        );
+
+        // Runtimes share the cores of a node with fewer cores than runtimes.
+        let nodes = vec![vec![0, 1]];
+        assert_eq!(
+            assign_core_sets(&nodes, 3),
+            vec![vec![0, 1], vec![0, 1], vec![0, 1]]
+        );
+    }
+}
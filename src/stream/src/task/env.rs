@@ -14,11 +14,13 @@
 
 use std::sync::Arc;
 
-use risingwave_common::config::StreamingConfig;
+use risingwave_common::config::{DynamicConfig, DynamicConfigReceiver, StreamingConfig};
 use risingwave_common::util::addr::HostAddr;
 use risingwave_source::{SourceManager, SourceManagerRef};
 use risingwave_storage::StateStoreImpl;
 
+use crate::task::{ChangelogManager, ChangelogManagerRef};
+
 pub(crate) type WorkerNodeId = u32;
 
 /// The global environment for task execution.
@@ -34,11 +36,17 @@ pub struct StreamEnvironment {
     /// Streaming related configurations.
     config: Arc<StreamingConfig>,
 
+    /// Configurations that can be changed at runtime.
+    dynamic_config: DynamicConfigReceiver,
+
     /// Current worker node id.
     worker_id: WorkerNodeId,
 
     /// State store for table scanning.
     state_store: StateStoreImpl,
+
+    /// Changelog of materialized views subscribed on this node.
+    changelog_manager: ChangelogManagerRef,
 }
 
 impl StreamEnvironment {
@@ -46,15 +54,19 @@ impl StreamEnvironment {
         source_manager: SourceManagerRef,
         server_addr: HostAddr,
         config: Arc<StreamingConfig>,
+        dynamic_config: DynamicConfigReceiver,
         worker_id: WorkerNodeId,
         state_store: StateStoreImpl,
     ) -> Self {
+        let changelog_manager = Arc::new(ChangelogManager::new(config.changelog_retained_epochs));
         StreamEnvironment {
             server_addr,
             source_manager,
             config,
+            dynamic_config,
             worker_id,
             state_store,
+            changelog_manager,
         }
     }
 
@@ -64,10 +76,13 @@ impl StreamEnvironment {
         use risingwave_source::MemSourceManager;
         use risingwave_storage::monitor::StateStoreMetrics;
 
+        let config = StreamingConfig::default();
         StreamEnvironment {
             server_addr: "127.0.0.1:5688".parse().unwrap(),
             source_manager: Arc::new(MemSourceManager::default()),
-            config: Arc::new(StreamingConfig::default()),
+            changelog_manager: Arc::new(ChangelogManager::new(config.changelog_retained_epochs)),
+            config: Arc::new(config),
+            dynamic_config: DynamicConfig::default().fixed(),
             worker_id: WorkerNodeId::default(),
             state_store: StateStoreImpl::shared_in_memory_store(Arc::new(
                 StateStoreMetrics::unused(),
@@ -91,6 +106,10 @@ impl StreamEnvironment {
         self.config.as_ref()
     }
 
+    pub fn dynamic_config(&self) -> DynamicConfigReceiver {
+        self.dynamic_config.clone()
+    }
+
     pub fn worker_id(&self) -> WorkerNodeId {
         self.worker_id
     }
@@ -98,4 +117,8 @@ impl StreamEnvironment {
     pub fn state_store(&self) -> StateStoreImpl {
         self.state_store.clone()
     }
+
+    pub fn changelog_manager(&self) -> &ChangelogManagerRef {
+        &self.changelog_manager
+    }
 }
@@ -0,0 +1,189 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use parking_lot::Mutex;
+use risingwave_pb::stream_service::{ActorProfile, ExecutorProfile};
+
+use super::ActorId;
+
+/// Statistics of an executor of an actor. They're counted all the time, and a profile of the actor,
+/// e.g. by `EXPLAIN ANALYZE MATERIALIZED VIEW`, samples how much they grow in a period.
+#[derive(Debug, Default)]
+pub struct ExecutorStats {
+    /// Number of rows output by the executor.
+    rows: AtomicU64,
+    /// Time spent in polling the executor, including the time spent in its inputs in the actor.
+    busy_time_ns: AtomicU64,
+    /// Total time from the first to the last input of the executor receiving the barrier of each
+    /// epoch.
+    barrier_align_time_ns: AtomicU64,
+    /// The epoch of the barrier being aligned, when the first input received it, and the number of
+    /// inputs that have received it.
+    aligning: Mutex<Option<(u64, Instant, usize)>>,
+}
+
+pub type ExecutorStatsRef = Arc<ExecutorStats>;
+
+impl ExecutorStats {
+    pub fn on_output(&self, rows: usize) {
+        self.rows.fetch_add(rows as u64, Ordering::Relaxed);
+    }
+
+    pub fn on_busy(&self, duration: Duration) {
+        self.busy_time_ns
+            .fetch_add(duration.as_nanos() as u64, Ordering::Relaxed);
+    }
+
+    /// Called when one of the `inputs` inputs of the executor receives the barrier of `epoch`.
+    pub fn on_input_barrier(&self, epoch: u64, inputs: usize) {
+        let mut aligning = self.aligning.lock();
+        if !matches!(*aligning, Some((aligning_epoch, _, _)) if aligning_epoch == epoch) {
+            *aligning = Some((epoch, Instant::now(), 0));
+        }
+        let (_, first_received_at, received) = aligning.as_mut().unwrap();
+        *received += 1;
+        if *received >= inputs {
+            let elapsed = first_received_at.elapsed();
+            self.barrier_align_time_ns
+                .fetch_add(elapsed.as_nanos() as u64, Ordering::Relaxed);
+            *aligning = None;
+        }
+    }
+
+    pub fn snapshot(&self) -> ExecutorStatsSnapshot {
+        ExecutorStatsSnapshot {
+            rows: self.rows.load(Ordering::Relaxed),
+            busy_time: Duration::from_nanos(self.busy_time_ns.load(Ordering::Relaxed)),
+            barrier_align_time: Duration::from_nanos(
+                self.barrier_align_time_ns.load(Ordering::Relaxed),
+            ),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ExecutorStatsSnapshot {
+    pub rows: u64,
+    pub busy_time: Duration,
+    pub barrier_align_time: Duration,
+}
+
+impl ExecutorStatsSnapshot {
+    /// Returns the statistics counted since `earlier`.
+    pub fn since(&self, earlier: &Self) -> Self {
+        Self {
+            rows: self.rows.saturating_sub(earlier.rows),
+            busy_time: self.busy_time.saturating_sub(earlier.busy_time),
+            barrier_align_time: self
+                .barrier_align_time
+                .saturating_sub(earlier.barrier_align_time),
+        }
+    }
+}
+
+/// Statistics of an actor, with those of its executors by their operator ids in the fragment.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ActorStats {
+    pub executors: BTreeMap<u64, ExecutorStatsSnapshot>,
+    /// Lookups hitting and missing the cache of the materialize executor.
+    pub materialize_cache_hits: u64,
+    pub materialize_cache_misses: u64,
+    /// Entries evicted from the caches of the join executors.
+    pub join_cache_evictions: u64,
+}
+
+impl ActorStats {
+    /// Returns the statistics counted since `earlier`. Executors created after `earlier` are
+    /// counted from zero.
+    pub fn since(&self, earlier: &Self) -> Self {
+        Self {
+            executors: self
+                .executors
+                .iter()
+                .map(|(operator_id, stats)| {
+                    let earlier = earlier
+                        .executors
+                        .get(operator_id)
+                        .copied()
+                        .unwrap_or_default();
+                    (*operator_id, stats.since(&earlier))
+                })
+                .collect(),
+            materialize_cache_hits: self
+                .materialize_cache_hits
+                .saturating_sub(earlier.materialize_cache_hits),
+            materialize_cache_misses: self
+                .materialize_cache_misses
+                .saturating_sub(earlier.materialize_cache_misses),
+            join_cache_evictions: self
+                .join_cache_evictions
+                .saturating_sub(earlier.join_cache_evictions),
+        }
+    }
+
+    pub fn to_protobuf(&self, actor_id: ActorId) -> ActorProfile {
+        ActorProfile {
+            actor_id,
+            executors: self
+                .executors
+                .iter()
+                .map(|(operator_id, stats)| ExecutorProfile {
+                    operator_id: *operator_id,
+                    rows: stats.rows,
+                    busy_time_ns: stats.busy_time.as_nanos() as u64,
+                    barrier_align_time_ns: stats.barrier_align_time.as_nanos() as u64,
+                })
+                .collect(),
+            materialize_cache_hits: self.materialize_cache_hits,
+            materialize_cache_misses: self.materialize_cache_misses,
+            join_cache_evictions: self.join_cache_evictions,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_barrier_align_time() {
+        let stats = ExecutorStats::default();
+        stats.on_input_barrier(1, 2);
+        std::thread::sleep(Duration::from_millis(10));
+        stats.on_input_barrier(1, 2);
+        let aligned = stats.snapshot().barrier_align_time;
+        assert!(aligned >= Duration::from_millis(10));
+
+        // The barrier of an executor with a single input is aligned immediately.
+        stats.on_input_barrier(2, 1);
+        assert!(stats.aligning.lock().is_none());
+
+        stats.on_output(3);
+        stats.on_busy(Duration::from_millis(1));
+        let earlier = stats.snapshot();
+        stats.on_output(2);
+        assert_eq!(
+            stats.snapshot().since(&earlier),
+            ExecutorStatsSnapshot {
+                rows: 2,
+                ..Default::default()
+            }
+        );
+    }
+}
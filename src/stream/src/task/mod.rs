@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::BTreeMap;
 use std::sync::Arc;
 
 use futures::channel::mpsc::{Receiver, Sender};
@@ -20,14 +21,29 @@ use parking_lot::{Mutex, MutexGuard};
 use risingwave_common::error::{ErrorCode, Result, RwError};
 use risingwave_common::util::addr::HostAddr;
 
+use crate::executor::monitor::StreamingMetrics;
 use crate::executor::Message;
 
+mod actor_runtime;
 mod barrier_manager;
+mod changelog_formatter;
+mod changelog_manager;
+mod channel_stats;
+#[cfg(not(madsim))]
+mod cpu_affinity;
 mod env;
+mod executor_stats;
+mod resource_group;
 mod stream_manager;
 
+pub use actor_runtime::*;
 pub use barrier_manager::*;
+pub use changelog_formatter::*;
+pub use changelog_manager::*;
+pub use channel_stats::*;
 pub use env::*;
+pub use executor_stats::*;
+pub use resource_group::*;
 pub use stream_manager::*;
 
 /// Default capacity of channel if two actors are on the same node
@@ -68,6 +84,15 @@ pub struct SharedContext {
     pub(crate) addr: HostAddr,
 
     pub(crate) barrier_manager: Arc<Mutex<LocalBarrierManager>>,
+
+    /// Stores the statistics of the channels, which are identified by the upstream and downstream
+    /// actor id like the channels.
+    channel_stats: Mutex<HashMap<UpDownActorIds, ChannelStatsRef>>,
+
+    /// Stores the statistics of the executors of each actor by their operator ids in the fragment.
+    executor_stats: Mutex<HashMap<ActorId, BTreeMap<u64, ExecutorStatsRef>>>,
+
+    streaming_metrics: Arc<StreamingMetrics>,
 }
 
 impl std::fmt::Debug for SharedContext {
@@ -79,11 +104,14 @@ impl std::fmt::Debug for SharedContext {
 }
 
 impl SharedContext {
-    pub fn new(addr: HostAddr) -> Self {
+    pub fn new(addr: HostAddr, streaming_metrics: Arc<StreamingMetrics>) -> Self {
         Self {
             channel_map: Mutex::new(HashMap::new()),
             addr,
             barrier_manager: Arc::new(Mutex::new(LocalBarrierManager::new())),
+            channel_stats: Mutex::new(HashMap::new()),
+            executor_stats: Mutex::new(HashMap::new()),
+            streaming_metrics,
         }
     }
 
@@ -93,6 +121,9 @@ impl SharedContext {
             channel_map: Mutex::new(HashMap::new()),
             addr: LOCAL_TEST_ADDR.clone(),
             barrier_manager: Arc::new(Mutex::new(LocalBarrierManager::for_test())),
+            channel_stats: Mutex::new(HashMap::new()),
+            executor_stats: Mutex::new(HashMap::new()),
+            streaming_metrics: Arc::new(StreamingMetrics::unused()),
         }
     }
 
@@ -150,12 +181,83 @@ impl SharedContext {
         self.lock_channel_map().insert(ids, channels);
     }
 
+    /// Returns the statistics of the channel between the actors, which are shared by the output of
+    /// the upstream actor and the receiver of the downstream actor.
+    pub fn channel_stats(&self, ids: UpDownActorIds) -> ChannelStatsRef {
+        self.channel_stats
+            .lock()
+            .entry(ids)
+            .or_insert_with(|| Arc::new(ChannelStats::new(&self.streaming_metrics, ids)))
+            .clone()
+    }
+
+    /// Returns the statistics of all channels.
+    pub fn all_channel_stats(&self) -> Vec<(UpDownActorIds, ChannelStatsRef)> {
+        self.channel_stats
+            .lock()
+            .iter()
+            .map(|(ids, stats)| (*ids, stats.clone()))
+            .collect()
+    }
+
+    /// Returns the statistics of the executor of the operator in the actor, which are shared by the
+    /// executor and the inputs measuring its barrier alignment.
+    pub fn executor_stats(&self, actor_id: ActorId, operator_id: u64) -> ExecutorStatsRef {
+        self.executor_stats
+            .lock()
+            .entry(actor_id)
+            .or_default()
+            .entry(operator_id)
+            .or_default()
+            .clone()
+    }
+
+    /// Returns the current statistics of the actor, or `None` if it's not built on this node.
+    pub fn actor_stats(&self, actor_id: ActorId) -> Option<ActorStats> {
+        let executors = self
+            .executor_stats
+            .lock()
+            .get(&actor_id)?
+            .iter()
+            .map(|(operator_id, stats)| (*operator_id, stats.snapshot()))
+            .collect();
+        let actor_id_string = actor_id.to_string();
+        let metrics = &self.streaming_metrics;
+        Some(ActorStats {
+            executors,
+            materialize_cache_hits: metrics
+                .materialize_cache_hit_count
+                .with_label_values(&[&actor_id_string])
+                .get(),
+            materialize_cache_misses: metrics
+                .materialize_cache_miss_count
+                .with_label_values(&[&actor_id_string])
+                .get(),
+            join_cache_evictions: ["left", "right"]
+                .iter()
+                .map(|side| {
+                    metrics
+                        .join_cache_evicted_count
+                        .with_label_values(&[&actor_id_string, side])
+                        .get()
+                })
+                .sum(),
+        })
+    }
+
+    pub fn remove_executor_stats(&self, actor_id: ActorId) {
+        self.executor_stats.lock().remove(&actor_id);
+    }
+
     pub fn retain<F>(&self, mut f: F)
     where
         F: FnMut(&(u32, u32)) -> bool,
     {
         self.lock_channel_map()
             .retain(|up_down_ids, _| f(up_down_ids));
+        self.channel_stats
+            .lock()
+            .retain(|up_down_ids, _| f(up_down_ids));
     }
 
     #[cfg(test)]
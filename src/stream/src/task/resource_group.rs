@@ -0,0 +1,195 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use madsim::collections::HashMap;
+use risingwave_common::catalog::TableId;
+use risingwave_common::config::{ResourceGroupConfig, StreamingConfig};
+use risingwave_pb::stream_plan::stream_node::NodeBody;
+use risingwave_pb::stream_plan::StreamNode;
+use risingwave_storage::hummock::CachePolicy;
+use risingwave_storage::StateStoreImpl;
+
+use super::ActorRuntime;
+
+/// The runtime and the memory budget of the actors of the materialized views in a resource group.
+pub struct ResourceGroup {
+    config: ResourceGroupConfig,
+    runtime: Arc<ActorRuntime>,
+}
+
+impl ResourceGroup {
+    /// The runtime to spawn the actors in the group on.
+    pub fn runtime(&self) -> &ActorRuntime {
+        &self.runtime
+    }
+
+    /// Target number of entries kept in the cache of each executor in the group.
+    pub fn executor_cache_capacity(&self) -> usize {
+        self.config.executor_cache_capacity
+    }
+
+    /// Makes the reads of the state tables of the executors in `node` follow the block cache
+    /// policy of the group.
+    pub fn apply_cache_policy(&self, state_store: &StateStoreImpl, node: &StreamNode) {
+        if self.config.fill_block_cache {
+            return;
+        }
+        if let Some(hummock) = state_store.hummock_storage() {
+            for table_id in state_table_ids(node) {
+                hummock.set_table_cache_policy(table_id, CachePolicy::NotFill);
+            }
+        }
+    }
+}
+
+/// The resource groups configured in [`StreamingConfig::resource_groups`].
+pub struct ResourceGroups {
+    default_group: Arc<ResourceGroup>,
+    groups: HashMap<String, Arc<ResourceGroup>>,
+}
+
+impl ResourceGroups {
+    pub fn new(config: &StreamingConfig) -> Self {
+        let default_runtime = Arc::new(ActorRuntime::new(config));
+        let groups = config
+            .resource_groups
+            .iter()
+            .map(|group| {
+                let runtime = if group.worker_threads_number == 0 {
+                    default_runtime.clone()
+                } else {
+                    Arc::new(ActorRuntime::with_worker_threads(
+                        &format!("actor-{}", group.name),
+                        group.worker_threads_number,
+                        1,
+                    ))
+                };
+                let resource_group = ResourceGroup {
+                    config: group.clone(),
+                    runtime,
+                };
+                (group.name.clone(), Arc::new(resource_group))
+            })
+            .collect();
+        Self {
+            default_group: Arc::new(ResourceGroup {
+                config: ResourceGroupConfig::default_group(),
+                runtime: default_runtime,
+            }),
+            groups,
+        }
+    }
+
+    /// Returns the resource group named `name`, or the default group if it's not configured on
+    /// this compute node.
+    pub fn get(&self, name: &str) -> Arc<ResourceGroup> {
+        if name.is_empty() {
+            return self.default_group.clone();
+        }
+        match self.groups.get(name) {
+            Some(group) => group.clone(),
+            None => {
+                tracing::warn!(
+                    "resource group {} is not configured, use the default group",
+                    name
+                );
+                self.default_group.clone()
+            }
+        }
+    }
+}
+
+/// Returns the ids of the tables that the executors in `node` keep their states in.
+fn state_table_ids(node: &StreamNode) -> Vec<u32> {
+    let mut table_ids = match node.node_body.as_ref() {
+        Some(NodeBody::Materialize(node)) => vec![TableId::from(&node.table_ref_id).table_id()],
+        Some(NodeBody::Arrange(node)) => vec![node.table_id],
+        Some(NodeBody::HashJoin(node)) => vec![node.left_table_id, node.right_table_id],
+        Some(NodeBody::GlobalSimpleAgg(node) | NodeBody::LocalSimpleAgg(node)) => {
+            node.table_ids.clone()
+        }
+        Some(NodeBody::HashAgg(node)) => node.table_ids.clone(),
+        Some(NodeBody::TopN(node) | NodeBody::AppendOnlyTopN(node)) => vec![node.table_id],
+        Some(NodeBody::EmitOnWindowClose(node)) => vec![node.table_id],
+        Some(NodeBody::TemporalFilter(node)) => vec![node.table_id],
+        Some(NodeBody::Dedup(node)) => vec![node.table_id],
+        _ => vec![],
+    };
+    table_ids.extend(node.input.iter().flat_map(state_table_ids));
+    table_ids
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_pb::stream_plan::{HashAggNode, TopNNode};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_resource_groups() {
+        let config = StreamingConfig {
+            resource_groups: vec![ResourceGroupConfig {
+                name: "critical".to_string(),
+                worker_threads_number: 1,
+                executor_cache_capacity: 1 << 20,
+                fill_block_cache: true,
+            }],
+            ..Default::default()
+        };
+        let groups = ResourceGroups::new(&config);
+        let thread_name = || std::thread::current().name().unwrap().to_string();
+
+        let critical = groups.get("critical");
+        assert_eq!(critical.executor_cache_capacity(), 1 << 20);
+        let name = critical
+            .runtime()
+            .spawn(1, async move { thread_name() })
+            .await
+            .unwrap();
+        assert_eq!(name, "risingwave-actor-critical-0");
+
+        // Views not in a configured group are in the default group.
+        for name in ["", "unknown"] {
+            let group = groups.get(name);
+            assert_eq!(group.executor_cache_capacity(), 1 << 16);
+            let name = group
+                .runtime()
+                .spawn(1, async move { thread_name() })
+                .await
+                .unwrap();
+            assert!(!name.starts_with("risingwave-actor"));
+        }
+    }
+
+    #[test]
+    fn test_state_table_ids() {
+        let node = StreamNode {
+            node_body: Some(NodeBody::TopN(TopNNode {
+                table_id: 3,
+                ..Default::default()
+            })),
+            input: vec![StreamNode {
+                node_body: Some(NodeBody::HashAgg(HashAggNode {
+                    table_ids: vec![1, 2],
+                    ..Default::default()
+                })),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        assert_eq!(state_table_ids(&node), vec![3, 1, 2]);
+    }
+}
@@ -15,11 +15,13 @@
 use std::fmt::Debug;
 use std::rc::Rc;
 use std::sync::Arc;
+use std::time::Duration;
 
 use futures::channel::mpsc::{channel, Receiver};
 use itertools::Itertools;
 use madsim::collections::{HashMap, HashSet};
 use parking_lot::Mutex;
+use risingwave_common::buffer::Bitmap;
 use risingwave_common::config::StreamingConfig;
 use risingwave_common::error::{ErrorCode, Result, RwError};
 use risingwave_common::try_match_expand;
@@ -34,14 +36,15 @@ use tokio::sync::oneshot;
 use tokio::task::JoinHandle;
 
 use super::{unique_executor_id, unique_operator_id, CollectResult};
+use crate::common::vnode_bitmap_from_protobuf;
 use crate::executor::dispatch::*;
 use crate::executor::merge::RemoteInput;
 use crate::executor::monitor::StreamingMetrics;
 use crate::executor::*;
 use crate::from_proto::create_executor;
 use crate::task::{
-    ActorId, ConsumableChannelPair, SharedContext, StreamEnvironment, UpDownActorIds,
-    LOCAL_OUTPUT_CHANNEL_SIZE,
+    ActorId, ActorStats, ChannelStatsRef, ConsumableChannelPair, ExecutorStatsRef, ResourceGroups,
+    SharedContext, StreamEnvironment, UpDownActorIds, LOCAL_OUTPUT_CHANNEL_SIZE,
 };
 
 #[cfg(test)]
@@ -83,6 +86,13 @@ pub struct LocalStreamManagerCore {
 
     /// Config of streaming engine
     pub(crate) config: StreamingConfig,
+
+    /// The runtimes and memory budgets of the actors by their resource groups.
+    resource_groups: ResourceGroups,
+
+    /// The epochs of the barriers collected since the last checkpoint, whose states are still in
+    /// the shared buffer.
+    uncheckpointed_epochs: Vec<u64>,
 }
 
 /// `LocalStreamManager` manages all stream executors in this project.
@@ -119,6 +129,17 @@ pub struct ExecutorParams {
 
     // Vnodes owned by this executor. Represented in bitmap.
     pub vnode_bitmap: Rc<Vec<u8>>,
+
+    /// Target number of entries kept in the cache of the executor, decided by the resource group
+    /// of the actor.
+    pub cache_capacity: usize,
+}
+
+impl ExecutorParams {
+    /// Returns the vnodes owned by the actor, or `None` if it's the only actor in its fragment.
+    pub fn vnodes(&self) -> Option<Arc<Bitmap>> {
+        (!self.vnode_bitmap.is_empty()).then(|| vnode_bitmap_from_protobuf(&self.vnode_bitmap))
+    }
 }
 
 impl Debug for ExecutorParams {
@@ -177,31 +198,45 @@ impl LocalStreamManager {
     }
 
     /// Broadcast a barrier to all senders. Returns when the barrier is fully collected.
+    ///
+    /// The states of the barrier stay in the shared buffer until a checkpoint is collected, which
+    /// syncs the states of all epochs since the last checkpoint.
     pub async fn send_and_collect_barrier(
         &self,
         barrier: &Barrier,
         actor_ids_to_send: impl IntoIterator<Item = ActorId>,
         actor_ids_to_collect: impl IntoIterator<Item = ActorId>,
-        need_sync: bool,
+        checkpoint: bool,
     ) -> Result<CollectResult> {
         let rx = self.send_barrier(barrier, actor_ids_to_send, actor_ids_to_collect)?;
 
         // Wait for all actors finishing this barrier.
         let mut collect_result = rx.await.unwrap();
 
+        let epochs_to_sync = {
+            let mut core = self.core.lock();
+            core.uncheckpointed_epochs.push(barrier.epoch.prev);
+            if checkpoint {
+                std::mem::take(&mut core.uncheckpointed_epochs)
+            } else {
+                vec![]
+            }
+        };
+
         // Sync states from shared buffer to S3 before telling meta service we've done.
-        if need_sync {
+        for epoch in epochs_to_sync {
             dispatch_state_store!(self.state_store(), store, {
-                match store.sync(Some(barrier.epoch.prev)).await {
+                match store.sync(Some(epoch)).await {
                     Ok(_) => {
-                        collect_result.synced_sstables =
-                            store.get_uncommitted_ssts(barrier.epoch.prev);
+                        collect_result
+                            .synced_sstables
+                            .extend(store.get_uncommitted_ssts(epoch));
                     }
                     // TODO: Handle sync failure by propagating it
                     // back to global barrier manager
                     Err(e) => panic!(
-                        "Failed to sync state store after receiving barrier {:?} due to {}",
-                        barrier, e
+                        "Failed to sync epoch {} of state store on barrier {:?} due to {}",
+                        epoch, barrier, e
                     ),
                 }
             });
@@ -228,39 +263,93 @@ impl LocalStreamManager {
         for id in actors {
             core.drop_actor(*id);
         }
-        tracing::debug!(actors = ?actors, "drop actors");
+        tracing::debug!(actor_ids = ?actors, "drop actors");
         Ok(())
     }
 
     /// Force stop all actors on this worker.
-    pub async fn stop_all_actors(&self, epoch: Epoch) -> Result<()> {
+    ///
+    /// The states not checkpointed yet are dropped, since the actors are restarted from the last
+    /// checkpoint `committed_epoch` on recovery. The sources restore the offsets persisted by the
+    /// checkpoint, and replay the data after them into the states rolled back along with them.
+    pub async fn stop_all_actors(&self, epoch: Epoch, committed_epoch: u64) -> Result<()> {
         let (actor_ids_to_send, actor_ids_to_collect) = {
             let core = self.core.lock();
             let actor_ids_to_send = core.context.lock_barrier_manager().all_senders();
             let actor_ids_to_collect = core.actor_infos.keys().cloned().collect::<HashSet<_>>();
             (actor_ids_to_send, actor_ids_to_collect)
         };
-        if actor_ids_to_send.is_empty() || actor_ids_to_collect.is_empty() {
-            return Ok(());
+        if !actor_ids_to_send.is_empty() && !actor_ids_to_collect.is_empty() {
+            let barrier = Barrier {
+                epoch,
+                mutation: Some(Arc::new(Mutation::Stop(actor_ids_to_collect.clone()))),
+                span: tracing::Span::none(),
+            };
+
+            self.send_and_collect_barrier(&barrier, actor_ids_to_send, actor_ids_to_collect, false)
+                .await?;
+            self.core.lock().drop_all_actors();
         }
-        let barrier = Barrier {
-            epoch,
-            mutation: Some(Arc::new(Mutation::Stop(actor_ids_to_collect.clone()))),
-            span: tracing::Span::none(),
-        };
 
-        self.send_and_collect_barrier(&barrier, actor_ids_to_send, actor_ids_to_collect, false)
-            .await?;
-        self.core.lock().drop_all_actors();
+        self.core.lock().uncheckpointed_epochs.clear();
+        dispatch_state_store!(self.state_store(), store, {
+            // The states of the checkpoint must be readable before dropping the shared buffer,
+            // which may still hold them if the local version is behind.
+            store.wait_epoch(committed_epoch).await?;
+            store.clear_shared_buffer();
+        });
 
         Ok(())
     }
 
+    /// Flushes all data in the shared buffer to the object store, e.g. before the node exits.
+    pub async fn sync_all(&self) -> Result<()> {
+        dispatch_state_store!(self.state_store(), store, {
+            store.sync(None).await?;
+        });
+        Ok(())
+    }
+
     pub fn take_receiver(&self, ids: UpDownActorIds) -> Result<Receiver<Message>> {
         let core = self.core.lock();
         core.context.take_receiver(&ids)
     }
 
+    /// Returns the statistics of the channel between the actors.
+    pub fn channel_stats(&self, ids: UpDownActorIds) -> ChannelStatsRef {
+        self.core.lock().context.channel_stats(ids)
+    }
+
+    /// Returns the statistics of all channels on this node, with the most backpressured ones, i.e.,
+    /// whose senders are blocked for the longest time, first.
+    pub fn channel_stats_by_backpressure(&self) -> Vec<(UpDownActorIds, ChannelStatsRef)> {
+        let mut all_stats = self.core.lock().context.all_channel_stats();
+        all_stats.sort_by_key(|(_, stats)| std::cmp::Reverse(stats.blocked_time()));
+        all_stats
+    }
+
+    /// Samples the statistics of the actors on this node for `duration`. The actors not built on
+    /// this node are omitted.
+    pub async fn profile_actors(
+        &self,
+        actor_ids: &[ActorId],
+        duration: Duration,
+    ) -> Vec<(ActorId, ActorStats)> {
+        let context = self.core.lock().context.clone();
+        let start: HashMap<_, _> = actor_ids
+            .iter()
+            .filter_map(|actor_id| Some((*actor_id, context.actor_stats(*actor_id)?)))
+            .collect();
+        tokio::time::sleep(duration).await;
+        actor_ids
+            .iter()
+            .filter_map(|actor_id| {
+                let stats = context.actor_stats(*actor_id)?;
+                Some((*actor_id, stats.since(start.get(actor_id)?)))
+            })
+            .collect()
+    }
+
     pub fn update_actors(
         &self,
         actors: &[stream_plan::StreamActor],
@@ -338,7 +427,7 @@ impl LocalStreamManagerCore {
         streaming_metrics: Arc<StreamingMetrics>,
         config: StreamingConfig,
     ) -> Self {
-        let context = SharedContext::new(addr);
+        let context = SharedContext::new(addr, streaming_metrics.clone());
         Self::with_store_and_context(state_store, context, streaming_metrics, config)
     }
 
@@ -359,7 +448,9 @@ impl LocalStreamManagerCore {
             state_store,
             streaming_metrics,
             compute_client_pool: ComputeClientPool::new(u64::MAX),
+            resource_groups: ResourceGroups::new(&config),
             config,
+            uncheckpointed_epochs: vec![],
         }
     }
 
@@ -462,8 +553,14 @@ impl LocalStreamManagerCore {
         store: impl StateStore,
         actor_context: &ActorContextRef,
         vnode_bitmap: Rc<Vec<u8>>,
+        cache_capacity: usize,
+        downstream_stats: Option<(ExecutorStatsRef, usize)>,
     ) -> Result<BoxedExecutor> {
         let op_info = node.get_identity().clone();
+        let stats = self.context.executor_stats(actor_id, node.operator_id);
+        // The barrier alignment of an executor with multiple inputs is recorded by its inputs.
+        let input_downstream_stats =
+            (node.input.len() > 1).then(|| (stats.clone(), node.input.len()));
         // Create the input executor before creating itself
         // The node with no input must be a `MergeNode`
         let input: Vec<_> = node
@@ -480,6 +577,8 @@ impl LocalStreamManagerCore {
                     store.clone(),
                     actor_context,
                     Rc::clone(&vnode_bitmap),
+                    cache_capacity,
+                    input_downstream_stats.clone(),
                 )
             })
             .try_collect()?;
@@ -506,6 +605,7 @@ impl LocalStreamManagerCore {
             executor_stats: self.streaming_metrics.clone(),
             actor_context: actor_context.clone(),
             vnode_bitmap,
+            cache_capacity,
         };
 
         let executor = create_executor(executor_params, self, node, store)?;
@@ -515,10 +615,11 @@ impl LocalStreamManagerCore {
             input_pos,
             self.streaming_metrics.clone(),
         );
-        Ok(executor)
+        Ok(ProfileExecutor::new(executor, stats, downstream_stats).boxed())
     }
 
     /// Create a chain(tree) of nodes and return the head executor.
+    #[allow(clippy::too_many_arguments)]
     fn create_nodes(
         &mut self,
         fragment_id: u32,
@@ -527,6 +628,7 @@ impl LocalStreamManagerCore {
         env: StreamEnvironment,
         actor_context: &ActorContextRef,
         vnode_bitmap: Rc<Vec<u8>>,
+        cache_capacity: usize,
     ) -> Result<BoxedExecutor> {
         dispatch_state_store!(self.state_store.clone(), store, {
             self.create_nodes_inner(
@@ -538,6 +640,8 @@ impl LocalStreamManagerCore {
                 store,
                 actor_context,
                 vnode_bitmap,
+                cache_capacity,
+                None,
             )
         })
     }
@@ -575,6 +679,7 @@ impl LocalStreamManagerCore {
 
                         let pool = self.compute_client_pool.clone();
                         let metrics = self.streaming_metrics.clone();
+                        let stats = self.context.channel_stats((up_id, actor_id));
                         tokio::spawn(async move {
                             let init_client = async move {
                                 let remote_input = RemoteInput::create(
@@ -583,7 +688,8 @@ impl LocalStreamManagerCore {
                                     sender,
                                     metrics,
                                 )
-                                .await?;
+                                .await?
+                                .with_stats(stats);
                                 Ok::<_, RwError>(remote_input)
                             };
                             match init_client.await {
@@ -615,7 +721,10 @@ impl LocalStreamManagerCore {
         for actor_id in actors {
             let actor_id = *actor_id;
             let actor = self.actors.remove(&actor_id).unwrap();
+            let fragment_id = actor.fragment_id;
             let actor_context = Arc::new(Mutex::new(ActorContext::default()));
+            let resource_group = self.resource_groups.get(&actor.resource_group);
+            resource_group.apply_cache_policy(&self.state_store, actor.get_nodes()?);
 
             let executor = self.create_nodes(
                 actor.fragment_id,
@@ -624,6 +733,7 @@ impl LocalStreamManagerCore {
                 env.clone(),
                 &actor_context,
                 Rc::new(actor.get_vnode_bitmap().to_owned()),
+                resource_group.executor_cache_capacity(),
             )?;
 
             let dispatcher = self.create_dispatcher(executor, &actor.dispatcher, actor_id)?;
@@ -636,7 +746,7 @@ impl LocalStreamManagerCore {
             );
             self.handles.insert(
                 actor_id,
-                tokio::spawn(async move {
+                resource_group.runtime().spawn(fragment_id, async move {
                     // unwrap the actor result to panic on error
                     actor.run().await.expect("actor failed");
                 }),
@@ -686,6 +796,7 @@ impl LocalStreamManagerCore {
     fn drop_actor(&mut self, actor_id: ActorId) {
         let handle = self.handles.remove(&actor_id).unwrap();
         self.context.retain(|&(up_id, _)| up_id != actor_id);
+        self.context.remove_executor_stats(actor_id);
 
         self.actor_infos.remove(&actor_id);
         self.actors.remove(&actor_id);
@@ -698,6 +809,7 @@ impl LocalStreamManagerCore {
     fn drop_all_actors(&mut self) {
         for (actor_id, handle) in self.handles.drain() {
             self.context.retain(|&(up_id, _)| up_id != actor_id);
+            self.context.remove_executor_stats(actor_id);
             self.actors.remove(&actor_id);
             // Task should have already stopped when this method is invoked.
             handle.abort();
@@ -0,0 +1,147 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+use clap::Parser;
+use madsim::runtime::{Handle, NodeHandle};
+use risingwave_compute::ComputeNodeOpts;
+use risingwave_meta::MetaNodeOpts;
+use risingwave_pb::common::WorkerType;
+use risingwave_rpc_client::{HummockMetaClient, MetaClient};
+
+const META_IP: &str = "192.168.1.1";
+const META_PORT: u16 = 5690;
+const CLIENT_IP: &str = "192.168.100.1";
+
+/// A cluster of a meta node and several compute nodes, each running on a simulated node.
+pub struct Cluster {
+    handle: Handle,
+    compute_nodes: Vec<NodeHandle>,
+    /// A node to send requests to the cluster from.
+    client: NodeHandle,
+    meta_client: MetaClient,
+}
+
+impl Cluster {
+    pub async fn start(compute_nodes: usize) -> Self {
+        let handle = Handle::current();
+        let meta_addr = format!("{}:{}", META_IP, META_PORT);
+
+        let meta_host = meta_addr.clone();
+        handle
+            .create_node()
+            .name("meta")
+            .ip(META_IP.parse().unwrap())
+            .init(move || {
+                let opts = MetaNodeOpts::parse_from([
+                    "meta-node",
+                    "--host",
+                    &meta_host,
+                    "--backend",
+                    "mem",
+                    // Detect crashed compute nodes soon.
+                    "--max-heartbeat-interval",
+                    "10000",
+                ]);
+                risingwave_meta::start(opts)
+            })
+            .build();
+        // Wait for the meta service to be ready.
+        tokio::time::sleep(Duration::from_secs(1)).await;
+
+        let compute_nodes = (1..=compute_nodes)
+            .map(|i| {
+                let ip = format!("192.168.2.{}", i);
+                let host = format!("{}:5688", ip);
+                let meta_address = format!("http://{}", meta_addr);
+                handle
+                    .create_node()
+                    .name(format!("compute-{}", i))
+                    .ip(ip.parse().unwrap())
+                    .init(move || {
+                        let opts = ComputeNodeOpts::parse_from([
+                            "compute-node",
+                            "--host",
+                            &host,
+                            "--meta-address",
+                            &meta_address,
+                            "--state-store",
+                            "hummock+memory",
+                        ]);
+                        risingwave_compute::start(opts)
+                    })
+                    .build()
+            })
+            .collect();
+
+        let client = handle
+            .create_node()
+            .name("client")
+            .ip(CLIENT_IP.parse().unwrap())
+            .build();
+        let meta_client = client
+            .spawn(async move {
+                let mut meta_client = MetaClient::new(&format!("http://{}", meta_addr))
+                    .await
+                    .unwrap();
+                let worker_id = meta_client
+                    .register(
+                        &format!("{}:2333", CLIENT_IP).parse().unwrap(),
+                        WorkerType::RiseCtl,
+                    )
+                    .await
+                    .unwrap();
+                meta_client.set_worker_id(worker_id);
+                meta_client
+            })
+            .await
+            .unwrap();
+
+        Self {
+            handle,
+            compute_nodes,
+            client,
+            meta_client,
+        }
+    }
+
+    pub fn compute_node_count(&self) -> usize {
+        self.compute_nodes.len()
+    }
+
+    /// Kills a compute node, which loses all its in-memory state.
+    pub fn kill_compute_node(&self, idx: usize) {
+        self.handle.kill(self.compute_nodes[idx].id());
+    }
+
+    /// Restarts a killed compute node from scratch.
+    pub fn restart_compute_node(&self, idx: usize) {
+        self.handle.restart(self.compute_nodes[idx].id());
+    }
+
+    /// Returns the max committed epoch of Hummock, which advances whenever a barrier is collected
+    /// from all compute nodes and committed.
+    pub async fn max_committed_epoch(&self) -> u64 {
+        let meta_client = self.meta_client.clone();
+        self.client
+            .spawn(async move {
+                let version = meta_client.pin_version(u64::MAX).await.unwrap();
+                meta_client.unpin_version(&[version.id]).await.unwrap();
+                version.max_committed_epoch
+            })
+            .await
+            .unwrap()
+    }
+}
@@ -0,0 +1,76 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Deterministic simulation tests of a RisingWave cluster. Meta and compute nodes run on the
+//! [madsim](https://github.com/madsim-rs/madsim) executor, which injects RPC delays and reorders
+//! messages, while the tests crash and restart nodes. A failure can be reproduced by running with
+//! the same seed.
+//!
+//! The tests must be built with `RUSTFLAGS="--cfg madsim"`, e.g. `./risedev sim-recovery`.
+
+#![warn(clippy::dbg_macro)]
+#![warn(clippy::disallowed_methods)]
+#![warn(clippy::doc_markdown)]
+#![warn(clippy::explicit_into_iter_loop)]
+#![warn(clippy::explicit_iter_loop)]
+#![warn(clippy::inconsistent_struct_constructor)]
+#![warn(clippy::map_flatten)]
+#![warn(clippy::no_effect_underscore_binding)]
+#![warn(clippy::await_holding_lock)]
+#![deny(unused_must_use)]
+#![deny(rustdoc::broken_intra_doc_links)]
+
+#[cfg(madsim)]
+mod cluster;
+mod opts;
+#[cfg(madsim)]
+mod recovery;
+
+use clap::Parser;
+pub use opts::*;
+
+pub fn simulation_main() -> i32 {
+    let opts = Opts::parse();
+
+    risingwave_logging::oneshot_common();
+    risingwave_logging::init_risingwave_logger(false, false);
+
+    run(opts)
+}
+
+#[cfg(madsim)]
+fn run(opts: Opts) -> i32 {
+    use std::time::Duration;
+
+    for seed in opts.seed..opts.seed + opts.count {
+        // Print the seed before running, so that a failed run can be reproduced.
+        eprintln!("running recovery test with seed {}", seed);
+        let mut config = madsim::Config::default();
+        // Messages are delayed randomly, so they can arrive in a different order than being sent.
+        config.net.send_latency =
+            Duration::from_millis(1)..Duration::from_millis(std::cmp::max(opts.max_latency_ms, 2));
+        let runtime = madsim::runtime::Runtime::with_seed_and_config(seed, config);
+        let opts = opts.clone();
+        runtime.block_on(async move { recovery::crash_recovery_test(&opts).await });
+    }
+    0
+}
+
+#[cfg(not(madsim))]
+fn run(_opts: Opts) -> i32 {
+    eprintln!(
+        "the simulation tests must be built with `RUSTFLAGS=\"--cfg madsim\"`, e.g. `./risedev sim-recovery`"
+    );
+    1
+}
@@ -0,0 +1,42 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use clap::Parser;
+
+#[derive(Parser, Debug, Clone)]
+pub struct Opts {
+    /// Seed of the first run. Each of the following runs uses the next seed.
+    #[clap(long, default_value = "1")]
+    pub seed: u64,
+
+    /// Number of runs.
+    #[clap(long, default_value = "1")]
+    pub count: u64,
+
+    /// Number of compute nodes in the cluster.
+    #[clap(long, default_value = "3")]
+    pub compute_nodes: usize,
+
+    /// Number of compute node crashes in each run.
+    #[clap(long, default_value = "5")]
+    pub crashes: usize,
+
+    /// Maximum latency of RPC messages in milliseconds.
+    #[clap(long, default_value = "50")]
+    pub max_latency_ms: u64,
+
+    /// Maximum time in seconds to wait for a barrier to be committed after a crash.
+    #[clap(long, default_value = "120")]
+    pub recovery_timeout_secs: u64,
+}
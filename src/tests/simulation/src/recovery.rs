@@ -0,0 +1,67 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+use madsim::rand::{thread_rng, Rng};
+use tokio::time::Instant;
+
+use crate::cluster::Cluster;
+use crate::Opts;
+
+/// Crashes a random compute node at a time, and checks that barriers are committed again after
+/// the cluster recovers.
+pub async fn crash_recovery_test(opts: &Opts) {
+    let cluster = Cluster::start(opts.compute_nodes).await;
+    let timeout = Duration::from_secs(opts.recovery_timeout_secs);
+    wait_for_commit(&cluster, timeout).await;
+
+    for round in 0..opts.crashes {
+        let (idx, downtime) = {
+            let mut rng = thread_rng();
+            (
+                rng.gen_range(0..cluster.compute_node_count()),
+                Duration::from_millis(rng.gen_range(0..5000)),
+            )
+        };
+        tracing::info!(
+            "round {}: crash compute node {} for {:?}",
+            round,
+            idx,
+            downtime
+        );
+        cluster.kill_compute_node(idx);
+        tokio::time::sleep(downtime).await;
+        cluster.restart_compute_node(idx);
+        wait_for_commit(&cluster, timeout).await;
+    }
+}
+
+/// Waits until a new barrier is committed.
+async fn wait_for_commit(cluster: &Cluster, timeout: Duration) {
+    let epoch = cluster.max_committed_epoch().await;
+    let deadline = Instant::now() + timeout;
+    loop {
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        if cluster.max_committed_epoch().await > epoch {
+            return;
+        }
+        if Instant::now() >= deadline {
+            panic!(
+                "no barrier is committed in {:?} after epoch {}",
+                timeout, epoch
+            );
+        }
+    }
+}
@@ -12,16 +12,27 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+//! Logging of `RisingWave` nodes. Logs about streaming jobs and barriers carry the ids they're
+//! about as structured fields with the standard names, so that the logs of the same actor or epoch
+//! can be correlated across nodes: `actor_id` (`actor_ids` for lists), `fragment_id`, `epoch`,
+//! `table_id` and `worker_id`.
+
 #![feature(let_chains)]
 
 mod trace_runtime;
 
 use std::time::Duration;
 
+use parking_lot::{const_mutex, Mutex};
 use tracing::Level;
-use tracing_subscriber::filter;
 use tracing_subscriber::layer::SubscriberExt;
 use tracing_subscriber::prelude::*;
+use tracing_subscriber::{filter, reload};
+
+type FilterReloader = Box<dyn Fn(filter::Targets) -> Result<(), String> + Send + Sync>;
+
+/// Replaces the filter of the logs to stdout. Set once the logger is initialized.
+static FMT_FILTER_RELOADER: Mutex<Option<FilterReloader>> = const_mutex(None);
 
 /// Configure log targets for all `RisingWave` crates. When new crates are added and TRACE level
 /// logs are needed, add them here.
@@ -64,6 +75,44 @@ fn configure_risingwave_targets_fmt(targets: filter::Targets) -> filter::Targets
     // }
 }
 
+/// The default filter of the logs to stdout.
+fn default_fmt_filter() -> filter::Targets {
+    let filter = filter::Targets::new()
+        // Only enable WARN and ERROR for 3rd-party crates
+        .with_target("aws_endpoint", Level::WARN)
+        .with_target("hyper", Level::WARN)
+        .with_target("h2", Level::WARN)
+        .with_target("tower", Level::WARN)
+        .with_target("isahc", Level::WARN);
+
+    // Configure RisingWave's own crates to log at TRACE level, uncomment the following line if
+    // needed.
+
+    let filter = configure_risingwave_targets_fmt(filter);
+
+    // Enable DEBUG level for all other crates
+    // TODO: remove this in release mode
+    filter.with_default(Level::DEBUG)
+}
+
+/// Changes the filter of the logs to stdout at runtime. `directives` are comma-separated
+/// `target=level`s overriding the default levels of the targets, e.g.
+/// `events::stream::barrier=trace`. Empty directives restore the default filter. The directives are
+/// only validated if the logger isn't initialized by [`init_risingwave_logger`], e.g. in tests.
+pub fn set_log_filter(directives: &str) -> Result<(), String> {
+    let mut filter = default_fmt_filter();
+    if !directives.trim().is_empty() {
+        let overrides: filter::Targets = directives
+            .parse()
+            .map_err(|e| format!("invalid log filter \"{}\": {}", directives, e))?;
+        filter = filter.with_targets(overrides);
+    }
+    match FMT_FILTER_RELOADER.lock().as_ref() {
+        Some(reload) => reload(filter),
+        None => Ok(()),
+    }
+}
+
 /// Init logger for RisingWave binaries.
 pub fn init_risingwave_logger(enable_jaeger_tracing: bool, colorful: bool) {
     use std::panic;
@@ -82,24 +131,13 @@ pub fn init_risingwave_logger(enable_jaeger_tracing: bool, colorful: bool) {
         let fmt_layer = tracing_subscriber::fmt::layer()
             .compact()
             .with_ansi(colorful);
-        let filter = filter::Targets::new()
-            // Only enable WARN and ERROR for 3rd-party crates
-            .with_target("aws_endpoint", Level::WARN)
-            .with_target("hyper", Level::WARN)
-            .with_target("h2", Level::WARN)
-            .with_target("tower", Level::WARN)
-            .with_target("isahc", Level::WARN);
-
-        // Configure RisingWave's own crates to log at TRACE level, uncomment the following line if
-        // needed.
-
-        let filter = configure_risingwave_targets_fmt(filter);
-
-        // Enable DEBUG level for all other crates
-        // TODO: remove this in release mode
-        let filter = filter.with_default(Level::DEBUG);
-
-        fmt_layer.with_filter(filter)
+        let (fmt_layer, handle) = reload::Layer::new(fmt_layer.with_filter(default_fmt_filter()));
+        *FMT_FILTER_RELOADER.lock() = Some(Box::new(move |filter| {
+            handle
+                .modify(|layer| *layer.filter_mut() = filter)
+                .map_err(|e| e.to_string())
+        }));
+        fmt_layer
     };
 
     if enable_jaeger_tracing {
@@ -14,11 +14,15 @@
 
 use thiserror::Error;
 
+use crate::pg_server::IdleTimeout;
+
 /// Error type used in pgwire crates.
 #[derive(Error, Debug)]
 pub enum PsqlError {
     #[error("Encode error {0}.")]
     CancelError(String),
+    #[error("terminating connection due to {0} timeout")]
+    IdleTimeout(&'static str),
 }
 
 impl PsqlError {
@@ -26,4 +30,12 @@ impl PsqlError {
     pub fn cancel() -> Self {
         PsqlError::CancelError("ERROR:  canceling statement due to user request".to_string())
     }
+
+    /// Construct the error sent before terminating a session idle for longer than `timeout`.
+    pub fn idle_timeout(timeout: IdleTimeout) -> Self {
+        match timeout {
+            IdleTimeout::InTransaction(_) => PsqlError::IdleTimeout("idle-in-transaction"),
+            IdleTimeout::Session(_) => PsqlError::IdleTimeout("idle-session"),
+        }
+    }
 }
@@ -0,0 +1,128 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use bytes::{BufMut, BytesMut};
+
+/// The format of the rows sent to the client by `COPY ... TO STDOUT`. Each row is a line.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CopyFormat {
+    /// Values separated by `delimiter`, where nulls are `\N` and special characters are escaped
+    /// by backslashes.
+    Text { delimiter: u8 },
+    /// Values separated by `delimiter`, where nulls are empty and values are quoted if needed.
+    /// The names of the columns are sent as the first line if `header` is set.
+    Csv { delimiter: u8, header: bool },
+}
+
+impl Default for CopyFormat {
+    fn default() -> Self {
+        CopyFormat::Text { delimiter: b'\t' }
+    }
+}
+
+impl CopyFormat {
+    /// Returns whether the names of the columns are sent before the rows.
+    pub fn has_header(&self) -> bool {
+        matches!(self, CopyFormat::Csv { header: true, .. })
+    }
+
+    /// Encodes a row as a line into `buf`.
+    pub fn encode_row<'a>(
+        &self,
+        values: impl IntoIterator<Item = Option<&'a str>>,
+        buf: &mut BytesMut,
+    ) {
+        for (i, value) in values.into_iter().enumerate() {
+            match self {
+                CopyFormat::Text { delimiter } => {
+                    if i > 0 {
+                        buf.put_u8(*delimiter);
+                    }
+                    match value {
+                        Some(value) => encode_text_value(value, *delimiter, buf),
+                        None => buf.put_slice(b"\\N"),
+                    }
+                }
+                CopyFormat::Csv { delimiter, .. } => {
+                    if i > 0 {
+                        buf.put_u8(*delimiter);
+                    }
+                    if let Some(value) = value {
+                        encode_csv_value(value, *delimiter, buf);
+                    }
+                }
+            }
+        }
+        buf.put_u8(b'\n');
+    }
+}
+
+fn encode_text_value(value: &str, delimiter: u8, buf: &mut BytesMut) {
+    for &byte in value.as_bytes() {
+        match byte {
+            b'\\' => buf.put_slice(b"\\\\"),
+            b'\n' => buf.put_slice(b"\\n"),
+            b'\r' => buf.put_slice(b"\\r"),
+            b'\t' if delimiter == b'\t' => buf.put_slice(b"\\t"),
+            _ if byte == delimiter => buf.put_slice(&[b'\\', delimiter]),
+            _ => buf.put_u8(byte),
+        }
+    }
+}
+
+/// Quotes the value if it's empty, which tells it from a null, or contains any special character.
+fn encode_csv_value(value: &str, delimiter: u8, buf: &mut BytesMut) {
+    let needs_quote = value.is_empty()
+        || value == "\\."
+        || value
+            .bytes()
+            .any(|byte| matches!(byte, b'"' | b'\n' | b'\r') || byte == delimiter);
+    if !needs_quote {
+        buf.put_slice(value.as_bytes());
+        return;
+    }
+    buf.put_u8(b'"');
+    for &byte in value.as_bytes() {
+        if byte == b'"' {
+            buf.put_u8(b'"');
+        }
+        buf.put_u8(byte);
+    }
+    buf.put_u8(b'"');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode(format: &CopyFormat, values: &[Option<&str>]) -> String {
+        let mut buf = BytesMut::new();
+        format.encode_row(values.iter().copied(), &mut buf);
+        String::from_utf8(buf.to_vec()).unwrap()
+    }
+
+    #[test]
+    fn test_encode_row() {
+        let values = [Some("a\tb"), None, Some(""), Some("c,\"d\"\n")];
+        assert_eq!(
+            encode(&CopyFormat::default(), &values),
+            "a\\tb\t\\N\t\tc,\"d\"\\n\n"
+        );
+        let csv = CopyFormat::Csv {
+            delimiter: b',',
+            header: false,
+        };
+        assert_eq!(encode(&csv, &values), "a\tb,,\"\",\"c,\"\"d\"\"\n\"\n");
+    }
+}
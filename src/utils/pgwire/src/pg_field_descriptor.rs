@@ -12,6 +12,10 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::io::{Error, ErrorKind, Result};
+
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+
 /// Port from PgFieldDescriptor.java
 #[derive(Debug, Clone)]
 pub struct PgFieldDescriptor {
@@ -85,6 +89,10 @@ impl PgFieldDescriptor {
     pub fn get_format_code(&self) -> i16 {
         self.format_code
     }
+
+    pub fn set_format_code(&mut self, format_code: i16) {
+        self.format_code = format_code;
+    }
 }
 
 #[derive(Debug, Copy, Clone)]
@@ -122,4 +130,104 @@ impl TypeOid {
             TypeOid::Decimal => 1231,
         }
     }
+
+    pub fn from_number(oid: i32) -> Result<TypeOid> {
+        match oid {
+            16 => Ok(TypeOid::Boolean),
+            20 => Ok(TypeOid::BigInt),
+            21 => Ok(TypeOid::SmallInt),
+            23 => Ok(TypeOid::Int),
+            700 => Ok(TypeOid::Float4),
+            701 => Ok(TypeOid::Float8),
+            1002 => Ok(TypeOid::CharArray),
+            // `text` is treated as `varchar`.
+            25 | 1043 => Ok(TypeOid::Varchar),
+            1082 => Ok(TypeOid::Date),
+            1083 => Ok(TypeOid::Time),
+            1114 => Ok(TypeOid::Timestamp),
+            1184 => Ok(TypeOid::Timestampz),
+            1231 | 1700 => Ok(TypeOid::Decimal),
+            _ => Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("Unsupported type oid: {}", oid),
+            )),
+        }
+    }
+
+    /// Converts a value in binary format to text format.
+    pub fn decode_binary(&self, mut value: &[u8]) -> Result<String> {
+        let actual_len = value.len();
+        let expect_len = |len: usize| {
+            if actual_len == len {
+                Ok(())
+            } else {
+                Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("Invalid binary length {} of {:?}", actual_len, self),
+                ))
+            }
+        };
+        match self {
+            TypeOid::Boolean => {
+                expect_len(1)?;
+                Ok(if value[0] != 0 { "t" } else { "f" }.to_string())
+            }
+            TypeOid::SmallInt => {
+                expect_len(2)?;
+                Ok(value.get_i16().to_string())
+            }
+            TypeOid::Int => {
+                expect_len(4)?;
+                Ok(value.get_i32().to_string())
+            }
+            TypeOid::BigInt => {
+                expect_len(8)?;
+                Ok(value.get_i64().to_string())
+            }
+            TypeOid::Float4 => {
+                expect_len(4)?;
+                Ok(value.get_f32().to_string())
+            }
+            TypeOid::Float8 => {
+                expect_len(8)?;
+                Ok(value.get_f64().to_string())
+            }
+            TypeOid::Varchar => String::from_utf8(value.to_vec())
+                .map_err(|e| Error::new(ErrorKind::InvalidInput, e)),
+            _ => Err(self.binary_unsupported()),
+        }
+    }
+
+    /// Converts a value in text format to binary format.
+    pub fn encode_binary(&self, value: &str) -> Result<Bytes> {
+        let invalid = |e: String| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                format!("Invalid value {:?} of {:?}: {}", value, self, e),
+            )
+        };
+        let mut buf = BytesMut::new();
+        match self {
+            TypeOid::Boolean => match value {
+                "t" | "true" => buf.put_u8(1),
+                "f" | "false" => buf.put_u8(0),
+                _ => return Err(invalid("not a boolean".to_string())),
+            },
+            TypeOid::SmallInt => buf.put_i16(value.parse().map_err(|e| invalid(format!("{}", e)))?),
+            TypeOid::Int => buf.put_i32(value.parse().map_err(|e| invalid(format!("{}", e)))?),
+            TypeOid::BigInt => buf.put_i64(value.parse().map_err(|e| invalid(format!("{}", e)))?),
+            TypeOid::Float4 => buf.put_f32(value.parse().map_err(|e| invalid(format!("{}", e)))?),
+            TypeOid::Float8 => buf.put_f64(value.parse().map_err(|e| invalid(format!("{}", e)))?),
+            TypeOid::Varchar => buf.put_slice(value.as_bytes()),
+            _ => return Err(self.binary_unsupported()),
+        }
+        Ok(buf.freeze())
+    }
+
+    fn binary_unsupported(&self) -> Error {
+        Error::new(
+            ErrorKind::InvalidInput,
+            format!("Binary format of {:?} is not supported", self),
+        )
+    }
 }
@@ -21,7 +21,7 @@ use byteorder::{BigEndian, ByteOrder};
 use bytes::{Buf, BufMut, Bytes, BytesMut};
 use tokio::io::{AsyncRead, AsyncReadExt};
 
-use crate::pg_field_descriptor::PgFieldDescriptor;
+use crate::pg_field_descriptor::{PgFieldDescriptor, TypeOid};
 use crate::pg_response::StatementType;
 use crate::pg_server::BoxedError;
 use crate::types::Row;
@@ -35,6 +35,8 @@ pub enum FeMessage {
     Describe(FeDescribeMessage),
     Bind(FeBindMessage),
     Execute(FeExecuteMessage),
+    Close(FeCloseMessage),
+    Password(FePasswordMessage),
     Sync,
     CancelQuery,
     Terminate,
@@ -68,47 +70,100 @@ pub struct FeQueryMessage {
 }
 
 #[derive(Debug)]
-pub struct FeBindMessage {}
+pub struct FeBindMessage {
+    pub portal_name: Bytes,
+    pub statement_name: Bytes,
+    /// Format codes of the parameters, where 0 is text and 1 is binary. An empty list means all
+    /// in text, and a single code applies to all the parameters.
+    pub param_format_codes: Vec<i16>,
+    /// Values of the parameters. `None` denotes NULL.
+    pub params: Vec<Option<Bytes>>,
+    /// Format codes of the result columns, with the same convention as `param_format_codes`.
+    pub result_format_codes: Vec<i16>,
+}
 
 #[derive(Debug)]
 pub struct FeExecuteMessage {
+    pub portal_name: Bytes,
+    /// Maximum number of rows to return. Zero denotes "no limit".
     pub max_rows: i32,
 }
 
+#[derive(Debug)]
+pub struct FeCloseMessage {
+    // 'S' to close a prepared statement; or 'P' to close a portal.
+    pub kind: u8,
+    pub name: Bytes,
+}
+
+/// The password sent by the client in the authentication, in clear text or encrypted with MD5.
+#[derive(Debug)]
+pub struct FePasswordMessage {
+    pub password: Bytes,
+}
+
 #[derive(Debug)]
 pub struct FeParseMessage {
+    pub statement_name: Bytes,
     pub query_string: Bytes,
+    /// Type oids of the parameters. 0 means the type is unspecified.
+    pub type_ids: Vec<i32>,
 }
 
 #[derive(Debug)]
 pub struct FeDescribeMessage {
     // 'S' to describe a prepared statement; or 'P' to describe a portal.
     pub kind: u8,
+    pub name: Bytes,
+}
+
+impl FePasswordMessage {
+    pub fn parse(mut buf: Bytes) -> Result<FeMessage> {
+        let password = read_null_terminated(&mut buf)?;
+
+        Ok(FeMessage::Password(FePasswordMessage { password }))
+    }
 }
 
 impl FeDescribeMessage {
     pub fn parse(mut buf: Bytes) -> Result<FeMessage> {
         let kind = buf.get_u8();
-        let _pstmt_name = read_null_terminated(&mut buf)?;
-
-        if kind != b'S' {
-            unimplemented!("only prepared statement Describe is implemented");
-        }
+        let name = read_null_terminated(&mut buf)?;
 
-        Ok(FeMessage::Describe(FeDescribeMessage { kind }))
+        Ok(FeMessage::Describe(FeDescribeMessage { kind, name }))
     }
 }
 
 impl FeBindMessage {
     pub fn parse(mut buf: Bytes) -> Result<FeMessage> {
         let portal_name = read_null_terminated(&mut buf)?;
-        let _pstmt_name = read_null_terminated(&mut buf)?;
+        let statement_name = read_null_terminated(&mut buf)?;
 
-        if !portal_name.is_empty() {
-            unimplemented!("named portals not implemented");
-        }
+        let param_format_len = buf.get_i16();
+        let param_format_codes = (0..param_format_len).map(|_| buf.get_i16()).collect();
 
-        Ok(FeMessage::Bind(FeBindMessage {}))
+        let params_len = buf.get_i16();
+        let params = (0..params_len)
+            .map(|_| {
+                let len = buf.get_i32();
+                if len < 0 {
+                    None
+                } else {
+                    Some(buf.split_to(len as usize))
+                }
+            })
+            .collect();
+
+        let result_format_len = buf.get_i16();
+        let result_format_codes = (0..result_format_len).map(|_| buf.get_i16()).collect();
+
+        Ok(FeMessage::Bind(FeBindMessage {
+            portal_name,
+            statement_name,
+            param_format_codes,
+            params,
+            result_format_codes,
+        }))
     }
 }
 
@@ -117,29 +172,34 @@ impl FeExecuteMessage {
         let portal_name = read_null_terminated(&mut buf)?;
         let max_rows = buf.get_i32();
 
-        if !portal_name.is_empty() {
-            unimplemented!("named portals not implemented");
-        }
+        Ok(FeMessage::Execute(FeExecuteMessage {
+            portal_name,
+            max_rows,
+        }))
+    }
+}
 
-        if max_rows != 0 {
-            unimplemented!("row limit in Execute message not supported");
-        }
+impl FeCloseMessage {
+    pub fn parse(mut buf: Bytes) -> Result<FeMessage> {
+        let kind = buf.get_u8();
+        let name = read_null_terminated(&mut buf)?;
 
-        Ok(FeMessage::Execute(FeExecuteMessage { max_rows }))
+        Ok(FeMessage::Close(FeCloseMessage { kind, name }))
     }
 }
 
 impl FeParseMessage {
     pub fn parse(mut buf: Bytes) -> Result<FeMessage> {
-        let _pstmt_name = read_null_terminated(&mut buf)?;
+        let statement_name = read_null_terminated(&mut buf)?;
         let query_string = read_null_terminated(&mut buf)?;
         let nparams = buf.get_i16();
+        let type_ids = (0..nparams).map(|_| buf.get_i32()).collect();
 
-        if nparams != 0 {
-            unimplemented!("query params not implemented");
-        }
-
-        Ok(FeMessage::Parse(FeParseMessage { query_string }))
+        Ok(FeMessage::Parse(FeParseMessage {
+            statement_name,
+            query_string,
+            type_ids,
+        }))
     }
 }
 
@@ -179,6 +239,8 @@ impl FeMessage {
             b'D' => FeDescribeMessage::parse(sql_bytes),
             b'B' => FeBindMessage::parse(sql_bytes),
             b'E' => FeExecuteMessage::parse(sql_bytes),
+            b'C' => FeCloseMessage::parse(sql_bytes),
+            b'p' => FePasswordMessage::parse(sql_bytes),
             b'S' => Ok(FeMessage::Sync),
             b'X' => Ok(FeMessage::Terminate),
             _ => Err(std::io::Error::new(
@@ -242,19 +304,30 @@ fn read_null_terminated(buf: &mut Bytes) -> Result<Bytes> {
 #[derive(Debug)]
 pub enum BeMessage<'a> {
     AuthenticationOk,
+    AuthenticationCleartextPassword,
+    AuthenticationMD5Password(&'a [u8; 4]),
     CommandComplete(BeCommandCompleteMessage),
     // Single byte - used in response to SSLRequest/GSSENCRequest.
-    EncryptionResponse,
+    EncryptionResponseNo,
+    EncryptionResponseSsl,
     EmptyQueryResponse,
     ParseComplete,
     BindComplete,
-    ParameterDescription,
+    CloseComplete,
+    PortalSuspended,
+    ParameterDescription(&'a [TypeOid]),
     NoData,
     DataRow(&'a Row),
+    /// A row whose values are already encoded, e.g. in binary format.
+    EncodedDataRow(&'a [Option<Bytes>]),
     ParameterStatus(BeParameterStatusMessage<'a>),
     ReadyForQuery,
     RowDescription(&'a [PgFieldDescriptor]),
     ErrorResponse(BoxedError),
+    /// Starts to copy the rows of the given number of columns in text format to the client.
+    CopyOutResponse(usize),
+    CopyData(&'a [u8]),
+    CopyDone,
 }
 
 #[derive(Debug)]
@@ -285,6 +358,27 @@ impl<'a> BeMessage<'a> {
                 buf.put_i32(0);
             }
 
+            // AuthenticationCleartextPassword
+            // +-----+----------+-----------+
+            // | 'R' | int32(8) | int32(3)  |
+            // +-----+----------+-----------+
+            BeMessage::AuthenticationCleartextPassword => {
+                buf.put_u8(b'R');
+                buf.put_i32(8);
+                buf.put_i32(3);
+            }
+
+            // AuthenticationMD5Password
+            // +-----+-----------+-----------+----------------+
+            // | 'R' | int32(12) | int32(5)  | byte4(salt)    |
+            // +-----+-----------+-----------+----------------+
+            BeMessage::AuthenticationMD5Password(salt) => {
+                buf.put_u8(b'R');
+                buf.put_i32(12);
+                buf.put_i32(5);
+                buf.put_slice(&salt[..]);
+            }
+
             // ParameterStatus
             // +-----+-----------+----------+------+-----------+------+
             // | 'S' | int32 len | str name | '\0' | str value | '\0' |
@@ -379,6 +473,22 @@ impl<'a> BeMessage<'a> {
                 })
                 .unwrap();
             }
+            BeMessage::EncodedDataRow(vals) => {
+                buf.put_u8(b'D');
+                write_body(buf, |buf| {
+                    buf.put_u16(vals.len() as u16); // num of cols
+                    for val_opt in vals.iter() {
+                        if let Some(val) = val_opt {
+                            buf.put_u32(val.len() as u32);
+                            buf.put_slice(val);
+                        } else {
+                            buf.put_i32(-1);
+                        }
+                    }
+                    Ok(())
+                })
+                .unwrap();
+            }
             // RowDescription
             // +-----+-----------+--------------+-------+-----+-------+
             // | 'T' | int32 len | int16 colNum | field | ... | field |
@@ -429,25 +539,82 @@ impl<'a> BeMessage<'a> {
                 write_body(buf, |_| Ok(()))?;
             }
 
-            BeMessage::ParameterDescription => {
+            BeMessage::CloseComplete => {
+                buf.put_u8(b'3');
+                write_body(buf, |_| Ok(()))?;
+            }
+
+            // PortalSuspended
+            // +-----+----------+
+            // | 's' | int32(4) |
+            // +-----+----------+
+            BeMessage::PortalSuspended => {
+                buf.put_u8(b's');
+                write_body(buf, |_| Ok(()))?;
+            }
+
+            // ParameterDescription
+            // +-----+-----------+----------------+-----------+-----+-----------+
+            // | 't' | int32 len | int16 paramNum | int32 oid | ... | int32 oid |
+            // +-----+-----------+----------------+-----------+-----+-----------+
+            BeMessage::ParameterDescription(param_types) => {
                 buf.put_u8(b't');
                 write_body(buf, |buf| {
-                    // we don't support params, so always 0
-                    buf.put_i16(0);
+                    buf.put_i16(param_types.len() as i16);
+                    for param_type in param_types.iter() {
+                        buf.put_i32(param_type.as_number());
+                    }
                     Ok(())
                 })
                 .unwrap();
             }
 
+            // CopyOutResponse
+            // +-----+-----------+--------------+--------------+--------------+-----+
+            // | 'H' | int32 len | int8 format  | int16 colNum | int16 format | ... |
+            // +-----+-----------+--------------+--------------+--------------+-----+
+            BeMessage::CopyOutResponse(columns) => {
+                buf.put_u8(b'H');
+                write_body(buf, |buf| {
+                    buf.put_i8(0); // text format
+                    buf.put_i16(*columns as i16);
+                    for _ in 0..*columns {
+                        buf.put_i16(0);
+                    }
+                    Ok(())
+                })?;
+            }
+
+            // CopyData
+            // +-----+-----------+-------+
+            // | 'd' | int32 len | bytes |
+            // +-----+-----------+-------+
+            BeMessage::CopyData(data) => {
+                buf.put_u8(b'd');
+                write_body(buf, |buf| {
+                    buf.put_slice(data);
+                    Ok(())
+                })?;
+            }
+
+            BeMessage::CopyDone => {
+                buf.put_u8(b'c');
+                write_body(buf, |_| Ok(()))?;
+            }
+
             BeMessage::NoData => {
                 buf.put_u8(b'n');
                 write_body(buf, |_| Ok(())).unwrap();
             }
 
-            BeMessage::EncryptionResponse => {
+            BeMessage::EncryptionResponseNo => {
                 buf.put_u8(b'N');
             }
 
+            BeMessage::EncryptionResponseSsl => {
+                buf.put_u8(b'S');
+            }
+
             // EmptyQueryResponse
             // +-----+----------+
             // | 'I' | int32(4) |
@@ -12,19 +12,31 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
 use std::io::{Error as IoError, ErrorKind, Result};
+use std::pin::Pin;
 use std::sync::Arc;
+use std::task::{Context, Poll};
 
 use bytes::{Bytes, BytesMut};
-use tokio::io::{AsyncRead, AsyncWrite, AsyncWriteExt};
+use futures::StreamExt;
+use openssl::ssl::{Ssl, SslAcceptor};
+use tokio::io::{AsyncRead, AsyncWrite, AsyncWriteExt, ReadBuf};
+use tokio_openssl::SslStream;
 
 use crate::error::PsqlError;
+use crate::pg_copy::CopyFormat;
 use crate::pg_field_descriptor::{PgFieldDescriptor, TypeOid};
 use crate::pg_message::{
-    BeCommandCompleteMessage, BeMessage, BeParameterStatusMessage, FeMessage, FeStartupMessage,
+    BeCommandCompleteMessage, BeMessage, BeParameterStatusMessage, FeBindMessage, FeMessage,
+    FeParseMessage, FePasswordMessage, FeStartupMessage,
 };
 use crate::pg_response::PgResponse;
-use crate::pg_server::{Session, SessionManager};
+use crate::pg_server::{
+    BoxedError, ConnectionCounter, ConnectionPermit, Session, SessionManager, StatementDesc,
+    UserAuthenticator,
+};
+use crate::types::Row;
 
 /// The state machine for each psql connection.
 /// Read pg messages from tcp stream and write results back.
@@ -32,8 +44,16 @@ pub struct PgProtocol<S, SM>
 where
     SM: SessionManager,
 {
-    /// Used for write/read message in tcp connection.
-    stream: S,
+    /// Used for write/read message in tcp connection. It's only taken while being upgraded to TLS.
+    stream: Option<PgStream<S>>,
+    /// Upgrades the connection to TLS on the request of the client if set.
+    tls_acceptor: Option<Arc<SslAcceptor>>,
+    connection_counter: Arc<ConnectionCounter>,
+    /// Counts the connection once the user is authenticated, so that clients failing to
+    /// authenticate can't use up the connections of a user.
+    _connection_permit: Option<ConnectionPermit>,
+    /// The user given in the startup message.
+    user_name: String,
     /// Write into buffer before flush to stream.
     buf_out: BytesMut,
     /// Current states of pg connection.
@@ -43,11 +63,91 @@ where
 
     session_mgr: Arc<SM>,
     session: Option<Arc<SM::Session>>,
+
+    /// Prepared statements created by `Parse`, keyed by the statement name. The statements
+    /// themselves are kept by the session.
+    statements: HashMap<Bytes, StatementDesc>,
+    /// Portals created by `Bind`, keyed by the portal name.
+    portals: HashMap<Bytes, Portal>,
+    /// Whether an error occurred in the extended query protocol, in which case the following
+    /// messages are discarded until a `Sync`.
+    ignore_until_sync: bool,
+}
+
+/// A portal is a prepared statement bound with parameters. A query can be executed by several
+/// `Execute` messages with row limits, in which case the portal keeps the result between them.
+struct Portal {
+    statement_name: String,
+    /// Parameter values in text format.
+    params: Vec<Option<String>>,
+    /// Format code of each result column, where 0 is text and 1 is binary.
+    result_formats: Vec<i16>,
+    /// Result columns with the requested format codes.
+    row_desc: Vec<PgFieldDescriptor>,
+    /// The result of the statement, if it has been executed and some of its rows have not been
+    /// sent.
+    result: Option<PortalResult>,
+}
+
+struct PortalResult {
+    result: PgResponse,
+    /// Rows taken from the result stream but not sent yet.
+    pending_rows: std::vec::IntoIter<Row>,
+}
+
+/// The stream of a connection, which is encrypted if the client requests so.
+enum PgStream<S> {
+    Unencrypted(S),
+    Ssl(SslStream<S>),
+}
+
+impl<S> AsyncRead for PgStream<S>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<Result<()>> {
+        match self.get_mut() {
+            PgStream::Unencrypted(stream) => Pin::new(stream).poll_read(cx, buf),
+            PgStream::Ssl(stream) => Pin::new(stream).poll_read(cx, buf),
+        }
+    }
+}
+
+impl<S> AsyncWrite for PgStream<S>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<Result<usize>> {
+        match self.get_mut() {
+            PgStream::Unencrypted(stream) => Pin::new(stream).poll_write(cx, buf),
+            PgStream::Ssl(stream) => Pin::new(stream).poll_write(cx, buf),
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        match self.get_mut() {
+            PgStream::Unencrypted(stream) => Pin::new(stream).poll_flush(cx),
+            PgStream::Ssl(stream) => Pin::new(stream).poll_flush(cx),
+        }
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        match self.get_mut() {
+            PgStream::Unencrypted(stream) => Pin::new(stream).poll_shutdown(cx),
+            PgStream::Ssl(stream) => Pin::new(stream).poll_shutdown(cx),
+        }
+    }
 }
 
 /// States flow happened from top to down.
 enum PgProtocolState {
     Startup,
+    /// Waiting for the password of the user.
+    Authentication,
     Regular,
 }
 
@@ -67,27 +167,58 @@ where
     S: AsyncWrite + AsyncRead + Unpin,
     SM: SessionManager,
 {
-    pub fn new(stream: S, session_mgr: Arc<SM>) -> Self {
+    pub fn new(
+        stream: S,
+        session_mgr: Arc<SM>,
+        tls_acceptor: Option<Arc<SslAcceptor>>,
+        connection_counter: Arc<ConnectionCounter>,
+    ) -> Self {
         Self {
-            stream,
+            stream: Some(PgStream::Unencrypted(stream)),
+            tls_acceptor,
+            connection_counter,
+            _connection_permit: None,
+            user_name: String::new(),
             is_terminate: false,
             state: PgProtocolState::Startup,
             buf_out: BytesMut::with_capacity(10 * 1024),
             session_mgr,
             session: None,
+            statements: HashMap::new(),
+            portals: HashMap::new(),
+            ignore_until_sync: false,
         }
     }
 
-    pub async fn process(&mut self, unnamed_query_string: &mut Bytes) -> Result<bool> {
-        if self.do_process(unnamed_query_string).await? {
+    pub async fn process(&mut self) -> Result<bool> {
+        if self.do_process().await? {
             return Ok(true);
         }
 
         Ok(self.is_terminate())
     }
 
-    async fn do_process(&mut self, unnamed_query_string: &mut Bytes) -> Result<bool> {
-        let msg = match self.read_message().await {
+    async fn do_process(&mut self) -> Result<bool> {
+        let idle_timeout = match self.state {
+            PgProtocolState::Regular => self.session.as_ref().unwrap().idle_timeout(),
+            _ => None,
+        };
+        let msg = match idle_timeout {
+            Some(idle_timeout) => {
+                match tokio::time::timeout(idle_timeout.duration(), self.read_message()).await {
+                    Ok(msg) => msg,
+                    Err(_) => {
+                        let e = PsqlError::idle_timeout(idle_timeout);
+                        tracing::info!("{}", e);
+                        self.write_message_no_flush(&BeMessage::ErrorResponse(Box::new(e)))?;
+                        self.flush().await?;
+                        return Ok(true);
+                    }
+                }
+            }
+            None => self.read_message().await,
+        };
+        let msg = match msg {
             Ok(msg) => msg,
             Err(e) => {
                 if e.kind() == std::io::ErrorKind::UnexpectedEof {
@@ -99,14 +230,29 @@ where
                 return Ok(false);
             }
         };
+        // Only the password is expected before the user is authenticated.
+        if matches!(self.state, PgProtocolState::Authentication)
+            && !matches!(msg, FeMessage::Password(_))
+        {
+            self.write_message_no_flush(&BeMessage::ErrorResponse(Box::new(IoError::new(
+                ErrorKind::InvalidInput,
+                "expected password message",
+            ))))?;
+            self.flush().await?;
+            return Ok(true);
+        }
         match msg {
-            FeMessage::Ssl => {
-                self.write_message_no_flush(&BeMessage::EncryptionResponse)
-                    .map_err(|e| {
+            FeMessage::Ssl => match self.tls_acceptor.clone() {
+                Some(tls_acceptor) => {
+                    self.write_message(&BeMessage::EncryptionResponseSsl)
+                        .await?;
+                    if let Err(e) = self.start_tls(&tls_acceptor).await {
                         tracing::error!("failed to handle ssl request: {}", e);
-                        e
-                    })?;
-            }
+                        return Ok(true);
+                    }
+                }
+                None => self.write_message_no_flush(&BeMessage::EncryptionResponseNo)?,
+            },
             FeMessage::Startup(msg) => {
                 if let Err(e) = self.process_startup_msg(msg) {
                     tracing::error!("failed to set up pg session: {}", e);
@@ -114,11 +260,20 @@ where
                     self.flush().await?;
                     return Ok(true);
                 }
-                self.state = PgProtocolState::Regular;
+            }
+            FeMessage::Password(msg) => {
+                if let Err(e) = self.process_password_msg(msg) {
+                    tracing::error!("failed to authenticate user: {}", e);
+                    self.write_message_no_flush(&BeMessage::ErrorResponse(Box::new(e)))?;
+                    self.flush().await?;
+                    return Ok(true);
+                }
             }
             FeMessage::Query(query_msg) => {
-                self.process_query_msg(query_msg.get_sql(), false).await?;
+                self.process_query_msg(query_msg.get_sql()).await?;
                 self.write_message_no_flush(&BeMessage::ReadyForQuery)?;
+                // Errors in a simple query don't affect the following messages.
+                self.ignore_until_sync = false;
             }
             FeMessage::CancelQuery => {
                 self.write_message_no_flush(&BeMessage::ErrorResponse(Box::new(
@@ -128,30 +283,76 @@ where
             FeMessage::Terminate => {
                 self.process_terminate();
             }
-            FeMessage::Parse(m) => {
-                *unnamed_query_string = m.query_string;
-                self.write_message(&BeMessage::ParseComplete).await?;
-            }
-            FeMessage::Bind(_) => {
-                self.write_message(&BeMessage::BindComplete).await?;
-            }
-            FeMessage::Execute(_) => {
-                self.process_query_msg(cstr_to_str(unnamed_query_string), true)
-                    .await?;
+            FeMessage::Parse(_)
+            | FeMessage::Bind(_)
+            | FeMessage::Describe(_)
+            | FeMessage::Execute(_)
+            | FeMessage::Close(_)
+                if self.ignore_until_sync => {}
+            FeMessage::Parse(m) => match self.process_parse_msg(m) {
+                Ok(()) => self.write_message_no_flush(&BeMessage::ParseComplete)?,
+                Err(e) => self.write_error(e)?,
+            },
+            FeMessage::Bind(m) => match self.process_bind_msg(m) {
+                Ok(()) => self.write_message_no_flush(&BeMessage::BindComplete)?,
+                Err(e) => self.write_error(Box::new(e))?,
+            },
+            FeMessage::Execute(m) => {
+                match self.portals.remove(&m.portal_name) {
+                    Some(portal) => {
+                        self.process_execute_msg(m.portal_name, portal, m.max_rows)
+                            .await?
+                    }
+                    None => {
+                        self.write_error(Box::new(not_exist_error("portal", &m.portal_name)))?
+                    }
+                }
                 // NOTE there is no ReadyForQuery message.
             }
-            FeMessage::Describe(_) => {
-                self.write_message_no_flush(&BeMessage::ParameterDescription)?;
-                // FIXME: Introduce parser to analyze statements and bind data type. Here just
-                // hard-code a VARCHAR.
-                self.write_message(&BeMessage::RowDescription(&[PgFieldDescriptor::new(
-                    "VARCHAR".to_string(),
-                    TypeOid::Varchar,
-                )]))
-                .await?;
+            FeMessage::Close(m) => {
+                if m.kind == b'S' {
+                    self.statements.remove(&m.name);
+                    self.session
+                        .as_ref()
+                        .unwrap()
+                        .close_statement(&String::from_utf8_lossy(&m.name));
+                } else {
+                    self.portals.remove(&m.name);
+                }
+                self.write_message_no_flush(&BeMessage::CloseComplete)?;
+            }
+            FeMessage::Describe(m) => {
+                let described = if m.kind == b'S' {
+                    self.statements
+                        .get(&m.name)
+                        .map(|desc| (Some(desc.param_types.clone()), desc.row_desc.clone()))
+                        .ok_or_else(|| not_exist_error("prepared statement", &m.name))
+                } else {
+                    self.portals
+                        .get(&m.name)
+                        .map(|portal| (None, portal.row_desc.clone()))
+                        .ok_or_else(|| not_exist_error("portal", &m.name))
+                };
+                match described {
+                    Ok((param_types, row_desc)) => {
+                        // Only describing a statement returns the parameters.
+                        if let Some(param_types) = param_types {
+                            self.write_message_no_flush(&BeMessage::ParameterDescription(
+                                &param_types,
+                            ))?;
+                        }
+                        if row_desc.is_empty() {
+                            self.write_message_no_flush(&BeMessage::NoData)?;
+                        } else {
+                            self.write_message_no_flush(&BeMessage::RowDescription(&row_desc))?;
+                        }
+                    }
+                    Err(e) => self.write_error(Box::new(e))?,
+                }
             }
             FeMessage::Sync => {
-                self.write_message(&BeMessage::ReadyForQuery).await?;
+                self.ignore_until_sync = false;
+                self.write_message_no_flush(&BeMessage::ReadyForQuery)?;
             }
         }
         self.flush().await?;
@@ -159,12 +360,36 @@ where
     }
 
     async fn read_message(&mut self) -> Result<FeMessage> {
+        let stream = self.stream.as_mut().unwrap();
         match self.state {
-            PgProtocolState::Startup => FeStartupMessage::read(&mut self.stream).await,
-            PgProtocolState::Regular => FeMessage::read(&mut self.stream).await,
+            PgProtocolState::Startup => FeStartupMessage::read(stream).await,
+            PgProtocolState::Authentication | PgProtocolState::Regular => {
+                FeMessage::read(stream).await
+            }
         }
     }
 
+    /// Upgrades the connection to TLS. The connection should be terminated on failure.
+    async fn start_tls(&mut self, tls_acceptor: &SslAcceptor) -> Result<()> {
+        let stream = match self.stream.take() {
+            Some(PgStream::Unencrypted(stream)) => stream,
+            _ => {
+                return Err(IoError::new(
+                    ErrorKind::InvalidInput,
+                    "the connection is encrypted already",
+                ))
+            }
+        };
+        let ssl = Ssl::new(tls_acceptor.context()).map_err(IoError::other)?;
+        let mut stream = SslStream::new(ssl, stream).map_err(IoError::other)?;
+        Pin::new(&mut stream)
+            .accept()
+            .await
+            .map_err(IoError::other)?;
+        self.stream = Some(PgStream::Ssl(stream));
+        Ok(())
+    }
+
     fn process_startup_msg(&mut self, msg: FeStartupMessage) -> Result<()> {
         let db_name = {
             match msg.config.get("database") {
@@ -172,7 +397,57 @@ where
                 Some(v) => v.to_string(),
             }
         };
-        self.session = Some(self.session_mgr.connect(&db_name).map_err(IoError::other)?);
+        let user_name = msg
+            .config
+            .get("user")
+            .ok_or_else(|| IoError::new(ErrorKind::InvalidInput, "user name is not specified"))?;
+        self.user_name = user_name.clone();
+        let session = self
+            .session_mgr
+            .connect(&db_name, user_name)
+            .map_err(IoError::other)?;
+        self.session = Some(session.clone());
+        match session.user_authenticator() {
+            UserAuthenticator::None => self.finish_authentication()?,
+            UserAuthenticator::ClearText(_) => {
+                self.write_message_no_flush(&BeMessage::AuthenticationCleartextPassword)?;
+                self.state = PgProtocolState::Authentication;
+            }
+            UserAuthenticator::Md5WithSalt { salt, .. } => {
+                self.write_message_no_flush(&BeMessage::AuthenticationMD5Password(salt))?;
+                self.state = PgProtocolState::Authentication;
+            }
+        }
+        Ok(())
+    }
+
+    fn process_password_msg(&mut self, msg: FePasswordMessage) -> Result<()> {
+        let authenticated = self
+            .session
+            .as_ref()
+            .unwrap()
+            .user_authenticator()
+            .authenticate(&msg.password);
+        if !authenticated {
+            return Err(IoError::new(
+                ErrorKind::InvalidInput,
+                "password authentication failed",
+            ));
+        }
+        self.finish_authentication()
+    }
+
+    /// Counts the connection of the authenticated user, and tells the client that the session is
+    /// ready. Fails if there are too many connections.
+    fn finish_authentication(&mut self) -> Result<()> {
+        self._connection_permit = Some(self.connection_counter.acquire(&self.user_name)?);
+        self.write_auth_ok()?;
+        self.state = PgProtocolState::Regular;
+        Ok(())
+    }
+
+    /// Tells the client that the authentication is done and the session is ready.
+    fn write_auth_ok(&mut self) -> Result<()> {
         self.write_message_no_flush(&BeMessage::AuthenticationOk)?;
         self.write_message_no_flush(&BeMessage::ParameterStatus(
             BeParameterStatusMessage::ClientEncoding("utf8"),
@@ -191,11 +466,7 @@ where
         self.is_terminate = true;
     }
 
-    async fn process_query_msg(
-        &mut self,
-        query_string: Result<&str>,
-        extended: bool,
-    ) -> Result<()> {
+    async fn process_query_msg(&mut self, query_string: Result<&str>) -> Result<()> {
         match query_string {
             Ok(sql) => {
                 tracing::trace!("receive query: {}", sql);
@@ -204,19 +475,7 @@ where
                 let process_res = session.run_statement(sql).await;
                 match process_res {
                     Ok(res) => {
-                        if res.is_empty() {
-                            self.write_message_no_flush(&BeMessage::EmptyQueryResponse)?;
-                        } else if res.is_query() {
-                            self.process_query_with_results(res, extended).await?;
-                        } else {
-                            self.write_message_no_flush(&BeMessage::CommandComplete(
-                                BeCommandCompleteMessage {
-                                    stmt_type: res.get_stmt_type(),
-                                    notice: res.get_notice(),
-                                    rows_cnt: res.get_effected_rows_cnt(),
-                                },
-                            ))?;
-                        }
+                        self.process_response(res, 0, &[], false).await?;
                     }
                     Err(e) => {
                         self.write_message_no_flush(&BeMessage::ErrorResponse(e))?;
@@ -231,28 +490,275 @@ where
         Ok(())
     }
 
-    async fn process_query_with_results(&mut self, res: PgResponse, extended: bool) -> Result<()> {
-        // The possible responses to Execute are the same as those described above for queries
-        // issued via simple query protocol, except that Execute doesn't cause ReadyForQuery or
-        // RowDescription to be issued.
-        // Quoted from: https://www.postgresql.org/docs/current/protocol-flow.html#PROTOCOL-FLOW-EXT-QUERY
-        if !extended {
-            self.write_message(&BeMessage::RowDescription(&res.get_row_desc()))
-                .await?;
+    fn process_parse_msg(&mut self, msg: FeParseMessage) -> std::result::Result<(), BoxedError> {
+        let sql = cstr_to_str(&msg.query_string)?;
+        tracing::trace!("prepare statement: {}", sql);
+        let param_types = msg
+            .type_ids
+            .iter()
+            .map(|&oid| {
+                // Oid 0 means the type is unspecified.
+                if oid == 0 {
+                    Ok(None)
+                } else {
+                    TypeOid::from_number(oid).map(Some)
+                }
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let desc = self.session.as_ref().unwrap().prepare_statement(
+            &String::from_utf8_lossy(&msg.statement_name),
+            sql,
+            param_types,
+        )?;
+        // An existing statement of the same name is replaced.
+        self.statements.insert(msg.statement_name, desc);
+        Ok(())
+    }
+
+    fn process_bind_msg(&mut self, msg: FeBindMessage) -> Result<()> {
+        let desc = self
+            .statements
+            .get(&msg.statement_name)
+            .ok_or_else(|| not_exist_error("prepared statement", &msg.statement_name))?;
+        if msg.params.len() != desc.param_types.len() {
+            return Err(IoError::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "bind message supplies {} parameters, but prepared statement requires {}",
+                    msg.params.len(),
+                    desc.param_types.len()
+                ),
+            ));
+        }
+
+        let param_formats = expand_format_codes(&msg.param_format_codes, msg.params.len())?;
+        let params = msg
+            .params
+            .iter()
+            .enumerate()
+            .map(|(i, param)| {
+                param
+                    .as_ref()
+                    .map(|value| {
+                        if param_formats[i] == 1 {
+                            desc.param_types[i].decode_binary(value)
+                        } else {
+                            cstr_to_str(value).map(str::to_string)
+                        }
+                    })
+                    .transpose()
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let result_formats = expand_format_codes(&msg.result_format_codes, desc.row_desc.len())?;
+        let mut row_desc = desc.row_desc.clone();
+        for (i, field) in row_desc.iter_mut().enumerate() {
+            field.set_format_code(result_formats[i]);
+        }
+
+        // Binding a portal name again destroys the previous portal.
+        self.portals.insert(
+            msg.portal_name,
+            Portal {
+                statement_name: String::from_utf8_lossy(&msg.statement_name).into_owned(),
+                params,
+                result_formats,
+                row_desc,
+                result: None,
+            },
+        );
+        Ok(())
+    }
+
+    async fn process_execute_msg(
+        &mut self,
+        portal_name: Bytes,
+        mut portal: Portal,
+        max_rows: i32,
+    ) -> Result<()> {
+        let suspended = match portal.result.take() {
+            // Resume the query suspended by the previous `Execute`.
+            Some(result) => {
+                self.send_rows(result, max_rows, &portal.result_formats)
+                    .await?
+            }
+            None => {
+                let session = self.session.clone().unwrap();
+                match session
+                    .run_prepared_statement(&portal.statement_name, portal.params.clone())
+                    .await
+                {
+                    Ok(res) => {
+                        self.process_response(res, max_rows, &portal.result_formats, true)
+                            .await?
+                    }
+                    Err(e) => {
+                        self.write_error(e)?;
+                        None
+                    }
+                }
+            }
+        };
+
+        // The portal is dropped once the result is exhausted.
+        if suspended.is_some() {
+            portal.result = suspended;
+            self.portals.insert(portal_name, portal);
+        }
+        Ok(())
+    }
+
+    /// Sends the response of a statement. For a query, at most `max_rows` rows are sent, or all of
+    /// them if `max_rows` is zero. The rest of the result is returned if the limit is reached.
+    async fn process_response(
+        &mut self,
+        res: PgResponse,
+        max_rows: i32,
+        result_formats: &[i16],
+        extended: bool,
+    ) -> Result<Option<PortalResult>> {
+        if res.is_empty() {
+            self.write_message_no_flush(&BeMessage::EmptyQueryResponse)?;
+            Ok(None)
+        } else if let Some(format) = res.copy_format().cloned() {
+            self.send_copy_out(res, format).await?;
+            Ok(None)
+        } else if res.is_query() {
+            // The possible responses to Execute are the same as those described above for queries
+            // issued via simple query protocol, except that Execute doesn't cause ReadyForQuery or
+            // RowDescription to be issued.
+            // Quoted from: https://www.postgresql.org/docs/current/protocol-flow.html#PROTOCOL-FLOW-EXT-QUERY
+            if !extended {
+                self.write_message(&BeMessage::RowDescription(&res.get_row_desc()))
+                    .await?;
+            }
+
+            let portal_result = PortalResult {
+                result: res,
+                pending_rows: vec![].into_iter(),
+            };
+            self.send_rows(portal_result, max_rows, result_formats)
+                .await
+        } else {
+            self.write_message_no_flush(&BeMessage::CommandComplete(BeCommandCompleteMessage {
+                stmt_type: res.get_stmt_type(),
+                notice: res.get_notice(),
+                rows_cnt: res.get_effected_rows_cnt(),
+            }))?;
+            Ok(None)
+        }
+    }
+
+    /// Sends at most `max_rows` rows of the result, or all of them if `max_rows` is zero. The
+    /// columns are encoded in binary format if the format code in `result_formats` is 1, and in
+    /// text format otherwise.
+    async fn send_rows(
+        &mut self,
+        mut portal_result: PortalResult,
+        max_rows: i32,
+        result_formats: &[i16],
+    ) -> Result<Option<PortalResult>> {
+        let max_rows = if max_rows > 0 {
+            max_rows as usize
+        } else {
+            usize::MAX
+        };
+        let binary_types = if result_formats.contains(&1) {
+            let row_desc = portal_result.result.get_row_desc();
+            Some(
+                row_desc
+                    .iter()
+                    .enumerate()
+                    .map(|(i, field)| {
+                        (result_formats.get(i) == Some(&1)).then(|| field.get_type_oid())
+                    })
+                    .collect::<Vec<_>>(),
+            )
+        } else {
+            None
+        };
+
+        let mut rows_cnt = 0;
+        while rows_cnt < max_rows {
+            if let Some(row) = portal_result.pending_rows.next() {
+                match &binary_types {
+                    Some(binary_types) => {
+                        let values = encode_row(&row, binary_types)?;
+                        self.write_message_no_flush(&BeMessage::EncodedDataRow(&values))?;
+                    }
+                    None => self.write_message_no_flush(&BeMessage::DataRow(&row))?,
+                }
+                rows_cnt += 1;
+                continue;
+            }
+
+            // Flush the rows of the current batch before pulling the next one, so that at most
+            // one batch of rows is buffered.
+            self.flush().await?;
+            match portal_result.result.values_stream().next().await {
+                Some(Ok(rows)) => portal_result.pending_rows = rows.into_iter(),
+                Some(Err(e)) => {
+                    self.write_error(e)?;
+                    return Ok(None);
+                }
+                None => {
+                    self.write_message_no_flush(&BeMessage::CommandComplete(
+                        BeCommandCompleteMessage {
+                            stmt_type: portal_result.result.get_stmt_type(),
+                            notice: portal_result.result.get_notice(),
+                            rows_cnt: rows_cnt as i32,
+                        },
+                    ))?;
+                    return Ok(None);
+                }
+            }
+        }
+
+        self.write_message_no_flush(&BeMessage::PortalSuspended)?;
+        Ok(Some(portal_result))
+    }
+
+    /// Copies all the rows of the result to the client in `format`, one `CopyData` message per
+    /// row. Like `send_rows`, only one batch of rows is buffered at a time.
+    async fn send_copy_out(&mut self, mut result: PgResponse, format: CopyFormat) -> Result<()> {
+        let row_desc = result.get_row_desc();
+        self.write_message_no_flush(&BeMessage::CopyOutResponse(row_desc.len()))?;
+        let mut line = BytesMut::new();
+        if format.has_header() {
+            format.encode_row(
+                row_desc.iter().map(|field| Some(field.get_name())),
+                &mut line,
+            );
+            self.write_message_no_flush(&BeMessage::CopyData(&line))?;
         }
 
         let mut rows_cnt = 0;
-        let iter = res.iter();
-        for val in iter {
-            self.write_message(&BeMessage::DataRow(val)).await?;
-            rows_cnt += 1;
+        while let Some(rows) = result.values_stream().next().await {
+            let rows = match rows {
+                Ok(rows) => rows,
+                Err(e) => return self.write_error(e),
+            };
+            for row in &rows {
+                line.clear();
+                format.encode_row(row.values().iter().map(Option::as_deref), &mut line);
+                self.write_message_no_flush(&BeMessage::CopyData(&line))?;
+            }
+            rows_cnt += rows.len();
+            self.flush().await?;
         }
+        self.write_message_no_flush(&BeMessage::CopyDone)?;
         self.write_message_no_flush(&BeMessage::CommandComplete(BeCommandCompleteMessage {
-            stmt_type: res.get_stmt_type(),
-            notice: res.get_notice(),
-            rows_cnt,
-        }))?;
-        Ok(())
+            stmt_type: result.get_stmt_type(),
+            notice: result.get_notice(),
+            rows_cnt: rows_cnt as i32,
+        }))
+    }
+
+    /// Writes an error. The following messages of the extended query protocol are discarded until
+    /// a `Sync`.
+    fn write_error(&mut self, e: BoxedError) -> Result<()> {
+        self.ignore_until_sync = true;
+        self.write_message_no_flush(&BeMessage::ErrorResponse(e))
     }
 
     fn is_terminate(&self) -> bool {
@@ -270,9 +776,53 @@ where
     }
 
     async fn flush(&mut self) -> Result<()> {
-        self.stream.write_all(&self.buf_out).await?;
+        let stream = self.stream.as_mut().unwrap();
+        stream.write_all(&self.buf_out).await?;
         self.buf_out.clear();
-        self.stream.flush().await?;
+        stream.flush().await?;
         Ok(())
     }
 }
+
+fn not_exist_error(kind: &str, name: &Bytes) -> IoError {
+    IoError::new(
+        ErrorKind::InvalidInput,
+        format!(
+            "{} \"{}\" does not exist",
+            kind,
+            String::from_utf8_lossy(name)
+        ),
+    )
+}
+
+/// Expands the format codes in `Bind` to one code per column. No code means all in text format,
+/// and a single code applies to all the columns.
+fn expand_format_codes(codes: &[i16], len: usize) -> Result<Vec<i16>> {
+    match codes.len() {
+        0 => Ok(vec![0; len]),
+        1 => Ok(vec![codes[0]; len]),
+        n if n == len => Ok(codes.to_vec()),
+        n => Err(IoError::new(
+            ErrorKind::InvalidInput,
+            format!("expected {} format codes, got {}", len, n),
+        )),
+    }
+}
+
+/// Encodes the values of `row`, where a value is in binary format if its type is given in
+/// `binary_types`, and in text format otherwise.
+fn encode_row(row: &Row, binary_types: &[Option<TypeOid>]) -> Result<Vec<Option<Bytes>>> {
+    row.values()
+        .iter()
+        .enumerate()
+        .map(|(i, value)| {
+            value
+                .as_ref()
+                .map(|value| match binary_types.get(i) {
+                    Some(Some(type_oid)) => type_oid.encode_binary(value),
+                    _ => Ok(Bytes::copy_from_slice(value.as_bytes())),
+                })
+                .transpose()
+        })
+        .collect()
+}
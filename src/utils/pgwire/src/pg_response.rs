@@ -14,8 +14,19 @@
 
 use std::fmt::Formatter;
 
+use futures::stream::{self, BoxStream};
+use futures::StreamExt;
+
+use crate::pg_copy::CopyFormat;
 use crate::pg_field_descriptor::PgFieldDescriptor;
+use crate::pg_server::BoxedError;
 use crate::types::Row;
+
+pub type RowSet = Vec<Row>;
+pub type RowSetResult = Result<RowSet, BoxedError>;
+/// The rows of a query result, produced batch by batch. Only one batch is buffered at a time when
+/// the result is sent to the client.
+pub type RowSetStream = BoxStream<'static, RowSetResult>;
 /// Port from StatementType.java.
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
@@ -35,8 +46,12 @@ pub enum StatementType {
     CREATE_DATABASE,
     CREATE_SCHEMA,
     CREATE_USER,
+    CREATE_FUNCTION,
     DESCRIBE_TABLE,
     GRANT_PRIVILEGE,
+    ALTER_TABLE,
+    ALTER_SOURCE,
+    ALTER_MATERIALIZED_VIEW,
     DROP_TABLE,
     DROP_MATERIALIZED_VIEW,
     DROP_INDEX,
@@ -44,6 +59,7 @@ pub enum StatementType {
     DROP_SCHEMA,
     DROP_DATABASE,
     DROP_USER,
+    DROP_FUNCTION,
     REVOKE_PRIVILEGE,
     // Introduce ORDER_BY statement type cuz Calcite unvalidated AST has SqlKind.ORDER_BY. Note
     // that Statement Type is not designed to be one to one mapping with SqlKind.
@@ -53,6 +69,10 @@ pub enum StatementType {
     SHOW_COMMAND,
     START_TRANSACTION,
     ABORT,
+    COMMIT,
+    ROLLBACK,
+    DECLARE_CURSOR,
+    CLOSE_CURSOR,
     FLUSH,
     OTHER,
     // EMPTY is used when query statement is empty (e.g. ";").
@@ -65,13 +85,27 @@ impl std::fmt::Display for StatementType {
     }
 }
 
-#[derive(Debug)]
 pub struct PgResponse {
     stmt_type: StatementType,
     row_cnt: i32,
     notice: Option<String>,
-    values: Vec<Row>,
+    values_stream: RowSetStream,
     row_desc: Vec<PgFieldDescriptor>,
+    /// The rows are copied to the client in this format rather than sent as a query result if
+    /// set, e.g. by `COPY ... TO STDOUT`.
+    copy_format: Option<CopyFormat>,
+}
+
+impl std::fmt::Debug for PgResponse {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PgResponse")
+            .field("stmt_type", &self.stmt_type)
+            .field("row_cnt", &self.row_cnt)
+            .field("notice", &self.notice)
+            .field("row_desc", &self.row_desc)
+            .field("copy_format", &self.copy_format)
+            .finish_non_exhaustive()
+    }
 }
 
 impl StatementType {
@@ -95,13 +129,30 @@ impl PgResponse {
         row_cnt: i32,
         values: Vec<Row>,
         row_desc: Vec<PgFieldDescriptor>,
+    ) -> Self {
+        Self::new_for_stream(
+            stmt_type,
+            row_cnt,
+            stream::once(async { Ok(values) }).boxed(),
+            row_desc,
+        )
+    }
+
+    /// Creates a response whose rows are produced lazily by `values_stream`, so that a large
+    /// result set doesn't need to be held in memory as a whole.
+    pub fn new_for_stream(
+        stmt_type: StatementType,
+        row_cnt: i32,
+        values_stream: RowSetStream,
+        row_desc: Vec<PgFieldDescriptor>,
     ) -> Self {
         Self {
             stmt_type,
             row_cnt,
-            values,
+            values_stream,
             row_desc,
             notice: None,
+            copy_format: None,
         }
     }
 
@@ -113,9 +164,10 @@ impl PgResponse {
         Self {
             stmt_type,
             row_cnt: 0,
-            values: vec![],
+            values_stream: stream::empty().boxed(),
             row_desc: vec![],
             notice: Some(notice),
+            copy_format: None,
         }
     }
 
@@ -135,9 +187,11 @@ impl PgResponse {
         matches!(
             self.stmt_type,
             StatementType::SELECT
+                | StatementType::FETCH
                 | StatementType::EXPLAIN
                 | StatementType::SHOW_COMMAND
                 | StatementType::DESCRIBE_TABLE
+                | StatementType::FLUSH
         )
     }
 
@@ -149,7 +203,28 @@ impl PgResponse {
         self.row_desc.clone()
     }
 
-    pub fn iter(&self) -> impl Iterator<Item = &Row> + '_ {
-        self.values.iter()
+    pub fn values_stream(&mut self) -> &mut RowSetStream {
+        &mut self.values_stream
+    }
+
+    pub fn copy_format(&self) -> Option<&CopyFormat> {
+        self.copy_format.as_ref()
+    }
+
+    /// Turns the result of a query into the rows copied to the client in `format`.
+    pub fn into_copy_out(self, format: CopyFormat) -> Self {
+        Self {
+            stmt_type: StatementType::COPY,
+            copy_format: Some(format),
+            ..self
+        }
+    }
+
+    /// Replaces the stream of the rows with the one transformed by `f`.
+    pub fn map_values_stream(self, f: impl FnOnce(RowSetStream) -> RowSetStream) -> Self {
+        Self {
+            values_stream: f(self.values_stream),
+            ..self
+        }
     }
 }
@@ -12,13 +12,17 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
 use std::io;
 use std::io::ErrorKind;
 use std::result::Result;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
+use openssl::ssl::{SslAcceptor, SslFiletype, SslMethod, SslVerifyMode};
 use tokio::net::{TcpListener, TcpStream};
 
+use crate::pg_field_descriptor::{PgFieldDescriptor, TypeOid};
 use crate::pg_protocol::PgProtocol;
 use crate::pg_response::PgResponse;
 
@@ -29,7 +33,9 @@ pub type BoxedError = Box<dyn std::error::Error + Send + Sync>;
 pub trait SessionManager: Send + Sync + 'static {
     type Session: Session;
 
-    fn connect(&self, database: &str) -> Result<Arc<Self::Session>, BoxedError>;
+    /// Connects to `database` as `user_name`. The returned session is used after the user is
+    /// authenticated by its [`Session::user_authenticator`].
+    fn connect(&self, database: &str, user_name: &str) -> Result<Arc<Self::Session>, BoxedError>;
 }
 
 /// A psql connection. Each connection binds with a database. Switching database will need to
@@ -37,22 +43,231 @@ pub trait SessionManager: Send + Sync + 'static {
 #[async_trait::async_trait]
 pub trait Session: Send + Sync {
     async fn run_statement(self: Arc<Self>, sql: &str) -> Result<PgResponse, BoxedError>;
+
+    /// Prepares `sql` as the prepared statement `name`, which replaces the existing one of the
+    /// same name. The types of parameters not given in `param_types` are inferred.
+    fn prepare_statement(
+        &self,
+        name: &str,
+        sql: &str,
+        param_types: Vec<Option<TypeOid>>,
+    ) -> Result<StatementDesc, BoxedError>;
+
+    /// Runs the prepared statement `name` with the parameter values in text format.
+    async fn run_prepared_statement(
+        self: Arc<Self>,
+        name: &str,
+        params: Vec<Option<String>>,
+    ) -> Result<PgResponse, BoxedError>;
+
+    /// Drops the prepared statement `name` if it exists.
+    fn close_statement(&self, name: &str);
+
+    /// How to authenticate the user of the session.
+    fn user_authenticator(&self) -> &UserAuthenticator;
+
+    /// How long the session may wait for the next message of the client before it's terminated.
+    /// `None` if it may wait forever.
+    fn idle_timeout(&self) -> Option<IdleTimeout>;
+}
+
+/// The timeout of a session waiting for the next message of the client, like
+/// `idle_in_transaction_session_timeout` and `idle_session_timeout` of PostgreSQL.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IdleTimeout {
+    /// The session is in a transaction.
+    InTransaction(Duration),
+    /// The session is not in a transaction.
+    Session(Duration),
+}
+
+impl IdleTimeout {
+    pub fn duration(&self) -> Duration {
+        match self {
+            IdleTimeout::InTransaction(duration) | IdleTimeout::Session(duration) => *duration,
+        }
+    }
+}
+
+/// How to authenticate a user when connecting.
+pub enum UserAuthenticator {
+    /// The user has no password.
+    None,
+    /// Asks for the password in clear text, which is accepted if the check passes.
+    ClearText(Box<dyn Fn(&[u8]) -> bool + Send + Sync>),
+    /// Asks for the password encrypted with MD5 and `salt`. `encrypted_password` is the
+    /// hex-encoded MD5 hash of the password concatenated with the user name, as stored in the
+    /// catalog.
+    Md5WithSalt {
+        encrypted_password: Vec<u8>,
+        salt: [u8; 4],
+    },
+}
+
+impl UserAuthenticator {
+    /// Checks the password sent by the client.
+    pub fn authenticate(&self, password: &[u8]) -> bool {
+        match self {
+            UserAuthenticator::None => true,
+            UserAuthenticator::ClearText(check) => check(password),
+            UserAuthenticator::Md5WithSalt {
+                encrypted_password,
+                salt,
+            } => {
+                // The client sends "md5" followed by the hex-encoded MD5 hash of the encrypted
+                // password concatenated with the salt.
+                let mut ctx = md5::Context::new();
+                ctx.consume(encrypted_password);
+                ctx.consume(salt);
+                let expected = format!("md5{:x}", ctx.compute());
+                password == expected.as_bytes()
+            }
+        }
+    }
+}
+
+/// Describes the parameters and the result columns of a prepared statement.
+#[derive(Debug, Clone, Default)]
+pub struct StatementDesc {
+    pub param_types: Vec<TypeOid>,
+    /// Empty if the statement returns no rows.
+    pub row_desc: Vec<PgFieldDescriptor>,
+}
+
+/// Configurations of the server that are not specific to a session.
+#[derive(Clone, Debug, Default)]
+pub struct PgServerConfig {
+    /// Connections are upgraded to TLS on the request of clients if set.
+    pub tls: Option<TlsConfig>,
+    pub limits: ConnectionLimits,
+}
+
+/// Files in PEM to serve connections over TLS with.
+#[derive(Clone, Debug)]
+pub struct TlsConfig {
+    pub cert_path: String,
+    pub key_path: String,
+    /// The CA certificates to verify the certificates of clients with. Clients are required to
+    /// present a certificate if set.
+    pub client_ca_path: Option<String>,
+}
+
+impl TlsConfig {
+    fn build_acceptor(&self) -> io::Result<SslAcceptor> {
+        let mut builder = SslAcceptor::mozilla_intermediate_v5(SslMethod::tls_server())?;
+        builder.set_private_key_file(&self.key_path, SslFiletype::PEM)?;
+        builder.set_certificate_chain_file(&self.cert_path)?;
+        builder.check_private_key()?;
+        if let Some(client_ca_path) = &self.client_ca_path {
+            builder.set_ca_file(client_ca_path)?;
+            builder.set_verify(SslVerifyMode::PEER | SslVerifyMode::FAIL_IF_NO_PEER_CERT);
+        }
+        Ok(builder.build())
+    }
+}
+
+/// Limits of the number of connections. `0` means unlimited.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ConnectionLimits {
+    pub max_connections: usize,
+    pub max_connections_per_user: usize,
+}
+
+/// Counts the connections in total and of each user, rejecting new ones beyond the limits.
+pub struct ConnectionCounter {
+    limits: ConnectionLimits,
+    /// The number of connections in total, and of each user.
+    counts: Mutex<(usize, HashMap<String, usize>)>,
+}
+
+impl ConnectionCounter {
+    pub fn new(limits: ConnectionLimits) -> Self {
+        Self {
+            limits,
+            counts: Default::default(),
+        }
+    }
+
+    /// Counts a new connection of `user_name`, which is no longer counted once the returned permit
+    /// is dropped.
+    pub fn acquire(self: &Arc<Self>, user_name: &str) -> io::Result<ConnectionPermit> {
+        let mut counts = self.counts.lock().unwrap();
+        let (total, per_user) = &mut *counts;
+        if self.limits.max_connections > 0 && *total >= self.limits.max_connections {
+            return Err(io::Error::new(
+                ErrorKind::Other,
+                "sorry, too many clients already",
+            ));
+        }
+        let user_count = per_user.entry(user_name.to_string()).or_default();
+        if self.limits.max_connections_per_user > 0
+            && *user_count >= self.limits.max_connections_per_user
+        {
+            return Err(io::Error::new(
+                ErrorKind::Other,
+                format!("too many connections for role \"{}\"", user_name),
+            ));
+        }
+        *user_count += 1;
+        *total += 1;
+        Ok(ConnectionPermit {
+            counter: self.clone(),
+            user_name: user_name.to_string(),
+        })
+    }
+}
+
+/// A connection counted by [`ConnectionCounter`].
+pub struct ConnectionPermit {
+    counter: Arc<ConnectionCounter>,
+    user_name: String,
+}
+
+impl Drop for ConnectionPermit {
+    fn drop(&mut self) {
+        let mut counts = self.counter.counts.lock().unwrap();
+        let (total, per_user) = &mut *counts;
+        *total -= 1;
+        if let Some(user_count) = per_user.get_mut(&self.user_name) {
+            *user_count -= 1;
+            if *user_count == 0 {
+                per_user.remove(&self.user_name);
+            }
+        }
+    }
 }
 
 /// Binds a Tcp listener at `addr`. Spawn a coroutine to serve every new connection.
-pub async fn pg_serve(addr: &str, session_mgr: Arc<impl SessionManager>) -> io::Result<()> {
+pub async fn pg_serve(
+    addr: &str,
+    session_mgr: Arc<impl SessionManager>,
+    config: PgServerConfig,
+) -> io::Result<()> {
+    let tls_acceptor = config
+        .tls
+        .as_ref()
+        .map(TlsConfig::build_acceptor)
+        .transpose()?
+        .map(Arc::new);
+    let connection_counter = Arc::new(ConnectionCounter::new(config.limits));
     let listener = TcpListener::bind(addr).await.unwrap();
     // accept connections and process them, spawning a new thread for each one
-    tracing::info!("Server Listening at {}", addr);
+    tracing::info!(
+        "Server Listening at {}, TLS enabled: {}",
+        addr,
+        tls_acceptor.is_some()
+    );
     loop {
         let session_mgr = session_mgr.clone();
+        let tls_acceptor = tls_acceptor.clone();
+        let connection_counter = connection_counter.clone();
         let conn_ret = listener.accept().await;
         match conn_ret {
             Ok((stream, peer_addr)) => {
                 tracing::info!("New connection: {}", peer_addr);
                 tokio::spawn(async move {
                     // connection succeeded
-                    pg_serve_conn(stream, session_mgr).await;
+                    pg_serve_conn(stream, session_mgr, tls_acceptor, connection_counter).await;
                     tracing::info!("Connection {} closed", peer_addr);
                 });
             }
@@ -64,11 +279,15 @@ pub async fn pg_serve(addr: &str, session_mgr: Arc<impl SessionManager>) -> io::
     }
 }
 
-async fn pg_serve_conn(socket: TcpStream, session_mgr: Arc<impl SessionManager>) {
-    let mut pg_proto = PgProtocol::new(socket, session_mgr);
-    let mut unnamed_query_string = bytes::Bytes::new();
+async fn pg_serve_conn(
+    socket: TcpStream,
+    session_mgr: Arc<impl SessionManager>,
+    tls_acceptor: Option<Arc<SslAcceptor>>,
+    connection_counter: Arc<ConnectionCounter>,
+) {
+    let mut pg_proto = PgProtocol::new(socket, session_mgr, tls_acceptor, connection_counter);
     loop {
-        let terminate = pg_proto.process(&mut unnamed_query_string).await;
+        let terminate = pg_proto.process().await;
         match terminate {
             Ok(is_ter) => {
                 if is_ter {
@@ -89,14 +308,32 @@ async fn pg_serve_conn(socket: TcpStream, session_mgr: Arc<impl SessionManager>)
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashMap;
     use std::error::Error;
-    use std::sync::Arc;
+    use std::pin::Pin;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
 
+    use futures::stream::{self, StreamExt, TryStreamExt};
+    use openssl::asn1::Asn1Time;
+    use openssl::bn::BigNum;
+    use openssl::hash::MessageDigest;
+    use openssl::pkey::PKey;
+    use openssl::rsa::Rsa;
+    use openssl::ssl::{SslConnector, SslMethod, SslVerifyMode};
+    use openssl::x509::{X509NameBuilder, X509};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpStream;
+    use tokio_openssl::SslStream;
     use tokio_postgres::NoTls;
 
+    use crate::pg_copy::CopyFormat;
     use crate::pg_field_descriptor::{PgFieldDescriptor, TypeOid};
     use crate::pg_response::{PgResponse, StatementType};
-    use crate::pg_server::{pg_serve, Session, SessionManager};
+    use crate::pg_server::{
+        pg_serve, ConnectionLimits, IdleTimeout, PgServerConfig, Session, SessionManager,
+        StatementDesc, TlsConfig, UserAuthenticator,
+    };
     use crate::types::Row;
 
     struct MockSessionManager {}
@@ -107,28 +344,134 @@ mod tests {
         fn connect(
             &self,
             _database: &str,
+            user_name: &str,
         ) -> Result<Arc<Self::Session>, Box<dyn Error + Send + Sync>> {
-            Ok(Arc::new(MockSession {}))
+            // Users named after an authentication method have the password "password".
+            let user_authenticator = match user_name {
+                "md5_user" => UserAuthenticator::Md5WithSalt {
+                    encrypted_password: format!(
+                        "{:x}",
+                        md5::compute(format!("password{}", user_name))
+                    )
+                    .into_bytes(),
+                    salt: [1, 2, 3, 4],
+                },
+                "clear_text_user" => UserAuthenticator::ClearText(Box::new(|password: &[u8]| {
+                    password == b"password"
+                })),
+                _ => UserAuthenticator::None,
+            };
+            // Sessions of "idle_user" are terminated once idle for 100ms.
+            let idle_timeout = (user_name == "idle_user")
+                .then(|| IdleTimeout::Session(Duration::from_millis(100)));
+            Ok(Arc::new(MockSession {
+                statements: Default::default(),
+                user_authenticator,
+                idle_timeout,
+            }))
         }
     }
 
-    struct MockSession {}
+    struct MockSession {
+        /// Sql of the prepared statements.
+        statements: Mutex<HashMap<String, String>>,
+        user_authenticator: UserAuthenticator,
+        idle_timeout: Option<IdleTimeout>,
+    }
+
+    impl MockSession {
+        fn response(sql: &str, params: Vec<Option<String>>) -> PgResponse {
+            let row_desc = vec![PgFieldDescriptor::new(
+                "VARCHAR".to_owned(),
+                TypeOid::Varchar,
+            )];
+            if sql.contains("numbers") {
+                // Returns "0" to "5" in 3 batches.
+                let rows_stream = stream::iter(0..3)
+                    .map(|batch| {
+                        Ok((batch * 2..batch * 2 + 2)
+                            .map(|i| Row::new(vec![Some(i.to_string())]))
+                            .collect())
+                    })
+                    .boxed();
+                let response =
+                    PgResponse::new_for_stream(StatementType::SELECT, 0, rows_stream, row_desc);
+                // Copies the numbers in CSV with a header.
+                if sql.starts_with("COPY") {
+                    return response.into_copy_out(CopyFormat::Csv {
+                        delimiter: b',',
+                        header: true,
+                    });
+                }
+                return response;
+            }
+            // Returns the first parameter if any.
+            let value = match params.into_iter().next() {
+                Some(param) => param,
+                None => Some("Hello, World".to_owned()),
+            };
+            PgResponse::new(
+                StatementType::SELECT,
+                1,
+                vec![Row::new(vec![value])],
+                row_desc,
+            )
+        }
+    }
 
     #[async_trait::async_trait]
     impl Session for MockSession {
         async fn run_statement(
             self: Arc<Self>,
-            _sql: &str,
+            sql: &str,
         ) -> Result<PgResponse, Box<dyn Error + Send + Sync>> {
-            Ok(PgResponse::new(
-                StatementType::SELECT,
-                1,
-                vec![Row::new(vec![Some("Hello, World".to_owned())])],
-                vec![PgFieldDescriptor::new(
+            Ok(Self::response(sql, vec![]))
+        }
+
+        fn prepare_statement(
+            &self,
+            name: &str,
+            sql: &str,
+            _param_types: Vec<Option<TypeOid>>,
+        ) -> Result<StatementDesc, Box<dyn Error + Send + Sync>> {
+            self.statements
+                .lock()
+                .unwrap()
+                .insert(name.to_owned(), sql.to_owned());
+            Ok(StatementDesc {
+                param_types: vec![TypeOid::Varchar; sql.matches('$').count()],
+                row_desc: vec![PgFieldDescriptor::new(
                     "VARCHAR".to_owned(),
                     TypeOid::Varchar,
                 )],
-            ))
+            })
+        }
+
+        async fn run_prepared_statement(
+            self: Arc<Self>,
+            name: &str,
+            params: Vec<Option<String>>,
+        ) -> Result<PgResponse, Box<dyn Error + Send + Sync>> {
+            let sql = self
+                .statements
+                .lock()
+                .unwrap()
+                .get(name)
+                .cloned()
+                .ok_or_else(|| format!("prepared statement \"{}\" does not exist", name))?;
+            Ok(Self::response(&sql, params))
+        }
+
+        fn close_statement(&self, name: &str) {
+            self.statements.lock().unwrap().remove(name);
+        }
+
+        fn user_authenticator(&self) -> &UserAuthenticator {
+            &self.user_authenticator
+        }
+
+        fn idle_timeout(&self) -> Option<IdleTimeout> {
+            self.idle_timeout
         }
     }
 
@@ -136,7 +479,7 @@ mod tests {
     /// The test below is copied from tokio-postgres doc.
     async fn test_psql_extended_mode_connect() {
         let session_mgr = Arc::new(MockSessionManager {});
-        tokio::spawn(async move { pg_serve("127.0.0.1:10000", session_mgr).await });
+        tokio::spawn(pg_serve("127.0.0.1:10000", session_mgr, Default::default()));
 
         // Connect to the database.
         let (client, connection) = tokio_postgres::connect("host=localhost port=10000", NoTls)
@@ -151,15 +494,293 @@ mod tests {
             }
         });
 
-        // Now we can execute a simple statement that just returns its parameter.
         let rows = client.query("SELECT 'Hello, World'", &[]).await.unwrap();
-        // FIXME: Enable this after handle prepared statement.
-        // let rows = client
-        //     .query("SELECT $1::TEXT", &[&"hello world"])
-        //     .await
-        //     .unwrap();
-
         let value: &str = rows[0].get(0);
         assert_eq!(value, "Hello, World");
+
+        // Now we can execute a simple statement that just returns its parameter.
+        let rows = client
+            .query("SELECT $1::TEXT", &[&"hello world"])
+            .await
+            .unwrap();
+        let value: &str = rows[0].get(0);
+        assert_eq!(value, "hello world");
+    }
+
+    #[tokio::test]
+    async fn test_psql_extended_mode_portal() {
+        let session_mgr = Arc::new(MockSessionManager {});
+        tokio::spawn(pg_serve("127.0.0.1:10001", session_mgr, Default::default()));
+
+        let (mut client, connection) = tokio_postgres::connect("host=localhost port=10001", NoTls)
+            .await
+            .unwrap();
+        tokio::spawn(async move {
+            if let Err(e) = connection.await {
+                eprintln!("connection error: {}", e);
+            }
+        });
+
+        // Portals can only be used in a transaction in `tokio_postgres`.
+        let transaction = client.transaction().await.unwrap();
+        let statement = transaction.prepare("SELECT * FROM numbers").await.unwrap();
+        let portal = transaction.bind(&statement, &[]).await.unwrap();
+
+        // Fetch the result with a row limit across the batches of the result.
+        let rows = transaction.query_portal(&portal, 3).await.unwrap();
+        let values: Vec<&str> = rows.iter().map(|row| row.get(0)).collect();
+        assert_eq!(values, vec!["0", "1", "2"]);
+        let rows = transaction.query_portal(&portal, 2).await.unwrap();
+        let values: Vec<&str> = rows.iter().map(|row| row.get(0)).collect();
+        assert_eq!(values, vec!["3", "4"]);
+        let rows = transaction.query_portal(&portal, 3).await.unwrap();
+        let values: Vec<&str> = rows.iter().map(|row| row.get(0)).collect();
+        assert_eq!(values, vec!["5"]);
+    }
+
+    #[tokio::test]
+    async fn test_psql_password_authentication() {
+        let session_mgr = Arc::new(MockSessionManager {});
+        tokio::spawn(pg_serve("127.0.0.1:10002", session_mgr, Default::default()));
+
+        for user in ["md5_user", "clear_text_user"] {
+            let (client, connection) = tokio_postgres::connect(
+                &format!("host=localhost port=10002 user={} password=password", user),
+                NoTls,
+            )
+            .await
+            .unwrap();
+            tokio::spawn(async move {
+                if let Err(e) = connection.await {
+                    eprintln!("connection error: {}", e);
+                }
+            });
+            let rows = client.query("SELECT 'Hello, World'", &[]).await.unwrap();
+            let value: &str = rows[0].get(0);
+            assert_eq!(value, "Hello, World");
+
+            let res = tokio_postgres::connect(
+                &format!("host=localhost port=10002 user={} password=wrong", user),
+                NoTls,
+            )
+            .await;
+            assert!(res.is_err());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_psql_idle_timeout() {
+        let session_mgr = Arc::new(MockSessionManager {});
+        tokio::spawn(pg_serve("127.0.0.1:10003", session_mgr, Default::default()));
+
+        let (client, connection) =
+            tokio_postgres::connect("host=localhost port=10003 user=idle_user", NoTls)
+                .await
+                .unwrap();
+        tokio::spawn(async move {
+            if let Err(e) = connection.await {
+                eprintln!("connection error: {}", e);
+            }
+        });
+        client.query("SELECT 'Hello, World'", &[]).await.unwrap();
+
+        // The session is terminated after being idle for too long.
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        assert!(client.query("SELECT 'Hello, World'", &[]).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_psql_connection_limits() {
+        let session_mgr = Arc::new(MockSessionManager {});
+        let config = PgServerConfig {
+            tls: None,
+            limits: ConnectionLimits {
+                max_connections: 2,
+                max_connections_per_user: 1,
+            },
+        };
+        tokio::spawn(pg_serve("127.0.0.1:10004", session_mgr, config));
+
+        let config = |user: &str| format!("host=localhost port=10004 user={}", user);
+        let (_client1, connection1) = tokio_postgres::connect(&config("user1"), NoTls)
+            .await
+            .unwrap();
+        tokio::spawn(connection1);
+        // Each user has at most 1 connection.
+        assert!(tokio_postgres::connect(&config("user1"), NoTls)
+            .await
+            .is_err());
+        let (_client2, connection2) = tokio_postgres::connect(&config("user2"), NoTls)
+            .await
+            .unwrap();
+        tokio::spawn(connection2);
+        // There're at most 2 connections in total.
+        assert!(tokio_postgres::connect(&config("user3"), NoTls)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_psql_connection_limits_after_authentication() {
+        let session_mgr = Arc::new(MockSessionManager {});
+        let config = PgServerConfig {
+            tls: None,
+            limits: ConnectionLimits {
+                max_connections: 1,
+                max_connections_per_user: 1,
+            },
+        };
+        tokio::spawn(pg_serve("127.0.0.1:10006", session_mgr, config));
+
+        // A client waiting to be authenticated doesn't take up the connection.
+        let mut stream = connect("127.0.0.1:10006").await;
+        stream
+            .write_all(&startup_message("clear_text_user"))
+            .await
+            .unwrap();
+        assert_eq!(stream.read_u8().await.unwrap(), b'R');
+
+        let (_client, connection) = tokio_postgres::connect(
+            "host=localhost port=10006 user=clear_text_user password=password",
+            NoTls,
+        )
+        .await
+        .unwrap();
+        tokio::spawn(connection);
+    }
+
+    /// Connects to `addr` once the server is listening.
+    async fn connect(addr: &str) -> TcpStream {
+        loop {
+            match TcpStream::connect(addr).await {
+                Ok(stream) => return stream,
+                Err(_) => tokio::time::sleep(Duration::from_millis(10)).await,
+            }
+        }
+    }
+
+    /// Writes a self-signed certificate of `localhost` and its private key to temporary files,
+    /// and returns their paths.
+    fn write_self_signed_cert(name: &str) -> (String, String) {
+        let key = PKey::from_rsa(Rsa::generate(2048).unwrap()).unwrap();
+        let mut subject = X509NameBuilder::new().unwrap();
+        subject.append_entry_by_text("CN", "localhost").unwrap();
+        let subject = subject.build();
+        let mut cert = X509::builder().unwrap();
+        cert.set_version(2).unwrap();
+        cert.set_serial_number(&BigNum::from_u32(1).unwrap().to_asn1_integer().unwrap())
+            .unwrap();
+        cert.set_subject_name(&subject).unwrap();
+        cert.set_issuer_name(&subject).unwrap();
+        cert.set_pubkey(&key).unwrap();
+        cert.set_not_before(&Asn1Time::days_from_now(0).unwrap())
+            .unwrap();
+        cert.set_not_after(&Asn1Time::days_from_now(1).unwrap())
+            .unwrap();
+        cert.sign(&key, MessageDigest::sha256()).unwrap();
+        let cert = cert.build();
+
+        let dir = std::env::temp_dir();
+        let prefix = format!("pgwire-{}-{}", name, std::process::id());
+        let cert_path = dir.join(format!("{}-cert.pem", prefix));
+        let key_path = dir.join(format!("{}-key.pem", prefix));
+        std::fs::write(&cert_path, cert.to_pem().unwrap()).unwrap();
+        std::fs::write(&key_path, key.private_key_to_pem_pkcs8().unwrap()).unwrap();
+        (
+            cert_path.to_str().unwrap().to_string(),
+            key_path.to_str().unwrap().to_string(),
+        )
+    }
+
+    fn ssl_request() -> Vec<u8> {
+        [8i32.to_be_bytes(), 80877103i32.to_be_bytes()].concat()
+    }
+
+    fn startup_message(user_name: &str) -> Vec<u8> {
+        let mut body = 196608i32.to_be_bytes().to_vec();
+        body.extend_from_slice(format!("user\0{}\0\0", user_name).as_bytes());
+        [((body.len() + 4) as i32).to_be_bytes().to_vec(), body].concat()
+    }
+
+    /// Requests TLS on a new connection to `addr`, and starts a session of `user1` over TLS once
+    /// the server agrees. Returns the type of the first message from the server.
+    async fn start_tls_session(addr: &str) -> std::io::Result<u8> {
+        let mut stream = connect(addr).await;
+        stream.write_all(&ssl_request()).await?;
+        // `EncryptionResponseSsl`
+        assert_eq!(stream.read_u8().await?, b'S');
+
+        // The certificate of the server is self-signed.
+        let mut connector = SslConnector::builder(SslMethod::tls_client())?;
+        connector.set_verify(SslVerifyMode::NONE);
+        let ssl = connector.build().configure()?.into_ssl("localhost")?;
+        let mut stream = SslStream::new(ssl, stream)?;
+        Pin::new(&mut stream)
+            .connect()
+            .await
+            .map_err(std::io::Error::other)?;
+        stream.write_all(&startup_message("user1")).await?;
+        stream.read_u8().await
+    }
+
+    #[tokio::test]
+    async fn test_psql_tls() {
+        let (cert_path, key_path) = write_self_signed_cert("server");
+        let config = PgServerConfig {
+            tls: Some(TlsConfig {
+                cert_path: cert_path.clone(),
+                key_path: key_path.clone(),
+                client_ca_path: None,
+            }),
+            limits: Default::default(),
+        };
+        tokio::spawn(pg_serve(
+            "127.0.0.1:10007",
+            Arc::new(MockSessionManager {}),
+            config,
+        ));
+        // `AuthenticationOk`
+        assert_eq!(start_tls_session("127.0.0.1:10007").await.unwrap(), b'R');
+
+        // Clients without a certificate signed by the CA are rejected.
+        let (client_ca_path, _) = write_self_signed_cert("client-ca");
+        let config = PgServerConfig {
+            tls: Some(TlsConfig {
+                cert_path,
+                key_path,
+                client_ca_path: Some(client_ca_path),
+            }),
+            limits: Default::default(),
+        };
+        tokio::spawn(pg_serve(
+            "127.0.0.1:10008",
+            Arc::new(MockSessionManager {}),
+            config,
+        ));
+        assert!(start_tls_session("127.0.0.1:10008").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_psql_copy_out() {
+        let session_mgr = Arc::new(MockSessionManager {});
+        tokio::spawn(pg_serve("127.0.0.1:10005", session_mgr, Default::default()));
+
+        let (client, connection) = tokio_postgres::connect("host=localhost port=10005", NoTls)
+            .await
+            .unwrap();
+        tokio::spawn(async move {
+            if let Err(e) = connection.await {
+                eprintln!("connection error: {}", e);
+            }
+        });
+
+        let data: Vec<_> = client
+            .copy_out("COPY (SELECT * FROM numbers) TO STDOUT WITH CSV HEADER")
+            .await
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(data.concat(), b"VARCHAR\n0\n1\n2\n3\n4\n5\n");
     }
 }